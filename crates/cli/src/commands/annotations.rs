@@ -0,0 +1,221 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use ritual_core::db::{FunctionAnnotation, ImportedSymbol, ProjectDb, ProjectLayout};
+use ritual_core::services::import::symbols::{parse_symbols, SymbolFormat};
+
+use crate::commands::{open_project_db, BinarySelector};
+use crate::output::{relative_time, Table};
+use crate::{canonicalize_or_current, sha256_file};
+
+/// Set (or clear) the human name/note for a function in a registered binary.
+///
+/// Annotations are keyed by the binary's content hash, so they apply to every run of the
+/// same build and survive re-runs.
+pub fn annotate_function_command(
+    root: &str,
+    binary: &str,
+    address: &str,
+    name: Option<String>,
+    note: Option<String>,
+    clear: bool,
+) -> Result<()> {
+    let address = parse_address(address)?;
+    if !clear && name.is_none() && note.is_none() {
+        return Err(anyhow!("Provide --name and/or --note (or --clear to remove the annotation)"));
+    }
+    if name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(anyhow!("Annotation name must not be empty"));
+    }
+
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let (binary_name, binary_hash) = resolve_binary_hash(&layout, &db, binary)?;
+
+    if clear {
+        let removed = db
+            .delete_function_annotation(&binary_hash, address)
+            .context("Failed to delete function annotation")?;
+        if removed == 0 {
+            println!("No annotation for {} @ 0x{:X}", binary_name, address);
+        } else {
+            println!("Cleared annotation for {} @ 0x{:X}", binary_name, address);
+        }
+        return Ok(());
+    }
+
+    let annotation = FunctionAnnotation {
+        binary_hash,
+        address,
+        name,
+        note,
+        updated_at: Utc::now().to_rfc3339(),
+    };
+    db.upsert_function_annotation(&annotation).context("Failed to store function annotation")?;
+
+    println!("Annotated {} @ 0x{:X}", binary_name, address);
+    if let Some(name) = &annotation.name {
+        println!("  Name: {}", name);
+    }
+    if let Some(note) = &annotation.note {
+        println!("  Note: {}", note);
+    }
+    Ok(())
+}
+
+/// List function annotations recorded for a registered binary.
+pub fn list_annotations_command(root: &str, binary: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let (binary_name, binary_hash) = resolve_binary_hash(&layout, &db, binary)?;
+    let annotations =
+        db.list_function_annotations(&binary_hash).context("Failed to list annotations")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&annotations)?);
+        return Ok(());
+    }
+
+    println!("Annotations for {}:", binary_name);
+    if annotations.is_empty() {
+        println!("(none)");
+        return Ok(());
+    }
+    let mut table = Table::new(&["address", "name", "updated", "note"]);
+    for a in annotations {
+        table.row(vec![
+            format!("0x{:X}", a.address).into(),
+            a.name.as_deref().unwrap_or("(unnamed)").into(),
+            relative_time(&a.updated_at).into(),
+            a.note.as_deref().unwrap_or("").into(),
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+/// Import function names for a registered binary from another tool's symbol map (an IDA
+/// `.map`, an `nm`/objcopy symbol listing, or Frida exports JSON).
+///
+/// Names are keyed by the binary's content hash and replace the backend's names in every
+/// later run of the binary, whichever backend runs it. Importing an address again overwrites
+/// its name; `replace` first drops every symbol previously imported for the binary. `base`
+/// is subtracted from each address (the module base of a runtime dump).
+pub fn import_symbols_command(
+    root: &str,
+    binary: &str,
+    file: &str,
+    format: &str,
+    base: Option<&str>,
+    replace: bool,
+) -> Result<()> {
+    let format = SymbolFormat::parse(format)?;
+    let base = base.map(parse_address).transpose()?.unwrap_or(0);
+    let file_path = canonicalize_or_current(file)?;
+    let text = fs::read_to_string(&file_path)
+        .with_context(|| format!("Failed to read symbol map {}", file_path.display()))?;
+    let parsed = parse_symbols(format, &text, base)
+        .with_context(|| format!("Failed to import symbols from {}", file_path.display()))?;
+
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let (binary_name, binary_hash) = resolve_binary_hash(&layout, &db, binary)?;
+
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    let source = format!("{} ({})", file_name, format);
+    let imported_at = Utc::now().to_rfc3339();
+    let removed = db
+        .transaction(|db| {
+            let removed = if replace { db.delete_imported_symbols(&binary_hash)? } else { 0 };
+            for (address, name) in &parsed.symbols {
+                db.upsert_imported_symbol(&ImportedSymbol {
+                    binary_hash: binary_hash.clone(),
+                    address: *address,
+                    name: name.clone(),
+                    source: source.clone(),
+                    imported_at: imported_at.clone(),
+                })?;
+            }
+            Ok(removed)
+        })
+        .context("Failed to store imported symbols")?;
+
+    println!(
+        "Imported {} symbol(s) for {} from {}",
+        parsed.symbols.len(),
+        binary_name,
+        file_path.display()
+    );
+    if replace {
+        println!("  Replaced: {} previously imported symbol(s)", removed);
+    }
+    if parsed.skipped > 0 {
+        let what = match format {
+            SymbolFormat::Map => "line(s)",
+            SymbolFormat::Json => "entries",
+        };
+        println!("  Skipped: {} {} without a function symbol", parsed.skipped, what);
+    }
+    Ok(())
+}
+
+/// Attach a timestamped note (a finding such as "vtable at 0x...") to the latest run of a
+/// ritual. Notes are shown by `show-ritual-run`, included in slice reports, and carried by
+/// `export-run` archives.
+pub fn annotate_run_command(root: &str, binary: &str, ritual: &str, note: &str) -> Result<()> {
+    if note.trim().is_empty() {
+        return Err(anyhow!("Run note must not be empty"));
+    }
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let run_id =
+        db.latest_run_id(binary, ritual).context("Failed to look up ritual run")?.ok_or_else(
+            || anyhow!("No ritual run found for binary '{}' / ritual '{}'", binary, ritual),
+        )?;
+    let note = db
+        .add_run_note(run_id, note, &Utc::now().to_rfc3339())
+        .context("Failed to store run note")?;
+
+    println!("Annotated run {} / {} (id {})", binary, ritual, run_id);
+    println!("  [{}] {}", note.created_at, note.note);
+    Ok(())
+}
+
+/// Resolve a binary selector to its name and content hash (computed from disk when the
+/// binary was registered with `--skip-hash`).
+pub(crate) fn resolve_binary_hash(
+    layout: &ProjectLayout,
+    db: &ProjectDb,
+    binary: &str,
+) -> Result<(String, String)> {
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let (_, record) = BinarySelector::from(binary).resolve(&binaries)?;
+    if let Some(hash) = &record.hash {
+        return Ok((record.name.clone(), hash.clone()));
+    }
+    let path = Path::new(&record.path);
+    let path = if path.is_absolute() { path.to_path_buf() } else { layout.root.join(path) };
+    if !path.is_file() {
+        return Err(anyhow!(
+            "Binary '{}' has no recorded hash and {} is missing; annotations are keyed by hash",
+            record.name,
+            path.display()
+        ));
+    }
+    Ok((record.name.clone(), sha256_file(&path)?))
+}
+
+pub(crate) fn parse_address(input: &str) -> Result<u64> {
+    let trimmed = input.trim();
+    let parsed = match trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => trimmed.parse::<u64>(),
+    };
+    parsed.map_err(|_| anyhow!("Invalid function address '{}' (use 0x-prefixed hex)", input))
+}
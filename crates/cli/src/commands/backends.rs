@@ -1,20 +1,97 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 use serde::Serialize;
 
-use ritual_core::db::BackendPaths;
-use ritual_core::services::analysis::default_backend_registry;
+use crate::canonicalize_or_current;
+use ritual_core::db::{BackendPaths, KNOWN_BACKENDS};
+use ritual_core::services::analysis::{default_backend_registry, BackendCapabilities};
+use ritual_core::services::config::UserConfig;
 
 #[derive(Debug, Serialize)]
 pub struct BackendInfo {
     pub name: String,
     pub description: String,
+    /// False for known backends whose Cargo feature is off in this build.
+    pub compiled: bool,
+    pub available: bool,
+    pub capabilities: BackendCapabilities,
 }
 
 /// List available analysis backends known to this binary.
 pub fn list_backends_command(json: bool) -> Result<()> {
+    list_backends_with_root_command(None, json)
+}
+
+/// List backends with their capabilities, resolving tool paths from the project at `root`
+/// (when given) or the user config before falling back to environment lookup.
+pub fn list_backends_with_root_command(root: Option<&str>, json: bool) -> Result<()> {
+    let paths = match root {
+        Some(root) => {
+            let layout = ritual_core::db::load_project_layout(canonicalize_or_current(root)?);
+            configured_backend_paths(&ritual_core::db::load_resolved_project_config(&layout)?)
+        }
+        None => UserConfig::load()?.backend_paths(),
+    };
+    let entries = backend_infos(&paths);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("Backends: (none)");
+        return Ok(());
+    }
+
+    println!("Backends:");
+    for entry in entries {
+        println!("- {}: {}", entry.name, entry.description);
+        let caps = &entry.capabilities;
+        match (&caps.unavailable, &caps.tool_version) {
+            (Some(reason), _) => println!("    available: no ({reason})"),
+            (None, Some(version)) => println!("    available: yes ({version})"),
+            (None, None) => println!("    available: yes"),
+        }
+        if !entry.compiled {
+            continue;
+        }
+        if !caps.archs.is_empty() {
+            println!("    archs: {}", caps.archs.join(", "));
+        }
+        let features: Vec<&str> = [
+            (caps.strings, "strings"),
+            (caps.imports, "imports"),
+            (caps.basic_blocks, "basic blocks"),
+            (caps.decompilation, "decompilation"),
+        ]
+        .into_iter()
+        .filter_map(|(on, label)| on.then_some(label))
+        .collect();
+        if !features.is_empty() {
+            println!("    extracts: {}", features.join(", "));
+        }
+        match (&caps.external_tool, &caps.tool_path) {
+            (Some(tool), Some(path)) => println!("    tool: {tool} at {}", path.display()),
+            (Some(tool), None) => println!("    tool: {tool} (not found)"),
+            (None, _) => println!("    tool: none (in-process)"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Every known backend (compiled or not) with its capabilities, sorted by name.
+pub fn backend_infos(paths: &BackendPaths) -> Vec<BackendInfo> {
     let registry = default_backend_registry();
-    let mut entries: Vec<BackendInfo> = registry
-        .names()
+    let mut names = registry.names();
+    for known in KNOWN_BACKENDS {
+        if !names.iter().any(|n| n == known) {
+            names.push(known.to_string());
+        }
+    }
+    let mut entries: Vec<BackendInfo> = names
         .into_iter()
         .map(|name| {
             let description = match name.as_str() {
@@ -22,34 +99,55 @@ pub fn list_backends_command(json: bool) -> Result<()> {
                     "Checks binary existence; placeholder until real analyzers are configured"
                         .to_string()
                 }
-                "capstone" => "Capstone-based quick disassembly (x86_64 demo)".to_string(),
+                "capstone" => "Capstone-based recursive-descent disassembly".to_string(),
+                "rizin" => "rizin analysis via `aflj`/`agfj` JSON".to_string(),
+                "wasm" => "WebAssembly modules via wasmparser (functions, calls, data strings)"
+                    .to_string(),
                 "ghidra" => {
-                    "Ghidra headless stub (requires GHIDRA_ANALYZE_HEADLESS or GHIDRA_INSTALL_DIR)"
+                    "Ghidra headless (requires GHIDRA_ANALYZE_HEADLESS or GHIDRA_INSTALL_DIR)"
+                        .to_string()
+                }
+                "ghidra-xml" => {
+                    "Ghidra XML export named by the spec's `ghidra_xml` (no Ghidra or Java needed)"
                         .to_string()
                 }
                 other => format!("Backend '{}'", other),
             };
-            BackendInfo { name: name.clone(), description }
+            let (compiled, capabilities) = match registry.get(&name) {
+                Some(backend) => {
+                    let tool_path = configured_tool_path(&name, paths);
+                    (true, backend.capabilities(tool_path.as_deref()))
+                }
+                None => (
+                    false,
+                    BackendCapabilities {
+                        unavailable: Some(format!(
+                            "not compiled into this build; enable the `{name}-backend` feature"
+                        )),
+                        ..BackendCapabilities::default()
+                    },
+                ),
+            };
+            BackendInfo {
+                name,
+                description,
+                compiled,
+                available: capabilities.is_available(),
+                capabilities,
+            }
         })
         .collect();
     entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&entries)?);
-        return Ok(());
-    }
-
-    if entries.is_empty() {
-        println!("Backends: (none)");
-        return Ok(());
+fn configured_tool_path(backend: &str, paths: &BackendPaths) -> Option<PathBuf> {
+    match backend {
+        "rizin" => paths.rizin.as_deref(),
+        "ghidra" => paths.ghidra_headless.as_deref(),
+        _ => None,
     }
-
-    println!("Backends:");
-    for entry in entries {
-        println!("- {}: {}", entry.name, entry.description);
-    }
-
-    Ok(())
+    .map(PathBuf::from)
 }
 
 /// Best-effort detection of configured backend tool paths from project config.
@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use ritual_core::analysis::baseline::BaselineComparison;
+use ritual_core::db::{RitualRunStatus, RunBaseline};
+use ritual_core::rituals::run::run_snapshot;
+use ritual_core::rituals::run::RunBinary;
+
+use crate::canonicalize_or_current;
+use crate::commands::{open_project_db, BinarySelector};
+
+/// Mark the latest run of a ritual on a binary as its baseline (or, with `clear`, remove
+/// the baseline).
+///
+/// Later runs of the ritual on the binary, or on another binary of its group, are compared
+/// against the baseline and fail on the regressions configured under `regressions.*`.
+pub fn set_baseline_command(root: &str, binary: &str, ritual: &str, clear: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let (binary_id, record) = (target.id, &target.binary);
+
+    if clear {
+        let removed =
+            db.clear_run_baseline(binary_id, ritual).context("Failed to clear baseline")?;
+        if removed == 0 {
+            println!("No baseline for {} / {}", record.name, ritual);
+        } else {
+            println!("Cleared baseline for {} / {}", record.name, ritual);
+        }
+        return Ok(());
+    }
+
+    let run_id = db
+        .latest_run_id(&target.run_name, ritual)
+        .context("Failed to look up ritual run")?
+        .ok_or_else(|| {
+            anyhow!("No ritual run found for binary '{}' / ritual '{}'", record.name, ritual)
+        })?;
+    let latest = db
+        .list_ritual_runs(Some(&target.run_name))
+        .context("Failed to list ritual runs")?
+        .into_iter()
+        .rev()
+        .find(|run| run.ritual == ritual);
+    if let Some(status @ (RitualRunStatus::Failed | RitualRunStatus::Canceled)) =
+        latest.map(|run| run.status)
+    {
+        return Err(anyhow!(
+            "Latest run of {} / {} {}; only finished runs can be baselines",
+            record.name,
+            ritual,
+            status.as_str()
+        ));
+    }
+
+    let baseline = RunBaseline {
+        binary_id,
+        binary: target.run_name.clone(),
+        ritual: ritual.to_string(),
+        run_id,
+        set_at: Utc::now().to_rfc3339(),
+    };
+    db.set_run_baseline(&baseline).context("Failed to store baseline")?;
+    let snapshot = run_snapshot(&db, run_id)?;
+
+    println!("Baseline for {} / {}: run {}", record.name, ritual, run_id);
+    println!(
+        "  Functions: {} ({} in slice), call edges: {}",
+        snapshot.counts.functions, snapshot.counts.in_slice_functions, snapshot.counts.call_edges
+    );
+    if !snapshot.unresolved_roots.is_empty() {
+        println!("  Unresolved roots: {}", snapshot.unresolved_roots.join(", "));
+    }
+    Ok(())
+}
+
+pub(crate) fn print_baseline(comparison: Option<&BaselineComparison>) {
+    let Some(comparison) = comparison else {
+        return;
+    };
+    let verdict = if comparison.is_ok() {
+        "ok".to_string()
+    } else {
+        format!("{} regression(s)", comparison.regressions.len())
+    };
+    println!(
+        "  Baseline: run {} of {} ({}): {}",
+        comparison.baseline.run_id,
+        comparison.baseline.binary,
+        verdict,
+        comparison.describe()
+    );
+}
@@ -0,0 +1,349 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::{DbResult, ProjectConfig, ProjectDb, ProjectLayout};
+use ritual_core::rituals::hooks::run_post_run_hooks;
+use ritual_core::rituals::run::{
+    compare_with_baseline, execute_run, prepare_run, record_failed_run, run_span, validate_run,
+    write_normalized_spec, write_run_outputs, PreparedRun, RunBinary, RunFlags,
+};
+use ritual_core::rituals::vars::{resolve_spec, ResolvedSpec, SpecVars};
+use ritual_core::services::analysis::{
+    default_backend_registry, persist_run, AnalysisResult, RunMetadata,
+};
+use ritual_core::services::cache::AnalysisCache;
+use ritual_core::services::config::UserConfig;
+use ritual_core::services::pipeline::PipelineState;
+use serde::Serialize;
+
+use crate::canonicalize_or_current;
+use crate::commands::{collect_ritual_specs, open_project_db};
+
+/// Outcome of one spec in a `batch-run`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchRunRow {
+    pub spec: String,
+    pub ritual: Option<String>,
+    pub binary: Option<String>,
+    pub backend: Option<String>,
+    /// `succeeded`, `failed`, or `canceled` (a backend tool hit the run's timeout or was
+    /// interrupted).
+    pub status: String,
+    pub functions: Option<usize>,
+    pub cache_hit: bool,
+    pub error: Option<String>,
+    /// Invariant violations found in the result (see `validate_result`).
+    pub validation_issues: usize,
+    /// Regressions against the ritual's baseline run (see `set-baseline`).
+    pub regressions: usize,
+}
+
+impl BatchRunRow {
+    fn failed(spec: &str, prepared: Option<&PreparedRun>, error: &anyhow::Error) -> Self {
+        Self {
+            spec: spec.to_string(),
+            ritual: prepared.map(|p| p.request.ritual_name.clone()),
+            binary: prepared.map(|p| p.request.binary_name.clone()),
+            backend: prepared.map(|p| p.meta.backend.clone()),
+            status: "failed".into(),
+            functions: None,
+            cache_hit: false,
+            error: Some(format!("{:#}", error)),
+            validation_issues: 0,
+            regressions: 0,
+        }
+    }
+}
+
+type Analyzed = Result<(AnalysisResult, bool, RunMetadata)>;
+
+/// Run every spec under `rituals/` against its binary, analyzing up to `jobs` at a time
+/// (else the user config's `jobs`, else one per CPU).
+///
+/// Specs that fail to parse/resolve, or whose output exists without `--force`, are reported
+/// as failed rows without stopping the batch. Successful runs are recorded in the project DB
+/// in one transaction after all analyses finish; analyses that fail (or are killed by
+/// `--timeout`) are recorded as `failed`/`canceled` runs. `variables` (`name=value`) fill
+/// `${name}` references in every spec. Errors when any spec failed.
+pub fn batch_run_command(
+    root: &str,
+    jobs: Option<usize>,
+    backend_override: Option<&str>,
+    flags: RunFlags,
+    variables: &[String],
+    json: bool,
+) -> Result<()> {
+    let jobs = match jobs.or(UserConfig::load()?.jobs) {
+        Some(0) => return Err(anyhow!("--jobs must be at least 1")),
+        Some(n) => n,
+        None => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+    };
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (config, _db_path, db) = open_project_db(&layout)?;
+    if !layout.rituals_dir.is_dir() {
+        return Err(anyhow!("Rituals directory not found at {}", layout.rituals_dir.display()));
+    }
+    let specs = collect_ritual_specs(&layout.rituals_dir)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let vars = SpecVars::new(&config, variables)?;
+
+    // Resolve every spec up front; failures become rows instead of aborting the batch.
+    let mut rows: Vec<Option<BatchRunRow>> = Vec::new();
+    let mut prepared: Vec<(usize, PreparedRun)> = Vec::new();
+    let mut claimed = HashSet::new();
+    for (slot, info) in specs.iter().enumerate() {
+        rows.push(None);
+        let entry = resolve_batch_entry(
+            &layout,
+            &config,
+            &db,
+            &binaries,
+            &vars,
+            &info.path,
+            backend_override,
+        )
+        .and_then(|mut run| run.load_symbols(&db).map(|()| run));
+        let mut run = match entry {
+            Ok(run) => run,
+            Err(err) => {
+                rows[slot] = Some(BatchRunRow::failed(&info.path, None, &err));
+                continue;
+            }
+        };
+        // Two specs writing the same run directory would clobber each other.
+        let ready = run.apply_flags(flags).and_then(|()| {
+            if claimed.insert(run.run_dir.clone()) {
+                prepare_output_dir(&run, flags.force)
+            } else {
+                Err(anyhow!("Another spec in this batch already targets {}", run.run_dir.display()))
+            }
+        });
+        match ready {
+            Ok(()) => prepared.push((slot, run)),
+            Err(err) => rows[slot] = Some(BatchRunRow::failed(&info.path, Some(&run), &err)),
+        }
+    }
+
+    // Analyze concurrently; workers never touch the DB.
+    let backends = default_backend_registry();
+    let cache = (!flags.no_cache).then(|| AnalysisCache::new(layout.cache_dir()));
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<Option<Analyzed>>> =
+        Mutex::new(prepared.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.min(prepared.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some((_, run)) = prepared.get(i) else { break };
+                let _span =
+                    run_span(&run.request.binary_name, &run.request.ritual_name, &run.run_dir);
+                let outcome =
+                    execute_run(run, &backends, &config, cache.as_ref(), backend_override, None);
+                outcomes.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(outcome);
+            });
+        }
+    });
+    let outcomes = outcomes.into_inner().unwrap_or_else(|e| e.into_inner());
+
+    // Record analyses that failed or timed out, then all successful runs atomically, then
+    // write the successful runs' outputs.
+    let finished: Vec<(&(usize, PreparedRun), AnalysisResult, bool, RunMetadata)> = prepared
+        .iter()
+        .zip(outcomes)
+        .filter_map(|(entry, outcome)| match outcome {
+            Some(Ok((result, cache_hit, meta))) => Some((entry, result, cache_hit, meta)),
+            Some(Err(err)) => {
+                let mut row = BatchRunRow::failed(&specs[entry.0].path, Some(&entry.1), &err);
+                match record_failed_run(&db, &entry.1, &err) {
+                    Ok(status) => {
+                        run_post_run_hooks(&config, &entry.1, &status);
+                        row.status = status.as_str().into();
+                    }
+                    Err(record_err) => tracing::warn!(
+                        "batch-run: failed to record run of {}: {:#}",
+                        specs[entry.0].path,
+                        record_err
+                    ),
+                }
+                rows[entry.0] = Some(row);
+                None
+            }
+            None => None,
+        })
+        .collect();
+    let validations: Vec<_> =
+        finished.iter().map(|((_, run), result, _, _)| validate_run(run, result)).collect();
+    let run_ids = db
+        .transaction(|db| {
+            finished
+                .iter()
+                .map(|((_, run), result, _, meta)| persist_run(db, &run.request, meta, result))
+                .collect::<DbResult<Vec<i64>>>()
+        })
+        .context("Failed to record batch runs in project DB")?;
+    for ((((slot, run), result, cache_hit, meta), validation), run_id) in
+        finished.iter().zip(&validations).zip(&run_ids)
+    {
+        let written =
+            compare_with_baseline(&db, &config, run.binary_id, &run.request.ritual_name, *run_id)
+                .and_then(|baseline| {
+                    write_run_outputs(run, result, meta, validation, baseline.as_ref())?;
+                    Ok(baseline)
+                });
+        rows[*slot] = Some(match written {
+            Ok(baseline) => {
+                run_post_run_hooks(&config, run, &meta.status);
+                BatchRunRow {
+                    spec: specs[*slot].path.clone(),
+                    ritual: Some(run.request.ritual_name.clone()),
+                    binary: Some(run.request.binary_name.clone()),
+                    backend: Some(meta.backend.clone()),
+                    status: "succeeded".into(),
+                    functions: Some(result.functions.len()),
+                    cache_hit: *cache_hit,
+                    error: None,
+                    validation_issues: validation.issues.len(),
+                    regressions: baseline.map_or(0, |b| b.regressions.len()),
+                }
+            }
+            Err(err) => BatchRunRow::failed(&specs[*slot].path, Some(run), &err),
+        });
+    }
+
+    let rows: Vec<BatchRunRow> = rows.into_iter().flatten().collect();
+    let failed = rows.iter().filter(|r| r.status != "succeeded").count();
+    if json {
+        let payload = serde_json::json!({
+            "jobs": jobs,
+            "succeeded": rows.len() - failed,
+            "failed": failed,
+            "runs": rows,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        print_batch_table(&rows, jobs);
+    }
+    if failed > 0 {
+        return Err(anyhow!("{} of {} ritual run(s) failed", failed, rows.len()));
+    }
+    let regressed = rows.iter().filter(|r| r.regressions > 0).count();
+    if regressed > 0 {
+        return Err(anyhow!(
+            "{} of {} ritual run(s) regressed against their baseline (see \"baseline\" in their report.json)",
+            regressed,
+            rows.len()
+        ));
+    }
+    let invalid = rows.iter().filter(|r| r.validation_issues > 0).count();
+    if flags.strict && invalid > 0 {
+        return Err(anyhow!(
+            "{} of {} ritual run(s) failed validation (see \"validation\" in their report.json)",
+            invalid,
+            rows.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Parse (filling in variables), validate, and resolve one spec against its binary.
+fn resolve_batch_entry(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    db: &ProjectDb,
+    binaries: &[(i64, ritual_core::db::BinaryRecord)],
+    vars: &SpecVars,
+    spec_path: &str,
+    backend_override: Option<&str>,
+) -> Result<PreparedRun> {
+    let path = Path::new(spec_path);
+    let bytes =
+        fs::read(path).with_context(|| format!("Failed to read ritual spec at {}", spec_path))?;
+    let json = path.extension().and_then(|e| e.to_str()) == Some("json");
+    let ResolvedSpec { spec, spec_hash, .. } = resolve_spec(&bytes, json, vars, binaries)?;
+    spec.validate()?;
+    let target = RunBinary::new(db, spec.binary.resolve(binaries)?)?;
+    let ritual = spec.name.clone();
+    let mut prepared =
+        prepare_run(layout, config, &target, spec, &spec_hash, &ritual, backend_override)?;
+    prepared.set_spec_file(path);
+    Ok(prepared)
+}
+
+/// Create (or, with `force`, recreate) a run's output directory and write its spec.
+///
+/// An interrupted pipeline of the same spec keeps its directory so it can resume.
+fn prepare_output_dir(run: &PreparedRun, force: bool) -> Result<()> {
+    let resume = !run.spec.stages.is_empty()
+        && !force
+        && PipelineState::load(&run.run_dir)?.is_some_and(|s| s.can_resume(&run.meta.spec_hash));
+    if run.run_dir.exists() && !resume {
+        if !force {
+            return Err(anyhow!(
+                "Ritual output already exists at {} (rerun with --force to overwrite)",
+                run.run_dir.display()
+            ));
+        }
+        fs::remove_dir_all(&run.run_dir).with_context(|| {
+            format!("Failed to clean existing ritual output dir {}", run.run_dir.display())
+        })?;
+    }
+    fs::create_dir_all(&run.run_dir)
+        .with_context(|| format!("Failed to create ritual output dir {}", run.run_dir.display()))?;
+    write_normalized_spec(run)
+}
+
+fn print_batch_table(rows: &[BatchRunRow], jobs: usize) {
+    let header = ["RITUAL", "BINARY", "BACKEND", "STATUS", "FUNCS", "DETAIL"];
+    let cells: Vec<[String; 6]> = rows
+        .iter()
+        .map(|r| {
+            let detail = match &r.error {
+                Some(err) => err.clone(),
+                None => {
+                    let mut notes = Vec::new();
+                    if r.cache_hit {
+                        notes.push("cached".to_string());
+                    }
+                    if r.validation_issues > 0 {
+                        notes.push(format!("{} validation issue(s)", r.validation_issues));
+                    }
+                    if r.regressions > 0 {
+                        notes.push(format!("{} regression(s)", r.regressions));
+                    }
+                    notes.join(", ")
+                }
+            };
+            [
+                r.ritual.clone().unwrap_or_else(|| r.spec.clone()),
+                r.binary.clone().unwrap_or_else(|| "-".into()),
+                r.backend.clone().unwrap_or_else(|| "-".into()),
+                r.status.clone(),
+                r.functions.map(|n| n.to_string()).unwrap_or_else(|| "-".into()),
+                detail,
+            ]
+        })
+        .collect();
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in &cells {
+        for (w, cell) in widths.iter_mut().zip(row.iter()) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    let line = |values: Vec<&str>| {
+        let padded: Vec<String> =
+            values.iter().zip(&widths).map(|(v, w)| format!("{:<w$}", v, w = *w)).collect();
+        padded.join("  ").trim_end().to_string()
+    };
+    println!("Batch run ({} spec(s), {} job(s))", rows.len(), jobs);
+    println!("{}", line(header.to_vec()));
+    for row in &cells {
+        println!("{}", line(row.iter().map(|c| c.as_str()).collect()));
+    }
+    let failed = rows.iter().filter(|r| r.status != "succeeded").count();
+    println!("{} succeeded, {} failed", rows.len() - failed, failed);
+}
@@ -1,10 +1,37 @@
 use std::path::Path;
 
-use crate::commands::open_project_db;
+use crate::commands::{open_project_db, tags_by, tags_cell, BinarySelector};
+use crate::output::Table;
 use crate::{canonicalize_or_current, sha256_file};
 use anyhow::{anyhow, Context, Result};
+use ritual_core::db::TagKind;
+use ritual_core::rituals::run::RunBinary;
+use ritual_core::services::binary_info::{binary_info, BinaryInfo};
+use ritual_core::services::image::BinaryImage;
+use ritual_core::services::import::pdb::{read_pdb_identity, PdbIdentity};
+use ritual_core::services::loader::pe_pdb_reference;
+use serde::Serialize;
 
-/// Register a binary in the project database.
+/// Group membership for a binary being registered (`add-binary --group`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinaryGroupOptions {
+    /// Logical binary the build belongs to (created on first use).
+    pub group: String,
+    /// Build label, unique within the group (e.g., "v1.3").
+    pub version: Option<String>,
+    /// Toolchain build identifier (GNU build-id, Mach-O UUID, PDB GUID).
+    pub build_id: Option<String>,
+}
+
+/// Optional extras of `add-binary`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddBinaryOptions {
+    pub group: Option<BinaryGroupOptions>,
+    /// PDB to associate with the binary (`--pdb`).
+    pub pdb: Option<String>,
+}
+
+/// Register a binary in the project database, optionally as a build of a binary group.
 pub fn add_binary_command(
     root: &str,
     path: &str,
@@ -12,9 +39,31 @@ pub fn add_binary_command(
     arch: Option<String>,
     hash: Option<String>,
     skip_hash: bool,
+    group: Option<BinaryGroupOptions>,
+) -> Result<()> {
+    let options = AddBinaryOptions { group, pdb: None };
+    add_binary_with_options(root, path, name, arch, hash, skip_hash, options)
+}
+
+/// [`add_binary_command`] with every option: a group membership and an associated PDB.
+///
+/// The binary's headers are read for its format, architecture, build id, libraries, and
+/// whether it is stripped; the detected architecture fills in a missing `arch` and the
+/// build id a missing group build id. The PDB's GUID and age must match the CodeView record
+/// of the binary (when it has one); they are stored so later runs notice a rebuilt binary or
+/// a replaced PDB.
+pub fn add_binary_with_options(
+    root: &str,
+    path: &str,
+    name: Option<String>,
+    arch: Option<String>,
+    hash: Option<String>,
+    skip_hash: bool,
+    options: AddBinaryOptions,
 ) -> Result<()> {
+    let AddBinaryOptions { mut group, pdb } = options;
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     let (_config, db_path, db) = open_project_db(&layout)?;
 
@@ -30,18 +79,7 @@ pub fn add_binary_command(
         return Err(anyhow!("Binary file does not exist: {}", abs_path.display()));
     }
 
-    // Store path relative to project root when possible.
-    let rel_path = abs_path
-        .canonicalize()
-        .ok()
-        .and_then(|abs_canon| {
-            root_path.canonicalize().ok().and_then(|root_canon| {
-                abs_canon.strip_prefix(&root_canon).ok().map(|p| p.to_path_buf())
-            })
-        })
-        .or_else(|| abs_path.strip_prefix(&root_path).ok().map(|p| p.to_path_buf()))
-        .unwrap_or_else(|| abs_path.clone());
-    let rel_path_str = rel_path.to_string_lossy().to_string();
+    let rel_path_str = project_relative(&root_path, &abs_path);
 
     let binary_name = name.unwrap_or_else(|| {
         input_path.file_name().and_then(|os| os.to_str()).unwrap_or(path).to_string()
@@ -55,30 +93,202 @@ pub fn add_binary_command(
         Some(sha256_file(&abs_path)?)
     };
 
+    let info = BinaryImage::open(&abs_path).ok().and_then(|image| binary_info(&image));
+    let detected_arch = info.as_ref().and_then(|info| info.arch.clone());
+    if let (Some(given), Some(detected)) = (&arch, &detected_arch) {
+        if !given.eq_ignore_ascii_case(detected) {
+            println!(
+                "Warning: --arch {} differs from the detected architecture {}",
+                given, detected
+            );
+        }
+    }
+    let arch = arch.or(detected_arch);
+    if let (Some(opts), Some(info)) = (&mut group, &info) {
+        if opts.build_id.is_none() {
+            opts.build_id = info.build_id.clone();
+        }
+    }
+
     let record =
         ritual_core::db::BinaryRecord { name: binary_name, path: rel_path_str, arch, hash };
 
+    // Reject a duplicate build label before inserting, so a failure leaves no orphan row.
+    if let Some(opts) = &group {
+        if opts.group.trim().is_empty() {
+            return Err(anyhow!("Binary group name must not be empty"));
+        }
+        let groups = db.list_binary_groups().context("Failed to list binary groups")?;
+        let taken = groups
+            .iter()
+            .filter(|g| g.name == opts.group)
+            .flat_map(|g| &g.members)
+            .any(|m| opts.version.is_some() && m.version == opts.version);
+        if taken {
+            return Err(anyhow!(
+                "Binary group '{}' already has a build with version '{}'",
+                opts.group,
+                opts.version.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    let pdb = pdb.map(|pdb| check_pdb(&root_path, &abs_path, &pdb)).transpose()?;
+
     let id = db.insert_binary(&record).context("Failed to insert binary record")?;
+    if let Some(info) = &info {
+        db.set_binary_info(id, info).context("Failed to store binary metadata")?;
+    }
+    if let Some((pdb_path, identity)) = &pdb {
+        db.set_binary_pdb(&ritual_core::db::BinaryPdb {
+            binary_id: id,
+            path: pdb_path.clone(),
+            guid: identity.guid.clone(),
+            age: identity.age,
+            associated_at: chrono::Utc::now().to_rfc3339(),
+        })
+        .context("Failed to associate the PDB")?;
+    }
+    if let Some(opts) = &group {
+        let group_id = db
+            .ensure_binary_group(&opts.group, &chrono::Utc::now().to_rfc3339())
+            .context("Failed to create binary group")?;
+        db.assign_binary_group(id, group_id, opts.version.as_deref(), opts.build_id.as_deref())
+            .context("Failed to add binary to group")?;
+    }
 
     println!("Added binary:");
     println!("  Id: {}", id);
     println!("  Name: {}", record.name);
     println!("  Path (relative): {}", record.path);
+    if let Some(info) = &info {
+        println!("  Format: {}", describe_binary_info(info));
+        if let Some(build_id) = info.build_id.as_ref().filter(|_| group.is_none()) {
+            println!("  Build id: {}", build_id);
+        }
+        if !info.libraries.is_empty() {
+            println!("  Libraries: {}", info.libraries.join(", "));
+        }
+    }
+    if let Some(opts) = &group {
+        let version = opts.version.as_deref().unwrap_or("(unversioned)");
+        println!("  Group: {} ({})", opts.group, version);
+        if let Some(build_id) = &opts.build_id {
+            println!("  Build id: {}", build_id);
+        }
+    }
+    if let Some((pdb_path, identity)) = &pdb {
+        println!("  PDB: {} ({})", pdb_path, identity);
+    }
     println!("  DB: {}", db_path.display());
 
     Ok(())
 }
 
-/// List all binaries registered in the project database.
-pub fn list_binaries_command(root: &str, json: bool) -> Result<()> {
+/// One-line summary of a binary's header facts, e.g.
+/// `ELF (x86_64, 64-bit, little-endian, not stripped)`.
+pub fn describe_binary_info(info: &BinaryInfo) -> String {
+    let mut facts = Vec::new();
+    facts.extend(info.arch.clone());
+    if !info.slices.is_empty() {
+        facts.push(info.slices.join(" + "));
+    }
+    facts.extend(info.bits.map(|bits| format!("{bits}-bit")));
+    facts.extend(info.endian.map(|endian| format!("{}-endian", endian.as_str())));
+    match info.stripped {
+        Some(true) => facts.push("stripped".into()),
+        Some(false) => facts.push("not stripped".into()),
+        None => {}
+    }
+    if facts.is_empty() {
+        info.format.to_string()
+    } else {
+        format!("{} ({})", info.format, facts.join(", "))
+    }
+}
+
+/// First 12 hex digits of a content hash for human listings, or `-` without one.
+fn short_hash(hash: Option<&str>) -> String {
+    hash.map(|h| h.get(..12).unwrap_or(h).to_string()).unwrap_or_else(|| "-".into())
+}
+
+/// `abs_path` relative to the project root when it lies inside it, otherwise as given.
+fn project_relative(root_path: &Path, abs_path: &Path) -> String {
+    abs_path
+        .canonicalize()
+        .ok()
+        .and_then(|abs_canon| {
+            root_path.canonicalize().ok().and_then(|root_canon| {
+                abs_canon.strip_prefix(&root_canon).ok().map(|p| p.to_path_buf())
+            })
+        })
+        .or_else(|| abs_path.strip_prefix(root_path).ok().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| abs_path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Read the identity of the PDB at `pdb` and check it against the binary's CodeView record;
+/// returns the path to store and the identity.
+fn check_pdb(root_path: &Path, binary: &Path, pdb: &str) -> Result<(String, PdbIdentity)> {
+    let abs_pdb = root_path.join(pdb);
+    if !abs_pdb.is_file() {
+        return Err(anyhow!("PDB file does not exist: {}", abs_pdb.display()));
+    }
+    let identity = read_pdb_identity(&abs_pdb)
+        .with_context(|| format!("Failed to read PDB {}", abs_pdb.display()))?;
+    let image = BinaryImage::open(binary)
+        .with_context(|| format!("Failed to read binary {}", binary.display()))?;
+    if let Some(reference) = pe_pdb_reference(&image) {
+        if !reference.identity.matches(&identity) {
+            return Err(anyhow!(
+                "PDB {} ({}) does not match {}, which was linked with {} ({})",
+                abs_pdb.display(),
+                identity,
+                binary.display(),
+                reference.path,
+                reference.identity
+            ));
+        }
+    }
+    Ok((project_relative(root_path, &abs_pdb), identity))
+}
+
+/// A binary as listed by `list-binaries --json`: the record plus its tags and the header
+/// facts read when it was added.
+#[derive(Debug, Serialize)]
+struct BinaryListing<'a> {
+    #[serde(flatten)]
+    binary: &'a ritual_core::db::BinaryRecord,
+    tags: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<&'a BinaryInfo>,
+}
+
+/// List all binaries registered in the project database, optionally only those tagged `tag`.
+pub fn list_binaries_command(root: &str, json: bool, tag: Option<&str>) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     let (_config, _db_path, db) = open_project_db(&layout)?;
-    let binaries = db.list_binaries().context("Failed to list binaries")?;
+    let tags = tags_by(&db, TagKind::Binary, |t| t.target_id)?;
+    let no_tags = Vec::new();
+    let mut binaries = Vec::new();
+    for (id, bin) in db.list_binaries_with_ids().context("Failed to list binaries")? {
+        let bin_tags = tags.get(&id).unwrap_or(&no_tags).as_slice();
+        if tag.is_some_and(|t| !bin_tags.iter().any(|have| have == t)) {
+            continue;
+        }
+        let info = db.binary_info(id).context("Failed to read binary metadata")?;
+        binaries.push((bin_tags, bin, info));
+    }
 
     if json {
-        let serialized = serde_json::to_string_pretty(&binaries)?;
+        let listing: Vec<BinaryListing> = binaries
+            .iter()
+            .map(|(tags, binary, info)| BinaryListing { binary, tags, metadata: info.as_ref() })
+            .collect();
+        let serialized = serde_json::to_string_pretty(&listing)?;
         println!("{}", serialized);
         return Ok(());
     }
@@ -90,14 +300,352 @@ pub fn list_binaries_command(root: &str, json: bool) -> Result<()> {
     }
 
     println!("Binaries:");
-    for bin in binaries {
-        let arch_display = bin.arch.as_deref().unwrap_or("(unspecified)");
-        let hash_display = bin.hash.as_deref().unwrap_or("(none)");
-        println!(
-            "- {} (path: {}, arch: {}, hash: {})",
-            bin.name, bin.path, arch_display, hash_display
-        );
+    let mut table = Table::new(&["name", "arch", "hash", "build id", "tags", "format", "path"]);
+    let mut libraries = Vec::new();
+    for (bin_tags, bin, info) in binaries {
+        let format = info.as_ref().map(describe_binary_info).unwrap_or_else(|| "-".into());
+        let build_id = info.as_ref().and_then(|info| info.build_id.clone());
+        if let Some(info) = info.filter(|info| !info.libraries.is_empty()) {
+            libraries.push(format!("{}: {}", bin.name, info.libraries.join(", ")));
+        }
+        table.row(vec![
+            bin.name.as_str().into(),
+            bin.arch.as_deref().unwrap_or("-").into(),
+            short_hash(bin.hash.as_deref()).into(),
+            build_id.unwrap_or_else(|| "-".into()).into(),
+            tags_cell(bin_tags).into(),
+            format.into(),
+            bin.path.as_str().into(),
+        ]);
+    }
+    table.print();
+    if !libraries.is_empty() {
+        println!();
+        println!("Libraries:");
+        for line in libraries {
+            println!("  {}", line);
+        }
     }
 
     Ok(())
 }
+
+/// List binary groups and the builds registered in each.
+pub fn list_binary_groups_command(root: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let groups = db.list_binary_groups().context("Failed to list binary groups")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&groups)?);
+        return Ok(());
+    }
+
+    println!("Binary groups:");
+    if groups.is_empty() {
+        println!("(none)");
+        return Ok(());
+    }
+    let mut table = Table::new(&["group", "version", "binary", "arch", "hash", "build id", "path"]);
+    for group in groups {
+        for m in group.members {
+            table.row(vec![
+                group.name.as_str().into(),
+                m.version.as_deref().unwrap_or("(unversioned)").into(),
+                m.binary.name.as_str().into(),
+                m.binary.arch.as_deref().unwrap_or("-").into(),
+                short_hash(m.binary.hash.as_deref()).into(),
+                m.build_id.as_deref().unwrap_or("-").into(),
+                m.binary.path.as_str().into(),
+            ]);
+        }
+    }
+    table.print();
+    Ok(())
+}
+
+/// How a registered binary compares with the file on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryDrift {
+    /// The file hashes to the recorded hash.
+    Ok,
+    /// The file hashes to something else (e.g. a game patch replaced it).
+    Changed,
+    /// No hash was recorded (`add-binary --skip-hash`).
+    Unhashed,
+    /// The file is gone.
+    Missing,
+}
+
+/// Result of re-hashing one registered binary.
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryVerification {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub status: BinaryDrift,
+    pub recorded_hash: Option<String>,
+    pub current_hash: Option<String>,
+    /// Whether `--update` rewrote the recorded hash.
+    pub updated: bool,
+    /// Runs newly marked `outdated` by `--mark-outdated`.
+    pub outdated_runs: usize,
+}
+
+/// What `remove-binary`/`rename-binary` do with ritual runs recorded for the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunHandling {
+    /// Fail when the binary has runs (in the DB or under `outputs/binaries/<name>`).
+    #[default]
+    Refuse,
+    /// Remove (or rename) the runs and their outputs along with the binary.
+    Cascade,
+    /// Leave the runs and outputs under the binary's current name, detached from it.
+    Keep,
+}
+
+impl RunHandling {
+    /// Map the `--cascade`/`--keep-runs` flags (mutually exclusive) to a handling.
+    pub fn from_flags(cascade: bool, keep_runs: bool) -> Self {
+        match (cascade, keep_runs) {
+            (true, _) => RunHandling::Cascade,
+            (false, true) => RunHandling::Keep,
+            (false, false) => RunHandling::Refuse,
+        }
+    }
+}
+
+/// Runs recorded for a binary (by id, or by name for legacy runs) and its output directory
+/// (named after its run name) when that holds anything. Errors under [`RunHandling::Refuse`]
+/// if either exists.
+fn binary_references(
+    db: &ritual_core::db::ProjectDb,
+    layout: &ritual_core::db::ProjectLayout,
+    target: &RunBinary,
+    runs: RunHandling,
+) -> Result<(usize, Option<std::path::PathBuf>)> {
+    let (id, name) = (target.id, &target.binary.name);
+    let run_count = db
+        .list_ritual_runs(None)
+        .context("Failed to list ritual runs")?
+        .iter()
+        .filter(|r| r.binary_id == Some(id) || (r.binary_id.is_none() && &r.binary == name))
+        .count();
+    let outputs = layout.binary_output_root(&target.run_name);
+    let outputs = std::fs::read_dir(&outputs)
+        .is_ok_and(|mut entries| entries.next().is_some())
+        .then_some(outputs);
+    if runs == RunHandling::Refuse && (run_count > 0 || outputs.is_some()) {
+        let outputs_note = outputs
+            .as_ref()
+            .map(|p| format!(" and outputs at {}", p.display()))
+            .unwrap_or_default();
+        return Err(anyhow!(
+            "Binary '{}' has {} ritual run(s){}; pass --cascade to include them or --keep-runs to leave them",
+            name,
+            run_count,
+            outputs_note
+        ));
+    }
+    Ok((run_count, outputs))
+}
+
+/// Unregister a binary.
+///
+/// Runs recorded for it are refused, deleted with their analysis rows and
+/// `outputs/binaries/<run name>` ([`RunHandling::Cascade`]), or kept under their name
+/// ([`RunHandling::Keep`]). The binary file itself is never touched.
+pub fn remove_binary_command(root: &str, binary: &str, runs: RunHandling) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let (id, record) = (target.id, &target.binary);
+    let (run_count, outputs) = binary_references(&db, &layout, &target, runs)?;
+
+    db.transaction(|db| {
+        match runs {
+            RunHandling::Cascade => db.delete_binary_runs(id, &record.name)?,
+            RunHandling::Keep => db.detach_binary_runs(id)?,
+            RunHandling::Refuse => 0,
+        };
+        db.delete_binary(id)
+    })
+    .with_context(|| format!("Failed to remove binary {}", record.name))?;
+
+    println!("Removed binary: {} (id {})", record.name, id);
+    match runs {
+        RunHandling::Cascade => {
+            if let Some(outputs) = &outputs {
+                std::fs::remove_dir_all(outputs).with_context(|| {
+                    format!("Failed to remove outputs at {}", outputs.display())
+                })?;
+                println!("  Removed outputs: {}", outputs.display());
+            }
+            println!("  Deleted {} run(s)", run_count);
+        }
+        RunHandling::Keep if run_count > 0 || outputs.is_some() => {
+            println!("  Kept {} run(s) under '{}'", run_count, target.run_name);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Rename a registered binary; slices whose default binary it was follow the new name.
+///
+/// Runs recorded for it are refused, renamed with `outputs/binaries/<run name>` moved to the
+/// new name ([`RunHandling::Cascade`]), or left under their old name ([`RunHandling::Keep`]).
+pub fn rename_binary_command(
+    root: &str,
+    binary: &str,
+    new_name: &str,
+    runs: RunHandling,
+) -> Result<()> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err(anyhow!("New binary name must not be empty"));
+    }
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let (id, record) = (target.id, &target.binary);
+    if binaries.iter().any(|(_, b)| b.name == new_name) {
+        return Err(anyhow!("A binary named '{}' is already registered", new_name));
+    }
+    let (run_count, outputs) = binary_references(&db, &layout, &target, runs)?;
+    let new_outputs = layout.binary_output_root(new_name);
+    if runs == RunHandling::Cascade && outputs.is_some() && new_outputs.exists() {
+        return Err(anyhow!("Outputs already exist at {}", new_outputs.display()));
+    }
+
+    let renamed = db
+        .transaction(|db| {
+            if runs == RunHandling::Keep {
+                db.detach_binary_runs(id)?;
+            }
+            db.rename_binary(id, &record.name, new_name, runs == RunHandling::Cascade)
+        })
+        .with_context(|| format!("Failed to rename binary {}", record.name))?;
+
+    println!("Renamed binary: {} -> {} (id {})", record.name, new_name, id);
+    match runs {
+        RunHandling::Cascade => {
+            if let Some(outputs) = &outputs {
+                std::fs::rename(outputs, &new_outputs).with_context(|| {
+                    format!("Failed to move {} to {}", outputs.display(), new_outputs.display())
+                })?;
+                println!("  Moved outputs: {}", new_outputs.display());
+            }
+            println!("  Renamed {} run(s)", renamed);
+        }
+        RunHandling::Keep if run_count > 0 || outputs.is_some() => {
+            println!("  Kept {} run(s) under '{}'", run_count, target.run_name);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Re-hash every registered binary and report drift from the recorded hashes.
+///
+/// `update` stores the current hash of changed/unhashed binaries; `mark_outdated` flags runs
+/// whose `binary_hash` no longer matches the file as `outdated`. Fails when drift remains
+/// afterwards (missing files, or changes without `update`), so it can gate scripts.
+pub fn verify_binaries_command(
+    root: &str,
+    update: bool,
+    mark_outdated: bool,
+    json: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+
+    let mut report = Vec::new();
+    for (id, binary) in db.list_binaries_with_ids().context("Failed to list binaries")? {
+        let path = Path::new(&binary.path);
+        let abs_path = if path.is_absolute() { path.to_path_buf() } else { layout.root.join(path) };
+        let current_hash = if abs_path.is_file() { Some(sha256_file(&abs_path)?) } else { None };
+        let status = match (&binary.hash, &current_hash) {
+            (_, None) => BinaryDrift::Missing,
+            (None, Some(_)) => BinaryDrift::Unhashed,
+            (Some(recorded), Some(current)) if recorded == current => BinaryDrift::Ok,
+            (Some(_), Some(_)) => BinaryDrift::Changed,
+        };
+
+        let mut entry = BinaryVerification {
+            id,
+            name: binary.name.clone(),
+            path: binary.path.clone(),
+            status,
+            recorded_hash: binary.hash.clone(),
+            current_hash: current_hash.clone(),
+            updated: false,
+            outdated_runs: 0,
+        };
+        if let Some(current) = &current_hash {
+            if update && matches!(status, BinaryDrift::Changed | BinaryDrift::Unhashed) {
+                db.update_binary_hash(id, Some(current))
+                    .with_context(|| format!("Failed to update hash of binary {}", binary.name))?;
+                entry.updated = true;
+            }
+            if mark_outdated {
+                entry.outdated_runs = db
+                    .mark_runs_outdated(id, &binary.name, current)
+                    .with_context(|| format!("Failed to mark runs of {} outdated", binary.name))?;
+            }
+        }
+        report.push(entry);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Binaries:");
+        if report.is_empty() {
+            println!("(none)");
+        }
+        for entry in &report {
+            let detail = match entry.status {
+                BinaryDrift::Ok => String::new(),
+                BinaryDrift::Missing => format!(" (file not found: {})", entry.path),
+                BinaryDrift::Unhashed | BinaryDrift::Changed => format!(
+                    " ({} -> {})",
+                    entry.recorded_hash.as_deref().unwrap_or("(none)"),
+                    entry.current_hash.as_deref().unwrap_or("(none)")
+                ),
+            };
+            let status = serde_json::to_value(entry.status)?;
+            println!("- {}: {}{}", entry.name, status.as_str().unwrap_or_default(), detail);
+            if entry.updated {
+                println!("  Updated recorded hash");
+            }
+            if entry.outdated_runs > 0 {
+                println!("  Marked {} run(s) outdated", entry.outdated_runs);
+            }
+        }
+    }
+
+    let drifted = report
+        .iter()
+        .filter(|e| match e.status {
+            BinaryDrift::Ok | BinaryDrift::Unhashed => false,
+            BinaryDrift::Changed => !e.updated,
+            BinaryDrift::Missing => true,
+        })
+        .count();
+    if drifted > 0 {
+        return Err(anyhow!(
+            "{} binary(ies) drifted from the project DB (rerun with --update to record new hashes)",
+            drifted
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use ritual_core::db::load_project_layout;
+use ritual_core::services::cache::AnalysisCache;
+
+use crate::canonicalize_or_current;
+
+/// Show entries and disk usage of the project analysis cache.
+pub fn cache_stats_command(root: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let cache = AnalysisCache::new(layout.cache_dir());
+    let stats = cache
+        .stats()
+        .with_context(|| format!("Failed to read cache at {}", cache.dir().display()))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!("Analysis cache: {}", stats.dir);
+    println!("  Entries: {}", stats.entries);
+    println!("  Size: {} bytes", stats.total_bytes);
+    if stats.unreadable > 0 {
+        println!("  Unreadable files: {} (removed by cache-clear)", stats.unreadable);
+    }
+    for item in &stats.items {
+        let hash = item.binary_hash.get(..12).unwrap_or(&item.binary_hash);
+        let version = item.backend_version.as_deref().unwrap_or("(unknown)");
+        println!(
+            "- {} {} {} binary {} ({} bytes, {})",
+            &item.digest[..item.digest.len().min(12)],
+            item.backend,
+            version,
+            hash,
+            item.bytes,
+            item.created_at
+        );
+    }
+    Ok(())
+}
+
+/// Remove every cached analysis result.
+pub fn cache_clear_command(root: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let cache = AnalysisCache::new(layout.cache_dir());
+    let (removed, freed) = cache
+        .clear()
+        .with_context(|| format!("Failed to clear cache at {}", cache.dir().display()))?;
+    println!(
+        "Removed {} cache entr{} ({} bytes)",
+        removed,
+        if removed == 1 { "y" } else { "ies" },
+        freed
+    );
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use crate::canonicalize_or_current;
+use crate::commands::load_project_config;
+use ritual_core::db::save_project_config;
+use ritual_core::services::analysis::default_backend_registry;
+
+/// Print every config key (unset optional keys show as `(unset)`).
+pub fn config_list_command(root: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let config = load_project_config(&layout)?;
+    let entries = config.entries();
+
+    if json {
+        let map: BTreeMap<String, Option<String>> = entries.into_iter().collect();
+        println!("{}", serde_json::to_string_pretty(&map)?);
+        return Ok(());
+    }
+    for (key, value) in entries {
+        println!("{} = {}", key, value.as_deref().unwrap_or("(unset)"));
+    }
+    Ok(())
+}
+
+/// Print one config key's value; an unset optional key prints nothing.
+pub fn config_get_command(root: &str, key: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let config = load_project_config(&layout)?;
+    if let Some(value) = config.get_key(key)? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Validate and store a config key.
+pub fn config_set_command(root: &str, key: &str, value: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let mut config = load_project_config(&layout)?;
+    config.set_key(key, value)?;
+    save_project_config(&layout, &config)?;
+    println!("Set {} = {}", key, value);
+    let registry = default_backend_registry();
+    let names = match key {
+        "default_backend" => vec![value.to_string()],
+        "backend_priority" => config.backend_priority.clone(),
+        _ => Vec::new(),
+    };
+    for name in names.iter().filter(|name| registry.get(name).is_none()) {
+        eprintln!(
+            "Warning: backend '{}' is not compiled into this build (enable its cargo feature)",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Clear an optional config key.
+pub fn config_unset_command(root: &str, key: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let mut config = load_project_config(&layout)?;
+    config.unset_key(key)?;
+    save_project_config(&layout, &config)?;
+    println!("Unset {}", key);
+    Ok(())
+}
@@ -0,0 +1,458 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::DateTime;
+use ritual_core::db::{
+    load_project_layout, LockHolder, ProjectLayout, ProjectLock, RitualRunStatus, SliceStatus,
+};
+use ritual_core::rituals::run::RUN_LOG_FILE;
+use serde::Serialize;
+
+use crate::canonicalize_or_current;
+use crate::commands::open_project_db;
+use crate::output::print_table;
+
+/// Runs shown by the dashboard, most recent first.
+pub const DASHBOARD_RECENT_RUNS: usize = 10;
+/// Lines shown from the end of the current run log.
+pub const DASHBOARD_LOG_LINES: usize = 12;
+
+/// What the dashboard shows at one refresh.
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardSnapshot {
+    pub project: String,
+    pub binaries: Vec<DashboardBinary>,
+    pub slices: Vec<DashboardSlice>,
+    /// Most recent first.
+    pub recent_runs: Vec<DashboardRun>,
+    /// Command holding the project lock (usually a run in progress); `None` when idle.
+    pub active: Option<LockHolder>,
+    /// End of the most recently written run log (`--log-json` runs only).
+    pub log: Option<DashboardLog>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardBinary {
+    pub name: String,
+    pub arch: Option<String>,
+    /// Recorded runs of the binary.
+    pub runs: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardSlice {
+    pub name: String,
+    pub status: SliceStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardRun {
+    pub binary: String,
+    pub ritual: String,
+    pub backend: String,
+    pub status: RitualRunStatus,
+    pub started_at: String,
+    pub finished_at: String,
+    /// `None` when either timestamp does not parse.
+    pub duration_secs: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardLog {
+    pub path: PathBuf,
+    /// Formatted like the stderr log (`HH:MM:SS LEVEL message key=value`).
+    pub lines: Vec<String>,
+}
+
+/// Read the project state the dashboard shows.
+pub fn collect_dashboard(layout: &ProjectLayout) -> Result<DashboardSnapshot> {
+    let (config, _db_path, db) = open_project_db(layout)?;
+    let runs = db.list_ritual_runs(None).context("Failed to list ritual runs")?;
+    let binaries = db
+        .list_binaries_with_ids()
+        .context("Failed to list binaries")?
+        .into_iter()
+        .map(|(id, binary)| DashboardBinary {
+            runs: runs
+                .iter()
+                .filter(|r| r.binary_id.map_or(r.binary == binary.name, |b| b == id))
+                .count(),
+            name: binary.name,
+            arch: binary.arch,
+        })
+        .collect();
+    let slices = db
+        .list_slices()
+        .context("Failed to list slices")?
+        .into_iter()
+        .filter(|s| s.archived_at.is_none())
+        .map(|s| DashboardSlice { name: s.name, status: s.status })
+        .collect();
+    let recent_runs = runs
+        .into_iter()
+        .rev()
+        .take(DASHBOARD_RECENT_RUNS)
+        .map(|run| DashboardRun {
+            duration_secs: run_duration(&run.started_at, &run.finished_at),
+            binary: run.binary,
+            ritual: run.ritual,
+            backend: run.backend,
+            status: run.status,
+            started_at: run.started_at,
+            finished_at: run.finished_at,
+        })
+        .collect();
+    Ok(DashboardSnapshot {
+        project: config.name,
+        binaries,
+        slices,
+        recent_runs,
+        active: ProjectLock::holder(layout),
+        log: latest_run_log(layout, DASHBOARD_LOG_LINES),
+    })
+}
+
+fn run_duration(started_at: &str, finished_at: &str) -> Option<f64> {
+    let started = DateTime::parse_from_rfc3339(started_at).ok()?;
+    let finished = DateTime::parse_from_rfc3339(finished_at).ok()?;
+    Some((finished - started).num_milliseconds().max(0) as f64 / 1000.0)
+}
+
+/// `850ms`, `12.4s`, `3m05s`.
+pub fn format_duration(secs: f64) -> String {
+    if secs < 1.0 {
+        format!("{}ms", (secs * 1000.0).round() as u64)
+    } else if secs < 60.0 {
+        format!("{:.1}s", secs)
+    } else {
+        let whole = secs.round() as u64;
+        format!("{}m{:02}s", whole / 60, whole % 60)
+    }
+}
+
+/// Tail of the run log under `outputs/binaries/<binary>/<ritual>/` written to last.
+fn latest_run_log(layout: &ProjectLayout, lines: usize) -> Option<DashboardLog> {
+    let mut latest: Option<(SystemTime, PathBuf)> = None;
+    for binary_dir in fs::read_dir(&layout.outputs_binaries_dir).ok()?.flatten() {
+        let Ok(run_dirs) = fs::read_dir(binary_dir.path()) else {
+            continue;
+        };
+        for run_dir in run_dirs.flatten() {
+            let path = run_dir.path().join(RUN_LOG_FILE);
+            let Ok(modified) = fs::metadata(&path).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if latest.as_ref().is_none_or(|(newest, _)| modified > *newest) {
+                latest = Some((modified, path));
+            }
+        }
+    }
+    let (_, path) = latest?;
+    let text = fs::read_to_string(&path).ok()?;
+    let all: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let lines = all[all.len().saturating_sub(lines)..].iter().map(|l| format_log_line(l)).collect();
+    Some(DashboardLog { path, lines })
+}
+
+/// One `run.log.jsonl` line as text; lines that are not log records are kept as they are.
+fn format_log_line(line: &str) -> String {
+    let Ok(serde_json::Value::Object(record)) = serde_json::from_str(line) else {
+        return line.to_string();
+    };
+    let text = |key: &str| record.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    let time = DateTime::parse_from_rfc3339(text("timestamp"))
+        .map(|ts| ts.format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+    let fields = record.get("fields").and_then(|f| f.as_object());
+    let message = fields.and_then(|f| f.get("message")).and_then(|m| m.as_str());
+    let mut out = format!("{} {:>5} {}", time, text("level"), message.unwrap_or_default());
+    if let Some(fields) = fields {
+        for (key, value) in fields.iter().filter(|(key, _)| *key != "message") {
+            match value {
+                serde_json::Value::String(value) => out.push_str(&format!(" {}={}", key, value)),
+                other => out.push_str(&format!(" {}={}", key, other)),
+            }
+        }
+    }
+    out
+}
+
+/// Show binaries, slices, recent runs, and the current run's log, refreshing every
+/// `interval_secs` (or print them once as text or JSON).
+pub fn dashboard_command(root: &str, interval_secs: u64, once: bool, json: bool) -> Result<()> {
+    if interval_secs == 0 {
+        return Err(anyhow!("--interval must be at least 1 second"));
+    }
+    let layout = load_project_layout(canonicalize_or_current(root)?);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&collect_dashboard(&layout)?)?);
+        return Ok(());
+    }
+    if once {
+        print_dashboard(&collect_dashboard(&layout)?);
+        return Ok(());
+    }
+    run_dashboard(&layout, Duration::from_secs(interval_secs))
+}
+
+fn print_dashboard(snapshot: &DashboardSnapshot) {
+    println!("Project: {}", snapshot.project);
+    match &snapshot.active {
+        Some(holder) => println!("Active: {}", holder),
+        None => println!("Active: idle"),
+    }
+
+    println!();
+    println!("Binaries ({}):", snapshot.binaries.len());
+    let cells: Vec<Vec<String>> = snapshot
+        .binaries
+        .iter()
+        .map(|b| {
+            vec![b.name.clone(), b.arch.clone().unwrap_or_else(|| "-".into()), b.runs.to_string()]
+        })
+        .collect();
+    print_table(&["name", "arch", "runs"], &cells);
+
+    println!();
+    println!("Slices ({}):", snapshot.slices.len());
+    let cells: Vec<Vec<String>> =
+        snapshot.slices.iter().map(|s| vec![s.name.clone(), format!("{:?}", s.status)]).collect();
+    print_table(&["name", "status"], &cells);
+
+    println!();
+    println!("Recent runs:");
+    let cells: Vec<Vec<String>> = snapshot
+        .recent_runs
+        .iter()
+        .map(|r| {
+            vec![
+                r.binary.clone(),
+                r.ritual.clone(),
+                r.status.as_str().to_string(),
+                r.backend.clone(),
+                r.duration_secs.map(format_duration).unwrap_or_else(|| "-".into()),
+                r.finished_at.clone(),
+            ]
+        })
+        .collect();
+    print_table(&["binary", "ritual", "status", "backend", "duration", "finished"], &cells);
+
+    if let Some(log) = &snapshot.log {
+        println!();
+        println!("Log ({}):", log.path.display());
+        for line in &log.lines {
+            println!("  {}", line);
+        }
+    }
+}
+
+#[cfg(not(feature = "dashboard"))]
+fn run_dashboard(_layout: &ProjectLayout, _interval: Duration) -> Result<()> {
+    Err(anyhow!(
+        "binary-slicer was built without the `dashboard` feature; rebuild with \
+         `--features dashboard` or pass --once/--json for a snapshot"
+    ))
+}
+
+#[cfg(feature = "dashboard")]
+fn run_dashboard(layout: &ProjectLayout, interval: Duration) -> Result<()> {
+    let colors = crate::output::colors_enabled();
+    let snapshot = collect_dashboard(layout)?;
+    let mut terminal = ratatui::init();
+    let result = tui::run(&mut terminal, layout, interval, snapshot, colors);
+    ratatui::restore();
+    result
+}
+
+#[cfg(feature = "dashboard")]
+mod tui {
+    use std::time::{Duration, Instant};
+
+    use anyhow::Result;
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Color, Modifier, Style};
+    use ratatui::text::{Line, Span};
+    use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+    use ratatui::{DefaultTerminal, Frame};
+    use ritual_core::db::{ProjectLayout, RitualRunStatus, SliceStatus};
+
+    use super::{collect_dashboard, format_duration, DashboardSnapshot};
+
+    /// Redraw until `q` or Esc; any other key refreshes right away. Without `colors`, only
+    /// bold headers set anything apart.
+    pub(super) fn run(
+        terminal: &mut DefaultTerminal,
+        layout: &ProjectLayout,
+        interval: Duration,
+        mut snapshot: DashboardSnapshot,
+        colors: bool,
+    ) -> Result<()> {
+        // A refresh can fail while a run holds the database; keep the last snapshot then.
+        let mut error: Option<String> = None;
+        loop {
+            terminal.draw(|frame| {
+                draw(frame, &snapshot, error.as_deref());
+                if !colors {
+                    for cell in frame.buffer_mut().content.iter_mut() {
+                        cell.set_fg(Color::Reset);
+                    }
+                }
+            })?;
+            let deadline = Instant::now() + interval;
+            while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+                if !event::poll(left)? {
+                    continue;
+                }
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                        return Ok(());
+                    }
+                }
+                break;
+            }
+            match collect_dashboard(layout) {
+                Ok(next) => {
+                    snapshot = next;
+                    error = None;
+                }
+                Err(err) => error = Some(format!("{err:#}")),
+            }
+        }
+    }
+
+    fn draw(frame: &mut Frame, snapshot: &DashboardSnapshot, error: Option<&str>) {
+        let [header, body, log] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(super::DASHBOARD_LOG_LINES as u16 + 2),
+        ])
+        .areas(frame.area());
+        let [left, runs] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(body);
+        let [binaries, slices] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(left);
+
+        let activity = match (error, &snapshot.active) {
+            (Some(err), _) => Span::styled(format!("refresh failed: {err}"), Color::Red),
+            (None, Some(holder)) => Span::styled(format!("busy: {holder}"), Color::Yellow),
+            (None, None) => Span::styled("idle", Color::Green),
+        };
+        let title = format!(" {} — q to quit ", snapshot.project);
+        frame.render_widget(
+            Paragraph::new(Line::from(activity))
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            header,
+        );
+
+        let rows = snapshot.binaries.iter().map(|b| {
+            Row::new(vec![
+                b.name.clone(),
+                b.arch.clone().unwrap_or_else(|| "-".into()),
+                b.runs.to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [Constraint::Percentage(60), Constraint::Percentage(25), Constraint::Percentage(15)],
+        )
+        .header(Row::new(vec!["name", "arch", "runs"]).style(bold()))
+        .block(titled(format!(" Binaries ({}) ", snapshot.binaries.len())));
+        frame.render_widget(table, binaries);
+
+        let rows = snapshot.slices.iter().map(|s| {
+            Row::new(vec![
+                Span::raw(s.name.clone()),
+                Span::styled(format!("{:?}", s.status), slice_color(s.status)),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Percentage(65), Constraint::Percentage(35)])
+            .header(Row::new(vec!["name", "status"]).style(bold()))
+            .block(titled(format!(" Slices ({}) ", snapshot.slices.len())));
+        frame.render_widget(table, slices);
+
+        let rows = snapshot.recent_runs.iter().map(|r| {
+            Row::new(vec![
+                Span::raw(r.binary.clone()),
+                Span::raw(r.ritual.clone()),
+                Span::styled(r.status.as_str(), run_color(&r.status)),
+                Span::raw(r.backend.clone()),
+                Span::raw(r.duration_secs.map(format_duration).unwrap_or_else(|| "-".into())),
+                Span::raw(r.finished_at.clone()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(18),
+                Constraint::Percentage(18),
+                Constraint::Length(10),
+                Constraint::Percentage(14),
+                Constraint::Length(9),
+                Constraint::Min(20),
+            ],
+        )
+        .header(
+            Row::new(vec!["binary", "ritual", "status", "backend", "duration", "finished"])
+                .style(bold()),
+        )
+        .block(titled(" Recent runs ".to_string()));
+        frame.render_widget(table, runs);
+
+        let (title, lines) = match &snapshot.log {
+            Some(log) => (
+                format!(" Log: {} ", log.path.display()),
+                log.lines.iter().map(|l| Line::styled(l.clone(), log_color(l))).collect(),
+            ),
+            None => (
+                " Log ".to_string(),
+                vec![Line::styled("No run log yet (run rituals with --log-json)", Color::DarkGray)],
+            ),
+        };
+        frame.render_widget(Paragraph::new(lines).block(titled(title)), log);
+    }
+
+    fn titled(title: String) -> Block<'static> {
+        Block::default().borders(Borders::ALL).title(title)
+    }
+
+    fn bold() -> Style {
+        Style::default().add_modifier(Modifier::BOLD)
+    }
+
+    fn slice_color(status: SliceStatus) -> Color {
+        match status {
+            SliceStatus::Planned => Color::Blue,
+            SliceStatus::Draft => Color::Yellow,
+            SliceStatus::Active => Color::Green,
+            SliceStatus::Deprecated => Color::DarkGray,
+        }
+    }
+
+    fn run_color(status: &RitualRunStatus) -> Color {
+        match status {
+            RitualRunStatus::Succeeded => Color::Green,
+            RitualRunStatus::Failed => Color::Red,
+            RitualRunStatus::Canceled | RitualRunStatus::Outdated => Color::Yellow,
+            RitualRunStatus::Stubbed => Color::Cyan,
+            RitualRunStatus::Pending | RitualRunStatus::Running => Color::Blue,
+            RitualRunStatus::Purged => Color::DarkGray,
+        }
+    }
+
+    fn log_color(line: &str) -> Style {
+        if line.contains(" ERROR ") {
+            Style::default().fg(Color::Red)
+        } else if line.contains("  WARN ") {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    }
+}
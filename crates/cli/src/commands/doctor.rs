@@ -0,0 +1,365 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::canonicalize_or_current;
+use crate::commands::{backend_infos, configured_backend_paths, open_project_db};
+use ritual_core::db::{
+    load_project_layout, load_resolved_project_config, project_db_path, unknown_config_keys,
+    ProjectConfig, ProjectDb, ProjectLayout, CURRENT_SCHEMA_VERSION,
+};
+use ritual_core::services::health::audit_outputs;
+
+/// Outcome of one `doctor` check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Worth fixing, but the project works.
+    Warning,
+    /// Commands will fail until it is fixed.
+    Error,
+    /// Was a problem; `--fix` repaired it.
+    Fixed,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warning => "warn",
+            CheckStatus::Error => "error",
+            CheckStatus::Fixed => "fixed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    /// What was checked, e.g. `backend rizin`, `schema`, `outputs`.
+    pub check: String,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix a problem by hand (or what `--fix` did).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(check: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self { check: check.into(), status, message: message.into(), fix: None }
+    }
+
+    fn fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Check backends, the DB schema, the project config, project directories, and run outputs,
+/// printing a fix for each problem. `fix` applies the safe ones: creating missing
+/// directories and an absent DB, migrating an older schema, and marking runs whose outputs
+/// are gone `purged`. Fails when errors remain.
+pub fn doctor_command(root: &str, fix: bool, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let mut checks = Vec::new();
+
+    let config = if layout.project_config_path.is_file() {
+        check_config(&layout, &mut checks)
+    } else {
+        checks.push(
+            DoctorCheck::new(
+                "project",
+                CheckStatus::Error,
+                format!("no project at {}", layout.root.display()),
+            )
+            .fix(format!("binary-slicer init-project --root {}", layout.root.display())),
+        );
+        None
+    };
+    check_backends(config.as_ref(), &mut checks)?;
+    if let Some(config) = &config {
+        check_directories(&layout, fix, &mut checks)?;
+        if check_schema(&layout, config, fix, &mut checks)? {
+            check_outputs(&layout, fix, &mut checks)?;
+        }
+    }
+
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let errors = count(CheckStatus::Error);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        for check in &checks {
+            println!("[{}] {}: {}", check.status.label(), check.check, check.message);
+            if let Some(fix) = &check.fix {
+                let verb = if check.status == CheckStatus::Fixed { "did" } else { "fix" };
+                println!("    {}: {}", verb, fix);
+            }
+        }
+        println!(
+            "{} check(s): {} ok, {} warning(s), {} error(s), {} fixed",
+            checks.len(),
+            count(CheckStatus::Ok),
+            count(CheckStatus::Warning),
+            errors,
+            count(CheckStatus::Fixed)
+        );
+    }
+    if errors > 0 {
+        bail!("doctor found {} error(s)", errors);
+    }
+    Ok(())
+}
+
+/// Parse the project config and flag keys this version does not know.
+fn check_config(layout: &ProjectLayout, checks: &mut Vec<DoctorCheck>) -> Option<ProjectConfig> {
+    let path = &layout.project_config_path;
+    let raw = fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|body| Ok(serde_json::from_str::<serde_json::Value>(&body)?));
+    let config = match raw {
+        Ok(raw) => {
+            for (key, suggestion) in unknown_config_keys(&raw) {
+                let fix = match suggestion {
+                    Some(known) => format!(
+                        "did you mean '{known}'? binary-slicer config set {known} <value>, then remove '{key}' from {}",
+                        path.display()
+                    ),
+                    None => format!("remove it from {} (this version ignores it)", path.display()),
+                };
+                checks.push(
+                    DoctorCheck::new(
+                        "config",
+                        CheckStatus::Warning,
+                        format!("unknown key '{key}'"),
+                    )
+                    .fix(fix),
+                );
+            }
+            load_resolved_project_config(layout)
+        }
+        Err(err) => Err(err),
+    };
+    match config {
+        Ok(config) => {
+            if !checks.iter().any(|c| c.check == "config") {
+                checks.push(DoctorCheck::new(
+                    "config",
+                    CheckStatus::Ok,
+                    format!("{} is valid", path.display()),
+                ));
+            }
+            Some(config)
+        }
+        Err(err) => {
+            checks.push(
+                DoctorCheck::new(
+                    "config",
+                    CheckStatus::Error,
+                    format!("cannot read {}: {:#}", path.display(), err),
+                )
+                .fix(format!("repair the JSON in {}", path.display())),
+            );
+            None
+        }
+    }
+}
+
+/// Run each compiled backend's availability probe (`rizin -v`, locating Ghidra's headless
+/// launcher). Backends the project selects must work; others only warn.
+fn check_backends(config: Option<&ProjectConfig>, checks: &mut Vec<DoctorCheck>) -> Result<()> {
+    let paths = match config {
+        Some(config) => configured_backend_paths(config),
+        None => ritual_core::services::config::UserConfig::load()?.backend_paths(),
+    };
+    let selected: Vec<&str> = config
+        .map(|c| c.default_backend.iter().chain(&c.backend_priority).map(String::as_str).collect())
+        .unwrap_or_default();
+    for info in backend_infos(&paths) {
+        let check = format!("backend {}", info.name);
+        let is_selected = selected.contains(&info.name.as_str());
+        let caps = &info.capabilities;
+        let reason = caps.unavailable.clone().unwrap_or_default();
+        let status = if is_selected { CheckStatus::Error } else { CheckStatus::Warning };
+        if !info.compiled {
+            if is_selected {
+                checks.push(
+                    DoctorCheck::new(&check, status, reason).fix(format!(
+                        "rebuild with the `{}-backend` feature, or pick another backend: binary-slicer config set default_backend capstone",
+                        info.name
+                    )),
+                );
+            }
+        } else if info.available {
+            let message = match (&caps.tool_version, &caps.tool_path) {
+                (Some(version), Some(path)) => format!("{} at {}", version, path.display()),
+                (Some(version), None) => version.clone(),
+                (None, _) => "available".to_string(),
+            };
+            checks.push(DoctorCheck::new(&check, CheckStatus::Ok, message));
+        } else {
+            let tool = caps.external_tool.as_deref().unwrap_or(info.name.as_str());
+            checks.push(DoctorCheck::new(&check, status, reason).fix(format!(
+                "install {tool}, then binary-slicer setup-backend --backend {} [--path <{tool}>]",
+                info.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The project directories `init-project` creates.
+fn check_directories(
+    layout: &ProjectLayout,
+    fix: bool,
+    checks: &mut Vec<DoctorCheck>,
+) -> Result<()> {
+    let dirs = [
+        &layout.slices_docs_dir,
+        &layout.reports_dir,
+        &layout.graphs_dir,
+        &layout.rituals_dir,
+        &layout.outputs_binaries_dir,
+    ];
+    let missing: Vec<_> = dirs.into_iter().filter(|dir| !dir.is_dir()).collect();
+    if missing.is_empty() {
+        checks.push(DoctorCheck::new("directories", CheckStatus::Ok, "all present"));
+    }
+    for dir in missing {
+        let message = format!("{} is missing", dir.display());
+        if fix {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            checks.push(
+                DoctorCheck::new("directories", CheckStatus::Fixed, message)
+                    .fix(format!("created {}", dir.display())),
+            );
+        } else {
+            checks.push(
+                DoctorCheck::new("directories", CheckStatus::Warning, message)
+                    .fix("binary-slicer doctor --fix (creates it)"),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Compare the DB schema version with this build's. Returns whether the DB is usable at the
+/// current version (so its runs can be checked).
+fn check_schema(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    fix: bool,
+    checks: &mut Vec<DoctorCheck>,
+) -> Result<bool> {
+    let db_path = project_db_path(layout, config);
+    if !db_path.is_file() {
+        let message = format!("project DB {} is missing", db_path.display());
+        if !fix {
+            checks.push(
+                DoctorCheck::new("schema", CheckStatus::Error, message)
+                    .fix("binary-slicer doctor --fix (creates an empty DB)"),
+            );
+            return Ok(false);
+        }
+        ProjectDb::open(&db_path).with_context(|| {
+            format!("Failed to create project database at {}", db_path.display())
+        })?;
+        checks.push(
+            DoctorCheck::new("schema", CheckStatus::Fixed, message)
+                .fix(format!("created an empty v{} DB", CURRENT_SCHEMA_VERSION)),
+        );
+        return Ok(true);
+    }
+
+    let version = ProjectDb::schema_version_at(&db_path)
+        .with_context(|| format!("Failed to read project database at {}", db_path.display()))?;
+    let message = format!("v{} (this build uses v{})", version, CURRENT_SCHEMA_VERSION);
+    if version == CURRENT_SCHEMA_VERSION {
+        checks.push(DoctorCheck::new("schema", CheckStatus::Ok, message));
+        Ok(true)
+    } else if version > CURRENT_SCHEMA_VERSION {
+        checks.push(
+            DoctorCheck::new("schema", CheckStatus::Error, message)
+                .fix("the DB was written by a newer binary-slicer; upgrade this one"),
+        );
+        Ok(false)
+    } else if fix {
+        ProjectDb::open(&db_path).with_context(|| {
+            format!("Failed to migrate project database at {}", db_path.display())
+        })?;
+        checks.push(
+            DoctorCheck::new("schema", CheckStatus::Fixed, message)
+                .fix(format!("migrated to v{}", CURRENT_SCHEMA_VERSION)),
+        );
+        Ok(true)
+    } else {
+        checks.push(
+            DoctorCheck::new("schema", CheckStatus::Warning, message).fix(
+                "binary-slicer doctor --fix (or any command that opens the project) migrates it",
+            ),
+        );
+        Ok(false)
+    }
+}
+
+/// Output directories without runs, and latest runs without output directories.
+fn check_outputs(layout: &ProjectLayout, fix: bool, checks: &mut Vec<DoctorCheck>) -> Result<()> {
+    let (_config, _db_path, db) = open_project_db(layout)?;
+    let runs = db.list_ritual_runs_with_ids(None).context("Failed to list ritual runs")?;
+    let audit = audit_outputs(layout, &runs).with_context(|| {
+        format!("Failed to scan outputs under {}", layout.outputs_binaries_dir.display())
+    })?;
+    if audit.is_clean() {
+        checks.push(DoctorCheck::new(
+            "outputs",
+            CheckStatus::Ok,
+            format!("{} run(s) match their output directories", runs.len()),
+        ));
+    }
+    for orphan in &audit.orphaned {
+        checks.push(
+            DoctorCheck::new(
+                "outputs",
+                CheckStatus::Warning,
+                format!(
+                    "{} has no run of {} / {} in the project DB",
+                    orphan.path.display(),
+                    orphan.binary,
+                    orphan.ritual
+                ),
+            )
+            .fix(format!(
+                "re-run the ritual to record it, or remove it: binary-slicer clean-outputs --binary {} --ritual {} --yes",
+                orphan.binary, orphan.ritual
+            )),
+        );
+    }
+    if fix && !audit.missing.is_empty() {
+        let ids: Vec<i64> = audit.missing.iter().map(|m| m.run_id).collect();
+        db.mark_runs_purged(&ids).context("Failed to mark runs purged")?;
+    }
+    for missing in &audit.missing {
+        let message = format!(
+            "run {} of {} / {} has no outputs at {}",
+            missing.run_id,
+            missing.binary,
+            missing.ritual,
+            missing.path.display()
+        );
+        let (status, hint) = if fix {
+            (CheckStatus::Fixed, format!("marked run {} purged", missing.run_id))
+        } else {
+            (
+                CheckStatus::Warning,
+                "run its spec again with run-ritual, or binary-slicer doctor --fix to mark the run purged"
+                    .to_string(),
+            )
+        };
+        checks.push(DoctorCheck::new("outputs", status, message).fix(hint));
+    }
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::load_project_layout;
+use ritual_core::rituals::hooks::run_post_run_hooks;
+use ritual_core::rituals::run::{
+    prepare_run, validate_run, write_normalized_spec, write_run_outputs, RunBinary,
+};
+use ritual_core::rituals::spec::sha256_bytes;
+use ritual_core::services::analysis::{finalize_result, persist_run};
+use ritual_core::services::entry_points::{
+    has_auto_roots, request_entry_points, resolve_auto_roots,
+};
+use ritual_core::services::exchange::{export_run, import_run, read_archive, write_archive};
+use ritual_core::services::import::{import_analysis, ImportFormat};
+use ritual_core::services::roots::{RootPattern, RootSpec};
+use ritual_core::services::tables::{export_tables, TableFormat};
+
+use crate::canonicalize_or_current;
+use crate::commands::{open_project_db, BinarySelector, RitualRoots, RitualSpec};
+
+/// Export the latest run of a ritual (DB rows + output files) as a `.tar.zst` archive.
+pub fn export_run_command(root: &str, binary: &str, ritual: &str, out: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+
+    let archive = export_run(&db, &layout, binary, ritual)?;
+    let out_path = Path::new(out);
+    if let Some(parent) = out_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let file = fs::File::create(out_path)
+        .with_context(|| format!("Failed to create archive at {}", out_path.display()))?;
+    write_archive(&archive, BufWriter::new(file))
+        .with_context(|| format!("Failed to write archive at {}", out_path.display()))?;
+
+    println!("Exported run {} / {}", binary, ritual);
+    println!("  Functions: {}", archive.analysis.functions.len());
+    println!("  Evidence: {}", archive.analysis.evidence.len());
+    println!("  Files: {}", archive.manifest.files.join(", "));
+    println!("  Archive: {}", out_path.display());
+    Ok(())
+}
+
+/// Write the latest run of a ritual as flat CSV or Parquet tables into `out`.
+pub fn export_tables_command(
+    root: &str,
+    binary: &str,
+    ritual: &str,
+    format: &str,
+    out: &str,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let format = TableFormat::parse(format)?;
+
+    let export = export_tables(&db, binary, ritual, format, Path::new(out))?;
+    println!("Exported run {} / {} (#{}) as {} tables", binary, ritual, export.run_id, format);
+    for table in &export.tables {
+        println!("  {}: {} row(s)", table.path.display(), table.rows);
+    }
+    Ok(())
+}
+
+/// Import a run archive produced by `export-run` into this project.
+pub fn import_run_command(root: &str, file: &str, force: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+
+    let input = fs::File::open(file).with_context(|| format!("Failed to open archive {}", file))?;
+    let archive = read_archive(BufReader::new(input))
+        .with_context(|| format!("Failed to read archive {}", file))?;
+    let imported = import_run(&db, &layout, &archive, force)?;
+
+    println!("Imported run {} / {} (id {})", imported.binary, imported.ritual, imported.run_id);
+    println!(
+        "  Exported with schema v{} by binary-slicer {}",
+        archive.manifest.schema_version, archive.manifest.tool_version
+    );
+    if imported.registered_binary {
+        println!(
+            "  Registered binary {} (file not copied; add it locally to re-run analysis)",
+            imported.binary
+        );
+    }
+    println!(
+        "  Output: {}",
+        layout.binary_output_root(&imported.binary).join(&imported.ritual).display()
+    );
+    Ok(())
+}
+
+/// Record another tool's analysis of `binary` (a BinExport2 file, the Ghidra export script's
+/// JSON, or Ghidra's XML export) as a run of ritual `ritual`, without running a backend.
+///
+/// `roots` are matched against the imported functions like a spec's roots; the run gets a
+/// normalized `spec.yaml` (backend = the format), `report.json`, and `graph.dot` like any run.
+pub fn import_analysis_command(
+    root: &str,
+    binary: &str,
+    file: &str,
+    format: &str,
+    ritual: &str,
+    roots: &[String],
+    force: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (config, _db_path, db) = open_project_db(&layout)?;
+    let format = ImportFormat::parse(format)?;
+    let file_path = canonicalize_or_current(file)?;
+    let data = fs::read(&file_path)
+        .with_context(|| format!("Failed to read {} export {}", format, file_path.display()))?;
+
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let selector = BinarySelector::from(binary);
+    let target = RunBinary::new(&db, selector.resolve(&binaries)?)?;
+    let roots = roots
+        .iter()
+        .map(|r| {
+            RootPattern::parse(r).with_context(|| format!("Invalid slice root '{}'", r))?;
+            Ok(RootSpec::from(r.as_str()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let spec = RitualSpec {
+        name: ritual.to_string(),
+        binary: selector,
+        roots: RitualRoots::List(roots),
+        max_depth: None,
+        backend: None,
+        description: Some(format!("Imported from {}", file_path.display())),
+        outputs: None,
+        stages: Vec::new(),
+        epoch: None,
+        raw: None,
+        timeout: None,
+        plugins: Vec::new(),
+        string_decoders: Vec::new(),
+        ghidra_xml: None,
+    };
+    spec.validate()?;
+    let mut prepared = prepare_run(
+        &layout,
+        &config,
+        &target,
+        spec,
+        &sha256_bytes(&data),
+        ritual,
+        Some(format.as_str()),
+    )?;
+    prepared.meta.backend_path = Some(file_path.display().to_string());
+    prepared.load_symbols(&db)?;
+
+    let mut imported = import_analysis(format, &data, Some(&file_path), &prepared.request.roots)
+        .with_context(|| format!("Failed to import {}", file_path.display()))?;
+    if has_auto_roots(&prepared.request.roots) {
+        // The export is usable without the binary; its auto roots just stay unresolved.
+        let entry_points = request_entry_points(&prepared.request).unwrap_or_else(|err| {
+            tracing::warn!("cannot read entry points of {}: {}", prepared.binary.name, err);
+            Vec::new()
+        });
+        resolve_auto_roots(&prepared.request.roots, &mut imported.result, &entry_points);
+    }
+    let mismatch = prepared
+        .meta
+        .binary_hash
+        .as_deref()
+        .filter(|hash| imported.matches_hash(hash) == Some(false));
+    if let Some(hash) = mismatch {
+        tracing::warn!(
+            "{} was exported from a different file (executable id {}, binary '{}' has {}); addresses may not line up",
+            file_path.display(),
+            imported.executable_id.as_deref().unwrap_or_default(),
+            prepared.binary.name,
+            hash
+        );
+    }
+
+    let run_dir = &prepared.run_dir;
+    if run_dir.exists() {
+        if !force {
+            return Err(anyhow!(
+                "Ritual output already exists at {} (rerun with --force to overwrite)",
+                run_dir.display()
+            ));
+        }
+        fs::remove_dir_all(run_dir).with_context(|| {
+            format!("Failed to clean existing ritual output dir {}", run_dir.display())
+        })?;
+    }
+    fs::create_dir_all(run_dir)
+        .with_context(|| format!("Failed to create ritual output dir {}", run_dir.display()))?;
+    write_normalized_spec(&prepared)?;
+
+    let result = finalize_result(&prepared.request, &prepared.meta, imported.result);
+    persist_run(&db, &prepared.request, &prepared.meta, &result)
+        .context("Failed to record imported run in project DB")?;
+    write_run_outputs(&prepared, &result, &prepared.meta, &validate_run(&prepared, &result), None)?;
+    run_post_run_hooks(&config, &prepared, &prepared.meta.status);
+
+    println!("Imported {} analysis as {} / {}", format, prepared.request.binary_name, ritual);
+    if let Some(name) = &imported.executable_name {
+        match &imported.architecture {
+            Some(arch) => println!("  Exported from: {} ({})", name, arch),
+            None => println!("  Exported from: {}", name),
+        }
+    }
+    if mismatch.is_some() {
+        println!("  Warning: the export's executable id does not match the binary's hash");
+    }
+    println!(
+        "  Functions: {} ({} basic blocks, {} call edges, {} evidence)",
+        result.functions.len(),
+        result.basic_blocks.len(),
+        result.call_edges.len(),
+        result.evidence.len()
+    );
+    for hit in &result.root_hits {
+        println!("  Root {}: {} function(s)", hit.root, hit.functions.len());
+    }
+    println!("  Output: {}", run_dir.display());
+    Ok(())
+}
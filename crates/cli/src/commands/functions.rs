@@ -0,0 +1,379 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::{FunctionFilter, ProjectLayout, ANALYSIS_PAGE_SIZE};
+use ritual_core::rituals::run::RunBinary;
+use ritual_core::services::analysis::{
+    AnalysisResult, BlockEdgeKind, CallEdge, EvidenceRecord, FunctionRecord, InstructionRecord,
+    RawImage,
+};
+use ritual_core::services::backends::disassemble_range;
+use serde_json::json;
+
+use crate::canonicalize_or_current;
+use crate::commands::annotations::parse_address;
+use crate::commands::{open_project_db, BinarySelector, RitualSpec};
+use crate::output::Table;
+
+/// Instructions listed when a function has neither a size nor recovered blocks.
+const UNSIZED_LISTING_LIMIT: usize = 256;
+
+/// Print a function's disassembly, call edges, and evidence from the latest run that
+/// recorded it (optionally restricted to one ritual).
+///
+/// The listing is not persisted: the function's byte range is re-disassembled from the
+/// registered binary on demand, so the binary must still be on disk.
+pub fn show_function_command(
+    root: &str,
+    binary: &str,
+    address: Option<&str>,
+    name: Option<&str>,
+    ritual: Option<&str>,
+    limit: usize,
+    json: bool,
+) -> Result<()> {
+    let address = address.map(parse_address).transpose()?;
+    if address.is_none() && name.is_none() {
+        return Err(anyhow!("Provide --address or --name"));
+    }
+
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let record = &target.binary;
+
+    let mut runs = db.list_ritual_runs(Some(&target.run_name)).context("Failed to list runs")?;
+    runs.retain(|run| ritual.is_none_or(|r| run.ritual == r));
+    runs.sort_by(|a, b| {
+        b.finished_at.cmp(&a.finished_at).then_with(|| b.started_at.cmp(&a.started_at))
+    });
+    let mut seen = BTreeSet::new();
+    let mut found = None;
+    for run in runs.iter().filter(|run| seen.insert(run.ritual.clone())) {
+        let Some((run_id, analysis)) = db
+            .load_analysis_skeleton(&run.binary, &run.ritual)
+            .context("Failed to load analysis result")?
+        else {
+            continue;
+        };
+        if let Some(idx) = find_function(&analysis.functions, address, name) {
+            found = Some((run, run_id, analysis, idx));
+            break;
+        }
+    }
+    let target = match address {
+        Some(address) => format!("0x{:X}", address),
+        None => format!("'{}'", name.unwrap_or_default()),
+    };
+    let (run, run_id, analysis, idx) = found.ok_or_else(|| {
+        anyhow!("No persisted analysis of binary '{}' contains function {}", record.name, target)
+    })?;
+    let function = &analysis.functions[idx];
+    let end = function_end(&analysis, function);
+
+    let path = Path::new(&record.path);
+    let path = if path.is_absolute() { path.to_path_buf() } else { layout.root.join(path) };
+    let (instructions, disassembly_error) = if path.is_file() {
+        let limit = if end.is_some() { limit } else { limit.min(UNSIZED_LISTING_LIMIT) };
+        // Functions of fat binaries are decoded from the slice they were found in; raw blobs
+        // are mapped the way the run's spec mapped them.
+        let raw = run_raw_image(&layout, &run.binary, &run.ritual);
+        let raw_arch = raw.as_ref().and_then(|(_, arch)| arch.as_deref());
+        let arch = function.arch.as_deref().or(raw_arch).or(record.arch.as_deref());
+        let raw = raw.as_ref().map(|(image, _)| image);
+        // The Thumb bit makes the listing decode Thumb code even without symbols.
+        let start = match function.isa.as_deref() {
+            Some("thumb") => function.address | 1,
+            _ => function.address,
+        };
+        match disassemble_range(&path, arch, raw, start, end, limit) {
+            Ok(instructions) => (instructions, None),
+            Err(err) => (Vec::new(), Some(err.to_string())),
+        }
+    } else {
+        (Vec::new(), Some(format!("binary missing on disk at {}", path.display())))
+    };
+
+    let range_end = end.unwrap_or_else(|| {
+        instructions
+            .last()
+            .map(|i| i.address + i.bytes.len() as u64)
+            .unwrap_or(function.address + 1)
+    });
+    let outgoing: Vec<&CallEdge> = analysis
+        .call_edges
+        .iter()
+        .filter(|e| e.from >= function.address && e.from < range_end)
+        .collect();
+    let incoming: Vec<(u64, Option<u64>)> = analysis
+        .call_edges
+        .iter()
+        .filter(|e| e.to == function.address)
+        .map(|e| (e.from, containing_function(&analysis.functions, e.from)))
+        .collect();
+    let mut evidence: Vec<EvidenceRecord> = Vec::new();
+    for page in db.evidence_pages_in_range(run_id, function.address, range_end, ANALYSIS_PAGE_SIZE)
+    {
+        evidence.extend(page.context("Failed to read evidence")?);
+    }
+
+    let names: HashMap<u64, &str> =
+        analysis.functions.iter().filter_map(|f| Some((f.address, f.name.as_deref()?))).collect();
+    let label = |address: u64| match names.get(&address) {
+        Some(name) => format!("{} @ 0x{:X}", name, address),
+        None => format!("0x{:X}", address),
+    };
+
+    if json {
+        let payload = json!({
+            "binary": run.binary,
+            "ritual": run.ritual,
+            "backend": run.backend,
+            "function": function,
+            "end": end,
+            "instructions": instructions,
+            "disassembly_error": disassembly_error,
+            "calls": outgoing.iter().map(|e| json!({
+                "site": e.from,
+                "to": e.to,
+                "name": e.to_name.as_deref().or_else(|| names.get(&e.to).copied()),
+                "import": e.to_import,
+            })).collect::<Vec<_>>(),
+            "called_by": incoming.iter().map(|(site, caller)| json!({
+                "site": site,
+                "caller": caller,
+                "name": caller.and_then(|c| names.get(&c)),
+            })).collect::<Vec<_>>(),
+            "evidence": evidence,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!("Function: {}", label(function.address));
+    let mut details = Vec::new();
+    if let Some(size) = function.size {
+        details.push(format!("size={}", size));
+    }
+    if let Some(sig) = &function.signature {
+        details.push(format!("sig={}", sig));
+    }
+    if let Some(arch) = &function.arch {
+        details.push(format!("arch={}", arch));
+    }
+    if let Some(isa) = &function.isa {
+        details.push(format!("isa={}", isa));
+    }
+    if let Some(mangled) = &function.mangled_name {
+        details.push(format!("mangled={}", mangled));
+    }
+    if !details.is_empty() {
+        println!("  {}", details.join(", "));
+    }
+    println!("  Binary: {}", record.name);
+    println!("  Run: {} ({}, finished {})", run.ritual, run.backend, run.finished_at);
+
+    println!("Disassembly:");
+    if let Some(err) = &disassembly_error {
+        println!("  (unavailable: {})", err);
+    } else if instructions.is_empty() {
+        println!("  (no instructions decoded)");
+    }
+    let block_starts: BTreeSet<u64> = analysis.basic_blocks.iter().map(|b| b.start).collect();
+    for insn in &instructions {
+        if insn.address != function.address && block_starts.contains(&insn.address) {
+            println!("  loc_{:X}:", insn.address);
+        }
+        println!("{}", format_instruction(insn));
+    }
+    if instructions.len() == limit && end.is_some_and(|end| instructions_end(&instructions) < end) {
+        println!("  ... (listing truncated at {} instructions)", limit);
+    }
+
+    println!("Calls:");
+    if outgoing.is_empty() {
+        println!("  (none)");
+    }
+    for e in &outgoing {
+        // Runs recorded before call targets were resolved carry no `to_name`.
+        let target = match &e.to_name {
+            Some(_) => format!("{} @ 0x{:X}", e.target_label(), e.to),
+            None => label(e.to),
+        };
+        println!("  0x{:X} -> {}", e.from, target);
+    }
+    println!("Called by:");
+    if incoming.is_empty() {
+        println!("  (none)");
+    }
+    for (site, caller) in &incoming {
+        match caller {
+            Some(caller) => println!("  {} (at 0x{:X})", label(*caller), site),
+            None => println!("  0x{:X}", site),
+        }
+    }
+    println!("Evidence:");
+    if evidence.is_empty() {
+        println!("  (none)");
+    }
+    for e in &evidence {
+        let source = e.source.as_ref().map(|s| format!("  ({})", s)).unwrap_or_default();
+        match &e.kind {
+            Some(kind) => println!("  0x{:X} [{:?}] {}{}", e.address, kind, e.description, source),
+            None => println!("  0x{:X} {}{}", e.address, e.description, source),
+        }
+    }
+    Ok(())
+}
+
+/// Print a page of the functions of a ritual's latest run, filtered in the project DB, with
+/// the annotation names of the run's build.
+pub fn list_functions_command(
+    root: &str,
+    binary: &str,
+    ritual: &str,
+    filter: &FunctionFilter,
+    json: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let record = &target.binary;
+    let run_id = db
+        .latest_run_id(&target.run_name, ritual)
+        .context("Failed to look up ritual run")?
+        .ok_or_else(|| {
+            anyhow!("No ritual run found for binary '{}' / ritual '{}'", record.name, ritual)
+        })?;
+    let page = db.list_functions(run_id, filter).context("Failed to list functions")?;
+
+    if json {
+        let out = json!({
+            "binary": target.run_name,
+            "ritual": ritual,
+            "run_id": run_id,
+            "offset": filter.offset,
+            "total": page.total,
+            "functions": page.functions,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    println!("Functions of {} / {} (run {}):", record.name, ritual, run_id);
+    if page.functions.is_empty() {
+        match page.total {
+            0 => println!("(none)"),
+            total => {
+                println!("(offset {} is past the {} matching function(s))", filter.offset, total)
+            }
+        }
+        return Ok(());
+    }
+    let mut table = Table::new(&["address", "name", "size", "slice", "annotation"]);
+    for f in &page.functions {
+        let slice = match (f.in_slice, f.is_boundary) {
+            (true, _) => "in",
+            (false, true) => "boundary",
+            (false, false) => "-",
+        };
+        table.row(vec![
+            format!("0x{:X}", f.address).into(),
+            f.name.as_deref().unwrap_or("-").into(),
+            f.size.map(|s| s.to_string()).unwrap_or_else(|| "-".into()).into(),
+            slice.into(),
+            f.annotation.as_deref().unwrap_or("").into(),
+        ]);
+    }
+    table.print();
+    let first = filter.offset + 1;
+    println!("Showing {}-{} of {}", first, filter.offset + page.functions.len(), page.total);
+    Ok(())
+}
+
+/// Raw-image mapping (and arch override) from the normalized spec a run was written with.
+fn run_raw_image(
+    layout: &ProjectLayout,
+    binary: &str,
+    ritual: &str,
+) -> Option<(RawImage, Option<String>)> {
+    let spec_path = layout.binary_output_root(binary).join(ritual).join("spec.yaml");
+    let spec: RitualSpec = serde_yaml::from_str(&std::fs::read_to_string(spec_path).ok()?).ok()?;
+    let raw = spec.raw?;
+    Some((raw.image().ok()?, raw.arch))
+}
+
+/// Index of the function at `address` (or containing it), or named `name` (symbol or
+/// original mangled name).
+fn find_function(
+    functions: &[FunctionRecord],
+    address: Option<u64>,
+    name: Option<&str>,
+) -> Option<usize> {
+    match (address, name) {
+        (Some(address), _) => functions.iter().position(|f| f.address == address).or_else(|| {
+            let containing = containing_function(functions, address)?;
+            functions.iter().position(|f| f.address == containing)
+        }),
+        (None, Some(name)) => functions.iter().position(|f| {
+            f.name.as_deref() == Some(name) || f.mangled_name.as_deref() == Some(name)
+        }),
+        (None, None) => None,
+    }
+}
+
+/// Start of the smallest sized function whose range contains `address`.
+fn containing_function(functions: &[FunctionRecord], address: u64) -> Option<u64> {
+    functions
+        .iter()
+        .filter_map(|f| Some((f.address, f.address.checked_add(u64::from(f.size?))?)))
+        .filter(|(start, end)| address >= *start && address < *end)
+        .min_by_key(|(start, end)| end - start)
+        .map(|(start, _)| start)
+}
+
+/// End of a function: from its size, else the extent of the blocks reachable from its
+/// entry without following calls.
+fn function_end(analysis: &AnalysisResult, function: &FunctionRecord) -> Option<u64> {
+    if let Some(size) = function.size.filter(|size| *size > 0) {
+        return Some(function.address + u64::from(size));
+    }
+    let blocks: HashMap<u64, _> = analysis.basic_blocks.iter().map(|b| (b.start, b)).collect();
+    let mut pending = vec![function.address];
+    let mut visited = BTreeSet::new();
+    let mut end = None;
+    while let Some(start) = pending.pop() {
+        let Some(block) = blocks.get(&start) else { continue };
+        if !visited.insert(start) {
+            continue;
+        }
+        end = end.max(Some(block.start + u64::from(block.byte_len)));
+        pending.extend(
+            block
+                .successors
+                .iter()
+                .filter(|e| !matches!(e.kind, BlockEdgeKind::Call | BlockEdgeKind::IndirectCall))
+                .map(|e| e.target),
+        );
+    }
+    end
+}
+
+fn instructions_end(instructions: &[InstructionRecord]) -> u64 {
+    instructions.last().map(|i| i.address + i.bytes.len() as u64).unwrap_or(0)
+}
+
+fn format_instruction(insn: &InstructionRecord) -> String {
+    let bytes: Vec<String> = insn.bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let text = if insn.operands.is_empty() {
+        insn.mnemonic.clone()
+    } else {
+        format!("{} {}", insn.mnemonic, insn.operands)
+    };
+    format!("  0x{:X}:  {:<24} {}", insn.address, bytes.join(" "), text)
+}
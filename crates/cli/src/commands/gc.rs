@@ -0,0 +1,79 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use ritual_core::services::retention::plan_purge;
+use serde_json::json;
+
+use crate::canonicalize_or_current;
+use crate::commands::open_project_db;
+
+/// Remove the output directories of runs the `retention` policy no longer keeps and mark
+/// those runs `purged` (with `dry_run`, only list them).
+///
+/// Only the latest run of a binary and ritual has outputs on disk; older runs it purges
+/// just change status.
+pub fn gc_outputs_command(root: &str, dry_run: bool, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (config, _db_path, db) = open_project_db(&layout)?;
+    let runs = db.list_ritual_runs_with_ids(None).context("Failed to list ritual runs")?;
+    let baselines: BTreeSet<i64> = db
+        .list_run_baselines()
+        .context("Failed to list baselines")?
+        .into_iter()
+        .map(|b| b.run_id)
+        .collect();
+    let purge = plan_purge(&runs, &baselines, &config.retention, Utc::now());
+
+    let mut removed = Vec::new();
+    for candidate in purge.iter().filter(|c| c.owns_outputs) {
+        let dir = layout.binary_output_root(&candidate.binary).join(&candidate.ritual);
+        if !dir.exists() {
+            continue;
+        }
+        if !dry_run {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to remove outputs at {}", dir.display()))?;
+        }
+        removed.push(dir);
+    }
+    if !dry_run {
+        let ids: Vec<i64> = purge.iter().map(|c| c.run_id).collect();
+        db.mark_runs_purged(&ids).context("Failed to mark runs purged")?;
+    }
+
+    if json {
+        let payload = json!({
+            "dry_run": dry_run,
+            "runs": purge,
+            "removed": removed.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if purge.is_empty() {
+        println!("Nothing to purge");
+        return Ok(());
+    }
+    let verb = if dry_run { "Would purge" } else { "Purged" };
+    println!("{} {} run(s):", verb, purge.len());
+    for candidate in &purge {
+        println!(
+            "  {} / {} (run {}, {}, finished {}): {}",
+            candidate.binary,
+            candidate.ritual,
+            candidate.run_id,
+            candidate.status.as_str(),
+            candidate.finished_at,
+            candidate.reason
+        );
+    }
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    for dir in &removed {
+        println!("{} outputs: {}", verb, dir.display());
+    }
+    Ok(())
+}
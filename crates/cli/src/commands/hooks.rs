@@ -0,0 +1,134 @@
+//! `open-run`: launch an external tool on a run, with the post-run hook placeholders (see
+//! [`ritual_core::rituals::hooks`]).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::{load_project_layout, FunctionAnnotation};
+use ritual_core::rituals::hooks::{
+    display_command, expand_command, program, run_placeholders, ANNOTATIONS_FILE,
+};
+use ritual_core::rituals::run::RunBinary;
+use serde::Serialize;
+
+use crate::canonicalize_or_current;
+use crate::commands::{open_project_db, BinarySelector};
+
+/// One function in the `annotations.json` written for external tools.
+#[derive(Debug, Serialize)]
+struct ExportedFunction {
+    address: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u32>,
+    in_slice: bool,
+    is_boundary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+/// Launch the external tool configured as `tools.<tool>` on the latest run of `ritual`.
+///
+/// Writes the run's functions (with human annotation names and notes) to `<run dir>/annotations.json`
+/// first. The template gets the post-run hook placeholders plus `{annotations}`; `dry_run`
+/// prints the command instead of launching it.
+pub fn open_run_command(
+    root: &str,
+    binary: &str,
+    ritual: &str,
+    tool: &str,
+    dry_run: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (config, _db_path, db) = open_project_db(&layout)?;
+    let template = config.tools.get(tool).ok_or_else(|| {
+        let configured: Vec<&str> = config.tools.keys().map(String::as_str).collect();
+        anyhow!(
+            "No tool '{}' configured (set one with `config set tools.{} \"<command> {{binary_path}} {{annotations}}\"`; configured: {})",
+            tool,
+            tool,
+            if configured.is_empty() { "none".to_string() } else { configured.join(", ") }
+        )
+    })?;
+
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let record = &target.binary;
+    let runs = db.list_ritual_runs(Some(&target.run_name)).context("Failed to list ritual runs")?;
+    let run = runs.into_iter().rev().find(|r| r.ritual == ritual).ok_or_else(|| {
+        anyhow!("No ritual run found for binary '{}' / ritual '{}'", record.name, ritual)
+    })?;
+    let mut functions = db
+        .load_analysis_result(&target.run_name, ritual)
+        .context("Failed to load run analysis")?
+        .map(|a| a.functions)
+        .unwrap_or_default();
+    functions.sort_by_key(|f| f.address);
+    let hash = run.binary_hash.clone().or_else(|| record.hash.clone());
+    let annotations = match &hash {
+        Some(hash) => db.list_function_annotations(hash).context("Failed to load annotations")?,
+        None => Vec::new(),
+    };
+
+    let run_dir = layout.binary_output_root(&target.run_name).join(ritual);
+    fs::create_dir_all(&run_dir)
+        .with_context(|| format!("Failed to create run dir {}", run_dir.display()))?;
+    let by_address: HashMap<u64, &FunctionAnnotation> =
+        annotations.iter().map(|a| (a.address, a)).collect();
+    let functions: Vec<ExportedFunction> = functions
+        .into_iter()
+        .map(|f| {
+            let annotation = by_address.get(&f.address);
+            ExportedFunction {
+                address: f.address,
+                name: annotation.and_then(|a| a.name.clone()).or(f.name),
+                size: f.size,
+                in_slice: f.in_slice,
+                is_boundary: f.is_boundary,
+                note: annotation.and_then(|a| a.note.clone()),
+            }
+        })
+        .collect();
+    let binary_path = {
+        let p = Path::new(&record.path);
+        if p.is_absolute() {
+            p.to_path_buf()
+        } else {
+            layout.root.join(p)
+        }
+    };
+    let annotations_path: PathBuf = run_dir.join(ANNOTATIONS_FILE);
+    let payload = serde_json::json!({
+        "binary": target.run_name,
+        "binary_path": binary_path.display().to_string(),
+        "binary_hash": hash,
+        "ritual": ritual,
+        "functions": functions,
+    });
+    fs::write(&annotations_path, serde_json::to_string_pretty(&payload)?)
+        .with_context(|| format!("Failed to write {}", annotations_path.display()))?;
+
+    let mut values =
+        run_placeholders(&layout.root, &run_dir, &run.binary, &binary_path, ritual, &run.status);
+    values.insert("annotations", annotations_path.display().to_string());
+    let argv = expand_command(template, &values)?;
+    if dry_run {
+        println!("{}", display_command(&argv));
+        return Ok(());
+    }
+    Command::new(program(&layout.root, &argv[0]))
+        .args(&argv[1..])
+        .current_dir(&layout.root)
+        .stdin(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to launch {} ({})", tool, argv[0]))?;
+    println!("Opened {}/{} with {}", record.name, ritual, tool);
+    println!("  Command: {}", display_command(&argv));
+    println!("  Annotations: {}", annotations_path.display());
+    Ok(())
+}
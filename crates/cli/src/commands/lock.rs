@@ -0,0 +1,36 @@
+use anyhow::{anyhow, Result};
+use ritual_core::db::{LockError, ProjectLayout, ProjectLock};
+
+use crate::canonicalize_or_current;
+
+/// Take the project lock (`.ritual/lock`) for a command that writes to the project.
+///
+/// Without `wait`, a lock held by another process is an error naming its PID and command;
+/// with `wait`, the command blocks until the holder finishes. Directories that are not an
+/// initialized project have nothing to lock, so `None` is returned and the command reports
+/// the missing project itself.
+pub fn acquire_project_lock(root: &str, command: &str, wait: bool) -> Result<Option<ProjectLock>> {
+    lock_project(&ProjectLayout::new(canonicalize_or_current(root)?), command, wait)
+}
+
+/// [`acquire_project_lock`] for an already resolved project layout.
+pub fn lock_project(
+    layout: &ProjectLayout,
+    command: &str,
+    wait: bool,
+) -> Result<Option<ProjectLock>> {
+    if !layout.meta_dir.is_dir() {
+        return Ok(None);
+    }
+    match ProjectLock::try_acquire(layout, command) {
+        Ok(lock) => Ok(Some(lock)),
+        Err(err @ LockError::Held { .. }) if wait => {
+            eprintln!("{}; waiting for it to finish...", err);
+            Ok(Some(ProjectLock::acquire(layout, command)?))
+        }
+        Err(err @ LockError::Held { .. }) => {
+            Err(anyhow!("{}; pass --wait to wait for it to finish", err))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
@@ -1,17 +1,57 @@
+pub mod annotations;
 pub mod backends;
+pub mod baselines;
+pub mod batch;
 pub mod binaries;
+pub mod cache;
+pub mod config;
+pub mod dashboard;
+pub mod doctor;
+pub mod exchange;
+pub mod functions;
+pub mod gc;
+pub mod hooks;
+pub mod lock;
 pub mod project;
+pub mod query;
+pub mod ritual_wizard;
 pub mod rituals;
+pub mod self_test;
+pub mod serve;
 pub mod setup;
 pub mod slices;
+pub mod spec;
 pub mod status;
+pub mod tags;
+pub mod templates;
 pub mod util;
+pub mod watch;
 
+pub use annotations::*;
 pub use backends::*;
+pub use baselines::*;
+pub use batch::*;
 pub use binaries::*;
+pub use cache::*;
+pub use config::*;
+pub use dashboard::*;
+pub use doctor::*;
+pub use exchange::*;
+pub use functions::*;
+pub use gc::*;
+pub use hooks::*;
+pub use lock::*;
 pub use project::*;
+pub use query::*;
+pub use ritual_wizard::*;
 pub use rituals::*;
+pub use self_test::*;
+pub use serve::*;
 pub use setup::*;
 pub use slices::*;
+pub use spec::*;
 pub use status::*;
+pub use tags::*;
+pub use templates::*;
 pub use util::*;
+pub use watch::*;
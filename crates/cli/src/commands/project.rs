@@ -2,6 +2,7 @@ use std::fs;
 
 use crate::commands::open_project_db;
 use crate::commands::rituals::analysis_summary;
+use crate::commands::templates::{apply_project_template, resolve_template};
 use crate::{canonicalize_or_current, infer_project_name};
 use anyhow::{Context, Result};
 use serde::Serialize;
@@ -37,6 +38,18 @@ pub struct ProjectInfoLayout {
 
 /// Initialize a new project at `root`.
 pub fn init_project_command(root: &str, name: Option<String>) -> Result<()> {
+    init_project_with_template_command(root, name, None)
+}
+
+/// Initialize a new project at `root`, pre-populated from a built-in template name or a
+/// template directory (see [`crate::commands::templates`]).
+pub fn init_project_with_template_command(
+    root: &str,
+    name: Option<String>,
+    template: Option<&str>,
+) -> Result<()> {
+    // Resolve the template first so a bad name or spec leaves nothing behind.
+    let template = template.map(resolve_template).transpose()?;
     let root_path = canonicalize_or_current(root)?;
     let layout = ritual_core::db::ProjectLayout::new(&root_path);
 
@@ -82,9 +95,13 @@ pub fn init_project_command(root: &str, name: Option<String>) -> Result<()> {
 
     // Create the project database immediately so follow-on commands (and tests)
     // can rely on its presence.
-    ritual_core::db::ProjectDb::open(&layout.db_path).with_context(|| {
+    let db = ritual_core::db::ProjectDb::open(&layout.db_path).with_context(|| {
         format!("Failed to initialize project database at {}", layout.db_path.display())
     })?;
+    let applied = match &template {
+        Some(template) => Some(apply_project_template(&layout, &db, template)?),
+        None => None,
+    };
 
     println!("Initialized Binary Slicer project:");
     println!("  Name: {}", project_name);
@@ -97,6 +114,21 @@ pub fn init_project_command(root: &str, name: Option<String>) -> Result<()> {
     println!("  Graphs dir: {}", layout.graphs_dir.display());
     println!("  Rituals dir: {}", layout.rituals_dir.display());
     println!("  Outputs dir: {}", layout.outputs_dir.display());
+    if let (Some(template), Some(applied)) = (&template, &applied) {
+        println!("  Template: {}", template.name);
+        if !applied.slices.is_empty() {
+            println!("  Slices: {}", applied.slices.join(", "));
+        }
+        if !applied.skipped_slices.is_empty() {
+            println!("  Existing slices kept: {}", applied.skipped_slices.join(", "));
+        }
+        for path in &applied.files {
+            println!("  Wrote: {}", path.display());
+        }
+        for path in &applied.skipped_files {
+            println!("  Kept existing: {}", path.display());
+        }
+    }
 
     Ok(())
 }
@@ -104,16 +136,16 @@ pub fn init_project_command(root: &str, name: Option<String>) -> Result<()> {
 /// Show basic information about an existing project.
 pub fn project_info_command(root: &str, json: bool) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     let (config, _db_path, db) = open_project_db(&layout)?;
     let binaries = db.list_binaries().context("Failed to list binaries")?;
     let slices = db.list_slices().context("Failed to list slices")?;
-    let db_runs = db.list_ritual_runs(None).unwrap_or_default();
+    let db_runs = db.list_ritual_runs_with_ids(None).unwrap_or_default();
 
     let mut ritual_runs: Vec<crate::commands::RitualRunInfo> = Vec::new();
-    for run in &db_runs {
-        let mut info = crate::commands::db_run_to_info(&layout, run);
+    for (id, run) in &db_runs {
+        let mut info = crate::commands::db_run_to_info(&layout, *id, run);
         if let Ok(Some(analysis)) = db.load_analysis_result(&run.binary, &run.ritual) {
             info.analysis = Some(analysis_summary(&analysis, Some(run)));
         }
@@ -229,6 +261,9 @@ pub fn project_info_command(root: &str, json: bool) -> Result<()> {
                             detail.push_str(&format!(" unmatched={}", coverage.unmatched.len()));
                         }
                     }
+                    if let Some(ratio) = a.coverage.slice_ratio() {
+                        detail.push_str(&format!(" slice={:.1}%", ratio * 100.0));
+                    }
                     detail.push(']');
                     detail
                 })
@@ -244,7 +279,11 @@ pub fn project_info_command(root: &str, json: bool) -> Result<()> {
         for slice in slices {
             let bin = slice.default_binary.as_deref().unwrap_or("(no default binary)");
             let desc = slice.description.clone().unwrap_or_else(|| "(no description)".into());
-            println!("- {} [{:?}] binary: {} -- {}", slice.name, slice.status, bin, desc);
+            let archived = if slice.is_archived() { " (archived)" } else { "" };
+            println!(
+                "- {} [{:?}]{} binary: {} -- {}",
+                slice.name, slice.status, archived, bin, desc
+            );
         }
     }
 
@@ -0,0 +1,102 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::load_project_layout;
+use ritual_core::rituals::run::RunBinary;
+use ritual_core::services::query::{execute_query, parse_query};
+use ritual_core::services::search::{search_analysis, SearchKind, SearchPattern, SearchScope};
+
+use crate::canonicalize_or_current;
+use crate::commands::{open_project_db, BinarySelector};
+use crate::output::print_table;
+
+/// Run a query over the persisted analysis of a binary's latest ritual run.
+pub fn query_command(
+    root: &str,
+    binary: &str,
+    ritual: &str,
+    query: &str,
+    json: bool,
+) -> Result<()> {
+    let parsed = parse_query(query).with_context(|| format!("Invalid query '{}'", query))?;
+
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
+    let record = &target.binary;
+    let result = db
+        .load_analysis_result(&target.run_name, ritual)
+        .context("Failed to load analysis result")?
+        .ok_or_else(|| {
+            anyhow!("No analysis found for binary '{}' / ritual '{}'", record.name, ritual)
+        })?;
+
+    let output = execute_query(&parsed, &result);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&output.to_json())?);
+        return Ok(());
+    }
+
+    let cells: Vec<Vec<String>> =
+        output.rows.iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+    print_table(&output.columns, &cells);
+    println!("({} {})", cells.len(), if cells.len() == 1 { "row" } else { "rows" });
+    Ok(())
+}
+
+/// Search function names and evidence of every binary's latest runs for `pattern`;
+/// `source` keeps only evidence from that backend or `backend/pass`.
+pub fn search_command(
+    root: &str,
+    pattern: &str,
+    binary: Option<&str>,
+    kinds: &[String],
+    regex: bool,
+    source: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let matcher = SearchPattern::new(pattern, regex)?;
+    let kinds = kinds.iter().map(|k| SearchKind::parse(k)).collect::<Result<BTreeSet<_>, _>>()?;
+
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    let binary = match binary {
+        Some(selector) => {
+            let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+            Some(RunBinary::new(&db, BinarySelector::from(selector).resolve(&binaries)?)?.run_name)
+        }
+        None => None,
+    };
+    let scope = SearchScope { binary, kinds, source: source.map(str::to_string) };
+    let hits =
+        search_analysis(&db, &matcher, &scope).context("Failed to search analysis results")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+    if hits.is_empty() {
+        println!("No matches for '{}'", pattern);
+        return Ok(());
+    }
+    let cells: Vec<Vec<String>> = hits
+        .iter()
+        .map(|hit| {
+            vec![
+                hit.binary.clone(),
+                format!("{} #{}", hit.ritual, hit.run_id),
+                hit.slice.clone().unwrap_or_else(|| "-".into()),
+                format!("0x{:X}", hit.address),
+                hit.kind.to_string(),
+                hit.source.clone().unwrap_or_else(|| "-".into()),
+                hit.text.clone(),
+            ]
+        })
+        .collect();
+    print_table(&["binary", "run", "slice", "address", "kind", "source", "match"], &cells);
+    println!("({} {})", hits.len(), if hits.len() == 1 { "match" } else { "matches" });
+    Ok(())
+}
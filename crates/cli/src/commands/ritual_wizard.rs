@@ -0,0 +1,387 @@
+//! `init-ritual`: write a ritual spec under `rituals/` from flags and prompts.
+//!
+//! Fields not given as flags are asked for on stdin (unless `--no-input`): the ritual name,
+//! the binary (from the registered list), roots one per line with completion against the
+//! function names of the binary's latest analysis, the backend, and the outputs. The spec is
+//! checked with [`check_spec`] before it is kept, and can be run straight away.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use ritual_core::services::analysis::default_backend_registry;
+use ritual_core::services::roots::{AutoRoot, RootSpec};
+use serde::Serialize;
+
+use crate::canonicalize_or_current;
+use crate::commands::{
+    check_spec, run_ritual_command, GraphOutputs, OutputFormat, RitualOutputs, RunFlags,
+};
+
+/// Ambiguous roots list at most this many candidates.
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Flags of `init-ritual`; every unset field is prompted for.
+#[derive(Debug, Clone, Default)]
+pub struct InitRitualOptions {
+    pub name: Option<String>,
+    pub binary: Option<String>,
+    pub roots: Vec<String>,
+    pub backend: Option<String>,
+    /// Output formats (`json`, `dot`, `html`, `md`); empty for all of them.
+    pub outputs: Vec<String>,
+    pub description: Option<String>,
+    /// Run the spec once it is written (without it, the user is asked).
+    pub run: bool,
+    /// Overwrite an existing spec of the same name.
+    pub force: bool,
+    /// Fail on missing fields instead of prompting.
+    pub no_input: bool,
+}
+
+/// What a typed root resolves to against the functions of the latest analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootCompletion {
+    /// A function name, an address, or a `regex:`/`demangled:`/`string:` root, kept as typed.
+    Known(String),
+    /// The only function whose name starts with (or else contains) the input.
+    Completed(String),
+    /// Several functions match; the user has to type more.
+    Ambiguous(Vec<String>),
+    /// Nothing matches (or there is no analysis to match against); kept as typed.
+    Unknown(String),
+}
+
+/// Complete `input` against `names` (sorted function names). Prefix matches win over
+/// substring matches, and both are case-insensitive.
+pub fn complete_root(input: &str, names: &[String]) -> RootCompletion {
+    if !matches!(RootSpec::from_canonical(input), RootSpec::Name(_))
+        || AutoRoot::from_keyword(input).is_some()
+        || names.iter().any(|n| n == input)
+    {
+        return RootCompletion::Known(input.to_string());
+    }
+    let needle = input.to_lowercase();
+    let mut matches: Vec<&String> =
+        names.iter().filter(|n| n.to_lowercase().starts_with(&needle)).collect();
+    if matches.is_empty() {
+        matches = names.iter().filter(|n| n.to_lowercase().contains(&needle)).collect();
+    }
+    match matches.as_slice() {
+        [] => RootCompletion::Unknown(input.to_string()),
+        [only] => RootCompletion::Completed(only.to_string()),
+        many => RootCompletion::Ambiguous(many.iter().map(|n| n.to_string()).collect()),
+    }
+}
+
+/// The spec as written: only the fields the wizard sets, in the order people write them.
+#[derive(Debug, Serialize)]
+struct SpecDraft {
+    name: String,
+    binary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    roots: Vec<RootSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backend: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outputs: Option<RitualOutputs>,
+}
+
+/// Write a ritual spec under `rituals/`, prompting on stdin for whatever `options` leaves out.
+pub fn init_ritual_command(root: &str, options: InitRitualOptions) -> Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    init_ritual(root, options, &mut stdin.lock(), &mut stdout.lock())
+}
+
+/// [`init_ritual_command`] reading answers from `input` and writing prompts to `out`.
+pub fn init_ritual<R: BufRead, W: Write>(
+    root: &str,
+    options: InitRitualOptions,
+    input: &mut R,
+    out: &mut W,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    let mut prompt = Prompter { input, out, enabled: !options.no_input };
+
+    let name = match options.name {
+        Some(name) => name,
+        None => prompt.ask_until("--name", "Ritual name", |name| {
+            check_ritual_name(name).map(|()| name.to_string())
+        })?,
+    };
+    check_ritual_name(&name)?;
+    let spec_path = layout.rituals_dir.join(format!("{name}.yaml"));
+    if spec_path.exists() && !options.force {
+        return Err(anyhow!(
+            "Ritual spec already exists at {}; pass --force to overwrite it",
+            spec_path.display()
+        ));
+    }
+
+    let binary = match options.binary {
+        Some(binary) => binary,
+        None => prompt_binary(&mut prompt, &db)?,
+    };
+    let roots = if options.roots.is_empty() {
+        prompt_roots(&mut prompt, &known_functions(&db, &binary))?
+    } else {
+        options.roots
+    };
+    let backend = match options.backend {
+        Some(backend) => Some(backend),
+        None if prompt.enabled => prompt_backend(&mut prompt)?,
+        None => None,
+    };
+    let outputs = if !options.outputs.is_empty() || !prompt.enabled {
+        parse_outputs(&options.outputs.join(","))?
+    } else {
+        prompt.ask_until("--outputs", "Outputs (json, dot, html, md; empty for all)", |line| {
+            parse_outputs(line)
+        })?
+    };
+
+    let draft = SpecDraft {
+        name: name.clone(),
+        binary,
+        description: options.description,
+        roots: roots.iter().map(|r| RootSpec::from_canonical(r)).collect(),
+        backend,
+        outputs,
+    };
+    let yaml = serde_yaml::to_string(&draft).context("Failed to serialize ritual spec")?;
+    write_checked_spec(&layout, &spec_path, &yaml)?;
+    writeln!(prompt.out, "Wrote ritual spec: {}", spec_path.display())?;
+    writeln!(prompt.out, "  Binary: {}", draft.binary)?;
+    writeln!(prompt.out, "  Roots: {}", roots.join(", "))?;
+    if let Some(backend) = &draft.backend {
+        writeln!(prompt.out, "  Backend: {}", backend)?;
+    }
+
+    let run = options.run
+        || (prompt.enabled
+            && prompt.ask_until("--run", "Run it now? [y/N]", |answer| {
+                match answer.to_ascii_lowercase().as_str() {
+                    "" | "n" | "no" => Ok(false),
+                    "y" | "yes" => Ok(true),
+                    _ => Err(anyhow!("Answer y or n")),
+                }
+            })?);
+    prompt.out.flush()?;
+    if run {
+        let file = spec_path.to_string_lossy();
+        run_ritual_command(root, &file, None, RunFlags::default(), &[], &[])?;
+    }
+    Ok(())
+}
+
+/// Write `yaml` to `path` and keep it only when [`check_spec`] finds no errors; a spec it
+/// replaced is put back otherwise.
+fn write_checked_spec(layout: &ProjectLayout, path: &std::path::Path, yaml: &str) -> Result<()> {
+    fs::create_dir_all(&layout.rituals_dir).with_context(|| {
+        format!("Failed to ensure rituals dir {}", layout.rituals_dir.display())
+    })?;
+    let previous = fs::read(path).ok();
+    fs::write(path, yaml)
+        .with_context(|| format!("Failed to write ritual spec at {}", path.display()))?;
+    let check = check_spec(layout, &path.to_string_lossy(), None, &[]);
+    for warning in &check.warnings {
+        tracing::warn!("{}", warning);
+    }
+    if check.is_valid() {
+        return Ok(());
+    }
+    match previous {
+        Some(bytes) => fs::write(path, bytes),
+        None => fs::remove_file(path),
+    }
+    .with_context(|| format!("Failed to restore {}", path.display()))?;
+    Err(anyhow!("Ritual spec is invalid:\n  {}", check.errors.join("\n  ")))
+}
+
+fn check_ritual_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.contains(['/', '\\']) {
+        return Err(anyhow!("Ritual name '{}' must be non-empty and must not contain '/'", name));
+    }
+    Ok(())
+}
+
+/// Reads answers line by line; with prompting off, every question is an error naming the
+/// flag that would have answered it.
+struct Prompter<'a, R, W> {
+    input: &'a mut R,
+    out: &'a mut W,
+    enabled: bool,
+}
+
+impl<R: BufRead, W: Write> Prompter<'_, R, W> {
+    fn ask(&mut self, flag: &str, question: &str) -> Result<String> {
+        if !self.enabled {
+            return Err(anyhow!("Missing {} (prompts are off with --no-input)", flag));
+        }
+        write!(self.out, "{}: ", question)?;
+        self.out.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(anyhow!("Input ended while asking for {}", flag));
+        }
+        Ok(line.trim().to_string())
+    }
+
+    /// Ask until `parse` accepts the answer, printing why it did not.
+    fn ask_until<T>(
+        &mut self,
+        flag: &str,
+        question: &str,
+        mut parse: impl FnMut(&str) -> Result<T>,
+    ) -> Result<T> {
+        loop {
+            let answer = self.ask(flag, question)?;
+            match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(err) => writeln!(self.out, "  {}", err)?,
+            }
+        }
+    }
+}
+
+/// Pick a registered binary by number or name (the only one when the answer is empty).
+fn prompt_binary<R: BufRead, W: Write>(
+    prompt: &mut Prompter<'_, R, W>,
+    db: &ProjectDb,
+) -> Result<String> {
+    let binaries = db.list_binaries().context("Failed to list binaries")?;
+    if binaries.is_empty() {
+        return Err(anyhow!("No binaries registered; run add-binary first"));
+    }
+    if prompt.enabled {
+        writeln!(prompt.out, "Registered binaries:")?;
+        for (i, binary) in binaries.iter().enumerate() {
+            let arch = binary.arch.as_deref().unwrap_or("unknown arch");
+            writeln!(prompt.out, "  {}) {} ({}, {})", i + 1, binary.name, binary.path, arch)?;
+        }
+    }
+    prompt.ask_until("--binary", "Binary (number or name)", |answer| {
+        let picked = match answer.parse::<usize>() {
+            Ok(n) => binaries.get(n.wrapping_sub(1)),
+            Err(_) if answer.is_empty() && binaries.len() == 1 => binaries.first(),
+            Err(_) => binaries.iter().find(|b| b.name == answer),
+        };
+        picked.map(|b| b.name.clone()).ok_or_else(|| anyhow!("No registered binary '{}'", answer))
+    })
+}
+
+/// Roots one per line until an empty line, completing names against `functions`.
+fn prompt_roots<R: BufRead, W: Write>(
+    prompt: &mut Prompter<'_, R, W>,
+    functions: &[String],
+) -> Result<Vec<String>> {
+    if prompt.enabled && !functions.is_empty() {
+        writeln!(
+            prompt.out,
+            "Roots are completed against {} functions of the latest analysis.",
+            functions.len()
+        )?;
+    }
+    let mut roots: Vec<String> = Vec::new();
+    loop {
+        let question = if roots.is_empty() {
+            "Root (name, 0x address, regex:, demangled:, string:, entrypoint, or exports)"
+        } else {
+            "Another root (empty to finish)"
+        };
+        let answer = prompt.ask("--root", question)?;
+        if answer.is_empty() {
+            if roots.is_empty() {
+                writeln!(prompt.out, "  At least one root is required")?;
+                continue;
+            }
+            return Ok(roots);
+        }
+        let root = match complete_root(&answer, functions) {
+            RootCompletion::Known(root) => root,
+            RootCompletion::Completed(root) => {
+                writeln!(prompt.out, "  -> {}", root)?;
+                root
+            }
+            RootCompletion::Ambiguous(candidates) => {
+                let shown = candidates.len().min(MAX_SUGGESTIONS);
+                let more = candidates.len() - shown;
+                let mut line = candidates[..shown].join(", ");
+                if more > 0 {
+                    line.push_str(&format!(", … ({} more)", more));
+                }
+                writeln!(prompt.out, "  Matches: {}", line)?;
+                continue;
+            }
+            RootCompletion::Unknown(root) => {
+                if !functions.is_empty() {
+                    writeln!(
+                        prompt.out,
+                        "  (no function named '{}' in the latest analysis)",
+                        root
+                    )?;
+                }
+                root
+            }
+        };
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+}
+
+/// A registered backend, or `None` (the project default) for an empty answer.
+fn prompt_backend<R: BufRead, W: Write>(prompt: &mut Prompter<'_, R, W>) -> Result<Option<String>> {
+    let names = default_backend_registry().names();
+    let question = format!("Backend ({}; empty for the project default)", names.join(", "));
+    prompt.ask_until("--backend", &question, |answer| match answer {
+        "" => Ok(None),
+        name if names.iter().any(|n| n == name) => Ok(Some(name.to_string())),
+        name => Err(anyhow!("Unknown backend '{}'", name)),
+    })
+}
+
+/// `outputs` block for a comma- or space-separated format list; `None` (everything) when
+/// the list is empty or names every format.
+fn parse_outputs(list: &str) -> Result<Option<RitualOutputs>> {
+    let mut formats = Vec::new();
+    for word in list.split([',', ' ']).map(str::trim).filter(|w| !w.is_empty()) {
+        let format = OutputFormat::all()
+            .into_iter()
+            .find(|f| f.as_str() == word.to_ascii_lowercase())
+            .ok_or_else(|| anyhow!("Unknown output format '{}' (json, dot, html, md)", word))?;
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    if formats.is_empty() || formats.len() == OutputFormat::all().len() {
+        return Ok(None);
+    }
+    let has = |format| formats.contains(&format);
+    Ok(Some(RitualOutputs {
+        reports: has(OutputFormat::Json) || has(OutputFormat::Html),
+        graphs: GraphOutputs::Enabled(has(OutputFormat::Dot)),
+        docs: has(OutputFormat::Md),
+        formats,
+    }))
+}
+
+/// Function names of the binary's most recent run with analysis, sorted and deduplicated.
+fn known_functions(db: &ProjectDb, binary: &str) -> Vec<String> {
+    let mut runs = db.list_ritual_runs(Some(binary)).unwrap_or_default();
+    runs.sort_by(|a, b| b.finished_at.cmp(&a.finished_at));
+    let Some((_, analysis)) =
+        runs.iter().find_map(|run| db.load_analysis_skeleton(binary, &run.ritual).ok().flatten())
+    else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = analysis.functions.into_iter().filter_map(|f| f.name).collect();
+    names.sort();
+    names.dedup();
+    names
+}
@@ -1,186 +1,45 @@
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use crate::canonicalize_or_current;
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
-use ritual_core::db::RitualRunStatus;
-use serde::Deserialize;
-use serde::Serialize;
-use sha2::Digest;
+use ritual_core::analysis::diff::{diff_analysis, AnalysisDiff};
+use ritual_core::analysis::matching::match_functions;
+use ritual_core::db::RunUsage;
+use ritual_core::rituals::hooks::{run_post_run_hooks, HookOutcome};
+use ritual_core::rituals::run::{
+    check_baseline, check_validation, compare_with_baseline, execute_run, failed_run_error,
+    prepare_run, run_ritual_spec, run_span, validate_run, write_normalized_spec, write_run_outputs,
+    RunBinary, SpecSource, StageProgress, UNTRACKED_OUTPUTS,
+};
+use serde::{Deserialize, Serialize};
 
+use crate::commands::baselines::print_baseline;
 use crate::commands::{
     collect_ritual_specs, load_runs_from_db, load_runs_from_db_and_disk, open_project_db,
-    validate_run_status,
+    tags_cell, validate_run_status,
 };
+use crate::output::{duration_ms, relative_time, run_status_tone, Cell, Table};
 use ritual_core::services::analysis::{
-    default_backend_registry, AnalysisOptions, AnalysisRequest, AnalysisResult, BlockEdgeKind,
-    RitualRunner, RunMetadata,
+    default_backend_registry, persist_run, AnalysisOptions, AnalysisResult, ResultValidation,
+};
+use ritual_core::services::cache::AnalysisCache;
+use ritual_core::services::manifest::OutputManifest;
+use ritual_core::services::pipeline::PipelineState;
+use ritual_core::services::report::REPORT_FILE;
+
+pub use ritual_core::rituals::run::{RitualRunMetadata, RitualRunOutcome, RunFlags};
+pub use ritual_core::rituals::spec::{
+    sha256_bytes, AutoRoots, BackendChoice, BinarySelector, GraphOutputs, OutputFormat,
+    RawImageSpec, RitualOutputs, RitualRoots, RitualSpec,
 };
-
-const DEFAULT_BACKEND_NAME: &str = "validate-only";
-
-fn default_backend_name() -> String {
-    DEFAULT_BACKEND_NAME.to_string()
-}
-
-fn default_backend_version(backend: &str) -> Option<String> {
-    match backend {
-        "validate-only" => Some("validate-only".to_string()),
-        _ => None,
-    }
-}
-
-fn resolve_backend_choice(
-    registry: &ritual_core::services::analysis::BackendRegistry,
-    override_backend: Option<&str>,
-    spec_backend: Option<String>,
-    config: &ritual_core::db::ProjectConfig,
-) -> String {
-    if let Some(b) = override_backend {
-        return b.to_string();
-    }
-    if let Some(b) = spec_backend {
-        if registry.get(&b).is_some() {
-            return b;
-        }
-    }
-    if let Some(b) = &config.default_backend {
-        if registry.get(b).is_some() {
-            return b.clone();
-        }
-    }
-    // Prefer rizin if available, else capstone, else validate-only.
-    for candidate in ["rizin", "capstone", DEFAULT_BACKEND_NAME] {
-        if registry.get(candidate).is_some() {
-            return candidate.to_string();
-        }
-    }
-    DEFAULT_BACKEND_NAME.to_string()
-}
-
-fn resolve_backend_path(backend: &str, config: &ritual_core::db::ProjectConfig) -> Option<PathBuf> {
-    match backend {
-        "rizin" => config.backends.rizin.as_ref().map(PathBuf::from),
-        "ghidra" => config.backends.ghidra_headless.as_ref().map(PathBuf::from),
-        _ => None,
-    }
-}
-
-fn resolve_backend_version(
-    backend: &str,
-    config: &ritual_core::db::ProjectConfig,
-) -> Option<String> {
-    match backend {
-        "rizin" => config.backend_versions.rizin.clone(),
-        "ghidra" => config.backend_versions.ghidra_headless.clone(),
-        "capstone" => config.backend_versions.capstone.clone(),
-        other => default_backend_version(other),
-    }
-    .or_else(|| default_backend_version(backend))
-}
-
-fn format_backend_label(
-    backend: &str,
-    backend_version: Option<&str>,
-    backend_path: Option<&str>,
-) -> String {
-    let mut label = format!("backend: {}", backend);
-    if let Some(v) = backend_version {
-        label.push_str(&format!(" {}", v));
-    }
-    if let Some(p) = backend_path {
-        label.push_str(&format!(" @ {}", p));
-    }
-    label
-}
-
-fn render_dot(result: &AnalysisResult, backend_label: Option<&str>) -> String {
-    let mut out = String::from("digraph G {\n  rankdir=LR;\n");
-    if let Some(label) = backend_label {
-        let safe_label = label.replace('"', "\\\"");
-        out.push_str(&format!("  label=\"{}\";\n  labelloc=top;\n", safe_label));
-    }
-    if result.functions.is_empty() && result.call_edges.is_empty() && result.basic_blocks.is_empty()
-    {
-        out.push_str("  // no graph data available\n}\n");
-        return out;
-    }
-    for func in &result.functions {
-        let label = func.name.clone().unwrap_or_else(|| format!("0x{:X}", func.address));
-        out.push_str(&format!("  f_{:X} [label=\"{}\" shape=box];\n", func.address, label));
-    }
-    for edge in &result.call_edges {
-        out.push_str(&format!("  f_{:X} -> f_{:X} [label=\"call\"];\n", edge.from, edge.to));
-    }
-    for bb in &result.basic_blocks {
-        out.push_str(&format!(
-            "  bb_{:X} [label=\"bb 0x{:X}\\nlen={}\" shape=ellipse];\n",
-            bb.start, bb.start, bb.len
-        ));
-        for succ in &bb.successors {
-            let label = match succ.kind {
-                BlockEdgeKind::Fallthrough => "fallthrough",
-                BlockEdgeKind::Jump => "jump",
-                BlockEdgeKind::ConditionalJump => "cjump",
-                BlockEdgeKind::IndirectJump => "ijump",
-                BlockEdgeKind::Call => "call",
-                BlockEdgeKind::IndirectCall => "icall",
-            };
-            out.push_str(&format!(
-                "  bb_{:X} -> bb_{:X} [label=\"{}\"];\n",
-                bb.start, succ.target, label
-            ));
-        }
-    }
-    out.push_str("}\n");
-    out
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct RitualSpec {
-    pub name: String,
-    pub binary: String,
-    pub roots: Vec<String>,
-    #[serde(default)]
-    pub max_depth: Option<u32>,
-    #[serde(default)]
-    pub backend: Option<String>,
-    #[serde(default)]
-    pub description: Option<String>,
-    #[serde(default)]
-    pub outputs: Option<RitualOutputs>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct RitualOutputs {
-    #[serde(default)]
-    pub reports: bool,
-    #[serde(default)]
-    pub graphs: bool,
-    #[serde(default)]
-    pub docs: bool,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RitualRunMetadata {
-    pub ritual: String,
-    pub binary: String,
-    pub spec_hash: String,
-    pub binary_hash: Option<String>,
-    #[serde(default = "default_backend_name")]
-    pub backend: String,
-    #[serde(default)]
-    pub backend_version: Option<String>,
-    #[serde(default)]
-    pub backend_path: Option<String>,
-    pub started_at: String,
-    pub finished_at: String,
-    pub status: RitualRunStatus,
-}
 
 #[derive(Debug, Serialize, Clone)]
 pub struct RitualRunInfo {
+    /// Row id of the run in the DB (`None` for runs found only on disk).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
     pub binary: String,
     pub name: String,
     pub path: String,
@@ -192,8 +51,19 @@ pub struct RitualRunInfo {
     pub backend_version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub backend_path: Option<String>,
+    /// Spec file the run came from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec_path: Option<String>,
+    /// Analysis options the run was made with (recorded in the DB only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis_options: Option<AnalysisOptions>,
+    /// Duration and tool resource usage (recorded in the DB only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RunUsage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analysis: Option<AnalysisSummary>,
+    /// Tags of the run (`tag add --binary B --ritual R`).
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -215,6 +85,8 @@ pub struct AnalysisSummary {
     pub root_coverage: Option<RootCoverage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_hits: Option<Vec<ritual_core::services::analysis::RootHit>>,
+    /// How much of the binary's executable code the slice covers.
+    pub coverage: ritual_core::analysis::coverage::SliceCoverage,
 }
 
 pub fn analysis_summary(
@@ -240,6 +112,7 @@ pub fn analysis_summary(
             .or_else(|| run.and_then(|r| r.backend_path.clone())),
         root_coverage: root_coverage(result),
         root_hits: (!result.root_hits.is_empty()).then(|| result.root_hits.clone()),
+        coverage: ritual_core::analysis::coverage::slice_coverage(result),
     }
 }
 
@@ -335,32 +208,13 @@ pub struct RitualSpecInfo {
     pub format: String,
 }
 
-impl RitualSpec {
-    pub fn validate(&self) -> Result<()> {
-        if self.name.trim().is_empty() {
-            return Err(anyhow!("Ritual spec 'name' is required"));
-        }
-        if self.binary.trim().is_empty() {
-            return Err(anyhow!("Ritual spec 'binary' is required"));
-        }
-        if self.roots.is_empty() {
-            return Err(anyhow!("Ritual spec must include at least one root"));
-        }
-        Ok(())
-    }
-}
-
-pub fn sha256_bytes(bytes: &[u8]) -> String {
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(bytes);
-    format!("{:x}", hasher.finalize())
-}
-
 pub fn db_run_to_info(
     layout: &ritual_core::db::ProjectLayout,
+    id: i64,
     rec: &ritual_core::db::RitualRunRecord,
 ) -> RitualRunInfo {
     RitualRunInfo {
+        id: Some(id),
         binary: rec.binary.clone(),
         name: rec.ritual.clone(),
         path: layout.binary_output_root(&rec.binary).join(&rec.ritual).display().to_string(),
@@ -371,186 +225,218 @@ pub fn db_run_to_info(
         backend: Some(rec.backend.clone()),
         backend_version: rec.backend_version.clone(),
         backend_path: rec.backend_path.clone(),
+        spec_path: rec.spec_path.clone(),
+        analysis_options: rec.analysis_options.clone(),
+        usage: rec.usage,
         analysis: None,
+        tags: Vec::new(),
+    }
+}
+
+/// `rerun-ritual` changes to the analysis options of the run it repeats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalysisOverrides {
+    pub max_depth: Option<u32>,
+    pub max_instructions: Option<usize>,
+    pub max_total_instructions: Option<usize>,
+    pub include_strings: Option<bool>,
+    pub include_imports: Option<bool>,
+}
+
+impl AnalysisOverrides {
+    pub fn apply(&self, options: &mut AnalysisOptions) {
+        if let Some(depth) = self.max_depth {
+            options.max_depth = Some(depth);
+        }
+        if let Some(budget) = self.max_instructions {
+            options.max_instructions = Some(budget);
+        }
+        if let Some(budget) = self.max_total_instructions {
+            options.max_total_instructions = Some(budget);
+        }
+        if let Some(strings) = self.include_strings {
+            options.include_strings = strings;
+        }
+        if let Some(imports) = self.include_imports {
+            options.include_imports = imports;
+        }
+    }
+}
+
+/// `max depth 3, max instructions 1024, strings on, imports off`; a total instruction budget
+/// shows as `max instructions 1024 (total 100000)`.
+pub fn format_analysis_options(options: &AnalysisOptions) -> String {
+    let limit = |value: Option<String>| value.unwrap_or_else(|| "unlimited".into());
+    let switch = |on: bool| if on { "on" } else { "off" };
+    let total = options.max_total_instructions.map(|n| format!(" (total {n})"));
+    format!(
+        "max depth {}, max instructions {}{}, strings {}, imports {}",
+        limit(options.max_depth.map(|d| d.to_string())),
+        limit(options.max_instructions.map(|n| n.to_string())),
+        total.unwrap_or_default(),
+        switch(options.include_strings),
+        switch(options.include_imports),
+    )
+}
+
+/// One-line run usage for `show-ritual-run`, e.g. `12.4s (tools: 9.8s CPU, 412 MiB peak)`.
+pub fn format_usage(usage: &RunUsage) -> String {
+    let mut tools = Vec::new();
+    if let Some(cpu) = usage.cpu_ms {
+        tools.push(format!("{} CPU", duration_ms(cpu)));
+    }
+    if let Some(kb) = usage.peak_rss_kb {
+        tools.push(format!("{} MiB peak", kb.div_ceil(1024)));
+    }
+    if tools.is_empty() {
+        duration_ms(usage.duration_ms)
+    } else {
+        format!("{} (tools: {})", duration_ms(usage.duration_ms), tools.join(", "))
+    }
+}
+
+/// Path of the spec under `rituals/` named `name` (for `run-ritual --spec-name`).
+///
+/// Specs match by their `name:` field first, then by file name without extension; a name
+/// shared by several specs is an error rather than a guess.
+pub fn find_ritual_spec(root: &str, name: &str) -> Result<String> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let specs = collect_ritual_specs(&layout.rituals_dir)?;
+    let stem = |spec: &RitualSpecInfo| {
+        Path::new(&spec.path).file_stem().map(|s| s.to_string_lossy().into_owned())
+    };
+    let mut matches: Vec<&RitualSpecInfo> = specs.iter().filter(|s| s.name == name).collect();
+    if matches.is_empty() {
+        matches = specs.iter().filter(|s| stem(s).as_deref() == Some(name)).collect();
+    }
+    match matches.as_slice() {
+        [spec] => Ok(spec.path.clone()),
+        [] => {
+            let names: Vec<&str> = specs.iter().map(|s| s.name.as_str()).collect();
+            Err(anyhow!(
+                "No ritual spec named '{}' under {} (available: {})",
+                name,
+                layout.rituals_dir.display(),
+                if names.is_empty() { "none".to_string() } else { names.join(", ") }
+            ))
+        }
+        many => {
+            let paths: Vec<&str> = many.iter().map(|s| s.path.as_str()).collect();
+            Err(anyhow!(
+                "Ritual spec name '{}' is ambiguous ({}); pass --file instead",
+                name,
+                paths.join(", ")
+            ))
+        }
     }
 }
 
 /// Run a ritual spec (stub analysis) and organize outputs per binary and ritual name.
+///
+/// `seed_strings` adds `string:` roots on top of the spec's, so functions referencing those
+/// strings seed the slice. `variables` (`name=value`) fill `${name}` references in the spec
+/// (see [`resolve_spec`]). `flags` control overwriting, caching, reproducibility, and the
+/// backend timeout (see [`RunFlags`]).
 pub fn run_ritual_command(
     root: &str,
     file: &str,
     backend_override: Option<&str>,
-    force: bool,
+    flags: RunFlags,
+    seed_strings: &[String],
+    variables: &[String],
 ) -> Result<()> {
-    use ritual_core::db::ProjectLayout;
-
     let root_path = canonicalize_or_current(root)?;
-    let layout = ProjectLayout::new(&root_path);
-
-    let (config, db_path, db) = open_project_db(&layout)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let source = SpecSource::File(file);
+    let outcome = run_ritual_spec(
+        &layout,
+        source,
+        backend_override,
+        flags,
+        seed_strings,
+        variables,
+        Some(&print_stage),
+    )?;
+    print_run_outcome(&outcome, flags)
+}
 
-    // Load ritual spec (supports YAML or JSON based on extension).
-    let spec_path = Path::new(file);
-    let spec_bytes = fs::read(spec_path)
-        .with_context(|| format!("Failed to read ritual spec at {}", spec_path.display()))?;
-    let spec_hash = sha256_bytes(&spec_bytes);
-    let spec: RitualSpec = if spec_path.extension().and_then(|e| e.to_str()) == Some("json") {
-        serde_json::from_slice(&spec_bytes).context("Failed to parse ritual spec JSON")?
-    } else {
-        serde_yaml::from_slice(&spec_bytes).context("Failed to parse ritual spec YAML")?
-    };
-    spec.validate()?;
+/// Run a ritual synthesized from a slice: its roots (see `slice-add-root`) against its
+/// default binary, named after the rituals that feed the slice. Otherwise like
+/// [`run_ritual_command`].
+pub fn run_slice_ritual_command(
+    root: &str,
+    slice: &str,
+    backend_override: Option<&str>,
+    flags: RunFlags,
+    seed_strings: &[String],
+    variables: &[String],
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let source = SpecSource::Slice(slice);
+    let outcome = run_ritual_spec(
+        &layout,
+        source,
+        backend_override,
+        flags,
+        seed_strings,
+        variables,
+        Some(&print_stage),
+    )?;
+    print_run_outcome(&outcome, flags)
+}
 
-    // Make sure the binary exists in the DB.
-    let binaries = db.list_binaries().context("Failed to list binaries")?;
-    let target_bin = binaries
-        .iter()
-        .find(|b| b.name == spec.binary || b.path.ends_with(&spec.binary))
-        .cloned()
-        .ok_or_else(|| anyhow!("Binary '{}' not found in project database", spec.binary))?;
-
-    // Prepare output directories.
-    let bin_output_root = layout.binary_output_root(&target_bin.name);
-    let run_output_root = bin_output_root.join(&spec.name);
-    if run_output_root.exists() {
-        if force {
-            fs::remove_dir_all(&run_output_root).with_context(|| {
-                format!("Failed to clean existing ritual output dir {}", run_output_root.display())
-            })?;
-        } else {
-            return Err(anyhow!(
-                "Ritual output already exists at {} (rerun with --force to overwrite)",
-                run_output_root.display()
-            ));
-        }
+fn print_run_outcome(outcome: &RitualRunOutcome, flags: RunFlags) -> Result<()> {
+    println!("Ran ritual (stub): {}", outcome.ritual);
+    println!("  Binary: {} (id {})", outcome.binary, outcome.binary_id);
+    println!("  Roots: {:?}", outcome.roots);
+    if !outcome.failed_backends.is_empty() {
+        let failed: Vec<&str> =
+            outcome.failed_backends.iter().map(|f| f.backend.as_str()).collect();
+        println!("  Backend: {} (after {} failed)", outcome.backend, failed.join(", "));
     }
-    fs::create_dir_all(&run_output_root).with_context(|| {
-        format!("Failed to create ritual output dir {}", run_output_root.display())
-    })?;
-
-    // Resolve binary hash (prefer stored hash; compute if missing).
-    let binary_path = {
-        let p = Path::new(&target_bin.path);
-        if p.is_absolute() {
-            p.to_path_buf()
-        } else {
-            root_path.join(p)
-        }
-    };
-    let binary_hash = if let Some(h) = &target_bin.hash {
-        Some(h.clone())
-    } else if binary_path.exists() {
-        Some(crate::sha256_file(&binary_path)?)
-    } else {
-        None
-    };
+    if outcome.cache_hit {
+        println!("  Analysis: reused cached result (--no-cache to re-analyze)");
+    }
+    for sub in &outcome.result.sub_slices {
+        println!("  Sub-slice {}: {} function(s)", sub.label, sub.functions.len());
+    }
+    println!("  Output: {}", outcome.run_dir.display());
+    if let Some(log_path) = crate::logging::run_log_path(&outcome.run_dir) {
+        println!("  Log: {}", log_path.display());
+    }
+    print_validation(&outcome.validation);
+    print_baseline(outcome.baseline.as_ref());
+    print_hooks(&outcome.hooks);
 
-    // Choose backend (CLI override > spec > config/default preference).
-    let backends = default_backend_registry();
-    let backend_name =
-        resolve_backend_choice(&backends, backend_override, spec.backend.clone(), &config);
-    let backend_path = resolve_backend_path(&backend_name, &config);
-    let mut spec_copy = spec;
-    if spec_copy.outputs.is_none() {
-        spec_copy.outputs = Some(RitualOutputs { reports: true, graphs: true, docs: true });
-    }
-    spec_copy.backend = Some(backend_name.clone());
-    let normalized_spec_path = run_output_root.join("spec.yaml");
-    let yaml = serde_yaml::to_string(&spec_copy).context("Failed to serialize ritual spec")?;
-    fs::write(&normalized_spec_path, yaml).with_context(|| {
-        format!("Failed to write normalized spec to {}", normalized_spec_path.display())
-    })?;
-
-    // Invoke analysis service (validate-only default backend for now).
-    let backend = backends.get(&backend_name).ok_or_else(|| {
-        anyhow!("Backend '{}' not found (available: {:?})", backend_name, backends.names())
-    })?;
-    let request = AnalysisRequest {
-        ritual_name: spec_copy.name.clone(),
-        binary_name: target_bin.name.clone(),
-        binary_path: binary_path.clone(),
-        roots: spec_copy.roots.clone(),
-        arch: target_bin.arch.clone(),
-        options: AnalysisOptions {
-            max_depth: spec_copy.max_depth,
-            include_imports: true,
-            include_strings: true,
-            max_instructions: Some(1024),
-        },
-        backend_path: backend_path.clone(),
-    };
-    let ctx = ritual_core::db::ProjectContext {
-        layout: layout.clone(),
-        config: config.clone(),
-        db_path: db_path.clone(),
-        db,
-    };
-    let runner = RitualRunner { ctx: &ctx, backend };
-    let run_meta = RunMetadata {
-        spec_hash: spec_hash.clone(),
-        binary_hash: binary_hash.clone(),
-        backend: backend_name.clone(),
-        backend_version: resolve_backend_version(&backend_name, &config),
-        backend_path: backend_path.as_ref().map(|p| p.display().to_string()),
-        status: RitualRunStatus::Stubbed,
-    };
-    let analysis_result = runner.run(&request, &run_meta)?;
-
-    // Write report from analysis result.
-    let backend_version =
-        analysis_result.backend_version.clone().or_else(|| run_meta.backend_version.clone());
-    let backend_path =
-        analysis_result.backend_path.clone().or_else(|| run_meta.backend_path.clone());
-    let backend_label =
-        format_backend_label(&backend_name, backend_version.as_deref(), backend_path.as_deref());
-    let report_path = run_output_root.join("report.json");
-    let report = serde_json::json!({
-        "ritual": spec_copy.name,
-        "binary": target_bin.name,
-        "roots": spec_copy.roots,
-        "max_depth": spec_copy.max_depth,
-        "status": run_meta.status.as_str(),
-        "backend": backend_name.clone(),
-        "backend_version": backend_version.clone(),
-        "backend_path": backend_path.clone(),
-        "functions": analysis_result.functions,
-        "edges": analysis_result.call_edges,
-        "basic_blocks": analysis_result.basic_blocks,
-        "evidence": analysis_result.evidence,
-    });
-    fs::write(&report_path, serde_json::to_string_pretty(&report)?)
-        .with_context(|| format!("Failed to write ritual report at {}", report_path.display()))?;
-
-    // Write run metadata.
-    let now = Utc::now().to_rfc3339();
-    let metadata = RitualRunMetadata {
-        ritual: spec_copy.name.clone(),
-        binary: target_bin.name.clone(),
-        spec_hash,
-        binary_hash,
-        backend: backend_name,
-        backend_version,
-        backend_path,
-        started_at: now.clone(),
-        finished_at: now,
-        status: run_meta.status,
-    };
-    let metadata_path = run_output_root.join("run_metadata.json");
-    fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)
-        .with_context(|| format!("Failed to write run metadata at {}", metadata_path.display()))?;
+    check_validation(flags, &outcome.validation, &outcome.run_dir)?;
+    check_baseline(outcome.baseline.as_ref(), &outcome.run_dir)
+}
 
-    // Write graph DOT (best-effort even if sparse).
-    let dot = render_dot(&analysis_result, Some(&backend_label));
-    let dot_path = run_output_root.join("graph.dot");
-    fs::write(&dot_path, dot)
-        .with_context(|| format!("Failed to write ritual graph at {}", dot_path.display()))?;
+/// Pipeline progress of a run, as printed by `run-ritual`.
+fn print_stage(progress: &StageProgress) {
+    println!("  {}", progress);
+}
 
-    println!("Ran ritual (stub): {}", spec_copy.name);
-    println!("  Binary: {}", target_bin.name);
-    println!("  Roots: {:?}", spec_copy.roots);
-    println!("  Output: {}", run_output_root.display());
+fn print_validation(validation: &ResultValidation) {
+    if !validation.is_ok() {
+        println!(
+            "  Validation: {} issue(s) (see report.json; --strict to fail)",
+            validation.issues.len()
+        );
+    }
+}
 
-    Ok(())
+fn print_hooks(hooks: &[HookOutcome]) {
+    for hook in hooks {
+        let result = match (hook.exit_code, &hook.error) {
+            (_, Some(error)) => error.clone(),
+            (Some(code), None) => format!("exit {}", code),
+            (None, None) => "terminated by a signal".into(),
+        };
+        println!("  Hook: {} ({})", hook.command, result);
+    }
 }
 
 /// Rerun a ritual by reusing a normalized spec from an existing run.
@@ -560,25 +446,20 @@ pub fn rerun_ritual_command(
     ritual: &str,
     as_name: &str,
     backend_override: Option<&str>,
-    force: bool,
+    flags: RunFlags,
+    overrides: AnalysisOverrides,
 ) -> Result<()> {
-    use ritual_core::db::ProjectLayout;
-
     let root_path = canonicalize_or_current(root)?;
-    let layout = ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
-    let (config, db_path, db) = open_project_db(&layout)?;
+    let (config, _db_path, db) = open_project_db(&layout)?;
 
-    // Verify binary exists in DB.
-    let binaries = db.list_binaries().context("Failed to list binaries")?;
-    let target_bin = binaries
-        .iter()
-        .find(|b| b.name == binary || b.path.ends_with(binary))
-        .cloned()
-        .ok_or_else(|| anyhow!("Binary '{}' not found in project database", binary))?;
+    // Verify binary exists in DB (accepts the same selector forms as specs).
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let target = RunBinary::new(&db, BinarySelector::from(binary).resolve(&binaries)?)?;
 
     // Locate existing run's spec.yaml.
-    let existing_run_root = layout.binary_output_root(&target_bin.name).join(ritual);
+    let existing_run_root = layout.binary_output_root(&target.run_name).join(ritual);
     let existing_spec = existing_run_root.join("spec.yaml");
     if !existing_spec.is_file() {
         return Err(anyhow!("Spec not found for existing run at {}", existing_spec.display()));
@@ -588,15 +469,33 @@ pub fn rerun_ritual_command(
     let spec_bytes = fs::read(&existing_spec)
         .with_context(|| format!("Failed to read existing spec at {}", existing_spec.display()))?;
     let spec_hash = sha256_bytes(&spec_bytes);
-    let mut spec: RitualSpec =
-        serde_yaml::from_slice(&spec_bytes).context("Failed to parse spec")?;
+    let spec: RitualSpec = serde_yaml::from_slice(&spec_bytes).context("Failed to parse spec")?;
     spec.validate()?;
+    let mut prepared =
+        prepare_run(&layout, &config, &target, spec, &spec_hash, as_name, backend_override)?;
+    let previous = db
+        .list_ritual_runs(Some(&target.run_name))
+        .context("Failed to list ritual runs")?
+        .into_iter()
+        .rev()
+        .find(|run| run.ritual == ritual && run.binary_id.is_none_or(|id| id == target.id));
+    if let Some(previous) = previous {
+        // The rerun comes from the same spec file, and analyzes the same way, as the run it
+        // repeats (runs recorded before analysis options were stored keep the defaults).
+        prepared.meta.spec_path = previous.spec_path;
+        if let Some(options) = previous.analysis_options {
+            prepared.request.options = options;
+        }
+    }
+    overrides.apply(&mut prepared.request.options);
+    prepared.apply_flags(flags)?;
+    prepared.load_symbols(&db)?;
 
     // Prepare output dirs for new run.
-    let new_run_root = layout.binary_output_root(&target_bin.name).join(as_name);
+    let new_run_root = &prepared.run_dir;
     if new_run_root.exists() {
-        if force {
-            fs::remove_dir_all(&new_run_root).with_context(|| {
+        if flags.force {
+            fs::remove_dir_all(new_run_root).with_context(|| {
                 format!("Failed to clean existing ritual output dir {}", new_run_root.display())
             })?;
         } else {
@@ -606,129 +505,46 @@ pub fn rerun_ritual_command(
             ));
         }
     }
-    fs::create_dir_all(&new_run_root)
+    fs::create_dir_all(new_run_root)
         .with_context(|| format!("Failed to create rerun dir {}", new_run_root.display()))?;
+    write_normalized_spec(&prepared)?;
+    let _span = run_span(&prepared.request.binary_name, as_name, new_run_root);
 
-    // Hash binary path.
-    let binary_path = {
-        let p = Path::new(&target_bin.path);
-        if p.is_absolute() {
-            p.to_path_buf()
-        } else {
-            root_path.join(p)
-        }
-    };
-    let binary_hash = if let Some(h) = &target_bin.hash {
-        Some(h.clone())
-    } else if binary_path.exists() {
-        Some(crate::sha256_file(&binary_path)?)
-    } else {
-        None
-    };
-
-    // Choose backend (CLI override > spec > config/default preference).
     let backends = default_backend_registry();
-    let backend_name =
-        resolve_backend_choice(&backends, backend_override, spec.backend.clone(), &config);
-    let backend_path = resolve_backend_path(&backend_name, &config);
-    if spec.outputs.is_none() {
-        spec.outputs = Some(RitualOutputs { reports: true, graphs: true, docs: true });
-    }
-    spec.backend = Some(backend_name.clone());
-    let normalized_spec_path = new_run_root.join("spec.yaml");
-    let yaml = serde_yaml::to_string(&spec).context("Failed to serialize ritual spec")?;
-    fs::write(&normalized_spec_path, yaml).with_context(|| {
-        format!("Failed to write normalized spec to {}", normalized_spec_path.display())
-    })?;
-
-    // Invoke analysis service (validate-only default backend for now).
-    let backend = backends.get(&backend_name).ok_or_else(|| {
-        anyhow!("Backend '{}' not found (available: {:?})", backend_name, backends.names())
-    })?;
-    let request = AnalysisRequest {
-        ritual_name: as_name.to_string(),
-        binary_name: target_bin.name.clone(),
-        binary_path: binary_path.clone(),
-        roots: spec.roots.clone(),
-        arch: target_bin.arch.clone(),
-        options: AnalysisOptions {
-            max_depth: spec.max_depth,
-            include_imports: true,
-            include_strings: true,
-            max_instructions: Some(1024),
-        },
-        backend_path: backend_path.clone(),
-    };
-    let ctx = ritual_core::db::ProjectContext {
-        layout: layout.clone(),
-        config: config.clone(),
-        db_path: db_path.clone(),
-        db,
-    };
-    let runner = RitualRunner { ctx: &ctx, backend };
-    let run_meta = RunMetadata {
-        spec_hash: spec_hash.clone(),
-        binary_hash: binary_hash.clone(),
-        backend: backend_name.clone(),
-        backend_version: resolve_backend_version(&backend_name, &config),
-        backend_path: backend_path.as_ref().map(|p| p.display().to_string()),
-        status: RitualRunStatus::Stubbed,
+    let cache = (!flags.no_cache).then(|| AnalysisCache::new(layout.cache_dir()));
+    let (analysis_result, cache_hit, run_meta) = match execute_run(
+        &prepared,
+        &backends,
+        &config,
+        cache.as_ref(),
+        backend_override,
+        Some(&print_stage),
+    ) {
+        Ok(run) => run,
+        Err(err) => return Err(failed_run_error(&db, &config, &prepared, err)),
     };
-    let analysis_result = runner.run(&request, &run_meta)?;
-
-    // Write report from analysis result.
-    let backend_version =
-        analysis_result.backend_version.clone().or_else(|| run_meta.backend_version.clone());
-    let backend_path =
-        analysis_result.backend_path.clone().or_else(|| run_meta.backend_path.clone());
-    let backend_label =
-        format_backend_label(&backend_name, backend_version.as_deref(), backend_path.as_deref());
-    let report_path = new_run_root.join("report.json");
-    let report = serde_json::json!({
-        "ritual": as_name,
-        "binary": target_bin.name,
-        "roots": spec.roots,
-        "max_depth": spec.max_depth,
-        "status": run_meta.status.as_str(),
-        "backend": backend_name.clone(),
-        "backend_version": backend_version.clone(),
-        "backend_path": backend_path.clone(),
-        "functions": analysis_result.functions,
-        "edges": analysis_result.call_edges,
-        "basic_blocks": analysis_result.basic_blocks,
-        "evidence": analysis_result.evidence,
-    });
-    fs::write(&report_path, serde_json::to_string_pretty(&report)?)
-        .with_context(|| format!("Failed to write ritual report at {}", report_path.display()))?;
-
-    // Write metadata for rerun.
-    let now = Utc::now().to_rfc3339();
-    let metadata = RitualRunMetadata {
-        ritual: as_name.to_string(),
-        binary: target_bin.name.clone(),
-        spec_hash,
-        binary_hash,
-        backend: backend_name,
-        backend_version,
-        backend_path,
-        started_at: now.clone(),
-        finished_at: now,
-        status: RitualRunStatus::Stubbed,
-    };
-    let metadata_path = new_run_root.join("run_metadata.json");
-    fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)
-        .with_context(|| format!("Failed to write run metadata at {}", metadata_path.display()))?;
-
-    let dot = render_dot(&analysis_result, Some(&backend_label));
-    let dot_path = new_run_root.join("graph.dot");
-    fs::write(&dot_path, dot)
-        .with_context(|| format!("Failed to write ritual graph at {}", dot_path.display()))?;
+    let validation = validate_run(&prepared, &analysis_result);
+    let run_id = persist_run(&db, &prepared.request, &run_meta, &analysis_result)
+        .context("Failed to record ritual run in project DB")?;
+    let baseline = compare_with_baseline(&db, &config, target.id, as_name, run_id)?;
+    write_run_outputs(&prepared, &analysis_result, &run_meta, &validation, baseline.as_ref())?;
+    let hooks = run_post_run_hooks(&config, &prepared, &run_meta.status);
 
     println!("Reran ritual (stub): {} -> {}", ritual, as_name);
-    println!("  Binary: {}", target_bin.name);
+    println!("  Binary: {}", prepared.binary.name);
+    if cache_hit {
+        println!("  Analysis: reused cached result (--no-cache to re-analyze)");
+    }
     println!("  Output: {}", new_run_root.display());
+    if let Some(log_path) = crate::logging::run_log_path(new_run_root) {
+        println!("  Log: {}", log_path.display());
+    }
+    print_validation(&validation);
+    print_baseline(baseline.as_ref());
+    print_hooks(&hooks);
 
-    Ok(())
+    check_validation(flags, &validation, new_run_root)?;
+    check_baseline(baseline.as_ref(), new_run_root)
 }
 
 /// Clean ritual outputs (per binary or per run) with confirmation gating.
@@ -740,7 +556,7 @@ pub fn clean_outputs_command(
     yes: bool,
 ) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     if !yes {
         return Err(anyhow!("Refusing to delete outputs without --yes"));
@@ -781,11 +597,21 @@ pub fn clean_outputs_command(
 }
 
 /// List ritual runs discovered under outputs/binaries (human or JSON).
-pub fn list_ritual_runs_command(root: &str, binary_filter: Option<&str>, json: bool) -> Result<()> {
+///
+/// `tag` keeps only runs carrying that tag.
+pub fn list_ritual_runs_command(
+    root: &str,
+    binary_filter: Option<&str>,
+    json: bool,
+    tag: Option<&str>,
+) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
-    let runs = load_runs_from_db_and_disk(&layout, binary_filter)?;
+    let mut runs = load_runs_from_db_and_disk(&layout, binary_filter)?;
+    if let Some(tag) = tag {
+        runs.retain(|run| run.tags.iter().any(|t| t == tag));
+    }
 
     if json {
         let serialized = serde_json::to_string_pretty(&runs)?;
@@ -799,56 +625,68 @@ pub fn list_ritual_runs_command(root: &str, binary_filter: Option<&str>, json: b
     }
 
     println!("Ritual runs:");
+    let mut table = Table::new(&[
+        "id", "binary", "ritual", "status", "finished", "took", "backend", "funcs", "edges",
+        "roots", "slice", "tags", "path",
+    ]);
     for run in runs {
-        let backend_display = run.backend.as_ref().map(|b| {
-            let version = run.backend_version.as_deref().unwrap_or("(unknown)");
-            if let Some(path) = &run.backend_path {
-                format!(" [backend: {} {} @ {}]", b, version, path)
-            } else {
-                format!(" [backend: {} {}]", b, version)
-            }
-        });
-        let analysis_display = run.analysis.as_ref().map(|a| {
-            format!(
-                " [analysis: funcs={} edges={} bbs={} evidence={} roots={}{}]",
-                a.functions,
-                a.call_edges,
-                a.basic_blocks,
-                a.evidence,
-                a.roots,
-                a.root_coverage
-                    .as_ref()
-                    .map(|c| format!(", matched={}", c.matched.len()))
-                    .unwrap_or_default()
-            )
+        let status = run.status.as_deref().unwrap_or("-");
+        let backend = match (&run.backend, &run.backend_version) {
+            (Some(b), Some(version)) if version != b => format!("{} {}", b, version),
+            (Some(b), _) => b.clone(),
+            (None, _) => "-".into(),
+        };
+        let analysis = run.analysis.as_ref();
+        let count = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+        let roots = analysis.map(|a| match &a.root_coverage {
+            Some(coverage) => format!("{}/{}", coverage.matched.len(), a.roots),
+            None => a.roots.to_string(),
         });
-        let extras = format!(
-            "{}{}",
-            backend_display.unwrap_or_default(),
-            analysis_display.unwrap_or_default()
-        );
-        println!("- {} / {} -> {}{}", run.binary, run.name, run.path, extras);
+        let slice =
+            analysis.and_then(|a| a.coverage.slice_ratio()).map(|r| format!("{:.1}%", r * 100.0));
+        table.row(vec![
+            run.id.map(|id| id.to_string()).unwrap_or_else(|| "-".into()).into(),
+            run.binary.as_str().into(),
+            run.name.as_str().into(),
+            Cell::toned(status, run_status_tone(status)),
+            run.finished_at.as_deref().map(relative_time).unwrap_or_else(|| "-".into()).into(),
+            run.usage.map(|u| duration_ms(u.duration_ms)).unwrap_or_else(|| "-".into()).into(),
+            backend.into(),
+            count(analysis.map(|a| a.functions)).into(),
+            count(analysis.map(|a| a.call_edges)).into(),
+            roots.unwrap_or_else(|| "-".into()).into(),
+            slice.unwrap_or_else(|| "-".into()).into(),
+            tags_cell(&run.tags).into(),
+            display_relative(&root_path, &run.path).into(),
+        ]);
     }
+    table.print();
     Ok(())
 }
 
 /// Show details for a single ritual run.
 pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: bool) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
     let run_root = layout.binary_output_root(binary).join(ritual);
 
-    // Load DB metadata if present.
+    // Load DB metadata of the latest run (the one the output directory holds) if present.
     let db_runs = load_runs_from_db(&layout, Some(binary)).unwrap_or_default();
-    let db_run = db_runs.into_iter().find(|r| r.ritual == ritual);
-    let db_analysis = open_project_db(&layout)
-        .ok()
-        .and_then(|(_cfg, _db_path, db)| db.load_analysis_result(binary, ritual).ok())
-        .flatten();
+    let db_run = db_runs.into_iter().rev().find(|r| r.ritual == ritual);
+    let db = open_project_db(&layout).ok().map(|(_cfg, _db_path, db)| db);
+    let db_analysis =
+        db.as_ref().and_then(|db| db.load_analysis_result(binary, ritual).ok()).flatten();
+    let notes = db
+        .as_ref()
+        .and_then(|db| {
+            let run_id = db.latest_run_id(binary, ritual).ok()??;
+            db.list_run_notes(run_id).ok()
+        })
+        .unwrap_or_default();
 
     // Fallback to on-disk metadata if DB is missing the run.
     let spec_path = run_root.join("spec.yaml");
-    let report_path = run_root.join("report.json");
+    let report_path = run_root.join(REPORT_FILE);
     let metadata_path = run_root.join("run_metadata.json");
     let disk_metadata: Option<RitualRunMetadata> = if metadata_path.exists() {
         Some(
@@ -865,6 +703,11 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
     if db_run.is_none() && !run_root.is_dir() {
         return Err(anyhow!("Ritual run not found in DB or at {}", run_root.display()));
     }
+    let stages = PipelineState::load(&run_root)?.map(|state| state.stages);
+    let coverage = db_analysis.as_ref().map(ritual_core::analysis::coverage::slice_coverage);
+    let run_error = disk_metadata.as_ref().and_then(|meta| meta.error.clone());
+    let registered_hash =
+        disk_metadata.as_ref().and_then(|meta| meta.registered_binary_hash.clone());
 
     if json {
         let payload = if let Some(run) = db_run.clone() {
@@ -876,15 +719,25 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
                 "report": report_path.display().to_string(),
                 "metadata": {
                     "spec_hash": run.spec_hash,
+                    "binary_id": run.binary_id,
                     "binary_hash": run.binary_hash,
+                    "registered_binary_hash": registered_hash,
                     "backend": run.backend,
                     "backend_version": run.backend_version,
                     "backend_path": run.backend_path,
+                    "spec_path": run.spec_path,
                     "status": run.status.as_str(),
                     "started_at": run.started_at,
                     "finished_at": run.finished_at,
+                    "deterministic": run.deterministic,
+                    "analysis_options": run.analysis_options,
+                    "usage": run.usage,
+                    "error": run_error,
                 },
                 "analysis": db_analysis,
+                "coverage": coverage,
+                "stages": stages,
+                "notes": notes,
             })
         } else {
             serde_json::json!({
@@ -895,6 +748,9 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
                 "report": report_path.display().to_string(),
                 "metadata": disk_metadata,
                 "analysis": db_analysis,
+                "coverage": coverage,
+                "stages": stages,
+                "notes": notes,
             })
         };
         println!("{}", serde_json::to_string_pretty(&payload)?);
@@ -910,10 +766,19 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
     match (db_run, disk_metadata) {
         (Some(run), _) => {
             println!("  Status: {}", run.status.as_str());
+            if let Some(id) = run.binary_id {
+                println!("  Binary id: {}", id);
+            }
             if let Some(bh) = run.binary_hash {
                 println!("  Binary hash: {}", bh);
             }
+            if let Some(hash) = &registered_hash {
+                println!("  Registered hash: {} (binary changed since registration)", hash);
+            }
             println!("  Spec hash: {}", run.spec_hash);
+            if let Some(path) = &run.spec_path {
+                println!("  Spec file: {}", path);
+            }
             println!("  Backend: {}", run.backend);
             if let Some(ver) = run.backend_version {
                 println!("  Backend version: {}", ver);
@@ -923,13 +788,31 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
             }
             println!("  Started:  {}", run.started_at);
             println!("  Finished: {}", run.finished_at);
+            if let Some(usage) = &run.usage {
+                println!("  Duration: {}", format_usage(usage));
+            }
+            if run.deterministic {
+                println!("  Deterministic: yes");
+            }
+            if let Some(options) = &run.analysis_options {
+                println!("  Analysis options: {}", format_analysis_options(options));
+            }
         }
         (None, Some(meta)) => {
             println!("  Status: {}", meta.status.as_str());
+            if let Some(id) = meta.binary_id {
+                println!("  Binary id: {}", id);
+            }
             if let Some(bh) = meta.binary_hash {
                 println!("  Binary hash: {}", bh);
             }
+            if let Some(hash) = &registered_hash {
+                println!("  Registered hash: {} (binary changed since registration)", hash);
+            }
             println!("  Spec hash: {}", meta.spec_hash);
+            if let Some(path) = &meta.spec_path {
+                println!("  Spec file: {}", path);
+            }
             println!("  Backend: {}", meta.backend);
             if let Some(ver) = &meta.backend_version {
                 println!("  Backend version: {}", ver);
@@ -939,15 +822,46 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
             }
             println!("  Started:  {}", meta.started_at);
             println!("  Finished: {}", meta.finished_at);
+            if meta.deterministic {
+                println!("  Deterministic: yes");
+            }
         }
         _ => println!("  (No run metadata found in DB or disk)"),
     }
+    if let Some(error) = &run_error {
+        println!("  Error: {}", error);
+    }
+    if !notes.is_empty() {
+        println!("  Notes:");
+        for note in &notes {
+            println!("    [{}] {}", note.created_at, note.note);
+        }
+    }
+    if let Some(stages) = &stages {
+        println!("  Stages:");
+        for stage in stages {
+            let mut line = format!("    {} [{}]", stage.name, stage.status.as_str());
+            if let Some(backend) = &stage.backend {
+                line.push_str(&format!(" backend={}", backend));
+            }
+            if let Some(functions) = stage.functions {
+                line.push_str(&format!(" functions={}", functions));
+            }
+            if let Some(error) = &stage.error {
+                line.push_str(&format!(" error: {}", error));
+            }
+            println!("{}", line);
+        }
+    }
     if let Some(analysis) = db_analysis {
         println!("  Analysis:");
         println!("    Functions: {}", analysis.functions.len());
         println!("    Call edges: {}", analysis.call_edges.len());
         println!("    Basic blocks: {}", analysis.basic_blocks.len());
         println!("    Evidence: {}", analysis.evidence.len());
+        if let Some(coverage) = &coverage {
+            println!("    Coverage: {}", coverage.describe());
+        }
         if !analysis.roots.is_empty() {
             println!("    Roots: {:?}", analysis.roots);
         }
@@ -961,11 +875,23 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
                 println!("    Unmatched roots: {:?}", coverage.unmatched);
             }
         }
+        if !analysis.sub_slices.is_empty() {
+            println!("    Sub-slices:");
+            for sub in &analysis.sub_slices {
+                println!(
+                    "      {} (roots: {}) -> {} function(s)",
+                    sub.label,
+                    sub.roots.join(", "),
+                    sub.functions.len()
+                );
+            }
+        }
         if !analysis.root_hits.is_empty() {
             println!("    Root hits:");
             for hit in &analysis.root_hits {
                 if hit.functions.is_empty() {
-                    println!("      {} -> (no matched functions)", hit.root);
+                    let reason = hit.error.as_deref().unwrap_or("no matched functions");
+                    println!("      {} -> ({})", hit.root, reason);
                 } else {
                     let labels: Vec<String> =
                         hit.functions.iter().map(|addr| format!("0x{addr:X}")).collect();
@@ -996,10 +922,345 @@ pub fn show_ritual_run_command(root: &str, binary: &str, ritual: &str, json: boo
     Ok(())
 }
 
+/// Check a run's output files against its `manifest.json`, and the manifest against the
+/// run recorded in the project DB.
+pub fn verify_run_command(root: &str, binary: &str, ritual: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let run_dir = layout.binary_output_root(binary).join(ritual);
+    if !run_dir.is_dir() {
+        return Err(anyhow!("Ritual run outputs not found at {}", run_dir.display()));
+    }
+    let manifest = OutputManifest::load(&run_dir)?;
+    let check = manifest.verify(&run_dir, UNTRACKED_OUTPUTS)?;
+
+    // A manifest from another run (e.g. outputs left behind by a crashed re-run) no longer
+    // matches the run the DB recorded last.
+    let db_run = load_runs_from_db(&layout, Some(binary))
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .find(|r| r.ritual == ritual);
+    let mut run_mismatches = Vec::new();
+    if let Some(run) = &db_run {
+        if run.spec_hash != manifest.spec_hash {
+            run_mismatches.push(format!(
+                "spec hash {} differs from the DB run's {}",
+                manifest.spec_hash, run.spec_hash
+            ));
+        }
+        if run.binary_hash.is_some() && run.binary_hash != manifest.binary_hash {
+            run_mismatches.push(format!(
+                "binary hash {} differs from the DB run's {}",
+                manifest.binary_hash.as_deref().unwrap_or("-"),
+                run.binary_hash.as_deref().unwrap_or("-")
+            ));
+        }
+    }
+    let ok = check.is_ok() && run_mismatches.is_empty();
+
+    if json {
+        let value = serde_json::json!({
+            "binary": binary,
+            "ritual": ritual,
+            "run_dir": run_dir.display().to_string(),
+            "created_at": manifest.created_at,
+            "ok": ok,
+            "verified": check.verified,
+            "mismatched": check.mismatched,
+            "missing": check.missing,
+            "unlisted": check.unlisted,
+            "run_mismatches": run_mismatches,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("Run outputs: {}", run_dir.display());
+        println!("  Manifest written: {}", manifest.created_at);
+        println!("  Verified: {} of {} file(s)", check.verified, manifest.files.len());
+        for m in &check.mismatched {
+            println!(
+                "  Modified: {} (sha256 {} -> {}, {} -> {} bytes)",
+                m.path, m.expected.sha256, m.actual.sha256, m.expected.size, m.actual.size
+            );
+        }
+        for path in &check.missing {
+            println!("  Missing: {}", path);
+        }
+        for path in &check.unlisted {
+            println!("  Not in manifest: {}", path);
+        }
+        for mismatch in &run_mismatches {
+            println!("  Manifest {}", mismatch);
+        }
+    }
+    if !ok {
+        return Err(anyhow!(
+            "Run outputs for {}/{} failed verification ({} modified, {} missing, {} unlisted file(s){})",
+            binary,
+            ritual,
+            check.mismatched.len(),
+            check.missing.len(),
+            check.unlisted.len(),
+            if run_mismatches.is_empty() { "" } else { "; manifest does not match the DB run" }
+        ));
+    }
+    Ok(())
+}
+
+/// Compare the persisted analysis of two ritual runs (functions, call edges, evidence).
+///
+/// `against_binary` defaults to `binary`; pass it to diff the same ritual across two builds.
+pub fn diff_runs_command(
+    root: &str,
+    binary: &str,
+    ritual: &str,
+    against: &str,
+    against_binary: Option<&str>,
+    assert_reproducible: bool,
+    json: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = open_project_db(&layout)?;
+    let against_binary = against_binary.unwrap_or(binary);
+
+    let load = |bin: &str, run: &str| -> Result<AnalysisResult> {
+        db.load_analysis_result(bin, run)
+            .with_context(|| format!("Failed to load analysis for {}/{}", bin, run))?
+            .ok_or_else(|| anyhow!("No persisted analysis for ritual run {}/{}", bin, run))
+    };
+    let base = load(binary, ritual)?;
+    let other = load(against_binary, against)?;
+    let diff = diff_analysis(&base, &other);
+
+    let latest_run = |bin: &str, run: &str| -> Result<Option<ritual_core::db::RitualRunRecord>> {
+        let runs = db.list_ritual_runs(Some(bin)).context("Failed to list ritual runs")?;
+        Ok(runs.into_iter().rfind(|r| r.ritual == run))
+    };
+    let base_run = latest_run(binary, ritual)?;
+    let other_run = latest_run(against_binary, against)?;
+    let problems = reproducibility_problems(base_run.as_ref(), other_run.as_ref(), diff.is_empty());
+    let reproducible = problems.is_empty();
+    let deterministic = |run: &Option<ritual_core::db::RitualRunRecord>| {
+        run.as_ref().is_some_and(|r| r.deterministic)
+    };
+
+    if json {
+        let payload = serde_json::json!({
+            "base": {
+                "binary": binary,
+                "ritual": ritual,
+                "deterministic": deterministic(&base_run),
+            },
+            "against": {
+                "binary": against_binary,
+                "ritual": against,
+                "deterministic": deterministic(&other_run),
+            },
+            "identical": diff.is_empty(),
+            "reproducible": reproducible,
+            "diff": diff,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        print_run_diff(binary, ritual, against_binary, against, &diff);
+        if assert_reproducible && reproducible {
+            println!("  Reproducible: yes");
+        }
+    }
+    if assert_reproducible && !reproducible {
+        return Err(anyhow!(
+            "Runs {}/{} and {}/{} are not reproducible: {}",
+            binary,
+            ritual,
+            against_binary,
+            against,
+            problems.join("; ")
+        ));
+    }
+    Ok(())
+}
+
+/// Why two runs do not reproduce each other (empty when they do): both must be deterministic,
+/// come from the same spec and binary, and produce identical analysis.
+fn reproducibility_problems(
+    base: Option<&ritual_core::db::RitualRunRecord>,
+    other: Option<&ritual_core::db::RitualRunRecord>,
+    identical: bool,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for run in [base, other].into_iter().flatten() {
+        if !run.deterministic {
+            problems
+                .push(format!("{}/{} was not run with --deterministic", run.binary, run.ritual));
+        }
+    }
+    if let (Some(base), Some(other)) = (base, other) {
+        if base.spec_hash != other.spec_hash {
+            problems.push("spec hashes differ".to_string());
+        }
+        if base.binary_hash != other.binary_hash {
+            problems.push("binary hashes differ".to_string());
+        }
+    } else {
+        problems.push("run metadata missing from the project DB".to_string());
+    }
+    if !identical {
+        problems.push("analysis results differ".to_string());
+    }
+    problems
+}
+
+fn print_run_diff(
+    binary: &str,
+    ritual: &str,
+    against_binary: &str,
+    against: &str,
+    diff: &AnalysisDiff,
+) {
+    println!("Diff {}/{} -> {}/{}", binary, ritual, against_binary, against);
+    if diff.is_empty() {
+        println!("  No differences.");
+        return;
+    }
+    let label = |name: &Option<String>, addr: u64| match name {
+        Some(n) => format!("{} @ 0x{:X}", n, addr),
+        None => format!("0x{:X}", addr),
+    };
+    let f = &diff.functions;
+    println!(
+        "  Functions: +{} -{} renamed={} moved={}",
+        f.added.len(),
+        f.removed.len(),
+        f.renamed.len(),
+        f.moved.len()
+    );
+    for func in &f.added {
+        println!("    + {}", label(&func.name, func.address));
+    }
+    for func in &f.removed {
+        println!("    - {}", label(&func.name, func.address));
+    }
+    for r in &f.renamed {
+        println!(
+            "    ~ 0x{:X}: {} -> {}",
+            r.address,
+            r.from.as_deref().unwrap_or("(unnamed)"),
+            r.to.as_deref().unwrap_or("(unnamed)")
+        );
+    }
+    for m in &f.moved {
+        println!("    > {}: 0x{:X} -> 0x{:X}", m.name, m.from, m.to);
+    }
+    let e = &diff.call_edges;
+    println!("  Call edges: +{} -{}", e.added.len(), e.removed.len());
+    for edge in &e.added {
+        println!(
+            "    + {} -> {}",
+            label(&edge.from_name, edge.from),
+            label(&edge.to_name, edge.to)
+        );
+    }
+    for edge in &e.removed {
+        println!(
+            "    - {} -> {}",
+            label(&edge.from_name, edge.from),
+            label(&edge.to_name, edge.to)
+        );
+    }
+    let ev = &diff.evidence;
+    println!("  Evidence: +{} -{}", ev.added.len(), ev.removed.len());
+    for item in &ev.added {
+        println!("    + {}", item.description);
+    }
+    for item in &ev.removed {
+        println!("    - {}", item.description);
+    }
+}
+
+/// Pair the functions of two ritual runs by fingerprint similarity, so functions are
+/// followed across builds whose addresses (and symbols) changed.
+pub fn match_functions_command(
+    root: &str,
+    binary: &str,
+    from: &str,
+    to: &str,
+    to_binary: Option<&str>,
+    min_similarity: f64,
+    json: bool,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&min_similarity) {
+        return Err(anyhow!("--min-similarity must be between 0 and 1, got {}", min_similarity));
+    }
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = open_project_db(&layout)?;
+    let to_binary = to_binary.unwrap_or(binary);
+
+    // Evidence and xrefs play no part in matching, so only the skeleton is loaded.
+    let load = |bin: &str, run: &str| -> Result<AnalysisResult> {
+        db.load_analysis_skeleton(bin, run)
+            .with_context(|| format!("Failed to load analysis for {}/{}", bin, run))?
+            .map(|(_, result)| result)
+            .ok_or_else(|| anyhow!("No persisted analysis for ritual run {}/{}", bin, run))
+    };
+    let base = load(binary, from)?;
+    let other = load(to_binary, to)?;
+    let matches = match_functions(&base.functions, &other.functions, min_similarity);
+
+    if json {
+        let payload = serde_json::json!({
+            "from": { "binary": binary, "ritual": from },
+            "to": { "binary": to_binary, "ritual": to },
+            "min_similarity": min_similarity,
+            "matches": matches,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!("Match {}/{} -> {}/{}", binary, from, to_binary, to);
+    let label = |name: &Option<String>, addr: u64| match name {
+        Some(n) => format!("{} @ 0x{:X}", n, addr),
+        None => format!("0x{:X}", addr),
+    };
+    println!("  Matched: {}", matches.matched.len());
+    for m in &matches.matched {
+        println!(
+            "    {} -> {} ({:.2})",
+            label(&m.from_name, m.from),
+            label(&m.to_name, m.to),
+            m.similarity
+        );
+    }
+    println!("  Unmatched: -{} +{}", matches.unmatched_from.len(), matches.unmatched_to.len());
+    for func in &matches.unmatched_from {
+        println!("    - {}", label(&func.name, func.address));
+    }
+    for func in &matches.unmatched_to {
+        println!("    + {}", label(&func.name, func.address));
+    }
+    if matches.unhashed_from + matches.unhashed_to > 0 {
+        println!(
+            "  Without fingerprints (skipped): {} / {} (re-run with a fingerprinting backend)",
+            matches.unhashed_from, matches.unhashed_to
+        );
+    }
+    Ok(())
+}
+
+/// `path` relative to the project root for human listings, or as given outside it.
+fn display_relative(root: &Path, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(root)
+        .map(|rel| rel.display().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
 /// List ritual specs under rituals/ (yaml/yml/json).
 pub fn list_ritual_specs_command(root: &str, json: bool) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
     let dir = &layout.rituals_dir;
     if !dir.exists() {
         println!("Rituals dir missing at {}", dir.display());
@@ -1019,45 +1280,73 @@ pub fn list_ritual_specs_command(root: &str, json: bool) -> Result<()> {
     }
 
     println!("Ritual specs:");
+    let mut table = Table::new(&["name", "binary", "format", "path"]);
     for spec in specs {
-        let binary_display = spec.binary.as_deref().unwrap_or("(unspecified)");
-        println!(
-            "- {} (binary: {}, format: {}, path: {})",
-            spec.name, binary_display, spec.format, spec.path
-        );
+        table.row(vec![
+            spec.name.as_str().into(),
+            spec.binary.as_deref().unwrap_or("(unspecified)").into(),
+            spec.format.as_str().into(),
+            display_relative(&root_path, &spec.path).into(),
+        ]);
     }
+    table.print();
     Ok(())
 }
 
+/// Runs `update-ritual-run-status` changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunTarget {
+    /// One run by row id.
+    Id(i64),
+    /// The latest run of a binary and ritual, or with `all_runs` every run under the names
+    /// (earlier runs of a ritual that was run again, or rerun under an old name).
+    Named { binary: String, ritual: String, all_runs: bool },
+}
+
 /// Update status of a ritual run in the DB.
 pub fn update_ritual_run_status_command(
     root: &str,
-    binary: &str,
-    ritual: &str,
+    target: &RunTarget,
     status: &str,
     finished_at: Option<String>,
 ) -> Result<()> {
     let status_enum = validate_run_status(status)?;
 
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     let (_config, _db_path, db) = open_project_db(&layout)?;
     let finished_val = finished_at.unwrap_or_else(|| Utc::now().to_rfc3339());
 
-    let updated = db
-        .update_ritual_run_status(binary, ritual, status_enum.as_str(), Some(&finished_val))
-        .context("Failed to update ritual run status")?;
+    let status_str = status_enum.as_str();
+    let updated = match target {
+        RunTarget::Id(run_id) => db.update_run_status(*run_id, status_str, Some(&finished_val)),
+        RunTarget::Named { binary, ritual, all_runs: false } => {
+            db.update_ritual_run_status(binary, ritual, status_str, Some(&finished_val))
+        }
+        RunTarget::Named { binary, ritual, all_runs: true } => {
+            db.update_all_ritual_run_statuses(binary, ritual, status_str, Some(&finished_val))
+        }
+    }
+    .context("Failed to update ritual run status")?;
     if updated == 0 {
-        return Err(anyhow!("No ritual run found for binary '{}' and ritual '{}'", binary, ritual));
+        return Err(match target {
+            RunTarget::Id(run_id) => anyhow!("No ritual run with id {}", run_id),
+            RunTarget::Named { binary, ritual, .. } => {
+                anyhow!("No ritual run found for binary '{}' and ritual '{}'", binary, ritual)
+            }
+        });
     }
 
+    let target_display = match target {
+        RunTarget::Id(run_id) => format!("run={}", run_id),
+        RunTarget::Named { binary, ritual, .. } => {
+            format!("binary='{}' ritual='{}' runs={}", binary, ritual, updated)
+        }
+    };
     println!(
-        "Updated ritual run status: binary='{}' ritual='{}' status='{}' finished_at='{}'",
-        binary,
-        ritual,
-        status_enum.as_str(),
-        finished_val
+        "Updated ritual run status: {} status='{}' finished_at='{}'",
+        target_display, status_str, finished_val
     );
 
     Ok(())
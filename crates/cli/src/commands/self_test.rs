@@ -0,0 +1,219 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ritual_core::analysis::graph::dot_body;
+use ritual_core::db::RitualRunStatus;
+use ritual_core::services::analysis::{
+    default_backend_registry, validate_result, AnalysisBackend, AnalysisOptions, AnalysisRequest,
+    AnalysisResult,
+};
+use ritual_core::services::fixtures::{check_invariants, synthetic_elf_x86_64, SyntheticBinary};
+use ritual_core::services::report::ReportV1;
+use serde::Serialize;
+
+/// Outcome of running one backend against the synthetic binary.
+#[derive(Debug, Serialize)]
+pub struct SelfTestRow {
+    pub backend: String,
+    pub passed: bool,
+    pub functions: usize,
+    pub call_edges: usize,
+    pub basic_blocks: usize,
+    pub duration_ms: u128,
+    /// Directory holding this backend's report.json/graph.dot, when the run produced a result.
+    pub artifacts: Option<String>,
+    pub failures: Vec<String>,
+}
+
+/// Full self-test report (the `--json` payload).
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    pub version: String,
+    pub artifacts_dir: String,
+    pub binary: String,
+    pub passed: bool,
+    pub backends: Vec<SelfTestRow>,
+}
+
+/// Generate a synthetic binary, run compiled backends against it, and print a pass/fail matrix.
+///
+/// The `ghidra-xml` backend reads a Ghidra XML export of the binary written next to it.
+///
+/// Artifacts are kept under `out` (default: a fresh temp directory) so failures can be
+/// inspected. Returns an error when any backend fails so the command is usable as a
+/// post-install check.
+pub fn self_test_command(backend: Option<&str>, out: Option<&str>, json: bool) -> Result<()> {
+    let out_dir = match out {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::temp_dir().join(format!(
+            "binary-slicer-self-test-{}-{}",
+            std::process::id(),
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default()
+        )),
+    };
+    let report = run_self_test(backend, &out_dir)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_matrix(&report);
+    }
+
+    if !report.passed {
+        let failed: Vec<&str> =
+            report.backends.iter().filter(|r| !r.passed).map(|r| r.backend.as_str()).collect();
+        bail!("Self-test failed for backend(s): {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Run the self-test into `out_dir` without printing; used by the command and by tests.
+pub fn run_self_test(backend: Option<&str>, out_dir: &Path) -> Result<SelfTestReport> {
+    let registry = default_backend_registry();
+    let names = match backend {
+        Some(name) => {
+            if registry.get(name).is_none() {
+                return Err(anyhow!(
+                    "Unknown backend '{}'. Available: {}",
+                    name,
+                    registry.names().join(", ")
+                ));
+            }
+            vec![name.to_string()]
+        }
+        None => registry.names(),
+    };
+
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    let binary = synthetic_elf_x86_64();
+    let binary_path = out_dir.join(binary.file_name);
+    fs::write(&binary_path, &binary.bytes)
+        .with_context(|| format!("Failed to write {}", binary_path.display()))?;
+
+    let mut rows = Vec::new();
+    for name in names {
+        let Some(backend) = registry.get(&name) else { continue };
+        rows.push(run_backend(backend, &name, &binary, &binary_path, out_dir)?);
+    }
+
+    Ok(SelfTestReport {
+        version: ritual_core::version().to_string(),
+        artifacts_dir: out_dir.display().to_string(),
+        binary: binary_path.display().to_string(),
+        passed: rows.iter().all(|r| r.passed),
+        backends: rows,
+    })
+}
+
+fn run_backend(
+    backend: &dyn AnalysisBackend,
+    name: &str,
+    binary: &SyntheticBinary,
+    binary_path: &Path,
+    out_dir: &Path,
+) -> Result<SelfTestRow> {
+    let backend_path = if name == "ghidra-xml" {
+        let export = out_dir.join(format!("{}.xml", binary.file_name));
+        fs::write(&export, binary.ghidra_xml())
+            .with_context(|| format!("Failed to write {}", export.display()))?;
+        Some(export)
+    } else {
+        None
+    };
+    let request = AnalysisRequest {
+        ritual_name: "SelfTest".into(),
+        binary_name: binary.file_name.into(),
+        binary_path: binary_path.to_path_buf(),
+        roots: binary.roots(),
+        arch: Some(binary.arch.into()),
+        options: AnalysisOptions::default(),
+        backend_path,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    };
+
+    let started = Instant::now();
+    let outcome = backend.analyze(&request);
+    let duration_ms = started.elapsed().as_millis();
+    let result = match outcome {
+        Ok(result) => result,
+        Err(err) => {
+            return Ok(SelfTestRow {
+                backend: name.to_string(),
+                passed: false,
+                functions: 0,
+                call_edges: 0,
+                basic_blocks: 0,
+                duration_ms,
+                artifacts: None,
+                failures: vec![err.to_string()],
+            })
+        }
+    };
+
+    let failures = check_invariants(binary, &result, name != "validate-only");
+    let artifacts = write_artifacts(&out_dir.join(name), &request, name, &result)?;
+    Ok(SelfTestRow {
+        backend: name.to_string(),
+        passed: failures.is_empty(),
+        functions: result.functions.len(),
+        call_edges: result.call_edges.len(),
+        basic_blocks: result.basic_blocks.len(),
+        duration_ms,
+        artifacts: Some(artifacts.display().to_string()),
+        failures,
+    })
+}
+
+fn write_artifacts(
+    dir: &Path,
+    request: &AnalysisRequest,
+    backend: &str,
+    result: &AnalysisResult,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut report = ReportV1::new(
+        &request.ritual_name,
+        &request.binary_name,
+        backend,
+        RitualRunStatus::Succeeded,
+        result,
+    );
+    report.validation = validate_result(result, None);
+    report.write(dir)?;
+    let dot = format!("digraph SelfTest {{\n  rankdir=LR;\n{}}}\n", dot_body(result));
+    fs::write(dir.join("graph.dot"), dot)
+        .with_context(|| format!("Failed to write graph in {}", dir.display()))?;
+    Ok(dir.to_path_buf())
+}
+
+fn print_matrix(report: &SelfTestReport) {
+    println!("binary-slicer {} self-test", report.version);
+    println!("Artifacts: {}", report.artifacts_dir);
+    let width = report.backends.iter().map(|r| r.backend.len()).max().unwrap_or(0).max(7);
+    println!(
+        "{:<width$}  {:<6}  {:>5}  {:>5}  {:>6}  {:>8}",
+        "BACKEND", "STATUS", "FUNCS", "EDGES", "BLOCKS", "TIME",
+    );
+    for row in &report.backends {
+        println!(
+            "{:<width$}  {:<6}  {:>5}  {:>5}  {:>6}  {:>6}ms",
+            row.backend,
+            if row.passed { "pass" } else { "FAIL" },
+            row.functions,
+            row.call_edges,
+            row.basic_blocks,
+            row.duration_ms,
+        );
+        for failure in &row.failures {
+            println!("{:<width$}    - {}", "", failure);
+        }
+    }
+    println!("Result: {}", if report.passed { "PASS" } else { "FAIL" });
+}
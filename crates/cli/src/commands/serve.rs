@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use ritual_core::rituals::run::{
+    check_baseline, check_validation, run_ritual_spec, RunBinary, RunFlags, SpecSource,
+};
+use ritual_core::services::analysis::AnalysisResult;
+use ritual_core::services::query::{execute_query, parse_query};
+use serde_json::{json, Value};
+
+use crate::canonicalize_or_current;
+use crate::commands::annotations::parse_address;
+use crate::commands::{lock_project, open_project_db, BinarySelector};
+
+/// Methods answered by [`Server`], reported by `initialize`.
+pub const SERVE_METHODS: &[&str] = &[
+    "initialize",
+    "list_binaries",
+    "list_slices",
+    "list_runs",
+    "function_evidence",
+    "xrefs",
+    "query",
+    "run_ritual",
+    "shutdown",
+    "exit",
+];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+const EVIDENCE_PAGE_SIZE: usize = 1000;
+
+/// A JSON-RPC failure: code plus message.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn params(message: impl Into<String>) -> Self {
+        Self { code: INVALID_PARAMS, message: message.into() }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(err: anyhow::Error) -> Self {
+        Self { code: SERVER_ERROR, message: format!("{:#}", err) }
+    }
+}
+
+impl From<ritual_core::db::DbError> for RpcError {
+    fn from(err: ritual_core::db::DbError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+type RpcResult = std::result::Result<Value, RpcError>;
+
+/// JSON-RPC 2.0 request handler over one open project DB.
+///
+/// Every method is a thin wrapper over the same core services the CLI commands use, so a
+/// long-running client sees exactly what `list-slices`, `query`, `run-ritual`, etc. would.
+pub struct Server {
+    layout: ProjectLayout,
+    db: ProjectDb,
+    shutdown: bool,
+    exit: bool,
+}
+
+impl Server {
+    /// Open the project at `root` and keep its DB open for the server's lifetime.
+    pub fn open(root: &str) -> Result<Self> {
+        let root_path = canonicalize_or_current(root)?;
+        let layout = ritual_core::db::load_project_layout(&root_path);
+        let (_config, _db_path, db) = open_project_db(&layout)?;
+        Ok(Self { layout, db, shutdown: false, exit: false })
+    }
+
+    /// Whether an `exit` notification has been received.
+    pub fn exited(&self) -> bool {
+        self.exit
+    }
+
+    /// Handle one raw message; `None` for notifications (requests without an `id`).
+    pub fn handle_text(&mut self, text: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(text) {
+            Ok(message) => self.handle(&message),
+            Err(err) => Some(error_response(Value::Null, PARSE_ERROR, &err.to_string())),
+        }
+    }
+
+    /// Handle one parsed message; `None` for notifications (requests without an `id`).
+    pub fn handle(&mut self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Request must be an object with a string 'method'",
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let outcome = self.dispatch(method, &params);
+        let id = id?;
+        Some(match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, err.code, &err.message),
+        })
+    }
+
+    fn dispatch(&mut self, method: &str, params: &Value) -> RpcResult {
+        if self.shutdown && method != "exit" {
+            return Err(RpcError {
+                code: INVALID_REQUEST,
+                message: "Server is shutting down".into(),
+            });
+        }
+        match method {
+            "initialize" => Ok(json!({
+                "name": "binary-slicer",
+                "version": env!("CARGO_PKG_VERSION"),
+                "root": self.layout.root.display().to_string(),
+                "methods": SERVE_METHODS,
+            })),
+            "list_binaries" => self.list_binaries(),
+            "list_slices" => Ok(serde_json::to_value(self.db.list_slices()?).unwrap_or_default()),
+            "list_runs" => {
+                let binary = opt_str(params, "binary")?;
+                Ok(serde_json::to_value(self.db.list_ritual_runs(binary)?).unwrap_or_default())
+            }
+            "function_evidence" => self.function_evidence(params),
+            "xrefs" => self.xrefs(params),
+            "query" => self.query(params),
+            "run_ritual" => self.run_ritual(params),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "exit" => {
+                self.exit = true;
+                Ok(Value::Null)
+            }
+            other => Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unknown method '{}'", other),
+            }),
+        }
+    }
+
+    fn list_binaries(&self) -> RpcResult {
+        let binaries = self.db.list_binaries_with_ids()?;
+        Ok(Value::Array(
+            binaries
+                .into_iter()
+                .map(|(id, b)| {
+                    json!({ "id": id, "name": b.name, "path": b.path, "arch": b.arch, "hash": b.hash })
+                })
+                .collect(),
+        ))
+    }
+
+    /// Latest run of `binary`/`ritual` without evidence or xrefs.
+    fn skeleton(&self, params: &Value) -> std::result::Result<(i64, AnalysisResult), RpcError> {
+        let binary = req_str(params, "binary")?;
+        let ritual = req_str(params, "ritual")?;
+        let binaries = self.db.list_binaries_with_ids()?;
+        let target = RunBinary::new(&self.db, BinarySelector::from(binary).resolve(&binaries)?)?;
+        self.db.load_analysis_skeleton(&target.run_name, ritual)?.ok_or_else(|| {
+            anyhow!("No analysis found for binary '{}' / ritual '{}'", target.binary.name, ritual)
+                .into()
+        })
+    }
+
+    fn function_evidence(&self, params: &Value) -> RpcResult {
+        let function = req_str(params, "function")?;
+        let (run_id, result) = self.skeleton(params)?;
+        let record = match parse_address(function) {
+            Ok(address) => result.functions.iter().find(|f| f.address == address),
+            Err(_) => result.functions.iter().find(|f| f.name.as_deref() == Some(function)),
+        }
+        .ok_or_else(|| RpcError::params(format!("Unknown function '{}'", function)))?;
+
+        let start = record.address;
+        let end = start.saturating_add(u64::from(record.size.unwrap_or(1).max(1)));
+        let mut evidence = Vec::new();
+        for page in self.db.evidence_pages_in_range(run_id, start, end, EVIDENCE_PAGE_SIZE) {
+            evidence.extend(page?);
+        }
+        Ok(json!({ "run_id": run_id, "function": record, "evidence": evidence }))
+    }
+
+    fn xrefs(&self, params: &Value) -> RpcResult {
+        let from = opt_address(params, "from")?;
+        let to = opt_address(params, "to")?;
+        let (run_id, _) = self.skeleton(params)?;
+        let xrefs: Vec<_> = self
+            .db
+            .list_xrefs(run_id)?
+            .into_iter()
+            .filter(|x| from.is_none_or(|a| x.from == a) && to.is_none_or(|a| x.to == a))
+            .collect();
+        Ok(json!({ "run_id": run_id, "xrefs": xrefs }))
+    }
+
+    fn query(&self, params: &Value) -> RpcResult {
+        let text = req_str(params, "query")?;
+        let parsed = parse_query(text)
+            .map_err(|err| RpcError::params(format!("Invalid query '{}': {}", text, err)))?;
+        let binary = req_str(params, "binary")?;
+        let ritual = req_str(params, "ritual")?;
+        let binaries = self.db.list_binaries_with_ids()?;
+        let target = RunBinary::new(&self.db, BinarySelector::from(binary).resolve(&binaries)?)?;
+        let result = self.db.load_analysis_result(&target.run_name, ritual)?.ok_or_else(|| {
+            anyhow!("No analysis found for binary '{}' / ritual '{}'", target.binary.name, ritual)
+        })?;
+        Ok(execute_query(&parsed, &result).to_json())
+    }
+
+    fn run_ritual(&self, params: &Value) -> RpcResult {
+        let file = req_str(params, "file")?;
+        let backend = opt_str(params, "backend")?;
+        let flags = RunFlags {
+            force: opt_bool(params, "force")?,
+            no_cache: opt_bool(params, "no_cache")?,
+            deterministic: opt_bool(params, "deterministic")?,
+            timeout: opt_u64(params, "timeout")?,
+            strict: opt_bool(params, "strict")?,
+            allow_hash_mismatch: opt_bool(params, "allow_hash_mismatch")?,
+        };
+        let seed_strings: Vec<String> = match params.get("seed_strings") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|_| RpcError::params("'seed_strings' must be an array of strings"))?,
+        };
+        // Spec variables as an object of names to string values.
+        let variables: Vec<String> = match params.get("variables") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(value) => serde_json::from_value::<BTreeMap<String, String>>(value.clone())
+                .map_err(|_| RpcError::params("'variables' must be an object of strings"))?
+                .into_iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect(),
+        };
+        let _lock = lock_project(&self.layout, "binary-slicer serve", true)?;
+        let source = SpecSource::File(file);
+        let outcome =
+            run_ritual_spec(&self.layout, source, backend, flags, &seed_strings, &variables, None)?;
+        check_validation(flags, &outcome.validation, &outcome.run_dir)?;
+        check_baseline(outcome.baseline.as_ref(), &outcome.run_dir)?;
+        Ok(json!({
+            "ritual": outcome.ritual,
+            "binary": outcome.binary,
+            "binary_id": outcome.binary_id,
+            "roots": outcome.roots,
+            "cache_hit": outcome.cache_hit,
+            "run_dir": outcome.run_dir.display().to_string(),
+            "functions": outcome.result.functions.len(),
+            "call_edges": outcome.result.call_edges.len(),
+            "evidence": outcome.result.evidence.len(),
+            "hooks": outcome.hooks,
+            "validation": outcome.validation,
+            "baseline": outcome.baseline,
+        }))
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn opt_str<'a>(params: &'a Value, key: &str) -> std::result::Result<Option<&'a str>, RpcError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(RpcError::params(format!("'{}' must be a string", key))),
+    }
+}
+
+fn req_str<'a>(params: &'a Value, key: &str) -> std::result::Result<&'a str, RpcError> {
+    opt_str(params, key)?.ok_or_else(|| RpcError::params(format!("Missing '{}'", key)))
+}
+
+fn opt_bool(params: &Value, key: &str) -> std::result::Result<bool, RpcError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(_) => Err(RpcError::params(format!("'{}' must be a boolean", key))),
+    }
+}
+
+fn opt_u64(params: &Value, key: &str) -> std::result::Result<Option<u64>, RpcError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| RpcError::params(format!("'{}' must be a non-negative integer", key))),
+    }
+}
+
+fn opt_address(params: &Value, key: &str) -> std::result::Result<Option<u64>, RpcError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => n
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| RpcError::params(format!("'{}' must be an address", key))),
+        Some(Value::String(s)) => parse_address(s)
+            .map(Some)
+            .map_err(|_| RpcError::params(format!("'{}' must be an address", key))),
+        Some(_) => Err(RpcError::params(format!("'{}' must be an address", key))),
+    }
+}
+
+/// Serve requests from `input` until EOF or `exit`, writing responses to `output`.
+///
+/// Messages are either one JSON object per line, or LSP-style `Content-Length:` framed;
+/// each response uses the framing of its request.
+pub fn serve_stream<R: Read, W: Write>(server: &mut Server, input: R, mut output: W) -> Result<()> {
+    let mut reader = BufReader::new(input);
+    let mut line = String::new();
+    while !server.exited() {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let framed = trimmed.to_ascii_lowercase().starts_with("content-length:");
+        let body = if framed {
+            let length: usize = trimmed["content-length:".len()..]
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid header '{}'", trimmed))?;
+            // Skip any further headers up to the blank separator line.
+            loop {
+                line.clear();
+                if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0u8; length];
+            reader.read_exact(&mut body).context("Truncated message body")?;
+            String::from_utf8_lossy(&body).into_owned()
+        } else {
+            trimmed.to_string()
+        };
+
+        if let Some(response) = server.handle_text(&body) {
+            let text = response.to_string();
+            if framed {
+                write!(output, "Content-Length: {}\r\n\r\n{}", text.len(), text)?;
+            } else {
+                writeln!(output, "{}", text)?;
+            }
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Answer JSON-RPC requests against the project at `root` over stdio, or over a unix
+/// socket at `socket` (one connection at a time; the DB stays open across connections).
+pub fn serve_command(root: &str, socket: Option<&str>) -> Result<()> {
+    let mut server = Server::open(root)?;
+    match socket {
+        None => serve_stream(&mut server, std::io::stdin().lock(), std::io::stdout().lock()),
+        Some(path) => serve_socket(&mut server, path),
+    }
+}
+
+#[cfg(unix)]
+fn serve_socket(server: &mut Server, path: &str) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    // Only clear a stale socket from a previous server, never an unrelated file.
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            bail!("{} exists and is not a socket", path);
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path))?;
+    }
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("Failed to bind socket {}", path))?;
+    eprintln!("Serving on {}", path);
+    let served = (|| {
+        for stream in listener.incoming() {
+            let stream = stream.context("Failed to accept connection")?;
+            let reader = stream.try_clone().context("Failed to clone connection")?;
+            if let Err(err) = serve_stream(server, reader, stream) {
+                tracing::warn!("serve: connection ended with error: {:#}", err);
+            }
+            if server.exited() {
+                break;
+            }
+        }
+        Ok(())
+    })();
+    let _ = std::fs::remove_file(path);
+    served
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_server: &mut Server, path: &str) -> Result<()> {
+    bail!("--socket {} is only supported on unix platforms", path)
+}
@@ -4,7 +4,7 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 use crate::canonicalize_or_current;
 use crate::commands::load_project_config;
@@ -18,7 +18,7 @@ pub fn setup_backend_command(
     write_path: bool,
 ) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
     let mut config = load_project_config(&layout)?;
 
     match backend {
@@ -51,9 +51,7 @@ pub fn setup_backend_command(
         println!("Set default_backend to {}", backend);
     }
 
-    let json = serde_json::to_string_pretty(&config)?;
-    fs::write(&layout.project_config_path, json)
-        .with_context(|| format!("Failed to write {}", layout.project_config_path.display()))?;
+    ritual_core::db::save_project_config(&layout, &config)?;
     println!("Updated project config at {}", layout.project_config_path.display());
 
     Ok(())
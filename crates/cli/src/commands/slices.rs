@@ -1,14 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::io::{BufWriter, Write};
 
 use crate::canonicalize_or_current;
-use anyhow::{Context, Result};
-use ritual_core::db::{RitualRunRecord, SliceRecord};
-use ritual_core::services::analysis::{AnalysisResult, BlockEdgeKind, EvidenceKind};
+use crate::commands::annotations::{parse_address, resolve_binary_hash};
+use crate::commands::{tags_by, tags_cell, OutputFormat, RitualOutputs, RitualRoots};
+use crate::output::{relative_time, slice_status_tone, Cell, Table, Tone};
+use anyhow::{anyhow, Context, Result};
+use ritual_core::analysis::coverage::{slice_coverage, SliceCoverage};
+use ritual_core::analysis::graph::{
+    dot_body_with_options, DotOptions, EvidenceFingerprints, NodeIds,
+};
+use ritual_core::analysis::metrics::{function_metrics, FunctionMetrics};
+use ritual_core::analysis::slices::{classify_slice_boundaries, SliceMembership};
+use ritual_core::db::{
+    FunctionAnnotation, MemberSource, ProjectDb, RitualRunRecord, RunNote, SliceMember,
+    SliceRecord, SliceStatus, SliceStatusChange, TagKind, ANALYSIS_PAGE_SIZE,
+};
+use ritual_core::services::analysis::{
+    apply_annotations, apply_slice_members, AnalysisResult, CallEdge, EvidenceKind, EvidenceRecord,
+    FunctionRecord,
+};
+use ritual_core::services::docs::{
+    analyst_sections, function_anchor, parse_root_list, render_analyst_section, render_toc,
+    replace_analyst_section, slice_doc_link, DocIndex, TocEntry, ANALYST_NOTES, ANALYST_ROOTS,
+};
+use ritual_core::services::image::BinaryImage;
+use ritual_core::services::render::html::{render_slice_report, EvidenceSection, SliceHtmlReport};
+use ritual_core::services::render::mermaid::mermaid_call_graph;
+use ritual_core::services::render::sarif::{binary_sections, SliceSarifReport, SliceSarifWriter};
+use ritual_core::services::report::REPORT_SCHEMA_VERSION;
+use ritual_core::services::roots::RootPattern;
+use ritual_core::services::scoring::{EvidenceScorer, EvidenceWeights, TopEvidence};
+use ritual_core::services::strings::referenced_string;
 use serde::Serialize;
 use serde_json;
 use serde_yaml;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Initialize a new slice record and its documentation scaffold.
 pub fn init_slice_command(
@@ -16,46 +44,154 @@ pub fn init_slice_command(
     name: &str,
     description: Option<String>,
     default_binary: Option<String>,
+) -> Result<()> {
+    init_slice_in_namespace_command(root, name, description, default_binary, None, None)
+}
+
+/// Initialize a new slice scoped to a binary group (`group`, which must exist) or an explicit
+/// `namespace`; with neither the slice is global. A scoped slice is named `name@namespace`
+/// and only takes runs of the group's binaries, so each group keeps its own membership.
+pub fn init_slice_in_namespace_command(
+    root: &str,
+    name: &str,
+    description: Option<String>,
+    default_binary: Option<String>,
+    group: Option<&str>,
+    namespace: Option<&str>,
 ) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     // Load project config.
-    let config_json = fs::read_to_string(&layout.project_config_path).with_context(|| {
-        format!("Failed to read project config at {}", layout.project_config_path.display())
-    })?;
-    let config: ritual_core::db::ProjectConfig =
-        serde_json::from_str(&config_json).context("Failed to parse project config JSON")?;
-
-    // Resolve DB path (may be relative or absolute in config).
-    let config_db_path = std::path::Path::new(&config.db.path);
-    let db_path = if config_db_path.is_absolute() {
-        config_db_path.to_path_buf()
-    } else {
-        layout.root.join(config_db_path)
-    };
-    let db = ritual_core::db::ProjectDb::open(&db_path)
-        .with_context(|| format!("Failed to open project database at {}", db_path.display()))?;
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    let namespace = resolve_slice_namespace(&db, group, namespace)?;
+    if namespace.is_some() {
+        check_slice_name_part("Slice name", name)?;
+    }
 
     // Insert slice record.
     let record = ritual_core::db::SliceRecord::new(name, ritual_core::db::SliceStatus::Planned)
-        .with_description(description.clone())
-        .with_default_binary(default_binary.clone());
-    db.insert_slice(&record).context("Failed to insert slice record")?;
+        .with_namespace(namespace)
+        .with_description(description)
+        .with_default_binary(default_binary);
+    let doc_path = insert_slice_with_doc(&db, &layout, &record)?;
+
+    println!("Initialized slice:");
+    println!("  Name: {}", record.name);
+    if let Some(ns) = &record.namespace {
+        println!("  Namespace: {}", ns);
+    }
+    println!("  Root: {}", layout.root.display());
+    println!("  Doc:  {}", doc_path.display());
+
+    Ok(())
+}
+
+/// Copy a slice's description and analyst roots into another binary group (or explicit
+/// namespace) as a new planned slice. Membership is not copied: the copy takes its functions
+/// from runs of the target group's binaries.
+pub fn clone_slice_command(
+    root: &str,
+    name: &str,
+    group: Option<&str>,
+    namespace: Option<&str>,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+
+    let source = find_slice(&db, name)?;
+    let Some(target) = resolve_slice_namespace(&db, group, namespace)? else {
+        return Err(anyhow!("Pass --to-group or --to-namespace to say where to clone the slice"));
+    };
+    if source.namespace.as_deref() == Some(target.as_str()) {
+        return Err(anyhow!("Slice '{}' is already in namespace '{}'", source.name, target));
+    }
+    check_slice_name_part("Slice name", source.ritual_name())?;
+    let mut record = SliceRecord::new(source.ritual_name(), SliceStatus::Planned)
+        .with_namespace(Some(target))
+        .with_description(source.description.clone());
+    record.roots = source.roots.clone();
+    if find_slice(&db, &record.name).is_ok() {
+        return Err(anyhow!("Slice '{}' already exists", record.name));
+    }
+    let doc_path = insert_slice_with_doc(&db, &layout, &record)?;
+
+    println!("Cloned slice {} to {}", source.name, record.name);
+    println!("  Roots: {}", record.roots.len());
+    println!("  Doc:  {}", doc_path.display());
+    Ok(())
+}
+
+/// Namespace for a new slice: the binary group `group` (which must exist), else `namespace`.
+fn resolve_slice_namespace(
+    db: &ProjectDb,
+    group: Option<&str>,
+    namespace: Option<&str>,
+) -> Result<Option<String>> {
+    let namespace = match (group, namespace) {
+        (Some(_), Some(_)) => {
+            return Err(anyhow!("Pass either a binary group or a namespace, not both"))
+        }
+        (Some(group), None) => {
+            let groups = db.list_binary_groups().context("Failed to list binary groups")?;
+            if !groups.iter().any(|g| g.name == group) {
+                return Err(anyhow!("Binary group '{}' not found", group));
+            }
+            group
+        }
+        (None, Some(namespace)) => namespace,
+        (None, None) => return Ok(None),
+    };
+    check_slice_name_part("Slice namespace", namespace)?;
+    Ok(Some(namespace.to_string()))
+}
+
+/// Names and namespaces of scoped slices must be non-empty and free of the `@` separator.
+fn check_slice_name_part(what: &str, value: &str) -> Result<()> {
+    if value.trim().is_empty() || value.contains('@') {
+        return Err(anyhow!("{} '{}' must be non-empty and must not contain '@'", what, value));
+    }
+    Ok(())
+}
+
+/// Insert `record` and write its doc scaffold; returns the doc path.
+fn insert_slice_with_doc(
+    db: &ProjectDb,
+    layout: &ritual_core::db::ProjectLayout,
+    record: &SliceRecord,
+) -> Result<PathBuf> {
+    db.insert_slice(record).context("Failed to insert slice record")?;
 
     // Create slice doc scaffold.
     fs::create_dir_all(&layout.slices_docs_dir).with_context(|| {
         format!("Failed to ensure slices docs dir {}", layout.slices_docs_dir.display())
     })?;
-    let doc_path = layout.slices_docs_dir.join(format!("{name}.md"));
+    let doc_path = layout.slices_docs_dir.join(format!("{}.md", record.name));
+    fs::write(&doc_path, record_doc_scaffold(record))
+        .with_context(|| format!("Failed to write slice doc at {}", doc_path.display()))?;
+    Ok(doc_path)
+}
+
+/// Placeholder doc for a new slice, replaced by `emit-slice-docs` once it has runs.
+pub fn slice_doc_scaffold(name: &str, description: Option<&str>) -> String {
+    record_doc_scaffold(
+        &SliceRecord::new(name, SliceStatus::Planned)
+            .with_description(description.map(str::to_string)),
+    )
+}
+
+/// [`slice_doc_scaffold`] for a full record, with its analyst roots and notes filled in.
+fn record_doc_scaffold(slice: &SliceRecord) -> String {
     let mut contents = String::new();
-    contents.push_str(&format!("# {name}\n\n"));
-    if let Some(desc) = description {
-        contents.push_str(&desc);
+    contents.push_str(&format!("# {}\n\n", slice.name));
+    if let Some(desc) = &slice.description {
+        contents.push_str(desc);
         contents.push_str("\n\n");
     } else {
         contents.push_str("TODO: add a human-readable description of this slice.\n\n");
     }
+    write_analyst_sections(&mut contents, slice, None);
     contents.push_str(
         "## Roots\n- TODO: list root functions (by address/name) that define this slice.\n\n",
     );
@@ -63,43 +199,30 @@ pub fn init_slice_command(
     contents.push_str(
         "## Evidence\n- TODO: xrefs, strings, patterns that justify membership in this slice.\n",
     );
-
-    fs::write(&doc_path, contents)
-        .with_context(|| format!("Failed to write slice doc at {}", doc_path.display()))?;
-
-    println!("Initialized slice:");
-    println!("  Name: {}", name);
-    println!("  Root: {}", layout.root.display());
-    println!("  Doc:  {}", doc_path.display());
-
-    Ok(())
+    contents
 }
 
-/// List all slices registered in the project database.
-pub fn list_slices_command(root: &str, json: bool) -> Result<()> {
+/// List slices registered in the project database (archived slices only with `include_archived`).
+pub fn list_slices_command(
+    root: &str,
+    json: bool,
+    include_archived: bool,
+    tag: Option<&str>,
+) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ritual_core::db::ProjectLayout::new(&root_path);
-
-    // Load project config so we know where the DB lives.
-    let config_json = fs::read_to_string(&layout.project_config_path).with_context(|| {
-        format!("Failed to read project config at {}", layout.project_config_path.display())
-    })?;
-
-    let config: ritual_core::db::ProjectConfig =
-        serde_json::from_str(&config_json).context("Failed to parse project config JSON")?;
-
-    // Resolve DB path (may be relative or absolute in config).
-    let config_db_path = std::path::Path::new(&config.db.path);
-    let db_path = if config_db_path.is_absolute() {
-        config_db_path.to_path_buf()
-    } else {
-        layout.root.join(config_db_path)
-    };
-
-    // Load DB metadata.
-    let db = ritual_core::db::ProjectDb::open(&db_path)
-        .with_context(|| format!("Failed to open project database at {}", db_path.display()))?;
-    let slices = db.list_slices().context("Failed to list slices")?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    let tags = tags_by(&db, TagKind::Slice, |t| t.name.clone())?;
+    let no_tags = Vec::new();
+    let tags_of = |slice: &SliceRecord| tags.get(&slice.name).unwrap_or(&no_tags);
+    let slices: Vec<SliceRecord> = db
+        .list_slices()
+        .context("Failed to list slices")?
+        .into_iter()
+        .filter(|s| include_archived || !s.is_archived())
+        .filter(|s| tag.is_none_or(|t| tags_of(s).iter().any(|have| have == t)))
+        .collect();
 
     if json {
         let payload: Vec<serde_json::Value> = slices
@@ -107,9 +230,12 @@ pub fn list_slices_command(root: &str, json: bool) -> Result<()> {
             .map(|s| {
                 serde_json::json!({
                     "name": s.name,
+                    "namespace": s.namespace,
                     "description": s.description,
                     "default_binary": s.default_binary,
                     "status": format!("{:?}", s.status),
+                    "archived_at": s.archived_at,
+                    "tags": tags_of(s),
                 })
             })
             .collect();
@@ -124,53 +250,692 @@ pub fn list_slices_command(root: &str, json: bool) -> Result<()> {
     }
 
     println!("Slices:");
-    for slice in slices {
-        let desc = slice.description.unwrap_or_else(|| "(no description)".to_string());
-        let bin = slice.default_binary.as_deref().unwrap_or("(no default binary)");
-        println!("- {} ({:?}) - {} [binary: {}]", slice.name, slice.status, desc, bin);
+    let mut table = Table::new(&["name", "status", "binary", "tags", "description"]);
+    for slice in &slices {
+        let status = match &slice.archived_at {
+            Some(at) => Cell::toned(
+                format!("{:?}, archived {}", slice.status, relative_time(at)),
+                Some(Tone::Dim),
+            ),
+            None => {
+                Cell::toned(format!("{:?}", slice.status), Some(slice_status_tone(slice.status)))
+            }
+        };
+        table.row(vec![
+            slice.name.as_str().into(),
+            status,
+            slice.default_binary.as_deref().unwrap_or("-").into(),
+            tags_cell(tags_of(slice)).into(),
+            slice.description.as_deref().unwrap_or("-").into(),
+        ]);
     }
+    table.print();
 
     Ok(())
 }
 
-/// Regenerate slice docs for all slices in the DB.
-pub fn emit_slice_docs_command(root: &str) -> Result<()> {
-    use ritual_core::db::{ProjectConfig, ProjectDb, ProjectLayout};
+/// Show one slice: its DB record, latest matching run with analysis counts, and the paths of
+/// its doc and emitted reports (human or JSON).
+pub fn show_slice_command(root: &str, name: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+
+    let slice = find_slice(&db, name)?;
+    let tags = tags_by(&db, TagKind::Slice, |t| t.name.clone())?.remove(&slice.name);
+    let tags = tags.unwrap_or_default();
+    let runs = db.list_ritual_runs(None).context("Failed to list ritual runs")?;
+    let groups = group_binaries(&db)?;
+    let latest_run = latest_run_for_slice(&slice, None, &runs, &groups);
+    let run_id = match latest_run {
+        Some(run) => db.latest_run_id(&run.binary, &run.ritual)?,
+        None => None,
+    };
+    let counts = match run_id {
+        Some(run_id) => Some(db.run_counts(run_id).context("Failed to count run analysis")?),
+        None => None,
+    };
+    let coverage = match run_id {
+        Some(run_id) => db.load_run_coverage(run_id).context("Failed to load slice coverage")?,
+        None => None,
+    };
+    let run_dir = latest_run.map(|run| layout.binary_output_root(&run.binary).join(&run.ritual));
+
+    let docs_dir = if slice.is_archived() {
+        layout.archived_slices_docs_dir()
+    } else {
+        layout.slices_docs_dir.clone()
+    };
+    let doc = Some(docs_dir.join(format!("{}.md", slice.name))).filter(|p| p.is_file());
+    let reports: Vec<PathBuf> = ["json", "html", "sarif"]
+        .iter()
+        .map(|ext| layout.reports_dir.join(format!("{}.{}", slice.name, ext)))
+        .filter(|p| p.is_file())
+        .collect();
+    let graph = Some(layout.graphs_dir.join(format!("{}.dot", slice.name))).filter(|p| p.is_file());
 
+    if json {
+        let payload = serde_json::json!({
+            "name": slice.name,
+            "namespace": slice.namespace,
+            "description": slice.description,
+            "default_binary": slice.default_binary,
+            "status": format!("{:?}", slice.status),
+            "archived_at": slice.archived_at,
+            "tags": tags,
+            "roots": slice.roots,
+            "notes": slice.notes,
+            "latest_run": latest_run.map(|run| serde_json::json!({
+                "binary": run.binary,
+                "ritual": run.ritual,
+                "status": run.status.as_str(),
+                "backend": run.backend,
+                "backend_version": run.backend_version,
+                "finished_at": run.finished_at,
+                "path": run_dir.as_ref().map(|p| p.display().to_string()),
+            })),
+            "counts": counts,
+            "coverage": coverage,
+            "doc": doc.as_ref().map(|p| p.display().to_string()),
+            "reports": reports.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+            "graph": graph.as_ref().map(|p| p.display().to_string()),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!("Slice: {}", slice.name);
+    if let Some(ns) = &slice.namespace {
+        println!("  Namespace: {}", ns);
+    }
+    println!("  Status: {:?}", slice.status);
+    if let Some(at) = &slice.archived_at {
+        println!("  Archived: {}", at);
+    }
+    println!("  Description: {}", slice.description.as_deref().unwrap_or("(no description)"));
+    println!(
+        "  Default binary: {}",
+        slice.default_binary.as_deref().unwrap_or("(no default binary)")
+    );
+    if !tags.is_empty() {
+        println!("  Tags: {}", tags.join(", "));
+    }
+    if !slice.roots.is_empty() {
+        println!("  Analyst roots: {}", slice.roots.join(", "));
+    }
+    if let Some(notes) = &slice.notes {
+        println!("  Analyst notes:");
+        for line in notes.lines() {
+            println!("    {}", line);
+        }
+    }
+    match (latest_run, &run_dir) {
+        (Some(run), Some(dir)) => {
+            let mut line = format!(
+                "  Latest run: {}/{} [{}] backend={} finished {}",
+                run.binary,
+                run.ritual,
+                run.status.as_str(),
+                run.backend,
+                run.finished_at
+            );
+            if let Some(version) = &run.backend_version {
+                line.push_str(&format!(" ({})", version));
+            }
+            println!("{}", line);
+            println!("    Output: {}", dir.display());
+        }
+        _ => println!("  Latest run: (none; run a ritual named '{}')", slice.ritual_name()),
+    }
+    if let Some(counts) = counts {
+        println!("    Functions: {} ({} in slice)", counts.functions, counts.in_slice_functions);
+        println!("    Call edges: {}", counts.call_edges);
+        println!("    Evidence: {}", counts.evidence);
+        println!("    Xrefs: {}", counts.xrefs);
+    }
+    if let Some(coverage) = &coverage {
+        println!("    Coverage: {}", coverage.describe());
+    }
+    match &doc {
+        Some(path) => println!("  Doc: {}", path.display()),
+        None => println!("  Doc: (none; run emit-slice-docs)"),
+    }
+    if reports.is_empty() {
+        println!("  Reports: (none; run emit-slice-reports)");
+    }
+    for path in &reports {
+        println!("  Report: {}", path.display());
+    }
+    if let Some(path) = &graph {
+        println!("  Graph: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Archive a slice: hide it from default listings and doc emission while keeping its DB rows,
+/// ritual runs, and analysis history. Its doc moves to `docs/slices/_archived/`.
+pub fn archive_slice_command(root: &str, name: &str) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
 
-    // Load project config.
-    let config_json = fs::read_to_string(&layout.project_config_path).with_context(|| {
-        format!("Failed to read project config at {}", layout.project_config_path.display())
+    let slice = find_slice(&db, name)?;
+    if slice.is_archived() {
+        return Err(anyhow!("Slice '{}' is already archived", name));
+    }
+    let archived_at = chrono::Utc::now().to_rfc3339();
+    db.archive_slice(name, &archived_at).context("Failed to archive slice")?;
+
+    let archived_dir = layout.archived_slices_docs_dir();
+    let doc_path = move_slice_doc(&layout.slices_docs_dir, &archived_dir, name)?;
+
+    println!("Archived slice: {}", name);
+    if let Some(path) = doc_path {
+        println!("  Doc:  {}", path.display());
+    }
+    Ok(())
+}
+
+/// Restore an archived slice so it shows up in listings and doc emission again.
+pub fn restore_slice_command(root: &str, name: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+
+    let slice = find_slice(&db, name)?;
+    if !slice.is_archived() {
+        return Err(anyhow!("Slice '{}' is not archived", name));
+    }
+    db.restore_slice(name).context("Failed to restore slice")?;
+
+    let archived_dir = layout.archived_slices_docs_dir();
+    let doc_path = move_slice_doc(&archived_dir, &layout.slices_docs_dir, name)?;
+
+    println!("Restored slice: {}", name);
+    if let Some(path) = doc_path {
+        println!("  Doc:  {}", path.display());
+    }
+    Ok(())
+}
+
+/// Move a slice to a new lifecycle status, recording the transition (with an optional note)
+/// in the slice's status history.
+pub fn update_slice_status_command(
+    root: &str,
+    name: &str,
+    status: &str,
+    note: Option<&str>,
+) -> Result<()> {
+    let status = SliceStatus::parse(status).ok_or_else(|| {
+        anyhow!(
+            "Unknown slice status '{}' (expected planned, draft, active, or deprecated)",
+            status
+        )
     })?;
-    let config: ProjectConfig =
-        serde_json::from_str(&config_json).context("Failed to parse project config JSON")?;
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
 
-    // Resolve DB path (may be relative or absolute in config).
-    let config_db_path = std::path::Path::new(&config.db.path);
-    let db_path = if config_db_path.is_absolute() {
-        config_db_path.to_path_buf()
-    } else {
-        layout.root.join(config_db_path)
+    let slice = find_slice(&db, name)?;
+    if slice.status == status {
+        return Err(anyhow!("Slice '{}' is already {:?}", name, status));
+    }
+    let changed_at = chrono::Utc::now().to_rfc3339();
+    let change = db
+        .update_slice_status(name, status, &changed_at, note)
+        .context("Failed to update slice status")?
+        .ok_or_else(|| anyhow!("Slice '{}' not found", name))?;
+
+    println!("Updated slice status: {}", name);
+    println!("  {:?} -> {:?} at {}", change.from, change.to, change.changed_at);
+    if let Some(note) = &change.note {
+        println!("  Note: {}", note);
+    }
+    Ok(())
+}
+
+/// Manually add a function to a slice for one build of a registered binary.
+///
+/// Manual membership is keyed by the binary's content hash and overrides what analysis runs
+/// derive; `emit-slice-docs`/`emit-slice-reports` merge the two.
+pub fn slice_add_function_command(
+    root: &str,
+    slice: &str,
+    binary: &str,
+    address: &str,
+) -> Result<()> {
+    let address = parse_address(address)?;
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    find_slice(&db, slice)?;
+    let (binary_name, binary_hash) = resolve_binary_hash(&layout, &db, binary)?;
+
+    db.upsert_slice_member(&SliceMember {
+        slice: slice.to_string(),
+        binary_hash,
+        address,
+        source: MemberSource::Manual,
+        excluded: false,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    })
+    .context("Failed to store slice member")?;
+    println!("Added {} @ 0x{:X} to slice {}", binary_name, address, slice);
+    Ok(())
+}
+
+/// Manually exclude a function from a slice, or with `reset` drop the manual override so
+/// analysis-derived membership applies again.
+pub fn slice_remove_function_command(
+    root: &str,
+    slice: &str,
+    binary: &str,
+    address: &str,
+    reset: bool,
+) -> Result<()> {
+    let address = parse_address(address)?;
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    find_slice(&db, slice)?;
+    let (binary_name, binary_hash) = resolve_binary_hash(&layout, &db, binary)?;
+
+    if reset {
+        let removed = db
+            .delete_slice_member(slice, &binary_hash, address, MemberSource::Manual)
+            .context("Failed to delete slice member")?;
+        if removed == 0 {
+            println!(
+                "No manual membership for {} @ 0x{:X} in slice {}",
+                binary_name, address, slice
+            );
+        } else {
+            println!("Reset membership of {} @ 0x{:X} in slice {}", binary_name, address, slice);
+        }
+        return Ok(());
+    }
+
+    db.upsert_slice_member(&SliceMember {
+        slice: slice.to_string(),
+        binary_hash,
+        address,
+        source: MemberSource::Manual,
+        excluded: true,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    })
+    .context("Failed to store slice member")?;
+    println!("Removed {} @ 0x{:X} from slice {}", binary_name, address, slice);
+    Ok(())
+}
+
+/// Add a root (same syntax as a ritual spec's roots) to the roots that define a slice.
+///
+/// `run-ritual --slice` analyzes the slice from these roots; a slice doc with an analyst
+/// roots section is rewritten to match so `sync-slice-docs` keeps the change.
+pub fn slice_add_root_command(root: &str, slice: &str, slice_root: &str) -> Result<()> {
+    RootPattern::parse(slice_root)
+        .with_context(|| format!("Invalid slice root '{}'", slice_root))?;
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    find_slice(&db, slice)?;
+
+    if db.add_slice_root(slice, slice_root).context("Failed to store slice root")? == 0 {
+        println!("Slice {} already has root {}", slice, slice_root);
+        return Ok(());
+    }
+    println!("Added root {} to slice {}", slice_root, slice);
+    sync_doc_roots(&layout, &db, slice)
+}
+
+/// Remove a root from the roots that define a slice.
+pub fn slice_remove_root_command(root: &str, slice: &str, slice_root: &str) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    find_slice(&db, slice)?;
+
+    if db.remove_slice_root(slice, slice_root).context("Failed to delete slice root")? == 0 {
+        return Err(anyhow!("Slice '{}' has no root '{}'", slice, slice_root));
+    }
+    println!("Removed root {} from slice {}", slice_root, slice);
+    sync_doc_roots(&layout, &db, slice)
+}
+
+/// List the roots that define a slice, in the order they were added.
+pub fn list_slice_roots_command(root: &str, slice: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    find_slice(&db, slice)?;
+    let roots = db.list_slice_roots(slice).context("Failed to list slice roots")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&roots)?);
+        return Ok(());
+    }
+    println!("Roots of slice {}:", slice);
+    if roots.is_empty() {
+        println!("(none)");
+    }
+    for slice_root in roots {
+        println!("- {}", slice_root);
+    }
+    Ok(())
+}
+
+/// Rewrite the analyst roots section of `slice`'s doc (when it has one) to the DB's roots.
+fn sync_doc_roots(
+    layout: &ritual_core::db::ProjectLayout,
+    db: &ProjectDb,
+    slice: &str,
+) -> Result<()> {
+    let doc_path = layout.slices_docs_dir.join(format!("{slice}.md"));
+    let Ok(doc) = fs::read_to_string(&doc_path) else {
+        return Ok(());
     };
+    let roots = db.list_slice_roots(slice).context("Failed to list slice roots")?;
+    let body = roots.iter().map(|root| format!("- {root}")).collect::<Vec<_>>().join("\n");
+    if let Some(updated) = replace_analyst_section(&doc, ANALYST_ROOTS, &body) {
+        fs::write(&doc_path, updated)
+            .with_context(|| format!("Failed to update {}", doc_path.display()))?;
+        println!("  Doc: {}", doc_path.display());
+    }
+    Ok(())
+}
+
+/// List recorded slice membership (manual overrides and analysis-derived rows).
+pub fn list_slice_members_command(
+    root: &str,
+    slice: &str,
+    binary: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    find_slice(&db, slice)?;
+    let hash = match binary {
+        Some(binary) => Some(resolve_binary_hash(&layout, &db, binary)?.1),
+        None => None,
+    };
+    let members =
+        db.list_slice_members(slice, hash.as_deref()).context("Failed to list slice members")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&members)?);
+        return Ok(());
+    }
+
+    println!("Members of slice {}:", slice);
+    if members.is_empty() {
+        println!("(none)");
+        return Ok(());
+    }
+    let mut table = Table::new(&["address", "source", "state", "binary", "updated"]);
+    for m in members {
+        let state = if m.excluded {
+            Cell::toned("excluded", Some(Tone::Red))
+        } else {
+            Cell::toned("included", Some(Tone::Green))
+        };
+        let hash = m.binary_hash.get(..12).unwrap_or(&m.binary_hash);
+        table.row(vec![
+            format!("0x{:X}", m.address).into(),
+            m.source.as_str().into(),
+            state,
+            hash.into(),
+            relative_time(&m.updated_at).into(),
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+/// One run in a slice's history (see [`slice_history_command`]).
+#[derive(Debug, Serialize)]
+struct SliceHistoryEntry {
+    run_id: i64,
+    binary: String,
+    /// Build label of the binary in its group, else the short binary hash.
+    version: Option<String>,
+    binary_hash: Option<String>,
+    backend: String,
+    status: String,
+    finished_at: String,
+    /// Members of the slice as of the run.
+    functions: usize,
+    /// Members gained and lost since the previous run, by function name (else address) so
+    /// they compare across builds.
+    added: Vec<String>,
+    removed: Vec<String>,
+    /// Whether the members were snapshotted when the run was recorded; older runs show their
+    /// in-slice functions without manual overrides.
+    snapshot: bool,
+}
+
+/// Names shown per delta line before eliding the rest.
+const HISTORY_DELTA_NAMES: usize = 8;
+
+/// List the runs that fed a slice, oldest first, with member counts, membership deltas, and
+/// binary versions.
+pub fn slice_history_command(root: &str, name: &str, json: bool) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    let slice = find_slice(&db, name)?;
+    let groups = group_binaries(&db)?;
+    let scope = slice.namespace.as_ref().and_then(|ns| groups.get(ns));
+    let mut versions: HashMap<String, String> = HashMap::new();
+    for group in db.list_binary_groups().context("Failed to list binary groups")? {
+        for member in group.members {
+            if let Some(version) = member.version {
+                versions.insert(member.binary.name, version);
+            }
+        }
+    }
+    let snapshots = db.slice_snapshots(&slice.name).context("Failed to load slice snapshots")?;
+    let mut runs: Vec<(i64, RitualRunRecord)> = db
+        .list_ritual_runs_with_ids(None)
+        .context("Failed to list ritual runs")?
+        .into_iter()
+        .filter(|(_, r)| {
+            r.ritual == slice.ritual_name() && scope.is_none_or(|bins| bins.contains(&r.binary))
+        })
+        .collect();
+    runs.sort_by(|(a_id, a), (b_id, b)| a.finished_at.cmp(&b.finished_at).then(a_id.cmp(b_id)));
+
+    let mut entries = Vec::new();
+    let mut previous: BTreeSet<String> = BTreeSet::new();
+    for (run_id, run) in runs {
+        let (members, snapshot) = match snapshots.get(&run_id) {
+            Some(members) => (members.clone(), true),
+            None => {
+                let counts = db.run_counts(run_id).context("Failed to count run analysis")?;
+                if counts.functions == 0 {
+                    continue;
+                }
+                (db.in_slice_function_addresses(run_id)?, false)
+            }
+        };
+        let names = db.function_names(run_id).context("Failed to load function names")?;
+        let labels: BTreeSet<String> = members
+            .iter()
+            .map(|a| names.get(a).cloned().unwrap_or_else(|| format!("0x{:X}", a)))
+            .collect();
+        let version = versions
+            .get(&run.binary)
+            .cloned()
+            .or_else(|| run.binary_hash.as_ref().map(|h| h.chars().take(12).collect()));
+        entries.push(SliceHistoryEntry {
+            run_id,
+            version,
+            binary_hash: run.binary_hash.clone(),
+            backend: run.backend.clone(),
+            status: run.status.as_str().to_string(),
+            finished_at: run.finished_at.clone(),
+            functions: members.len(),
+            added: labels.difference(&previous).cloned().collect(),
+            removed: previous.difference(&labels).cloned().collect(),
+            snapshot,
+            binary: run.binary,
+        });
+        previous = labels;
+    }
+
+    if json {
+        let payload = serde_json::json!({ "slice": slice.name, "runs": entries });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!("History of slice {}:", slice.name);
+    if entries.is_empty() {
+        println!("(no runs; run a ritual named '{}')", slice.ritual_name());
+        return Ok(());
+    }
+    for entry in &entries {
+        let mut line = format!("- run {} {} {}", entry.run_id, entry.finished_at, entry.binary);
+        if let Some(version) = &entry.version {
+            line.push_str(&format!(" ({})", version));
+        }
+        line.push_str(&format!(
+            " [{}] backend={}: {} functions (+{} -{})",
+            entry.status,
+            entry.backend,
+            entry.functions,
+            entry.added.len(),
+            entry.removed.len()
+        ));
+        if !entry.snapshot {
+            line.push_str(" [no snapshot]");
+        }
+        println!("{}", line);
+        for (sign, names) in [("+", &entry.added), ("-", &entry.removed)] {
+            if names.is_empty() {
+                continue;
+            }
+            let shown: Vec<&str> =
+                names.iter().take(HISTORY_DELTA_NAMES).map(String::as_str).collect();
+            let more = names.len().saturating_sub(HISTORY_DELTA_NAMES);
+            let suffix = if more > 0 { format!(" (+{} more)", more) } else { String::new() };
+            println!("    {} {}{}", sign, shown.join(", "), suffix);
+        }
+    }
+    Ok(())
+}
+
+fn find_slice(db: &ritual_core::db::ProjectDb, name: &str) -> Result<SliceRecord> {
+    db.list_slices()
+        .context("Failed to list slices")?
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| anyhow!("Slice '{}' not found", name))
+}
+
+/// Move `<name>.md` between slice doc directories; returns the new path if a doc existed.
+fn move_slice_doc(from_dir: &Path, to_dir: &Path, name: &str) -> Result<Option<PathBuf>> {
+    let from = from_dir.join(format!("{name}.md"));
+    if !from.is_file() {
+        return Ok(None);
+    }
+    fs::create_dir_all(to_dir)
+        .with_context(|| format!("Failed to ensure docs dir {}", to_dir.display()))?;
+    let to = to_dir.join(format!("{name}.md"));
+    fs::rename(&from, &to)
+        .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+    Ok(Some(to))
+}
+
+/// Regenerate slice docs for all slices in the DB.
+/// Regenerate Markdown docs for every active slice.
+///
+/// Analyst sections of an existing doc are kept as written; docs without them get the
+/// slice's roots and notes from the DB (see [`sync_slice_docs_command`]). Evidence is scored with the project's `evidence_weights` (see
+/// [`ritual_core::services::scoring`]); each function and slice gets a ranked
+/// `Top evidence` list, and records scoring below `min_score` are counted but not listed.
+/// With `mermaid_max_nodes`, each doc embeds a Mermaid call graph capped at that many
+/// functions.
+pub fn emit_slice_docs_command(
+    root: &str,
+    min_score: f64,
+    mermaid_max_nodes: Option<usize>,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+
+    // Load project config.
+    let (config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+    let weights = EvidenceWeights::with_overrides(&config.evidence_weights);
 
     fs::create_dir_all(&layout.slices_docs_dir).with_context(|| {
         format!("Failed to ensure slices docs dir {}", layout.slices_docs_dir.display())
     })?;
 
-    let db = ProjectDb::open(&db_path)
-        .with_context(|| format!("Failed to open project database at {}", db_path.display()))?;
-
     let runs = db.list_ritual_runs(None).unwrap_or_default();
-    let slices = db.list_slices().context("Failed to list slices")?;
+    let slices: Vec<SliceRecord> = db
+        .list_slices()
+        .context("Failed to list slices")?
+        .into_iter()
+        .filter(|s| !s.is_archived())
+        .collect();
     if slices.is_empty() {
         println!("No slices to emit docs for.");
         return Ok(());
     }
 
+    // First pass: pull analysis (latest matching run) for every slice so docs can link to
+    // functions documented by other slices. Evidence is left in the DB and streamed per slice.
+    // Manual membership is merged before annotations so added functions pick up names.
+    let groups = group_binaries(&db)?;
+    let memberships = slice_memberships(&db, &slices, &runs, None, &groups);
+    let mut prepared = Vec::new();
     for slice in slices {
+        let latest_run = latest_run_for_slice(&slice, None, &runs, &groups);
+        let annotations = latest_run.map(|run| annotations_for_run(&db, run)).unwrap_or_default();
+        let mut analysis = latest_run
+            .and_then(|run| db.load_analysis_skeleton(&run.binary, &run.ritual).ok())
+            .flatten();
+        let mut manual = Vec::new();
+        if let (Some(run), Some((_, a))) = (latest_run, analysis.as_mut()) {
+            manual = merge_slice_members(&db, &slice.name, run, a)?;
+            apply_annotations(a, &annotations);
+            classify_against_other_slices(&db, &memberships, &slice.name, run, a);
+        }
+        let notes: BTreeMap<u64, String> =
+            annotations.into_iter().filter_map(|a| Some((a.address, a.note?))).collect();
+        let manual: HashMap<u64, bool> = manual.iter().map(|m| (m.address, m.excluded)).collect();
+        prepared.push((slice, latest_run, analysis, notes, manual));
+    }
+    let mut index = DocIndex::new();
+    for (slice, latest_run, analysis, _, _) in &prepared {
+        if let (Some(run), Some((_, a))) = (latest_run, analysis) {
+            index.add_slice(&slice.name, &run.binary, a);
+        }
+    }
+
+    for (slice, latest_run, analysis, notes, manual) in prepared {
+        let outputs = outputs_for_run(&layout, latest_run);
+        print_skipped_outputs(&slice.name, &outputs, &[OutputFormat::Md]);
+        if !outputs.writes(OutputFormat::Md) {
+            continue;
+        }
+        let digest = match &analysis {
+            Some((run_id, a)) => Some(
+                EvidenceDigest::collect(
+                    &db,
+                    *run_id,
+                    &FunctionLocator::new(&a.functions),
+                    weights,
+                    min_score,
+                )
+                .with_context(|| format!("Failed to read evidence for slice {}", slice.name))?,
+            ),
+            None => None,
+        };
+        let analysis = analysis.map(|(_, a)| a);
         let doc_path = layout.slices_docs_dir.join(format!("{}.md", slice.name));
+        let analyst = fs::read_to_string(&doc_path).map(|doc| analyst_sections(&doc)).ok();
         let mut contents = String::new();
         contents.push_str(&format!("# {}\n\n", slice.name));
         if let Some(desc) = &slice.description {
@@ -182,11 +947,11 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
         if let Some(bin) = &slice.default_binary {
             contents.push_str(&format!("**Default binary:** {}\n\n", bin));
         }
-        // Pull analysis (latest matching run) to populate functions/evidence if available.
-        let latest_run = latest_run_for_slice(&slice, None, &runs);
-        let analysis = latest_run
-            .and_then(|run| db.load_analysis_result(&run.binary, &run.ritual).ok())
-            .flatten();
+        contents.push_str(&format!("**Status:** {:?}\n\n", slice.status));
+        let status_history = db
+            .slice_status_history(&slice.name)
+            .with_context(|| format!("Failed to read status history for slice {}", slice.name))?;
+        let binary = latest_run.map(|run| run.binary.as_str()).unwrap_or_default();
         let roots = analysis
             .as_ref()
             .map(|a| a.roots.clone())
@@ -201,13 +966,14 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
             .as_ref()
             .and_then(|a| a.backend_path.clone())
             .or_else(|| latest_run.and_then(|r| r.backend_path.clone()));
-        let mapping =
-            analysis.as_ref().map(|a| map_evidence_to_functions(&a.functions, &a.evidence));
         let root_coverage = analysis
             .as_ref()
             .map(|a| compute_root_coverage(&roots, &a.functions, &a.root_hits))
             .unwrap_or_default();
-        let summary = analysis.as_ref().map(|a| summarize_analysis(a, roots.len()));
+        let summary = analysis
+            .as_ref()
+            .zip(digest.as_ref())
+            .map(|(a, d)| summarize_analysis(a, roots.len(), d.counts.clone()));
 
         if let Some(run) = latest_run {
             contents.push_str("**Backend:** ");
@@ -221,6 +987,44 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
             contents.push_str("\n\n");
         }
 
+        let backlinks =
+            analysis.as_ref().map(|a| index.backlinks(&slice.name, binary, a)).unwrap_or_default();
+        let mut toc = Vec::new();
+        if summary.is_some() {
+            toc.push(TocEntry::section("Summary"));
+        }
+        if !status_history.is_empty() {
+            toc.push(TocEntry::section("Status history"));
+        }
+        let call_graph = analysis
+            .as_ref()
+            .zip(mermaid_max_nodes)
+            .filter(|(a, _)| !a.functions.is_empty())
+            .map(|(a, max_nodes)| mermaid_call_graph(a, max_nodes));
+        if call_graph.is_some() {
+            toc.push(TocEntry::section("Call graph"));
+        }
+        toc.push(TocEntry::section("Analyst notes"));
+        toc.push(TocEntry::section("Analyst roots"));
+        toc.push(TocEntry::section("Roots"));
+        toc.push(TocEntry::section("Functions"));
+        for f in analysis.iter().flat_map(|a| &a.functions) {
+            let label = f.name.clone().unwrap_or_else(|| format!("0x{:X}", f.address));
+            toc.push(TocEntry {
+                level: 1,
+                title: format!("{} @ 0x{:X}", label, f.address),
+                anchor: function_anchor(f.address),
+            });
+        }
+        if !backlinks.is_empty() {
+            toc.push(TocEntry::section("Backlinks"));
+        }
+        if digest.as_ref().is_some_and(|d| !d.top.is_empty()) {
+            toc.push(TocEntry::section("Top evidence"));
+        }
+        toc.push(TocEntry::section("Evidence"));
+        contents.push_str(&render_toc(&toc));
+
         if let Some(summary) = &summary {
             contents.push_str("## Summary\n");
             contents.push_str(&format!(
@@ -232,6 +1036,7 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
                 summary.call_edges, summary.cross_slice_calls
             ));
             contents.push_str(&format!("- Basic blocks: {}\n", summary.basic_blocks));
+            contents.push_str(&format!("- Coverage: {}\n", summary.coverage.describe()));
             contents.push_str(&format!("- Roots: {}\n", summary.roots));
             contents.push_str(&format!(
                 "- Evidence: total={} strings={} imports={} calls={} other={}\n\n",
@@ -252,6 +1057,16 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
             contents.push_str("\n\n");
         }
 
+        write_status_history(&mut contents, &status_history);
+
+        if let Some(graph) = &call_graph {
+            contents.push_str("## Call graph\n");
+            contents.push_str(graph);
+            contents.push('\n');
+        }
+
+        write_analyst_sections(&mut contents, &slice, analyst.as_ref());
+
         if latest_run.is_some() {
             contents.push_str("## Roots\n");
             if roots.is_empty() {
@@ -285,35 +1100,55 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
                     if let Some(size) = f.size {
                         tags.push(format!("size={}", size));
                     }
+                    if let Some(sig) = &f.signature {
+                        tags.push(format!("sig={}", sig));
+                    }
+                    if let Some(arch) = &f.arch {
+                        tags.push(format!("arch={}", arch));
+                    }
+                    if let Some(isa) = &f.isa {
+                        tags.push(format!("isa={}", isa));
+                    }
                     if f.in_slice {
                         tags.push("in-slice".into());
                     }
                     if f.is_boundary {
                         tags.push("boundary".into());
                     }
-                    let func_evidence = mapping
-                        .as_ref()
-                        .and_then(|m| m.by_function.get(&f.address))
-                        .cloned()
-                        .unwrap_or_default();
-                    let func_buckets = categorize_evidence(&func_evidence);
-                    contents.push_str(&format!("- {} @ 0x{:X}", label, f.address));
+                    match manual.get(&f.address) {
+                        Some(false) => tags.push("manual".into()),
+                        Some(true) => tags.push("manually excluded".into()),
+                        None => {}
+                    }
+                    let func_evidence = digest.as_ref().and_then(|d| d.by_function.get(&f.address));
+                    contents.push_str(&format!(
+                        "- <a id=\"{}\"></a>{} @ 0x{:X}",
+                        function_anchor(f.address),
+                        label,
+                        f.address
+                    ));
                     if !tags.is_empty() {
                         contents.push_str(&format!(" ({})", tags.join(", ")));
                     }
-                    if func_buckets.total() > 0 {
+                    if let Some(fe) = func_evidence {
                         contents.push_str(&format!(
                             " — evidence: total={} strings={} imports={} calls={} other={}",
-                            func_buckets.total(),
-                            func_buckets.strings.len(),
-                            func_buckets.imports.len(),
-                            func_buckets.calls.len(),
-                            func_buckets.other.len()
+                            fe.counts.total,
+                            fe.counts.strings,
+                            fe.counts.imports,
+                            fe.counts.calls,
+                            fe.counts.other
                         ));
                     }
                     contents.push('\n');
-                    if !func_evidence.is_empty() {
-                        write_inline_evidence(&mut contents, &func_evidence, 5);
+                    if let Some(note) = notes.get(&f.address) {
+                        contents.push_str(&format!("  - Note: {}\n", note));
+                    }
+                    write_call_links(&mut contents, &index, &slice.name, binary, a, f.address);
+                    if let Some(fe) = func_evidence {
+                        write_inline_strings(&mut contents, &fe.strings);
+                        write_inline_top_evidence(&mut contents, &fe.top);
+                        write_inline_evidence(&mut contents, &fe.sample);
                         contents.push('\n');
                     }
                 }
@@ -322,34 +1157,59 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
             contents.push_str("- TODO: populated by analysis runs.\n\n");
         }
 
+        if !backlinks.is_empty() {
+            contents.push_str("## Backlinks\n");
+            for reference in &backlinks {
+                let caller = index
+                    .slice_function_link(&reference.slice, binary, reference.from)
+                    .unwrap_or_else(|| format!("0x{:X}", reference.from));
+                let callee = index
+                    .function_link(&slice.name, binary, reference.to)
+                    .unwrap_or_else(|| format!("0x{:X}", reference.to));
+                contents.push_str(&format!(
+                    "- [{}]({}): {} -> {}\n",
+                    reference.slice,
+                    slice_doc_link(&reference.slice),
+                    caller,
+                    callee
+                ));
+            }
+            contents.push('\n');
+        }
+
+        if let Some(d) = digest.as_ref().filter(|d| !d.top.is_empty()) {
+            contents.push_str("## Top evidence\n");
+            for item in d.top.items() {
+                contents.push_str(&format!(
+                    "- [{:.2}] 0x{:X}: {}\n",
+                    item.score, item.evidence.address, item.evidence.description
+                ));
+            }
+            contents.push('\n');
+        }
+
         contents.push_str("## Evidence\n");
-        if let Some(a) = &analysis {
-            if a.evidence.is_empty() {
+        if let Some(d) = &digest {
+            if d.counts.total == 0 {
                 contents.push_str("- (no evidence recorded)\n");
             } else {
-                let categorized = categorize_evidence(&a.evidence);
                 contents.push_str(&format!(
                     "- Summary: total={} strings={} imports={} calls={} other={}\n\n",
-                    categorized.total(),
-                    categorized.strings.len(),
-                    categorized.imports.len(),
-                    categorized.calls.len(),
-                    categorized.other.len()
+                    d.counts.total,
+                    d.counts.strings,
+                    d.counts.imports,
+                    d.counts.calls,
+                    d.counts.other
                 ));
-                write_evidence_section(&mut contents, "Strings", &categorized.strings, 15);
-                write_evidence_section(&mut contents, "Imports", &categorized.imports, 15);
-                write_evidence_section(&mut contents, "Calls", &categorized.calls, 15);
-                write_evidence_section(&mut contents, "Other evidence", &categorized.other, 15);
-                if let Some(m) = &mapping {
-                    if !m.unmapped.is_empty() {
-                        write_evidence_section(
-                            &mut contents,
-                            "Unmapped evidence (no matching function)",
-                            &m.unmapped,
-                            15,
-                        );
-                    }
-                }
+                write_evidence_section(&mut contents, "Strings", &d.strings);
+                write_evidence_section(&mut contents, "Imports", &d.imports);
+                write_evidence_section(&mut contents, "Calls", &d.calls);
+                write_evidence_section(&mut contents, "Other evidence", &d.other);
+                write_evidence_section(
+                    &mut contents,
+                    "Unmapped evidence (no matching function)",
+                    &d.unmapped,
+                );
             }
         } else {
             contents.push_str(
@@ -362,30 +1222,176 @@ pub fn emit_slice_docs_command(root: &str) -> Result<()> {
         println!("Emitted slice doc: {}", doc_path.display());
     }
 
-    Ok(())
+    Ok(())
+}
+
+/// `## Analyst notes` and `## Analyst roots`, each kept from `existing` (the analyst sections
+/// of the doc being replaced) when it has them, otherwise filled from the slice record.
+fn write_analyst_sections(
+    contents: &mut String,
+    slice: &SliceRecord,
+    existing: Option<&BTreeMap<String, String>>,
+) {
+    let kept = |name: &str| existing.and_then(|sections| sections.get(name)).cloned();
+    let notes = kept(ANALYST_NOTES).unwrap_or_else(|| slice.notes.clone().unwrap_or_default());
+    let roots = kept(ANALYST_ROOTS).unwrap_or_else(|| {
+        slice.roots.iter().map(|root| format!("- {root}")).collect::<Vec<_>>().join("\n")
+    });
+    contents.push_str("## Analyst notes\n");
+    contents.push_str(&render_analyst_section(ANALYST_NOTES, &notes));
+    contents.push('\n');
+    contents.push_str("## Analyst roots\n");
+    contents.push_str(&render_analyst_section(ANALYST_ROOTS, &roots));
+    contents.push('\n');
+}
+
+/// What `sync-slice-docs --json` reports for one slice doc.
+#[derive(Debug, Serialize)]
+struct SliceDocSync {
+    slice: String,
+    doc: String,
+    /// Roots from the doc's roots section; `None` when the doc has none.
+    roots: Option<Vec<String>>,
+    /// Notes from the doc's notes section (`Some("")` clears them); `None` when it has none.
+    notes: Option<String>,
+    /// Whether the DB differed from the doc (and was updated, unless a dry run).
+    changed: bool,
+}
+
+/// Read the analyst sections of active slices' docs (or just `slice`'s) back into the DB:
+/// the roots section's bullets become the slice's roots and the notes section its notes.
+/// Docs without analyst sections, and sections a doc lacks, leave the DB as it is.
+pub fn sync_slice_docs_command(
+    root: &str,
+    slice: Option<&str>,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
+
+    let slices = match slice {
+        Some(name) => vec![find_slice(&db, name)?],
+        None => db
+            .list_slices()
+            .context("Failed to list slices")?
+            .into_iter()
+            .filter(|s| !s.is_archived())
+            .collect(),
+    };
+    let mut synced = Vec::new();
+    for record in slices {
+        let doc_path = layout.slices_docs_dir.join(format!("{}.md", record.name));
+        let Ok(doc) = fs::read_to_string(&doc_path) else {
+            if !json {
+                println!("Skipped {}: no doc at {}", record.name, doc_path.display());
+            }
+            continue;
+        };
+        let sections = analyst_sections(&doc);
+        let roots = sections.get(ANALYST_ROOTS).map(|body| parse_root_list(body));
+        let notes = sections.get(ANALYST_NOTES).map(|body| body.trim().to_string());
+        if roots.is_none() && notes.is_none() {
+            if !json {
+                println!(
+                    "Skipped {}: no analyst sections in {} (run emit-slice-docs to add them)",
+                    record.name,
+                    doc_path.display()
+                );
+            }
+            continue;
+        }
+        let new_roots = roots.clone().unwrap_or_else(|| record.roots.clone());
+        let new_notes = match &notes {
+            Some(notes) => Some(notes.clone()).filter(|n| !n.is_empty()),
+            None => record.notes.clone(),
+        };
+        let changed = new_roots != record.roots || new_notes != record.notes;
+        if changed && !dry_run {
+            db.set_slice_roots_and_notes(&record.name, &new_roots, new_notes.as_deref())
+                .with_context(|| format!("Failed to update slice {}", record.name))?;
+        }
+        if !json {
+            let verb = match (changed, dry_run) {
+                (false, _) => "Unchanged",
+                (true, true) => "Would update",
+                (true, false) => "Updated",
+            };
+            println!(
+                "{} {}: {} roots, {} ({})",
+                verb,
+                record.name,
+                new_roots.len(),
+                if new_notes.is_some() { "notes" } else { "no notes" },
+                doc_path.display()
+            );
+        }
+        synced.push(SliceDocSync {
+            slice: record.name,
+            doc: doc_path.display().to_string(),
+            roots,
+            notes,
+            changed,
+        });
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&synced)?);
+    }
+    Ok(())
+}
+
+/// `## Status history` section listing transitions oldest first (nothing when there are none).
+fn write_status_history(contents: &mut String, history: &[SliceStatusChange]) {
+    if history.is_empty() {
+        return;
+    }
+    contents.push_str("## Status history\n");
+    for change in history {
+        contents
+            .push_str(&format!("- {}: {:?} -> {:?}", change.changed_at, change.from, change.to));
+        if let Some(note) = &change.note {
+            contents.push_str(&format!(" ({})", note));
+        }
+        contents.push('\n');
+    }
+    contents.push('\n');
+}
+
+/// Output format of `emit-slice-reports`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SliceReportFormat {
+    /// `reports/<slice>.json` and `.html`, plus `graphs/<slice>.dot`.
+    #[default]
+    Json,
+    /// `reports/<slice>.sarif` (SARIF 2.1.0) for code-review tooling.
+    Sarif,
 }
 
-/// Regenerate slice reports for all slices in the DB.
-pub fn emit_slice_reports_command(root: &str, preferred_binary: Option<&str>) -> Result<()> {
-    use ritual_core::db::{ProjectConfig, ProjectDb, ProjectLayout};
+impl std::str::FromStr for SliceReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(SliceReportFormat::Json),
+            "sarif" => Ok(SliceReportFormat::Sarif),
+            other => Err(anyhow!("Unknown report format '{}' (expected json or sarif)", other)),
+        }
+    }
+}
 
+/// Regenerate slice reports for all slices in the DB.
+pub fn emit_slice_reports_command(
+    root: &str,
+    preferred_binary: Option<&str>,
+    format: SliceReportFormat,
+    graph_options: &DotOptions,
+) -> Result<()> {
     let root_path = canonicalize_or_current(root)?;
-    let layout = ProjectLayout::new(&root_path);
+    let layout = ritual_core::db::load_project_layout(&root_path);
 
     // Load project config.
-    let config_json = fs::read_to_string(&layout.project_config_path).with_context(|| {
-        format!("Failed to read project config at {}", layout.project_config_path.display())
-    })?;
-    let config: ProjectConfig =
-        serde_json::from_str(&config_json).context("Failed to parse project config JSON")?;
-
-    // Resolve DB path (may be relative or absolute in config).
-    let config_db_path = std::path::Path::new(&config.db.path);
-    let db_path = if config_db_path.is_absolute() {
-        config_db_path.to_path_buf()
-    } else {
-        layout.root.join(config_db_path)
-    };
+    let (_config, _db_path, db) = ritual_core::db::open_project_db(&layout)?;
 
     fs::create_dir_all(&layout.reports_dir).with_context(|| {
         format!("Failed to ensure reports dir {}", layout.reports_dir.display())
@@ -393,14 +1399,24 @@ pub fn emit_slice_reports_command(root: &str, preferred_binary: Option<&str>) ->
     fs::create_dir_all(&layout.graphs_dir)
         .with_context(|| format!("Failed to ensure graphs dir {}", layout.graphs_dir.display()))?;
 
-    let db = ProjectDb::open(&db_path)
-        .with_context(|| format!("Failed to open project database at {}", db_path.display()))?;
-
-    let slices = db.list_slices().context("Failed to list slices")?;
+    let slices: Vec<SliceRecord> = db
+        .list_slices()
+        .context("Failed to list slices")?
+        .into_iter()
+        .filter(|s| !s.is_archived())
+        .collect();
     if slices.is_empty() {
         println!("No slices to emit reports for.");
         return Ok(());
     }
+    let groups = group_binaries(&db)?;
+    let memberships = slice_memberships(
+        &db,
+        &slices,
+        &db.list_ritual_runs(None).unwrap_or_default(),
+        preferred_binary,
+        &groups,
+    );
 
     for slice in slices {
         let report_path = layout.reports_dir.join(format!("{}.json", slice.name));
@@ -408,82 +1424,164 @@ pub fn emit_slice_reports_command(root: &str, preferred_binary: Option<&str>) ->
 
         // Heuristic: use the latest ritual run whose name matches the slice name.
         let all_runs = db.list_ritual_runs(None).unwrap_or_default();
-        let latest_run = latest_run_for_slice(&slice, preferred_binary, &all_runs);
-        let analysis = latest_run
-            .and_then(|run| db.load_analysis_result(&run.binary, &run.ritual).ok())
+        let latest_run = latest_run_for_slice(&slice, preferred_binary, &all_runs, &groups);
+        let outputs = outputs_for_run(&layout, latest_run);
+        if format == SliceReportFormat::Sarif {
+            let sarif_path = layout.reports_dir.join(format!("{}.sarif", slice.name));
+            let file = fs::File::create(&sarif_path).with_context(|| {
+                format!("Failed to create slice SARIF report at {}", sarif_path.display())
+            })?;
+            write_slice_sarif_for_run(
+                BufWriter::new(file),
+                &db,
+                &layout,
+                &memberships,
+                &slice,
+                latest_run,
+            )
+            .with_context(|| {
+                format!("Failed to write slice SARIF report at {}", sarif_path.display())
+            })?;
+            println!("Emitted slice SARIF report: {}", sarif_path.display());
+            continue;
+        }
+        let formats = [OutputFormat::Json, OutputFormat::Html, OutputFormat::Dot];
+        print_skipped_outputs(&slice.name, &outputs, &formats);
+        if !formats.into_iter().any(|format| outputs.writes(format)) {
+            continue;
+        }
+        let annotations = latest_run.map(|run| annotations_for_run(&db, run)).unwrap_or_default();
+        let mut analysis = latest_run
+            .and_then(|run| db.load_analysis_skeleton(&run.binary, &run.ritual).ok())
             .flatten();
+        let mut manual_members = Vec::new();
+        if let (Some(run), Some((_, a))) = (latest_run, analysis.as_mut()) {
+            manual_members = merge_slice_members(&db, &slice.name, run, a)?;
+            apply_annotations(a, &annotations);
+            classify_against_other_slices(&db, &memberships, &slice.name, run, a);
+        }
         let roots = analysis
             .as_ref()
-            .map(|a| a.roots.clone())
+            .map(|(_, a)| a.roots.clone())
             .filter(|r| !r.is_empty())
             .or_else(|| latest_run.map(|run| load_roots_for_run(&layout, run)))
             .unwrap_or_default();
-
-        let (functions, call_edges, evidence, basic_blocks) = if let Some(a) = &analysis {
-            (
-                serde_json::to_value(&a.functions)?,
-                serde_json::to_value(&a.call_edges)?,
-                serde_json::to_value(&a.evidence)?,
-                serde_json::to_value(&a.basic_blocks)?,
-            )
-        } else {
-            (
-                serde_json::json!([]),
-                serde_json::json!([]),
-                serde_json::json!([]),
-                serde_json::json!([]),
-            )
-        };
         let backend = latest_run.map(|r| r.backend.clone());
         let backend_version = analysis
             .as_ref()
-            .and_then(|a| a.backend_version.clone())
+            .and_then(|(_, a)| a.backend_version.clone())
             .or_else(|| latest_run.and_then(|r| r.backend_version.clone()));
         let backend_path = analysis
             .as_ref()
-            .and_then(|a| a.backend_path.clone())
+            .and_then(|(_, a)| a.backend_path.clone())
             .or_else(|| latest_run.and_then(|r| r.backend_path.clone()));
-        let categorized = analysis.as_ref().map(|a| categorize_evidence(&a.evidence));
-        let mapping =
-            analysis.as_ref().map(|a| map_evidence_to_functions(&a.functions, &a.evidence));
+        let notes = match &analysis {
+            Some((run_id, _)) => db.list_run_notes(*run_id).context("Failed to load run notes")?,
+            None => Vec::new(),
+        };
         let root_coverage = analysis
             .as_ref()
-            .map(|a| compute_root_coverage(&roots, &a.functions, &a.root_hits))
+            .map(|(_, a)| compute_root_coverage(&roots, &a.functions, &a.root_hits))
             .unwrap_or_default();
-        let summary = analysis.as_ref().map(|a| summarize_analysis(a, roots.len()));
-        let function_evidence = analysis
-            .as_ref()
-            .and_then(|a| mapping.as_ref().map(|m| build_function_evidence_json(&a.functions, m)));
 
-        let report = serde_json::json!({
-            "name": slice.name,
-            "description": slice.description,
-            "status": format!("{:?}", slice.status),
-            "roots": roots,
-            "root_hits": analysis.as_ref().map(|a| a.root_hits.clone()).unwrap_or_default(),
-            "functions": functions,
-            "call_edges": call_edges,
-            "basic_blocks": basic_blocks,
-            "evidence": evidence,
-            "evidence_counts": categorized.as_ref().map(|c| c.counts()),
-            "strings": categorized.as_ref().map(|c| c.strings.clone()).unwrap_or_default(),
-            "imports": categorized.as_ref().map(|c| c.imports.clone()).unwrap_or_default(),
-            "calls": categorized.as_ref().map(|c| c.calls.clone()).unwrap_or_default(),
-            "other_evidence": categorized.as_ref().map(|c| c.other.clone()).unwrap_or_default(),
-            "backend": backend,
-            "backend_version": backend_version,
-            "backend_path": backend_path,
-            "analysis_summary": summary,
-            "function_evidence": function_evidence,
-            "root_coverage": root_coverage,
-        });
-        let serialized = serde_json::to_string_pretty(&report)?;
-        fs::write(&report_path, serialized).with_context(|| {
+        // The JSON report streams the evidence digest the HTML report and graph use, so it is
+        // still produced (into a sink) when only the JSON file is disabled.
+        let sink: Box<dyn Write> = if outputs.writes(OutputFormat::Json) {
+            let file = fs::File::create(&report_path).with_context(|| {
+                format!("Failed to create slice report at {}", report_path.display())
+            })?;
+            Box::new(BufWriter::new(file))
+        } else {
+            Box::new(std::io::sink())
+        };
+        let mut report = JsonObjectWriter::new(sink)?;
+        report.field("report_schema_version", &REPORT_SCHEMA_VERSION)?;
+        report.field("name", &slice.name)?;
+        report.field("description", &slice.description)?;
+        report.field("status", &format!("{:?}", slice.status))?;
+        report.field("roots", &roots)?;
+        let evidence = match &analysis {
+            Some((run_id, a)) => {
+                report.field("root_hits", &a.root_hits)?;
+                report.field("functions", &a.functions)?;
+                report.field("annotations", &annotations)?;
+                report.field("run_notes", &notes)?;
+                report.field("manual_members", &manual_members)?;
+                report.field("call_edges", &a.call_edges)?;
+                report.field("basic_blocks", &a.basic_blocks)?;
+                let (digest, fingerprints) = write_report_evidence(&mut report, &db, *run_id, a)
+                    .with_context(|| {
+                        format!("Failed to write slice report at {}", report_path.display())
+                    })?;
+                report.field("evidence_counts", &digest.counts)?;
+                report.field(
+                    "analysis_summary",
+                    &summarize_analysis(a, roots.len(), digest.counts.clone()),
+                )?;
+                Some((digest, fingerprints))
+            }
+            None => {
+                for key in [
+                    "root_hits",
+                    "functions",
+                    "annotations",
+                    "run_notes",
+                    "manual_members",
+                    "call_edges",
+                    "basic_blocks",
+                    "xrefs",
+                ] {
+                    report.field(key, &serde_json::json!([]))?;
+                }
+                for key in ["evidence", "strings", "imports", "calls", "other_evidence"] {
+                    report.field(key, &serde_json::json!([]))?;
+                }
+                for key in ["function_evidence", "evidence_counts", "analysis_summary"] {
+                    report.field(key, &serde_json::Value::Null)?;
+                }
+                None
+            }
+        };
+        report.field("backend", &backend)?;
+        report.field("backend_version", &backend_version)?;
+        report.field("backend_path", &backend_path)?;
+        report.field("root_coverage", &root_coverage)?;
+        report.finish().with_context(|| {
             format!("Failed to write slice report at {}", report_path.display())
         })?;
-        println!("Emitted slice report: {}", report_path.display());
+        if outputs.writes(OutputFormat::Json) {
+            println!("Emitted slice report: {}", report_path.display());
+        }
+
+        if outputs.writes(OutputFormat::Html) {
+            let html_path = layout.reports_dir.join(format!("{}.html", slice.name));
+            let html = render_slice_html(
+                &slice,
+                latest_run,
+                &roots,
+                analysis.as_ref().map(|(_, a)| a),
+                evidence.as_ref().map(|(digest, _)| digest),
+                &notes,
+            );
+            fs::write(&html_path, html).with_context(|| {
+                format!("Failed to write slice HTML report at {}", html_path.display())
+            })?;
+            println!("Emitted slice HTML report: {}", html_path.display());
+        }
 
-        let dot = render_dot_from_analysis(analysis.as_ref(), backend, backend_version.as_deref());
+        if !outputs.writes(OutputFormat::Dot) {
+            continue;
+        }
+        let graph = analysis
+            .as_ref()
+            .zip(evidence.as_ref())
+            .map(|((_, a), (_, fp))| (a, NodeIds::with_evidence(a, fp)));
+        let dot = render_dot_from_analysis(
+            graph.as_ref().map(|(a, ids)| (*a, ids)),
+            backend,
+            backend_version.as_deref(),
+            graph_options,
+        );
         fs::write(&graph_path, dot)
             .with_context(|| format!("Failed to write slice graph at {}", graph_path.display()))?;
         println!("Emitted slice graph: {}", graph_path.display());
@@ -492,10 +1590,206 @@ pub fn emit_slice_reports_command(root: &str, preferred_binary: Option<&str>) ->
     Ok(())
 }
 
-fn render_dot_from_analysis(
+/// Stream a run's xrefs and evidence into a slice report without materializing them.
+///
+/// Evidence is read in pages several times (all, per category, per function, unmapped) so
+/// only counts and one page are held in memory at a time. Returns the aggregated digest and
+/// the evidence fingerprints needed for stable graph node ids.
+fn write_report_evidence<W: Write>(
+    report: &mut JsonObjectWriter<W>,
+    db: &ProjectDb,
+    run_id: i64,
+    analysis: &AnalysisResult,
+) -> Result<(EvidenceDigest, EvidenceFingerprints)> {
+    report.begin_array("xrefs")?;
+    for page in db.xref_pages(run_id, ANALYSIS_PAGE_SIZE) {
+        for x in page? {
+            report.item(&x)?;
+        }
+    }
+    report.end_array()?;
+
+    let locator = FunctionLocator::new(&analysis.functions);
+    let mut digest = EvidenceDigest::default();
+    let mut fingerprints = EvidenceFingerprints::new(&analysis.functions);
+    report.begin_array("evidence")?;
+    for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+        for e in page? {
+            digest.push(&e, locator.locate(e.address));
+            fingerprints.add(&e);
+            report.item(&e)?;
+        }
+    }
+    report.end_array()?;
+
+    for (key, category, count) in [
+        ("strings", EvidenceCategory::Strings, digest.counts.strings),
+        ("imports", EvidenceCategory::Imports, digest.counts.imports),
+        ("calls", EvidenceCategory::Calls, digest.counts.calls),
+        ("other_evidence", EvidenceCategory::Other, digest.counts.other),
+    ] {
+        report.begin_array(key)?;
+        if count > 0 {
+            for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+                for e in page?.iter().filter(|e| evidence_category(e) == category) {
+                    report.item(e)?;
+                }
+            }
+        }
+        report.end_array()?;
+    }
+
+    report.key("function_evidence")?;
+    report.raw("{\"by_function\": {")?;
+    let mut written = HashSet::new();
+    for func in &analysis.functions {
+        let Some(entry) = digest.by_function.get(&func.address) else {
+            continue;
+        };
+        if !written.insert(func.address) {
+            continue;
+        }
+        report.raw(if written.len() == 1 { "\n    " } else { ",\n    " })?;
+        report.value(&format!("0x{:X}", func.address))?;
+        report.raw(": {\"function\": ")?;
+        report.value(func)?;
+        report.raw(", \"evidence\": [")?;
+        let end = func.address.saturating_add(u64::from(func.size.unwrap_or(1)));
+        let mut first = true;
+        for page in db.evidence_pages_in_range(run_id, func.address, end, ANALYSIS_PAGE_SIZE) {
+            for e in page?.iter().filter(|e| locator.locate(e.address) == Some(func.address)) {
+                report.raw(if first { "" } else { ", " })?;
+                report.value(e)?;
+                first = false;
+            }
+        }
+        report.raw("], \"evidence_counts\": ")?;
+        report.value(&entry.counts)?;
+        report.raw("}")?;
+    }
+    report.raw(if written.is_empty() { "}, \"unmapped\": [" } else { "\n  }, \"unmapped\": [" })?;
+    if digest.unmapped.count > 0 {
+        let mut first = true;
+        for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+            for e in page?.iter().filter(|e| locator.locate(e.address).is_none()) {
+                report.raw(if first { "" } else { ", " })?;
+                report.value(e)?;
+                first = false;
+            }
+        }
+    }
+    report.raw("]}")?;
+
+    Ok((digest, fingerprints))
+}
+
+/// Writes a JSON object one field at a time so large arrays can be streamed straight from
+/// paged DB reads instead of being collected into a `serde_json::Value` first.
+struct JsonObjectWriter<W: Write> {
+    out: W,
+    fields: usize,
+    items: usize,
+}
+
+impl<W: Write> JsonObjectWriter<W> {
+    fn new(mut out: W) -> Result<Self> {
+        out.write_all(b"{")?;
+        Ok(Self { out, fields: 0, items: 0 })
+    }
+
+    fn key(&mut self, key: &str) -> Result<()> {
+        let sep = if self.fields == 0 { "" } else { "," };
+        write!(self.out, "{}\n  {}: ", sep, serde_json::to_string(key)?)?;
+        self.fields += 1;
+        Ok(())
+    }
+
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        serde_json::to_writer(&mut self.out, value)?;
+        Ok(())
+    }
+
+    fn raw(&mut self, text: &str) -> Result<()> {
+        self.out.write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<()> {
+        self.key(key)?;
+        self.value(value)
+    }
+
+    fn begin_array(&mut self, key: &str) -> Result<()> {
+        self.key(key)?;
+        self.items = 0;
+        self.raw("[")
+    }
+
+    fn item<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.raw(if self.items == 0 { "\n    " } else { ",\n    " })?;
+        self.items += 1;
+        self.value(value)
+    }
+
+    fn end_array(&mut self) -> Result<()> {
+        self.raw(if self.items == 0 { "]" } else { "\n  ]" })
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.raw("\n}\n")?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Render the self-contained HTML report for a slice from its analysis and evidence digest.
+fn render_slice_html(
+    slice: &SliceRecord,
+    run: Option<&RitualRunRecord>,
+    roots: &[String],
     analysis: Option<&AnalysisResult>,
+    digest: Option<&EvidenceDigest>,
+    notes: &[RunNote],
+) -> String {
+    let section = |title: &str, sample: &EvidenceSample| EvidenceSection {
+        title: title.to_string(),
+        total: sample.count,
+        records: sample.items.clone(),
+    };
+    let (evidence, function_evidence) = match digest {
+        Some(d) => (
+            vec![
+                section("Strings", &d.strings),
+                section("Imports", &d.imports),
+                section("Calls", &d.calls),
+                section("Other evidence", &d.other),
+                section("Unmapped evidence (no matching function)", &d.unmapped),
+            ],
+            d.by_function.iter().map(|(addr, fe)| (*addr, fe.counts.total)).collect(),
+        ),
+        None => (Vec::new(), HashMap::new()),
+    };
+    render_slice_report(&SliceHtmlReport {
+        name: &slice.name,
+        description: slice.description.as_deref(),
+        binary: run.map(|r| r.binary.as_str()),
+        backend: run.map(|r| r.backend.as_str()),
+        backend_version: analysis
+            .and_then(|a| a.backend_version.as_deref())
+            .or_else(|| run.and_then(|r| r.backend_version.as_deref())),
+        roots,
+        analysis,
+        function_evidence,
+        evidence,
+        notes,
+    })
+}
+
+fn render_dot_from_analysis(
+    analysis: Option<(&AnalysisResult, &NodeIds)>,
     backend: Option<String>,
     backend_version: Option<&str>,
+    options: &DotOptions,
 ) -> String {
     let mut out = String::from("digraph Slice {\n  rankdir=LR;\n");
     if let Some(b) = backend {
@@ -506,34 +1800,8 @@ fn render_dot_from_analysis(
         let safe_label = label.replace('"', "\\\"");
         out.push_str(&format!("  label=\"{}\";\n  labelloc=top;\n", safe_label));
     }
-    if let Some(result) = analysis {
-        for func in &result.functions {
-            let label = func.name.clone().unwrap_or_else(|| format!("0x{:X}", func.address));
-            out.push_str(&format!("  f_{:X} [label=\"{}\" shape=box];\n", func.address, label));
-        }
-        for edge in &result.call_edges {
-            out.push_str(&format!("  f_{:X} -> f_{:X} [label=\"call\"];\n", edge.from, edge.to));
-        }
-        for bb in &result.basic_blocks {
-            out.push_str(&format!(
-                "  bb_{:X} [label=\"bb 0x{:X}\\nlen={}\" shape=ellipse];\n",
-                bb.start, bb.start, bb.len
-            ));
-            for succ in &bb.successors {
-                let label = match succ.kind {
-                    BlockEdgeKind::Fallthrough => "fallthrough",
-                    BlockEdgeKind::Jump => "jump",
-                    BlockEdgeKind::ConditionalJump => "cjump",
-                    BlockEdgeKind::IndirectJump => "ijump",
-                    BlockEdgeKind::Call => "call",
-                    BlockEdgeKind::IndirectCall => "icall",
-                };
-                out.push_str(&format!(
-                    "  bb_{:X} -> bb_{:X} [label=\"{}\"];\n",
-                    bb.start, succ.target, label
-                ));
-            }
-        }
+    if let Some((result, ids)) = analysis {
+        out.push_str(&dot_body_with_options(result, ids, options));
     } else {
         out.push_str("  // no analysis available for this slice\n");
     }
@@ -541,19 +1809,202 @@ fn render_dot_from_analysis(
     out
 }
 
+/// Write the SARIF log for a slice from its latest run, with annotations and manual members
+/// applied and locations resolved against the binary's sections when the binary is still on
+/// disk. Evidence is streamed from the DB in pages, like the JSON report's.
+fn write_slice_sarif_for_run<W: Write>(
+    out: W,
+    db: &ProjectDb,
+    layout: &ritual_core::db::ProjectLayout,
+    memberships: &HashMap<String, SliceMembership>,
+    slice: &SliceRecord,
+    run: Option<&RitualRunRecord>,
+) -> Result<()> {
+    let mut analysis = match run {
+        Some(run) => db
+            .load_analysis_skeleton(&run.binary, &run.ritual)
+            .with_context(|| format!("Failed to load analysis for slice {}", slice.name))?,
+        None => None,
+    };
+    if let (Some(run), Some((_, a))) = (run, analysis.as_mut()) {
+        merge_slice_members(db, &slice.name, run, a)?;
+        apply_annotations(a, &annotations_for_run(db, run));
+        classify_against_other_slices(db, memberships, &slice.name, run, a);
+    }
+    let binary = run.and_then(|run| {
+        db.list_binaries_with_ids()
+            .ok()?
+            .into_iter()
+            .find(|(id, b)| run.binary_id.map_or(b.name == run.binary, |rid| rid == *id))
+            .map(|(_, b)| b)
+    });
+    let sections = binary
+        .as_ref()
+        .and_then(|b| {
+            let path = Path::new(&b.path);
+            let path = if path.is_absolute() { path.to_path_buf() } else { layout.root.join(path) };
+            BinaryImage::open(&path).ok()
+        })
+        .map(|image| binary_sections(&image))
+        .unwrap_or_default();
+    let binary_hash = run.and_then(|run| run_binary_hash(db, run));
+    let backend_version = analysis
+        .as_ref()
+        .and_then(|(_, a)| a.backend_version.clone())
+        .or_else(|| run.and_then(|r| r.backend_version.clone()));
+    let report = SliceSarifReport {
+        slice: &slice.name,
+        description: slice.description.as_deref(),
+        tool_version: env!("CARGO_PKG_VERSION"),
+        ritual: run.map(|r| r.ritual.as_str()),
+        binary: run.map(|r| r.binary.as_str()),
+        binary_uri: binary.as_ref().map(|b| b.path.as_str()),
+        binary_hash: binary_hash.as_deref(),
+        backend: run.map(|r| r.backend.as_str()),
+        backend_version: backend_version.as_deref(),
+        analysis: analysis.as_ref().map(|(_, a)| a),
+        sections: &sections,
+    };
+    let mut sarif = SliceSarifWriter::new(out, &report)?;
+    if let Some((run_id, _)) = &analysis {
+        for page in db.evidence_pages(*run_id, ANALYSIS_PAGE_SIZE) {
+            for e in page? {
+                sarif.evidence(&e)?;
+            }
+        }
+    }
+    sarif.finish()?;
+    Ok(())
+}
+
+/// Hash of the build a run analyzed: the run's recorded binary hash, falling back to the
+/// hash currently registered for the binary.
+fn run_binary_hash(db: &ProjectDb, run: &RitualRunRecord) -> Option<String> {
+    run.binary_hash.clone().or_else(|| {
+        db.list_binaries_with_ids()
+            .ok()?
+            .into_iter()
+            .find(|(id, b)| run.binary_id.map_or(b.name == run.binary, |rid| rid == *id))
+            .and_then(|(_, b)| b.hash)
+    })
+}
+
+/// Annotations for the build a run analyzed.
+fn annotations_for_run(db: &ProjectDb, run: &RitualRunRecord) -> Vec<FunctionAnnotation> {
+    run_binary_hash(db, run).and_then(|h| db.list_function_annotations(&h).ok()).unwrap_or_default()
+}
+
+/// Record the analysis-derived members of a slice for the run's build, then overlay the
+/// manual overrides onto `analysis`. Returns the manual rows that were applied.
+fn merge_slice_members(
+    db: &ProjectDb,
+    slice: &str,
+    run: &RitualRunRecord,
+    analysis: &mut AnalysisResult,
+) -> Result<Vec<SliceMember>> {
+    let Some(hash) = run_binary_hash(db, run) else {
+        return Ok(Vec::new());
+    };
+    let derived: Vec<u64> =
+        analysis.functions.iter().filter(|f| f.in_slice).map(|f| f.address).collect();
+    db.replace_analysis_slice_members(slice, &hash, &derived, &chrono::Utc::now().to_rfc3339())
+        .with_context(|| format!("Failed to record analysis members of slice {}", slice))?;
+    let manual: Vec<SliceMember> = db
+        .list_slice_members(slice, Some(&hash))
+        .with_context(|| format!("Failed to list members of slice {}", slice))?
+        .into_iter()
+        .filter(|m| m.source == MemberSource::Manual)
+        .collect();
+    apply_slice_members(analysis, &manual);
+    Ok(manual)
+}
+
+/// Key grouping runs of the same build: the binary hash, else the binary name.
+fn membership_key(db: &ProjectDb, run: &RitualRunRecord) -> String {
+    run_binary_hash(db, run).unwrap_or_else(|| run.binary.clone())
+}
+
+/// Members of every slice per build (see [`membership_key`]): the in-slice functions of each
+/// slice's latest run with manual overrides applied.
+fn slice_memberships(
+    db: &ProjectDb,
+    slices: &[SliceRecord],
+    runs: &[RitualRunRecord],
+    preferred_binary: Option<&str>,
+    groups: &GroupBinaries,
+) -> HashMap<String, SliceMembership> {
+    let mut out: HashMap<String, SliceMembership> = HashMap::new();
+    for slice in slices {
+        let Some(run) = latest_run_for_slice(slice, preferred_binary, runs, groups) else {
+            continue;
+        };
+        let Ok(Some(run_id)) = db.latest_run_id(&run.binary, &run.ritual) else {
+            continue;
+        };
+        let mut members: BTreeSet<u64> =
+            db.in_slice_function_addresses(run_id).unwrap_or_default().into_iter().collect();
+        let key = membership_key(db, run);
+        let manual = db.list_slice_members(&slice.name, Some(&key)).unwrap_or_default();
+        for m in manual.into_iter().filter(|m| m.source == MemberSource::Manual) {
+            if m.excluded {
+                members.remove(&m.address);
+            } else {
+                members.insert(m.address);
+            }
+        }
+        out.entry(key).or_default().insert(slice.name.clone(), members);
+    }
+    out
+}
+
+/// Mark cross-slice calls and boundary functions against the other slices of the same build.
+fn classify_against_other_slices(
+    db: &ProjectDb,
+    memberships: &HashMap<String, SliceMembership>,
+    slice: &str,
+    run: &RitualRunRecord,
+    analysis: &mut AnalysisResult,
+) {
+    let empty = SliceMembership::new();
+    let membership = memberships.get(&membership_key(db, run)).unwrap_or(&empty);
+    classify_slice_boundaries(analysis, slice, membership);
+}
+
+/// Binary names of each binary group, by group name.
+type GroupBinaries = HashMap<String, HashSet<String>>;
+
+fn group_binaries(db: &ProjectDb) -> Result<GroupBinaries> {
+    Ok(db
+        .list_binary_groups()
+        .context("Failed to list binary groups")?
+        .into_iter()
+        .map(|g| (g.name, g.members.into_iter().map(|m| m.binary.name).collect()))
+        .collect())
+}
+
+/// Latest run of the ritual named after `slice`, preferring `preferred_binary` (else the
+/// slice's default binary). Slices namespaced by a binary group only see runs of its binaries.
 fn latest_run_for_slice<'a>(
     slice: &SliceRecord,
     preferred_binary: Option<&str>,
     runs: &'a [RitualRunRecord],
+    groups: &GroupBinaries,
 ) -> Option<&'a RitualRunRecord> {
+    let scope = slice.namespace.as_ref().and_then(|ns| groups.get(ns));
+    let runs: Vec<&RitualRunRecord> = runs
+        .iter()
+        .filter(|r| {
+            r.ritual == slice.ritual_name() && scope.is_none_or(|bins| bins.contains(&r.binary))
+        })
+        .collect();
     let filtered: Vec<&RitualRunRecord> = runs
         .iter()
+        .copied()
         .filter(|r| {
-            r.ritual == slice.name
-                && preferred_binary
-                    .map(|b| r.binary == b)
-                    .or_else(|| slice.default_binary.as_ref().map(|b| r.binary == *b))
-                    .unwrap_or(true)
+            preferred_binary
+                .map(|b| r.binary == b)
+                .or_else(|| slice.default_binary.as_ref().map(|b| r.binary == *b))
+                .unwrap_or(true)
         })
         .collect();
     if !filtered.is_empty() {
@@ -561,8 +2012,7 @@ fn latest_run_for_slice<'a>(
             .into_iter()
             .max_by(|a, b| a.finished_at.cmp(&b.finished_at).then(a.started_at.cmp(&b.started_at)))
     } else {
-        runs.iter()
-            .filter(|r| r.ritual == slice.name)
+        runs.into_iter()
             .max_by(|a, b| a.finished_at.cmp(&b.finished_at).then(a.started_at.cmp(&b.started_at)))
     }
 }
@@ -579,49 +2029,76 @@ fn load_roots_for_run(
     parse_roots_from_spec(&spec_path).unwrap_or_default()
 }
 
+/// Outputs enabled by the normalized spec of a slice's latest run (everything when the slice
+/// has no run or the spec cannot be read).
+fn outputs_for_run(
+    layout: &ritual_core::db::ProjectLayout,
+    run: Option<&RitualRunRecord>,
+) -> RitualOutputs {
+    let outputs = run.and_then(|run| {
+        let spec_path = layout.binary_output_root(&run.binary).join(&run.ritual).join("spec.yaml");
+        let body = std::fs::read_to_string(spec_path).ok()?;
+        let outputs =
+            serde_yaml::from_str::<serde_yaml::Value>(&body).ok()?.get("outputs")?.clone();
+        serde_yaml::from_value(outputs).ok()
+    });
+    outputs.unwrap_or_else(RitualOutputs::all)
+}
+
+/// Print which of a slice's artifacts its run's spec outputs disabled.
+fn print_skipped_outputs(slice: &str, outputs: &RitualOutputs, formats: &[OutputFormat]) {
+    let skipped: Vec<&str> =
+        formats.iter().filter(|f| !outputs.writes(**f)).map(OutputFormat::as_str).collect();
+    if !skipped.is_empty() {
+        println!(
+            "Skipped {} output(s) for slice {} (disabled by its run's spec outputs)",
+            skipped.join(", "),
+            slice
+        );
+    }
+}
+
 fn parse_roots_from_spec(path: &Path) -> Option<Vec<String>> {
     let body = std::fs::read_to_string(path).ok()?;
-    let mut roots: Option<Vec<String>> = serde_yaml::from_str::<serde_yaml::Value>(&body)
+    let mut roots: Option<RitualRoots> = serde_yaml::from_str::<serde_yaml::Value>(&body)
         .ok()
         .and_then(|v| v.get("roots").cloned())
-        .and_then(value_to_strings);
+        .and_then(|v| serde_yaml::from_value(v).ok());
     if roots.is_none() {
         roots = serde_json::from_str::<serde_json::Value>(&body)
             .ok()
             .and_then(|v| v.get("roots").cloned())
-            .and_then(json_value_to_strings);
+            .and_then(|v| serde_json::from_value(v).ok());
     }
-    roots
+    roots.map(|r| r.all())
 }
 
-fn value_to_strings(value: serde_yaml::Value) -> Option<Vec<String>> {
-    let seq = value.as_sequence()?;
-    let mut out = Vec::new();
-    for item in seq {
-        if let Some(s) = item.as_str() {
-            out.push(s.to_string());
-        }
-    }
-    Some(out)
+/// Records kept per evidence bucket in slice docs (exact counts are always reported).
+const DOC_SECTION_SAMPLES: usize = 15;
+/// Records listed inline under each function in slice docs.
+const DOC_INLINE_SAMPLES: usize = 5;
+/// Highest-scoring records listed per slice in slice docs.
+const DOC_TOP_EVIDENCE: usize = 10;
+/// Highest-scoring records listed under each function in slice docs.
+const DOC_INLINE_TOP_EVIDENCE: usize = 3;
+/// Referenced strings listed under each function in slice docs.
+const DOC_INLINE_STRINGS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EvidenceCategory {
+    Strings,
+    Imports,
+    Calls,
+    Other,
 }
 
-fn json_value_to_strings(value: serde_json::Value) -> Option<Vec<String>> {
-    let seq = value.as_array()?;
-    let mut out = Vec::new();
-    for item in seq {
-        if let Some(s) = item.as_str() {
-            out.push(s.to_string());
-        }
+fn evidence_category(e: &EvidenceRecord) -> EvidenceCategory {
+    match e.kind {
+        Some(EvidenceKind::String) => EvidenceCategory::Strings,
+        Some(EvidenceKind::Import) => EvidenceCategory::Imports,
+        Some(EvidenceKind::Call) => EvidenceCategory::Calls,
+        _ => EvidenceCategory::Other,
     }
-    Some(out)
-}
-
-#[derive(Clone)]
-struct EvidenceBuckets {
-    strings: Vec<ritual_core::services::analysis::EvidenceRecord>,
-    imports: Vec<ritual_core::services::analysis::EvidenceRecord>,
-    calls: Vec<ritual_core::services::analysis::EvidenceRecord>,
-    other: Vec<ritual_core::services::analysis::EvidenceRecord>,
 }
 
 #[derive(Clone, Debug, Default, Serialize)]
@@ -633,27 +2110,157 @@ struct EvidenceCounts {
     other: usize,
 }
 
-impl EvidenceBuckets {
-    fn total(&self) -> usize {
-        self.strings.len() + self.imports.len() + self.calls.len() + self.other.len()
+impl EvidenceCounts {
+    fn add(&mut self, category: EvidenceCategory) {
+        self.total += 1;
+        match category {
+            EvidenceCategory::Strings => self.strings += 1,
+            EvidenceCategory::Imports => self.imports += 1,
+            EvidenceCategory::Calls => self.calls += 1,
+            EvidenceCategory::Other => self.other += 1,
+        }
+    }
+}
+
+/// Exact count plus the first `limit` records of an evidence bucket.
+#[derive(Clone, Debug)]
+struct EvidenceSample {
+    count: usize,
+    items: Vec<EvidenceRecord>,
+    limit: usize,
+}
+
+impl EvidenceSample {
+    fn new(limit: usize) -> Self {
+        Self { count: 0, items: Vec::new(), limit }
     }
-    fn as_counts(&self) -> EvidenceCounts {
-        EvidenceCounts {
-            total: self.total(),
-            strings: self.strings.len(),
-            imports: self.imports.len(),
-            calls: self.calls.len(),
-            other: self.other.len(),
+
+    /// Count `e`, keeping it as a sample only when `listed`.
+    fn push(&mut self, e: &EvidenceRecord, listed: bool) {
+        self.count += 1;
+        if listed && self.items.len() < self.limit {
+            self.items.push(e.clone());
         }
     }
-    fn counts(&self) -> serde_json::Value {
-        serde_json::json!({
-            "total": self.total(),
-            "strings": self.strings.len(),
-            "imports": self.imports.len(),
-            "calls": self.calls.len(),
-            "other": self.other.len(),
-        })
+}
+
+#[derive(Clone, Debug)]
+struct FunctionEvidence {
+    counts: EvidenceCounts,
+    sample: EvidenceSample,
+    top: TopEvidence,
+    /// Distinct strings the function's instructions reference, in evidence order.
+    strings: Vec<String>,
+}
+
+/// Bounded aggregate of a run's evidence, built incrementally from paged DB reads: exact
+/// counts overall, per function, and unmapped, with a fixed number of sample records each
+/// and (for docs) the top-scoring records.
+#[derive(Clone, Debug)]
+struct EvidenceDigest {
+    counts: EvidenceCounts,
+    strings: EvidenceSample,
+    imports: EvidenceSample,
+    calls: EvidenceSample,
+    other: EvidenceSample,
+    by_function: HashMap<u64, FunctionEvidence>,
+    unmapped: EvidenceSample,
+    top: TopEvidence,
+}
+
+impl Default for EvidenceDigest {
+    fn default() -> Self {
+        Self {
+            counts: EvidenceCounts::default(),
+            strings: EvidenceSample::new(DOC_SECTION_SAMPLES),
+            imports: EvidenceSample::new(DOC_SECTION_SAMPLES),
+            calls: EvidenceSample::new(DOC_SECTION_SAMPLES),
+            other: EvidenceSample::new(DOC_SECTION_SAMPLES),
+            by_function: HashMap::new(),
+            unmapped: EvidenceSample::new(DOC_SECTION_SAMPLES),
+            top: TopEvidence::new(DOC_TOP_EVIDENCE),
+        }
+    }
+}
+
+impl EvidenceDigest {
+    /// Stream all evidence for `run_id` from the DB, one page at a time: a first pass
+    /// gathers the run-wide frequencies the scorer needs, the second scores and aggregates.
+    fn collect(
+        db: &ProjectDb,
+        run_id: i64,
+        locator: &FunctionLocator,
+        weights: EvidenceWeights,
+        min_score: f64,
+    ) -> Result<Self> {
+        let mut scorer = EvidenceScorer::new(weights);
+        for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+            for e in page? {
+                scorer.observe(&e);
+            }
+        }
+        let mut digest = Self::default();
+        for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+            for e in page? {
+                let score = scorer.score(&e);
+                let listed = score >= min_score;
+                digest.record(&e, locator.locate(e.address), listed.then_some(score));
+            }
+        }
+        Ok(digest)
+    }
+
+    /// Account for one evidence record owned by the function at `owner` (if any).
+    fn push(&mut self, e: &EvidenceRecord, owner: Option<u64>) {
+        self.add(e, owner, true);
+    }
+
+    /// [`EvidenceDigest::push`] for a scored record: `Some(score)` also ranks it into the
+    /// top-evidence lists, `None` (below the minimum score) counts it without listing it.
+    fn record(&mut self, e: &EvidenceRecord, owner: Option<u64>, score: Option<f64>) {
+        let entry = self.add(e, owner, score.is_some());
+        if let Some(score) = score {
+            if let Some(entry) = entry {
+                entry.top.push(e, score);
+            }
+            self.top.push(e, score);
+        }
+    }
+
+    /// Count `e` overall and for its owning function (or as unmapped), sampling it only when
+    /// `listed`. Returns the owning function's entry.
+    fn add(
+        &mut self,
+        e: &EvidenceRecord,
+        owner: Option<u64>,
+        listed: bool,
+    ) -> Option<&mut FunctionEvidence> {
+        let category = evidence_category(e);
+        self.counts.add(category);
+        match category {
+            EvidenceCategory::Strings => self.strings.push(e, listed),
+            EvidenceCategory::Imports => self.imports.push(e, listed),
+            EvidenceCategory::Calls => self.calls.push(e, listed),
+            EvidenceCategory::Other => self.other.push(e, listed),
+        }
+        let Some(addr) = owner else {
+            self.unmapped.push(e, listed);
+            return None;
+        };
+        let entry = self.by_function.entry(addr).or_insert_with(|| FunctionEvidence {
+            counts: EvidenceCounts::default(),
+            sample: EvidenceSample::new(DOC_INLINE_SAMPLES),
+            top: TopEvidence::new(DOC_INLINE_TOP_EVIDENCE),
+            strings: Vec::new(),
+        });
+        entry.counts.add(category);
+        if let Some(text) = referenced_string(e) {
+            if !entry.strings.iter().any(|s| s == text) {
+                entry.strings.push(text.to_string());
+            }
+        }
+        entry.sample.push(e, listed);
+        Some(entry)
     }
 }
 
@@ -667,45 +2274,37 @@ struct AnalysisSummary {
     basic_blocks: usize,
     roots: usize,
     evidence: EvidenceCounts,
+    coverage: SliceCoverage,
+    /// In-slice functions with the highest cyclomatic complexity, most complex first.
+    most_complex: Vec<FunctionMetrics>,
 }
 
+/// Functions listed under `most_complex` in slice reports.
+const REPORT_MOST_COMPLEX: usize = 10;
+
 #[derive(Clone, Debug, Default, Serialize)]
 struct RootCoverage {
     matched: Vec<String>,
     unmatched: Vec<String>,
 }
 
-fn categorize_evidence(
-    evidence: &[ritual_core::services::analysis::EvidenceRecord],
-) -> EvidenceBuckets {
-    let mut buckets = EvidenceBuckets {
-        strings: Vec::new(),
-        imports: Vec::new(),
-        calls: Vec::new(),
-        other: Vec::new(),
-    };
-    for e in evidence {
-        match e.kind {
-            Some(EvidenceKind::String) => buckets.strings.push(e.clone()),
-            Some(EvidenceKind::Import) => buckets.imports.push(e.clone()),
-            Some(EvidenceKind::Call) => buckets.calls.push(e.clone()),
-            _ => buckets.other.push(e.clone()),
-        }
-    }
-    buckets
-}
-
-#[derive(Default)]
-struct EvidenceMapping {
-    by_function: HashMap<u64, Vec<ritual_core::services::analysis::EvidenceRecord>>,
-    unmapped: Vec<ritual_core::services::analysis::EvidenceRecord>,
-}
-
-fn summarize_analysis(analysis: &AnalysisResult, roots: usize) -> AnalysisSummary {
-    let evidence = categorize_evidence(&analysis.evidence);
+fn summarize_analysis(
+    analysis: &AnalysisResult,
+    roots: usize,
+    evidence: EvidenceCounts,
+) -> AnalysisSummary {
     let functions_in_slice = analysis.functions.iter().filter(|f| f.in_slice).count();
     let boundary_functions = analysis.functions.iter().filter(|f| f.is_boundary).count();
     let cross_slice_calls = analysis.call_edges.iter().filter(|e| e.is_cross_slice).count();
+    let mut most_complex: Vec<FunctionMetrics> = analysis
+        .functions
+        .iter()
+        .zip(function_metrics(analysis))
+        .filter(|(f, m)| f.in_slice && m.complexity.is_some())
+        .map(|(_, m)| m)
+        .collect();
+    most_complex.sort_by_key(|m| (std::cmp::Reverse(m.complexity), m.address));
+    most_complex.truncate(REPORT_MOST_COMPLEX);
     AnalysisSummary {
         functions: analysis.functions.len(),
         functions_in_slice,
@@ -714,7 +2313,9 @@ fn summarize_analysis(analysis: &AnalysisResult, roots: usize) -> AnalysisSummar
         cross_slice_calls,
         basic_blocks: analysis.basic_blocks.len(),
         roots,
-        evidence: evidence.as_counts(),
+        evidence,
+        coverage: slice_coverage(analysis),
+        most_complex,
     }
 }
 
@@ -773,47 +2374,55 @@ fn parse_hex_addr(root: &str) -> Option<u64> {
     u64::from_str_radix(trimmed, 16).ok()
 }
 
-fn map_evidence_to_functions(
-    functions: &[ritual_core::services::analysis::FunctionRecord],
-    evidence: &[ritual_core::services::analysis::EvidenceRecord],
-) -> EvidenceMapping {
-    let mut mapping = EvidenceMapping::default();
-    for ev in evidence {
-        if let Some(addr) = find_function_for_evidence(functions, ev.address) {
-            mapping.by_function.entry(addr).or_default().push(ev.clone());
-        } else {
-            mapping.unmapped.push(ev.clone());
+/// Maps addresses to their owning function: the smallest sized function containing the
+/// address, else a size-less function starting exactly there.
+struct FunctionLocator {
+    /// Function start -> (end, position in the function list) for sized functions.
+    sized: BTreeMap<u64, Vec<(u64, usize)>>,
+    max_span: u64,
+    sizeless: HashMap<u64, usize>,
+}
+
+impl FunctionLocator {
+    fn new(functions: &[FunctionRecord]) -> Self {
+        let mut sized: BTreeMap<u64, Vec<(u64, usize)>> = BTreeMap::new();
+        let mut sizeless = HashMap::new();
+        let mut max_span = 0;
+        for (idx, f) in functions.iter().enumerate() {
+            match f.size {
+                Some(size) => {
+                    max_span = max_span.max(u64::from(size));
+                    sized
+                        .entry(f.address)
+                        .or_default()
+                        .push((f.address.saturating_add(size as u64), idx));
+                }
+                None => {
+                    sizeless.entry(f.address).or_insert(idx);
+                }
+            }
         }
+        Self { sized, max_span, sizeless }
     }
-    mapping
-}
 
-fn find_function_for_evidence(
-    functions: &[ritual_core::services::analysis::FunctionRecord],
-    addr: u64,
-) -> Option<u64> {
-    let mut best: Option<(u64, u64)> = None;
-    for func in functions {
-        if let Some(size) = func.size {
-            let start = func.address;
-            let end = start.saturating_add(size as u64);
-            if addr >= start && addr < end {
-                let span = end.saturating_sub(start);
-                if best.map(|(_, s)| span < s).unwrap_or(true) {
-                    best = Some((func.address, span));
+    fn locate(&self, addr: u64) -> Option<u64> {
+        let mut best: Option<(u64, usize, u64)> = None;
+        for (start, ranges) in self.sized.range(addr.saturating_sub(self.max_span)..=addr) {
+            for (end, idx) in ranges.iter().filter(|(end, _)| addr < *end) {
+                let key = (end - start, *idx, *start);
+                if best.map(|b| (key.0, key.1) < (b.0, b.1)).unwrap_or(true) {
+                    best = Some(key);
                 }
             }
-        } else if addr == func.address && best.is_none() {
-            best = Some((func.address, u64::MAX));
         }
+        best.map(|(_, _, start)| start).or_else(|| self.sizeless.get(&addr).map(|_| addr))
     }
-    best.map(|(a, _)| a)
 }
 
 fn format_root_hit(root: &str, analysis: &AnalysisResult) -> Option<String> {
     let hit = analysis.root_hits.iter().find(|h| h.root == root)?;
     if hit.functions.is_empty() {
-        return None;
+        return hit.error.clone();
     }
     let labels: Vec<String> =
         hit.functions.iter().map(|addr| function_label(*addr, &analysis.functions)).collect();
@@ -832,58 +2441,89 @@ fn function_label(
     format!("0x{:X}", addr)
 }
 
-fn write_evidence_section(
+/// Emit "Calls:"/"Called by:" sub-bullets linking to documented functions (here or in other slices).
+fn write_call_links(
     buf: &mut String,
-    heading: &str,
-    items: &[ritual_core::services::analysis::EvidenceRecord],
-    limit: usize,
+    index: &DocIndex,
+    slice: &str,
+    binary: &str,
+    analysis: &AnalysisResult,
+    addr: u64,
 ) {
-    if items.is_empty() {
+    let link = |target: u64| {
+        index.function_link(slice, binary, target).unwrap_or_else(|| format!("0x{:X}", target))
+    };
+    // Undocumented callees fall back to their resolved name (`memcpy@plt`) or address.
+    let mut callees: Vec<&CallEdge> =
+        analysis.call_edges.iter().filter(|e| e.from == addr).collect();
+    callees.sort_unstable_by_key(|e| e.to);
+    callees.dedup_by_key(|e| e.to);
+    if !callees.is_empty() {
+        let links: Vec<String> = callees
+            .into_iter()
+            .map(|e| index.function_link(slice, binary, e.to).unwrap_or_else(|| e.target_label()))
+            .collect();
+        buf.push_str(&format!("  - Calls: {}\n", links.join(", ")));
+    }
+    let mut callers: Vec<u64> = index.callers_of(binary, addr).iter().map(|r| r.from).collect();
+    callers.sort_unstable();
+    callers.dedup();
+    if !callers.is_empty() {
+        let links: Vec<String> = callers.into_iter().map(link).collect();
+        buf.push_str(&format!("  - Called by: {}\n", links.join(", ")));
+    }
+}
+
+fn write_evidence_section(buf: &mut String, heading: &str, sample: &EvidenceSample) {
+    if sample.count == 0 {
         return;
     }
     buf.push_str(&format!("### {}\n", heading));
-    for e in items.iter().take(limit) {
+    for e in &sample.items {
         buf.push_str(&format!("- 0x{:X}: {}\n", e.address, e.description));
     }
-    if items.len() > limit {
-        buf.push_str(&format!("- ... ({} more {})\n", items.len() - limit, heading.to_lowercase()));
+    if sample.count > sample.items.len() {
+        buf.push_str(&format!(
+            "- ... ({} more {})\n",
+            sample.count - sample.items.len(),
+            heading.to_lowercase()
+        ));
     }
     buf.push('\n');
 }
 
-fn write_inline_evidence(
-    buf: &mut String,
-    items: &[ritual_core::services::analysis::EvidenceRecord],
-    limit: usize,
-) {
-    for e in items.iter().take(limit) {
-        buf.push_str(&format!("  - 0x{:X}: {}\n", e.address, e.description));
+/// `Uses strings: "a", "b"` line for a function's referenced strings.
+fn write_inline_strings(buf: &mut String, strings: &[String]) {
+    if strings.is_empty() {
+        return;
     }
-    if items.len() > limit {
-        buf.push_str(&format!("  - ... ({} more entries)\n", items.len() - limit));
+    let listed: Vec<String> =
+        strings.iter().take(DOC_INLINE_STRINGS).map(|s| format!("{:?}", s)).collect();
+    buf.push_str(&format!("  - Uses strings: {}", listed.join(", ")));
+    if strings.len() > DOC_INLINE_STRINGS {
+        buf.push_str(&format!(" (+{} more)", strings.len() - DOC_INLINE_STRINGS));
     }
+    buf.push('\n');
 }
 
-fn build_function_evidence_json(
-    functions: &[ritual_core::services::analysis::FunctionRecord],
-    mapping: &EvidenceMapping,
-) -> serde_json::Value {
-    let mut by_function = serde_json::Map::new();
-    for func in functions {
-        if let Some(items) = mapping.by_function.get(&func.address) {
-            let counts = categorize_evidence(items);
-            by_function.insert(
-                format!("0x{:X}", func.address),
-                serde_json::json!({
-                    "function": func,
-                    "evidence": items,
-                    "evidence_counts": counts.counts(),
-                }),
-            );
-        }
+fn write_inline_top_evidence(buf: &mut String, top: &TopEvidence) {
+    if top.is_empty() {
+        return;
+    }
+    buf.push_str("  - Top evidence:\n");
+    for item in top.items() {
+        buf.push_str(&format!(
+            "    - [{:.2}] 0x{:X}: {}\n",
+            item.score, item.evidence.address, item.evidence.description
+        ));
+    }
+}
+
+fn write_inline_evidence(buf: &mut String, sample: &EvidenceSample) {
+    for e in &sample.items {
+        buf.push_str(&format!("  - 0x{:X}: {}\n", e.address, e.description));
+    }
+    if sample.count > sample.items.len() {
+        buf.push_str(&format!("  - ... ({} more entries)\n", sample.count - sample.items.len()));
     }
-    serde_json::json!({
-        "by_function": serde_json::Value::Object(by_function),
-        "unmapped": mapping.unmapped.clone(),
-    })
 }
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::ProjectLayout;
+use ritual_core::rituals::run::{resolve_backend_chain, RunBinary};
+use ritual_core::rituals::spec::RitualSpec;
+use ritual_core::rituals::vars::{resolve_spec, SpecVars};
+use ritual_core::services::analysis::default_backend_registry;
+use ritual_core::services::roots::RootPattern;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::canonicalize_or_current;
+use crate::commands::open_project_db;
+
+/// Outcome of [`check_spec`]: what the spec resolves to, and everything wrong with it.
+#[derive(Debug, Default, Serialize)]
+pub struct SpecCheck {
+    pub file: String,
+    pub ritual: Option<String>,
+    pub binary: Option<String>,
+    pub backend: Option<String>,
+    /// Backends tried after `backend` fails, in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallback_backends: Vec<String>,
+    pub roots: Vec<String>,
+    /// Variables the spec referenced, with the values substituted.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// Problems that would make `run-ritual` fail or silently do something else.
+    pub errors: Vec<String>,
+    /// Suspicious but runnable (unknown fields, missing binary file, existing output).
+    pub warnings: Vec<String>,
+}
+
+impl SpecCheck {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Validate the spec at `file` against the project at `layout` without running it: spec
+/// structure, root syntax, backend names, and binary registration. `variables`
+/// (`name=value`) fill `${name}` references as for `run-ritual`. Collects every problem
+/// instead of stopping at the first.
+pub fn check_spec(
+    layout: &ProjectLayout,
+    file: &str,
+    backend_override: Option<&str>,
+    variables: &[String],
+) -> SpecCheck {
+    let mut check = SpecCheck { file: file.to_string(), ..SpecCheck::default() };
+    let path = Path::new(file);
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            check.errors.push(format!("Failed to read ritual spec at {}: {}", path.display(), err));
+            return check;
+        }
+    };
+    let json = path.extension().and_then(|e| e.to_str()) == Some("json");
+    // The project (config defaults, binaries) is only needed up front to fill in variables.
+    let project = open_project_db(layout).and_then(|(config, _db_path, db)| {
+        let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+        Ok((config, db, binaries))
+    });
+    let resolved = match &project {
+        Ok((config, _db, binaries)) => SpecVars::new(config, variables)
+            .and_then(|vars| resolve_spec(&bytes, json, &vars, binaries)),
+        Err(_) => resolve_spec(&bytes, json, &SpecVars::default(), &[]),
+    };
+    let spec = match resolved {
+        Ok(resolved) => {
+            check.variables = resolved.variables;
+            resolved.spec
+        }
+        Err(err) => {
+            check.errors.push(format!("{:#}", err));
+            return check;
+        }
+    };
+    check.ritual = Some(spec.name.clone());
+    check.roots = spec.roots.all();
+    check.warnings.extend(unknown_fields(&bytes, json));
+
+    if let Err(err) = spec.validate() {
+        check.errors.push(format!("{:#}", err));
+    }
+    for root in &check.roots {
+        if let Err(err) = RootPattern::parse(root) {
+            check.errors.push(format!("Root '{}': {}", root, err));
+        }
+    }
+    for stage in &spec.stages {
+        for root in stage.roots.iter().flatten() {
+            if let Err(err) = RootPattern::parse(root) {
+                check.errors.push(format!("Stage '{}' root '{}': {}", stage.name, root, err));
+            }
+        }
+    }
+
+    let registry = default_backend_registry();
+    let available = registry.names().join(", ");
+    let mut unknown_backend = |label: String, name: &str| {
+        if registry.get(name).is_none() {
+            check
+                .errors
+                .push(format!("{} '{}' is not available (available: {})", label, name, available));
+        }
+    };
+    if let Some(name) = backend_override {
+        unknown_backend("Backend".into(), name);
+    } else if let Some(choice) = &spec.backend {
+        // A fallback list only needs one backend that this build can run.
+        let missing: Vec<&String> =
+            choice.names().iter().filter(|name| registry.get(name).is_none()).collect();
+        if missing.len() == choice.names().len() {
+            unknown_backend("Spec backend".into(), &choice.to_string());
+        } else {
+            check.warnings.extend(missing.iter().map(|name| {
+                format!("Spec backend '{}' is not available and will be skipped", name)
+            }));
+        }
+    }
+    for stage in &spec.stages {
+        if let Some(name) = &stage.backend {
+            unknown_backend(format!("Stage '{}' backend", stage.name), name);
+        }
+    }
+
+    let (config, db, binaries) = match project {
+        Ok(project) => project,
+        Err(err) => {
+            check.errors.push(format!("{:#}", err));
+            return check;
+        }
+    };
+    let mut chain =
+        resolve_backend_chain(&registry, backend_override, spec.backend.as_ref(), &config);
+    check.backend = Some(chain.remove(0));
+    check.fallback_backends = chain;
+    match spec.binary.resolve(&binaries).and_then(|resolved| RunBinary::new(&db, resolved)) {
+        Ok(RunBinary { binary, run_name, .. }) => {
+            check.binary = Some(binary.name.clone());
+            let binary_path = layout.root.join(&binary.path);
+            if !binary_path.exists() {
+                check.warnings.push(format!(
+                    "Binary '{}' is registered but missing on disk at {}",
+                    binary.name,
+                    binary_path.display()
+                ));
+            }
+            let run_dir = layout.binary_output_root(&run_name).join(&spec.name);
+            if run_dir.exists() {
+                check.warnings.push(format!(
+                    "Output already exists at {} (run-ritual needs --force)",
+                    run_dir.display()
+                ));
+            }
+        }
+        Err(err) => check.errors.push(format!("{:#}", err)),
+    }
+    check
+}
+
+/// Top-level keys the spec parser ignores.
+fn unknown_fields(bytes: &[u8], json: bool) -> Vec<String> {
+    let value: Option<Value> =
+        if json { serde_json::from_slice(bytes).ok() } else { serde_yaml::from_slice(bytes).ok() };
+    let schema = ritual_spec_schema();
+    let known = &schema["properties"];
+    value
+        .as_ref()
+        .and_then(Value::as_object)
+        .map(|object| {
+            object
+                .keys()
+                .filter(|key| known.get(key.as_str()).is_none())
+                .map(|key| format!("Unknown field '{}' is ignored", key))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Validate a ritual spec without executing it.
+pub fn validate_spec_command(
+    root: &str,
+    file: &str,
+    backend_override: Option<&str>,
+    variables: &[String],
+    json: bool,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    let check = check_spec(&layout, file, backend_override, variables);
+
+    if json {
+        let mut value = serde_json::to_value(&check)?;
+        value["valid"] = Value::Bool(check.is_valid());
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("Spec: {}", check.file);
+        if let Some(ritual) = &check.ritual {
+            println!("  Ritual: {}", ritual);
+        }
+        if let Some(binary) = &check.binary {
+            println!("  Binary: {}", binary);
+        }
+        if let Some(backend) = &check.backend {
+            if check.fallback_backends.is_empty() {
+                println!("  Backend: {}", backend);
+            } else {
+                println!(
+                    "  Backend: {} (falls back to {})",
+                    backend,
+                    check.fallback_backends.join(", ")
+                );
+            }
+        }
+        if !check.roots.is_empty() {
+            println!("  Roots: {}", check.roots.join(", "));
+        }
+        if !check.variables.is_empty() {
+            let pairs: Vec<String> =
+                check.variables.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            println!("  Variables: {}", pairs.join(", "));
+        }
+        for warning in &check.warnings {
+            println!("  warning: {}", warning);
+        }
+        for error in &check.errors {
+            println!("  error: {}", error);
+        }
+        if check.is_valid() {
+            println!("Spec is valid.");
+        }
+    }
+
+    if check.is_valid() {
+        Ok(())
+    } else {
+        Err(anyhow!("Ritual spec {} has {} error(s)", check.file, check.errors.len()))
+    }
+}
+
+/// Print (or write to `out`) the JSON Schema for ritual specs.
+pub fn spec_schema_command(out: Option<&str>) -> Result<()> {
+    let text = serde_json::to_string_pretty(&ritual_spec_schema())?;
+    match out {
+        Some(path) => {
+            fs::write(path, format!("{}\n", text))
+                .with_context(|| format!("Failed to write {}", path))?;
+            println!("Wrote ritual spec schema to {}", path);
+        }
+        None => println!("{}", text),
+    }
+    Ok(())
+}
+
+/// JSON Schema (draft 2020-12) describing [`RitualSpec`] as its serde types accept it
+/// (derived with `schemars`, see [`RitualSpec::schema`]).
+pub fn ritual_spec_schema() -> Value {
+    RitualSpec::schema()
+}
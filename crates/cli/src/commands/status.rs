@@ -9,8 +9,11 @@ pub fn validate_run_status(status: &str) -> Result<RitualRunStatus> {
         "failed" => Ok(RitualRunStatus::Failed),
         "canceled" => Ok(RitualRunStatus::Canceled),
         "stubbed" => Ok(RitualRunStatus::Stubbed),
+        "outdated" => Ok(RitualRunStatus::Outdated),
+        "purged" => Ok(RitualRunStatus::Purged),
         other => Err(anyhow!(
-            "Invalid status '{}'. Allowed: pending, running, succeeded, failed, canceled, stubbed",
+            "Invalid status '{}'. Allowed: pending, running, succeeded, failed, canceled, \
+             stubbed, outdated, purged",
             other
         )),
     }
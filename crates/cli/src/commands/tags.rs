@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use ritual_core::db::{load_project_layout, ProjectDb, TagKind, Tagging};
+use ritual_core::rituals::run::RunBinary;
+
+use crate::canonicalize_or_current;
+use crate::commands::{open_project_db, BinarySelector};
+use crate::output::Table;
+
+/// What `tag add`/`tag remove` apply to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagTarget {
+    Slice(String),
+    /// A registered binary (any selector form specs accept).
+    Binary(String),
+    /// The latest run of `ritual` against `binary`.
+    Run {
+        binary: String,
+        ritual: String,
+    },
+}
+
+impl TagTarget {
+    /// Build a target from `--slice`, `--binary`, and `--ritual` (a ritual needs its binary).
+    pub fn from_args(
+        slice: Option<String>,
+        binary: Option<String>,
+        ritual: Option<String>,
+    ) -> Result<Self> {
+        match (slice, binary, ritual) {
+            (Some(slice), None, None) => Ok(TagTarget::Slice(slice)),
+            (None, Some(binary), None) => Ok(TagTarget::Binary(binary)),
+            (None, Some(binary), Some(ritual)) => Ok(TagTarget::Run { binary, ritual }),
+            _ => Err(anyhow!(
+                "Tag a slice (--slice), a binary (--binary), or a run (--binary and --ritual)"
+            )),
+        }
+    }
+
+    /// Kind, row id, and display label of the target.
+    fn resolve(&self, db: &ProjectDb) -> Result<(TagKind, i64, String)> {
+        match self {
+            TagTarget::Slice(name) => {
+                let id = db
+                    .slice_id(name)
+                    .context("Failed to look up slice")?
+                    .ok_or_else(|| anyhow!("Slice '{}' not found", name))?;
+                Ok((TagKind::Slice, id, format!("slice {}", name)))
+            }
+            TagTarget::Binary(selector) => {
+                let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+                let (id, record) = BinarySelector::from(selector.as_str()).resolve(&binaries)?;
+                Ok((TagKind::Binary, *id, format!("binary {}", record.name)))
+            }
+            TagTarget::Run { binary, ritual } => {
+                let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+                let name = match BinarySelector::from(binary.as_str()).resolve(&binaries) {
+                    Ok(resolved) => RunBinary::new(db, resolved)?.run_name,
+                    // Runs outlive their binary (`remove-binary --keep-runs`).
+                    Err(_) => binary.clone(),
+                };
+                let id = db.latest_run_id(&name, ritual).context("Failed to look up ritual run")?;
+                let id = id.ok_or_else(|| {
+                    anyhow!("No ritual run {}/{} in the project DB", name, ritual)
+                })?;
+                Ok((TagKind::Run, id, format!("run {}/{}", name, ritual)))
+            }
+        }
+    }
+}
+
+fn validate_tag(tag: &str) -> Result<&str> {
+    let tag = tag.trim();
+    if tag.is_empty() || tag.contains(char::is_whitespace) || tag.contains(',') {
+        return Err(anyhow!("Tag '{}' must be non-empty without spaces or commas", tag));
+    }
+    Ok(tag)
+}
+
+fn open_db(root: &str) -> Result<ProjectDb> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = load_project_layout(&root_path);
+    let (_config, _db_path, db) = open_project_db(&layout)?;
+    Ok(db)
+}
+
+/// Attach a tag (e.g. `baseline`, `obfuscated`, `priority`) to a slice, binary, or run.
+pub fn tag_add_command(root: &str, target: &TagTarget, tag: &str) -> Result<()> {
+    let tag = validate_tag(tag)?;
+    let db = open_db(root)?;
+    let (kind, id, label) = target.resolve(&db)?;
+    let added =
+        db.add_tag(kind, id, tag, &chrono::Utc::now().to_rfc3339()).context("Failed to add tag")?;
+    if added {
+        println!("Tagged {} with '{}'", label, tag);
+    } else {
+        println!("{} is already tagged '{}'", label, tag);
+    }
+    Ok(())
+}
+
+/// Remove a tag from a slice, binary, or run; fails when the target does not have it.
+pub fn tag_remove_command(root: &str, target: &TagTarget, tag: &str) -> Result<()> {
+    let tag = validate_tag(tag)?;
+    let db = open_db(root)?;
+    let (kind, id, label) = target.resolve(&db)?;
+    if db.remove_tag(kind, id, tag).context("Failed to remove tag")? == 0 {
+        return Err(anyhow!("{} is not tagged '{}'", label, tag));
+    }
+    println!("Removed tag '{}' from {}", tag, label);
+    Ok(())
+}
+
+/// List tagged slices, binaries, and runs, optionally only one kind and/or one tag.
+pub fn tag_list_command(
+    root: &str,
+    kind: Option<&str>,
+    tag: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let kind = kind
+        .map(|k| {
+            TagKind::parse(k)
+                .ok_or_else(|| anyhow!("Unknown tag kind '{}' (expected slice, binary, or run)", k))
+        })
+        .transpose()?;
+    let db = open_db(root)?;
+    let taggings = db.list_taggings(kind, tag).context("Failed to list tags")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&taggings)?);
+        return Ok(());
+    }
+    println!("Tags:");
+    if taggings.is_empty() {
+        println!("(none)");
+        return Ok(());
+    }
+    let mut by_tag: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for t in &taggings {
+        let target = match &t.ritual {
+            Some(ritual) => format!("run {}/{}", t.name, ritual),
+            None => format!("{} {}", t.kind.as_str(), t.name),
+        };
+        by_tag.entry(t.tag.as_str()).or_default().push(target);
+    }
+    let mut table = Table::new(&["tag", "count", "targets"]);
+    for (tag, targets) in by_tag {
+        table.row(vec![tag.into(), targets.len().to_string().into(), targets.join(", ").into()]);
+    }
+    table.print();
+    Ok(())
+}
+
+/// Tags of every target of `kind`, grouped by `key` (e.g. slice name or binary id).
+pub(crate) fn tags_by<K: Ord>(
+    db: &ProjectDb,
+    kind: TagKind,
+    key: impl Fn(&Tagging) -> K,
+) -> Result<BTreeMap<K, Vec<String>>> {
+    let mut out: BTreeMap<K, Vec<String>> = BTreeMap::new();
+    for tagging in db.list_taggings(Some(kind), None).context("Failed to list tags")? {
+        let tags = out.entry(key(&tagging)).or_default();
+        if !tags.contains(&tagging.tag) {
+            tags.push(tagging.tag);
+        }
+    }
+    Ok(out)
+}
+
+/// Tags column of human listings: `a, b`, or `-` when there are no tags.
+pub(crate) fn tags_cell(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "-".into()
+    } else {
+        tags.join(", ")
+    }
+}
@@ -0,0 +1,237 @@
+//! Project templates for `init-project --template`.
+//!
+//! A template is a directory laid out like a project:
+//! - `template.yaml` — `name`, optional `description`, and `slices` (`name`, optional
+//!   `description` and `default_binary`) to register,
+//! - `rituals/*.yaml` — ritual specs copied into the project's `rituals/`,
+//! - `docs/**/*.md` — docs copied into the project's `docs/` (`docs/slices/<Slice>.md`
+//!   replaces the generated slice scaffold).
+//!
+//! Built-in templates ship in `crates/cli/templates/` and are embedded in the binary;
+//! `--template` also accepts a path to a directory in the same format.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ritual_core::db::{ProjectDb, ProjectLayout, SliceRecord, SliceStatus};
+use serde::Deserialize;
+
+use crate::commands::slices::slice_doc_scaffold;
+use crate::commands::RitualSpec;
+
+const MANIFEST_FILE: &str = "template.yaml";
+
+/// Built-in templates: name and files relative to the template root.
+const BUILTIN_TEMPLATES: &[(&str, &[(&str, &str)])] = &[(
+    "game-client",
+    &[
+        ("template.yaml", include_str!("../../templates/game-client/template.yaml")),
+        ("rituals/UI.yaml", include_str!("../../templates/game-client/rituals/UI.yaml")),
+        (
+            "rituals/Networking.yaml",
+            include_str!("../../templates/game-client/rituals/Networking.yaml"),
+        ),
+        (
+            "rituals/AutoUpdate.yaml",
+            include_str!("../../templates/game-client/rituals/AutoUpdate.yaml"),
+        ),
+        ("rituals/Save.yaml", include_str!("../../templates/game-client/rituals/Save.yaml")),
+        ("docs/README.md", include_str!("../../templates/game-client/docs/README.md")),
+    ],
+)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct TemplateManifest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    slices: Vec<TemplateSlice>,
+}
+
+/// A slice a template registers.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TemplateSlice {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default_binary: Option<String>,
+}
+
+/// A file a template copies, relative to the project root (`rituals/…` or `docs/…`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// A parsed and validated project template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    pub slices: Vec<TemplateSlice>,
+    pub files: Vec<TemplateFile>,
+}
+
+/// What applying a template changed; existing slices and files are left alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateApplied {
+    pub slices: Vec<String>,
+    pub skipped_slices: Vec<String>,
+    pub files: Vec<PathBuf>,
+    pub skipped_files: Vec<PathBuf>,
+}
+
+/// Names of the built-in templates.
+pub fn builtin_template_names() -> Vec<&'static str> {
+    BUILTIN_TEMPLATES.iter().map(|(name, _)| *name).collect()
+}
+
+/// Resolve `--template`: an existing directory is loaded from disk, anything else must name a
+/// built-in template.
+pub fn resolve_template(template: &str) -> Result<ProjectTemplate> {
+    let path = Path::new(template);
+    if path.is_dir() {
+        return load_template_dir(path);
+    }
+    let (name, files) =
+        BUILTIN_TEMPLATES.iter().find(|(name, _)| *name == template).ok_or_else(|| {
+            anyhow!(
+                "Unknown template '{}' (not a directory; built-in templates: {})",
+                template,
+                builtin_template_names().join(", ")
+            )
+        })?;
+    let files = files.iter().map(|(path, contents)| (PathBuf::from(path), contents.to_string()));
+    parse_template(name, files.collect())
+}
+
+/// Load a template directory.
+pub fn load_template_dir(dir: &Path) -> Result<ProjectTemplate> {
+    let manifest = dir.join(MANIFEST_FILE);
+    if !manifest.is_file() {
+        bail!("Template directory {} has no {}", dir.display(), MANIFEST_FILE);
+    }
+    let mut files = Vec::new();
+    collect_template_files(dir, dir, &mut files)?;
+    let fallback = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    parse_template(&fallback, files)
+}
+
+fn collect_template_files(root: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read template dir {}", dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_template_files(root, &path, out)?;
+            continue;
+        }
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        if rel != Path::new(MANIFEST_FILE) && template_target(&rel).is_none() {
+            continue;
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template file {}", path.display()))?;
+        out.push((rel, contents));
+    }
+    Ok(())
+}
+
+/// Whether a template file is copied: YAML specs under `rituals/`, Markdown under `docs/`.
+fn template_target(rel: &Path) -> Option<&'static str> {
+    let ext = rel.extension().and_then(|e| e.to_str()).unwrap_or("");
+    match rel.components().next()?.as_os_str().to_str()? {
+        "rituals" if matches!(ext, "yaml" | "yml") => Some("rituals"),
+        "docs" if ext == "md" => Some("docs"),
+        _ => None,
+    }
+}
+
+fn parse_template(fallback_name: &str, files: Vec<(PathBuf, String)>) -> Result<ProjectTemplate> {
+    let mut manifest = None;
+    let mut copied = Vec::new();
+    for (path, contents) in files {
+        if path == Path::new(MANIFEST_FILE) {
+            let parsed: TemplateManifest = serde_yaml::from_str(&contents)
+                .with_context(|| format!("Failed to parse template {}", MANIFEST_FILE))?;
+            manifest = Some(parsed);
+            continue;
+        }
+        if template_target(&path) == Some("rituals") {
+            let spec = RitualSpec::from_slice(contents.as_bytes(), false)
+                .with_context(|| format!("Invalid ritual spec in template: {}", path.display()))?;
+            spec.validate()
+                .with_context(|| format!("Invalid ritual spec in template: {}", path.display()))?;
+        }
+        copied.push(TemplateFile { path, contents });
+    }
+    let manifest = manifest.ok_or_else(|| anyhow!("Template has no {}", MANIFEST_FILE))?;
+    for (i, slice) in manifest.slices.iter().enumerate() {
+        if slice.name.trim().is_empty() {
+            bail!("Template slice #{} has an empty name", i + 1);
+        }
+        if manifest.slices[..i].iter().any(|s| s.name == slice.name) {
+            bail!("Template lists slice '{}' more than once", slice.name);
+        }
+    }
+    Ok(ProjectTemplate {
+        name: manifest.name.unwrap_or_else(|| fallback_name.to_string()),
+        description: manifest.description,
+        slices: manifest.slices,
+        files: copied,
+    })
+}
+
+/// Register the template's slices (with doc scaffolds) and copy its files into the project.
+/// Slices that already exist and files already on disk are skipped, so re-initializing a
+/// project never overwrites work.
+pub fn apply_project_template(
+    layout: &ProjectLayout,
+    db: &ProjectDb,
+    template: &ProjectTemplate,
+) -> Result<TemplateApplied> {
+    let mut applied = TemplateApplied::default();
+    let existing = db.list_slices().context("Failed to list slices")?;
+    for slice in &template.slices {
+        if existing.iter().any(|s| s.name == slice.name) {
+            applied.skipped_slices.push(slice.name.clone());
+            continue;
+        }
+        let record = SliceRecord::new(&slice.name, SliceStatus::Planned)
+            .with_description(slice.description.clone())
+            .with_default_binary(slice.default_binary.clone());
+        db.insert_slice(&record)
+            .with_context(|| format!("Failed to insert slice record '{}'", slice.name))?;
+        applied.slices.push(slice.name.clone());
+    }
+
+    let mut files: Vec<(PathBuf, String)> =
+        template.files.iter().map(|f| (layout.root.join(&f.path), f.contents.clone())).collect();
+    for slice in &template.slices {
+        let doc = layout.slices_docs_dir.join(format!("{}.md", slice.name));
+        if !files.iter().any(|(path, _)| *path == doc) {
+            files.push((doc, slice_doc_scaffold(&slice.name, slice.description.as_deref())));
+        }
+    }
+    for (path, contents) in files {
+        if path.exists() {
+            applied.skipped_files.push(path);
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create dir {}", parent.display()))?;
+        }
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write template file {}", path.display()))?;
+        applied.files.push(path);
+    }
+    Ok(applied)
+}
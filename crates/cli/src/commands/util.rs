@@ -4,7 +4,7 @@ use std::path::Path;
 use anyhow::{Context, Result};
 
 use crate::commands::rituals::analysis_summary;
-use crate::commands::{RitualRunInfo, RitualRunMetadata, RitualSpecInfo};
+use crate::commands::{BinarySelector, RitualRunInfo, RitualRunMetadata, RitualSpecInfo};
 
 /// Load the project config JSON from disk (delegates to core helper).
 pub fn load_project_config(
@@ -98,6 +98,7 @@ pub fn collect_ritual_runs_on_disk(
             };
 
             runs.push(RitualRunInfo {
+                id: None,
                 binary: bin_name.clone(),
                 name: run_name,
                 path: run_path.display().to_string(),
@@ -108,7 +109,11 @@ pub fn collect_ritual_runs_on_disk(
                 backend,
                 backend_version,
                 backend_path,
+                spec_path: None,
+                analysis_options: None,
+                usage: None,
                 analysis: None,
+                tags: Vec::new(),
             });
         }
     }
@@ -141,8 +146,9 @@ pub fn collect_ritual_specs(dir: &Path) -> Result<Vec<RitualSpecInfo>> {
                 .map(|s| s.to_string());
             let binary = parsed
                 .as_ref()
-                .and_then(|v| v.get("binary").and_then(|b| b.as_str()))
-                .map(|s| s.to_string());
+                .and_then(|v| v.get("binary").cloned())
+                .and_then(|b| serde_json::from_value::<BinarySelector>(b).ok())
+                .map(|b| b.to_string());
             (name, binary)
         } else {
             let parsed: Option<serde_yaml::Value> = serde_yaml::from_str(&body).ok();
@@ -152,8 +158,9 @@ pub fn collect_ritual_specs(dir: &Path) -> Result<Vec<RitualSpecInfo>> {
                 .map(|s| s.to_string());
             let binary = parsed
                 .as_ref()
-                .and_then(|v| v.get("binary").and_then(|b| b.as_str()))
-                .map(|s| s.to_string());
+                .and_then(|v| v.get("binary").cloned())
+                .and_then(|b| serde_yaml::from_value::<BinarySelector>(b).ok())
+                .map(|b| b.to_string());
             (name, binary)
         };
         let name = name_field.unwrap_or_else(|| {
@@ -179,9 +186,15 @@ pub fn load_runs_from_db_and_disk(
 ) -> Result<Vec<RitualRunInfo>> {
     let (_config, _db_path, db) = open_project_db(layout)?;
     let mut runs: Vec<RitualRunInfo> = Vec::new();
-    let db_runs = db.list_ritual_runs(binary_filter).unwrap_or_default();
-    for run in &db_runs {
-        let mut info = crate::commands::db_run_to_info(layout, run);
+    let db_runs = db.list_ritual_runs_with_ids(binary_filter).unwrap_or_default();
+    let tags = crate::commands::tags_by(&db, ritual_core::db::TagKind::Run, |t| {
+        (t.name.clone(), t.ritual.clone().unwrap_or_default())
+    })?;
+    for (id, run) in &db_runs {
+        let mut info = crate::commands::db_run_to_info(layout, *id, run);
+        if let Some(run_tags) = tags.get(&(run.binary.clone(), run.ritual.clone())) {
+            info.tags = run_tags.clone();
+        }
         if let Ok(Some(analysis)) = db.load_analysis_result(&run.binary, &run.ritual) {
             info.analysis = Some(analysis_summary(&analysis, Some(run)));
         }
@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use ritual_core::db::ProjectLayout;
+use ritual_core::rituals::run::{RunBinary, RunFlags};
+use ritual_core::rituals::vars::{resolve_spec, SpecVars};
+
+use crate::canonicalize_or_current;
+use crate::commands::{lock_project, open_project_db, run_ritual_command};
+
+/// A ritual spec under `rituals/` together with the registered binary it resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchTarget {
+    pub spec_path: PathBuf,
+    pub ritual: String,
+    pub binary_id: i64,
+    /// Name the binary's runs are recorded under (see `ProjectDb::binary_run_name`).
+    pub binary: String,
+    pub binary_path: PathBuf,
+    /// Hash recorded for the binary at registration (refreshed when the file changes).
+    pub binary_hash: Option<String>,
+}
+
+/// Outcome of re-running one watch target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchRerun {
+    pub run_id: Option<i64>,
+    /// Previous run that now points at `run_id` via `superseded_by`.
+    pub superseded: Option<i64>,
+}
+
+/// Resolve every spec in `rituals/` to its binary.
+///
+/// Specs that fail to parse or resolve are reported on stderr and skipped so one broken file
+/// does not stop the watcher.
+pub fn watch_targets(layout: &ProjectLayout) -> Result<Vec<WatchTarget>> {
+    let (config, _, db) = open_project_db(layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+    let vars = SpecVars::new(&config, &[])?;
+    let mut spec_paths: Vec<PathBuf> = match std::fs::read_dir(&layout.rituals_dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.is_file()
+                    && matches!(
+                        p.extension().and_then(|e| e.to_str()),
+                        Some("yaml" | "yml" | "json")
+                    )
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    spec_paths.sort();
+
+    let mut targets = Vec::new();
+    for spec_path in spec_paths {
+        let bytes = std::fs::read(&spec_path)
+            .with_context(|| format!("Failed to read ritual spec at {}", spec_path.display()));
+        let resolved = bytes.and_then(|bytes| {
+            let json = spec_path.extension().and_then(|e| e.to_str()) == Some("json");
+            let spec = resolve_spec(&bytes, json, &vars, &binaries)?.spec;
+            spec.validate()?;
+            Ok((spec.name, RunBinary::new(&db, spec.binary.resolve(&binaries)?)?))
+        });
+        match resolved {
+            Ok((ritual, RunBinary { id: binary_id, binary: bin, run_name })) => {
+                let path = Path::new(&bin.path);
+                let binary_path =
+                    if path.is_absolute() { path.to_path_buf() } else { layout.root.join(path) };
+                targets.push(WatchTarget {
+                    spec_path,
+                    ritual,
+                    binary_id,
+                    binary: run_name,
+                    binary_path,
+                    binary_hash: bin.hash,
+                });
+            }
+            Err(err) => eprintln!("Skipping {}: {err:#}", spec_path.display()),
+        }
+    }
+    Ok(targets)
+}
+
+/// Targets whose spec or binary is among `changed` (paths compared after normalization).
+pub fn affected_targets<'a>(
+    targets: &'a [WatchTarget],
+    changed: &[PathBuf],
+) -> Vec<&'a WatchTarget> {
+    let changed: BTreeSet<PathBuf> = changed.iter().map(|p| normalize_path(p)).collect();
+    targets
+        .iter()
+        .filter(|t| {
+            changed.contains(&normalize_path(&t.spec_path))
+                || changed.contains(&normalize_path(&t.binary_path))
+        })
+        .collect()
+}
+
+/// Re-run a target through `run-ritual` and link the previous run to the new one.
+///
+/// When the binary changed on disk and a hash was recorded for it, the stored hash is
+/// refreshed first so the new run records the hash of what was actually analyzed.
+pub fn rerun_watch_target(
+    root: &str,
+    target: &WatchTarget,
+    backend: Option<&str>,
+    force: bool,
+) -> Result<WatchRerun> {
+    let layout = ritual_core::db::load_project_layout(canonicalize_or_current(root)?);
+    // Queue behind other writers rather than skipping the change.
+    let _lock = lock_project(&layout, "binary-slicer watch", true)?;
+    let (_, _, db) = open_project_db(&layout)?;
+    if target.binary_hash.is_some() && target.binary_path.is_file() {
+        let hash = crate::sha256_file(&target.binary_path)?;
+        if target.binary_hash.as_deref() != Some(hash.as_str()) {
+            db.update_binary_hash(target.binary_id, Some(&hash))
+                .context("Failed to refresh binary hash")?;
+        }
+    }
+    let previous = db.latest_run_id(&target.binary, &target.ritual)?;
+
+    let spec = target.spec_path.to_string_lossy();
+    run_ritual_command(root, &spec, backend, RunFlags { force, ..RunFlags::default() }, &[], &[])?;
+
+    let run_id = db.latest_run_id(&target.binary, &target.ritual)?;
+    let superseded = match (previous, run_id) {
+        (Some(old), Some(new)) if old != new => {
+            db.mark_run_superseded(old, new).context("Failed to link superseded run")?;
+            Some(old)
+        }
+        _ => None,
+    };
+    Ok(WatchRerun { run_id, superseded })
+}
+
+/// Watch registered binaries and `rituals/*` specs, re-running affected rituals on change.
+///
+/// Events are debounced for `debounce_ms` so a rebuild that touches a file several times
+/// triggers a single run. `max_cycles` stops after that many batches that re-ran something
+/// (the watcher otherwise runs until interrupted).
+pub fn watch_command(
+    root: &str,
+    backend: Option<&str>,
+    force: bool,
+    debounce_ms: u64,
+    max_cycles: Option<usize>,
+) -> Result<()> {
+    let root_path = canonicalize_or_current(root)?;
+    let layout = ritual_core::db::load_project_layout(&root_path);
+    if !layout.rituals_dir.is_dir() {
+        return Err(anyhow!("Rituals directory not found at {}", layout.rituals_dir.display()));
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    let mut watched_dirs = BTreeSet::new();
+    let mut targets = watch_targets(&layout)?;
+    watch_dirs(&mut watcher, &mut watched_dirs, &layout, &targets)?;
+    println!(
+        "Watching {} ritual spec(s) and {} binary path(s) under {} (Ctrl+C to stop)",
+        targets.len(),
+        targets.iter().map(|t| &t.binary_path).collect::<BTreeSet<_>>().len(),
+        root_path.display()
+    );
+
+    let debounce = Duration::from_millis(debounce_ms);
+    let mut cycles = 0;
+    while let Ok(first) = rx.recv() {
+        let mut changed = Vec::new();
+        collect_event_paths(first, &mut changed);
+        while let Ok(next) = rx.recv_timeout(debounce) {
+            collect_event_paths(next, &mut changed);
+        }
+        if changed.is_empty() {
+            continue;
+        }
+
+        // Specs may have been added or re-pointed at another binary; resolve again.
+        targets = watch_targets(&layout)?;
+        watch_dirs(&mut watcher, &mut watched_dirs, &layout, &targets)?;
+        let affected = affected_targets(&targets, &changed);
+        if affected.is_empty() {
+            continue;
+        }
+
+        for target in affected {
+            println!("Change detected; re-running {} for {}", target.ritual, target.binary);
+            match rerun_watch_target(root, target, backend, force) {
+                Ok(WatchRerun { run_id: Some(id), superseded: Some(old) }) => {
+                    println!("  Run #{id} supersedes run #{old}")
+                }
+                Ok(WatchRerun { run_id: Some(id), .. }) => println!("  Run #{id} recorded"),
+                Ok(_) => {}
+                Err(err) => eprintln!("  Failed to re-run {}: {err:#}", target.ritual),
+            }
+        }
+
+        cycles += 1;
+        if max_cycles.is_some_and(|max| cycles >= max) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Watch the rituals dir and each binary's parent directory (non-recursively).
+///
+/// Parents are watched rather than the files themselves because linkers and editors often
+/// replace files via rename, which would silently drop a per-file watch.
+fn watch_dirs(
+    watcher: &mut impl Watcher,
+    watched: &mut BTreeSet<PathBuf>,
+    layout: &ProjectLayout,
+    targets: &[WatchTarget],
+) -> Result<()> {
+    let dirs = std::iter::once(layout.rituals_dir.clone())
+        .chain(targets.iter().filter_map(|t| t.binary_path.parent().map(Path::to_path_buf)));
+    for dir in dirs {
+        let dir = normalize_path(&dir);
+        if !dir.is_dir() || watched.contains(&dir) {
+            continue;
+        }
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+        watched.insert(dir);
+    }
+    Ok(())
+}
+
+fn collect_event_paths(event: notify::Result<notify::Event>, out: &mut Vec<PathBuf>) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => out.extend(event.paths),
+        Ok(_) => {}
+        Err(err) => eprintln!("Watch error: {err}"),
+    }
+}
+
+/// Canonicalize the parent directory and keep the file name, so paths compare equal even
+/// while the file itself is briefly missing during a replace.
+fn normalize_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent().and_then(|p| p.canonicalize().ok()), path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => path.to_path_buf(),
+    }
+}
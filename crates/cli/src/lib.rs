@@ -1,12 +1,12 @@
 pub mod commands;
+pub mod logging;
+pub mod output;
 
 use std::env;
-use std::fs;
-use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use sha2::{Digest, Sha256};
+pub use ritual_core::rituals::run::sha256_file;
 
 /// Canonicalize the root path if possible, falling back to the given string
 /// relative to the current working directory.
@@ -33,25 +33,3 @@ pub fn canonicalize_or_current(root: &str) -> Result<PathBuf> {
 pub fn infer_project_name(root: &Path) -> String {
     root.file_name().and_then(|os_str| os_str.to_str()).unwrap_or("unnamed-project").to_string()
 }
-
-/// Compute the SHA-256 hash of a file and return it as a hex string.
-pub fn sha256_file(path: &Path) -> Result<String> {
-    let file = fs::File::open(path)
-        .with_context(|| format!("Failed to open binary for hashing: {}", path.display()))?;
-    let mut reader = BufReader::new(file);
-    let mut hasher = Sha256::new();
-    let mut buf = [0u8; 8192];
-
-    loop {
-        let n = reader
-            .read(&mut buf)
-            .with_context(|| format!("Failed to read binary for hashing: {}", path.display()))?;
-        if n == 0 {
-            break;
-        }
-        hasher.update(&buf[..n]);
-    }
-
-    let digest = hasher.finalize();
-    Ok(format!("{:x}", digest))
-}
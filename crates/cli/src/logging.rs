@@ -0,0 +1,154 @@
+//! Process-wide `tracing` subscriber for the CLI.
+//!
+//! Human-readable events go to stderr (warnings by default, everything down to debug with
+//! `--verbose`). With `--log-json`, every event emitted inside a ritual run span (see
+//! [`ritual_core::rituals::run::run_span`]) is also written as one JSON object per line to
+//! `<run dir>/run.log.jsonl`, so long analyses can be inspected after the fact. Each run's
+//! file is opened once, when its span starts, and closed with the span.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use ritual_core::rituals::run::RUN_LOG_FILE;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Span, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
+
+/// Logging flags shared by every command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogOptions {
+    pub verbose: bool,
+    pub json: bool,
+}
+
+static OPTIONS: OnceLock<LogOptions> = OnceLock::new();
+
+/// Install the CLI subscriber; later calls keep the first configuration.
+pub fn init(options: LogOptions) {
+    let options = *OPTIONS.get_or_init(|| options);
+    let stderr_level = if options.verbose { LevelFilter::DEBUG } else { LevelFilter::WARN };
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(io::stderr)
+        .without_time()
+        .with_target(false)
+        .with_filter(stderr_level);
+    let json = options.json.then(|| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_span_list(true)
+            .with_current_span(false)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(RunLogWriter)
+            .with_filter(LevelFilter::DEBUG)
+    });
+    let run_logs = options.json.then_some(RunLogLayer);
+    let _ = tracing_subscriber::registry().with(stderr).with(run_logs).with(json).try_init();
+}
+
+/// Where events of a run in `run_dir` are written, when `--log-json` is active.
+pub fn run_log_path(run_dir: &Path) -> Option<PathBuf> {
+    OPTIONS.get().filter(|o| o.json).map(|_| run_dir.join(RUN_LOG_FILE))
+}
+
+/// The open `run.log.jsonl` of a run, stored in its span's extensions.
+#[derive(Clone)]
+struct RunLog(Arc<Mutex<LineWriter<File>>>);
+
+impl RunLog {
+    fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(RunLog(Arc::new(Mutex::new(LineWriter::new(file)))))
+    }
+}
+
+/// Opens the log of each run span as the span is created, from its `run_dir` field.
+struct RunLogLayer;
+
+impl<S> Layer<S> for RunLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "run" {
+            return;
+        }
+        let mut run_dir = RunDir(None);
+        attrs.record(&mut run_dir);
+        let (Some(run_dir), Some(span)) = (run_dir.0, ctx.span(id)) else {
+            return;
+        };
+        let path = run_dir.join(RUN_LOG_FILE);
+        match RunLog::open(&path) {
+            Ok(log) => span.extensions_mut().insert(log),
+            Err(err) => tracing::warn!("cannot open {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// The `run_dir` field of a run span.
+struct RunDir(Option<PathBuf>);
+
+impl Visit for RunDir {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "run_dir" {
+            self.0 = Some(PathBuf::from(format!("{:?}", value)));
+        }
+    }
+}
+
+/// Log of the innermost run span around the current event, if any.
+fn current_run_log() -> Option<RunLog> {
+    Span::current()
+        .with_subscriber(|(id, dispatch)| {
+            let registry = dispatch.downcast_ref::<Registry>()?;
+            let span = registry.span(id)?;
+            let log = span.scope().find_map(|s| s.extensions().get::<RunLog>().cloned());
+            log
+        })
+        .flatten()
+}
+
+/// Routes JSON events to the log of the run they belong to; events outside a run are dropped.
+struct RunLogWriter;
+
+impl<'a> MakeWriter<'a> for RunLogWriter {
+    type Writer = RunLogHandle;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RunLogHandle(current_run_log())
+    }
+}
+
+struct RunLogHandle(Option<RunLog>);
+
+impl Write for RunLogHandle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &self.0 {
+            // One locked write per formatted event, so parallel runs never interleave lines.
+            Some(RunLog(file)) => {
+                file.lock().unwrap_or_else(|e| e.into_inner()).write_all(buf)?;
+                Ok(buf.len())
+            }
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &self.0 {
+            Some(RunLog(file)) => file.lock().unwrap_or_else(|e| e.into_inner()).flush(),
+            None => Ok(()),
+        }
+    }
+}
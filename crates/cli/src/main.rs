@@ -1,6 +1,10 @@
 use anyhow::Result;
 use binary_slicer::commands;
-use clap::{Parser, Subcommand};
+use binary_slicer::logging::{self, LogOptions};
+use binary_slicer::output;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use ritual_core::rituals::hooks::display_command;
+use ritual_core::services::config::{ColorChoice, UserConfig};
 
 /// Slice-oriented reverse-engineering assistant CLI.
 ///
@@ -16,6 +20,27 @@ use clap::{Parser, Subcommand};
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
+
+    /// Log debug events (backend invocations, cache hits, timings) to stderr.
+    #[arg(long, global = true, default_value_t = false)]
+    verbose: bool,
+
+    /// Also write machine-readable events to `run.log.jsonl` in each ritual run directory.
+    #[arg(long, global = true, default_value_t = false)]
+    log_json: bool,
+
+    /// When another process holds the project lock, wait for it instead of failing.
+    #[arg(long, global = true, default_value_t = false, overrides_with = "no_wait")]
+    wait: bool,
+
+    /// Fail right away when another process holds the project lock (the default).
+    #[arg(long, global = true, default_value_t = false, overrides_with = "wait")]
+    no_wait: bool,
+
+    /// Print tables and statuses without colors (also honored: `NO_COLOR`, the user config's
+    /// `color`).
+    #[arg(long, global = true, default_value_t = false)]
+    no_color: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -36,6 +61,11 @@ enum Command {
         /// Optional project name. If omitted, the name is derived from the root directory.
         #[arg(long)]
         name: Option<String>,
+
+        /// Pre-populate slices, ritual specs, and docs from a built-in template (e.g.
+        /// `game-client`) or a template directory.
+        #[arg(long)]
+        template: Option<String>,
     },
 
     /// Show basic information about an existing Binary Slicer project.
@@ -63,7 +93,8 @@ enum Command {
         #[arg(long)]
         name: Option<String>,
 
-        /// Optional architecture hint (e.g., armv7, x86_64).
+        /// Architecture (e.g., armv7, x86_64). Defaults to the one in the binary's headers;
+        /// for a universal Mach-O, selects the slice runs analyze.
         #[arg(long)]
         arch: Option<String>,
 
@@ -74,6 +105,24 @@ enum Command {
         /// Skip hash computation (stores no hash).
         #[arg(long, default_value_t = false)]
         skip_hash: bool,
+
+        /// Register this file as a build of a binary group (created on first use).
+        #[arg(long)]
+        group: Option<String>,
+
+        /// Build label within the group (e.g., v1.3); must be unique per group.
+        #[arg(long, requires = "group")]
+        build_version: Option<String>,
+
+        /// Toolchain build identifier (GNU build-id, Mach-O UUID, PDB GUID). Defaults to
+        /// the one in the binary's headers.
+        #[arg(long, requires = "group")]
+        build_id: Option<String>,
+
+        /// PDB with the binary's symbols; its GUID/age must match the binary's CodeView
+        /// record. Without it, runs use a `.pdb` next to the binary when there is one.
+        #[arg(long)]
+        pdb: Option<String>,
     },
 
     /// Initialize a new slice record and its documentation scaffold.
@@ -93,6 +142,34 @@ enum Command {
         /// Optional default binary this slice is associated with.
         #[arg(long)]
         binary: Option<String>,
+
+        /// Scope the slice to this binary group (named `<name>@<group>`); it only takes runs
+        /// of the group's binaries.
+        #[arg(long)]
+        group: Option<String>,
+
+        /// Scope the slice to an explicit namespace (named `<name>@<namespace>`).
+        #[arg(long, conflicts_with = "group")]
+        namespace: Option<String>,
+    },
+
+    /// Copy a slice's description and roots into another binary group or namespace.
+    CloneSlice {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Slice to copy (`Networking`, or `Networking@x86` for a scoped slice).
+        #[arg(long)]
+        name: String,
+
+        /// Binary group the copy is scoped to.
+        #[arg(long, required_unless_present = "to_namespace")]
+        to_group: Option<String>,
+
+        /// Explicit namespace the copy is scoped to.
+        #[arg(long, conflicts_with = "to_group")]
+        to_namespace: Option<String>,
     },
 
     /// List all slices registered in the project database.
@@ -104,115 +181,197 @@ enum Command {
         /// Emit JSON instead of human-readable text.
         #[arg(long, default_value_t = false)]
         json: bool,
+
+        /// Include archived slices.
+        #[arg(long, default_value_t = false)]
+        include_archived: bool,
+
+        /// Only list entries carrying this tag.
+        #[arg(long)]
+        tag: Option<String>,
     },
 
-    /// List all binaries registered in the project database.
-    ListBinaries {
+    /// Show a slice: status, description, default binary, latest run with counts, doc, reports.
+    ShowSlice {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
+        /// Slice name (required).
+        #[arg(long)]
+        name: String,
+
         /// Emit JSON instead of human-readable text.
         #[arg(long, default_value_t = false)]
         json: bool,
     },
 
-    /// Regenerate slice docs for all slices registered in the project DB.
-    EmitSliceDocs {
+    /// Archive a slice (hidden from listings/docs; runs, analysis, and docs are preserved).
+    ArchiveSlice {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
+
+        /// Slice name (required).
+        #[arg(long)]
+        name: String,
     },
 
-    /// Regenerate slice JSON reports for all slices registered in the project DB.
-    EmitSliceReports {
+    /// Restore a previously archived slice.
+    RestoreSlice {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Prefer analysis runs for this binary when choosing data per slice (falls back to slice default or any).
+        /// Slice name (required).
         #[arg(long)]
-        binary: Option<String>,
+        name: String,
     },
 
-    /// Run a ritual spec (YAML/JSON) against a target binary (analysis stub for now).
-    RunRitual {
+    /// Change a slice's status (planned, draft, active, deprecated) and record the transition.
+    UpdateSliceStatus {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Path to the ritual spec (YAML/JSON).
+        /// Slice name (required).
         #[arg(long)]
-        file: String,
+        name: String,
 
-        /// Backend to use (overrides backend in the spec). Defaults to validate-only.
+        /// New status: planned, draft, active, or deprecated.
         #[arg(long)]
-        backend: Option<String>,
+        status: String,
 
-        /// Overwrite an existing ritual run output directory if present.
-        #[arg(long, default_value_t = false)]
-        force: bool,
+        /// Optional reason, kept in the status history.
+        #[arg(long)]
+        note: Option<String>,
     },
 
-    /// Clean ritual outputs under `outputs/binaries` with safety guardrails.
-    CleanOutputs {
+    /// Manually add a function to a slice (overrides analysis-derived membership).
+    SliceAddFunction {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Binary name to clean (required unless --all is set).
+        /// Slice name.
         #[arg(long)]
-        binary: Option<String>,
+        slice: String,
 
-        /// Specific ritual run to clean (requires --binary).
+        /// Binary name (or `sha256:<hash>` selector); membership is keyed by its hash.
         #[arg(long)]
-        ritual: Option<String>,
+        binary: String,
 
-        /// Clean all outputs/binaries (dangerous; requires --yes).
-        #[arg(long, default_value_t = false)]
-        all: bool,
+        /// Function address (0x-prefixed hex).
+        #[arg(long)]
+        address: String,
+    },
 
-        /// Required confirmation flag to avoid accidental deletion.
+    /// Manually exclude a function from a slice (overrides analysis-derived membership).
+    SliceRemoveFunction {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Slice name.
+        #[arg(long)]
+        slice: String,
+
+        /// Binary name (or `sha256:<hash>` selector); membership is keyed by its hash.
+        #[arg(long)]
+        binary: String,
+
+        /// Function address (0x-prefixed hex).
+        #[arg(long)]
+        address: String,
+
+        /// Drop the manual override instead, so analysis-derived membership applies again.
         #[arg(long, default_value_t = false)]
-        yes: bool,
+        reset: bool,
     },
 
-    /// List ritual runs discovered under outputs/binaries (human or JSON).
-    ListRitualRuns {
+    /// Add a root to the roots that define a slice (used by `run-ritual --slice`).
+    SliceAddRoot {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Optional binary name to filter runs.
+        /// Slice name.
         #[arg(long)]
-        binary: Option<String>,
+        slice: String,
+
+        /// Root to add (same syntax as spec roots, e.g. `net_init`, `0x401000`,
+        /// `regex:net_.*`).
+        #[arg(long = "slice-root", value_name = "ROOT")]
+        slice_root: String,
+    },
+
+    /// Remove a root from the roots that define a slice.
+    SliceRemoveRoot {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Slice name.
+        #[arg(long)]
+        slice: String,
+
+        /// Root to remove, as listed by `list-slice-roots`.
+        #[arg(long = "slice-root", value_name = "ROOT")]
+        slice_root: String,
+    },
+
+    /// List the roots that define a slice.
+    ListSliceRoots {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Slice name.
+        #[arg(long)]
+        slice: String,
 
         /// Emit JSON instead of human-readable text.
         #[arg(long, default_value_t = false)]
         json: bool,
     },
 
-    /// Show details for a ritual run (metadata + paths).
-    ShowRitualRun {
+    /// List the runs that fed a slice over time: member counts, membership deltas between
+    /// runs, and binary versions.
+    SliceHistory {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Binary name (required).
+        /// Slice name.
         #[arg(long)]
-        binary: String,
+        name: String,
 
-        /// Ritual name (required).
+        /// Emit JSON (for plotting) instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// List recorded slice membership (manual overrides and analysis-derived rows).
+    ListSliceMembers {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Slice name.
         #[arg(long)]
-        ritual: String,
+        slice: String,
+
+        /// Only show membership for this binary's build.
+        #[arg(long)]
+        binary: Option<String>,
 
         /// Emit JSON instead of human-readable text.
         #[arg(long, default_value_t = false)]
         json: bool,
     },
 
-    /// List ritual specs discovered under `rituals/` (human or JSON).
-    ListRitualSpecs {
+    /// List binary groups and the builds registered in each.
+    ListBinaryGroups {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
@@ -222,150 +381,1816 @@ enum Command {
         json: bool,
     },
 
-    /// Update the status of a ritual run recorded in the project DB.
-    UpdateRitualRunStatus {
+    /// List all binaries registered in the project database.
+    ListBinaries {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Binary name (required).
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+
+        /// Only list entries carrying this tag.
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Unregister a binary, deleting (--cascade) or keeping (--keep-runs) its ritual runs.
+    RemoveBinary {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
         #[arg(long)]
         binary: String,
 
-        /// Ritual name (required).
+        /// Also delete the binary's runs, their analysis, and outputs/binaries/<name>.
+        #[arg(long, default_value_t = false, conflicts_with = "keep_runs")]
+        cascade: bool,
+
+        /// Keep the binary's runs and outputs under its name.
+        #[arg(long, default_value_t = false)]
+        keep_runs: bool,
+    },
+
+    /// Rename a binary, renaming (--cascade) or keeping (--keep-runs) its ritual runs.
+    RenameBinary {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
         #[arg(long)]
-        ritual: String,
+        binary: String,
 
-        /// New status (one of: pending, running, succeeded, failed, canceled, stubbed).
+        /// New name for the binary.
         #[arg(long)]
-        status: String,
+        new_name: String,
 
-        /// Optional finished_at timestamp override (RFC3339). Defaults to now if omitted.
+        /// Also rename the binary's runs and move outputs/binaries/<name> to the new name.
+        #[arg(long, default_value_t = false, conflicts_with = "keep_runs")]
+        cascade: bool,
+
+        /// Keep the binary's runs and outputs under the old name.
+        #[arg(long, default_value_t = false)]
+        keep_runs: bool,
+    },
+
+    /// Give a function a human name and/or note (stored per binary hash; survives re-runs).
+    AnnotateFunction {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector) the function belongs to.
         #[arg(long)]
-        finished_at: Option<String>,
+        binary: String,
+
+        /// Function address (0x-prefixed hex).
+        #[arg(long)]
+        address: String,
+
+        /// Human-friendly name; preferred over backend names in slice docs/reports.
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Free-form note shown alongside the function in slice docs/reports.
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Remove the annotation instead of setting it.
+        #[arg(long, default_value_t = false, conflicts_with_all = ["name", "note"])]
+        clear: bool,
     },
 
-    /// Rerun an existing ritual using its normalized spec, storing results under a new name.
-    RerunRitual {
+    /// Import function names from another tool's symbol map (stored per binary hash; they
+    /// replace backend names in later runs).
+    ImportSymbols {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Binary name (required).
+        /// Binary name (or `sha256:<hash>` selector) the symbols belong to.
         #[arg(long)]
         binary: String,
 
-        /// Existing ritual run name to copy spec from.
+        /// Symbol map to read.
+        #[arg(long)]
+        file: String,
+
+        /// Symbol map format: map (IDA .map, nm/objcopy listings) or json (Frida exports).
+        #[arg(long, default_value = "map")]
+        format: String,
+
+        /// Address subtracted from every symbol (0x-prefixed hex), e.g. the module base of
+        /// Frida exports.
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Drop the binary's previously imported symbols first.
+        #[arg(long, default_value_t = false)]
+        replace: bool,
+    },
+
+    /// Attach a timestamped note to the latest run of a ritual (shown by `show-ritual-run`).
+    AnnotateRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name the ritual was run against.
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name.
         #[arg(long)]
         ritual: String,
 
-        /// New ritual run name to use for the rerun (required).
+        /// Finding to record (e.g. "vtable at 0x401200").
         #[arg(long)]
-        as_name: String,
+        note: String,
+    },
 
-        /// Backend to use (overrides backend in the spec). Defaults to validate-only.
+    /// Mark the latest run of a ritual as the baseline later runs are compared against.
+    ///
+    /// Runs of the ritual on the binary (or another binary of its group) then fail when they
+    /// regress: by default when in-slice functions drop by more than 10% or a root the
+    /// baseline resolved stops resolving (see the `regressions.*` config keys).
+    SetBaseline {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
         #[arg(long)]
-        backend: Option<String>,
+        binary: String,
 
-        /// Overwrite output directory if it already exists.
-        #[arg(long, default_value_t = false)]
-        force: bool,
+        /// Ritual name.
+        #[arg(long)]
+        ritual: String,
+
+        /// Remove the baseline instead of setting it.
+        #[arg(long)]
+        clear: bool,
     },
 
-    /// List available analysis backends (human or JSON).
-    ListBackends {
+    /// List function annotations for a binary.
+    ListAnnotations {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
+        #[arg(long)]
+        binary: String,
+
         /// Emit JSON instead of human-readable text.
         #[arg(long, default_value_t = false)]
         json: bool,
     },
 
-    /// Detect and configure a backend tool path, optionally setting default backend.
-    SetupBackend {
+    /// Store the analyst sections of slice docs (roots and notes) in the project database.
+    SyncSliceDocs {
         /// Project root directory. Defaults to the current working directory.
         #[arg(long, default_value = ".")]
         root: String,
 
-        /// Backend to configure (e.g., rizin, ghidra).
+        /// Only sync this slice's doc (default: every active slice).
         #[arg(long)]
-        backend: String,
+        slice: Option<String>,
 
-        /// Optional explicit tool path (e.g., path to rizin or analyzeHeadless).
-        #[arg(long)]
-        path: Option<String>,
+        /// Report what would change without writing to the database.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
 
-        /// Set this backend as the project default.
+        /// Emit JSON instead of human-readable text.
         #[arg(long, default_value_t = false)]
-        set_default: bool,
+        json: bool,
+    },
 
-        /// Append the resolved tool directory to your shell profile PATH.
+    /// Regenerate slice docs for all slices registered in the project DB.
+    EmitSliceDocs {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Only list evidence scoring at least this much (counts still include everything).
+        #[arg(long, default_value_t = 0.0)]
+        min_score: f64,
+
+        /// Embed a Mermaid call graph (`graph TD`, clustered by sub-slice) in each doc.
         #[arg(long, default_value_t = false)]
-        write_path: bool,
+        mermaid: bool,
+
+        /// Keep at most N functions in the Mermaid graph; the rest fold into an overflow node.
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = ritual_core::services::render::mermaid::MERMAID_MAX_NODES,
+            requires = "mermaid"
+        )]
+        mermaid_max_nodes: usize,
     },
-}
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let cmd = cli.command.unwrap_or(Command::Hello { slice: "DefaultSlice".to_string() });
+    /// Regenerate slice JSON reports for all slices registered in the project DB.
+    EmitSliceReports {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
 
-    match cmd {
-        Command::Hello { slice } => hello_command(&slice)?,
-        Command::InitProject { root, name } => commands::init_project_command(&root, name)?,
-        Command::ProjectInfo { root, json } => commands::project_info_command(&root, json)?,
-        Command::AddBinary { root, path, name, arch, hash, skip_hash } => {
-            commands::add_binary_command(&root, &path, name, arch, hash, skip_hash)?
-        }
-        Command::InitSlice { root, name, description, binary } => {
-            commands::init_slice_command(&root, &name, description, binary)?
-        }
-        Command::ListSlices { root, json } => commands::list_slices_command(&root, json)?,
-        Command::ListBinaries { root, json } => commands::list_binaries_command(&root, json)?,
-        Command::EmitSliceDocs { root } => commands::emit_slice_docs_command(&root)?,
-        Command::EmitSliceReports { root, binary } => {
-            commands::emit_slice_reports_command(&root, binary.as_deref())?
-        }
-        Command::RunRitual { root, file, backend, force } => {
-            commands::run_ritual_command(&root, &file, backend.as_deref(), force)?
+        /// Prefer analysis runs for this binary when choosing data per slice (falls back to slice default or any).
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Output format: `json` (JSON + HTML reports and DOT graphs) or `sarif` (SARIF 2.1.0).
+        #[arg(long, default_value = "json")]
+        format: commands::SliceReportFormat,
+
+        /// Group graph nodes into one cluster per sub-slice (and one for the slice).
+        #[arg(long, default_value_t = false)]
+        graph_cluster: bool,
+
+        /// Draw functions only, folding basic blocks into their function.
+        #[arg(long, default_value_t = false)]
+        graph_collapse_blocks: bool,
+
+        /// Merge parallel graph edges into one edge with a count.
+        #[arg(long, default_value_t = false)]
+        graph_dedup_edges: bool,
+
+        /// Keep at most N graph nodes; the rest fold into an overflow summary node.
+        #[arg(long, value_name = "N")]
+        graph_max_nodes: Option<usize>,
+    },
+
+    /// Write a ritual spec under `rituals/`, prompting for every field not given as a flag.
+    InitRitual {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Ritual name (also the spec's file name).
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Registered binary the ritual runs against.
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Root function: a name, `0x` address, or `regex:`/`demangled:`/`string:` pattern
+        /// (repeatable).
+        #[arg(long = "root-fn", value_name = "ROOT")]
+        roots: Vec<String>,
+
+        /// Backend the spec pins (left to the project default when omitted).
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Output formats to write (`json`, `dot`, `html`, `md`; all when omitted).
+        #[arg(long, value_delimiter = ',')]
+        outputs: Vec<String>,
+
+        /// Optional description written into the spec.
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Run the spec once it is written.
+        #[arg(long, default_value_t = false)]
+        run: bool,
+
+        /// Overwrite an existing spec with the same name.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+
+        /// Never prompt; fail when a required field is missing.
+        #[arg(long, default_value_t = false)]
+        no_input: bool,
+    },
+
+    /// Run a ritual spec (YAML/JSON) against a target binary (analysis stub for now).
+    RunRitual {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Path to the ritual spec (YAML/JSON).
+        #[arg(
+            long,
+            conflicts_with_all = ["spec_name", "slice"],
+            required_unless_present_any = ["spec_name", "slice"]
+        )]
+        file: Option<String>,
+
+        /// Run the spec under `rituals/` with this name (its `name:`, else its file name)
+        /// instead of passing `--file`.
+        #[arg(long, value_name = "NAME", conflicts_with = "slice")]
+        spec_name: Option<String>,
+
+        /// Analyze this slice from its roots (`slice-add-root`) and default binary instead
+        /// of a spec file.
+        #[arg(long, value_name = "SLICE")]
+        slice: Option<String>,
+
+        /// Backend to use (overrides backend in the spec). Defaults to validate-only.
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Overwrite an existing ritual run output directory if present.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+
+        /// Always invoke the backend instead of reusing a cached result from .ritual/cache.
+        #[arg(long, default_value_t = false)]
+        no_cache: bool,
+
+        /// Seed the slice with functions referencing a string containing this text (repeatable).
+        #[arg(long = "seed-from-string", value_name = "TEXT")]
+        seed_from_string: Vec<String>,
+
+        /// Value for a `${NAME}` variable in the spec (repeatable; overrides config
+        /// `variables.NAME`).
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        set: Vec<String>,
+
+        /// Sort output, record fixed timestamps (the spec's `epoch`, else 1970-01-01), and
+        /// store project-relative paths so repeated runs produce identical outputs.
+        #[arg(long, default_value_t = false)]
+        deterministic: bool,
+
+        /// Kill the backend's external tools after this many seconds and record the run as
+        /// canceled (overrides the spec's `timeout`).
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Exit non-zero when the analysis result fails validation (duplicate functions,
+        /// unmapped block targets, calls into the middle of functions, unresolved roots).
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+
+        /// Analyze the binary even if its file no longer matches the hash it was registered
+        /// with (the run records both hashes).
+        #[arg(long, default_value_t = false)]
+        allow_hash_mismatch: bool,
+    },
+
+    /// Run every spec under rituals/ against its binary, several at a time.
+    BatchRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Number of specs analyzed concurrently (defaults to the available CPUs).
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
+
+        /// Backend to use for every spec (overrides backends in the specs).
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Overwrite existing ritual run output directories.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+
+        /// Always invoke the backend instead of reusing cached results from .ritual/cache.
+        #[arg(long, default_value_t = false)]
+        no_cache: bool,
+
+        /// Sort output, record fixed timestamps (the spec's `epoch`, else 1970-01-01), and
+        /// store project-relative paths so repeated runs produce identical outputs.
+        #[arg(long, default_value_t = false)]
+        deterministic: bool,
+
+        /// Kill the backend's external tools after this many seconds and record the run as
+        /// canceled (overrides the spec's `timeout`).
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Exit non-zero when the analysis result fails validation (duplicate functions,
+        /// unmapped block targets, calls into the middle of functions, unresolved roots).
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+
+        /// Analyze the binary even if its file no longer matches the hash it was registered
+        /// with (the run records both hashes).
+        #[arg(long, default_value_t = false)]
+        allow_hash_mismatch: bool,
+
+        /// Value for a `${NAME}` variable in every spec (repeatable; overrides config
+        /// `variables.NAME`).
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        set: Vec<String>,
+
+        /// Emit the summary as JSON.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Export the latest run of a ritual as a portable `.tar.zst` archive.
+    ExportRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name the run belongs to.
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name to export.
+        #[arg(long)]
+        ritual: String,
+
+        /// Archive path to write (e.g. run.tar.zst).
+        #[arg(long)]
+        out: String,
+    },
+
+    /// Export the latest run of a ritual as flat tables (functions, call edges, basic blocks,
+    /// block edges, evidence) for pandas, DuckDB, and other data tools.
+    ExportTables {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name the run belongs to.
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name to export.
+        #[arg(long)]
+        ritual: String,
+
+        /// Table format: csv, or parquet (needs the `parquet` feature).
+        #[arg(long, default_value = "csv")]
+        format: String,
+
+        /// Directory to write `<table>.<format>` files into (created if missing).
+        #[arg(long)]
+        out: String,
+    },
+
+    /// Open the latest run of a ritual in an external tool configured as `tools.<name>`.
+    ///
+    /// Writes the run's functions and annotations to `annotations.json` in the run directory
+    /// and substitutes `{binary_path}`, `{annotations}`, `{run_dir}` etc. into the tool command.
+    OpenRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or id / hash prefix) the run belongs to.
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name to open.
+        #[arg(long)]
+        ritual: String,
+
+        /// Tool to launch (a `tools.<name>` config key, e.g. ghidra).
+        #[arg(long = "with", value_name = "TOOL")]
+        tool: String,
+
+        /// Print the command instead of launching it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import a run archive produced by `export-run`.
+    ImportRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Archive to import.
+        #[arg(long)]
+        file: String,
+
+        /// Overwrite existing outputs for the same binary/ritual.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+
+    /// Record another tool's analysis of a binary as a ritual run, without running a backend.
+    ///
+    /// Reads BinExport2 files (IDA Pro, Ghidra, Binary Ninja) or the JSON written by the bundled
+    /// `BinarySlicerExport.java` Ghidra script from an existing project.
+    ImportAnalysis {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary (name, id, or sha256: prefix) the export was made from.
+        #[arg(long)]
+        binary: String,
+
+        /// Export file to read.
+        #[arg(long)]
+        file: String,
+
+        /// Export format: binexport, ghidra-json, or ghidra-xml.
+        #[arg(long, default_value = "binexport")]
+        format: String,
+
+        /// Ritual name to record the run under.
+        #[arg(long = "as-ritual", value_name = "NAME")]
+        ritual: String,
+
+        /// Slice root matched against the imported functions (repeatable; same syntax as spec
+        /// roots, e.g. `net_init`, `0x401000`, `regex:net_.*`).
+        #[arg(long = "slice-root", value_name = "ROOT", required = true)]
+        slice_roots: Vec<String>,
+
+        /// Overwrite an existing run of the same ritual.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+
+    /// Query persisted analysis results, e.g. `functions where size > 512 and in_slice`.
+    Query {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name whose latest run is queried.
+        #[arg(long)]
+        ritual: String,
+
+        /// `<entity> [where <filter>] [order by <field> [desc]] [limit N]`; entities are
+        /// functions, edges, evidence, blocks, and xrefs.
+        query: String,
+
+        /// Emit JSON instead of a table.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Search function names, strings, and imports of every binary's latest runs.
+    Search {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Text to find (case-insensitive substring, or a regex with --regex).
+        pattern: String,
+
+        /// Only search runs of this binary (name or `sha256:<hash>` selector).
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Only search this kind of text: string, function, or import (repeatable).
+        #[arg(long = "kind")]
+        kinds: Vec<String>,
+
+        /// Treat the pattern as a regular expression.
+        #[arg(long, default_value_t = false)]
+        regex: bool,
+
+        /// Only search evidence produced by this backend or `backend/pass` (e.g. `capstone`,
+        /// `core/string-xrefs`, `plugin/score`); function names are skipped.
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Emit JSON instead of a table.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Show a function's disassembly, call edges, and evidence from its latest run.
+    ShowFunction {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
+        #[arg(long)]
+        binary: String,
+
+        /// Function address (0x-prefixed hex); an address inside a function selects it.
+        #[arg(long, conflicts_with = "name", required_unless_present = "name")]
+        address: Option<String>,
+
+        /// Function name (symbol or mangled name).
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Only consider runs of this ritual (default: the latest run recording the function).
+        #[arg(long)]
+        ritual: Option<String>,
+
+        /// Maximum instructions to list.
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+
+        /// Emit JSON instead of text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// List the functions of a ritual's latest run, filtered and paged in the project DB.
+    ListFunctions {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (or `sha256:<hash>` selector).
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual whose latest run to list.
+        #[arg(long)]
+        ritual: String,
+
+        /// Only functions in the slice.
+        #[arg(long, default_value_t = false)]
+        in_slice: bool,
+
+        /// Only functions of at least this many bytes.
+        #[arg(long)]
+        min_size: Option<u32>,
+
+        /// Only functions whose name or annotation name contains this (case-insensitive).
+        #[arg(long)]
+        name_contains: Option<String>,
+
+        /// Maximum functions to list.
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Matching functions to skip before listing.
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// Emit JSON instead of text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Re-run rituals whenever a registered binary or a `rituals/*` spec changes.
+    Watch {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Backend to use for re-runs (overrides backend in the spec).
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Overwrite existing ritual output directories (needed to re-run an existing ritual).
+        #[arg(long, default_value_t = false)]
+        force: bool,
+
+        /// Quiet period after a change before re-running, in milliseconds.
+        #[arg(long, default_value_t = 500)]
+        debounce_ms: u64,
+
+        /// Exit after this many change batches that re-ran something.
+        #[arg(long)]
+        max_cycles: Option<usize>,
+    },
+
+    /// Live view of binaries, slices, recent runs, and the current run's log.
+    ///
+    /// The full-screen view needs the `dashboard` build feature; `--once` and `--json`
+    /// print a single snapshot without it.
+    Dashboard {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Seconds between refreshes.
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+
+        /// Print one snapshot as text and exit.
+        #[arg(long, default_value_t = false)]
+        once: bool,
+
+        /// Print one snapshot as JSON and exit.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Remove the outputs of runs the `retention` policy no longer keeps.
+    ///
+    /// The newest `retention.keep_last` runs of each ritual (default 5), succeeded runs, and
+    /// baselines are kept; failed and stubbed runs older than `retention.failed_max_age_days`
+    /// go even when recent. Purged runs keep their analysis in the DB with status `purged`.
+    GcOutputs {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// List what would be purged without deleting anything.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Clean ritual outputs under `outputs/binaries` with safety guardrails.
+    CleanOutputs {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name to clean (required unless --all is set).
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Specific ritual run to clean (requires --binary).
+        #[arg(long)]
+        ritual: Option<String>,
+
+        /// Clean all outputs/binaries (dangerous; requires --yes).
+        #[arg(long, default_value_t = false)]
+        all: bool,
+
+        /// Required confirmation flag to avoid accidental deletion.
+        #[arg(long, default_value_t = false)]
+        yes: bool,
+    },
+
+    /// List ritual runs discovered under outputs/binaries (human or JSON).
+    ListRitualRuns {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Optional binary name to filter runs.
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+
+        /// Only list entries carrying this tag.
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Show details for a ritual run (metadata + paths).
+    ShowRitualRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (required).
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name (required).
+        #[arg(long)]
+        ritual: String,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Check a run's output files against the checksums in its manifest.json.
+    VerifyRun {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (required).
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual name (required).
+        #[arg(long)]
+        ritual: String,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Compare two ritual runs (functions, call edges, evidence) from the project DB.
+    DiffRuns {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name of the base run (required).
+        #[arg(long)]
+        binary: String,
+
+        /// Base ritual run name (required).
+        #[arg(long)]
+        ritual: String,
+
+        /// Ritual run name to compare against (required).
+        #[arg(long)]
+        against: String,
+
+        /// Binary name of the run to compare against. Defaults to --binary.
+        #[arg(long)]
+        against_binary: Option<String>,
+
+        /// Fail unless both runs are deterministic, share spec and binary hashes, and match.
+        #[arg(long, default_value_t = false)]
+        assert_reproducible: bool,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Pair the functions of two ritual runs by fingerprint similarity (cross-build identity).
+    MatchFunctions {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name of the `--from` run (required).
+        #[arg(long)]
+        binary: String,
+
+        /// Ritual run to match from (required).
+        #[arg(long)]
+        from: String,
+
+        /// Ritual run to match to (required).
+        #[arg(long)]
+        to: String,
+
+        /// Binary name of the `--to` run. Defaults to --binary.
+        #[arg(long)]
+        to_binary: Option<String>,
+
+        /// Minimum fingerprint similarity (0-1) for two functions to be paired.
+        #[arg(long, default_value_t = ritual_core::analysis::matching::DEFAULT_MIN_SIMILARITY)]
+        min_similarity: f64,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// List ritual specs discovered under `rituals/` (human or JSON).
+    ListRitualSpecs {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Update the status of a ritual run recorded in the project DB.
+    UpdateRitualRunStatus {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (with --ritual; updates the latest run of the pair).
+        #[arg(long, required_unless_present = "run_id", requires = "ritual")]
+        binary: Option<String>,
+
+        /// Ritual name (with --binary).
+        #[arg(long, requires = "binary")]
+        ritual: Option<String>,
+
+        /// Update this run (ids are listed by `list-ritual-runs`) instead of a binary/ritual pair.
+        #[arg(long, conflicts_with_all = ["binary", "ritual"])]
+        run_id: Option<i64>,
+
+        /// Update every run of the binary/ritual pair, not only the latest.
+        #[arg(long, default_value_t = false, requires = "binary")]
+        all_runs: bool,
+
+        /// New status (one of: pending, running, succeeded, failed, canceled, stubbed, outdated,
+        /// purged).
+        #[arg(long)]
+        status: String,
+
+        /// Optional finished_at timestamp override (RFC3339). Defaults to now if omitted.
+        #[arg(long)]
+        finished_at: Option<String>,
+    },
+
+    /// Rerun an existing ritual using its normalized spec, storing results under a new name.
+    RerunRitual {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Binary name (required).
+        #[arg(long)]
+        binary: String,
+
+        /// Existing ritual run name to copy spec from.
+        #[arg(long)]
+        ritual: String,
+
+        /// New ritual run name to use for the rerun (required).
+        #[arg(long)]
+        as_name: String,
+
+        /// Backend to use (overrides backend in the spec). Defaults to validate-only.
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Overwrite output directory if it already exists.
+        #[arg(long, default_value_t = false)]
+        force: bool,
+
+        /// Always invoke the backend instead of reusing a cached result from .ritual/cache.
+        #[arg(long, default_value_t = false)]
+        no_cache: bool,
+
+        /// Sort output, record fixed timestamps (the spec's `epoch`, else 1970-01-01), and
+        /// store project-relative paths so repeated runs produce identical outputs.
+        #[arg(long, default_value_t = false)]
+        deterministic: bool,
+
+        /// Kill the backend's external tools after this many seconds and record the run as
+        /// canceled (overrides the spec's `timeout`).
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Exit non-zero when the analysis result fails validation (duplicate functions,
+        /// unmapped block targets, calls into the middle of functions, unresolved roots).
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+
+        /// Analyze the binary even if its file no longer matches the hash it was registered
+        /// with (the run records both hashes).
+        #[arg(long, default_value_t = false)]
+        allow_hash_mismatch: bool,
+
+        /// Call depth limit (default: the repeated run's analysis options).
+        #[arg(long)]
+        max_depth: Option<u32>,
+
+        /// Per-function instruction budget for disassembling backends (default: the repeated
+        /// run's).
+        #[arg(long)]
+        max_instructions: Option<usize>,
+
+        /// Instruction budget over the whole binary (default: the repeated run's).
+        #[arg(long)]
+        max_total_instructions: Option<usize>,
+
+        /// Collect string evidence (default: the repeated run's).
+        #[arg(long, value_name = "BOOL")]
+        include_strings: Option<bool>,
+
+        /// Collect import evidence (default: the repeated run's).
+        #[arg(long, value_name = "BOOL")]
+        include_imports: Option<bool>,
+    },
+
+    /// List analysis backends with their capabilities and availability (human or JSON).
+    ListBackends {
+        /// Resolve tool paths from this project's config (`backends.*`) before the environment.
+        #[arg(long)]
+        root: Option<String>,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Run every compiled backend against a synthetic binary and print a pass/fail matrix.
+    SelfTest {
+        /// Only exercise this backend (default: all compiled backends).
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Keep the artifacts in this directory instead of a fresh temp directory.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Show entries and disk usage of the analysis cache (.ritual/cache).
+    CacheStats {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Remove all cached analysis results.
+    CacheClear {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+    },
+
+    /// Detect and configure a backend tool path, optionally setting default backend.
+    SetupBackend {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Backend to configure (e.g., rizin, ghidra).
+        #[arg(long)]
+        backend: String,
+
+        /// Optional explicit tool path (e.g., path to rizin or analyzeHeadless).
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Set this backend as the project default.
+        #[arg(long, default_value_t = false)]
+        set_default: bool,
+
+        /// Append the resolved tool directory to your shell profile PATH.
+        #[arg(long, default_value_t = false)]
+        write_path: bool,
+    },
+
+    /// Read or change project config keys (`.ritual/project.json`; user-level settings from
+    /// `~/.config/binary-slicer/config.toml` are not shown).
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Tag slices, binaries, and runs (e.g. `baseline`, `obfuscated`, `priority`).
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Re-hash registered binaries and report drift from the hashes in the project DB.
+    VerifyBinaries {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Record the current hash of changed (or unhashed) binaries.
+        #[arg(long, default_value_t = false)]
+        update: bool,
+
+        /// Mark runs whose binary hash no longer matches the file as `outdated`.
+        #[arg(long, default_value_t = false)]
+        mark_outdated: bool,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Check backends, the DB schema, project config and directories, and run outputs, with
+    /// a fix for each problem found.
+    Doctor {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Apply the safe fixes: create missing directories and DB, migrate an older schema,
+        /// and mark runs whose outputs are gone `purged`.
+        #[arg(long, default_value_t = false)]
+        fix: bool,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Validate a ritual spec (structure, roots, backend, binary registration) without running it.
+    ValidateSpec {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Path to ritual spec file (YAML or JSON).
+        #[arg(long)]
+        file: String,
+
+        /// Backend the spec would run with (overrides backend in the spec).
+        #[arg(long)]
+        backend: Option<String>,
+
+        /// Value for a `${NAME}` variable in the spec (repeatable; overrides config
+        /// `variables.NAME`).
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        set: Vec<String>,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Print the JSON Schema for ritual specs (for editor validation/autocomplete).
+    Schema {
+        /// Write the schema to this file instead of stdout.
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Keep the project DB open and answer JSON-RPC requests over stdio or a unix socket.
+    Serve {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Listen on this unix socket path instead of stdio.
+        #[arg(long, value_name = "PATH")]
+        socket: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TagAction {
+    /// Attach a tag to a slice, a binary, or a run (--binary plus --ritual).
+    Add {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Tag name (no spaces or commas).
+        tag: String,
+
+        /// Tag this slice.
+        #[arg(long, conflicts_with_all = ["binary", "ritual"])]
+        slice: Option<String>,
+
+        /// Tag this binary (name or `sha256:<hash>` selector), or with --ritual its run.
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Tag the latest run of this ritual against --binary.
+        #[arg(long, requires = "binary")]
+        ritual: Option<String>,
+    },
+
+    /// Remove a tag from a slice, a binary, or a run (--binary plus --ritual).
+    Remove {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Tag name.
+        tag: String,
+
+        /// Untag this slice.
+        #[arg(long, conflicts_with_all = ["binary", "ritual"])]
+        slice: Option<String>,
+
+        /// Untag this binary (name or `sha256:<hash>` selector), or with --ritual its run.
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Untag the latest run of this ritual against --binary.
+        #[arg(long, requires = "binary")]
+        ritual: Option<String>,
+    },
+
+    /// List tags and what carries them.
+    List {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Only this kind of target: slice, binary, or run.
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Only this tag.
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// List every config key and its value.
+    List {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Emit JSON instead of human-readable text.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Print the value of one key (e.g., default_backend, backends.rizin).
+    Get {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Dotted config key.
+        key: String,
+    },
+
+    /// Validate and set a key (e.g., `config set default_backend rizin`).
+    Set {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Dotted config key.
+        key: String,
+
+        /// New value.
+        value: String,
+    },
+
+    /// Clear an optional key.
+    Unset {
+        /// Project root directory. Defaults to the current working directory.
+        #[arg(long, default_value = ".")]
+        root: String,
+
+        /// Dotted config key.
+        key: String,
+    },
+}
+
+fn main() -> Result<()> {
+    // Help and usage errors follow the user config's `color` (see `services::config`).
+    let color_choice = UserConfig::load().ok().and_then(|config| config.color).unwrap_or_default();
+    let color = match color_choice {
+        ColorChoice::Always => clap::ColorChoice::Always,
+        ColorChoice::Never => clap::ColorChoice::Never,
+        ColorChoice::Auto => clap::ColorChoice::Auto,
+    };
+    let matches = Cli::command().color(color).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    logging::init(LogOptions { verbose: cli.verbose, json: cli.log_json });
+    output::init(cli.no_color, color_choice);
+    let _span =
+        tracing::info_span!("command", subcommand = %matches.subcommand_name().unwrap_or("hello"))
+            .entered();
+    let cmd = cli.command.unwrap_or(Command::Hello { slice: "DefaultSlice".to_string() });
+    let _lock = match cmd.lock_root() {
+        Some(root) => {
+            let argv: Vec<String> = std::iter::once("binary-slicer".to_string())
+                .chain(std::env::args().skip(1))
+                .collect();
+            commands::acquire_project_lock(root, &display_command(&argv), cli.wait)?
+        }
+        None => None,
+    };
+
+    match cmd {
+        Command::Hello { slice } => hello_command(&slice)?,
+        Command::InitProject { root, name, template } => {
+            commands::init_project_with_template_command(&root, name, template.as_deref())?
+        }
+        Command::ProjectInfo { root, json } => commands::project_info_command(&root, json)?,
+        Command::AddBinary {
+            root,
+            path,
+            name,
+            arch,
+            hash,
+            skip_hash,
+            group,
+            build_version,
+            build_id,
+            pdb,
+        } => {
+            let group = group.map(|group| commands::BinaryGroupOptions {
+                group,
+                version: build_version,
+                build_id,
+            });
+            let options = commands::AddBinaryOptions { group, pdb };
+            commands::add_binary_with_options(&root, &path, name, arch, hash, skip_hash, options)?
+        }
+        Command::InitSlice { root, name, description, binary, group, namespace } => {
+            commands::init_slice_in_namespace_command(
+                &root,
+                &name,
+                description,
+                binary,
+                group.as_deref(),
+                namespace.as_deref(),
+            )?
+        }
+        Command::CloneSlice { root, name, to_group, to_namespace } => {
+            commands::clone_slice_command(
+                &root,
+                &name,
+                to_group.as_deref(),
+                to_namespace.as_deref(),
+            )?
+        }
+        Command::ListSlices { root, json, include_archived, tag } => {
+            commands::list_slices_command(&root, json, include_archived, tag.as_deref())?
+        }
+        Command::ShowSlice { root, name, json } => {
+            commands::show_slice_command(&root, &name, json)?
+        }
+        Command::ArchiveSlice { root, name } => commands::archive_slice_command(&root, &name)?,
+        Command::RestoreSlice { root, name } => commands::restore_slice_command(&root, &name)?,
+        Command::UpdateSliceStatus { root, name, status, note } => {
+            commands::update_slice_status_command(&root, &name, &status, note.as_deref())?
+        }
+        Command::SliceAddFunction { root, slice, binary, address } => {
+            commands::slice_add_function_command(&root, &slice, &binary, &address)?
+        }
+        Command::SliceRemoveFunction { root, slice, binary, address, reset } => {
+            commands::slice_remove_function_command(&root, &slice, &binary, &address, reset)?
+        }
+        Command::SliceAddRoot { root, slice, slice_root } => {
+            commands::slice_add_root_command(&root, &slice, &slice_root)?
+        }
+        Command::SliceRemoveRoot { root, slice, slice_root } => {
+            commands::slice_remove_root_command(&root, &slice, &slice_root)?
+        }
+        Command::ListSliceRoots { root, slice, json } => {
+            commands::list_slice_roots_command(&root, &slice, json)?
+        }
+        Command::SliceHistory { root, name, json } => {
+            commands::slice_history_command(&root, &name, json)?
+        }
+        Command::ListSliceMembers { root, slice, binary, json } => {
+            commands::list_slice_members_command(&root, &slice, binary.as_deref(), json)?
+        }
+        Command::ListBinaryGroups { root, json } => {
+            commands::list_binary_groups_command(&root, json)?
+        }
+        Command::ListBinaries { root, json, tag } => {
+            commands::list_binaries_command(&root, json, tag.as_deref())?
+        }
+        Command::RemoveBinary { root, binary, cascade, keep_runs } => {
+            let runs = commands::RunHandling::from_flags(cascade, keep_runs);
+            commands::remove_binary_command(&root, &binary, runs)?
+        }
+        Command::RenameBinary { root, binary, new_name, cascade, keep_runs } => {
+            let runs = commands::RunHandling::from_flags(cascade, keep_runs);
+            commands::rename_binary_command(&root, &binary, &new_name, runs)?
+        }
+        Command::AnnotateFunction { root, binary, address, name, note, clear } => {
+            commands::annotate_function_command(&root, &binary, &address, name, note, clear)?
+        }
+        Command::ImportSymbols { root, binary, file, format, base, replace } => {
+            commands::import_symbols_command(
+                &root,
+                &binary,
+                &file,
+                &format,
+                base.as_deref(),
+                replace,
+            )?
+        }
+        Command::AnnotateRun { root, binary, ritual, note } => {
+            commands::annotate_run_command(&root, &binary, &ritual, &note)?
+        }
+        Command::SetBaseline { root, binary, ritual, clear } => {
+            commands::set_baseline_command(&root, &binary, &ritual, clear)?
+        }
+        Command::ListAnnotations { root, binary, json } => {
+            commands::list_annotations_command(&root, &binary, json)?
+        }
+        Command::SyncSliceDocs { root, slice, dry_run, json } => {
+            commands::sync_slice_docs_command(&root, slice.as_deref(), dry_run, json)?
+        }
+        Command::EmitSliceDocs { root, min_score, mermaid, mermaid_max_nodes } => {
+            let mermaid = mermaid.then_some(mermaid_max_nodes);
+            commands::emit_slice_docs_command(&root, min_score, mermaid)?
+        }
+        Command::EmitSliceReports {
+            root,
+            binary,
+            format,
+            graph_cluster,
+            graph_collapse_blocks,
+            graph_dedup_edges,
+            graph_max_nodes,
+        } => {
+            let graph = ritual_core::analysis::graph::DotOptions {
+                cluster: graph_cluster,
+                collapse_blocks: graph_collapse_blocks,
+                dedup_edges: graph_dedup_edges,
+                max_nodes: graph_max_nodes,
+            };
+            commands::emit_slice_reports_command(&root, binary.as_deref(), format, &graph)?
+        }
+        Command::InitRitual {
+            root,
+            name,
+            binary,
+            roots,
+            backend,
+            outputs,
+            description,
+            run,
+            force,
+            no_input,
+        } => {
+            let options = commands::InitRitualOptions {
+                name,
+                binary,
+                roots,
+                backend,
+                outputs,
+                description,
+                run,
+                force,
+                no_input,
+            };
+            commands::init_ritual_command(&root, options)?
+        }
+        Command::RunRitual {
+            root,
+            file,
+            spec_name,
+            slice,
+            backend,
+            force,
+            no_cache,
+            seed_from_string,
+            set,
+            deterministic,
+            timeout,
+            strict,
+            allow_hash_mismatch,
+        } => {
+            let flags = commands::RunFlags {
+                force,
+                no_cache,
+                deterministic,
+                timeout,
+                strict,
+                allow_hash_mismatch,
+            };
+            if let Some(slice) = slice {
+                commands::run_slice_ritual_command(
+                    &root,
+                    &slice,
+                    backend.as_deref(),
+                    flags,
+                    &seed_from_string,
+                    &set,
+                )?
+            } else {
+                let file = match spec_name {
+                    Some(name) => commands::find_ritual_spec(&root, &name)?,
+                    None => file.unwrap_or_default(),
+                };
+                commands::run_ritual_command(
+                    &root,
+                    &file,
+                    backend.as_deref(),
+                    flags,
+                    &seed_from_string,
+                    &set,
+                )?
+            }
+        }
+        Command::BatchRun {
+            root,
+            jobs,
+            backend,
+            force,
+            no_cache,
+            deterministic,
+            timeout,
+            strict,
+            allow_hash_mismatch,
+            set,
+            json,
+        } => {
+            let flags = commands::RunFlags {
+                force,
+                no_cache,
+                deterministic,
+                timeout,
+                strict,
+                allow_hash_mismatch,
+            };
+            commands::batch_run_command(&root, jobs, backend.as_deref(), flags, &set, json)?
+        }
+        Command::ExportRun { root, binary, ritual, out } => {
+            commands::export_run_command(&root, &binary, &ritual, &out)?
+        }
+        Command::ExportTables { root, binary, ritual, format, out } => {
+            commands::export_tables_command(&root, &binary, &ritual, &format, &out)?
+        }
+        Command::OpenRun { root, binary, ritual, tool, dry_run } => {
+            commands::open_run_command(&root, &binary, &ritual, &tool, dry_run)?
+        }
+        Command::ImportAnalysis { root, binary, file, format, ritual, slice_roots, force } => {
+            commands::import_analysis_command(
+                &root,
+                &binary,
+                &file,
+                &format,
+                &ritual,
+                &slice_roots,
+                force,
+            )?
+        }
+        Command::Query { root, binary, ritual, query, json } => {
+            commands::query_command(&root, &binary, &ritual, &query, json)?
+        }
+        Command::Search { root, pattern, binary, kinds, regex, source, json } => {
+            commands::search_command(
+                &root,
+                &pattern,
+                binary.as_deref(),
+                &kinds,
+                regex,
+                source.as_deref(),
+                json,
+            )?
+        }
+        Command::ShowFunction { root, binary, address, name, ritual, limit, json } => {
+            commands::show_function_command(
+                &root,
+                &binary,
+                address.as_deref(),
+                name.as_deref(),
+                ritual.as_deref(),
+                limit,
+                json,
+            )?
+        }
+        Command::ListFunctions {
+            root,
+            binary,
+            ritual,
+            in_slice,
+            min_size,
+            name_contains,
+            limit,
+            offset,
+            json,
+        } => {
+            let filter = ritual_core::db::FunctionFilter {
+                in_slice,
+                min_size,
+                name_contains,
+                limit,
+                offset,
+            };
+            commands::list_functions_command(&root, &binary, &ritual, &filter, json)?
+        }
+        Command::ImportRun { root, file, force } => {
+            commands::import_run_command(&root, &file, force)?
+        }
+        Command::Watch { root, backend, force, debounce_ms, max_cycles } => {
+            commands::watch_command(&root, backend.as_deref(), force, debounce_ms, max_cycles)?
+        }
+        Command::Dashboard { root, interval, once, json } => {
+            commands::dashboard_command(&root, interval, once, json)?
+        }
+        Command::GcOutputs { root, dry_run, json } => {
+            commands::gc_outputs_command(&root, dry_run, json)?
         }
         Command::CleanOutputs { root, binary, ritual, all, yes } => {
             commands::clean_outputs_command(&root, binary.as_deref(), ritual.as_deref(), all, yes)?
         }
-        Command::ListRitualRuns { root, binary, json } => {
-            commands::list_ritual_runs_command(&root, binary.as_deref(), json)?
+        Command::ListRitualRuns { root, binary, json, tag } => {
+            commands::list_ritual_runs_command(&root, binary.as_deref(), json, tag.as_deref())?
         }
         Command::ShowRitualRun { root, binary, ritual, json } => {
             commands::show_ritual_run_command(&root, &binary, &ritual, json)?
         }
-        Command::ListRitualSpecs { root, json } => {
-            commands::list_ritual_specs_command(&root, json)?
+        Command::VerifyRun { root, binary, ritual, json } => {
+            commands::verify_run_command(&root, &binary, &ritual, json)?
         }
-        Command::UpdateRitualRunStatus { root, binary, ritual, status, finished_at } => {
-            commands::update_ritual_run_status_command(
+        Command::DiffRuns {
+            root,
+            binary,
+            ritual,
+            against,
+            against_binary,
+            assert_reproducible,
+            json,
+        } => commands::diff_runs_command(
+            &root,
+            &binary,
+            &ritual,
+            &against,
+            against_binary.as_deref(),
+            assert_reproducible,
+            json,
+        )?,
+        Command::MatchFunctions { root, binary, from, to, to_binary, min_similarity, json } => {
+            commands::match_functions_command(
                 &root,
                 &binary,
-                &ritual,
-                &status,
-                finished_at,
+                &from,
+                &to,
+                to_binary.as_deref(),
+                min_similarity,
+                json,
             )?
         }
-        Command::RerunRitual { root, binary, ritual, as_name, backend, force } => {
+        Command::ListRitualSpecs { root, json } => {
+            commands::list_ritual_specs_command(&root, json)?
+        }
+        Command::UpdateRitualRunStatus {
+            root,
+            binary,
+            ritual,
+            run_id,
+            all_runs,
+            status,
+            finished_at,
+        } => {
+            let target = match (run_id, binary, ritual) {
+                (Some(run_id), _, _) => commands::RunTarget::Id(run_id),
+                (None, Some(binary), Some(ritual)) => {
+                    commands::RunTarget::Named { binary, ritual, all_runs }
+                }
+                _ => unreachable!("clap requires --run-id or --binary with --ritual"),
+            };
+            commands::update_ritual_run_status_command(&root, &target, &status, finished_at)?
+        }
+        Command::RerunRitual {
+            root,
+            binary,
+            ritual,
+            as_name,
+            backend,
+            force,
+            no_cache,
+            deterministic,
+            timeout,
+            strict,
+            allow_hash_mismatch,
+            max_depth,
+            max_instructions,
+            max_total_instructions,
+            include_strings,
+            include_imports,
+        } => {
+            let flags = commands::RunFlags {
+                force,
+                no_cache,
+                deterministic,
+                timeout,
+                strict,
+                allow_hash_mismatch,
+            };
+            let overrides = commands::AnalysisOverrides {
+                max_depth,
+                max_instructions,
+                max_total_instructions,
+                include_strings,
+                include_imports,
+            };
             commands::rerun_ritual_command(
                 &root,
                 &binary,
                 &ritual,
                 &as_name,
                 backend.as_deref(),
-                force,
+                flags,
+                overrides,
             )?
         }
-        Command::ListBackends { json } => commands::list_backends_command(json)?,
+        Command::ListBackends { root, json } => {
+            commands::list_backends_with_root_command(root.as_deref(), json)?
+        }
+        Command::SelfTest { backend, out, json } => {
+            commands::self_test_command(backend.as_deref(), out.as_deref(), json)?
+        }
+        Command::CacheStats { root, json } => commands::cache_stats_command(&root, json)?,
+        Command::CacheClear { root } => commands::cache_clear_command(&root)?,
         Command::SetupBackend { root, backend, path, set_default, write_path } => {
             commands::setup_backend_command(&root, &backend, path, set_default, write_path)?
         }
+        Command::Config { action } => match action {
+            ConfigAction::List { root, json } => commands::config_list_command(&root, json)?,
+            ConfigAction::Get { root, key } => commands::config_get_command(&root, &key)?,
+            ConfigAction::Set { root, key, value } => {
+                commands::config_set_command(&root, &key, &value)?
+            }
+            ConfigAction::Unset { root, key } => commands::config_unset_command(&root, &key)?,
+        },
+        Command::Tag { action } => match action {
+            TagAction::Add { root, tag, slice, binary, ritual } => {
+                let target = commands::TagTarget::from_args(slice, binary, ritual)?;
+                commands::tag_add_command(&root, &target, &tag)?
+            }
+            TagAction::Remove { root, tag, slice, binary, ritual } => {
+                let target = commands::TagTarget::from_args(slice, binary, ritual)?;
+                commands::tag_remove_command(&root, &target, &tag)?
+            }
+            TagAction::List { root, kind, tag, json } => {
+                commands::tag_list_command(&root, kind.as_deref(), tag.as_deref(), json)?
+            }
+        },
+        Command::VerifyBinaries { root, update, mark_outdated, json } => {
+            commands::verify_binaries_command(&root, update, mark_outdated, json)?
+        }
+        Command::Doctor { root, fix, json } => commands::doctor_command(&root, fix, json)?,
+        Command::ValidateSpec { root, file, backend, set, json } => {
+            commands::validate_spec_command(&root, &file, backend.as_deref(), &set, json)?
+        }
+        Command::Schema { out } => commands::spec_schema_command(out.as_deref())?,
+        Command::Serve { root, socket } => commands::serve_command(&root, socket.as_deref())?,
     }
 
     Ok(())
 }
 
+impl Command {
+    /// Project root of commands that write to the project; they hold the project lock while
+    /// they run. Read-only commands bypass it, and `watch` and `serve` lock around each run
+    /// instead of for their whole lifetime.
+    fn lock_root(&self) -> Option<&str> {
+        match self {
+            Command::InitProject { root, .. }
+            | Command::AddBinary { root, .. }
+            | Command::InitSlice { root, .. }
+            | Command::CloneSlice { root, .. }
+            | Command::ArchiveSlice { root, .. }
+            | Command::RestoreSlice { root, .. }
+            | Command::UpdateSliceStatus { root, .. }
+            | Command::SliceAddFunction { root, .. }
+            | Command::SliceRemoveFunction { root, .. }
+            | Command::SliceAddRoot { root, .. }
+            | Command::SliceRemoveRoot { root, .. }
+            | Command::RemoveBinary { root, .. }
+            | Command::RenameBinary { root, .. }
+            | Command::AnnotateFunction { root, .. }
+            | Command::ImportSymbols { root, .. }
+            | Command::AnnotateRun { root, .. }
+            | Command::SetBaseline { root, .. }
+            | Command::EmitSliceDocs { root, .. }
+            | Command::SyncSliceDocs { root, .. }
+            | Command::EmitSliceReports { root, .. }
+            | Command::InitRitual { root, .. }
+            | Command::RunRitual { root, .. }
+            | Command::BatchRun { root, .. }
+            | Command::OpenRun { root, .. }
+            | Command::ImportAnalysis { root, .. }
+            | Command::ImportRun { root, .. }
+            | Command::CleanOutputs { root, .. }
+            | Command::GcOutputs { root, .. }
+            | Command::UpdateRitualRunStatus { root, .. }
+            | Command::RerunRitual { root, .. }
+            | Command::CacheClear { root }
+            | Command::SetupBackend { root, .. } => Some(root),
+            Command::VerifyBinaries { root, update, mark_outdated, .. } => {
+                (*update || *mark_outdated).then_some(root.as_str())
+            }
+            Command::Doctor { root, fix, .. } => fix.then_some(root.as_str()),
+            Command::Config { action } => match action {
+                ConfigAction::Set { root, .. } | ConfigAction::Unset { root, .. } => Some(root),
+                ConfigAction::List { .. } | ConfigAction::Get { .. } => None,
+            },
+            Command::Tag { action } => match action {
+                TagAction::Add { root, .. } | TagAction::Remove { root, .. } => Some(root),
+                TagAction::List { .. } => None,
+            },
+            Command::Hello { .. }
+            | Command::ProjectInfo { .. }
+            | Command::ListSlices { .. }
+            | Command::ShowSlice { .. }
+            | Command::ListSliceMembers { .. }
+            | Command::ListSliceRoots { .. }
+            | Command::SliceHistory { .. }
+            | Command::ListBinaryGroups { .. }
+            | Command::ListBinaries { .. }
+            | Command::ListAnnotations { .. }
+            | Command::ExportRun { .. }
+            | Command::ExportTables { .. }
+            | Command::Query { .. }
+            | Command::Search { .. }
+            | Command::ShowFunction { .. }
+            | Command::ListFunctions { .. }
+            | Command::Watch { .. }
+            | Command::Dashboard { .. }
+            | Command::ListRitualRuns { .. }
+            | Command::ShowRitualRun { .. }
+            | Command::VerifyRun { .. }
+            | Command::DiffRuns { .. }
+            | Command::MatchFunctions { .. }
+            | Command::ListRitualSpecs { .. }
+            | Command::ListBackends { .. }
+            | Command::SelfTest { .. }
+            | Command::CacheStats { .. }
+            | Command::ValidateSpec { .. }
+            | Command::Schema { .. }
+            | Command::Serve { .. } => None,
+        }
+    }
+}
+
 /// "Hello" smoke-test command.
 fn hello_command(slice_name: &str) -> Result<()> {
     // NOTE: crate name `ritual-core` in Cargo.toml is `ritual_core` in Rust code.
@@ -0,0 +1,232 @@
+//! Human-readable output shared by the list commands: column-aligned tables, status colors,
+//! and relative timestamps. JSON output never goes through here.
+//!
+//! Colors are ANSI escapes decided once per process by [`init`]: off with `--no-color`; else
+//! as the user config's `color` says, where `auto` (the default) colors only when stdout is a
+//! terminal and `NO_COLOR` is unset or empty. Callers that never call [`init`] (the library
+//! API, tests) get plain text.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Utc};
+use ritual_core::db::SliceStatus;
+use ritual_core::services::config::ColorChoice;
+
+static COLORS: AtomicBool = AtomicBool::new(false);
+
+/// Decide whether this process colors its output (see the module docs).
+pub fn init(no_color: bool, choice: ColorChoice) {
+    let colors = use_colors(no_color, choice, std::io::stdout().is_terminal());
+    COLORS.store(colors, Ordering::Relaxed);
+}
+
+/// Whether output is colored given `--no-color`, the configured choice, and whether stdout is
+/// a terminal.
+pub fn use_colors(no_color: bool, choice: ColorChoice, terminal: bool) -> bool {
+    if no_color {
+        return false;
+    }
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => terminal && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+    }
+}
+
+/// Whether [`init`] turned colors on.
+pub fn colors_enabled() -> bool {
+    COLORS.load(Ordering::Relaxed)
+}
+
+/// Foreground color of a table cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Green,
+    Red,
+    Yellow,
+    Blue,
+    Cyan,
+    Dim,
+}
+
+impl Tone {
+    fn code(self) -> &'static str {
+        match self {
+            Tone::Green => "32",
+            Tone::Red => "31",
+            Tone::Yellow => "33",
+            Tone::Blue => "34",
+            Tone::Cyan => "36",
+            Tone::Dim => "90",
+        }
+    }
+}
+
+/// `text` wrapped in the escapes for `tone`.
+pub fn paint(text: &str, tone: Tone) -> String {
+    format!("\x1b[{}m{}\x1b[0m", tone.code(), text)
+}
+
+/// Color of a slice status (the same as in `dashboard`).
+pub fn slice_status_tone(status: SliceStatus) -> Tone {
+    match status {
+        SliceStatus::Planned => Tone::Blue,
+        SliceStatus::Draft => Tone::Yellow,
+        SliceStatus::Active => Tone::Green,
+        SliceStatus::Deprecated => Tone::Dim,
+    }
+}
+
+/// Color of a ritual run status; `None` for statuses this build does not know.
+pub fn run_status_tone(status: &str) -> Option<Tone> {
+    match status {
+        "succeeded" => Some(Tone::Green),
+        "failed" => Some(Tone::Red),
+        "canceled" | "outdated" => Some(Tone::Yellow),
+        "stubbed" => Some(Tone::Cyan),
+        "pending" | "running" => Some(Tone::Blue),
+        "purged" => Some(Tone::Dim),
+        _ => None,
+    }
+}
+
+/// `timestamp` (RFC 3339) relative to now, e.g. `5m ago`; see [`relative_time_at`].
+pub fn relative_time(timestamp: &str) -> String {
+    relative_time_at(timestamp, Utc::now())
+}
+
+/// `timestamp` relative to `now`: `just now`, `12m ago`, `3h ago`, or `4d ago` within a
+/// month, else its date. Timestamps that do not parse are returned as given.
+pub fn relative_time_at(timestamp: &str, now: DateTime<Utc>) -> String {
+    let Ok(at) = DateTime::parse_from_rfc3339(timestamp) else {
+        return timestamp.to_string();
+    };
+    let secs = (now - at.with_timezone(&Utc)).num_seconds();
+    match secs {
+        -59..=59 => "just now".into(),
+        60..=3_599 => format!("{}m ago", secs / 60),
+        3_600..=86_399 => format!("{}h ago", secs / 3_600),
+        86_400..=2_591_999 => format!("{}d ago", secs / 86_400),
+        _ => at.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// A run duration for humans: `850ms`, `12.4s`, `3m05s`, `2h07m`.
+pub fn duration_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    match ms {
+        0..=999 => format!("{}ms", ms),
+        1_000..=59_999 => format!("{:.1}s", ms as f64 / 1000.0),
+        60_000..=3_599_999 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// One table cell: its text and optional color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    text: String,
+    tone: Option<Tone>,
+}
+
+impl Cell {
+    /// A cell shown in `tone` when colors are on.
+    pub fn toned(text: impl Into<String>, tone: Option<Tone>) -> Self {
+        Self { text: text.into(), tone }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Self { text, tone: None }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
+
+/// Rows under a header, each column padded to its widest cell.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    columns: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(columns: &[&str]) -> Self {
+        Self { columns: columns.iter().map(|c| c.to_string()).collect(), rows: Vec::new() }
+    }
+
+    /// Append a row; it must have one cell per column.
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        debug_assert_eq!(cells.len(), self.columns.len());
+        self.rows.push(cells);
+    }
+
+    /// The table as text, one line per row after the header and its rule. Widths count
+    /// characters of the plain text, so colored cells line up with plain ones.
+    pub fn render(&self, colors: bool) -> String {
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                self.rows
+                    .iter()
+                    .map(|r| r[i].text.chars().count())
+                    .fold(col.chars().count(), usize::max)
+            })
+            .collect();
+
+        let mut out = String::new();
+        let header = self.columns.iter().zip(&widths).map(|(col, w)| {
+            let shown = if colors { format!("\x1b[1m{}\x1b[0m", col) } else { col.clone() };
+            (shown, w - col.chars().count())
+        });
+        out.push_str(&join_line(header));
+        out.push_str(&join_line(widths.iter().map(|w| ("-".repeat(*w), 0))));
+        for row in &self.rows {
+            out.push_str(&join_line(row.iter().zip(&widths).map(|(cell, w)| {
+                let shown = match cell.tone {
+                    Some(tone) if colors => paint(&cell.text, tone),
+                    _ => cell.text.clone(),
+                };
+                (shown, w - cell.text.chars().count())
+            })));
+        }
+        out
+    }
+
+    /// Print the table to stdout, colored when [`init`] turned colors on.
+    pub fn print(&self) {
+        print!("{}", self.render(colors_enabled()));
+    }
+}
+
+/// One output line: each shown cell followed by its padding, two spaces between columns.
+fn join_line(cells: impl Iterator<Item = (String, usize)>) -> String {
+    let mut line = String::new();
+    for (i, (shown, pad)) in cells.enumerate() {
+        if i > 0 {
+            line.push_str("  ");
+        }
+        line.push_str(&shown);
+        line.push_str(&" ".repeat(pad));
+    }
+    let mut line = line.trim_end().to_string();
+    line.push('\n');
+    line
+}
+
+/// Print `cells` under `columns` as a plain [`Table`].
+pub fn print_table(columns: &[&str], cells: &[Vec<String>]) {
+    let mut table = Table::new(columns);
+    for row in cells {
+        table.row(row.iter().map(|c| Cell::from(c.as_str())).collect());
+    }
+    table.print();
+}
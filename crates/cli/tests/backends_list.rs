@@ -1,5 +1,6 @@
 use assert_cmd::cargo::cargo_bin_cmd;
-use binary_slicer::commands::list_backends_command;
+use binary_slicer::commands::{backend_infos, list_backends_command};
+use ritual_core::db::{BackendPaths, KNOWN_BACKENDS};
 use tempfile::tempdir;
 
 #[test]
@@ -9,6 +10,52 @@ fn list_backends_reports_available_backends() {
     list_backends_command(true).unwrap();
 }
 
+#[test]
+fn backend_infos_cover_every_known_backend_with_capabilities() {
+    let infos = backend_infos(&BackendPaths::default());
+    let names: Vec<&str> = infos.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, KNOWN_BACKENDS.to_vec());
+
+    let validate = infos.iter().find(|i| i.name == "validate-only").unwrap();
+    assert!(validate.compiled && validate.available);
+    assert!(validate.capabilities.archs.is_empty());
+
+    for info in infos.iter().filter(|i| !i.compiled) {
+        assert!(!info.available);
+        let reason = info.capabilities.unavailable.as_deref().unwrap();
+        assert!(reason.contains(&format!("{}-backend", info.name)), "{reason}");
+    }
+}
+
+#[test]
+fn list_backends_json_uses_project_tool_paths() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    binary_slicer::commands::init_project_command(&root, Some("CapsProj".into())).unwrap();
+    let missing = temp.path().join("no-such-rizin");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "backends.rizin", missing.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-backends", "--root", &root, "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).expect("list-backends json");
+    let rizin = body.as_array().unwrap().iter().find(|b| b["name"] == "rizin").unwrap();
+    assert_eq!(rizin["available"], false);
+    if rizin["compiled"] == true {
+        assert_eq!(rizin["capabilities"]["tool_path"], missing.to_str().unwrap());
+        assert_eq!(rizin["capabilities"]["external_tool"], "rizin");
+    }
+    let validate = body.as_array().unwrap().iter().find(|b| b["name"] == "validate-only").unwrap();
+    assert_eq!(validate["available"], true);
+}
+
 #[test]
 fn configured_backends_round_trip_in_project_info() {
     let temp = tempdir().unwrap();
@@ -48,3 +95,42 @@ fn configured_backend_paths_returns_configured_values() {
     let paths = binary_slicer::commands::configured_backend_paths(&cfg);
     assert_eq!(paths.rizin.as_deref(), Some("/usr/bin/rizin"));
 }
+
+#[test]
+fn self_test_runs_compiled_backends_and_writes_artifacts() {
+    let temp = tempdir().unwrap();
+    let report = binary_slicer::commands::run_self_test(None, temp.path()).unwrap();
+    assert!(report.passed, "{:?}", report.backends);
+    let names: Vec<&str> = report.backends.iter().map(|r| r.backend.as_str()).collect();
+    assert!(names.contains(&"validate-only"));
+    assert!(std::path::Path::new(&report.binary).is_file());
+    for row in &report.backends {
+        let dir = std::path::Path::new(row.artifacts.as_deref().unwrap());
+        let report = ritual_core::services::report::ReportV1::load(dir).unwrap();
+        assert_eq!(report.backend, row.backend);
+        assert_eq!(report.call_edges.len(), row.call_edges);
+        assert!(dir.join("graph.dot").is_file());
+    }
+
+    let err = binary_slicer::commands::run_self_test(Some("nope"), temp.path()).unwrap_err();
+    assert!(err.to_string().contains("Unknown backend 'nope'"));
+}
+
+#[test]
+fn self_test_cli_emits_json_matrix() {
+    let temp = tempdir().unwrap();
+    let out = temp.path().join("artifacts");
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["self-test", "--backend", "validate-only", "--json", "--out"])
+        .arg(&out)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).expect("self-test json");
+    assert_eq!(body["passed"], true);
+    assert_eq!(body["backends"][0]["backend"], "validate-only");
+    assert_eq!(body["artifacts_dir"], out.display().to_string());
+    assert!(out.join("validate-only").join("report.json").is_file());
+}
@@ -0,0 +1,159 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{
+    add_binary_command, init_project_command, remove_binary_command, RunHandling,
+};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use std::fs;
+use tempfile::tempdir;
+
+/// A project with binary `Game` and one validate-only run `Boot` of it.
+fn project_with_run() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Lifecycle".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let spec = temp.path().join("boot.yaml");
+    fs::write(&spec, "name: Boot\nbinary: Game\nroots: [main]\nbackend: validate-only\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success();
+    (temp, root)
+}
+
+#[test]
+fn binaries_with_runs_are_only_removed_with_cascade_or_keep_runs() {
+    let (_temp, root) = project_with_run();
+    let layout = ProjectLayout::new(&root);
+    let outputs = layout.binary_output_root("Game");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["remove-binary", "--root", &root, "--binary", "Game"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has 1 ritual run(s)"))
+        .stderr(predicate::str::contains("--cascade"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["remove-binary", "--root", &root, "--binary", "Game", "--cascade", "--keep-runs"])
+        .assert()
+        .failure();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["remove-binary", "--root", &root, "--binary", "Game", "--cascade"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed binary: Game"))
+        .stdout(predicate::str::contains("Deleted 1 run(s)"));
+    assert!(!outputs.exists());
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    assert!(db.list_binaries().unwrap().is_empty());
+    assert!(db.list_ritual_runs(None).unwrap().is_empty());
+}
+
+#[test]
+fn keep_runs_leaves_runs_and_outputs_under_the_old_name() {
+    let (_temp, root) = project_with_run();
+    remove_binary_command(&root, "Game", RunHandling::Keep).unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    assert!(layout.binary_output_root("Game").join("Boot").is_dir());
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    assert!(db.list_binaries().unwrap().is_empty());
+    let runs = db.list_ritual_runs(Some("Game")).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].binary_id, None);
+}
+
+#[test]
+fn rename_binary_cascade_moves_runs_and_outputs() {
+    let (_temp, root) = project_with_run();
+    let layout = ProjectLayout::new(&root);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["rename-binary", "--root", &root, "--binary", "Game", "--new-name", "Game"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already registered"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["rename-binary", "--root", &root, "--binary", "Game", "--new-name", "Client"])
+        .arg("--cascade")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Renamed binary: Game -> Client"))
+        .stdout(predicate::str::contains("Renamed 1 run(s)"));
+    assert!(!layout.binary_output_root("Game").exists());
+    assert!(layout.binary_output_root("Client").join("Boot").join("spec.yaml").is_file());
+
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    assert_eq!(db.list_binaries().unwrap()[0].name, "Client");
+    assert_eq!(db.list_ritual_runs(Some("Client")).unwrap().len(), 1);
+
+    // The moved run is still usable under the new name.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "Client", "--ritual", "Boot"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn runs_refuse_a_binary_replaced_since_registration() {
+    let (temp, root) = project_with_run();
+    let layout = ProjectLayout::new(&root);
+    let spec = temp.path().join("boot.yaml");
+    let registered =
+        ProjectDb::open(&layout.db_path).unwrap().list_binaries().unwrap()[0].hash.clone().unwrap();
+    fs::write(temp.path().join("game.bin"), b"patched game").unwrap();
+
+    let run = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap(), "--force"]);
+        cmd.args(extra);
+        cmd.assert()
+    };
+    run(&[])
+        .failure()
+        .stderr(predicate::str::contains("Binary 'Game' changed since it was registered"))
+        .stderr(predicate::str::contains(format!("registered as {registered}")))
+        .stderr(predicate::str::contains("--allow-hash-mismatch"));
+
+    // Allowed mismatches record the analyzed file's hash and the registered one.
+    run(&["--allow-hash-mismatch"]).success();
+    let metadata: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(layout.binary_output_root("Game").join("Boot/run_metadata.json"))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(metadata["registered_binary_hash"], registered.as_str());
+    let current = metadata["binary_hash"].as_str().unwrap().to_string();
+    assert_ne!(current, registered);
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "Game", "--ritual", "Boot"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Binary hash: {current}")))
+        .stdout(predicate::str::contains(format!("Registered hash: {registered}")));
+
+    // Accepting the new file makes plain runs work again.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["verify-binaries", "--root", &root, "--update"])
+        .assert()
+        .success();
+    run(&[]).success();
+    let metadata =
+        fs::read_to_string(layout.binary_output_root("Game").join("Boot/run_metadata.json"))
+            .unwrap();
+    assert!(!metadata.contains("registered_binary_hash"), "{metadata}");
+}
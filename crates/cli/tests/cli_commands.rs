@@ -320,6 +320,95 @@ roots: [entry_point]
         .failure();
 }
 
+/// `update-ritual-run-status` changes only the latest run of a ritual unless told otherwise.
+#[test]
+fn update_ritual_run_status_targets_latest_run_or_run_id() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    let bin_path = root.join("libStatus3.so");
+    fs::write(&bin_path, b"dummy").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .arg("add-binary")
+        .arg("--root")
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .arg("--name")
+        .arg("StatusBin3")
+        .assert()
+        .success();
+    let spec_path = root.join("status3.yaml");
+    fs::write(&spec_path, "name: StatusRun3\nbinary: StatusBin3\nroots: [entry_point]\n")
+        .expect("write spec");
+    for _ in 0..2 {
+        cargo_bin_cmd!("binary-slicer")
+            .arg("run-ritual")
+            .arg("--root")
+            .arg(root)
+            .arg("--file")
+            .arg(&spec_path)
+            .arg("--force")
+            .assert()
+            .success();
+    }
+    let statuses = || -> Vec<(i64, String)> {
+        let output = cargo_bin_cmd!("binary-slicer")
+            .args(["list-ritual-runs", "--json", "--root"])
+            .arg(root)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let runs: Vec<serde_json::Value> = serde_json::from_slice(&output).expect("runs json");
+        runs.iter()
+            .map(|r| (r["id"].as_i64().unwrap(), r["status"].as_str().unwrap().to_string()))
+            .collect()
+    };
+    let update = |args: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.arg("update-ritual-run-status").arg("--root").arg(root).args(args);
+        cmd
+    };
+    let before = statuses();
+    assert_eq!(before.len(), 2);
+    let (first, second) = (before[0].0, before[1].0);
+
+    update(&["--binary", "StatusBin3", "--ritual", "StatusRun3", "--status", "failed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("runs=1"));
+    assert_eq!(statuses(), [(first, before[0].1.clone()), (second, "failed".to_string())]);
+
+    update(&["--run-id", &first.to_string(), "--status", "canceled"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("run={}", first)));
+    assert_eq!(statuses(), [(first, "canceled".into()), (second, "failed".into())]);
+    update(&["--run-id", "999", "--status", "canceled"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ritual run with id 999"));
+    update(&["--run-id", "1", "--binary", "StatusBin3", "--status", "failed"]).assert().failure();
+
+    let args = ["--binary", "StatusBin3", "--ritual", "StatusRun3", "--all-runs"];
+    update(&args).args(["--status", "stubbed"]).assert().success();
+    assert_eq!(statuses(), [(first, "stubbed".into()), (second, "stubbed".into())]);
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-runs", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(format!(
+                r"(?m)^{}\s+StatusBin3\s+StatusRun3\s+stubbed\s+just now\s+\d+(ms|\.\ds)\s",
+                second
+            ))
+            .unwrap(),
+        );
+}
+
 /// `rerun-ritual` should reuse a normalized spec and create a new run entry.
 #[test]
 fn rerun_ritual_creates_new_run_from_existing_spec() {
@@ -394,6 +483,70 @@ roots: [entry_point]
     assert_eq!(runs.len(), 2);
 }
 
+/// Runs record their analysis options; `rerun-ritual` repeats them unless overridden.
+#[test]
+fn rerun_ritual_reuses_recorded_analysis_options() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path().to_string_lossy().to_string();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root", &root]).assert().success();
+    fs::write(temp.path().join("libOpts.so"), b"dummy").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--path", "libOpts.so"])
+        .assert()
+        .success();
+    let spec_path = temp.path().join("opts.yaml");
+    fs::write(&spec_path, "name: Opts\nbinary: libOpts.so\nroots: [start]\nmax_depth: 4\n")
+        .expect("write spec");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "validate-only", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "libOpts.so", "--ritual", "Opts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Analysis options: max depth 4, max instructions 1024, strings on, imports on",
+        ));
+
+    let rerun = |from: &str, to: &str, extra: &[&str]| {
+        cargo_bin_cmd!("binary-slicer")
+            .args(["rerun-ritual", "--root", &root, "--binary", "libOpts.so", "--ritual", from])
+            .args(["--as-name", to, "--backend", "validate-only"])
+            .args(extra)
+            .assert()
+            .success();
+        let output = cargo_bin_cmd!("binary-slicer")
+            .args(["show-ritual-run", "--root", &root, "--binary", "libOpts.so", "--ritual", to])
+            .arg("--json")
+            .output()
+            .expect("show run");
+        let run: serde_json::Value = serde_json::from_slice(&output.stdout).expect("run json");
+        run["metadata"]["analysis_options"].clone()
+    };
+    let options = rerun("Opts", "Shallow", &["--max-depth", "1", "--include-strings", "false"]);
+    assert_eq!(
+        options,
+        serde_json::json!({
+            "max_depth": 1,
+            "include_imports": true,
+            "include_strings": false,
+            "max_instructions": 1024,
+        })
+    );
+    // Rerunning the rerun keeps its options.
+    assert_eq!(rerun("Shallow", "ShallowAgain", &[]), options);
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-runs", "--root", &root, "--json"])
+        .output()
+        .expect("list runs");
+    let runs: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).expect("runs json");
+    let shallow = runs.iter().find(|r| r["name"] == "Shallow").expect("Shallow listed");
+    assert_eq!(shallow["analysis_options"], options);
+}
+
 /// `add-binary` should fail when the target binary path does not exist.
 #[test]
 fn add_binary_fails_for_missing_file() {
@@ -598,8 +751,8 @@ fn list_binaries_reports_registered_binary_with_hash() {
         .assert()
         .success()
         .stdout(predicate::str::contains("gameclient.bin"))
-        .stdout(predicate::str::contains("arch: arm64"))
-        .stdout(predicate::str::contains(&expected_hash));
+        .stdout(predicate::str::is_match(r"(?m)^gameclient\.bin\s+arm64\s").unwrap())
+        .stdout(predicate::str::contains(&expected_hash[..12]));
 }
 
 /// `emit-slice-docs` should regenerate docs from DB slices.
@@ -638,6 +791,72 @@ fn emit_slice_docs_regenerates_docs() {
     assert!(contents.contains("Telemetry slice"));
 }
 
+/// Analyst sections survive `emit-slice-docs` and `sync-slice-docs` stores them in the DB.
+#[test]
+fn slice_doc_analyst_sections_survive_regeneration_and_sync_back() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path().to_string_lossy().to_string();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root", &root]).assert().success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Net"])
+        .assert()
+        .success();
+
+    let doc_path = ritual_core::db::ProjectLayout::new(temp.path()).slices_docs_dir.join("Net.md");
+    let doc = std::fs::read_to_string(&doc_path).unwrap();
+    let doc = doc
+        .replace(
+            "<!-- analyst:begin notes -->\n",
+            "<!-- analyst:begin notes -->\nHandshake is in Net::open.\n",
+        )
+        .replace(
+            "<!-- analyst:begin roots -->\n",
+            "<!-- analyst:begin roots -->\n- Net::open\n- `0x401000`\n",
+        );
+    std::fs::write(&doc_path, doc).unwrap();
+
+    cargo_bin_cmd!("binary-slicer").args(["emit-slice-docs", "--root", &root]).assert().success();
+    let regenerated = std::fs::read_to_string(&doc_path).unwrap();
+    assert!(regenerated.contains("## Analyst notes\n<!-- analyst:begin notes -->\nHandshake"));
+    assert!(regenerated.contains("- Net::open\n- `0x401000`\n<!-- analyst:end roots -->"));
+    assert!(regenerated.contains("- [Analyst roots](#analyst-roots)"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["sync-slice-docs", "--root", &root, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would update Net: 2 roots, notes"));
+    let show = |root: &str| -> serde_json::Value {
+        let out = cargo_bin_cmd!("binary-slicer")
+            .args(["show-slice", "--root", root, "--name", "Net", "--json"])
+            .output()
+            .unwrap();
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+    assert_eq!(show(&root)["roots"], serde_json::json!([]));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["sync-slice-docs", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated Net: 2 roots, notes"));
+    let slice = show(&root);
+    assert_eq!(slice["roots"], serde_json::json!(["Net::open", "0x401000"]));
+    assert_eq!(slice["notes"], "Handshake is in Net::open.");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["sync-slice-docs", "--root", &root, "--slice", "Net"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unchanged Net"));
+
+    // A doc rewritten without the sections falls back to the DB's roots and notes.
+    std::fs::remove_file(&doc_path).unwrap();
+    cargo_bin_cmd!("binary-slicer").args(["emit-slice-docs", "--root", &root]).assert().success();
+    let restored = std::fs::read_to_string(&doc_path).unwrap();
+    assert!(restored.contains("<!-- analyst:begin notes -->\nHandshake is in Net::open.\n"));
+    assert!(restored.contains("<!-- analyst:begin roots -->\n- Net::open\n- 0x401000\n"));
+}
+
 /// `emit-slice-reports` should write JSON reports per slice.
 #[test]
 fn emit_slice_reports_regenerates_reports() {
@@ -768,6 +987,9 @@ roots:
         serde_json::from_str(&report_contents).expect("parse report json");
     assert_eq!(report_json["binary"], "ExampleBin");
     assert_eq!(report_json["ritual"], "SliceRun");
+    assert_eq!(report_json["report_schema_version"], 1);
+    assert!(report_json["call_edges"].is_array());
+    assert!(report_json.get("edges").is_none());
 }
 
 /// `run-ritual` should handle JSON specs too.
@@ -1017,6 +1239,44 @@ roots: [entry_point]
     assert!(payload["analysis"]["roots"].is_array());
 }
 
+/// After a forced re-run, `show-ritual-run` describes the new run, not the first one.
+#[test]
+fn show_ritual_run_reports_the_latest_run() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path().to_string_lossy().to_string();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root", &root]).assert().success();
+    fs::write(temp.path().join("libLatest.so"), b"payload").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--path", "libLatest.so", "--name", "LatestBin"])
+        .assert()
+        .success();
+
+    let spec_path = temp.path().join("latest.yaml");
+    let run = |spec_yaml: &str| {
+        fs::write(&spec_path, spec_yaml).expect("write spec");
+        cargo_bin_cmd!("binary-slicer")
+            .args(["run-ritual", "--root", &root, "--force", "--file"])
+            .arg(&spec_path)
+            .assert()
+            .success();
+        format!("{:x}", Sha256::digest(spec_yaml.as_bytes()))
+    };
+    let first = run("name: Latest\nbinary: LatestBin\nroots: [entry_point]\nmax_depth: 1\n");
+    let second = run("name: Latest\nbinary: LatestBin\nroots: [entry_point]\nmax_depth: 2\n");
+    assert_ne!(first, second);
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "LatestBin", "--ritual", "Latest"])
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let payload: serde_json::Value = serde_json::from_slice(&output).expect("parse json");
+    assert_eq!(payload["metadata"]["spec_hash"], second);
+}
+
 /// `show-ritual-run` should prefer DB metadata when available.
 #[test]
 fn show_ritual_run_uses_db_metadata() {
@@ -1070,8 +1330,114 @@ roots: [entry_point]
         .clone();
     let payload: serde_json::Value = serde_json::from_slice(&output).expect("parse show json");
     assert_eq!(payload["metadata"]["status"], "stubbed");
+    assert!(payload["metadata"]["usage"]["duration_ms"].is_u64());
+    // No external tool ran, so there is no CPU time or memory to report.
+    assert!(payload["metadata"]["usage"].get("cpu_ms").is_none());
     assert_eq!(payload["binary"], "ShowDbBin");
     assert_eq!(payload["ritual"], "ShowDbRun");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--binary", "ShowDbBin", "--ritual", "ShowDbRun", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^  Duration: \d+(ms|\.\ds)$").unwrap());
+}
+
+/// `run-ritual --spec-name` finds the spec under rituals/ and runs record where they came from.
+#[test]
+fn run_ritual_by_spec_name_records_spec_path() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    let bin_path = root.join("libSpecName.so");
+    fs::write(&bin_path, b"payload").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .args(["--name", "SpecNameBin"])
+        .assert()
+        .success();
+    let layout = ritual_core::db::ProjectLayout::new(root);
+    fs::write(
+        layout.rituals_dir.join("net-spec.yaml"),
+        "name: NetRun\nbinary: SpecNameBin\nroots: [entry_point]\n",
+    )
+    .expect("write spec");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root"])
+        .arg(root)
+        .args(["--spec-name", "NetRun"])
+        .assert()
+        .success();
+    // The file name works too.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root"])
+        .arg(root)
+        .args(["--spec-name", "net-spec", "--force"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root"])
+        .arg(root)
+        .args(["--spec-name", "Missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ritual spec named 'Missing'"))
+        .stderr(predicate::str::contains("available: NetRun"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root"])
+        .arg(root)
+        .args(["--spec-name", "NetRun", "--file", "x.yaml"])
+        .assert()
+        .failure();
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root"])
+        .arg(root)
+        .args(["--binary", "SpecNameBin", "--ritual", "NetRun", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let payload: serde_json::Value = serde_json::from_slice(&output).expect("parse show json");
+    assert_eq!(payload["metadata"]["spec_path"], "rituals/net-spec.yaml");
+    let metadata: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(
+            layout.binary_output_root("SpecNameBin").join("NetRun/run_metadata.json"),
+        )
+        .expect("read metadata"),
+    )
+    .expect("parse metadata");
+    assert_eq!(metadata["spec_path"], "rituals/net-spec.yaml");
+
+    // A rerun comes from the same spec file.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["rerun-ritual", "--root"])
+        .arg(root)
+        .args(["--binary", "SpecNameBin", "--ritual", "NetRun", "--as-name", "NetRerun"])
+        .assert()
+        .success();
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-runs", "--root"])
+        .arg(root)
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let runs: serde_json::Value = serde_json::from_slice(&output).expect("parse runs json");
+    let runs = runs.as_array().expect("runs array");
+    assert!(!runs.is_empty());
+    for run in runs {
+        assert_eq!(run["spec_path"], "rituals/net-spec.yaml", "{run}");
+    }
 }
 
 /// `list-ritual-specs` should report specs under rituals/.
@@ -1544,3 +1910,958 @@ fn list_binaries_json_output() {
     assert_eq!(binaries[0].name, "client.bin");
     assert!(binaries[0].hash.as_ref().is_some());
 }
+
+/// `diff-runs` should compare persisted analysis between two runs (JSON + human).
+#[test]
+fn diff_runs_reports_function_and_edge_changes() {
+    use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+    use ritual_core::services::analysis::{AnalysisResult, CallEdge, FunctionRecord};
+
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+
+    let layout = ProjectLayout::new(root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let func = |address: u64, name: &str| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    for (binary, ritual, functions, call_edges) in [
+        (
+            "GameV1",
+            "Net",
+            vec![func(0x10, "send"), func(0x20, "encrypt")],
+            vec![CallEdge {
+                from: 0x10,
+                to: 0x20,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            }],
+        ),
+        (
+            "GameV2",
+            "Net",
+            vec![func(0x10, "send"), func(0x30, "compress")],
+            vec![CallEdge {
+                from: 0x10,
+                to: 0x30,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            }],
+        ),
+    ] {
+        let run = RitualRunRecord {
+            binary: binary.into(),
+            ritual: ritual.into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "validate-only".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        };
+        let run_id = db.insert_ritual_run(&run).expect("insert run");
+        let analysis = AnalysisResult {
+            functions,
+            call_edges,
+            evidence: Vec::new(),
+            basic_blocks: Vec::new(),
+            roots: Vec::new(),
+            root_hits: Vec::new(),
+            sub_slices: Vec::new(),
+            backend_version: None,
+            backend_path: None,
+            xrefs: Vec::new(),
+            text_bytes: None,
+        };
+        db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+    }
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["diff-runs", "--binary", "GameV1", "--ritual", "Net", "--against", "Net"])
+        .args(["--against-binary", "GameV2", "--json", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).expect("parse diff json");
+    assert_eq!(body["identical"], false);
+    assert_eq!(body["against"]["binary"], "GameV2");
+    assert_eq!(body["diff"]["functions"]["added"][0]["name"], "compress");
+    assert_eq!(body["diff"]["functions"]["removed"][0]["name"], "encrypt");
+    assert_eq!(body["diff"]["call_edges"]["added"][0]["to_name"], "compress");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["diff-runs", "--binary", "GameV1", "--ritual", "Net", "--against", "Net"])
+        .args(["--against-binary", "GameV2", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Functions: +1 -1 renamed=0 moved=0"))
+        .stdout(predicate::str::contains("+ send @ 0x10 -> compress @ 0x30"));
+
+    // Same run on both sides -> no differences; unknown runs error out.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["diff-runs", "--binary", "GameV1", "--ritual", "Net", "--against", "Net", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No differences."));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["diff-runs", "--binary", "GameV1", "--ritual", "Net", "--against", "Missing"])
+        .arg("--root")
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No persisted analysis"));
+}
+
+/// `match-functions` pairs functions across builds by fingerprint, ignoring addresses.
+#[test]
+fn match_functions_pairs_moved_and_renamed_functions() {
+    use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+    use ritual_core::services::analysis::{AnalysisResult, FunctionRecord};
+
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+
+    let layout = ProjectLayout::new(root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let func = |address: u64, name: &str, hash: Option<&str>| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(0x20),
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: hash.map(str::to_string),
+        isa: None,
+    };
+    for (ritual, functions) in [
+        (
+            "V1",
+            vec![
+                func(0x1000, "send", Some("00000000ffffffff")),
+                func(0x1100, "encrypt", Some("0f0f0f0f0f0f0f0f")),
+                func(0x1200, "legacy", Some("aaaaaaaaaaaaaaaa")),
+                func(0x1300, "stub", None),
+            ],
+        ),
+        (
+            "V2",
+            vec![
+                // Two bits flipped: similarity 62/64.
+                func(0x2040, "sub_2040", Some("00000003ffffffff")),
+                func(0x2100, "encrypt", Some("0f0f0f0f0f0f0f0f")),
+                func(0x2200, "compress", Some("5555555555555555")),
+            ],
+        ),
+    ] {
+        let run = RitualRunRecord {
+            binary: "Game".into(),
+            ritual: ritual.into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        };
+        let run_id = db.insert_ritual_run(&run).expect("insert run");
+        let analysis = AnalysisResult {
+            functions,
+            call_edges: Vec::new(),
+            evidence: Vec::new(),
+            basic_blocks: Vec::new(),
+            roots: Vec::new(),
+            root_hits: Vec::new(),
+            sub_slices: Vec::new(),
+            backend_version: None,
+            backend_path: None,
+            xrefs: Vec::new(),
+            text_bytes: None,
+        };
+        db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+    }
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["match-functions", "--binary", "Game", "--from", "V1", "--to", "V2", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Matched: 2"))
+        .stdout(predicate::str::contains("send @ 0x1000 -> sub_2040 @ 0x2040 (0.97)"))
+        .stdout(predicate::str::contains("encrypt @ 0x1100 -> encrypt @ 0x2100 (1.00)"))
+        .stdout(predicate::str::contains("Unmatched: -1 +1"))
+        .stdout(predicate::str::contains("- legacy @ 0x1200"))
+        .stdout(predicate::str::contains("+ compress @ 0x2200"))
+        .stdout(predicate::str::contains("Without fingerprints (skipped): 1 / 0"));
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["match-functions", "--binary", "Game", "--from", "V1", "--to", "V2"])
+        .args(["--min-similarity", "1", "--json", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).expect("parse match json");
+    assert_eq!(body["to"]["binary"], "Game");
+    let matched = body["matches"]["matched"].as_array().unwrap();
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0]["from"], 0x1100);
+    assert_eq!(matched[0]["to"], 0x2100);
+    assert_eq!(body["matches"]["unmatched_from"].as_array().unwrap().len(), 2);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["match-functions", "--binary", "Game", "--from", "V1", "--to", "Missing"])
+        .arg("--root")
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No persisted analysis for ritual run Game/Missing"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["match-functions", "--binary", "Game", "--from", "V1", "--to", "V2"])
+        .args(["--min-similarity", "1.5", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--min-similarity must be between 0 and 1"));
+}
+
+/// `archive-slice` hides a slice from listings/docs but keeps its doc under `_archived/`;
+/// `restore-slice` brings it back.
+#[test]
+fn archive_and_restore_slice_round_trip() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    for name in ["Keep", "Legacy"] {
+        cargo_bin_cmd!("binary-slicer")
+            .args(["init-slice", "--name", name, "--root"])
+            .arg(root)
+            .assert()
+            .success();
+    }
+    let layout = ritual_core::db::ProjectLayout::new(root);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["archive-slice", "--name", "Legacy", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archived slice: Legacy"));
+    assert!(!layout.slices_docs_dir.join("Legacy.md").exists());
+    assert!(layout.archived_slices_docs_dir().join("Legacy.md").is_file());
+
+    // Hidden by default, visible with --include-archived.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-slices", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Keep"))
+        .stdout(predicate::str::contains("Legacy").not());
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-slices", "--json", "--include-archived", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let listed: serde_json::Value = serde_json::from_slice(&output).expect("parse slices json");
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+    assert!(listed[1]["archived_at"].is_string());
+
+    // Doc emission skips archived slices.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-docs", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+    assert!(!layout.slices_docs_dir.join("Legacy.md").exists());
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["archive-slice", "--name", "Legacy", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already archived"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["restore-slice", "--name", "Legacy", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+    assert!(layout.slices_docs_dir.join("Legacy.md").is_file());
+    assert!(!layout.archived_slices_docs_dir().join("Legacy.md").exists());
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-slices", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Legacy"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["restore-slice", "--name", "Missing", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
+
+/// `show-slice` gathers the slice record, its latest run with counts, and doc/report paths.
+#[test]
+fn show_slice_aggregates_record_run_docs_and_reports() {
+    use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+    use ritual_core::services::analysis::{AnalysisResult, CallEdge, FunctionRecord};
+
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--name", "Net", "--binary", "Game", "--description", "Sockets"])
+        .arg("--root")
+        .arg(root)
+        .assert()
+        .success();
+
+    // Before any run: the scaffolded doc, no run, no reports.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-slice", "--name", "Net", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Slice: Net"))
+        .stdout(predicate::str::contains("Description: Sockets"))
+        .stdout(predicate::str::contains("Default binary: Game"))
+        .stdout(predicate::str::contains("Latest run: (none"))
+        .stdout(predicate::str::contains("Reports: (none; run emit-slice-reports)"));
+
+    let layout = ProjectLayout::new(root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let func = |address: u64, in_slice: bool| FunctionRecord {
+        address,
+        name: Some(format!("fn_{address:x}")),
+        size: None,
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let run = RitualRunRecord {
+        binary: "Game".into(),
+        ritual: "Net".into(),
+        spec_hash: "sh".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let run_id = db.insert_ritual_run(&run).expect("insert run");
+    let analysis = AnalysisResult {
+        functions: vec![func(0x10, true), func(0x20, true), func(0x30, false)],
+        call_edges: vec![CallEdge {
+            from: 0x10,
+            to: 0x20,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
+        }],
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-reports", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-slice", "--name", "Net", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Latest run: Game/Net [succeeded] backend=validate-only"))
+        .stdout(predicate::str::contains("Functions: 3 (2 in slice)"))
+        .stdout(predicate::str::contains("Call edges: 1"))
+        .stdout(predicate::str::contains("Report: ").and(predicate::str::contains("Net.html")));
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["show-slice", "--name", "Net", "--json", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).expect("parse show-slice json");
+    assert_eq!(body["status"], "Planned");
+    assert_eq!(body["latest_run"]["binary"], "Game");
+    assert_eq!(body["counts"]["functions"], 3);
+    assert_eq!(body["counts"]["in_slice_functions"], 2);
+    assert!(body["doc"].as_str().unwrap().ends_with("Net.md"));
+    let reports: Vec<&str> =
+        body["reports"].as_array().unwrap().iter().map(|r| r.as_str().unwrap()).collect();
+    assert_eq!(reports.len(), 2, "json and html reports: {reports:?}");
+    assert!(body["graph"].as_str().unwrap().ends_with("Net.dot"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-slice", "--name", "Missing", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Missing' not found"));
+}
+
+/// `update-slice-status` records each transition; slice docs show the current status and the
+/// history.
+#[test]
+fn update_slice_status_records_history_in_docs() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--name", "Net", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["update-slice-status", "--name", "Net", "--status", "active", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Planned -> Active"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["update-slice-status", "--name", "Net", "--status", "Deprecated"])
+        .args(["--note", "replaced by Net2", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["update-slice-status", "--name", "Net", "--status", "deprecated", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already Deprecated"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["update-slice-status", "--name", "Net", "--status", "retired", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown slice status 'retired'"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-docs", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+    let layout = ritual_core::db::ProjectLayout::new(root);
+    let doc = std::fs::read_to_string(layout.slices_docs_dir.join("Net.md")).unwrap();
+    assert!(doc.contains("**Status:** Deprecated"));
+    let history = doc.split("## Status history\n").nth(1).expect("history section");
+    let lines: Vec<&str> = history.lines().take_while(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with(": Planned -> Active"));
+    assert!(lines[1].ends_with(": Active -> Deprecated (replaced by Net2)"));
+}
+
+#[test]
+fn validate_spec_reports_every_problem_without_running() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    binary_slicer::commands::init_project_command(&root, Some("SpecProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+
+    let good = temp.path().join("good.yaml");
+    fs::write(
+        &good,
+        "name: Good\nbinary: demo\nroots: [main, {address: 0x401010}]\nbackend: capstone\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["validate-spec", "--root", &root, "--file"])
+        .arg(&good)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Binary: demo"))
+        .stdout(predicate::str::contains("Backend: capstone"))
+        .stdout(predicate::str::contains("Roots: main, 0x401010"))
+        .stdout(predicate::str::contains("Spec is valid."));
+    // Nothing ran.
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    assert!(!layout.binary_output_root("demo").join("Good").exists());
+
+    let bad = temp.path().join("bad.yaml");
+    fs::write(
+        &bad,
+        "name: Bad\nbinary: nope\nroots: ['regex:(', '0xZZ']\nbackend: objdump\ncolour: red\n\
+         stages:\n  - name: s1\n    backend: ida\n",
+    )
+    .unwrap();
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["validate-spec", "--json", "--root", &root, "--file"])
+        .arg(&bad)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has 5 error(s)"))
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["valid"], false);
+    let errors: Vec<&str> =
+        report["errors"].as_array().unwrap().iter().map(|e| e.as_str().unwrap()).collect();
+    let has = |needle: &str| errors.iter().any(|e| e.contains(needle));
+    assert!(has("Root 'regex:('"), "{errors:?}");
+    assert!(has("Root '0xZZ'"), "{errors:?}");
+    assert!(has("Spec backend 'objdump' is not available"), "{errors:?}");
+    assert!(has("Stage 's1' backend 'ida' is not available"), "{errors:?}");
+    assert!(has("nope"), "unregistered binary: {errors:?}");
+    assert_eq!(report["warnings"][0], "Unknown field 'colour' is ignored");
+}
+
+#[test]
+fn schema_describes_every_serialized_spec_field() {
+    let output = cargo_bin_cmd!("binary-slicer")
+        .arg("schema")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let schema: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(schema["required"], serde_json::json!(["name", "binary", "roots"]));
+    assert_eq!(schema, binary_slicer::commands::ritual_spec_schema());
+
+    let spec: binary_slicer::commands::RitualSpec = serde_yaml::from_str(
+        "name: Full\n\
+         binary: {name: demo, arch: x86_64}\n\
+         roots: {net: [main, {regex: '^net_'}], ui: [{address: 4096}, {demangled: 'A::b()'}, {string: hi}]}\n\
+         max_depth: 2\n\
+         backend: capstone\n\
+         description: everything\n\
+         outputs: {reports: true, docs: true, graphs: {cluster: true, collapse_blocks: true, dedup_edges: true, max_nodes: 9}}\n\
+         stages:\n  - {name: a, backend: capstone, needs: [b], roots: [main], max_depth: 1}\n\
+         epoch: 1700000000\n\
+         raw: {load_address: '0x8000000', arch: arm, thumb: true, endian: big}\n\
+         timeout: 30\n\
+         plugins: [plugins/tag.wasm]\n\
+         string_decoders: ['xor:5a']\n\
+         ghidra_xml: exports/demo.xml\n",
+    )
+    .unwrap();
+    let value = serde_json::to_value(&spec).unwrap();
+    // Every field is populated, and the serialized form parses back to the same spec.
+    assert_eq!(value.as_object().unwrap().len(), schema["properties"].as_object().unwrap().len());
+    let reparsed: binary_slicer::commands::RitualSpec =
+        serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&reparsed).unwrap(), value);
+    // Unknown fields are ignored (validate-spec warns), so the schema does not reject them.
+    assert!(!serde_json::to_string(&schema).unwrap().contains("additionalProperties\":false"));
+    let keys = |v: &serde_json::Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+    let described = |def: &serde_json::Value, key: &str| def["properties"].get(key).is_some();
+    let defs = &schema["$defs"];
+    for key in keys(&value) {
+        assert!(described(&schema, &key), "top-level '{key}' missing from schema");
+    }
+    for key in keys(&value["binary"]) {
+        assert!(described(&defs["BinarySelector"]["anyOf"][1], &key), "binary '{key}'");
+    }
+    for key in keys(&value["outputs"]) {
+        assert!(described(&defs["RitualOutputs"], &key), "outputs '{key}'");
+    }
+    for key in keys(&value["outputs"]["graphs"]) {
+        assert!(described(&defs["DotOptions"], &key), "graphs '{key}'");
+    }
+    for key in keys(&value["stages"][0]) {
+        assert!(described(&defs["StageSpec"], &key), "stage '{key}'");
+    }
+    for key in keys(&value["raw"]) {
+        assert!(described(&defs["RawImageSpec"], &key), "raw '{key}'");
+    }
+    let root_shapes: Vec<String> = defs["RootSpec"]["anyOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|shape| shape["required"][0].as_str().map(str::to_string))
+        .collect();
+    for root in value["roots"].as_object().unwrap().values().flat_map(|r| r.as_array().unwrap()) {
+        if let Some(object) = root.as_object() {
+            let key = object.keys().next().unwrap();
+            assert!(root_shapes.contains(key), "root shape '{key}'");
+        }
+    }
+
+    let out_dir = tempdir().unwrap();
+    let out = out_dir.path().join("spec.schema.json");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["schema", "--out"])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote ritual spec schema"));
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(written, schema);
+}
+
+/// `show-function` re-disassembles a function from the registered binary and lists its call
+/// edges and evidence from the latest run.
+#[test]
+fn show_function_lists_disassembly_calls_and_evidence() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    binary_slicer::commands::init_project_command(&root, Some("ShowFn".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    let spec = temp.path().join("show.yaml");
+    fs::write(&spec, "name: Show\nbinary: demo\nroots: [main]\nbackend: capstone\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file"])
+        .arg(&spec)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-function", "--root", &root, "--binary", "demo", "--name", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Function: main @ 0x401000"))
+        .stdout(predicate::str::contains("Run: Show (capstone"))
+        .stdout(predicate::str::contains("0x401000:  e8 0b 00 00 00"))
+        .stdout(predicate::str::contains("0x401005:  c3"))
+        .stdout(predicate::str::contains("0x401000 -> helper @ 0x401010"))
+        .stdout(predicate::str::contains("Evidence:\n  0x401000 call 0x401010"));
+
+    // An address inside a function selects it; JSON carries the listing and callers.
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["show-function", "--root", &root, "--binary", "demo", "--address", "0x401011"])
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let shown: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(shown["function"]["name"], "helper");
+    let mnemonics: Vec<&str> = shown["instructions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["mnemonic"].as_str().unwrap())
+        .collect();
+    assert_eq!(mnemonics, vec!["xor", "ret"]);
+    assert_eq!(shown["called_by"][0]["name"], "main");
+    assert_eq!(shown["called_by"][0]["site"], 0x401000);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-function", "--root", &root, "--binary", "demo", "--name", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("contains function 'missing'"));
+
+    // Without the binary on disk the listing is skipped but edges still show.
+    fs::remove_file(&bin_path).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-function", "--root", &root, "--binary", "demo", "--name", "helper"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(unavailable: binary missing on disk"))
+        .stdout(predicate::str::contains("main @ 0x401000 (at 0x401000)"));
+}
+
+/// Fat Mach-O runs analyze every architecture slice (or the registered `--arch`), and the
+/// slice each function came from is kept in the report, the DB, and `show-function`.
+#[test]
+fn fat_macho_runs_record_the_arch_slice_of_each_function() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    binary_slicer::commands::init_project_command(&root, Some("Fat".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_macho_fat();
+    let bin_path = temp.path().join(fixture.file_name);
+    fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "uni", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    let spec = temp.path().join("fat.yaml");
+    fs::write(&spec, "name: Fat\nbinary: uni\nroots: [main]\nbackend: capstone\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file"])
+        .arg(&spec)
+        .assert()
+        .success();
+
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let report_path = layout.binary_output_root("uni").join("Fat").join("report.json");
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(report_path).unwrap()).unwrap();
+    assert_eq!(report["arch_slices"], serde_json::json!(["arm64", "x86_64"]));
+    let arm_main = fixture.functions[2].address;
+    let function = report["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["address"] == arm_main)
+        .expect("arm64 main");
+    assert_eq!(function["arch"], "arm64");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-function", "--root", &root, "--binary", "uni", "--address"])
+        .arg(format!("0x{:X}", arm_main))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("arch=arm64"))
+        .stdout(predicate::str::contains("bl #0x100002010"));
+}
+
+#[test]
+fn run_ritual_reports_validation_and_fails_with_strict() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    let bin_path = root.join("libStrict.so");
+    fs::write(&bin_path, b"payload").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .args(["--name", "StrictBin"])
+        .assert()
+        .success();
+    let spec = root.join("strict.yaml");
+    fs::write(&spec, "name: StrictRun\nbinary: StrictBin\nroots: [net_init]\n").expect("spec");
+    let run = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["run-ritual", "--root"])
+            .arg(root)
+            .arg("--file")
+            .arg(&spec)
+            .args(["--backend", "validate-only"]);
+        cmd
+    };
+
+    // validate-only resolves no roots, which validation reports without failing the run.
+    run().assert().success().stdout(predicate::str::contains("Validation: 1 issue(s)"));
+    let layout = ritual_core::db::ProjectLayout::new(root);
+    let report_path = layout.binary_output_root("StrictBin").join("StrictRun").join("report.json");
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).expect("report")).expect("json");
+    let issues = report["validation"]["issues"].as_array().expect("issues");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0]["check"], "unresolved_root");
+    assert_eq!(issues[0]["message"], "root 'net_init' did not resolve: no function matched");
+
+    run()
+        .args(["--force", "--strict"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Analysis result failed validation with 1 issue(s)"))
+        .stderr(predicate::str::contains("root 'net_init' did not resolve"));
+    // The run is still recorded so its report can be inspected.
+    assert!(report_path.is_file());
+}
+
+#[test]
+fn spec_outputs_select_the_artifacts_runs_and_slice_reports_write() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    let bin_path = root.join("libOut.so");
+    fs::write(&bin_path, b"payload").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .args(["--name", "OutBin"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root"])
+        .arg(root)
+        .args(["--name", "Quick"])
+        .assert()
+        .success();
+    let spec = root.join("quick.yaml");
+    let run = |outputs: &str| {
+        fs::write(
+            &spec,
+            format!("name: Quick\nbinary: OutBin\nroots: [main]\noutputs: {outputs}\n"),
+        )
+        .expect("spec");
+        cargo_bin_cmd!("binary-slicer")
+            .args(["run-ritual", "--root"])
+            .arg(root)
+            .arg("--file")
+            .arg(&spec)
+            .args(["--backend", "validate-only", "--force"])
+            .assert()
+            .success();
+    };
+    let layout = ritual_core::db::ProjectLayout::new(root);
+    let run_dir = layout.binary_output_root("OutBin").join("Quick");
+
+    run("{reports: true, graphs: true, docs: true, formats: [json]}");
+    assert!(run_dir.join("report.json").is_file());
+    assert!(!run_dir.join("graph.dot").exists());
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-reports", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped html, dot output(s) for slice Quick"));
+    assert!(layout.reports_dir.join("Quick.json").is_file());
+    assert!(!layout.reports_dir.join("Quick.html").exists());
+    assert!(!layout.graphs_dir.join("Quick.dot").exists());
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-docs", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped md output(s) for slice Quick"));
+
+    // Re-running with other outputs drops the artifacts the spec no longer asks for.
+    run("{graphs: true}");
+    assert!(!run_dir.join("report.json").exists());
+    assert!(run_dir.join("graph.dot").is_file());
+    let normalized = fs::read_to_string(run_dir.join("spec.yaml")).expect("spec.yaml");
+    assert!(normalized.contains("formats:"), "{normalized}");
+}
+
+#[test]
+fn verify_run_checks_outputs_against_the_manifest() {
+    let temp = tempdir().expect("temp dir");
+    let root = temp.path();
+
+    cargo_bin_cmd!("binary-slicer").arg("init-project").arg("--root").arg(root).assert().success();
+    let bin_path = root.join("libVerify.so");
+    fs::write(&bin_path, b"payload").expect("write binary");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .args(["--name", "VerifyBin"])
+        .assert()
+        .success();
+    let spec = root.join("verify.yaml");
+    fs::write(&spec, "name: VerifyRun\nbinary: VerifyBin\nroots: [main]\n").expect("spec");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root"])
+        .arg(root)
+        .arg("--file")
+        .arg(&spec)
+        .args(["--backend", "validate-only"])
+        .assert()
+        .success();
+    let verify = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["verify-run", "--root"]).arg(root).args([
+            "--binary",
+            "VerifyBin",
+            "--ritual",
+            "VerifyRun",
+        ]);
+        cmd
+    };
+
+    let run_dir =
+        ritual_core::db::ProjectLayout::new(root).binary_output_root("VerifyBin").join("VerifyRun");
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(run_dir.join("manifest.json")).expect("manifest"))
+            .expect("json");
+    let files: Vec<&str> =
+        manifest["files"].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap()).collect();
+    assert_eq!(files, ["graph.dot", "report.json", "run_metadata.json", "spec.yaml"]);
+    verify().assert().success().stdout(predicate::str::contains("Verified: 4 of 4 file(s)"));
+
+    // A truncated report (e.g. after a crash) fails verification.
+    fs::write(run_dir.join("report.json"), "{").expect("truncate");
+    verify()
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Modified: report.json"))
+        .stderr(predicate::str::contains(
+            "Run outputs for VerifyBin/VerifyRun failed verification (1 modified, 0 missing, 0 unlisted file(s))",
+        ));
+    let output = verify().arg("--json").assert().failure().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("json");
+    assert_eq!(report["ok"], false);
+    assert_eq!(report["mismatched"][0]["path"], "report.json");
+
+    fs::remove_file(run_dir.join("manifest.json")).expect("remove manifest");
+    verify().assert().failure().stderr(predicate::str::contains("No manifest.json in"));
+}
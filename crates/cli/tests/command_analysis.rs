@@ -1,9 +1,10 @@
 use assert_cmd::cargo::cargo_bin_cmd;
 use binary_slicer::commands::init_project_command;
+use predicates::prelude::*;
 use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
 use ritual_core::services::analysis::{
-    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceRecord, FunctionRecord,
-    RootHit,
+    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind, EvidenceRecord,
+    EvidenceSource, FunctionRecord, RootHit,
 };
 use serde_json::{self, Value};
 use tempfile::tempdir;
@@ -29,6 +30,12 @@ fn show_ritual_run_json_includes_persisted_analysis_counts() {
         status: RitualRunStatus::Succeeded,
         started_at: "t0".into(),
         finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let run_id = db.insert_ritual_run(&run).expect("insert run");
 
@@ -39,22 +46,43 @@ fn show_ritual_run_json_includes_persisted_analysis_counts() {
             size: Some(16),
             in_slice: false,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x1000,
+            to: 0x2000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x1000, to: 0x2000, is_cross_slice: false }],
         basic_blocks: vec![BasicBlock {
             start: 0x1000,
-            len: 8,
+            byte_len: 8,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x2000, kind: BlockEdgeKind::Jump }],
         }],
         evidence: vec![EvidenceRecord {
             address: 0x1000,
             description: "test-evidence".into(),
             kind: None,
+            source: None,
+            run_id: None,
         }],
         roots: vec!["entry_point".into()],
-        root_hits: vec![RootHit { root: "entry_point".into(), functions: vec![0x1000] }],
+        root_hits: vec![RootHit {
+            root: "entry_point".into(),
+            functions: vec![0x1000],
+            error: None,
+        }],
         backend_version: Some("rz-1.0".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
 
@@ -122,6 +150,12 @@ fn project_info_json_includes_analysis_summary() {
         status: RitualRunStatus::Succeeded,
         started_at: "t0".into(),
         finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let run_id = db.insert_ritual_run(&run).expect("insert run");
     let analysis = AnalysisResult {
@@ -131,22 +165,43 @@ fn project_info_json_includes_analysis_summary() {
             size: Some(12),
             in_slice: false,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x2000,
+            to: 0x3000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x2000, to: 0x3000, is_cross_slice: false }],
         basic_blocks: vec![BasicBlock {
             start: 0x2000,
-            len: 12,
+            byte_len: 12,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x3000, kind: BlockEdgeKind::Jump }],
         }],
         evidence: vec![EvidenceRecord {
             address: 0x2000,
             description: "evidence".into(),
             kind: None,
+            source: None,
+            run_id: None,
         }],
         roots: vec!["entry_point".into()],
-        root_hits: vec![RootHit { root: "entry_point".into(), functions: vec![0x2000] }],
+        root_hits: vec![RootHit {
+            root: "entry_point".into(),
+            functions: vec![0x2000],
+            error: None,
+        }],
         backend_version: Some("rz-1.0".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
 
@@ -201,6 +256,12 @@ fn list_ritual_runs_json_includes_analysis_summary() {
         status: RitualRunStatus::Succeeded,
         started_at: "t0".into(),
         finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let run_id = db.insert_ritual_run(&run).unwrap();
     let analysis = AnalysisResult {
@@ -210,18 +271,43 @@ fn list_ritual_runs_json_includes_analysis_summary() {
             size: Some(8),
             in_slice: false,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x3000,
+            to: 0x4000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x3000, to: 0x4000, is_cross_slice: false }],
         basic_blocks: vec![BasicBlock {
             start: 0x3000,
-            len: 8,
+            byte_len: 8,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x4000, kind: BlockEdgeKind::Jump }],
         }],
-        evidence: vec![EvidenceRecord { address: 0x3000, description: "list".into(), kind: None }],
+        evidence: vec![EvidenceRecord {
+            address: 0x3000,
+            description: "list".into(),
+            kind: None,
+            source: None,
+            run_id: None,
+        }],
         roots: vec!["entry_point".into()],
-        root_hits: vec![RootHit { root: "entry_point".into(), functions: vec![0x3000] }],
+        root_hits: vec![RootHit {
+            root: "entry_point".into(),
+            functions: vec![0x3000],
+            error: None,
+        }],
         backend_version: Some("rz-1.0".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(run_id, &analysis).unwrap();
 
@@ -250,3 +336,1007 @@ fn list_ritual_runs_json_includes_analysis_summary() {
     let root_hits = analysis_json["root_hits"].as_array().expect("root hits missing");
     assert_eq!(root_hits.len(), 1);
 }
+
+#[test]
+fn export_and_import_run_via_cli() {
+    let src = tempdir().unwrap();
+    let src_root = src.path().to_string_lossy().to_string();
+    init_project_command(&src_root, Some("Src".into())).unwrap();
+    let bin_path = src.path().join("libDemo.so");
+    std::fs::write(&bin_path, b"\x7fELF demo").unwrap();
+    binary_slicer::commands::add_binary_command(
+        &src_root,
+        &bin_path.to_string_lossy(),
+        None,
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let spec_path = src.path().join("rituals").join("demo.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Demo\nbinary: libDemo.so\nroots: [entry]\nbackend: validate-only\n",
+    )
+    .unwrap();
+    binary_slicer::commands::run_ritual_command(
+        &src_root,
+        &spec_path.to_string_lossy(),
+        None,
+        binary_slicer::commands::RunFlags::default(),
+        &[],
+        &[],
+    )
+    .unwrap();
+
+    let archive = src.path().join("share").join("demo.tar.zst");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["export-run", "--root", &src_root, "--binary", "libDemo.so", "--ritual", "Demo"])
+        .arg("--out")
+        .arg(&archive)
+        .assert()
+        .success();
+    assert!(archive.is_file());
+
+    let dst = tempdir().unwrap();
+    let dst_root = dst.path().to_string_lossy().to_string();
+    init_project_command(&dst_root, Some("Dst".into())).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["import-run", "--root", &dst_root, "--file"])
+        .arg(&archive)
+        .assert()
+        .success();
+    let layout = ProjectLayout::new(&dst_root);
+    let run_dir = layout.binary_output_root("libDemo.so").join("Demo");
+    assert!(run_dir.join("report.json").is_file());
+    assert!(run_dir.join("spec.yaml").is_file());
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    assert_eq!(db.list_ritual_runs(Some("libDemo.so")).unwrap().len(), 1);
+
+    // A second import without --force refuses to clobber outputs.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["import-run", "--root", &dst_root, "--file"])
+        .arg(&archive)
+        .assert()
+        .failure();
+
+    let tables = src.path().join("tables");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["export-tables", "--root", &src_root, "--binary", "libDemo.so"])
+        .args(["--ritual", "Demo", "--format", "csv", "--out"])
+        .arg(&tables)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported run libDemo.so / Demo"))
+        .stdout(predicate::str::contains("functions.csv: 0 row(s)"));
+    // validate-only finds nothing, but every table still gets its header.
+    let evidence = std::fs::read_to_string(tables.join("evidence.csv")).unwrap();
+    assert_eq!(evidence, "address,kind,source,description\n");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["export-tables", "--root", &src_root, "--binary", "libDemo.so"])
+        .args(["--ritual", "Missing", "--out"])
+        .arg(&tables)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No run of ritual 'Missing'"));
+}
+
+#[test]
+fn query_command_filters_persisted_analysis() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("QueryProj".into())).unwrap();
+    let bin_path = temp.path().join("libQuery.so");
+    std::fs::write(&bin_path, b"\x7fELF query").unwrap();
+    binary_slicer::commands::add_binary_command(
+        &root,
+        &bin_path.to_string_lossy(),
+        None,
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "libQuery.so".into(),
+            ritual: "Net".into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let function = |address: u64, name: &str, size: u32, in_slice: bool| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(size),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let analysis = AnalysisResult {
+        functions: vec![
+            function(0x1000, "big_in_slice", 1024, true),
+            function(0x2000, "big_outside", 4096, false),
+            function(0x3000, "small_in_slice", 32, true),
+        ],
+        call_edges: Vec::new(),
+        basic_blocks: vec![BasicBlock {
+            start: 0x3000,
+            byte_len: 8,
+            insn_count: Some(2),
+            successors: vec![BlockEdge { target: 0x3000, kind: BlockEdgeKind::ConditionalJump }],
+        }],
+        evidence: vec![EvidenceRecord {
+            address: 0x1004,
+            description: "string: http://api.example".into(),
+            kind: Some(ritual_core::services::analysis::EvidenceKind::String),
+            source: None,
+            run_id: None,
+        }],
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).unwrap();
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["query", "--root", &root, "--binary", "libQuery.so", "--ritual", "Net", "--json"])
+        .arg("functions where size > 512 and in_slice")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let rows: Value = serde_json::from_slice(&output).unwrap();
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["name"], "big_in_slice");
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["query", "--root", &root, "--binary", "libQuery.so", "--ritual", "Net", "--json"])
+        .arg("functions order by complexity desc limit 20")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let rows: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(rows[0]["name"], "small_in_slice");
+    assert_eq!(rows[0]["complexity"], 2);
+    assert_eq!(rows[0]["insn_count"], 2);
+    assert_eq!(rows[1]["complexity"], Value::Null);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["query", "--root", &root, "--binary", "libQuery.so", "--ritual", "Net"])
+        .arg(r#"evidence where kind = string and description contains "http""#)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("0x1004").and(predicates::str::contains("(1 row)")));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["query", "--root", &root, "--binary", "libQuery.so", "--ritual", "Net"])
+        .arg("functions where colour = red")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Unknown field 'colour'"));
+}
+
+#[test]
+fn search_command_finds_names_and_evidence_across_runs() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("SearchProj".into())).unwrap();
+    binary_slicer::commands::init_slice_command(&root, "Updater", None, None).unwrap();
+    for name in ["libApp.so", "libOther.so"] {
+        let bin_path = temp.path().join(name);
+        std::fs::write(&bin_path, name.as_bytes()).unwrap();
+        binary_slicer::commands::add_binary_command(
+            &root,
+            &bin_path.to_string_lossy(),
+            None,
+            None,
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let run = |binary: &str, ritual: &str| {
+        db.insert_ritual_run(&RitualRunRecord {
+            binary: binary.into(),
+            ritual: ritual.into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap()
+    };
+    let analysis = |function: &str, evidence: Vec<(u64, &str, EvidenceKind)>| AnalysisResult {
+        functions: vec![FunctionRecord {
+            address: 0x1000,
+            name: Some(function.into()),
+            size: Some(16),
+            in_slice: true,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: Vec::new(),
+        basic_blocks: Vec::new(),
+        evidence: evidence
+            .into_iter()
+            .map(|(address, description, kind)| EvidenceRecord {
+                address,
+                description: description.into(),
+                source: Some(EvidenceSource::new(
+                    "capstone",
+                    if kind == EvidenceKind::Import { "imports" } else { "strings" },
+                )),
+                kind: Some(kind),
+                run_id: None,
+            })
+            .collect(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    let stale = run("libApp.so", "Updater");
+    db.insert_analysis_result(stale, &analysis("AutoUpdate_old", Vec::new())).unwrap();
+    let latest = run("libApp.so", "Updater");
+    db.insert_analysis_result(
+        latest,
+        &analysis(
+            "AutoUpdate::check",
+            vec![
+                (0x1010, "string: https://updates.example/AUTOUPDATE", EvidenceKind::String),
+                (0x1020, "import: autoupdate_schedule", EvidenceKind::Import),
+                (0x1030, "string: unrelated", EvidenceKind::String),
+            ],
+        ),
+    )
+    .unwrap();
+    let other = run("libOther.so", "Scratch");
+    db.insert_analysis_result(
+        other,
+        &analysis("main", vec![(0x2000, "string: autoupdate disabled", EvidenceKind::String)]),
+    )
+    .unwrap();
+
+    let search = |args: &[&str]| {
+        let output = cargo_bin_cmd!("binary-slicer")
+            .args(["search", "--root", &root, "--json"])
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice::<Value>(&output).unwrap().as_array().unwrap().clone()
+    };
+
+    // Case-insensitive over the latest run of each binary/ritual only.
+    let hits = search(&["AutoUpdate"]);
+    let texts: Vec<&str> = hits.iter().map(|h| h["text"].as_str().unwrap()).collect();
+    assert_eq!(
+        texts,
+        [
+            "AutoUpdate::check",
+            "string: https://updates.example/AUTOUPDATE",
+            "import: autoupdate_schedule",
+            "string: autoupdate disabled",
+        ]
+    );
+    assert_eq!(hits[0]["binary"], "libApp.so");
+    assert_eq!(hits[0]["run_id"], latest);
+    assert_eq!(hits[0]["slice"], "Updater");
+    assert_eq!(hits[0]["kind"], "function");
+    assert_eq!(hits[3]["slice"], Value::Null);
+
+    let hits = search(&["autoupdate", "--binary", "libApp.so", "--kind", "string"]);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["address"], 0x1010);
+    let hits = search(&["^autoupdate_", "--regex", "--kind", "function"]);
+    assert!(hits.is_empty());
+    let hits = search(&["autoupdate_\\w+$", "--regex"]);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["kind"], "import");
+    assert_eq!(hits[0]["source"], "capstone/imports");
+
+    // A source filter matches a backend or a backend/pass, and skips function names.
+    assert_eq!(search(&["autoupdate", "--source", "capstone"]).len(), 3);
+    let hits = search(&["autoupdate", "--source", "capstone/imports"]);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["address"], 0x1020);
+    assert!(search(&["autoupdate", "--source", "rizin"]).is_empty());
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["search", "--root", &root, "autoupdate", "--kind", "function"])
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("libApp.so")
+                .and(predicates::str::contains(format!("Updater #{}", latest)))
+                .and(predicates::str::contains("0x1000"))
+                .and(predicates::str::contains("(1 match)")),
+        );
+    cargo_bin_cmd!("binary-slicer")
+        .args(["search", "--root", &root, "x", "--kind", "symbol"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Unknown search kind 'symbol'"));
+}
+
+#[test]
+fn run_ritual_pipeline_feeds_stages_and_resumes_after_failure() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("PipelineProj".into())).unwrap();
+
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+
+    // `carve` names a backend that is not compiled in, so the first attempt stops there.
+    let spec_path = temp.path().join("pipeline.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Pipeline\nbinary: demo\nroots: [helper]\nstages:\n  - name: carve\n    needs: symbols\n    backend: no-such-backend\n  - name: symbols\n    backend: capstone\n",
+    )
+    .unwrap();
+    let run = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["run-ritual", "--root", &root, "--file"]).arg(&spec_path);
+        cmd
+    };
+    run()
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Stage symbols (capstone)"))
+        .stderr(predicate::str::contains("Pipeline stage 'carve' failed"));
+
+    let run_dir = ProjectLayout::new(&root).binary_output_root("demo").join("Pipeline");
+    let state: Value =
+        serde_json::from_slice(&std::fs::read(run_dir.join("stages.json")).unwrap()).unwrap();
+    assert_eq!(state["stages"][0]["name"], "symbols");
+    assert_eq!(state["stages"][0]["status"], "succeeded");
+    assert_eq!(state["stages"][1]["status"], "failed");
+    assert!(state["stages"][1]["error"].as_str().unwrap().contains("no-such-backend"));
+    assert!(run_dir.join("stages/symbols.json").is_file());
+
+    // Rerunning the same spec resumes at `carve`; the override lets it succeed.
+    run()
+        .args(["--backend", "validate-only"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Stage symbols: reused result"))
+        .stdout(predicate::str::contains("Stage carve (validate-only)"));
+
+    let report: Value =
+        serde_json::from_slice(&std::fs::read(run_dir.join("report.json")).unwrap()).unwrap();
+    assert_eq!(report["backend"], "validate-only");
+    assert_eq!(report["stages"][1]["status"], "succeeded");
+    let helper = fixture.functions.iter().find(|f| f.name == "helper").unwrap();
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let analysis = db.load_analysis_result("demo", "Pipeline").unwrap().unwrap();
+    assert_eq!(analysis.roots, vec![format!("0x{:X}", helper.address)], "roots fed by symbols");
+
+    // A finished pipeline is not resumed.
+    run().assert().failure().stderr(predicate::str::contains("already exists"));
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "demo", "--ritual", "Pipeline"])
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let shown: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(shown["stages"][1]["backend"], "validate-only");
+}
+
+#[test]
+fn run_ritual_rejects_pipeline_cycles() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("PipelineProj".into())).unwrap();
+    let spec_path = temp.path().join("cycle.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Cycle\nbinary: demo\nroots: [main]\nstages:\n  - {name: a, needs: b}\n  - {name: b, needs: [a]}\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file"])
+        .arg(&spec_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("dependency cycle: a, b"));
+}
+
+#[test]
+fn batch_run_executes_all_specs_and_summarizes_failures() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("BatchProj".into())).unwrap();
+
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    for name in ["alpha", "beta"] {
+        let bin_path = temp.path().join(format!("{name}.elf"));
+        std::fs::write(&bin_path, &fixture.bytes).unwrap();
+        cargo_bin_cmd!("binary-slicer")
+            .args(["add-binary", "--root", &root, "--name", name, "--skip-hash", "--path"])
+            .arg(&bin_path)
+            .assert()
+            .success();
+    }
+    let rituals = ProjectLayout::new(&root).rituals_dir;
+    std::fs::write(rituals.join("a.yaml"), "name: RunA\nbinary: alpha\nroots: [main]\n").unwrap();
+    std::fs::write(
+        rituals.join("b.json"),
+        r#"{"name": "RunB", "binary": "beta", "roots": ["helper"]}"#,
+    )
+    .unwrap();
+    std::fs::write(rituals.join("c.yaml"), "name: RunC\nbinary: gamma\nroots: [main]\n").unwrap();
+
+    let batch = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["batch-run", "--root", &root, "--jobs", "2", "--backend", "capstone"]);
+        cmd
+    };
+    let output = batch()
+        .arg("--json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 of 3 ritual run(s) failed"))
+        .get_output()
+        .stdout
+        .clone();
+    let summary: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(summary["jobs"], 2);
+    assert_eq!(summary["succeeded"], 2);
+    let runs = summary["runs"].as_array().unwrap();
+    let by_ritual = |name: &str| runs.iter().find(|r| r["ritual"] == name).cloned();
+    assert_eq!(by_ritual("RunA").unwrap()["status"], "succeeded");
+    assert_eq!(by_ritual("RunB").unwrap()["binary"], "beta");
+    assert!(by_ritual("RunB").unwrap()["functions"].as_u64().unwrap() >= 1);
+    let failed: Vec<_> = runs.iter().filter(|r| r["status"] == "failed").collect();
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0]["spec"].as_str().unwrap().ends_with("c.yaml"));
+    assert!(failed[0]["error"].as_str().unwrap().contains("gamma"));
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let recorded: Vec<String> =
+        db.list_ritual_runs(None).unwrap().into_iter().map(|r| r.ritual).collect();
+    assert_eq!(recorded.len(), 2);
+    assert!(recorded.contains(&"RunA".to_string()) && recorded.contains(&"RunB".to_string()));
+    assert!(layout.binary_output_root("beta").join("RunB/report.json").is_file());
+
+    // Existing outputs are reported per spec unless --force is given.
+    std::fs::remove_file(rituals.join("c.yaml")).unwrap();
+    batch().assert().failure().stdout(predicate::str::contains("already exists"));
+    batch()
+        .arg("--force")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Batch run (2 spec(s), 2 job(s))"))
+        .stdout(predicate::str::contains("2 succeeded, 0 failed"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["batch-run", "--root", &root, "--jobs", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--jobs must be at least 1"));
+}
+
+#[test]
+fn log_json_writes_run_events_and_verbose_logs_to_stderr() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("LogProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    let spec_path = temp.path().join("log.yaml");
+    std::fs::write(&spec_path, "name: Logged\nbinary: demo\nroots: [main]\n").unwrap();
+    let run_dir = ProjectLayout::new(&root).binary_output_root("demo").join("Logged");
+    let log_path = run_dir.join("run.log.jsonl");
+
+    // Quiet by default: no debug output and no event log.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+    assert!(!log_path.exists());
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["--verbose", "run-ritual", "--root", &root, "--backend", "capstone", "--force"])
+        .arg("--file")
+        .arg(&spec_path)
+        .args(["--log-json", "--no-cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Log: "))
+        .stderr(predicate::str::contains("command{subcommand=run-ritual}"))
+        .stderr(predicate::str::contains("backend finished"));
+
+    let events: Vec<Value> = std::fs::read_to_string(&log_path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let message = |e: &Value, text: &str| e["fields"]["message"] == text;
+    let finished = events.iter().find(|e| message(e, "backend finished")).unwrap();
+    assert_eq!(finished["level"], "INFO");
+    assert_eq!(finished["fields"]["functions"], fixture.functions.len());
+    let spans: Vec<&str> =
+        finished["spans"].as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(spans, vec!["command", "run", "backend"]);
+    assert_eq!(finished["spans"][2]["backend"], "capstone");
+    // Closing the backend span reports its timings, inside the spans that enclose it.
+    let closed = events
+        .iter()
+        .find(|e| message(e, "close") && e["target"] == "ritual_core::services::analysis")
+        .unwrap();
+    assert_eq!(closed["spans"].as_array().unwrap().len(), 2);
+    assert!(closed["fields"]["time.busy"].is_string());
+    assert!(events.iter().any(|e| message(e, "recorded ritual run")));
+}
+
+#[test]
+fn run_ritual_resolves_address_regex_and_demangled_roots() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("RootsProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    let spec_path = temp.path().join("roots.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Patterns\nbinary: demo\nroots:\n  - { address: 0x401010 }\n  - { regex: \"ma.*\" }\n  - { demangled: \"Game::tick\" }\n  - { regex: \"(\" }\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success();
+
+    let run_dir = ProjectLayout::new(&root).binary_output_root("demo").join("Patterns");
+    let report: Value =
+        serde_json::from_str(&std::fs::read_to_string(run_dir.join("report.json")).unwrap())
+            .unwrap();
+    let hits = report["root_hits"].as_array().expect("root hits");
+    let hit = |root: &str| hits.iter().find(|h| h["root"] == root).expect(root).clone();
+    assert_eq!(hit("0x401010")["functions"], serde_json::json!([0x401010]));
+    assert_eq!(hit("regex:ma.*")["functions"], serde_json::json!([0x401000]));
+    assert_eq!(hit("demangled:Game::tick")["error"], "no function matched");
+    assert!(hit("regex:(")["error"].as_str().unwrap().starts_with("Invalid root regex"));
+
+    // Matched roots put their functions in the slice.
+    let functions = report["functions"].as_array().unwrap();
+    assert!(functions.iter().filter(|f| f["in_slice"] == true).count() >= 2);
+
+    // The normalized spec keeps the structured entries.
+    let spec = std::fs::read_to_string(run_dir.join("spec.yaml")).unwrap();
+    assert!(spec.contains("regex: ma.*"), "{spec}");
+    assert!(spec.contains("address: 4198416"), "{spec}");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "demo", "--ritual", "Patterns"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("demangled:Game::tick -> (no function matched)"));
+}
+
+#[test]
+fn run_ritual_seeds_roots_from_referenced_strings() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("StringsProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64_strings();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    let spec_path = temp.path().join("seed.yaml");
+    std::fs::write(&spec_path, "name: Seeded\nbinary: demo\nroots: []\nmax_depth: 0\n").unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .args(["--seed-from-string", "AutoUpdate", "--seed-from-string", "Not present"])
+        .assert()
+        .success();
+
+    let run_dir = ProjectLayout::new(&root).binary_output_root("demo").join("Seeded");
+    let report: Value =
+        serde_json::from_str(&std::fs::read_to_string(run_dir.join("report.json")).unwrap())
+            .unwrap();
+    let hits = report["root_hits"].as_array().expect("root hits");
+    let hit = |root: &str| hits.iter().find(|h| h["root"] == root).expect(root).clone();
+    assert_eq!(hit("string:AutoUpdate")["functions"], serde_json::json!([0x401000, 0x401020]));
+    assert_eq!(hit("string:Not present")["error"], "no function references this string");
+
+    let in_slice: Vec<&str> = report["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["in_slice"] == true)
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert!(in_slice.contains(&"main") && in_slice.contains(&"updater"), "{in_slice:?}");
+    let evidence = report["evidence"].as_array().unwrap();
+    assert!(evidence.iter().any(|e| e["address"] == 0x401000
+        && e["description"] == "string xref: \"AutoUpdate: checking for patches\" @ 0x402000"));
+
+    // The seeds are part of the normalized spec, so a rerun reproduces them.
+    let spec = std::fs::read_to_string(run_dir.join("spec.yaml")).unwrap();
+    assert!(spec.contains("string: AutoUpdate"), "{spec}");
+}
+
+#[test]
+fn emit_slice_reports_writes_sarif_with_section_addresses() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("SarifProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Boot"])
+        .assert()
+        .success();
+    let spec_path = temp.path().join("boot.yaml");
+    std::fs::write(&spec_path, "name: Boot\nbinary: demo\nroots: [main, missing]\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-reports", "--root", &root, "--format", "sarif"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Emitted slice SARIF report"));
+
+    let layout = ProjectLayout::new(&root);
+    let sarif_path = layout.reports_dir.join("Boot.sarif");
+    let log: Value = serde_json::from_str(&std::fs::read_to_string(&sarif_path).unwrap()).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["properties"]["ritual"], "Boot");
+    assert!(run["addresses"].as_array().unwrap().iter().any(|a| a["name"] == ".text"));
+    let results = run["results"].as_array().unwrap();
+    let main = results
+        .iter()
+        .find(|r| r["locations"][0]["logicalLocations"][0]["name"] == "main")
+        .expect("main result");
+    assert_eq!(main["locations"][0]["physicalLocation"]["address"]["absoluteAddress"], 0x401000);
+    assert_eq!(main["properties"]["section"], ".text");
+    assert!(results.iter().any(|r| r["ruleId"] == "root/unmatched"));
+    // SARIF mode does not emit the JSON/HTML reports.
+    assert!(!layout.reports_dir.join("Boot.json").exists());
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-reports", "--root", &root, "--format", "xml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown report format 'xml'"));
+}
+
+#[test]
+fn graph_options_from_spec_and_flags_shape_dot_output() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("GraphProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Boot"])
+        .assert()
+        .success();
+    let spec_path = temp.path().join("boot.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Boot\nbinary: demo\nroots: [main]\noutputs:\n  graphs: {cluster: true, collapse_blocks: true}\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success();
+
+    let layout = ProjectLayout::new(&root);
+    let run_dir = layout.binary_output_root("demo").join("Boot");
+    let dot = std::fs::read_to_string(run_dir.join("graph.dot")).unwrap();
+    assert!(dot.contains("subgraph \"cluster_slice\" {"), "{dot}");
+    assert!(!dot.contains("shape=ellipse"), "{dot}");
+    // The normalized spec keeps the options so reruns render the same graph.
+    let spec = std::fs::read_to_string(run_dir.join("spec.yaml")).unwrap();
+    assert!(spec.contains("collapse_blocks: true"), "{spec}");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["emit-slice-reports", "--root", &root, "--graph-max-nodes", "1"])
+        .assert()
+        .success();
+    let dot = std::fs::read_to_string(layout.graphs_dir.join("Boot.dot")).unwrap();
+    assert!(dot.contains("\"fn:main\" [label"), "{dot}");
+    assert!(dot.contains("more nodes\" shape=note overflow=true"), "{dot}");
+}
+
+#[test]
+fn serve_answers_json_rpc_over_stdio() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("ServeProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64_strings();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    let spec_path = temp.path().join("serve.yaml");
+    std::fs::write(&spec_path, "name: Served\nbinary: demo\nroots: [main]\nbackend: capstone\n")
+        .unwrap();
+
+    let requests = [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "run_ritual",
+            "params": {"file": spec_path.to_string_lossy()}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "list_runs",
+            "params": {"binary": "demo"}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 4, "method": "function_evidence",
+            "params": {"binary": "demo", "ritual": "Served", "function": "main"}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 5, "method": "xrefs",
+            "params": {"binary": "demo", "ritual": "Served", "to": "0x402000"}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 6, "method": "query",
+            "params": {"binary": "demo", "ritual": "Served", "query": "functions where in_slice"}}),
+        // Notifications get no response.
+        serde_json::json!({"jsonrpc": "2.0", "method": "list_slices"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 7, "method": "frobnicate"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 8, "method": "xrefs", "params": {}}),
+    ];
+    let mut stdin: String = requests.iter().map(|r| format!("{r}\n")).collect();
+    stdin.push_str("{not json\n");
+    stdin.push_str("{\"jsonrpc\":\"2.0\",\"method\":\"exit\"}\n");
+    stdin.push_str("{\"jsonrpc\":\"2.0\",\"id\":99,\"method\":\"initialize\"}\n");
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["serve", "--root", &root])
+        .write_stdin(stdin)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let responses: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // Nothing is answered after `exit`.
+    assert_eq!(responses.len(), 9, "{responses:?}");
+    let by_id = |id: i64| responses.iter().find(|r| r["id"] == id).expect("response").clone();
+
+    let init = by_id(1);
+    assert!(init["result"]["methods"].as_array().unwrap().iter().any(|m| m == "run_ritual"));
+    let run = by_id(2)["result"].clone();
+    assert_eq!(run["ritual"], "Served");
+    assert_eq!(run["binary"], "demo");
+    assert!(run["functions"].as_u64().unwrap() >= 3, "{run}");
+    let runs = by_id(3)["result"].clone();
+    assert_eq!(runs.as_array().unwrap().len(), 1);
+    assert_eq!(runs[0]["ritual"], "Served");
+
+    let evidence = by_id(4)["result"].clone();
+    assert_eq!(evidence["function"]["address"], 0x401000);
+    let evidence = evidence["evidence"].as_array().unwrap();
+    assert!(!evidence.is_empty());
+    assert!(evidence
+        .iter()
+        .all(|e| e["address"].as_u64().unwrap() >= 0x401000
+            && e["address"].as_u64().unwrap() < 0x401010));
+
+    let xrefs = by_id(5)["result"]["xrefs"].as_array().unwrap().clone();
+    assert!(!xrefs.is_empty());
+    assert!(xrefs.iter().all(|x| x["to"] == 0x402000), "{xrefs:?}");
+    let rows = by_id(6)["result"].as_array().unwrap().clone();
+    assert!(rows.iter().any(|r| r["name"] == "main"), "{rows:?}");
+
+    assert_eq!(by_id(7)["error"]["code"], -32601);
+    assert_eq!(by_id(8)["error"]["code"], -32602);
+    let parse_error = responses.iter().find(|r| r["id"].is_null()).expect("parse error");
+    assert_eq!(parse_error["error"]["code"], -32700);
+}
+
+#[test]
+fn serve_answers_content_length_framed_requests_in_kind() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("FramedProj".into())).unwrap();
+    let mut server = binary_slicer::commands::Server::open(&root).unwrap();
+
+    let body = r#"{"jsonrpc":"2.0","id":"a","method":"list_slices"}"#;
+    let input = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let mut output = Vec::new();
+    binary_slicer::commands::serve_stream(&mut server, input.as_bytes(), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let (header, payload) = output.split_once("\r\n\r\n").expect("framed response");
+    assert_eq!(header, format!("Content-Length: {}", payload.len()));
+    let response: Value = serde_json::from_str(payload).unwrap();
+    assert_eq!(response["id"], "a");
+    assert_eq!(response["result"], serde_json::json!([]));
+}
+
+#[test]
+fn slice_docs_show_inferred_signatures() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("SigProj".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Boot"])
+        .assert()
+        .success();
+    let spec_path = temp.path().join("boot.yaml");
+    std::fs::write(&spec_path, "name: Boot\nbinary: demo\nroots: [main]\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success();
+
+    // The signature is persisted with the run...
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let result = db.load_analysis_result("demo", "Boot").unwrap().expect("analysis");
+    let main = result.functions.iter().find(|f| f.address == 0x401000).expect("main");
+    let sig = main.signature.as_ref().expect("inferred signature");
+    assert_eq!(sig.to_string(), "sysv64(0 params)", "main only writes rdi before its call");
+
+    // ...and surfaces in the slice doc's function tags.
+    cargo_bin_cmd!("binary-slicer").args(["emit-slice-docs", "--root", &root]).assert().success();
+    let doc =
+        std::fs::read_to_string(ProjectLayout::new(&root).slices_docs_dir.join("Boot.md")).unwrap();
+    let main_line = doc.lines().find(|l| l.contains("</a>main @ 0x401000")).expect("main entry");
+    assert!(main_line.contains("sig=sysv64(0 params)"), "{main_line}");
+}
+
+#[test]
+fn slice_docs_list_the_strings_each_function_uses() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("StringDocs".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64_strings();
+    let bin_path = temp.path().join(fixture.file_name);
+    std::fs::write(&bin_path, &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--name", "demo", "--path"])
+        .arg(&bin_path)
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Boot"])
+        .assert()
+        .success();
+    let spec_path = temp.path().join("boot.yaml");
+    std::fs::write(&spec_path, "name: Boot\nbinary: demo\nroots: [main]\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "capstone", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer").args(["emit-slice-docs", "--root", &root]).assert().success();
+    let doc =
+        std::fs::read_to_string(ProjectLayout::new(&root).slices_docs_dir.join("Boot.md")).unwrap();
+    // The lines nested under one function's entry.
+    let entry = |name: &str| -> Vec<&str> {
+        let marker = format!("</a>{name} @ ");
+        let mut lines = doc.lines().skip_while(|l| !l.contains(&marker));
+        lines.next();
+        lines.take_while(|l| l.starts_with("  ")).collect()
+    };
+    assert!(
+        entry("main").contains(&"  - Uses strings: \"AutoUpdate: checking for patches\""),
+        "{doc}"
+    );
+    // A reference into the middle of a string names the whole literal.
+    assert!(
+        entry("updater").contains(&"  - Uses strings: \"AutoUpdate: checking for patches\""),
+        "{doc}"
+    );
+    assert!(entry("helper").contains(&"  - Uses strings: \"Network ready\""), "{doc}");
+}
@@ -22,6 +22,7 @@ fn project_info_reports_binaries_and_slices() {
         Some("x86_64".into()),
         None,
         false,
+        None,
     )
     .unwrap();
     init_slice_command(&root, "SliceA", Some("Test slice".into()), None).unwrap();
@@ -38,6 +39,12 @@ fn project_info_reports_binaries_and_slices() {
         status: ritual_core::db::RitualRunStatus::Succeeded,
         started_at: "t0".into(),
         finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     db.insert_ritual_run(&run).unwrap();
 
@@ -1,15 +1,16 @@
 use binary_slicer::commands::{
     clean_outputs_command, emit_slice_docs_command, emit_slice_reports_command,
     init_project_command, list_ritual_runs_command, list_slices_command, project_info_command,
-    setup_backend_command, show_ritual_run_command,
+    setup_backend_command, show_ritual_run_command, SliceReportFormat,
 };
+use ritual_core::analysis::graph::DotOptions;
 use tempfile::tempdir;
 
 #[test]
 fn list_slices_errors_when_config_missing() {
     let temp = tempdir().unwrap();
     let root = temp.path().to_string_lossy().to_string();
-    let err = list_slices_command(&root, false).unwrap_err();
+    let err = list_slices_command(&root, false, false, None).unwrap_err();
     assert!(err.to_string().contains("Failed to read project config"), "unexpected error: {err}");
 }
 
@@ -25,7 +26,9 @@ fn emit_slice_reports_errors_when_db_missing() {
     cfg.db.path = ".ritual/project.json/bad.db".into();
     std::fs::write(&layout.project_config_path, serde_json::to_string_pretty(&cfg).unwrap())
         .unwrap();
-    let err = emit_slice_reports_command(&root, None).unwrap_err();
+    let err =
+        emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+            .unwrap_err();
     assert!(err.to_string().contains("Failed to open project database"), "unexpected error: {err}");
 }
 
@@ -91,7 +94,7 @@ fn list_ritual_runs_filter_no_matches() {
     let root = temp.path().to_string_lossy().to_string();
     init_project_command(&root, Some("NoRunsProj".into())).unwrap();
     // Should print none even with a filter that does not match.
-    list_ritual_runs_command(&root, Some("MissingBin"), false).unwrap();
+    list_ritual_runs_command(&root, Some("MissingBin"), false, None).unwrap();
 }
 
 #[test]
@@ -106,7 +109,7 @@ fn emit_slice_docs_errors_when_db_missing() {
     cfg.db.path = ".ritual/project.json/bad_docs.db".into();
     std::fs::write(&layout.project_config_path, serde_json::to_string_pretty(&cfg).unwrap())
         .unwrap();
-    let err = emit_slice_docs_command(&root).unwrap_err();
+    let err = emit_slice_docs_command(&root, 0.0, None).unwrap_err();
     assert!(err.to_string().contains("Failed to open project database"), "unexpected error: {err}");
 }
 
@@ -167,6 +170,7 @@ fn add_binary_errors_when_config_missing() {
         None,
         None,
         false,
+        None,
     )
     .unwrap_err();
     assert!(err.to_string().contains("Failed to read project config"), "unexpected error: {err}");
@@ -176,7 +180,7 @@ fn add_binary_errors_when_config_missing() {
 fn list_binaries_errors_when_config_missing() {
     let temp = tempdir().unwrap();
     let root = temp.path().to_string_lossy().to_string();
-    let err = binary_slicer::commands::list_binaries_command(&root, false).unwrap_err();
+    let err = binary_slicer::commands::list_binaries_command(&root, false, None).unwrap_err();
     assert!(err.to_string().contains("Failed to read project config"), "unexpected error: {err}");
 }
 
@@ -184,7 +188,7 @@ fn list_binaries_errors_when_config_missing() {
 fn list_ritual_runs_errors_when_config_missing() {
     let temp = tempdir().unwrap();
     let root = temp.path().to_string_lossy().to_string();
-    let err = list_ritual_runs_command(&root, None, false).unwrap_err();
+    let err = list_ritual_runs_command(&root, None, false, None).unwrap_err();
     assert!(err.to_string().contains("Failed to read project config"), "unexpected error: {err}");
 }
 
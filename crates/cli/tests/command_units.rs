@@ -1,12 +1,15 @@
 use assert_cmd::cargo::cargo_bin_cmd;
 use binary_slicer::commands::{
-    add_binary_command, clean_outputs_command, collect_ritual_runs_on_disk, collect_ritual_specs,
-    emit_slice_docs_command, emit_slice_reports_command, init_project_command, init_slice_command,
-    list_backends_command, list_binaries_command, list_ritual_runs_command,
-    list_ritual_specs_command, list_slices_command, project_info_command, rerun_ritual_command,
-    run_ritual_command, sha256_bytes, show_ritual_run_command, update_ritual_run_status_command,
-    validate_run_status, RitualRunMetadata, RitualSpec,
+    add_binary_command, cache_clear_command, cache_stats_command, clean_outputs_command,
+    collect_ritual_runs_on_disk, collect_ritual_specs, emit_slice_docs_command,
+    emit_slice_reports_command, init_project_command, init_slice_command, list_backends_command,
+    list_binaries_command, list_ritual_runs_command, list_ritual_specs_command,
+    list_slices_command, project_info_command, rerun_ritual_command, run_ritual_command,
+    sha256_bytes, show_ritual_run_command, update_ritual_run_status_command, validate_run_status,
+    AnalysisOverrides, BinarySelector, RitualRunMetadata, RitualSpec, RunFlags, RunTarget,
+    SliceReportFormat,
 };
+use ritual_core::analysis::graph::DotOptions;
 use ritual_core::db::RitualRunStatus;
 use tempfile::tempdir;
 
@@ -28,17 +31,216 @@ fn rejects_unknown_status() {
 fn ritual_spec_validation_rejects_missing_fields() {
     let invalid = RitualSpec {
         name: "".to_string(),
-        binary: "".to_string(),
-        roots: vec![],
+        binary: "".into(),
+        roots: Vec::new().into(),
         max_depth: None,
         backend: None,
         description: None,
         outputs: None,
+        stages: Vec::new(),
+        epoch: None,
+        raw: None,
+        timeout: None,
+        plugins: Vec::new(),
+        string_decoders: Vec::new(),
+        ghidra_xml: None,
     };
     let err = invalid.validate().unwrap_err();
     assert!(err.to_string().contains("required"));
 }
 
+#[test]
+fn ritual_spec_validation_rejects_zero_timeout() {
+    let spec: RitualSpec =
+        serde_yaml::from_str("name: A\nbinary: Game\nroots: [entry]\ntimeout: 0\n").unwrap();
+    let err = spec.validate().unwrap_err();
+    assert!(err.to_string().contains("'timeout' must be at least 1 second"));
+
+    let spec: RitualSpec =
+        serde_yaml::from_str("name: A\nbinary: Game\nroots: [entry]\ntimeout: 30\n").unwrap();
+    spec.validate().unwrap();
+    assert_eq!(spec.timeout, Some(30));
+}
+
+#[test]
+fn ritual_spec_parses_binary_selector_forms() {
+    let by_name: RitualSpec =
+        serde_yaml::from_str("name: A\nbinary: libGame.so\nroots: [entry]\n").unwrap();
+    assert_eq!(by_name.binary, BinarySelector::Name("libGame.so".into()));
+    assert_eq!(by_name.binary.hash(), None);
+
+    let by_hash: RitualSpec =
+        serde_yaml::from_str("name: B\nbinary: sha256:abcd\nroots: [entry]\n").unwrap();
+    assert_eq!(by_hash.binary.hash(), Some("abcd"));
+
+    let qualified: RitualSpec =
+        serde_yaml::from_str("name: C\nbinary: {name: libGame.so, arch: arm64}\nroots: [entry]\n")
+            .unwrap();
+    assert_eq!(
+        qualified.binary,
+        BinarySelector::Qualified { name: "libGame.so".into(), arch: Some("arm64".into()) }
+    );
+    assert_eq!(qualified.binary.to_string(), "libGame.so (arm64)");
+
+    let empty_hash: RitualSpec =
+        serde_yaml::from_str("name: D\nbinary: 'sha256:'\nroots: [entry]\n").unwrap();
+    assert!(empty_hash.validate().is_err());
+}
+
+#[test]
+fn run_ritual_resolves_binary_by_hash_or_arch() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("IdentityProj".into())).unwrap();
+
+    for (arch, payload) in [("armv7", b"armv7 payload"), ("arm64", b"arm64 payload")] {
+        let dir = temp.path().join(arch);
+        std::fs::create_dir_all(&dir).unwrap();
+        let bin_path = dir.join("libGame.so");
+        std::fs::write(&bin_path, payload).unwrap();
+        add_binary_command(
+            &root,
+            bin_path.to_str().unwrap(),
+            Some("libGame.so".into()),
+            Some(arch.into()),
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+
+    // A bare name now matches two binaries and must not silently pick one.
+    let ambiguous = temp.path().join("ambiguous.yaml");
+    std::fs::write(&ambiguous, "name: ByName\nbinary: libGame.so\nroots: [entry]\n").unwrap();
+    let err =
+        run_ritual_command(&root, ambiguous.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+            .unwrap_err();
+    assert!(err.to_string().contains("ambiguous"), "unexpected error: {err}");
+
+    let by_arch = temp.path().join("arch.yaml");
+    std::fs::write(
+        &by_arch,
+        "name: ByArch\nbinary: {name: libGame.so, arch: arm64}\nroots: [entry]\n",
+    )
+    .unwrap();
+    run_ritual_command(&root, by_arch.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
+
+    let armv7_hash = sha256_bytes(b"armv7 payload");
+    let by_hash = temp.path().join("hash.yaml");
+    std::fs::write(
+        &by_hash,
+        format!("name: ByHash\nbinary: sha256:{}\nroots: [entry]\n", &armv7_hash[..12]),
+    )
+    .unwrap();
+    run_ritual_command(&root, by_hash.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
+
+    // The second binary's runs are recorded under its name plus a hash prefix.
+    let arm64_name = format!("libGame.so@{}", &sha256_bytes(b"arm64 payload")[..12]);
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let read_json = |binary: &str, ritual: &str, file: &str| -> serde_json::Value {
+        let path = layout.binary_output_root(binary).join(ritual).join(file);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    };
+    let arch_report = read_json(&arm64_name, "ByArch", "report.json");
+    assert_eq!(arch_report["binary_id"], 2);
+    assert_eq!(arch_report["binary_hash"], sha256_bytes(b"arm64 payload"));
+    let hash_meta = read_json("libGame.so", "ByHash", "run_metadata.json");
+    assert_eq!(hash_meta["binary_id"], 1);
+    assert_eq!(hash_meta["binary_hash"], armv7_hash);
+
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout).unwrap();
+    let runs = db.list_ritual_runs(None).unwrap();
+    let ids: Vec<_> =
+        runs.iter().map(|r| (r.binary.as_str(), r.ritual.as_str(), r.binary_id)).collect();
+    assert_eq!(
+        ids,
+        vec![(arm64_name.as_str(), "ByArch", Some(2)), ("libGame.so", "ByHash", Some(1))]
+    );
+
+    // Rerun accepts the same selector syntax on the command line.
+    rerun_ritual_command(
+        &root,
+        &format!("sha256:{}", armv7_hash),
+        "ByHash",
+        "ByHashAgain",
+        None,
+        RunFlags::default(),
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+    assert_eq!(read_json("libGame.so", "ByHashAgain", "report.json")["binary_id"], 1);
+}
+
+#[test]
+fn same_named_binaries_keep_separate_run_outputs() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("SameNameProj".into())).unwrap();
+    for arch in ["armv7", "arm64"] {
+        let dir = temp.path().join(arch);
+        std::fs::create_dir_all(&dir).unwrap();
+        let bin_path = dir.join("libgame.so");
+        std::fs::write(&bin_path, format!("{arch} payload")).unwrap();
+        add_binary_command(
+            &root,
+            bin_path.to_str().unwrap(),
+            Some("libgame.so".into()),
+            Some(arch.into()),
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+
+    // The same ritual on both builds, the second-registered one first.
+    for arch in ["arm64", "armv7"] {
+        let spec = temp.path().join(format!("{arch}.yaml"));
+        std::fs::write(
+            &spec,
+            format!("name: Net\nbinary: {{name: libgame.so, arch: {arch}}}\nroots: [entry]\n"),
+        )
+        .unwrap();
+        run_ritual_command(&root, spec.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+            .unwrap();
+    }
+
+    let arm64_name = format!("libgame.so@{}", &sha256_bytes(b"arm64 payload")[..12]);
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    for (binary, id, arch) in [("libgame.so", 1, "armv7"), (arm64_name.as_str(), 2, "arm64")] {
+        let report_path = layout.binary_output_root(binary).join("Net").join("report.json");
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(report_path).unwrap()).unwrap();
+        assert_eq!(report["binary_id"], id);
+        assert_eq!(report["binary_hash"], sha256_bytes(format!("{arch} payload").as_bytes()));
+    }
+
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout).unwrap();
+    let ids = |binary: &str| -> Vec<Option<i64>> {
+        db.list_ritual_runs(Some(binary)).unwrap().iter().map(|r| r.binary_id).collect()
+    };
+    assert_eq!(ids("libgame.so"), [Some(1)]);
+    assert_eq!(ids(&arm64_name), [Some(2)]);
+
+    // The run name also works as a selector, and reruns stay with their binary.
+    rerun_ritual_command(
+        &root,
+        &arm64_name,
+        "Net",
+        "NetAgain",
+        None,
+        RunFlags::default(),
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+    assert!(layout.binary_output_root(&arm64_name).join("NetAgain").join("report.json").is_file());
+    assert_eq!(ids(&arm64_name), [Some(2), Some(2)]);
+    assert_eq!(ids("libgame.so"), [Some(1)]);
+}
+
 #[test]
 fn sha256_bytes_matches_known_hash() {
     let hash = sha256_bytes(b"abc");
@@ -60,6 +262,12 @@ fn run_metadata_round_trips_json() {
         started_at: "now".into(),
         finished_at: "later".into(),
         status: RitualRunStatus::Succeeded,
+        binary_id: None,
+        deterministic: false,
+        error: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        registered_binary_hash: None,
     };
     std::fs::write(&path, serde_json::to_string(&metadata).unwrap()).unwrap();
     let parsed: RitualRunMetadata =
@@ -115,6 +323,12 @@ fn collect_ritual_runs_on_disk_reads_metadata() {
         started_at: "s".into(),
         finished_at: "f".into(),
         status: ritual_core::db::RitualRunStatus::Stubbed,
+        binary_id: None,
+        deterministic: false,
+        error: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        registered_binary_hash: None,
     };
     std::fs::write(
         run_dir.join("run_metadata.json"),
@@ -150,6 +364,12 @@ fn project_info_includes_disk_only_runs() {
         started_at: "s".into(),
         finished_at: "f".into(),
         status: ritual_core::db::RitualRunStatus::Stubbed,
+        binary_id: None,
+        deterministic: false,
+        error: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        registered_binary_hash: None,
     };
     std::fs::write(
         run_dir.join("run_metadata.json"),
@@ -179,15 +399,17 @@ fn direct_project_and_slice_commands_execute() {
         Some("armv7".into()),
         None,
         false,
+        None,
     )
     .unwrap();
-    list_binaries_command(&root, false).unwrap();
+    list_binaries_command(&root, false, None).unwrap();
 
     // slice commands
     init_slice_command(&root, "SliceA", Some("desc".into()), Some("BinA".into())).unwrap();
-    list_slices_command(&root, false).unwrap();
-    emit_slice_docs_command(&root).unwrap();
-    emit_slice_reports_command(&root, None).unwrap();
+    list_slices_command(&root, false, false, None).unwrap();
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
 
     // project-info human and json
     project_info_command(&root, false).unwrap();
@@ -215,23 +437,43 @@ fn direct_ritual_commands_execute_and_update_status() {
     init_project_command(&root, Some("RitualProj".into())).unwrap();
     let bin_path = temp.path().join("binR.so");
     std::fs::write(&bin_path, b"payload").unwrap();
-    add_binary_command(&root, bin_path.to_str().unwrap(), Some("BinR".into()), None, None, false)
-        .unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinR".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
 
     // write spec yaml and run
     let spec_path = temp.path().join("rit.yaml");
     std::fs::write(&spec_path, "name: RunOne\nbinary: BinR\nroots: [entry_point]\nmax_depth: 1\n")
         .unwrap();
-    run_ritual_command(&root, spec_path.to_str().unwrap(), None, false).unwrap();
+    run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
 
     // list & show runs/specs
-    list_ritual_runs_command(&root, Some("BinR"), true).unwrap();
+    list_ritual_runs_command(&root, Some("BinR"), true, None).unwrap();
     list_ritual_specs_command(&root, false).unwrap();
     show_ritual_run_command(&root, "BinR", "RunOne", true).unwrap();
 
     // rerun and update status
-    rerun_ritual_command(&root, "BinR", "RunOne", "RunTwo", None, true).unwrap();
-    update_ritual_run_status_command(&root, "BinR", "RunTwo", "succeeded", None).unwrap();
+    rerun_ritual_command(
+        &root,
+        "BinR",
+        "RunOne",
+        "RunTwo",
+        None,
+        RunFlags { force: true, ..RunFlags::default() },
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+    let target =
+        RunTarget::Named { binary: "BinR".into(), ritual: "RunTwo".into(), all_runs: false };
+    update_ritual_run_status_command(&root, &target, "succeeded", None).unwrap();
 
     // clean outputs
     clean_outputs_command(&root, Some("BinR"), None, false, true).unwrap();
@@ -243,8 +485,9 @@ fn emit_slice_commands_handle_empty_db() {
     let root = temp.path().to_string_lossy().to_string();
     init_project_command(&root, Some("EmptyProj".into())).unwrap();
     // No slices registered -> should short-circuit gracefully.
-    emit_slice_docs_command(&root).unwrap();
-    emit_slice_reports_command(&root, None).unwrap();
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
 }
 
 #[test]
@@ -252,13 +495,13 @@ fn list_commands_handle_empty_sets_and_json() {
     let temp = tempdir().unwrap();
     let root = temp.path().to_string_lossy().to_string();
     init_project_command(&root, Some("ListProj".into())).unwrap();
-    list_slices_command(&root, true).unwrap();
-    list_binaries_command(&root, true).unwrap();
+    list_slices_command(&root, true, false, None).unwrap();
+    list_binaries_command(&root, true, None).unwrap();
     // backends list should always succeed (json and human)
     list_backends_command(true).unwrap();
     list_backends_command(false).unwrap();
     // ritual runs (human/json) empty path
-    list_ritual_runs_command(&root, None, false).unwrap();
+    list_ritual_runs_command(&root, None, false, None).unwrap();
 }
 
 #[test]
@@ -268,10 +511,18 @@ fn add_binary_honors_skip_hash_flag() {
     init_project_command(&root, Some("HashProj".into())).unwrap();
     let bin_path = temp.path().join("nohash.bin");
     std::fs::write(&bin_path, b"bytes").unwrap();
-    add_binary_command(&root, bin_path.to_str().unwrap(), Some("NoHash".into()), None, None, true)
-        .unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("NoHash".into()),
+        None,
+        None,
+        true,
+        None,
+    )
+    .unwrap();
     // JSON list should still succeed even without hash present.
-    list_binaries_command(&root, true).unwrap();
+    list_binaries_command(&root, true, None).unwrap();
 }
 
 #[test]
@@ -294,16 +545,33 @@ fn run_ritual_force_overwrites_existing_output() {
     init_project_command(&root, Some("ForceProj".into())).unwrap();
     let bin_path = temp.path().join("binF.so");
     std::fs::write(&bin_path, b"payload").unwrap();
-    add_binary_command(&root, bin_path.to_str().unwrap(), Some("BinF".into()), None, None, false)
-        .unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinF".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
 
     let spec_path = temp.path().join("force.yaml");
     std::fs::write(&spec_path, "name: ForceRun\nbinary: BinF\nroots: [entry]\nmax_depth: 1\n")
         .unwrap();
 
-    run_ritual_command(&root, spec_path.to_str().unwrap(), None, false).unwrap();
+    run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
     // Re-run with force to hit overwrite branch.
-    run_ritual_command(&root, spec_path.to_str().unwrap(), None, true).unwrap();
+    run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        None,
+        RunFlags { force: true, ..RunFlags::default() },
+        &[],
+        &[],
+    )
+    .unwrap();
 }
 
 #[test]
@@ -313,13 +581,24 @@ fn run_ritual_errors_when_output_exists_without_force() {
     init_project_command(&root, Some("NoForceProj".into())).unwrap();
     let bin_path = temp.path().join("binNF.so");
     std::fs::write(&bin_path, b"payload").unwrap();
-    add_binary_command(&root, bin_path.to_str().unwrap(), Some("BinNF".into()), None, None, false)
-        .unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinNF".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
     let spec_path = temp.path().join("noforce.yaml");
     std::fs::write(&spec_path, "name: RunNF\nbinary: BinNF\nroots: [entry_point]\nmax_depth: 1\n")
         .unwrap();
-    run_ritual_command(&root, spec_path.to_str().unwrap(), None, false).unwrap();
-    let err = run_ritual_command(&root, spec_path.to_str().unwrap(), None, false).unwrap_err();
+    run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
+    let err =
+        run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+            .unwrap_err();
     assert!(err.to_string().contains("already exists"));
 }
 
@@ -347,8 +626,16 @@ fn run_ritual_errors_on_unknown_backend() {
     init_project_command(&root, Some("BackendProj".into())).unwrap();
     let bin_path = temp.path().join("binBK.so");
     std::fs::write(&bin_path, b"payload").unwrap();
-    add_binary_command(&root, bin_path.to_str().unwrap(), Some("BinBK".into()), None, None, false)
-        .unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinBK".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
 
     let spec_path = temp.path().join("backend.yaml");
     std::fs::write(
@@ -364,10 +651,86 @@ fn run_ritual_errors_on_unknown_backend() {
     config.default_backend = Some("validate-only".into());
     std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
 
+    let err = run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        Some("missing-backend"),
+        RunFlags::default(),
+        &[],
+        &[],
+    )
+    .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(message.contains("Ritual run BinBK/BackendRun failed"));
+    assert!(message.contains("Backend 'missing-backend' not found"));
+}
+
+#[test]
+fn run_ritual_skips_unavailable_backends_in_a_fallback_chain() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("ChainProj".into())).unwrap();
+    let bin_path = temp.path().join("binCH.so");
+    std::fs::write(&bin_path, b"payload").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinCH".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let read_json = |ritual: &str, file: &str| -> serde_json::Value {
+        let path = layout.binary_output_root("BinCH").join(ritual).join(file);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    };
+
+    // The spec's list wins over the config; names not compiled in are skipped.
+    let mut config: ritual_core::db::ProjectConfig =
+        serde_json::from_str(&std::fs::read_to_string(&layout.project_config_path).unwrap())
+            .unwrap();
+    config.backend_priority = vec!["validate-only".into()];
+    std::fs::write(&layout.project_config_path, serde_json::to_string_pretty(&config).unwrap())
+        .unwrap();
+    let spec_path = temp.path().join("chain.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: ChainRun\nbinary: BinCH\nroots: [entry_point]\nbackend: [no-such-tool, validate-only]\n",
+    )
+    .unwrap();
+    let spec = RitualSpec::from_path(&spec_path).unwrap();
+    assert_eq!(spec.backend.unwrap().names(), ["no-such-tool", "validate-only"]);
+    run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
+    assert_eq!(read_json("ChainRun", "report.json")["backend"], "validate-only");
+    assert_eq!(read_json("ChainRun", "run_metadata.json")["backend"], "validate-only");
+    let normalized: RitualSpec = serde_yaml::from_str(
+        &std::fs::read_to_string(layout.binary_output_root("BinCH").join("ChainRun/spec.yaml"))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(normalized.backend.unwrap().names(), ["validate-only"]);
+
+    // Without a spec backend the config's priority list is used.
+    std::fs::write(&spec_path, "name: PriorityRun\nbinary: BinCH\nroots: [entry_point]\n").unwrap();
+    run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+        .unwrap();
+    assert_eq!(read_json("PriorityRun", "report.json")["backend"], "validate-only");
+    assert!(read_json("PriorityRun", "report.json").get("failed_backends").is_none());
+
+    // An empty list is rejected up front.
+    std::fs::write(
+        &spec_path,
+        "name: EmptyRun\nbinary: BinCH\nroots: [entry_point]\nbackend: []\n",
+    )
+    .unwrap();
     let err =
-        run_ritual_command(&root, spec_path.to_str().unwrap(), Some("missing-backend"), false)
+        run_ritual_command(&root, spec_path.to_str().unwrap(), None, RunFlags::default(), &[], &[])
             .unwrap_err();
-    assert!(err.to_string().contains("Backend 'missing-backend' not found"));
+    assert!(err.to_string().contains("'backend' list must name at least one backend"), "{err:#}");
 }
 
 #[test]
@@ -375,8 +738,8 @@ fn list_ritual_runs_handles_empty_state() {
     let temp = tempdir().unwrap();
     let root = temp.path().to_string_lossy().to_string();
     init_project_command(&root, Some("EmptyRuns".into())).unwrap();
-    list_ritual_runs_command(&root, None, false).unwrap();
-    list_ritual_runs_command(&root, None, true).unwrap();
+    list_ritual_runs_command(&root, None, false, None).unwrap();
+    list_ritual_runs_command(&root, None, true, None).unwrap();
 }
 
 #[test]
@@ -394,7 +757,7 @@ fn list_binaries_human_when_empty() {
     let temp = tempdir().unwrap();
     let root = temp.path().to_string_lossy().to_string();
     init_project_command(&root, Some("EmptyBins".into())).unwrap();
-    list_binaries_command(&root, false).unwrap();
+    list_binaries_command(&root, false, None).unwrap();
 }
 
 #[test]
@@ -427,6 +790,12 @@ fn show_ritual_run_prefers_disk_metadata_when_db_missing() {
         started_at: "s".into(),
         finished_at: "f".into(),
         status: ritual_core::db::RitualRunStatus::Stubbed,
+        binary_id: None,
+        deterministic: false,
+        error: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        registered_binary_hash: None,
     };
     std::fs::write(
         run_dir.join("run_metadata.json"),
@@ -459,14 +828,20 @@ fn list_ritual_runs_filters_disk_runs_only() {
         started_at: "s".into(),
         finished_at: "f".into(),
         status: ritual_core::db::RitualRunStatus::Stubbed,
+        binary_id: None,
+        deterministic: false,
+        error: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        registered_binary_hash: None,
     };
     std::fs::write(
         run_root.join("run_metadata.json"),
         serde_json::to_string_pretty(&metadata).unwrap(),
     )
     .unwrap();
-    list_ritual_runs_command(&root, Some("FilterBin"), false).unwrap();
-    list_ritual_runs_command(&root, Some("FilterBin"), true).unwrap();
+    list_ritual_runs_command(&root, Some("FilterBin"), false, None).unwrap();
+    list_ritual_runs_command(&root, Some("FilterBin"), true, None).unwrap();
 }
 
 #[test]
@@ -477,11 +852,225 @@ fn list_and_emit_slices_with_descriptions() {
     init_slice_command(&root, "Gameplay", Some("Core gameplay loop".into()), None).unwrap();
 
     // Human listing should include description and status.
-    list_slices_command(&root, false).unwrap();
+    list_slices_command(&root, false, false, None).unwrap();
     // JSON listing should also succeed.
-    list_slices_command(&root, true).unwrap();
+    list_slices_command(&root, true, false, None).unwrap();
 
     // Emit docs and reports when slices are present (non-empty branches).
-    emit_slice_docs_command(&root).unwrap();
-    emit_slice_reports_command(&root, None).unwrap();
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
+}
+
+#[test]
+fn run_ritual_with_labeled_roots_groups_report_by_sub_slice() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("SubSliceProj".into())).unwrap();
+    let bin_path = temp.path().join("binSS.so");
+    std::fs::write(&bin_path, b"payload").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinSS".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let spec_path = temp.path().join("grouped.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Grouped\nbinary: BinSS\nroots:\n  ui: [CUIManager::init]\n  net: [send_packet, recv_packet]\n",
+    )
+    .unwrap();
+    run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        Some("validate-only"),
+        RunFlags::default(),
+        &[],
+        &[],
+    )
+    .unwrap();
+
+    let run_root =
+        ritual_core::db::ProjectLayout::new(&root).binary_output_root("BinSS").join("Grouped");
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(run_root.join("report.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        report["roots"],
+        serde_json::json!(["send_packet", "recv_packet", "CUIManager::init"])
+    );
+    let labels: Vec<&str> = report["sub_slices"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["label"].as_str().unwrap())
+        .collect();
+    assert_eq!(labels, vec!["net", "ui"]);
+    assert_eq!(report["sub_slices"][0]["roots"], serde_json::json!(["send_packet", "recv_packet"]));
+
+    // Normalized spec keeps the labeled form so reruns carve the same sub-slices.
+    let normalized: RitualSpec =
+        serde_yaml::from_str(&std::fs::read_to_string(run_root.join("spec.yaml")).unwrap())
+            .unwrap();
+    assert_eq!(normalized.roots.groups().len(), 2);
+    rerun_ritual_command(
+        &root,
+        "BinSS",
+        "Grouped",
+        "GroupedAgain",
+        None,
+        RunFlags::default(),
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+    show_ritual_run_command(&root, "BinSS", "GroupedAgain", false).unwrap();
+
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let (_cfg, _db_path, db) = ritual_core::db::open_project_db(&layout).unwrap();
+    let analysis = db.load_analysis_result("BinSS", "GroupedAgain").unwrap().unwrap();
+    assert_eq!(analysis.sub_slices.len(), 2);
+
+    let bad_spec = temp.path().join("empty_group.yaml");
+    std::fs::write(&bad_spec, "name: Bad\nbinary: BinSS\nroots:\n  ui: []\n").unwrap();
+    let err =
+        run_ritual_command(&root, bad_spec.to_str().unwrap(), None, RunFlags::default(), &[], &[])
+            .unwrap_err();
+    assert!(err.to_string().contains("sub-slice 'ui'"));
+}
+
+#[test]
+fn run_ritual_with_auto_roots_asks_for_entry_points_and_exports() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("AutoProj".into())).unwrap();
+    let bin_path = temp.path().join("auto.bin");
+    std::fs::write(&bin_path, b"not an executable").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinA".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let spec_path = temp.path().join("auto.yaml");
+    std::fs::write(&spec_path, "name: Explore\nbinary: BinA\nroots: auto\n").unwrap();
+    let spec = RitualSpec::from_path(&spec_path).unwrap();
+    assert_eq!(spec.roots.all(), vec!["auto:entrypoint", "auto:exports"]);
+    spec.validate().unwrap();
+    run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        Some("validate-only"),
+        RunFlags::default(),
+        &[],
+        &[],
+    )
+    .unwrap();
+
+    let run_root =
+        ritual_core::db::ProjectLayout::new(&root).binary_output_root("BinA").join("Explore");
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(run_root.join("report.json")).unwrap())
+            .unwrap();
+    assert_eq!(report["roots"], serde_json::json!(["auto:entrypoint", "auto:exports"]));
+    // The file declares nothing, so each root records why it is empty.
+    let errors: Vec<&str> = report["root_hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["error"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        errors,
+        ["binary declares no entry point at a known function", "binary exports no known function"]
+    );
+    let normalized = std::fs::read_to_string(run_root.join("spec.yaml")).unwrap();
+    assert!(normalized.contains("roots: auto"), "{normalized}");
+
+    // Extra roots turn `auto` into the list it stands for.
+    let mut roots = spec.roots.clone();
+    roots.add("strings", "main".into());
+    assert_eq!(roots.all(), vec!["auto:entrypoint", "auto:exports", "main"]);
+}
+
+#[test]
+fn run_ritual_reuses_cached_analysis_unless_no_cache() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("CacheProj".into())).unwrap();
+    let bin_path = temp.path().join("cache.bin");
+    std::fs::write(&bin_path, b"cache payload").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinC".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let spec_path = temp.path().join("cached.yaml");
+    std::fs::write(
+        &spec_path,
+        "name: Cached\nbinary: BinC\nroots: [entry]\nbackend: validate-only\n",
+    )
+    .unwrap();
+
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let cache = ritual_core::services::cache::AnalysisCache::new(layout.cache_dir());
+    run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        None,
+        RunFlags { no_cache: true, ..RunFlags::default() },
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(cache.stats().unwrap().entries, 0);
+    run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        None,
+        RunFlags { force: true, ..RunFlags::default() },
+        &[],
+        &[],
+    )
+    .unwrap();
+    run_ritual_command(
+        &root,
+        spec_path.to_str().unwrap(),
+        None,
+        RunFlags { force: true, ..RunFlags::default() },
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(cache.stats().unwrap().entries, 1);
+    rerun_ritual_command(
+        &root,
+        "BinC",
+        "Cached",
+        "CachedAgain",
+        None,
+        RunFlags::default(),
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+    assert_eq!(cache.stats().unwrap().entries, 1);
+
+    cache_stats_command(&root, true).unwrap();
+    cache_clear_command(&root).unwrap();
+    assert_eq!(cache.stats().unwrap().entries, 0);
 }
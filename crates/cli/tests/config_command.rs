@@ -0,0 +1,200 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::init_project_command;
+use predicates::prelude::*;
+use serde_json::Value;
+use tempfile::tempdir;
+
+#[test]
+fn config_set_get_list_and_unset_round_trip() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("ConfigProj".into())).unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "default_backend", "capstone"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Set default_backend = capstone"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "backends.rizin", "/opt/rizin/bin/rizin"])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "get", "--root", &root, "default_backend"])
+        .assert()
+        .success()
+        .stdout("capstone\n");
+
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let config = ritual_core::db::load_project_config(&layout).unwrap();
+    assert_eq!(config.default_backend.as_deref(), Some("capstone"));
+    assert_eq!(config.backends.rizin.as_deref(), Some("/opt/rizin/bin/rizin"));
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["config", "list", "--root", &root, "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let listed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listed["name"], "ConfigProj");
+    assert_eq!(listed["backends.rizin"], "/opt/rizin/bin/rizin");
+    assert!(listed["description"].is_null());
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "list", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("default_backend = capstone"))
+        .stdout(predicate::str::contains("backends.ghidra_headless = (unset)"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "unset", "--root", &root, "default_backend"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "get", "--root", &root, "default_backend"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn config_rejects_unknown_keys_and_invalid_values() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("ConfigErrors".into())).unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "default-backend", "capstone"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown config key 'default-backend'"))
+        .stderr(predicate::str::contains("did you mean 'default_backend'?"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "default_backend", "ida"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown backend 'ida'"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "config_version", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("read-only"));
+
+    let layout = ritual_core::db::ProjectLayout::new(&root);
+    let config = ritual_core::db::load_project_config(&layout).unwrap();
+    assert!(config.default_backend.is_none());
+}
+
+#[test]
+fn layout_config_moves_run_outputs_out_of_the_project() {
+    let temp = tempdir().unwrap();
+    let scratch = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("ScratchProj".into())).unwrap();
+    let outputs = format!("{}/{{project}}/outputs", scratch.path().display());
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "layout.outputs_dir", &outputs])
+        .assert()
+        .success();
+
+    std::fs::write(temp.path().join("libScratch.so"), b"dummy").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--path", "libScratch.so"])
+        .assert()
+        .success();
+    let spec = temp.path().join("scratch.yaml");
+    std::fs::write(&spec, "name: ScratchRun\nbinary: libScratch.so\nroots: [start]\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "validate-only", "--file"])
+        .arg(&spec)
+        .assert()
+        .success();
+
+    let run_dir = scratch.path().join("ScratchProj/outputs/binaries/libScratch.so/ScratchRun");
+    assert!(run_dir.join("run_metadata.json").is_file());
+    assert!(!temp.path().join("outputs/binaries/libScratch.so").exists());
+
+    // Commands that read outputs find them in the configured place.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-runs", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ScratchRun"));
+    cargo_bin_cmd!("binary-slicer")
+        .args([
+            "verify-run",
+            "--root",
+            &root,
+            "--binary",
+            "libScratch.so",
+            "--ritual",
+            "ScratchRun",
+        ])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["project-info", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Outputs dir: OK ({})",
+            scratch.path().join("ScratchProj/outputs").display()
+        )));
+}
+
+#[test]
+fn user_config_fills_in_what_the_project_leaves_unset() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("proj").to_string_lossy().to_string();
+    std::fs::create_dir_all(&root).unwrap();
+    init_project_command(&root, Some("UserProj".into())).unwrap();
+    let home = temp.path().join("home");
+    let xdg = temp.path().join("xdg");
+    std::fs::create_dir_all(xdg.join("binary-slicer")).unwrap();
+    let user_config = xdg.join("binary-slicer").join("config.toml");
+    std::fs::write(
+        &user_config,
+        "default_backend = \"validate-only\"\njobs = 3\ncolor = \"never\"\n\n\
+         [backends]\nrizin = \"~/tools/rizin\"\n",
+    )
+    .unwrap();
+    let bin = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.env("HOME", &home).env("XDG_CONFIG_HOME", &xdg);
+        cmd
+    };
+    let project_info = || -> Value {
+        let output = bin().args(["project-info", "--root", &root, "--json"]).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let info = project_info();
+    assert_eq!(info["default_backend"], "validate-only");
+    let rizin = home.join("tools").join("rizin");
+    assert_eq!(info["backends"]["rizin"], rizin.to_string_lossy().as_ref());
+    // `config get` reads the project file, which stays untouched.
+    bin().args(["config", "get", "--root", &root, "default_backend"]).assert().success().stdout("");
+    let output = bin().args(["batch-run", "--root", &root, "--json"]).output().unwrap();
+    let batch: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(batch["jobs"], 3);
+
+    // Project settings win.
+    bin()
+        .args(["config", "set", "--root", &root, "default_backend", "capstone"])
+        .assert()
+        .success();
+    assert_eq!(project_info()["default_backend"], "capstone");
+    let output =
+        bin().args(["batch-run", "--root", &root, "--json", "--jobs", "2"]).output().unwrap();
+    assert_eq!(serde_json::from_slice::<Value>(&output.stdout).unwrap()["jobs"], 2);
+
+    std::fs::write(&user_config, "jobs = \"many\"\n").unwrap();
+    bin()
+        .args(["project-info", "--root", &root])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse user config at"));
+}
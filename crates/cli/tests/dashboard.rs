@@ -0,0 +1,102 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{format_duration, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectLayout, ProjectLock};
+use serde_json::Value;
+use tempfile::tempdir;
+
+#[test]
+fn dashboard_snapshots_show_binaries_slices_runs_and_the_run_log() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Board".into())).unwrap();
+    std::fs::write(temp.path().join("libBoard.so"), b"dummy").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--path", "libBoard.so", "--arch", "arm64"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Net"])
+        .assert()
+        .success();
+    let spec = temp.path().join("net.yaml");
+    std::fs::write(&spec, "name: Net\nbinary: libBoard.so\nroots: [start]\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--backend", "validate-only", "--log-json", "--file"])
+        .arg(&spec)
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["dashboard", "--root", &root, "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let snapshot: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(snapshot["project"], "Board");
+    assert_eq!(snapshot["binaries"][0]["name"], "libBoard.so");
+    assert_eq!(snapshot["binaries"][0]["runs"], 1);
+    assert_eq!(snapshot["slices"][0]["name"], "Net");
+    assert_eq!(snapshot["slices"][0]["status"], "Planned");
+    let run = &snapshot["recent_runs"][0];
+    assert_eq!(run["ritual"], "Net");
+    assert_eq!(run["backend"], "validate-only");
+    assert!(run["duration_secs"].as_f64().unwrap() >= 0.0);
+    assert_eq!(snapshot["active"], Value::Null);
+    let log = &snapshot["log"];
+    assert!(log["path"].as_str().unwrap().ends_with("run.log.jsonl"), "{log}");
+    let lines = log["lines"].as_array().unwrap();
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|l| !l.as_str().unwrap().starts_with('{')), "{log}");
+
+    // A command holding the project lock shows as the current activity.
+    let lock = ProjectLock::try_acquire(
+        &ProjectLayout::new(temp.path()),
+        "binary-slicer run-ritual --file net.yaml",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["dashboard", "--root", &root, "--once"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Project: Board"))
+        .stdout(predicate::str::contains("Active: pid"))
+        .stdout(predicate::str::contains("`binary-slicer run-ritual --file net.yaml`"))
+        .stdout(predicate::str::contains("Binaries (1):"))
+        .stdout(predicate::str::contains("Slices (1):"))
+        .stdout(predicate::str::contains("validate-only"))
+        .stdout(predicate::str::contains("Log ("));
+    drop(lock);
+}
+
+#[test]
+fn dashboard_rejects_a_zero_interval() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, None).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["dashboard", "--root", &root, "--interval", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--interval must be at least 1 second"));
+}
+
+#[cfg(not(feature = "dashboard"))]
+#[test]
+fn dashboard_without_the_feature_points_at_snapshots() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, None).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["dashboard", "--root", &root])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("built without the `dashboard` feature"));
+}
+
+#[test]
+fn durations_read_at_a_glance() {
+    assert_eq!(format_duration(0.25), "250ms");
+    assert_eq!(format_duration(12.44), "12.4s");
+    assert_eq!(format_duration(185.0), "3m05s");
+}
@@ -0,0 +1,135 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{
+    add_binary_command, init_project_command, rerun_ritual_command, AnalysisOverrides,
+    RitualRunMetadata, RunFlags,
+};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use std::fs;
+use tempfile::tempdir;
+
+fn project_with_spec(spec: &str) -> (tempfile::TempDir, String, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("DetProj".into())).unwrap();
+    let bin_path = temp.path().join("det.bin");
+    fs::write(&bin_path, b"\x7fELF-ish").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("BinD".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let spec_path = temp.path().join("det.yaml");
+    fs::write(&spec_path, spec).unwrap();
+    let spec_path = spec_path.to_string_lossy().to_string();
+    (temp, root, spec_path)
+}
+
+#[test]
+fn deterministic_runs_write_identical_outputs_with_fixed_timestamps() {
+    let (_temp, root, spec) = project_with_spec(
+        "name: Det\nbinary: BinD\nroots: [entry]\nbackend: validate-only\nepoch: 1700000000\n",
+    );
+    let run = |force: bool| {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["run-ritual", "--root", &root, "--file", &spec, "--deterministic"]);
+        if force {
+            cmd.arg("--force");
+        }
+        cmd.assert().success();
+    };
+    let layout = ProjectLayout::new(&root);
+    let run_dir = layout.binary_output_root("BinD").join("Det");
+    let read = |name: &str| fs::read_to_string(run_dir.join(name)).unwrap();
+
+    run(false);
+    let (report, metadata) = (read("report.json"), read("run_metadata.json"));
+    run(true);
+    assert_eq!(read("report.json"), report);
+    assert_eq!(read("run_metadata.json"), metadata);
+
+    let meta: RitualRunMetadata = serde_json::from_str(&metadata).unwrap();
+    assert!(meta.deterministic);
+    assert_eq!(meta.started_at, "2023-11-14T22:13:20+00:00");
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    assert_eq!(report["deterministic"], true);
+    assert_eq!(report["epoch"], "2023-11-14T22:13:20+00:00");
+
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let runs = db.list_ritual_runs(Some("BinD")).unwrap();
+    assert!(runs.iter().all(|r| r.deterministic && r.finished_at == meta.started_at));
+}
+
+#[test]
+fn diff_runs_asserts_reproducibility_of_deterministic_reruns() {
+    let (_temp, root, spec) =
+        project_with_spec("name: Base\nbinary: BinD\nroots: [entry]\nbackend: validate-only\n");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", &spec])
+        .assert()
+        .success();
+    let deterministic = RunFlags { deterministic: true, ..RunFlags::default() };
+    rerun_ritual_command(
+        &root,
+        "BinD",
+        "Base",
+        "First",
+        None,
+        deterministic,
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+    rerun_ritual_command(
+        &root,
+        "BinD",
+        "Base",
+        "Second",
+        None,
+        deterministic,
+        AnalysisOverrides::default(),
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["diff-runs", "--root", &root, "--binary", "BinD", "--ritual", "First"])
+        .args(["--against", "Second", "--assert-reproducible", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(body["reproducible"], true);
+    assert_eq!(body["base"]["deterministic"], true);
+    let epoch = ritual_core::services::deterministic::DETERMINISTIC_EPOCH;
+    let layout = ProjectLayout::new(&root);
+    let metadata = fs::read_to_string(
+        layout.binary_output_root("BinD").join("Second").join("run_metadata.json"),
+    )
+    .unwrap();
+    assert!(metadata.contains(epoch));
+
+    // The original run was not deterministic, so it cannot vouch for reproducibility.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["diff-runs", "--root", &root, "--binary", "BinD", "--ritual", "Base"])
+        .args(["--against", "First", "--assert-reproducible"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("BinD/Base was not run with --deterministic"));
+}
+
+#[test]
+fn invalid_spec_epoch_is_rejected() {
+    let (_temp, root, spec) =
+        project_with_spec("name: Bad\nbinary: BinD\nroots: [entry]\nepoch: last tuesday\n");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", &spec, "--deterministic"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'epoch' must be an RFC 3339 timestamp"));
+}
@@ -0,0 +1,109 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::init_project_command;
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+fn run(binary: &str, ritual: &str) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        ritual: ritual.into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "now".into(),
+        finished_at: "now".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+fn doctor_json(root: &str, fix: bool) -> Vec<Value> {
+    let mut cmd = cargo_bin_cmd!("binary-slicer");
+    cmd.args(["doctor", "--root", root, "--json"]);
+    if fix {
+        cmd.arg("--fix");
+    }
+    let output = cmd.assert().success().get_output().stdout.clone();
+    serde_json::from_slice::<Value>(&output).unwrap().as_array().unwrap().clone()
+}
+
+fn statuses(checks: &[Value], check: &str) -> Vec<String> {
+    checks
+        .iter()
+        .filter(|c| c["check"] == check)
+        .map(|c| c["status"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn doctor_reports_a_fresh_project_healthy() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Doc".into())).unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["doctor", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[ok] schema: v"))
+        .stdout(predicate::str::contains("0 warning(s), 0 error(s)"));
+}
+
+#[test]
+fn doctor_warns_about_drift_and_fixes_what_it_can() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Doc".into())).unwrap();
+    let layout = ProjectLayout::new(&root);
+    let config_path = layout.meta_dir.join("project.json");
+    let mut config: Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    config["defualt_backend"] = "capstone".into();
+    fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+    fs::remove_dir_all(&layout.graphs_dir).unwrap();
+    fs::create_dir_all(layout.binary_output_root("ghost").join("Old")).unwrap();
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let lost = db.insert_ritual_run(&run("game", "Net")).unwrap();
+    drop(db);
+
+    let checks = doctor_json(&root, false);
+    let config_check = checks.iter().find(|c| c["check"] == "config").unwrap();
+    assert_eq!(config_check["status"], "warning");
+    assert_eq!(config_check["message"], "unknown key 'defualt_backend'");
+    assert!(config_check["fix"].as_str().unwrap().contains("did you mean 'default_backend'"));
+    assert_eq!(statuses(&checks, "directories"), ["warning"]);
+    assert_eq!(statuses(&checks, "outputs"), ["warning", "warning"]);
+
+    let checks = doctor_json(&root, true);
+    assert_eq!(statuses(&checks, "directories"), ["fixed"]);
+    assert!(layout.graphs_dir.is_dir());
+    // Orphaned directories are left for clean-outputs; the run without outputs is purged.
+    assert_eq!(statuses(&checks, "outputs"), ["warning", "fixed"]);
+    assert!(layout.binary_output_root("ghost").join("Old").is_dir());
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let runs = db.list_ritual_runs_with_ids(None).unwrap();
+    assert_eq!(runs[0].0, lost);
+    assert_eq!(runs[0].1.status, RitualRunStatus::Purged);
+}
+
+#[test]
+fn doctor_fails_outside_a_project() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("missing").to_string_lossy().to_string();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["doctor", "--root", &root])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("[error] project: no project at"))
+        .stderr(predicate::str::contains("doctor found 1 error(s)"));
+}
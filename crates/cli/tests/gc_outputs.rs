@@ -0,0 +1,128 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::init_project_command;
+use predicates::prelude::*;
+use ritual_core::db::{
+    BinaryRecord, ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus, RunBaseline,
+};
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+fn run(binary: &str, ritual: &str, status: RitualRunStatus, finished_at: &str) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        ritual: ritual.into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status,
+        started_at: finished_at.into(),
+        finished_at: finished_at.into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+#[test]
+fn gc_outputs_purges_runs_past_the_retention_policy() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Gc".into())).unwrap();
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let old = db.insert_binary(&BinaryRecord::new("game-1.0", "bins/1.0/game")).unwrap();
+    db.insert_binary(&BinaryRecord::new("game-1.1", "bins/1.1/game")).unwrap();
+    db.insert_binary(&BinaryRecord::new("game-1.2", "bins/1.2/game")).unwrap();
+    let runs = [
+        ("game-1.0", RitualRunStatus::Succeeded, "2020-01-01T00:00:00+00:00"),
+        ("game-1.1", RitualRunStatus::Failed, "2020-01-02T00:00:00+00:00"),
+        ("game-1.2", RitualRunStatus::Stubbed, "2020-01-03T00:00:00+00:00"),
+    ];
+    for (binary, status, finished_at) in runs {
+        db.insert_ritual_run(&run(binary, "Net", status, finished_at)).unwrap();
+        let dir = layout.binary_output_root(binary).join("Net");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("report.json"), "{}").unwrap();
+    }
+    drop(db);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["gc-outputs", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to purge"));
+
+    // Keeping one run per ritual: the succeeded run stays, the failed one goes.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "retention.keep_last", "1"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["gc-outputs", "--root", &root, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would purge 1 run(s):"))
+        .stdout(predicate::str::contains(
+            "game-1.1 / Net (run 2, failed, finished 2020-01-02T00:00:00+00:00): older than the last 1 run(s) of its ritual",
+        ))
+        .stdout(predicate::str::contains("Would remove outputs:"));
+    assert!(layout.binary_output_root("game-1.1").join("Net").is_dir(), "dry run deletes nothing");
+
+    // Baselines are kept even when succeeded runs are not.
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    db.set_run_baseline(&RunBaseline {
+        binary_id: old,
+        binary: "game-1.0".into(),
+        ritual: "Net".into(),
+        run_id: 1,
+        set_at: "t0".into(),
+    })
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "retention.keep_succeeded", "false"])
+        .assert()
+        .success();
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["gc-outputs", "--root", &root, "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["dry_run"], false);
+    assert_eq!(report["runs"].as_array().unwrap().len(), 1);
+    assert_eq!(report["runs"][0]["run_id"], 2);
+    assert_eq!(report["runs"][0]["reason"]["kind"], "past_keep_last");
+    assert_eq!(report["removed"].as_array().unwrap().len(), 1);
+
+    assert!(!layout.binary_output_root("game-1.1").join("Net").exists());
+    assert!(layout.binary_output_root("game-1.0").join("Net").is_dir());
+    assert!(layout.binary_output_root("game-1.2").join("Net").is_dir());
+    let statuses: Vec<RitualRunStatus> =
+        db.list_ritual_runs(None).unwrap().into_iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        [RitualRunStatus::Succeeded, RitualRunStatus::Purged, RitualRunStatus::Stubbed]
+    );
+
+    // Old failures go even when they are the newest run of their ritual.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "--root", &root, "retention.failed_max_age_days", "30"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["gc-outputs", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Purged 1 run(s):"))
+        .stdout(predicate::str::contains("game-1.2 / Net (run 3, stubbed"))
+        .stdout(predicate::str::contains("(limit 30)"));
+    assert!(!layout.binary_output_root("game-1.2").join("Net").exists());
+}
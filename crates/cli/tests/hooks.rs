@@ -0,0 +1,130 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::ProjectLayout;
+use std::fs;
+use tempfile::tempdir;
+
+/// A project with binary `Game` and a validate-only spec `rituals/net.yaml`.
+fn project() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Hooks".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        Some("x86_64".into()),
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("rituals").join("net.yaml"),
+        "name: Net\nbinary: Game\nroots: [\"net_init\"]\nbackend: validate-only\n",
+    )
+    .unwrap();
+    (temp, root)
+}
+
+fn config_set(root: &str, key: &str, value: &str) {
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", key, value, "--root", root])
+        .assert()
+        .success();
+}
+
+#[test]
+fn post_run_hooks_run_after_each_run_and_never_fail_it() {
+    let (temp, root) = project();
+    config_set(
+        &root,
+        "hooks.post_run",
+        r#"["sh -c 'echo {status} {ritual} {binary} > hook.txt'", "false", "echo {nope}"]"#,
+    );
+    let spec = temp.path().join("rituals").join("net.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hook: sh -c 'echo stubbed Net Game > hook.txt' (exit 0)"))
+        .stdout(predicate::str::contains("Hook: false (exit 1)"))
+        .stdout(predicate::str::contains("unknown placeholder {nope}"));
+
+    // Hooks run from the project root; their output is collected in the run directory.
+    let hook_out = fs::read_to_string(temp.path().join("hook.txt")).unwrap();
+    assert_eq!(hook_out.trim(), "stubbed Net Game");
+    let run_dir = ProjectLayout::new(&root).binary_output_root("Game").join("Net");
+    let log = fs::read_to_string(run_dir.join("hooks.log")).unwrap();
+    assert!(log.contains("$ false\n[exit: exit status: 1]"), "{log}");
+
+    // batch-run fires the hooks as well.
+    fs::remove_file(temp.path().join("hook.txt")).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["batch-run", "--root", &root, "--force"])
+        .assert()
+        .success();
+    assert!(temp.path().join("hook.txt").exists());
+}
+
+#[test]
+fn hooks_post_run_accepts_a_single_command_and_rejects_bad_json() {
+    let (_temp, root) = project();
+    config_set(&root, "hooks.post_run", "./scripts/upload.sh {run_dir}");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "get", "hooks.post_run", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"["./scripts/upload.sh {run_dir}"]"#));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "hooks.post_run", "[\"unterminated", "--root", &root])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn open_run_writes_annotations_and_substitutes_the_tool_command() {
+    let (temp, root) = project();
+    let spec = temp.path().join("rituals").join("net.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["open-run", "--root", &root, "--binary", "Game", "--ritual", "Net"])
+        .args(["--with", "ghidra", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No tool 'ghidra' configured"))
+        .stderr(predicate::str::contains("config set tools.ghidra"));
+
+    config_set(&root, "tools.ghidra", "ghidraRun {binary_path} --script-args {annotations}");
+    let run_dir = ProjectLayout::new(&root).binary_output_root("Game").join("Net");
+    let annotations = run_dir.join("annotations.json");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["open-run", "--root", &root, "--binary", "Game", "--ritual", "Net"])
+        .args(["--with", "ghidra", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ghidraRun "))
+        .stdout(predicate::str::contains("game.bin --script-args "))
+        .stdout(predicate::str::contains(annotations.display().to_string()));
+
+    let payload: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&annotations).unwrap()).unwrap();
+    assert_eq!(payload["binary"], "Game");
+    assert_eq!(payload["ritual"], "Net");
+    assert!(payload["binary_path"].as_str().unwrap().ends_with("game.bin"), "{payload}");
+    assert!(payload["functions"].is_array(), "{payload}");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["open-run", "--root", &root, "--binary", "Game", "--ritual", "Other"])
+        .args(["--with", "ghidra", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ritual run found"));
+}
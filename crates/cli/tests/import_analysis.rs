@@ -0,0 +1,249 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use std::fs;
+use tempfile::tempdir;
+
+const EXPORT: &str = r#"{
+  "program": "game.bin",
+  "language": "x86:LE:64:default",
+  "text_bytes": 4096,
+  "functions": [
+    {"entry": 4096, "name": "net_init", "size": 32, "calls": [{"to": 8192, "name": "net_send"}]},
+    {"entry": 8192, "name": "net_send", "size": 16},
+    {"entry": 12288, "name": "render", "size": 64}
+  ],
+  "strings": [],
+  "imports": []
+}"#;
+
+#[test]
+fn import_analysis_records_a_ghidra_export_as_a_run() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Import".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let export = temp.path().join("game.json");
+    fs::write(&export, EXPORT).unwrap();
+
+    let import = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["import-analysis", "--root", &root, "--binary", "Game"])
+            .args(["--file", export.to_str().unwrap(), "--format", "ghidra-json"])
+            .args(["--as-ritual", "GhidraImport", "--slice-root", "regex:net_.*"]);
+        cmd
+    };
+    import()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported ghidra-json analysis as Game / GhidraImport"))
+        .stdout(predicate::str::contains("Exported from: game.bin (x86:LE:64:default)"))
+        .stdout(predicate::str::contains("Functions: 3"))
+        .stdout(predicate::str::contains("Root regex:net_.*: 2 function(s)"));
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let runs = db.list_ritual_runs(Some("Game")).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].ritual, "GhidraImport");
+    assert_eq!(runs[0].backend, "ghidra-json");
+    let analysis = db.load_analysis_result("Game", "GhidraImport").unwrap().unwrap();
+    assert_eq!(analysis.functions.len(), 3);
+    assert_eq!(analysis.call_edges.len(), 1);
+    assert_eq!(analysis.text_bytes, Some(4096));
+    let run_dir = layout.binary_output_root("Game").join("GhidraImport");
+    for file in ["spec.yaml", "report.json", "graph.dot", "run_metadata.json"] {
+        assert!(run_dir.join(file).is_file(), "{file}");
+    }
+    let spec = fs::read_to_string(run_dir.join("spec.yaml")).unwrap();
+    assert!(spec.contains("backend: ghidra-json"), "{spec}");
+
+    import().assert().failure().stderr(predicate::str::contains("--force"));
+    import().arg("--force").assert().success();
+}
+
+#[test]
+fn import_analysis_rejects_bad_input() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Import".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let export = temp.path().join("game.BinExport");
+    fs::write(&export, b"not protobuf").unwrap();
+
+    let base = |format: &str, root_arg: &str| {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["import-analysis", "--root", &root, "--binary", "Game"])
+            .args(["--file", export.to_str().unwrap(), "--format", format])
+            .args(["--as-ritual", "Imported", "--slice-root", root_arg]);
+        cmd
+    };
+    base("ida", "main")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown import format 'ida'"));
+    base("binexport", "regex:(")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid slice root 'regex:('"));
+    base("binexport", "main")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid BinExport file"));
+    assert!(!ProjectLayout::new(&root).binary_output_root("Game").join("Imported").exists());
+}
+
+#[test]
+fn import_symbols_names_functions_of_later_runs() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Import".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let map = temp.path().join("game.map");
+    fs::write(&map, "  Address         Publics by Value\n 0001:00003000       draw_frame\n")
+        .unwrap();
+    let exports = temp.path().join("exports.json");
+    fs::write(&exports, r#"[{"type": "function", "name": "net_boot", "address": "0x10001000"}]"#)
+        .unwrap();
+
+    let import_symbols = |file: &std::path::Path, format: &str| {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["import-symbols", "--root", &root, "--binary", "Game"]).args([
+            "--file",
+            file.to_str().unwrap(),
+            "--format",
+            format,
+        ]);
+        cmd
+    };
+    import_symbols(&map, "map")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 symbol(s) for Game"))
+        .stdout(predicate::str::contains("Skipped: 1 line(s) without a function symbol"));
+    import_symbols(&exports, "json")
+        .args(["--base", "0x10000000"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 symbol(s) for Game"));
+    import_symbols(&map, "ida")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown symbol map format 'ida'"));
+
+    // Imported names win over the names in the export.
+    let export = temp.path().join("game.json");
+    fs::write(&export, EXPORT).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["import-analysis", "--root", &root, "--binary", "Game"])
+        .args(["--file", export.to_str().unwrap(), "--format", "ghidra-json"])
+        .args(["--as-ritual", "Named", "--slice-root", "net_send"])
+        .assert()
+        .success();
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let analysis = db.load_analysis_result("Game", "Named").unwrap().unwrap();
+    let names: Vec<_> = analysis.functions.iter().map(|f| f.name.as_deref().unwrap()).collect();
+    assert_eq!(names, vec!["net_boot", "net_send", "draw_frame"]);
+
+    import_symbols(&map, "map")
+        .arg("--replace")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Replaced: 2 previously imported symbol(s)"));
+    let hash = db.list_binaries().unwrap()[0].hash.clone().unwrap();
+    assert_eq!(db.list_imported_symbols(&hash).unwrap().len(), 1);
+}
+
+#[test]
+fn ghidra_xml_backend_runs_rituals_from_an_xml_export() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Xml".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("exports")).unwrap();
+    fs::write(
+        temp.path().join("exports/game.xml"),
+        r#"<PROGRAM NAME="game.bin">
+  <FUNCTIONS>
+    <FUNCTION ENTRY_POINT="00001000" NAME="net_init"><ADDRESS_RANGE START="00001000" END="0000101f" /></FUNCTION>
+    <FUNCTION ENTRY_POINT="00002000" NAME="net_send"><ADDRESS_RANGE START="00002000" END="0000200f" /></FUNCTION>
+  </FUNCTIONS>
+  <MARKUP><MEMORY_REFERENCE ADDRESS="00001004" TO_ADDRESS="00002000" /></MARKUP>
+</PROGRAM>"#,
+    )
+    .unwrap();
+    let spec = temp.path().join("net.yaml");
+    fs::write(
+        &spec,
+        "name: Net\nbinary: Game\nroots: [net_init]\nbackend: ghidra-xml\nghidra_xml: exports/game.xml\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let runs = db.list_ritual_runs(Some("Game")).unwrap();
+    assert_eq!(runs[0].backend, "ghidra-xml");
+    assert!(runs[0].backend_version.as_deref().is_some_and(|v| v.starts_with("export ")));
+    let analysis = db.load_analysis_result("Game", "Net").unwrap().unwrap();
+    assert_eq!(analysis.functions.len(), 2);
+    let edges: Vec<_> = analysis.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    assert_eq!(edges, [(0x1000, 0x2000)]);
+
+    // The backend has nothing to read without the spec's export.
+    fs::write(&spec, "name: Net\nbinary: Game\nroots: [net_init]\nbackend: ghidra-xml\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--force", "--file", spec.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("set 'ghidra_xml' in the ritual spec"));
+}
@@ -56,3 +56,242 @@ fn init_project_and_add_binary_registers_in_db() {
     let expected_hash = format!("{:x}", hasher.finalize());
     assert_eq!(binaries[0].hash.as_deref(), Some(expected_hash.as_str()));
 }
+
+#[test]
+fn add_binary_reads_format_and_arch_from_the_headers() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root"]).arg(root).assert().success();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    fs::write(root.join(fixture.file_name), &fixture.bytes).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .args(["--path", fixture.file_name])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Format: ELF (x86_64, 64-bit, little-endian, not stripped)",
+        ));
+
+    let db = ProjectDb::open(&ProjectLayout::new(root).db_path).expect("open db");
+    assert_eq!(db.list_binaries().unwrap()[0].arch.as_deref(), Some("x86_64"));
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-binaries", "--json", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let binaries: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let metadata = &binaries[0]["metadata"];
+    assert_eq!(metadata["format"], "elf");
+    assert_eq!(metadata["bits"], 64);
+    assert_eq!(metadata["stripped"], false);
+}
+
+#[test]
+fn add_binary_keeps_an_explicit_arch_and_lists_wasm_modules() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root"]).arg(root).assert().success();
+    fs::write(root.join("game.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--path", "game.wasm", "--arch", "wasm32", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-binaries", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"(?m)^game\.wasm\s+wasm32\s").unwrap())
+        .stdout(predicates::str::contains("  WebAssembly (32-bit, little-endian)  "));
+}
+
+#[test]
+fn add_binary_with_group_tracks_builds_across_versions() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root"]).arg(root).assert().success();
+
+    for (version, bytes, build_id) in
+        [("v1.2", &b"build-1.2"[..], Some("aa11")), ("v1.3", &b"build-1.3"[..], None)]
+    {
+        let bin_path = root.join(format!("libExampleGame-{version}.so"));
+        fs::write(&bin_path, bytes).unwrap();
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["add-binary", "--root"]).arg(root).arg("--path").arg(&bin_path).args([
+            "--group",
+            "libExampleGame.so",
+            "--build-version",
+            version,
+        ]);
+        if let Some(build_id) = build_id {
+            cmd.args(["--build-id", build_id]);
+        }
+        cmd.assert().success();
+    }
+
+    // A duplicate build label is rejected without registering the file.
+    let dup = root.join("libExampleGame-dup.so");
+    fs::write(&dup, b"dup").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&dup)
+        .args(["--group", "libExampleGame.so", "--build-version", "v1.3"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("already has a build with version 'v1.3'"));
+    // --build-version only makes sense with --group.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&dup)
+        .args(["--build-version", "v9"])
+        .assert()
+        .failure();
+
+    let db = ProjectDb::open(&ProjectLayout::new(root).db_path).expect("open db");
+    assert_eq!(db.list_binaries().unwrap().len(), 2);
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-binary-groups", "--json", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let groups: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let groups = groups.as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["name"], "libExampleGame.so");
+    let members = groups[0]["members"].as_array().unwrap();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0]["version"], "v1.2");
+    assert_eq!(members[0]["build_id"], "aa11");
+    assert_eq!(members[1]["version"], "v1.3");
+    assert!(members[1]["build_id"].is_null());
+    assert_ne!(members[0]["binary"]["hash"], members[1]["binary"]["hash"]);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-binary-groups", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"(?m)^libExampleGame\.so\s+v1\.2\s").unwrap())
+        .stdout(predicates::str::is_match(r"(?m)^libExampleGame\.so\s+v1\.3\s").unwrap());
+}
+
+#[test]
+fn add_binary_rejects_missing_or_unreadable_pdbs() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path();
+    cargo_bin_cmd!("binary-slicer").args(["init-project", "--root"]).arg(root).assert().success();
+    let bin_path = root.join("Game.exe");
+    fs::write(&bin_path, b"MZ-not-really").unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .args(["--pdb", "Missing.pdb"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("PDB file does not exist"));
+
+    fs::write(root.join("Game.pdb"), b"not a program database").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root"])
+        .arg(root)
+        .arg("--path")
+        .arg(&bin_path)
+        .args(["--pdb", "Game.pdb"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to read PDB"));
+
+    // Neither attempt registered the binary.
+    let db = ProjectDb::open(&ProjectLayout::new(root).db_path).expect("open db");
+    assert!(db.list_binaries().unwrap().is_empty());
+}
+
+#[test]
+fn verify_binaries_reports_drift_updates_hashes_and_marks_runs_outdated() {
+    let dir = tempdir().expect("tempdir");
+    let root = dir.path();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-project", "--name", "Drift", "--root"])
+        .arg(root)
+        .assert()
+        .success();
+    for (name, bytes) in [("game.so", &b"v1"[..]), ("tools.so", &b"tools"[..])] {
+        fs::write(root.join(name), bytes).unwrap();
+        cargo_bin_cmd!("binary-slicer")
+            .args(["add-binary", "--name", name, "--root"])
+            .arg(root)
+            .arg("--path")
+            .arg(root.join(name))
+            .assert()
+            .success();
+    }
+    let spec = root.join("rituals").join("boot.yaml");
+    fs::write(&spec, "name: Boot\nbinary: game.so\nroots: [entry]\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root"])
+        .arg(root)
+        .arg("--file")
+        .arg(&spec)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["verify-binaries", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("- game.so: ok"));
+
+    // A patch replaces the binary: drift is reported and the command fails.
+    fs::write(root.join("game.so"), b"v2").unwrap();
+    let out = cargo_bin_cmd!("binary-slicer")
+        .args(["verify-binaries", "--json", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("1 binary(ies) drifted"))
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(report[0]["status"], "changed");
+    assert_eq!(report[0]["current_hash"], format!("{:x}", Sha256::digest(b"v2")));
+    assert_eq!(report[1]["status"], "ok");
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["verify-binaries", "--update", "--mark-outdated", "--root"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Marked 1 run(s) outdated"));
+    let db = ProjectDb::open(&ProjectLayout::new(root).db_path).unwrap();
+    assert_eq!(db.list_binaries().unwrap()[0].hash, Some(format!("{:x}", Sha256::digest(b"v2"))));
+    assert_eq!(db.list_ritual_runs(None).unwrap()[0].status.as_str(), "outdated");
+
+    fs::remove_file(root.join("tools.so")).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["verify-binaries", "--root"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("- tools.so: missing (file not found: tools.so)"));
+}
@@ -0,0 +1,215 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{
+    add_binary_command, complete_root, init_project_command, RootCompletion,
+};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+use ritual_core::services::analysis::{AnalysisResult, FunctionRecord};
+use serde_yaml::Value;
+use tempfile::tempdir;
+
+fn func(address: u64, name: &str) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn setup() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Wizard".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    (temp, root)
+}
+
+fn read_spec(root: &str, name: &str) -> Value {
+    let path = ProjectLayout::new(root).rituals_dir.join(format!("{name}.yaml"));
+    serde_yaml::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn completion_prefers_exact_then_prefix_then_substring_matches() {
+    let names: Vec<String> =
+        ["net_init", "net_recv", "net_send", "ui_draw_net"].iter().map(|n| n.to_string()).collect();
+    assert_eq!(complete_root("net_init", &names), RootCompletion::Known("net_init".into()));
+    assert_eq!(complete_root("NET_I", &names), RootCompletion::Completed("net_init".into()));
+    assert_eq!(
+        complete_root("net_", &names),
+        RootCompletion::Ambiguous(vec!["net_init".into(), "net_recv".into(), "net_send".into()])
+    );
+    assert_eq!(complete_root("draw", &names), RootCompletion::Completed("ui_draw_net".into()));
+    assert_eq!(complete_root("audio", &names), RootCompletion::Unknown("audio".into()));
+    assert_eq!(complete_root("0x1000", &names), RootCompletion::Known("0x1000".into()));
+    assert_eq!(complete_root("regex:^net", &names), RootCompletion::Known("regex:^net".into()));
+}
+
+#[test]
+fn init_ritual_writes_a_spec_from_flags_and_runs_it() {
+    let (_temp, root) = setup();
+    cargo_bin_cmd!("binary-slicer")
+        .args([
+            "init-ritual",
+            "--root",
+            &root,
+            "--name",
+            "Net",
+            "--binary",
+            "Game",
+            "--root-fn",
+            "net_init",
+            "--root-fn",
+            "0x1000",
+            "--root-fn",
+            "string:http",
+            "--backend",
+            "validate-only",
+            "--outputs",
+            "json,md",
+            "--no-input",
+            "--run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote ritual spec:"))
+        .stdout(predicate::str::contains("Ran ritual (stub): Net"));
+
+    let spec = read_spec(&root, "Net");
+    assert_eq!(spec["binary"], "Game");
+    assert_eq!(spec["backend"], "validate-only");
+    assert_eq!(spec["roots"][0], "net_init");
+    assert_eq!(spec["roots"][1]["address"], "0x1000");
+    assert_eq!(spec["roots"][2]["string"], "http");
+    assert_eq!(spec["outputs"]["formats"], serde_yaml::from_str::<Value>("[json, md]").unwrap());
+    assert!(ProjectLayout::new(&root).binary_output_root("Game").join("Net").exists());
+
+    // An existing spec is kept unless --force is given.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-ritual", "--root", &root, "--name", "Net", "--no-input"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+
+    // Missing fields fail instead of prompting, and an invalid spec is not left behind.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-ritual", "--root", &root, "--name", "Ui", "--binary", "Game", "--no-input"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Missing --root"));
+    cargo_bin_cmd!("binary-slicer")
+        .args([
+            "init-ritual",
+            "--root",
+            &root,
+            "--name",
+            "Ui",
+            "--binary",
+            "Nope",
+            "--root-fn",
+            "ui_draw",
+            "--no-input",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Ritual spec is invalid"));
+    assert!(!ProjectLayout::new(&root).rituals_dir.join("Ui.yaml").exists());
+}
+
+#[test]
+fn init_ritual_prompts_and_completes_roots_from_the_latest_analysis() {
+    let (_temp, root) = setup();
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "Game".into(),
+            ritual: "Old".into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "validate-only".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let analysis = AnalysisResult {
+        functions: vec![
+            func(0x1000, "net_init"),
+            func(0x2000, "net_recv"),
+            func(0x3000, "net_send"),
+            func(0x4000, "ui_draw"),
+        ],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).unwrap();
+    drop(db);
+
+    // Name, binary by number, roots (completed, ambiguous, regex), an unknown backend
+    // corrected, all outputs, and no run.
+    let answers = "Net\n1\nnet_i\nnet_\nnet_r\nregex:^ui_\n\nnope\nvalidate-only\n\nn\n";
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-ritual", "--root", &root])
+        .write_stdin(answers)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1) Game"))
+        .stdout(predicate::str::contains("-> net_init"))
+        .stdout(predicate::str::contains("Matches: net_init, net_recv, net_send"))
+        .stdout(predicate::str::contains("Unknown backend 'nope'"))
+        .stdout(predicate::str::contains("Ran ritual").not());
+
+    let spec = read_spec(&root, "Net");
+    assert_eq!(spec["name"], "Net");
+    assert_eq!(spec["binary"], "Game");
+    assert_eq!(spec["roots"][0], "net_init");
+    assert_eq!(spec["roots"][1], "net_recv");
+    assert_eq!(spec["roots"][2]["regex"], "^ui_");
+    assert_eq!(spec["backend"], "validate-only");
+    assert!(spec.get("outputs").is_none());
+
+    // Input that ends early fails rather than writing a partial spec.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-ritual", "--root", &root])
+        .write_stdin("Ui\n1\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Input ended while asking for --root"));
+    assert!(!ProjectLayout::new(&root).rituals_dir.join("Ui.yaml").exists());
+}
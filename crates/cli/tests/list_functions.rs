@@ -0,0 +1,133 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+use ritual_core::services::analysis::{AnalysisResult, FunctionRecord};
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+fn function(address: u64, name: &str, size: u32, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(size),
+        in_slice,
+        is_boundary: !in_slice,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+/// A project with binary `Game` and a `Net` run of three functions.
+fn project() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Functions".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let hash = db.list_binaries_with_ids().unwrap()[0].1.hash.clone();
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "Game".into(),
+            ritual: "Net".into(),
+            spec_hash: "spec".into(),
+            binary_hash: hash,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let result = AnalysisResult {
+        functions: vec![
+            function(0x1000, "net_init", 32, true),
+            function(0x1100, "net_send", 128, true),
+            function(0x2000, "sub_2000", 8, false),
+        ],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+    (temp, root)
+}
+
+#[test]
+fn list_functions_prints_a_filtered_page_with_annotations() {
+    let (_temp, root) = project();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["annotate-function", "--root", &root, "--binary", "Game"])
+        .args(["--address", "0x2000", "--name", "net_checksum"])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-functions", "--root", &root, "--binary", "Game", "--ritual", "Net"])
+        .args(["--name-contains", "checksum"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0x2000"))
+        .stdout(predicate::str::contains("net_checksum"))
+        .stdout(predicate::str::contains("boundary"))
+        .stdout(predicate::str::contains("Showing 1-1 of 1"))
+        .stdout(predicate::str::contains("net_init").not());
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["list-functions", "--root", &root, "--binary", "Game", "--ritual", "Net"])
+        .args(["--in-slice", "--min-size", "16", "--limit", "1", "--offset", "1", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let listing: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(listing["run_id"], 1);
+    assert_eq!(listing["total"], 2);
+    assert_eq!(listing["offset"], 1);
+    let functions = listing["functions"].as_array().unwrap();
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0]["address"], 0x1100);
+    assert_eq!(functions[0]["name"], "net_send");
+    assert_eq!(functions[0]["in_slice"], true);
+    assert!(functions[0].get("annotation").is_none());
+}
+
+#[test]
+fn list_functions_requires_a_recorded_run() {
+    let (_temp, root) = project();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-functions", "--root", &root, "--binary", "Game", "--ritual", "Gfx"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ritual run found for binary 'Game' / ritual 'Gfx'"));
+}
@@ -0,0 +1,88 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::init_project_command;
+use binary_slicer::output::{duration_ms, relative_time_at, use_colors, Cell, Table, Tone};
+use chrono::{DateTime, Utc};
+use predicates::prelude::*;
+use ritual_core::services::config::ColorChoice;
+use tempfile::tempdir;
+
+#[test]
+fn tables_align_on_visible_width_and_color_only_when_asked() {
+    let mut table = Table::new(&["name", "status", "note"]);
+    table.row(vec!["Networking".into(), Cell::toned("Active", Some(Tone::Green)), "".into()]);
+    table.row(vec!["UI".into(), Cell::toned("Draft", Some(Tone::Yellow)), "wip".into()]);
+
+    assert_eq!(
+        table.render(false),
+        "name        status  note\n\
+         ----------  ------  ----\n\
+         Networking  Active\n\
+         UI          Draft   wip\n"
+    );
+    let colored = table.render(true);
+    assert!(colored.contains("\x1b[1mname\x1b[0m        "));
+    // Padding follows the escapes, so colored rows line up with plain ones.
+    assert!(colored.contains("UI          \x1b[33mDraft\x1b[0m   wip\n"));
+
+    assert!(!use_colors(true, ColorChoice::Always, true));
+    assert!(use_colors(false, ColorChoice::Always, false));
+    assert!(!use_colors(false, ColorChoice::Never, true));
+    assert!(!use_colors(false, ColorChoice::Auto, false));
+}
+
+#[test]
+fn relative_times_shorten_recent_timestamps_and_keep_the_rest_readable() {
+    let now: DateTime<Utc> = "2026-03-10T12:00:00Z".parse().unwrap();
+    assert_eq!(relative_time_at("2026-03-10T11:59:30Z", now), "just now");
+    assert_eq!(relative_time_at("2026-03-10T11:48:00Z", now), "12m ago");
+    assert_eq!(relative_time_at("2026-03-10T09:00:00+00:00", now), "3h ago");
+    assert_eq!(relative_time_at("2026-03-06T12:00:00Z", now), "4d ago");
+    assert_eq!(relative_time_at("2025-12-01T08:00:00Z", now), "2025-12-01");
+    assert_eq!(relative_time_at("2026-04-01T00:00:00Z", now), "2026-04-01");
+    assert_eq!(relative_time_at("not a time", now), "not a time");
+}
+
+#[test]
+fn durations_pick_the_largest_useful_unit() {
+    assert_eq!(duration_ms(850), "850ms");
+    assert_eq!(duration_ms(12_400), "12.4s");
+    assert_eq!(duration_ms(185_000), "3m05s");
+    assert_eq!(duration_ms(7_620_000), "2h07m");
+}
+
+#[test]
+fn no_color_overrides_a_user_config_that_always_colors() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("proj").to_string_lossy().to_string();
+    init_project_command(&root, Some("Colors".into())).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Networking"])
+        .assert()
+        .success();
+    let config_home = temp.path().join("config");
+    fs::create_dir_all(config_home.join("binary-slicer")).unwrap();
+    fs::write(config_home.join("binary-slicer/config.toml"), "color = \"always\"\n").unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["list-slices", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[34mPlanned\x1b[0m"));
+    cargo_bin_cmd!("binary-slicer")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["list-slices", "--root", &root, "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Networking  Planned"))
+        .stdout(predicate::str::contains("\x1b[").not());
+    // JSON is never colored.
+    cargo_bin_cmd!("binary-slicer")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["list-slices", "--root", &root, "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+}
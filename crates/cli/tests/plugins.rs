@@ -0,0 +1,120 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::ProjectLayout;
+use serde_json::Value;
+use tempfile::tempdir;
+
+#[test]
+fn ritual_plugins_edit_the_result_before_it_is_recorded() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Plugins".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    fs::create_dir(temp.path().join("plugins")).unwrap();
+    // Answers every input with one piece of evidence.
+    let score = r#"(module
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 64) "{\"evidence\": [{\"address\": 4096, \"description\": \"score: 0.9\"}]}")
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 64))
+    (i32.store (i32.const 4) (i32.const 62))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))"#;
+    fs::write(temp.path().join("plugins").join("score.wasm"), wat::parse_str(score).unwrap())
+        .unwrap();
+
+    let spec = temp.path().join("net.yaml");
+    fs::write(
+        &spec,
+        "name: Net\nbinary: Game\nroots: [net_init]\nbackend: validate-only\nplugins: [./plugins/score.wasm]\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success();
+    let run_dir = ProjectLayout::new(&root).binary_output_root("Game").join("Net");
+    let report: Value =
+        serde_json::from_str(&fs::read_to_string(run_dir.join("report.json")).unwrap()).unwrap();
+    let evidence = report["evidence"].as_array().unwrap();
+    assert!(evidence.iter().any(|e| e["address"] == 4096 && e["description"] == "score: 0.9"));
+
+    // A plugin that is not there fails the run.
+    fs::remove_file(temp.path().join("plugins").join("score.wasm")).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--force", "--file", spec.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Ritual run Game/Net failed"))
+        .stderr(predicate::str::contains("Plugin module not found at"));
+}
+
+#[test]
+fn string_decoders_add_decoded_strings_to_the_run() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Decoders".into())).unwrap();
+    let bin_path = temp.path().join("fw.bin");
+    let mut image = vec![0u8; 0x10];
+    image.extend(b"AutoUpdate server".iter().map(|b| b ^ 0x5a));
+    image.push(0);
+    image.extend(b"c2VjcmV0IGtleQ==\0");
+    fs::write(&bin_path, &image).unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Fw".into()),
+        Some("armv7".into()),
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let spec = temp.path().join("update.yaml");
+    fs::write(
+        &spec,
+        "name: Update\nbinary: Fw\nroots: [main]\nbackend: validate-only\nraw: {load_address: 0x1000}\nstring_decoders: [xor:0x5a, base64]\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success();
+    let run_dir = ProjectLayout::new(&root).binary_output_root("Fw").join("Update");
+    let report: Value =
+        serde_json::from_str(&fs::read_to_string(run_dir.join("report.json")).unwrap()).unwrap();
+    let decoded: Vec<(u64, &str)> = report["evidence"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["source"]["backend"] == "decoder")
+        .map(|e| (e["address"].as_u64().unwrap(), e["description"].as_str().unwrap()))
+        .collect();
+    assert_eq!(decoded, [(0x1010, "string: AutoUpdate server"), (0x1022, "string: secret key")]);
+
+    fs::write(
+        &spec,
+        "name: Update\nbinary: Fw\nroots: [main]\nbackend: validate-only\nraw: {load_address: 0x1000}\nstring_decoders: [xor:0x1ff]\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--force", "--file", spec.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid XOR key in string decoder 'xor:0x1ff'"));
+}
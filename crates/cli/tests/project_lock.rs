@@ -0,0 +1,70 @@
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::init_project_command;
+use predicates::prelude::*;
+use ritual_core::db::{ProjectLayout, ProjectLock};
+use tempfile::tempdir;
+
+#[test]
+fn mutating_commands_fail_fast_on_a_locked_project_but_reads_pass() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Locked".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    std::fs::write(&bin_path, b"game").unwrap();
+
+    let layout = ProjectLayout::new(temp.path());
+    let lock =
+        ProjectLock::try_acquire(&layout, "binary-slicer run-ritual --file net.yaml").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--path", bin_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Project is locked by pid {} (`binary-slicer run-ritual --file net.yaml`",
+            std::process::id()
+        )))
+        .stderr(predicate::str::contains("pass --wait to wait for it to finish"));
+    for args in [vec!["list-binaries"], vec!["config", "list"], vec!["project-info"]] {
+        cargo_bin_cmd!("binary-slicer").args(&args).args(["--root", &root]).assert().success();
+    }
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "default_backend", "validate-only", "--root", &root])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Project is locked"));
+    drop(lock);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["add-binary", "--root", &root, "--path", bin_path.to_str().unwrap(), "--no-wait"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn wait_blocks_until_the_lock_is_released() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Waiting".into())).unwrap();
+    let layout = ProjectLayout::new(temp.path());
+    let lock = ProjectLock::try_acquire(&layout, "holder").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_binary-slicer"))
+        .args(["--wait", "init-slice", "--root", &root, "--name", "Net"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(child.try_wait().unwrap().is_none(), "ran while the project was locked");
+    drop(lock);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Project is locked by pid"), "{stderr}");
+    assert!(stderr.contains("waiting for it to finish"), "{stderr}");
+    assert!(layout.slices_docs_dir.join("Net.md").is_file());
+}
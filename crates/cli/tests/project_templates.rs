@@ -0,0 +1,101 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{
+    builtin_template_names, init_project_with_template_command, resolve_template,
+};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, SliceStatus};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn init_project_with_game_client_template_creates_slices_specs_and_docs() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-project", "--root", &root, "--name", "Game", "--template", "game-client"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Template: game-client"))
+        .stdout(predicate::str::contains("Slices: UI, Networking, AutoUpdate, Save"));
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let slices = db.list_slices().unwrap();
+    let names: Vec<&str> = slices.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["UI", "Networking", "AutoUpdate", "Save"]);
+    assert!(slices.iter().all(|s| s.status == SliceStatus::Planned && s.description.is_some()));
+
+    for name in &names {
+        assert!(layout.rituals_dir.join(format!("{name}.yaml")).is_file());
+        let doc = fs::read_to_string(layout.slices_docs_dir.join(format!("{name}.md"))).unwrap();
+        assert!(doc.starts_with(&format!("# {name}\n")));
+    }
+    assert!(layout.docs_dir.join("README.md").is_file());
+
+    // The shipped specs pass validation and are discovered like hand-written ones.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-specs", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Networking"));
+}
+
+#[test]
+fn template_directory_is_applied_without_overwriting_existing_work() {
+    let temp = tempdir().unwrap();
+    let template = temp.path().join("tpl");
+    fs::create_dir_all(template.join("rituals")).unwrap();
+    fs::create_dir_all(template.join("docs/slices")).unwrap();
+    fs::write(
+        template.join("template.yaml"),
+        "name: custom\nslices:\n  - name: Combat\n    description: Damage and hit tests.\n  - name: Audio\n",
+    )
+    .unwrap();
+    fs::write(
+        template.join("rituals/Combat.yaml"),
+        "name: Combat\nbinary: Client\nroots:\n  - regex: \".*Damage.*\"\n",
+    )
+    .unwrap();
+    fs::write(template.join("docs/slices/Combat.md"), "# Combat\n\nHand-written notes.\n").unwrap();
+    fs::write(template.join("rituals/notes.txt"), "ignored").unwrap();
+
+    let root_dir = temp.path().join("project");
+    fs::create_dir_all(root_dir.join("docs/slices")).unwrap();
+    fs::write(root_dir.join("docs/slices/Audio.md"), "# Audio\n\nMine.\n").unwrap();
+    let root = root_dir.to_string_lossy().to_string();
+    init_project_with_template_command(&root, None, Some(template.to_str().unwrap())).unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    let combat = fs::read_to_string(layout.slices_docs_dir.join("Combat.md")).unwrap();
+    assert!(combat.contains("Hand-written notes."));
+    let audio = fs::read_to_string(layout.slices_docs_dir.join("Audio.md")).unwrap();
+    assert_eq!(audio, "# Audio\n\nMine.\n");
+    assert!(layout.rituals_dir.join("Combat.yaml").is_file());
+    assert!(!layout.rituals_dir.join("notes.txt").exists());
+
+    // Re-initializing keeps the registered slices instead of failing on duplicates.
+    init_project_with_template_command(&root, None, Some(template.to_str().unwrap())).unwrap();
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    assert_eq!(db.list_slices().unwrap().len(), 2);
+}
+
+#[test]
+fn unknown_or_invalid_templates_are_rejected_before_creating_the_project() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("project");
+    let err =
+        init_project_with_template_command(root.to_str().unwrap(), None, Some("no-such-template"))
+            .unwrap_err();
+    assert!(err.to_string().contains("built-in templates: game-client"));
+    assert!(!root.join(".ritual").exists());
+
+    let template = temp.path().join("broken");
+    fs::create_dir_all(template.join("rituals")).unwrap();
+    fs::write(template.join("template.yaml"), "slices: []\n").unwrap();
+    fs::write(template.join("rituals/bad.yaml"), "name: Bad\nroots: [main]\n").unwrap();
+    let err = resolve_template(template.to_str().unwrap()).unwrap_err();
+    assert!(format!("{err:#}").contains("rituals/bad.yaml"));
+
+    assert!(builtin_template_names().contains(&"game-client"));
+}
@@ -0,0 +1,126 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command, RitualSpec};
+use predicates::prelude::*;
+use ritual_core::db::ProjectLayout;
+use ritual_core::services::analysis::Endian;
+use std::fs;
+use tempfile::tempdir;
+
+fn project_with_firmware() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Firmware".into())).unwrap();
+    let bin_path = temp.path().join("fw.bin");
+    fs::write(&bin_path, [0x00, 0xF0, 0x00, 0xF8, 0x70, 0x47]).unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Fw".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    (temp, root)
+}
+
+fn run_spec(root: &str, spec: &str) -> assert_cmd::assert::Assert {
+    let spec_path = std::path::Path::new(root).join("fw.yaml");
+    fs::write(&spec_path, spec).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", root, "--file", spec_path.to_str().unwrap()])
+        .assert()
+}
+
+#[test]
+fn raw_spec_is_kept_in_the_normalized_run_spec() {
+    let (_temp, root) = project_with_firmware();
+    run_spec(
+        &root,
+        "name: Boot\nbinary: Fw\nroots: [{address: 0x1000}]\nbackend: validate-only\n\
+         raw:\n  load_address: '0x1000'\n  arch: arm\n  thumb: true\n  endian: little\n",
+    )
+    .success();
+
+    let layout = ProjectLayout::new(&root);
+    let spec_yaml =
+        fs::read_to_string(layout.binary_output_root("Fw").join("Boot").join("spec.yaml")).unwrap();
+    let spec: RitualSpec = serde_yaml::from_str(&spec_yaml).unwrap();
+    let raw = spec.raw.expect("raw block persisted");
+    assert_eq!(raw.arch.as_deref(), Some("arm"));
+    let image = raw.image().unwrap();
+    assert_eq!(image.load_address, 0x1000);
+    assert!(image.thumb);
+    assert_eq!(image.endian, Endian::Little);
+}
+
+#[test]
+fn raw_specs_need_a_valid_load_address_and_an_arch() {
+    let (_temp, root) = project_with_firmware();
+    run_spec(
+        &root,
+        "name: Bad\nbinary: Fw\nroots: [main]\nbackend: validate-only\nraw: {load_address: 'zz'}\n",
+    )
+    .failure()
+    .stderr(predicate::str::contains("'raw.load_address' must be an integer"));
+
+    // The binary was registered without --arch, so the spec has to name one.
+    run_spec(
+        &root,
+        "name: NoArch\nbinary: Fw\nroots: [main]\nbackend: validate-only\nraw: {load_address: 4096}\n",
+    )
+    .failure()
+    .stderr(predicate::str::contains("needs an architecture"));
+}
+
+#[test]
+fn max_depth_runs_pass_strict_validation() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Chain".into())).unwrap();
+    // 0x00: call 0x10; ret
+    // 0x10: call 0x20; ret
+    // 0x20: nop x8; ret
+    let mut bytes = vec![0xE8, 0x0B, 0x00, 0x00, 0x00, 0xC3];
+    bytes.resize(0x10, 0xCC);
+    bytes.extend_from_slice(&[0xE8, 0x0B, 0x00, 0x00, 0x00, 0xC3]);
+    bytes.resize(0x20, 0xCC);
+    bytes.extend_from_slice(&[0x90; 8]);
+    bytes.push(0xC3);
+    let bin_path = temp.path().join("fw.bin");
+    fs::write(&bin_path, bytes).unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Fw".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    let spec_path = temp.path().join("fw.yaml");
+    fs::write(
+        &spec_path,
+        "name: Shallow\nbinary: Fw\nroots: [{address: 0x0}]\nbackend: capstone\nmax_depth: 1\n\
+         raw: {load_address: 0x0, arch: x86_64}\n",
+    )
+    .unwrap();
+
+    // The call 0x10 makes past max_depth still lands on a function, so validation passes.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--strict", "--file"])
+        .arg(&spec_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Validation:").not());
+
+    let layout = ProjectLayout::new(&root);
+    let report_path = layout.binary_output_root("Fw").join("Shallow").join("report.json");
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(report_path).unwrap()).unwrap();
+    assert_eq!(report["validation"]["issues"], serde_json::json!([]));
+    let stub = report["functions"].as_array().unwrap().iter().find(|f| f["address"] == 0x20);
+    assert_eq!(stub.expect("stub at 0x20")["name"], "sub_20");
+}
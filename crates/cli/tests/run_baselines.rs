@@ -0,0 +1,102 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::ProjectLayout;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+const EXPORT: &str = r#"{
+  "program": "game.bin",
+  "language": "x86:LE:64:default",
+  "functions": [
+    {"entry": 4096, "name": "net_init", "size": 32, "calls": [{"to": 8192, "name": "net_send"}]},
+    {"entry": 8192, "name": "net_send", "size": 16},
+    {"entry": 12288, "name": "render", "size": 64}
+  ],
+  "strings": [],
+  "imports": []
+}"#;
+
+#[test]
+fn runs_fail_when_they_regress_against_the_baseline() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Gate".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["set-baseline", "--root", &root, "--binary", "Game", "--ritual", "Net"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ritual run found for binary 'Game' / ritual 'Net'"));
+
+    // The baseline: an imported analysis resolving the network functions.
+    let export = temp.path().join("game.json");
+    fs::write(&export, EXPORT).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["import-analysis", "--root", &root, "--binary", "Game"])
+        .args(["--file", export.to_str().unwrap(), "--format", "ghidra-json"])
+        .args(["--as-ritual", "Net", "--slice-root", "regex:net_.*"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["set-baseline", "--root", &root, "--binary", "Game", "--ritual", "Net"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Baseline for Game / Net: run 1"))
+        .stdout(predicate::str::contains("Functions: 3 (3 in slice), call edges: 1"));
+
+    // A run that finds nothing loses the slice and its root.
+    let spec = temp.path().join("net.yaml");
+    fs::write(&spec, "name: Net\nbinary: Game\nroots: [\"regex:net_.*\"]\n").unwrap();
+    let run = || {
+        let mut cmd = cargo_bin_cmd!("binary-slicer");
+        cmd.args(["run-ritual", "--root", &root, "--backend", "validate-only", "--force"])
+            .arg("--file")
+            .arg(&spec);
+        cmd
+    };
+    run()
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Baseline: run 1 of Game (2 regression(s))"))
+        .stderr(predicate::str::contains("Run regressed against baseline run 1 of Game"))
+        .stderr(predicate::str::contains("in-slice functions dropped 100.0% (3 -> 0; limit 10%)"))
+        .stderr(predicate::str::contains("roots no longer resolved: regex:net_.*"));
+    let run_dir = ProjectLayout::new(&root).binary_output_root("Game").join("Net");
+    let report: Value =
+        serde_json::from_str(&fs::read_to_string(run_dir.join("report.json")).unwrap()).unwrap();
+    assert_eq!(report["baseline"]["baseline"]["run_id"], 1);
+    assert_eq!(report["baseline"]["lost_roots"][0], "regex:net_.*");
+    assert_eq!(report["baseline"]["regressions"].as_array().unwrap().len(), 2);
+
+    // Looser limits let the same run pass.
+    for (key, value) in
+        [("regressions.max_in_slice_drop", "100"), ("regressions.unresolved_roots", "false")]
+    {
+        cargo_bin_cmd!("binary-slicer")
+            .args(["config", "set", "--root", &root, key, value])
+            .assert()
+            .success();
+    }
+    run().assert().success().stdout(predicate::str::contains("Baseline: run 1 of Game (ok)"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["set-baseline", "--root", &root, "--binary", "Game", "--ritual", "Net", "--clear"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cleared baseline for Game / Net"));
+    run().assert().success().stdout(predicate::str::contains("Baseline:").not());
+}
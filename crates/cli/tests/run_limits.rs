@@ -0,0 +1,173 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command, RitualRunMetadata};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunStatus};
+use std::fs;
+use tempfile::tempdir;
+
+/// A project with binaries `Game` (on disk) and `Gone` (deleted after registration), and a
+/// validate-only spec per binary under `rituals/`.
+fn project_with_missing_binary() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Limits".into())).unwrap();
+    for (name, file) in [("Game", "game.bin"), ("Gone", "gone.bin")] {
+        let bin_path = temp.path().join(file);
+        fs::write(&bin_path, b"bytes").unwrap();
+        add_binary_command(
+            &root,
+            bin_path.to_str().unwrap(),
+            Some(name.into()),
+            None,
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+    fs::remove_file(temp.path().join("gone.bin")).unwrap();
+    let rituals = temp.path().join("rituals");
+    fs::write(
+        rituals.join("boot.yaml"),
+        "name: Boot\nbinary: Game\nroots: [main]\nbackend: validate-only\ntimeout: 30\n",
+    )
+    .unwrap();
+    fs::write(
+        rituals.join("lost.yaml"),
+        "name: Lost\nbinary: Gone\nroots: [main]\nbackend: validate-only\n",
+    )
+    .unwrap();
+    (temp, root)
+}
+
+#[test]
+fn timeouts_are_accepted_from_specs_and_flags() {
+    let (temp, root) = project_with_missing_binary();
+    let spec = temp.path().join("rituals").join("boot.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap(), "--timeout", "5"])
+        .assert()
+        .success();
+    let layout = ProjectLayout::new(&root);
+    let normalized =
+        fs::read_to_string(layout.binary_output_root("Game").join("Boot").join("spec.yaml"))
+            .unwrap();
+    assert!(normalized.contains("timeout: 30"));
+
+    let zero = temp.path().join("zero.yaml");
+    fs::write(&zero, "name: Zero\nbinary: Game\nroots: [main]\ntimeout: 0\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", zero.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'timeout' must be at least 1 second"));
+}
+
+#[test]
+fn failed_analyses_are_recorded_with_their_error() {
+    let (temp, root) = project_with_missing_binary();
+    let spec = temp.path().join("rituals").join("lost.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Ritual run Gone/Lost failed"))
+        .stderr(predicate::str::contains("Binary not found"));
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let runs = db.list_ritual_runs(Some("Gone")).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].status, RitualRunStatus::Failed);
+    let metadata_path = layout.binary_output_root("Gone").join("Lost").join("run_metadata.json");
+    let metadata: RitualRunMetadata =
+        serde_json::from_str(&fs::read_to_string(metadata_path).unwrap()).unwrap();
+    assert_eq!(metadata.status, RitualRunStatus::Failed);
+    assert!(metadata.error.unwrap().contains("Binary not found"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["show-ritual-run", "--root", &root, "--binary", "Gone", "--ritual", "Lost"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Status: failed"))
+        .stdout(predicate::str::contains("Error: Binary not found"));
+}
+
+#[test]
+fn batch_runs_record_failed_analyses_alongside_successes() {
+    let (_temp, root) = project_with_missing_binary();
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["batch-run", "--root", &root, "--json", "--timeout", "60"])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let summary: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(summary["succeeded"], 1);
+    assert_eq!(summary["failed"], 1);
+    let lost =
+        summary["runs"].as_array().unwrap().iter().find(|row| row["ritual"] == "Lost").unwrap();
+    assert_eq!(lost["status"], "failed");
+
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let statuses: Vec<_> =
+        db.list_ritual_runs(None).unwrap().into_iter().map(|r| (r.ritual, r.status)).collect();
+    assert!(statuses.contains(&("Boot".to_string(), RitualRunStatus::Stubbed)));
+    assert!(statuses.contains(&("Lost".to_string(), RitualRunStatus::Failed)));
+}
+
+#[cfg(unix)]
+#[test]
+fn interrupted_runs_are_recorded_as_canceled() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Interrupted".into())).unwrap();
+    let fixture = ritual_core::services::fixtures::synthetic_elf_x86_64();
+    let bin_path = temp.path().join(fixture.file_name);
+    fs::write(&bin_path, &fixture.bytes).unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    // A decoder hook that hangs, with a helper that would outlive it.
+    let hook = temp.path().join("hang.sh");
+    fs::write(&hook, "#!/bin/sh\ntouch started\n(sleep 2; touch survived) &\nsleep 30\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    let spec = temp.path().join("hang.yaml");
+    fs::write(
+        &spec,
+        "name: Hang\nbinary: Game\nroots: [main]\nbackend: capstone\nstring_decoders: [hang.sh]\n",
+    )
+    .unwrap();
+
+    let run = std::process::Command::new(env!("CARGO_BIN_EXE_binary-slicer"))
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    while !temp.path().join("started").exists() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let pid = run.id().to_string();
+    assert!(std::process::Command::new("kill").args(["-INT", &pid]).status().unwrap().success());
+    let output = run.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Ritual run Game/Hang canceled"), "{stderr}");
+    assert!(stderr.contains("hang.sh was interrupted and killed"), "{stderr}");
+
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let runs = db.list_ritual_runs(Some("Game")).unwrap();
+    assert_eq!(runs[0].status, RitualRunStatus::Canceled);
+    std::thread::sleep(std::time::Duration::from_millis(2500));
+    assert!(!temp.path().join("survived").exists(), "hook helper outlived the interrupt");
+}
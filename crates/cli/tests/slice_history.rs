@@ -0,0 +1,183 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command, BinaryGroupOptions};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+use ritual_core::services::analysis::{AnalysisResult, FunctionRecord};
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn func(address: u64, name: &str, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+/// Record a run of the `Networking` ritual with `functions`, snapshotting slices like
+/// `persist_run` unless `snapshot` is false (runs recorded before snapshots existed).
+fn record_run(
+    db: &ProjectDb,
+    binary: &str,
+    finished_at: &str,
+    functions: Vec<FunctionRecord>,
+    snapshot: bool,
+) -> i64 {
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: binary.into(),
+            ritual: "Networking".into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "validate-only".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: finished_at.into(),
+            finished_at: finished_at.into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let analysis = AnalysisResult {
+        functions,
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).unwrap();
+    if snapshot {
+        db.record_slice_snapshots(run_id).unwrap();
+    }
+    run_id
+}
+
+#[test]
+fn slice_history_lists_member_counts_and_deltas_per_run() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Game".into())).unwrap();
+    for (name, version) in [("Game10", "1.0"), ("Game11", "1.1")] {
+        let path = temp.path().join(format!("{name}.so"));
+        fs::write(&path, name).unwrap();
+        let group = BinaryGroupOptions {
+            group: "game".into(),
+            version: Some(version.into()),
+            build_id: None,
+        };
+        add_binary_command(
+            &root,
+            path.to_str().unwrap(),
+            Some(name.into()),
+            None,
+            None,
+            false,
+            Some(group),
+        )
+        .unwrap();
+    }
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Networking"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-history", "--root", &root, "--name", "Networking"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(no runs; run a ritual named 'Networking')"));
+
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    let first = record_run(
+        &db,
+        "Game10",
+        "2026-01-01T00:00:00Z",
+        vec![
+            func(0x1000, "net_init", true),
+            func(0x2000, "net_send", true),
+            func(0x3000, "ui_draw", false),
+        ],
+        true,
+    );
+    // A manual override of the newer build is part of its snapshot.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-add-function", "--root", &root, "--slice", "Networking"])
+        .args(["--binary", "Game11", "--address", "0x3100"])
+        .assert()
+        .success();
+    let second = record_run(
+        &db,
+        "Game11",
+        "2026-02-01T00:00:00Z",
+        vec![
+            func(0x1100, "net_init", true),
+            func(0x2200, "net_recv", true),
+            func(0x3100, "ui_draw", false),
+        ],
+        true,
+    );
+    let legacy = record_run(
+        &db,
+        "Game11",
+        "2026-03-01T00:00:00Z",
+        vec![func(0x1100, "net_init", true)],
+        false,
+    );
+    drop(db);
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["slice-history", "--root", &root, "--name", "Networking", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let history: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(history["slice"], "Networking");
+    let runs = history["runs"].as_array().unwrap();
+    let ids: Vec<i64> = runs.iter().map(|r| r["run_id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, [first, second, legacy]);
+    assert_eq!(runs[0]["version"], "1.0");
+    assert_eq!(runs[0]["functions"], 2);
+    assert_eq!(runs[0]["added"], serde_json::json!(["net_init", "net_send"]));
+    assert_eq!(runs[1]["version"], "1.1");
+    assert_eq!(runs[1]["functions"], 3);
+    assert_eq!(runs[1]["added"], serde_json::json!(["net_recv", "ui_draw"]));
+    assert_eq!(runs[1]["removed"], serde_json::json!(["net_send"]));
+    assert_eq!(runs[1]["snapshot"], true);
+    assert_eq!(runs[2]["functions"], 1);
+    assert_eq!(runs[2]["removed"], serde_json::json!(["net_recv", "ui_draw"]));
+    assert_eq!(runs[2]["snapshot"], false);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-history", "--root", &root, "--name", "Networking"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Game11 (1.1) [succeeded] backend=validate-only"))
+        .stdout(predicate::str::contains("3 functions (+2 -1)"))
+        .stdout(predicate::str::contains("    - net_send"))
+        .stdout(predicate::str::contains("[no snapshot]"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-history", "--root", &root, "--name", "Missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Missing' not found"));
+}
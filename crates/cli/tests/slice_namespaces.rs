@@ -0,0 +1,104 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command, BinaryGroupOptions};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn run(binary: &str, finished_at: &str) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        ritual: "Networking".into(),
+        spec_hash: "sh".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: finished_at.into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+fn show_slice(root: &str, name: &str) -> Value {
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["show-slice", "--root", root, "--name", name, "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn slices_are_scoped_per_binary_group_and_cloned_across_groups() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Game".into())).unwrap();
+    for (name, group) in [("GameArm", "armv7"), ("GameX86", "x86")] {
+        let path = temp.path().join(format!("{name}.so"));
+        fs::write(&path, name).unwrap();
+        let group =
+            BinaryGroupOptions { group: group.into(), version: Some(name.into()), build_id: None };
+        add_binary_command(
+            &root,
+            path.to_str().unwrap(),
+            Some(name.into()),
+            None,
+            None,
+            false,
+            Some(group),
+        )
+        .unwrap();
+    }
+    let db = ProjectDb::open(&ProjectLayout::new(&root).db_path).unwrap();
+    db.insert_ritual_run(&run("GameArm", "t1")).unwrap();
+    db.insert_ritual_run(&run("GameX86", "t2")).unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Networking", "--group", "x86"])
+        .args(["--description", "Sockets and packets"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Name: Networking@x86"));
+    db.set_slice_roots_and_notes("Networking@x86", &["net_init".into()], Some("x86 only")).unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["clone-slice", "--root", &root, "--name", "Networking@x86", "--to-group", "armv7"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cloned slice Networking@x86 to Networking@armv7"));
+
+    // The copy keeps description and roots, and only sees runs of its group's binaries.
+    let arm = show_slice(&root, "Networking@armv7");
+    assert_eq!(arm["namespace"], "armv7");
+    assert_eq!(arm["description"], "Sockets and packets");
+    assert_eq!(arm["roots"], serde_json::json!(["net_init"]));
+    assert_eq!(arm["notes"], Value::Null);
+    assert_eq!(arm["latest_run"]["binary"], "GameArm");
+    assert_eq!(show_slice(&root, "Networking@x86")["latest_run"]["binary"], "GameX86");
+    let doc = ProjectLayout::new(&root).slices_docs_dir.join("Networking@armv7.md");
+    assert!(fs::read_to_string(doc).unwrap().contains("- net_init"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["clone-slice", "--root", &root, "--name", "Networking@x86", "--to-group", "armv7"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Networking@armv7' already exists"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["clone-slice", "--root", &root, "--name", "Networking@x86", "--to-group", "mips"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Binary group 'mips' not found"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Net@work", "--namespace", "lab"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("must not contain '@'"));
+}
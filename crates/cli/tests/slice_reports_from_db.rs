@@ -1,6 +1,8 @@
 use binary_slicer::commands::{
     emit_slice_docs_command, emit_slice_reports_command, init_project_command, init_slice_command,
+    slice_add_function_command, slice_remove_function_command, SliceReportFormat,
 };
+use ritual_core::analysis::graph::DotOptions;
 use ritual_core::db::{ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus};
 use ritual_core::services::analysis::{
     AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind, EvidenceRecord,
@@ -31,6 +33,12 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
         status: RitualRunStatus::Succeeded,
         started_at: "t0".into(),
         finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let run_id = db.insert_ritual_run(&run).expect("insert run");
     let analysis = AnalysisResult {
@@ -40,22 +48,39 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
             size: Some(8),
             in_slice: true,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x1000,
+            to: 0x2000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x1000, to: 0x2000, is_cross_slice: false }],
         basic_blocks: vec![BasicBlock {
             start: 0x1000,
-            len: 8,
+            byte_len: 8,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x2000, kind: BlockEdgeKind::Jump }],
         }],
         evidence: vec![EvidenceRecord {
             address: 0x1000,
             description: "import foo".into(),
             kind: Some(EvidenceKind::Import),
+            source: None,
+            run_id: None,
         }],
         roots: vec!["root_a".into()],
-        root_hits: vec![RootHit { root: "root_a".into(), functions: vec![0x1000] }],
+        root_hits: vec![RootHit { root: "root_a".into(), functions: vec![0x1000], error: None }],
         backend_version: Some("rz-1.0".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
 
@@ -71,6 +96,12 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
         status: RitualRunStatus::Succeeded,
         started_at: "t2".into(),
         finished_at: "t9".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let newer_run_id = db.insert_ritual_run(&newer_run).expect("insert newer run");
     let newer_analysis = AnalysisResult {
@@ -80,25 +111,42 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
             size: Some(12),
             in_slice: true,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x2000,
+            to: 0x3000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x2000, to: 0x3000, is_cross_slice: false }],
         basic_blocks: vec![BasicBlock {
             start: 0x2000,
-            len: 12,
+            byte_len: 12,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x3000, kind: BlockEdgeKind::Jump }],
         }],
         evidence: vec![EvidenceRecord {
             address: 0x2000,
             description: "newer import".into(),
             kind: Some(EvidenceKind::Import),
+            source: None,
+            run_id: None,
         }],
         roots: vec!["root_a".into(), "root_b".into()],
         root_hits: vec![
-            RootHit { root: "root_a".into(), functions: vec![0x2000] },
-            RootHit { root: "root_b".into(), functions: Vec::new() },
+            RootHit { root: "root_a".into(), functions: vec![0x2000], error: None },
+            RootHit { root: "root_b".into(), functions: Vec::new(), error: None },
         ],
         backend_version: Some("rz-2.0".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(newer_run_id, &newer_analysis).expect("insert newer analysis");
 
@@ -114,6 +162,12 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
         status: RitualRunStatus::Succeeded,
         started_at: "t10".into(),
         finished_at: "t11".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let bin_b_run_id = db.insert_ritual_run(&bin_b_run).expect("insert bin b run");
     let bin_b_analysis = AnalysisResult {
@@ -123,31 +177,53 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
             size: Some(10),
             in_slice: true,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x4000,
+            to: 0x5000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x4000, to: 0x5000, is_cross_slice: false }],
         basic_blocks: vec![BasicBlock {
             start: 0x4000,
-            len: 10,
+            byte_len: 10,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x5000, kind: BlockEdgeKind::Jump }],
         }],
         evidence: vec![EvidenceRecord {
             address: 0x4000,
             description: "binb call".into(),
             kind: Some(EvidenceKind::Call),
+            source: None,
+            run_id: None,
         }],
         roots: vec!["root_b".into()],
-        root_hits: vec![RootHit { root: "root_b".into(), functions: vec![0x4000] }],
+        root_hits: vec![RootHit { root: "root_b".into(), functions: vec![0x4000], error: None }],
         backend_version: Some("rz-2.1".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(bin_b_run_id, &bin_b_analysis).expect("insert bin b analysis");
 
-    emit_slice_reports_command(&root, None).unwrap();
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
 
     let report_path = layout.reports_dir.join("SliceOne.json");
     let graph_path = layout.graphs_dir.join("SliceOne.dot");
     assert!(report_path.is_file(), "report should be written");
     assert!(graph_path.is_file(), "graph should be written");
+    let dot = std::fs::read_to_string(&graph_path).unwrap();
+    assert!(dot.contains("\"fn:NewerFunc\" [label=\"NewerFunc\" shape=box addr=\"0x2000\"]"));
+    assert!(dot.contains("\"fn:NewerFunc\" -> \"addr:0x3000\" [label=\"call\"]"));
+    assert!(dot.contains("\"fn:NewerFunc+0x0\" -> \"bb:0x3000\" [label=\"jump\"]"));
 
     let report: Value =
         serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
@@ -157,6 +233,7 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
     assert_eq!(report["backend"], "rizin");
     assert_eq!(report["backend_version"], "rz-2.0");
     assert_eq!(report["backend_path"], "/usr/bin/rizin");
+    assert_eq!(report["report_schema_version"], 1);
     assert_eq!(report["call_edges"].as_array().unwrap().len(), 1);
     let ev = report["evidence"].as_array().unwrap();
     assert!(!ev.is_empty());
@@ -182,8 +259,21 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
     assert!(graph.contains("NewerFunc") || graph.contains("0x2000"));
     assert!(graph.contains("call") || graph.contains("jump"));
 
+    let html = std::fs::read_to_string(layout.reports_dir.join("SliceOne.html")).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<td>NewerFunc</td>"), "function table lists the function");
+    assert!(html.contains("<svg"), "call graph is embedded");
+    assert!(html.contains("<details>\n<summary>Imports (1)</summary>"), "{html}");
+    assert!(!html.contains("<link") && !html.contains("<script"), "report is self-contained");
+
     // Override binary to pick BinB run instead of default slice linkage.
-    emit_slice_reports_command(&root, Some("BinB")).unwrap();
+    emit_slice_reports_command(
+        &root,
+        Some("BinB"),
+        SliceReportFormat::Json,
+        &DotOptions::default(),
+    )
+    .unwrap();
     let report_override: Value =
         serde_json::from_str(&std::fs::read_to_string(&report_path).unwrap()).unwrap();
     let funcs_override = report_override["functions"].as_array().unwrap();
@@ -202,7 +292,7 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
     assert_eq!(root_hits_override.len(), 1);
 
     // Docs should include evidence/functions from the chosen run.
-    emit_slice_docs_command(&root).unwrap();
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
     let doc_body = std::fs::read_to_string(layout.slices_docs_dir.join("SliceOne.md")).unwrap();
     assert!(doc_body.contains("BinA")); // default binary printed
     assert!(doc_body.contains("NewerFunc")); // uses default binary (BinA) run by default
@@ -211,3 +301,746 @@ fn emit_slice_reports_and_graphs_use_db_analysis() {
     assert!(doc_body.contains("Evidence")); // evidence section populated
     assert!(doc_body.contains("Backend:** rizin"));
 }
+
+#[test]
+fn emit_slice_docs_links_functions_across_slices() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+
+    init_project_command(&root, Some("LinkProj".into())).unwrap();
+    init_slice_command(&root, "UI", None, Some("BinL".into())).unwrap();
+    init_slice_command(&root, "Net", None, Some("BinL".into())).unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let seed = |ritual: &str, analysis: AnalysisResult| {
+        let run = RitualRunRecord {
+            binary: "BinL".into(),
+            ritual: ritual.into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "validate-only".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        };
+        let run_id = db.insert_ritual_run(&run).expect("insert run");
+        db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+    };
+    let func = |address: u64, name: &str| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    seed(
+        "UI",
+        AnalysisResult {
+            functions: vec![func(0x1000, "ui_init"), func(0x1100, "ui_draw")],
+            call_edges: vec![
+                CallEdge {
+                    from: 0x1000,
+                    to: 0x1100,
+                    is_cross_slice: false,
+                    to_name: None,
+                    to_import: false,
+                },
+                CallEdge {
+                    from: 0x1000,
+                    to: 0x2000,
+                    is_cross_slice: true,
+                    to_name: None,
+                    to_import: false,
+                },
+            ],
+            basic_blocks: Vec::new(),
+            evidence: Vec::new(),
+            roots: vec!["ui_init".into()],
+            root_hits: Vec::new(),
+            sub_slices: Vec::new(),
+            backend_version: None,
+            backend_path: None,
+            xrefs: Vec::new(),
+            text_bytes: None,
+        },
+    );
+    seed(
+        "Net",
+        AnalysisResult {
+            functions: vec![func(0x2000, "send_packet")],
+            call_edges: Vec::new(),
+            basic_blocks: Vec::new(),
+            evidence: Vec::new(),
+            roots: vec!["send_packet".into()],
+            root_hits: Vec::new(),
+            sub_slices: Vec::new(),
+            backend_version: None,
+            backend_path: None,
+            xrefs: Vec::new(),
+            text_bytes: None,
+        },
+    );
+
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+
+    let ui_doc = std::fs::read_to_string(layout.slices_docs_dir.join("UI.md")).unwrap();
+    assert!(ui_doc.contains("## Contents\n"));
+    assert!(ui_doc.contains("- [Functions](#functions)\n  - [ui_init @ 0x1000](#fn-0x1000)"));
+    assert!(ui_doc.contains("- <a id=\"fn-0x1100\"></a>ui_draw @ 0x1100"));
+    assert!(
+        ui_doc.contains("  - Calls: [ui_draw](#fn-0x1100), [send_packet](Net.md#fn-0x2000) (Net)")
+    );
+    assert!(ui_doc.contains("  - Called by: [ui_init](#fn-0x1000)"));
+    assert!(!ui_doc.contains("## Backlinks"));
+
+    let net_doc = std::fs::read_to_string(layout.slices_docs_dir.join("Net.md")).unwrap();
+    assert!(net_doc.contains("- [Backlinks](#backlinks)"));
+    assert!(net_doc.contains("  - Called by: [ui_init](UI.md#fn-0x1000) (UI)"));
+    assert!(net_doc.contains(
+        "## Backlinks\n- [UI](UI.md): [ui_init](UI.md#fn-0x1000) -> [send_packet](#fn-0x2000)"
+    ));
+    assert!(!ui_doc.contains("```mermaid"), "graphs are opt-in");
+
+    emit_slice_docs_command(&root, 0.0, Some(2)).unwrap();
+    let ui_doc = std::fs::read_to_string(layout.slices_docs_dir.join("UI.md")).unwrap();
+    assert!(ui_doc.contains("- [Call graph](#call-graph)\n"), "{ui_doc}");
+    assert!(ui_doc.contains("## Call graph\n```mermaid\ngraph TD\n"), "{ui_doc}");
+    assert!(ui_doc.contains("overflow[\"+1 more functions\"]"), "{ui_doc}");
+}
+
+#[test]
+fn emit_slice_docs_and_reports_stream_evidence_across_pages() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+
+    init_project_command(&root, Some("BigProj".into())).unwrap();
+    init_slice_command(&root, "Big", None, Some("BinA".into())).unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "BinA".into(),
+            ritual: "Big".into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .expect("insert run");
+
+    // More rows than one DB page: strings inside `hot`, imports outside any function.
+    let page = ritual_core::db::ANALYSIS_PAGE_SIZE;
+    let strings = page + 7;
+    let unmapped = 20;
+    let mut evidence: Vec<EvidenceRecord> = (0..strings)
+        .map(|i| EvidenceRecord {
+            address: 0x1000 + (i as u64 % 0x100),
+            description: format!("string s{}", i),
+            kind: Some(EvidenceKind::String),
+            source: None,
+            run_id: None,
+        })
+        .collect();
+    evidence.extend((0..unmapped).map(|i| EvidenceRecord {
+        address: 0x9000 + i as u64,
+        description: format!("import i{}", i),
+        kind: Some(EvidenceKind::Import),
+        source: None,
+        run_id: None,
+    }));
+    let analysis = AnalysisResult {
+        functions: vec![
+            FunctionRecord {
+                address: 0x1000,
+                name: Some("hot".into()),
+                size: Some(0x100),
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            },
+            FunctionRecord {
+                address: 0x2000,
+                name: None,
+                size: Some(0x10),
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            },
+        ],
+        call_edges: vec![CallEdge {
+            from: 0x1000,
+            to: 0x2000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
+        }],
+        basic_blocks: Vec::new(),
+        evidence,
+        roots: vec!["hot".into()],
+        root_hits: vec![RootHit { root: "hot".into(), functions: vec![0x1000], error: None }],
+        backend_version: None,
+        backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+
+    emit_slice_docs_command(&root, 0.0, None).expect("emit docs");
+    let doc = std::fs::read_to_string(layout.slices_docs_dir.join("Big.md")).unwrap();
+    let total = strings + unmapped;
+    assert!(doc.contains(&format!(
+        "- Evidence: total={} strings={} imports={} calls=0 other=0",
+        total, strings, unmapped
+    )));
+    assert!(
+        doc.contains(&format!("hot @ 0x1000 (size=256, in-slice) — evidence: total={}", strings))
+    );
+    assert!(doc.contains(&format!("  - ... ({} more entries)", strings - 5)));
+    assert!(doc.contains(&format!("- ... ({} more strings)", strings - 15)));
+    assert!(doc.contains("### Unmapped evidence (no matching function)"));
+    assert!(doc.contains("- ... (5 more unmapped evidence (no matching function))"));
+
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .expect("emit reports");
+    let report: Value = serde_json::from_str(
+        &std::fs::read_to_string(layout.reports_dir.join("Big.json")).unwrap(),
+    )
+    .expect("report is valid JSON");
+    assert_eq!(report["evidence"].as_array().unwrap().len(), total);
+    assert_eq!(report["strings"].as_array().unwrap().len(), strings);
+    assert_eq!(report["imports"].as_array().unwrap().len(), unmapped);
+    assert_eq!(report["evidence_counts"]["total"], total);
+    assert_eq!(report["analysis_summary"]["evidence"]["strings"], strings);
+    let hot = &report["function_evidence"]["by_function"]["0x1000"];
+    assert_eq!(hot["evidence"].as_array().unwrap().len(), strings);
+    assert_eq!(hot["evidence_counts"]["strings"], strings);
+    assert!(report["function_evidence"]["by_function"].get("0x2000").is_none());
+    assert_eq!(report["function_evidence"]["unmapped"].as_array().unwrap().len(), unmapped);
+
+    // Graph ids match the in-memory computation over the full result.
+    let dot = std::fs::read_to_string(layout.graphs_dir.join("Big.dot")).unwrap();
+    let ids = ritual_core::analysis::graph::NodeIds::new(&analysis);
+    assert!(dot.contains(&format!("\"{}\"", ids.function(0x2000))));
+}
+
+#[test]
+fn annotated_names_and_notes_flow_into_slice_docs_and_reports() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+
+    init_project_command(&root, Some("AnnoProj".into())).unwrap();
+    init_slice_command(&root, "Net", None, Some("libNet.so".into())).unwrap();
+    let layout = ProjectLayout::new(&root);
+    let bin_path = layout.root.join("libNet.so");
+    std::fs::write(&bin_path, b"\x7fELF net").unwrap();
+    binary_slicer::commands::add_binary_command(
+        &root,
+        &bin_path.to_string_lossy(),
+        None,
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let hash = db.list_binaries().unwrap()[0].hash.clone();
+    let run = RitualRunRecord {
+        binary: "libNet.so".into(),
+        ritual: "Net".into(),
+        spec_hash: "sh".into(),
+        binary_hash: hash,
+        backend: "capstone".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id: Some(1),
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let run_id = db.insert_ritual_run(&run).expect("insert run");
+    let analysis = AnalysisResult {
+        functions: vec![
+            FunctionRecord {
+                address: 0x1234,
+                name: Some("sub_1234".into()),
+                size: Some(16),
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            },
+            FunctionRecord {
+                address: 0x2000,
+                name: None,
+                size: None,
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            },
+        ],
+        call_edges: vec![CallEdge {
+            from: 0x1234,
+            to: 0x2000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
+        }],
+        basic_blocks: Vec::new(),
+        evidence: Vec::new(),
+        roots: vec!["0x1234".into()],
+        root_hits: vec![RootHit { root: "0x1234".into(), functions: vec![0x1234], error: None }],
+        backend_version: None,
+        backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+
+    binary_slicer::commands::annotate_function_command(
+        &root,
+        "libNet.so",
+        "0x1234",
+        Some("parse_packet".into()),
+        Some("Decodes the framing header".into()),
+        false,
+    )
+    .unwrap();
+    // A later note-only update keeps the name.
+    binary_slicer::commands::annotate_function_command(
+        &root,
+        "libNet.so",
+        "0x2000",
+        None,
+        Some("checksum helper".into()),
+        false,
+    )
+    .unwrap();
+    assert!(binary_slicer::commands::annotate_function_command(
+        &root,
+        "libNet.so",
+        "0x2000",
+        None,
+        None,
+        false
+    )
+    .is_err());
+    binary_slicer::commands::annotate_run_command(&root, "libNet.so", "Net", "vtable at 0x4000")
+        .unwrap();
+    assert!(binary_slicer::commands::annotate_run_command(&root, "libNet.so", "Gfx", "x").is_err());
+    assert!(binary_slicer::commands::annotate_run_command(&root, "libNet.so", "Net", " ").is_err());
+
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+    let doc = std::fs::read_to_string(layout.slices_docs_dir.join("Net.md")).unwrap();
+    assert!(doc.contains("parse_packet @ 0x1234"), "{doc}");
+    assert!(!doc.contains("sub_1234"), "{doc}");
+    assert!(doc.contains("  - Note: Decodes the framing header"), "{doc}");
+    assert!(doc.contains("  - Note: checksum helper"), "{doc}");
+
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
+    let report: Value = serde_json::from_str(
+        &std::fs::read_to_string(layout.reports_dir.join("Net.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(report["functions"][0]["name"], "parse_packet");
+    assert!(report["functions"][1]["name"].is_null());
+    assert_eq!(report["annotations"].as_array().unwrap().len(), 2);
+    assert_eq!(report["annotations"][1]["note"], "checksum helper");
+    assert_eq!(report["run_notes"].as_array().unwrap().len(), 1);
+    assert_eq!(report["run_notes"][0]["note"], "vtable at 0x4000");
+    let html = std::fs::read_to_string(layout.reports_dir.join("Net.html")).unwrap();
+    assert!(html.contains("vtable at 0x4000"), "{html}");
+
+    binary_slicer::commands::annotate_function_command(
+        &root,
+        "libNet.so",
+        "0x1234",
+        None,
+        None,
+        true,
+    )
+    .unwrap();
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
+    let report: Value = serde_json::from_str(
+        &std::fs::read_to_string(layout.reports_dir.join("Net.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(report["functions"][0]["name"], "sub_1234");
+}
+
+#[test]
+fn manual_slice_membership_merges_into_docs_and_reports() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+
+    init_project_command(&root, Some("MemberProj".into())).unwrap();
+    init_slice_command(&root, "Net", None, Some("libNet.so".into())).unwrap();
+    let layout = ProjectLayout::new(&root);
+    let bin_path = layout.root.join("libNet.so");
+    std::fs::write(&bin_path, b"\x7fELF members").unwrap();
+    binary_slicer::commands::add_binary_command(
+        &root,
+        &bin_path.to_string_lossy(),
+        None,
+        None,
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let hash = db.list_binaries().unwrap()[0].hash.clone().unwrap();
+    let run = RitualRunRecord {
+        binary: "libNet.so".into(),
+        ritual: "Net".into(),
+        spec_hash: "sh".into(),
+        binary_hash: Some(hash.clone()),
+        backend: "capstone".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id: Some(1),
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let run_id = db.insert_ritual_run(&run).expect("insert run");
+    let func = |address: u64, in_slice: bool| FunctionRecord {
+        address,
+        name: Some(format!("fn_{:x}", address)),
+        size: Some(8),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let analysis = AnalysisResult {
+        functions: vec![func(0x1000, true), func(0x2000, true), func(0x3000, false)],
+        call_edges: Vec::new(),
+        basic_blocks: Vec::new(),
+        evidence: Vec::new(),
+        roots: vec!["fn_1000".into()],
+        root_hits: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).expect("insert analysis");
+
+    slice_add_function_command(&root, "Net", "libNet.so", "0x3000").unwrap();
+    slice_add_function_command(&root, "Net", "libNet.so", "0x4000").unwrap();
+    slice_remove_function_command(&root, "Net", "libNet.so", "0x2000", false).unwrap();
+    assert!(slice_add_function_command(&root, "Missing", "libNet.so", "0x1000").is_err());
+    assert!(slice_add_function_command(&root, "Net", "libNet.so", "nope").is_err());
+
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
+    let report: Value = serde_json::from_str(
+        &std::fs::read_to_string(layout.reports_dir.join("Net.json")).unwrap(),
+    )
+    .unwrap();
+    let in_slice = |addr: u64| {
+        report["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["address"] == addr)
+            .map(|f| f["in_slice"].as_bool().unwrap())
+    };
+    assert_eq!(in_slice(0x1000), Some(true));
+    assert_eq!(in_slice(0x2000), Some(false));
+    assert_eq!(in_slice(0x3000), Some(true));
+    assert_eq!(in_slice(0x4000), Some(true), "manual additions unknown to analysis are listed");
+    assert_eq!(report["manual_members"].as_array().unwrap().len(), 3);
+
+    // Emission records the analysis-derived members alongside the manual ones.
+    let members = db.list_slice_members("Net", Some(&hash)).unwrap();
+    let analysis_rows: Vec<u64> = members
+        .iter()
+        .filter(|m| m.source == ritual_core::db::MemberSource::Analysis)
+        .map(|m| m.address)
+        .collect();
+    assert_eq!(analysis_rows, vec![0x1000, 0x2000]);
+
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+    let doc = std::fs::read_to_string(layout.slices_docs_dir.join("Net.md")).unwrap();
+    assert!(doc.contains("fn_3000 @ 0x3000 (size=8, in-slice, manual)"), "{doc}");
+    assert!(doc.contains("fn_2000 @ 0x2000 (size=8, manually excluded)"), "{doc}");
+    assert!(doc.contains("Functions: 4 (in-slice=3"), "{doc}");
+
+    // Resetting the exclusion restores analysis-derived membership.
+    slice_remove_function_command(&root, "Net", "libNet.so", "0x2000", true).unwrap();
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
+    let report: Value = serde_json::from_str(
+        &std::fs::read_to_string(layout.reports_dir.join("Net.json")).unwrap(),
+    )
+    .unwrap();
+    let excluded = report["functions"].as_array().unwrap().iter().find(|f| f["address"] == 0x2000);
+    assert_eq!(excluded.unwrap()["in_slice"], true);
+}
+
+#[test]
+fn emit_slice_reports_classify_cross_slice_calls_between_slices_of_a_binary() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("CrossProj".into())).unwrap();
+    init_slice_command(&root, "Net", None, Some("BinA".into())).unwrap();
+    init_slice_command(&root, "Logging", None, Some("BinA".into())).unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let func = |address: u64, name: &str, in_slice: bool| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(16),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    // Both runs see the whole binary; each carves its own slice.
+    for (ritual, net, logging) in [("Net", true, false), ("Logging", false, true)] {
+        let run_id = db
+            .insert_ritual_run(&RitualRunRecord {
+                binary: "BinA".into(),
+                ritual: ritual.into(),
+                spec_hash: "sh".into(),
+                binary_hash: None,
+                backend: "rizin".into(),
+                backend_version: None,
+                backend_path: None,
+                status: RitualRunStatus::Succeeded,
+                started_at: "t0".into(),
+                finished_at: "t1".into(),
+                binary_id: None,
+                superseded_by: None,
+                deterministic: false,
+                spec_path: None,
+                analysis_options: None,
+                usage: None,
+            })
+            .unwrap();
+        let analysis = AnalysisResult {
+            functions: vec![
+                func(0x1000, "net_send", net),
+                func(0x1100, "net_parse", net),
+                func(0x2000, "log_write", logging),
+            ],
+            call_edges: vec![
+                CallEdge {
+                    from: 0x1000,
+                    to: 0x1100,
+                    is_cross_slice: false,
+                    to_name: None,
+                    to_import: false,
+                },
+                CallEdge {
+                    from: 0x1000,
+                    to: 0x2000,
+                    is_cross_slice: false,
+                    to_name: None,
+                    to_import: false,
+                },
+            ],
+            basic_blocks: Vec::new(),
+            evidence: Vec::new(),
+            roots: Vec::new(),
+            root_hits: Vec::new(),
+            backend_version: None,
+            backend_path: None,
+            sub_slices: Vec::new(),
+            xrefs: Vec::new(),
+            text_bytes: None,
+        };
+        db.insert_analysis_result(run_id, &analysis).unwrap();
+    }
+
+    emit_slice_reports_command(&root, None, SliceReportFormat::Json, &DotOptions::default())
+        .unwrap();
+
+    let read = |name: &str| -> Value {
+        let path = layout.reports_dir.join(format!("{name}.json"));
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    };
+    let net = read("Net");
+    assert_eq!(net["analysis_summary"]["cross_slice_calls"], 1);
+    assert_eq!(net["analysis_summary"]["boundary_functions"], 1);
+    let edges = net["call_edges"].as_array().unwrap();
+    let cross = edges.iter().find(|e| e["to"] == 0x2000).unwrap();
+    assert_eq!(cross["is_cross_slice"], true);
+    let boundary = net["functions"].as_array().unwrap().iter().find(|f| f["address"] == 0x1000);
+    assert_eq!(boundary.unwrap()["is_boundary"], true);
+
+    // The callee side sees the same edge from the other direction.
+    let logging = read("Logging");
+    assert_eq!(logging["analysis_summary"]["cross_slice_calls"], 1);
+    assert_eq!(logging["analysis_summary"]["boundary_functions"], 1);
+
+    let dot = std::fs::read_to_string(layout.graphs_dir.join("Net.dot")).unwrap();
+    assert!(dot.contains("cross_slice=true"), "{dot}");
+    assert!(dot.contains("boundary=true"), "{dot}");
+
+    // Classification happens at emit time; the persisted run is untouched.
+    let (_, stored) = db.load_analysis_skeleton("BinA", "Net").unwrap().unwrap();
+    assert!(stored.call_edges.iter().all(|e| !e.is_cross_slice));
+}
+
+#[test]
+fn emit_slice_docs_ranks_evidence_and_filters_by_min_score() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("ScoreProj".into())).unwrap();
+    init_slice_command(&root, "Scored", None, Some("BinA".into())).unwrap();
+
+    let layout = ProjectLayout::new(&root);
+    let db = ProjectDb::open(&layout.db_path).expect("open db");
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "BinA".into(),
+            ritual: "Scored".into(),
+            spec_hash: "sh".into(),
+            binary_hash: None,
+            backend: "rizin".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t0".into(),
+            finished_at: "t1".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let evidence = |address: u64, description: &str, kind: Option<EvidenceKind>| EvidenceRecord {
+        address,
+        description: description.into(),
+        kind,
+        source: None,
+        run_id: None,
+    };
+    let analysis = AnalysisResult {
+        functions: vec![FunctionRecord {
+            address: 0x1000,
+            name: Some("banner".into()),
+            size: Some(0x40),
+            in_slice: true,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: Vec::new(),
+        basic_blocks: Vec::new(),
+        evidence: vec![
+            evidence(0x1000, "reg operand 35", None),
+            evidence(0x1002, "reg operand 36", None),
+            evidence(0x1004, "string: unique banner", Some(EvidenceKind::String)),
+            evidence(0x1008, "call -> sym.imp.printf", Some(EvidenceKind::Call)),
+            evidence(0x5000, "import: printf", Some(EvidenceKind::Import)),
+        ],
+        roots: vec!["banner".into()],
+        root_hits: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).unwrap();
+    let doc_path = layout.slices_docs_dir.join("Scored.md");
+
+    emit_slice_docs_command(&root, 0.0, None).unwrap();
+    let doc = std::fs::read_to_string(&doc_path).unwrap();
+    let top = doc.split("## Top evidence\n").nth(1).expect("top evidence section");
+    let lines: Vec<&str> = top.lines().take(3).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "- [4.00] 0x1004: string: unique banner",
+            "- [3.00] 0x1008: call -> sym.imp.printf",
+            "- [3.00] 0x5000: import: printf",
+        ],
+        "{doc}"
+    );
+    assert!(
+        doc.contains("  - Top evidence:\n    - [4.00] 0x1004: string: unique banner\n"),
+        "{doc}"
+    );
+    assert!(doc.contains("0x1000: reg operand 35"), "{doc}");
+
+    // Low-scoring operand records are still counted but no longer listed.
+    emit_slice_docs_command(&root, 1.0, None).unwrap();
+    let doc = std::fs::read_to_string(&doc_path).unwrap();
+    assert!(!doc.contains("reg operand"), "{doc}");
+    assert!(doc.contains("- Summary: total=5 strings=1 imports=1 calls=1 other=2"), "{doc}");
+    assert!(doc.contains("[4.00] 0x1004: string: unique banner"), "{doc}");
+}
@@ -0,0 +1,155 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use std::fs;
+use tempfile::tempdir;
+
+/// A project with binary `Game` and slices `Telemetry` (default binary `Game`) and `Loose`
+/// (no default binary).
+fn slice_project() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Roots".into())).unwrap();
+    let bin_path = temp.path().join("game.bin");
+    fs::write(&bin_path, b"game").unwrap();
+    add_binary_command(
+        &root,
+        bin_path.to_str().unwrap(),
+        Some("Game".into()),
+        Some("x86_64".into()),
+        None,
+        false,
+        None,
+    )
+    .unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Telemetry", "--binary", "Game"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["init-slice", "--root", &root, "--name", "Loose"])
+        .assert()
+        .success();
+    (temp, root)
+}
+
+fn slice_roots(root: &str, slice: &str) -> serde_json::Value {
+    let out = cargo_bin_cmd!("binary-slicer")
+        .args(["list-slice-roots", "--root", root, "--slice", slice, "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+#[test]
+fn slice_roots_are_added_listed_and_removed() {
+    let (temp, root) = slice_project();
+    for slice_root in ["telemetry_init", "regex:^tm_.*"] {
+        cargo_bin_cmd!("binary-slicer")
+            .args(["slice-add-root", "--root", &root, "--slice", "Telemetry"])
+            .args(["--slice-root", slice_root])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "Added root {slice_root} to slice Telemetry"
+            )));
+    }
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-add-root", "--root", &root, "--slice", "Telemetry"])
+        .args(["--slice-root", "telemetry_init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already has root telemetry_init"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-add-root", "--root", &root, "--slice", "Telemetry"])
+        .args(["--slice-root", "regex:("])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid slice root 'regex:('"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-add-root", "--root", &root, "--slice", "Missing", "--slice-root", "x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Missing' not found"));
+    assert_eq!(
+        slice_roots(&root, "Telemetry"),
+        serde_json::json!(["telemetry_init", "regex:^tm_.*"])
+    );
+
+    // The slice doc's analyst roots section follows, so sync-slice-docs keeps the roots.
+    let doc_path = ProjectLayout::new(temp.path()).slices_docs_dir.join("Telemetry.md");
+    let doc = fs::read_to_string(&doc_path).unwrap();
+    assert!(
+        doc.contains("<!-- analyst:begin roots -->\n- telemetry_init\n- regex:^tm_.*\n"),
+        "{doc}"
+    );
+    cargo_bin_cmd!("binary-slicer")
+        .args(["sync-slice-docs", "--root", &root, "--slice", "Telemetry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unchanged Telemetry"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-remove-root", "--root", &root, "--slice", "Telemetry"])
+        .args(["--slice-root", "telemetry_init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed root telemetry_init from slice Telemetry"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["slice-remove-root", "--root", &root, "--slice", "Telemetry"])
+        .args(["--slice-root", "telemetry_init"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Telemetry' has no root 'telemetry_init'"));
+    assert_eq!(slice_roots(&root, "Telemetry"), serde_json::json!(["regex:^tm_.*"]));
+    assert!(!fs::read_to_string(&doc_path).unwrap().contains("- telemetry_init"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-slice-roots", "--root", &root, "--slice", "Loose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Roots of slice Loose:\n(none)"));
+}
+
+#[test]
+fn run_ritual_synthesizes_a_spec_from_slice_roots() {
+    let (temp, root) = slice_project();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--slice", "Telemetry"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Telemetry' has no roots"));
+    let db = ProjectDb::open(&ProjectLayout::new(temp.path()).db_path).unwrap();
+    db.add_slice_root("Telemetry", "telemetry_init").unwrap();
+    db.add_slice_root("Loose", "loose_init").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--slice", "Loose"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Loose' has no default binary"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--slice", "Telemetry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ran ritual (stub): Telemetry"))
+        .stdout(predicate::str::contains("Binary: Game"))
+        .stdout(predicate::str::contains("telemetry_init"));
+    let layout = ProjectLayout::new(&root);
+    let normalized =
+        fs::read_to_string(layout.binary_output_root("Game").join("Telemetry").join("spec.yaml"))
+            .unwrap();
+    assert!(normalized.contains("name: Telemetry"), "{normalized}");
+    assert!(normalized.contains("telemetry_init"), "{normalized}");
+    let runs = db.list_ritual_runs(Some("Game")).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].ritual, "Telemetry");
+    assert_eq!(runs[0].spec_path, None);
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--slice", "Telemetry", "--spec-name", "x"])
+        .assert()
+        .failure();
+}
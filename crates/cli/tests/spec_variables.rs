@@ -0,0 +1,134 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{add_binary_command, init_project_command};
+use predicates::prelude::*;
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use std::fs;
+use tempfile::tempdir;
+
+/// A project with binary `Game-1.2` (arch x86_64), config default `depth=2`, and a spec
+/// under `rituals/` templated on `version`, `depth`, and `binary.arch`.
+fn templated_project() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Vars".into())).unwrap();
+    for version in ["1.2", "1.3"] {
+        let bin_path = temp.path().join(format!("game-{version}.bin"));
+        fs::write(&bin_path, version.as_bytes()).unwrap();
+        add_binary_command(
+            &root,
+            bin_path.to_str().unwrap(),
+            Some(format!("Game-{version}")),
+            Some("x86_64".into()),
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+    cargo_bin_cmd!("binary-slicer")
+        .args(["config", "set", "variables.depth", "2", "--root", &root])
+        .assert()
+        .success();
+    fs::write(
+        temp.path().join("rituals").join("net.yaml"),
+        "# Net slice of Game ${version} ($${literal} stays)\n\
+         name: Net-${version}\n\
+         binary: Game-${version}\n\
+         roots: [\"net_${binary.arch}\"]\n\
+         max_depth: ${depth}\n\
+         backend: validate-only\n",
+    )
+    .unwrap();
+    (temp, root)
+}
+
+#[test]
+fn run_ritual_substitutes_set_config_and_builtin_variables() {
+    let (temp, root) = templated_project();
+    let spec = temp.path().join("rituals").join("net.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .args(["--set", "version=1.3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ran ritual (stub): Net-1.3"))
+        .stdout(predicate::str::contains("Binary: Game-1.3"))
+        .stdout(predicate::str::contains("net_x86_64"));
+
+    // The normalized spec records the substituted values.
+    let layout = ProjectLayout::new(&root);
+    let normalized =
+        fs::read_to_string(layout.binary_output_root("Game-1.3").join("Net-1.3").join("spec.yaml"))
+            .unwrap();
+    assert!(normalized.contains("name: Net-1.3"), "{normalized}");
+    assert!(normalized.contains("max_depth: 2"), "{normalized}");
+    assert!(normalized.contains("net_x86_64"), "{normalized}");
+    assert!(!normalized.contains("${"), "{normalized}");
+
+    // --set overrides the config default; different values hash differently.
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .args(["--set", "version=1.2", "--set", "depth=5"])
+        .assert()
+        .success();
+    let normalized =
+        fs::read_to_string(layout.binary_output_root("Game-1.2").join("Net-1.2").join("spec.yaml"))
+            .unwrap();
+    assert!(normalized.contains("max_depth: 5"), "{normalized}");
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let hashes: Vec<String> =
+        db.list_ritual_runs(None).unwrap().into_iter().map(|run| run.spec_hash).collect();
+    assert_eq!(hashes.len(), 2);
+    assert_ne!(hashes[0], hashes[1]);
+}
+
+#[test]
+fn undefined_and_malformed_variables_are_errors() {
+    let (temp, root) = templated_project();
+    let spec = temp.path().join("rituals").join("net.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Undefined spec variable(s) 'version'"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .args(["--set", "binary.arch=arm"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'binary.arch' is a built-in variable"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["batch-run", "--root", &root, "--set", "version"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--set expects NAME=VALUE"));
+}
+
+#[test]
+fn validate_spec_and_batch_run_accept_variables() {
+    let (temp, root) = templated_project();
+    let spec = temp.path().join("rituals").join("net.yaml");
+    cargo_bin_cmd!("binary-slicer")
+        .args(["validate-spec", "--root", &root, "--file", spec.to_str().unwrap()])
+        .args(["--set", "version=1.2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ritual: Net-1.2"))
+        .stdout(predicate::str::contains("Variables: binary.arch=x86_64, depth=2, version=1.2"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["validate-spec", "--root", &root, "--file", spec.to_str().unwrap(), "--json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Undefined spec variable(s) 'version'"));
+
+    let output = cargo_bin_cmd!("binary-slicer")
+        .args(["batch-run", "--root", &root, "--json", "--set", "version=1.2"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let summary: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(summary["runs"][0]["ritual"], "Net-1.2");
+    assert_eq!(summary["runs"][0]["binary"], "Game-1.2");
+}
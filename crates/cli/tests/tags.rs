@@ -0,0 +1,145 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use binary_slicer::commands::{
+    add_binary_command, init_project_command, init_slice_command, tag_add_command, TagTarget,
+};
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+/// A project with slice `Net`, binaries `Game`/`Tool`, and one validate-only run `Boot` of `Game`.
+fn tagged_project() -> (tempfile::TempDir, String) {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    init_project_command(&root, Some("Tags".into())).unwrap();
+    init_slice_command(&root, "Net", None, None).unwrap();
+    for name in ["Game", "Tool"] {
+        let bin_path = temp.path().join(format!("{}.bin", name));
+        fs::write(&bin_path, name.as_bytes()).unwrap();
+        add_binary_command(
+            &root,
+            bin_path.to_str().unwrap(),
+            Some(name.into()),
+            None,
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+    }
+    let spec = temp.path().join("boot.yaml");
+    fs::write(&spec, "name: Boot\nbinary: Game\nroots: [main]\nbackend: validate-only\n").unwrap();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["run-ritual", "--root", &root, "--file", spec.to_str().unwrap()])
+        .assert()
+        .success();
+    (temp, root)
+}
+
+#[test]
+fn tags_filter_slice_binary_and_run_listings() {
+    let (_temp, root) = tagged_project();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "priority", "--root", &root, "--slice", "Net"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tagged slice Net with 'priority'"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "obfuscated", "--root", &root, "--binary", "Game"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "baseline", "--root", &root, "--binary", "Game", "--ritual", "Boot"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tagged run Game/Boot with 'baseline'"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "baseline", "--root", &root, "--binary", "Game", "--ritual", "Boot"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already tagged"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-slices", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^Net\s.*\spriority\s").unwrap());
+
+    let out = cargo_bin_cmd!("binary-slicer")
+        .args(["list-binaries", "--root", &root, "--tag", "obfuscated", "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let binaries: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let binaries = binaries.as_array().unwrap();
+    assert_eq!(binaries.len(), 1, "Tool is not tagged");
+    assert_eq!(binaries[0]["name"], "Game");
+    assert_eq!(binaries[0]["tags"], serde_json::json!(["obfuscated"]));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-runs", "--root", &root, "--tag", "baseline"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^\d+\s+Game\s+Boot\s.*\sbaseline\s").unwrap());
+    cargo_bin_cmd!("binary-slicer")
+        .args(["list-ritual-runs", "--root", &root, "--tag", "priority"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Boot").not());
+
+    let out = cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "list", "--root", &root, "--kind", "run", "--json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let taggings: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let taggings = taggings.as_array().unwrap();
+    assert_eq!(taggings.len(), 1);
+    assert_eq!(taggings[0]["tag"], "baseline");
+    assert_eq!(taggings[0]["kind"], "run");
+    assert_eq!(taggings[0]["name"], "Game");
+    assert_eq!(taggings[0]["ritual"], "Boot");
+}
+
+#[test]
+fn tag_remove_and_bad_targets_fail_cleanly() {
+    let (_temp, root) = tagged_project();
+    tag_add_command(&root, &TagTarget::Slice("Net".into()), "priority").unwrap();
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "remove", "priority", "--root", &root, "--slice", "Net"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed tag 'priority' from slice Net"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "remove", "priority", "--root", &root, "--slice", "Net"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("slice Net is not tagged 'priority'"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "list", "--root", &root])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(none)"));
+
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "x", "--root", &root, "--slice", "Missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Slice 'Missing' not found"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "x", "--root", &root, "--binary", "Tool", "--ritual", "Boot"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ritual run Tool/Boot"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "add", "two words", "--root", &root, "--slice", "Net"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("without spaces or commas"));
+    cargo_bin_cmd!("binary-slicer")
+        .args(["tag", "list", "--root", &root, "--kind", "function"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown tag kind 'function'"));
+    assert!(TagTarget::from_args(None, None, Some("Boot".into())).is_err());
+}
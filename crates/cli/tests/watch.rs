@@ -0,0 +1,101 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use binary_slicer::commands::{
+    add_binary_command, affected_targets, init_project_command, rerun_watch_target,
+    run_ritual_command, watch_targets, RunFlags,
+};
+use ritual_core::db::{ProjectDb, ProjectLayout};
+use tempfile::tempdir;
+
+fn setup_project(root: &str) -> ProjectLayout {
+    init_project_command(root, Some("WatchProj".into())).unwrap();
+    let layout = ProjectLayout::new(root);
+    let bin_path = layout.root.join("libWatch.so");
+    fs::write(&bin_path, b"\x7fELF v1").unwrap();
+    add_binary_command(root, &bin_path.to_string_lossy(), None, None, None, false, None).unwrap();
+    fs::write(
+        layout.rituals_dir.join("watch.yaml"),
+        "name: WatchRun\nbinary: libWatch.so\nroots: [entry]\nbackend: validate-only\n",
+    )
+    .unwrap();
+    layout
+}
+
+#[test]
+fn rerun_links_superseded_run_and_refreshes_binary_hash() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    let layout = setup_project(&root);
+    let spec_path = layout.rituals_dir.join("watch.yaml");
+    run_ritual_command(&root, &spec_path.to_string_lossy(), None, RunFlags::default(), &[], &[])
+        .unwrap();
+
+    let targets = watch_targets(&layout).unwrap();
+    assert_eq!(targets.len(), 1);
+    assert_eq!(targets[0].ritual, "WatchRun");
+    let bin_path = targets[0].binary_path.clone();
+    assert_eq!(affected_targets(&targets, std::slice::from_ref(&bin_path)).len(), 1);
+    assert_eq!(affected_targets(&targets, std::slice::from_ref(&spec_path)).len(), 1);
+    assert!(affected_targets(&targets, &[layout.root.join("other.so")]).is_empty());
+
+    // Without --force the existing output blocks the re-run.
+    fs::write(&bin_path, b"\x7fELF v2").unwrap();
+    assert!(rerun_watch_target(&root, &targets[0], None, false).is_err());
+
+    let rerun = rerun_watch_target(&root, &targets[0], None, true).unwrap();
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let first = rerun.superseded.expect("previous run linked");
+    assert_eq!(rerun.run_id, db.latest_run_id("libWatch.so", "WatchRun").unwrap());
+    let runs = db.list_ritual_runs(Some("libWatch.so")).unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].superseded_by, rerun.run_id);
+    assert_eq!(runs[1].superseded_by, None);
+    assert_ne!(runs[0].binary_hash, runs[1].binary_hash);
+    assert_eq!(runs[1].binary_hash, db.list_binaries().unwrap()[0].hash);
+    assert!(first < rerun.run_id.unwrap());
+}
+
+#[test]
+fn watch_command_reruns_when_spec_changes() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().to_string_lossy().to_string();
+    let layout = setup_project(&root);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_binary-slicer"))
+        .args(["watch", "--root", &root, "--force", "--debounce-ms", "100", "--max-cycles", "1"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let _ = tx.send(line);
+        }
+    });
+
+    let banner = rx.recv_timeout(Duration::from_secs(20)).expect("watch banner");
+    assert!(banner.starts_with("Watching 1 ritual spec(s)"), "{banner}");
+    fs::write(
+        layout.rituals_dir.join("watch.yaml"),
+        "name: WatchRun\nbinary: libWatch.so\nroots: [entry, other]\nbackend: validate-only\n",
+    )
+    .unwrap();
+
+    let mut lines = Vec::new();
+    while let Ok(line) = rx.recv_timeout(Duration::from_secs(20)) {
+        lines.push(line);
+    }
+    let status = child.wait().unwrap();
+    assert!(status.success());
+    assert!(lines.iter().any(|l| l.contains("re-running WatchRun for libWatch.so")), "{lines:?}");
+
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    let runs = db.list_ritual_runs(Some("libWatch.so")).unwrap();
+    assert_eq!(runs.len(), 1);
+}
@@ -0,0 +1,162 @@
+//! Regression gating of ritual runs against a baseline run (`set-baseline`).
+//!
+//! A run is compared with its ritual's baseline on headline counts (in-slice functions,
+//! discovered functions, call edges) and on its roots. A count that drops by more than the
+//! configured share of the baseline's, or a root the baseline resolved that now matches no
+//! function, is a regression; limits come from [`RegressionConfig`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::{RegressionConfig, RunBaseline, RunCounts};
+use crate::services::analysis::RootHit;
+
+/// What a run is compared on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    pub counts: RunCounts,
+    /// Roots that matched no function, sorted.
+    pub unresolved_roots: Vec<String>,
+}
+
+impl RunSnapshot {
+    /// Snapshot of a persisted run (see `ProjectDb::run_counts` and `ProjectDb::run_root_hits`).
+    pub fn new(counts: RunCounts, root_hits: &[RootHit]) -> Self {
+        let mut unresolved_roots: Vec<String> = root_hits
+            .iter()
+            .filter(|hit| hit.functions.is_empty())
+            .map(|hit| hit.root.clone())
+            .collect();
+        unresolved_roots.sort();
+        unresolved_roots.dedup();
+        Self { counts, unresolved_roots }
+    }
+}
+
+/// One count of the run next to the baseline's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricChange {
+    /// `in_slice_functions`, `functions`, or `call_edges`.
+    pub metric: String,
+    pub baseline: usize,
+    pub current: usize,
+    /// Largest allowed drop in percent; `None` when the count is reported but not checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_drop: Option<f64>,
+    pub regressed: bool,
+}
+
+impl MetricChange {
+    /// Change relative to the baseline in percent (negative for a drop); `None` when the
+    /// baseline count is zero.
+    pub fn change_percent(&self) -> Option<f64> {
+        (self.baseline > 0)
+            .then(|| (self.current as f64 - self.baseline as f64) / self.baseline as f64 * 100.0)
+    }
+
+    fn label(&self) -> &str {
+        match self.metric.as_str() {
+            "in_slice_functions" => "in-slice functions",
+            "call_edges" => "call edges",
+            other => other,
+        }
+    }
+}
+
+/// A run compared with its ritual's baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineComparison {
+    pub baseline: RunBaseline,
+    pub metrics: Vec<MetricChange>,
+    /// Roots the baseline resolved that the run did not.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lost_roots: Vec<String>,
+    /// One line per regression that fails the run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regressions: Vec<String>,
+}
+
+impl BaselineComparison {
+    /// Compare `current` with the `before` snapshot of the `baseline` run.
+    pub fn new(
+        baseline: RunBaseline,
+        before: &RunSnapshot,
+        current: &RunSnapshot,
+        config: &RegressionConfig,
+    ) -> Self {
+        let in_slice_limit =
+            config.max_in_slice_drop.unwrap_or(RegressionConfig::DEFAULT_MAX_IN_SLICE_DROP);
+        let metrics = vec![
+            metric(
+                "in_slice_functions",
+                before.counts.in_slice_functions,
+                current.counts.in_slice_functions,
+                Some(in_slice_limit),
+            ),
+            metric(
+                "functions",
+                before.counts.functions,
+                current.counts.functions,
+                config.max_function_drop,
+            ),
+            metric(
+                "call_edges",
+                before.counts.call_edges,
+                current.counts.call_edges,
+                config.max_call_edge_drop,
+            ),
+        ];
+        let mut regressions: Vec<String> = metrics
+            .iter()
+            .filter(|m| m.regressed)
+            .map(|m| {
+                format!(
+                    "{} dropped {:.1}% ({} -> {}; limit {}%)",
+                    m.label(),
+                    -m.change_percent().unwrap_or_default(),
+                    m.baseline,
+                    m.current,
+                    m.max_drop.unwrap_or_default()
+                )
+            })
+            .collect();
+        let lost_roots: Vec<String> = current
+            .unresolved_roots
+            .iter()
+            .filter(|root| !before.unresolved_roots.contains(root))
+            .cloned()
+            .collect();
+        if config.unresolved_roots.unwrap_or(true) && !lost_roots.is_empty() {
+            regressions.push(format!("roots no longer resolved: {}", lost_roots.join(", ")));
+        }
+        Self { baseline, metrics, lost_roots, regressions }
+    }
+
+    /// Whether the run has no regression.
+    pub fn is_ok(&self) -> bool {
+        self.regressions.is_empty()
+    }
+
+    /// Counts side by side, e.g. `in-slice functions 40 -> 38 (-5.0%), functions 120 -> 120`.
+    pub fn describe(&self) -> String {
+        self.metrics
+            .iter()
+            .map(|m| match m.change_percent() {
+                Some(change) if m.current != m.baseline => {
+                    format!("{} {} -> {} ({:+.1}%)", m.label(), m.baseline, m.current, change)
+                }
+                _ => format!("{} {} -> {}", m.label(), m.baseline, m.current),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn metric(name: &str, baseline: usize, current: usize, max_drop: Option<f64>) -> MetricChange {
+    let mut change =
+        MetricChange { metric: name.to_string(), baseline, current, max_drop, regressed: false };
+    change.regressed = match (max_drop, change.change_percent()) {
+        (Some(limit), Some(percent)) => -percent > limit,
+        _ => false,
+    };
+    change
+}
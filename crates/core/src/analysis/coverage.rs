@@ -0,0 +1,125 @@
+//! How much of a binary's executable code a run's slice explains.
+//!
+//! Function sizes are merged as address ranges so overlapping records (thunks, fat-binary
+//! duplicates) are not counted twice. Instruction counts come from basic blocks that start
+//! inside the ranges and are only reported when the backend recorded them for every block.
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::analysis::{AnalysisResult, FunctionRecord};
+
+/// Slice coverage of one run, in bytes of code (and instructions when known).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SliceCoverage {
+    /// Executable bytes in the binary; `None` when the backend did not report it.
+    pub text_bytes: Option<u64>,
+    /// Bytes covered by any discovered function.
+    pub function_bytes: u64,
+    /// Bytes covered by in-slice functions.
+    pub slice_bytes: u64,
+    pub slice_functions: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slice_insns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_insns: Option<u64>,
+}
+
+impl SliceCoverage {
+    /// Fraction of the executable bytes inside the slice, from 0.0 to 1.0.
+    pub fn slice_ratio(&self) -> Option<f64> {
+        ratio(self.slice_bytes, self.text_bytes?)
+    }
+
+    /// Fraction of the executable bytes inside any discovered function.
+    pub fn function_ratio(&self) -> Option<f64> {
+        ratio(self.function_bytes, self.text_bytes?)
+    }
+
+    /// Executable bytes no discovered function accounts for.
+    pub fn unexplored_bytes(&self) -> Option<u64> {
+        Some(self.text_bytes?.saturating_sub(self.function_bytes))
+    }
+
+    /// One-line description, e.g. `slice 12.5% of text (512 of 4096 bytes, 3 function(s)); ...`.
+    pub fn describe(&self) -> String {
+        let insns = match (self.slice_insns, self.total_insns) {
+            (Some(slice), Some(total)) => format!(", {} of {} insns", slice, total),
+            _ => String::new(),
+        };
+        match (self.text_bytes, self.slice_ratio()) {
+            (Some(text), Some(r)) => format!(
+                "slice {:.1}% of text ({} of {} bytes, {} function(s){}); {} bytes outside any function",
+                r * 100.0,
+                self.slice_bytes,
+                text,
+                self.slice_functions,
+                insns,
+                self.unexplored_bytes().unwrap_or(0)
+            ),
+            _ => format!(
+                "slice {} bytes ({} function(s){}); text size unknown",
+                self.slice_bytes, self.slice_functions, insns
+            ),
+        }
+    }
+}
+
+fn ratio(part: u64, whole: u64) -> Option<f64> {
+    if whole == 0 {
+        return None;
+    }
+    Some((part as f64 / whole as f64).min(1.0))
+}
+
+/// Compute the slice coverage of an analysis result.
+pub fn slice_coverage(result: &AnalysisResult) -> SliceCoverage {
+    let all = ranges(result.functions.iter());
+    let slice = ranges(result.functions.iter().filter(|f| f.in_slice));
+    let insns = |ranges: &[(u64, u64)]| -> Option<u64> {
+        let mut total = 0u64;
+        let mut any = false;
+        for block in &result.basic_blocks {
+            if contains(ranges, block.start) {
+                total += u64::from(block.insn_count?);
+                any = true;
+            }
+        }
+        any.then_some(total)
+    };
+    SliceCoverage {
+        text_bytes: result.text_bytes,
+        function_bytes: span(&all),
+        slice_bytes: span(&slice),
+        slice_functions: result.functions.iter().filter(|f| f.in_slice).count(),
+        slice_insns: insns(&slice),
+        total_insns: insns(&all),
+    }
+}
+
+/// Sorted, merged `[start, end)` ranges of sized functions.
+fn ranges<'a>(functions: impl Iterator<Item = &'a FunctionRecord>) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = functions
+        .filter_map(|f| {
+            let size = u64::from(f.size.filter(|s| *s > 0)?);
+            Some((f.address, f.address.saturating_add(size)))
+        })
+        .collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn span(ranges: &[(u64, u64)]) -> u64 {
+    ranges.iter().map(|(start, end)| end - start).sum()
+}
+
+fn contains(ranges: &[(u64, u64)], addr: u64) -> bool {
+    let idx = ranges.partition_point(|(start, _)| *start <= addr);
+    idx > 0 && addr < ranges[idx - 1].1
+}
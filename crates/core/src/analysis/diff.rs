@@ -0,0 +1,175 @@
+//! Structural diff between two analysis results (e.g. the same ritual run against two builds).
+//!
+//! Functions are matched by address first, then by name for functions whose address moved
+//! between builds. Call edges are compared after mapping moved functions back onto the base
+//! addresses, and evidence is compared by kind + description (addresses shift across builds).
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::analysis::{AnalysisResult, EvidenceRecord, FunctionRecord};
+
+/// A function present at the same address in both runs under a different name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionRename {
+    pub address: u64,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// A function with the same name found at a different address in the other run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionMove {
+    pub name: String,
+    pub from: u64,
+    pub to: u64,
+}
+
+/// A call edge reported with the addresses and names of the run it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeRef {
+    pub from: u64,
+    pub to: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionChanges {
+    pub added: Vec<FunctionRecord>,
+    pub removed: Vec<FunctionRecord>,
+    pub renamed: Vec<FunctionRename>,
+    pub moved: Vec<FunctionMove>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeChanges {
+    pub added: Vec<EdgeRef>,
+    pub removed: Vec<EdgeRef>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceChanges {
+    pub added: Vec<EvidenceRecord>,
+    pub removed: Vec<EvidenceRecord>,
+}
+
+/// Differences going from `base` to `other`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisDiff {
+    pub functions: FunctionChanges,
+    pub call_edges: EdgeChanges,
+    pub evidence: EvidenceChanges,
+}
+
+impl AnalysisDiff {
+    /// True when both runs are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.functions.added.is_empty()
+            && self.functions.removed.is_empty()
+            && self.functions.renamed.is_empty()
+            && self.functions.moved.is_empty()
+            && self.call_edges.added.is_empty()
+            && self.call_edges.removed.is_empty()
+            && self.evidence.added.is_empty()
+            && self.evidence.removed.is_empty()
+    }
+}
+
+/// Compare two analysis results; `base` is the older run.
+pub fn diff_analysis(base: &AnalysisResult, other: &AnalysisResult) -> AnalysisDiff {
+    let base_by_addr: HashMap<u64, &FunctionRecord> =
+        base.functions.iter().map(|f| (f.address, f)).collect();
+    let other_by_addr: HashMap<u64, &FunctionRecord> =
+        other.functions.iter().map(|f| (f.address, f)).collect();
+
+    let mut functions = FunctionChanges::default();
+    for f in &base.functions {
+        if let Some(o) = other_by_addr.get(&f.address) {
+            if o.name != f.name {
+                functions.renamed.push(FunctionRename {
+                    address: f.address,
+                    from: f.name.clone(),
+                    to: o.name.clone(),
+                });
+            }
+        }
+    }
+
+    // Unmatched functions on either side; pair them up by name to detect moves.
+    let mut unmatched_other: HashMap<&str, &FunctionRecord> = other
+        .functions
+        .iter()
+        .filter(|f| !base_by_addr.contains_key(&f.address))
+        .filter_map(|f| f.name.as_deref().map(|n| (n, f)))
+        .collect();
+    // Maps other-run addresses onto base-run addresses for edge comparison.
+    let mut other_to_base: HashMap<u64, u64> = HashMap::new();
+    for f in base.functions.iter().filter(|f| !other_by_addr.contains_key(&f.address)) {
+        match f.name.as_deref().and_then(|n| unmatched_other.remove(n)) {
+            Some(o) => {
+                other_to_base.insert(o.address, f.address);
+                functions.moved.push(FunctionMove {
+                    name: o.name.clone().unwrap_or_default(),
+                    from: f.address,
+                    to: o.address,
+                });
+            }
+            None => functions.removed.push(f.clone()),
+        }
+    }
+    let moved_to: HashSet<u64> = other_to_base.keys().copied().collect();
+    functions.added = other
+        .functions
+        .iter()
+        .filter(|f| !base_by_addr.contains_key(&f.address) && !moved_to.contains(&f.address))
+        .cloned()
+        .collect();
+
+    let canonical = |addr: u64| other_to_base.get(&addr).copied().unwrap_or(addr);
+    let base_edges: BTreeSet<(u64, u64)> = base.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    let other_edges: BTreeSet<(u64, u64)> =
+        other.call_edges.iter().map(|e| (canonical(e.from), canonical(e.to))).collect();
+    let mut call_edges = EdgeChanges::default();
+    for e in &base.call_edges {
+        if !other_edges.contains(&(e.from, e.to)) {
+            push_unique(&mut call_edges.removed, edge_ref(e.from, e.to, &base_by_addr));
+        }
+    }
+    for e in &other.call_edges {
+        if !base_edges.contains(&(canonical(e.from), canonical(e.to))) {
+            push_unique(&mut call_edges.added, edge_ref(e.from, e.to, &other_by_addr));
+        }
+    }
+
+    let key = |e: &EvidenceRecord| (e.kind.clone(), e.description.clone());
+    let base_evidence: HashSet<_> = base.evidence.iter().map(key).collect();
+    let other_evidence: HashSet<_> = other.evidence.iter().map(key).collect();
+    let mut evidence = EvidenceChanges::default();
+    for e in &base.evidence {
+        if !other_evidence.contains(&key(e)) && !evidence.removed.iter().any(|r| key(r) == key(e)) {
+            evidence.removed.push(e.clone());
+        }
+    }
+    for e in &other.evidence {
+        if !base_evidence.contains(&key(e)) && !evidence.added.iter().any(|r| key(r) == key(e)) {
+            evidence.added.push(e.clone());
+        }
+    }
+
+    AnalysisDiff { functions, call_edges, evidence }
+}
+
+fn edge_ref(from: u64, to: u64, names: &HashMap<u64, &FunctionRecord>) -> EdgeRef {
+    let name = |addr: u64| names.get(&addr).and_then(|f| f.name.clone());
+    EdgeRef { from, to, from_name: name(from), to_name: name(to) }
+}
+
+fn push_unique(edges: &mut Vec<EdgeRef>, edge: EdgeRef) {
+    if !edges.contains(&edge) {
+        edges.push(edge);
+    }
+}
@@ -0,0 +1,537 @@
+//! Stable graph node identity for graph exports.
+//!
+//! Addresses change between builds, so exported graphs use the best-known function name as
+//! the node id, falling back to a content hash of the function (size, block layout relative to
+//! the entry, callee names, and attached evidence). Addresses are kept as node attributes.
+//! Block ids are derived from their function's id plus the offset from the entry point.
+//! Evidence enters the hash as an order-independent fingerprint, so it can be accumulated
+//! from paged DB reads without holding every evidence row in memory.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::services::analysis::{
+    AnalysisResult, BasicBlock, BlockEdgeKind, EvidenceRecord, FunctionRecord,
+};
+
+/// Number of hex characters kept from the SHA-256 content hash.
+const CONTENT_HASH_LEN: usize = 12;
+
+/// Stable node ids for the functions and basic blocks of one analysis result.
+#[derive(Debug, Clone, Default)]
+pub struct NodeIds {
+    functions: BTreeMap<u64, String>,
+    /// (entry, end) ranges of known functions, keyed by entry address.
+    ranges: BTreeMap<u64, u64>,
+}
+
+impl NodeIds {
+    /// Assign ids to every function in `result`.
+    ///
+    /// Named functions use `fn:<name>`; unnamed ones use `sub:<content hash>`. When two
+    /// functions would share an id, the content hash (and, if still ambiguous, an ordinal in
+    /// address order) is appended so ids stay unique and deterministic.
+    pub fn new(result: &AnalysisResult) -> Self {
+        Self::with_evidence(result, &EvidenceFingerprints::from_result(result))
+    }
+
+    /// Like [`NodeIds::new`], but with evidence fingerprints gathered separately (e.g. when
+    /// `result.evidence` was not loaded and evidence is streamed from the DB instead).
+    pub fn with_evidence(result: &AnalysisResult, evidence: &EvidenceFingerprints) -> Self {
+        let mut functions: Vec<&FunctionRecord> = result.functions.iter().collect();
+        functions.sort_by_key(|f| f.address);
+        functions.dedup_by_key(|f| f.address);
+
+        let ranges: BTreeMap<u64, u64> = functions
+            .iter()
+            .map(|f| (f.address, f.address.saturating_add(u64::from(f.size.unwrap_or(1).max(1)))))
+            .collect();
+
+        let base_ids: Vec<String> = functions
+            .iter()
+            .map(|f| match f.name.as_deref().filter(|n| !n.is_empty()) {
+                Some(name) => format!("fn:{}", name),
+                None => format!("sub:{}", content_hash(f, result, evidence.get(f.address))),
+            })
+            .collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for id in &base_ids {
+            *counts.entry(id.as_str()).or_default() += 1;
+        }
+
+        let mut assigned: HashMap<String, usize> = HashMap::new();
+        let mut ids = BTreeMap::new();
+        for (f, base) in functions.iter().zip(&base_ids) {
+            let mut id = base.clone();
+            if counts[base.as_str()] > 1 && base.starts_with("fn:") {
+                id = format!("{}#{}", base, content_hash(f, result, evidence.get(f.address)));
+            }
+            let seen = assigned.entry(id.clone()).or_default();
+            *seen += 1;
+            if *seen > 1 {
+                id = format!("{}~{}", id, seen);
+            }
+            ids.insert(f.address, id);
+        }
+
+        Self { functions: ids, ranges }
+    }
+
+    /// Entry address of the known function containing `addr`, if any.
+    fn containing_function(&self, addr: u64) -> Option<u64> {
+        self.ranges
+            .range(..=addr)
+            .next_back()
+            .filter(|(_, end)| addr < **end)
+            .map(|(start, _)| *start)
+    }
+
+    /// Id for a function entry (or a call target that is not a known function).
+    pub fn function(&self, addr: u64) -> String {
+        self.functions.get(&addr).cloned().unwrap_or_else(|| format!("addr:0x{:X}", addr))
+    }
+
+    /// Id for a basic block: `<function id>+0x<offset>` when inside a known function.
+    pub fn block(&self, start: u64) -> String {
+        match self.containing_function(start) {
+            Some(entry) => format!("{}+0x{:X}", self.function(entry), start - entry),
+            None => format!("bb:0x{:X}", start),
+        }
+    }
+}
+
+/// Order-independent digest of the evidence descriptions inside one function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvidenceFingerprint {
+    lanes: [u64; 4],
+    count: u64,
+}
+
+impl EvidenceFingerprint {
+    /// Fold one evidence description into the fingerprint (commutative, so order is irrelevant).
+    pub fn add(&mut self, description: &str) {
+        let digest = Sha256::digest(description.as_bytes());
+        for (lane, chunk) in self.lanes.iter_mut().zip(digest.chunks_exact(8)) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            *lane = lane.wrapping_add(u64::from_le_bytes(bytes));
+        }
+        self.count += 1;
+    }
+}
+
+/// Evidence fingerprints for every function of a result, fed one evidence record at a time.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceFingerprints {
+    /// Function entry -> end (exclusive).
+    ranges: BTreeMap<u64, u64>,
+    max_span: u64,
+    by_function: HashMap<u64, EvidenceFingerprint>,
+}
+
+impl EvidenceFingerprints {
+    pub fn new(functions: &[FunctionRecord]) -> Self {
+        let mut ranges = BTreeMap::new();
+        let mut max_span = 0;
+        for f in functions {
+            let span = u64::from(f.size.unwrap_or(1).max(1));
+            max_span = max_span.max(span);
+            ranges.entry(f.address).or_insert(f.address.saturating_add(span));
+        }
+        Self { ranges, max_span, by_function: HashMap::new() }
+    }
+
+    /// Fingerprints over an in-memory result's evidence.
+    pub fn from_result(result: &AnalysisResult) -> Self {
+        let mut out = Self::new(&result.functions);
+        for e in &result.evidence {
+            out.add(e);
+        }
+        out
+    }
+
+    /// Attribute `evidence` to every function whose range contains its address.
+    pub fn add(&mut self, evidence: &EvidenceRecord) {
+        let addr = evidence.address;
+        for (start, end) in self.ranges.range(addr.saturating_sub(self.max_span)..=addr) {
+            if addr < *end {
+                self.by_function.entry(*start).or_default().add(&evidence.description);
+            }
+        }
+    }
+
+    pub fn get(&self, entry: u64) -> EvidenceFingerprint {
+        self.by_function.get(&entry).copied().unwrap_or_default()
+    }
+}
+
+/// Address-independent content hash of a function (first 12 hex chars of SHA-256).
+pub fn function_content_hash(func: &FunctionRecord, result: &AnalysisResult) -> String {
+    let mut evidence = EvidenceFingerprint::default();
+    let end = func.address.saturating_add(u64::from(func.size.unwrap_or(1).max(1)));
+    for e in result.evidence.iter().filter(|e| e.address >= func.address && e.address < end) {
+        evidence.add(&e.description);
+    }
+    content_hash(func, result, evidence)
+}
+
+fn content_hash(
+    func: &FunctionRecord,
+    result: &AnalysisResult,
+    evidence: EvidenceFingerprint,
+) -> String {
+    let entry = func.address;
+    let end = entry.saturating_add(u64::from(func.size.unwrap_or(1).max(1)));
+    let inside = |addr: u64| addr >= entry && addr < end;
+    let names: HashMap<u64, &str> =
+        result.functions.iter().filter_map(|f| f.name.as_deref().map(|n| (f.address, n))).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!("size={:?}\n", func.size));
+
+    let mut blocks: Vec<_> = result.basic_blocks.iter().filter(|b| inside(b.start)).collect();
+    blocks.sort_by_key(|b| b.start);
+    for block in blocks {
+        hasher.update(format!(
+            "bb +{:X} bytes={} insns={:?}",
+            block.start - entry,
+            block.byte_len,
+            block.insn_count
+        ));
+        for succ in &block.successors {
+            let target = if inside(succ.target) {
+                format!("+{:X}", succ.target - entry)
+            } else {
+                names.get(&succ.target).map(|n| n.to_string()).unwrap_or_else(|| "?".into())
+            };
+            hasher.update(format!(" {}:{}", edge_kind_label(&succ.kind), target));
+        }
+        hasher.update("\n");
+    }
+
+    let mut callees: Vec<&str> = result
+        .call_edges
+        .iter()
+        .filter(|e| e.from == entry)
+        .map(|e| names.get(&e.to).copied().unwrap_or("?"))
+        .collect();
+    callees.sort_unstable();
+    for callee in callees {
+        hasher.update(format!("call {}\n", callee));
+    }
+
+    if evidence.count > 0 {
+        hasher.update(format!("evidence n={} {:016x?}\n", evidence.count, evidence.lanes));
+    }
+
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    hex[..CONTENT_HASH_LEN].to_string()
+}
+
+/// Block size for graph labels: `16 bytes, 4 insns`, omitting what the backend did not report.
+pub fn block_metrics(block: &BasicBlock) -> String {
+    let bytes = (block.byte_len > 0).then(|| format!("{} bytes", block.byte_len));
+    let insns = block.insn_count.map(|n| format!("{n} insns"));
+    let parts: Vec<String> = bytes.into_iter().chain(insns).collect();
+    if parts.is_empty() {
+        "size unknown".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Short label used for basic-block successor edges in graph exports.
+pub fn edge_kind_label(kind: &BlockEdgeKind) -> &'static str {
+    match kind {
+        BlockEdgeKind::Fallthrough => "fallthrough",
+        BlockEdgeKind::Jump => "jump",
+        BlockEdgeKind::ConditionalJump => "cjump",
+        BlockEdgeKind::IndirectJump => "ijump",
+        BlockEdgeKind::Call => "call",
+        BlockEdgeKind::IndirectCall => "icall",
+    }
+}
+
+/// Extra DOT attributes for boundary functions.
+const BOUNDARY_NODE_STYLE: &str = " style=filled fillcolor=\"#f6d7a7\" boundary=true";
+/// Extra DOT attributes for cross-slice call edges (same orange as the HTML call graph).
+const CROSS_SLICE_EDGE_STYLE: &str = " color=\"#d9822b\" style=dashed cross_slice=true";
+
+/// Layout options for DOT exports, for results too large to draw flat.
+///
+/// The default renders every function and basic block with one edge per call/successor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DotOptions {
+    /// Group nodes into a `cluster_<label>` subgraph per sub-slice; other in-slice functions
+    /// go into `cluster_slice`. Blocks follow their function.
+    pub cluster: bool,
+    /// Draw functions only: blocks are dropped and jumps between functions become
+    /// function-level edges (calls are already covered by call edges).
+    pub collapse_blocks: bool,
+    /// Merge parallel edges with the same label into one edge with a `count` attribute.
+    pub dedup_edges: bool,
+    /// Keep at most this many nodes (in-slice functions first, then other functions, then
+    /// blocks); the rest fold into one `overflow` node that keeps their edges.
+    pub max_nodes: Option<usize>,
+}
+
+/// Node id of the summary node standing in for nodes dropped by [`DotOptions::max_nodes`].
+pub const OVERFLOW_NODE_ID: &str = "overflow";
+
+/// Render the node/edge statements of a DOT graph using stable node ids.
+///
+/// Each function/block node carries its address as an `addr` attribute so tooling can still
+/// map nodes back to a particular build. Boundary functions and cross-slice calls are drawn
+/// in distinct colors.
+pub fn dot_body(result: &AnalysisResult) -> String {
+    dot_body_with_ids(result, &NodeIds::new(result))
+}
+
+/// [`dot_body`] with precomputed node ids (see [`NodeIds::with_evidence`]).
+pub fn dot_body_with_ids(result: &AnalysisResult, ids: &NodeIds) -> String {
+    dot_body_with_options(result, ids, &DotOptions::default())
+}
+
+struct DotNode {
+    id: String,
+    attrs: String,
+    /// Entry of the function the node belongs to (the function itself, or a block's owner).
+    function: Option<u64>,
+    is_block: bool,
+}
+
+struct DotEdge {
+    from: String,
+    to: String,
+    label: &'static str,
+    style: &'static str,
+    count: usize,
+}
+
+enum Stmt {
+    Node(usize),
+    Edge(usize),
+}
+
+/// [`dot_body_with_ids`] with clustering, block collapsing, edge de-duplication, and a node
+/// cap applied per `options`.
+pub fn dot_body_with_options(
+    result: &AnalysisResult,
+    ids: &NodeIds,
+    options: &DotOptions,
+) -> String {
+    let mut nodes: Vec<DotNode> = Vec::new();
+    let mut edges: Vec<DotEdge> = Vec::new();
+    let mut stmts: Vec<Stmt> = Vec::new();
+    let mut push_edge = |stmts: &mut Vec<Stmt>, edge: DotEdge| {
+        stmts.push(Stmt::Edge(edges.len()));
+        edges.push(edge);
+    };
+
+    for func in &result.functions {
+        let label = func.name.clone().unwrap_or_else(|| format!("0x{:X}", func.address));
+        stmts.push(Stmt::Node(nodes.len()));
+        nodes.push(DotNode {
+            id: ids.function(func.address),
+            attrs: format!(
+                "label={} shape=box addr=\"0x{:X}\"{}",
+                quote(&label),
+                func.address,
+                if func.is_boundary { BOUNDARY_NODE_STYLE } else { "" }
+            ),
+            function: Some(func.address),
+            is_block: false,
+        });
+    }
+    for edge in &result.call_edges {
+        push_edge(
+            &mut stmts,
+            DotEdge {
+                from: ids.function(edge.from),
+                to: ids.function(edge.to),
+                label: "call",
+                style: if edge.is_cross_slice { CROSS_SLICE_EDGE_STYLE } else { "" },
+                count: 1,
+            },
+        );
+    }
+    for bb in &result.basic_blocks {
+        let owner = ids.containing_function(bb.start);
+        if options.collapse_blocks {
+            let Some(owner) = owner else { continue };
+            for succ in &bb.successors {
+                let target = ids.containing_function(succ.target);
+                let is_call =
+                    matches!(succ.kind, BlockEdgeKind::Call | BlockEdgeKind::IndirectCall);
+                if is_call || target == Some(owner) {
+                    continue;
+                }
+                push_edge(
+                    &mut stmts,
+                    DotEdge {
+                        from: ids.function(owner),
+                        to: target
+                            .map(|t| ids.function(t))
+                            .unwrap_or_else(|| ids.block(succ.target)),
+                        label: edge_kind_label(&succ.kind),
+                        style: "",
+                        count: 1,
+                    },
+                );
+            }
+            continue;
+        }
+        stmts.push(Stmt::Node(nodes.len()));
+        nodes.push(DotNode {
+            id: ids.block(bb.start),
+            attrs: format!(
+                "label=\"bb 0x{:X}\\n{}\" shape=ellipse addr=\"0x{:X}\"",
+                bb.start,
+                block_metrics(bb),
+                bb.start
+            ),
+            function: owner,
+            is_block: true,
+        });
+        for succ in &bb.successors {
+            push_edge(
+                &mut stmts,
+                DotEdge {
+                    from: ids.block(bb.start),
+                    to: ids.block(succ.target),
+                    label: edge_kind_label(&succ.kind),
+                    style: "",
+                    count: 1,
+                },
+            );
+        }
+    }
+
+    // Node cap: drop the lowest-priority nodes and reroute their edges to the overflow node.
+    let in_slice: HashSet<u64> =
+        result.functions.iter().filter(|f| f.in_slice).map(|f| f.address).collect();
+    let mut dropped: HashSet<usize> = HashSet::new();
+    if let Some(max) = options.max_nodes.filter(|max| nodes.len() > *max) {
+        let mut order: Vec<usize> = (0..nodes.len()).collect();
+        order.sort_by_key(|&i| {
+            let node = &nodes[i];
+            let outside = !node.function.is_some_and(|f| in_slice.contains(&f));
+            (node.is_block, outside, i)
+        });
+        dropped.extend(order.into_iter().skip(max));
+    }
+    if !dropped.is_empty() {
+        let dropped_ids: HashSet<&str> = dropped.iter().map(|&i| nodes[i].id.as_str()).collect();
+        let reroute = |id: &mut String| {
+            if dropped_ids.contains(id.as_str()) {
+                *id = OVERFLOW_NODE_ID.to_string();
+            }
+        };
+        for edge in &mut edges {
+            reroute(&mut edge.from);
+            reroute(&mut edge.to);
+        }
+        stmts.retain(|stmt| match stmt {
+            Stmt::Node(i) => !dropped.contains(i),
+            Stmt::Edge(i) => edges[*i].from != OVERFLOW_NODE_ID || edges[*i].to != OVERFLOW_NODE_ID,
+        });
+    }
+
+    if options.dedup_edges {
+        let mut first: HashMap<(String, String, &str, &str), usize> = HashMap::new();
+        let mut keep = vec![true; edges.len()];
+        for i in 0..edges.len() {
+            let e = &edges[i];
+            let key = (e.from.clone(), e.to.clone(), e.label, e.style);
+            match first.get(&key) {
+                Some(&j) => {
+                    edges[j].count += 1;
+                    keep[i] = false;
+                }
+                None => {
+                    first.insert(key, i);
+                }
+            }
+        }
+        stmts.retain(|stmt| !matches!(stmt, Stmt::Edge(i) if !keep[*i]));
+    }
+
+    let clusters: BTreeMap<u64, String> = if options.cluster {
+        let mut labels = BTreeMap::new();
+        let mut sub_slices: Vec<_> = result.sub_slices.iter().collect();
+        sub_slices.sort_by(|a, b| a.label.cmp(&b.label));
+        for sub in sub_slices {
+            for f in &sub.functions {
+                labels.entry(*f).or_insert_with(|| sub.label.clone());
+            }
+        }
+        for f in &in_slice {
+            labels.entry(*f).or_insert_with(|| "slice".to_string());
+        }
+        labels
+    } else {
+        BTreeMap::new()
+    };
+    let cluster_of = |node: &DotNode| node.function.and_then(|f| clusters.get(&f));
+
+    let node_stmt =
+        |node: &DotNode, indent: &str| format!("{}{} [{}];\n", indent, quote(&node.id), node.attrs);
+    let mut out = String::new();
+    let mut grouped: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for stmt in &stmts {
+        if let Stmt::Node(i) = stmt {
+            if let Some(label) = cluster_of(&nodes[*i]) {
+                grouped.entry(label.as_str()).or_default().push(*i);
+            }
+        }
+    }
+    for (label, members) in &grouped {
+        out.push_str(&format!("  subgraph {} {{\n", quote(&format!("cluster_{}", label))));
+        out.push_str(&format!("    label={};\n", quote(label)));
+        for &i in members {
+            out.push_str(&node_stmt(&nodes[i], "    "));
+        }
+        out.push_str("  }\n");
+    }
+    for stmt in &stmts {
+        match stmt {
+            Stmt::Node(i) if cluster_of(&nodes[*i]).is_none() => {
+                out.push_str(&node_stmt(&nodes[*i], "  "))
+            }
+            Stmt::Node(_) => {}
+            Stmt::Edge(i) => {
+                let e = &edges[*i];
+                let (label, count) = if e.count > 1 {
+                    (format!("{} x{}", e.label, e.count), format!(" count={}", e.count))
+                } else {
+                    (e.label.to_string(), String::new())
+                };
+                out.push_str(&format!(
+                    "  {} -> {} [label={}{}{}];\n",
+                    quote(&e.from),
+                    quote(&e.to),
+                    quote(&label),
+                    count,
+                    e.style
+                ));
+            }
+        }
+    }
+    if !dropped.is_empty() {
+        out.push_str(&format!(
+            "  {} [label=\"+{} more nodes\" shape=note overflow=true];\n",
+            quote(OVERFLOW_NODE_ID),
+            dropped.len()
+        ));
+    }
+    out
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
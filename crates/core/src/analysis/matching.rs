@@ -0,0 +1,134 @@
+//! Cross-run function identity from fuzzy fingerprints.
+//!
+//! Unlike [`crate::analysis::diff`], which pairs functions by address and then by name,
+//! matching works on [`FunctionRecord::fuzzy_hash`], so stripped or renamed functions are
+//! still recognised after they move. Identical fingerprints are paired first; the remaining
+//! functions are paired greedily by descending similarity, preferring equal names and then
+//! the smallest address shift on ties. Each function is matched at most once.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::analysis::FunctionRecord;
+use crate::services::fingerprint::hash_similarity;
+
+/// Similarity below which two functions are not considered the same.
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.85;
+
+/// A function of the `from` run paired with one of the `to` run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionMatch {
+    pub from: u64,
+    pub to: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_name: Option<String>,
+    pub similarity: f64,
+}
+
+/// Result of [`match_functions`]; unmatched lists hold fingerprinted functions only.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionMatches {
+    pub matched: Vec<FunctionMatch>,
+    pub unmatched_from: Vec<FunctionRecord>,
+    pub unmatched_to: Vec<FunctionRecord>,
+    /// Functions without a fingerprint (skipped) in the `from` and `to` runs.
+    pub unhashed_from: usize,
+    pub unhashed_to: usize,
+}
+
+/// Candidate pair: similarity, whether the names differ, address shift, from, to.
+type Candidate = (f64, bool, u64, usize, usize);
+
+/// Pair the functions of `from` with those of `to` whose fingerprints are at least
+/// `min_similarity` alike.
+pub fn match_functions(
+    from: &[FunctionRecord],
+    to: &[FunctionRecord],
+    min_similarity: f64,
+) -> FunctionMatches {
+    let hashed = |funcs: &[FunctionRecord]| -> Vec<usize> {
+        funcs.iter().enumerate().filter(|(_, f)| f.fuzzy_hash.is_some()).map(|(i, _)| i).collect()
+    };
+    let from_hashed = hashed(from);
+    let to_hashed = hashed(to);
+    let candidate = |i: usize, j: usize, similarity: f64| -> Candidate {
+        let (a, b) = (&from[i], &to[j]);
+        let renamed = a.name.is_none() || a.name != b.name;
+        (similarity, renamed, a.address.abs_diff(b.address), i, j)
+    };
+
+    // Identical fingerprints first: cheap, and the common case between close builds.
+    let mut exact: Vec<Candidate> = Vec::new();
+    let mut to_by_hash: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for &j in &to_hashed {
+        to_by_hash.entry(to[j].fuzzy_hash.as_deref().unwrap_or_default()).or_default().push(j);
+    }
+    for &i in &from_hashed {
+        let hash = from[i].fuzzy_hash.as_deref().unwrap_or_default();
+        for &j in to_by_hash.get(hash).into_iter().flatten() {
+            exact.push(candidate(i, j, 1.0));
+        }
+    }
+    let mut taken_from = HashSet::new();
+    let mut taken_to = HashSet::new();
+    let mut matched = Vec::new();
+    pair_greedily(exact, &mut taken_from, &mut taken_to, &mut matched);
+
+    let mut fuzzy: Vec<Candidate> = Vec::new();
+    for &i in from_hashed.iter().filter(|i| !taken_from.contains(*i)) {
+        let a = from[i].fuzzy_hash.as_deref().unwrap_or_default();
+        for &j in to_hashed.iter().filter(|j| !taken_to.contains(*j)) {
+            let b = to[j].fuzzy_hash.as_deref().unwrap_or_default();
+            if let Some(similarity) = hash_similarity(a, b).filter(|s| *s >= min_similarity) {
+                fuzzy.push(candidate(i, j, similarity));
+            }
+        }
+    }
+    pair_greedily(fuzzy, &mut taken_from, &mut taken_to, &mut matched);
+
+    let mut matched: Vec<FunctionMatch> = matched
+        .into_iter()
+        .map(|(similarity, i, j)| FunctionMatch {
+            from: from[i].address,
+            to: to[j].address,
+            from_name: from[i].name.clone(),
+            to_name: to[j].name.clone(),
+            similarity,
+        })
+        .collect();
+    matched.sort_by_key(|m| (m.from, m.to));
+    let unmatched = |funcs: &[FunctionRecord], indices: &[usize], taken: &HashSet<usize>| {
+        let mut out: Vec<FunctionRecord> =
+            indices.iter().filter(|i| !taken.contains(*i)).map(|&i| funcs[i].clone()).collect();
+        out.sort_by_key(|f| f.address);
+        out
+    };
+    FunctionMatches {
+        matched,
+        unmatched_from: unmatched(from, &from_hashed, &taken_from),
+        unmatched_to: unmatched(to, &to_hashed, &taken_to),
+        unhashed_from: from.len() - from_hashed.len(),
+        unhashed_to: to.len() - to_hashed.len(),
+    }
+}
+
+fn pair_greedily(
+    mut candidates: Vec<Candidate>,
+    taken_from: &mut HashSet<usize>,
+    taken_to: &mut HashSet<usize>,
+    matched: &mut Vec<(f64, usize, usize)>,
+) {
+    candidates.sort_by(|a, b| {
+        b.0.total_cmp(&a.0).then_with(|| (a.1, a.2, a.3, a.4).cmp(&(b.1, b.2, b.3, b.4)))
+    });
+    for (similarity, _, _, i, j) in candidates {
+        if !taken_from.contains(&i) && !taken_to.contains(&j) {
+            taken_from.insert(i);
+            taken_to.insert(j);
+            matched.push((similarity, i, j));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Per-function size and complexity metrics, for ranking reverse-engineering targets.
+//!
+//! A function's basic blocks are those reachable from its entry block over jump and
+//! fall-through edges, stopping at other functions' entries (tail calls). Cyclomatic
+//! complexity is `E - N + 2` over that graph; edges to targets the backend did not split
+//! into blocks (indirect jumps, unknown code) are not counted. Fan-in and fan-out count
+//! distinct callers and callees, and string references come from
+//! [`crate::services::strings::string_references`].
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::analysis::{AnalysisResult, BasicBlock, BlockEdgeKind};
+use crate::services::strings::string_references;
+
+/// Metrics of one function.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    pub address: u64,
+    /// Instructions across the function's blocks; `None` when the backend did not count
+    /// them for every block (or found no blocks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insn_count: Option<u64>,
+    pub blocks: u64,
+    /// Cyclomatic complexity of the function's CFG; `None` without blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complexity: Option<u64>,
+    /// Distinct functions calling this one.
+    pub fan_in: u64,
+    /// Distinct call targets of this function.
+    pub fan_out: u64,
+    /// Instructions referencing a string literal.
+    pub string_refs: u64,
+}
+
+/// Metrics for every function of `result`, in function order.
+pub fn function_metrics(result: &AnalysisResult) -> Vec<FunctionMetrics> {
+    let blocks: HashMap<u64, &BasicBlock> =
+        result.basic_blocks.iter().map(|b| (b.start, b)).collect();
+    let entries: HashSet<u64> = result.functions.iter().map(|f| f.address).collect();
+    let mut callers: HashMap<u64, BTreeSet<u64>> = HashMap::new();
+    let mut callees: HashMap<u64, BTreeSet<u64>> = HashMap::new();
+    for edge in &result.call_edges {
+        callers.entry(edge.to).or_default().insert(edge.from);
+        callees.entry(edge.from).or_default().insert(edge.to);
+    }
+    let mut string_refs: HashMap<u64, u64> = HashMap::new();
+    for function in string_references(result).into_iter().filter_map(|r| r.function) {
+        *string_refs.entry(function).or_default() += 1;
+    }
+
+    result
+        .functions
+        .iter()
+        .map(|f| {
+            let own = function_blocks(f.address, &blocks, &entries);
+            let starts: HashSet<u64> = own.iter().map(|b| b.start).collect();
+            let edges = own
+                .iter()
+                .flat_map(|b| &b.successors)
+                .filter(|s| is_local(&s.kind) && starts.contains(&s.target))
+                .count();
+            let insn_count = (!own.is_empty())
+                .then(|| own.iter().map(|b| b.insn_count.map(u64::from)).sum::<Option<u64>>())
+                .flatten();
+            FunctionMetrics {
+                address: f.address,
+                insn_count,
+                blocks: own.len() as u64,
+                complexity: (!own.is_empty())
+                    .then(|| (edges as i64 - own.len() as i64 + 2).max(1) as u64),
+                fan_in: callers.get(&f.address).map_or(0, |c| c.len() as u64),
+                fan_out: callees.get(&f.address).map_or(0, |c| c.len() as u64),
+                string_refs: string_refs.get(&f.address).copied().unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+/// Blocks reachable from `entry` without leaving the function, in address order.
+fn function_blocks<'a>(
+    entry: u64,
+    blocks: &HashMap<u64, &'a BasicBlock>,
+    entries: &HashSet<u64>,
+) -> Vec<&'a BasicBlock> {
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![entry];
+    while let Some(start) = pending.pop() {
+        let Some(block) = blocks.get(&start) else {
+            continue;
+        };
+        if !seen.insert(start) {
+            continue;
+        }
+        for succ in block.successors.iter().filter(|s| is_local(&s.kind)) {
+            if !entries.contains(&succ.target) || succ.target == entry {
+                pending.push(succ.target);
+            }
+        }
+    }
+    seen.into_iter().filter_map(|start| blocks.get(&start).copied()).collect()
+}
+
+/// Whether a successor stays inside the function (calls return to the next block instead).
+fn is_local(kind: &BlockEdgeKind) -> bool {
+    !matches!(kind, BlockEdgeKind::Call | BlockEdgeKind::IndirectCall)
+}
@@ -5,6 +5,14 @@
 //! - Implement slice carving from root functions
 //! - Classify functions as in-slice / boundary / helper
 
+pub mod baseline;
+pub mod coverage;
+pub mod diff;
+pub mod graph;
+pub mod matching;
+pub mod metrics;
+pub mod slices;
+
 use crate::model::{Function, SliceId};
 
 /// Minimal placeholder for a slice analysis result.
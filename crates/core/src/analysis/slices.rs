@@ -0,0 +1,75 @@
+//! Cross-slice call detection and boundary classification.
+//!
+//! Backends carve one slice at a time, so `CallEdge::is_cross_slice` and
+//! `FunctionRecord::is_boundary` are only meaningful once the other slices of the same binary
+//! are known. [`classify_slice_boundaries`] intersects a slice's members (its `in_slice`
+//! functions) with the membership sets of the other slices:
+//! - a call edge is cross-slice when one end is in the slice and the other end belongs to a
+//!   different slice only;
+//! - an in-slice function is a boundary when it calls, or is called by, a known function
+//!   outside the slice.
+//!
+//! Flags are only ever set, never cleared, so backend-provided boundaries (e.g. Ghidra thunks)
+//! survive and classifying twice is harmless.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::Serialize;
+
+use crate::services::analysis::AnalysisResult;
+
+/// Function addresses per slice name, for one binary build.
+pub type SliceMembership = BTreeMap<String, BTreeSet<u64>>;
+
+/// What [`classify_slice_boundaries`] found, counted after classification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BoundaryCounts {
+    pub boundary_functions: usize,
+    pub cross_slice_calls: usize,
+}
+
+/// Mark cross-slice edges and boundary functions of `slice` in `result`.
+///
+/// `membership` may include `slice` itself; its entry is ignored in favour of the `in_slice`
+/// flags in `result`.
+pub fn classify_slice_boundaries(
+    result: &mut AnalysisResult,
+    slice: &str,
+    membership: &SliceMembership,
+) -> BoundaryCounts {
+    let members: HashSet<u64> =
+        result.functions.iter().filter(|f| f.in_slice).map(|f| f.address).collect();
+    let known: HashSet<u64> = result.functions.iter().map(|f| f.address).collect();
+    let elsewhere: HashSet<u64> = membership
+        .iter()
+        .filter(|(name, _)| name.as_str() != slice)
+        .flat_map(|(_, addrs)| addrs.iter().copied())
+        .filter(|addr| !members.contains(addr))
+        .collect();
+
+    let mut boundary = HashSet::new();
+    for edge in &mut result.call_edges {
+        let from_in = members.contains(&edge.from);
+        let to_in = members.contains(&edge.to);
+        if from_in == to_in {
+            continue;
+        }
+        let (inside, outside) = if from_in { (edge.from, edge.to) } else { (edge.to, edge.from) };
+        if elsewhere.contains(&outside) {
+            edge.is_cross_slice = true;
+        }
+        if known.contains(&outside) || elsewhere.contains(&outside) {
+            boundary.insert(inside);
+        }
+    }
+    for f in &mut result.functions {
+        if boundary.contains(&f.address) {
+            f.is_boundary = true;
+        }
+    }
+
+    BoundaryCounts {
+        boundary_functions: result.functions.iter().filter(|f| f.is_boundary).count(),
+        cross_slice_calls: result.call_edges.iter().filter(|e| e.is_cross_slice).count(),
+    }
+}
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// Placeholder for database configuration.
 ///
@@ -32,12 +35,37 @@ pub struct ProjectConfig {
     /// Optional default analysis backend to use when none is provided in CLI or spec.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_backend: Option<String>,
+    /// Backends to try in order when the spec names none; the runner falls back to the next
+    /// one when a tool is missing or fails. Takes precedence over `default_backend`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backend_priority: Vec<String>,
     /// Optional per-backend tool paths.
     #[serde(default, skip_serializing_if = "BackendPaths::is_empty")]
     pub backends: BackendPaths,
     /// Optional per-backend tool versions (best-effort detection).
     #[serde(default, skip_serializing_if = "BackendVersions::is_empty")]
     pub backend_versions: BackendVersions,
+    /// Optional overrides of the evidence scoring weights used by slice docs.
+    #[serde(default, skip_serializing_if = "EvidenceWeightOverrides::is_empty")]
+    pub evidence_weights: EvidenceWeightOverrides,
+    /// Project-wide defaults for `${name}` variables in ritual specs (`--set` overrides them).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// Commands the runner executes after each run.
+    #[serde(default, skip_serializing_if = "HookConfig::is_empty")]
+    pub hooks: HookConfig,
+    /// External tools for `open-run --with <name>`, as command templates.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, String>,
+    /// Directories for generated artifacts, overriding the defaults under the project root.
+    #[serde(default, skip_serializing_if = "LayoutConfig::is_empty")]
+    pub layout: LayoutConfig,
+    /// What fails a run compared against its ritual's baseline (`set-baseline`).
+    #[serde(default, skip_serializing_if = "RegressionConfig::is_empty")]
+    pub regressions: RegressionConfig,
+    /// Which runs `gc-outputs` keeps.
+    #[serde(default, skip_serializing_if = "RetentionConfig::is_empty")]
+    pub retention: RetentionConfig,
 }
 
 impl ProjectConfig {
@@ -49,14 +77,22 @@ impl ProjectConfig {
             config_version: "0.1.0".to_string(),
             db: DbConfig::new(db_path),
             default_backend: None,
+            backend_priority: Vec::new(),
             backends: BackendPaths::default(),
             backend_versions: BackendVersions::default(),
+            evidence_weights: EvidenceWeightOverrides::default(),
+            variables: BTreeMap::new(),
+            hooks: HookConfig::default(),
+            tools: BTreeMap::new(),
+            layout: LayoutConfig::default(),
+            regressions: RegressionConfig::default(),
+            retention: RetentionConfig::default(),
         }
     }
 }
 
 /// Optional tool paths for analysis backends.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct BackendPaths {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub rizin: Option<String>,
@@ -86,3 +122,608 @@ impl BackendVersions {
         self.rizin.is_none() && self.ghidra_headless.is_none() && self.capstone.is_none()
     }
 }
+
+/// Per-kind evidence weights overriding the defaults of
+/// [`EvidenceWeights`](crate::services::scoring::EvidenceWeights).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EvidenceWeightOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other: Option<f64>,
+}
+
+impl EvidenceWeightOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.string.is_none()
+            && self.import.is_none()
+            && self.call.is_none()
+            && self.other.is_none()
+    }
+}
+
+/// Commands run after ritual runs. Each is a command line with `{placeholder}` arguments
+/// (`{run_dir}`, `{status}`, ...) filled in by the runner.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HookConfig {
+    /// Run after every run, whether it succeeded, failed, or was canceled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_run: Vec<String>,
+}
+
+impl HookConfig {
+    pub fn is_empty(&self) -> bool {
+        self.post_run.is_empty()
+    }
+}
+
+/// Where generated artifacts go (see [`crate::db::ProjectLayout::with_config`]).
+///
+/// Each entry is a path template: `{project}` expands to the project name and `{root}` to
+/// the project root; relative paths are resolved against the root. Unset entries keep the
+/// default directory.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct LayoutConfig {
+    /// Run outputs (`outputs/`, holding `binaries/<binary>/<ritual>`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs_dir: Option<String>,
+    /// Slice reports (`reports/`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reports_dir: Option<String>,
+    /// Slice graphs (`graphs/`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphs_dir: Option<String>,
+}
+
+impl LayoutConfig {
+    /// Placeholders a layout template may use.
+    pub const PLACEHOLDERS: [&'static str; 2] = ["{project}", "{root}"];
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs_dir.is_none() && self.reports_dir.is_none() && self.graphs_dir.is_none()
+    }
+}
+
+/// Regressions that fail a run compared against its ritual's baseline run.
+///
+/// Drops are percentages of the baseline's count; unset limits other than
+/// `max_in_slice_drop` are not checked.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct RegressionConfig {
+    /// Largest allowed drop in in-slice functions (default
+    /// [`RegressionConfig::DEFAULT_MAX_IN_SLICE_DROP`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_in_slice_drop: Option<f64>,
+    /// Largest allowed drop in discovered functions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_function_drop: Option<f64>,
+    /// Largest allowed drop in call edges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_call_edge_drop: Option<f64>,
+    /// Whether a root the baseline resolved that now resolves to nothing fails the run
+    /// (default `true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unresolved_roots: Option<bool>,
+}
+
+impl RegressionConfig {
+    /// In-slice function drop, in percent, allowed when `max_in_slice_drop` is unset.
+    pub const DEFAULT_MAX_IN_SLICE_DROP: f64 = 10.0;
+
+    pub fn is_empty(&self) -> bool {
+        self.max_in_slice_drop.is_none()
+            && self.max_function_drop.is_none()
+            && self.max_call_edge_drop.is_none()
+            && self.unresolved_roots.is_none()
+    }
+}
+
+/// Which ritual runs `gc-outputs` keeps; the others lose their output directory and are
+/// marked `purged`.
+///
+/// Baseline runs and runs still pending or running are never purged.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct RetentionConfig {
+    /// Newest runs of each ritual kept whatever their status (default
+    /// [`RetentionConfig::DEFAULT_KEEP_LAST`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_last: Option<u64>,
+    /// Whether succeeded runs past `keep_last` are kept too (default `true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_succeeded: Option<bool>,
+    /// Whether baseline runs are kept (default `true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_baselines: Option<bool>,
+    /// Age in days after which failed, canceled, and stubbed runs are purged even within
+    /// `keep_last`; unset keeps them like any other run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_max_age_days: Option<u64>,
+}
+
+impl RetentionConfig {
+    /// Runs per ritual kept when `keep_last` is unset.
+    pub const DEFAULT_KEEP_LAST: u64 = 5;
+
+    pub fn is_empty(&self) -> bool {
+        self.keep_last.is_none()
+            && self.keep_succeeded.is_none()
+            && self.keep_baselines.is_none()
+            && self.failed_max_age_days.is_none()
+    }
+}
+
+/// Dotted keys addressable through `binary-slicer config`, with whether they can be set.
+pub const CONFIG_KEYS: &[(&str, bool)] = &[
+    ("name", true),
+    ("description", true),
+    ("config_version", false),
+    ("db.path", true),
+    ("default_backend", true),
+    ("backend_priority", true),
+    ("backends.rizin", true),
+    ("backends.ghidra_headless", true),
+    ("backend_versions.rizin", true),
+    ("backend_versions.ghidra_headless", true),
+    ("backend_versions.capstone", true),
+    ("evidence_weights.string", true),
+    ("evidence_weights.import", true),
+    ("evidence_weights.call", true),
+    ("evidence_weights.other", true),
+    ("hooks.post_run", true),
+    ("layout.outputs_dir", true),
+    ("layout.reports_dir", true),
+    ("layout.graphs_dir", true),
+    ("regressions.max_in_slice_drop", true),
+    ("regressions.max_function_drop", true),
+    ("regressions.max_call_edge_drop", true),
+    ("regressions.unresolved_roots", true),
+    ("retention.keep_last", true),
+    ("retention.keep_succeeded", true),
+    ("retention.keep_baselines", true),
+    ("retention.failed_max_age_days", true),
+];
+
+/// Prefix of the config keys holding spec variable defaults (`variables.<name>`).
+pub const VARIABLE_KEY_PREFIX: &str = "variables.";
+
+/// Prefix of the config keys holding external tool commands (`tools.<name>`).
+pub const TOOL_KEY_PREFIX: &str = "tools.";
+
+/// Namespaces of the built-in spec variables, which config and `--set` cannot define.
+pub const BUILTIN_VARIABLE_NAMESPACES: &[&str] = &["binary", "project"];
+
+/// Whether `name` can be used as a spec variable: ASCII letters, digits, `_`, `-`, and `.`,
+/// starting with a letter or `_`.
+pub fn is_valid_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Whether `name` is reserved for a built-in variable (`binary.arch`, `project.name`, ...).
+pub fn is_builtin_variable(name: &str) -> bool {
+    BUILTIN_VARIABLE_NAMESPACES
+        .iter()
+        .any(|ns| name.strip_prefix(ns).is_some_and(|rest| rest.starts_with('.')))
+}
+
+/// Every analysis backend name this project format recognizes (feature-gated ones included).
+pub const KNOWN_BACKENDS: &[&str] =
+    &["capstone", "ghidra", "ghidra-xml", "rizin", "validate-only", "wasm"];
+
+/// Errors from reading or changing a config key.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConfigKeyError {
+    #[error("Unknown config key '{key}'{}; valid keys: {}", suggestion_text(.suggestion), key_list())]
+    UnknownKey { key: String, suggestion: Option<String> },
+    #[error("Config key '{0}' is read-only")]
+    ReadOnly(String),
+    #[error("Config key '{0}' is required and cannot be unset")]
+    Required(String),
+    #[error("Invalid value for '{key}': {message}")]
+    InvalidValue { key: String, message: String },
+}
+
+fn suggestion_text(suggestion: &Option<String>) -> String {
+    suggestion.as_ref().map(|s| format!(" (did you mean '{s}'?)")).unwrap_or_default()
+}
+
+fn key_list() -> String {
+    CONFIG_KEYS.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(", ")
+}
+
+impl ProjectConfig {
+    /// Every known key with its current value, in [`CONFIG_KEYS`] order, followed by one
+    /// `variables.<name>` key per spec variable default and one `tools.<name>` key per tool.
+    pub fn entries(&self) -> Vec<(String, Option<String>)> {
+        let known = CONFIG_KEYS
+            .iter()
+            .map(|(key, _)| (key.to_string(), self.get_key(key).expect("known config key")));
+        let variables = self
+            .variables
+            .iter()
+            .map(|(name, value)| (format!("{VARIABLE_KEY_PREFIX}{name}"), Some(value.clone())));
+        let tools = self
+            .tools
+            .iter()
+            .map(|(name, command)| (format!("{TOOL_KEY_PREFIX}{name}"), Some(command.clone())));
+        known.chain(variables).chain(tools).collect()
+    }
+
+    /// Current value of a dotted key (`None` when an optional field is unset).
+    pub fn get_key(&self, key: &str) -> Result<Option<String>, ConfigKeyError> {
+        if let Some(name) = key.strip_prefix(VARIABLE_KEY_PREFIX) {
+            variable_name(key, name)?;
+            return Ok(self.variables.get(name).cloned());
+        }
+        if let Some(name) = key.strip_prefix(TOOL_KEY_PREFIX) {
+            tool_name(key, name)?;
+            return Ok(self.tools.get(name).cloned());
+        }
+        Ok(match key {
+            "name" => Some(self.name.clone()),
+            "description" => self.description.clone(),
+            "config_version" => Some(self.config_version.clone()),
+            "db.path" => Some(self.db.path.clone()),
+            "default_backend" => self.default_backend.clone(),
+            "backend_priority" => (!self.backend_priority.is_empty())
+                .then(|| serde_json::to_string(&self.backend_priority).expect("strings serialize")),
+            "backends.rizin" => self.backends.rizin.clone(),
+            "backends.ghidra_headless" => self.backends.ghidra_headless.clone(),
+            "backend_versions.rizin" => self.backend_versions.rizin.clone(),
+            "backend_versions.ghidra_headless" => self.backend_versions.ghidra_headless.clone(),
+            "backend_versions.capstone" => self.backend_versions.capstone.clone(),
+            "evidence_weights.string" => self.evidence_weights.string.map(|w| w.to_string()),
+            "evidence_weights.import" => self.evidence_weights.import.map(|w| w.to_string()),
+            "evidence_weights.call" => self.evidence_weights.call.map(|w| w.to_string()),
+            "evidence_weights.other" => self.evidence_weights.other.map(|w| w.to_string()),
+            "hooks.post_run" => (!self.hooks.post_run.is_empty())
+                .then(|| serde_json::to_string(&self.hooks.post_run).expect("strings serialize")),
+            "layout.outputs_dir" => self.layout.outputs_dir.clone(),
+            "layout.reports_dir" => self.layout.reports_dir.clone(),
+            "layout.graphs_dir" => self.layout.graphs_dir.clone(),
+            "regressions.max_in_slice_drop" => {
+                self.regressions.max_in_slice_drop.map(|p| p.to_string())
+            }
+            "regressions.max_function_drop" => {
+                self.regressions.max_function_drop.map(|p| p.to_string())
+            }
+            "regressions.max_call_edge_drop" => {
+                self.regressions.max_call_edge_drop.map(|p| p.to_string())
+            }
+            "regressions.unresolved_roots" => {
+                self.regressions.unresolved_roots.map(|on| on.to_string())
+            }
+            "retention.keep_last" => self.retention.keep_last.map(|n| n.to_string()),
+            "retention.keep_succeeded" => self.retention.keep_succeeded.map(|on| on.to_string()),
+            "retention.keep_baselines" => self.retention.keep_baselines.map(|on| on.to_string()),
+            "retention.failed_max_age_days" => {
+                self.retention.failed_max_age_days.map(|days| days.to_string())
+            }
+            other => return Err(unknown_key(other)),
+        })
+    }
+
+    /// Set a dotted key after validating the value. `default_backend` must name one of
+    /// [`KNOWN_BACKENDS`], whether or not it is compiled into this build, and evidence
+    /// weights must be non-negative numbers.
+    /// `hooks.post_run` takes one command, or a JSON array of commands; `backend_priority`
+    /// takes a JSON array or comma-separated list of known backends. Layout templates may only
+    /// use the [`LayoutConfig::PLACEHOLDERS`]. Regression drops are non-negative percentages
+    /// and `regressions.unresolved_roots` is `true` or `false`. Retention counts and ages are
+    /// non-negative integers.
+    pub fn set_key(&mut self, key: &str, value: &str) -> Result<(), ConfigKeyError> {
+        let slot = self.slot(key)?;
+        if value.trim().is_empty() {
+            return Err(ConfigKeyError::InvalidValue {
+                key: key.to_string(),
+                message: "value must not be empty".into(),
+            });
+        }
+        if key == "default_backend" && !KNOWN_BACKENDS.contains(&value) {
+            return Err(ConfigKeyError::InvalidValue {
+                key: key.to_string(),
+                message: format!(
+                    "unknown backend '{}' (available: {})",
+                    value,
+                    KNOWN_BACKENDS.join(", ")
+                ),
+            });
+        }
+        if key.starts_with("layout.") {
+            if let Some(unknown) = unknown_placeholder(value) {
+                return Err(ConfigKeyError::InvalidValue {
+                    key: key.to_string(),
+                    message: format!(
+                        "unknown placeholder '{}' (available: {})",
+                        unknown,
+                        LayoutConfig::PLACEHOLDERS.join(", ")
+                    ),
+                });
+            }
+        }
+        match slot {
+            Slot::Backends(field) => {
+                let names: Vec<String> = if value.trim_start().starts_with('[') {
+                    serde_json::from_str(value).map_err(|e| ConfigKeyError::InvalidValue {
+                        key: key.to_string(),
+                        message: format!("expected a JSON array of backends: {e}"),
+                    })?
+                } else {
+                    value.split(',').map(|name| name.trim().to_string()).collect()
+                };
+                if let Some(unknown) = names.iter().find(|n| !KNOWN_BACKENDS.contains(&n.as_str()))
+                {
+                    return Err(ConfigKeyError::InvalidValue {
+                        key: key.to_string(),
+                        message: format!(
+                            "unknown backend '{}' (available: {})",
+                            unknown,
+                            KNOWN_BACKENDS.join(", ")
+                        ),
+                    });
+                }
+                *field = names;
+            }
+            Slot::Required(field) => *field = value.to_string(),
+            Slot::Optional(field) => *field = Some(value.to_string()),
+            Slot::Variable(name) => {
+                self.variables.insert(name, value.to_string());
+            }
+            Slot::Tool(name) => {
+                self.tools.insert(name, value.to_string());
+            }
+            Slot::Commands(field) => {
+                *field = if value.trim_start().starts_with('[') {
+                    serde_json::from_str::<Vec<String>>(value).map_err(|e| {
+                        ConfigKeyError::InvalidValue {
+                            key: key.to_string(),
+                            message: format!("expected a JSON array of commands: {e}"),
+                        }
+                    })?
+                } else {
+                    vec![value.to_string()]
+                };
+            }
+            Slot::Weight(field) => {
+                let weight =
+                    value.trim().parse::<f64>().ok().filter(|w| w.is_finite() && *w >= 0.0);
+                let Some(weight) = weight else {
+                    return Err(ConfigKeyError::InvalidValue {
+                        key: key.to_string(),
+                        message: format!("expected a non-negative number, got '{}'", value),
+                    });
+                };
+                *field = Some(weight);
+            }
+            Slot::Flag(field) => {
+                let Ok(on) = value.trim().parse::<bool>() else {
+                    return Err(ConfigKeyError::InvalidValue {
+                        key: key.to_string(),
+                        message: format!("expected true or false, got '{}'", value),
+                    });
+                };
+                *field = Some(on);
+            }
+            Slot::Count(field) => {
+                let Ok(count) = value.trim().parse::<u64>() else {
+                    return Err(ConfigKeyError::InvalidValue {
+                        key: key.to_string(),
+                        message: format!("expected a non-negative integer, got '{}'", value),
+                    });
+                };
+                *field = Some(count);
+            }
+        }
+        Ok(())
+    }
+
+    /// Clear an optional key.
+    pub fn unset_key(&mut self, key: &str) -> Result<(), ConfigKeyError> {
+        match self.slot(key)? {
+            Slot::Optional(field) => {
+                *field = None;
+                Ok(())
+            }
+            Slot::Weight(field) => {
+                *field = None;
+                Ok(())
+            }
+            Slot::Flag(field) => {
+                *field = None;
+                Ok(())
+            }
+            Slot::Count(field) => {
+                *field = None;
+                Ok(())
+            }
+            Slot::Variable(name) => {
+                self.variables.remove(&name);
+                Ok(())
+            }
+            Slot::Tool(name) => {
+                self.tools.remove(&name);
+                Ok(())
+            }
+            Slot::Commands(field) | Slot::Backends(field) => {
+                field.clear();
+                Ok(())
+            }
+            Slot::Required(_) => Err(ConfigKeyError::Required(key.to_string())),
+        }
+    }
+
+    fn slot(&mut self, key: &str) -> Result<Slot<'_>, ConfigKeyError> {
+        if let Some(name) = key.strip_prefix(VARIABLE_KEY_PREFIX) {
+            return Ok(Slot::Variable(variable_name(key, name)?.to_string()));
+        }
+        if let Some(name) = key.strip_prefix(TOOL_KEY_PREFIX) {
+            return Ok(Slot::Tool(tool_name(key, name)?.to_string()));
+        }
+        Ok(match key {
+            "name" => Slot::Required(&mut self.name),
+            "description" => Slot::Optional(&mut self.description),
+            "config_version" => return Err(ConfigKeyError::ReadOnly(key.to_string())),
+            "db.path" => Slot::Required(&mut self.db.path),
+            "default_backend" => Slot::Optional(&mut self.default_backend),
+            "backend_priority" => Slot::Backends(&mut self.backend_priority),
+            "backends.rizin" => Slot::Optional(&mut self.backends.rizin),
+            "backends.ghidra_headless" => Slot::Optional(&mut self.backends.ghidra_headless),
+            "backend_versions.rizin" => Slot::Optional(&mut self.backend_versions.rizin),
+            "backend_versions.ghidra_headless" => {
+                Slot::Optional(&mut self.backend_versions.ghidra_headless)
+            }
+            "backend_versions.capstone" => Slot::Optional(&mut self.backend_versions.capstone),
+            "evidence_weights.string" => Slot::Weight(&mut self.evidence_weights.string),
+            "evidence_weights.import" => Slot::Weight(&mut self.evidence_weights.import),
+            "evidence_weights.call" => Slot::Weight(&mut self.evidence_weights.call),
+            "evidence_weights.other" => Slot::Weight(&mut self.evidence_weights.other),
+            "hooks.post_run" => Slot::Commands(&mut self.hooks.post_run),
+            "layout.outputs_dir" => Slot::Optional(&mut self.layout.outputs_dir),
+            "layout.reports_dir" => Slot::Optional(&mut self.layout.reports_dir),
+            "layout.graphs_dir" => Slot::Optional(&mut self.layout.graphs_dir),
+            "regressions.max_in_slice_drop" => {
+                Slot::Weight(&mut self.regressions.max_in_slice_drop)
+            }
+            "regressions.max_function_drop" => {
+                Slot::Weight(&mut self.regressions.max_function_drop)
+            }
+            "regressions.max_call_edge_drop" => {
+                Slot::Weight(&mut self.regressions.max_call_edge_drop)
+            }
+            "regressions.unresolved_roots" => Slot::Flag(&mut self.regressions.unresolved_roots),
+            "retention.keep_last" => Slot::Count(&mut self.retention.keep_last),
+            "retention.keep_succeeded" => Slot::Flag(&mut self.retention.keep_succeeded),
+            "retention.keep_baselines" => Slot::Flag(&mut self.retention.keep_baselines),
+            "retention.failed_max_age_days" => Slot::Count(&mut self.retention.failed_max_age_days),
+            other => return Err(unknown_key(other)),
+        })
+    }
+}
+
+enum Slot<'a> {
+    Required(&'a mut String),
+    Optional(&'a mut Option<String>),
+    Weight(&'a mut Option<f64>),
+    Flag(&'a mut Option<bool>),
+    Count(&'a mut Option<u64>),
+    Commands(&'a mut Vec<String>),
+    Backends(&'a mut Vec<String>),
+    Variable(String),
+    Tool(String),
+}
+
+/// Check the `<name>` of a `variables.<name>` key.
+fn variable_name<'a>(key: &str, name: &'a str) -> Result<&'a str, ConfigKeyError> {
+    let invalid = |message: String| ConfigKeyError::InvalidValue { key: key.to_string(), message };
+    if !is_valid_variable_name(name) {
+        return Err(invalid(format!(
+            "'{}' is not a valid variable name (letters, digits, '_', '-', '.')",
+            name
+        )));
+    }
+    if is_builtin_variable(name) {
+        return Err(invalid(format!("'{}' is a built-in variable and cannot be set", name)));
+    }
+    Ok(name)
+}
+
+/// First `{...}` placeholder of a layout template that is not one of
+/// [`LayoutConfig::PLACEHOLDERS`].
+fn unknown_placeholder(template: &str) -> Option<&str> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').map_or(rest.len(), |end| start + end + 1);
+        let placeholder = &rest[start..end];
+        if !LayoutConfig::PLACEHOLDERS.contains(&placeholder) {
+            return Some(placeholder);
+        }
+        rest = &rest[end..];
+    }
+    None
+}
+
+/// Check the `<name>` of a `tools.<name>` key.
+fn tool_name<'a>(key: &str, name: &'a str) -> Result<&'a str, ConfigKeyError> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+    if !valid {
+        return Err(ConfigKeyError::InvalidValue {
+            key: key.to_string(),
+            message: format!("'{}' is not a valid tool name (letters, digits, '_', '-')", name),
+        });
+    }
+    Ok(name)
+}
+
+/// Dotted keys of a raw `project.json` that this version does not know, each with the known
+/// key it most likely meant. Unknown keys are ignored when the config is loaded and dropped
+/// the next time it is saved.
+pub fn unknown_config_keys(raw: &serde_json::Value) -> Vec<(String, Option<String>)> {
+    fn walk(value: &serde_json::Value, prefix: &str, out: &mut Vec<(String, Option<String>)>) {
+        let Some(object) = value.as_object() else {
+            return;
+        };
+        for (name, value) in object {
+            let key = if prefix.is_empty() { name.clone() } else { format!("{prefix}.{name}") };
+            let dynamic = [VARIABLE_KEY_PREFIX, TOOL_KEY_PREFIX]
+                .iter()
+                .any(|p| key.starts_with(p) || format!("{key}.") == *p);
+            if dynamic || CONFIG_KEYS.iter().any(|(k, _)| *k == key) {
+                continue;
+            }
+            if CONFIG_KEYS.iter().any(|(k, _)| k.starts_with(&format!("{key}."))) {
+                walk(value, &key, out);
+            } else if let ConfigKeyError::UnknownKey { key, suggestion } = unknown_key(&key) {
+                out.push((key, suggestion));
+            }
+        }
+    }
+    let mut out = Vec::new();
+    walk(raw, "", &mut out);
+    out
+}
+
+/// Suggest the known key closest to a typo (same key after `_`/`-` normalization, a shared
+/// suffix such as `rizin` -> `backends.rizin`, or edit distance <= 2).
+fn unknown_key(key: &str) -> ConfigKeyError {
+    let normalized = key.replace('-', "_").to_ascii_lowercase();
+    let suggestion = CONFIG_KEYS
+        .iter()
+        .map(|(k, _)| *k)
+        .filter_map(|k| {
+            let score = if k == normalized {
+                0
+            } else if k.ends_with(&format!(".{normalized}")) {
+                1
+            } else {
+                let d = edit_distance(k, &normalized);
+                if d > 2 {
+                    return None;
+                }
+                d + 1
+            };
+            Some((score, k))
+        })
+        .min_by_key(|(score, _)| *score)
+        .map(|(_, k)| k.to_string());
+    ConfigKeyError::UnknownKey { key: key.to_string(), suggestion }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
@@ -1,5 +1,7 @@
 use std::path::{Path, PathBuf};
 
+use crate::db::ProjectConfig;
+
 /// Logical layout of a project on disk.
 ///
 /// This is derived from a chosen root path. It does *not* perform any IO itself.
@@ -63,6 +65,33 @@ impl ProjectLayout {
         }
     }
 
+    /// Apply the directory overrides of `config.layout`: templates are expanded (`{project}`
+    /// to the project name, `{root}` to the root) and relative results resolved against the
+    /// root. `outputs_binaries_dir` follows `outputs_dir`.
+    pub fn with_config(mut self, config: &ProjectConfig) -> Self {
+        let resolve = |template: &str| {
+            let root = self.root.to_string_lossy();
+            let path =
+                PathBuf::from(template.replace("{project}", &config.name).replace("{root}", &root));
+            if path.is_absolute() {
+                path
+            } else {
+                self.root.join(path)
+            }
+        };
+        if let Some(dir) = config.layout.outputs_dir.as_deref().map(resolve) {
+            self.outputs_binaries_dir = dir.join("binaries");
+            self.outputs_dir = dir;
+        }
+        if let Some(dir) = config.layout.reports_dir.as_deref().map(resolve) {
+            self.reports_dir = dir;
+        }
+        if let Some(dir) = config.layout.graphs_dir.as_deref().map(resolve) {
+            self.graphs_dir = dir;
+        }
+        self
+    }
+
     /// Compute a database path string suitable for storing in `ProjectConfig`,
     /// typically as a path relative to `root`.
     pub fn db_path_relative_string(&self) -> String {
@@ -72,6 +101,21 @@ impl ProjectLayout {
         }
     }
 
+    /// Directory holding docs of archived slices (docs/slices/_archived).
+    pub fn archived_slices_docs_dir(&self) -> PathBuf {
+        self.slices_docs_dir.join("_archived")
+    }
+
+    /// Directory for cached backend results (.ritual/cache).
+    pub fn cache_dir(&self) -> PathBuf {
+        self.meta_dir.join("cache")
+    }
+
+    /// Advisory lock file held by commands that write to the project (.ritual/lock).
+    pub fn lock_path(&self) -> PathBuf {
+        self.meta_dir.join("lock")
+    }
+
     /// Helper to compute a per-binary output root directory.
     pub fn binary_output_root(&self, binary_name: &str) -> PathBuf {
         self.outputs_binaries_dir.join(binary_name)
@@ -0,0 +1,116 @@
+//! Advisory project lock (`.ritual/lock`) held by commands that write to a project.
+//!
+//! The OS lock on the file is what excludes other writers, so a crashed process never leaves
+//! a stale lock behind. The file's contents only describe the current holder for error
+//! messages; they may be unreadable where locks are mandatory (Windows).
+
+use std::fmt;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::db::ProjectLayout;
+
+/// Process holding the project lock, as recorded in the lock file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// Command line of the holder (e.g. `binary-slicer run-ritual --file rituals/net.yaml`).
+    pub command: String,
+    /// RFC 3339 time the lock was taken.
+    pub acquired_at: String,
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pid {} (`{}`, since {})", self.pid, self.command, self.acquired_at)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("Project is locked by {}", holder_text(.holder))]
+    Held { holder: Option<LockHolder> },
+    #[error("Failed to lock project at {}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+}
+
+fn holder_text(holder: &Option<LockHolder>) -> String {
+    holder.as_ref().map(ToString::to_string).unwrap_or_else(|| "another process".into())
+}
+
+/// Exclusive lock on a project, released when dropped.
+#[derive(Debug)]
+pub struct ProjectLock {
+    file: File,
+}
+
+impl ProjectLock {
+    /// Take the lock for `command`, failing with [`LockError::Held`] if another process has it.
+    pub fn try_acquire(layout: &ProjectLayout, command: &str) -> Result<Self, LockError> {
+        let path = layout.lock_path();
+        let file = open(&path)?;
+        match file.try_lock() {
+            Ok(()) => Self::record(file, &path, command),
+            Err(TryLockError::WouldBlock) => Err(LockError::Held { holder: read_holder(&path) }),
+            Err(TryLockError::Error(source)) => Err(LockError::Io { path, source }),
+        }
+    }
+
+    /// Take the lock for `command`, blocking until the current holder releases it.
+    pub fn acquire(layout: &ProjectLayout, command: &str) -> Result<Self, LockError> {
+        let path = layout.lock_path();
+        let file = open(&path)?;
+        file.lock().map_err(|source| LockError::Io { path: path.clone(), source })?;
+        Self::record(file, &path, command)
+    }
+
+    /// The process recorded as holding the lock, without taking it (`dashboard`).
+    ///
+    /// The record outlives a holder that crashed, so this can name a process that is gone.
+    pub fn holder(layout: &ProjectLayout) -> Option<LockHolder> {
+        read_holder(&layout.lock_path())
+    }
+
+    fn record(mut file: File, path: &Path, command: &str) -> Result<Self, LockError> {
+        let holder = LockHolder {
+            pid: std::process::id(),
+            command: command.to_string(),
+            acquired_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let json = serde_json::to_string(&holder).unwrap_or_default();
+        file.set_len(0)
+            .and_then(|_| file.seek(SeekFrom::Start(0)))
+            .and_then(|_| file.write_all(json.as_bytes()))
+            .and_then(|_| file.flush())
+            .map_err(|source| LockError::Io { path: path.to_path_buf(), source })?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        // Clear the holder before closing the file releases the lock.
+        let _ = self.file.set_len(0);
+    }
+}
+
+fn open(path: &Path) -> Result<File, LockError> {
+    // Never truncate on open: the holder's record must survive until we hold the lock.
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|source| LockError::Io { path: path.to_path_buf(), source })
+}
+
+fn read_holder(path: &Path) -> Option<LockHolder> {
+    let mut text = String::new();
+    File::open(path).ok()?.read_to_string(&mut text).ok()?;
+    serde_json::from_str(&text).ok()
+}
@@ -1,15 +1,32 @@
 pub mod config;
 pub mod context;
 pub mod layout;
+pub mod lock;
 pub mod models;
 pub mod project_db;
 pub mod util;
 
-pub use config::{BackendPaths, BackendVersions, DbConfig, ProjectConfig};
+pub use config::{
+    unknown_config_keys, BackendPaths, BackendVersions, ConfigKeyError, DbConfig,
+    EvidenceWeightOverrides, HookConfig, LayoutConfig, ProjectConfig, RegressionConfig,
+    RetentionConfig, BUILTIN_VARIABLE_NAMESPACES, CONFIG_KEYS, KNOWN_BACKENDS, TOOL_KEY_PREFIX,
+    VARIABLE_KEY_PREFIX,
+};
 pub use context::ProjectContext;
 pub use layout::ProjectLayout;
+pub use lock::{LockError, LockHolder, ProjectLock};
+pub use models::qualified_slice_name;
 pub use models::{
-    BinaryRecord, ProjectSnapshot, RitualRunRecord, RitualRunStatus, SliceRecord, SliceStatus,
+    BinaryGroup, BinaryGroupMember, BinaryPdb, BinaryRecord, FunctionAnnotation, FunctionFilter,
+    FunctionListing, FunctionPage, ImportedSymbol, MemberSource, ProjectSnapshot, RitualRunRecord,
+    RitualRunStatus, RunBaseline, RunCounts, RunNote, RunUsage, SliceMember, SliceRecord,
+    SliceStatus, SliceStatusChange, TagKind, Tagging,
+};
+pub use project_db::{
+    DbError, DbResult, EvidencePages, ProjectDb, XrefPages, ANALYSIS_PAGE_SIZE,
+    CURRENT_SCHEMA_VERSION,
+};
+pub use util::{
+    load_project_config, load_project_layout, load_resolved_project_config, open_project_db,
+    project_db_path, save_project_config,
 };
-pub use project_db::{DbError, DbResult, ProjectDb};
-pub use util::{load_project_config, open_project_db};
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::services::analysis::AnalysisOptions;
+
 /// High-level lifecycle status of a slice.
 ///
 /// This is intentionally simple; finer-grained states can be added later.
@@ -36,6 +38,39 @@ impl SliceStatus {
             _ => SliceStatus::Draft,
         }
     }
+
+    /// Parse a status name case-insensitively (`planned`, `Active`...).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "planned" => Some(SliceStatus::Planned),
+            "draft" => Some(SliceStatus::Draft),
+            "active" => Some(SliceStatus::Active),
+            "deprecated" => Some(SliceStatus::Deprecated),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded transition of a slice's [`SliceStatus`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SliceStatusChange {
+    pub slice: String,
+    pub from: SliceStatus,
+    pub to: SliceStatus,
+    /// When the status changed (RFC3339).
+    pub changed_at: String,
+    /// Optional reason given with the change.
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Timestamped finding attached to a ritual run (`annotate-run`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunNote {
+    pub run_id: i64,
+    pub note: String,
+    /// When the note was added (RFC3339).
+    pub created_at: String,
 }
 
 /// Record describing a binary known to the project.
@@ -66,19 +101,64 @@ impl BinaryRecord {
 /// orthogonal to any specific analysis run.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SliceRecord {
-    /// Slice name (e.g., "AutoUpdateManager", "CUIManager", "Networking").
+    /// Slice name (e.g., "AutoUpdateManager", "CUIManager", "Networking"); qualified as
+    /// `Networking@armv7` for slices in a namespace (see [`qualified_slice_name`]).
     pub name: String,
+    /// Namespace the slice is scoped to: a binary group name, or an explicit label. `None`
+    /// for global slices.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
     /// Optional human-written description of this slice's purpose.
     pub description: Option<String>,
     /// Optional default binary this slice is associated with.
     pub default_binary: Option<String>,
     /// Lifecycle status.
     pub status: SliceStatus,
+    /// When the slice was archived (RFC3339); `None` for active slices.
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    /// Roots the analyst listed in the slice doc (`sync-slice-docs`).
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// Free-form analyst notes from the slice doc (`sync-slice-docs`).
+    #[serde(default)]
+    pub notes: Option<String>,
 }
 
 impl SliceRecord {
     pub fn new(name: impl Into<String>, status: SliceStatus) -> Self {
-        Self { name: name.into(), description: None, default_binary: None, status }
+        Self {
+            name: name.into(),
+            namespace: None,
+            description: None,
+            default_binary: None,
+            status,
+            archived_at: None,
+            roots: Vec::new(),
+            notes: None,
+        }
+    }
+
+    /// Archived slices are hidden from default listings and doc emission but keep their history.
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    /// Name of the rituals whose runs feed this slice: the name without its namespace.
+    pub fn ritual_name(&self) -> &str {
+        self.namespace
+            .as_deref()
+            .and_then(|ns| self.name.strip_suffix(ns)?.strip_suffix('@'))
+            .unwrap_or(&self.name)
+    }
+
+    /// Builder-style helper to place the slice in a namespace; qualifies its name to match.
+    pub fn with_namespace(mut self, namespace: Option<String>) -> Self {
+        if let Some(ns) = &namespace {
+            self.name = qualified_slice_name(self.ritual_name(), Some(ns));
+        }
+        self.namespace = namespace;
+        self
     }
 
     /// Builder-style helper to attach a description when constructing a record.
@@ -94,6 +174,15 @@ impl SliceRecord {
     }
 }
 
+/// Name a slice is stored and addressed under: `name` for global slices, `name@namespace`
+/// for slices in a namespace, so each namespace can have its own `Networking`.
+pub fn qualified_slice_name(name: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(ns) => format!("{name}@{ns}"),
+        None => name.to_string(),
+    }
+}
+
 /// A high-level snapshot of project metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectSnapshot {
@@ -112,6 +201,10 @@ pub enum RitualRunStatus {
     Failed,
     Canceled,
     Stubbed,
+    /// The binary changed on disk after the run (see `verify-binaries --mark-outdated`).
+    Outdated,
+    /// Output directory removed by `gc-outputs`; the analysis rows stay in the DB.
+    Purged,
 }
 
 impl RitualRunStatus {
@@ -123,6 +216,8 @@ impl RitualRunStatus {
             RitualRunStatus::Failed => "failed",
             RitualRunStatus::Canceled => "canceled",
             RitualRunStatus::Stubbed => "stubbed",
+            RitualRunStatus::Outdated => "outdated",
+            RitualRunStatus::Purged => "purged",
         }
     }
 }
@@ -131,6 +226,9 @@ impl RitualRunStatus {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RitualRunRecord {
     pub binary: String,
+    /// Row id of the resolved binary in the `binaries` table (absent for legacy runs).
+    #[serde(default)]
+    pub binary_id: Option<i64>,
     pub ritual: String,
     pub spec_hash: String,
     pub binary_hash: Option<String>,
@@ -140,4 +238,251 @@ pub struct RitualRunRecord {
     pub status: RitualRunStatus,
     pub started_at: String,
     pub finished_at: String,
+    /// Id of the run that replaced this one (set when `watch` re-runs the ritual).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<i64>,
+    /// Produced by a `--deterministic` run (sorted output, fixed timestamps, relative paths).
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Spec file the run came from (project-relative when it lives inside the project).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_path: Option<String>,
+    /// Traversal options the backend ran with (absent for runs recorded before schema v35).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis_options: Option<AnalysisOptions>,
+    /// How long the analysis took and what its tool processes used (absent for runs
+    /// recorded before schema v44 and for imported runs).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RunUsage>,
+}
+
+/// Wall-clock time and resources of one run's analysis.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunUsage {
+    /// Milliseconds from asking the backend (or the cache) to the finished result.
+    pub duration_ms: u64,
+    /// User plus system CPU time of the external tool processes the backend ran; `None` for
+    /// in-process backends and where the OS does not report it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_ms: Option<u64>,
+    /// Largest resident set of those processes, in KiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss_kb: Option<u64>,
+}
+
+/// Row counts of a run's persisted analysis (see `ProjectDb::run_counts`).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunCounts {
+    pub functions: usize,
+    /// Functions the run marked `in_slice`.
+    pub in_slice_functions: usize,
+    pub call_edges: usize,
+    pub evidence: usize,
+    pub xrefs: usize,
+}
+
+/// Which of a run's functions `ProjectDb::list_functions` returns, and which page of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionFilter {
+    /// Only functions the run marked `in_slice`.
+    pub in_slice: bool,
+    /// Only functions of at least this many bytes (unsized functions are left out).
+    pub min_size: Option<u32>,
+    /// Case-insensitive substring of the function name or its annotation name.
+    pub name_contains: Option<String>,
+    /// Rows to return after skipping `offset` (all of them when `None`).
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+/// A function of a run, with the annotation name of its build when one is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FunctionListing {
+    pub address: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    pub in_slice: bool,
+    pub is_boundary: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>,
+}
+
+/// One page of `ProjectDb::list_functions`, ordered by address; `total` counts every match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FunctionPage {
+    pub total: usize,
+    pub functions: Vec<FunctionListing>,
+}
+
+/// Human-assigned name and/or note for a function, keyed by binary content hash so it
+/// survives re-runs and applies to every run of the same build.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FunctionAnnotation {
+    pub binary_hash: String,
+    pub address: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// When the annotation was last changed (RFC3339).
+    pub updated_at: String,
+}
+
+/// A function name imported from another tool's symbol map (`import-symbols`).
+///
+/// Keyed by binary hash like [`FunctionAnnotation`]; runs of the binary name functions at
+/// these addresses with the imported names instead of the backend's.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportedSymbol {
+    pub binary_hash: String,
+    pub address: u64,
+    pub name: String,
+    /// Symbol map the name came from (its file name and format, e.g. `app.map (map)`).
+    pub source: String,
+    /// When the symbol was imported (RFC3339).
+    pub imported_at: String,
+}
+
+/// PDB associated with a binary (`add-binary --pdb`).
+///
+/// The GUID and age are recorded at association time so a rebuilt binary or a replaced PDB
+/// is noticed before its symbols name the wrong code.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BinaryPdb {
+    pub binary_id: i64,
+    /// Path to the PDB, relative to the project root if possible.
+    pub path: String,
+    pub guid: String,
+    pub age: u32,
+    /// When the PDB was associated (RFC3339).
+    pub associated_at: String,
+}
+
+/// Run that later runs of a ritual are compared against (`set-baseline`).
+///
+/// A baseline set on one binary also covers the other binaries of its group, so a build
+/// tracked across patches is gated against the run the baseline was taken from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunBaseline {
+    pub binary_id: i64,
+    /// Name of the binary the baseline run analyzed.
+    pub binary: String,
+    pub ritual: String,
+    pub run_id: i64,
+    /// When the baseline was set (RFC3339).
+    pub set_at: String,
+}
+
+/// Where a slice membership row came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberSource {
+    /// Recorded by `slice-add-function` / `slice-remove-function`.
+    Manual,
+    /// Derived from the `in_slice` flags of the slice's latest analysis run.
+    Analysis,
+}
+
+impl MemberSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemberSource::Manual => "manual",
+            MemberSource::Analysis => "analysis",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "manual" => Some(MemberSource::Manual),
+            "analysis" => Some(MemberSource::Analysis),
+            _ => None,
+        }
+    }
+}
+
+/// A function's membership in a slice for one build (keyed by binary content hash).
+///
+/// Manual rows override analysis-derived membership: `excluded` manual rows drop a function
+/// the analysis put in the slice, non-excluded ones add it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SliceMember {
+    pub slice: String,
+    pub binary_hash: String,
+    pub address: u64,
+    pub source: MemberSource,
+    #[serde(default)]
+    pub excluded: bool,
+    /// When the row was last written (RFC3339).
+    pub updated_at: String,
+}
+
+/// A registered binary's place in a [`BinaryGroup`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BinaryGroupMember {
+    /// Row id in the `binaries` table.
+    pub binary_id: i64,
+    pub binary: BinaryRecord,
+    /// Build label within the group (e.g., "v1.3"); unique per group when set.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Toolchain build identifier (GNU build-id, Mach-O UUID, PDB GUID), if known.
+    #[serde(default)]
+    pub build_id: Option<String>,
+}
+
+/// The same logical binary tracked across builds (e.g., every `libExampleGame.so` release).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BinaryGroup {
+    pub name: String,
+    /// When the group was created (RFC3339).
+    pub created_at: String,
+    /// Members in registration order.
+    pub members: Vec<BinaryGroupMember>,
+}
+
+/// What a tag is attached to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    Slice,
+    Binary,
+    /// A ritual run row (tagging a binary/ritual pair tags its latest run).
+    Run,
+}
+
+impl TagKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TagKind::Slice => "slice",
+            TagKind::Binary => "binary",
+            TagKind::Run => "run",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "slice" => Some(TagKind::Slice),
+            "binary" => Some(TagKind::Binary),
+            "run" => Some(TagKind::Run),
+            _ => None,
+        }
+    }
+}
+
+/// A tag attached to a slice, binary, or run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Tagging {
+    pub tag: String,
+    pub kind: TagKind,
+    /// Row id of the tagged slice, binary, or ritual run.
+    pub target_id: i64,
+    /// Slice or binary name; for runs, the run's binary.
+    pub name: String,
+    /// The run's ritual (runs only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ritual: Option<String>,
+    /// When the tag was attached (RFC3339).
+    pub created_at: String,
 }
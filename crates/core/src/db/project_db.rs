@@ -1,9 +1,17 @@
+use std::collections::BTreeMap;
+use std::ops::Deref;
 use std::path::Path;
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Transaction};
 use thiserror::Error;
 
-use crate::db::{BinaryRecord, RitualRunRecord, RitualRunStatus, SliceRecord, SliceStatus};
+use crate::db::{
+    BinaryGroup, BinaryGroupMember, BinaryPdb, BinaryRecord, FunctionAnnotation, FunctionFilter,
+    FunctionListing, FunctionPage, ImportedSymbol, MemberSource, RitualRunRecord, RitualRunStatus,
+    RunBaseline, RunCounts, RunNote, RunUsage, SliceMember, SliceRecord, SliceStatus,
+    SliceStatusChange, TagKind, Tagging,
+};
+use crate::services::binary_info::BinaryInfo;
 
 /// Minimum schema version we know how to handle.
 ///
@@ -11,7 +19,10 @@ use crate::db::{BinaryRecord, RitualRunRecord, RitualRunStatus, SliceRecord, Sli
 const MIN_SUPPORTED_SCHEMA_VERSION: i32 = 0;
 
 /// Latest schema version this crate knows about.
-pub const CURRENT_SCHEMA_VERSION: i32 = 9;
+pub const CURRENT_SCHEMA_VERSION: i32 = 45;
+
+/// Rows fetched per query when paging through large analysis tables (evidence, xrefs).
+pub const ANALYSIS_PAGE_SIZE: usize = 10_000;
 
 /// Error type for project database operations.
 #[derive(Debug, Error)]
@@ -30,6 +41,49 @@ pub enum DbError {
 /// Convenience result type for DB operations.
 pub type DbResult<T> = Result<T, DbError>;
 
+/// Tables holding a run's persisted analysis, keyed by `run_id`.
+const ANALYSIS_TABLES: [&str; 13] = [
+    "analysis_functions",
+    "analysis_call_edges",
+    "analysis_basic_block_edges",
+    "analysis_basic_blocks",
+    "analysis_evidence",
+    "analysis_roots",
+    "analysis_root_hits",
+    "analysis_sub_slice_roots",
+    "analysis_sub_slice_functions",
+    "analysis_xrefs",
+    "analysis_coverage",
+    "function_metrics",
+    "slice_snapshots",
+];
+
+/// Write scope from [`ProjectDb::begin`]; committing a joined scope is left to its owner.
+enum TxGuard<'a> {
+    Own(Transaction<'a>),
+    Joined(&'a Connection),
+}
+
+impl TxGuard<'_> {
+    fn commit(self) -> DbResult<()> {
+        if let TxGuard::Own(tx) = self {
+            tx.commit()?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for TxGuard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            TxGuard::Own(tx) => tx,
+            TxGuard::Joined(conn) => conn,
+        }
+    }
+}
+
 /// SQLite-backed project database.
 ///
 /// This is a thin wrapper around `rusqlite::Connection` that is responsible for:
@@ -49,12 +103,36 @@ impl ProjectDb {
         Ok(Self { conn })
     }
 
+    /// Schema version of the database at `path`, read without creating or migrating it.
+    pub fn schema_version_at(path: &Path) -> DbResult<i32> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        current_schema_version(&conn)
+    }
+
     /// Expose a reference to the underlying connection for advanced callers.
     /// For most code, prefer higher-level helpers.
     pub fn connection(&self) -> &Connection {
         &self.conn
     }
 
+    /// Run `f` in a single transaction: its writes commit together, or roll back together
+    /// when it returns an error. Helpers that open their own transaction join this one.
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> DbResult<T>) -> DbResult<T> {
+        let tx = self.conn.unchecked_transaction()?;
+        let out = f(self)?;
+        tx.commit()?;
+        Ok(out)
+    }
+
+    /// Open a transaction, or join the caller's when one is already open.
+    fn begin(&self) -> DbResult<TxGuard<'_>> {
+        if self.conn.is_autocommit() {
+            Ok(TxGuard::Own(self.conn.unchecked_transaction()?))
+        } else {
+            Ok(TxGuard::Joined(&self.conn))
+        }
+    }
+
     /// Insert a binary record and return its row id.
     pub fn insert_binary(&self, record: &BinaryRecord) -> DbResult<i64> {
         self.conn.execute(
@@ -67,6 +145,15 @@ impl ProjectDb {
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Replace the stored hash of a binary (e.g. after the file was rebuilt in place).
+    ///
+    /// Returns the number of rows affected.
+    pub fn update_binary_hash(&self, id: i64, hash: Option<&str>) -> DbResult<usize> {
+        let affected =
+            self.conn.execute("UPDATE binaries SET hash = ?1 WHERE id = ?2", params![hash, id])?;
+        Ok(affected)
+    }
+
     /// List all binaries (ordered by id).
     pub fn list_binaries(&self) -> DbResult<Vec<BinaryRecord>> {
         let mut stmt = self.conn.prepare(
@@ -92,37 +179,374 @@ impl ProjectDb {
         Ok(out)
     }
 
-    /// Insert a slice record and return its row id.
-    pub fn insert_slice(&self, record: &SliceRecord) -> DbResult<i64> {
+    /// List all binaries together with their row ids (ordered by id).
+    ///
+    /// Row ids give callers a stable identity when several binaries share a name.
+    pub fn list_binaries_with_ids(&self) -> DbResult<Vec<(i64, BinaryRecord)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, name, path, arch, hash
+            FROM binaries
+            ORDER BY id
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                BinaryRecord {
+                    name: row.get(1)?,
+                    path: row.get(2)?,
+                    arch: row.get(3)?,
+                    hash: row.get(4)?,
+                },
+            ))
+        })?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        Ok(out)
+    }
+
+    /// Delete a binary row; returns the number of rows deleted (0 when `id` does not exist).
+    ///
+    /// Runs recorded for the binary are not touched; see [`ProjectDb::delete_binary_runs`] and
+    /// [`ProjectDb::detach_binary_runs`].
+    pub fn delete_binary(&self, id: i64) -> DbResult<usize> {
+        let tx = self.begin()?;
+        tx.execute("DELETE FROM taggings WHERE kind = 'binary' AND target_id = ?1", params![id])?;
+        tx.execute("DELETE FROM binary_pdbs WHERE binary_id = ?1", params![id])?;
+        tx.execute("DELETE FROM binary_info WHERE binary_id = ?1", params![id])?;
+        tx.execute("DELETE FROM run_baselines WHERE binary_id = ?1", params![id])?;
+        let affected = tx.execute("DELETE FROM binaries WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(affected)
+    }
+
+    /// Rename a binary and point slices whose default binary was `old_name` at the new name.
+    ///
+    /// With `rename_runs`, its runs (matched by id, or by name for legacy runs without one)
+    /// are renamed too. Returns the number of runs renamed.
+    pub fn rename_binary(
+        &self,
+        id: i64,
+        old_name: &str,
+        new_name: &str,
+        rename_runs: bool,
+    ) -> DbResult<usize> {
+        let tx = self.begin()?;
+        tx.execute("UPDATE binaries SET name = ?2 WHERE id = ?1", params![id, new_name])?;
+        tx.execute(
+            "UPDATE slices SET default_binary = ?2 WHERE default_binary = ?1",
+            params![old_name, new_name],
+        )?;
+        let renamed = if rename_runs {
+            tx.execute(
+                r#"
+                UPDATE ritual_runs SET binary = ?3
+                WHERE binary_id = ?1 OR (binary_id IS NULL AND binary = ?2)
+                "#,
+                params![id, old_name, new_name],
+            )?
+        } else {
+            0
+        };
+        tx.commit()?;
+        Ok(renamed)
+    }
+
+    /// Delete a binary's runs (matched by id, or by name for legacy runs without one) and
+    /// their analysis rows. Returns the number of runs deleted.
+    pub fn delete_binary_runs(&self, binary_id: i64, binary: &str) -> DbResult<usize> {
+        let tx = self.begin()?;
+        let filter = "binary_id = ?1 OR (binary_id IS NULL AND binary = ?2)";
+        let runs = format!("SELECT id FROM ritual_runs WHERE {filter}");
+        tx.execute(
+            &format!("DELETE FROM taggings WHERE kind = 'run' AND target_id IN ({runs})"),
+            params![binary_id, binary],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM run_notes WHERE run_id IN ({runs})"),
+            params![binary_id, binary],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM run_baselines WHERE run_id IN ({runs})"),
+            params![binary_id, binary],
+        )?;
+        for table in ANALYSIS_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE run_id IN ({runs})"),
+                params![binary_id, binary],
+            )?;
+        }
+        let deleted = tx.execute(
+            &format!("DELETE FROM ritual_runs WHERE {filter}"),
+            params![binary_id, binary],
+        )?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Name the runs of binary `id` are recorded under, which also names its directory under
+    /// `outputs/binaries`; `None` when no binary has that id.
+    ///
+    /// That is the name of its latest run, so it stays put once used. A binary without runs
+    /// gets its own name, or `<name>@<first 12 hex digits of its hash>` (`<name>#<id>`
+    /// without a hash) when a binary registered before it shares the name or another
+    /// binary's runs already use it.
+    pub fn binary_run_name(&self, id: i64) -> DbResult<Option<String>> {
+        let recorded: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT binary FROM ritual_runs WHERE binary_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if recorded.is_some() {
+            return Ok(recorded);
+        }
+        let Some((name, hash)) = self
+            .conn
+            .query_row("SELECT name, hash FROM binaries WHERE id = ?1", params![id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        let taken: bool = self.conn.query_row(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM binaries WHERE name = ?1 AND id < ?2)
+                OR EXISTS (
+                    SELECT 1 FROM ritual_runs
+                    WHERE binary = ?1 AND binary_id IS NOT NULL AND binary_id <> ?2
+                )
+            "#,
+            params![name, id],
+            |row| row.get(0),
+        )?;
+        Ok(Some(match (taken, hash) {
+            (false, _) => name,
+            (true, Some(hash)) => format!("{name}@{}", hash.get(..12).unwrap_or(&hash)),
+            (true, None) => format!("{name}#{id}"),
+        }))
+    }
+
+    /// Clear the binary id of a binary's runs so they outlive it; they keep the binary name
+    /// they were recorded under. Returns the number of runs detached.
+    pub fn detach_binary_runs(&self, binary_id: i64) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "UPDATE ritual_runs SET binary_id = NULL WHERE binary_id = ?1",
+            params![binary_id],
+        )?;
+        Ok(affected)
+    }
+
+    /// Return the id of the named binary group, creating it if needed.
+    pub fn ensure_binary_group(&self, name: &str, created_at: &str) -> DbResult<i64> {
         self.conn.execute(
+            "INSERT OR IGNORE INTO binary_groups (name, created_at) VALUES (?1, ?2)",
+            params![name, created_at],
+        )?;
+        let id = self.conn.query_row(
+            "SELECT id FROM binary_groups WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// Place a binary in a group with an optional build label and build id.
+    ///
+    /// Fails with a constraint error when the group already has a member with `version`.
+    /// Returns the number of rows affected (0 when `binary_id` does not exist).
+    pub fn assign_binary_group(
+        &self,
+        binary_id: i64,
+        group_id: i64,
+        version: Option<&str>,
+        build_id: Option<&str>,
+    ) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "UPDATE binaries SET group_id = ?1, version = ?2, build_id = ?3 WHERE id = ?4",
+            params![group_id, version, build_id, binary_id],
+        )?;
+        Ok(affected)
+    }
+
+    /// List binary groups (ordered by name) with their members (ordered by binary id).
+    pub fn list_binary_groups(&self) -> DbResult<Vec<BinaryGroup>> {
+        let mut groups: Vec<(i64, BinaryGroup)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, name, created_at FROM binary_groups ORDER BY name")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    BinaryGroup { name: row.get(1)?, created_at: row.get(2)?, members: Vec::new() },
+                ))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let mut stmt = self.conn.prepare(
             r#"
-            INSERT INTO slices (name, description, default_binary, status)
-            VALUES (?1, ?2, ?3, ?4)
+            SELECT id, name, path, arch, hash, group_id, version, build_id
+            FROM binaries
+            WHERE group_id IS NOT NULL
+            ORDER BY id
             "#,
-            params![record.name, record.description, record.default_binary, record.status.to_i32(),],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(5)?,
+                BinaryGroupMember {
+                    binary_id: row.get(0)?,
+                    binary: BinaryRecord {
+                        name: row.get(1)?,
+                        path: row.get(2)?,
+                        arch: row.get(3)?,
+                        hash: row.get(4)?,
+                    },
+                    version: row.get(6)?,
+                    build_id: row.get(7)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (group_id, member) = row?;
+            if let Some((_, group)) = groups.iter_mut().find(|(id, _)| *id == group_id) {
+                group.members.push(member);
+            }
+        }
+        Ok(groups.into_iter().map(|(_, group)| group).collect())
+    }
+
+    /// Insert a slice record (and its roots) and return its row id.
+    pub fn insert_slice(&self, record: &SliceRecord) -> DbResult<i64> {
+        let tx = self.begin()?;
+        tx.execute(
+            r#"
+            INSERT INTO slices (name, description, default_binary, status, notes, namespace)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                record.name,
+                record.description,
+                record.default_binary,
+                record.status.to_i32(),
+                record.notes,
+                record.namespace,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        insert_slice_roots(&tx, id, &record.roots)?;
+        tx.commit()?;
+        Ok(id)
     }
 
-    /// List all slices (ordered by id).
+    /// List all slices, including archived ones (ordered by id).
     pub fn list_slices(&self) -> DbResult<Vec<SliceRecord>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT name, description, default_binary, status
+            SELECT name, description, default_binary, status, archived_at, id, notes, namespace
             FROM slices
             ORDER BY id
             "#,
         )?;
         let rows = stmt.query_map([], |row| {
             let status_int: i32 = row.get(3)?;
-            Ok(SliceRecord {
+            let record = SliceRecord {
                 name: row.get(0)?,
+                namespace: row.get(7)?,
                 description: row.get(1)?,
                 default_binary: row.get(2)?,
                 status: SliceStatus::from_i32(status_int),
-            })
+                archived_at: row.get(4)?,
+                roots: Vec::new(),
+                notes: row.get(6)?,
+            };
+            Ok((row.get::<_, i64>(5)?, record))
         })?;
+        let mut slices = Vec::new();
+        for row in rows {
+            slices.push(row?);
+        }
 
+        let mut roots: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT slice_id, root FROM slice_roots ORDER BY slice_id, position")?;
+        let rows =
+            stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (slice_id, root) = row?;
+            roots.entry(slice_id).or_default().push(root);
+        }
+
+        Ok(slices
+            .into_iter()
+            .map(|(id, mut record)| {
+                record.roots = roots.remove(&id).unwrap_or_default();
+                record
+            })
+            .collect())
+    }
+
+    /// Mark an active slice as archived; returns the number of rows updated (0 if unknown or
+    /// already archived). Runs and analysis rows are left untouched.
+    pub fn archive_slice(&self, name: &str, archived_at: &str) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "UPDATE slices SET archived_at = ?2 WHERE name = ?1 AND archived_at IS NULL",
+            params![name, archived_at],
+        )?;
+        Ok(affected)
+    }
+
+    /// Restore an archived slice; returns the number of rows updated (0 if not archived).
+    pub fn restore_slice(&self, name: &str) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "UPDATE slices SET archived_at = NULL WHERE name = ?1 AND archived_at IS NOT NULL",
+            params![name],
+        )?;
+        Ok(affected)
+    }
+
+    /// Replace the analyst roots and notes of a slice; returns the number of rows updated (0
+    /// when the slice does not exist).
+    pub fn set_slice_roots_and_notes(
+        &self,
+        name: &str,
+        roots: &[String],
+        notes: Option<&str>,
+    ) -> DbResult<usize> {
+        let tx = self.begin()?;
+        let affected =
+            tx.execute("UPDATE slices SET notes = ?2 WHERE name = ?1", params![name, notes])?;
+        if let Some(id) = tx
+            .query_row("SELECT id FROM slices WHERE name = ?1", params![name], |row| row.get(0))
+            .optional()?
+        {
+            tx.execute("DELETE FROM slice_roots WHERE slice_id = ?1", params![id])?;
+            insert_slice_roots(&tx, id, roots)?;
+        }
+        tx.commit()?;
+        Ok(affected)
+    }
+
+    /// Roots of a slice in the order they were added (empty for an unknown slice).
+    pub fn list_slice_roots(&self, name: &str) -> DbResult<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT r.root
+            FROM slice_roots r
+            JOIN slices s ON s.id = r.slice_id
+            WHERE s.name = ?1
+            ORDER BY r.position
+            "#,
+        )?;
+        let rows = stmt.query_map(params![name], |row| row.get(0))?;
         let mut out = Vec::new();
         for row in rows {
             out.push(row?);
@@ -130,12 +554,133 @@ impl ProjectDb {
         Ok(out)
     }
 
+    /// Append `root` to a slice's roots; returns the number of rows inserted (0 when the
+    /// slice does not exist or already has the root).
+    pub fn add_slice_root(&self, name: &str, root: &str) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            r#"
+            INSERT OR IGNORE INTO slice_roots (slice_id, position, root)
+            SELECT s.id,
+                   COALESCE((SELECT MAX(position) + 1 FROM slice_roots WHERE slice_id = s.id), 0),
+                   ?2
+            FROM slices s
+            WHERE s.name = ?1
+            "#,
+            params![name, root],
+        )?;
+        Ok(affected)
+    }
+
+    /// Remove `root` from a slice's roots; returns the number of rows deleted.
+    pub fn remove_slice_root(&self, name: &str, root: &str) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            r#"
+            DELETE FROM slice_roots
+            WHERE slice_id = (SELECT id FROM slices WHERE name = ?1) AND root = ?2
+            "#,
+            params![name, root],
+        )?;
+        Ok(affected)
+    }
+
+    /// Change a slice's status and record the transition in `slice_status_history`.
+    ///
+    /// Returns the recorded change, or `None` when the slice does not exist. Setting the
+    /// current status again is not a transition and records nothing.
+    pub fn update_slice_status(
+        &self,
+        name: &str,
+        status: SliceStatus,
+        changed_at: &str,
+        note: Option<&str>,
+    ) -> DbResult<Option<SliceStatusChange>> {
+        let tx = self.begin()?;
+        let current: Option<(i64, i32)> = tx
+            .query_row("SELECT id, status FROM slices WHERE name = ?1", params![name], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()?;
+        let Some((slice_id, from)) = current else {
+            return Ok(None);
+        };
+        let from = SliceStatus::from_i32(from);
+        if from == status {
+            return Ok(None);
+        }
+        tx.execute(
+            "UPDATE slices SET status = ?2 WHERE id = ?1",
+            params![slice_id, status.to_i32()],
+        )?;
+        tx.execute(
+            r#"
+            INSERT INTO slice_status_history (slice_id, from_status, to_status, changed_at, note)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![slice_id, from.to_i32(), status.to_i32(), changed_at, note],
+        )?;
+        tx.commit()?;
+        Ok(Some(SliceStatusChange {
+            slice: name.to_string(),
+            from,
+            to: status,
+            changed_at: changed_at.to_string(),
+            note: note.map(str::to_string),
+        }))
+    }
+
+    /// Status transitions of a slice, oldest first.
+    pub fn slice_status_history(&self, name: &str) -> DbResult<Vec<SliceStatusChange>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT s.name, h.from_status, h.to_status, h.changed_at, h.note
+            FROM slice_status_history h
+            JOIN slices s ON s.id = h.slice_id
+            WHERE s.name = ?1
+            ORDER BY h.id
+            "#,
+        )?;
+        let rows = stmt.query_map(params![name], |row| {
+            Ok(SliceStatusChange {
+                slice: row.get(0)?,
+                from: SliceStatus::from_i32(row.get(1)?),
+                to: SliceStatus::from_i32(row.get(2)?),
+                changed_at: row.get(3)?,
+                note: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Attach a note to a ritual run and return it.
+    pub fn add_run_note(&self, run_id: i64, note: &str, created_at: &str) -> DbResult<RunNote> {
+        self.conn.execute(
+            "INSERT INTO run_notes (run_id, note, created_at) VALUES (?1, ?2, ?3)",
+            params![run_id, note, created_at],
+        )?;
+        Ok(RunNote { run_id, note: note.to_string(), created_at: created_at.to_string() })
+    }
+
+    /// Notes attached to a ritual run, oldest first.
+    pub fn list_run_notes(&self, run_id: i64) -> DbResult<Vec<RunNote>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT run_id, note, created_at FROM run_notes WHERE run_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok(RunNote { run_id: row.get(0)?, note: row.get(1)?, created_at: row.get(2)? })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     /// Insert a ritual run record and return its row id.
     pub fn insert_ritual_run(&self, record: &RitualRunRecord) -> DbResult<i64> {
+        let options_json = record
+            .analysis_options
+            .as_ref()
+            .map(|options| serde_json::to_string(options).unwrap_or_default());
         self.conn.execute(
             r#"
-            INSERT INTO ritual_runs (binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            INSERT INTO ritual_runs (binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at, binary_id, deterministic, spec_path, analysis_options, duration_ms, cpu_ms, peak_rss_kb)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
             "#,
             params![
                 record.binary,
@@ -147,7 +692,14 @@ impl ProjectDb {
                 record.backend_path,
                 record.status.as_str(),
                 record.started_at,
-                record.finished_at
+                record.finished_at,
+                record.binary_id,
+                record.deterministic,
+                record.spec_path,
+                options_json,
+                record.usage.map(|u| u.duration_ms as i64),
+                record.usage.and_then(|u| u.cpu_ms).map(|ms| ms as i64),
+                record.usage.and_then(|u| u.peak_rss_kb).map(|kb| kb as i64)
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
@@ -159,34 +711,48 @@ impl ProjectDb {
         run_id: i64,
         result: &crate::services::analysis::AnalysisResult,
     ) -> DbResult<()> {
-        let tx = self.conn.unchecked_transaction()?;
+        let tx = self.begin()?;
 
         // Clear any existing rows for this run to avoid stale data on reruns.
-        tx.execute("DELETE FROM analysis_functions WHERE run_id = ?1", params![run_id])?;
-        tx.execute("DELETE FROM analysis_call_edges WHERE run_id = ?1", params![run_id])?;
-        tx.execute("DELETE FROM analysis_basic_block_edges WHERE run_id = ?1", params![run_id])?;
-        tx.execute("DELETE FROM analysis_basic_blocks WHERE run_id = ?1", params![run_id])?;
-        tx.execute("DELETE FROM analysis_evidence WHERE run_id = ?1", params![run_id])?;
-        tx.execute("DELETE FROM analysis_roots WHERE run_id = ?1", params![run_id])?;
-        tx.execute("DELETE FROM analysis_root_hits WHERE run_id = ?1", params![run_id])?;
+        for table in ANALYSIS_TABLES {
+            tx.execute(&format!("DELETE FROM {table} WHERE run_id = ?1"), params![run_id])?;
+        }
 
         {
             let mut stmt = tx.prepare(
                 r#"
-                INSERT OR REPLACE INTO analysis_functions (run_id, address, name, size)
-                VALUES (?1, ?2, ?3, ?4)
+                INSERT OR REPLACE INTO analysis_functions
+                    (run_id, address, name, size, in_slice, is_boundary, mangled_name,
+                     param_count, calling_convention, stack_cleanup, arch, fuzzy_hash, isa)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
                 "#,
             )?;
             for f in &result.functions {
-                stmt.execute(params![run_id, f.address as i64, f.name, f.size.map(|s| s as i64)])?;
+                let sig = f.signature.as_ref();
+                stmt.execute(params![
+                    run_id,
+                    f.address as i64,
+                    f.name,
+                    f.size.map(|s| s as i64),
+                    f.in_slice,
+                    f.is_boundary,
+                    f.mangled_name,
+                    sig.map(|s| s.params as i64),
+                    sig.map(|s| s.convention.as_str()),
+                    sig.and_then(|s| s.stack_cleanup).map(|b| b as i64),
+                    f.arch,
+                    f.fuzzy_hash,
+                    f.isa
+                ])?;
             }
         }
 
         {
             let mut stmt = tx.prepare(
                 r#"
-                INSERT OR REPLACE INTO analysis_call_edges (run_id, from_addr, to_addr, is_cross_slice)
-                VALUES (?1, ?2, ?3, ?4)
+                INSERT OR REPLACE INTO analysis_call_edges
+                    (run_id, from_addr, to_addr, is_cross_slice, to_name, to_import)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
                 "#,
             )?;
             for e in &result.call_edges {
@@ -194,7 +760,9 @@ impl ProjectDb {
                     run_id,
                     e.from as i64,
                     e.to as i64,
-                    if e.is_cross_slice { 1 } else { 0 }
+                    if e.is_cross_slice { 1 } else { 0 },
+                    e.to_name,
+                    e.to_import
                 ])?;
             }
         }
@@ -202,8 +770,8 @@ impl ProjectDb {
         {
             let mut stmt_block = tx.prepare(
                 r#"
-                INSERT OR REPLACE INTO analysis_basic_blocks (run_id, start, len)
-                VALUES (?1, ?2, ?3)
+                INSERT OR REPLACE INTO analysis_basic_blocks (run_id, start, byte_len, insn_count)
+                VALUES (?1, ?2, ?3, ?4)
                 "#,
             )?;
             let mut stmt_edge = tx.prepare(
@@ -213,7 +781,12 @@ impl ProjectDb {
                 "#,
             )?;
             for bb in &result.basic_blocks {
-                stmt_block.execute(params![run_id, bb.start as i64, bb.len as i64])?;
+                stmt_block.execute(params![
+                    run_id,
+                    bb.start as i64,
+                    bb.byte_len as i64,
+                    bb.insn_count.map(i64::from)
+                ])?;
                 for succ in &bb.successors {
                     stmt_edge.execute(params![
                         run_id,
@@ -228,13 +801,15 @@ impl ProjectDb {
         {
             let mut stmt = tx.prepare(
                 r#"
-                INSERT OR REPLACE INTO analysis_evidence (run_id, address, description, kind)
-                VALUES (?1, ?2, ?3, ?4)
+                INSERT OR REPLACE INTO analysis_evidence
+                    (run_id, address, description, kind, source)
+                VALUES (?1, ?2, ?3, ?4, ?5)
                 "#,
             )?;
             for ev in &result.evidence {
                 let kind_str = ev.kind.as_ref().map(evidence_kind_to_str);
-                stmt.execute(params![run_id, ev.address as i64, ev.description, kind_str])?;
+                let source = ev.source.as_ref().map(|s| s.to_string());
+                stmt.execute(params![run_id, ev.address as i64, ev.description, kind_str, source])?;
             }
         }
 
@@ -253,39 +828,156 @@ impl ProjectDb {
         {
             let mut stmt = tx.prepare(
                 r#"
-                INSERT OR REPLACE INTO analysis_root_hits (run_id, root, function_addr, matched)
-                VALUES (?1, ?2, ?3, ?4)
+                INSERT OR REPLACE INTO analysis_root_hits
+                    (run_id, root, function_addr, matched, error)
+                VALUES (?1, ?2, ?3, ?4, ?5)
                 "#,
             )?;
             if result.root_hits.is_empty() {
                 for root in &result.roots {
-                    stmt.execute(params![run_id, root, Option::<i64>::None, 0])?;
+                    stmt.execute(params![run_id, root, Option::<i64>::None, 0, None::<String>])?;
                 }
             } else {
                 for hit in &result.root_hits {
                     if hit.functions.is_empty() {
-                        stmt.execute(params![run_id, &hit.root, Option::<i64>::None, 0])?;
+                        stmt.execute(params![
+                            run_id,
+                            &hit.root,
+                            Option::<i64>::None,
+                            0,
+                            &hit.error
+                        ])?;
                     } else {
                         for func in &hit.functions {
-                            stmt.execute(params![run_id, &hit.root, *func as i64, 1])?;
+                            stmt.execute(params![
+                                run_id,
+                                &hit.root,
+                                *func as i64,
+                                1,
+                                None::<String>
+                            ])?;
                         }
                     }
                 }
             }
         }
 
+        {
+            let mut stmt_root = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO analysis_sub_slice_roots (run_id, label, idx, root)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+            )?;
+            let mut stmt_func = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO analysis_sub_slice_functions (run_id, label, function_addr)
+                VALUES (?1, ?2, ?3)
+                "#,
+            )?;
+            for sub in &result.sub_slices {
+                for (idx, root) in sub.roots.iter().enumerate() {
+                    stmt_root.execute(params![run_id, &sub.label, idx as i64, root])?;
+                }
+                for func in &sub.functions {
+                    stmt_func.execute(params![run_id, &sub.label, *func as i64])?;
+                }
+            }
+        }
+
+        write_xrefs(&tx, run_id, &result.xrefs)?;
+
+        let coverage = crate::analysis::coverage::slice_coverage(result);
+        tx.execute(
+            r#"
+            INSERT OR REPLACE INTO analysis_coverage
+                (run_id, text_bytes, function_bytes, slice_bytes, slice_functions,
+                 slice_insns, total_insns)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![
+                run_id,
+                coverage.text_bytes.map(|n| n as i64),
+                coverage.function_bytes as i64,
+                coverage.slice_bytes as i64,
+                coverage.slice_functions as i64,
+                coverage.slice_insns.map(|n| n as i64),
+                coverage.total_insns.map(|n| n as i64)
+            ],
+        )?;
+
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO function_metrics
+                    (run_id, address, insn_count, blocks, complexity, fan_in, fan_out, string_refs)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+            )?;
+            for m in crate::analysis::metrics::function_metrics(result) {
+                stmt.execute(params![
+                    run_id,
+                    m.address as i64,
+                    m.insn_count.map(|n| n as i64),
+                    m.blocks as i64,
+                    m.complexity.map(|n| n as i64),
+                    m.fan_in as i64,
+                    m.fan_out as i64,
+                    m.string_refs as i64
+                ])?;
+            }
+        }
+
         tx.commit()?;
         Ok(())
     }
 
-    /// Load the most recent run id for a given binary/ritual name.
-    pub fn latest_run_id(&self, binary: &str, ritual: &str) -> DbResult<Option<i64>> {
+    /// Append structured xrefs for a ritual run.
+    pub fn insert_xrefs(
+        &self,
+        run_id: i64,
+        xrefs: &[crate::services::analysis::XrefRecord],
+    ) -> DbResult<()> {
+        let tx = self.begin()?;
+        write_xrefs(&tx, run_id, xrefs)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// List structured xrefs for a ritual run, ordered by source then target address.
+    pub fn list_xrefs(&self, run_id: i64) -> DbResult<Vec<crate::services::analysis::XrefRecord>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT id FROM ritual_runs
-            WHERE binary = ?1 AND ritual = ?2
-            ORDER BY id DESC
-            LIMIT 1
+            SELECT from_addr, to_addr, kind, section, preview FROM analysis_xrefs
+            WHERE run_id = ?1
+            ORDER BY from_addr, to_addr, rowid
+            "#,
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok(crate::services::analysis::XrefRecord {
+                from: row.get::<_, i64>(0)? as u64,
+                to: row.get::<_, i64>(1)? as u64,
+                kind: crate::services::analysis::XrefKind::parse(&row.get::<_, String>(2)?),
+                section: row.get(3)?,
+                preview: row.get(4)?,
+            })
+        })?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        Ok(out)
+    }
+
+    /// Load the most recent run id for a given binary/ritual name.
+    pub fn latest_run_id(&self, binary: &str, ritual: &str) -> DbResult<Option<i64>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id FROM ritual_runs
+            WHERE binary = ?1 AND ritual = ?2
+            ORDER BY id DESC
+            LIMIT 1
             "#,
         )?;
         let mut rows = stmt.query(params![binary, ritual])?;
@@ -296,12 +988,63 @@ impl ProjectDb {
         }
     }
 
+    /// Addresses of the functions a run marked `in_slice`, in address order.
+    pub fn in_slice_function_addresses(&self, run_id: i64) -> DbResult<Vec<u64>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT address FROM analysis_functions
+            WHERE run_id = ?1 AND in_slice = 1
+            ORDER BY address
+            "#,
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| row.get::<_, i64>(0))?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row? as u64);
+        }
+        Ok(out)
+    }
+
+    /// Names of a run's named functions, by address.
+    pub fn function_names(&self, run_id: i64) -> DbResult<BTreeMap<u64, String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT address, name FROM analysis_functions WHERE run_id = ?1 AND name IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok((row.get::<_, i64>(0)? as u64, row.get::<_, String>(1)?))
+        })?;
+        Ok(rows.collect::<Result<BTreeMap<_, _>, _>>()?)
+    }
+
     /// Load persisted analysis result for a given binary/ritual, if present.
+    ///
+    /// This materializes every evidence and xref row; for very large runs prefer
+    /// [`ProjectDb::load_analysis_skeleton`] plus [`ProjectDb::evidence_pages`] /
+    /// [`ProjectDb::xref_pages`].
     pub fn load_analysis_result(
         &self,
         binary: &str,
         ritual: &str,
     ) -> DbResult<Option<crate::services::analysis::AnalysisResult>> {
+        let Some((run_id, mut result)) = self.load_analysis_skeleton(binary, ritual)? else {
+            return Ok(None);
+        };
+        for page in self.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+            result.evidence.extend(page?);
+        }
+        result.xrefs = self.list_xrefs(run_id)?;
+        Ok(Some(result))
+    }
+
+    /// Load the latest run id and its analysis result *without* evidence or xrefs.
+    ///
+    /// Functions, call edges, blocks, and roots are bounded by program size; evidence and
+    /// xrefs can run to millions of rows, so callers stream those separately in pages.
+    pub fn load_analysis_skeleton(
+        &self,
+        binary: &str,
+        ritual: &str,
+    ) -> DbResult<Option<(i64, crate::services::analysis::AnalysisResult)>> {
         let run_id = if let Some(id) = self.latest_run_id(binary, ritual)? {
             id
         } else {
@@ -313,7 +1056,9 @@ impl ProjectDb {
         {
             let mut stmt = self.conn.prepare(
                 r#"
-                SELECT address, name, size FROM analysis_functions
+                SELECT address, name, size, in_slice, is_boundary, mangled_name,
+                       param_count, calling_convention, stack_cleanup, arch, fuzzy_hash, isa
+                FROM analysis_functions
                 WHERE run_id = ?1
                 "#,
             )?;
@@ -322,8 +1067,17 @@ impl ProjectDb {
                     address: row.get::<_, i64>(0)? as u64,
                     name: row.get(1)?,
                     size: row.get::<_, Option<i64>>(2)?.map(|v| v as u32),
-                    in_slice: true,
-                    is_boundary: false,
+                    in_slice: row.get(3)?,
+                    is_boundary: row.get(4)?,
+                    mangled_name: row.get(5)?,
+                    signature: parse_signature(
+                        row.get::<_, Option<i64>>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                        row.get::<_, Option<i64>>(8)?,
+                    ),
+                    arch: row.get(9)?,
+                    fuzzy_hash: row.get(10)?,
+                    isa: row.get(11)?,
                 })
             })?;
             for r in rows {
@@ -336,7 +1090,8 @@ impl ProjectDb {
         {
             let mut stmt = self.conn.prepare(
                 r#"
-                SELECT from_addr, to_addr, is_cross_slice FROM analysis_call_edges
+                SELECT from_addr, to_addr, is_cross_slice, to_name, to_import
+                FROM analysis_call_edges
                 WHERE run_id = ?1
                 "#,
             )?;
@@ -345,6 +1100,8 @@ impl ProjectDb {
                     from: row.get::<_, i64>(0)? as u64,
                     to: row.get::<_, i64>(1)? as u64,
                     is_cross_slice: row.get::<_, i64>(2)? != 0,
+                    to_name: row.get(3)?,
+                    to_import: row.get::<_, i64>(4)? != 0,
                 })
             })?;
             for r in rows {
@@ -357,7 +1114,7 @@ impl ProjectDb {
         {
             let mut blocks_stmt = self.conn.prepare(
                 r#"
-                SELECT start, len FROM analysis_basic_blocks
+                SELECT start, byte_len, insn_count FROM analysis_basic_blocks
                 WHERE run_id = ?1
                 "#,
             )?;
@@ -381,38 +1138,26 @@ impl ProjectDb {
                 .collect::<Result<_, _>>()?;
 
             let rows = blocks_stmt.query_map(params![run_id], |row| {
-                Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u32))
+                Ok((
+                    row.get::<_, i64>(0)? as u64,
+                    row.get::<_, i64>(1)? as u32,
+                    row.get::<_, Option<i64>>(2)?.map(|n| n as u32),
+                ))
             })?;
             for r in rows {
-                let (start, len) = r?;
+                let (start, byte_len, insn_count) = r?;
                 let successors = edge_rows
                     .iter()
                     .filter_map(
                         |(from, edge)| if *from == start { Some(edge.clone()) } else { None },
                     )
                     .collect();
-                basic_blocks.push(crate::services::analysis::BasicBlock { start, len, successors });
-            }
-        }
-
-        // Evidence
-        let mut evidence = Vec::new();
-        {
-            let mut stmt = self.conn.prepare(
-                r#"
-                SELECT address, description, kind FROM analysis_evidence
-                WHERE run_id = ?1
-                "#,
-            )?;
-            let rows = stmt.query_map(params![run_id], |row| {
-                Ok(crate::services::analysis::EvidenceRecord {
-                    address: row.get::<_, i64>(0)? as u64,
-                    description: row.get(1)?,
-                    kind: parse_evidence_kind(row.get::<_, Option<String>>(2)?),
-                })
-            })?;
-            for r in rows {
-                evidence.push(r?);
+                basic_blocks.push(crate::services::analysis::BasicBlock {
+                    start,
+                    byte_len,
+                    insn_count,
+                    successors,
+                });
             }
         }
 
@@ -433,51 +1178,49 @@ impl ProjectDb {
         }
 
         // Root hits (optional; fallback to empty when not recorded).
-        let mut root_hits: Vec<crate::services::analysis::RootHit> = Vec::new();
+        let root_hits = self.run_root_hits(run_id)?;
+
+        // Sub-slices (labeled root groups), ordered by label.
+        let mut sub_slices: Vec<crate::services::analysis::SubSlice> = Vec::new();
         {
-            let mut stmt = self.conn.prepare(
+            let mut roots_stmt = self.conn.prepare(
                 r#"
-                SELECT root, function_addr, matched
-                FROM analysis_root_hits
+                SELECT label, root FROM analysis_sub_slice_roots
                 WHERE run_id = ?1
+                ORDER BY label, idx
                 "#,
             )?;
-            let rows = stmt.query_map(params![run_id], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, Option<i64>>(1)?,
-                    row.get::<_, i64>(2)? != 0,
-                ))
+            let rows = roots_stmt.query_map(params![run_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
             })?;
-            let mut temp: std::collections::HashMap<String, (Vec<u64>, bool)> =
-                std::collections::HashMap::new();
             for r in rows {
-                let (root, func, matched) = r?;
-                let entry = temp.entry(root).or_insert_with(|| (Vec::new(), false));
-                if let Some(f) = func {
-                    entry.0.push(f as u64);
-                    entry.1 = entry.1 || matched;
-                } else if matched {
-                    entry.1 = true;
+                let (label, root) = r?;
+                match sub_slices.last_mut() {
+                    Some(last) if last.label == label => last.roots.push(root),
+                    _ => sub_slices.push(crate::services::analysis::SubSlice {
+                        label,
+                        roots: vec![root],
+                        functions: Vec::new(),
+                    }),
                 }
             }
-            for (root, (functions, matched_flag)) in temp {
-                let functions_sorted = {
-                    let mut f = functions;
-                    f.sort_unstable();
-                    f
-                };
-                if matched_flag || !functions_sorted.is_empty() {
-                    root_hits.push(crate::services::analysis::RootHit {
-                        root,
-                        functions: functions_sorted,
-                    });
-                } else {
-                    root_hits
-                        .push(crate::services::analysis::RootHit { root, functions: Vec::new() });
+
+            let mut funcs_stmt = self.conn.prepare(
+                r#"
+                SELECT label, function_addr FROM analysis_sub_slice_functions
+                WHERE run_id = ?1
+                ORDER BY label, function_addr
+                "#,
+            )?;
+            let rows = funcs_stmt.query_map(params![run_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })?;
+            for r in rows {
+                let (label, addr) = r?;
+                if let Some(sub) = sub_slices.iter_mut().find(|s| s.label == label) {
+                    sub.functions.push(addr);
                 }
             }
-            root_hits.sort_by(|a, b| a.root.cmp(&b.root));
         }
 
         let (backend_version, backend_path) = self.conn.query_row(
@@ -485,42 +1228,212 @@ impl ProjectDb {
             params![run_id],
             |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?)),
         )?;
+        let text_bytes = self.load_run_coverage(run_id)?.and_then(|c| c.text_bytes);
+
+        Ok(Some((
+            run_id,
+            crate::services::analysis::AnalysisResult {
+                functions,
+                call_edges,
+                evidence: Vec::new(),
+                basic_blocks,
+                roots,
+                root_hits,
+                sub_slices,
+                backend_version,
+                backend_path,
+                xrefs: Vec::new(),
+                text_bytes,
+            },
+        )))
+    }
 
-        Ok(Some(crate::services::analysis::AnalysisResult {
-            functions,
-            call_edges,
-            evidence,
-            basic_blocks,
-            roots,
-            root_hits,
-            backend_version,
-            backend_path,
-        }))
+    /// A run's root hits, ordered by root (empty for runs that recorded none).
+    pub fn run_root_hits(&self, run_id: i64) -> DbResult<Vec<crate::services::analysis::RootHit>> {
+        let mut root_hits: Vec<crate::services::analysis::RootHit> = Vec::new();
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT root, function_addr, matched, error
+            FROM analysis_root_hits
+            WHERE run_id = ?1
+            "#,
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                row.get::<_, i64>(2)? != 0,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+        let mut temp: std::collections::HashMap<String, (Vec<u64>, bool, Option<String>)> =
+            std::collections::HashMap::new();
+        for r in rows {
+            let (root, func, matched, error) = r?;
+            let entry = temp.entry(root).or_insert_with(|| (Vec::new(), false, None));
+            if error.is_some() {
+                entry.2 = error;
+            }
+            if let Some(f) = func {
+                entry.0.push(f as u64);
+                entry.1 = entry.1 || matched;
+            } else if matched {
+                entry.1 = true;
+            }
+        }
+        for (root, (mut functions, _matched, error)) in temp {
+            functions.sort_unstable();
+            root_hits.push(crate::services::analysis::RootHit { root, functions, error });
+        }
+        root_hits.sort_by(|a, b| a.root.cmp(&b.root));
+        Ok(root_hits)
     }
-    /// List ritual runs, optionally filtered by binary name.
-    pub fn list_ritual_runs(&self, binary: Option<&str>) -> DbResult<Vec<RitualRunRecord>> {
-        fn map_run(row: &rusqlite::Row<'_>) -> rusqlite::Result<RitualRunRecord> {
-            Ok(RitualRunRecord {
-                binary: row.get(0)?,
-                ritual: row.get(1)?,
-                spec_hash: row.get(2)?,
-                binary_hash: row.get(3)?,
-                backend: row.get(4)?,
-                backend_version: row.get(5).ok(),
-                backend_path: row.get(6).ok(),
-                status: {
-                    let s: String = row.get(7)?;
-                    s.parse::<RitualRunStatusString>()?.0
+
+    /// Slice coverage recorded for a run (`None` for runs persisted before coverage was).
+    pub fn load_run_coverage(
+        &self,
+        run_id: i64,
+    ) -> DbResult<Option<crate::analysis::coverage::SliceCoverage>> {
+        let coverage = self
+            .conn
+            .query_row(
+                r#"
+                SELECT text_bytes, function_bytes, slice_bytes, slice_functions,
+                       slice_insns, total_insns
+                FROM analysis_coverage
+                WHERE run_id = ?1
+                "#,
+                params![run_id],
+                |row| {
+                    Ok(crate::analysis::coverage::SliceCoverage {
+                        text_bytes: row.get::<_, Option<i64>>(0)?.map(|n| n as u64),
+                        function_bytes: row.get::<_, i64>(1)? as u64,
+                        slice_bytes: row.get::<_, i64>(2)? as u64,
+                        slice_functions: row.get::<_, i64>(3)? as usize,
+                        slice_insns: row.get::<_, Option<i64>>(4)?.map(|n| n as u64),
+                        total_insns: row.get::<_, Option<i64>>(5)?.map(|n| n as u64),
+                    })
                 },
-                started_at: row.get(8)?,
-                finished_at: row.get(9)?,
+            )
+            .optional()?;
+        Ok(coverage)
+    }
+
+    /// Function metrics recorded for a run, in address order (empty for runs persisted
+    /// before metrics were).
+    pub fn load_function_metrics(
+        &self,
+        run_id: i64,
+    ) -> DbResult<Vec<crate::analysis::metrics::FunctionMetrics>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT address, insn_count, blocks, complexity, fan_in, fan_out, string_refs
+            FROM function_metrics
+            WHERE run_id = ?1
+            ORDER BY address
+            "#,
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok(crate::analysis::metrics::FunctionMetrics {
+                address: row.get::<_, i64>(0)? as u64,
+                insn_count: row.get::<_, Option<i64>>(1)?.map(|n| n as u64),
+                blocks: row.get::<_, i64>(2)? as u64,
+                complexity: row.get::<_, Option<i64>>(3)?.map(|n| n as u64),
+                fan_in: row.get::<_, i64>(4)? as u64,
+                fan_out: row.get::<_, i64>(5)? as u64,
+                string_refs: row.get::<_, i64>(6)? as u64,
             })
-        }
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Count a run's functions, call edges, evidence, and xrefs without loading them.
+    pub fn run_counts(&self, run_id: i64) -> DbResult<RunCounts> {
+        let count = |sql: &str| -> DbResult<usize> {
+            let n: i64 = self.conn.query_row(sql, params![run_id], |row| row.get(0))?;
+            Ok(n as usize)
+        };
+        Ok(RunCounts {
+            functions: count("SELECT COUNT(*) FROM analysis_functions WHERE run_id = ?1")?,
+            in_slice_functions: count(
+                "SELECT COUNT(*) FROM analysis_functions WHERE run_id = ?1 AND in_slice = 1",
+            )?,
+            call_edges: count("SELECT COUNT(*) FROM analysis_call_edges WHERE run_id = ?1")?,
+            evidence: count("SELECT COUNT(*) FROM analysis_evidence WHERE run_id = ?1")?,
+            xrefs: count("SELECT COUNT(*) FROM analysis_xrefs WHERE run_id = ?1")?,
+        })
+    }
+
+    /// A run's functions matching `filter`, by address, with annotation names from the run's
+    /// binary hash; `total` counts the matches before paging.
+    pub fn list_functions(&self, run_id: i64, filter: &FunctionFilter) -> DbResult<FunctionPage> {
+        let from = r#"
+            FROM analysis_functions f
+            LEFT JOIN function_annotations a
+                ON a.address = f.address
+                AND a.binary_hash = (SELECT binary_hash FROM ritual_runs WHERE id = ?1)
+            WHERE f.run_id = ?1
+                AND (?2 = 0 OR f.in_slice = 1)
+                AND (?3 IS NULL OR f.size >= ?3)
+                AND (?4 IS NULL
+                    OR instr(lower(f.name), lower(?4)) > 0
+                    OR instr(lower(a.name), lower(?4)) > 0)
+        "#;
+        let min_size = filter.min_size.map(i64::from);
+        let name = filter.name_contains.as_deref();
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) {from}"),
+            params![run_id, filter.in_slice, min_size, name],
+            |row| row.get(0),
+        )?;
+        // SQLite reads a negative LIMIT as no limit.
+        let limit = filter.limit.map_or(-1, |n| n as i64);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT f.address, f.name, f.size, f.in_slice, f.is_boundary, a.name {from} \
+             ORDER BY f.address LIMIT ?5 OFFSET ?6"
+        ))?;
+        let rows = stmt.query_map(
+            params![run_id, filter.in_slice, min_size, name, limit, filter.offset as i64],
+            |row| {
+                Ok(FunctionListing {
+                    address: row.get::<_, i64>(0)? as u64,
+                    name: row.get(1)?,
+                    size: row.get::<_, Option<i64>>(2)?.map(|s| s as u32),
+                    in_slice: row.get(3)?,
+                    is_boundary: row.get(4)?,
+                    annotation: row.get(5)?,
+                })
+            },
+        )?;
+        Ok(FunctionPage { total: total as usize, functions: rows.collect::<Result<_, _>>()? })
+    }
+
+    /// Page through a run's evidence in insertion order, `page_size` rows per query.
+    pub fn evidence_pages(&self, run_id: i64, page_size: usize) -> EvidencePages<'_> {
+        EvidencePages::new(self, run_id, None, page_size)
+    }
+
+    /// Page through a run's evidence with `start <= address < end`, ordered by address.
+    pub fn evidence_pages_in_range(
+        &self,
+        run_id: i64,
+        start: u64,
+        end: u64,
+        page_size: usize,
+    ) -> EvidencePages<'_> {
+        EvidencePages::new(self, run_id, Some((start, end)), page_size)
+    }
 
+    /// Page through a run's xrefs in the same order as [`ProjectDb::list_xrefs`].
+    pub fn xref_pages(&self, run_id: i64, page_size: usize) -> XrefPages<'_> {
+        XrefPages { db: self, run_id, after: None, page_size: page_size.max(1), done: false }
+    }
+    /// List ritual runs, optionally filtered by binary name.
+    pub fn list_ritual_runs(&self, binary: Option<&str>) -> DbResult<Vec<RitualRunRecord>> {
         let mut stmt = if binary.is_some() {
             self.conn.prepare(
                 r#"
-                SELECT binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at
+                SELECT binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at, binary_id, superseded_by, deterministic, spec_path, analysis_options, duration_ms, cpu_ms, peak_rss_kb
                 FROM ritual_runs
                 WHERE binary = ?1
                 ORDER BY id
@@ -529,7 +1442,7 @@ impl ProjectDb {
         } else {
             self.conn.prepare(
                 r#"
-                SELECT binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at
+                SELECT binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at, binary_id, superseded_by, deterministic, spec_path, analysis_options, duration_ms, cpu_ms, peak_rss_kb
                 FROM ritual_runs
                 ORDER BY id
                 "#,
@@ -549,15 +1462,87 @@ impl ProjectDb {
         Ok(out)
     }
 
-    /// Update status (and optionally finished_at) for a ritual run.
+    /// List ritual runs together with their row ids (ordered by id), optionally filtered by
+    /// binary name.
     ///
-    /// Returns the number of rows affected.
+    /// Row ids tell apart runs that share a binary and ritual name.
+    pub fn list_ritual_runs_with_ids(
+        &self,
+        binary: Option<&str>,
+    ) -> DbResult<Vec<(i64, RitualRunRecord)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT binary, ritual, spec_hash, binary_hash, backend, backend_version, backend_path, status, started_at, finished_at, binary_id, superseded_by, deterministic, spec_path, analysis_options, duration_ms, cpu_ms, peak_rss_kb, id
+            FROM ritual_runs
+            WHERE ?1 IS NULL OR binary = ?1
+            ORDER BY id
+            "#,
+        )?;
+        let rows = stmt.query_map(params![binary], |row| Ok((row.get(18)?, map_run(row)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Mark runs as `purged` (see `gc-outputs`); returns the number of rows updated.
+    pub fn mark_runs_purged(&self, run_ids: &[i64]) -> DbResult<usize> {
+        let tx = self.begin()?;
+        let mut affected = 0;
+        {
+            let mut stmt = tx.prepare("UPDATE ritual_runs SET status = 'purged' WHERE id = ?1")?;
+            for run_id in run_ids {
+                affected += stmt.execute(params![run_id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(affected)
+    }
+
+    /// Update status (and optionally finished_at) of the latest run of a binary and ritual;
+    /// earlier runs under the same names keep theirs (see
+    /// [`ProjectDb::update_all_ritual_run_statuses`]).
+    ///
+    /// Returns the number of rows affected (0 or 1).
     pub fn update_ritual_run_status(
         &self,
         binary: &str,
         ritual: &str,
         status: &str,
         finished_at: Option<&str>,
+    ) -> DbResult<usize> {
+        match self.latest_run_id(binary, ritual)? {
+            Some(run_id) => self.update_run_status(run_id, status, finished_at),
+            None => Ok(0),
+        }
+    }
+
+    /// Update status (and optionally finished_at) of one run by row id.
+    ///
+    /// Returns the number of rows affected (0 when `run_id` does not exist).
+    pub fn update_run_status(
+        &self,
+        run_id: i64,
+        status: &str,
+        finished_at: Option<&str>,
+    ) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            r#"
+            UPDATE ritual_runs
+            SET status = ?1, finished_at = COALESCE(?2, finished_at)
+            WHERE id = ?3
+            "#,
+            params![status, finished_at, run_id],
+        )?;
+        Ok(affected)
+    }
+
+    /// Update status (and optionally finished_at) of every run of a binary and ritual.
+    ///
+    /// Returns the number of rows affected.
+    pub fn update_all_ritual_run_statuses(
+        &self,
+        binary: &str,
+        ritual: &str,
+        status: &str,
+        finished_at: Option<&str>,
     ) -> DbResult<usize> {
         let affected = if let Some(finish) = finished_at {
             self.conn.execute(
@@ -578,7 +1563,742 @@ impl ProjectDb {
                 params![status, binary, ritual],
             )?
         };
-        Ok(affected)
+        Ok(affected)
+    }
+
+    /// Insert or update the annotation for a function.
+    ///
+    /// `None` fields keep any previously stored value, so a name and a note can be set
+    /// independently.
+    pub fn upsert_function_annotation(&self, annotation: &FunctionAnnotation) -> DbResult<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO function_annotations (binary_hash, address, name, note, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (binary_hash, address) DO UPDATE SET
+                name = COALESCE(excluded.name, name),
+                note = COALESCE(excluded.note, note),
+                updated_at = excluded.updated_at
+            "#,
+            params![
+                annotation.binary_hash,
+                annotation.address as i64,
+                annotation.name,
+                annotation.note,
+                annotation.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// List annotations for a binary hash, ordered by address.
+    pub fn list_function_annotations(
+        &self,
+        binary_hash: &str,
+    ) -> DbResult<Vec<FunctionAnnotation>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT binary_hash, address, name, note, updated_at
+            FROM function_annotations
+            WHERE binary_hash = ?1
+            ORDER BY address
+            "#,
+        )?;
+        let rows = stmt.query_map(params![binary_hash], |row| {
+            Ok(FunctionAnnotation {
+                binary_hash: row.get(0)?,
+                address: row.get::<_, i64>(1)? as u64,
+                name: row.get(2)?,
+                note: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Remove the annotation for a function. Returns the number of rows affected.
+    pub fn delete_function_annotation(&self, binary_hash: &str, address: u64) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "DELETE FROM function_annotations WHERE binary_hash = ?1 AND address = ?2",
+            params![binary_hash, address as i64],
+        )?;
+        Ok(affected)
+    }
+
+    /// Insert or replace the imported name of a function; imports of the same address
+    /// overwrite each other.
+    pub fn upsert_imported_symbol(&self, symbol: &ImportedSymbol) -> DbResult<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO imported_symbols (binary_hash, address, name, source, imported_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (binary_hash, address) DO UPDATE SET
+                name = excluded.name,
+                source = excluded.source,
+                imported_at = excluded.imported_at
+            "#,
+            params![
+                symbol.binary_hash,
+                symbol.address as i64,
+                symbol.name,
+                symbol.source,
+                symbol.imported_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// List imported symbols for a binary hash, ordered by address.
+    pub fn list_imported_symbols(&self, binary_hash: &str) -> DbResult<Vec<ImportedSymbol>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT binary_hash, address, name, source, imported_at
+            FROM imported_symbols
+            WHERE binary_hash = ?1
+            ORDER BY address
+            "#,
+        )?;
+        let rows = stmt.query_map(params![binary_hash], |row| {
+            Ok(ImportedSymbol {
+                binary_hash: row.get(0)?,
+                address: row.get::<_, i64>(1)? as u64,
+                name: row.get(2)?,
+                source: row.get(3)?,
+                imported_at: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Remove every imported symbol of a binary hash. Returns the number of rows affected.
+    pub fn delete_imported_symbols(&self, binary_hash: &str) -> DbResult<usize> {
+        let affected = self
+            .conn
+            .execute("DELETE FROM imported_symbols WHERE binary_hash = ?1", params![binary_hash])?;
+        Ok(affected)
+    }
+
+    /// Associate a PDB with a binary, replacing any earlier association.
+    pub fn set_binary_pdb(&self, pdb: &BinaryPdb) -> DbResult<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO binary_pdbs (binary_id, path, guid, age, associated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (binary_id) DO UPDATE SET
+                path = excluded.path,
+                guid = excluded.guid,
+                age = excluded.age,
+                associated_at = excluded.associated_at
+            "#,
+            params![pdb.binary_id, pdb.path, pdb.guid, pdb.age, pdb.associated_at],
+        )?;
+        Ok(())
+    }
+
+    /// PDB associated with a binary, if any.
+    pub fn binary_pdb(&self, binary_id: i64) -> DbResult<Option<BinaryPdb>> {
+        let pdb = self
+            .conn
+            .query_row(
+                r#"
+                SELECT binary_id, path, guid, age, associated_at
+                FROM binary_pdbs
+                WHERE binary_id = ?1
+                "#,
+                params![binary_id],
+                |row| {
+                    Ok(BinaryPdb {
+                        binary_id: row.get(0)?,
+                        path: row.get(1)?,
+                        guid: row.get(2)?,
+                        age: row.get(3)?,
+                        associated_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(pdb)
+    }
+
+    /// Make `baseline.run_id` the baseline of its binary and ritual, replacing an earlier one.
+    pub fn set_run_baseline(&self, baseline: &RunBaseline) -> DbResult<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO run_baselines (binary_id, ritual, run_id, set_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (binary_id, ritual) DO UPDATE SET
+                run_id = excluded.run_id,
+                set_at = excluded.set_at
+            "#,
+            params![baseline.binary_id, baseline.ritual, baseline.run_id, baseline.set_at],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the baseline of a binary and ritual; returns the number of rows deleted.
+    pub fn clear_run_baseline(&self, binary_id: i64, ritual: &str) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "DELETE FROM run_baselines WHERE binary_id = ?1 AND ritual = ?2",
+            params![binary_id, ritual],
+        )?;
+        Ok(affected)
+    }
+
+    /// Every baseline, ordered by binary id and ritual.
+    pub fn list_run_baselines(&self) -> DbResult<Vec<RunBaseline>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT rb.binary_id, b.name, rb.ritual, rb.run_id, rb.set_at
+            FROM run_baselines rb
+            JOIN binaries b ON b.id = rb.binary_id
+            ORDER BY rb.binary_id, rb.ritual
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(RunBaseline {
+                binary_id: row.get(0)?,
+                binary: row.get(1)?,
+                ritual: row.get(2)?,
+                run_id: row.get(3)?,
+                set_at: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Baseline that runs of `ritual` on `binary_id` are compared against: the binary's own,
+    /// else the most recently set one of another binary in its group.
+    pub fn run_baseline(&self, binary_id: i64, ritual: &str) -> DbResult<Option<RunBaseline>> {
+        let baseline = self
+            .conn
+            .query_row(
+                r#"
+                SELECT rb.binary_id, b.name, rb.ritual, rb.run_id, rb.set_at
+                FROM run_baselines rb
+                JOIN binaries b ON b.id = rb.binary_id
+                WHERE rb.ritual = ?2
+                  AND (rb.binary_id = ?1 OR b.group_id = (SELECT group_id FROM binaries WHERE id = ?1))
+                ORDER BY rb.binary_id = ?1 DESC, rb.set_at DESC
+                LIMIT 1
+                "#,
+                params![binary_id, ritual],
+                |row| {
+                    Ok(RunBaseline {
+                        binary_id: row.get(0)?,
+                        binary: row.get(1)?,
+                        ritual: row.get(2)?,
+                        run_id: row.get(3)?,
+                        set_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(baseline)
+    }
+
+    /// Store the header facts read from a binary, replacing earlier ones.
+    pub fn set_binary_info(&self, binary_id: i64, info: &BinaryInfo) -> DbResult<()> {
+        let json = serde_json::to_string(info).unwrap_or_default();
+        self.conn.execute(
+            r#"
+            INSERT INTO binary_info (binary_id, info) VALUES (?1, ?2)
+            ON CONFLICT (binary_id) DO UPDATE SET info = excluded.info
+            "#,
+            params![binary_id, json],
+        )?;
+        Ok(())
+    }
+
+    /// Header facts of a binary, if they were read when it was added.
+    ///
+    /// Rows this version cannot parse read as `None`.
+    pub fn binary_info(&self, binary_id: i64) -> DbResult<Option<BinaryInfo>> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT info FROM binary_info WHERE binary_id = ?1",
+                params![binary_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Insert or update a slice membership row.
+    ///
+    /// Returns the number of rows written (0 when the slice does not exist).
+    pub fn upsert_slice_member(&self, member: &SliceMember) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            r#"
+            INSERT INTO slice_members (slice_id, binary_hash, address, source, excluded, updated_at)
+            SELECT id, ?2, ?3, ?4, ?5, ?6 FROM slices WHERE name = ?1
+            ON CONFLICT (slice_id, binary_hash, address, source) DO UPDATE SET
+                excluded = excluded.excluded,
+                updated_at = excluded.updated_at
+            "#,
+            params![
+                member.slice,
+                member.binary_hash,
+                member.address as i64,
+                member.source.as_str(),
+                member.excluded,
+                member.updated_at
+            ],
+        )?;
+        Ok(affected)
+    }
+
+    /// Remove a slice membership row. Returns the number of rows affected.
+    pub fn delete_slice_member(
+        &self,
+        slice: &str,
+        binary_hash: &str,
+        address: u64,
+        source: MemberSource,
+    ) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            r#"
+            DELETE FROM slice_members
+            WHERE slice_id = (SELECT id FROM slices WHERE name = ?1)
+              AND binary_hash = ?2 AND address = ?3 AND source = ?4
+            "#,
+            params![slice, binary_hash, address as i64, source.as_str()],
+        )?;
+        Ok(affected)
+    }
+
+    /// Replace the analysis-derived members of a slice for one build.
+    pub fn replace_analysis_slice_members(
+        &self,
+        slice: &str,
+        binary_hash: &str,
+        addresses: &[u64],
+        updated_at: &str,
+    ) -> DbResult<()> {
+        let tx = self.begin()?;
+        tx.execute(
+            r#"
+            DELETE FROM slice_members
+            WHERE slice_id = (SELECT id FROM slices WHERE name = ?1)
+              AND binary_hash = ?2 AND source = ?3
+            "#,
+            params![slice, binary_hash, MemberSource::Analysis.as_str()],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO slice_members
+                    (slice_id, binary_hash, address, source, excluded, updated_at)
+                SELECT id, ?2, ?3, ?4, 0, ?5 FROM slices WHERE name = ?1
+                "#,
+            )?;
+            for address in addresses {
+                stmt.execute(params![
+                    slice,
+                    binary_hash,
+                    *address as i64,
+                    MemberSource::Analysis.as_str(),
+                    updated_at
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// List membership rows for a slice (optionally one build), ordered by hash, address,
+    /// then source.
+    pub fn list_slice_members(
+        &self,
+        slice: &str,
+        binary_hash: Option<&str>,
+    ) -> DbResult<Vec<SliceMember>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT s.name, m.binary_hash, m.address, m.source, m.excluded, m.updated_at
+            FROM slice_members m
+            JOIN slices s ON s.id = m.slice_id
+            WHERE s.name = ?1 AND (?2 IS NULL OR m.binary_hash = ?2)
+            ORDER BY m.binary_hash, m.address, m.source
+            "#,
+        )?;
+        let rows = stmt.query_map(params![slice, binary_hash], |row| {
+            let source: String = row.get(3)?;
+            Ok(SliceMember {
+                slice: row.get(0)?,
+                binary_hash: row.get(1)?,
+                address: row.get::<_, i64>(2)? as u64,
+                source: MemberSource::parse(&source).unwrap_or(MemberSource::Manual),
+                excluded: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Snapshot the members of every slice run `run_id` feeds: its in-slice functions with
+    /// the slice's manual overrides for the build applied. A slice is fed by runs of the
+    /// ritual named after it; one namespaced by a binary group only by runs of its binaries.
+    ///
+    /// `slice_members` only keeps the latest members per build; snapshots keep each run's,
+    /// so `slice-history` can replay them. Returns the number of slices snapshotted.
+    pub fn record_slice_snapshots(&self, run_id: i64) -> DbResult<usize> {
+        let run: Option<(String, String, String)> = self
+            .conn
+            .query_row(
+                r#"
+                SELECT r.ritual, r.binary, COALESCE(r.binary_hash, b.hash, r.binary)
+                FROM ritual_runs r
+                LEFT JOIN binaries b
+                    ON b.id = r.binary_id OR (r.binary_id IS NULL AND b.name = r.binary)
+                WHERE r.id = ?1
+                LIMIT 1
+                "#,
+                params![run_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((ritual, binary, build)) = run else {
+            return Ok(0);
+        };
+        let groups = self.list_binary_groups()?;
+        let slices: Vec<SliceRecord> = self
+            .list_slices()?
+            .into_iter()
+            .filter(|s| {
+                s.ritual_name() == ritual
+                    && s.namespace
+                        .as_ref()
+                        .and_then(|ns| groups.iter().find(|g| g.name == *ns))
+                        .is_none_or(|g| g.members.iter().any(|m| m.binary.name == binary))
+            })
+            .collect();
+        if slices.is_empty() {
+            return Ok(0);
+        }
+
+        let derived = self.in_slice_function_addresses(run_id)?;
+        let mut snapshots = Vec::new();
+        for slice in &slices {
+            let mut members: std::collections::BTreeSet<u64> = derived.iter().copied().collect();
+            let manual = self.list_slice_members(&slice.name, Some(&build))?;
+            for m in manual.into_iter().filter(|m| m.source == MemberSource::Manual) {
+                if m.excluded {
+                    members.remove(&m.address);
+                } else {
+                    members.insert(m.address);
+                }
+            }
+            let members: Vec<u64> = members.into_iter().collect();
+            snapshots.push((&slice.name, serde_json::to_string(&members).unwrap_or_default()));
+        }
+
+        let tx = self.begin()?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO slice_snapshots (slice_id, run_id, members)
+                SELECT id, ?2, ?3 FROM slices WHERE name = ?1
+                "#,
+            )?;
+            for (slice, members) in &snapshots {
+                stmt.execute(params![slice, run_id, members])?;
+            }
+        }
+        tx.commit()?;
+        Ok(snapshots.len())
+    }
+
+    /// Member snapshots of a slice by run id (see [`ProjectDb::record_slice_snapshots`]).
+    pub fn slice_snapshots(&self, slice: &str) -> DbResult<BTreeMap<i64, Vec<u64>>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT n.run_id, n.members
+            FROM slice_snapshots n
+            JOIN slices s ON s.id = n.slice_id
+            WHERE s.name = ?1
+            "#,
+        )?;
+        let rows = stmt.query_map(params![slice], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut out = BTreeMap::new();
+        for row in rows {
+            let (run_id, members) = row?;
+            out.insert(run_id, serde_json::from_str(&members).unwrap_or_default());
+        }
+        Ok(out)
+    }
+
+    /// Link a run to the run that replaced it.
+    ///
+    /// Returns the number of rows affected (0 when `run_id` does not exist).
+    pub fn mark_run_superseded(&self, run_id: i64, superseded_by: i64) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            "UPDATE ritual_runs SET superseded_by = ?1 WHERE id = ?2",
+            params![superseded_by, run_id],
+        )?;
+        Ok(affected)
+    }
+
+    /// Mark runs of a binary whose recorded `binary_hash` differs from `current_hash` as
+    /// `outdated`. Runs are matched by binary id, or by name for legacy runs without one;
+    /// runs without a recorded hash are left alone.
+    ///
+    /// Returns the number of runs newly marked.
+    pub fn mark_runs_outdated(
+        &self,
+        binary_id: i64,
+        binary: &str,
+        current_hash: &str,
+    ) -> DbResult<usize> {
+        let affected = self.conn.execute(
+            r#"
+            UPDATE ritual_runs SET status = 'outdated'
+            WHERE (binary_id = ?1 OR (binary_id IS NULL AND binary = ?2))
+              AND binary_hash IS NOT NULL AND binary_hash != ?3
+              AND status != 'outdated'
+            "#,
+            params![binary_id, binary, current_hash],
+        )?;
+        Ok(affected)
+    }
+
+    /// Row id of the named slice.
+    pub fn slice_id(&self, name: &str) -> DbResult<Option<i64>> {
+        let id = self
+            .conn
+            .query_row("SELECT id FROM slices WHERE name = ?1", params![name], |row| row.get(0))
+            .optional()?;
+        Ok(id)
+    }
+
+    /// Attach `tag` to a slice, binary, or run (by row id), creating the tag on first use.
+    ///
+    /// Returns `false` when the target already had the tag.
+    pub fn add_tag(
+        &self,
+        kind: TagKind,
+        target_id: i64,
+        tag: &str,
+        created_at: &str,
+    ) -> DbResult<bool> {
+        let tx = self.begin()?;
+        tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", params![tag])?;
+        let added = tx.execute(
+            r#"
+            INSERT OR IGNORE INTO taggings (tag_id, kind, target_id, created_at)
+            SELECT id, ?2, ?3, ?4 FROM tags WHERE name = ?1
+            "#,
+            params![tag, kind.as_str(), target_id, created_at],
+        )?;
+        tx.commit()?;
+        Ok(added > 0)
+    }
+
+    /// Detach `tag` from a target; a tag left on nothing is dropped. Returns the number of
+    /// taggings removed (0 when the target did not have the tag).
+    pub fn remove_tag(&self, kind: TagKind, target_id: i64, tag: &str) -> DbResult<usize> {
+        let tx = self.begin()?;
+        let removed = tx.execute(
+            r#"
+            DELETE FROM taggings
+            WHERE kind = ?2 AND target_id = ?3
+              AND tag_id = (SELECT id FROM tags WHERE name = ?1)
+            "#,
+            params![tag, kind.as_str(), target_id],
+        )?;
+        tx.execute("DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM taggings)", [])?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Taggings, optionally restricted to one kind of target and/or one tag, ordered by tag,
+    /// kind, and target. Taggings whose target no longer exists are skipped.
+    pub fn list_taggings(
+        &self,
+        kind: Option<TagKind>,
+        tag: Option<&str>,
+    ) -> DbResult<Vec<Tagging>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT t.name, g.kind, g.target_id, g.created_at,
+                   COALESCE(s.name, b.name, r.binary), r.ritual
+            FROM taggings g
+            JOIN tags t ON t.id = g.tag_id
+            LEFT JOIN slices s ON g.kind = 'slice' AND s.id = g.target_id
+            LEFT JOIN binaries b ON g.kind = 'binary' AND b.id = g.target_id
+            LEFT JOIN ritual_runs r ON g.kind = 'run' AND r.id = g.target_id
+            WHERE COALESCE(s.id, b.id, r.id) IS NOT NULL
+              AND (?1 IS NULL OR g.kind = ?1)
+              AND (?2 IS NULL OR t.name = ?2)
+            ORDER BY t.name, g.kind, g.target_id
+            "#,
+        )?;
+        let rows = stmt.query_map(params![kind.map(|k| k.as_str()), tag], |row| {
+            let kind: String = row.get(1)?;
+            Ok(Tagging {
+                tag: row.get(0)?,
+                kind: TagKind::parse(&kind).unwrap_or(TagKind::Run),
+                target_id: row.get(2)?,
+                created_at: row.get(3)?,
+                name: row.get(4)?,
+                ritual: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+/// Keyset-paginated reader over `analysis_evidence` for one run.
+///
+/// Each `next()` issues one bounded query, so memory stays flat however many rows a run has.
+pub struct EvidencePages<'a> {
+    db: &'a ProjectDb,
+    run_id: i64,
+    range: Option<(u64, u64)>,
+    /// `(address, rowid)` of the last row returned.
+    after: Option<(i64, i64)>,
+    page_size: usize,
+    done: bool,
+}
+
+impl<'a> EvidencePages<'a> {
+    fn new(db: &'a ProjectDb, run_id: i64, range: Option<(u64, u64)>, page_size: usize) -> Self {
+        Self { db, run_id, range, after: None, page_size: page_size.max(1), done: false }
+    }
+
+    fn fetch(&self) -> DbResult<Vec<(i64, crate::services::analysis::EvidenceRecord)>> {
+        let map_row = |row: &rusqlite::Row<'_>| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                crate::services::analysis::EvidenceRecord {
+                    address: row.get::<_, i64>(1)? as u64,
+                    description: row.get(2)?,
+                    kind: parse_evidence_kind(row.get::<_, Option<String>>(3)?),
+                    source: row
+                        .get::<_, Option<String>>(4)?
+                        .map(|s| crate::services::analysis::EvidenceSource::parse(&s)),
+                    run_id: Some(self.run_id),
+                },
+            ))
+        };
+        let (after_addr, after_rowid) = self.after.unwrap_or((i64::MIN, i64::MIN));
+        let limit = self.page_size as i64;
+        let rows = match self.range {
+            None => {
+                let mut stmt = self.db.conn.prepare_cached(
+                    r#"
+                    SELECT rowid, address, description, kind, source FROM analysis_evidence
+                    WHERE run_id = ?1 AND rowid > ?2
+                    ORDER BY rowid
+                    LIMIT ?3
+                    "#,
+                )?;
+                let rows = stmt.query_map(params![self.run_id, after_rowid, limit], map_row)?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            }
+            Some((start, end)) => {
+                let mut stmt = self.db.conn.prepare_cached(
+                    r#"
+                    SELECT rowid, address, description, kind, source FROM analysis_evidence
+                    WHERE run_id = ?1 AND address >= ?2 AND address < ?3
+                      AND (address, rowid) > (?4, ?5)
+                    ORDER BY address, rowid
+                    LIMIT ?6
+                    "#,
+                )?;
+                let rows = stmt.query_map(
+                    params![self.run_id, start as i64, end as i64, after_addr, after_rowid, limit],
+                    map_row,
+                )?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        Ok(rows)
+    }
+}
+
+impl Iterator for EvidencePages<'_> {
+    type Item = DbResult<Vec<crate::services::analysis::EvidenceRecord>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let rows = match self.fetch() {
+            Ok(rows) => rows,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+        self.done = rows.len() < self.page_size;
+        let (rowid, last) = rows.last()?;
+        self.after = Some((last.address as i64, *rowid));
+        Some(Ok(rows.into_iter().map(|(_, e)| e).collect()))
+    }
+}
+
+/// Keyset-paginated reader over `analysis_xrefs` for one run (see [`EvidencePages`]).
+pub struct XrefPages<'a> {
+    db: &'a ProjectDb,
+    run_id: i64,
+    /// `(from_addr, to_addr, rowid)` of the last row returned.
+    after: Option<(i64, i64, i64)>,
+    page_size: usize,
+    done: bool,
+}
+
+impl XrefPages<'_> {
+    fn fetch(&self) -> DbResult<Vec<(i64, crate::services::analysis::XrefRecord)>> {
+        let (after_from, after_to, after_rowid) =
+            self.after.unwrap_or((i64::MIN, i64::MIN, i64::MIN));
+        let mut stmt = self.db.conn.prepare_cached(
+            r#"
+            SELECT rowid, from_addr, to_addr, kind, section, preview FROM analysis_xrefs
+            WHERE run_id = ?1 AND (from_addr, to_addr, rowid) > (?2, ?3, ?4)
+            ORDER BY from_addr, to_addr, rowid
+            LIMIT ?5
+            "#,
+        )?;
+        let rows = stmt.query_map(
+            params![self.run_id, after_from, after_to, after_rowid, self.page_size as i64],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    crate::services::analysis::XrefRecord {
+                        from: row.get::<_, i64>(1)? as u64,
+                        to: row.get::<_, i64>(2)? as u64,
+                        kind: crate::services::analysis::XrefKind::parse(&row.get::<_, String>(3)?),
+                        section: row.get(4)?,
+                        preview: row.get(5)?,
+                    },
+                ))
+            },
+        )?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+impl Iterator for XrefPages<'_> {
+    type Item = DbResult<Vec<crate::services::analysis::XrefRecord>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let rows = match self.fetch() {
+            Ok(rows) => rows,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+        self.done = rows.len() < self.page_size;
+        let (rowid, last) = rows.last()?;
+        self.after = Some((last.from as i64, last.to as i64, *rowid));
+        Some(Ok(rows.into_iter().map(|(_, x)| x).collect()))
     }
 }
 
@@ -597,6 +2317,42 @@ impl ProjectDb {
 /// - 7: add evidence kind column
 /// - 8: add analysis_roots table for persisted roots per run
 /// - 9: add analysis_root_hits table for per-root matches
+/// - 10: add binary_id column to ritual_runs (resolved binary identity)
+/// - 11: add analysis_sub_slice_roots/functions tables for labeled root groups
+/// - 12: add archived_at column to slices (soft-delete/archival)
+/// - 13: add analysis_xrefs table for structured cross-references
+/// - 14: add address indexes on evidence/xrefs for paged (streaming) reads
+/// - 15: add superseded_by column to ritual_runs (watch re-runs)
+/// - 16: add function_annotations table (human names/notes keyed by binary hash)
+/// - 17: add in_slice/is_boundary columns to analysis_functions
+/// - 18: add slice_members table (manual and analysis-derived slice membership)
+/// - 19: add binary_groups table and group_id/version/build_id columns to binaries
+/// - 20: add mangled_name column to analysis_functions (original symbol of demangled names)
+/// - 21: add error column to analysis_root_hits (why a root resolved to nothing)
+/// - 22: add param_count/calling_convention/stack_cleanup columns to analysis_functions
+/// - 23: add slice_status_history table (recorded slice status transitions)
+/// - 24: add arch column to analysis_functions (architecture slice of fat binaries)
+/// - 25: add deterministic column to ritual_runs (reproducible `--deterministic` runs)
+/// - 26: add tags/taggings tables (tags on slices, binaries, and runs)
+/// - 27: add fuzzy_hash column to analysis_functions (cross-build function matching)
+/// - 28: split analysis_basic_blocks.len into byte_len and insn_count
+/// - 29: add analysis_coverage table (slice coverage against the binary's executable bytes)
+/// - 30: add isa column to analysis_functions (ARM/Thumb mode of 32-bit ARM functions)
+/// - 31: add run_notes table (timestamped findings attached to ritual runs)
+/// - 32: add spec_path column to ritual_runs (spec file a run came from)
+/// - 33: add imported_symbols table (symbol-map names keyed by binary hash)
+/// - 34: add binary_pdbs table (PDB path, GUID, and age associated with a binary)
+/// - 35: add analysis_options column to ritual_runs (traversal options as JSON)
+/// - 36: add binary_info table (format, arch, build id, ... read from a binary's headers)
+/// - 37: add roots and notes columns to slices (analyst sections synced from slice docs)
+/// - 38: add run_baselines table (baseline run per binary and ritual for regression gating)
+/// - 39: add to_name and to_import columns to analysis_call_edges (resolved call targets)
+/// - 40: add function_metrics table (per-function size, complexity, and call fan-in/out)
+/// - 41: add namespace column to slices (slices scoped to a binary group or label)
+/// - 42: add source column to analysis_evidence (backend/pass provenance)
+/// - 43: add slice_snapshots table (slice members as of each run, for `slice-history`)
+/// - 44: add duration_ms, cpu_ms, and peak_rss_kb columns to ritual_runs (run resource usage)
+/// - 45: add slice_roots table (a slice's defining roots; replaces the slices.roots column)
 fn apply_migrations(conn: &Connection) -> DbResult<()> {
     let mut current_version = current_schema_version(conn)?;
 
@@ -782,11 +2538,561 @@ fn apply_migrations(conn: &Connection) -> DbResult<()> {
             COMMIT;
             "#,
         )?;
+        current_version = 9;
+    }
+
+    if current_version < 10 {
+        let has_column = column_exists(conn, "ritual_runs", "binary_id")?;
+        if !has_column {
+            conn.execute("ALTER TABLE ritual_runs ADD COLUMN binary_id INTEGER;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 10;", [])?;
+        current_version = 10;
+    }
+
+    if current_version < 11 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS analysis_sub_slice_roots (
+                run_id INTEGER NOT NULL,
+                label  TEXT NOT NULL,
+                idx    INTEGER NOT NULL,
+                root   TEXT NOT NULL,
+                PRIMARY KEY(run_id, label, idx)
+            );
+            CREATE TABLE IF NOT EXISTS analysis_sub_slice_functions (
+                run_id        INTEGER NOT NULL,
+                label         TEXT NOT NULL,
+                function_addr INTEGER NOT NULL,
+                PRIMARY KEY(run_id, label, function_addr)
+            );
+            PRAGMA user_version = 11;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 11;
+    }
+
+    if current_version < 12 {
+        let has_column = column_exists(conn, "slices", "archived_at")?;
+        if !has_column {
+            conn.execute("ALTER TABLE slices ADD COLUMN archived_at TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 12;", [])?;
+        current_version = 12;
+    }
+
+    if current_version < 13 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS analysis_xrefs (
+                run_id    INTEGER NOT NULL,
+                from_addr INTEGER NOT NULL,
+                to_addr   INTEGER NOT NULL,
+                kind      TEXT NOT NULL,
+                section   TEXT,
+                preview   TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_analysis_xrefs_run_to ON analysis_xrefs(run_id, to_addr);
+            PRAGMA user_version = 13;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 13;
+    }
+
+    if current_version < 14 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE INDEX IF NOT EXISTS idx_analysis_evidence_run_addr
+                ON analysis_evidence(run_id, address);
+            CREATE INDEX IF NOT EXISTS idx_analysis_xrefs_run_from
+                ON analysis_xrefs(run_id, from_addr, to_addr);
+            PRAGMA user_version = 14;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 14;
+    }
+
+    if current_version < 15 {
+        let has_column = column_exists(conn, "ritual_runs", "superseded_by")?;
+        if !has_column {
+            conn.execute("ALTER TABLE ritual_runs ADD COLUMN superseded_by INTEGER;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 15;", [])?;
+        current_version = 15;
+    }
+
+    if current_version < 16 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS function_annotations (
+                binary_hash TEXT NOT NULL,
+                address     INTEGER NOT NULL,
+                name        TEXT,
+                note        TEXT,
+                updated_at  TEXT NOT NULL,
+                PRIMARY KEY (binary_hash, address)
+            );
+            PRAGMA user_version = 16;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 16;
+    }
+
+    if current_version < 17 {
+        // Rows written before v17 were always loaded as in-slice, non-boundary.
+        if !column_exists(conn, "analysis_functions", "in_slice")? {
+            conn.execute(
+                "ALTER TABLE analysis_functions ADD COLUMN in_slice INTEGER NOT NULL DEFAULT 1;",
+                [],
+            )?;
+        }
+        if !column_exists(conn, "analysis_functions", "is_boundary")? {
+            conn.execute(
+                "ALTER TABLE analysis_functions ADD COLUMN is_boundary INTEGER NOT NULL DEFAULT 0;",
+                [],
+            )?;
+        }
+        conn.execute("PRAGMA user_version = 17;", [])?;
+        current_version = 17;
+    }
+
+    if current_version < 18 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS slice_members (
+                slice_id    INTEGER NOT NULL REFERENCES slices(id) ON DELETE CASCADE,
+                binary_hash TEXT NOT NULL,
+                address     INTEGER NOT NULL,
+                source      TEXT NOT NULL,
+                excluded    INTEGER NOT NULL DEFAULT 0,
+                updated_at  TEXT NOT NULL,
+                PRIMARY KEY (slice_id, binary_hash, address, source)
+            );
+            PRAGMA user_version = 18;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 18;
+    }
+
+    if current_version < 19 {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS binary_groups (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                name       TEXT NOT NULL UNIQUE,
+                created_at TEXT NOT NULL
+            );
+            "#,
+        )?;
+        for (column, ty) in [("group_id", "INTEGER"), ("version", "TEXT"), ("build_id", "TEXT")] {
+            if !column_exists(conn, "binaries", column)? {
+                conn.execute(&format!("ALTER TABLE binaries ADD COLUMN {column} {ty};"), [])?;
+            }
+        }
+        conn.execute_batch(
+            r#"
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_binaries_group_version
+                ON binaries(group_id, version);
+            PRAGMA user_version = 19;
+            "#,
+        )?;
+        current_version = 19;
+    }
+
+    if current_version < 20 {
+        if !column_exists(conn, "analysis_functions", "mangled_name")? {
+            conn.execute("ALTER TABLE analysis_functions ADD COLUMN mangled_name TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 20;", [])?;
+        current_version = 20;
+    }
+
+    if current_version < 21 {
+        if !column_exists(conn, "analysis_root_hits", "error")? {
+            conn.execute("ALTER TABLE analysis_root_hits ADD COLUMN error TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 21;", [])?;
+        current_version = 21;
+    }
+
+    if current_version < 22 {
+        for (column, ty) in [
+            ("param_count", "INTEGER"),
+            ("calling_convention", "TEXT"),
+            ("stack_cleanup", "INTEGER"),
+        ] {
+            if !column_exists(conn, "analysis_functions", column)? {
+                conn.execute(
+                    &format!("ALTER TABLE analysis_functions ADD COLUMN {column} {ty};"),
+                    [],
+                )?;
+            }
+        }
+        conn.execute("PRAGMA user_version = 22;", [])?;
+        current_version = 22;
+    }
+
+    if current_version < 23 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS slice_status_history (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                slice_id    INTEGER NOT NULL REFERENCES slices(id) ON DELETE CASCADE,
+                from_status INTEGER NOT NULL,
+                to_status   INTEGER NOT NULL,
+                changed_at  TEXT NOT NULL,
+                note        TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_slice_status_history_slice
+                ON slice_status_history(slice_id, id);
+            PRAGMA user_version = 23;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 23;
+    }
+
+    if current_version < 24 {
+        if !column_exists(conn, "analysis_functions", "arch")? {
+            conn.execute("ALTER TABLE analysis_functions ADD COLUMN arch TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 24;", [])?;
+        current_version = 24;
+    }
+
+    if current_version < 25 {
+        if !column_exists(conn, "ritual_runs", "deterministic")? {
+            conn.execute(
+                "ALTER TABLE ritual_runs ADD COLUMN deterministic INTEGER NOT NULL DEFAULT 0;",
+                [],
+            )?;
+        }
+        conn.execute("PRAGMA user_version = 25;", [])?;
+        current_version = 25;
+    }
+
+    if current_version < 26 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS tags (
+                id   INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE
+            );
+            CREATE TABLE IF NOT EXISTS taggings (
+                tag_id     INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+                kind       TEXT NOT NULL,
+                target_id  INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (tag_id, kind, target_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_taggings_target ON taggings(kind, target_id);
+            PRAGMA user_version = 26;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 26;
+    }
+
+    if current_version < 27 {
+        if !column_exists(conn, "analysis_functions", "fuzzy_hash")? {
+            conn.execute("ALTER TABLE analysis_functions ADD COLUMN fuzzy_hash TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 27;", [])?;
+        current_version = 27;
+    }
+
+    if current_version < 28 {
+        // `len` held instruction counts for Capstone runs and byte sizes for the others.
+        if !column_exists(conn, "analysis_basic_blocks", "byte_len")? {
+            conn.execute_batch(
+                r#"
+                BEGIN;
+                ALTER TABLE analysis_basic_blocks RENAME COLUMN len TO byte_len;
+                ALTER TABLE analysis_basic_blocks ADD COLUMN insn_count INTEGER;
+                UPDATE analysis_basic_blocks SET insn_count = byte_len, byte_len = 0
+                WHERE run_id IN (SELECT id FROM ritual_runs WHERE backend = 'capstone');
+                COMMIT;
+                "#,
+            )?;
+        }
+        conn.execute("PRAGMA user_version = 28;", [])?;
+        current_version = 28;
+    }
+
+    if current_version < 29 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS analysis_coverage (
+                run_id          INTEGER PRIMARY KEY,
+                text_bytes      INTEGER,
+                function_bytes  INTEGER NOT NULL,
+                slice_bytes     INTEGER NOT NULL,
+                slice_functions INTEGER NOT NULL,
+                slice_insns     INTEGER,
+                total_insns     INTEGER
+            );
+            PRAGMA user_version = 29;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 29;
+    }
+
+    if current_version < 30 {
+        if !column_exists(conn, "analysis_functions", "isa")? {
+            conn.execute("ALTER TABLE analysis_functions ADD COLUMN isa TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 30;", [])?;
+        current_version = 30;
+    }
+
+    if current_version < 31 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS run_notes (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                run_id     INTEGER NOT NULL REFERENCES ritual_runs(id) ON DELETE CASCADE,
+                note       TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_run_notes_run ON run_notes(run_id, id);
+            PRAGMA user_version = 31;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 31;
+    }
+
+    if current_version < 32 {
+        if !column_exists(conn, "ritual_runs", "spec_path")? {
+            conn.execute("ALTER TABLE ritual_runs ADD COLUMN spec_path TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 32;", [])?;
+        current_version = 32;
+    }
+
+    if current_version < 33 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS imported_symbols (
+                binary_hash TEXT NOT NULL,
+                address     INTEGER NOT NULL,
+                name        TEXT NOT NULL,
+                source      TEXT NOT NULL,
+                imported_at TEXT NOT NULL,
+                PRIMARY KEY (binary_hash, address)
+            );
+            PRAGMA user_version = 33;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 33;
+    }
+
+    if current_version < 34 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS binary_pdbs (
+                binary_id     INTEGER PRIMARY KEY,
+                path          TEXT NOT NULL,
+                guid          TEXT NOT NULL,
+                age           INTEGER NOT NULL,
+                associated_at TEXT NOT NULL
+            );
+            PRAGMA user_version = 34;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 34;
+    }
+
+    if current_version < 35 {
+        if !column_exists(conn, "ritual_runs", "analysis_options")? {
+            conn.execute("ALTER TABLE ritual_runs ADD COLUMN analysis_options TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 35;", [])?;
+        current_version = 35;
+    }
+
+    if current_version < 36 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS binary_info (
+                binary_id INTEGER PRIMARY KEY,
+                info      TEXT NOT NULL
+            );
+            PRAGMA user_version = 36;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 36;
+    }
+
+    if current_version < 37 {
+        if !column_exists(conn, "slices", "roots")? {
+            conn.execute("ALTER TABLE slices ADD COLUMN roots TEXT;", [])?;
+        }
+        if !column_exists(conn, "slices", "notes")? {
+            conn.execute("ALTER TABLE slices ADD COLUMN notes TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 37;", [])?;
+        current_version = 37;
+    }
+
+    if current_version < 38 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS run_baselines (
+                binary_id INTEGER NOT NULL,
+                ritual    TEXT NOT NULL,
+                run_id    INTEGER NOT NULL,
+                set_at    TEXT NOT NULL,
+                PRIMARY KEY (binary_id, ritual)
+            );
+            PRAGMA user_version = 38;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 38;
+    }
+
+    if current_version < 39 {
+        if !column_exists(conn, "analysis_call_edges", "to_name")? {
+            conn.execute("ALTER TABLE analysis_call_edges ADD COLUMN to_name TEXT;", [])?;
+        }
+        if !column_exists(conn, "analysis_call_edges", "to_import")? {
+            conn.execute(
+                "ALTER TABLE analysis_call_edges ADD COLUMN to_import INTEGER NOT NULL DEFAULT 0;",
+                [],
+            )?;
+        }
+        conn.execute("PRAGMA user_version = 39;", [])?;
+        current_version = 39;
+    }
+
+    if current_version < 40 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS function_metrics (
+                run_id      INTEGER NOT NULL,
+                address     INTEGER NOT NULL,
+                insn_count  INTEGER,
+                blocks      INTEGER NOT NULL,
+                complexity  INTEGER,
+                fan_in      INTEGER NOT NULL,
+                fan_out     INTEGER NOT NULL,
+                string_refs INTEGER NOT NULL,
+                PRIMARY KEY (run_id, address)
+            );
+            PRAGMA user_version = 40;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 40;
+    }
+
+    if current_version < 41 {
+        if !column_exists(conn, "slices", "namespace")? {
+            conn.execute("ALTER TABLE slices ADD COLUMN namespace TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 41;", [])?;
+        current_version = 41;
+    }
+
+    if current_version < 42 {
+        if !column_exists(conn, "analysis_evidence", "source")? {
+            conn.execute("ALTER TABLE analysis_evidence ADD COLUMN source TEXT;", [])?;
+        }
+        conn.execute("PRAGMA user_version = 42;", [])?;
+        current_version = 42;
+    }
+
+    if current_version < 43 {
+        conn.execute_batch(
+            r#"
+            BEGIN;
+            CREATE TABLE IF NOT EXISTS slice_snapshots (
+                slice_id INTEGER NOT NULL,
+                run_id   INTEGER NOT NULL,
+                members  TEXT NOT NULL,
+                PRIMARY KEY (slice_id, run_id)
+            );
+            PRAGMA user_version = 43;
+            COMMIT;
+            "#,
+        )?;
+        current_version = 43;
+    }
+
+    if current_version < 44 {
+        for column in ["duration_ms", "cpu_ms", "peak_rss_kb"] {
+            if !column_exists(conn, "ritual_runs", column)? {
+                conn.execute(&format!("ALTER TABLE ritual_runs ADD COLUMN {column} INTEGER;"), [])?;
+            }
+        }
+        conn.execute("PRAGMA user_version = 44;", [])?;
+        current_version = 44;
+    }
+
+    if current_version < 45 {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS slice_roots (
+                slice_id INTEGER NOT NULL REFERENCES slices(id) ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                root     TEXT NOT NULL,
+                PRIMARY KEY (slice_id, root)
+            );
+            "#,
+        )?;
+        // Move the roots synced from slice docs (a JSON list per slice since v37) into rows.
+        let legacy: Vec<(i64, String)> = {
+            let mut stmt = tx.prepare("SELECT id, roots FROM slices WHERE roots IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        for (id, json) in legacy {
+            let roots: Vec<String> = serde_json::from_str(&json).unwrap_or_default();
+            insert_slice_roots(&tx, id, &roots)?;
+        }
+        tx.execute_batch("UPDATE slices SET roots = NULL; PRAGMA user_version = 45;")?;
+        tx.commit()?;
     }
 
     Ok(())
 }
 
+/// Insert `roots` as the roots of slice `slice_id`, in order (duplicates are dropped).
+fn insert_slice_roots(conn: &Connection, slice_id: i64, roots: &[String]) -> DbResult<()> {
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO slice_roots (slice_id, position, root) VALUES (?1, ?2, ?3)",
+    )?;
+    for (position, root) in roots.iter().enumerate() {
+        stmt.execute(params![slice_id, position as i64, root])?;
+    }
+    Ok(())
+}
+
 /// Read the SQLite schema version from `PRAGMA user_version`.
 fn current_schema_version(conn: &Connection) -> DbResult<i32> {
     let version: i32 = conn.query_row("PRAGMA user_version;", [], |row| row.get(0))?;
@@ -805,6 +3111,20 @@ fn column_exists(conn: &Connection, table: &str, column: &str) -> DbResult<bool>
     Ok(false)
 }
 
+fn parse_signature(
+    params: Option<i64>,
+    convention: Option<String>,
+    stack_cleanup: Option<i64>,
+) -> Option<crate::services::signature::FunctionSignature> {
+    Some(crate::services::signature::FunctionSignature {
+        params: params? as u32,
+        convention: crate::services::signature::CallingConvention::from_name(
+            convention.as_deref().unwrap_or_default(),
+        ),
+        stack_cleanup: stack_cleanup.map(|b| b as u32),
+    })
+}
+
 fn parse_edge_kind(kind: &str) -> crate::services::analysis::BlockEdgeKind {
     match kind {
         "Jump" | "jump" => crate::services::analysis::BlockEdgeKind::Jump,
@@ -816,13 +3136,32 @@ fn parse_edge_kind(kind: &str) -> crate::services::analysis::BlockEdgeKind {
     }
 }
 
-fn evidence_kind_to_str(kind: &crate::services::analysis::EvidenceKind) -> &'static str {
-    match kind {
-        crate::services::analysis::EvidenceKind::String => "string",
-        crate::services::analysis::EvidenceKind::Import => "import",
-        crate::services::analysis::EvidenceKind::Call => "call",
-        crate::services::analysis::EvidenceKind::Other => "other",
+fn write_xrefs(
+    conn: &Connection,
+    run_id: i64,
+    xrefs: &[crate::services::analysis::XrefRecord],
+) -> DbResult<()> {
+    let mut stmt = conn.prepare(
+        r#"
+        INSERT INTO analysis_xrefs (run_id, from_addr, to_addr, kind, section, preview)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+    )?;
+    for x in xrefs {
+        stmt.execute(params![
+            run_id,
+            x.from as i64,
+            x.to as i64,
+            x.kind.as_str(),
+            x.section,
+            x.preview
+        ])?;
     }
+    Ok(())
+}
+
+fn evidence_kind_to_str(kind: &crate::services::analysis::EvidenceKind) -> &'static str {
+    kind.as_str()
 }
 
 fn parse_evidence_kind(kind: Option<String>) -> Option<crate::services::analysis::EvidenceKind> {
@@ -836,6 +3175,38 @@ fn parse_evidence_kind(kind: Option<String>) -> Option<crate::services::analysis
 }
 
 /// Helper for parsing status strings into RitualRunStatus with better errors.
+/// Map a `ritual_runs` row selected in [`ProjectDb::list_ritual_runs`] column order.
+fn map_run(row: &rusqlite::Row<'_>) -> rusqlite::Result<RitualRunRecord> {
+    Ok(RitualRunRecord {
+        binary: row.get(0)?,
+        binary_id: row.get(10)?,
+        ritual: row.get(1)?,
+        spec_hash: row.get(2)?,
+        binary_hash: row.get(3)?,
+        backend: row.get(4)?,
+        backend_version: row.get(5).ok(),
+        backend_path: row.get(6).ok(),
+        status: {
+            let s: String = row.get(7)?;
+            s.parse::<RitualRunStatusString>()?.0
+        },
+        started_at: row.get(8)?,
+        finished_at: row.get(9)?,
+        superseded_by: row.get(11)?,
+        deterministic: row.get(12)?,
+        spec_path: row.get(13)?,
+        // Stored as JSON; options this version cannot read are reported as unknown.
+        analysis_options: row
+            .get::<_, Option<String>>(14)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        usage: row.get::<_, Option<i64>>(15)?.map(|duration_ms| RunUsage {
+            duration_ms: duration_ms as u64,
+            cpu_ms: row.get::<_, Option<i64>>(16).ok().flatten().map(|ms| ms as u64),
+            peak_rss_kb: row.get::<_, Option<i64>>(17).ok().flatten().map(|kb| kb as u64),
+        }),
+    })
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct RitualRunStatusString(pub RitualRunStatus);
 
@@ -850,6 +3221,8 @@ impl std::str::FromStr for RitualRunStatusString {
             "failed" => RitualRunStatus::Failed,
             "canceled" => RitualRunStatus::Canceled,
             "stubbed" => RitualRunStatus::Stubbed,
+            "outdated" => RitualRunStatus::Outdated,
+            "purged" => RitualRunStatus::Purged,
             _other => {
                 return Err(rusqlite::Error::InvalidQuery);
             }
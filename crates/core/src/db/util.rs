@@ -3,8 +3,12 @@ use std::path::PathBuf;
 use anyhow::{Context, Result};
 
 use crate::db::{ProjectConfig, ProjectDb, ProjectLayout};
+use crate::services::config::resolve_project_config;
 
 /// Load the project config JSON from disk for a given layout.
+///
+/// This is the project file alone; commands that write the config back load it this way.
+/// [`load_resolved_project_config`] merges in the user config.
 pub fn load_project_config(layout: &ProjectLayout) -> Result<ProjectConfig> {
     let config_json = std::fs::read_to_string(&layout.project_config_path).with_context(|| {
         format!("Failed to read project config at {}", layout.project_config_path.display())
@@ -14,16 +18,47 @@ pub fn load_project_config(layout: &ProjectLayout) -> Result<ProjectConfig> {
     Ok(config)
 }
 
-/// Resolve the DB path (respecting relative/absolute config) and open a ProjectDb.
+/// Load the project config with the user config merged under it (see
+/// [`crate::services::config`]).
+pub fn load_resolved_project_config(layout: &ProjectLayout) -> Result<ProjectConfig> {
+    Ok(resolve_project_config(load_project_config(layout)?)?)
+}
+
+/// Layout of the project at `root` with the directory overrides of its config applied (see
+/// [`ProjectLayout::with_config`]); the default layout when there is no readable config yet.
+pub fn load_project_layout(root: impl AsRef<std::path::Path>) -> ProjectLayout {
+    let layout = ProjectLayout::new(root);
+    match load_project_config(&layout) {
+        Ok(config) => layout.with_config(&config),
+        Err(_) => layout,
+    }
+}
+
+/// Write the project config JSON for a given layout.
+pub fn save_project_config(layout: &ProjectLayout, config: &ProjectConfig) -> Result<()> {
+    let json = serde_json::to_string_pretty(config)?;
+    std::fs::write(&layout.project_config_path, json).with_context(|| {
+        format!("Failed to write project config at {}", layout.project_config_path.display())
+    })
+}
+
+/// Resolve the DB path (respecting relative/absolute config) and open a ProjectDb. The
+/// returned config has the user config merged in.
 pub fn open_project_db(layout: &ProjectLayout) -> Result<(ProjectConfig, PathBuf, ProjectDb)> {
-    let config = load_project_config(layout)?;
+    let config = load_resolved_project_config(layout)?;
+    let db_path = project_db_path(layout, &config);
+    let db = ProjectDb::open(&db_path)
+        .with_context(|| format!("Failed to open project database at {}", db_path.display()))?;
+    Ok((config, db_path, db))
+}
+
+/// Path of the project DB `config` points at: `db.path`, relative to the project root
+/// unless absolute.
+pub fn project_db_path(layout: &ProjectLayout, config: &ProjectConfig) -> PathBuf {
     let config_db_path = std::path::Path::new(&config.db.path);
-    let db_path = if config_db_path.is_absolute() {
+    if config_db_path.is_absolute() {
         config_db_path.to_path_buf()
     } else {
         layout.root.join(config_db_path)
-    };
-    let db = ProjectDb::open(&db_path)
-        .with_context(|| format!("Failed to open project database at {}", db_path.display()))?;
-    Ok((config, db_path, db))
+    }
 }
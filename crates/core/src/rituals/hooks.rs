@@ -0,0 +1,246 @@
+//! Post-run hooks: external commands configured with `{placeholder}` arguments.
+//!
+//! A command is split into words the way a shell would (whitespace, `'…'` and `"…"` quoting)
+//! but runs directly rather than through a shell, so substituted paths need no quoting. Commands
+//! run from the project root. Hook output is appended to `<run dir>/hooks.log`; a failing hook
+//! is reported but never changes the status of the run.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use crate::db::{ProjectConfig, RitualRunStatus};
+use crate::rituals::run::PreparedRun;
+use crate::services::report::REPORT_FILE;
+
+/// File in the run directory collecting the output of post-run hooks.
+pub const HOOKS_LOG_FILE: &str = "hooks.log";
+
+/// File in the run directory written by `open-run` for the external tool.
+pub const ANNOTATIONS_FILE: &str = "annotations.json";
+
+/// Split a command line into words, honoring single and double quotes.
+pub fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if let Some(q) = quote {
+        return Err(anyhow!("Unterminated {} quote in command: {}", q, command));
+    }
+    if in_word {
+        words.push(word);
+    }
+    if words.is_empty() {
+        return Err(anyhow!("Empty command"));
+    }
+    Ok(words)
+}
+
+/// Join words back into a command line, quoting words [`split_command`] would otherwise split.
+pub fn display_command(argv: &[String]) -> String {
+    argv.iter()
+        .map(|word| {
+            if !word.is_empty()
+                && !word.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"')
+            {
+                word.clone()
+            } else if word.contains('\'') {
+                format!("\"{}\"", word)
+            } else {
+                format!("'{}'", word)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split `template` into words and replace `{name}` placeholders with `values`
+/// (`{{` and `}}` write literal braces). Unknown placeholders are an error.
+pub fn expand_command(template: &str, values: &BTreeMap<&str, String>) -> Result<Vec<String>> {
+    split_command(template)?
+        .into_iter()
+        .map(|word| expand_word(&word, values))
+        .collect::<Result<Vec<_>>>()
+        .with_context(|| format!("Invalid command template: {}", template))
+}
+
+fn expand_word(word: &str, values: &BTreeMap<&str, String>) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = word;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{").or_else(|| tail.strip_prefix("}}")) {
+            out.push_str(&tail[..1]);
+            rest = after;
+            continue;
+        }
+        let end = tail
+            .strip_prefix('{')
+            .and_then(|t| t.find('}'))
+            .ok_or_else(|| anyhow!("unbalanced brace in '{}' (use {{{{ or }}}})", word))?;
+        let name = &tail[1..end + 1];
+        let value = values.get(name).ok_or_else(|| {
+            let known: Vec<String> = values.keys().map(|k| format!("{{{}}}", k)).collect();
+            anyhow!("unknown placeholder {{{}}} (available: {})", name, known.join(", "))
+        })?;
+        out.push_str(value);
+        rest = &tail[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// How one post-run hook ended.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookOutcome {
+    pub command: String,
+    /// Exit code; `None` when the hook could not be started or was killed by a signal.
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HookOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Run the configured `hooks.post_run` commands for a run that ended with `status`.
+///
+/// Placeholders: `{root}`, `{run_dir}`, `{binary}`, `{binary_path}`, `{ritual}`, `{status}`,
+/// `{spec}`, and `{report}`. Failures are logged as warnings and returned, never raised.
+pub fn run_post_run_hooks(
+    config: &ProjectConfig,
+    prepared: &PreparedRun,
+    status: &RitualRunStatus,
+) -> Vec<HookOutcome> {
+    if config.hooks.post_run.is_empty() {
+        return Vec::new();
+    }
+    let values = run_placeholders(
+        &prepared.root,
+        &prepared.run_dir,
+        &prepared.request.binary_name,
+        &prepared.request.binary_path,
+        &prepared.request.ritual_name,
+        status,
+    );
+    let log_path = prepared.run_dir.join(HOOKS_LOG_FILE);
+    config
+        .hooks
+        .post_run
+        .iter()
+        .map(|template| {
+            let outcome = run_hook(template, &values, &prepared.root, &log_path);
+            if !outcome.succeeded() {
+                tracing::warn!(
+                    "post_run hook '{}' failed: {}",
+                    outcome.command,
+                    outcome.error.clone().unwrap_or_else(|| match outcome.exit_code {
+                        Some(code) => format!("exit code {}", code),
+                        None => "terminated by a signal".into(),
+                    })
+                );
+            }
+            outcome
+        })
+        .collect()
+}
+
+/// Values of the post-run hook placeholders for a run of `ritual` on `binary`.
+pub fn run_placeholders<'a>(
+    root: &Path,
+    run_dir: &Path,
+    binary: &str,
+    binary_path: &Path,
+    ritual: &str,
+    status: &RitualRunStatus,
+) -> BTreeMap<&'a str, String> {
+    BTreeMap::from([
+        ("root", root.display().to_string()),
+        ("run_dir", run_dir.display().to_string()),
+        ("binary", binary.to_string()),
+        ("binary_path", binary_path.display().to_string()),
+        ("ritual", ritual.to_string()),
+        ("status", status.as_str().to_string()),
+        ("spec", run_dir.join("spec.yaml").display().to_string()),
+        ("report", run_dir.join(REPORT_FILE).display().to_string()),
+    ])
+}
+
+fn run_hook(
+    template: &str,
+    values: &BTreeMap<&str, String>,
+    cwd: &Path,
+    log_path: &Path,
+) -> HookOutcome {
+    let failed = |command: String, err: anyhow::Error| HookOutcome {
+        command,
+        exit_code: None,
+        error: Some(format!("{:#}", err)),
+    };
+    let argv = match expand_command(template, values) {
+        Ok(argv) => argv,
+        Err(err) => return failed(template.to_string(), err),
+    };
+    let command = display_command(&argv);
+    let output = Command::new(program(cwd, &argv[0]))
+        .args(&argv[1..])
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to start {}", argv[0]));
+    let output = match output {
+        Ok(output) => output,
+        Err(err) => return failed(command, err),
+    };
+    let logged = OpenOptions::new().create(true).append(true).open(log_path).and_then(|mut log| {
+        writeln!(log, "$ {}", command)?;
+        log.write_all(&output.stdout)?;
+        log.write_all(&output.stderr)?;
+        writeln!(log, "[exit: {}]", output.status)
+    });
+    if let Err(err) = logged {
+        tracing::warn!("Failed to write {}: {}", log_path.display(), err);
+    }
+    HookOutcome { command, exit_code: output.status.code(), error: None }
+}
+
+/// Resolve a relative program path such as `./scripts/upload.sh` against the project root;
+/// bare names are looked up on `PATH`.
+pub fn program(root: &Path, name: &str) -> PathBuf {
+    let path = Path::new(name);
+    if path.is_relative() && path.components().count() > 1 {
+        root.join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
@@ -1,12 +1,18 @@
-//! Ritual DSL: configuration and execution of analysis pipelines.
+//! Ritual DSL: specs describing an analysis, and the pipeline that runs them.
 //!
-//! For now this is just a placeholder. Eventually this module will:
-//! - Define a data structure for rituals (probably serde-friendly)
-//! - Parse rituals from YAML/JSON files
-//! - Execute rituals by driving the analysis layer
+//! - [`spec`]: the schema of the YAML/JSON spec files under `rituals/`
+//! - [`vars`]: `${name}` variables filled in before a spec is parsed
+//! - [`run`]: analyzing a spec's binary, recording the run, and writing its outputs
+//! - [`hooks`]: post-run hook commands
+//!
+//! Nothing here prints; front ends (CLI, Python bindings) present the
+//! [`run::RitualRunOutcome`] themselves.
+
+pub mod hooks;
+pub mod run;
+pub mod spec;
+pub mod vars;
 
-/// Placeholder type representing a ritual identifier.
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub struct RitualId {
-    pub name: String,
-}
+pub use run::{run_ritual_spec, RitualRunOutcome, RunFlags, SpecSource};
+pub use spec::{BackendChoice, BinarySelector, RitualRoots, RitualSpec};
+pub use vars::{resolve_spec, ResolvedSpec, SpecVars};
@@ -0,0 +1,1264 @@
+//! Running a ritual spec: resolve it against its binary, analyze it with the backend chain
+//! (or its pipeline stages), record the run in the project DB, and write the run directory
+//! (`spec.yaml`, `report.json`, `graph.dot`, `run_metadata.json`, `manifest.json`).
+//!
+//! [`run_ritual_spec`] does all of it. Front ends that schedule runs themselves (batches,
+//! reruns under a new name) call the steps: [`prepare_run`], [`execute_run`],
+//! [`validate_run`], and [`write_run_outputs`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::span::EnteredSpan;
+
+use crate::analysis::baseline::{BaselineComparison, RunSnapshot};
+use crate::analysis::graph::{dot_body_with_options, DotOptions, NodeIds};
+use crate::db::{open_project_db, ProjectDb, RitualRunStatus, RunUsage};
+use crate::rituals::hooks::{run_post_run_hooks, HookOutcome, ANNOTATIONS_FILE, HOOKS_LOG_FILE};
+use crate::rituals::spec::{
+    BackendChoice, BinarySelector, OutputFormat, RawImageSpec, RitualOutputs, RitualRoots,
+    RitualSpec,
+};
+use crate::rituals::vars::{resolve_spec, ResolvedSpec, SpecVars};
+use crate::services::analysis::{
+    analyze_with_fallback, default_backend_registry, persist_failed_run, persist_run,
+    validate_result, AnalysisError, AnalysisOptions, AnalysisRequest, AnalysisResult,
+    BackendFailure, BackendRegistry, CachedRun, ResultValidation, RootGroup, RunMetadata,
+};
+use crate::services::cache::AnalysisCache;
+use crate::services::decoders::{byte_sequences, run_decoders};
+use crate::services::deterministic::{
+    canonicalize_result, normalize_path, parse_epoch, DETERMINISTIC_EPOCH,
+};
+use crate::services::image::BinaryImage;
+use crate::services::import::ghidra_xml::export_version;
+use crate::services::import::pdb::{read_pdb, PdbIdentity};
+use crate::services::loader::{pe_pdb_reference, request_layout};
+use crate::services::manifest::{hash_outputs, OutputManifest};
+use crate::services::pipeline::{
+    feed_roots, load_stage_result, save_stage_result, stage_order, PipelineState, StageStatus,
+};
+use crate::services::plugins::run_plugins;
+use crate::services::process::Deadline;
+use crate::services::report::{ReportV1, REPORT_FILE};
+use crate::services::roots::RootSpec;
+
+const DEFAULT_BACKEND_NAME: &str = "validate-only";
+
+fn default_backend_name() -> String {
+    DEFAULT_BACKEND_NAME.to_string()
+}
+
+fn default_backend_version(backend: &str) -> Option<String> {
+    match backend {
+        "validate-only" => Some("validate-only".to_string()),
+        _ => None,
+    }
+}
+
+/// Backends a run tries, in order (never empty).
+///
+/// The CLI override is used alone. Otherwise the first non-empty of: the spec's `backend`
+/// (one name or a fallback list), the config's `backend_priority`, and `default_backend`,
+/// each without the backends not compiled into this build; then the built-in preference.
+pub fn resolve_backend_chain(
+    registry: &crate::services::analysis::BackendRegistry,
+    override_backend: Option<&str>,
+    spec_backend: Option<&BackendChoice>,
+    config: &crate::db::ProjectConfig,
+) -> Vec<String> {
+    if let Some(b) = override_backend {
+        return vec![b.to_string()];
+    }
+    let available = |names: &[String]| -> Vec<String> {
+        names.iter().filter(|name| registry.get(name).is_some()).cloned().collect()
+    };
+    let chain = spec_backend
+        .map(|b| available(b.names()))
+        .filter(|chain| !chain.is_empty())
+        .or_else(|| Some(available(&config.backend_priority)).filter(|c| !c.is_empty()))
+        .or_else(|| Some(available(config.default_backend.as_slice())).filter(|c| !c.is_empty()));
+    if let Some(chain) = chain {
+        return chain;
+    }
+    // Prefer rizin if available, else capstone, else validate-only.
+    for candidate in ["rizin", "capstone", DEFAULT_BACKEND_NAME] {
+        if registry.get(candidate).is_some() {
+            return vec![candidate.to_string()];
+        }
+    }
+    vec![DEFAULT_BACKEND_NAME.to_string()]
+}
+
+/// Tool path (or, for `ghidra-xml`, the spec's export relative to the project `root`) a
+/// backend is run with.
+fn resolve_backend_path(
+    backend: &str,
+    spec: &RitualSpec,
+    root: &Path,
+    config: &crate::db::ProjectConfig,
+) -> Option<PathBuf> {
+    match backend {
+        "rizin" => config.backends.rizin.as_ref().map(PathBuf::from),
+        "ghidra" => config.backends.ghidra_headless.as_ref().map(PathBuf::from),
+        "ghidra-xml" => spec.ghidra_xml.as_ref().map(|p| root.join(p)),
+        _ => None,
+    }
+}
+
+fn resolve_backend_version(
+    backend: &str,
+    backend_path: Option<&Path>,
+    config: &crate::db::ProjectConfig,
+) -> Option<String> {
+    match backend {
+        "rizin" => config.backend_versions.rizin.clone(),
+        "ghidra" => config.backend_versions.ghidra_headless.clone(),
+        "capstone" => config.backend_versions.capstone.clone(),
+        // The export is the tool; keying the version on its contents keeps the cache honest.
+        "ghidra-xml" => {
+            backend_path.and_then(|p| fs::read(p).ok()).map(|data| export_version(&data))
+        }
+        other => default_backend_version(other),
+    }
+    .or_else(|| default_backend_version(backend))
+}
+
+fn format_backend_label(
+    backend: &str,
+    backend_version: Option<&str>,
+    backend_path: Option<&str>,
+) -> String {
+    let mut label = format!("backend: {}", backend);
+    if let Some(v) = backend_version {
+        label.push_str(&format!(" {}", v));
+    }
+    if let Some(p) = backend_path {
+        label.push_str(&format!(" @ {}", p));
+    }
+    label
+}
+
+fn render_dot(
+    result: &AnalysisResult,
+    backend_label: Option<&str>,
+    options: &DotOptions,
+) -> String {
+    let mut out = String::from("digraph G {\n  rankdir=LR;\n");
+    if let Some(label) = backend_label {
+        let safe_label = label.replace('"', "\\\"");
+        out.push_str(&format!("  label=\"{}\";\n  labelloc=top;\n", safe_label));
+    }
+    if result.functions.is_empty() && result.call_edges.is_empty() && result.basic_blocks.is_empty()
+    {
+        out.push_str("  // no graph data available\n}\n");
+        return out;
+    }
+    out.push_str(&dot_body_with_options(result, &NodeIds::new(result), options));
+    out.push_str("}\n");
+    out
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RitualRunMetadata {
+    pub ritual: String,
+    pub binary: String,
+    pub spec_hash: String,
+    #[serde(default)]
+    pub binary_id: Option<i64>,
+    pub binary_hash: Option<String>,
+    #[serde(default = "default_backend_name")]
+    pub backend: String,
+    #[serde(default)]
+    pub backend_version: Option<String>,
+    #[serde(default)]
+    pub backend_path: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
+    pub status: RitualRunStatus,
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Why a `failed`/`canceled` run stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Backends of the spec's fallback chain that failed before `backend` succeeded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_backends: Vec<BackendFailure>,
+    /// Spec file the run came from (project-relative when inside the project).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_path: Option<String>,
+    /// Hash the binary was registered with, when it differs from `binary_hash` (the file
+    /// actually analyzed, with `--allow-hash-mismatch`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registered_binary_hash: Option<String>,
+}
+
+/// One ritual spec resolved against its binary, ready to execute.
+pub struct PreparedRun {
+    /// Normalized spec (backend and outputs filled in), as written to `spec.yaml`.
+    pub spec: RitualSpec,
+    pub binary_id: i64,
+    pub binary: crate::db::BinaryRecord,
+    pub run_dir: PathBuf,
+    pub request: AnalysisRequest,
+    pub meta: RunMetadata,
+    /// Backends to try in order; `meta` describes the first.
+    pub backend_chain: Vec<String>,
+    /// Project root, for project-relative paths in deterministic runs.
+    pub root: PathBuf,
+    /// Hash the binary was registered with, when the file now hashes differently
+    /// (`meta.binary_hash`).
+    pub registered_hash: Option<String>,
+}
+
+impl PreparedRun {
+    /// Apply the per-invocation [`RunFlags`] that change how the analysis runs.
+    ///
+    /// Fails when the binary changed since it was registered, unless
+    /// `flags.allow_hash_mismatch` is set.
+    pub fn apply_flags(&mut self, flags: RunFlags) -> Result<()> {
+        if let Some(registered) = &self.registered_hash {
+            let current = self.meta.binary_hash.as_deref().unwrap_or_default();
+            if !flags.allow_hash_mismatch {
+                return Err(anyhow!(
+                    "Binary '{}' changed since it was registered: {} has sha256 {}, registered as {} (run verify-binaries --update to accept the new file, or pass --allow-hash-mismatch)",
+                    self.binary.name,
+                    self.request.binary_path.display(),
+                    current,
+                    registered
+                ));
+            }
+            tracing::warn!(
+                "analyzing {} although it changed since it was registered (sha256 {}, registered as {})",
+                self.binary.name,
+                current,
+                registered
+            );
+        }
+        if flags.deterministic {
+            self.make_deterministic();
+        }
+        if let Some(secs) = flags.timeout {
+            self.request.timeout = Some(Duration::from_secs(secs));
+        }
+        Ok(())
+    }
+
+    /// Switch the run to deterministic mode: sorted output, the spec's `epoch` (or
+    /// [`DETERMINISTIC_EPOCH`]) as every timestamp, and project-relative paths.
+    pub fn make_deterministic(&mut self) {
+        self.meta.deterministic = true;
+        let epoch = self.spec.epoch.as_deref().and_then(parse_epoch);
+        self.meta.epoch = Some(epoch.unwrap_or_else(|| DETERMINISTIC_EPOCH.to_string()));
+        self.meta.backend_path = self.meta.backend_path.take().map(|p| self.normalize(&p));
+        self.meta.spec_path = self.meta.spec_path.take().map(|p| {
+            if Path::new(&p).is_absolute() {
+                normalize_path(&p, &self.root)
+            } else {
+                p
+            }
+        });
+    }
+
+    /// Record `file` as the spec the run came from: project-relative (with `/` separators)
+    /// when it lies inside the project, otherwise absolute (just the file name when
+    /// deterministic).
+    pub fn set_spec_file(&mut self, file: &Path) {
+        let path = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+        let root = fs::canonicalize(&self.root).unwrap_or_else(|_| self.root.clone());
+        let path = path.display().to_string();
+        self.meta.spec_path =
+            Some(if Path::new(&path).starts_with(&root) || self.meta.deterministic {
+                normalize_path(&path, &root)
+            } else {
+                path
+            });
+    }
+
+    /// Name functions after the symbols imported for the binary (`import-symbols`) and its
+    /// PDB (see [`PreparedRun::load_pdb`]).
+    pub fn load_symbols(&mut self, db: &ProjectDb) -> Result<()> {
+        self.load_pdb(db)?;
+        let Some(hash) = &self.meta.binary_hash else {
+            return Ok(());
+        };
+        let symbols = db.list_imported_symbols(hash).context("Failed to load imported symbols")?;
+        self.request.symbols = symbols.into_iter().map(|s| (s.address, s.name)).collect();
+        Ok(())
+    }
+
+    /// Load the binary's PDB: the one associated by `add-binary --pdb`, else a `.pdb` next
+    /// to the binary. A PDB that no longer matches the binary's CodeView record or the
+    /// GUID/age recorded at association (a rebuilt binary, a replaced PDB) is skipped with a
+    /// warning, since it would name the wrong code.
+    fn load_pdb(&mut self, db: &ProjectDb) -> Result<()> {
+        if self.request.raw.is_some() {
+            return Ok(());
+        }
+        let associated =
+            db.binary_pdb(self.binary_id).context("Failed to load the binary's PDB")?;
+        let path = match &associated {
+            Some(pdb) => self.root.join(&pdb.path),
+            None => self.request.binary_path.with_extension("pdb"),
+        };
+        if associated.is_none() && !path.is_file() {
+            return Ok(());
+        }
+        let symbols = match read_pdb(&path) {
+            Ok(symbols) => symbols,
+            Err(err) => {
+                tracing::warn!("Ignoring PDB {}: {}", path.display(), err);
+                return Ok(());
+            }
+        };
+        let linked = BinaryImage::open(&self.request.binary_path)
+            .ok()
+            .and_then(|image| pe_pdb_reference(&image))
+            .map(|reference| reference.identity);
+        let expected =
+            linked.or_else(|| associated.map(|pdb| PdbIdentity { guid: pdb.guid, age: pdb.age }));
+        if let Some(expected) = expected.filter(|e| !e.matches(&symbols.identity)) {
+            tracing::warn!(
+                "Ignoring PDB {} ({}): {} expects {}",
+                path.display(),
+                symbols.identity,
+                self.binary.name,
+                expected
+            );
+            return Ok(());
+        }
+        let image_base = request_layout(&self.request).map(|l| l.image_base).unwrap_or_default();
+        self.request.pdb = Some(symbols.rebase(image_base));
+        Ok(())
+    }
+
+    /// `path` as recorded by this run: project-relative when deterministic, else as-is.
+    pub fn normalize(&self, path: &str) -> String {
+        if self.meta.deterministic {
+            normalize_path(path, &self.root)
+        } else {
+            path.to_string()
+        }
+    }
+}
+
+/// How a ritual run treats existing output, the analysis cache, and reproducibility.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunFlags {
+    /// Overwrite an existing run directory.
+    pub force: bool,
+    /// Skip the analysis cache.
+    pub no_cache: bool,
+    /// Sort output, record fixed timestamps, and store project-relative paths.
+    pub deterministic: bool,
+    /// Wall-clock limit in seconds for external backend tools (overrides the spec's `timeout`).
+    pub timeout: Option<u64>,
+    /// Fail when the result breaks an invariant checked by [`validate_result`].
+    pub strict: bool,
+    /// Analyze a binary whose file no longer matches its registered hash instead of failing
+    /// (`run_metadata.json` records both hashes).
+    pub allow_hash_mismatch: bool,
+}
+
+/// A binary resolved for its runs.
+#[derive(Debug, Clone)]
+pub struct RunBinary {
+    pub id: i64,
+    pub binary: crate::db::BinaryRecord,
+    /// Name its runs are recorded and written under; differs from the binary's name when
+    /// another binary shares it (see `ProjectDb::binary_run_name`).
+    pub run_name: String,
+}
+
+impl RunBinary {
+    /// `resolved` is an entry of `ProjectDb::list_binaries_with_ids`, e.g. as picked by
+    /// [`BinarySelector::resolve`].
+    pub fn new(
+        db: &crate::db::ProjectDb,
+        resolved: &(i64, crate::db::BinaryRecord),
+    ) -> Result<Self> {
+        let (id, binary) = resolved.clone();
+        let run_name = db
+            .binary_run_name(id)
+            .context("Failed to look up the binary's run name")?
+            .unwrap_or_else(|| binary.name.clone());
+        Ok(RunBinary { id, binary, run_name })
+    }
+}
+
+/// Compute the SHA-256 hash of a file and return it as a hex string.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open binary for hashing: {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 8192];
+
+    loop {
+        let n = reader
+            .read(&mut buf)
+            .with_context(|| format!("Failed to read binary for hashing: {}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    let digest = hasher.finalize();
+    Ok(format!("{:x}", digest))
+}
+
+/// Resolve the backend for a validated spec and build the analysis request against `target`.
+///
+/// `ritual` names the run (the spec name, or `--as-name` for reruns).
+pub fn prepare_run(
+    layout: &crate::db::ProjectLayout,
+    config: &crate::db::ProjectConfig,
+    target: &RunBinary,
+    mut spec: RitualSpec,
+    spec_hash: &str,
+    ritual: &str,
+    backend_override: Option<&str>,
+) -> Result<PreparedRun> {
+    let (binary_id, binary) = (target.id, target.binary.clone());
+    let run_dir = layout.binary_output_root(&target.run_name).join(ritual);
+
+    // Re-hash the binary: the run records what is actually analyzed, and a file replaced
+    // since registration is caught by `PreparedRun::apply_flags`.
+    let binary_path = {
+        let p = Path::new(&binary.path);
+        if p.is_absolute() {
+            p.to_path_buf()
+        } else {
+            layout.root.join(p)
+        }
+    };
+    let current_hash = if binary_path.is_file() { Some(sha256_file(&binary_path)?) } else { None };
+    let registered_hash =
+        binary.hash.clone().filter(|h| current_hash.as_ref().is_some_and(|c| c != h));
+    let binary_hash = current_hash.or_else(|| binary.hash.clone());
+
+    // Choose backends (CLI override > spec > config/default preference).
+    let backends = default_backend_registry();
+    let backend_chain =
+        resolve_backend_chain(&backends, backend_override, spec.backend.as_ref(), config);
+    let backend_name = backend_chain[0].clone();
+    let backend_path = resolve_backend_path(&backend_name, &spec, &layout.root, config);
+    if spec.outputs.is_none() {
+        spec.outputs = Some(RitualOutputs::all());
+    }
+    spec.backend = Some(BackendChoice::from(backend_chain.clone()));
+
+    let raw = spec.raw.as_ref().map(RawImageSpec::image).transpose()?;
+    let arch = spec.raw.as_ref().and_then(|r| r.arch.clone()).or_else(|| binary.arch.clone());
+    if raw.is_some() && arch.is_none() {
+        return Err(anyhow!(
+            "Raw image spec for '{}' needs an architecture: set 'raw.arch' or register the binary with --arch",
+            binary.name
+        ));
+    }
+
+    let request = AnalysisRequest {
+        ritual_name: ritual.to_string(),
+        binary_name: target.run_name.clone(),
+        binary_path,
+        roots: spec.roots.all(),
+        arch,
+        options: AnalysisOptions {
+            max_depth: spec.max_depth,
+            include_imports: true,
+            include_strings: true,
+            max_instructions: Some(1024),
+            max_total_instructions: None,
+        },
+        backend_path: backend_path.clone(),
+        root_groups: spec.roots.groups(),
+        raw,
+        timeout: spec.timeout.map(Duration::from_secs),
+        symbols: BTreeMap::new(),
+        pdb: None,
+    };
+    let meta = RunMetadata {
+        spec_hash: spec_hash.to_string(),
+        binary_id: Some(binary_id),
+        binary_hash,
+        backend: backend_name.clone(),
+        backend_version: resolve_backend_version(&backend_name, backend_path.as_deref(), config),
+        backend_path: backend_path.as_ref().map(|p| p.display().to_string()),
+        status: RitualRunStatus::Stubbed,
+        deterministic: false,
+        epoch: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        started_at: None,
+        usage: None,
+    };
+    let root = layout.root.clone();
+    Ok(PreparedRun {
+        spec,
+        binary_id,
+        binary,
+        run_dir,
+        request,
+        meta,
+        backend_chain,
+        root,
+        registered_hash,
+    })
+}
+
+/// Write the normalized spec to `<run dir>/spec.yaml`.
+pub fn write_normalized_spec(prepared: &PreparedRun) -> Result<()> {
+    let normalized_spec_path = prepared.run_dir.join("spec.yaml");
+    let yaml = serde_yaml::to_string(&prepared.spec).context("Failed to serialize ritual spec")?;
+    fs::write(&normalized_spec_path, yaml).with_context(|| {
+        format!("Failed to write normalized spec to {}", normalized_spec_path.display())
+    })
+}
+
+/// Analyze a prepared run (single backend or pipeline) without touching the project DB.
+///
+/// Returns the result, whether it came from the cache, and the metadata to persist (naming
+/// the backend that produced the result; a pipeline reports its last stage's). `progress`
+/// is told as each pipeline stage finishes.
+pub fn execute_run(
+    prepared: &PreparedRun,
+    backends: &BackendRegistry,
+    config: &crate::db::ProjectConfig,
+    cache: Option<&AnalysisCache>,
+    backend_override: Option<&str>,
+    progress: Option<&dyn Fn(&StageProgress)>,
+) -> Result<(AnalysisResult, bool, RunMetadata)> {
+    let (mut result, cache_hit, meta) = if !prepared.spec.stages.is_empty() {
+        let run = run_pipeline(prepared, backends, config, cache, backend_override, progress)?;
+        (run.result, run.cache_hit, run.meta)
+    } else {
+        let (run, meta) = analyze_chain(
+            prepared,
+            backends,
+            config,
+            &prepared.request,
+            &prepared.backend_chain,
+            cache,
+        )?;
+        (run.result, run.cache_hit, meta)
+    };
+    apply_decoders(prepared, &mut result)?;
+    apply_plugins(prepared, &mut result)?;
+    Ok((result, cache_hit, meta))
+}
+
+/// Run the spec's `string_decoders` over the binary's data sections (after the cache, like
+/// plugins), adding the strings they recover to `result`.
+fn apply_decoders(prepared: &PreparedRun, result: &mut AnalysisResult) -> Result<()> {
+    let decoders = prepared.spec.decoders()?;
+    if decoders.is_empty() {
+        return Ok(());
+    }
+    let Some(layout) = request_layout(&prepared.request) else {
+        tracing::warn!(
+            "cannot map the sections of {}; string decoders skipped",
+            prepared.binary.name
+        );
+        return Ok(());
+    };
+    let image = BinaryImage::open(&prepared.request.binary_path)?;
+    let sequences = byte_sequences(image.bytes(), &layout);
+    let reports = run_decoders(
+        result,
+        &sequences,
+        &decoders,
+        &prepared.root,
+        &prepared.binary.name,
+        &Deadline::new(prepared.request.timeout),
+    )?;
+    for report in &reports {
+        tracing::info!(
+            "String decoder {}: {} string(s) added",
+            report.decoder,
+            report.strings_added
+        );
+    }
+    if prepared.meta.deterministic {
+        canonicalize_result(result);
+    }
+    Ok(())
+}
+
+/// Run the spec's `plugins` over a finished result (after the cache, so plugins always run).
+fn apply_plugins(prepared: &PreparedRun, result: &mut AnalysisResult) -> Result<()> {
+    if prepared.spec.plugins.is_empty() {
+        return Ok(());
+    }
+    let reports = run_plugins(
+        result,
+        &prepared.spec.plugins,
+        &prepared.root,
+        &prepared.request.ritual_name,
+        &prepared.binary.name,
+        &Deadline::new(prepared.request.timeout),
+    )?;
+    for report in &reports {
+        tracing::info!(
+            "Plugin {}: {} evidence added, {} renamed, {} slice change(s), {} unknown address(es)",
+            report.plugin,
+            report.evidence_added,
+            report.renamed,
+            report.slice_changed,
+            report.unknown_addresses.len()
+        );
+    }
+    if prepared.meta.deterministic {
+        canonicalize_result(result);
+    }
+    Ok(())
+}
+
+/// [`analyze_with_fallback`] with each backend's configured tool path and version.
+fn analyze_chain(
+    prepared: &PreparedRun,
+    backends: &BackendRegistry,
+    config: &crate::db::ProjectConfig,
+    request: &AnalysisRequest,
+    chain: &[String],
+    cache: Option<&AnalysisCache>,
+) -> Result<(CachedRun, RunMetadata)> {
+    let (mut run, meta) = analyze_with_fallback(backends, chain, cache, |name| {
+        let mut request = request.clone();
+        request.backend_path = resolve_backend_path(name, &prepared.spec, &prepared.root, config);
+        let meta = RunMetadata {
+            backend: name.to_string(),
+            backend_version: resolve_backend_version(name, request.backend_path.as_deref(), config),
+            backend_path: request
+                .backend_path
+                .as_ref()
+                .map(|p| prepared.normalize(&p.display().to_string())),
+            ..prepared.meta.clone()
+        };
+        (request, meta)
+    })?;
+    run.result.backend_path = run.result.backend_path.map(|p| prepared.normalize(&p));
+    Ok((run, meta))
+}
+
+/// Check a finished run's result against its binary's section layout (see
+/// [`validate_result`]); issues are logged and later written to `report.json`.
+pub fn validate_run(prepared: &PreparedRun, result: &AnalysisResult) -> ResultValidation {
+    let validation = validate_result(result, request_layout(&prepared.request).as_ref());
+    if !validation.is_ok() {
+        tracing::warn!(
+            "{} / {}: analysis result has {} validation issue(s)",
+            prepared.request.binary_name,
+            prepared.request.ritual_name,
+            validation.issues.len()
+        );
+    }
+    validation
+}
+
+/// Under `--strict`, fail when `validation` found issues, listing the first few.
+pub fn check_validation(
+    flags: RunFlags,
+    validation: &ResultValidation,
+    run_dir: &Path,
+) -> Result<()> {
+    if !flags.strict || validation.is_ok() {
+        return Ok(());
+    }
+    let mut message = format!(
+        "Analysis result failed validation with {} issue(s) (see \"validation\" in {})",
+        validation.issues.len(),
+        run_dir.join(REPORT_FILE).display()
+    );
+    for issue in validation.issues.iter().take(MAX_LISTED_ISSUES) {
+        message.push_str(&format!("\n  - {}", issue.message));
+    }
+    if validation.issues.len() > MAX_LISTED_ISSUES {
+        message
+            .push_str(&format!("\n  ... and {} more", validation.issues.len() - MAX_LISTED_ISSUES));
+    }
+    Err(anyhow!(message))
+}
+
+/// Validation issues spelled out in a `--strict` failure.
+const MAX_LISTED_ISSUES: usize = 10;
+
+/// Compare a just-recorded run with the baseline of its ritual, if one is set.
+pub fn compare_with_baseline(
+    db: &ProjectDb,
+    config: &crate::db::ProjectConfig,
+    binary_id: i64,
+    ritual: &str,
+    run_id: i64,
+) -> Result<Option<BaselineComparison>> {
+    let Some(baseline) = db.run_baseline(binary_id, ritual).context("Failed to load baseline")?
+    else {
+        return Ok(None);
+    };
+    let before = run_snapshot(db, baseline.run_id)?;
+    let current = run_snapshot(db, run_id)?;
+    let comparison = BaselineComparison::new(baseline, &before, &current, &config.regressions);
+    if !comparison.is_ok() {
+        tracing::warn!(
+            "{} / {}: {} regression(s) against baseline run {}",
+            comparison.baseline.binary,
+            ritual,
+            comparison.regressions.len(),
+            comparison.baseline.run_id
+        );
+    }
+    Ok(Some(comparison))
+}
+
+/// Counts and root hits of a recorded run, as compared against a baseline.
+pub fn run_snapshot(db: &ProjectDb, run_id: i64) -> Result<RunSnapshot> {
+    let counts = db.run_counts(run_id).context("Failed to count run rows")?;
+    let root_hits = db.run_root_hits(run_id).context("Failed to load root hits")?;
+    Ok(RunSnapshot::new(counts, &root_hits))
+}
+
+/// Fail when the run regressed against its baseline, listing the regressions.
+pub fn check_baseline(comparison: Option<&BaselineComparison>, run_dir: &Path) -> Result<()> {
+    let Some(comparison) = comparison.filter(|c| !c.is_ok()) else {
+        return Ok(());
+    };
+    let mut message = format!(
+        "Run regressed against baseline run {} of {} (see \"baseline\" in {})",
+        comparison.baseline.run_id,
+        comparison.baseline.binary,
+        run_dir.join(REPORT_FILE).display()
+    );
+    for regression in &comparison.regressions {
+        message.push_str(&format!("\n  - {}", regression));
+    }
+    Err(anyhow!(message))
+}
+
+/// Write `report.json`, `graph.dot`, `run_metadata.json`, and `manifest.json` for a finished
+/// run; `baseline` is its comparison with the ritual's baseline run, if one is set.
+pub fn write_run_outputs(
+    prepared: &PreparedRun,
+    result: &AnalysisResult,
+    meta: &RunMetadata,
+    validation: &ResultValidation,
+    baseline: Option<&BaselineComparison>,
+) -> Result<()> {
+    let outputs = prepared.spec.outputs.clone().unwrap_or_else(RitualOutputs::all);
+    let backend_version = result.backend_version.clone().or_else(|| meta.backend_version.clone());
+    let backend_path = result.backend_path.clone().or_else(|| meta.backend_path.clone());
+
+    if outputs.writes(OutputFormat::Json) {
+        write_run_report(prepared, result, meta, validation, baseline)?;
+    } else {
+        remove_stale_output(&prepared.run_dir.join(REPORT_FILE))?;
+    }
+
+    // Write graph DOT (best-effort even if sparse).
+    let dot_path = prepared.run_dir.join("graph.dot");
+    if outputs.writes(OutputFormat::Dot) {
+        let backend_label = format_backend_label(
+            &meta.backend,
+            backend_version.as_deref(),
+            backend_path.as_deref(),
+        );
+        let dot = render_dot(result, Some(&backend_label), &outputs.graphs.dot_options());
+        fs::write(&dot_path, dot)
+            .with_context(|| format!("Failed to write ritual graph at {}", dot_path.display()))?;
+    } else {
+        remove_stale_output(&dot_path)?;
+    }
+
+    write_run_metadata(prepared, meta, backend_version, backend_path, None)?;
+    write_run_manifest(prepared, meta)
+}
+
+/// File name of the per-run JSON event log (written by the front end's `tracing` subscriber
+/// for events inside [`run_span`]).
+pub const RUN_LOG_FILE: &str = "run.log.jsonl";
+
+/// Enter the `run` span of one ritual run. Its `run_dir` field tells subscribers where the
+/// run's files are, e.g. to log its events to [`RUN_LOG_FILE`] there.
+pub fn run_span(binary: &str, ritual: &str, run_dir: &Path) -> EnteredSpan {
+    tracing::info_span!("run", binary = %binary, ritual = %ritual, run_dir = %run_dir.display())
+        .entered()
+}
+
+/// Run-directory files that keep changing after a run finishes, so `manifest.json` leaves
+/// them out: the JSON run log and post-run hook output.
+pub const UNTRACKED_OUTPUTS: &[&str] = &[RUN_LOG_FILE, HOOKS_LOG_FILE, ANNOTATIONS_FILE];
+
+/// Write `manifest.json` with the checksum of every output the run wrote (`verify-run`).
+fn write_run_manifest(prepared: &PreparedRun, meta: &RunMetadata) -> Result<()> {
+    let manifest = OutputManifest {
+        binary: prepared.request.binary_name.clone(),
+        ritual: prepared.request.ritual_name.clone(),
+        spec_hash: meta.spec_hash.clone(),
+        binary_hash: meta.binary_hash.clone(),
+        created_at: meta.timestamp(),
+        files: hash_outputs(&prepared.run_dir, UNTRACKED_OUTPUTS)?,
+    };
+    manifest.write(&prepared.run_dir)?;
+    Ok(())
+}
+
+/// Write `report.json` from the analysis result.
+fn write_run_report(
+    prepared: &PreparedRun,
+    result: &AnalysisResult,
+    meta: &RunMetadata,
+    validation: &ResultValidation,
+    baseline: Option<&BaselineComparison>,
+) -> Result<()> {
+    let mut report = ReportV1::new(
+        &prepared.request.ritual_name,
+        &prepared.request.binary_name,
+        &meta.backend,
+        meta.status.clone(),
+        result,
+    );
+    report.binary_id = Some(prepared.binary_id);
+    report.binary_hash = meta.binary_hash.clone();
+    report.roots = prepared.spec.roots.all();
+    report.max_depth = prepared.spec.max_depth;
+    report.backend_version = report.backend_version.or_else(|| meta.backend_version.clone());
+    report.backend_path = report.backend_path.or_else(|| meta.backend_path.clone());
+    report.validation = validation.clone();
+    report.failed_backends = meta.failed_backends.clone();
+    report.stages = PipelineState::load(&prepared.run_dir)?.map(|state| state.stages);
+    report.baseline = baseline.cloned();
+    if meta.deterministic {
+        report.deterministic = true;
+        report.epoch = meta.epoch.clone();
+    }
+    report.write(&prepared.run_dir)?;
+    Ok(())
+}
+
+/// Remove an artifact a previous run wrote but the current spec's outputs disable.
+fn remove_stale_output(path: &Path) -> Result<()> {
+    if path.is_file() {
+        fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale output {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Write `run_metadata.json`; `error` says why a failed/canceled run stopped.
+fn write_run_metadata(
+    prepared: &PreparedRun,
+    meta: &RunMetadata,
+    backend_version: Option<String>,
+    backend_path: Option<String>,
+    error: Option<String>,
+) -> Result<()> {
+    let now = meta.timestamp();
+    let started_at = meta.started_at.clone().unwrap_or_else(|| now.clone());
+    let metadata = RitualRunMetadata {
+        ritual: prepared.request.ritual_name.clone(),
+        binary: prepared.request.binary_name.clone(),
+        spec_hash: meta.spec_hash.clone(),
+        binary_id: Some(prepared.binary_id),
+        binary_hash: meta.binary_hash.clone(),
+        backend: meta.backend.clone(),
+        backend_version,
+        backend_path,
+        started_at,
+        finished_at: now,
+        status: meta.status.clone(),
+        deterministic: meta.deterministic,
+        error,
+        failed_backends: meta.failed_backends.clone(),
+        spec_path: meta.spec_path.clone(),
+        registered_binary_hash: prepared.registered_hash.clone(),
+    };
+    let metadata_path = prepared.run_dir.join("run_metadata.json");
+    fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)
+        .with_context(|| format!("Failed to write run metadata at {}", metadata_path.display()))
+}
+
+/// Record a run whose analysis returned `err`: `canceled` when a backend tool hit the run's
+/// timeout or was interrupted, otherwise `failed`. Adds the run to the project DB (without analysis rows) and
+/// writes `run_metadata.json` with the error.
+pub fn record_failed_run(
+    db: &ProjectDb,
+    prepared: &PreparedRun,
+    err: &anyhow::Error,
+) -> Result<RitualRunStatus> {
+    let canceled = err
+        .chain()
+        .any(|cause| cause.downcast_ref::<AnalysisError>().is_some_and(|e| e.is_canceled()));
+    let status = if canceled { RitualRunStatus::Canceled } else { RitualRunStatus::Failed };
+    let meta = RunMetadata { status: status.clone(), ..prepared.meta.clone() };
+    persist_failed_run(db, &prepared.request, &meta)
+        .context("Failed to record ritual run in project DB")?;
+    write_run_metadata(
+        prepared,
+        &meta,
+        meta.backend_version.clone(),
+        meta.backend_path.clone(),
+        Some(format!("{:#}", err)),
+    )?;
+    write_run_manifest(prepared, &meta)?;
+    Ok(status)
+}
+
+/// A pipeline stage that finished, as reported to the `progress` callback of [`execute_run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageProgress {
+    /// The stage succeeded in an earlier attempt; its saved result was reused.
+    Reused { stage: String },
+    /// The stage ran with `backend` and found `functions` functions.
+    Finished { stage: String, backend: String, functions: usize },
+}
+
+impl fmt::Display for StageProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageProgress::Reused { stage } => {
+                write!(f, "Stage {}: reused result from a previous attempt", stage)
+            }
+            StageProgress::Finished { stage, backend, functions } => {
+                write!(f, "Stage {} ({}): {} function(s)", stage, backend, functions)
+            }
+        }
+    }
+}
+
+/// Final result of a pipeline run plus the metadata of the stage that produced it.
+struct PipelineRun {
+    result: AnalysisResult,
+    meta: RunMetadata,
+    cache_hit: bool,
+}
+
+/// Run the spec's stages in dependency order, writing per-stage status/results into the
+/// run directory.
+///
+/// Picks up from `stages.json` when it belongs to the same spec and is incomplete. Earlier
+/// stages feed later ones via [`feed_roots`]; the last stage's result becomes the run.
+fn run_pipeline(
+    prepared: &PreparedRun,
+    backends: &BackendRegistry,
+    config: &crate::db::ProjectConfig,
+    cache: Option<&AnalysisCache>,
+    backend_override: Option<&str>,
+    progress: Option<&dyn Fn(&StageProgress)>,
+) -> Result<PipelineRun> {
+    let (spec, request, meta, run_dir) =
+        (&prepared.spec, &prepared.request, &prepared.meta, prepared.run_dir.as_path());
+    let order = stage_order(&spec.stages)?;
+    let last = *order.last().ok_or_else(|| anyhow!("Ritual pipeline has no stages"))?;
+    let mut state = match PipelineState::load(run_dir)? {
+        Some(state) if state.can_resume(&meta.spec_hash) => state,
+        _ => PipelineState::new(&meta.spec_hash, &spec.stages, &order),
+    };
+    let mut results: BTreeMap<String, AnalysisResult> = BTreeMap::new();
+    let pipeline_started = meta.timestamp();
+    let clock = Instant::now();
+    let mut stage_usage: Vec<RunUsage> = Vec::new();
+
+    for &i in &order {
+        let stage = &spec.stages[i];
+        let succeeded =
+            state.stage(&stage.name).is_some_and(|s| s.status == StageStatus::Succeeded);
+        if succeeded && i != last {
+            if let Some(result) = load_stage_result(run_dir, &stage.name) {
+                if let Some(progress) = progress {
+                    progress(&StageProgress::Reused { stage: stage.name.clone() });
+                }
+                results.insert(stage.name.clone(), result);
+                continue;
+            }
+        }
+
+        // CLI override > stage backend > spec-level chain. A backend named by the stage is
+        // required as-is rather than silently swapped for another one.
+        let chain = match backend_override.or(stage.backend.as_deref()) {
+            Some(name) => vec![name.to_string()],
+            None => prepared.backend_chain.clone(),
+        };
+        let upstream: Vec<&AnalysisResult> =
+            stage.needs.iter().filter_map(|n| results.get(n)).collect();
+        let mut stage_request = request.clone();
+        stage_request.roots =
+            feed_roots(stage.roots.as_deref().unwrap_or(&request.roots), &upstream);
+        stage_request.root_groups = if stage.roots.is_some() {
+            Vec::new()
+        } else {
+            request
+                .root_groups
+                .iter()
+                .map(|g| RootGroup {
+                    label: g.label.clone(),
+                    roots: feed_roots(&g.roots, &upstream),
+                })
+                .collect()
+        };
+        stage_request.options.max_depth = stage.max_depth.or(request.options.max_depth);
+
+        let _stage_span =
+            tracing::info_span!("stage", stage = %stage.name, needs = %stage.needs.join(","))
+                .entered();
+        let started_at = meta.timestamp();
+        // Only the last stage goes through the cache; its result is the run's.
+        let stage_cache = if i == last { cache } else { None };
+        let outcome =
+            analyze_chain(prepared, backends, config, &stage_request, &chain, stage_cache);
+
+        let entry = state
+            .stage_mut(&stage.name)
+            .ok_or_else(|| anyhow!("Pipeline state is missing stage '{}'", stage.name))?;
+        let backend_name = match &outcome {
+            Ok((_, stage_meta)) => stage_meta.backend.clone(),
+            Err(_) => chain[0].clone(),
+        };
+        entry.backend = Some(backend_name.clone());
+        entry.started_at = Some(started_at);
+        entry.finished_at = Some(meta.timestamp());
+        match outcome {
+            Ok((run, mut stage_meta)) => {
+                stage_usage.push(run.usage);
+                entry.status = StageStatus::Succeeded;
+                entry.error = None;
+                entry.functions = Some(run.result.functions.len());
+                save_stage_result(run_dir, &stage.name, &run.result)?;
+                state.save(run_dir)?;
+                if let Some(progress) = progress {
+                    progress(&StageProgress::Finished {
+                        stage: stage.name.clone(),
+                        backend: backend_name.clone(),
+                        functions: run.result.functions.len(),
+                    });
+                }
+                if i == last {
+                    // The run spans every stage of this attempt, not just the last one.
+                    stage_meta.started_at = Some(pipeline_started);
+                    stage_meta.usage = Some(RunUsage {
+                        duration_ms: clock.elapsed().as_millis() as u64,
+                        cpu_ms: stage_usage.iter().filter_map(|u| u.cpu_ms).reduce(|a, b| a + b),
+                        peak_rss_kb: stage_usage.iter().filter_map(|u| u.peak_rss_kb).max(),
+                    });
+                    return Ok(PipelineRun {
+                        result: run.result,
+                        meta: stage_meta,
+                        cache_hit: run.cache_hit,
+                    });
+                }
+                results.insert(stage.name.clone(), run.result);
+            }
+            Err(err) => {
+                entry.status = StageStatus::Failed;
+                entry.error = Some(format!("{:#}", err));
+                entry.functions = None;
+                state.save(run_dir)?;
+                return Err(err.context(format!(
+                    "Pipeline stage '{}' failed (rerun the same spec to resume from this stage)",
+                    stage.name
+                )));
+            }
+        }
+    }
+    Err(anyhow!("Ritual pipeline finished without running its last stage"))
+}
+
+/// Record the failed analysis of `prepared`, run the post-run hooks, and describe how the
+/// run ended.
+pub fn failed_run_error(
+    db: &ProjectDb,
+    config: &crate::db::ProjectConfig,
+    prepared: &PreparedRun,
+    err: anyhow::Error,
+) -> anyhow::Error {
+    match record_failed_run(db, prepared, &err) {
+        Ok(status) => {
+            run_post_run_hooks(config, prepared, &status);
+            err.context(format!(
+                "Ritual run {}/{} {}",
+                prepared.request.binary_name,
+                prepared.request.ritual_name,
+                status.as_str()
+            ))
+        }
+        Err(record_err) => {
+            err.context(format!("Failed to record the failed run: {:#}", record_err))
+        }
+    }
+}
+
+/// The spec `run-ritual --slice` runs: the slice's roots against its default binary, named
+/// after the rituals that feed the slice so the run updates it.
+pub fn slice_spec(db: &ProjectDb, name: &str) -> Result<RitualSpec> {
+    let slice = db
+        .list_slices()
+        .context("Failed to list slices")?
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| anyhow!("Slice '{}' not found", name))?;
+    if slice.roots.is_empty() {
+        return Err(anyhow!("Slice '{}' has no roots (add them with slice-add-root)", name));
+    }
+    let binary = slice.default_binary.as_deref().ok_or_else(|| {
+        anyhow!("Slice '{}' has no default binary (set one with init-slice --binary)", name)
+    })?;
+    Ok(RitualSpec {
+        name: slice.ritual_name().to_string(),
+        binary: BinarySelector::from(binary),
+        roots: RitualRoots::List(slice.roots.iter().map(|r| RootSpec::from(r.as_str())).collect()),
+        max_depth: None,
+        backend: None,
+        description: Some(format!("Roots of slice {}", slice.name)),
+        outputs: None,
+        stages: Vec::new(),
+        epoch: None,
+        raw: None,
+        timeout: None,
+        plugins: Vec::new(),
+        string_decoders: Vec::new(),
+        ghidra_xml: None,
+    })
+}
+
+/// A finished [`run_ritual_spec`].
+#[derive(Debug)]
+pub struct RitualRunOutcome {
+    pub ritual: String,
+    pub binary: String,
+    pub binary_id: i64,
+    pub roots: Vec<String>,
+    /// Backend that produced the result.
+    pub backend: String,
+    /// Backends of the fallback chain that failed first.
+    pub failed_backends: Vec<BackendFailure>,
+    pub cache_hit: bool,
+    pub run_dir: PathBuf,
+    pub result: AnalysisResult,
+    /// Post-run hooks that ran (see [`run_post_run_hooks`]).
+    pub hooks: Vec<HookOutcome>,
+    pub validation: ResultValidation,
+    /// Comparison with the ritual's baseline run, when one is set (see `set-baseline`).
+    pub baseline: Option<BaselineComparison>,
+}
+
+/// Where [`run_ritual_spec`] gets its spec.
+#[derive(Debug, Clone, Copy)]
+pub enum SpecSource<'a> {
+    /// A YAML or JSON spec file.
+    File(&'a str),
+    /// The roots and default binary of the named slice (see [`slice_spec`]).
+    Slice(&'a str),
+}
+
+/// Run the spec from `source`: analyze its binary, record the run in the project DB, and
+/// write the run outputs. Prints nothing; `progress` is told as each pipeline stage finishes.
+pub fn run_ritual_spec(
+    layout: &crate::db::ProjectLayout,
+    source: SpecSource<'_>,
+    backend_override: Option<&str>,
+    flags: RunFlags,
+    seed_strings: &[String],
+    variables: &[String],
+    progress: Option<&dyn Fn(&StageProgress)>,
+) -> Result<RitualRunOutcome> {
+    let (config, _db_path, db) = open_project_db(layout)?;
+    let binaries = db.list_binaries_with_ids().context("Failed to list binaries")?;
+
+    // Load ritual spec (supports YAML or JSON based on extension) and fill in its variables.
+    let (spec_bytes, json, spec_path) = match source {
+        SpecSource::File(file) => {
+            let spec_path = Path::new(file);
+            let spec_bytes = fs::read(spec_path).with_context(|| {
+                format!("Failed to read ritual spec at {}", spec_path.display())
+            })?;
+            let json = spec_path.extension().and_then(|e| e.to_str()) == Some("json");
+            (spec_bytes, json, Some(spec_path))
+        }
+        SpecSource::Slice(slice) => {
+            (serde_yaml::to_string(&slice_spec(&db, slice)?)?.into_bytes(), false, None)
+        }
+    };
+    let vars = SpecVars::new(&config, variables)?;
+    let ResolvedSpec { mut spec, spec_hash, .. } =
+        resolve_spec(&spec_bytes, json, &vars, &binaries)?;
+    for string in seed_strings {
+        spec.roots.add("strings", RootSpec::String { string: string.clone() });
+    }
+    spec.validate()?;
+
+    // Resolve the spec's binary selector to a single registered binary.
+    let target = RunBinary::new(&db, spec.binary.resolve(&binaries)?)?;
+    let ritual = spec.name.clone();
+    let mut prepared =
+        prepare_run(layout, &config, &target, spec, &spec_hash, &ritual, backend_override)?;
+    prepared.apply_flags(flags)?;
+    if let Some(spec_path) = spec_path {
+        prepared.set_spec_file(spec_path);
+    }
+    prepared.load_symbols(&db)?;
+
+    // Prepare output directories. An interrupted pipeline of the same spec resumes in place
+    // instead of starting over.
+    let run_output_root = &prepared.run_dir;
+    let resume = !prepared.spec.stages.is_empty()
+        && !flags.force
+        && PipelineState::load(run_output_root)?.is_some_and(|s| s.can_resume(&spec_hash));
+    if run_output_root.exists() && !resume {
+        if flags.force {
+            fs::remove_dir_all(run_output_root).with_context(|| {
+                format!("Failed to clean existing ritual output dir {}", run_output_root.display())
+            })?;
+        } else {
+            return Err(anyhow!(
+                "Ritual output already exists at {} (rerun with --force to overwrite)",
+                run_output_root.display()
+            ));
+        }
+    }
+    fs::create_dir_all(run_output_root).with_context(|| {
+        format!("Failed to create ritual output dir {}", run_output_root.display())
+    })?;
+    write_normalized_spec(&prepared)?;
+    let _span = run_span(&prepared.request.binary_name, &ritual, run_output_root);
+
+    // Invoke analysis service, then persist the run and write its outputs.
+    let backends = default_backend_registry();
+    let cache = (!flags.no_cache).then(|| AnalysisCache::new(layout.cache_dir()));
+    let (analysis_result, cache_hit, run_meta) = match execute_run(
+        &prepared,
+        &backends,
+        &config,
+        cache.as_ref(),
+        backend_override,
+        progress,
+    ) {
+        Ok(run) => run,
+        Err(err) => return Err(failed_run_error(&db, &config, &prepared, err)),
+    };
+    let validation = validate_run(&prepared, &analysis_result);
+    let run_id = persist_run(&db, &prepared.request, &run_meta, &analysis_result)
+        .context("Failed to record ritual run in project DB")?;
+    let baseline = compare_with_baseline(&db, &config, prepared.binary_id, &ritual, run_id)?;
+    write_run_outputs(&prepared, &analysis_result, &run_meta, &validation, baseline.as_ref())?;
+    let hooks = run_post_run_hooks(&config, &prepared, &run_meta.status);
+
+    Ok(RitualRunOutcome {
+        ritual: prepared.spec.name.clone(),
+        binary: prepared.request.binary_name.clone(),
+        binary_id: prepared.binary_id,
+        roots: prepared.spec.roots.all(),
+        backend: run_meta.backend.clone(),
+        failed_backends: run_meta.failed_backends.clone(),
+        cache_hit,
+        run_dir: prepared.run_dir.clone(),
+        result: analysis_result,
+        hooks,
+        validation,
+        baseline,
+    })
+}
@@ -0,0 +1,586 @@
+//! Ritual specs: the YAML/JSON files under `rituals/` describing what to analyze.
+//!
+//! A spec names a registered binary, the roots the slice starts from, and optionally the
+//! backend(s), depth limit, pipeline stages, raw-image mapping, plugins, string decoders, and
+//! which outputs a run writes. [`RitualSpec::validate`] checks what serde cannot.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+use crate::analysis::graph::DotOptions;
+use crate::services::analysis::{Endian, RawImage, RootGroup};
+use crate::services::decoders::Decoder;
+use crate::services::deterministic::parse_epoch;
+use crate::services::pipeline::{stage_order, StageSpec};
+use crate::services::roots::{AutoRoot, RootAddress, RootSpec};
+
+/// A binary-slicer ritual: which binary to analyze, from which roots, with which backend.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[schemars(title = "Ritual spec")]
+pub struct RitualSpec {
+    /// Ritual name; also the run output directory name.
+    #[schemars(length(min = 1))]
+    pub name: String,
+    pub binary: BinarySelector,
+    pub roots: RitualRoots,
+    /// Call-graph depth to follow from the roots.
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    #[serde(default)]
+    pub backend: Option<BackendChoice>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub outputs: Option<RitualOutputs>,
+    /// Optional pipeline stages; when present the run executes them in dependency order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<StageSpec>,
+    /// Timestamp `--deterministic` runs record (RFC 3339 or Unix seconds; default 1970-01-01).
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "epoch_value")]
+    #[schemars(with = "Option<Epoch>")]
+    pub epoch: Option<String>,
+    /// Treat the binary as a flat blob (e.g. a firmware dump) instead of parsing a container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<RawImageSpec>,
+    /// Wall-clock limit in seconds for the backend's external tools; the run is killed and
+    /// recorded as `canceled` when it runs out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// WASM modules run in order after analysis to add evidence, rename functions, or
+    /// change slice membership (paths relative to the project root; see
+    /// [`crate::services::plugins`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<String>,
+    /// Decoders run in order over the binary's data before the plugins, adding the strings
+    /// they recover as evidence: `xor`, `xor:<key>`, `base64`, or a hook path (see
+    /// [`crate::services::decoders`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub string_decoders: Vec<String>,
+    /// Ghidra XML export the `ghidra-xml` backend reads instead of analyzing the binary
+    /// (relative to the project root).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ghidra_xml: Option<String>,
+}
+
+/// `backend:` of a ritual spec: one backend, or a priority list tried in order until one
+/// succeeds (`backend: [ghidra, rizin, capstone]`).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BackendChoice {
+    One(String),
+    Chain(Vec<String>),
+}
+
+impl BackendChoice {
+    pub fn names(&self) -> &[String] {
+        match self {
+            BackendChoice::One(name) => std::slice::from_ref(name),
+            BackendChoice::Chain(names) => names,
+        }
+    }
+}
+
+impl From<Vec<String>> for BackendChoice {
+    fn from(mut names: Vec<String>) -> Self {
+        if names.len() == 1 {
+            BackendChoice::One(names.remove(0))
+        } else {
+            BackendChoice::Chain(names)
+        }
+    }
+}
+
+impl From<&str> for BackendChoice {
+    fn from(value: &str) -> Self {
+        BackendChoice::One(value.to_string())
+    }
+}
+
+impl fmt::Display for BackendChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.names().join(", "))
+    }
+}
+
+/// `raw:` block of a ritual spec: where the blob is loaded and how to decode it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct RawImageSpec {
+    /// Address the first byte is loaded at (integer or `0x…` string).
+    pub load_address: RootAddress,
+    /// Architecture to decode as; defaults to the arch the binary was registered with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    /// Decode ARM code as Thumb.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub thumb: bool,
+    #[serde(default)]
+    pub endian: Endian,
+}
+
+impl RawImageSpec {
+    /// The backend-facing mapping; fails when the load address is not a number.
+    pub fn image(&self) -> Result<RawImage> {
+        let load_address = self.load_address.value().ok_or_else(|| {
+            anyhow!("Ritual spec 'raw.load_address' must be an integer or 0x-prefixed hex address")
+        })?;
+        Ok(RawImage { load_address, thumb: self.thumb, endian: self.endian })
+    }
+}
+
+/// `epoch:` as written: Unix seconds, or an RFC 3339 (or quoted) timestamp.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum Epoch {
+    Seconds(i64),
+    Text(String),
+}
+
+/// Accept `epoch: 1700000000` as well as quoted or RFC 3339 values.
+fn epoch_value<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    Ok(Option::<Epoch>::deserialize(de)?.map(|e| match e {
+        Epoch::Seconds(secs) => secs.to_string(),
+        Epoch::Text(text) => text,
+    }))
+}
+
+/// How a ritual spec refers to a registered binary.
+///
+/// Accepted forms:
+/// - `binary: libGame.so` (name, or a suffix of the stored path)
+/// - `binary: sha256:abcd...` (content hash; a unique prefix is enough)
+/// - `binary: {name: libGame.so, arch: arm64}` (name narrowed by architecture)
+/// - `binary: libGame.so@1a2b3c4d5e6f` or `binary: libGame.so#2` (name narrowed by a hash
+///   prefix or row id, the forms `ProjectDb::binary_run_name` gives same-named binaries)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BinarySelector {
+    Name(String),
+    Qualified {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        arch: Option<String>,
+    },
+}
+
+const SHA256_SELECTOR_PREFIX: &str = "sha256:";
+
+impl BinarySelector {
+    /// Returns the hash prefix when the selector uses the `sha256:` form.
+    pub fn hash(&self) -> Option<&str> {
+        match self {
+            BinarySelector::Name(value) => value.strip_prefix(SHA256_SELECTOR_PREFIX),
+            BinarySelector::Qualified { .. } => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BinarySelector::Name(value) => {
+                value.trim().is_empty() || self.hash().is_some_and(|h| h.trim().is_empty())
+            }
+            BinarySelector::Qualified { name, .. } => name.trim().is_empty(),
+        }
+    }
+
+    /// Resolve the selector against registered binaries (as returned by
+    /// `ProjectDb::list_binaries_with_ids`).
+    ///
+    /// Resolution is deterministic: hash selectors must match exactly one binary by
+    /// hash prefix; name selectors prefer exact name matches over path-suffix matches
+    /// and error when more than one candidate remains.
+    pub fn resolve<'a>(
+        &self,
+        binaries: &'a [(i64, crate::db::BinaryRecord)],
+    ) -> Result<&'a (i64, crate::db::BinaryRecord)> {
+        let candidates: Vec<&(i64, crate::db::BinaryRecord)> = match self {
+            BinarySelector::Name(_) if self.hash().is_some() => {
+                let wanted = self.hash().unwrap_or_default().trim().to_ascii_lowercase();
+                binaries
+                    .iter()
+                    .filter(|(_, b)| {
+                        b.hash.as_ref().is_some_and(|h| h.to_ascii_lowercase().starts_with(&wanted))
+                    })
+                    .collect()
+            }
+            BinarySelector::Name(name) => match_binaries_by_name(binaries, name, None),
+            BinarySelector::Qualified { name, arch } => {
+                match_binaries_by_name(binaries, name, arch.as_deref())
+            }
+        };
+
+        match candidates.as_slice() {
+            [] => Err(anyhow!("Binary '{}' not found in project database", self)),
+            [single] => Ok(single),
+            many => {
+                let listing: Vec<String> = many
+                    .iter()
+                    .map(|(id, b)| {
+                        format!(
+                            "#{} {} (arch: {}, hash: {})",
+                            id,
+                            b.name,
+                            b.arch.as_deref().unwrap_or("unknown"),
+                            b.hash.as_deref().unwrap_or("none")
+                        )
+                    })
+                    .collect();
+                Err(anyhow!(
+                    "Binary '{}' is ambiguous ({} matches: {}); use `sha256:<hash>` or `{{name, arch}}` to disambiguate",
+                    self,
+                    many.len(),
+                    listing.join("; ")
+                ))
+            }
+        }
+    }
+}
+
+fn match_binaries_by_name<'a>(
+    binaries: &'a [(i64, crate::db::BinaryRecord)],
+    name: &str,
+    arch: Option<&str>,
+) -> Vec<&'a (i64, crate::db::BinaryRecord)> {
+    let arch_ok = |b: &crate::db::BinaryRecord| match arch {
+        Some(want) => b.arch.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(want)),
+        None => true,
+    };
+    let exact: Vec<_> = binaries.iter().filter(|(_, b)| b.name == name && arch_ok(b)).collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    let suffixed: Vec<_> =
+        binaries.iter().filter(|(_, b)| b.path.ends_with(name) && arch_ok(b)).collect();
+    if !suffixed.is_empty() {
+        return suffixed;
+    }
+    // Run names of same-named binaries: `<name>@<hash prefix>` or `<name>#<id>`.
+    let qualified = |id: i64, b: &crate::db::BinaryRecord| {
+        if let Some((base, hash)) = name.rsplit_once('@') {
+            let hash = hash.to_ascii_lowercase();
+            b.name == base
+                && !hash.is_empty()
+                && b.hash.as_ref().is_some_and(|h| h.to_ascii_lowercase().starts_with(&hash))
+        } else if let Some((base, wanted)) = name.rsplit_once('#') {
+            b.name == base && wanted.parse() == Ok(id)
+        } else {
+            false
+        }
+    };
+    binaries.iter().filter(|(id, b)| qualified(*id, b) && arch_ok(b)).collect()
+}
+
+impl fmt::Display for BinarySelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinarySelector::Name(value) => write!(f, "{}", value),
+            BinarySelector::Qualified { name, arch: Some(arch) } => {
+                write!(f, "{} ({})", name, arch)
+            }
+            BinarySelector::Qualified { name, arch: None } => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<&str> for BinarySelector {
+    fn from(value: &str) -> Self {
+        BinarySelector::Name(value.to_string())
+    }
+}
+
+/// Roots declared by a ritual spec.
+///
+/// Either a flat list (`roots: [entry_point]`) or named sub-slices
+/// (`roots: {ui: [CUIManager::init], net: [send_packet]}`), in which case one run
+/// carves a sub-slice per label and the report groups functions accordingly. Entries may
+/// also be `{address: 0x401000}`, `{regex: ".*Update.*"}`, `{demangled: "Game::tick"}`,
+/// `{string: "AutoUpdate"}` (functions referencing a string containing the text), or
+/// `entrypoint` / `exports` (the binary's entry point / exported functions). `roots: auto`
+/// is short for `[entrypoint, exports]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum RitualRoots {
+    List(Vec<RootSpec>),
+    Labeled(BTreeMap<String, Vec<RootSpec>>),
+    Auto(AutoRoots),
+}
+
+/// The `auto` keyword of `roots: auto`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoRoots {
+    Auto,
+}
+
+impl AutoRoots {
+    /// The list `roots: auto` stands for.
+    fn expand() -> Vec<RootSpec> {
+        AutoRoot::ALL.iter().map(|auto| RootSpec::Name(auto.keyword().to_string())).collect()
+    }
+}
+
+impl RitualRoots {
+    /// All roots in their canonical string form: declaration order for `List`, label order
+    /// for `Labeled` (groups are kept sorted by label, each root listed once under its first
+    /// label), and every auto root for `Auto`.
+    pub fn all(&self) -> Vec<String> {
+        match self {
+            RitualRoots::List(roots) => roots.iter().map(RootSpec::canonical).collect(),
+            RitualRoots::Auto(_) => AutoRoot::ALL.iter().map(|auto| auto.canonical()).collect(),
+            RitualRoots::Labeled(groups) => {
+                let mut out: Vec<String> = Vec::new();
+                for root in groups.values().flatten().map(RootSpec::canonical) {
+                    if !out.contains(&root) {
+                        out.push(root);
+                    }
+                }
+                out
+            }
+        }
+    }
+
+    /// Labeled root groups for the analysis request (empty for flat lists).
+    pub fn groups(&self) -> Vec<RootGroup> {
+        match self {
+            RitualRoots::List(_) | RitualRoots::Auto(_) => Vec::new(),
+            RitualRoots::Labeled(groups) => groups
+                .iter()
+                .map(|(label, roots)| RootGroup {
+                    label: label.clone(),
+                    roots: roots.iter().map(RootSpec::canonical).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Add a root unless already declared; labeled specs collect extra roots under `label`.
+    pub fn add(&mut self, label: &str, root: RootSpec) {
+        let roots = match self {
+            RitualRoots::List(roots) => roots,
+            RitualRoots::Labeled(groups) => groups.entry(label.to_string()).or_default(),
+            RitualRoots::Auto(_) => {
+                *self = RitualRoots::List(AutoRoots::expand());
+                return self.add(label, root);
+            }
+        };
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            RitualRoots::List(roots) => roots.is_empty(),
+            RitualRoots::Labeled(groups) => groups.is_empty(),
+            RitualRoots::Auto(_) => false,
+        }
+    }
+}
+
+impl From<Vec<String>> for RitualRoots {
+    fn from(roots: Vec<String>) -> Self {
+        RitualRoots::List(roots.into_iter().map(RootSpec::from).collect())
+    }
+}
+
+/// `outputs` in a spec: which artifacts a run and the slice reports/docs built from it write.
+///
+/// `reports` gates `report.json` and the slice JSON/HTML reports, `graphs` the run and slice
+/// `graph.dot`, and `docs` the slice Markdown doc; `formats` further limits all of them to
+/// the listed file types.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RitualOutputs {
+    #[serde(default)]
+    pub reports: bool,
+    #[serde(default)]
+    pub graphs: GraphOutputs,
+    #[serde(default)]
+    pub docs: bool,
+    #[serde(default = "OutputFormat::all")]
+    pub formats: Vec<OutputFormat>,
+}
+
+impl RitualOutputs {
+    /// Every output enabled (the default for specs without an `outputs` block).
+    pub fn all() -> Self {
+        RitualOutputs {
+            reports: true,
+            graphs: GraphOutputs::Enabled(true),
+            docs: true,
+            formats: OutputFormat::all(),
+        }
+    }
+
+    /// Whether artifacts of `format` are written.
+    pub fn writes(&self, format: OutputFormat) -> bool {
+        let enabled = match format {
+            OutputFormat::Json | OutputFormat::Html => self.reports,
+            OutputFormat::Dot => self.graphs.is_enabled(),
+            OutputFormat::Md => self.docs,
+        };
+        enabled && self.formats.contains(&format)
+    }
+}
+
+/// File types listed in `outputs.formats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    Json,
+    Dot,
+    Html,
+    Md,
+}
+
+impl OutputFormat {
+    pub fn all() -> Vec<OutputFormat> {
+        vec![OutputFormat::Json, OutputFormat::Dot, OutputFormat::Html, OutputFormat::Md]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Html => "html",
+            OutputFormat::Md => "md",
+        }
+    }
+}
+
+/// `outputs.graphs` in a spec: a plain switch, or DOT layout options (which imply `true`),
+/// e.g. `graphs: {cluster: true, collapse_blocks: true, dedup_edges: true, max_nodes: 500}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum GraphOutputs {
+    Enabled(bool),
+    Options(DotOptions),
+}
+
+impl Default for GraphOutputs {
+    fn default() -> Self {
+        GraphOutputs::Enabled(false)
+    }
+}
+
+impl GraphOutputs {
+    /// Whether graphs are written (options imply `true`).
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            GraphOutputs::Enabled(enabled) => *enabled,
+            GraphOutputs::Options(_) => true,
+        }
+    }
+
+    /// DOT layout options (defaults for the plain switch).
+    pub fn dot_options(&self) -> DotOptions {
+        match self {
+            GraphOutputs::Enabled(_) => DotOptions::default(),
+            GraphOutputs::Options(options) => options.clone(),
+        }
+    }
+}
+
+impl RitualSpec {
+    /// The parsed `string_decoders`.
+    pub fn decoders(&self) -> Result<Vec<Decoder>> {
+        self.string_decoders
+            .iter()
+            .map(|d| Decoder::parse(d).context("Invalid ritual spec 'string_decoders' entry"))
+            .collect()
+    }
+
+    /// Parse a spec from JSON or YAML bytes (`json` selects the format).
+    pub fn from_slice(bytes: &[u8], json: bool) -> Result<Self> {
+        if json {
+            serde_json::from_slice(bytes).context("Failed to parse ritual spec JSON")
+        } else {
+            serde_yaml::from_slice(bytes).context("Failed to parse ritual spec YAML")
+        }
+    }
+
+    /// Read and parse a spec file; `.json` files are JSON, anything else is YAML.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read ritual spec at {}", path.display()))?;
+        Self::from_slice(&bytes, path.extension().and_then(|e| e.to_str()) == Some("json"))
+    }
+
+    /// JSON Schema (draft 2020-12) derived from the spec types, as serde deserializes them.
+    ///
+    /// Unknown fields are ignored by the parser, so the schema leaves `additionalProperties`
+    /// open rather than rejecting them.
+    pub fn schema() -> serde_json::Value {
+        schemars::schema_for!(RitualSpec).into()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("Ritual spec 'name' is required"));
+        }
+        if self.binary.is_empty() {
+            return Err(anyhow!("Ritual spec 'binary' is required"));
+        }
+        if self.roots.is_empty() {
+            return Err(anyhow!("Ritual spec must include at least one root"));
+        }
+        if let RitualRoots::Labeled(groups) = &self.roots {
+            for (label, roots) in groups {
+                if label.trim().is_empty() {
+                    return Err(anyhow!("Ritual spec sub-slice labels must not be empty"));
+                }
+                if roots.is_empty() {
+                    return Err(anyhow!(
+                        "Ritual spec sub-slice '{}' must include at least one root",
+                        label
+                    ));
+                }
+            }
+        }
+        if self.backend.as_ref().is_some_and(|b| b.names().is_empty()) {
+            return Err(anyhow!("Ritual spec 'backend' list must name at least one backend"));
+        }
+        if !self.stages.is_empty() {
+            stage_order(&self.stages)?;
+        }
+        if let Some(epoch) = &self.epoch {
+            if parse_epoch(epoch).is_none() {
+                return Err(anyhow!(
+                    "Ritual spec 'epoch' must be an RFC 3339 timestamp or Unix seconds (got '{}')",
+                    epoch
+                ));
+            }
+        }
+        if let Some(raw) = &self.raw {
+            raw.image()?;
+            if raw.arch.as_deref().is_some_and(|a| a.trim().is_empty()) {
+                return Err(anyhow!("Ritual spec 'raw.arch' must not be empty"));
+            }
+        }
+        if self.timeout == Some(0) {
+            return Err(anyhow!("Ritual spec 'timeout' must be at least 1 second"));
+        }
+        if self.plugins.iter().any(|p| p.trim().is_empty()) {
+            return Err(anyhow!("Ritual spec 'plugins' entries must not be empty"));
+        }
+        if self.string_decoders.iter().any(|d| d.trim().is_empty()) {
+            return Err(anyhow!("Ritual spec 'string_decoders' entries must not be empty"));
+        }
+        self.decoders()?;
+        if self.ghidra_xml.as_ref().is_some_and(|p| p.trim().is_empty()) {
+            return Err(anyhow!("Ritual spec 'ghidra_xml' must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+pub fn sha256_bytes(bytes: &[u8]) -> String {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,170 @@
+//! `${name}` variables in ritual specs.
+//!
+//! Values come from `--set name=value`, then the project config's `variables` defaults, plus
+//! built-ins describing the project (`project.name`) and the spec's binary (`binary.name`,
+//! `binary.arch`, `binary.hash`, `binary.path`). References are replaced in the spec source
+//! before it is parsed and validated, so YAML typing applies to the result: `max_depth:
+//! ${depth}` is a number and `name: "${version}"` a string. `$${` writes a literal `${`.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::db::config::{is_builtin_variable, is_valid_variable_name};
+use crate::db::{BinaryRecord, ProjectConfig};
+use crate::rituals::spec::{sha256_bytes, RitualSpec};
+
+/// Variables available to a spec, before the binary built-ins are known.
+#[derive(Debug, Clone, Default)]
+pub struct SpecVars {
+    values: BTreeMap<String, String>,
+}
+
+impl SpecVars {
+    /// Config defaults overlaid with `sets` (`name=value` pairs from `--set`).
+    pub fn new(config: &ProjectConfig, sets: &[String]) -> Result<Self> {
+        let mut values = config.variables.clone();
+        for set in sets {
+            let (name, value) = parse_set(set)?;
+            values.insert(name, value);
+        }
+        values.insert("project.name".into(), config.name.clone());
+        Ok(Self { values })
+    }
+
+    fn with_binary(&self, binary: &BinaryRecord) -> BTreeMap<String, String> {
+        let mut values = self.values.clone();
+        values.insert("binary.name".into(), binary.name.clone());
+        values.insert("binary.path".into(), binary.path.clone());
+        if let Some(arch) = &binary.arch {
+            values.insert("binary.arch".into(), arch.clone());
+        }
+        if let Some(hash) = &binary.hash {
+            values.insert("binary.hash".into(), hash.clone());
+        }
+        values
+    }
+}
+
+/// Split a `--set name=value` argument.
+pub fn parse_set(set: &str) -> Result<(String, String)> {
+    let (name, value) =
+        set.split_once('=').ok_or_else(|| anyhow!("--set expects NAME=VALUE, got '{}'", set))?;
+    let name = name.trim();
+    if !is_valid_variable_name(name) {
+        return Err(anyhow!(
+            "--set: '{}' is not a valid variable name (letters, digits, '_', '-', '.')",
+            name
+        ));
+    }
+    if is_builtin_variable(name) {
+        return Err(anyhow!("--set: '{}' is a built-in variable and cannot be set", name));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// A spec after variable substitution.
+#[derive(Debug, Clone)]
+pub struct ResolvedSpec {
+    pub spec: RitualSpec,
+    /// SHA-256 of the substituted spec source (the file's own hash when it has no variables).
+    pub spec_hash: String,
+    /// Variables the spec referenced, with the values substituted.
+    pub variables: BTreeMap<String, String>,
+}
+
+/// Parse spec `bytes` (JSON when `json`), substituting `${name}` references.
+///
+/// `binary.*` built-ins come from the binary the spec's `binary` selector resolves to among
+/// `binaries`, so the selector itself can only use config and `--set` variables. Errors name
+/// every undefined variable.
+pub fn resolve_spec(
+    bytes: &[u8],
+    json: bool,
+    vars: &SpecVars,
+    binaries: &[(i64, BinaryRecord)],
+) -> Result<ResolvedSpec> {
+    let source = String::from_utf8_lossy(bytes);
+    if !source.contains("${") {
+        return Ok(ResolvedSpec {
+            spec: RitualSpec::from_slice(bytes, json)?,
+            spec_hash: sha256_bytes(bytes),
+            variables: BTreeMap::new(),
+        });
+    }
+
+    // First pass without binary built-ins, to find the spec's binary.
+    let mut values = vars.values.clone();
+    if source.contains("${binary.") {
+        let first = substitute(&source, &vars.values);
+        let missing: Vec<String> =
+            first.missing.into_iter().filter(|name| !name.starts_with("binary.")).collect();
+        if !missing.is_empty() {
+            return Err(undefined(&missing));
+        }
+        let partial = RitualSpec::from_slice(first.text.as_bytes(), json)?;
+        let (_, binary) = partial.binary.resolve(binaries)?;
+        values = vars.with_binary(binary);
+    }
+    let resolved = substitute(&source, &values);
+    if !resolved.missing.is_empty() {
+        return Err(undefined(&resolved.missing));
+    }
+    Ok(ResolvedSpec {
+        spec: RitualSpec::from_slice(resolved.text.as_bytes(), json)?,
+        spec_hash: sha256_bytes(resolved.text.as_bytes()),
+        variables: resolved.used,
+    })
+}
+
+fn undefined(missing: &[String]) -> anyhow::Error {
+    let names: Vec<String> = missing.iter().map(|name| format!("'{}'", name)).collect();
+    anyhow!(
+        "Undefined spec variable(s) {} (pass --set NAME=VALUE or set config variables.NAME)",
+        names.join(", ")
+    )
+}
+
+#[derive(Default)]
+struct Substituted {
+    text: String,
+    used: BTreeMap<String, String>,
+    /// Undefined names in order of appearance; references to them are left in place.
+    missing: Vec<String>,
+}
+
+fn substitute(source: &str, values: &BTreeMap<String, String>) -> Substituted {
+    let mut out = Substituted::default();
+    let mut rest = source;
+    while let Some(start) = rest.find('$') {
+        out.text.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(escaped) = tail.strip_prefix("$${") {
+            out.text.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let reference = tail.strip_prefix("${").and_then(|r| r.find('}').map(|end| (r, end)));
+        let Some((reference, end)) = reference else {
+            out.text.push('$');
+            rest = &tail[1..];
+            continue;
+        };
+        let name = reference[..end].trim();
+        match values.get(name) {
+            Some(value) => {
+                out.text.push_str(value);
+                out.used.insert(name.to_string(), value.clone());
+            }
+            None => {
+                out.text.push_str(&tail[..end + 3]);
+                if !out.missing.iter().any(|m| m == name) {
+                    out.missing.push(name.to_string());
+                }
+            }
+        }
+        rest = &reference[end + 1..];
+    }
+    out.text.push_str(rest);
+    out
+}
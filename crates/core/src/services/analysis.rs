@@ -1,11 +1,21 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::db::{ProjectContext, RitualRunRecord, RitualRunStatus};
+use crate::db::{
+    DbResult, FunctionAnnotation, MemberSource, ProjectContext, ProjectDb, RitualRunRecord,
+    RitualRunStatus, RunUsage, SliceMember,
+};
+use crate::services::cache::{AnalysisCache, CacheKey};
+use crate::services::entry_points::{apply_auto_roots, EntryPoint};
+use crate::services::import::pdb::PdbSymbols;
+use crate::services::process::track_child_usage;
+use crate::services::roots::RootPattern;
 
 /// Minimal IR for functions encountered during analysis.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,6 +25,23 @@ pub struct FunctionRecord {
     pub size: Option<u32>,
     pub in_slice: bool,
     pub is_boundary: bool,
+    /// Original symbol when `name` was demangled (see [`crate::services::demangle`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mangled_name: Option<String>,
+    /// Parameter count and calling convention (see [`crate::services::signature`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<crate::services::signature::FunctionSignature>,
+    /// Architecture slice the function came from, for multi-arch (fat Mach-O) binaries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    /// Address-independent instruction fingerprint for matching the function across builds
+    /// (see [`crate::services::fingerprint`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuzzy_hash: Option<String>,
+    /// Instruction set the function was decoded in (`arm` or `thumb` on 32-bit ARM, where
+    /// binaries mix both); `None` on architectures with a single mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isa: Option<String>,
 }
 
 /// Call edge between functions.
@@ -23,6 +50,26 @@ pub struct CallEdge {
     pub from: u64,
     pub to: u64,
     pub is_cross_slice: bool,
+    /// Name of the call target, resolved against the function table and imports (see
+    /// [`resolve_call_edges`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_name: Option<String>,
+    /// Whether the target is an import stub (PLT entry, thunk, or imported function).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub to_import: bool,
+}
+
+impl CallEdge {
+    /// Target of the edge as reports print it: `name`, `name@plt` for imports (versioned ELF
+    /// imports such as `memcpy@GLIBC_2.14` as they are), or the hex address when it was not
+    /// resolved.
+    pub fn target_label(&self) -> String {
+        match &self.to_name {
+            Some(name) if self.to_import && !name.contains('@') => format!("{name}@plt"),
+            Some(name) => name.clone(),
+            None => format!("0x{:X}", self.to),
+        }
+    }
 }
 
 /// Evidence to justify classification/decisions.
@@ -32,10 +79,54 @@ pub struct EvidenceRecord {
     pub description: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub kind: Option<EvidenceKind>,
+    /// Backend and pass that produced the record (`None` only for results recorded before
+    /// provenance was tracked).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<EvidenceSource>,
+    /// Run the record was loaded from; set when read back from the project DB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<i64>,
+}
+
+/// Provenance of an evidence record: the backend (or importer, `core` for passes run after
+/// every backend, `plugin` for WASM plugins, `decoder` for string decoders) and the pass within
+/// it. Written as `backend/pass`, e.g. `capstone/strings` or `core/string-xrefs`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EvidenceSource {
+    pub backend: String,
+    pub pass: String,
+}
+
+impl EvidenceSource {
+    /// `backend` of passes ritual-core runs on every result (string xrefs, PDB types).
+    pub const CORE: &'static str = "core";
+
+    pub fn new(backend: impl Into<String>, pass: impl Into<String>) -> Self {
+        Self { backend: backend.into(), pass: pass.into() }
+    }
+
+    /// Parse the `backend/pass` form; a value without `/` is a backend with pass `analysis`.
+    pub fn parse(text: &str) -> Self {
+        match text.split_once('/') {
+            Some((backend, pass)) => Self::new(backend, pass),
+            None => Self::new(text, "analysis"),
+        }
+    }
+
+    /// Whether `filter` names this source: its backend, or the full `backend/pass`.
+    pub fn matches(&self, filter: &str) -> bool {
+        self.backend == filter || self.to_string() == filter
+    }
+}
+
+impl std::fmt::Display for EvidenceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.backend, self.pass)
+    }
 }
 
 /// Optional classification for evidence entries to support grouping in reports.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EvidenceKind {
     String,
@@ -44,6 +135,72 @@ pub enum EvidenceKind {
     Other,
 }
 
+impl EvidenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvidenceKind::String => "string",
+            EvidenceKind::Import => "import",
+            EvidenceKind::Call => "call",
+            EvidenceKind::Other => "other",
+        }
+    }
+}
+
+/// Structured cross-reference from an instruction to a target address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XrefRecord {
+    pub from: u64,
+    pub to: u64,
+    pub kind: XrefKind,
+    /// Section containing the target, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    /// Printable preview of the bytes at the target (non-printables shown as `.`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+/// How an xref target was referenced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum XrefKind {
+    /// Immediate operand pointing into a mapped section.
+    Immediate,
+    /// Memory operand displacement (e.g. RIP-relative loads).
+    Memory,
+    /// Address built across several instructions (`adrp`+`add`, `lea` then a register-based
+    /// operand) and resolved by constant propagation.
+    Computed,
+    Call,
+    Jump,
+    Other,
+}
+
+impl XrefKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            XrefKind::Immediate => "immediate",
+            XrefKind::Memory => "memory",
+            XrefKind::Computed => "computed",
+            XrefKind::Call => "call",
+            XrefKind::Jump => "jump",
+            XrefKind::Other => "other",
+        }
+    }
+
+    /// Parse a persisted kind; unknown values map to `Other`.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "immediate" => XrefKind::Immediate,
+            "memory" => XrefKind::Memory,
+            "computed" => XrefKind::Computed,
+            "call" => XrefKind::Call,
+            "jump" => XrefKind::Jump,
+            _ => XrefKind::Other,
+        }
+    }
+}
+
 /// Kind of control-flow edge for a basic block successor.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BlockEdgeKind {
@@ -55,11 +212,25 @@ pub enum BlockEdgeKind {
     IndirectCall,
 }
 
+/// One disassembled instruction, for listings (not persisted with analysis results).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionRecord {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub operands: String,
+}
+
 /// Basic block representation for lightweight CFG export.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BasicBlock {
     pub start: u64,
-    pub len: u32,
+    /// Size in bytes; 0 when unknown (Capstone runs recorded before schema v28).
+    #[serde(default)]
+    pub byte_len: u32,
+    /// Number of instructions, when the backend reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insn_count: Option<u32>,
     pub successors: Vec<BlockEdge>,
 }
 
@@ -76,39 +247,90 @@ pub struct AnalysisResult {
     pub functions: Vec<FunctionRecord>,
     pub call_edges: Vec<CallEdge>,
     pub evidence: Vec<EvidenceRecord>,
+    /// Structured xrefs (also summarized as free-text evidence for older consumers).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub xrefs: Vec<XrefRecord>,
     pub basic_blocks: Vec<BasicBlock>,
     pub roots: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub root_hits: Vec<RootHit>,
+    /// Labeled sub-slices carved by this run (empty unless the spec grouped its roots).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_slices: Vec<SubSlice>,
     pub backend_version: Option<String>,
     pub backend_path: Option<String>,
+    /// Bytes of executable code in the binary, the denominator of slice coverage (`None` when
+    /// the backend cannot tell).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_bytes: Option<u64>,
 }
 
 /// Metadata to persist alongside an analysis run.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RunMetadata {
     pub spec_hash: String,
+    /// Row id of the binary the spec resolved to, if known.
+    #[serde(default)]
+    pub binary_id: Option<i64>,
     pub binary_hash: Option<String>,
     pub backend: String,
     pub backend_version: Option<String>,
     pub backend_path: Option<String>,
     pub status: RitualRunStatus,
+    /// Sort the result and record fixed timestamps (see [`crate::services::deterministic`]).
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Fixed timestamp recorded instead of the current time (set for deterministic runs).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<String>,
+    /// Backends of a fallback chain that failed before `backend` produced the result.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_backends: Vec<BackendFailure>,
+    /// Spec file the run came from (project-relative when inside the project).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_path: Option<String>,
+    /// When the analysis started; the run is recorded as starting when it is recorded when
+    /// unset. Filled in from [`CachedRun::record_timing`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// Duration and tool resource usage of the analysis (see [`CachedRun::record_timing`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RunUsage>,
+}
+
+/// A backend that was tried and failed before the run fell back to the next one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendFailure {
+    pub backend: String,
+    pub error: String,
+}
+
+impl RunMetadata {
+    /// Timestamp to record for the run: the fixed `epoch` when set, otherwise now.
+    pub fn timestamp(&self) -> String {
+        self.epoch.clone().unwrap_or_else(|| Utc::now().to_rfc3339())
+    }
 }
 
 /// Options for analysis traversal.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct AnalysisOptions {
+    /// Call depth to follow from the roots; callees beyond it are not explored.
     pub max_depth: Option<u32>,
     pub include_imports: bool,
     pub include_strings: bool,
-    /// Optional instruction budget for backends that disassemble.
+    /// Instructions a disassembling backend decodes per function.
     pub max_instructions: Option<usize>,
+    /// Instructions a disassembling backend decodes over the whole binary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_instructions: Option<usize>,
 }
 
 /// Request to analyze a binary for a ritual.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisRequest {
     pub ritual_name: String,
+    /// Binary name the run is recorded under (see `ProjectDb::binary_run_name`).
     pub binary_name: String,
     pub binary_path: PathBuf,
     pub roots: Vec<String>,
@@ -117,6 +339,72 @@ pub struct AnalysisRequest {
     pub options: AnalysisOptions,
     /// Optional explicit backend tool path (e.g., configured rizin/ghidra path).
     pub backend_path: Option<PathBuf>,
+    /// Optional labeled root groups; each group becomes a sub-slice of the run.
+    #[serde(default)]
+    pub root_groups: Vec<RootGroup>,
+    /// Map the file as a raw image (no container format) instead of parsing it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<RawImage>,
+    /// Wall-clock limit for the external tools a backend drives; they are killed once it is
+    /// spent (see [`crate::services::process`]). In-process backends ignore it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Duration>,
+    /// Function names imported from symbol maps (`import-symbols`), by address; they replace
+    /// the backend's names (see [`apply_imported_symbols`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub symbols: BTreeMap<u64, String>,
+    /// Functions from the binary's PDB, rebased onto the image; they name and size functions
+    /// and add their types as evidence (see [`apply_pdb_symbols`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdb: Option<PdbSymbols>,
+}
+
+/// How to map a raw image such as a firmware dump: the whole file is code and data loaded
+/// at `load_address`. The architecture comes from [`AnalysisRequest::arch`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RawImage {
+    /// Address the first byte of the file is loaded at.
+    pub load_address: u64,
+    /// Decode ARM code as Thumb.
+    #[serde(default)]
+    pub thumb: bool,
+    #[serde(default)]
+    pub endian: Endian,
+}
+
+/// Byte order of a raw image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endian::Little => "little",
+            Endian::Big => "big",
+        }
+    }
+}
+
+/// Named group of roots declared by a ritual spec (e.g., `ui: [CUIManager::init]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootGroup {
+    pub label: String,
+    pub roots: Vec<String>,
+}
+
+/// Functions attributed to a labeled root group: its matched roots plus everything
+/// reachable from them through call edges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubSlice {
+    pub label: String,
+    pub roots: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<u64>,
 }
 
 /// Mapping of a ritual root to the function(s) it matched in the backend.
@@ -125,22 +413,86 @@ pub struct RootHit {
     pub root: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub functions: Vec<u64>,
+    /// Why the root resolved to nothing (no matching function, or an invalid pattern).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Error)]
 pub enum AnalysisError {
     #[error("Binary not found at {0}")]
     MissingBinary(PathBuf),
-    #[error("Backend not found: {0}")]
-    MissingBackend(String),
+    #[error("Backend '{name}' not found (available: {})", .available.join(", "))]
+    MissingBackend { name: String, available: Vec<String> },
     #[error("Analysis backend error: {0}")]
     Backend(String),
+    /// An external tool ran past the run's `timeout` and was killed.
+    #[error("{tool} timed out after {after:?} and was killed")]
+    Timeout { tool: String, after: Duration },
+    /// An external tool was killed because the process got SIGINT or SIGTERM.
+    #[error("{tool} was interrupted and killed")]
+    Interrupted { tool: String },
+    /// Every backend of a fallback chain failed; `last` is the error of the last one tried.
+    #[error("Backend fallback failed (tried {}): {last}", .tried.join(", "))]
+    Fallback { tried: Vec<String>, last: Box<AnalysisError> },
+}
+
+impl AnalysisError {
+    /// Whether a backend tool ran out of time or was interrupted (directly or as the end of a
+    /// fallback chain).
+    pub fn is_canceled(&self) -> bool {
+        match self {
+            AnalysisError::Timeout { .. } | AnalysisError::Interrupted { .. } => true,
+            AnalysisError::Fallback { last, .. } => last.is_canceled(),
+            _ => false,
+        }
+    }
+}
+
+/// What a backend extracts and whether it can run on this machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendCapabilities {
+    /// Architectures the backend disassembles (empty when it does not disassemble).
+    pub archs: Vec<String>,
+    pub strings: bool,
+    pub imports: bool,
+    pub basic_blocks: bool,
+    pub decompilation: bool,
+    /// External executable the backend drives (`None` for in-process backends).
+    pub external_tool: Option<String>,
+    /// Where the external tool resolved to.
+    pub tool_path: Option<PathBuf>,
+    /// Version of the external tool or linked library.
+    pub tool_version: Option<String>,
+    /// Why the backend cannot run here (`None` when it can).
+    pub unavailable: Option<String>,
+}
+
+impl BackendCapabilities {
+    pub fn is_available(&self) -> bool {
+        self.unavailable.is_none()
+    }
 }
 
 /// Trait implemented by analysis backends (e.g., Capstone + rizin).
 pub trait AnalysisBackend: Send + Sync {
     fn analyze(&self, request: &AnalysisRequest) -> Result<AnalysisResult, AnalysisError>;
     fn name(&self) -> &'static str;
+
+    /// Supported architectures and outputs, plus tool resolution. `tool_path` is the
+    /// configured tool location (project `backends.*`), which wins over environment lookup as
+    /// [`AnalysisRequest::backend_path`] does. Resolving may spawn the tool to read its
+    /// version.
+    fn capabilities(&self, _tool_path: Option<&Path>) -> BackendCapabilities {
+        BackendCapabilities::default()
+    }
+
+    /// Entry point and exported functions of the request's binary, for `entrypoint` /
+    /// `exports` roots. Only asked when the request has such roots; the default reads the
+    /// ELF, PE, or Mach-O headers.
+    fn entry_points(&self, request: &AnalysisRequest) -> Result<Vec<EntryPoint>, AnalysisError> {
+        crate::services::entry_points::request_entry_points(request)
+    }
 }
 
 /// Registry for analysis backends; callers select by name.
@@ -183,43 +535,279 @@ impl<'a> RitualRunner<'a> {
         request: &AnalysisRequest,
         meta: &RunMetadata,
     ) -> Result<AnalysisResult, AnalysisError> {
-        // Verify binary exists on disk if provided as a relative path in config.
-        if !request.binary_path.is_file() {
-            return Err(AnalysisError::MissingBinary(request.binary_path.clone()));
-        }
+        let run = analyze_request(self.backend, request, meta, None)?;
+        self.persist(request, meta, &run);
+        Ok(run.result)
+    }
 
-        let mut result = self.backend.analyze(request)?;
-        if result.backend_path.is_none() {
-            result.backend_path = request.backend_path.as_ref().map(|p| p.display().to_string());
+    /// Like [`RitualRunner::run`], but serve/store the backend result through `cache`.
+    ///
+    /// Runs whose binary hash is unknown bypass the cache. Failing to write an entry does not
+    /// fail the run.
+    pub fn run_cached(
+        &self,
+        request: &AnalysisRequest,
+        meta: &RunMetadata,
+        cache: &AnalysisCache,
+    ) -> Result<CachedRun, AnalysisError> {
+        let run = analyze_request(self.backend, request, meta, Some(cache))?;
+        self.persist(request, meta, &run);
+        Ok(run)
+    }
+
+    /// Persist the run record plus analysis rows (best-effort; errors do not fail the run).
+    fn persist(&self, request: &AnalysisRequest, meta: &RunMetadata, run: &CachedRun) {
+        let mut meta = meta.clone();
+        run.record_timing(&mut meta);
+        let _ = persist_run(&self.ctx.db, request, &meta, &run.result);
+    }
+}
+
+/// Run `backend` for `request` without touching the project DB.
+///
+/// With a `cache`, results are served from/stored into it; runs whose binary hash is unknown
+/// bypass the cache and failing to write an entry is ignored. The returned result has
+/// backend metadata and sub-slices filled in (see [`finalize_result`]). Needs no DB handle,
+/// so batches can analyze on worker threads and persist afterwards with [`persist_run`].
+///
+/// The returned run says how long that took and what the backend's tool processes used.
+pub fn analyze_request(
+    backend: &dyn AnalysisBackend,
+    request: &AnalysisRequest,
+    meta: &RunMetadata,
+    cache: Option<&AnalysisCache>,
+) -> Result<CachedRun, AnalysisError> {
+    // Verify binary exists on disk if provided as a relative path in config.
+    if !request.binary_path.is_file() {
+        return Err(AnalysisError::MissingBinary(request.binary_path.clone()));
+    }
+    let started_at = meta.timestamp();
+    let clock = Instant::now();
+    let (outcome, children) = track_child_usage(|| analyze_untimed(backend, request, meta, cache));
+    let (result, cache_hit) = outcome?;
+    let usage = RunUsage {
+        duration_ms: clock.elapsed().as_millis() as u64,
+        cpu_ms: children.map(|c| c.cpu_ms),
+        peak_rss_kb: children.map(|c| c.peak_rss_kb),
+    };
+    Ok(CachedRun { result, cache_hit, started_at, usage })
+}
+
+/// [`analyze_request`] after the binary check: the finalized result and whether it came
+/// from the cache.
+fn analyze_untimed(
+    backend: &dyn AnalysisBackend,
+    request: &AnalysisRequest,
+    meta: &RunMetadata,
+    cache: Option<&AnalysisCache>,
+) -> Result<(AnalysisResult, bool), AnalysisError> {
+    let _span = tracing::info_span!(
+        "backend",
+        backend = backend.name(),
+        binary = %request.binary_name,
+        ritual = %request.ritual_name,
+    )
+    .entered();
+    let key = cache.and_then(|_| CacheKey::for_request(request, meta));
+    if let (Some(cache), Some(key)) = (cache, &key) {
+        if let Some(mut result) = cache.get(key) {
+            tracing::debug!(functions = result.functions.len(), "reused cached analysis result");
+            apply_auto_roots(backend, request, &mut result)?;
+            return Ok((finalize_result(request, meta, result), true));
         }
-        if result.backend_version.is_none() && meta.backend_version.is_some() {
-            result.backend_version = meta.backend_version.clone();
+    }
+    tracing::debug!(
+        roots = request.roots.len(),
+        path = %request.binary_path.display(),
+        "invoking backend"
+    );
+    let mut result = backend.analyze(request).inspect_err(|err| {
+        tracing::debug!("backend failed: {}", err);
+    })?;
+    tracing::info!(
+        functions = result.functions.len(),
+        call_edges = result.call_edges.len(),
+        basic_blocks = result.basic_blocks.len(),
+        evidence = result.evidence.len(),
+        "backend finished"
+    );
+    if let (Some(cache), Some(key)) = (cache, &key) {
+        if let Err(err) = cache.put(key, &result) {
+            tracing::warn!("failed to write analysis cache entry: {}", err);
         }
+    }
+    // After caching: entry points come from the binary, not the backend's analysis.
+    apply_auto_roots(backend, request, &mut result)?;
+    Ok((finalize_result(request, meta, result), false))
+}
 
-        // Persist a ritual run record in the DB (stub status until we store richer data).
-        let now = Utc::now().to_rfc3339();
-        let run_record = RitualRunRecord {
-            binary: request.binary_name.clone(),
-            ritual: request.ritual_name.clone(),
-            spec_hash: meta.spec_hash.clone(),
-            binary_hash: meta.binary_hash.clone(),
-            backend: meta.backend.clone(),
-            backend_version: result
-                .backend_version
-                .clone()
-                .or_else(|| meta.backend_version.clone()),
-            backend_path: result.backend_path.clone().or_else(|| meta.backend_path.clone()),
-            status: meta.status.clone(),
-            started_at: now.clone(),
-            finished_at: now,
+/// Run `request` through the backends of `chain` in order until one succeeds.
+///
+/// `prepare` gives the request and metadata for each backend (tool path, version). A backend
+/// that is not registered or fails hands over to the next one; a missing binary, a timeout,
+/// or an interrupt ends the chain, since another backend would not fare better. The returned metadata names
+/// the backend that produced the result and lists the ones that failed before it.
+pub fn analyze_with_fallback(
+    registry: &BackendRegistry,
+    chain: &[String],
+    cache: Option<&AnalysisCache>,
+    mut prepare: impl FnMut(&str) -> (AnalysisRequest, RunMetadata),
+) -> Result<(CachedRun, RunMetadata), AnalysisError> {
+    let mut failed: Vec<BackendFailure> = Vec::new();
+    let mut last_err =
+        AnalysisError::MissingBackend { name: String::new(), available: registry.names() };
+    for name in chain {
+        let (request, mut meta) = prepare(name);
+        meta.failed_backends = failed.clone();
+        let outcome = match registry.get(name) {
+            Some(backend) => analyze_request(backend, &request, &meta, cache),
+            None => Err(AnalysisError::MissingBackend {
+                name: name.clone(),
+                available: registry.names(),
+            }),
         };
-        let run_id = self.ctx.db.insert_ritual_run(&run_record).ok();
-        if let Some(id) = run_id {
-            // Best-effort persistence of analysis details; ignore errors to avoid failing the run.
-            let _ = self.ctx.db.insert_analysis_result(id, &result);
+        let err = match outcome {
+            Ok(run) => {
+                run.record_timing(&mut meta);
+                return Ok((run, meta));
+            }
+            Err(err) => err,
+        };
+        let fatal = matches!(err, AnalysisError::MissingBinary(_)) || err.is_canceled();
+        if !fatal && failed.len() + 1 < chain.len() {
+            tracing::warn!("backend {} failed, trying the next one: {}", name, err);
+        }
+        failed.push(BackendFailure { backend: name.clone(), error: err.to_string() });
+        last_err = err;
+        if fatal {
+            break;
         }
+    }
+    if failed.len() > 1 {
+        let tried = failed.into_iter().map(|f| f.backend).collect();
+        return Err(AnalysisError::Fallback { tried, last: Box::new(last_err) });
+    }
+    Err(last_err)
+}
 
-        Ok(result)
+/// Fill backend metadata, evidence sources, and sub-slices the backend left empty, link strings to the code
+/// referencing them (resolving `string:` roots), apply imported symbol names, and demangle
+/// function names.
+pub fn finalize_result(
+    request: &AnalysisRequest,
+    meta: &RunMetadata,
+    mut result: AnalysisResult,
+) -> AnalysisResult {
+    if result.backend_path.is_none() {
+        result.backend_path = request.backend_path.as_ref().map(|p| p.display().to_string());
+    }
+    if result.backend_version.is_none() && meta.backend_version.is_some() {
+        result.backend_version = meta.backend_version.clone();
+    }
+    // Before the core passes, which label their own rows.
+    for evidence in result.evidence.iter_mut().filter(|e| e.source.is_none()) {
+        evidence.source = Some(EvidenceSource::new(&meta.backend, "analysis"));
+    }
+    crate::services::strings::link_string_xrefs(&mut result);
+    crate::services::strings::resolve_string_roots(&mut result);
+    if result.sub_slices.is_empty() && !request.root_groups.is_empty() {
+        result.sub_slices = build_sub_slices(&request.root_groups, &result);
+    }
+    // After root matching, which compares against the names the backend reported.
+    if let Some(pdb) = &request.pdb {
+        apply_pdb_symbols(&mut result, pdb);
+    }
+    apply_imported_symbols(&mut result, &request.symbols);
+    crate::services::demangle::demangle_functions(&mut result.functions);
+    // After every renaming step, so edges carry the names the report shows.
+    resolve_call_edges(&mut result);
+    if meta.deterministic {
+        crate::services::deterministic::canonicalize_result(&mut result);
+    }
+    result
+}
+
+/// Insert the ritual run record, its analysis rows, and snapshots of the slices it feeds
+/// (see [`ProjectDb::record_slice_snapshots`]); returns the new run id.
+///
+/// Call inside [`ProjectDb::transaction`] to persist several runs atomically.
+pub fn persist_run(
+    db: &ProjectDb,
+    request: &AnalysisRequest,
+    meta: &RunMetadata,
+    result: &AnalysisResult,
+) -> DbResult<i64> {
+    let run_id = db.insert_ritual_run(&run_record(request, meta, Some(result)))?;
+    db.insert_analysis_result(run_id, result)?;
+    db.record_slice_snapshots(run_id)?;
+    tracing::debug!(run_id, ritual = %request.ritual_name, "recorded ritual run");
+    Ok(run_id)
+}
+
+/// Insert the run record of an analysis that did not finish, without analysis rows;
+/// `meta.status` says how it ended (e.g. `failed`, or `canceled` after a timeout).
+pub fn persist_failed_run(
+    db: &ProjectDb,
+    request: &AnalysisRequest,
+    meta: &RunMetadata,
+) -> DbResult<i64> {
+    let run_id = db.insert_ritual_run(&run_record(request, meta, None))?;
+    tracing::debug!(
+        run_id,
+        ritual = %request.ritual_name,
+        status = meta.status.as_str(),
+        "recorded unfinished ritual run"
+    );
+    Ok(run_id)
+}
+
+fn run_record(
+    request: &AnalysisRequest,
+    meta: &RunMetadata,
+    result: Option<&AnalysisResult>,
+) -> RitualRunRecord {
+    let now = meta.timestamp();
+    RitualRunRecord {
+        binary: request.binary_name.clone(),
+        binary_id: meta.binary_id,
+        ritual: request.ritual_name.clone(),
+        spec_hash: meta.spec_hash.clone(),
+        binary_hash: meta.binary_hash.clone(),
+        backend: meta.backend.clone(),
+        backend_version: result
+            .and_then(|r| r.backend_version.clone())
+            .or_else(|| meta.backend_version.clone()),
+        backend_path: result
+            .and_then(|r| r.backend_path.clone())
+            .or_else(|| meta.backend_path.clone()),
+        status: meta.status.clone(),
+        started_at: meta.started_at.clone().unwrap_or_else(|| now.clone()),
+        finished_at: now,
+        superseded_by: None,
+        deterministic: meta.deterministic,
+        spec_path: meta.spec_path.clone(),
+        analysis_options: Some(request.options.clone()),
+        usage: meta.usage,
+    }
+}
+
+/// Result of [`RitualRunner::run_cached`].
+#[derive(Debug, Clone)]
+pub struct CachedRun {
+    pub result: AnalysisResult,
+    /// True when the backend was skipped because a cached result matched.
+    pub cache_hit: bool,
+    /// When the analysis started ([`RunMetadata::timestamp`], so fixed for deterministic
+    /// runs).
+    pub started_at: String,
+    pub usage: RunUsage,
+}
+
+impl CachedRun {
+    /// Record this run's start time and usage in `meta`, for [`persist_run`].
+    pub fn record_timing(&self, meta: &mut RunMetadata) {
+        meta.started_at = Some(self.started_at.clone());
+        meta.usage = Some(self.usage);
     }
 }
 
@@ -241,22 +829,33 @@ impl AnalysisBackend for ValidateOnlyBackend {
             root_hits: request
                 .roots
                 .iter()
-                .map(|r| RootHit { root: r.clone(), functions: Vec::new() })
+                .map(|r| RootHit { root: r.clone(), functions: Vec::new(), error: None })
                 .collect(),
             backend_version: Some("validate-only".into()),
             backend_path: None,
+            sub_slices: Vec::new(),
+            xrefs: Vec::new(),
+            text_bytes: None,
         })
     }
 
     fn name(&self) -> &'static str {
         "validate-only"
     }
+
+    fn capabilities(&self, _tool_path: Option<&Path>) -> BackendCapabilities {
+        BackendCapabilities {
+            tool_version: Some("validate-only".into()),
+            ..BackendCapabilities::default()
+        }
+    }
 }
 
 /// Convenience builder for a registry populated with the validate-only backend.
 pub fn default_backend_registry() -> BackendRegistry {
     let mut registry = BackendRegistry::new();
     registry.register(ValidateOnlyBackend);
+    registry.register(crate::services::backends::GhidraXmlBackend);
     #[cfg(feature = "capstone-backend")]
     {
         registry.register(crate::services::backends::CapstoneBackend);
@@ -269,38 +868,326 @@ pub fn default_backend_registry() -> BackendRegistry {
     {
         registry.register(crate::services::backends::GhidraBackend);
     }
+    #[cfg(feature = "wasm-backend")]
+    {
+        registry.register(crate::services::backends::WasmBackend);
+    }
     registry
 }
 
-/// Utility to map roots to functions by exact name or hex address (0x-prefixed).
+/// Overlay human-assigned names onto analyzed functions (annotations win over backend names).
+pub fn apply_annotations(result: &mut AnalysisResult, annotations: &[FunctionAnnotation]) {
+    let names: HashMap<u64, &str> = annotations
+        .iter()
+        .filter_map(|a| a.name.as_deref().map(|name| (a.address, name)))
+        .collect();
+    for func in &mut result.functions {
+        if let Some(name) = names.get(&func.address) {
+            func.name = Some((*name).to_string());
+        }
+    }
+}
+
+/// Name call targets: import records (`import: NAME [(lib)]`, or `import module.name` for
+/// WebAssembly) at the target mark the edge as a call into an import; otherwise the name of
+/// the function at the target is used. Targets neither table knows keep `to_name` unset.
+pub fn resolve_call_edges(result: &mut AnalysisResult) {
+    let imports: HashMap<u64, &str> = result
+        .evidence
+        .iter()
+        .filter(|e| e.kind == Some(EvidenceKind::Import))
+        .filter_map(|e| Some((e.address, import_symbol(&e.description)?)))
+        .collect();
+    let functions: HashMap<u64, &str> =
+        result.functions.iter().filter_map(|f| Some((f.address, f.name.as_deref()?))).collect();
+    for edge in &mut result.call_edges {
+        if let Some(name) = imports.get(&edge.to) {
+            edge.to_name = Some(name.to_string());
+            edge.to_import = true;
+        } else if let Some(name) = functions.get(&edge.to) {
+            edge.to_name = Some(name.to_string());
+            edge.to_import = false;
+        }
+    }
+}
+
+/// Symbol of an import record's description, without its library.
+fn import_symbol(description: &str) -> Option<&str> {
+    let rest =
+        description.strip_prefix("import: ").or_else(|| description.strip_prefix("import "))?;
+    let name = rest.split_once(" (").map_or(rest, |(name, _)| name).trim();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Name functions after imported symbols, replacing whatever name the backend found.
+///
+/// Imported names may be mangled; the original symbol of a replaced name is dropped so
+/// demangling starts from the imported one.
+pub fn apply_imported_symbols(result: &mut AnalysisResult, symbols: &BTreeMap<u64, String>) {
+    for func in &mut result.functions {
+        if let Some(name) = symbols.get(&func.address) {
+            if func.name.as_ref() != Some(name) {
+                func.name = Some(name.clone());
+                func.mangled_name = None;
+            }
+        }
+    }
+}
+
+/// Name functions after their PDB procedures, fill in sizes the backend did not find, and
+/// record the types of in-slice functions as `pdb type: …` evidence.
+pub fn apply_pdb_symbols(result: &mut AnalysisResult, pdb: &PdbSymbols) {
+    let by_address: HashMap<u64, _> = pdb.functions.iter().map(|f| (f.address, f)).collect();
+    for func in &mut result.functions {
+        let Some(symbol) = by_address.get(&func.address) else {
+            continue;
+        };
+        if func.name.as_deref() != Some(symbol.name.as_str()) {
+            func.name = Some(symbol.name.clone());
+            func.mangled_name = symbol.mangled_name.clone();
+        }
+        if func.size.is_none() {
+            func.size = symbol.size;
+        }
+        let Some(type_name) = symbol.type_name.as_deref().filter(|_| func.in_slice) else {
+            continue;
+        };
+        let description = format!("pdb type: {type_name}");
+        if !result
+            .evidence
+            .iter()
+            .any(|e| e.address == func.address && e.description == description)
+        {
+            result.evidence.push(EvidenceRecord {
+                address: func.address,
+                description,
+                kind: Some(EvidenceKind::Other),
+                source: Some(EvidenceSource::new(EvidenceSource::CORE, "pdb-types")),
+                run_id: None,
+            });
+        }
+    }
+}
+
+/// Overlay manual slice membership onto analyzed functions.
+///
+/// Manual additions mark functions in-slice (adding unnamed records for addresses the backend
+/// did not report); manual exclusions clear `in_slice`. Analysis-derived rows are already
+/// reflected in the result and are ignored.
+pub fn apply_slice_members(result: &mut AnalysisResult, members: &[SliceMember]) {
+    for member in members.iter().filter(|m| m.source == MemberSource::Manual) {
+        match result.functions.iter_mut().find(|f| f.address == member.address) {
+            Some(func) => func.in_slice = !member.excluded,
+            None if !member.excluded => result.functions.push(FunctionRecord {
+                address: member.address,
+                name: None,
+                size: None,
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            }),
+            None => {}
+        }
+    }
+}
+
+/// Map roots to the functions they match (see [`crate::services::roots`] for root forms).
+///
+/// Roots that match nothing, or whose pattern is invalid, get an `error` explaining why.
 pub fn build_root_hits(roots: &[String], functions: &[FunctionRecord]) -> Vec<RootHit> {
     roots
         .iter()
-        .map(|root| {
-            let addr_match = parse_hex_addr(root);
-            let mut matches = Vec::new();
-            for f in functions {
-                if let Some(addr) = addr_match {
-                    if f.address == addr {
-                        matches.push(f.address);
-                        continue;
+        .map(|root| match RootPattern::parse(root) {
+            Ok(pattern) => {
+                let matches: Vec<u64> =
+                    functions.iter().filter(|f| pattern.matches(f)).map(|f| f.address).collect();
+                let error = matches.is_empty().then(|| "no function matched".to_string());
+                RootHit { root: root.clone(), functions: matches, error }
+            }
+            Err(err) => {
+                RootHit { root: root.clone(), functions: Vec::new(), error: Some(err.to_string()) }
+            }
+        })
+        .collect()
+}
+
+/// Attribute functions to labeled root groups.
+///
+/// Each group is seeded with the functions its roots matched (from `root_hits` when the
+/// backend provided them, otherwise by name/address) and then expanded along call edges.
+/// A function reachable from several groups is listed under each of them.
+pub fn build_sub_slices(groups: &[RootGroup], result: &AnalysisResult) -> Vec<SubSlice> {
+    let known: std::collections::HashSet<u64> =
+        result.functions.iter().map(|f| f.address).collect();
+    let mut callees: HashMap<u64, Vec<u64>> = HashMap::new();
+    for edge in &result.call_edges {
+        callees.entry(edge.from).or_default().push(edge.to);
+    }
+
+    groups
+        .iter()
+        .map(|group| {
+            let mut seeds = Vec::new();
+            for root in &group.roots {
+                match result.root_hits.iter().find(|h| &h.root == root) {
+                    Some(hit) => seeds.extend(hit.functions.iter().copied()),
+                    None => {
+                        for hit in build_root_hits(std::slice::from_ref(root), &result.functions) {
+                            seeds.extend(hit.functions);
+                        }
                     }
                 }
-                if let Some(name) = &f.name {
-                    if name == root {
-                        matches.push(f.address);
-                    }
+            }
+
+            let mut seen = std::collections::BTreeSet::new();
+            let mut queue: std::collections::VecDeque<u64> = seeds.into_iter().collect();
+            while let Some(addr) = queue.pop_front() {
+                if !known.contains(&addr) || !seen.insert(addr) {
+                    continue;
                 }
+                if let Some(next) = callees.get(&addr) {
+                    queue.extend(next.iter().copied());
+                }
+            }
+
+            SubSlice {
+                label: group.label.clone(),
+                roots: group.roots.clone(),
+                functions: seen.into_iter().collect(),
             }
-            RootHit { root: root.clone(), functions: matches }
         })
         .collect()
 }
 
-fn parse_hex_addr(root: &str) -> Option<u64> {
-    let trimmed = root.trim_start_matches("0x");
-    if trimmed.is_empty() {
-        return None;
+/// Invariants [`validate_result`] checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationCheck {
+    /// Two functions share an entry address (within one architecture slice).
+    DuplicateFunction,
+    /// A basic block or one of its direct successors lies outside every mapped section.
+    UnmappedBlockTarget,
+    /// A call edge targets neither a function start nor a known import.
+    CallTargetNotFunction,
+    /// A root matched no function.
+    UnresolvedRoot,
+}
+
+/// One invariant violation in an analysis result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub check: ValidationCheck,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<u64>,
+    pub message: String,
+}
+
+/// Outcome of [`validate_result`], written as the `validation` section of `report.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultValidation {
+    pub issues: Vec<ValidationIssue>,
+    /// Checks that could not run, and why (e.g. no section layout for the binary).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+impl ResultValidation {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check a result's CFG invariants before it is persisted: no duplicate functions, basic
+/// blocks and their direct successors inside mapped sections of `layout`, call edges landing
+/// on function starts (or on addresses the backend noted as imports), and every root
+/// resolved. Block bounds are skipped when the layout is unknown.
+pub fn validate_result(
+    result: &AnalysisResult,
+    layout: Option<&crate::services::loader::ImageLayout>,
+) -> ResultValidation {
+    let mut issues = Vec::new();
+    let mut skipped = Vec::new();
+    let issue = |check, address, message| ValidationIssue { check, address, message };
+
+    let mut seen = std::collections::HashSet::new();
+    for func in &result.functions {
+        if !seen.insert((func.address, func.arch.as_deref())) {
+            issues.push(issue(
+                ValidationCheck::DuplicateFunction,
+                Some(func.address),
+                format!("duplicate function at 0x{:X}", func.address),
+            ));
+        }
+    }
+
+    if let Some(layout) = layout {
+        let mapped = |addr: u64| layout.region_for(addr).is_some();
+        for block in &result.basic_blocks {
+            if !mapped(block.start) {
+                issues.push(issue(
+                    ValidationCheck::UnmappedBlockTarget,
+                    Some(block.start),
+                    format!("basic block 0x{:X} lies outside every mapped section", block.start),
+                ));
+            }
+            let direct = block.successors.iter().filter(|edge| {
+                !matches!(edge.kind, BlockEdgeKind::IndirectJump | BlockEdgeKind::IndirectCall)
+            });
+            for edge in direct.filter(|edge| !mapped(edge.target)) {
+                issues.push(issue(
+                    ValidationCheck::UnmappedBlockTarget,
+                    Some(edge.target),
+                    format!(
+                        "basic block 0x{:X} branches to unmapped address 0x{:X}",
+                        block.start, edge.target
+                    ),
+                ));
+            }
+        }
+    } else if !result.basic_blocks.is_empty() {
+        skipped.push("block targets: the binary's section layout is unknown".into());
+    }
+
+    let starts: std::collections::HashSet<u64> = result
+        .functions
+        .iter()
+        .map(|f| f.address)
+        .chain(
+            result
+                .evidence
+                .iter()
+                .filter(|e| e.kind == Some(EvidenceKind::Import))
+                .map(|e| e.address),
+        )
+        .collect();
+    let mut reported = std::collections::HashSet::new();
+    for edge in result.call_edges.iter().filter(|e| !starts.contains(&e.to)) {
+        if reported.insert(edge.to) {
+            issues.push(issue(
+                ValidationCheck::CallTargetNotFunction,
+                Some(edge.to),
+                format!(
+                    "call from 0x{:X} targets 0x{:X}, which is not a function start",
+                    edge.from, edge.to
+                ),
+            ));
+        }
+    }
+
+    for root in &result.roots {
+        let hit = result.root_hits.iter().find(|h| &h.root == root);
+        if hit.is_none_or(|h| h.functions.is_empty()) {
+            let reason = hit.and_then(|h| h.error.as_deref()).unwrap_or("no function matched");
+            issues.push(issue(
+                ValidationCheck::UnresolvedRoot,
+                None,
+                format!("root '{}' did not resolve: {}", root, reason),
+            ));
+        }
     }
-    u64::from_str_radix(trimmed, 16).ok()
+    ResultValidation { issues, skipped }
 }
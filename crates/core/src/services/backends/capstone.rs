@@ -1,32 +1,53 @@
-use std::collections::HashSet;
-use std::fs;
-use std::path::PathBuf;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
 
 use capstone::{arch, prelude::*, Capstone, InsnGroupId};
 use goblin::{elf, mach, pe, Object};
 
 use crate::services::analysis::{
-    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, BlockEdge, BlockEdgeKind,
-    CallEdge, EvidenceRecord, FunctionRecord,
+    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, BackendCapabilities,
+    BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, Endian, EvidenceKind, EvidenceRecord,
+    EvidenceSource, FunctionRecord, InstructionRecord, RawImage, XrefKind, XrefRecord,
 };
+use crate::services::fingerprint::mnemonic_hash;
+use crate::services::image::BinaryImage;
+use crate::services::import::pdb::PdbSymbols;
+use crate::services::loader::{image_layout, ImageFormat, ImageLayout, MappedRegion};
+use crate::services::plt::{elf_plt_imports, PltImport};
+use crate::services::signature::{
+    infer_signature, CallingConvention, FunctionSignature, InsnEffects,
+};
+use crate::services::strings::{is_string_section, literal_at, StringLiteral, ZERO_FILL_SECTIONS};
 
 pub struct CapstoneBackend;
 
+/// Architectures [`make_cs`] builds a disassembler for.
+const CAPSTONE_ARCHS: &[&str] = &["x86", "x86_64", "arm", "arm64", "riscv32", "riscv", "ppc"];
+
 #[derive(Debug, Clone)]
 struct SymbolInfo {
     name: String,
     address: u64,
     size: Option<u64>,
     file_range: Option<(usize, usize)>,
+    /// 32-bit ARM function in Thumb mode.
+    thumb: bool,
 }
 
-#[derive(Debug, Clone)]
-struct SectionRange {
-    name: String,
-    start: u64,
-    end: u64,
-    file_offset: Option<usize>,
-    size: Option<usize>,
+/// ARM mapping symbols by address: `$a` starts ARM code (`Some(false)`), `$t` Thumb code
+/// (`Some(true)`), and `$d` literal data (`None`).
+type ModeMap = BTreeMap<u64, Option<bool>>;
+
+/// Split a 32-bit ARM code address into its instruction address and whether it is Thumb:
+/// symbols, entry points, and interworking branch targets set the low bit for Thumb code.
+fn split_thumb_bit(address: u64) -> (u64, bool) {
+    (address & !1, address & 1 == 1)
+}
+
+/// Mode of the code at `addr` per the closest preceding mapping symbol, else `fallback`.
+fn mode_at(modes: &ModeMap, addr: u64, fallback: bool) -> bool {
+    modes.range(..=addr).next_back().and_then(|(_, mode)| *mode).unwrap_or(fallback)
 }
 
 fn capstone_version() -> Option<String> {
@@ -51,7 +72,7 @@ fn capstone_arch_from_object(bytes: &[u8]) -> Option<String> {
             Object::PE(pe) => match pe.header.coff_header.machine {
                 pe::header::COFF_MACHINE_X86 => Some("x86".into()),
                 pe::header::COFF_MACHINE_X86_64 => Some("x86_64".into()),
-                pe::header::COFF_MACHINE_ARM => Some("arm".into()),
+                pe::header::COFF_MACHINE_ARM | pe::header::COFF_MACHINE_ARMNT => Some("arm".into()),
                 pe::header::COFF_MACHINE_ARM64 => Some("arm64".into()),
                 _ => None,
             },
@@ -121,62 +142,166 @@ fn make_cs(arch: &str) -> Result<Capstone, AnalysisError> {
     }
 }
 
-fn section_range_to_file(
-    addr: u64,
-    size: Option<u64>,
-    sec_addr: u64,
-    sec_size: u64,
-    sec_offset: u64,
-    bytes_len: usize,
-) -> Option<(usize, usize)> {
-    if addr < sec_addr || addr >= sec_addr + sec_size {
-        return None;
+/// Disassembler for a raw image: [`make_cs`] with the image's Thumb and byte-order options.
+///
+/// Thumb needs an ARM arch; big-endian decoding is available for ARM, ARM64, and PowerPC.
+fn make_raw_cs(arch: &str, raw: &RawImage) -> Result<Capstone, AnalysisError> {
+    let endian = match raw.endian {
+        Endian::Little => capstone::Endian::Little,
+        Endian::Big => capstone::Endian::Big,
+    };
+    let init_err =
+        |e: capstone::Error| AnalysisError::Backend(format!("capstone init failed: {e}"));
+    let arch = canonical_arch(arch);
+    match arch.as_str() {
+        "arm" => {
+            let mode =
+                if raw.thumb { arch::arm::ArchMode::Thumb } else { arch::arm::ArchMode::Arm };
+            Capstone::new().arm().mode(mode).endian(endian).detail(true).build().map_err(init_err)
+        }
+        _ if raw.thumb => {
+            Err(AnalysisError::Backend(format!("thumb decoding needs an ARM arch, not '{arch}'")))
+        }
+        "arm64" => Capstone::new()
+            .arm64()
+            .mode(arch::arm64::ArchMode::Arm)
+            .endian(endian)
+            .detail(true)
+            .build()
+            .map_err(init_err),
+        "ppc" => Capstone::new()
+            .ppc()
+            .mode(arch::ppc::ArchMode::Mode64)
+            .endian(endian)
+            .detail(true)
+            .build()
+            .map_err(init_err),
+        _ if raw.endian == Endian::Big => Err(AnalysisError::Backend(format!(
+            "big-endian decoding is not supported for '{arch}' (use arm, arm64, or ppc)"
+        ))),
+        _ => make_cs(&arch),
     }
-    let offset_in_section = addr.saturating_sub(sec_addr);
-    let start = sec_offset.saturating_add(offset_in_section);
-    if start as usize >= bytes_len {
-        return None;
+}
+
+/// Disassemblers for one binary. 32-bit ARM code mixes ARM and Thumb, so ARM binaries get a
+/// decoder for each mode and every function is decoded in its own.
+struct Decoders {
+    cs: Capstone,
+    /// Thumb decoder, for ARM binaries only.
+    thumb: Option<Capstone>,
+    /// Mode of ARM code that no symbol, mapping symbol, or branch identifies.
+    default_thumb: bool,
+    /// Whether functions get the constant-propagation pass ([`Descent::propagate_constants`]):
+    /// only x86_64 and arm64 build addresses across instructions.
+    track_constants: bool,
+}
+
+impl Decoders {
+    fn new(arch: &str, raw: Option<&RawImage>) -> Result<Self, AnalysisError> {
+        let canonical = canonical_arch(arch);
+        if canonical != "arm" {
+            let cs = match raw {
+                Some(raw) => make_raw_cs(arch, raw)?,
+                None => make_cs(arch)?,
+            };
+            let track_constants = matches!(canonical.as_str(), "x86_64" | "arm64");
+            return Ok(Self { cs, thumb: None, default_thumb: false, track_constants });
+        }
+        let raw = raw.cloned().unwrap_or_default();
+        Ok(Self {
+            cs: make_raw_cs(arch, &RawImage { thumb: false, ..raw.clone() })?,
+            thumb: Some(make_raw_cs(arch, &RawImage { thumb: true, ..raw.clone() })?),
+            default_thumb: raw.thumb,
+            track_constants: false,
+        })
     }
-    let available = sec_size.saturating_sub(offset_in_section);
-    let length = size.unwrap_or(available).min(available);
-    let end = start.saturating_add(length);
-    let end = end.min(bytes_len as u64);
-    if end <= start {
-        None
-    } else {
-        Some((start as usize, end as usize))
+
+    fn get(&self, thumb: bool) -> &Capstone {
+        match &self.thumb {
+            Some(cs) if thumb => cs,
+            _ => &self.cs,
+        }
+    }
+
+    /// Split the Thumb bit off `address` on ARM; other architectures use every address bit.
+    fn split(&self, address: u64) -> (u64, bool) {
+        if self.thumb.is_some() {
+            split_thumb_bit(address)
+        } else {
+            (address, false)
+        }
+    }
+
+    /// `FunctionRecord::isa` of a function decoded in `thumb` mode.
+    fn isa(&self, thumb: bool) -> Option<String> {
+        let isa = if thumb { "thumb" } else { "arm" };
+        self.thumb.as_ref().map(|_| isa.to_string())
     }
 }
 
-fn elf_symbols(elf: &elf::Elf, bytes_len: usize) -> Vec<SymbolInfo> {
+fn elf_symbols(elf: &elf::Elf, layout: &ImageLayout, bytes_len: usize) -> Vec<SymbolInfo> {
+    // Relocatable objects use section-relative values, so address 0 is a real function there.
+    let relocatable = elf.header.e_type == elf::header::ET_REL;
+    let arm = elf.header.e_machine == elf::header::EM_ARM;
     let mut symbols = Vec::new();
-    for sym in &elf.syms {
-        if sym.is_function()
-            && sym.st_value > 0
-            && sym.st_shndx != elf::section_header::SHN_UNDEF as usize
-        {
-            let name = elf.strtab.get_at(sym.st_name).unwrap_or("").to_string();
+    let tables = [(&elf.syms, &elf.strtab), (&elf.dynsyms, &elf.dynstrtab)];
+    for (syms, strtab) in tables {
+        for sym in syms.iter() {
+            if !sym.is_function()
+                || (sym.st_value == 0 && !relocatable)
+                || sym.st_shndx == elf::section_header::SHN_UNDEF as usize
+            {
+                continue;
+            }
+            let name = strtab.get_at(sym.st_name).unwrap_or("").to_string();
             if name.is_empty() {
                 continue;
             }
             let size = if sym.st_size > 0 { Some(sym.st_size) } else { None };
-            let file_range = elf.section_headers.get(sym.st_shndx).and_then(|shdr| {
-                section_range_to_file(
-                    sym.st_value,
-                    size,
-                    shdr.sh_addr,
-                    shdr.sh_size,
-                    shdr.sh_offset,
-                    bytes_len,
-                )
-            });
-            symbols.push(SymbolInfo { name, address: sym.st_value, size, file_range });
+            let (address, thumb) =
+                if arm { split_thumb_bit(sym.st_value) } else { (sym.st_value, false) };
+            // Resolve through the symbol's own section: in relocatable objects every section
+            // starts at 0, so the address alone is ambiguous.
+            let file_range = layout
+                .sections
+                .get(sym.st_shndx)
+                .and_then(|sec| sec.file_range(address, size, bytes_len));
+            symbols.push(SymbolInfo { name, address, size, file_range, thumb });
         }
     }
     symbols
 }
 
-fn mach_symbols(bin: &mach::MachO, bytes_len: usize) -> Vec<SymbolInfo> {
+/// `$a`/`$t`/`$d` mapping symbols of a 32-bit ARM ELF (possibly suffixed, as in `$t.42`).
+///
+/// Only the static symbol table carries them, so stripped binaries fall back on the Thumb bit
+/// of their dynamic symbols.
+fn elf_mapping_symbols(elf: &elf::Elf) -> ModeMap {
+    let mut modes = ModeMap::new();
+    if elf.header.e_machine != elf::header::EM_ARM {
+        return modes;
+    }
+    for sym in elf.syms.iter() {
+        if sym.st_shndx == elf::section_header::SHN_UNDEF as usize {
+            continue;
+        }
+        let name = elf.strtab.get_at(sym.st_name).unwrap_or("");
+        let mode = match name.split('.').next() {
+            Some("$a") => Some(false),
+            Some("$t") => Some(true),
+            Some("$d") => None,
+            _ => continue,
+        };
+        modes.insert(sym.st_value, mode);
+    }
+    modes
+}
+
+/// `n_desc` flag of Mach-O symbols defined in Thumb code.
+const N_ARM_THUMB_DEF: u16 = 0x0008;
+
+fn mach_symbols(bin: &mach::MachO, layout: &ImageLayout, bytes_len: usize) -> Vec<SymbolInfo> {
+    let arm = bin.header.cputype() == mach::cputype::CPU_TYPE_ARM;
     let mut symbols = Vec::new();
     for sym in bin.symbols() {
         let Ok((name, nlist)) = sym else { continue };
@@ -187,33 +312,20 @@ fn mach_symbols(bin: &mach::MachO, bytes_len: usize) -> Vec<SymbolInfo> {
         if name.is_empty() {
             continue;
         }
-        symbols.push(SymbolInfo { name, address: nlist.n_value, size: None, file_range: None });
-    }
-
-    // Best-effort: map to sections to recover file slices when possible.
-    let mut mapped = Vec::new();
-    for (sec, _) in bin.segments.sections().flatten().filter_map(Result::ok) {
-        mapped.push(sec);
-    }
-    for sym in symbols.iter_mut() {
-        for sec in &mapped {
-            if let Some(range) = section_range_to_file(
-                sym.address,
-                sym.size,
-                sec.addr,
-                sec.size,
-                sec.offset.into(),
-                bytes_len,
-            ) {
-                sym.file_range = Some(range);
-                break;
-            }
-        }
+        let file_range = layout.file_range(nlist.n_value, None, bytes_len);
+        let thumb = arm && nlist.n_desc & N_ARM_THUMB_DEF != 0;
+        symbols.push(SymbolInfo { name, address: nlist.n_value, size: None, file_range, thumb });
     }
     symbols
 }
 
-fn pe_symbols(pe: &pe::PE, _bytes_len: usize) -> Vec<SymbolInfo> {
+/// Exports are RVAs; they are rebased onto the image base like every other PE address.
+/// ARM exports carry the Thumb bit like ELF symbols do.
+fn pe_symbols(pe: &pe::PE, layout: &ImageLayout, bytes_len: usize) -> Vec<SymbolInfo> {
+    let arm = matches!(
+        pe.header.coff_header.machine,
+        pe::header::COFF_MACHINE_ARM | pe::header::COFF_MACHINE_ARMNT
+    );
     let mut symbols = Vec::new();
     for exp in &pe.exports {
         if exp.rva == 0 {
@@ -223,77 +335,71 @@ fn pe_symbols(pe: &pe::PE, _bytes_len: usize) -> Vec<SymbolInfo> {
         if name.is_empty() {
             continue;
         }
-        let mut file_range = None;
-        for sec in &pe.sections {
-            let start = sec.virtual_address as u64;
-            let size = if sec.virtual_size == 0 {
-                sec.size_of_raw_data as u64
-            } else {
-                sec.virtual_size as u64
-            };
-            if (exp.rva as u64) >= start && (exp.rva as u64) < start + size {
-                let offset = sec.pointer_to_raw_data as u64 + (exp.rva as u64 - start);
-                let available = size.saturating_sub(exp.rva as u64 - start);
-                file_range = Some((offset as usize, offset.saturating_add(available) as usize));
-                break;
-            }
-        }
-
-        symbols.push(SymbolInfo { name, address: exp.rva as u64, size: None, file_range });
+        let address = layout.rva_to_va(exp.rva as u64);
+        let (address, thumb) = if arm { split_thumb_bit(address) } else { (address, false) };
+        let file_range = layout.file_range(address, None, bytes_len);
+        symbols.push(SymbolInfo { name, address, size: None, file_range, thumb });
     }
     symbols
 }
 
-fn extract_symbols(bytes: &[u8]) -> Vec<SymbolInfo> {
+fn extract_symbols(bytes: &[u8], layout: Option<&ImageLayout>) -> Vec<SymbolInfo> {
+    let Some(layout) = layout.filter(|l| l.format != ImageFormat::Raw) else {
+        return Vec::new();
+    };
     match Object::parse(bytes) {
-        Ok(Object::Elf(elf)) => elf_symbols(&elf, bytes.len()),
-        Ok(Object::PE(pe)) => pe_symbols(&pe, bytes.len()),
-        Ok(Object::Mach(mach::Mach::Binary(bin))) => mach_symbols(&bin, bytes.len()),
+        Ok(Object::Elf(elf)) => elf_symbols(&elf, layout, bytes.len()),
+        Ok(Object::PE(pe)) => pe_symbols(&pe, layout, bytes.len()),
+        Ok(Object::Mach(mach::Mach::Binary(bin))) => mach_symbols(&bin, layout, bytes.len()),
         _ => Vec::new(),
     }
 }
 
-fn collect_sections(bytes: &[u8]) -> Vec<SectionRange> {
-    match Object::parse(bytes) {
-        Ok(Object::Elf(elf)) => elf
-            .section_headers
-            .iter()
-            .map(|sh| SectionRange {
-                name: elf.strtab.get_at(sh.sh_name).unwrap_or("").to_string(),
-                start: sh.sh_addr,
-                end: sh.sh_addr.saturating_add(sh.sh_size),
-                file_offset: Some(sh.sh_offset as usize),
-                size: Some(sh.sh_size as usize),
-            })
-            .collect(),
-        Ok(Object::PE(pe)) => pe
-            .sections
-            .iter()
-            .map(|sec| SectionRange {
-                name: sec.name().unwrap_or_default().to_string(),
-                start: sec.virtual_address as u64,
-                end: sec.virtual_address as u64 + sec.virtual_size as u64,
-                file_offset: Some(sec.pointer_to_raw_data as usize),
-                size: Some(sec.size_of_raw_data as usize),
-            })
-            .collect(),
-        Ok(Object::Mach(mach::Mach::Binary(bin))) => bin
-            .segments
-            .sections()
-            .flatten()
-            .filter_map(|res| res.ok())
-            .map(|(sec, _)| SectionRange {
-                name: sec.name().unwrap_or("").to_string(),
-                start: sec.addr,
-                end: sec.addr.saturating_add(sec.size),
-                file_offset: Some(sec.offset as usize),
-                size: Some(sec.size as usize),
-            })
-            .collect(),
-        _ => Vec::new(),
+/// Functions of the request's PDB (already rebased onto the image), with their sizes.
+fn pdb_symbols(
+    pdb: Option<&PdbSymbols>,
+    layout: Option<&ImageLayout>,
+    bytes_len: usize,
+) -> Vec<SymbolInfo> {
+    let (Some(pdb), Some(layout)) = (pdb, layout) else {
+        return Vec::new();
+    };
+    pdb.functions
+        .iter()
+        .map(|f| {
+            let size = f.size.map(u64::from);
+            SymbolInfo {
+                name: f.name.clone(),
+                address: f.address,
+                size,
+                file_range: layout.file_range(f.address, size, bytes_len),
+                thumb: false,
+            }
+        })
+        .collect()
+}
+
+fn extract_mapping_symbols(bytes: &[u8], layout: Option<&ImageLayout>) -> ModeMap {
+    match (layout.map(|l| l.format), Object::parse(bytes)) {
+        (Some(ImageFormat::Elf), Ok(Object::Elf(elf))) => elf_mapping_symbols(&elf),
+        _ => ModeMap::new(),
     }
 }
 
+/// Section names and address ranges for SARIF locations.
+pub(crate) fn binary_sections(bytes: &[u8]) -> Vec<crate::services::render::sarif::BinarySection> {
+    image_layout(bytes)
+        .map(|layout| layout.sections)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| crate::services::render::sarif::BinarySection {
+            start: s.address,
+            end: s.end(),
+            name: s.name,
+        })
+        .collect()
+}
+
 fn decode_call_target(detail: &capstone::InsnDetail) -> Option<u64> {
     detail.arch_detail().operands().iter().find_map(|op| match op {
         capstone::arch::ArchOperand::X86Operand(op) => {
@@ -312,7 +418,7 @@ fn decode_call_target(detail: &capstone::InsnDetail) -> Option<u64> {
         }
         capstone::arch::ArchOperand::Arm64Operand(op) => {
             if let capstone::arch::arm64::Arm64OperandType::Imm(imm) = op.op_type {
-                Some(imm as u64)
+                Some(strip_pac(imm as u64))
             } else {
                 None
             }
@@ -321,174 +427,1525 @@ fn decode_call_target(detail: &capstone::InsnDetail) -> Option<u64> {
     })
 }
 
+/// Drop an AArch64 pointer authentication code the way `xpaci` does, assuming 48-bit virtual
+/// addresses: bit 55 picks the upper (all ones) or lower (all zeros) half of the address space.
+fn strip_pac(value: u64) -> u64 {
+    const VA_MASK: u64 = (1 << 48) - 1;
+    if value & (1 << 55) != 0 {
+        value | !VA_MASK
+    } else {
+        value & VA_MASK
+    }
+}
+
+/// Control transfer of an AArch64 pointer-authenticated branch (ARMv8.3 PAC).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthBranch {
+    Call,
+    Jump,
+    Return,
+}
+
+impl AuthBranch {
+    fn as_str(self) -> &'static str {
+        match self {
+            AuthBranch::Call => "call",
+            AuthBranch::Jump => "jump",
+            AuthBranch::Return => "return",
+        }
+    }
+}
+
+/// Authenticated branches by mnemonic: older Capstone builds decode `blraa`/`braa`/`retaa`
+/// without call/jump/return groups. The `paciasp`/`autiasp`/`bti` hints need nothing: they
+/// fall through like any other instruction.
+fn authenticated_branch(mnemonic: &str) -> Option<AuthBranch> {
+    match mnemonic {
+        "blraa" | "blraaz" | "blrab" | "blrabz" => Some(AuthBranch::Call),
+        "braa" | "braaz" | "brab" | "brabz" => Some(AuthBranch::Jump),
+        "retaa" | "retab" | "eretaa" | "eretab" => Some(AuthBranch::Return),
+        _ => None,
+    }
+}
+
+/// What operand targets are resolved against: the mapped sections and the strings in them.
+struct XrefTargets<'a> {
+    bytes: &'a [u8],
+    sections: &'a [MappedRegion],
+    /// Sorted by address (see [`scan_strings`]).
+    strings: &'a [StringLiteral],
+}
+
+/// Preview of the bytes at `target` inside `sec`: a target inside a string previews the text
+/// from there on; anything else shows up to 16 bytes with non-printable ones masked.
+fn xref_preview(targets: &XrefTargets, sec: &MappedRegion, target: u64) -> Option<String> {
+    let XrefTargets { bytes, strings, .. } = *targets;
+    if let Some(literal) = literal_at(strings, target) {
+        return Some(literal.value[(target - literal.address) as usize..].to_string());
+    }
+    let start = sec.file_offset_of(target)? as usize;
+    if start >= bytes.len() {
+        return None;
+    }
+    let end = (start + 16).min(bytes.len());
+    let slice = &bytes[start..end];
+    let mut s = String::new();
+    for b in slice {
+        let ch = *b as char;
+        if ch.is_ascii_graphic() || ch == ' ' {
+            s.push(ch);
+        } else {
+            s.push('.');
+        }
+    }
+    Some(s)
+}
+
+/// Provenance of rows from one of this backend's passes.
+fn evidence_source(pass: &str) -> Option<EvidenceSource> {
+    Some(EvidenceSource::new("capstone", pass))
+}
+
+/// Record a reference from `address` to `target` when it lands inside a known section, as
+/// both a structured xref and the legacy free-text evidence line.
+fn record_xref(
+    targets: &XrefTargets,
+    address: u64,
+    target: u64,
+    kind: XrefKind,
+    evidence: &mut Vec<EvidenceRecord>,
+    xrefs: &mut Vec<XrefRecord>,
+) {
+    let Some(sec) = targets.sections.iter().find(|s| s.contains(target)) else {
+        return;
+    };
+    let preview = xref_preview(targets, sec, target);
+    let label = match kind {
+        XrefKind::Memory => "mem",
+        XrefKind::Computed => "computed",
+        _ => "imm",
+    };
+    let description = match &preview {
+        Some(preview) => format!(
+            "xref {label} 0x{target:X} -> section {} (0x{:X}-0x{:X}) preview=\"{preview}\"",
+            sec.name,
+            sec.address,
+            sec.end()
+        ),
+        None => format!(
+            "xref {label} 0x{target:X} -> section {} (0x{:X}-0x{:X})",
+            sec.name,
+            sec.address,
+            sec.end()
+        ),
+    };
+    evidence.push(EvidenceRecord {
+        address,
+        description,
+        kind: None,
+        source: evidence_source("xrefs"),
+        run_id: None,
+    });
+    xrefs.push(XrefRecord {
+        from: address,
+        to: target,
+        kind,
+        section: Some(sec.name.clone()),
+        preview,
+    });
+}
+
+/// Immediates and RIP-relative operands that land inside a known section become xrefs.
+///
+/// The page an AArch64 `adrp` loads is not itself referenced; the address the following
+/// instructions build from it is reported by [`Descent::propagate_constants`].
 fn operand_evidence(
     detail: &capstone::InsnDetail,
-    sections: &[SectionRange],
-    bytes: &[u8],
+    mnemonic: &str,
+    targets: &XrefTargets,
     address: u64,
+    next_address: u64,
     evidence: &mut Vec<EvidenceRecord>,
+    xrefs: &mut Vec<XrefRecord>,
 ) {
-    let preview_for = |sec: &SectionRange, imm: u64| -> Option<String> {
-        let file_off = sec.file_offset?;
-        let size = sec.size?;
-        if imm < sec.start || imm >= sec.end {
-            return None;
-        }
-        let offset_in_sec = (imm - sec.start) as usize;
-        if offset_in_sec >= size {
-            return None;
-        }
-        let start = file_off.saturating_add(offset_in_sec);
-        if start >= bytes.len() {
-            return None;
-        }
-        let end = (start + 16).min(bytes.len());
-        let slice = &bytes[start..end];
-        let mut s = String::new();
-        for b in slice {
-            let ch = *b as char;
-            if ch.is_ascii_graphic() || ch == ' ' {
-                s.push(ch);
-            } else {
-                s.push('.');
-            }
-        }
-        Some(s)
+    let push_xref = |target: u64,
+                     kind: XrefKind,
+                     evidence: &mut Vec<EvidenceRecord>,
+                     xrefs: &mut Vec<XrefRecord>| {
+        record_xref(targets, address, target, kind, evidence, xrefs)
     };
 
+    let reg_evidence = |description: String| EvidenceRecord {
+        address,
+        description,
+        kind: None,
+        source: evidence_source("xrefs"),
+        run_id: None,
+    };
     for op in detail.arch_detail().operands() {
         match op {
             capstone::arch::ArchOperand::X86Operand(op) => match &op.op_type {
                 capstone::arch::x86::X86OperandType::Imm(imm) => {
-                    if let Some(sec) =
-                        sections.iter().find(|s| (*imm as u64) >= s.start && (*imm as u64) < s.end)
-                    {
-                        if let Some(preview) = preview_for(sec, *imm as u64) {
-                            evidence.push(EvidenceRecord {
-                                address,
-                                description: format!(
-                                    "xref imm 0x{imm:X} -> section {} (0x{:X}-0x{:X}) preview=\"{preview}\"",
-                                    sec.name, sec.start, sec.end
-                                ),
-                                kind: None,
-                            });
-                        } else {
-                            evidence.push(EvidenceRecord {
-                                address,
-                                description: format!(
-                                    "xref imm 0x{imm:X} -> section {} (0x{:X}-0x{:X})",
-                                    sec.name, sec.start, sec.end
-                                ),
-                                kind: None,
-                            });
-                        }
-                    }
+                    push_xref(*imm as u64, XrefKind::Immediate, evidence, xrefs)
                 }
                 capstone::arch::x86::X86OperandType::Reg(reg) => {
-                    evidence.push(EvidenceRecord {
-                        address,
-                        description: format!("reg operand {:?}", reg.0),
-                        kind: None,
-                    });
+                    evidence.push(reg_evidence(format!("reg operand {:?}", reg.0)));
                 }
                 capstone::arch::x86::X86OperandType::Mem(mem) => {
                     let disp = mem.disp();
-                    evidence.push(EvidenceRecord {
-                        address,
-                        description: format!(
-                            "mem operand base={:?} index={:?} scale={} disp=0x{disp:X}",
-                            mem.base().0,
-                            mem.index().0,
-                            mem.scale()
-                        ),
-                        kind: None,
-                    });
+                    evidence.push(reg_evidence(format!(
+                        "mem operand base={:?} index={:?} scale={} disp=0x{disp:X}",
+                        mem.base().0,
+                        mem.index().0,
+                        mem.scale()
+                    )));
+                    // PC-relative load (`lea rdi, [rip + disp]`): the target is relative to
+                    // the next instruction.
+                    if mem.base() == RegId(arch::x86::X86Reg::X86_REG_RIP as u16) {
+                        let target = next_address.wrapping_add(disp as u64);
+                        push_xref(target, XrefKind::Memory, evidence, xrefs);
+                    }
                 }
                 _ => {}
             },
-            capstone::arch::ArchOperand::ArmOperand(op) => {
-                if let capstone::arch::arm::ArmOperandType::Imm(imm) = op.op_type {
-                    if let Some(sec) =
-                        sections.iter().find(|s| (imm as u64) >= s.start && (imm as u64) < s.end)
-                    {
-                        if let Some(preview) = preview_for(sec, imm as u64) {
-                            evidence.push(EvidenceRecord {
-                                address,
-                                description: format!(
-                                    "xref imm 0x{imm:X} -> section {} (0x{:X}-0x{:X}) preview=\"{preview}\"",
-                                    sec.name, sec.start, sec.end
-                                ),
-                                kind: None,
-                            });
-                        } else {
-                            evidence.push(EvidenceRecord {
-                                address,
-                                description: format!(
-                                    "xref imm 0x{imm:X} -> section {} (0x{:X}-0x{:X})",
-                                    sec.name, sec.start, sec.end
-                                ),
-                                kind: None,
-                            });
+            capstone::arch::ArchOperand::ArmOperand(op) => match op.op_type {
+                capstone::arch::arm::ArmOperandType::Imm(imm) => {
+                    push_xref(imm as u64, XrefKind::Immediate, evidence, xrefs)
+                }
+                capstone::arch::arm::ArmOperandType::Reg(reg) => {
+                    evidence.push(reg_evidence(format!("reg operand {:?}", reg.0)));
+                }
+                _ => {}
+            },
+            capstone::arch::ArchOperand::Arm64Operand(op) => match op.op_type {
+                capstone::arch::arm64::Arm64OperandType::Imm(imm) if mnemonic != "adrp" => {
+                    push_xref(strip_pac(imm as u64), XrefKind::Immediate, evidence, xrefs)
+                }
+                capstone::arch::arm64::Arm64OperandType::Reg(reg) => {
+                    evidence.push(reg_evidence(format!("reg operand {:?}", reg.0)));
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Shortest run of printable bytes reported as a string.
+const MIN_STRING_LEN: usize = 4;
+/// Cap on strings extracted from one binary.
+const STRING_LIMIT: usize = 4096;
+/// NUL-terminated printable ASCII strings in mapped, non-executable data sections (see
+/// [`is_string_section`]), sorted by address.
+fn scan_strings(
+    bytes: &[u8],
+    sections: &[MappedRegion],
+    code: &[CodeRegion],
+) -> Vec<StringLiteral> {
+    let mut out = Vec::new();
+    for sec in sections {
+        let overlaps_code = code.iter().any(|r| sec.address < r.end && r.start < sec.end());
+        if sec.address == 0
+            || overlaps_code
+            || !is_string_section(&sec.name)
+            || ZERO_FILL_SECTIONS.contains(&sec.name.as_str())
+        {
+            continue;
+        }
+        let Some(data) = sec.file_bytes(bytes) else {
+            continue;
+        };
+        let mut start = 0;
+        for (i, &b) in data.iter().enumerate() {
+            if b.is_ascii_graphic() || b == b' ' || b == b'\t' {
+                continue;
+            }
+            if b == 0 && i - start >= MIN_STRING_LEN {
+                out.push(StringLiteral {
+                    address: sec.address + start as u64,
+                    value: String::from_utf8_lossy(&data[start..i]).into_owned(),
+                });
+                if out.len() >= STRING_LIMIT {
+                    break;
+                }
+            }
+            start = i + 1;
+        }
+        if out.len() >= STRING_LIMIT {
+            break;
+        }
+    }
+    out.sort_by_key(|s| s.address);
+    out
+}
+
+/// File-backed executable range used to map virtual addresses to bytes.
+#[derive(Debug, Clone, Copy)]
+struct CodeRegion {
+    start: u64,
+    end: u64,
+    file_offset: usize,
+}
+
+/// File-backed executable regions of a parsed image (see [`ImageLayout::code_regions`]).
+///
+/// Returns `None` when the bytes are not a recognized object, so callers treat them as a raw
+/// code blob mapped at address 0.
+fn code_regions(layout: Option<&ImageLayout>) -> Option<Vec<CodeRegion>> {
+    let regions = layout?
+        .code_regions()
+        .into_iter()
+        .map(|r| CodeRegion {
+            start: r.address,
+            end: r.address.saturating_add(r.file_size.min(r.virtual_size)),
+            file_offset: r.file_offset as usize,
+        })
+        .collect();
+    Some(regions)
+}
+
+/// Roots written as `0x401000` or `sub_401000` seed functions at that address.
+fn root_seed_address(root: &str) -> Option<u64> {
+    let hex = root.strip_prefix("0x").or_else(|| root.strip_prefix("sub_"))?;
+    u64::from_str_radix(hex, 16).ok()
+}
+
+fn has_group(detail: &capstone::InsnDetail, group: capstone::InsnGroupType::Type) -> bool {
+    detail.groups().contains(&InsnGroupId(group as u8))
+}
+
+/// True when any operand is not an immediate (register/memory target).
+fn has_non_imm_operand(detail: &capstone::InsnDetail) -> bool {
+    detail.arch_detail().operands().iter().any(|op| {
+        !matches!(
+            op,
+            capstone::arch::ArchOperand::X86Operand(x)
+                if matches!(x.op_type, capstone::arch::x86::X86OperandType::Imm(_))
+        ) && !matches!(
+            op,
+            capstone::arch::ArchOperand::ArmOperand(x)
+                if matches!(x.op_type, capstone::arch::arm::ArmOperandType::Imm(_))
+        ) && !matches!(
+            op,
+            capstone::arch::ArchOperand::Arm64Operand(x)
+                if matches!(x.op_type, capstone::arch::arm64::Arm64OperandType::Imm(_))
+        )
+    })
+}
+
+/// Conditional branch mnemonics across the supported architectures (x86 `jcc`, ARM `bcc`,
+/// AArch64 `b.cond`/`cbz`/`tbz`, RISC-V `beq`...).
+fn is_conditional_jump(mnemonic: &str) -> bool {
+    let m = mnemonic.to_lowercase();
+    match m.as_str() {
+        "jmp" | "ljmp" | "j" | "jr" | "jal" | "jalr" | "b" | "br" | "bx" | "ba" | "bl" | "blx"
+        | "blr" | "bctr" | "braa" | "brab" | "braaz" | "brabz" => false,
+        _ => m.starts_with('j') || m.starts_with('b') || m.starts_with("cb") || m.starts_with("tb"),
+    }
+}
+
+/// Control-flow effect of one instruction.
+///
+/// A call with `exchange` is an ARM `blx` to an immediate, which switches between ARM and
+/// Thumb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Normal,
+    Call { target: Option<u64>, indirect: bool, exchange: bool },
+    Jump { target: Option<u64>, indirect: bool, conditional: bool },
+    Return,
+    Halt,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DecodedInsn {
+    len: u64,
+    flow: Flow,
+}
+
+/// Registers known to hold an address, keyed by the name of their full-width register.
+type RegValues = HashMap<String, u64>;
+
+/// Full-width AArch64 register that `name` is a view of (`w3` is the low half of `x3`).
+fn arm64_reg_key(name: &str) -> String {
+    match name {
+        "wsp" => "sp".to_string(),
+        "wzr" => "xzr".to_string(),
+        _ => match name.strip_prefix('w') {
+            Some(n) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => format!("x{n}"),
+            _ => name.to_string(),
+        },
+    }
+}
+
+/// Full-width x86_64 register that `name` is a view of (`eax`, `ax`, `al` are parts of
+/// `rax`; `r8d` of `r8`).
+fn x86_reg_key(name: &str) -> String {
+    let legacy = match name {
+        "eax" | "ax" | "al" | "ah" => "rax",
+        "ebx" | "bx" | "bl" | "bh" => "rbx",
+        "ecx" | "cx" | "cl" | "ch" => "rcx",
+        "edx" | "dx" | "dl" | "dh" => "rdx",
+        "esi" | "si" | "sil" => "rsi",
+        "edi" | "di" | "dil" => "rdi",
+        "ebp" | "bp" | "bpl" => "rbp",
+        "esp" | "sp" | "spl" => "rsp",
+        _ => {
+            let numbered = name.trim_end_matches(['d', 'w', 'b']);
+            return match numbered.strip_prefix('r') {
+                Some(n) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => {
+                    numbered.to_string()
+                }
+                _ => name.to_string(),
+            };
+        }
+    };
+    legacy.to_string()
+}
+
+/// Apply one instruction to the known register values and return the addresses it computes.
+///
+/// Modelled: `adrp`/`adr` and `lea reg, [rip + disp]` load an address, `add`/`sub` of an
+/// immediate and register moves carry it, and a memory operand whose base is known (and has
+/// no index) resolves to base + displacement. Only full-width registers carry values; any
+/// other write to a register forgets it. `writeback` marks a pre-indexed (`!`) access.
+fn propagate_insn(
+    cs: &Capstone,
+    detail: &capstone::InsnDetail,
+    mnemonic: &str,
+    writeback: bool,
+    next_address: u64,
+    regs: &mut RegValues,
+) -> Vec<u64> {
+    let operands = detail.arch_detail().operands();
+    let reg_key: fn(&str) -> String = match operands.first() {
+        Some(capstone::arch::ArchOperand::Arm64Operand(_)) => arm64_reg_key,
+        Some(capstone::arch::ArchOperand::X86Operand(_)) => x86_reg_key,
+        _ => return Vec::new(),
+    };
+    let key = |reg: RegId| cs.reg_name(reg).map(|name| (reg_key(&name), name));
+    let value = |regs: &RegValues, reg: RegId| {
+        key(reg).filter(|(key, name)| key == name).and_then(|(key, _)| regs.get(&key).copied())
+    };
+    let offset = |base: u64, imm: i64| match mnemonic {
+        "sub" => base.wrapping_sub(imm as u64),
+        _ => base.wrapping_add(imm as u64),
+    };
+
+    let mut targets = Vec::new();
+    // The register given a new value (`None`: an unknown one), else every register written.
+    let assigned: Option<(RegId, Option<u64>)>;
+    let mut written = Vec::new();
+    match operands.first() {
+        Some(capstone::arch::ArchOperand::Arm64Operand(_)) => {
+            use capstone::arch::arm64::{Arm64OperandType as Op, Arm64Shift};
+
+            let ops: Vec<_> = operands
+                .iter()
+                .filter_map(|op| match op {
+                    capstone::arch::ArchOperand::Arm64Operand(op) => Some(op),
+                    _ => None,
+                })
+                .collect();
+            assigned = match (mnemonic, ops.as_slice()) {
+                ("adrp" | "adr", [dst, src]) => match (&dst.op_type, &src.op_type) {
+                    (Op::Reg(dst), Op::Imm(page)) => Some((*dst, Some(*page as u64))),
+                    _ => None,
+                },
+                ("add" | "sub", [dst, src, imm]) if matches!(imm.shift, Arm64Shift::Invalid) => {
+                    match (&dst.op_type, &src.op_type, &imm.op_type) {
+                        (Op::Reg(dst), Op::Reg(src), Op::Imm(imm)) => {
+                            let address = value(regs, *src).map(|base| offset(base, *imm));
+                            targets.extend(address);
+                            Some((*dst, address))
                         }
+                        _ => None,
                     }
                 }
-                if let capstone::arch::arm::ArmOperandType::Reg(reg) = op.op_type {
-                    evidence.push(EvidenceRecord {
-                        address,
-                        description: format!("reg operand {:?}", reg.0),
-                        kind: None,
-                    });
+                ("mov", [dst, src]) => match (&dst.op_type, &src.op_type) {
+                    (Op::Reg(dst), Op::Reg(src)) => Some((*dst, value(regs, *src))),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if assigned.is_none() {
+                // As in `insn_effects`: loads write every register operand, stores and
+                // compares none (exclusive stores write their status register), anything
+                // else its first.
+                let all_written = mnemonic.starts_with("ld");
+                let read_only = (mnemonic.starts_with("st") && !mnemonic.contains('x'))
+                    || matches!(mnemonic, "cmp" | "cmn" | "tst");
+                for (index, op) in ops.iter().enumerate() {
+                    match &op.op_type {
+                        Op::Mem(mem) => {
+                            if mem.index().0 == 0 {
+                                if let Some(base) = value(regs, mem.base()) {
+                                    targets.push(base.wrapping_add(mem.disp() as u64));
+                                }
+                            }
+                            // Pre-indexed (`[x0, #8]!`) and post-indexed (`[x0], #8`) accesses
+                            // update the base.
+                            if writeback || index + 1 < ops.len() {
+                                written.push(mem.base());
+                            }
+                        }
+                        Op::Reg(reg) if all_written || (index == 0 && !read_only) => {
+                            written.push(*reg)
+                        }
+                        _ => {}
+                    }
                 }
             }
-            capstone::arch::ArchOperand::Arm64Operand(op) => match op.op_type {
-                capstone::arch::arm64::Arm64OperandType::Imm(imm) => {
-                    if let Some(sec) =
-                        sections.iter().find(|s| (imm as u64) >= s.start && (imm as u64) < s.end)
-                    {
-                        if let Some(preview) = preview_for(sec, imm as u64) {
-                            evidence.push(EvidenceRecord {
-                                    address,
-                                    description: format!(
-                                        "xref imm 0x{imm:X} -> section {} (0x{:X}-0x{:X}) preview=\"{preview}\"",
-                                        sec.name, sec.start, sec.end
-                                    ),
-                                    kind: None,
-                                });
-                        } else {
-                            evidence.push(EvidenceRecord {
-                                address,
-                                description: format!(
-                                    "xref imm 0x{imm:X} -> section {} (0x{:X}-0x{:X})",
-                                    sec.name, sec.start, sec.end
-                                ),
-                                kind: None,
+        }
+        _ => {
+            use capstone::arch::x86::X86OperandType as Op;
+
+            let rip = RegId(arch::x86::X86Reg::X86_REG_RIP as u16);
+            let ops: Vec<_> = operands
+                .iter()
+                .filter_map(|op| match op {
+                    capstone::arch::ArchOperand::X86Operand(op) => Some(op),
+                    _ => None,
+                })
+                .collect();
+            assigned = match (mnemonic, ops.as_slice()) {
+                ("lea", [dst, src]) => match (&dst.op_type, &src.op_type) {
+                    // `operand_evidence` already reports the RIP-relative target itself.
+                    (Op::Reg(dst), Op::Mem(mem)) if mem.base() == rip => {
+                        Some((*dst, Some(next_address.wrapping_add(mem.disp() as u64))))
+                    }
+                    (Op::Reg(dst), Op::Mem(mem)) => {
+                        let address = (mem.index().0 == 0)
+                            .then(|| value(regs, mem.base()))
+                            .flatten()
+                            .map(|base| base.wrapping_add(mem.disp() as u64));
+                        targets.extend(address);
+                        Some((*dst, address))
+                    }
+                    _ => None,
+                },
+                ("add" | "sub", [dst, imm]) => match (&dst.op_type, &imm.op_type) {
+                    (Op::Reg(dst), Op::Imm(imm)) => {
+                        let address = value(regs, *dst).map(|base| offset(base, *imm));
+                        targets.extend(address);
+                        Some((*dst, address))
+                    }
+                    _ => None,
+                },
+                ("mov", [dst, src]) => match (&dst.op_type, &src.op_type) {
+                    (Op::Reg(dst), Op::Reg(src)) => Some((*dst, value(regs, *src))),
+                    _ => None,
+                },
+                _ => None,
+            };
+            if assigned.is_none() {
+                for (index, op) in ops.iter().enumerate() {
+                    match &op.op_type {
+                        Op::Mem(mem) if mem.base() != rip && mem.index().0 == 0 => {
+                            if let Some(base) = value(regs, mem.base()) {
+                                targets.push(base.wrapping_add(mem.disp() as u64));
+                            }
+                        }
+                        Op::Reg(reg) => {
+                            let access = op.access.unwrap_or(if index == 0 {
+                                capstone::RegAccessType::WriteOnly
+                            } else {
+                                capstone::RegAccessType::ReadOnly
                             });
+                            if access.is_writable() {
+                                written.push(*reg);
+                            }
                         }
+                        _ => {}
                     }
                 }
-                capstone::arch::arm64::Arm64OperandType::Reg(reg) => {
-                    evidence.push(EvidenceRecord {
-                        address,
-                        description: format!("reg operand {:?}", reg.0),
-                        kind: None,
+            }
+        }
+    }
+
+    let mut known = None;
+    match assigned {
+        Some((dst, Some(address))) => match key(dst) {
+            Some((key, name)) if key == name => known = Some((key, address)),
+            _ => written.push(dst),
+        },
+        Some((dst, None)) => written.push(dst),
+        None => {}
+    }
+    for reg in written.into_iter().chain(detail.regs_write().iter().copied()) {
+        if let Some((key, _)) = key(reg) {
+            regs.remove(&key);
+        }
+    }
+    regs.extend(known);
+    targets
+}
+
+/// Caps on free-text evidence; structural data (edges, blocks, xrefs) is not capped. Records
+/// over a cap are counted and reported in one truncation record.
+const EVIDENCE_LIMIT: usize = 128;
+const BLOCK_EVIDENCE_LIMIT: usize = 64;
+
+/// Instructions decoded per function when the request sets no budget.
+const DEFAULT_FUNCTION_BUDGET: usize = 2048;
+
+/// How far one analysis may go (see [`crate::services::analysis::AnalysisOptions`]).
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    /// Instructions explored per function.
+    per_function: usize,
+    /// Distinct instructions decoded over the whole binary.
+    total: Option<usize>,
+    /// Call depth from the seeds (symbols, roots, entry point) whose callees are explored.
+    max_depth: Option<u32>,
+}
+
+/// Record of a limit that cut the analysis short: `truncated: <what was left out>`.
+fn truncation(address: u64, description: String) -> EvidenceRecord {
+    EvidenceRecord {
+        address,
+        description: format!("truncated: {description}"),
+        kind: Some(EvidenceKind::Other),
+        source: evidence_source("limits"),
+        run_id: None,
+    }
+}
+
+/// Records dropped over an evidence cap: first address and count.
+#[derive(Debug, Default)]
+struct Dropped(Option<(u64, usize)>);
+
+impl Dropped {
+    fn add(&mut self, address: u64, count: usize) {
+        if count > 0 {
+            let (first, total) = self.0.get_or_insert((address, 0));
+            *first = (*first).min(address);
+            *total += count;
+        }
+    }
+
+    fn truncation(&self, what: &str, limit: usize) -> Option<EvidenceRecord> {
+        self.0.map(|(address, count)| {
+            truncation(address, format!("{count} {what} record(s) over the cap of {limit} dropped"))
+        })
+    }
+}
+
+/// Recursive-descent disassembler state for one binary.
+///
+/// Each address is decoded once; evidence, xrefs, and call edges are emitted at that point,
+/// so shared code (tails, overlapping functions) is not reported twice.
+struct Descent<'a> {
+    decoders: &'a Decoders,
+    bytes: &'a [u8],
+    regions: Vec<CodeRegion>,
+    modes: ModeMap,
+    /// ARM mode of the code being explored.
+    thumb: bool,
+    targets: XrefTargets<'a>,
+    insns: HashMap<u64, Option<DecodedInsn>>,
+    limits: Limits,
+    /// Instructions decoded so far, counted against `limits.total`.
+    decoded: usize,
+    evidence: Vec<EvidenceRecord>,
+    dropped_evidence: Dropped,
+    block_evidence: usize,
+    dropped_blocks: Dropped,
+    /// Where limits cut the analysis short; never capped.
+    truncations: Vec<EvidenceRecord>,
+    xrefs: Vec<XrefRecord>,
+    /// (from, to) of the xrefs constant propagation added, so code shared by several
+    /// functions reports each once.
+    computed: HashSet<(u64, u64)>,
+    call_edges: Vec<CallEdge>,
+    basic_blocks: BTreeMap<u64, BasicBlock>,
+}
+
+/// Functions found so far, keyed by entry address.
+#[derive(Debug, Clone)]
+struct DiscoveredFunction {
+    name: String,
+    size: Option<u64>,
+    thumb: bool,
+    /// Calls between a seed and this function.
+    depth: u32,
+}
+
+impl<'a> Descent<'a> {
+    fn new(
+        decoders: &'a Decoders,
+        bytes: &'a [u8],
+        regions: Vec<CodeRegion>,
+        modes: ModeMap,
+        thumb: bool,
+        targets: XrefTargets<'a>,
+        limits: Limits,
+    ) -> Self {
+        Descent {
+            decoders,
+            bytes,
+            regions,
+            modes,
+            thumb,
+            targets,
+            insns: HashMap::new(),
+            limits,
+            decoded: 0,
+            evidence: Vec::new(),
+            dropped_evidence: Dropped::default(),
+            block_evidence: 0,
+            dropped_blocks: Dropped::default(),
+            truncations: Vec::new(),
+            xrefs: Vec::new(),
+            computed: HashSet::new(),
+            call_edges: Vec::new(),
+            basic_blocks: BTreeMap::new(),
+        }
+    }
+
+    fn is_code(&self, addr: u64) -> bool {
+        self.regions.iter().any(|r| addr >= r.start && addr < r.end)
+    }
+
+    fn code_at(&self, addr: u64) -> Option<&'a [u8]> {
+        let region = self.regions.iter().find(|r| addr >= r.start && addr < r.end)?;
+        let start = region.file_offset.checked_add((addr - region.start) as usize)?;
+        let end = region
+            .file_offset
+            .saturating_add((region.end - region.start) as usize)
+            .min(self.bytes.len());
+        self.bytes.get(start..end).filter(|code| !code.is_empty())
+    }
+
+    fn decode(&mut self, addr: u64) -> Option<DecodedInsn> {
+        if let Some(cached) = self.insns.get(&addr) {
+            return *cached;
+        }
+        self.decoded += 1;
+        let decoded = self.decode_uncached(addr);
+        self.insns.insert(addr, decoded);
+        decoded
+    }
+
+    /// Whether the total instruction budget is spent.
+    fn exhausted(&self) -> bool {
+        self.limits.total.is_some_and(|total| self.decoded >= total)
+    }
+
+    /// Add free-text evidence up to [`EVIDENCE_LIMIT`], counting what does not fit.
+    fn push_evidence(&mut self, address: u64, records: Vec<EvidenceRecord>) {
+        let room = EVIDENCE_LIMIT.saturating_sub(self.evidence.len());
+        self.dropped_evidence.add(address, records.len().saturating_sub(room));
+        self.evidence.extend(records.into_iter().take(room));
+    }
+
+    fn decode_uncached(&mut self, addr: u64) -> Option<DecodedInsn> {
+        let code = self.code_at(addr)?;
+        let cs = self.decoders.get(self.thumb);
+        let insns = cs.disasm_count(code, addr, 1).ok()?;
+        let insn = insns.iter().next()?;
+        let len = insn.bytes().len() as u64;
+        if len == 0 {
+            return None;
+        }
+        let mnemonic = insn.mnemonic().unwrap_or("");
+        let listing = EvidenceRecord {
+            address: addr,
+            description: format!("{} {}", mnemonic, insn.op_str().unwrap_or("")).trim().to_string(),
+            kind: None,
+            source: evidence_source("disasm"),
+            run_id: None,
+        };
+        self.push_evidence(addr, vec![listing]);
+
+        let Ok(detail) = cs.insn_detail(insn) else {
+            return Some(DecodedInsn { len, flow: Flow::Normal });
+        };
+        let auth = authenticated_branch(mnemonic);
+        if let Some(auth) = auth {
+            let line = EvidenceRecord {
+                address: addr,
+                description: format!(
+                    "pac_{} 0x{:X} {} {}",
+                    auth.as_str(),
+                    addr,
+                    mnemonic,
+                    insn.op_str().unwrap_or("")
+                )
+                .trim()
+                .to_string(),
+                kind: None,
+                source: evidence_source("calls"),
+                run_id: None,
+            };
+            self.push_evidence(addr, vec![line]);
+        }
+        let flow = if has_group(&detail, capstone::InsnGroupType::CS_GRP_CALL)
+            || auth == Some(AuthBranch::Call)
+        {
+            let target = decode_call_target(&detail);
+            if let Some(target) = target {
+                self.call_edges.push(CallEdge {
+                    from: addr,
+                    to: target,
+                    is_cross_slice: false,
+                    to_name: None,
+                    to_import: false,
+                });
+                let line = EvidenceRecord {
+                    address: addr,
+                    description: format!("call_edge 0x{:X} -> 0x{:X}", addr, target),
+                    kind: None,
+                    source: evidence_source("calls"),
+                    run_id: None,
+                };
+                self.push_evidence(addr, vec![line]);
+            }
+            let indirect = has_non_imm_operand(&detail);
+            Flow::Call { target, indirect, exchange: mnemonic == "blx" && !indirect }
+        } else if has_group(&detail, capstone::InsnGroupType::CS_GRP_RET)
+            || auth == Some(AuthBranch::Return)
+        {
+            Flow::Return
+        } else if has_group(&detail, capstone::InsnGroupType::CS_GRP_JUMP)
+            || auth == Some(AuthBranch::Jump)
+        {
+            Flow::Jump {
+                target: decode_call_target(&detail),
+                indirect: has_non_imm_operand(&detail),
+                conditional: is_conditional_jump(mnemonic),
+            }
+        } else if matches!(mnemonic, "hlt" | "ud2" | "int3") {
+            Flow::Halt
+        } else {
+            Flow::Normal
+        };
+
+        let mut operand_lines = Vec::new();
+        operand_evidence(
+            &detail,
+            mnemonic,
+            &self.targets,
+            addr,
+            addr + len,
+            &mut operand_lines,
+            &mut self.xrefs,
+        );
+        self.push_evidence(addr, operand_lines);
+
+        Some(DecodedInsn { len, flow })
+    }
+
+    /// Explore the code reachable from `entry` (decoded as Thumb when `thumb`) without
+    /// crossing into other functions, within the per-function and total instruction budgets.
+    ///
+    /// Returns call/tail-jump targets that look like new functions as `(target, mode they are
+    /// entered in, call site)`, and the end of the highest instruction reached (used as the
+    /// size of synthesized functions). Running out of either budget adds a truncation record.
+    fn explore(
+        &mut self,
+        entry: u64,
+        thumb: bool,
+        bound: Option<u64>,
+        functions: &BTreeMap<u64, DiscoveredFunction>,
+    ) -> (Vec<(u64, bool, u64)>, u64) {
+        self.thumb = thumb;
+        let in_scope = |this: &Self, addr: u64| {
+            this.is_code(addr)
+                && (addr == entry || !functions.contains_key(&addr))
+                && bound.is_none_or(|end| addr >= entry && addr < end)
+        };
+
+        let mut visited: BTreeMap<u64, DecodedInsn> = BTreeMap::new();
+        let mut leaders = BTreeSet::from([entry]);
+        let mut new_functions = Vec::new();
+        let mut work = vec![entry];
+        let (mut over_budget, mut exhausted) = (false, false);
+        while let Some(start) = work.pop() {
+            let mut addr = start;
+            while !visited.contains_key(&addr) && in_scope(self, addr) {
+                if visited.len() >= self.limits.per_function {
+                    over_budget = true;
+                    break;
+                }
+                if !self.insns.contains_key(&addr) && self.exhausted() {
+                    exhausted = true;
+                    break;
+                }
+                let Some(insn) = self.decode(addr) else { break };
+                visited.insert(addr, insn);
+                let next = addr.wrapping_add(insn.len);
+                match insn.flow {
+                    Flow::Normal => addr = next,
+                    Flow::Call { target, exchange, .. } => {
+                        // `call next` is the get-PC idiom, not a function entry.
+                        if let Some(target) = target.filter(|t| *t != next && self.is_code(*t)) {
+                            if !functions.contains_key(&target) {
+                                // Mapping symbols at the target win over the branch's mode.
+                                let mode = mode_at(&self.modes, target, thumb != exchange);
+                                new_functions.push((target, mode, addr));
+                            }
+                        }
+                        leaders.insert(next);
+                        addr = next;
+                    }
+                    Flow::Jump { target, conditional, .. } => {
+                        if let Some(target) = target {
+                            if in_scope(self, target) {
+                                leaders.insert(target);
+                                work.push(target);
+                            } else if self.is_code(target) && !functions.contains_key(&target) {
+                                // Unconditional jump out of a sized function: a tail call.
+                                let mode = mode_at(&self.modes, target, thumb);
+                                new_functions.push((target, mode, addr));
+                            }
+                        }
+                        if conditional {
+                            leaders.insert(next);
+                            work.push(next);
+                        }
+                        break;
+                    }
+                    Flow::Return | Flow::Halt => break,
+                }
+            }
+        }
+
+        if over_budget {
+            self.truncations.push(truncation(
+                entry,
+                format!(
+                    "code at 0x{entry:X} exceeds the budget of {} instructions per function; its blocks, calls, and size are incomplete",
+                    self.limits.per_function
+                ),
+            ));
+        }
+        if let Some(total) = self.limits.total.filter(|_| exhausted) {
+            self.truncations.push(truncation(
+                entry,
+                format!("total budget of {total} instructions ran out in the code at 0x{entry:X}"),
+            ));
+        }
+        let end = visited.iter().map(|(addr, insn)| addr + insn.len).max().unwrap_or(entry);
+        self.propagate_constants(&visited, &leaders);
+        self.build_blocks(&visited, &leaders, functions);
+        (new_functions, end)
+    }
+
+    /// Constant propagation over one explored function.
+    ///
+    /// Position-independent code builds addresses in several instructions (`adrp x0, page`
+    /// then `add x0, x0, #off` or `ldr x1, [x0, #off]`; `lea rbx, [rip + disp]` then
+    /// `[rbx + 8]`), which operand-by-operand decoding cannot resolve. Registers holding a
+    /// known address are followed through each basic block in address order, and every
+    /// address they produce becomes a [`XrefKind::Computed`] xref. Values do not survive
+    /// block boundaries: a join point may be reached with different register contents.
+    fn propagate_constants(
+        &mut self,
+        visited: &BTreeMap<u64, DecodedInsn>,
+        leaders: &BTreeSet<u64>,
+    ) {
+        if !self.decoders.track_constants {
+            return;
+        }
+        let cs = self.decoders.get(self.thumb);
+        let mut regs = RegValues::new();
+        let mut fallthrough = None;
+        for (&addr, decoded) in visited {
+            if leaders.contains(&addr) || fallthrough != Some(addr) {
+                regs.clear();
+            }
+            fallthrough = (decoded.flow == Flow::Normal).then_some(addr + decoded.len);
+            let insns = self.code_at(addr).and_then(|code| cs.disasm_count(code, addr, 1).ok());
+            let Some(insn) = insns.as_ref().and_then(|insns| insns.iter().next()) else {
+                regs.clear();
+                continue;
+            };
+            let Ok(detail) = cs.insn_detail(insn) else {
+                regs.clear();
+                continue;
+            };
+            let next_address = addr + insn.bytes().len() as u64;
+            let mnemonic = insn.mnemonic().unwrap_or("");
+            let writeback = insn.op_str().is_some_and(|ops| ops.contains('!'));
+            for target in propagate_insn(cs, &detail, mnemonic, writeback, next_address, &mut regs)
+            {
+                if !self.computed.insert((addr, target)) {
+                    continue;
+                }
+                let mut lines = Vec::new();
+                record_xref(
+                    &self.targets,
+                    addr,
+                    target,
+                    XrefKind::Computed,
+                    &mut lines,
+                    &mut self.xrefs,
+                );
+                self.push_evidence(addr, lines);
+            }
+        }
+    }
+
+    /// Split visited instructions into basic blocks at leaders and control-flow instructions.
+    fn build_blocks(
+        &mut self,
+        visited: &BTreeMap<u64, DecodedInsn>,
+        leaders: &BTreeSet<u64>,
+        functions: &BTreeMap<u64, DiscoveredFunction>,
+    ) {
+        let insns: Vec<(u64, DecodedInsn)> = visited.iter().map(|(a, i)| (*a, *i)).collect();
+        let mut idx = 0;
+        while idx < insns.len() {
+            let start = insns[idx].0;
+            let mut insn_count = 0u32;
+            let mut successors = Vec::new();
+            while idx < insns.len() {
+                let (addr, insn) = insns[idx];
+                idx += 1;
+                insn_count += 1;
+                let next = addr.wrapping_add(insn.len);
+                let next_visited = insns.get(idx).is_some_and(|(a, _)| *a == next);
+                let fallthrough = BlockEdge { target: next, kind: BlockEdgeKind::Fallthrough };
+                match insn.flow {
+                    Flow::Normal => {
+                        if !next_visited {
+                            if functions.contains_key(&next) {
+                                successors.push(fallthrough);
+                            }
+                            break;
+                        }
+                        if leaders.contains(&next) {
+                            successors.push(fallthrough);
+                            break;
+                        }
+                    }
+                    Flow::Call { target, indirect, .. } => {
+                        if let Some(target) = target {
+                            let kind = if indirect {
+                                BlockEdgeKind::IndirectCall
+                            } else {
+                                BlockEdgeKind::Call
+                            };
+                            successors.push(BlockEdge { target, kind });
+                        }
+                        if next_visited {
+                            successors.push(fallthrough);
+                        }
+                        break;
+                    }
+                    Flow::Jump { target, indirect, conditional } => {
+                        if let Some(target) = target {
+                            let kind = if indirect {
+                                BlockEdgeKind::IndirectJump
+                            } else if conditional {
+                                BlockEdgeKind::ConditionalJump
+                            } else {
+                                BlockEdgeKind::Jump
+                            };
+                            successors.push(BlockEdge { target, kind });
+                        }
+                        if conditional && next_visited {
+                            successors.push(fallthrough);
+                        }
+                        break;
+                    }
+                    Flow::Return | Flow::Halt => break,
+                }
+            }
+            if self.basic_blocks.contains_key(&start) {
+                continue;
+            }
+            let (last, insn) = insns[idx - 1];
+            let byte_len = (last + insn.len - start) as u32;
+            if self.block_evidence >= BLOCK_EVIDENCE_LIMIT {
+                self.dropped_blocks.add(start, 1);
+            } else {
+                self.block_evidence += 1;
+                self.evidence.push(EvidenceRecord {
+                    address: start,
+                    description: format!(
+                        "basic_block start=0x{start:016X} bytes={byte_len} insns={insn_count}"
+                    ),
+                    kind: None,
+                    source: evidence_source("blocks"),
+                    run_id: None,
+                });
+            }
+            self.basic_blocks.insert(
+                start,
+                BasicBlock { start, byte_len, insn_count: Some(insn_count), successors },
+            );
+        }
+    }
+}
+
+/// Record a function entry (named `sub_<ADDR>` unless a symbol name is given) first reached
+/// `depth` calls from a seed and queue it for exploration; already-known entries are left
+/// untouched.
+fn queue_function(
+    functions: &mut BTreeMap<u64, DiscoveredFunction>,
+    queue: &mut VecDeque<u64>,
+    address: u64,
+    name: Option<&str>,
+    size: Option<u64>,
+    thumb: bool,
+    depth: u32,
+) {
+    if let Entry::Vacant(slot) = functions.entry(address) {
+        let name = name.map(str::to_string).unwrap_or_else(|| format!("sub_{:X}", address));
+        slot.insert(DiscoveredFunction { name, size, thumb, depth });
+        queue.push_back(address);
+    }
+}
+
+/// Instructions swept per function for signature inference.
+const SIGNATURE_SCAN_LIMIT: usize = 256;
+
+/// Instructions swept per function for its fingerprint.
+const FINGERPRINT_SCAN_LIMIT: usize = 4096;
+
+/// Fingerprint the function at `entry` from the mnemonics of a linear sweep of `code`.
+fn function_fingerprint(cs: &Capstone, code: &[u8], entry: u64) -> Option<String> {
+    let insns = cs.disasm_count(code, entry, FINGERPRINT_SCAN_LIMIT).ok()?;
+    let mnemonics: Vec<&str> = insns.iter().filter_map(|insn| insn.mnemonic()).collect();
+    mnemonic_hash(&mnemonics)
+}
+
+/// Stack pointer tracking for a linear sweep: offsets are relative to the stack pointer at
+/// function entry (x86 only; other architectures pass arguments in registers).
+#[derive(Debug, Default)]
+struct StackFrame {
+    /// Entry-relative stack pointer; `None` once it is adjusted in a way we cannot follow.
+    sp: Option<i64>,
+    /// Entry-relative frame pointer after `mov ebp, esp`.
+    fp: Option<i64>,
+}
+
+/// Infer the signature of the function at `entry` from a linear sweep of `code` (its bytes,
+/// bounded by its size).
+fn infer_function_signature(
+    cs: &Capstone,
+    code: &[u8],
+    entry: u64,
+    convention: CallingConvention,
+) -> Option<FunctionSignature> {
+    let insns = cs.disasm_count(code, entry, SIGNATURE_SCAN_LIMIT).ok()?;
+    let mut frame = StackFrame { sp: Some(0), fp: None };
+    let mut effects = Vec::new();
+    for insn in insns.iter() {
+        let Ok(detail) = cs.insn_detail(insn) else { break };
+        effects.push(insn_effects(cs, insn, &detail, &mut frame));
+    }
+    (!effects.is_empty()).then(|| infer_signature(convention, &effects))
+}
+
+fn insn_effects(
+    cs: &Capstone,
+    insn: &capstone::Insn,
+    detail: &capstone::InsnDetail,
+    frame: &mut StackFrame,
+) -> InsnEffects {
+    let name = |reg: RegId| cs.reg_name(reg).map(|n| n.to_ascii_lowercase());
+    let mnemonic = insn.mnemonic().unwrap_or("").to_ascii_lowercase();
+    let mut effects = InsnEffects {
+        reads: detail.regs_read().iter().filter_map(|r| name(*r)).collect(),
+        writes: detail.regs_write().iter().filter_map(|r| name(*r)).collect(),
+        transfers: has_group(detail, capstone::InsnGroupType::CS_GRP_CALL)
+            || has_group(detail, capstone::InsnGroupType::CS_GRP_JUMP)
+            || has_group(detail, capstone::InsnGroupType::CS_GRP_RET)
+            || authenticated_branch(&mnemonic).is_some(),
+        ..InsnEffects::default()
+    };
+    let operands = detail.arch_detail().operands();
+
+    // ARM and RISC-V operands carry no access flags: loads and data-processing instructions
+    // write their first register, stores/compares/branches only read.
+    let read_only = mnemonic.starts_with("st")
+        || mnemonic.starts_with("cb")
+        || mnemonic.starts_with("tb")
+        || mnemonic.starts_with('b')
+        || matches!(
+            mnemonic.as_str(),
+            "cmp" | "cmn" | "tst" | "teq" | "push" | "ret" | "retaa" | "retab"
+        );
+    let all_written = mnemonic.starts_with("ld") || mnemonic == "pop";
+    let plain_reg = |effects: &mut InsnEffects, index: usize, reg: RegId| {
+        let Some(reg) = name(reg) else { return };
+        if all_written || (index == 0 && !read_only) {
+            effects.writes.push(reg);
+        } else {
+            effects.reads.push(reg);
+        }
+    };
+
+    let mut x86_regs = Vec::new();
+    for (index, op) in operands.iter().enumerate() {
+        match op {
+            capstone::arch::ArchOperand::X86Operand(op) => match &op.op_type {
+                capstone::arch::x86::X86OperandType::Reg(reg) => {
+                    x86_regs.push(*reg);
+                    let access = op.access.unwrap_or(if index == 0 {
+                        capstone::RegAccessType::WriteOnly
+                    } else {
+                        capstone::RegAccessType::ReadOnly
                     });
+                    if let Some(reg) = name(*reg) {
+                        if access.is_readable() {
+                            effects.reads.push(reg.clone());
+                        }
+                        if access.is_writable() {
+                            effects.writes.push(reg);
+                        }
+                    }
+                }
+                capstone::arch::x86::X86OperandType::Mem(mem) => {
+                    effects.reads.extend([mem.base(), mem.index()].into_iter().filter_map(name));
+                    let base = name(mem.base()).unwrap_or_default();
+                    let offset = match base.as_str() {
+                        "esp" | "rsp" => frame.sp,
+                        "ebp" | "rbp" => frame.fp,
+                        _ => None,
+                    };
+                    if let Some(offset) = offset {
+                        effects.stack_reads.push(offset + mem.disp());
+                    }
+                }
+                capstone::arch::x86::X86OperandType::Imm(imm) if mnemonic.starts_with("ret") => {
+                    effects.ret_pop = u32::try_from(*imm).ok();
+                }
+                _ => {}
+            },
+            capstone::arch::ArchOperand::ArmOperand(op) => match &op.op_type {
+                capstone::arch::arm::ArmOperandType::Reg(reg) => {
+                    plain_reg(&mut effects, index, *reg)
+                }
+                capstone::arch::arm::ArmOperandType::Mem(mem) => {
+                    effects.reads.extend([mem.base(), mem.index()].into_iter().filter_map(name))
+                }
+                _ => {}
+            },
+            capstone::arch::ArchOperand::Arm64Operand(op) => match &op.op_type {
+                capstone::arch::arm64::Arm64OperandType::Reg(reg) => {
+                    plain_reg(&mut effects, index, *reg)
+                }
+                capstone::arch::arm64::Arm64OperandType::Mem(mem) => {
+                    effects.reads.extend([mem.base(), mem.index()].into_iter().filter_map(name))
+                }
+                _ => {}
+            },
+            capstone::arch::ArchOperand::RiscVOperand(op) => match op {
+                capstone::arch::riscv::RiscVOperand::Reg(reg) => {
+                    plain_reg(&mut effects, index, *reg)
+                }
+                capstone::arch::riscv::RiscVOperand::Mem(mem) => {
+                    effects.reads.extend(name(mem.base()))
                 }
                 _ => {}
             },
             _ => {}
         }
     }
+
+    // `xor eax, eax` and friends only write their register.
+    if matches!(mnemonic.as_str(), "xor" | "sub" | "pxor" | "xorps" | "xorpd")
+        && x86_regs.len() == 2
+        && x86_regs[0] == x86_regs[1]
+    {
+        effects.reads.clear();
+    }
+
+    track_stack(cs, &mnemonic, &operands, &effects, frame);
+    effects
 }
 
-impl CapstoneBackend {
-    fn load_bytes(path: &PathBuf) -> Result<Vec<u8>, AnalysisError> {
-        fs::read(path).map_err(|_| AnalysisError::MissingBinary(path.clone()))
+/// Follow `push`/`pop`, `sub/add esp, imm`, and `mov ebp, esp` through a linear sweep.
+fn track_stack(
+    cs: &Capstone,
+    mnemonic: &str,
+    operands: &[capstone::arch::ArchOperand],
+    effects: &InsnEffects,
+    frame: &mut StackFrame,
+) {
+    let x86: Vec<&capstone::arch::x86::X86OperandType> = operands
+        .iter()
+        .filter_map(|op| match op {
+            capstone::arch::ArchOperand::X86Operand(op) => Some(&op.op_type),
+            _ => None,
+        })
+        .collect();
+    if x86.is_empty() {
+        return;
+    }
+    let reg_name = |op: &capstone::arch::x86::X86OperandType| match op {
+        capstone::arch::x86::X86OperandType::Reg(reg) => cs.reg_name(*reg),
+        _ => None,
+    };
+    let is_sp = |name: &str| name == "esp" || name == "rsp";
+    let word = if effects.writes.iter().any(|r| r == "rsp") { 8 } else { 4 };
+    let dest = x86.first().and_then(|op| reg_name(op));
+    let imm = x86.get(1).and_then(|op| match op {
+        capstone::arch::x86::X86OperandType::Imm(imm) => Some(*imm),
+        _ => None,
+    });
+    match (mnemonic, dest.as_deref()) {
+        ("push", _) => frame.sp = frame.sp.map(|sp| sp - word),
+        ("pop", _) => frame.sp = frame.sp.map(|sp| sp + word),
+        ("sub", Some(d)) if is_sp(d) => frame.sp = frame.sp.zip(imm).map(|(sp, imm)| sp - imm),
+        ("add", Some(d)) if is_sp(d) => frame.sp = frame.sp.zip(imm).map(|(sp, imm)| sp + imm),
+        ("mov", Some("ebp" | "rbp"))
+            if x86.get(1).and_then(|op| reg_name(op)).is_some_and(|src| is_sp(&src)) =>
+        {
+            frame.fp = frame.sp;
+        }
+        ("call", _) => {}
+        _ if effects.writes.iter().any(|r| is_sp(r)) => frame.sp = None,
+        _ => {}
     }
 }
 
+/// Canonical Capstone name for an architecture hint or Mach-O arch flag (`aarch64`,
+/// `arm64e` -> `arm64`, `armv7s` -> `arm`, `i386` -> `x86`...).
+fn canonical_arch(name: &str) -> String {
+    let name = name.trim().to_ascii_lowercase();
+    match name.as_str() {
+        "amd64" | "x86_64h" => "x86_64".into(),
+        "i386" | "i686" => "x86".into(),
+        "aarch64" | "arm64e" | "arm64_32" => "arm64".into(),
+        "ppc64" | "powerpc" => "ppc".into(),
+        other if other.starts_with("armv") => "arm".into(),
+        _ => name,
+    }
+}
+
+/// (arch, bytes) of each selected slice of a fat Mach-O.
+type ArchSlices<'a> = Vec<(String, &'a [u8])>;
+
+/// Architecture slices of a fat (universal) Mach-O as (arch, bytes), or `None` for any other
+/// file.
+///
+/// `arch_hint` selects the matching slice; without one (or with `all`/`universal`) every
+/// slice is returned. A hint that matches no slice is an error listing the available ones.
+fn fat_slices<'a>(
+    bytes: &'a [u8],
+    arch_hint: Option<&str>,
+) -> Option<Result<ArchSlices<'a>, AnalysisError>> {
+    let Ok(Object::Mach(mach::Mach::Fat(fat))) = Object::parse(bytes) else {
+        return None;
+    };
+    let mut slices = Vec::new();
+    for fat_arch in fat.iter_arches() {
+        let fat_arch = match fat_arch {
+            Ok(fat_arch) => fat_arch,
+            Err(err) => {
+                return Some(Err(AnalysisError::Backend(format!("invalid fat Mach-O: {err}"))));
+            }
+        };
+        let slice = fat_arch.slice(bytes);
+        let arch = capstone_arch_from_object(slice).unwrap_or_else(|| {
+            mach::constants::cputype::get_arch_name_from_types(
+                fat_arch.cputype,
+                fat_arch.cpusubtype,
+            )
+            .map(canonical_arch)
+            .unwrap_or_else(|| format!("cputype-{}", fat_arch.cputype))
+        });
+        slices.push((arch, slice));
+    }
+    let wanted = arch_hint.map(canonical_arch).filter(|h| h != "all" && h != "universal");
+    let Some(wanted) = wanted else {
+        return Some(Ok(slices));
+    };
+    let available: Vec<String> = slices.iter().map(|(arch, _)| arch.clone()).collect();
+    let selected: Vec<_> = slices.into_iter().filter(|(arch, _)| *arch == wanted).collect();
+    if selected.is_empty() {
+        return Some(Err(AnalysisError::Backend(format!(
+            "fat Mach-O has no '{}' slice (available: {})",
+            wanted,
+            available.join(", ")
+        ))));
+    }
+    Some(Ok(selected))
+}
+
+/// Fold the result of one fat slice into the results of the previous ones.
+///
+/// Root hits are recomputed over the merged functions so a root matched in any slice counts.
+fn merge_slice_results(
+    request: &AnalysisRequest,
+    mut merged: AnalysisResult,
+    slice: AnalysisResult,
+) -> AnalysisResult {
+    merged.functions.extend(slice.functions);
+    merged.call_edges.extend(slice.call_edges);
+    merged.evidence.extend(slice.evidence);
+    merged.basic_blocks.extend(slice.basic_blocks);
+    merged.xrefs.extend(slice.xrefs);
+    merged.text_bytes = match (merged.text_bytes, slice.text_bytes) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    };
+    merged.root_hits =
+        crate::services::analysis::build_root_hits(&request.roots, &merged.functions);
+    merged
+}
+
+/// Disassemble `[start, end)` of the binary at `path` for listings.
+///
+/// Addresses map to file bytes the same way [`CapstoneBackend`] maps them during analysis
+/// (executable sections and symbol ranges, or the `raw` mapping; unrecognized files are raw
+/// code at 0). Without `end`, decoding stops after the first return. At most `limit`
+/// instructions are returned, and decoding also stops at the first undecodable byte.
+///
+/// ARM code is decoded as Thumb when `start` has the Thumb bit set or a symbol or mapping
+/// symbol there says so.
+pub fn disassemble_range(
+    path: &Path,
+    arch: Option<&str>,
+    raw: Option<&RawImage>,
+    start: u64,
+    end: Option<u64>,
+    limit: usize,
+) -> Result<Vec<InstructionRecord>, AnalysisError> {
+    let file = BinaryImage::open(path)?;
+    let slices = if raw.is_some() { None } else { fat_slices(&file, arch) };
+    let (arch, bytes) = match slices {
+        Some(slices) => {
+            let (arch, slice) = slices?.into_iter().next().ok_or_else(|| {
+                AnalysisError::Backend("fat Mach-O has no architecture slices".into())
+            })?;
+            (Some(arch), slice)
+        }
+        None => (arch.map(str::to_string), file.bytes()),
+    };
+    let arch = capstone_arch_from_hint(arch.as_deref())
+        .or_else(|| capstone_arch_from_object(bytes))
+        .unwrap_or_else(|| "x86_64".to_string());
+    let decoders = Decoders::new(&arch, raw)?;
+    let layout = match raw {
+        Some(raw) => Some(ImageLayout::raw(raw.load_address, bytes.len() as u64)),
+        None => image_layout(bytes),
+    };
+    let symbols = extract_symbols(bytes, layout.as_ref());
+    let modes = extract_mapping_symbols(bytes, layout.as_ref());
+    let (start, thumb_bit) = decoders.split(start);
+    let thumb = thumb_bit
+        || match symbols.iter().find(|sym| sym.address == start) {
+            Some(sym) => sym.thumb,
+            None => mode_at(&modes, start, decoders.default_thumb),
+        };
+    let cs = decoders.get(thumb);
+    let mut regions = code_regions(layout.as_ref())
+        .unwrap_or_else(|| vec![CodeRegion { start: 0, end: bytes.len() as u64, file_offset: 0 }]);
+    for sym in symbols {
+        if let Some((from, to)) = sym.file_range {
+            regions.push(CodeRegion {
+                start: sym.address,
+                end: sym.address + (to - from) as u64,
+                file_offset: from,
+            });
+        }
+    }
+    // Only `code_at` is used: nothing is explored, so no budget applies.
+    let limits = Limits { per_function: usize::MAX, total: None, max_depth: None };
+    let targets = XrefTargets { bytes, sections: &[], strings: &[] };
+    let descent = Descent::new(&decoders, bytes, regions, modes, thumb, targets, limits);
+
+    let mut out = Vec::new();
+    let mut addr = start;
+    while out.len() < limit && end.is_none_or(|end| addr < end) {
+        let Some(code) = descent.code_at(addr) else { break };
+        let code = match end {
+            Some(end) => &code[..code.len().min((end - addr) as usize)],
+            None => code,
+        };
+        let Ok(insns) = cs.disasm_count(code, addr, 1) else { break };
+        let Some(insn) = insns.iter().next() else { break };
+        let is_return = cs
+            .insn_detail(insn)
+            .map(|detail| has_group(&detail, capstone::InsnGroupType::CS_GRP_RET))
+            .unwrap_or(false)
+            || authenticated_branch(insn.mnemonic().unwrap_or_default())
+                == Some(AuthBranch::Return);
+        out.push(InstructionRecord {
+            address: insn.address(),
+            bytes: insn.bytes().to_vec(),
+            mnemonic: insn.mnemonic().unwrap_or_default().to_string(),
+            operands: insn.op_str().unwrap_or_default().to_string(),
+        });
+        addr = insn.address() + insn.bytes().len() as u64;
+        if end.is_none() && is_return {
+            break;
+        }
+    }
+    Ok(out)
+}
+
 impl AnalysisBackend for CapstoneBackend {
+    /// Recursive descent from symbols, the entry point, and address-style roots.
+    ///
+    /// Direct call and jump targets are followed through executable sections; call targets
+    /// without a symbol become `sub_XXXX` functions, so stripped binaries still yield a call
+    /// graph. Bytes that are not a recognized object are treated as raw code at address 0.
+    ///
+    /// Fat (universal) Mach-O files are split into their architecture slices: the request's
+    /// `arch` picks one, and without one (or with `all`) every slice is analyzed and the
+    /// results merged, each function tagged with the slice it came from.
+    ///
+    /// With [`AnalysisRequest::raw`] the file is never parsed: it is mapped flat at the load
+    /// address and explored from address roots (or swept from the load address without any).
     fn analyze(&self, request: &AnalysisRequest) -> Result<AnalysisResult, AnalysisError> {
-        let bytes = Self::load_bytes(&request.binary_path)?;
+        let bytes = BinaryImage::open(&request.binary_path)?;
+        let slices = match request.raw {
+            Some(_) => None,
+            None => fat_slices(&bytes, request.arch.as_deref()),
+        };
+        match slices {
+            Some(slices) => {
+                let mut merged: Option<AnalysisResult> = None;
+                for (arch, slice) in slices? {
+                    let mut result = Self::analyze_bytes(request, slice, Some(&arch))?;
+                    for function in &mut result.functions {
+                        function.arch = Some(arch.clone());
+                    }
+                    merged = Some(match merged {
+                        None => result,
+                        Some(merged) => merge_slice_results(request, merged, result),
+                    });
+                }
+                merged.ok_or_else(|| {
+                    AnalysisError::Backend("fat Mach-O has no architecture slices".into())
+                })
+            }
+            None => Self::analyze_bytes(request, &bytes, request.arch.as_deref()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "capstone"
+    }
+
+    fn capabilities(&self, _tool_path: Option<&Path>) -> BackendCapabilities {
+        BackendCapabilities {
+            archs: CAPSTONE_ARCHS.iter().map(|a| a.to_string()).collect(),
+            strings: true,
+            imports: true,
+            basic_blocks: true,
+            decompilation: false,
+            external_tool: None,
+            tool_path: None,
+            tool_version: capstone_version(),
+            unavailable: None,
+        }
+    }
+}
+
+impl CapstoneBackend {
+    fn analyze_bytes(
+        request: &AnalysisRequest,
+        bytes: &[u8],
+        arch_hint: Option<&str>,
+    ) -> Result<AnalysisResult, AnalysisError> {
         let backend_version = capstone_version();
         if bytes.is_empty() {
             return Ok(AnalysisResult {
@@ -503,261 +1960,254 @@ impl AnalysisBackend for CapstoneBackend {
                     .map(|r| crate::services::analysis::RootHit {
                         root: r.clone(),
                         functions: Vec::new(),
+                        error: None,
                     })
                     .collect(),
                 backend_version,
                 backend_path: None,
+                sub_slices: Vec::new(),
+                xrefs: Vec::new(),
+                text_bytes: None,
             });
         }
 
-        let arch = capstone_arch_from_hint(request.arch.as_deref())
-            .or_else(|| capstone_arch_from_object(&bytes))
+        let arch = capstone_arch_from_hint(arch_hint)
+            .or_else(|| capstone_arch_from_object(bytes))
             .unwrap_or_else(|| "x86_64".to_string());
-        let cs = make_cs(&arch)?;
+        let decoders = Decoders::new(&arch, request.raw.as_ref())?;
 
-        let max_instructions = request.options.max_instructions.unwrap_or(2048);
-        let mut evidence = Vec::new();
-        let mut call_edges = Vec::new();
-        let mut functions = Vec::new();
-        let mut basic_blocks = Vec::new();
-        let section_ranges = collect_sections(&bytes);
-
-        let symbols = extract_symbols(&bytes);
-        for sym in symbols {
+        let limits = Limits {
+            per_function: request
+                .options
+                .max_instructions
+                .unwrap_or(DEFAULT_FUNCTION_BUDGET)
+                .max(1),
+            total: request.options.max_total_instructions,
+            max_depth: request.options.max_depth,
+        };
+        let layout = match &request.raw {
+            Some(raw) => Some(ImageLayout::raw(raw.load_address, bytes.len() as u64)),
+            None => image_layout(bytes),
+        };
+        let section_ranges = layout.as_ref().map(|l| l.sections.clone()).unwrap_or_default();
+        // PDB functions come first so their names win over exports at the same address.
+        let mut symbols = pdb_symbols(request.pdb.as_ref(), layout.as_ref(), bytes.len());
+        symbols.extend(extract_symbols(bytes, layout.as_ref()));
+        let modes = extract_mapping_symbols(bytes, layout.as_ref());
+        let parsed_regions = code_regions(layout.as_ref());
+        // Strings are scanned up front so operand xrefs into them preview the literal; they
+        // are only reported as evidence when asked for, and `finalize_result` then links each
+        // one to the instructions referencing it.
+        let strings =
+            scan_strings(bytes, &section_ranges, parsed_regions.as_deref().unwrap_or(&[]));
+        let raw_blob = parsed_regions.is_none();
+        // Calls into PLT stubs reach imports, which are not functions of this binary.
+        let plt_imports: BTreeMap<u64, PltImport> = match request.raw {
+            Some(_) => BTreeMap::new(),
+            None => elf_plt_imports(bytes).into_iter().map(|i| (i.address, i)).collect(),
+        };
+        let mut regions = parsed_regions.unwrap_or_else(|| {
+            vec![CodeRegion { start: 0, end: bytes.len() as u64, file_offset: 0 }]
+        });
+        let text_bytes: u64 = regions.iter().map(|r| r.end - r.start).sum();
+        // Symbols whose bytes were located are decodable even outside flagged sections.
+        for sym in &symbols {
             if let Some((start, end)) = sym.file_range {
-                let slice = &bytes[start..end];
-                if let Ok(insns) =
-                    cs.disasm_count(slice, sym.address, max_instructions.saturating_sub(1))
-                {
-                    let mut current_block_start = insns.iter().next().map(|i| i.address());
-                    let mut current_block_len: u32 = 0;
-                    let mut successors: Vec<BlockEdge> = Vec::new();
-
-                    for (idx, i) in insns.iter().take(64).enumerate() {
-                        if evidence.len() >= 128 {
-                            break;
-                        }
-                        evidence.push(EvidenceRecord {
-                            address: i.address(),
-                            description: format!(
-                                "{} {}",
-                                i.mnemonic().unwrap_or(""),
-                                i.op_str().unwrap_or("")
-                            )
-                            .trim()
-                            .to_string(),
-                            kind: None,
-                        });
-                        current_block_len += 1;
-
-                        if let Ok(detail) = cs.insn_detail(i) {
-                            let is_call = detail.groups().iter().any(|g| {
-                                *g == InsnGroupId(capstone::InsnGroupType::CS_GRP_CALL as u8)
-                            });
-                            let is_jump = detail.groups().iter().any(|g| {
-                                *g == InsnGroupId(capstone::InsnGroupType::CS_GRP_JUMP as u8)
-                            });
-                            let is_ret = detail.groups().iter().any(|g| {
-                                *g == InsnGroupId(capstone::InsnGroupType::CS_GRP_RET as u8)
-                            });
+                regions.push(CodeRegion {
+                    start: sym.address,
+                    end: sym.address + (end - start) as u64,
+                    file_offset: start,
+                });
+            }
+        }
 
-                            if is_call {
-                                let indirect = detail.arch_detail().operands().iter().any(|op| {
-                                    !matches!(
-                                        op,
-                                        capstone::arch::ArchOperand::X86Operand(x)
-                                            if matches!(x.op_type, capstone::arch::x86::X86OperandType::Imm(_))
-                                    ) && !matches!(
-                                        op,
-                                        capstone::arch::ArchOperand::ArmOperand(x)
-                                            if matches!(x.op_type, capstone::arch::arm::ArmOperandType::Imm(_))
-                                    ) && !matches!(
-                                        op,
-                                        capstone::arch::ArchOperand::Arm64Operand(x)
-                                            if matches!(x.op_type, capstone::arch::arm64::Arm64OperandType::Imm(_))
-                                    )
-                                });
-                                if let Some(target) = decode_call_target(&detail) {
-                                    call_edges.push(CallEdge {
-                                        from: i.address(),
-                                        to: target,
-                                        is_cross_slice: false,
-                                    });
-                                    successors.push(BlockEdge {
-                                        target,
-                                        kind: if indirect {
-                                            BlockEdgeKind::IndirectCall
-                                        } else {
-                                            BlockEdgeKind::Call
-                                        },
-                                    });
-                                    evidence.push(EvidenceRecord {
-                                        address: i.address(),
-                                        description: format!(
-                                            "call_edge 0x{:X} -> 0x{:X}",
-                                            i.address(),
-                                            target
-                                        ),
-                                        kind: None,
-                                    });
-                                }
-                            } else if is_jump {
-                                let mnemonic = i.mnemonic().unwrap_or("").to_lowercase();
-                                let conditional = mnemonic.starts_with('j')
-                                    && mnemonic != "jmp"
-                                    && mnemonic != "jr";
-                                let indirect = detail.arch_detail().operands().iter().any(|op| {
-                                    !matches!(
-                                        op,
-                                        capstone::arch::ArchOperand::X86Operand(x)
-                                            if matches!(x.op_type, capstone::arch::x86::X86OperandType::Imm(_))
-                                    ) && !matches!(
-                                        op,
-                                        capstone::arch::ArchOperand::ArmOperand(x)
-                                            if matches!(x.op_type, capstone::arch::arm::ArmOperandType::Imm(_))
-                                    ) && !matches!(
-                                        op,
-                                        capstone::arch::ArchOperand::Arm64Operand(x)
-                                            if matches!(x.op_type, capstone::arch::arm64::Arm64OperandType::Imm(_))
-                                    )
-                                });
-                                if let Some(target) = decode_call_target(&detail) {
-                                    successors.push(BlockEdge {
-                                        target,
-                                        kind: if indirect {
-                                            BlockEdgeKind::IndirectJump
-                                        } else if conditional {
-                                            BlockEdgeKind::ConditionalJump
-                                        } else {
-                                            BlockEdgeKind::Jump
-                                        },
-                                    });
-                                }
-                            }
+        let default_thumb = decoders.default_thumb;
+        let targets = XrefTargets { bytes, sections: &section_ranges, strings: &strings };
+        let mut descent =
+            Descent::new(&decoders, bytes, regions, modes, default_thumb, targets, limits);
 
-                            operand_evidence(
-                                &detail,
-                                &section_ranges,
-                                &bytes,
-                                i.address(),
-                                &mut evidence,
-                            );
-
-                            let is_last = idx + 1 == insns.len();
-                            if is_call || is_jump || is_ret || is_last {
-                                if !is_ret && !is_jump {
-                                    if let Some(next) = insns.get(idx + 1) {
-                                        successors.push(BlockEdge {
-                                            target: next.address(),
-                                            kind: BlockEdgeKind::Fallthrough,
-                                        });
-                                    }
-                                }
-                                if let Some(start_addr) = current_block_start {
-                                    basic_blocks.push(crate::services::analysis::BasicBlock {
-                                        start: start_addr,
-                                        len: current_block_len.max(1),
-                                        successors: successors.clone(),
-                                    });
-                                }
-                                current_block_start =
-                                    i.address().checked_add(i.bytes().len() as u64);
-                                current_block_len = 0;
-                                successors.clear();
-                            }
-                        }
+        let mut functions: BTreeMap<u64, DiscoveredFunction> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        for sym in &symbols {
+            let (address, name) = (sym.address, Some(sym.name.as_str()));
+            queue_function(&mut functions, &mut queue, address, name, sym.size, sym.thumb, 0);
+        }
+        // ARM entry points and roots such as `0x8001` carry the Thumb bit.
+        let root_seeds: HashMap<u64, bool> = request
+            .roots
+            .iter()
+            .filter_map(|r| root_seed_address(r))
+            .map(|address| decoders.split(address))
+            .collect();
+        let entry = layout.as_ref().and_then(|l| l.entry).map(|e| decoders.split(e));
+        let mut seeds: Vec<(u64, bool)> =
+            entry.into_iter().chain(root_seeds.iter().map(|(a, t)| (*a, *t))).collect();
+        seeds.sort_unstable();
+        for (seed, thumb_bit) in seeds {
+            if descent.is_code(seed) {
+                let thumb = thumb_bit || mode_at(&descent.modes, seed, default_thumb);
+                queue_function(&mut functions, &mut queue, seed, None, None, thumb, 0);
+            }
+        }
+        // Unmapped raw blobs start executing at 0; objects and mapped raw images with nothing
+        // to anchor on are swept from the start of each code region. Either way that code is
+        // not attributed to a function.
+        let unattributed: Vec<u64> = if raw_blob {
+            vec![0]
+        } else if functions.is_empty() {
+            let mut starts: Vec<u64> = descent.regions.iter().map(|r| r.start).collect();
+            starts.sort_unstable();
+            starts.dedup();
+            starts
+        } else {
+            Vec::new()
+        };
+        // Callees of code `depth` calls from a seed, up to `max_depth`. Each callee left out
+        // is reported once and kept as an unexplored, unsized stub so its call edges still
+        // land on a function.
+        let queue_callees = |descent: &mut Descent,
+                             functions: &mut BTreeMap<u64, DiscoveredFunction>,
+                             queue: &mut VecDeque<u64>,
+                             found: Vec<(u64, bool, u64)>,
+                             depth: u32| {
+            for (target, thumb, from) in found {
+                if plt_imports.contains_key(&target) || functions.contains_key(&target) {
+                    continue;
+                }
+                match limits.max_depth.filter(|max| depth >= *max) {
+                    Some(max) => {
+                        let name = format!("sub_{:X}", target);
+                        let stub = DiscoveredFunction { name, size: None, thumb, depth: depth + 1 };
+                        functions.insert(target, stub);
+                        descent.truncations.push(truncation(
+                            from,
+                            format!("call to 0x{target:X} not followed past max_depth {max}"),
+                        ));
                     }
+                    None => queue_function(functions, queue, target, None, None, thumb, depth + 1),
                 }
             }
-
-            functions.push(FunctionRecord {
-                address: sym.address,
-                name: Some(sym.name.clone()),
-                size: sym.size.map(|s| s as u32),
-                in_slice: request.roots.iter().any(|r| r == &sym.name),
-                is_boundary: false,
-            });
+        };
+        for start in unattributed {
+            let thumb = mode_at(&descent.modes, start, default_thumb);
+            let (found, _) = descent.explore(start, thumb, None, &functions);
+            queue_callees(&mut descent, &mut functions, &mut queue, found, 0);
         }
 
-        if evidence.is_empty() {
-            if let Ok(insns) = cs.disasm_count(&bytes, 0, max_instructions) {
-                let mut current_block_start = insns.iter().next().map(|i| i.address());
-                let mut current_block_len: u32 = 0;
-                for i in insns.iter().take(128) {
-                    evidence.push(EvidenceRecord {
-                        address: i.address(),
-                        description: format!(
-                            "{} {}",
-                            i.mnemonic().unwrap_or(""),
-                            i.op_str().unwrap_or("")
-                        )
-                        .trim()
-                        .to_string(),
-                        kind: None,
-                    });
-                    current_block_len += 1;
-                    if let Ok(detail) = cs.insn_detail(i) {
-                        let is_block_term = detail.groups().iter().any(|g| {
-                            *g == InsnGroupId(capstone::InsnGroupType::CS_GRP_CALL as u8)
-                                || *g == InsnGroupId(capstone::InsnGroupType::CS_GRP_JUMP as u8)
-                                || *g == InsnGroupId(capstone::InsnGroupType::CS_GRP_RET as u8)
-                        });
-                        if is_block_term {
-                            if let Some(start_addr) = current_block_start {
-                                evidence.push(EvidenceRecord {
-                                    address: start_addr,
-                                    description: format!(
-                                        "basic_block start=0x{start_addr:016X} len={current_block_len}"
-                                    ),
-                                    kind: None,
-                                });
-                            }
-                            current_block_start = i.address().checked_add(i.bytes().len() as u64);
-                            current_block_len = 0;
-                        }
-                        if detail
-                            .groups()
-                            .contains(&InsnGroupId(capstone::InsnGroupType::CS_GRP_CALL as u8))
-                        {
-                            if let Some(target) = decode_call_target(&detail) {
-                                call_edges.push(CallEdge {
-                                    from: i.address(),
-                                    to: target,
-                                    is_cross_slice: false,
-                                });
-                                evidence.push(EvidenceRecord {
-                                    address: i.address(),
-                                    description: format!(
-                                        "call_edge 0x{:X} -> 0x{:X}",
-                                        i.address(),
-                                        target
-                                    ),
-                                    kind: None,
-                                });
-                            }
-                        }
-                    }
-                }
-                if let Some(start_addr) = current_block_start {
-                    basic_blocks.push(crate::services::analysis::BasicBlock {
-                        start: start_addr,
-                        len: current_block_len.max(1),
-                        successors: Vec::new(),
-                    });
+        while let Some(addr) = queue.pop_front() {
+            if descent.exhausted() {
+                // The total budget ran out: the rest keep their symbol data but are not
+                // explored.
+                let left = queue.len() + 1;
+                descent.truncations.push(truncation(
+                    addr,
+                    format!("{left} function(s) not explored after the total budget ran out"),
+                ));
+                break;
+            }
+            let func = &functions[&addr];
+            let (bound, thumb) = (func.size.map(|size| addr.saturating_add(size)), func.thumb);
+            let depth = func.depth;
+            let (found, end) = descent.explore(addr, thumb, bound, &functions);
+            if let Some(func) = functions.get_mut(&addr) {
+                if func.size.is_none() && end > addr {
+                    func.size = Some(end - addr);
                 }
             }
+            queue_callees(&mut descent, &mut functions, &mut queue, found, depth);
+        }
+
+        let pe = layout.as_ref().is_some_and(|l| l.format == ImageFormat::Pe);
+        let mut signatures: HashMap<u64, FunctionSignature> =
+            CallingConvention::for_arch(&arch, pe)
+                .map(|convention| {
+                    functions
+                        .iter()
+                        .filter_map(|(address, func)| {
+                            let code = descent.code_at(*address)?;
+                            let len =
+                                func.size.map_or(code.len(), |s| (s as usize).min(code.len()));
+                            let cs = decoders.get(func.thumb);
+                            infer_function_signature(cs, &code[..len], *address, convention)
+                                .map(|sig| (*address, sig))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+        // Only sized functions: without an end the sweep would run into unrelated code.
+        let mut fingerprints: HashMap<u64, String> = functions
+            .iter()
+            .filter_map(|(address, func)| {
+                let code = descent.code_at(*address)?;
+                let len = (func.size? as usize).min(code.len());
+                let cs = decoders.get(func.thumb);
+                function_fingerprint(cs, &code[..len], *address).map(|hash| (*address, hash))
+            })
+            .collect();
+
+        let Descent {
+            mut evidence,
+            dropped_evidence,
+            dropped_blocks,
+            truncations,
+            xrefs,
+            mut call_edges,
+            basic_blocks,
+            ..
+        } = descent;
+        evidence.extend(dropped_evidence.truncation("disassembly evidence", EVIDENCE_LIMIT));
+        evidence.extend(dropped_blocks.truncation("basic block evidence", BLOCK_EVIDENCE_LIMIT));
+        evidence.extend(truncations);
+        if request.options.include_strings {
+            evidence.extend(strings.iter().map(|s| s.evidence("capstone")));
+        }
+        call_edges.sort_by_key(|e| (e.from, e.to));
+        // Imports are reported when asked for and whenever something calls them, so
+        // `resolve_call_edges` can name those edges.
+        let mut called = HashSet::new();
+        for edge in &call_edges {
+            if let Some(import) = plt_imports.get(&edge.to) {
+                evidence.push(import.call_evidence(edge.from, evidence_source("plt")));
+                called.insert(edge.to);
+            }
+        }
+        for import in plt_imports.values() {
+            if request.options.include_imports || called.contains(&import.address) {
+                evidence.push(import.import_evidence(evidence_source("plt")));
+            }
         }
+        let basic_blocks: Vec<BasicBlock> = basic_blocks.into_values().collect();
+        let mut functions: Vec<FunctionRecord> = functions
+            .into_iter()
+            .map(|(address, func)| FunctionRecord {
+                address,
+                in_slice: root_seeds.contains_key(&address),
+                name: Some(func.name),
+                size: func.size.map(|s| s as u32),
+                is_boundary: false,
+                mangled_name: None,
+                signature: signatures.remove(&address),
+                arch: None,
+                fuzzy_hash: fingerprints.remove(&address),
+                isa: decoders.isa(func.thumb),
+            })
+            .collect();
 
         if functions.is_empty() {
             let mut seen = HashSet::new();
             for edge in &call_edges {
-                if edge.to > 0 && seen.insert(edge.to) {
+                if edge.to > 0 && !plt_imports.contains_key(&edge.to) && seen.insert(edge.to) {
                     functions.push(FunctionRecord {
                         address: edge.to,
                         name: Some(format!("sub_{:X}", edge.to)),
                         size: None,
                         in_slice: false,
                         is_boundary: false,
+                        mangled_name: None,
+                        signature: None,
+                        arch: None,
+                        fuzzy_hash: None,
+                        isa: None,
                     });
                 }
             }
@@ -775,11 +2225,22 @@ impl AnalysisBackend for CapstoneBackend {
                     size: None,
                     in_slice: true,
                     is_boundary: false,
+                    mangled_name: None,
+                    signature: None,
+                    arch: None,
+                    fuzzy_hash: None,
+                    isa: None,
                 })
                 .collect();
         }
 
         let root_hits = crate::services::analysis::build_root_hits(&request.roots, &functions);
+        // Name, pattern, and demangled roots put every function they matched in the slice.
+        let matched: HashSet<u64> =
+            root_hits.iter().flat_map(|hit| hit.functions.iter().copied()).collect();
+        for function in &mut functions {
+            function.in_slice |= matched.contains(&function.address);
+        }
         Ok(AnalysisResult {
             functions,
             call_edges,
@@ -789,10 +2250,9 @@ impl AnalysisBackend for CapstoneBackend {
             root_hits,
             backend_version,
             backend_path: None,
+            sub_slices: Vec::new(),
+            xrefs,
+            text_bytes: Some(text_bytes),
         })
     }
-
-    fn name(&self) -> &'static str {
-        "capstone"
-    }
 }
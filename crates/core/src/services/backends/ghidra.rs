@@ -1,17 +1,30 @@
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::services::analysis::{
-    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, CallEdge, EvidenceRecord,
-    FunctionRecord,
+    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, BackendCapabilities,
+    EvidenceKind, EvidenceRecord, EvidenceSource,
 };
+use crate::services::import::ghidra::{map_export, GhidraExport};
+use crate::services::process::{run_supervised, Deadline};
+
+/// Ghidra post-script bundled with the crate; written next to the temporary project at runtime.
+const EXPORT_SCRIPT_NAME: &str = "BinarySlicerExport.java";
+const EXPORT_SCRIPT: &str = include_str!("scripts/BinarySlicerExport.java");
+
+/// Architecture families reported by `list-backends` (Ghidra's processor modules cover more).
+const GHIDRA_ARCHS: &[&str] =
+    &["x86", "x86_64", "arm", "arm64", "mips", "ppc", "riscv32", "riscv", "sparc"];
 
 /// Resolve the analyzeHeadless executable path from environment variables.
 ///
 /// Precedence:
 /// - `GHIDRA_ANALYZE_HEADLESS` pointing directly to the executable.
-/// - `GHIDRA_INSTALL_DIR`, appended with platform-specific analyzeHeadless name.
+/// - `GHIDRA_INSTALL_DIR`, appended with platform-specific analyzeHeadless name
+///   (checked under `support/` first, matching the Ghidra distribution layout).
 fn resolve_headless_path() -> Result<PathBuf, String> {
     if let Ok(p) = env::var("GHIDRA_ANALYZE_HEADLESS") {
         let path = PathBuf::from(p);
@@ -21,39 +34,100 @@ fn resolve_headless_path() -> Result<PathBuf, String> {
     }
 
     if let Ok(dir) = env::var("GHIDRA_INSTALL_DIR") {
-        let mut p = PathBuf::from(dir);
-        if cfg!(windows) {
-            p = p.join("analyzeHeadless.bat");
-        } else {
-            p = p.join("analyzeHeadless");
-        }
-        if p.is_file() {
-            return Ok(p);
+        let exe = if cfg!(windows) { "analyzeHeadless.bat" } else { "analyzeHeadless" };
+        let dir = PathBuf::from(dir);
+        for candidate in [dir.join("support").join(exe), dir.join(exe)] {
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
         }
     }
 
     Err("Set GHIDRA_ANALYZE_HEADLESS (path to analyzeHeadless) or GHIDRA_INSTALL_DIR".to_string())
 }
 
+/// Determine the Ghidra version from `Ghidra/application.properties` next to the install.
+///
+/// analyzeHeadless has no version flag, so we read the properties file that ships with every
+/// Ghidra distribution (`<install>/support/analyzeHeadless` -> `<install>/Ghidra/...`).
 fn ghidra_version(headless: &Path) -> Result<String, String> {
-    let output = Command::new(headless)
-        .arg("-version")
-        .output()
-        .map_err(|e| format!("failed to spawn analyzeHeadless: {e}"))?;
-    if !output.status.success() {
-        return Err(format!("analyzeHeadless exited with {}", output.status));
+    if let Some(fake) = env::var_os("BS_GHIDRA_FAKE_VERSION") {
+        return Ok(fake.to_string_lossy().to_string());
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let first = stdout.lines().next().unwrap_or("").trim();
-    if first.is_empty() {
-        Err("analyzeHeadless returned empty version string".to_string())
-    } else {
-        Ok(first.to_string())
+    let parent = headless.parent().unwrap_or_else(|| Path::new("."));
+    for install_root in [parent.parent(), Some(parent)].into_iter().flatten() {
+        let props = install_root.join("Ghidra").join("application.properties");
+        if let Ok(body) = fs::read_to_string(&props) {
+            if let Some(version) = body
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("application.version="))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+            {
+                return Ok(format!("ghidra {}", version));
+            }
+        }
     }
+    Err(format!(
+        "could not determine Ghidra version (no Ghidra/application.properties near {})",
+        headless.display()
+    ))
+}
+
+/// Run analyzeHeadless against the binary with the bundled export script and return its JSON,
+/// killing it once `deadline` runs out.
+fn run_headless_export(
+    headless: &Path,
+    binary: &Path,
+    deadline: &Deadline,
+) -> Result<String, AnalysisError> {
+    let nonce = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let work_dir =
+        env::temp_dir().join(format!("binary-slicer-ghidra-{}-{}", std::process::id(), nonce));
+    let project_dir = work_dir.join("project");
+    let script_dir = work_dir.join("scripts");
+    let out_json = work_dir.join("export.json");
+    fs::create_dir_all(&project_dir)
+        .and_then(|_| fs::create_dir_all(&script_dir))
+        .and_then(|_| fs::write(script_dir.join(EXPORT_SCRIPT_NAME), EXPORT_SCRIPT))
+        .map_err(|e| AnalysisError::Backend(format!("failed to prepare Ghidra workspace: {e}")))?;
+
+    let mut cmd = Command::new(headless);
+    cmd.arg(&project_dir)
+        .arg("binary-slicer")
+        .arg("-import")
+        .arg(binary)
+        .arg("-overwrite")
+        .arg("-scriptPath")
+        .arg(&script_dir)
+        .arg("-postScript")
+        .arg(EXPORT_SCRIPT_NAME)
+        .arg(&out_json)
+        .arg("-deleteProject");
+
+    let result = run_supervised(&mut cmd, "analyzeHeadless", deadline).and_then(|output| {
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AnalysisError::Backend(format!(
+                "analyzeHeadless exited with {}: {}",
+                output.status,
+                stderr.lines().last().unwrap_or("").trim()
+            )));
+        }
+        fs::read_to_string(&out_json).map_err(|e| {
+            AnalysisError::Backend(format!(
+                "Ghidra export script produced no output at {}: {e}",
+                out_json.display()
+            ))
+        })
+    });
+    let _ = fs::remove_dir_all(&work_dir);
+    result
 }
 
-/// Minimal Ghidra headless backend stub: validates binary, resolves headless path, and records
-/// Ghidra version as evidence. Full analysis to follow in later iterations.
+/// Ghidra headless backend: imports the binary into a throwaway project, runs the bundled
+/// export post-script, and maps functions, call edges, basic blocks, decompiled signatures,
+/// strings, and imports into the common IR.
 pub struct GhidraBackend;
 
 impl AnalysisBackend for GhidraBackend {
@@ -62,39 +136,75 @@ impl AnalysisBackend for GhidraBackend {
             return Err(AnalysisError::MissingBinary(request.binary_path.clone()));
         }
 
-        let headless = resolve_headless_path().map_err(AnalysisError::Backend)?;
+        // Allow tests to feed synthetic export JSON via env to avoid needing Ghidra installed.
+        let fake_json = env::var_os("BS_GHIDRA_FAKE_JSON");
+        let headless = match (&request.backend_path, &fake_json) {
+            (Some(path), _) => path.clone(),
+            (None, Some(_)) => {
+                resolve_headless_path().unwrap_or_else(|_| PathBuf::from("analyzeHeadless"))
+            }
+            (None, None) => resolve_headless_path().map_err(AnalysisError::Backend)?,
+        };
         let version = ghidra_version(&headless).map_err(AnalysisError::Backend)?;
 
-        // Synthetic functions per root for now; real analysis will populate from Ghidra output.
-        let functions: Vec<FunctionRecord> = request
-            .roots
-            .iter()
-            .enumerate()
-            .map(|(idx, name)| FunctionRecord {
-                address: 0x3000 + idx as u64,
-                name: Some(name.clone()),
-                size: None,
-                in_slice: true,
-                is_boundary: false,
-            })
-            .collect();
-
-        let evidence =
-            vec![EvidenceRecord { address: 0, description: version.clone(), kind: None }];
-
-        Ok(AnalysisResult {
-            functions,
-            call_edges: vec![CallEdge { from: 0, to: 0, is_cross_slice: false }],
-            evidence,
-            basic_blocks: vec![],
-            roots: request.roots.clone(),
-            root_hits: crate::services::analysis::build_root_hits(&request.roots, &functions),
-            backend_version: Some(version),
-            backend_path: Some(headless.to_string_lossy().to_string()),
-        })
+        let body = if let Some(path) = fake_json {
+            fs::read_to_string(path).map_err(|e| {
+                AnalysisError::Backend(format!("failed to read BS_GHIDRA_FAKE_JSON: {e}"))
+            })?
+        } else {
+            run_headless_export(&headless, &request.binary_path, &Deadline::new(request.timeout))?
+        };
+        let export: GhidraExport = serde_json::from_str(&body).map_err(|e| {
+            AnalysisError::Backend(format!("failed to parse Ghidra export JSON: {e}"))
+        })?;
+
+        let mut result = map_export(export, &request.roots, &request.options);
+        result.evidence.push(EvidenceRecord {
+            address: 0,
+            description: version.clone(),
+            kind: Some(EvidenceKind::Other),
+            source: Some(EvidenceSource::new("ghidra", "version")),
+            run_id: None,
+        });
+        result.backend_version = Some(version);
+        result.backend_path = Some(headless.display().to_string());
+        Ok(result)
     }
 
     fn name(&self) -> &'static str {
         "ghidra"
     }
+
+    fn capabilities(&self, tool_path: Option<&Path>) -> BackendCapabilities {
+        let mut caps = BackendCapabilities {
+            archs: GHIDRA_ARCHS.iter().map(|a| a.to_string()).collect(),
+            strings: true,
+            imports: true,
+            basic_blocks: true,
+            decompilation: true,
+            external_tool: Some("analyzeHeadless".into()),
+            ..BackendCapabilities::default()
+        };
+        let headless = match tool_path {
+            Some(path) if path.is_file() => path.to_path_buf(),
+            Some(path) => {
+                caps.unavailable =
+                    Some(format!("configured analyzeHeadless not found at {}", path.display()));
+                return caps;
+            }
+            None => match resolve_headless_path() {
+                Ok(path) => path,
+                Err(reason) => {
+                    caps.unavailable = Some(reason);
+                    return caps;
+                }
+            },
+        };
+        match ghidra_version(&headless) {
+            Ok(version) => caps.tool_version = Some(version),
+            Err(reason) => caps.unavailable = Some(reason),
+        }
+        caps.tool_path = Some(headless);
+        caps
+    }
 }
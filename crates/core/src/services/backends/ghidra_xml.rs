@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path;
+
+use crate::services::analysis::{
+    build_root_hits, AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult,
+    BackendCapabilities,
+};
+use crate::services::import::ghidra_xml::{export_version, parse, BACKEND_NAME};
+
+/// Ghidra XML backend: maps a program Ghidra already exported as XML (the request's
+/// `backend_path`, set by a ritual spec's `ghidra_xml`) into the common IR, so projects can
+/// use Ghidra's analysis on machines without Ghidra or Java.
+pub struct GhidraXmlBackend;
+
+impl AnalysisBackend for GhidraXmlBackend {
+    fn analyze(&self, request: &AnalysisRequest) -> Result<AnalysisResult, AnalysisError> {
+        let path = request.backend_path.as_deref().ok_or_else(|| {
+            AnalysisError::Backend(
+                "the ghidra-xml backend needs an XML export: set 'ghidra_xml' in the ritual spec"
+                    .into(),
+            )
+        })?;
+        let data = fs::read(path).map_err(|e| {
+            AnalysisError::Backend(format!(
+                "failed to read Ghidra XML export {}: {e}",
+                path.display()
+            ))
+        })?;
+        let mut result = parse(&data, Some(path), &request.options)
+            .map_err(|e| AnalysisError::Backend(e.to_string()))?
+            .result;
+        result.roots = request.roots.clone();
+        result.root_hits = build_root_hits(&request.roots, &result.functions);
+        result.backend_version = Some(export_version(&data));
+        result.backend_path = Some(path.display().to_string());
+        Ok(result)
+    }
+
+    fn name(&self) -> &'static str {
+        BACKEND_NAME
+    }
+
+    fn capabilities(&self, _tool_path: Option<&Path>) -> BackendCapabilities {
+        BackendCapabilities { strings: true, imports: true, ..BackendCapabilities::default() }
+    }
+}
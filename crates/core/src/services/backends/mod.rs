@@ -2,12 +2,18 @@
 pub mod capstone;
 #[cfg(feature = "ghidra-backend")]
 pub mod ghidra;
+pub mod ghidra_xml;
 #[cfg(feature = "rizin-backend")]
 pub mod rizin;
+#[cfg(feature = "wasm-backend")]
+pub mod wasm;
 
 #[cfg(feature = "capstone-backend")]
-pub use capstone::CapstoneBackend;
+pub use capstone::{disassemble_range, CapstoneBackend};
 #[cfg(feature = "ghidra-backend")]
 pub use ghidra::GhidraBackend;
+pub use ghidra_xml::GhidraXmlBackend;
 #[cfg(feature = "rizin-backend")]
 pub use rizin::RizinBackend;
+#[cfg(feature = "wasm-backend")]
+pub use wasm::WasmBackend;
@@ -5,13 +5,20 @@ use std::process::Command;
 use serde::Deserialize;
 
 use crate::services::analysis::{
-    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, CallEdge, EvidenceRecord,
-    FunctionRecord,
+    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, BackendCapabilities, CallEdge,
+    EvidenceRecord, EvidenceSource, FunctionRecord,
 };
+use crate::services::process::{run_supervised, Deadline};
+use crate::services::signature::{CallingConvention, FunctionSignature};
 
 /// Rizin-backed analyzer that shells out to rizin/rz with a minimal script to gather symbols.
 pub struct RizinBackend;
 
+/// Architecture families reported by `list-backends` (rizin supports more; these are the ones
+/// ritual specs and `add-binary --arch` commonly name).
+const RIZIN_ARCHS: &[&str] =
+    &["x86", "x86_64", "arm", "arm64", "mips", "ppc", "riscv32", "riscv", "sparc"];
+
 impl AnalysisBackend for RizinBackend {
     fn analyze(&self, request: &AnalysisRequest) -> Result<AnalysisResult, AnalysisError> {
         if !request.binary_path.is_file() {
@@ -20,6 +27,7 @@ impl AnalysisBackend for RizinBackend {
 
         let rizin_path = request.backend_path.clone().unwrap_or_else(resolve_rizin_path);
         let version = version_string(&rizin_path).map_err(AnalysisError::Backend)?;
+        let deadline = Deadline::new(request.timeout);
 
         // Allow tests to feed synthetic JSON via env to avoid needing rizin installed.
         let (functions, call_edges, mut evidence) =
@@ -29,7 +37,7 @@ impl AnalysisBackend for RizinBackend {
                 })?;
                 parse_functions(&body)?
             } else {
-                let json = run_rizin_json(&rizin_path, &request.binary_path, "aa;aflj")?;
+                let json = run_rizin_json(&rizin_path, &request.binary_path, "aa;aflj", &deadline)?;
                 parse_functions(&json)?
             };
 
@@ -39,7 +47,7 @@ impl AnalysisBackend for RizinBackend {
             })?;
             parse_basic_blocks(&body)?
         } else {
-            let json = run_rizin_json(&rizin_path, &request.binary_path, "aa;agfj")?;
+            let json = run_rizin_json(&rizin_path, &request.binary_path, "aa;agfj", &deadline)?;
             parse_basic_blocks(&json)?
         };
 
@@ -47,17 +55,18 @@ impl AnalysisBackend for RizinBackend {
             address: 0,
             description: version.clone(),
             kind: Some(crate::services::analysis::EvidenceKind::Other),
+            source: evidence_source("version"),
+            run_id: None,
         });
 
         // Strings as evidence (optional).
         if request.options.include_strings {
-            let strings_json = std::env::var_os("BS_RIZIN_FAKE_STRINGS")
-                .map(|p| fs::read_to_string(&p).map_err(|e| e.to_string()))
-                .transpose()
-                .map_err(AnalysisError::Backend)?
-                .unwrap_or_else(|| {
-                    run_rizin_json(&rizin_path, &request.binary_path, "izj").unwrap_or_default()
-                });
+            let strings_json = match std::env::var_os("BS_RIZIN_FAKE_STRINGS") {
+                Some(p) => {
+                    fs::read_to_string(&p).map_err(|e| AnalysisError::Backend(e.to_string()))?
+                }
+                None => optional_rizin_json(&rizin_path, &request.binary_path, "izj", &deadline)?,
+            };
             if !strings_json.is_empty() {
                 evidence.extend(parse_strings(&strings_json)?);
             }
@@ -65,45 +74,75 @@ impl AnalysisBackend for RizinBackend {
 
         // Imports as evidence (optional).
         if request.options.include_imports {
-            let imports_json = std::env::var_os("BS_RIZIN_FAKE_IMPORTS")
-                .map(|p| fs::read_to_string(&p).map_err(|e| e.to_string()))
-                .transpose()
-                .map_err(AnalysisError::Backend)?
-                .unwrap_or_else(|| {
-                    run_rizin_json(&rizin_path, &request.binary_path, "iij").unwrap_or_default()
-                });
+            let imports_json = match std::env::var_os("BS_RIZIN_FAKE_IMPORTS") {
+                Some(p) => {
+                    fs::read_to_string(&p).map_err(|e| AnalysisError::Backend(e.to_string()))?
+                }
+                None => optional_rizin_json(&rizin_path, &request.binary_path, "iij", &deadline)?,
+            };
             if !imports_json.is_empty() {
                 evidence.extend(parse_imports(&imports_json)?);
             }
         }
 
+        // Executable section sizes, the denominator of slice coverage (optional).
+        let sections_json = match std::env::var_os("BS_RIZIN_FAKE_SECTIONS") {
+            Some(p) => fs::read_to_string(&p).map_err(|e| AnalysisError::Backend(e.to_string()))?,
+            None => optional_rizin_json(&rizin_path, &request.binary_path, "iSj", &deadline)?,
+        };
+        let text_bytes =
+            if sections_json.is_empty() { None } else { parse_text_bytes(&sections_json)? };
+
+        let root_hits = crate::services::analysis::build_root_hits(&request.roots, &functions);
         Ok(AnalysisResult {
             functions,
             call_edges,
             evidence,
             basic_blocks,
             roots: request.roots.clone(),
-            root_hits: crate::services::analysis::build_root_hits(&request.roots, &functions),
+            root_hits,
             backend_version: Some(version),
             backend_path: Some(rizin_path.display().to_string()),
+            sub_slices: Vec::new(),
+            xrefs: Vec::new(),
+            text_bytes,
         })
     }
 
     fn name(&self) -> &'static str {
         "rizin"
     }
+
+    fn capabilities(&self, tool_path: Option<&Path>) -> BackendCapabilities {
+        let rizin_path = tool_path.map(Path::to_path_buf).unwrap_or_else(resolve_rizin_path);
+        let version = version_string(&rizin_path);
+        BackendCapabilities {
+            archs: RIZIN_ARCHS.iter().map(|a| a.to_string()).collect(),
+            strings: true,
+            imports: true,
+            basic_blocks: true,
+            decompilation: false,
+            external_tool: Some("rizin".into()),
+            tool_path: Some(rizin_path),
+            tool_version: version.as_ref().ok().cloned(),
+            unavailable: version.err(),
+        }
+    }
 }
 
 fn resolve_rizin_path() -> PathBuf {
     std::env::var_os("RIZIN_BIN").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("rizin"))
 }
 
-fn run_rizin_json(rizin_bin: &Path, binary: &Path, command: &str) -> Result<String, AnalysisError> {
-    let output = Command::new(rizin_bin)
-        .args(["-2", "-q0", "-c", command])
-        .arg(binary)
-        .output()
-        .map_err(|e| AnalysisError::Backend(format!("failed to spawn rizin: {e}")))?;
+fn run_rizin_json(
+    rizin_bin: &Path,
+    binary: &Path,
+    command: &str,
+    deadline: &Deadline,
+) -> Result<String, AnalysisError> {
+    let mut cmd = Command::new(rizin_bin);
+    cmd.args(["-2", "-q0", "-c", command]).arg(binary);
+    let output = run_supervised(&mut cmd, "rizin", deadline)?;
     if !output.status.success() {
         return Err(AnalysisError::Backend(format!("rizin exited with {}", output.status)));
     }
@@ -111,6 +150,20 @@ fn run_rizin_json(rizin_bin: &Path, binary: &Path, command: &str) -> Result<Stri
     Ok(stdout)
 }
 
+/// [`run_rizin_json`] for optional data (strings, imports): a failing command yields nothing,
+/// but running out of time or being interrupted still fails the run.
+fn optional_rizin_json(
+    rizin_bin: &Path,
+    binary: &Path,
+    command: &str,
+    deadline: &Deadline,
+) -> Result<String, AnalysisError> {
+    match run_rizin_json(rizin_bin, binary, command, deadline) {
+        Err(err) if err.is_canceled() => Err(err),
+        result => Ok(result.unwrap_or_default()),
+    }
+}
+
 fn parse_functions(
     body: &str,
 ) -> Result<(Vec<FunctionRecord>, Vec<CallEdge>, Vec<EvidenceRecord>), AnalysisError> {
@@ -128,6 +181,15 @@ fn parse_functions(
             size: f.size.map(|s| s as u32),
             in_slice: true,
             is_boundary: false,
+            mangled_name: None,
+            signature: f.nargs.map(|params| FunctionSignature {
+                params,
+                convention: CallingConvention::from_name(f.calltype.as_deref().unwrap_or_default()),
+                stack_cleanup: None,
+            }),
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
         });
         if let Some(callrefs) = f.callrefs {
             for cref in callrefs {
@@ -136,6 +198,8 @@ fn parse_functions(
                         from,
                         to: cref.addr.unwrap_or(0),
                         is_cross_slice: false,
+                        to_name: None,
+                        to_import: false,
                     });
                     let desc = cref
                         .name
@@ -145,6 +209,8 @@ fn parse_functions(
                         address: from,
                         description: desc,
                         kind: Some(crate::services::analysis::EvidenceKind::Call),
+                        source: evidence_source("calls"),
+                        run_id: None,
                     });
                 }
             }
@@ -181,6 +247,10 @@ struct RizinFunction {
     #[serde(default)]
     size: Option<u64>,
     #[serde(default)]
+    nargs: Option<u32>,
+    #[serde(default)]
+    calltype: Option<String>,
+    #[serde(default)]
     callrefs: Option<Vec<RizinCallRef>>,
 }
 
@@ -210,6 +280,11 @@ struct RizinBlock {
     #[serde(default)]
     size: Option<u64>,
     #[serde(default)]
+    ninstr: Option<u32>,
+    /// Disassembled instructions (`agfj`); only counted.
+    #[serde(default)]
+    ops: Option<Vec<serde::de::IgnoredAny>>,
+    #[serde(default)]
     jump: Option<u64>,
     #[serde(default)]
     fail: Option<u64>,
@@ -240,7 +315,8 @@ fn parse_basic_blocks(
                 }
                 blocks_out.push(crate::services::analysis::BasicBlock {
                     start,
-                    len: b.size.unwrap_or(0) as u32,
+                    byte_len: b.size.unwrap_or(0) as u32,
+                    insn_count: b.ninstr.or(b.ops.map(|ops| ops.len() as u32)),
                     successors,
                 });
             }
@@ -267,6 +343,11 @@ struct RizinImport {
     bind: Option<String>,
 }
 
+/// Provenance of rows from one of this backend's passes.
+fn evidence_source(pass: &str) -> Option<EvidenceSource> {
+    Some(EvidenceSource::new("rizin", pass))
+}
+
 fn parse_strings(body: &str) -> Result<Vec<EvidenceRecord>, AnalysisError> {
     let strs: Vec<RizinString> = serde_json::from_str(body)
         .map_err(|e| AnalysisError::Backend(format!("failed to parse rizin strings JSON: {e}")))?;
@@ -277,11 +358,30 @@ fn parse_strings(body: &str) -> Result<Vec<EvidenceRecord>, AnalysisError> {
                 address: s.vaddr.unwrap_or(0),
                 description: format!("string: {}", text),
                 kind: Some(crate::services::analysis::EvidenceKind::String),
+                source: evidence_source("strings"),
+                run_id: None,
             })
         })
         .collect())
 }
 
+#[derive(Debug, Deserialize)]
+struct RizinSection {
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    perm: String,
+}
+
+/// Total file size of the executable sections in rizin `iSj` output; `None` when there are
+/// none (e.g. a raw blob rizin could not map).
+fn parse_text_bytes(body: &str) -> Result<Option<u64>, AnalysisError> {
+    let sections: Vec<RizinSection> = serde_json::from_str(body)
+        .map_err(|e| AnalysisError::Backend(format!("failed to parse rizin sections JSON: {e}")))?;
+    let text: u64 = sections.iter().filter(|s| s.perm.contains('x')).map(|s| s.size).sum();
+    Ok((text > 0).then_some(text))
+}
+
 fn parse_imports(body: &str) -> Result<Vec<EvidenceRecord>, AnalysisError> {
     let imports: Vec<RizinImport> = serde_json::from_str(body)
         .map_err(|e| AnalysisError::Backend(format!("failed to parse rizin imports JSON: {e}")))?;
@@ -292,6 +392,8 @@ fn parse_imports(body: &str) -> Result<Vec<EvidenceRecord>, AnalysisError> {
                 address: imp.plt.unwrap_or(0),
                 description: format!("import: {}", name),
                 kind: Some(crate::services::analysis::EvidenceKind::Import),
+                source: evidence_source("imports"),
+                run_id: None,
             })
         })
         .collect())
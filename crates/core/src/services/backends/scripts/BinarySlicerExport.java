@@ -0,0 +1,192 @@
+// Exports functions, call edges, basic blocks, signatures, strings, and imports as JSON
+// for the binary-slicer Ghidra backend.
+//
+// Usage (post-script): -postScript BinarySlicerExport.java <out.json> [nodecompile]
+// Run from the Script Manager of an existing project, it asks for the output file instead;
+// load the result with `binary-slicer import-analysis --format ghidra-json`.
+//@category BinarySlicer
+
+import java.io.File;
+import java.io.FileWriter;
+import java.io.Writer;
+import java.util.ArrayList;
+import java.util.List;
+
+import ghidra.app.decompiler.DecompInterface;
+import ghidra.app.decompiler.DecompileResults;
+import ghidra.app.script.GhidraScript;
+import ghidra.program.model.address.Address;
+import ghidra.program.model.block.BasicBlockModel;
+import ghidra.program.model.block.CodeBlock;
+import ghidra.program.model.block.CodeBlockIterator;
+import ghidra.program.model.block.CodeBlockReference;
+import ghidra.program.model.block.CodeBlockReferenceIterator;
+import ghidra.program.model.listing.Data;
+import ghidra.program.model.listing.DataIterator;
+import ghidra.program.model.listing.Function;
+import ghidra.program.model.listing.FunctionIterator;
+import ghidra.program.model.listing.InstructionIterator;
+import ghidra.program.model.mem.MemoryBlock;
+import ghidra.program.model.symbol.FlowType;
+
+public class BinarySlicerExport extends GhidraScript {
+
+    @Override
+    public void run() throws Exception {
+        String[] args = getScriptArgs();
+        String outPath;
+        if (args.length >= 1) {
+            outPath = args[0];
+        } else if (isRunningHeadless()) {
+            printerr("usage: BinarySlicerExport <out.json> [nodecompile]");
+            return;
+        } else {
+            File chosen = askFile("Export for binary-slicer", "Export");
+            outPath = chosen.getPath();
+        }
+        boolean decompile = !(args.length > 1 && args[1].equals("nodecompile"));
+
+        DecompInterface decompiler = null;
+        if (decompile) {
+            decompiler = new DecompInterface();
+            decompiler.openProgram(currentProgram);
+        }
+
+        BasicBlockModel blockModel = new BasicBlockModel(currentProgram);
+        List<String> functions = new ArrayList<>();
+        FunctionIterator it = currentProgram.getFunctionManager().getFunctions(true);
+        while (it.hasNext() && !monitor.isCancelled()) {
+            Function f = it.next();
+            functions.add(functionJson(f, blockModel, decompiler));
+        }
+        if (decompiler != null) {
+            decompiler.dispose();
+        }
+
+        List<String> strings = new ArrayList<>();
+        DataIterator data = currentProgram.getListing().getDefinedData(true);
+        while (data.hasNext() && !monitor.isCancelled()) {
+            Data d = data.next();
+            if (d.hasStringValue() && d.getValue() != null) {
+                strings.add("{\"address\":" + offset(d.getAddress()) + ",\"value\":"
+                    + quote(d.getValue().toString()) + "}");
+            }
+        }
+
+        List<String> imports = new ArrayList<>();
+        FunctionIterator externals = currentProgram.getFunctionManager().getExternalFunctions();
+        while (externals.hasNext()) {
+            Function ext = externals.next();
+            Address[] thunks = ext.getFunctionThunkAddresses();
+            String address = (thunks != null && thunks.length > 0) ? offset(thunks[0]) : "0";
+            String library = ext.getExternalLocation() != null
+                ? ext.getExternalLocation().getLibraryName()
+                : null;
+            imports.add("{\"name\":" + quote(ext.getName()) + ",\"address\":" + address
+                + ",\"library\":" + quote(library) + "}");
+        }
+
+        long textBytes = 0;
+        for (MemoryBlock block : currentProgram.getMemory().getBlocks()) {
+            if (block.isExecute() && block.isInitialized() && !block.isExternalBlock()) {
+                textBytes += block.getSize();
+            }
+        }
+
+        try (Writer out = new FileWriter(outPath)) {
+            out.write("{\"program\":" + quote(currentProgram.getName()));
+            out.write(",\"sha256\":" + quote(currentProgram.getExecutableSHA256()));
+            out.write(",\"language\":" + quote(currentProgram.getLanguageID().toString()));
+            out.write(",\"text_bytes\":" + textBytes);
+            out.write(",\"functions\":[" + String.join(",", functions) + "]");
+            out.write(",\"strings\":[" + String.join(",", strings) + "]");
+            out.write(",\"imports\":[" + String.join(",", imports) + "]}");
+        }
+    }
+
+    private String functionJson(Function f, BasicBlockModel model, DecompInterface decompiler)
+            throws Exception {
+        List<String> calls = new ArrayList<>();
+        for (Function callee : f.getCalledFunctions(monitor)) {
+            calls.add("{\"to\":" + offset(callee.getEntryPoint()) + ",\"name\":"
+                + quote(callee.getName()) + "}");
+        }
+
+        List<String> blocks = new ArrayList<>();
+        CodeBlockIterator it = model.getCodeBlocksContaining(f.getBody(), monitor);
+        while (it.hasNext()) {
+            CodeBlock block = it.next();
+            List<String> successors = new ArrayList<>();
+            CodeBlockReferenceIterator dests = block.getDestinations(monitor);
+            while (dests.hasNext()) {
+                CodeBlockReference ref = dests.next();
+                successors.add("{\"target\":" + offset(ref.getDestinationAddress())
+                    + ",\"kind\":" + quote(flowKind(ref.getFlowType())) + "}");
+            }
+            int instructions = 0;
+            InstructionIterator insns = currentProgram.getListing().getInstructions(block, true);
+            while (insns.hasNext()) {
+                insns.next();
+                instructions++;
+            }
+            blocks.add("{\"start\":" + offset(block.getMinAddress()) + ",\"size\":"
+                + block.getNumAddresses() + ",\"instructions\":" + instructions
+                + ",\"successors\":[" + String.join(",", successors) + "]}");
+        }
+
+        String signature = f.getSignature().getPrototypeString();
+        if (decompiler != null) {
+            DecompileResults res = decompiler.decompileFunction(f, 30, monitor);
+            if (res != null && res.decompileCompleted() && res.getDecompiledFunction() != null) {
+                signature = res.getDecompiledFunction().getSignature();
+            }
+        }
+
+        return "{\"entry\":" + offset(f.getEntryPoint()) + ",\"name\":" + quote(f.getName())
+            + ",\"size\":" + f.getBody().getNumAddresses() + ",\"signature\":" + quote(signature)
+            + ",\"params\":" + f.getParameterCount()
+            + ",\"calling_convention\":" + quote(f.getCallingConventionName())
+            + ",\"thunk\":" + f.isThunk() + ",\"calls\":[" + String.join(",", calls)
+            + "],\"blocks\":[" + String.join(",", blocks) + "]}";
+    }
+
+    private static String flowKind(FlowType flow) {
+        if (flow.isCall()) {
+            return flow.isComputed() ? "icall" : "call";
+        }
+        if (flow.isConditional()) {
+            return "cjump";
+        }
+        if (flow.isJump()) {
+            return flow.isComputed() ? "ijump" : "jump";
+        }
+        return "fallthrough";
+    }
+
+    private static String offset(Address address) {
+        return address == null ? "0" : Long.toUnsignedString(address.getOffset());
+    }
+
+    private static String quote(String value) {
+        if (value == null) {
+            return "null";
+        }
+        StringBuilder sb = new StringBuilder("\"");
+        for (char c : value.toCharArray()) {
+            switch (c) {
+                case '"': sb.append("\\\""); break;
+                case '\\': sb.append("\\\\"); break;
+                case '\n': sb.append("\\n"); break;
+                case '\r': sb.append("\\r"); break;
+                case '\t': sb.append("\\t"); break;
+                default:
+                    if (c < 0x20) {
+                        sb.append(String.format("\\u%04x", (int) c));
+                    } else {
+                        sb.append(c);
+                    }
+            }
+        }
+        return sb.append('"').toString();
+    }
+}
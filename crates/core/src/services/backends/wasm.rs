@@ -0,0 +1,467 @@
+//! WebAssembly backend: functions, calls, and data-segment strings of a wasm module.
+//!
+//! Wasm code has no load address, so functions are placed at the file offset of their body
+//! (as `wasm-objdump` and browser devtools report code offsets) and call edges start at the
+//! offset of the calling instruction. Imported functions have no body and sit at the offset
+//! of their import entry. Strings live in linear memory: they are reported at the memory
+//! address their active data segment initializes, which is what `i32.const` operands in the
+//! code point at, so those operands become xrefs linking each string to its users.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use wasmparser::{
+    CompositeInnerType, ConstExpr, DataKind, ElementItems, ElementKind, ExternalKind, FuncType,
+    FunctionBody, KnownCustom, Name, Operator, Parser, Payload, TypeRef,
+};
+
+use crate::services::analysis::{
+    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, BackendCapabilities, CallEdge,
+    EvidenceKind, EvidenceRecord, EvidenceSource, FunctionRecord, XrefKind, XrefRecord,
+};
+use crate::services::entry_points::{EntryKind, EntryPoint};
+use crate::services::fingerprint::mnemonic_hash;
+use crate::services::image::BinaryImage;
+use crate::services::strings::{literal_at, StringLiteral};
+
+/// In-process WebAssembly analyzer built on `wasmparser`.
+pub struct WasmBackend;
+
+const WASM_ARCHS: &[&str] = &["wasm32", "wasm64"];
+
+/// Shortest printable run reported as a string (matches the Capstone backend).
+const MIN_STRING_LEN: usize = 4;
+/// Cap on strings collected from data segments.
+const STRING_LIMIT: usize = 4096;
+
+/// Reported as the backend version (part of the analysis cache key).
+const WASMPARSER_VERSION: &str = "wasmparser 0.245";
+
+/// A function of the module, imported or defined, in function index order.
+struct WasmFunction<'a> {
+    address: u64,
+    size: Option<u32>,
+    type_index: u32,
+    /// `module.name` of an imported function.
+    import: Option<String>,
+    body: Option<FunctionBody<'a>>,
+}
+
+/// The parts of a module the backend reads.
+#[derive(Default)]
+struct WasmModule<'a> {
+    /// Function types by type index (`None` for struct/array types).
+    types: Vec<Option<FuncType>>,
+    functions: Vec<WasmFunction<'a>>,
+    /// Function names from the `name` custom section, then exports.
+    names: HashMap<u32, String>,
+    export_names: HashMap<u32, String>,
+    /// Index of the start function.
+    start: Option<u32>,
+    /// Functions placed in each table by active element segments.
+    tables: BTreeMap<u32, BTreeSet<u32>>,
+    /// Active data segments: memory address and bytes.
+    data: Vec<(u64, &'a [u8])>,
+    imports: Vec<EvidenceRecord>,
+    /// Size of the code section, which holds every function body.
+    code_bytes: u64,
+}
+
+impl<'a> WasmModule<'a> {
+    fn parse(bytes: &'a [u8]) -> wasmparser::Result<Self> {
+        let mut module = WasmModule::default();
+        // Type indices of the defined functions, matched with code bodies in order.
+        let mut defined_types = Vec::new();
+        let mut bodies = Vec::new();
+        for payload in Parser::new(0).parse_all(bytes) {
+            match payload? {
+                Payload::TypeSection(reader) => {
+                    for group in reader {
+                        for ty in group?.into_types() {
+                            module.types.push(match ty.composite_type.inner {
+                                CompositeInnerType::Func(func) => Some(func),
+                                _ => None,
+                            });
+                        }
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader.into_imports_with_offsets() {
+                        let (offset, import) = import?;
+                        let (TypeRef::Func(type_index) | TypeRef::FuncExact(type_index)) =
+                            import.ty
+                        else {
+                            continue;
+                        };
+                        let qualified = format!("{}.{}", import.module, import.name);
+                        module.imports.push(EvidenceRecord {
+                            address: offset as u64,
+                            description: format!("import {qualified}"),
+                            kind: Some(EvidenceKind::Import),
+                            source: evidence_source("imports"),
+                            run_id: None,
+                        });
+                        module.functions.push(WasmFunction {
+                            address: offset as u64,
+                            size: None,
+                            type_index,
+                            import: Some(qualified),
+                            body: None,
+                        });
+                    }
+                }
+                Payload::FunctionSection(reader) => {
+                    for type_index in reader {
+                        defined_types.push(type_index?);
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export?;
+                        if export.kind == ExternalKind::Func {
+                            module
+                                .export_names
+                                .entry(export.index)
+                                .or_insert_with(|| export.name.to_string());
+                        }
+                    }
+                }
+                Payload::StartSection { func, .. } => module.start = Some(func),
+                Payload::ElementSection(reader) => {
+                    for element in reader {
+                        let element = element?;
+                        let ElementKind::Active { table_index, .. } = element.kind else {
+                            continue;
+                        };
+                        let table = module.tables.entry(table_index.unwrap_or(0)).or_default();
+                        match element.items {
+                            ElementItems::Functions(funcs) => {
+                                for func in funcs {
+                                    table.insert(func?);
+                                }
+                            }
+                            ElementItems::Expressions(_, exprs) => {
+                                for expr in exprs {
+                                    let mut ops = expr?.get_operators_reader();
+                                    if let Operator::RefFunc { function_index } = ops.read()? {
+                                        table.insert(function_index);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                Payload::DataSection(reader) => {
+                    for data in reader {
+                        let data = data?;
+                        if let DataKind::Active { memory_index: 0, offset_expr } = data.kind {
+                            if let Some(address) = const_address(&offset_expr) {
+                                module.data.push((address, data.data));
+                            }
+                        }
+                    }
+                }
+                Payload::CodeSectionStart { range, .. } => module.code_bytes = range.len() as u64,
+                Payload::CodeSectionEntry(body) => bodies.push(body),
+                Payload::CustomSection(reader) => {
+                    if let KnownCustom::Name(names) = reader.as_known() {
+                        for name in names {
+                            let Ok(Name::Function(map)) = name else {
+                                continue;
+                            };
+                            for naming in map {
+                                let naming = naming?;
+                                module.names.insert(naming.index, naming.name.to_string());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (body, type_index) in bodies.into_iter().zip(defined_types) {
+            let range = body.range();
+            module.functions.push(WasmFunction {
+                address: range.start as u64,
+                size: Some(range.len() as u32),
+                type_index,
+                import: None,
+                body: Some(body),
+            });
+        }
+        Ok(module)
+    }
+
+    fn name(&self, index: u32) -> String {
+        let func = &self.functions[index as usize];
+        self.names
+            .get(&index)
+            .or_else(|| self.export_names.get(&index))
+            .cloned()
+            .or_else(|| func.import.clone())
+            .unwrap_or_else(|| format!("sub_{:X}", func.address))
+    }
+
+    fn func_type(&self, type_index: u32) -> Option<&FuncType> {
+        self.types.get(type_index as usize)?.as_ref()
+    }
+
+    /// Functions a `call_indirect` through `table` with type `type_index` may reach: the
+    /// table's functions of the same signature.
+    fn indirect_targets(&self, table: u32, type_index: u32) -> Vec<u32> {
+        let Some(expected) = self.func_type(type_index) else {
+            return Vec::new();
+        };
+        self.tables
+            .get(&table)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&index| {
+                self.functions
+                    .get(index as usize)
+                    .is_some_and(|f| self.func_type(f.type_index) == Some(expected))
+            })
+            .collect()
+    }
+
+    /// Printable NUL-terminated runs in the active data segments, sorted by address.
+    fn strings(&self) -> Vec<StringLiteral> {
+        let mut out = Vec::new();
+        'segments: for (base, data) in &self.data {
+            let mut start = 0;
+            for (i, &b) in data.iter().enumerate() {
+                if b.is_ascii_graphic() || b == b' ' || b == b'\t' {
+                    continue;
+                }
+                if b == 0 && i - start >= MIN_STRING_LEN {
+                    out.push(StringLiteral {
+                        address: base + start as u64,
+                        value: String::from_utf8_lossy(&data[start..i]).into_owned(),
+                    });
+                    if out.len() >= STRING_LIMIT {
+                        break 'segments;
+                    }
+                }
+                start = i + 1;
+            }
+        }
+        out.sort_by_key(|s| s.address);
+        out
+    }
+}
+
+/// Address of a constant `i32.const`/`i64.const` offset expression.
+fn const_address(expr: &ConstExpr) -> Option<u64> {
+    match expr.get_operators_reader().read().ok()? {
+        Operator::I32Const { value } => Some(value as u32 as u64),
+        Operator::I64Const { value } => Some(value as u64),
+        _ => None,
+    }
+}
+
+/// Operator name without its immediates (`I32Const { value: 1 }` -> `I32Const`).
+fn operator_mnemonic(op: &Operator) -> String {
+    let debug = format!("{op:?}");
+    debug.split([' ', '{', '(']).next().unwrap_or_default().to_string()
+}
+
+/// What one function body contributes to the result.
+#[derive(Default)]
+struct BodyScan {
+    call_edges: Vec<CallEdge>,
+    xrefs: Vec<XrefRecord>,
+    evidence: Vec<EvidenceRecord>,
+    mnemonics: Vec<String>,
+}
+
+fn scan_body(
+    module: &WasmModule,
+    body: &FunctionBody,
+    strings: &[StringLiteral],
+    max_instructions: usize,
+) -> wasmparser::Result<BodyScan> {
+    let mut scan = BodyScan::default();
+    let mut ops = body.get_operators_reader()?;
+    let address_of = |index: u32| module.functions.get(index as usize).map(|f| f.address);
+    while !ops.eof() && scan.mnemonics.len() < max_instructions {
+        let (op, offset) = ops.read_with_offset()?;
+        let from = offset as u64;
+        scan.mnemonics.push(operator_mnemonic(&op));
+        let callees = match op {
+            Operator::Call { function_index } | Operator::ReturnCall { function_index } => {
+                vec![function_index]
+            }
+            Operator::CallIndirect { type_index, table_index }
+            | Operator::ReturnCallIndirect { type_index, table_index } => {
+                module.indirect_targets(table_index, type_index)
+            }
+            Operator::I32Const { value } => {
+                push_string_xref(value as u32 as u64, from, strings, &mut scan);
+                continue;
+            }
+            Operator::I64Const { value } => {
+                push_string_xref(value as u64, from, strings, &mut scan);
+                continue;
+            }
+            _ => continue,
+        };
+        for to in callees.into_iter().filter_map(address_of) {
+            scan.call_edges.push(CallEdge {
+                from,
+                to,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            });
+            scan.evidence.push(EvidenceRecord {
+                address: from,
+                description: format!("call_edge 0x{:X} -> 0x{:X}", from, to),
+                kind: None,
+                source: evidence_source("calls"),
+                run_id: None,
+            });
+        }
+    }
+    Ok(scan)
+}
+
+/// Provenance of rows from one of this backend's passes.
+fn evidence_source(pass: &str) -> Option<EvidenceSource> {
+    Some(EvidenceSource::new("wasm", pass))
+}
+
+fn push_string_xref(target: u64, from: u64, strings: &[StringLiteral], scan: &mut BodyScan) {
+    let Some(literal) = literal_at(strings, target) else {
+        return;
+    };
+    let preview = literal.value[(target - literal.address) as usize..].to_string();
+    scan.evidence.push(EvidenceRecord {
+        address: from,
+        description: format!("xref imm 0x{target:X} -> section data preview=\"{preview}\""),
+        kind: None,
+        source: evidence_source("xrefs"),
+        run_id: None,
+    });
+    scan.xrefs.push(XrefRecord {
+        from,
+        to: target,
+        kind: XrefKind::Immediate,
+        section: Some("data".into()),
+        preview: Some(preview),
+    });
+}
+
+impl AnalysisBackend for WasmBackend {
+    /// Every imported and defined function of the module, with call edges from `call` and
+    /// `call_indirect` (resolved to the functions of matching type placed in the table by
+    /// active element segments) and strings from active data segments.
+    fn analyze(&self, request: &AnalysisRequest) -> Result<AnalysisResult, AnalysisError> {
+        let bytes = BinaryImage::open(&request.binary_path)?;
+        let invalid = |e: wasmparser::BinaryReaderError| {
+            AnalysisError::Backend(format!("invalid wasm module: {e}"))
+        };
+        let module = WasmModule::parse(&bytes).map_err(invalid)?;
+        let strings = module.strings();
+        let max_instructions = request.options.max_instructions.unwrap_or(usize::MAX).max(1);
+        let root_seeds: HashSet<u64> = request
+            .roots
+            .iter()
+            .filter_map(|r| u64::from_str_radix(r.strip_prefix("0x")?, 16).ok())
+            .collect();
+
+        let mut functions = Vec::new();
+        let mut call_edges = Vec::new();
+        let mut xrefs = Vec::new();
+        let mut evidence = Vec::new();
+        for (index, func) in module.functions.iter().enumerate() {
+            let mut fuzzy_hash = None;
+            if let Some(body) = &func.body {
+                let scan = scan_body(&module, body, &strings, max_instructions).map_err(invalid)?;
+                fuzzy_hash = mnemonic_hash(&scan.mnemonics);
+                call_edges.extend(scan.call_edges);
+                xrefs.extend(scan.xrefs);
+                evidence.extend(scan.evidence);
+            }
+            functions.push(FunctionRecord {
+                address: func.address,
+                name: Some(module.name(index as u32)),
+                size: func.size,
+                in_slice: root_seeds.contains(&func.address),
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash,
+                isa: None,
+            });
+        }
+        if request.options.include_imports {
+            evidence.extend(module.imports.iter().cloned());
+        }
+        if request.options.include_strings {
+            evidence.extend(strings.iter().map(|s| s.evidence("wasm")));
+        }
+        call_edges.sort_by_key(|e| (e.from, e.to));
+        functions.sort_by_key(|f| f.address);
+
+        let root_hits = crate::services::analysis::build_root_hits(&request.roots, &functions);
+        let matched: HashSet<u64> =
+            root_hits.iter().flat_map(|hit| hit.functions.iter().copied()).collect();
+        for function in &mut functions {
+            function.in_slice |= matched.contains(&function.address);
+        }
+        Ok(AnalysisResult {
+            functions,
+            call_edges,
+            evidence,
+            basic_blocks: Vec::new(),
+            roots: request.roots.clone(),
+            root_hits,
+            backend_version: Some(WASMPARSER_VERSION.into()),
+            backend_path: None,
+            sub_slices: Vec::new(),
+            xrefs,
+            text_bytes: (module.code_bytes > 0).then_some(module.code_bytes),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "wasm"
+    }
+
+    /// The start function and exported functions, at the addresses `analyze` gives them.
+    fn entry_points(&self, request: &AnalysisRequest) -> Result<Vec<EntryPoint>, AnalysisError> {
+        let bytes = BinaryImage::open(&request.binary_path)?;
+        let module = WasmModule::parse(&bytes)
+            .map_err(|e| AnalysisError::Backend(format!("invalid wasm module: {e}")))?;
+        let mut points: Vec<(EntryKind, u32)> =
+            module.start.map(|index| (EntryKind::Entry, index)).into_iter().collect();
+        let mut exports: Vec<u32> = module.export_names.keys().copied().collect();
+        exports.sort_unstable();
+        points.extend(exports.into_iter().map(|index| (EntryKind::Export, index)));
+        Ok(points
+            .into_iter()
+            .filter(|(_, index)| (*index as usize) < module.functions.len())
+            .map(|(kind, index)| EntryPoint {
+                kind,
+                address: module.functions[index as usize].address,
+                name: Some(module.name(index)),
+            })
+            .collect())
+    }
+
+    fn capabilities(&self, _tool_path: Option<&Path>) -> BackendCapabilities {
+        BackendCapabilities {
+            archs: WASM_ARCHS.iter().map(|a| a.to_string()).collect(),
+            strings: true,
+            imports: true,
+            basic_blocks: false,
+            decompilation: false,
+            external_tool: None,
+            tool_path: None,
+            tool_version: Some(WASMPARSER_VERSION.into()),
+            unavailable: None,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Header facts about a binary, read when it is registered (`add-binary`, `list-binaries`).
+//!
+//! Format, architecture, word size, byte order, build id, linked libraries, and whether the
+//! symbol table was stripped all come from the container headers, so a project does not
+//! depend on a hand-written `--arch` hint. ELF, PE, and Mach-O parsing needs the
+//! `capstone-backend` feature; WebAssembly modules are recognized by their magic number.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::analysis::Endian;
+
+/// Container format of a binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BinaryFormat {
+    Elf,
+    Pe,
+    MachO,
+    /// Universal Mach-O holding one image per architecture.
+    FatMachO,
+    Wasm,
+}
+
+impl fmt::Display for BinaryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinaryFormat::Elf => "ELF",
+            BinaryFormat::Pe => "PE",
+            BinaryFormat::MachO => "Mach-O",
+            BinaryFormat::FatMachO => "Mach-O (universal)",
+            BinaryFormat::Wasm => "WebAssembly",
+        })
+    }
+}
+
+/// What a binary's headers say about it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinaryInfo {
+    pub format: BinaryFormat,
+    /// Architecture in backend naming (`x86_64`, `x86`, `arm64`, `arm`, ...); `None` for
+    /// universal Mach-O (see `slices`) and machines this does not name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    /// Word size in bits (32 or 64).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bits: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endian: Option<Endian>,
+    /// GNU build id (hex), Mach-O `LC_UUID`, or PE CodeView GUID/age.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_id: Option<String>,
+    /// Shared libraries the binary links against (`DT_NEEDED`, PE imports, Mach-O dylibs).
+    #[serde(default)]
+    pub libraries: Vec<String>,
+    /// Whether local symbols are gone: no named `.symtab` entry (ELF), no defined local
+    /// symbol (Mach-O), or neither COFF symbols nor a CodeView record (PE). `None` when the
+    /// format has no such notion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stripped: Option<bool>,
+    /// Architectures of a universal Mach-O's slices.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slices: Vec<String>,
+}
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// Header facts of `bytes`, or `None` when they are not a recognized container (or, for
+/// ELF, PE, and Mach-O, without the `capstone-backend` feature).
+pub fn binary_info(bytes: &[u8]) -> Option<BinaryInfo> {
+    if bytes.starts_with(WASM_MAGIC) {
+        return Some(BinaryInfo {
+            format: BinaryFormat::Wasm,
+            arch: None,
+            bits: Some(32),
+            endian: Some(Endian::Little),
+            build_id: None,
+            libraries: Vec::new(),
+            stripped: None,
+            slices: Vec::new(),
+        });
+    }
+    #[cfg(feature = "capstone-backend")]
+    {
+        parse::binary_info(bytes)
+    }
+    #[cfg(not(feature = "capstone-backend"))]
+    None
+}
+
+#[cfg(feature = "capstone-backend")]
+mod parse {
+    use goblin::{elf, mach, pe, Object};
+
+    use super::{BinaryFormat, BinaryInfo};
+    use crate::services::analysis::Endian;
+    use crate::services::import::pdb::PdbIdentity;
+
+    pub(super) fn binary_info(bytes: &[u8]) -> Option<BinaryInfo> {
+        match Object::parse(bytes).ok()? {
+            Object::Elf(elf) => Some(elf_info(&elf, bytes)),
+            Object::PE(pe) => Some(pe_info(&pe)),
+            Object::Mach(mach::Mach::Binary(bin)) => Some(mach_info(&bin)),
+            Object::Mach(mach::Mach::Fat(fat)) => Some(fat_info(&fat, bytes)),
+            _ => None,
+        }
+    }
+
+    fn endian(little: bool) -> Option<Endian> {
+        Some(if little { Endian::Little } else { Endian::Big })
+    }
+
+    fn bits(is_64: bool) -> Option<u8> {
+        Some(if is_64 { 64 } else { 32 })
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn elf_info(elf: &elf::Elf, bytes: &[u8]) -> BinaryInfo {
+        use elf::header::*;
+        let arch = match elf.header.e_machine {
+            EM_X86_64 => Some("x86_64"),
+            EM_386 => Some("x86"),
+            EM_AARCH64 => Some("arm64"),
+            EM_ARM => Some("arm"),
+            EM_MIPS => Some("mips"),
+            EM_PPC => Some("ppc"),
+            EM_PPC64 => Some("ppc64"),
+            EM_RISCV => Some("riscv"),
+            _ => None,
+        };
+        // Executables carry the note in a PT_NOTE segment; relocatable objects only in the
+        // section.
+        let notes = elf
+            .iter_note_headers(bytes)
+            .or_else(|| elf.iter_note_sections(bytes, Some(".note.gnu.build-id")));
+        let build_id = notes.into_iter().flatten().filter_map(Result::ok).find_map(|note| {
+            (note.name == "GNU" && note.n_type == elf::note::NT_GNU_BUILD_ID)
+                .then(|| hex(note.desc))
+        });
+        BinaryInfo {
+            format: BinaryFormat::Elf,
+            arch: arch.map(str::to_string),
+            bits: bits(elf.is_64),
+            endian: endian(elf.little_endian),
+            build_id,
+            libraries: elf.libraries.iter().map(|lib| lib.to_string()).collect(),
+            stripped: Some(!elf.syms.iter().any(|sym| sym.st_name != 0)),
+            slices: Vec::new(),
+        }
+    }
+
+    fn pe_info(pe: &pe::PE) -> BinaryInfo {
+        use pe::header::*;
+        let arch = match pe.header.coff_header.machine {
+            COFF_MACHINE_X86 => Some("x86"),
+            COFF_MACHINE_X86_64 => Some("x86_64"),
+            COFF_MACHINE_ARM | COFF_MACHINE_ARMNT => Some("arm"),
+            COFF_MACHINE_ARM64 => Some("arm64"),
+            _ => None,
+        };
+        let codeview = pe.debug_data.as_ref().and_then(|d| d.codeview_pdb70_debug_info.as_ref());
+        let build_id =
+            codeview.map(|cv| PdbIdentity::from_codeview(&cv.signature, cv.age).to_string());
+        let mut libraries: Vec<String> = Vec::new();
+        for lib in &pe.libraries {
+            if !libraries.iter().any(|have| have.eq_ignore_ascii_case(lib)) {
+                libraries.push(lib.to_string());
+            }
+        }
+        let has_symbols = pe.header.coff_header.number_of_symbol_table > 0;
+        BinaryInfo {
+            format: BinaryFormat::Pe,
+            arch: arch.map(str::to_string),
+            bits: bits(pe.is_64),
+            endian: Some(Endian::Little),
+            build_id,
+            libraries,
+            stripped: Some(codeview.is_none() && !has_symbols),
+            slices: Vec::new(),
+        }
+    }
+
+    fn mach_info(bin: &mach::MachO) -> BinaryInfo {
+        use mach::cputype::*;
+        let arch = match bin.header.cputype() {
+            CPU_TYPE_X86 => Some("x86"),
+            CPU_TYPE_X86_64 => Some("x86_64"),
+            CPU_TYPE_ARM => Some("arm"),
+            CPU_TYPE_ARM64 | CPU_TYPE_ARM64_32 => Some("arm64"),
+            CPU_TYPE_POWERPC => Some("ppc"),
+            CPU_TYPE_POWERPC64 => Some("ppc64"),
+            _ => None,
+        };
+        let build_id = bin.load_commands.iter().find_map(|lc| match &lc.command {
+            mach::load_command::CommandVariant::Uuid(uuid) => Some(format_uuid(&uuid.uuid)),
+            _ => None,
+        });
+        // Stripped images keep only the external symbols the dynamic linker needs.
+        let has_locals = bin.symbols().filter_map(Result::ok).any(|(_, nlist)| {
+            !nlist.is_undefined() && !nlist.is_stab() && nlist.n_type & mach::symbols::N_EXT == 0
+        });
+        BinaryInfo {
+            format: BinaryFormat::MachO,
+            arch: arch.map(str::to_string),
+            bits: bits(bin.is_64),
+            endian: endian(bin.little_endian),
+            build_id,
+            libraries: bin
+                .libs
+                .iter()
+                .filter(|lib| **lib != "self")
+                .map(|lib| lib.to_string())
+                .collect(),
+            stripped: Some(!has_locals),
+            slices: Vec::new(),
+        }
+    }
+
+    fn fat_info(fat: &mach::MultiArch, bytes: &[u8]) -> BinaryInfo {
+        let slices = fat
+            .iter_arches()
+            .filter_map(Result::ok)
+            .map(|arch| {
+                binary_info(arch.slice(bytes)).and_then(|info| info.arch).unwrap_or_else(|| {
+                    mach::constants::cputype::get_arch_name_from_types(
+                        arch.cputype,
+                        arch.cpusubtype,
+                    )
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("cputype-{}", arch.cputype))
+                })
+            })
+            .collect();
+        BinaryInfo {
+            format: BinaryFormat::FatMachO,
+            arch: None,
+            bits: None,
+            endian: None,
+            build_id: None,
+            libraries: Vec::new(),
+            stripped: None,
+            slices,
+        }
+    }
+
+    /// `LC_UUID` the way `dwarfdump --uuid` prints it.
+    fn format_uuid(uuid: &[u8; 16]) -> String {
+        let hex = hex(uuid).to_ascii_uppercase();
+        format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+    }
+}
@@ -0,0 +1,196 @@
+//! On-disk cache of backend analysis results (`.ritual/cache/`).
+//!
+//! Entries are keyed by everything that can change a backend's output: binary hash, backend
+//! name/version, the tool version (Capstone is compiled in), analysis options, arch hint, and
+//! roots/root groups. A hit skips disassembly entirely; run records and per-run DB rows are
+//! still written as for an uncached run.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::services::analysis::{
+    AnalysisOptions, AnalysisRequest, AnalysisResult, RawImage, RootGroup, RunMetadata,
+};
+
+const ENTRY_EXTENSION: &str = "json";
+
+/// Inputs that identify a cached analysis result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub binary_hash: String,
+    pub backend: String,
+    pub backend_version: Option<String>,
+    pub tool_version: String,
+    pub options: AnalysisOptions,
+    pub arch: Option<String>,
+    pub roots: Vec<String>,
+    #[serde(default)]
+    pub root_groups: Vec<RootGroup>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<RawImage>,
+    /// Identity (`guid/age`) of the PDB the request carries; its functions seed the analysis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdb: Option<String>,
+}
+
+impl CacheKey {
+    /// Build a key for a request; `None` when the binary hash is unknown (nothing to key on).
+    pub fn for_request(request: &AnalysisRequest, meta: &RunMetadata) -> Option<Self> {
+        Some(Self {
+            binary_hash: meta.binary_hash.clone()?,
+            backend: meta.backend.clone(),
+            backend_version: meta.backend_version.clone(),
+            tool_version: crate::version().to_string(),
+            options: request.options.clone(),
+            arch: request.arch.clone(),
+            roots: request.roots.clone(),
+            root_groups: request.root_groups.clone(),
+            raw: request.raw.clone(),
+            pdb: request.pdb.as_ref().map(|pdb| pdb.identity.to_string()),
+        })
+    }
+
+    /// Stable hex digest used as the entry file name.
+    pub fn digest(&self) -> String {
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// A cached result plus the key it was stored under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub key: CacheKey,
+    pub created_at: String,
+    pub result: AnalysisResult,
+}
+
+/// Summary of one entry for `cache-stats`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheEntryInfo {
+    pub digest: String,
+    pub binary_hash: String,
+    pub backend: String,
+    pub backend_version: Option<String>,
+    pub created_at: String,
+    pub bytes: u64,
+}
+
+/// Totals for the cache directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub dir: String,
+    pub entries: usize,
+    pub total_bytes: u64,
+    /// Files that could not be parsed as entries (still counted in `total_bytes`).
+    pub unreadable: usize,
+    pub items: Vec<CacheEntryInfo>,
+}
+
+/// File-per-entry analysis cache rooted at a directory.
+#[derive(Debug, Clone)]
+pub struct AnalysisCache {
+    dir: PathBuf,
+}
+
+impl AnalysisCache {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{}.{}", key.digest(), ENTRY_EXTENSION))
+    }
+
+    /// Look up a result; unreadable or mismatched entries are treated as misses.
+    pub fn get(&self, key: &CacheKey) -> Option<AnalysisResult> {
+        let bytes = fs::read(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&bytes).ok()?;
+        (entry.key == *key).then_some(entry.result)
+    }
+
+    /// Store a result, replacing any previous entry for the key.
+    ///
+    /// Written to a temp file and renamed so a concurrent reader never sees a partial entry.
+    pub fn put(&self, key: &CacheKey, result: &AnalysisResult) -> std::io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            key: key.clone(),
+            created_at: Utc::now().to_rfc3339(),
+            result: result.clone(),
+        };
+        let path = self.entry_path(key);
+        let tmp = path.with_extension(format!("{}.tmp-{}", ENTRY_EXTENSION, std::process::id()));
+        fs::write(&tmp, serde_json::to_vec(&entry)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// Enumerate entries (a missing directory is an empty cache).
+    pub fn stats(&self) -> std::io::Result<CacheStats> {
+        let mut stats = CacheStats { dir: self.dir.display().to_string(), ..Default::default() };
+        for path in self.entry_files()? {
+            let bytes = fs::metadata(&path)?.len();
+            stats.total_bytes += bytes;
+            let entry = fs::read(&path)
+                .ok()
+                .and_then(|data| serde_json::from_slice::<CacheEntry>(&data).ok());
+            let Some(entry) = entry else {
+                stats.unreadable += 1;
+                continue;
+            };
+            stats.entries += 1;
+            stats.items.push(CacheEntryInfo {
+                digest: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                binary_hash: entry.key.binary_hash,
+                backend: entry.key.backend,
+                backend_version: entry.key.backend_version,
+                created_at: entry.created_at,
+                bytes,
+            });
+        }
+        stats.items.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.digest.cmp(&b.digest)));
+        Ok(stats)
+    }
+
+    /// Remove every entry; returns (files removed, bytes freed).
+    pub fn clear(&self) -> std::io::Result<(usize, u64)> {
+        let mut removed = 0;
+        let mut freed = 0;
+        for path in self.entry_files()? {
+            freed += fs::metadata(&path)?.len();
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+        Ok((removed, freed))
+    }
+
+    fn entry_files(&self) -> std::io::Result<Vec<PathBuf>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some(ENTRY_EXTENSION)
+            {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
@@ -0,0 +1,171 @@
+//! User-level settings shared by every project.
+//!
+//! `$XDG_CONFIG_HOME/binary-slicer/config.toml` (`~/.config/binary-slicer/config.toml` when
+//! `XDG_CONFIG_HOME` is unset) holds what an analyst would otherwise repeat in each project:
+//! the default backend, tool paths, batch parallelism, and color output.
+//! [`resolve_project_config`] merges it under a project's config when the project is opened;
+//! whatever the project sets wins, and the project file itself is never rewritten with user
+//! values.
+//!
+//! ```toml
+//! default_backend = "rizin"
+//! jobs = 8
+//! color = "never"
+//!
+//! [backends]
+//! rizin = "~/tools/rizin/bin/rizin"
+//! ghidra_headless = "/opt/ghidra/support/analyzeHeadless"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::db::{BackendPaths, ProjectConfig, KNOWN_BACKENDS};
+
+/// Directory under the XDG config home holding the user config.
+pub const USER_CONFIG_DIR: &str = "binary-slicer";
+
+/// File name of the user config.
+pub const USER_CONFIG_FILE: &str = "config.toml";
+
+/// When the CLI colors its output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Color when writing to a terminal (and `NO_COLOR` is unset).
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Settings read from the user config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    /// Backend for projects that set neither `default_backend` nor `backend_priority`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_backend: Option<String>,
+    /// Tool paths for projects that do not set their own; `~/` expands to the home directory.
+    #[serde(default, skip_serializing_if = "BackendPaths::is_empty")]
+    pub backends: BackendPaths,
+    /// Analyses `batch-run` runs at once when `--jobs` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorChoice>,
+}
+
+/// Errors from reading the user config.
+#[derive(Debug, Error)]
+pub enum UserConfigError {
+    #[error("Failed to read user config at {path}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Failed to parse user config at {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("Invalid value for '{key}' in user config at {path}: {message}")]
+    InvalidValue { path: PathBuf, key: String, message: String },
+}
+
+/// Path of the user config: under `$XDG_CONFIG_HOME` when it is set to an absolute path,
+/// else under `$HOME/.config`. `None` when neither is available.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home_dir().map(|home| home.join(".config")))?;
+    Some(config_home.join(USER_CONFIG_DIR).join(USER_CONFIG_FILE))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from)
+}
+
+impl UserConfig {
+    /// Load the user config at [`user_config_path`]; empty when there is none.
+    pub fn load() -> Result<Self, UserConfigError> {
+        match user_config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load and check the user config at `path`; empty when the file does not exist.
+    pub fn load_from(path: &Path) -> Result<Self, UserConfigError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => return Err(UserConfigError::Read { path: path.to_path_buf(), source }),
+        };
+        let config: Self = toml::from_str(&text).map_err(|err| UserConfigError::Parse {
+            path: path.to_path_buf(),
+            message: err.message().to_string(),
+        })?;
+        config.check(path)?;
+        Ok(config)
+    }
+
+    fn check(&self, path: &Path) -> Result<(), UserConfigError> {
+        let invalid = |key: &str, message: String| UserConfigError::InvalidValue {
+            path: path.to_path_buf(),
+            key: key.to_string(),
+            message,
+        };
+        if let Some(backend) = &self.default_backend {
+            if !KNOWN_BACKENDS.contains(&backend.as_str()) {
+                return Err(invalid(
+                    "default_backend",
+                    format!(
+                        "unknown backend '{}' (available: {})",
+                        backend,
+                        KNOWN_BACKENDS.join(", ")
+                    ),
+                ));
+            }
+        }
+        if self.jobs == Some(0) {
+            return Err(invalid("jobs", "must be at least 1".into()));
+        }
+        Ok(())
+    }
+
+    /// Tool paths with `~/` expanded.
+    pub fn backend_paths(&self) -> BackendPaths {
+        BackendPaths {
+            rizin: self.backends.rizin.as_deref().map(expand_home),
+            ghidra_headless: self.backends.ghidra_headless.as_deref().map(expand_home),
+        }
+    }
+
+    /// Fill the settings `config` leaves unset from this user config.
+    pub fn merge_into(&self, config: &mut ProjectConfig) {
+        if config.default_backend.is_none() && config.backend_priority.is_empty() {
+            config.default_backend = self.default_backend.clone();
+        }
+        let paths = self.backend_paths();
+        if config.backends.rizin.is_none() {
+            config.backends.rizin = paths.rizin;
+        }
+        if config.backends.ghidra_headless.is_none() {
+            config.backends.ghidra_headless = paths.ghidra_headless;
+        }
+    }
+}
+
+/// `project` with the user config merged under it (see [`UserConfig::merge_into`]).
+pub fn resolve_project_config(
+    mut project: ProjectConfig,
+) -> Result<ProjectConfig, UserConfigError> {
+    UserConfig::load()?.merge_into(&mut project);
+    Ok(project)
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
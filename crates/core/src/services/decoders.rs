@@ -0,0 +1,420 @@
+//! String decoders: recover obfuscated strings from a binary's data.
+//!
+//! Games often store strings XOR-encoded or base64-wrapped, so the strings pass sees noise where
+//! the interesting text is. A ritual spec may list decoders (`string_decoders: [xor, base64]`)
+//! that run over the binary's data after analysis. The input is [`byte_sequences`]: runs of
+//! non-NUL bytes in data sections (the whole file for raw images). Each decoded string becomes
+//! `EvidenceKind::String` evidence at the address of its encoded bytes, with source
+//! `decoder/<pass>`. Strings are then re-linked to the code referencing them, so `string:` roots
+//! match decoded content.
+//!
+//! Built-in decoders:
+//! - `xor:<key>` XORs every sequence with one byte (`xor:0x5a`).
+//! - `xor` searches all single-byte keys and keeps the one that turns most of a sequence into
+//!   text. Sequences that are already printable are skipped.
+//! - `base64` decodes printable sequences in the standard alphabet.
+//!
+//! Anything else is a hook, a path relative to the project root. `.wasm` modules run on the
+//! built-in WASI runtime (see [`crate::services::wasi`]); other hooks are run directly. A hook reads
+//! a [`DecoderInput`] as JSON on stdin and writes a [`DecoderOutput`] as JSON on stdout.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::services::analysis::{AnalysisError, AnalysisResult, EvidenceSource};
+use crate::services::loader::{ImageLayout, MappedRegion};
+use crate::services::process::{run_supervised_with_input, Deadline};
+use crate::services::strings::{
+    is_string_section, link_string_xrefs, resolve_string_roots, StringLiteral, ZERO_FILL_SECTIONS,
+};
+use crate::services::wasi::run_wasi;
+
+/// Version of the hook input and output formats.
+pub const DECODER_API_VERSION: u32 = 1;
+
+/// Shortest decoded string reported.
+const MIN_DECODED_LEN: usize = 4;
+/// Shortest string the `xor` key search reports; short matches are mostly chance.
+const MIN_GUESSED_LEN: usize = 6;
+/// Longer runs of non-NUL bytes are code or tables, not strings.
+const MAX_SEQUENCE_LEN: usize = 4096;
+/// Cap on sequences extracted from one binary.
+const SEQUENCE_LIMIT: usize = 16384;
+/// Cap on strings one decoder adds.
+const DECODED_LIMIT: usize = 4096;
+
+#[derive(Debug, Error)]
+pub enum DecoderError {
+    #[error("Invalid XOR key in string decoder '{0}' (expected a byte such as xor:0x5a)")]
+    InvalidKey(String),
+    #[error("String decoder hook not found at {0}")]
+    MissingHook(PathBuf),
+    #[error("String decoder {decoder} could not run")]
+    Run {
+        decoder: String,
+        #[source]
+        source: AnalysisError,
+    },
+    #[error("String decoder {decoder} exited with {}: {stderr}", .code.map_or("a signal".to_string(), |c| format!("status {c}")))]
+    Exit { decoder: String, code: Option<i32>, stderr: String },
+    #[error("String decoder {decoder} wrote invalid output: {message}")]
+    InvalidOutput { decoder: String, message: String },
+    #[error(
+        "String decoder {decoder} speaks API version {version} (expected {DECODER_API_VERSION})"
+    )]
+    UnsupportedApi { decoder: String, version: u32 },
+}
+
+/// One entry of a spec's `string_decoders`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoder {
+    /// XOR with a fixed byte, or (`None`) with the best single-byte key per sequence.
+    Xor(Option<u8>),
+    Base64,
+    /// A WASM module or program, relative to the project root.
+    Hook(String),
+}
+
+impl Decoder {
+    pub fn parse(text: &str) -> Result<Self, DecoderError> {
+        let text = text.trim();
+        match text {
+            "xor" => Ok(Decoder::Xor(None)),
+            "base64" => Ok(Decoder::Base64),
+            _ => match text.strip_prefix("xor:") {
+                Some(key) => parse_key(key)
+                    .map(|key| Decoder::Xor(Some(key)))
+                    .ok_or_else(|| DecoderError::InvalidKey(text.to_string())),
+                None => Ok(Decoder::Hook(text.to_string())),
+            },
+        }
+    }
+
+    /// How the decoder is written in a spec.
+    pub fn name(&self) -> String {
+        match self {
+            Decoder::Xor(None) => "xor".into(),
+            Decoder::Xor(Some(key)) => format!("xor:0x{key:02x}"),
+            Decoder::Base64 => "base64".into(),
+            Decoder::Hook(path) => path.clone(),
+        }
+    }
+}
+
+/// `0x5a` or `90`; zero is no key.
+fn parse_key(text: &str) -> Option<u8> {
+    let key = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16).ok()?,
+        None => text.parse().ok()?,
+    };
+    (key != 0).then_some(key)
+}
+
+/// A run of non-NUL bytes in the binary's data, at the address it is loaded to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteSequence {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// What a hook reads on stdin.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecoderInput<'a> {
+    pub api_version: u32,
+    pub binary: &'a str,
+    pub sequences: Vec<HookSequence>,
+}
+
+/// A [`ByteSequence`] as hooks see it, its bytes in lowercase hex.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookSequence {
+    pub address: u64,
+    pub bytes: String,
+}
+
+/// What a hook writes on stdout; every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DecoderOutput {
+    /// When set, must equal [`DECODER_API_VERSION`].
+    pub api_version: Option<u32>,
+    /// Decoded strings, at the address of their encoded bytes.
+    pub strings: Vec<DecodedString>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DecodedString {
+    pub address: u64,
+    pub value: String,
+}
+
+/// What one decoder added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DecoderReport {
+    pub decoder: String,
+    pub strings_added: usize,
+}
+
+/// Runs of at least four non-NUL bytes in the data sections of `layout`, or in its segments when
+/// it has no sections (raw images), in address order.
+pub fn byte_sequences(bytes: &[u8], layout: &ImageLayout) -> Vec<ByteSequence> {
+    let regions: Vec<&MappedRegion> = if layout.sections.is_empty() {
+        layout.segments.iter().collect()
+    } else {
+        layout
+            .sections
+            .iter()
+            .filter(|s| {
+                !s.executable
+                    && s.address != 0
+                    && is_string_section(&s.name)
+                    && !ZERO_FILL_SECTIONS.contains(&s.name.as_str())
+            })
+            .collect()
+    };
+    let mut out = Vec::new();
+    'regions: for region in regions {
+        let Some(data) = region.file_bytes(bytes) else {
+            continue;
+        };
+        let mut offset = 0;
+        for run in data.split(|b| *b == 0) {
+            if (MIN_DECODED_LEN..=MAX_SEQUENCE_LEN).contains(&run.len()) {
+                out.push(ByteSequence {
+                    address: region.address + offset as u64,
+                    bytes: run.to_vec(),
+                });
+                if out.len() >= SEQUENCE_LIMIT {
+                    break 'regions;
+                }
+            }
+            offset += run.len() + 1;
+        }
+    }
+    out.sort_by_key(|s| s.address);
+    out
+}
+
+/// Run `decoders` in order over `sequences` and add what they decode to `result` as string
+/// evidence; then link the new strings to their references and resolve `string:` roots again.
+/// Hook paths are resolved against `base`.
+pub fn run_decoders(
+    result: &mut AnalysisResult,
+    sequences: &[ByteSequence],
+    decoders: &[Decoder],
+    base: &Path,
+    binary: &str,
+    deadline: &Deadline,
+) -> Result<Vec<DecoderReport>, DecoderError> {
+    let mut reports = Vec::new();
+    for decoder in decoders {
+        let decoded = match decoder {
+            Decoder::Xor(Some(key)) => sequences
+                .iter()
+                .flat_map(|s| xor_strings(s, *key, MIN_DECODED_LEN))
+                .map(|s| (s, format!("xor:0x{key:02x}")))
+                .collect(),
+            Decoder::Xor(None) => sequences
+                .iter()
+                .filter_map(guess_xor)
+                .flat_map(|(key, strings)| {
+                    strings.into_iter().map(move |s| (s, format!("xor:0x{key:02x}")))
+                })
+                .collect(),
+            Decoder::Base64 => {
+                sequences.iter().filter_map(base64_string).map(|s| (s, "base64".into())).collect()
+            }
+            Decoder::Hook(hook) => {
+                let pass = Path::new(hook)
+                    .file_stem()
+                    .map_or(hook.clone(), |stem| stem.to_string_lossy().into_owned());
+                run_hook(hook, sequences, base, binary, deadline)?
+                    .into_iter()
+                    .map(|s| (s, pass.clone()))
+                    .collect()
+            }
+        };
+        let strings_added = add_strings(result, decoded);
+        reports.push(DecoderReport { decoder: decoder.name(), strings_added });
+    }
+    if reports.iter().any(|r| r.strings_added > 0) {
+        link_string_xrefs(result);
+        resolve_string_roots(result);
+    }
+    Ok(reports)
+}
+
+/// Add `strings` (with their decoder pass) as evidence, skipping ones already reported at the
+/// same address; returns the number added.
+fn add_strings(result: &mut AnalysisResult, strings: Vec<(StringLiteral, String)>) -> usize {
+    let mut added = 0;
+    for (literal, pass) in strings {
+        if added >= DECODED_LIMIT {
+            break;
+        }
+        let mut record = literal.evidence("decoder");
+        record.source = Some(EvidenceSource::new("decoder", pass));
+        if result
+            .evidence
+            .iter()
+            .any(|e| (e.address, &e.description) == (record.address, &record.description))
+        {
+            continue;
+        }
+        result.evidence.push(record);
+        added += 1;
+    }
+    added
+}
+
+/// Printable ASCII that reads like text: at least one letter, and mostly letters, digits, and
+/// spaces.
+fn looks_like_text(bytes: &[u8]) -> bool {
+    let printable = bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ' || *b == b'\t');
+    let wordy = bytes.iter().filter(|b| b.is_ascii_alphanumeric() || **b == b' ').count();
+    printable && bytes.iter().any(u8::is_ascii_alphabetic) && wordy * 4 >= bytes.len() * 3
+}
+
+/// Text in `sequence` XORed with `key`. Encoded terminators decode to NUL, so one sequence may
+/// hold several strings.
+fn xor_strings(sequence: &ByteSequence, key: u8, min_len: usize) -> Vec<StringLiteral> {
+    let decoded: Vec<u8> = sequence.bytes.iter().map(|b| b ^ key).collect();
+    let mut out = Vec::new();
+    let mut offset = 0;
+    for piece in decoded.split(|b| *b == 0) {
+        if piece.len() >= min_len && looks_like_text(piece) {
+            out.push(StringLiteral {
+                address: sequence.address + offset as u64,
+                value: String::from_utf8_lossy(piece).into_owned(),
+            });
+        }
+        offset += piece.len() + 1;
+    }
+    out
+}
+
+/// The single-byte key that decodes the most of `sequence` into text (at least half of it),
+/// preferring lowercase text on ties, with the strings it decodes.
+fn guess_xor(sequence: &ByteSequence) -> Option<(u8, Vec<StringLiteral>)> {
+    if looks_like_text(&sequence.bytes) {
+        return None;
+    }
+    let score = |strings: &[StringLiteral]| {
+        let covered: usize = strings.iter().map(|s| s.value.len()).sum();
+        let lower = strings
+            .iter()
+            .flat_map(|s| s.value.bytes())
+            .filter(|b| b.is_ascii_lowercase() || *b == b' ')
+            .count();
+        (covered, lower)
+    };
+    let (key, strings) = (1..=u8::MAX)
+        .map(|key| (key, xor_strings(sequence, key, MIN_GUESSED_LEN)))
+        .max_by(|a, b| score(&a.1).cmp(&score(&b.1)).then(b.0.cmp(&a.0)))?;
+    let (covered, _) = score(&strings);
+    (covered > 0 && covered * 2 >= sequence.bytes.len()).then_some((key, strings))
+}
+
+/// The text a base64 `sequence` decodes to, if it is text.
+fn base64_string(sequence: &ByteSequence) -> Option<StringLiteral> {
+    if sequence.bytes.len() < 8 {
+        return None;
+    }
+    let decoded = base64_decode(&sequence.bytes)?;
+    let text = decoded.strip_suffix(&[0]).unwrap_or(&decoded);
+    (text.len() >= MIN_DECODED_LEN && looks_like_text(text)).then(|| StringLiteral {
+        address: sequence.address,
+        value: String::from_utf8_lossy(text).into_owned(),
+    })
+}
+
+/// Standard-alphabet base64, padded or not.
+fn base64_decode(text: &[u8]) -> Option<Vec<u8>> {
+    let body = text.strip_suffix(b"==").or_else(|| text.strip_suffix(b"=")).unwrap_or(text);
+    if body.len() % 4 == 1 || (body.len() != text.len() && !text.len().is_multiple_of(4)) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(body.len() * 3 / 4);
+    let (mut acc, mut bits) = (0u32, 0);
+    for &c in body {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        acc = (acc << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn run_hook(
+    hook: &str,
+    sequences: &[ByteSequence],
+    base: &Path,
+    binary: &str,
+    deadline: &Deadline,
+) -> Result<Vec<StringLiteral>, DecoderError> {
+    let path = base.join(hook);
+    if !path.is_file() {
+        return Err(DecoderError::MissingHook(path));
+    }
+    let sequences = sequences
+        .iter()
+        .map(|s| HookSequence {
+            address: s.address,
+            bytes: s.bytes.iter().map(|b| format!("{b:02x}")).collect(),
+        })
+        .collect();
+    let input = DecoderInput { api_version: DECODER_API_VERSION, binary, sequences };
+    let input = serde_json::to_vec(&input).expect("byte sequences serialize");
+    let run = |source| DecoderError::Run { decoder: hook.to_string(), source };
+    let (code, stdout, stderr) = if path.extension().is_some_and(|ext| ext == "wasm") {
+        let output = run_wasi(&path, hook, input, deadline).map_err(run)?;
+        (Some(output.code), output.stdout, output.stderr)
+    } else {
+        let output =
+            run_supervised_with_input(Command::new(&path).current_dir(base), hook, input, deadline)
+                .map_err(run)?;
+        (output.status.code(), output.stdout, output.stderr)
+    };
+    if code != Some(0) {
+        return Err(DecoderError::Exit {
+            decoder: hook.to_string(),
+            code,
+            stderr: String::from_utf8_lossy(&stderr).trim().to_string(),
+        });
+    }
+    let output = parse_decoder_output(hook, &stdout)?;
+    Ok(output
+        .strings
+        .into_iter()
+        .filter(|s| !s.value.is_empty())
+        .map(|s| StringLiteral { address: s.address, value: s.value })
+        .collect())
+}
+
+/// Parse a hook's stdout; empty output means nothing was decoded.
+pub fn parse_decoder_output(decoder: &str, stdout: &[u8]) -> Result<DecoderOutput, DecoderError> {
+    if stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(DecoderOutput::default());
+    }
+    let output: DecoderOutput = serde_json::from_slice(stdout).map_err(|e| {
+        DecoderError::InvalidOutput { decoder: decoder.to_string(), message: e.to_string() }
+    })?;
+    if let Some(version) = output.api_version.filter(|v| *v != DECODER_API_VERSION) {
+        return Err(DecoderError::UnsupportedApi { decoder: decoder.to_string(), version });
+    }
+    Ok(output)
+}
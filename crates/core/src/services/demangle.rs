@@ -0,0 +1,63 @@
+//! Symbol demangling for backend function names.
+//!
+//! Backends report raw linker symbols (`_ZN7Example4initEv`). [`demangle_functions`] rewrites
+//! [`FunctionRecord::name`] to a readable form and keeps the original in
+//! [`FunctionRecord::mangled_name`], so reports, DOT graphs, and docs show `Example::init()`.
+//!
+//! Supported schemes:
+//! - Rust legacy (`_ZN…17h<hash>E`, printed without the trailing hash) and v0 (`_R…`)
+//!   mangling, via `rustc-demangle`.
+//! - The Itanium C++ ABI via `cpp_demangle`, with `c++filt`-style output (`foo(char const*)`).
+//!
+//! Mach-O symbols with the extra leading underscore (`__Z…`, `__R…`) are accepted. Symbol
+//! names come from untrusted binaries, so both demanglers run with recursion limits and a
+//! name either demangler rejects is left mangled.
+
+use cpp_demangle::{DemangleOptions, ParseOptions, Symbol};
+
+use crate::services::analysis::FunctionRecord;
+
+/// Nesting depth past which a C++ symbol is rejected rather than parsed (names, types,
+/// template arguments). Real symbols stay far below it.
+const CPP_RECURSION_LIMIT: u32 = 64;
+
+/// Demangle every function name that is a recognized mangled symbol.
+///
+/// Records that already carry a `mangled_name` are left alone, so this is idempotent.
+pub fn demangle_functions(functions: &mut [FunctionRecord]) {
+    for function in functions {
+        if function.mangled_name.is_some() {
+            continue;
+        }
+        if let Some(readable) = function.name.as_deref().and_then(demangle) {
+            function.mangled_name = function.name.replace(readable);
+        }
+    }
+}
+
+/// Readable form of a mangled Rust or C++ symbol; `None` when `symbol` is not mangled or
+/// cannot be demangled.
+pub fn demangle(symbol: &str) -> Option<String> {
+    // Mach-O prefixes every C symbol with an underscore, so `_Z…` arrives as `__Z…`.
+    let unprefixed = symbol.strip_prefix('_').filter(|s| s.starts_with('_')).unwrap_or(symbol);
+    if !unprefixed.starts_with("_Z") && !unprefixed.starts_with("_R") {
+        return None;
+    }
+    demangle_rust(symbol).or_else(|| demangle_cpp(symbol))
+}
+
+/// Rust symbols, legacy or v0. A legacy symbol must end in the `h<hash>` segment rustc adds,
+/// so plain C++ `_ZN…E` names go to the C++ demangler.
+fn demangle_rust(symbol: &str) -> Option<String> {
+    let demangled = rustc_demangle::try_demangle(symbol).ok()?;
+    let (full, readable) = (demangled.to_string(), format!("{demangled:#}"));
+    let is_v0 = symbol.trim_start_matches('_').starts_with('R');
+    (is_v0 || full != readable).then_some(readable)
+}
+
+fn demangle_cpp(symbol: &str) -> Option<String> {
+    let parse = ParseOptions::default().recursion_limit(CPP_RECURSION_LIMIT);
+    let parsed = Symbol::new_with_options(symbol.as_bytes(), &parse).ok()?;
+    let options = DemangleOptions::new().recursion_limit(CPP_RECURSION_LIMIT);
+    parsed.demangle_with_options(&options).ok().filter(|readable| readable != symbol)
+}
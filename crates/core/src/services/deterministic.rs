@@ -0,0 +1,92 @@
+//! Reproducible run output for `--deterministic` runs.
+//!
+//! Backends may report functions, edges, and evidence in hash-map or thread-completion order,
+//! and runs are stamped with the wall clock and absolute tool paths. A deterministic run
+//! sorts every list in the result ([`canonicalize_result`]), records a fixed timestamp (the
+//! spec's `epoch`, or [`DETERMINISTIC_EPOCH`]), and stores paths project-relative
+//! ([`normalize_path`]), so two runs of the same spec and binary produce byte-identical
+//! reports on any machine.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::services::analysis::{AnalysisResult, EvidenceKind};
+
+/// Timestamp recorded by deterministic runs whose spec declares no `epoch`.
+pub const DETERMINISTIC_EPOCH: &str = "1970-01-01T00:00:00+00:00";
+
+/// Normalize a spec `epoch` (RFC 3339, or Unix seconds) to the RFC 3339 form runs record.
+pub fn parse_epoch(value: &str) -> Option<String> {
+    let value = value.trim();
+    if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        let secs: i64 = value.parse().ok()?;
+        return DateTime::<Utc>::from_timestamp(secs, 0).map(|t| t.to_rfc3339());
+    }
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc).to_rfc3339())
+}
+
+/// Sort every list in `result` into a stable order: functions by address, edges and xrefs by
+/// endpoints, evidence by address then text, blocks by start, and the addresses of each root
+/// hit and sub-slice ascending. Roots keep their spec order.
+pub fn canonicalize_result(result: &mut AnalysisResult) {
+    result.functions.sort_by(|a, b| {
+        a.address
+            .cmp(&b.address)
+            .then_with(|| a.arch.cmp(&b.arch))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    result.call_edges.sort_by_key(|e| (e.from, e.to, e.is_cross_slice));
+    result.evidence.sort_by(|a, b| {
+        a.address
+            .cmp(&b.address)
+            .then_with(|| a.description.cmp(&b.description))
+            .then_with(|| kind_rank(&a.kind).cmp(&kind_rank(&b.kind)))
+    });
+    result.xrefs.sort_by(|a, b| {
+        (a.from, a.to, a.kind.as_str(), &a.section, &a.preview).cmp(&(
+            b.from,
+            b.to,
+            b.kind.as_str(),
+            &b.section,
+            &b.preview,
+        ))
+    });
+    result
+        .basic_blocks
+        .sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.byte_len.cmp(&b.byte_len)));
+    for hit in &mut result.root_hits {
+        hit.functions.sort_unstable();
+    }
+    for sub in &mut result.sub_slices {
+        sub.functions.sort_unstable();
+    }
+}
+
+fn kind_rank(kind: &Option<EvidenceKind>) -> u8 {
+    match kind {
+        None => 0,
+        Some(EvidenceKind::String) => 1,
+        Some(EvidenceKind::Import) => 2,
+        Some(EvidenceKind::Call) => 3,
+        Some(EvidenceKind::Other) => 4,
+    }
+}
+
+/// `path` relative to the project `root` (with `/` separators) when it lies inside the
+/// project, otherwise just its file name, so recorded paths do not depend on where the
+/// project or the tools are installed.
+pub fn normalize_path(path: &str, root: &Path) -> String {
+    let candidate = Path::new(path);
+    if let Ok(rel) = candidate.strip_prefix(root) {
+        let parts: Vec<String> =
+            rel.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+        if !parts.is_empty() {
+            return parts.join("/");
+        }
+    }
+    match candidate.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.to_string(),
+    }
+}
@@ -0,0 +1,247 @@
+//! Markdown helpers for generated slice docs.
+//!
+//! Provides stable anchors, a table-of-contents renderer, and a cross-slice index so
+//! call references and backlinks can link between documented functions (within a doc
+//! or across slice docs in the same directory).
+//!
+//! Generated docs also carry analyst sections: text between `<!-- analyst:begin NAME -->`
+//! and `<!-- analyst:end NAME -->` markers belongs to the analyst. Regenerating a doc keeps
+//! it as written, and `sync-slice-docs` reads it back into the project DB.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::services::analysis::AnalysisResult;
+
+/// GitHub-style heading slug (lowercase, spaces to `-`, punctuation dropped).
+pub fn anchor_slug(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            ' ' => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Stable anchor id for a function, derived from its address (names may be missing or clash).
+pub fn function_anchor(address: u64) -> String {
+    format!("fn-0x{:x}", address)
+}
+
+/// Relative link to another slice doc (docs live side by side under `docs/slices/`).
+pub fn slice_doc_link(slice: &str) -> String {
+    format!("{}.md", slice.replace(' ', "%20"))
+}
+
+/// A single table-of-contents entry (level 0 = section, 1 = nested entry).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocEntry {
+    pub level: usize,
+    pub title: String,
+    pub anchor: String,
+}
+
+impl TocEntry {
+    /// Entry for a `## Heading`, using the slug Markdown renderers generate.
+    pub fn section(title: &str) -> Self {
+        Self { level: 0, title: title.to_string(), anchor: anchor_slug(title) }
+    }
+}
+
+/// Render a `## Contents` block linking to the given entries.
+pub fn render_toc(entries: &[TocEntry]) -> String {
+    let mut out = String::from("## Contents\n");
+    for entry in entries {
+        out.push_str(&format!(
+            "{}- [{}](#{})\n",
+            "  ".repeat(entry.level),
+            entry.title,
+            entry.anchor
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+/// Analyst section holding free-form notes on the slice.
+pub const ANALYST_NOTES: &str = "notes";
+/// Analyst section listing the slice's roots, one per bullet.
+pub const ANALYST_ROOTS: &str = "roots";
+
+/// An analyst section with its markers; `body` is kept as written.
+pub fn render_analyst_section(name: &str, body: &str) -> String {
+    let mut out = format!("<!-- analyst:begin {name} -->\n");
+    let body = body.trim_end_matches('\n');
+    if !body.is_empty() {
+        out.push_str(body);
+        out.push('\n');
+    }
+    out.push_str(&format!("<!-- analyst:end {name} -->\n"));
+    out
+}
+
+/// Bodies of the analyst sections in `markdown`, by name. A section whose end marker is
+/// missing (or names another section) is ignored.
+pub fn analyst_sections(markdown: &str) -> BTreeMap<String, String> {
+    let mut sections = BTreeMap::new();
+    let mut open: Option<(&str, Vec<&str>)> = None;
+    for line in markdown.lines() {
+        if let Some(name) = analyst_marker(line, "begin") {
+            open = Some((name, Vec::new()));
+        } else if let Some(name) = analyst_marker(line, "end") {
+            if let Some((open_name, lines)) = open.take().filter(|(open, _)| *open == name) {
+                sections.insert(open_name.to_string(), lines.join("\n"));
+            }
+        } else if let Some((_, lines)) = open.as_mut() {
+            lines.push(line);
+        }
+    }
+    sections
+}
+
+/// `markdown` with the body of analyst section `name` replaced by `body`, or `None` when
+/// the section is missing (or never closed). Everything outside the section is kept as is.
+pub fn replace_analyst_section(markdown: &str, name: &str, body: &str) -> Option<String> {
+    let lines: Vec<&str> = markdown.split_inclusive('\n').collect();
+    let begin = lines.iter().position(|line| analyst_marker(line, "begin") == Some(name))?;
+    let end = begin
+        + 1
+        + lines[begin + 1..].iter().position(|line| {
+            analyst_marker(line, "begin").is_some() || analyst_marker(line, "end").is_some()
+        })?;
+    if analyst_marker(lines[end], "end") != Some(name) {
+        return None;
+    }
+    let mut out = lines[..begin].concat();
+    out.push_str(&render_analyst_section(name, body));
+    out.push_str(&lines[end + 1..].concat());
+    Some(out)
+}
+
+/// Section name of an `<!-- analyst:{kind} NAME -->` marker line.
+fn analyst_marker<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    let rest = line.trim().strip_prefix("<!-- analyst:")?.strip_prefix(kind)?;
+    let name = rest.strip_prefix(' ')?.strip_suffix("-->")?.trim();
+    (!name.is_empty() && !name.contains(char::is_whitespace)).then_some(name)
+}
+
+/// Roots listed in an analyst roots section: one per `-` or `*` bullet, with surrounding
+/// backticks dropped and duplicates skipped. Other lines are ignored.
+pub fn parse_root_list(body: &str) -> Vec<String> {
+    let mut roots: Vec<String> = Vec::new();
+    for line in body.lines() {
+        let line = line.trim();
+        let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) else {
+            continue;
+        };
+        let root = item.trim().trim_matches('`').trim();
+        if !root.is_empty() && !roots.iter().any(|have| have == root) {
+            roots.push(root.to_string());
+        }
+    }
+    roots
+}
+
+/// Where a documented function lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocTarget {
+    pub slice: String,
+    pub label: String,
+    pub anchor: String,
+}
+
+/// A call into a documented function, recorded for backlinks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocReference {
+    pub slice: String,
+    pub from: u64,
+    pub to: u64,
+}
+
+/// Index of documented functions and call references across slices.
+///
+/// Addresses are keyed per binary so identically-addressed functions in different
+/// binaries never link to each other.
+#[derive(Debug, Default)]
+pub struct DocIndex {
+    targets: HashMap<(String, u64), Vec<DocTarget>>,
+    callers: HashMap<(String, u64), Vec<DocReference>>,
+}
+
+impl DocIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the functions and call edges documented by `slice` (analyzed against `binary`).
+    pub fn add_slice(&mut self, slice: &str, binary: &str, result: &AnalysisResult) {
+        for f in &result.functions {
+            let label = f.name.clone().unwrap_or_else(|| format!("0x{:X}", f.address));
+            self.targets.entry((binary.to_string(), f.address)).or_default().push(DocTarget {
+                slice: slice.to_string(),
+                label,
+                anchor: function_anchor(f.address),
+            });
+        }
+        for edge in &result.call_edges {
+            let refs = self.callers.entry((binary.to_string(), edge.to)).or_default();
+            let reference = DocReference { slice: slice.to_string(), from: edge.from, to: edge.to };
+            if !refs.contains(&reference) {
+                refs.push(reference);
+            }
+        }
+    }
+
+    /// Documented locations for a function, if any.
+    pub fn targets(&self, binary: &str, address: u64) -> &[DocTarget] {
+        self.targets.get(&(binary.to_string(), address)).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Markdown link to a function as seen from `current_slice`'s doc.
+    ///
+    /// Prefers the in-page anchor when the function is documented in the current slice,
+    /// otherwise links into the first other slice that documents it.
+    pub fn function_link(&self, current_slice: &str, binary: &str, address: u64) -> Option<String> {
+        let targets = self.targets(binary, address);
+        if let Some(local) = targets.iter().find(|t| t.slice == current_slice) {
+            return Some(format!("[{}](#{})", local.label, local.anchor));
+        }
+        targets.first().map(|t| {
+            format!("[{}]({}#{}) ({})", t.label, slice_doc_link(&t.slice), t.anchor, t.slice)
+        })
+    }
+
+    /// Markdown link to a function as documented in `slice`'s doc, for use from other docs.
+    pub fn slice_function_link(&self, slice: &str, binary: &str, address: u64) -> Option<String> {
+        self.targets(binary, address)
+            .iter()
+            .find(|t| t.slice == slice)
+            .map(|t| format!("[{}]({}#{})", t.label, slice_doc_link(&t.slice), t.anchor))
+    }
+
+    /// Call references into `address` (from any slice), in insertion order.
+    pub fn callers_of(&self, binary: &str, address: u64) -> &[DocReference] {
+        self.callers.get(&(binary.to_string(), address)).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Call references from other slices into functions documented by `slice`.
+    pub fn backlinks(
+        &self,
+        slice: &str,
+        binary: &str,
+        result: &AnalysisResult,
+    ) -> Vec<DocReference> {
+        let mut out = Vec::new();
+        for f in &result.functions {
+            for reference in self.callers_of(binary, f.address) {
+                if reference.slice != slice && !out.contains(reference) {
+                    out.push(reference.clone());
+                }
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,205 @@
+//! Entry points and exported functions, for the `entrypoint` / `exports` ritual roots.
+//!
+//! A first ritual on an unfamiliar binary rarely knows symbol names, but every container
+//! declares where execution starts and what it exports. Backends report those addresses
+//! through [`AnalysisBackend::entry_points`] (by default read from the ELF, PE, or thin Mach-O
+//! headers with [`header_entry_points`]), and [`resolve_auto_roots`] turns the `auto:` roots
+//! into the functions found at them once the backend has run.
+
+use std::collections::HashSet;
+
+use crate::services::analysis::{
+    AnalysisBackend, AnalysisError, AnalysisRequest, AnalysisResult, RootHit,
+};
+use crate::services::image::BinaryImage;
+use crate::services::roots::AutoRoot;
+
+/// Why an address is an entry point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// Where execution starts.
+    Entry,
+    /// An exported function.
+    Export,
+}
+
+impl EntryKind {
+    fn root(self) -> AutoRoot {
+        match self {
+            EntryKind::Entry => AutoRoot::Entry,
+            EntryKind::Export => AutoRoot::Exports,
+        }
+    }
+}
+
+/// An address the binary declares as callable from outside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub kind: EntryKind,
+    /// Address in the backend's address space (virtual address; body offset for wasm).
+    pub address: u64,
+    /// Export name, when the binary gives one.
+    pub name: Option<String>,
+}
+
+/// Whether any of `roots` needs [`AnalysisBackend::entry_points`].
+pub fn has_auto_roots(roots: &[String]) -> bool {
+    roots.iter().any(|root| AutoRoot::from_canonical(root).is_some())
+}
+
+/// Entry points of the binary a request analyzes, read from its headers. Raw images have
+/// none (their entry is whatever the analyst gives as an address root).
+pub fn request_entry_points(request: &AnalysisRequest) -> Result<Vec<EntryPoint>, AnalysisError> {
+    if request.raw.is_some() {
+        return Ok(Vec::new());
+    }
+    let image = BinaryImage::open(&request.binary_path)?;
+    Ok(header_entry_points(image.bytes()))
+}
+
+/// Entry point and exported functions of an ELF, PE, or thin Mach-O image; empty for
+/// anything else. Thumb bits are cleared on 32-bit ARM.
+pub fn header_entry_points(bytes: &[u8]) -> Vec<EntryPoint> {
+    #[cfg(feature = "capstone-backend")]
+    {
+        parse::entry_points(bytes)
+    }
+    #[cfg(not(feature = "capstone-backend"))]
+    {
+        let _ = bytes;
+        Vec::new()
+    }
+}
+
+/// Point every `auto:entrypoint` / `auto:exports` root of `roots` at the functions of `result`
+/// starting at matching entry points, and put those functions in the slice. Roots that find
+/// nothing keep an error in their `root_hits` entry.
+pub fn resolve_auto_roots(
+    roots: &[String],
+    result: &mut AnalysisResult,
+    entry_points: &[EntryPoint],
+) {
+    let known: HashSet<u64> = result.functions.iter().map(|f| f.address).collect();
+    let mut seeded = HashSet::new();
+    for root in roots {
+        let Some(auto) = AutoRoot::from_canonical(root) else {
+            continue;
+        };
+        let mut functions: Vec<u64> = entry_points
+            .iter()
+            .filter(|point| point.kind.root() == auto && known.contains(&point.address))
+            .map(|point| point.address)
+            .collect();
+        functions.sort_unstable();
+        functions.dedup();
+        let error = functions.is_empty().then(|| match auto {
+            AutoRoot::Entry => "binary declares no entry point at a known function".to_string(),
+            AutoRoot::Exports => "binary exports no known function".to_string(),
+        });
+        seeded.extend(functions.iter().copied());
+        let hit = RootHit { root: root.clone(), functions, error };
+        match result.root_hits.iter_mut().find(|existing| existing.root == *root) {
+            Some(existing) => *existing = hit,
+            None => result.root_hits.push(hit),
+        }
+    }
+    for function in &mut result.functions {
+        function.in_slice |= seeded.contains(&function.address);
+    }
+}
+
+/// [`request_entry_points`] then [`resolve_auto_roots`], when `request` has `auto:` roots.
+pub(crate) fn apply_auto_roots(
+    backend: &dyn AnalysisBackend,
+    request: &AnalysisRequest,
+    result: &mut AnalysisResult,
+) -> Result<(), AnalysisError> {
+    if !has_auto_roots(&request.roots) {
+        return Ok(());
+    }
+    let entry_points = backend.entry_points(request)?;
+    tracing::debug!(entry_points = entry_points.len(), "resolving automatic roots");
+    resolve_auto_roots(&request.roots, result, &entry_points);
+    Ok(())
+}
+
+#[cfg(feature = "capstone-backend")]
+mod parse {
+    use goblin::{elf, mach, pe, Object};
+
+    use super::{EntryKind, EntryPoint};
+
+    pub fn entry_points(bytes: &[u8]) -> Vec<EntryPoint> {
+        let mut points = match Object::parse(bytes) {
+            Ok(Object::Elf(elf)) => elf_entry_points(&elf),
+            Ok(Object::PE(pe)) => pe_entry_points(&pe),
+            Ok(Object::Mach(mach::Mach::Binary(bin))) => mach_entry_points(&bin),
+            _ => Vec::new(),
+        };
+        points.sort_by_key(|p| (p.address, p.kind == EntryKind::Export));
+        points.dedup_by(|a, b| a.address == b.address && a.kind == b.kind);
+        points
+    }
+
+    fn point(kind: EntryKind, address: u64, name: Option<&str>, thumb: bool) -> EntryPoint {
+        EntryPoint {
+            kind,
+            address: if thumb { address & !1 } else { address },
+            name: name.filter(|n| !n.is_empty()).map(str::to_string),
+        }
+    }
+
+    fn elf_entry_points(elf: &elf::Elf) -> Vec<EntryPoint> {
+        let thumb = elf.header.e_machine == elf::header::EM_ARM;
+        let mut points = Vec::new();
+        if elf.entry != 0 {
+            points.push(point(EntryKind::Entry, elf.entry, None, thumb));
+        }
+        for sym in elf.dynsyms.iter() {
+            let exported = sym.st_type() == elf::sym::STT_FUNC
+                && sym.st_bind() != elf::sym::STB_LOCAL
+                && sym.st_shndx != elf::section_header::SHN_UNDEF as usize
+                && sym.st_visibility() != elf::sym::STV_HIDDEN
+                && sym.st_value != 0;
+            if exported {
+                let name = elf.dynstrtab.get_at(sym.st_name);
+                points.push(point(EntryKind::Export, sym.st_value, name, thumb));
+            }
+        }
+        points
+    }
+
+    fn pe_entry_points(pe: &pe::PE) -> Vec<EntryPoint> {
+        let thumb = pe.header.coff_header.machine == pe::header::COFF_MACHINE_ARMNT;
+        let image_base = pe.image_base as u64;
+        let mut points = Vec::new();
+        if pe.entry != 0 {
+            points.push(point(EntryKind::Entry, image_base + pe.entry as u64, None, thumb));
+        }
+        // Forwarders name another DLL's function and have no code here.
+        for export in pe.exports.iter().filter(|e| e.reexport.is_none() && e.rva != 0) {
+            points.push(point(
+                EntryKind::Export,
+                image_base + export.rva as u64,
+                export.name,
+                thumb,
+            ));
+        }
+        points
+    }
+
+    fn mach_entry_points(bin: &mach::MachO) -> Vec<EntryPoint> {
+        let mut points = Vec::new();
+        if bin.entry != 0 {
+            points.push(point(EntryKind::Entry, bin.entry, None, false));
+        }
+        for (name, nlist) in bin.symbols().flatten() {
+            if nlist.is_stab() || !nlist.is_global() || nlist.is_undefined() || nlist.n_sect == 0 {
+                continue;
+            }
+            let name = name.strip_prefix('_').unwrap_or(name);
+            points.push(point(EntryKind::Export, nlist.n_value, Some(name), false));
+        }
+        points
+    }
+}
@@ -0,0 +1,307 @@
+//! Portable ritual-run archives (`export-run` / `import-run`).
+//!
+//! An archive is a zstd-compressed tar with:
+//! - `manifest.json`: format/schema version stamps, the run record, and the binary record.
+//! - `analysis.json`: the persisted `AnalysisResult` (functions, edges, blocks, evidence, xrefs).
+//! - `annotations.json`: function annotations for the run's binary hash.
+//! - `notes.json`: notes attached to the run with `annotate-run`.
+//! - `outputs/<file>`: the run's output directory (normalized spec, report, graph, metadata).
+//!
+//! Archives record the DB schema version they were exported from; importing into an older
+//! tool that does not know that schema is rejected rather than silently dropping data.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::db::project_db::CURRENT_SCHEMA_VERSION;
+use crate::db::{
+    BinaryRecord, DbError, FunctionAnnotation, ProjectDb, ProjectLayout, RitualRunRecord, RunNote,
+};
+use crate::services::analysis::AnalysisResult;
+
+/// Version of the archive layout itself (bumped on incompatible layout changes).
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const ANALYSIS_ENTRY: &str = "analysis.json";
+const ANNOTATIONS_ENTRY: &str = "annotations.json";
+const NOTES_ENTRY: &str = "notes.json";
+const OUTPUTS_PREFIX: &str = "outputs/";
+
+#[derive(Debug, Error)]
+pub enum ExchangeError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Db(#[from] DbError),
+    #[error("No ritual run found for binary '{binary}' / ritual '{ritual}'")]
+    MissingRun { binary: String, ritual: String },
+    #[error("Archive is missing {0}")]
+    MissingEntry(&'static str),
+    #[error("Unsupported archive format version {found} (supported: {supported})")]
+    UnsupportedFormat { found: u32, supported: u32 },
+    #[error("Archive was exported with schema v{found}; this build supports up to v{max}")]
+    NewerSchema { found: i32, max: i32 },
+    #[error("Invalid archive entry name: {0}")]
+    InvalidEntry(String),
+    #[error("Run output already exists at {0} (use --force to overwrite)")]
+    OutputExists(String),
+}
+
+pub type ExchangeResult<T> = Result<T, ExchangeError>;
+
+/// Version stamps and identity of an exported run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub format_version: u32,
+    pub schema_version: i32,
+    pub tool_version: String,
+    pub exported_at: String,
+    pub run: RitualRunRecord,
+    /// Binary record the run resolved to, when it is still registered.
+    #[serde(default)]
+    pub binary: Option<BinaryRecord>,
+    /// Output file names carried under `outputs/`.
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// In-memory contents of a run archive.
+#[derive(Debug, Clone)]
+pub struct RunArchive {
+    pub manifest: RunManifest,
+    pub analysis: AnalysisResult,
+    pub annotations: Vec<FunctionAnnotation>,
+    pub notes: Vec<RunNote>,
+    /// Output files keyed by plain file name.
+    pub outputs: BTreeMap<String, Vec<u8>>,
+}
+
+/// Result of importing an archive into a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedRun {
+    pub run_id: i64,
+    pub binary: String,
+    pub ritual: String,
+    /// True when no matching binary was registered and the archived record was added.
+    pub registered_binary: bool,
+}
+
+/// Collect the latest run for `binary`/`ritual` (DB rows plus output files) into an archive.
+pub fn export_run(
+    db: &ProjectDb,
+    layout: &ProjectLayout,
+    binary: &str,
+    ritual: &str,
+) -> ExchangeResult<RunArchive> {
+    let missing = || ExchangeError::MissingRun { binary: binary.into(), ritual: ritual.into() };
+    let run = db.list_ritual_runs(Some(binary))?.into_iter().rev().find(|r| r.ritual == ritual);
+    let run = run.ok_or_else(missing)?;
+    let mut analysis = db.load_analysis_result(binary, ritual)?.ok_or_else(missing)?;
+    // Run ids mean nothing in the importing project.
+    for evidence in &mut analysis.evidence {
+        evidence.run_id = None;
+    }
+
+    let binary_record = db
+        .list_binaries_with_ids()?
+        .into_iter()
+        .find(|(id, b)| run.binary_id.map_or(b.name == run.binary, |rid| rid == *id))
+        .map(|(_, b)| b);
+    let hash =
+        run.binary_hash.as_deref().or(binary_record.as_ref().and_then(|b| b.hash.as_deref()));
+    let annotations = match hash {
+        Some(hash) => db.list_function_annotations(hash)?,
+        None => Vec::new(),
+    };
+    let notes = match db.latest_run_id(binary, ritual)? {
+        Some(run_id) => db.list_run_notes(run_id)?,
+        None => Vec::new(),
+    };
+
+    let mut outputs = BTreeMap::new();
+    let run_dir = layout.binary_output_root(binary).join(ritual);
+    if run_dir.is_dir() {
+        for entry in fs::read_dir(&run_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                outputs.insert(name, fs::read(entry.path())?);
+            }
+        }
+    }
+
+    let manifest = RunManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        schema_version: CURRENT_SCHEMA_VERSION,
+        tool_version: crate::version().to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+        run,
+        binary: binary_record,
+        files: outputs.keys().cloned().collect(),
+    };
+    Ok(RunArchive { manifest, analysis, annotations, notes, outputs })
+}
+
+/// Serialize an archive as tar + zstd.
+pub fn write_archive<W: Write>(archive: &RunArchive, out: W) -> ExchangeResult<()> {
+    let encoder = zstd::Encoder::new(out, 0)?;
+    let mut builder = tar::Builder::new(encoder);
+    let mut append = |path: &str, data: &[u8]| -> ExchangeResult<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, data)?;
+        Ok(())
+    };
+    append(MANIFEST_ENTRY, &serde_json::to_vec_pretty(&archive.manifest)?)?;
+    append(ANALYSIS_ENTRY, &serde_json::to_vec(&archive.analysis)?)?;
+    append(ANNOTATIONS_ENTRY, &serde_json::to_vec_pretty(&archive.annotations)?)?;
+    append(NOTES_ENTRY, &serde_json::to_vec_pretty(&archive.notes)?)?;
+    for (name, data) in &archive.outputs {
+        append(&format!("{OUTPUTS_PREFIX}{}", checked_file_name(name)?), data)?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Parse an archive, validating its format and schema stamps.
+pub fn read_archive<R: Read>(input: R) -> ExchangeResult<RunArchive> {
+    let mut tar = tar::Archive::new(zstd::Decoder::new(input)?);
+    let mut manifest = None;
+    let mut analysis = None;
+    let mut annotations = Vec::new();
+    let mut notes = Vec::new();
+    let mut outputs = BTreeMap::new();
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        match path.as_str() {
+            MANIFEST_ENTRY => manifest = Some(serde_json::from_slice::<RunManifest>(&data)?),
+            ANALYSIS_ENTRY => analysis = Some(serde_json::from_slice(&data)?),
+            ANNOTATIONS_ENTRY => annotations = serde_json::from_slice(&data)?,
+            NOTES_ENTRY => notes = serde_json::from_slice(&data)?,
+            other => match other.strip_prefix(OUTPUTS_PREFIX) {
+                Some(name) => {
+                    outputs.insert(checked_file_name(name)?.to_string(), data);
+                }
+                None => return Err(ExchangeError::InvalidEntry(other.to_string())),
+            },
+        }
+    }
+
+    let manifest = manifest.ok_or(ExchangeError::MissingEntry(MANIFEST_ENTRY))?;
+    if manifest.format_version != ARCHIVE_FORMAT_VERSION {
+        return Err(ExchangeError::UnsupportedFormat {
+            found: manifest.format_version,
+            supported: ARCHIVE_FORMAT_VERSION,
+        });
+    }
+    if manifest.schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(ExchangeError::NewerSchema {
+            found: manifest.schema_version,
+            max: CURRENT_SCHEMA_VERSION,
+        });
+    }
+    let analysis = analysis.ok_or(ExchangeError::MissingEntry(ANALYSIS_ENTRY))?;
+    Ok(RunArchive { manifest, analysis, annotations, notes, outputs })
+}
+
+/// Import an archive as a new run in the project.
+///
+/// The binary is matched by hash, then by name; when neither is registered the archived
+/// binary record is added (its file may not exist on this machine). Existing outputs for the
+/// same binary/ritual are only replaced when `force` is set.
+pub fn import_run(
+    db: &ProjectDb,
+    layout: &ProjectLayout,
+    archive: &RunArchive,
+    force: bool,
+) -> ExchangeResult<ImportedRun> {
+    let run = &archive.manifest.run;
+    let hash = run
+        .binary_hash
+        .clone()
+        .or_else(|| archive.manifest.binary.as_ref().and_then(|b| b.hash.clone()));
+    let binaries = db.list_binaries_with_ids()?;
+    let existing = binaries
+        .iter()
+        .find(|(_, b)| hash.is_some() && b.hash == hash)
+        .or_else(|| binaries.iter().find(|(_, b)| b.name == run.binary));
+
+    let (binary_id, registered_binary) = match existing {
+        Some((id, _)) => (*id, false),
+        None => {
+            let record = archive.manifest.binary.clone().unwrap_or_else(|| BinaryRecord {
+                name: run.binary.clone(),
+                path: run.binary.clone(),
+                arch: None,
+                hash: hash.clone(),
+            });
+            (db.insert_binary(&record)?, true)
+        }
+    };
+    // The exporting project may have recorded the run under another name; record it under
+    // the name runs of the binary use here.
+    let binary_name = db.binary_run_name(binary_id)?.unwrap_or_else(|| run.binary.clone());
+
+    let run_dir = layout.binary_output_root(&binary_name).join(&run.ritual);
+    if run_dir.exists() && !archive.outputs.is_empty() {
+        if !force {
+            if registered_binary {
+                db.delete_binary(binary_id)?;
+            }
+            return Err(ExchangeError::OutputExists(run_dir.display().to_string()));
+        }
+        fs::remove_dir_all(&run_dir)?;
+    }
+
+    let record = RitualRunRecord {
+        binary: binary_name.clone(),
+        binary_id: Some(binary_id),
+        superseded_by: None,
+        ..run.clone()
+    };
+    let run_id = db.insert_ritual_run(&record)?;
+    db.insert_analysis_result(run_id, &archive.analysis)?;
+    db.record_slice_snapshots(run_id)?;
+    for annotation in &archive.annotations {
+        db.upsert_function_annotation(annotation)?;
+    }
+    for note in &archive.notes {
+        db.add_run_note(run_id, &note.note, &note.created_at)?;
+    }
+
+    if !archive.outputs.is_empty() {
+        fs::create_dir_all(&run_dir)?;
+        for (name, data) in &archive.outputs {
+            fs::write(run_dir.join(checked_file_name(name)?), data)?;
+        }
+    }
+
+    Ok(ImportedRun { run_id, binary: binary_name, ritual: run.ritual.clone(), registered_binary })
+}
+
+/// Output entries must be plain file names so an archive cannot write outside the run dir.
+fn checked_file_name(name: &str) -> ExchangeResult<&str> {
+    let plain = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !name.contains(':');
+    if plain {
+        Ok(name)
+    } else {
+        Err(ExchangeError::InvalidEntry(name.to_string()))
+    }
+}
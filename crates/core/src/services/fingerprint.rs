@@ -0,0 +1,65 @@
+//! Fuzzy function fingerprints for recognising the same function across builds.
+//!
+//! A fingerprint is a 64-bit SimHash over overlapping windows of three instruction
+//! mnemonics. Operands are ignored, so relocated code (different call targets, shifted
+//! addresses, renumbered stack slots) hashes the same, while a small edit flips only a few
+//! bits. [`hash_similarity`] compares two fingerprints by their Hamming distance.
+
+/// Mnemonics per shingle.
+const SHINGLE: usize = 3;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(words: &[String]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            hash ^= u64::from(b' ');
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        for byte in word.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+/// Fingerprint of a function body given its instruction mnemonics in address order, as 16
+/// lowercase hex digits; `None` for an empty body.
+pub fn mnemonic_hash<S: AsRef<str>>(mnemonics: &[S]) -> Option<String> {
+    let words: Vec<String> = mnemonics
+        .iter()
+        .map(|m| m.as_ref().trim().to_ascii_lowercase())
+        .filter(|m| !m.is_empty())
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    let mut weights = [0i64; 64];
+    for shingle in words.windows(SHINGLE.min(words.len())) {
+        let hash = fnv1a(shingle);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash >> bit & 1 == 1 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+    let simhash = weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0u64, |acc, (bit, _)| acc | 1 << bit);
+    Some(format!("{:016x}", simhash))
+}
+
+/// Similarity of two fingerprints from 0.0 (every bit differs) to 1.0 (identical); `None`
+/// when either is not a fingerprint produced by [`mnemonic_hash`].
+pub fn hash_similarity(a: &str, b: &str) -> Option<f64> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+    Some(1.0 - f64::from((a ^ b).count_ones()) / 64.0)
+}
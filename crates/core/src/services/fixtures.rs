@@ -0,0 +1,887 @@
+//! Synthetic binaries for end-to-end smoke checks (`binary-slicer self-test`).
+//!
+//! The images are assembled by hand so they need no toolchain or extra dependencies: a
+//! minimal ELF64 with a `.text` section, a symbol table, and two functions where `main`
+//! calls `helper` (plus variants: stripped, x86_64 and arm64 ones with `.rodata` strings, a
+//! dynamically linked one calling versioned imports through its PLT, and a fat Mach-O carrying
+//! x86_64 and arm64 slices).
+
+use super::analysis::AnalysisResult;
+
+/// Function planted in a synthetic binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticFunction {
+    pub name: &'static str,
+    pub address: u64,
+    pub size: u32,
+}
+
+/// In-memory synthetic binary plus the ground truth a backend should recover from it.
+#[derive(Debug, Clone)]
+pub struct SyntheticBinary {
+    /// Suggested file name when writing the image to disk.
+    pub file_name: &'static str,
+    pub arch: &'static str,
+    pub bytes: Vec<u8>,
+    pub functions: Vec<SyntheticFunction>,
+    /// Direct calls as (caller entry, callee entry).
+    pub calls: Vec<(u64, u64)>,
+}
+
+impl SyntheticBinary {
+    /// Function names, usable as ritual roots.
+    pub fn roots(&self) -> Vec<String> {
+        self.functions.iter().map(|f| f.name.to_string()).collect()
+    }
+
+    /// The planted functions and calls as a Ghidra XML export, as the `ghidra-xml` backend
+    /// reads it.
+    pub fn ghidra_xml(&self) -> String {
+        let mut xml = format!("<PROGRAM NAME=\"{}\">\n  <MARKUP>\n", self.file_name);
+        for (caller, callee) in &self.calls {
+            xml.push_str(&format!(
+                "    <MEMORY_REFERENCE ADDRESS=\"{caller:08x}\" TO_ADDRESS=\"{callee:08x}\" />\n"
+            ));
+        }
+        xml.push_str("  </MARKUP>\n  <FUNCTIONS>\n");
+        for f in &self.functions {
+            xml.push_str(&format!(
+                "    <FUNCTION ENTRY_POINT=\"{:08x}\" NAME=\"{}\">\n      <ADDRESS_RANGE START=\"{:08x}\" END=\"{:08x}\" />\n    </FUNCTION>\n",
+                f.address,
+                f.name,
+                f.address,
+                f.address + u64::from(f.size) - 1
+            ));
+        }
+        xml.push_str("  </FUNCTIONS>\n</PROGRAM>\n");
+        xml
+    }
+
+    fn function_containing(&self, addr: u64) -> Option<&SyntheticFunction> {
+        self.functions.iter().find(|f| addr >= f.address && addr < f.address + f.size as u64)
+    }
+}
+
+const TEXT_ADDR: u64 = 0x401000;
+const RODATA_ADDR: u64 = 0x402000;
+const HELPER_OFFSET: u64 = 0x10;
+
+/// Build a tiny x86_64 ELF: `main` (call helper; ret) and `helper` (xor eax, eax; ret).
+pub fn synthetic_elf_x86_64() -> SyntheticBinary {
+    let helper_addr = TEXT_ADDR + HELPER_OFFSET;
+    // call rel32 is relative to the next instruction (main + 5).
+    let rel = (HELPER_OFFSET as i32 - 5).to_le_bytes();
+    let mut text = vec![0xE8, rel[0], rel[1], rel[2], rel[3], 0xC3];
+    text.resize(HELPER_OFFSET as usize, 0x90);
+    text.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+
+    let functions = vec![
+        SyntheticFunction { name: "main", address: TEXT_ADDR, size: 6 },
+        SyntheticFunction { name: "helper", address: helper_addr, size: 3 },
+    ];
+    let bytes = build_elf64(&text, &functions);
+    SyntheticBinary {
+        file_name: "self-test-x86_64.elf",
+        arch: "x86_64",
+        bytes,
+        functions,
+        calls: vec![(TEXT_ADDR, helper_addr)],
+    }
+}
+
+/// Build a stripped x86_64 ELF (entry point only, no function symbols) with a call chain
+/// `entry -> helper -> leaf` and a conditional branch in `entry`.
+///
+/// Functions are named the way backends synthesize them (`sub_<ADDR>`), so a backend doing
+/// recursive descent from the entry point passes [`check_invariants`] unchanged.
+pub fn synthetic_elf_x86_64_stripped() -> SyntheticBinary {
+    const HELPER: u64 = 0x20;
+    const LEAF: u64 = 0x30;
+    let rel = |from_next: u64, to: u64| ((to as i64 - from_next as i64) as i32).to_le_bytes();
+    let mut text = Vec::new();
+    text.push(0xE8); // call helper
+    text.extend_from_slice(&rel(5, HELPER));
+    text.extend_from_slice(&[0x85, 0xC0]); // test eax, eax
+    text.extend_from_slice(&[0x74, 0x02]); // je ret
+    text.extend_from_slice(&[0x31, 0xC0]); // xor eax, eax
+    text.push(0xC3); // ret
+    text.resize(HELPER as usize, 0xCC);
+    text.push(0xE8); // call leaf
+    text.extend_from_slice(&rel(HELPER + 5, LEAF));
+    text.push(0xC3);
+    text.resize(LEAF as usize, 0xCC);
+    text.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+
+    let functions = vec![
+        SyntheticFunction { name: "sub_401000", address: TEXT_ADDR, size: 0xC },
+        SyntheticFunction { name: "sub_401020", address: TEXT_ADDR + HELPER, size: 6 },
+        SyntheticFunction { name: "sub_401030", address: TEXT_ADDR + LEAF, size: 3 },
+    ];
+    let bytes = build_elf64(&text, &[]);
+    SyntheticBinary {
+        file_name: "self-test-x86_64-stripped.elf",
+        arch: "x86_64",
+        bytes,
+        calls: vec![
+            (functions[0].address, functions[1].address),
+            (functions[1].address, functions[2].address),
+        ],
+        functions,
+    }
+}
+
+/// Strings planted by [`synthetic_elf_x86_64_strings`], as (address, text).
+pub const SYNTHETIC_STRINGS: [(u64, &str); 2] =
+    [(RODATA_ADDR, "AutoUpdate: checking for patches"), (RODATA_ADDR + 33, "Network ready")];
+
+/// Build an x86_64 ELF whose code references `.rodata` strings ([`SYNTHETIC_STRINGS`]):
+/// `main` loads the AutoUpdate string RIP-relatively and calls `helper`, `helper` passes the
+/// Network string as an immediate, and `updater` loads an address inside the AutoUpdate string.
+pub fn synthetic_elf_x86_64_strings() -> SyntheticBinary {
+    const UPDATER: u64 = 0x20;
+    let [(auto_update, _), (network, _)] = SYNTHETIC_STRINGS;
+    let rip_disp = |next: u64, to: u64| (to as i64 - (TEXT_ADDR + next) as i64) as i32;
+    let mut text = vec![0x48, 0x8D, 0x3D]; // lea rdi, [rip + disp32]
+    text.extend_from_slice(&rip_disp(7, auto_update).to_le_bytes());
+    text.push(0xE8); // call helper
+    text.extend_from_slice(&(HELPER_OFFSET as i32 - 12).to_le_bytes());
+    text.push(0xC3);
+    text.resize(HELPER_OFFSET as usize, 0x90);
+    text.push(0xBF); // mov edi, imm32
+    text.extend_from_slice(&(network as u32).to_le_bytes());
+    text.push(0xC3);
+    text.resize(UPDATER as usize, 0x90);
+    text.extend_from_slice(&[0x48, 0x8D, 0x35]); // lea rsi, [rip + disp32]
+    text.extend_from_slice(&rip_disp(UPDATER + 7, auto_update + 12).to_le_bytes());
+    text.push(0xC3);
+
+    let mut rodata = Vec::new();
+    for (_, value) in SYNTHETIC_STRINGS {
+        rodata.extend_from_slice(value.as_bytes());
+        rodata.push(0);
+    }
+    let functions = vec![
+        SyntheticFunction { name: "main", address: TEXT_ADDR, size: 13 },
+        SyntheticFunction { name: "helper", address: TEXT_ADDR + HELPER_OFFSET, size: 6 },
+        SyntheticFunction { name: "updater", address: TEXT_ADDR + UPDATER, size: 8 },
+    ];
+    let bytes = build_elf64_with_rodata(EM_X86_64, &text, &rodata, &functions);
+    SyntheticBinary {
+        file_name: "self-test-x86_64-strings.elf",
+        arch: "x86_64",
+        bytes,
+        functions,
+        calls: vec![(TEXT_ADDR, TEXT_ADDR + HELPER_OFFSET)],
+    }
+}
+
+/// Build an arm64 ELF whose position-independent `main` reaches the [`SYNTHETIC_STRINGS`]
+/// through `adrp` page loads: `adrp x0` + `add x0, x0, #33` addresses the Network string and
+/// `adrp x1` + `ldrb w2, [x1, #12]` reads a byte inside the AutoUpdate string.
+pub fn synthetic_elf_arm64_strings() -> SyntheticBinary {
+    let [(auto_update, _), (network, _)] = SYNTHETIC_STRINGS;
+    let page = |reg: u32| {
+        let pages = ((RODATA_ADDR >> 12) - (TEXT_ADDR >> 12)) as u32;
+        0x9000_0000 | ((pages & 3) << 29) | ((pages >> 2) << 5) | reg // adrp
+    };
+    let add_x0 = 0x9100_0000 | (((network - RODATA_ADDR) as u32) << 10);
+    let ldrb_w2 = 0x3940_0000 | (((auto_update + 12 - RODATA_ADDR) as u32) << 10) | (1 << 5) | 2;
+    let words = [page(0), add_x0, page(1), ldrb_w2, 0xD65F_03C0];
+    let text: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+
+    let mut rodata = Vec::new();
+    for (_, value) in SYNTHETIC_STRINGS {
+        rodata.extend_from_slice(value.as_bytes());
+        rodata.push(0);
+    }
+    let functions = vec![SyntheticFunction { name: "main", address: TEXT_ADDR, size: 20 }];
+    let bytes = build_elf64_with_rodata(EM_AARCH64, &text, &rodata, &functions);
+    SyntheticBinary {
+        file_name: "self-test-arm64-strings.elf",
+        arch: "arm64",
+        bytes,
+        functions,
+        calls: Vec::new(),
+    }
+}
+
+/// Imports of [`synthetic_elf_x86_64_plt`], as (PLT stub address, versioned name, library).
+pub const SYNTHETIC_PLT_IMPORTS: [(u64, &str, &str); 2] = [
+    (PLT_ADDR + 0x10, "puts@GLIBC_2.2.5", "libc.so.6"),
+    (PLT_ADDR + 0x20, "memcpy@GLIBC_2.14", "libc.so.6"),
+];
+
+const PLT_ADDR: u64 = TEXT_ADDR + 0x100;
+
+/// Build a dynamically linked x86_64 ELF whose `main` calls `puts` and `memcpy` through lazy
+/// PLT stubs ([`SYNTHETIC_PLT_IMPORTS`]), with `.rela.plt` naming the GOT slots and
+/// `.gnu.version` / `.gnu.version_r` versioning them against `libc.so.6`.
+pub fn synthetic_elf_x86_64_plt() -> SyntheticBinary {
+    let [(puts, _, _), (memcpy, _, _)] = SYNTHETIC_PLT_IMPORTS;
+    let rel = |next: u64, to: u64| ((to as i64 - (TEXT_ADDR + next) as i64) as i32).to_le_bytes();
+    let mut text = vec![0xE8]; // call puts@plt
+    text.extend_from_slice(&rel(5, puts));
+    text.push(0xE8); // call memcpy@plt
+    text.extend_from_slice(&rel(10, memcpy));
+    text.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+
+    let functions = vec![SyntheticFunction { name: "main", address: TEXT_ADDR, size: 13 }];
+    let bytes = build_elf64_dynamic(&text, &functions, &[("puts", 2), ("memcpy", 3)]);
+    SyntheticBinary {
+        file_name: "self-test-x86_64-plt.elf",
+        arch: "x86_64",
+        bytes,
+        functions,
+        calls: Vec::new(),
+    }
+}
+
+/// `__text` addresses of the fat Mach-O slices; they differ so merged results stay
+/// unambiguous.
+const MACHO_X86_64_TEXT: u64 = 0x1_0000_1000;
+const MACHO_ARM64_TEXT: u64 = 0x1_0000_2000;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000C;
+
+/// Build a fat (universal) Mach-O with an x86_64 and an arm64 slice, each holding `main`
+/// (calls `helper`) and `helper`.
+pub fn synthetic_macho_fat() -> SyntheticBinary {
+    let x86_helper = MACHO_X86_64_TEXT + HELPER_OFFSET;
+    let rel = (HELPER_OFFSET as i32 - 5).to_le_bytes();
+    let mut x86_text = vec![0xE8, rel[0], rel[1], rel[2], rel[3], 0xC3];
+    x86_text.resize(HELPER_OFFSET as usize, 0x90);
+    x86_text.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+
+    // bl helper; ret; nop; nop; mov w0, #0; ret
+    let arm_helper = MACHO_ARM64_TEXT + HELPER_OFFSET;
+    let bl = 0x9400_0000u32 | (HELPER_OFFSET as u32 / 4);
+    let mut arm_text = Vec::new();
+    for word in [bl, 0xD65F_03C0, 0xD503_201F, 0xD503_201F, 0x5280_0000, 0xD65F_03C0] {
+        arm_text.extend_from_slice(&word.to_le_bytes());
+    }
+
+    let x86_functions = [
+        SyntheticFunction { name: "main", address: MACHO_X86_64_TEXT, size: 6 },
+        SyntheticFunction { name: "helper", address: x86_helper, size: 3 },
+    ];
+    let arm_functions = [
+        SyntheticFunction { name: "main", address: MACHO_ARM64_TEXT, size: 8 },
+        SyntheticFunction { name: "helper", address: arm_helper, size: 8 },
+    ];
+    let slices = [
+        (
+            CPU_TYPE_X86_64,
+            3,
+            build_macho64(CPU_TYPE_X86_64, 3, &x86_text, MACHO_X86_64_TEXT, &x86_functions),
+        ),
+        (
+            CPU_TYPE_ARM64,
+            0,
+            build_macho64(CPU_TYPE_ARM64, 0, &arm_text, MACHO_ARM64_TEXT, &arm_functions),
+        ),
+    ];
+
+    // fat_header + fat_arch entries are big-endian; slices start on 4 KiB boundaries.
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&0xCAFE_BABEu32.to_be_bytes());
+    bytes.extend_from_slice(&(slices.len() as u32).to_be_bytes());
+    let mut offset = 0x1000u32;
+    let mut layout = Vec::new();
+    for (cputype, cpusubtype, image) in &slices {
+        for field in [*cputype, *cpusubtype, offset, image.len() as u32, 12] {
+            bytes.extend_from_slice(&field.to_be_bytes());
+        }
+        layout.push(offset);
+        offset += (image.len() as u32).next_multiple_of(0x1000);
+    }
+    for ((_, _, image), offset) in slices.iter().zip(layout) {
+        bytes.resize(offset as usize, 0);
+        bytes.extend_from_slice(image);
+    }
+
+    SyntheticBinary {
+        file_name: "self-test-universal.macho",
+        arch: "universal",
+        bytes,
+        functions: x86_functions.into_iter().chain(arm_functions).collect(),
+        calls: vec![(MACHO_X86_64_TEXT, x86_helper), (MACHO_ARM64_TEXT, arm_helper)],
+    }
+}
+
+/// Thin 64-bit Mach-O executable with one `__TEXT,__text` section and a symbol table.
+fn build_macho64(
+    cputype: u32,
+    cpusubtype: u32,
+    text: &[u8],
+    text_addr: u64,
+    functions: &[SyntheticFunction],
+) -> Vec<u8> {
+    const HEADER_SIZE: usize = 32;
+    const SEGMENT_SIZE: usize = 72 + 80;
+    const SYMTAB_SIZE: usize = 24;
+
+    let mut strtab = vec![0u8];
+    let mut symtab = Vec::new();
+    for func in functions {
+        symtab.extend_from_slice(&(strtab.len() as u32).to_le_bytes());
+        strtab.push(b'_');
+        strtab.extend_from_slice(func.name.as_bytes());
+        strtab.push(0);
+        symtab.push(0x0F); // N_SECT | N_EXT
+        symtab.push(1); // __text
+        symtab.extend_from_slice(&0u16.to_le_bytes());
+        symtab.extend_from_slice(&func.address.to_le_bytes());
+    }
+
+    let mut out = vec![0u8; HEADER_SIZE + SEGMENT_SIZE + SYMTAB_SIZE];
+    let text_off = place(&mut out, text, 16);
+    let symtab_off = place(&mut out, &symtab, 8);
+    let strtab_off = place(&mut out, &strtab, 1);
+
+    let name16 = |name: &str| {
+        let mut field = [0u8; 16];
+        field[..name.len()].copy_from_slice(name.as_bytes());
+        field
+    };
+    let mut cmds = Vec::new();
+    cmds.extend_from_slice(&0x19u32.to_le_bytes()); // LC_SEGMENT_64
+    cmds.extend_from_slice(&(SEGMENT_SIZE as u32).to_le_bytes());
+    cmds.extend_from_slice(&name16("__TEXT"));
+    cmds.extend_from_slice(&text_addr.to_le_bytes());
+    cmds.extend_from_slice(&(text.len() as u64).to_le_bytes());
+    cmds.extend_from_slice(&text_off.to_le_bytes());
+    cmds.extend_from_slice(&(text.len() as u64).to_le_bytes());
+    for field in [5u32, 5, 1, 0] {
+        cmds.extend_from_slice(&field.to_le_bytes()); // maxprot, initprot, nsects, flags
+    }
+    cmds.extend_from_slice(&name16("__text"));
+    cmds.extend_from_slice(&name16("__TEXT"));
+    cmds.extend_from_slice(&text_addr.to_le_bytes());
+    cmds.extend_from_slice(&(text.len() as u64).to_le_bytes());
+    // offset, align, reloff, nreloc, flags (pure + some instructions), reserved1-3
+    for field in [text_off as u32, 4, 0, 0, 0x8000_0400, 0, 0, 0] {
+        cmds.extend_from_slice(&field.to_le_bytes());
+    }
+    cmds.extend_from_slice(&0x2u32.to_le_bytes()); // LC_SYMTAB
+    for field in [
+        SYMTAB_SIZE as u32,
+        symtab_off as u32,
+        functions.len() as u32,
+        strtab_off as u32,
+        strtab.len() as u32,
+    ] {
+        cmds.extend_from_slice(&field.to_le_bytes());
+    }
+
+    let mut header = Vec::with_capacity(HEADER_SIZE);
+    // magic, cputype, cpusubtype, MH_EXECUTE, ncmds, sizeofcmds, flags, reserved
+    for field in [0xFEED_FACFu32, cputype, cpusubtype, 2, 2, cmds.len() as u32, 0, 0] {
+        header.extend_from_slice(&field.to_le_bytes());
+    }
+    out[..HEADER_SIZE].copy_from_slice(&header);
+    out[HEADER_SIZE..HEADER_SIZE + cmds.len()].copy_from_slice(&cmds);
+    out
+}
+
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+
+fn build_elf64(text: &[u8], functions: &[SyntheticFunction]) -> Vec<u8> {
+    build_elf64_with_rodata(EM_X86_64, text, &[], functions)
+}
+
+/// ELF64 image for `machine` with `.text`, an optional `.rodata` (at `RODATA_ADDR`, appended
+/// as the last section so the other section indices do not move), and a symbol table.
+fn build_elf64_with_rodata(
+    machine: u16,
+    text: &[u8],
+    rodata: &[u8],
+    functions: &[SyntheticFunction],
+) -> Vec<u8> {
+    const EHDR_SIZE: usize = 64;
+    const SHDR_SIZE: usize = 64;
+    const SYM_SIZE: usize = 24;
+
+    let mut strtab = vec![0u8];
+    let mut symtab = vec![0u8; SYM_SIZE];
+    for func in functions {
+        let name_off = strtab.len() as u32;
+        strtab.extend_from_slice(func.name.as_bytes());
+        strtab.push(0);
+        symtab.extend_from_slice(&name_off.to_le_bytes());
+        symtab.push(0x12); // STB_GLOBAL | STT_FUNC
+        symtab.push(0);
+        symtab.extend_from_slice(&1u16.to_le_bytes()); // .text
+        symtab.extend_from_slice(&func.address.to_le_bytes());
+        symtab.extend_from_slice(&(func.size as u64).to_le_bytes());
+    }
+    let mut shstrtab = b"\0.text\0.symtab\0.strtab\0.shstrtab\0".to_vec();
+    let rodata_name = shstrtab.len() as u32;
+    if !rodata.is_empty() {
+        shstrtab.extend_from_slice(b".rodata\0");
+    }
+
+    let mut out = vec![0u8; EHDR_SIZE];
+    let text_off = place(&mut out, text, 16);
+    let symtab_off = place(&mut out, &symtab, 8);
+    let strtab_off = place(&mut out, &strtab, 1);
+    let shstrtab_off = place(&mut out, &shstrtab, 1);
+    let rodata_off = place(&mut out, rodata, 8);
+    let shoff = place(&mut out, &[], 8);
+
+    let text_len = text.len() as u64;
+    let mut sections = vec![
+        SectionHeader::default(),
+        SectionHeader {
+            name: 1,
+            kind: 1,    // SHT_PROGBITS
+            flags: 0x6, // SHF_ALLOC | SHF_EXECINSTR
+            addr: TEXT_ADDR,
+            offset: text_off,
+            size: text_len,
+            align: 16,
+            ..Default::default()
+        },
+        SectionHeader {
+            name: 7,
+            kind: 2, // SHT_SYMTAB
+            offset: symtab_off,
+            size: symtab.len() as u64,
+            link: 3,
+            info: 1,
+            align: 8,
+            entsize: SYM_SIZE as u64,
+            ..Default::default()
+        },
+        SectionHeader {
+            name: 15,
+            kind: 3,
+            offset: strtab_off,
+            size: strtab.len() as u64,
+            align: 1,
+            ..Default::default()
+        },
+        SectionHeader {
+            name: 23,
+            kind: 3,
+            offset: shstrtab_off,
+            size: shstrtab.len() as u64,
+            align: 1,
+            ..Default::default()
+        },
+    ];
+    if !rodata.is_empty() {
+        sections.push(SectionHeader {
+            name: rodata_name,
+            kind: 1,    // SHT_PROGBITS
+            flags: 0x2, // SHF_ALLOC
+            addr: RODATA_ADDR,
+            offset: rodata_off,
+            size: rodata.len() as u64,
+            align: 8,
+            ..Default::default()
+        });
+    }
+    for section in &sections {
+        section.write(&mut out);
+    }
+
+    let mut ehdr = Vec::with_capacity(EHDR_SIZE);
+    ehdr.extend_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+    ehdr.extend_from_slice(&[0u8; 8]);
+    ehdr.extend_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    ehdr.extend_from_slice(&machine.to_le_bytes());
+    ehdr.extend_from_slice(&1u32.to_le_bytes());
+    ehdr.extend_from_slice(&TEXT_ADDR.to_le_bytes()); // e_entry
+    ehdr.extend_from_slice(&0u64.to_le_bytes()); // e_phoff
+    ehdr.extend_from_slice(&shoff.to_le_bytes());
+    ehdr.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    ehdr.extend_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+    ehdr.extend_from_slice(&0u16.to_le_bytes()); // e_phentsize
+    ehdr.extend_from_slice(&0u16.to_le_bytes()); // e_phnum
+    ehdr.extend_from_slice(&(SHDR_SIZE as u16).to_le_bytes());
+    ehdr.extend_from_slice(&(sections.len() as u16).to_le_bytes());
+    ehdr.extend_from_slice(&4u16.to_le_bytes()); // e_shstrndx
+    out[..EHDR_SIZE].copy_from_slice(&ehdr);
+    out
+}
+
+/// Dynamically linked x86_64 ELF64 image with `.text` and the symbol table of
+/// [`build_elf64_with_rodata`], plus a lazy `.plt` calling `imports` (name, version index into
+/// `GLIBC_2.2.5` = 2 / `GLIBC_2.14` = 3) through `.got.plt`. One `PT_LOAD` maps the file at
+/// `TEXT_ADDR - 0x1000`, so every section's address is that base plus its file offset.
+fn build_elf64_dynamic(
+    text: &[u8],
+    functions: &[SyntheticFunction],
+    imports: &[(&str, u16)],
+) -> Vec<u8> {
+    const EHDR_SIZE: usize = 64;
+    const PHDR_SIZE: usize = 56;
+    const SHDR_SIZE: usize = 64;
+    const SYM_SIZE: usize = 24;
+    const BASE: u64 = TEXT_ADDR - 0x1000;
+    const VERSIONS: [&str; 2] = ["GLIBC_2.2.5", "GLIBC_2.14"];
+
+    let sym = |out: &mut Vec<u8>, name: u32, info: u8, shndx: u16, value: u64, size: u64| {
+        out.extend_from_slice(&name.to_le_bytes());
+        out.extend_from_slice(&[info, 0]);
+        out.extend_from_slice(&shndx.to_le_bytes());
+        out.extend_from_slice(&value.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+    };
+    let mut strtab = vec![0u8];
+    let mut symtab = vec![0u8; SYM_SIZE];
+    for func in functions {
+        let name_off = strtab.len() as u32;
+        strtab.extend_from_slice(func.name.as_bytes());
+        strtab.push(0);
+        // STB_GLOBAL | STT_FUNC in .text
+        sym(&mut symtab, name_off, 0x12, 1, func.address, func.size as u64);
+    }
+    let mut dynstr = b"\0libc.so.6\0".to_vec();
+    let mut add_dynstr = |name: &str| {
+        let offset = dynstr.len() as u32;
+        dynstr.extend_from_slice(name.as_bytes());
+        dynstr.push(0);
+        offset
+    };
+    let mut dynsym = vec![0u8; SYM_SIZE];
+    for (name, _) in imports {
+        // Undefined STB_GLOBAL | STT_FUNC
+        sym(&mut dynsym, add_dynstr(name), 0x12, 0, 0, 0);
+    }
+    let version_names: Vec<u32> = VERSIONS.iter().map(|v| add_dynstr(v)).collect();
+    let shstrtab = b"\0.text\0.symtab\0.strtab\0.shstrtab\0.plt\0.got.plt\0.dynsym\0.dynstr\0\
+        .rela.plt\0.gnu.version\0.gnu.version_r\0.dynamic\0"
+        .to_vec();
+    let shname = |name: &str| {
+        let needle = format!("\0{}\0", name);
+        shstrtab.windows(needle.len()).position(|w| w == needle.as_bytes()).unwrap() as u32 + 1
+    };
+
+    // Lay out the file: headers, then .text at file offset 0x1000 (TEXT_ADDR) and .plt at
+    // PLT_ADDR; the data sections follow.
+    let mut out = vec![0u8; EHDR_SIZE + 2 * PHDR_SIZE];
+    out.resize(0x1000, 0);
+    let text_off = place(&mut out, text, 16);
+    out.resize((PLT_ADDR - BASE) as usize, 0);
+    let plt_off = out.len() as u64;
+    let got_off = plt_off + 16 * (imports.len() as u64 + 1);
+    let got_addr = BASE + got_off;
+    let at = |offset: u64| (BASE + offset) as i64;
+    // PLT0: push [GOT+8]; jmp [GOT+16]; nop
+    let mut plt = vec![0xFF, 0x35];
+    plt.extend_from_slice(&((got_addr + 8) as i64 - at(plt_off + 6)).to_le_bytes()[..4]);
+    plt.extend_from_slice(&[0xFF, 0x25]);
+    plt.extend_from_slice(&((got_addr + 16) as i64 - at(plt_off + 12)).to_le_bytes()[..4]);
+    plt.extend_from_slice(&[0x0F, 0x1F, 0x40, 0x00]);
+    let mut got = vec![0u8; 24];
+    for index in 0..imports.len() as u64 {
+        let stub = plt_off + 16 * (index + 1);
+        let slot = got_addr + 24 + 8 * index;
+        plt.extend_from_slice(&[0xFF, 0x25]); // jmp [slot]
+        plt.extend_from_slice(&(slot as i64 - at(stub + 6)).to_le_bytes()[..4]);
+        plt.push(0x68); // push index
+        plt.extend_from_slice(&(index as u32).to_le_bytes());
+        plt.push(0xE9); // jmp PLT0
+        plt.extend_from_slice(&(at(plt_off) - at(stub + 16)).to_le_bytes()[..4]);
+        // Until bound, a slot points back at its stub's push.
+        got.extend_from_slice(&(BASE + stub + 6).to_le_bytes());
+    }
+    out.extend_from_slice(&plt);
+    let got_off = place(&mut out, &got, 8);
+    debug_assert_eq!(BASE + got_off, got_addr);
+
+    let mut rela_plt = Vec::new();
+    for index in 0..imports.len() as u64 {
+        rela_plt.extend_from_slice(&(got_addr + 24 + 8 * index).to_le_bytes());
+        rela_plt.extend_from_slice(&(((index + 1) << 32) | 7).to_le_bytes()); // R_X86_64_JUMP_SLOT
+        rela_plt.extend_from_slice(&0i64.to_le_bytes());
+    }
+    let mut versym = vec![0u8; 2];
+    for (_, version) in imports {
+        versym.extend_from_slice(&version.to_le_bytes());
+    }
+    // One Verneed for libc.so.6 followed by a Vernaux per version.
+    let mut verneed = Vec::new();
+    verneed.extend_from_slice(&1u16.to_le_bytes()); // vn_version
+    verneed.extend_from_slice(&(VERSIONS.len() as u16).to_le_bytes());
+    verneed.extend_from_slice(&1u32.to_le_bytes()); // vn_file: "libc.so.6"
+    verneed.extend_from_slice(&16u32.to_le_bytes()); // vn_aux
+    verneed.extend_from_slice(&0u32.to_le_bytes()); // vn_next
+    for (i, (version, name)) in VERSIONS.iter().zip(&version_names).enumerate() {
+        verneed.extend_from_slice(&elf_hash(version).to_le_bytes());
+        verneed.extend_from_slice(&0u16.to_le_bytes()); // vna_flags
+        verneed.extend_from_slice(&(i as u16 + 2).to_le_bytes()); // vna_other
+        verneed.extend_from_slice(&name.to_le_bytes());
+        let next = if i + 1 < VERSIONS.len() { 16u32 } else { 0 };
+        verneed.extend_from_slice(&next.to_le_bytes());
+    }
+
+    let dynsym_off = place(&mut out, &dynsym, 8);
+    let dynstr_off = place(&mut out, &dynstr, 1);
+    let rela_off = place(&mut out, &rela_plt, 8);
+    let versym_off = place(&mut out, &versym, 2);
+    let verneed_off = place(&mut out, &verneed, 4);
+    let dynamic: Vec<u8> = [
+        (1, 1),                            // DT_NEEDED "libc.so.6"
+        (5, BASE + dynstr_off),            // DT_STRTAB
+        (6, BASE + dynsym_off),            // DT_SYMTAB
+        (10, dynstr.len() as u64),         // DT_STRSZ
+        (11, SYM_SIZE as u64),             // DT_SYMENT
+        (3, got_addr),                     // DT_PLTGOT
+        (2, rela_plt.len() as u64),        // DT_PLTRELSZ
+        (20, 7),                           // DT_PLTREL = DT_RELA
+        (23, BASE + rela_off),             // DT_JMPREL
+        (0x6fff_fff0, BASE + versym_off),  // DT_VERSYM
+        (0x6fff_fffe, BASE + verneed_off), // DT_VERNEED
+        (0x6fff_ffff, 1),                  // DT_VERNEEDNUM
+        (0, 0),                            // DT_NULL
+    ]
+    .iter()
+    .flat_map(|(tag, value): &(u64, u64)| [tag.to_le_bytes(), value.to_le_bytes()])
+    .flatten()
+    .collect();
+    let dynamic_off = place(&mut out, &dynamic, 8);
+    let symtab_off = place(&mut out, &symtab, 8);
+    let strtab_off = place(&mut out, &strtab, 1);
+    let shstrtab_off = place(&mut out, &shstrtab, 1);
+    let shoff = place(&mut out, &[], 8);
+
+    let alloc = |name: &str, kind: u32, flags: u64, offset: u64, size: usize| SectionHeader {
+        name: shname(name),
+        kind,
+        flags: flags | 0x2, // SHF_ALLOC
+        addr: BASE + offset,
+        offset,
+        size: size as u64,
+        align: 8,
+        ..Default::default()
+    };
+    let sections = [
+        SectionHeader::default(),
+        SectionHeader { align: 16, ..alloc(".text", 1, 0x4, text_off, text.len()) },
+        SectionHeader {
+            name: shname(".symtab"),
+            kind: 2, // SHT_SYMTAB
+            offset: symtab_off,
+            size: symtab.len() as u64,
+            link: 3,
+            info: 1,
+            align: 8,
+            entsize: SYM_SIZE as u64,
+            ..Default::default()
+        },
+        SectionHeader {
+            name: shname(".strtab"),
+            kind: 3,
+            offset: strtab_off,
+            size: strtab.len() as u64,
+            align: 1,
+            ..Default::default()
+        },
+        SectionHeader {
+            name: shname(".shstrtab"),
+            kind: 3,
+            offset: shstrtab_off,
+            size: shstrtab.len() as u64,
+            align: 1,
+            ..Default::default()
+        },
+        SectionHeader { align: 16, entsize: 16, ..alloc(".plt", 1, 0x4, plt_off, plt.len()) },
+        SectionHeader { entsize: 8, ..alloc(".got.plt", 1, 0x1, got_off, got.len()) },
+        // SHT_DYNSYM, linked to .dynstr; one local symbol (the null one)
+        SectionHeader {
+            link: 8,
+            info: 1,
+            entsize: SYM_SIZE as u64,
+            ..alloc(".dynsym", 11, 0, dynsym_off, dynsym.len())
+        },
+        SectionHeader { align: 1, ..alloc(".dynstr", 3, 0, dynstr_off, dynstr.len()) },
+        // SHT_RELA for .got.plt (SHF_INFO_LINK), symbols from .dynsym
+        SectionHeader {
+            link: 7,
+            info: 6,
+            entsize: 24,
+            ..alloc(".rela.plt", 4, 0x40, rela_off, rela_plt.len())
+        },
+        // SHT_GNU_VERSYM
+        SectionHeader {
+            link: 7,
+            align: 2,
+            entsize: 2,
+            ..alloc(".gnu.version", 0x6fff_ffff, 0, versym_off, versym.len())
+        },
+        // SHT_GNU_VERNEED with one entry
+        SectionHeader {
+            link: 8,
+            info: 1,
+            align: 4,
+            ..alloc(".gnu.version_r", 0x6fff_fffe, 0, verneed_off, verneed.len())
+        },
+        // SHT_DYNAMIC
+        SectionHeader {
+            link: 8,
+            entsize: 16,
+            ..alloc(".dynamic", 6, 0x1, dynamic_off, dynamic.len())
+        },
+    ];
+    for section in &sections {
+        section.write(&mut out);
+    }
+
+    let mut headers = Vec::with_capacity(EHDR_SIZE + 2 * PHDR_SIZE);
+    headers.extend_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+    headers.extend_from_slice(&[0u8; 8]);
+    headers.extend_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    headers.extend_from_slice(&EM_X86_64.to_le_bytes());
+    headers.extend_from_slice(&1u32.to_le_bytes());
+    headers.extend_from_slice(&TEXT_ADDR.to_le_bytes()); // e_entry
+    headers.extend_from_slice(&(EHDR_SIZE as u64).to_le_bytes()); // e_phoff
+    headers.extend_from_slice(&shoff.to_le_bytes());
+    headers.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    headers.extend_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+    headers.extend_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+    headers.extend_from_slice(&2u16.to_le_bytes()); // e_phnum
+    headers.extend_from_slice(&(SHDR_SIZE as u16).to_le_bytes());
+    headers.extend_from_slice(&(sections.len() as u16).to_le_bytes());
+    headers.extend_from_slice(&4u16.to_le_bytes()); // e_shstrndx
+    let file_len = out.len() as u64;
+    // PT_LOAD (RWX) of the whole file, then PT_DYNAMIC (RW)
+    for (kind, flags, offset, size, align) in
+        [(1u32, 7u32, 0, file_len, 0x1000u64), (2, 6, dynamic_off, dynamic.len() as u64, 8)]
+    {
+        headers.extend_from_slice(&kind.to_le_bytes());
+        headers.extend_from_slice(&flags.to_le_bytes());
+        headers.extend_from_slice(&offset.to_le_bytes());
+        headers.extend_from_slice(&(BASE + offset).to_le_bytes()); // p_vaddr
+        headers.extend_from_slice(&(BASE + offset).to_le_bytes()); // p_paddr
+        headers.extend_from_slice(&size.to_le_bytes()); // p_filesz
+        headers.extend_from_slice(&size.to_le_bytes()); // p_memsz
+        headers.extend_from_slice(&align.to_le_bytes());
+    }
+    out[..headers.len()].copy_from_slice(&headers);
+    out
+}
+
+/// SysV ELF hash of a symbol version name (`vna_hash`).
+fn elf_hash(name: &str) -> u32 {
+    let mut hash = 0u32;
+    for byte in name.bytes() {
+        hash = (hash << 4).wrapping_add(byte as u32);
+        let high = hash & 0xF000_0000;
+        hash ^= high >> 24;
+        hash &= !high;
+    }
+    hash
+}
+
+/// ELF64 section header fields (`kind` is `sh_type`).
+#[derive(Default)]
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entsize: u64,
+}
+
+impl SectionHeader {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.name.to_le_bytes());
+        out.extend_from_slice(&self.kind.to_le_bytes());
+        out.extend_from_slice(&self.flags.to_le_bytes());
+        out.extend_from_slice(&self.addr.to_le_bytes());
+        out.extend_from_slice(&self.offset.to_le_bytes());
+        out.extend_from_slice(&self.size.to_le_bytes());
+        out.extend_from_slice(&self.link.to_le_bytes());
+        out.extend_from_slice(&self.info.to_le_bytes());
+        out.extend_from_slice(&self.align.to_le_bytes());
+        out.extend_from_slice(&self.entsize.to_le_bytes());
+    }
+}
+
+/// Append `data` at the next `align`-byte boundary and return its file offset.
+fn place(out: &mut Vec<u8>, data: &[u8], align: usize) -> u64 {
+    while !out.len().is_multiple_of(align) {
+        out.push(0);
+    }
+    let offset = out.len() as u64;
+    out.extend_from_slice(data);
+    offset
+}
+
+/// Check a backend's result against the synthetic binary it analyzed.
+///
+/// Structural invariants always apply; when `expect_functions` is set the planted functions
+/// and calls must also be recovered. Backends may prefix symbol names (e.g. `sym.main`).
+/// Returns a list of human-readable violations (empty when the result is sound).
+pub fn check_invariants(
+    binary: &SyntheticBinary,
+    result: &AnalysisResult,
+    expect_functions: bool,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    if result.roots != binary.roots() {
+        failures.push(format!("roots not echoed: {:?}", result.roots));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for func in &result.functions {
+        if !seen.insert(func.address) {
+            failures.push(format!("duplicate function at 0x{:X}", func.address));
+        }
+    }
+    for hit in &result.root_hits {
+        for addr in &hit.functions {
+            if !seen.contains(addr) {
+                failures.push(format!("root '{}' hit unknown function 0x{:X}", hit.root, addr));
+            }
+        }
+    }
+    for block in &result.basic_blocks {
+        if block.byte_len == 0 {
+            failures.push(format!("empty basic block at 0x{:X}", block.start));
+        }
+    }
+
+    if !expect_functions {
+        return failures;
+    }
+    for expected in &binary.functions {
+        let found = result.functions.iter().find(|f| f.address == expected.address);
+        match found.and_then(|f| f.name.as_deref()) {
+            Some(name)
+                if name == expected.name || name.ends_with(&format!(".{}", expected.name)) => {}
+            Some(name) => failures.push(format!(
+                "function at 0x{:X} named '{}', expected '{}'",
+                expected.address, name, expected.name
+            )),
+            None if found.is_some() => {}
+            None => failures
+                .push(format!("missing function '{}' at 0x{:X}", expected.name, expected.address)),
+        }
+        let resolved = result
+            .root_hits
+            .iter()
+            .any(|h| h.root == expected.name && h.functions.contains(&expected.address));
+        if !resolved {
+            failures.push(format!("root '{}' did not resolve", expected.name));
+        }
+    }
+    for (caller, callee) in &binary.calls {
+        let present = result.call_edges.iter().any(|e| {
+            e.to == *callee
+                && binary.function_containing(e.from).map(|f| f.address) == Some(*caller)
+        });
+        if !present {
+            failures.push(format!("missing call edge 0x{:X} -> 0x{:X}", caller, callee));
+        }
+    }
+    failures
+}
@@ -0,0 +1,111 @@
+//! Consistency of run outputs with the project DB (`doctor`).
+//!
+//! Only the latest run of a binary and ritual has an output directory,
+//! `outputs/binaries/<binary>/<ritual>`; older runs were overwritten by it. A directory with no
+//! run of its binary and ritual in the DB is orphaned (left behind by a replaced DB or copied
+//! in by hand), and a latest run that was not purged but has no directory lost its outputs.
+//! Pending and running runs are skipped: their outputs may not exist yet.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::db::{ProjectLayout, RitualRunRecord, RitualRunStatus};
+
+/// An output directory no run in the DB accounts for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanedOutput {
+    pub binary: String,
+    pub ritual: String,
+    pub path: PathBuf,
+}
+
+/// The latest run of a binary and ritual, whose output directory is gone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingOutput {
+    pub run_id: i64,
+    pub binary: String,
+    pub ritual: String,
+    pub path: PathBuf,
+}
+
+/// Output directories and DB runs that do not match, each sorted by binary and ritual.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OutputAudit {
+    pub orphaned: Vec<OrphanedOutput>,
+    pub missing: Vec<MissingOutput>,
+}
+
+impl OutputAudit {
+    pub fn is_clean(&self) -> bool {
+        self.orphaned.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Compare the output directories under `layout` with `runs` (row id and record, as from
+/// `ProjectDb::list_ritual_runs_with_ids`).
+pub fn audit_outputs(
+    layout: &ProjectLayout,
+    runs: &[(i64, RitualRunRecord)],
+) -> io::Result<OutputAudit> {
+    let mut latest: BTreeMap<(&str, &str), (i64, &RitualRunRecord)> = BTreeMap::new();
+    for (id, run) in runs {
+        let entry = latest.entry((run.binary.as_str(), run.ritual.as_str())).or_insert((*id, run));
+        if *id > entry.0 {
+            *entry = (*id, run);
+        }
+    }
+
+    let mut audit = OutputAudit::default();
+    let mut on_disk = HashSet::new();
+    for (binary, ritual, path) in output_dirs(&layout.outputs_binaries_dir)? {
+        if latest.contains_key(&(binary.as_str(), ritual.as_str())) {
+            on_disk.insert((binary, ritual));
+        } else {
+            audit.orphaned.push(OrphanedOutput { binary, ritual, path });
+        }
+    }
+    for ((binary, ritual), (run_id, run)) in latest {
+        let settled = !matches!(
+            run.status,
+            RitualRunStatus::Purged | RitualRunStatus::Pending | RitualRunStatus::Running
+        );
+        if settled && !on_disk.contains(&(binary.to_string(), ritual.to_string())) {
+            audit.missing.push(MissingOutput {
+                run_id,
+                binary: binary.to_string(),
+                ritual: ritual.to_string(),
+                path: layout.binary_output_root(binary).join(ritual),
+            });
+        }
+    }
+    Ok(audit)
+}
+
+/// `(binary, ritual, path)` of every `<binary>/<ritual>` directory under `dir`, sorted.
+fn output_dirs(dir: &Path) -> io::Result<Vec<(String, String, PathBuf)>> {
+    let subdirs = |dir: &Path| -> io::Result<Vec<(String, PathBuf)>> {
+        let mut out = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                out.push((entry.file_name().to_string_lossy().to_string(), entry.path()));
+            }
+        }
+        out.sort();
+        Ok(out)
+    };
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut dirs = Vec::new();
+    for (binary, binary_dir) in subdirs(dir)? {
+        for (ritual, path) in subdirs(&binary_dir)? {
+            dirs.push((binary.clone(), ritual, path));
+        }
+    }
+    Ok(dirs)
+}
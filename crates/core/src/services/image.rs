@@ -0,0 +1,89 @@
+//! Read-only views of binaries on disk.
+//!
+//! Game archives and engine libraries run to gigabytes, so backends and the strings scan do
+//! not read the whole file into memory: [`BinaryImage::open`] maps it instead, and the pages a
+//! pass touches are loaded (and dropped again) by the OS. Sections are read through
+//! bounds-checked views ([`BinaryImage::view`], [`MappedRegion::file_bytes`]), so a header
+//! pointing past the end of a truncated file yields nothing rather than a panic.
+
+use std::fs::File;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+use crate::services::analysis::AnalysisError;
+use crate::services::loader::MappedRegion;
+
+/// The bytes of a binary, memory-mapped when possible.
+#[derive(Debug)]
+pub struct BinaryImage {
+    path: PathBuf,
+    data: ImageData,
+}
+
+#[derive(Debug)]
+enum ImageData {
+    Mapped(Mmap),
+    /// Files that cannot be mapped (empty files, pipes, some network filesystems) are read.
+    Owned(Vec<u8>),
+}
+
+impl BinaryImage {
+    /// Map the file at `path`, falling back to reading it when it cannot be mapped.
+    pub fn open(path: &Path) -> Result<Self, AnalysisError> {
+        let missing = || AnalysisError::MissingBinary(path.to_path_buf());
+        let file = File::open(path).map_err(|_| missing())?;
+        let len = file.metadata().map_err(|_| missing())?.len();
+        // SAFETY: the mapping is read-only and private to this process. Binaries are inputs
+        // the project never writes; if another process truncates one mid-run, reads past the
+        // new end fault, the same failure mode as every mmap-based loader (rizin, Ghidra).
+        let mapped = (len > 0).then(|| unsafe { Mmap::map(&file) }.ok()).flatten();
+        let data = match mapped {
+            Some(map) => ImageData::Mapped(map),
+            None => ImageData::Owned(std::fs::read(path).map_err(|_| missing())?),
+        };
+        Ok(Self { path: path.to_path_buf(), data })
+    }
+
+    /// An in-memory image (fixtures, bytes extracted from an archive).
+    pub fn from_bytes(path: impl Into<PathBuf>, bytes: Vec<u8>) -> Self {
+        Self { path: path.into(), data: ImageData::Owned(bytes) }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the bytes are mapped from the file rather than held in memory.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.data, ImageData::Mapped(_))
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        match &self.data {
+            ImageData::Mapped(map) => map,
+            ImageData::Owned(bytes) => bytes,
+        }
+    }
+
+    /// `len` bytes at file `offset`, or `None` when any of them lies past the end of the file.
+    pub fn view(&self, offset: u64, len: u64) -> Option<&[u8]> {
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(len).ok()?)?;
+        self.bytes().get(start..end)
+    }
+
+    /// File-backed bytes of `region` (see [`MappedRegion::file_bytes`]).
+    pub fn region(&self, region: &MappedRegion) -> Option<&[u8]> {
+        region.file_bytes(self.bytes())
+    }
+}
+
+impl Deref for BinaryImage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.bytes()
+    }
+}
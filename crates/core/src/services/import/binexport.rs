@@ -0,0 +1,583 @@
+//! Reader for BinExport2 files (`.BinExport`), the protobuf format the BinExport plugin writes
+//! from IDA Pro, Ghidra, and Binary Ninja.
+//!
+//! Only the messages that map onto the common IR are decoded, with a small wire-format reader
+//! instead of generated code:
+//! - call graph vertices become functions (imports, library functions, and thunks as
+//!   boundaries) and call graph edges become call edges;
+//! - flow graphs give basic blocks, their edges, and each function's size;
+//! - string references become string evidence at the referencing instruction;
+//! - executable sections give the text size used for slice coverage.
+//!
+//! Instruction addresses follow the format's rule: an instruction without an explicit address
+//! directly follows the previous one (previous address plus its `raw_bytes` length).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::{ImportError, ImportedAnalysis};
+use crate::services::analysis::{
+    AnalysisOptions, AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind,
+    EvidenceRecord, EvidenceSource, FunctionRecord,
+};
+
+/// `CallGraph.Vertex.Type` values.
+const VERTEX_NORMAL: u64 = 0;
+const VERTEX_IMPORTED: u64 = 2;
+
+/// Decode a BinExport2 file and map it into an analysis result (without roots).
+pub(crate) fn parse(
+    data: &[u8],
+    options: &AnalysisOptions,
+) -> Result<ImportedAnalysis, ImportError> {
+    let export = BinExport::decode(data)?;
+    Ok(export.into_analysis(options))
+}
+
+/// A decoded protobuf field value.
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    /// `fixed32`/`fixed64` values; no field read here uses them.
+    Fixed,
+}
+
+impl<'a> Value<'a> {
+    fn uint(&self, field: &str) -> Result<u64, ImportError> {
+        match self {
+            Value::Varint(v) => Ok(*v),
+            _ => Err(malformed(format!("{} is not a varint", field))),
+        }
+    }
+
+    /// Indexes are `int32`; negative values (never valid) become out-of-range indexes.
+    fn index(&self, field: &str) -> Result<usize, ImportError> {
+        Ok(usize::try_from(self.uint(field)?).unwrap_or(usize::MAX))
+    }
+
+    fn bytes(&self, field: &str) -> Result<&'a [u8], ImportError> {
+        match self {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(malformed(format!("{} is not length-delimited", field))),
+        }
+    }
+
+    fn string(&self, field: &str) -> Result<String, ImportError> {
+        Ok(String::from_utf8_lossy(self.bytes(field)?).into_owned())
+    }
+
+    /// A repeated integer field, accepting both packed and unpacked encodings.
+    fn extend_uints(&self, field: &str, out: &mut Vec<u64>) -> Result<(), ImportError> {
+        match self {
+            Value::Varint(v) => out.push(*v),
+            Value::Bytes(mut packed) => {
+                while !packed.is_empty() {
+                    out.push(varint(&mut packed)?);
+                }
+            }
+            Value::Fixed => return Err(malformed(format!("{} is not a varint", field))),
+        }
+        Ok(())
+    }
+}
+
+fn malformed(message: String) -> ImportError {
+    ImportError::BinExport(message)
+}
+
+fn varint(buf: &mut &[u8]) -> Result<u64, ImportError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) =
+            buf.split_first().ok_or_else(|| malformed("truncated varint".into()))?;
+        *buf = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(malformed("varint longer than 10 bytes".into()))
+}
+
+/// Call `f` with every `(field number, value)` of the message in `buf`.
+fn each_field<'a>(
+    mut buf: &'a [u8],
+    mut f: impl FnMut(u64, Value<'a>) -> Result<(), ImportError>,
+) -> Result<(), ImportError> {
+    while !buf.is_empty() {
+        let key = varint(&mut buf)?;
+        let value = match key & 7 {
+            0 => Value::Varint(varint(&mut buf)?),
+            1 | 5 => {
+                let width = if key & 7 == 1 { 8 } else { 4 };
+                if buf.len() < width {
+                    return Err(malformed("truncated fixed-width field".into()));
+                }
+                buf = &buf[width..];
+                Value::Fixed
+            }
+            2 => {
+                let len = usize::try_from(varint(&mut buf)?).unwrap_or(usize::MAX);
+                if buf.len() < len {
+                    return Err(malformed("truncated length-delimited field".into()));
+                }
+                let (bytes, rest) = buf.split_at(len);
+                buf = rest;
+                Value::Bytes(bytes)
+            }
+            wire => return Err(malformed(format!("unsupported wire type {}", wire))),
+        };
+        f(key >> 3, value)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct Meta {
+    executable_name: Option<String>,
+    executable_id: Option<String>,
+    architecture: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Instruction {
+    address: Option<u64>,
+    len: u64,
+}
+
+/// `BasicBlock.IndexRange`: instructions `begin..end` (`end` defaults to `begin + 1`).
+#[derive(Debug, Default)]
+struct IndexRange {
+    begin: usize,
+    end: Option<usize>,
+}
+
+/// Edges and the entry block use indexes into the global basic block table, like
+/// `FlowGraph.basic_block_index`.
+#[derive(Debug, Default)]
+struct FlowEdge {
+    source: u64,
+    target: u64,
+    /// `FlowGraph.Edge.Type`, `UNCONDITIONAL` (3) by default.
+    kind: u64,
+}
+
+#[derive(Debug, Default)]
+struct FlowGraph {
+    blocks: Vec<u64>,
+    entry: Option<u64>,
+    edges: Vec<FlowEdge>,
+}
+
+#[derive(Debug, Default)]
+struct Vertex {
+    address: u64,
+    kind: u64,
+    mangled: Option<String>,
+    demangled: Option<String>,
+    library: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct Reference {
+    instruction: usize,
+    string: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct BinExport {
+    meta: Meta,
+    instructions: Vec<Instruction>,
+    basic_blocks: Vec<Vec<IndexRange>>,
+    flow_graphs: Vec<FlowGraph>,
+    vertices: Vec<Vertex>,
+    call_edges: Vec<(usize, usize)>,
+    strings: Vec<String>,
+    string_refs: Vec<Reference>,
+    /// Sizes of the executable sections.
+    code_sections: Vec<u64>,
+    libraries: Vec<String>,
+}
+
+impl BinExport {
+    fn decode(data: &[u8]) -> Result<Self, ImportError> {
+        let mut export = BinExport::default();
+        each_field(data, |field, value| {
+            match field {
+                1 => export.meta = decode_meta(value.bytes("meta_information")?)?,
+                5 => export.instructions.push(decode_instruction(value.bytes("instruction")?)?),
+                6 => export.basic_blocks.push(decode_basic_block(value.bytes("basic_block")?)?),
+                7 => export.flow_graphs.push(decode_flow_graph(value.bytes("flow_graph")?)?),
+                8 => decode_call_graph(value.bytes("call_graph")?, &mut export)?,
+                9 => export.strings.push(value.string("string_table")?),
+                11 => export.string_refs.push(decode_reference(value.bytes("string_reference")?)?),
+                13 => {
+                    let (size, executable) = decode_section(value.bytes("section")?)?;
+                    if executable {
+                        export.code_sections.push(size);
+                    }
+                }
+                14 => export.libraries.push(decode_library(value.bytes("library")?)?),
+                _ => {}
+            }
+            Ok(())
+        })?;
+        if export.meta.executable_id.is_none() && export.vertices.is_empty() {
+            return Err(malformed(
+                "no meta information or call graph (not a BinExport2 file?)".into(),
+            ));
+        }
+        Ok(export)
+    }
+
+    fn into_analysis(self, options: &AnalysisOptions) -> ImportedAnalysis {
+        // Resolve instruction addresses.
+        let mut addresses = Vec::with_capacity(self.instructions.len());
+        let mut next = 0u64;
+        for insn in &self.instructions {
+            let address = insn.address.unwrap_or(next);
+            addresses.push(address);
+            next = address.wrapping_add(insn.len);
+        }
+
+        // Basic blocks: start address, byte length, and instruction count.
+        let blocks: Vec<Option<(u64, u64, u32)>> = self
+            .basic_blocks
+            .iter()
+            .map(|ranges| {
+                let mut start = None;
+                let (mut len, mut count) = (0u64, 0u32);
+                for range in ranges {
+                    let end = range
+                        .end
+                        .unwrap_or(range.begin.saturating_add(1))
+                        .min(self.instructions.len());
+                    let span = range.begin.min(end)..end;
+                    for (address, insn) in
+                        addresses[span.clone()].iter().zip(&self.instructions[span])
+                    {
+                        start.get_or_insert(*address);
+                        len += insn.len;
+                        count += 1;
+                    }
+                }
+                start.map(|start| (start, len, count))
+            })
+            .collect();
+        let block =
+            |index: u64| -> Option<(u64, u64, u32)> { *blocks.get(usize::try_from(index).ok()?)? };
+
+        let vertex_at: HashMap<u64, &Vertex> =
+            self.vertices.iter().map(|v| (v.address, v)).collect();
+        let mut functions: BTreeMap<u64, FunctionRecord> = BTreeMap::new();
+        let mut basic_blocks: BTreeMap<u64, BasicBlock> = BTreeMap::new();
+        for graph in &self.flow_graphs {
+            let Some((entry, _, _)) = graph.entry.and_then(block) else {
+                continue;
+            };
+            let mut size = 0u64;
+            for &index in &graph.blocks {
+                if let Some((start, len, count)) = block(index) {
+                    size += len;
+                    basic_blocks.entry(start).or_insert_with(|| BasicBlock {
+                        start,
+                        byte_len: u32::try_from(len).unwrap_or(u32::MAX),
+                        insn_count: Some(count),
+                        successors: Vec::new(),
+                    });
+                }
+            }
+            for edge in &graph.edges {
+                if let (Some((source, ..)), Some((target, ..))) =
+                    (block(edge.source), block(edge.target))
+                {
+                    let successor = BlockEdge { target, kind: edge_kind(edge.kind) };
+                    if let Some(block) = basic_blocks.get_mut(&source) {
+                        if !block.successors.contains(&successor) {
+                            block.successors.push(successor);
+                        }
+                    }
+                }
+            }
+            functions.insert(
+                entry,
+                function_record(entry, u32::try_from(size).ok(), vertex_at.get(&entry).copied()),
+            );
+        }
+        // Call graph vertices without a flow graph: imports, library functions, and thunks.
+        for vertex in &self.vertices {
+            functions
+                .entry(vertex.address)
+                .or_insert_with(|| function_record(vertex.address, None, Some(vertex)));
+        }
+
+        let mut evidence = Vec::new();
+        let mut call_edges = Vec::new();
+        let mut seen = BTreeSet::new();
+        for &(source, target) in &self.call_edges {
+            let (Some(from), Some(to)) = (self.vertices.get(source), self.vertices.get(target))
+            else {
+                continue;
+            };
+            if !seen.insert((from.address, to.address)) {
+                continue;
+            }
+            call_edges.push(CallEdge {
+                from: from.address,
+                to: to.address,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            });
+            evidence.push(EvidenceRecord {
+                address: from.address,
+                description: match vertex_name(to) {
+                    Some(name) => format!("call -> {}", name),
+                    None => format!("call -> 0x{:X}", to.address),
+                },
+                kind: Some(EvidenceKind::Call),
+                source: evidence_source("calls"),
+                run_id: None,
+            });
+        }
+        if options.include_imports {
+            for vertex in self.vertices.iter().filter(|v| v.kind == VERTEX_IMPORTED) {
+                let name = vertex_name(vertex).unwrap_or("?");
+                let library = vertex.library.and_then(|i| self.libraries.get(i));
+                evidence.push(EvidenceRecord {
+                    address: vertex.address,
+                    description: match library.filter(|l| !l.is_empty()) {
+                        Some(library) => format!("import: {} ({})", name, library),
+                        None => format!("import: {}", name),
+                    },
+                    kind: Some(EvidenceKind::Import),
+                    source: evidence_source("imports"),
+                    run_id: None,
+                });
+            }
+        }
+        if options.include_strings {
+            for reference in &self.string_refs {
+                let text = reference.string.and_then(|i| self.strings.get(i));
+                if let (Some(&address), Some(text)) = (addresses.get(reference.instruction), text) {
+                    evidence.push(EvidenceRecord {
+                        address,
+                        description: format!("string: {}", text),
+                        kind: Some(EvidenceKind::String),
+                        source: evidence_source("strings"),
+                        run_id: None,
+                    });
+                }
+            }
+        }
+
+        let text_bytes: u64 = self.code_sections.iter().sum();
+        ImportedAnalysis {
+            result: AnalysisResult {
+                functions: functions.into_values().collect(),
+                call_edges,
+                evidence,
+                xrefs: Vec::new(),
+                basic_blocks: basic_blocks.into_values().collect(),
+                roots: Vec::new(),
+                root_hits: Vec::new(),
+                sub_slices: Vec::new(),
+                backend_version: None,
+                backend_path: None,
+                text_bytes: (text_bytes > 0).then_some(text_bytes),
+            },
+            executable_id: self.meta.executable_id.filter(|id| !id.is_empty()),
+            executable_name: self.meta.executable_name.filter(|name| !name.is_empty()),
+            architecture: self.meta.architecture.filter(|arch| !arch.is_empty()),
+        }
+    }
+}
+
+/// Provenance of rows from one of the export's sections.
+fn evidence_source(pass: &str) -> Option<EvidenceSource> {
+    Some(EvidenceSource::new("binexport", pass))
+}
+
+fn vertex_name(vertex: &Vertex) -> Option<&str> {
+    vertex.demangled.as_deref().or(vertex.mangled.as_deref()).filter(|n| !n.is_empty())
+}
+
+fn function_record(address: u64, size: Option<u32>, vertex: Option<&Vertex>) -> FunctionRecord {
+    let mangled = vertex.and_then(|v| v.mangled.clone()).filter(|n| !n.is_empty());
+    let demangled = vertex.and_then(|v| v.demangled.clone()).filter(|n| !n.is_empty());
+    // Keep the exporting tool's demangling (it may cover schemes ours does not), and leave a
+    // lone mangled name for the common demangling pass.
+    let (name, mangled_name) = match (demangled, mangled) {
+        (Some(demangled), Some(mangled)) if demangled != mangled => {
+            (Some(demangled), Some(mangled))
+        }
+        (demangled, mangled) => (demangled.or(mangled), None),
+    };
+    FunctionRecord {
+        address,
+        name,
+        size: size.filter(|s| *s > 0),
+        in_slice: true,
+        is_boundary: vertex.is_some_and(|v| v.kind != VERTEX_NORMAL),
+        mangled_name,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn edge_kind(kind: u64) -> BlockEdgeKind {
+    match kind {
+        1 => BlockEdgeKind::ConditionalJump,
+        2 => BlockEdgeKind::Fallthrough,
+        4 => BlockEdgeKind::IndirectJump,
+        _ => BlockEdgeKind::Jump,
+    }
+}
+
+fn decode_meta(buf: &[u8]) -> Result<Meta, ImportError> {
+    let mut meta = Meta::default();
+    each_field(buf, |field, value| {
+        match field {
+            1 => meta.executable_name = Some(value.string("executable_name")?),
+            2 => meta.executable_id = Some(value.string("executable_id")?),
+            3 => meta.architecture = Some(value.string("architecture_name")?),
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(meta)
+}
+
+fn decode_instruction(buf: &[u8]) -> Result<Instruction, ImportError> {
+    let mut insn = Instruction::default();
+    each_field(buf, |field, value| {
+        match field {
+            1 => insn.address = Some(value.uint("instruction.address")?),
+            5 => insn.len = value.bytes("instruction.raw_bytes")?.len() as u64,
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(insn)
+}
+
+fn decode_basic_block(buf: &[u8]) -> Result<Vec<IndexRange>, ImportError> {
+    let mut ranges = Vec::new();
+    each_field(buf, |field, value| {
+        if field == 1 {
+            let mut range = IndexRange::default();
+            each_field(value.bytes("basic_block.instruction_index")?, |field, value| {
+                match field {
+                    1 => range.begin = value.index("begin_index")?,
+                    2 => range.end = Some(value.index("end_index")?),
+                    _ => {}
+                }
+                Ok(())
+            })?;
+            ranges.push(range);
+        }
+        Ok(())
+    })?;
+    Ok(ranges)
+}
+
+fn decode_flow_graph(buf: &[u8]) -> Result<FlowGraph, ImportError> {
+    let mut graph = FlowGraph::default();
+    each_field(buf, |field, value| {
+        match field {
+            1 => value.extend_uints("flow_graph.basic_block_index", &mut graph.blocks)?,
+            2 => {
+                let mut edge = FlowEdge { kind: 3, ..FlowEdge::default() };
+                each_field(value.bytes("flow_graph.edge")?, |field, value| {
+                    match field {
+                        1 => edge.source = value.uint("source_basic_block_index")?,
+                        2 => edge.target = value.uint("target_basic_block_index")?,
+                        3 => edge.kind = value.uint("edge.type")?,
+                        _ => {}
+                    }
+                    Ok(())
+                })?;
+                graph.edges.push(edge);
+            }
+            3 => graph.entry = Some(value.uint("entry_basic_block_index")?),
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(graph)
+}
+
+fn decode_call_graph(buf: &[u8], export: &mut BinExport) -> Result<(), ImportError> {
+    each_field(buf, |field, value| {
+        match field {
+            1 => {
+                let mut vertex = Vertex::default();
+                each_field(value.bytes("call_graph.vertex")?, |field, value| {
+                    match field {
+                        1 => vertex.address = value.uint("vertex.address")?,
+                        2 => vertex.kind = value.uint("vertex.type")?,
+                        3 => vertex.mangled = Some(value.string("vertex.mangled_name")?),
+                        4 => vertex.demangled = Some(value.string("vertex.demangled_name")?),
+                        5 => vertex.library = Some(value.index("vertex.library_index")?),
+                        _ => {}
+                    }
+                    Ok(())
+                })?;
+                export.vertices.push(vertex);
+            }
+            2 => {
+                let (mut source, mut target) = (0, 0);
+                each_field(value.bytes("call_graph.edge")?, |field, value| {
+                    match field {
+                        1 => source = value.index("source_vertex_index")?,
+                        2 => target = value.index("target_vertex_index")?,
+                        _ => {}
+                    }
+                    Ok(())
+                })?;
+                export.call_edges.push((source, target));
+            }
+            _ => {}
+        }
+        Ok(())
+    })
+}
+
+fn decode_reference(buf: &[u8]) -> Result<Reference, ImportError> {
+    let mut reference = Reference::default();
+    each_field(buf, |field, value| {
+        match field {
+            1 => reference.instruction = value.index("reference.instruction_index")?,
+            4 => reference.string = Some(value.index("reference.string_table_index")?),
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(reference)
+}
+
+/// `(size, executable)` of a `Section`.
+fn decode_section(buf: &[u8]) -> Result<(u64, bool), ImportError> {
+    let (mut size, mut executable) = (0, false);
+    each_field(buf, |field, value| {
+        match field {
+            2 => size = value.uint("section.size")?,
+            5 => executable = value.uint("section.flag_x")? != 0,
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok((size, executable))
+}
+
+fn decode_library(buf: &[u8]) -> Result<String, ImportError> {
+    let mut name = String::new();
+    each_field(buf, |field, value| {
+        if field == 3 {
+            name = value.string("library.name")?;
+        }
+        Ok(())
+    })?;
+    Ok(name)
+}
@@ -0,0 +1,222 @@
+//! The JSON format of the bundled `BinarySlicerExport.java` Ghidra script, shared by the
+//! Ghidra backend (which runs the script headless) and `import-analysis --format ghidra-json`
+//! (which reads a file the script wrote from an existing Ghidra project).
+
+use serde::Deserialize;
+
+use crate::services::analysis::{
+    build_root_hits, AnalysisOptions, AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind,
+    CallEdge, EvidenceKind, EvidenceRecord, EvidenceSource, FunctionRecord,
+};
+use crate::services::signature::{CallingConvention, FunctionSignature};
+
+/// Map a Ghidra export into the common IR; `options` decide whether strings and imports
+/// become evidence.
+pub(crate) fn map_export(
+    export: GhidraExport,
+    roots: &[String],
+    options: &AnalysisOptions,
+) -> AnalysisResult {
+    let mut functions = Vec::new();
+    let mut call_edges = Vec::new();
+    let mut evidence = Vec::new();
+    let mut basic_blocks = Vec::new();
+
+    for f in export.functions {
+        functions.push(FunctionRecord {
+            address: f.entry,
+            name: f.name.clone(),
+            size: f.size.map(|s| s as u32),
+            in_slice: true,
+            is_boundary: f.thunk,
+            mangled_name: None,
+            signature: f.params.map(|params| FunctionSignature {
+                params,
+                convention: CallingConvention::from_name(
+                    f.calling_convention.as_deref().unwrap_or_default(),
+                ),
+                stack_cleanup: None,
+            }),
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        });
+        if let Some(sig) = f.signature.filter(|s| !s.trim().is_empty()) {
+            evidence.push(EvidenceRecord {
+                address: f.entry,
+                description: format!("signature: {}", sig),
+                kind: Some(EvidenceKind::Other),
+                source: evidence_source("signatures"),
+                run_id: None,
+            });
+        }
+        for call in f.calls {
+            call_edges.push(CallEdge {
+                from: f.entry,
+                to: call.to,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            });
+            let desc = call
+                .name
+                .map(|name| format!("call -> {}", name))
+                .unwrap_or_else(|| format!("call -> 0x{:X}", call.to));
+            evidence.push(EvidenceRecord {
+                address: f.entry,
+                description: desc,
+                kind: Some(EvidenceKind::Call),
+                source: evidence_source("calls"),
+                run_id: None,
+            });
+        }
+        for block in f.blocks {
+            basic_blocks.push(BasicBlock {
+                start: block.start,
+                byte_len: block.size as u32,
+                insn_count: block.instructions,
+                successors: block
+                    .successors
+                    .into_iter()
+                    .map(|s| BlockEdge { target: s.target, kind: parse_flow_kind(&s.kind) })
+                    .collect(),
+            });
+        }
+    }
+
+    if options.include_strings {
+        evidence.extend(export.strings.into_iter().map(|s| EvidenceRecord {
+            address: s.address,
+            description: format!("string: {}", s.value),
+            kind: Some(EvidenceKind::String),
+            source: evidence_source("strings"),
+            run_id: None,
+        }));
+    }
+    if options.include_imports {
+        evidence.extend(export.imports.into_iter().map(|imp| EvidenceRecord {
+            address: imp.address,
+            description: match imp.library {
+                Some(lib) if !lib.is_empty() => format!("import: {} ({})", imp.name, lib),
+                _ => format!("import: {}", imp.name),
+            },
+            kind: Some(EvidenceKind::Import),
+            source: evidence_source("imports"),
+            run_id: None,
+        }));
+    }
+
+    let root_hits = build_root_hits(roots, &functions);
+    AnalysisResult {
+        functions,
+        call_edges,
+        evidence,
+        basic_blocks,
+        roots: roots.to_vec(),
+        root_hits,
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: export.text_bytes.filter(|n| *n > 0),
+    }
+}
+
+/// Provenance of rows from one of the export's sections.
+fn evidence_source(pass: &str) -> Option<EvidenceSource> {
+    Some(EvidenceSource::new("ghidra", pass))
+}
+
+fn parse_flow_kind(kind: &str) -> BlockEdgeKind {
+    match kind {
+        "jump" => BlockEdgeKind::Jump,
+        "cjump" => BlockEdgeKind::ConditionalJump,
+        "ijump" => BlockEdgeKind::IndirectJump,
+        "call" => BlockEdgeKind::Call,
+        "icall" => BlockEdgeKind::IndirectCall,
+        _ => BlockEdgeKind::Fallthrough,
+    }
+}
+
+/// JSON written by `BinarySlicerExport.java`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct GhidraExport {
+    #[serde(default)]
+    pub(crate) program: Option<String>,
+    /// Ghidra language ID, e.g. `x86:LE:64:default`.
+    #[serde(default)]
+    pub(crate) language: Option<String>,
+    /// SHA-256 of the program's original file (older scripts omit it).
+    #[serde(default)]
+    pub(crate) sha256: Option<String>,
+    /// Size of the initialized executable memory blocks (older scripts omit it).
+    #[serde(default)]
+    pub(crate) text_bytes: Option<u64>,
+    #[serde(default)]
+    functions: Vec<GhidraFunction>,
+    #[serde(default)]
+    strings: Vec<GhidraString>,
+    #[serde(default)]
+    imports: Vec<GhidraImport>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhidraFunction {
+    entry: u64,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    params: Option<u32>,
+    #[serde(default)]
+    calling_convention: Option<String>,
+    #[serde(default)]
+    thunk: bool,
+    #[serde(default)]
+    calls: Vec<GhidraCall>,
+    #[serde(default)]
+    blocks: Vec<GhidraBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhidraCall {
+    to: u64,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhidraBlock {
+    start: u64,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    instructions: Option<u32>,
+    #[serde(default)]
+    successors: Vec<GhidraSuccessor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhidraSuccessor {
+    target: u64,
+    #[serde(default)]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhidraString {
+    address: u64,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhidraImport {
+    name: String,
+    #[serde(default)]
+    address: u64,
+    #[serde(default)]
+    library: Option<String>,
+}
@@ -0,0 +1,406 @@
+//! Ghidra's XML export (`File > Export Program...` as "XML"), read by `import-analysis --format
+//! ghidra-xml` and the `ghidra-xml` backend without running Ghidra or Java.
+//!
+//! Functions come from `FUNCTIONS` (entry, name, body ranges, prototype comment), names of
+//! unnamed functions from `SYMBOL_TABLE`, and call edges, xrefs, and imports from the
+//! references under `MARKUP`. The exporter writes the memory blocks' bytes to a companion
+//! `.bytes` file named in `MEMORY_MAP`; when it sits next to the XML, the strings defined under
+//! `DATA` become evidence too.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+use sha2::{Digest, Sha256};
+
+use super::{ImportError, ImportedAnalysis};
+use crate::services::analysis::{
+    AnalysisOptions, AnalysisResult, CallEdge, EvidenceKind, EvidenceRecord, EvidenceSource,
+    FunctionRecord, XrefKind, XrefRecord,
+};
+
+/// Name of the backend, and of the evidence source of rows mapped from an XML export.
+pub const BACKEND_NAME: &str = "ghidra-xml";
+
+/// Address spaces that do not map program memory (externals, stack, registers, p-code).
+const NON_MEMORY_SPACES: &[&str] =
+    &["EXTERNAL", "OTHER", "stack", "register", "const", "unique", "HASH", "join"];
+
+/// Version a run records for an export: a prefix of its SHA-256, so cached results follow the
+/// file's contents rather than its path.
+pub fn export_version(data: &[u8]) -> String {
+    let digest: String = Sha256::digest(data).iter().take(6).map(|b| format!("{b:02x}")).collect();
+    format!("export {digest}")
+}
+
+/// Parse an XML export into the common IR; `path` is where it was read from, for finding the
+/// `.bytes` file (without one, no strings are read). Every function starts in the slice.
+pub fn parse(
+    data: &[u8],
+    path: Option<&Path>,
+    options: &AnalysisOptions,
+) -> Result<ImportedAnalysis, ImportError> {
+    let text = std::str::from_utf8(data).map_err(|e| ImportError::Xml(e.to_string()))?;
+    let doc = Document::parse(text).map_err(|e| ImportError::Xml(e.to_string()))?;
+    let program = doc.root_element();
+    if !program.has_tag_name("PROGRAM") {
+        return Err(ImportError::Xml(format!(
+            "root element is <{}>, expected <PROGRAM>",
+            program.tag_name().name()
+        )));
+    }
+    let processor = child(program, "PROCESSOR");
+    let big_endian = processor.and_then(|p| p.attribute("ENDIAN")) == Some("big");
+    let memory = MemoryMap::parse(program, path.and_then(Path::parent));
+
+    let mut functions = Vec::new();
+    let mut bodies = Vec::new();
+    let mut evidence = Vec::new();
+    for f in section(program, "FUNCTIONS", "FUNCTION") {
+        let Some(entry) = f.attribute("ENTRY_POINT").and_then(parse_address) else {
+            continue;
+        };
+        let ranges: Vec<(u64, u64)> = elements(f, "ADDRESS_RANGE")
+            .filter_map(|r| {
+                let start = parse_address(r.attribute("START")?)?;
+                let end = parse_address(r.attribute("END")?)?;
+                (end >= start).then_some((start, end))
+            })
+            .collect();
+        let size = (!ranges.is_empty())
+            .then(|| ranges.iter().map(|(start, end)| end - start + 1).sum::<u64>())
+            .and_then(|size| u32::try_from(size).ok());
+        functions.push(FunctionRecord {
+            address: entry,
+            name: f.attribute("NAME").filter(|n| !n.is_empty()).map(str::to_string),
+            size,
+            in_slice: true,
+            is_boundary: f.attribute("LIBRARY_FUNCTION") == Some("y"),
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        });
+        bodies.push((entry, ranges));
+        let prototype = child(f, "TYPEINFO_CMT").and_then(|c| c.text()).map(str::trim);
+        if let Some(prototype) = prototype.filter(|p| !p.is_empty()) {
+            evidence.push(EvidenceRecord {
+                address: entry,
+                description: format!("signature: {}", prototype),
+                kind: Some(EvidenceKind::Other),
+                source: evidence_source("signatures"),
+                run_id: None,
+            });
+        }
+    }
+    name_from_symbols(program, &mut functions);
+
+    let entries: HashMap<u64, Option<&str>> =
+        functions.iter().map(|f| (f.address, f.name.as_deref())).collect();
+    let containing = |address: u64| {
+        bodies.iter().find_map(|(entry, ranges)| {
+            ranges.iter().any(|(start, end)| (*start..=*end).contains(&address)).then_some(*entry)
+        })
+    };
+
+    let mut imports = Vec::new();
+    let mut seen_imports = HashSet::new();
+    for r in section(program, "MARKUP", "EXT_LIBRARY_REFERENCE") {
+        let (Some(address), Some(label)) = (
+            r.attribute("ADDRESS").and_then(parse_address),
+            r.attribute("LIB_LABEL").filter(|l| !l.is_empty()),
+        ) else {
+            continue;
+        };
+        if seen_imports.insert((address, label)) {
+            imports.push((address, label, r.attribute("LIB_PROG_NAME").filter(|l| !l.is_empty())));
+        }
+    }
+    let import_slots: HashMap<u64, &str> =
+        imports.iter().map(|(a, label, _)| (*a, *label)).collect();
+
+    let mut calls = BTreeSet::new();
+    let mut xrefs = Vec::new();
+    for r in section(program, "MARKUP", "MEMORY_REFERENCE") {
+        let (Some(from), Some(to)) = (
+            r.attribute("ADDRESS").and_then(parse_address),
+            r.attribute("TO_ADDRESS").and_then(parse_address),
+        ) else {
+            continue;
+        };
+        let Some(caller) = containing(from) else {
+            continue;
+        };
+        let is_call = entries.contains_key(&to) || import_slots.contains_key(&to);
+        if is_call {
+            calls.insert((caller, to));
+        }
+        xrefs.push(XrefRecord {
+            from,
+            to,
+            kind: if is_call { XrefKind::Call } else { XrefKind::Memory },
+            section: memory.block_name(to).map(str::to_string),
+            preview: None,
+        });
+    }
+    let mut call_edges = Vec::new();
+    for (from, to) in calls {
+        call_edges.push(CallEdge {
+            from,
+            to,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
+        });
+        let name = import_slots.get(&to).copied().or_else(|| entries.get(&to).copied().flatten());
+        evidence.push(EvidenceRecord {
+            address: from,
+            description: match name {
+                Some(name) => format!("call -> {}", name),
+                None => format!("call -> 0x{:X}", to),
+            },
+            kind: Some(EvidenceKind::Call),
+            source: evidence_source("calls"),
+            run_id: None,
+        });
+    }
+
+    if options.include_strings {
+        for d in section(program, "DATA", "DEFINED_DATA") {
+            let (Some(address), Some(datatype), Some(size)) = (
+                d.attribute("ADDRESS").and_then(parse_address),
+                d.attribute("DATATYPE"),
+                d.attribute("SIZE").and_then(parse_number),
+            ) else {
+                continue;
+            };
+            let Some(value) = memory
+                .read(address, size)
+                .and_then(|bytes| decode_string(datatype, &bytes, big_endian))
+            else {
+                continue;
+            };
+            evidence.push(EvidenceRecord {
+                address,
+                description: format!("string: {}", value),
+                kind: Some(EvidenceKind::String),
+                source: evidence_source("strings"),
+                run_id: None,
+            });
+        }
+    }
+    if options.include_imports {
+        evidence.extend(imports.iter().map(|(address, label, library)| EvidenceRecord {
+            address: *address,
+            description: match library {
+                Some(lib) => format!("import: {} ({})", label, lib),
+                None => format!("import: {}", label),
+            },
+            kind: Some(EvidenceKind::Import),
+            source: evidence_source("imports"),
+            run_id: None,
+        }));
+    }
+
+    Ok(ImportedAnalysis {
+        result: AnalysisResult {
+            functions,
+            call_edges,
+            evidence,
+            basic_blocks: Vec::new(),
+            roots: Vec::new(),
+            root_hits: Vec::new(),
+            sub_slices: Vec::new(),
+            xrefs,
+            backend_version: None,
+            backend_path: None,
+            text_bytes: Some(memory.executable_bytes).filter(|n| *n > 0),
+        },
+        executable_id: None,
+        executable_name: program.attribute("NAME").map(str::to_string),
+        architecture: processor.and_then(|p| p.attribute("LANGUAGE_PROVIDER")).map(str::to_string),
+    })
+}
+
+/// Name functions the export left unnamed (or gave a default `FUN_` name) after a symbol at
+/// their entry, preferring the primary one.
+fn name_from_symbols(program: Node, functions: &mut [FunctionRecord]) {
+    let mut names: HashMap<u64, (bool, &str)> = HashMap::new();
+    for s in section(program, "SYMBOL_TABLE", "SYMBOL") {
+        let (Some(address), Some(name)) = (
+            s.attribute("ADDRESS").and_then(parse_address),
+            s.attribute("NAME").filter(|n| !n.is_empty() && !is_default_name(n)),
+        ) else {
+            continue;
+        };
+        let primary = s.attribute("PRIMARY") == Some("y");
+        let entry = names.entry(address).or_insert((primary, name));
+        if primary && !entry.0 {
+            *entry = (primary, name);
+        }
+    }
+    for f in functions {
+        if f.name.as_deref().is_none_or(is_default_name) {
+            if let Some((_, name)) = names.get(&f.address) {
+                f.name = Some(name.to_string());
+            }
+        }
+    }
+}
+
+/// Names Ghidra makes up for functions nobody named.
+fn is_default_name(name: &str) -> bool {
+    name.strip_prefix("FUN_").is_some_and(|rest| rest.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Text of a defined string, up to its first NUL; `None` for other data types and empty text.
+fn decode_string(datatype: &str, bytes: &[u8], big_endian: bool) -> Option<String> {
+    let datatype = datatype.to_ascii_lowercase();
+    let text = if datatype.contains("unicode32") || datatype.starts_with("pascal") {
+        return None;
+    } else if datatype.contains("unicode") || datatype.starts_with("wchar") {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| {
+                if big_endian {
+                    u16::from_be_bytes([c[0], c[1]])
+                } else {
+                    u16::from_le_bytes([c[0], c[1]])
+                }
+            })
+            .take_while(|u| *u != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else if datatype.contains("string") || datatype.starts_with("char[") {
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    } else {
+        return None;
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// `MEMORY_MAP` blocks and the bytes of those whose contents were exported.
+struct MemoryMap {
+    blocks: Vec<Block>,
+    files: HashMap<String, Vec<u8>>,
+    executable_bytes: u64,
+}
+
+struct Block {
+    name: String,
+    start: u64,
+    len: u64,
+    /// `(start, len, file, offset)` of each exported run of the block's bytes.
+    contents: Vec<(u64, u64, String, u64)>,
+}
+
+impl MemoryMap {
+    /// Read the map under `program`, loading the content files it names from `dir`.
+    fn parse(program: Node, dir: Option<&Path>) -> Self {
+        let mut map = MemoryMap { blocks: Vec::new(), files: HashMap::new(), executable_bytes: 0 };
+        for s in section(program, "MEMORY_MAP", "MEMORY_SECTION") {
+            let (Some(start), Some(len)) = (
+                s.attribute("START_ADDR").and_then(parse_address),
+                s.attribute("LENGTH").and_then(parse_number),
+            ) else {
+                continue;
+            };
+            if s.attribute("PERMISSIONS").is_some_and(|p| p.contains('x')) {
+                map.executable_bytes += len;
+            }
+            let mut contents = Vec::new();
+            for c in elements(s, "MEMORY_CONTENTS") {
+                let Some(file) = c.attribute("FILE_NAME") else {
+                    continue;
+                };
+                let c_start = c.attribute("START_ADDR").and_then(parse_address).unwrap_or(start);
+                let c_len = c.attribute("LENGTH").and_then(parse_number).unwrap_or(len);
+                let offset = c.attribute("FILE_OFFSET").and_then(parse_number).unwrap_or(0);
+                contents.push((c_start, c_len, file.to_string(), offset));
+                if let Some(dir) = dir.filter(|_| !map.files.contains_key(file)) {
+                    // Only the file name counts: the export sits next to its bytes.
+                    let name = Path::new(file).file_name().unwrap_or_default();
+                    if let Ok(bytes) = fs::read(dir.join(name)) {
+                        map.files.insert(file.to_string(), bytes);
+                    }
+                }
+            }
+            let name = s.attribute("NAME").unwrap_or_default().to_string();
+            map.blocks.push(Block { name, start, len, contents });
+        }
+        map
+    }
+
+    fn block_name(&self, address: u64) -> Option<&str> {
+        self.blocks
+            .iter()
+            .find(|b| address >= b.start && address - b.start < b.len)
+            .map(|b| b.name.as_str())
+            .filter(|n| !n.is_empty())
+    }
+
+    /// `len` bytes at `address`, when one exported run holds them all.
+    fn read(&self, address: u64, len: u64) -> Option<Vec<u8>> {
+        self.blocks.iter().flat_map(|b| &b.contents).find_map(|(start, run_len, file, offset)| {
+            let delta = address.checked_sub(*start)?;
+            if delta.checked_add(len)? > *run_len {
+                return None;
+            }
+            let from = usize::try_from(offset.checked_add(delta)?).ok()?;
+            let bytes = self.files.get(file)?;
+            bytes.get(from..from.checked_add(usize::try_from(len).ok()?)?).map(<[u8]>::to_vec)
+        })
+    }
+}
+
+/// Provenance of rows from one part of the export.
+fn evidence_source(pass: &str) -> Option<EvidenceSource> {
+    Some(EvidenceSource::new(BACKEND_NAME, pass))
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn elements<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+/// The `item` elements of every `section` element under `program`.
+fn section<'a, 'input: 'a>(
+    program: Node<'a, 'input>,
+    section: &'a str,
+    item: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    elements(program, section).flat_map(move |s| elements(s, item))
+}
+
+/// A hex address as the exporter writes it (`00401000`, optionally `0x`-prefixed or qualified
+/// by its space, `ram:00401000`); `None` outside program memory.
+fn parse_address(value: &str) -> Option<u64> {
+    let value = match value.rsplit_once(':') {
+        Some((space, _)) if NON_MEMORY_SPACES.contains(&space.trim_end_matches(':')) => {
+            return None;
+        }
+        Some((_, offset)) => offset,
+        None => value,
+    };
+    let value = value.trim();
+    let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// A size or offset: `0x`-prefixed hex, or decimal.
+fn parse_number(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
@@ -0,0 +1,148 @@
+//! Seed analysis results from other tools' exports (`import-analysis`).
+//!
+//! Teams that already analyzed a binary elsewhere can carve slices from that work instead of
+//! re-running a backend:
+//! - `binexport`: BinExport2 files written by the BinExport plugin for IDA Pro, Ghidra, or
+//!   Binary Ninja (see [`binexport`]).
+//! - `ghidra-json`: the JSON of the bundled `BinarySlicerExport.java` script, run from an
+//!   existing Ghidra project (see [`ghidra`]); the Ghidra backend reads the same format.
+//! - `ghidra-xml`: Ghidra's own XML export (see [`ghidra_xml`]); the `ghidra-xml` backend
+//!   reads the same format.
+//!
+//! All map into the common IR like a backend would; roots are matched against the imported
+//! functions. Symbol maps (`import-symbols`, see [`symbols`]) only name functions; PDBs
+//! (see [`pdb`]) also give them sizes and types.
+
+pub mod binexport;
+pub mod ghidra;
+pub mod ghidra_xml;
+pub mod pdb;
+pub mod symbols;
+
+use std::fmt;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::services::analysis::{build_root_hits, AnalysisOptions, AnalysisResult};
+
+/// File formats `import-analysis` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    BinExport,
+    GhidraJson,
+    GhidraXml,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] =
+        [ImportFormat::BinExport, ImportFormat::GhidraJson, ImportFormat::GhidraXml];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::BinExport => "binexport",
+            ImportFormat::GhidraJson => "ghidra-json",
+            ImportFormat::GhidraXml => "ghidra-xml",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, ImportError> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| ImportError::UnknownFormat(value.to_string()))
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Unknown import format '{0}' (expected binexport, ghidra-json, or ghidra-xml)")]
+    UnknownFormat(String),
+    #[error("Invalid BinExport file: {0}")]
+    BinExport(String),
+    #[error("Invalid Ghidra export JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid Ghidra XML export: {0}")]
+    Xml(String),
+    #[error("Unknown symbol map format '{0}' (expected map or json)")]
+    UnknownSymbolFormat(String),
+    #[error("Invalid symbol JSON: {0}")]
+    SymbolJson(String),
+    #[error("Symbol '{name}' at 0x{address:X} lies below the base address 0x{base:X}")]
+    SymbolBelowBase { name: String, address: u64, base: u64 },
+    #[error("No symbols found in the symbol map")]
+    NoSymbols,
+    #[error("Invalid PDB: {0}")]
+    Pdb(String),
+    #[error("PDB support is not compiled in (enable the pdb-symbols feature)")]
+    PdbUnsupported,
+}
+
+#[cfg(feature = "pdb-symbols")]
+impl From<::pdb::Error> for ImportError {
+    fn from(err: ::pdb::Error) -> Self {
+        ImportError::Pdb(err.to_string())
+    }
+}
+
+/// An imported analysis plus what the export says about the binary it came from.
+#[derive(Debug, Clone)]
+pub struct ImportedAnalysis {
+    pub result: AnalysisResult,
+    /// Hash identifying the analyzed file (BinExport `executable_id`, the Ghidra script's
+    /// `sha256`).
+    pub executable_id: Option<String>,
+    pub executable_name: Option<String>,
+    pub architecture: Option<String>,
+}
+
+impl ImportedAnalysis {
+    /// Whether the export was made from the binary with SHA-256 `hash`; `None` when the export
+    /// carries no SHA-256 to compare (older exporters record SHA-1 or nothing).
+    pub fn matches_hash(&self, hash: &str) -> Option<bool> {
+        let id = self.executable_id.as_deref()?;
+        (id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| id.eq_ignore_ascii_case(hash))
+    }
+}
+
+/// Parse `data` in `format` and match `roots` against the imported functions. `path` is where
+/// the export was read from; a `ghidra-xml` export's strings are read from the `.bytes` file
+/// next to it.
+///
+/// Strings and imports always become evidence; every function starts in the slice, as with
+/// the rizin and Ghidra backends.
+pub fn import_analysis(
+    format: ImportFormat,
+    data: &[u8],
+    path: Option<&Path>,
+    roots: &[String],
+) -> Result<ImportedAnalysis, ImportError> {
+    let options =
+        AnalysisOptions { include_imports: true, include_strings: true, ..Default::default() };
+    let mut imported = match format {
+        ImportFormat::BinExport => binexport::parse(data, &options)?,
+        ImportFormat::GhidraXml => ghidra_xml::parse(data, path, &options)?,
+        ImportFormat::GhidraJson => {
+            let export: ghidra::GhidraExport = serde_json::from_slice(data)?;
+            let executable_id = export.sha256.clone();
+            let executable_name = export.program.clone();
+            let architecture = export.language.clone();
+            ImportedAnalysis {
+                result: ghidra::map_export(export, roots, &options),
+                executable_id,
+                executable_name,
+                architecture,
+            }
+        }
+    };
+    imported.result.roots = roots.to_vec();
+    imported.result.root_hits = build_root_hits(roots, &imported.result.functions);
+    Ok(imported)
+}
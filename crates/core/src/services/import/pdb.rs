@@ -0,0 +1,306 @@
+//! Program databases of Windows binaries (`add-binary --pdb`, or a `.pdb` next to the PE).
+//!
+//! MSVC keeps a build's symbols in a separate PDB. Procedures from its module streams give
+//! functions their undecorated name, their size, and their type; function publics add the
+//! decorated names, and name functions no module describes (by their decorated name).
+//!
+//! A PDB describes exactly one build: the one whose CodeView debug record carries the same
+//! GUID and age ([`PdbIdentity`]). Callers compare the two before using a PDB, since a stale
+//! one names the wrong code. Reading PDBs needs the `pdb-symbols` feature.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::import::ImportError;
+
+/// GUID and age that tie a PDB to the build it was written for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PdbIdentity {
+    /// Lowercase, hyphenated (`1b2c3d4e-…`).
+    pub guid: String,
+    pub age: u32,
+}
+
+impl PdbIdentity {
+    /// Identity from a PE CodeView (`RSDS`) record, whose GUID is stored in the Windows
+    /// `GUID` layout (the first three fields little-endian).
+    pub fn from_codeview(signature: &[u8; 16], age: u32) -> Self {
+        let s = signature;
+        let guid = format!(
+            "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            u32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+            u16::from_le_bytes([s[4], s[5]]),
+            u16::from_le_bytes([s[6], s[7]]),
+            s[8],
+            s[9],
+            s[10],
+            s[11],
+            s[12],
+            s[13],
+            s[14],
+            s[15],
+        );
+        PdbIdentity { guid, age }
+    }
+
+    /// Whether both identities name the same build.
+    pub fn matches(&self, other: &PdbIdentity) -> bool {
+        self.guid.eq_ignore_ascii_case(&other.guid) && self.age == other.age
+    }
+}
+
+impl fmt::Display for PdbIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.guid, self.age)
+    }
+}
+
+/// A function described by a PDB.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PdbFunction {
+    /// RVA as read; a VA once the symbols are [rebased](PdbSymbols::rebase).
+    pub address: u64,
+    pub name: String,
+    /// Decorated public symbol, when it differs from `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mangled_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    /// Function type, such as `void (Game::*)(float)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+}
+
+/// The functions of a PDB, by address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PdbSymbols {
+    pub identity: PdbIdentity,
+    pub functions: Vec<PdbFunction>,
+}
+
+impl PdbSymbols {
+    /// Turn the RVAs into VAs of an image loaded at `image_base`.
+    pub fn rebase(mut self, image_base: u64) -> Self {
+        for function in &mut self.functions {
+            function.address = image_base.wrapping_add(function.address);
+        }
+        self
+    }
+}
+
+/// GUID and age of the PDB at `path`, without reading its symbols.
+pub fn read_pdb_identity(path: &Path) -> Result<PdbIdentity, ImportError> {
+    #[cfg(feature = "pdb-symbols")]
+    {
+        parse::identity(path)
+    }
+    #[cfg(not(feature = "pdb-symbols"))]
+    {
+        let _ = path;
+        Err(ImportError::PdbUnsupported)
+    }
+}
+
+/// Functions of the PDB at `path`, sorted by RVA.
+pub fn read_pdb(path: &Path) -> Result<PdbSymbols, ImportError> {
+    #[cfg(feature = "pdb-symbols")]
+    {
+        parse::symbols(path)
+    }
+    #[cfg(not(feature = "pdb-symbols"))]
+    {
+        let _ = path;
+        Err(ImportError::PdbUnsupported)
+    }
+}
+
+#[cfg(feature = "pdb-symbols")]
+mod parse {
+    use std::collections::btree_map::Entry;
+    use std::collections::BTreeMap;
+    use std::fs::File;
+    use std::path::Path;
+
+    use pdb::{
+        FallibleIterator, PointerMode, PrimitiveKind, SymbolData, TypeData, TypeFinder, TypeIndex,
+        PDB,
+    };
+
+    use super::{PdbFunction, PdbIdentity, PdbSymbols};
+    use crate::services::import::ImportError;
+
+    /// Nesting limit when spelling out a type (self-referential records exist in the wild).
+    const MAX_TYPE_DEPTH: usize = 8;
+
+    fn open(path: &Path) -> Result<PDB<'static, File>, ImportError> {
+        let file = File::open(path).map_err(|err| ImportError::Pdb(err.to_string()))?;
+        Ok(PDB::open(file)?)
+    }
+
+    pub(super) fn identity(path: &Path) -> Result<PdbIdentity, ImportError> {
+        identity_of(&mut open(path)?)
+    }
+
+    /// The DBI stream's age is the one the linker wrote into the image; the PDB info
+    /// stream's age also counts later rewrites of the file.
+    fn identity_of(pdb: &mut PDB<'static, File>) -> Result<PdbIdentity, ImportError> {
+        let info = pdb.pdb_information()?;
+        let age = pdb.debug_information()?.age().unwrap_or(info.age);
+        Ok(PdbIdentity { guid: info.guid.to_string(), age })
+    }
+
+    pub(super) fn symbols(path: &Path) -> Result<PdbSymbols, ImportError> {
+        let mut pdb = open(path)?;
+        let identity = identity_of(&mut pdb)?;
+        let address_map = pdb.address_map()?;
+        let types = pdb.type_information()?;
+        let mut finder = types.finder();
+        let mut type_iter = types.iter();
+        while type_iter.next()?.is_some() {
+            finder.update(&type_iter);
+        }
+
+        let mut functions: BTreeMap<u64, PdbFunction> = BTreeMap::new();
+        let dbi = pdb.debug_information()?;
+        let mut modules = dbi.modules()?;
+        while let Some(module) = modules.next()? {
+            let Some(info) = pdb.module_info(&module)? else {
+                continue;
+            };
+            let mut symbols = info.symbols()?;
+            while let Some(symbol) = symbols.next()? {
+                let Ok(SymbolData::Procedure(procedure)) = symbol.parse() else {
+                    continue;
+                };
+                let Some(rva) = procedure.offset.to_rva(&address_map) else {
+                    continue;
+                };
+                let address = u64::from(rva.0);
+                functions.entry(address).or_insert_with(|| PdbFunction {
+                    address,
+                    name: procedure.name.to_string().into_owned(),
+                    mangled_name: None,
+                    size: (procedure.len > 0).then_some(procedure.len),
+                    type_name: format_type(&finder, procedure.type_index, 0),
+                });
+            }
+        }
+
+        let globals = pdb.global_symbols()?;
+        let mut symbols = globals.iter();
+        while let Some(symbol) = symbols.next()? {
+            let Ok(SymbolData::Public(public)) = symbol.parse() else {
+                continue;
+            };
+            let Some(rva) = public.offset.to_rva(&address_map).filter(|_| public.function) else {
+                continue;
+            };
+            let address = u64::from(rva.0);
+            let name = public.name.to_string().into_owned();
+            match functions.entry(address) {
+                Entry::Occupied(mut slot) => {
+                    let function = slot.get_mut();
+                    if function.mangled_name.is_none() && function.name != name {
+                        function.mangled_name = Some(name);
+                    }
+                }
+                Entry::Vacant(slot) => {
+                    slot.insert(PdbFunction {
+                        address,
+                        name,
+                        mangled_name: None,
+                        size: None,
+                        type_name: None,
+                    });
+                }
+            }
+        }
+        Ok(PdbSymbols { identity, functions: functions.into_values().collect() })
+    }
+
+    /// C++ spelling of a type, or `None` for records this does not spell out.
+    fn format_type(finder: &TypeFinder<'_>, index: TypeIndex, depth: usize) -> Option<String> {
+        if depth > MAX_TYPE_DEPTH {
+            return None;
+        }
+        let inner = |index| format_type(finder, index, depth + 1);
+        let name = match finder.find(index).ok()?.parse().ok()? {
+            TypeData::Primitive(primitive) => {
+                let base = primitive_name(primitive.kind);
+                match primitive.indirection {
+                    Some(_) => format!("{base}*"),
+                    None => base.to_string(),
+                }
+            }
+            TypeData::Class(class) => class.name.to_string().into_owned(),
+            TypeData::Union(union) => union.name.to_string().into_owned(),
+            TypeData::Enumeration(enumeration) => enumeration.name.to_string().into_owned(),
+            TypeData::Modifier(modifier) if modifier.constant => {
+                format!("const {}", inner(modifier.underlying_type)?)
+            }
+            TypeData::Modifier(modifier) => inner(modifier.underlying_type)?,
+            TypeData::Pointer(pointer) => {
+                let sigil = match pointer.attributes.pointer_mode() {
+                    PointerMode::LValueReference => "&",
+                    PointerMode::RValueReference => "&&",
+                    _ => "*",
+                };
+                format!("{}{sigil}", inner(pointer.underlying_type)?)
+            }
+            TypeData::Array(array) => format!("{}[]", inner(array.element_type)?),
+            TypeData::Procedure(procedure) => {
+                let ret = procedure.return_type.and_then(inner);
+                let args = format_arguments(finder, procedure.argument_list, depth + 1);
+                format!("{} ({args})", ret.as_deref().unwrap_or("void"))
+            }
+            TypeData::MemberFunction(method) => {
+                let ret = inner(method.return_type);
+                let class = inner(method.class_type)?;
+                let args = format_arguments(finder, method.argument_list, depth + 1);
+                format!("{} ({class}::*)({args})", ret.as_deref().unwrap_or("void"))
+            }
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    fn format_arguments(finder: &TypeFinder<'_>, index: TypeIndex, depth: usize) -> String {
+        let Some(TypeData::ArgumentList(list)) =
+            finder.find(index).ok().and_then(|item| item.parse().ok())
+        else {
+            return String::new();
+        };
+        list.arguments
+            .into_iter()
+            .map(|arg| format_type(finder, arg, depth).unwrap_or_else(|| "?".to_string()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn primitive_name(kind: PrimitiveKind) -> &'static str {
+        match kind {
+            PrimitiveKind::NoType | PrimitiveKind::Void => "void",
+            PrimitiveKind::Char | PrimitiveKind::RChar | PrimitiveKind::I8 => "char",
+            PrimitiveKind::UChar | PrimitiveKind::U8 => "unsigned char",
+            PrimitiveKind::WChar => "wchar_t",
+            PrimitiveKind::RChar16 => "char16_t",
+            PrimitiveKind::RChar32 => "char32_t",
+            PrimitiveKind::Short | PrimitiveKind::I16 => "short",
+            PrimitiveKind::UShort | PrimitiveKind::U16 => "unsigned short",
+            PrimitiveKind::Long => "long",
+            PrimitiveKind::ULong => "unsigned long",
+            PrimitiveKind::I32 => "int",
+            PrimitiveKind::U32 => "unsigned int",
+            PrimitiveKind::Quad | PrimitiveKind::I64 => "__int64",
+            PrimitiveKind::UQuad | PrimitiveKind::U64 => "unsigned __int64",
+            PrimitiveKind::F32 => "float",
+            PrimitiveKind::F64 => "double",
+            PrimitiveKind::F80 => "long double",
+            PrimitiveKind::Bool8 => "bool",
+            PrimitiveKind::HRESULT => "HRESULT",
+            _ => "?",
+        }
+    }
+}
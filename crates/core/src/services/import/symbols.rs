@@ -0,0 +1,164 @@
+//! Symbol maps from other tools (`import-symbols`), used to name functions of stripped
+//! binaries.
+//!
+//! - `map`: text symbol listings, one symbol per line. Understood line forms are IDA `.map`
+//!   publics (`0001:00401000  _main`, the offset read as the address as in flat binaries),
+//!   `nm`/`objcopy --syms` style (`0000000000401000 T main`), objcopy `--add-symbol`
+//!   definitions (`main=0x401000,function`), and plain `address name` pairs. Other lines
+//!   (headers, segment tables, undefined symbols, comments) are skipped.
+//! - `json`: an array of `{ "name", "address" }` objects, such as Frida's
+//!   `Module.enumerateExports()` or `enumerateSymbols()`; addresses may be numbers or hex
+//!   strings, and entries whose `type` is not `function` are skipped.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::services::import::ImportError;
+
+/// File formats `import-symbols` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolFormat {
+    Map,
+    Json,
+}
+
+impl SymbolFormat {
+    pub const ALL: [SymbolFormat; 2] = [SymbolFormat::Map, SymbolFormat::Json];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolFormat::Map => "map",
+            SymbolFormat::Json => "json",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, ImportError> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| ImportError::UnknownSymbolFormat(value.to_string()))
+    }
+}
+
+impl fmt::Display for SymbolFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Names read from a symbol map, by address (a later entry for an address wins).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolMap {
+    pub symbols: BTreeMap<u64, String>,
+    /// Lines or entries that were not a symbol definition.
+    pub skipped: usize,
+}
+
+/// Parse a symbol map, subtracting `base` from every address (the module base of a runtime
+/// dump such as Frida's exports; 0 for files that already use the binary's addresses).
+pub fn parse_symbols(
+    format: SymbolFormat,
+    text: &str,
+    base: u64,
+) -> Result<SymbolMap, ImportError> {
+    let entries = match format {
+        SymbolFormat::Map => {
+            text.lines().map(|line| parse_map_line(line.trim())).collect::<Vec<_>>()
+        }
+        SymbolFormat::Json => {
+            let entries: Vec<Value> = serde_json::from_str(text)
+                .map_err(|err| ImportError::SymbolJson(err.to_string()))?;
+            entries.into_iter().map(parse_json_entry).collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    let mut map = SymbolMap::default();
+    for entry in entries {
+        let Some((address, name)) = entry else {
+            map.skipped += 1;
+            continue;
+        };
+        let address = address.checked_sub(base).ok_or_else(|| ImportError::SymbolBelowBase {
+            name: name.clone(),
+            address,
+            base,
+        })?;
+        map.symbols.insert(address, name);
+    }
+    if map.symbols.is_empty() {
+        return Err(ImportError::NoSymbols);
+    }
+    Ok(map)
+}
+
+fn parse_map_line(line: &str) -> Option<(u64, String)> {
+    if line.is_empty() || line.starts_with(['#', ';']) || line.starts_with("//") {
+        return None;
+    }
+    let line = line.strip_prefix("--add-symbol").map(str::trim_start).unwrap_or(line);
+    let definition = line.split_once('=').filter(|(name, _)| !name.contains(char::is_whitespace));
+    if let Some((name, rest)) = definition {
+        let address = rest.split(',').next().unwrap_or_default().trim();
+        return symbol(parse_hex(address)?, name.trim());
+    }
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        [address, name] => {
+            let address = match address.split_once(':') {
+                Some((segment, offset)) if parse_hex(segment).is_some() => parse_hex(offset)?,
+                _ => parse_hex(address)?,
+            };
+            symbol(address, name)
+        }
+        [address, kind, name] if kind.len() == 1 && !kind.eq_ignore_ascii_case("u") => {
+            symbol(parse_hex(address)?, name)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonSymbol {
+    name: Option<String>,
+    address: Option<Value>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+fn parse_json_entry(entry: Value) -> Result<Option<(u64, String)>, ImportError> {
+    let entry: JsonSymbol =
+        serde_json::from_value(entry).map_err(|err| ImportError::SymbolJson(err.to_string()))?;
+    if entry.kind.as_deref().is_some_and(|kind| kind != "function") {
+        return Ok(None);
+    }
+    let (Some(name), Some(address)) = (entry.name, entry.address) else {
+        return Ok(None);
+    };
+    let parsed = match &address {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => parse_hex(s),
+        _ => None,
+    };
+    match parsed {
+        Some(address) => Ok(symbol(address, &name)),
+        None => Err(ImportError::SymbolJson(format!(
+            "invalid address {} for symbol '{}'",
+            address, name
+        ))),
+    }
+}
+
+fn symbol(address: u64, name: &str) -> Option<(u64, String)> {
+    (!name.is_empty()).then(|| (address, name.to_string()))
+}
+
+/// Hex address with or without `0x` (IDA and nm write bare hex).
+fn parse_hex(text: &str) -> Option<u64> {
+    let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    if digits.is_empty() {
+        return None;
+    }
+    u64::from_str_radix(digits, 16).ok()
+}
@@ -0,0 +1,368 @@
+//! Address translation for loaded images: image base, section mapping, and RVA/VA/file
+//! offset conversion.
+//!
+//! Every address a backend reports (function entries, call targets, xrefs, evidence) is a
+//! virtual address in the image's preferred load layout, the same convention rizin and
+//! Ghidra use. PE headers describe sections and exports by RVA (relative to the image
+//! base), ELF and Mach-O by VA; [`ImageLayout`] holds both views so callers convert through
+//! one place instead of re-deriving section math per format.
+
+use crate::services::analysis::AnalysisRequest;
+use crate::services::import::pdb::PdbIdentity;
+
+/// Container format of a parsed image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Elf,
+    Pe,
+    MachO,
+    /// A flat image with no headers (see [`ImageLayout::raw`]).
+    Raw,
+}
+
+/// A section or segment mapped into the image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedRegion {
+    pub name: String,
+    /// Virtual address of the first byte.
+    pub address: u64,
+    /// Size in memory.
+    pub virtual_size: u64,
+    pub file_offset: u64,
+    /// Bytes backed by the file; the rest of the region (up to `virtual_size`) is zero-fill.
+    pub file_size: u64,
+    pub executable: bool,
+}
+
+impl MappedRegion {
+    pub fn end(&self) -> u64 {
+        self.address.saturating_add(self.virtual_size)
+    }
+
+    pub fn contains(&self, va: u64) -> bool {
+        va >= self.address && va < self.end()
+    }
+
+    /// File offset of `va`, or `None` outside the region or in its zero-fill tail.
+    pub fn file_offset_of(&self, va: u64) -> Option<u64> {
+        let delta = va.checked_sub(self.address)?;
+        (delta < self.file_size.min(self.virtual_size)).then(|| self.file_offset + delta)
+    }
+
+    /// File range `[start, end)` for `size` bytes at `va` (the rest of the file-backed part
+    /// of the region without a size), clamped to a file of `file_len` bytes.
+    pub fn file_range(
+        &self,
+        va: u64,
+        size: Option<u64>,
+        file_len: usize,
+    ) -> Option<(usize, usize)> {
+        let start = self.file_offset_of(va)?;
+        let available = self.file_size.min(self.virtual_size) - (va - self.address);
+        let end = start.saturating_add(size.unwrap_or(available).min(available));
+        let end = end.min(file_len as u64);
+        (start < end).then_some((start as usize, end as usize))
+    }
+
+    /// The region's file-backed bytes in `file`, cut short where the file ends; `None` when
+    /// the region has no bytes in the file.
+    pub fn file_bytes<'a>(&self, file: &'a [u8]) -> Option<&'a [u8]> {
+        let start = usize::try_from(self.file_offset).ok()?;
+        let size = usize::try_from(self.file_size.min(self.virtual_size)).ok()?;
+        let data = file.get(start..start.saturating_add(size).min(file.len()))?;
+        (!data.is_empty()).then_some(data)
+    }
+}
+
+/// Memory layout of an image at its preferred base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageLayout {
+    pub format: ImageFormat,
+    /// Preferred load address: the PE `ImageBase`, the lowest ELF `PT_LOAD` address, or the
+    /// Mach-O `__TEXT` address (0 when the image has none, e.g. relocatable objects).
+    pub image_base: u64,
+    /// Entry point VA, when the image declares one.
+    pub entry: Option<u64>,
+    /// Sections in header order (for ELF, index `i` is section header `i`).
+    pub sections: Vec<MappedRegion>,
+    /// Loadable segments (ELF `PT_LOAD`, Mach-O segments; empty for PE).
+    pub segments: Vec<MappedRegion>,
+}
+
+impl ImageLayout {
+    /// A raw image of `len` bytes loaded at `load_address`: one executable segment covering
+    /// the whole file, with the load address as the image base.
+    pub fn raw(load_address: u64, len: u64) -> Self {
+        ImageLayout {
+            format: ImageFormat::Raw,
+            image_base: load_address,
+            entry: None,
+            sections: Vec::new(),
+            segments: vec![MappedRegion {
+                name: "raw".into(),
+                address: load_address,
+                virtual_size: len,
+                file_offset: 0,
+                file_size: len,
+                executable: true,
+            }],
+        }
+    }
+
+    pub fn rva_to_va(&self, rva: u64) -> u64 {
+        self.image_base.wrapping_add(rva)
+    }
+
+    /// RVA of `va`, or `None` for addresses below the image base.
+    pub fn va_to_rva(&self, va: u64) -> Option<u64> {
+        va.checked_sub(self.image_base)
+    }
+
+    /// Section containing `va`, falling back to segments for section-less images.
+    pub fn region_for(&self, va: u64) -> Option<&MappedRegion> {
+        self.sections
+            .iter()
+            .find(|s| s.virtual_size > 0 && s.contains(va))
+            .or_else(|| self.segments.iter().find(|s| s.contains(va)))
+    }
+
+    /// File offset holding the byte at `va`, or `None` when it is unmapped or zero-fill.
+    pub fn va_to_file_offset(&self, va: u64) -> Option<u64> {
+        self.region_for(va)?.file_offset_of(va)
+    }
+
+    pub fn rva_to_file_offset(&self, rva: u64) -> Option<u64> {
+        self.va_to_file_offset(self.rva_to_va(rva))
+    }
+
+    /// VA the byte at `offset` is loaded to, or `None` when no region maps it.
+    pub fn file_offset_to_va(&self, offset: u64) -> Option<u64> {
+        self.sections
+            .iter()
+            .chain(&self.segments)
+            .filter(|s| s.virtual_size > 0)
+            .find(|s| {
+                offset >= s.file_offset && offset - s.file_offset < s.file_size.min(s.virtual_size)
+            })
+            .map(|s| s.address + (offset - s.file_offset))
+    }
+
+    /// File range for `size` bytes at `va`, bounded by the file-backed part of its region.
+    pub fn file_range(
+        &self,
+        va: u64,
+        size: Option<u64>,
+        file_len: usize,
+    ) -> Option<(usize, usize)> {
+        self.region_for(va)?.file_range(va, size, file_len)
+    }
+
+    /// File-backed executable regions: executable sections, or executable segments when no
+    /// section is marked executable.
+    pub fn code_regions(&self) -> Vec<&MappedRegion> {
+        fn code(regions: &[MappedRegion]) -> Vec<&MappedRegion> {
+            regions.iter().filter(|r| r.executable && r.file_size > 0).collect()
+        }
+        let sections = code(&self.sections);
+        if sections.is_empty() {
+            code(&self.segments)
+        } else {
+            sections
+        }
+    }
+}
+
+/// Layout of the binary `request` analyzes: the raw mapping for raw images, otherwise the
+/// parsed container. `None` when the file cannot be read or parsed (container parsing needs
+/// the `capstone-backend` feature).
+pub fn request_layout(request: &AnalysisRequest) -> Option<ImageLayout> {
+    if let Some(raw) = &request.raw {
+        let len = std::fs::metadata(&request.binary_path).ok()?.len();
+        return Some(ImageLayout::raw(raw.load_address, len));
+    }
+    #[cfg(feature = "capstone-backend")]
+    {
+        let image = crate::services::image::BinaryImage::open(&request.binary_path).ok()?;
+        image_layout(&image)
+    }
+    #[cfg(not(feature = "capstone-backend"))]
+    None
+}
+
+/// The PDB a PE image was linked with, from its CodeView debug record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdbReference {
+    /// GUID and age the matching PDB carries.
+    pub identity: PdbIdentity,
+    /// PDB path the linker recorded (a path on the build machine).
+    pub path: String,
+}
+
+/// CodeView PDB reference of a PE image; `None` for other formats, PEs linked without debug
+/// info, or without the `capstone-backend` feature.
+pub fn pe_pdb_reference(bytes: &[u8]) -> Option<PdbReference> {
+    #[cfg(feature = "capstone-backend")]
+    {
+        parse::pe_pdb_reference(bytes)
+    }
+    #[cfg(not(feature = "capstone-backend"))]
+    {
+        let _ = bytes;
+        None
+    }
+}
+
+#[cfg(feature = "capstone-backend")]
+pub use parse::image_layout;
+
+#[cfg(feature = "capstone-backend")]
+mod parse {
+    use goblin::{elf, mach, pe, Object};
+
+    use super::{ImageFormat, ImageLayout, MappedRegion, PdbIdentity, PdbReference};
+
+    /// Layout of an ELF, PE, or thin Mach-O image, or `None` for anything else (including
+    /// fat Mach-O containers; parse each slice instead).
+    pub fn image_layout(bytes: &[u8]) -> Option<ImageLayout> {
+        match Object::parse(bytes).ok()? {
+            Object::Elf(elf) => Some(elf_layout(&elf)),
+            Object::PE(pe) => Some(pe_layout(&pe)),
+            Object::Mach(mach::Mach::Binary(bin)) => Some(mach_layout(&bin)),
+            _ => None,
+        }
+    }
+
+    fn elf_layout(elf: &elf::Elf) -> ImageLayout {
+        let sections = elf
+            .section_headers
+            .iter()
+            .map(|sh| MappedRegion {
+                name: elf.shdr_strtab.get_at(sh.sh_name).unwrap_or("").to_string(),
+                address: sh.sh_addr,
+                // Non-allocated sections (symbol tables, debug info) occupy no memory.
+                virtual_size: if sh.sh_flags & u64::from(elf::section_header::SHF_ALLOC) != 0 {
+                    sh.sh_size
+                } else {
+                    0
+                },
+                file_offset: sh.sh_offset,
+                file_size: if sh.sh_type == elf::section_header::SHT_NOBITS {
+                    0
+                } else {
+                    sh.sh_size
+                },
+                executable: sh.sh_flags & u64::from(elf::section_header::SHF_EXECINSTR) != 0,
+            })
+            .collect();
+        let segments: Vec<MappedRegion> = elf
+            .program_headers
+            .iter()
+            .filter(|ph| ph.p_type == elf::program_header::PT_LOAD)
+            .map(|ph| MappedRegion {
+                name: String::new(),
+                address: ph.p_vaddr,
+                virtual_size: ph.p_memsz.max(ph.p_filesz),
+                file_offset: ph.p_offset,
+                file_size: ph.p_filesz,
+                executable: ph.p_flags & elf::program_header::PF_X != 0,
+            })
+            .collect();
+        ImageLayout {
+            format: ImageFormat::Elf,
+            image_base: segments.iter().map(|s| s.address).min().unwrap_or(0),
+            entry: (elf.entry != 0).then_some(elf.entry),
+            sections,
+            segments,
+        }
+    }
+
+    pub(super) fn pe_pdb_reference(bytes: &[u8]) -> Option<PdbReference> {
+        let Object::PE(pe) = Object::parse(bytes).ok()? else {
+            return None;
+        };
+        let codeview = pe.debug_data?.codeview_pdb70_debug_info?;
+        let path = String::from_utf8_lossy(codeview.filename).trim_end_matches('\0').to_string();
+        Some(PdbReference {
+            identity: PdbIdentity::from_codeview(&codeview.signature, codeview.age),
+            path,
+        })
+    }
+
+    fn pe_layout(pe: &pe::PE) -> ImageLayout {
+        let image_base = pe.image_base as u64;
+        let sections = pe
+            .sections
+            .iter()
+            .map(|sec| {
+                // A zero VirtualSize means the raw size is the mapped size.
+                let virtual_size = match sec.virtual_size {
+                    0 => sec.size_of_raw_data,
+                    v => v,
+                };
+                MappedRegion {
+                    name: sec.name().unwrap_or_default().to_string(),
+                    address: image_base + sec.virtual_address as u64,
+                    virtual_size: virtual_size as u64,
+                    file_offset: sec.pointer_to_raw_data as u64,
+                    file_size: sec.size_of_raw_data as u64,
+                    executable: sec.characteristics
+                        & (pe::section_table::IMAGE_SCN_CNT_CODE
+                            | pe::section_table::IMAGE_SCN_MEM_EXECUTE)
+                        != 0,
+                }
+            })
+            .collect();
+        ImageLayout {
+            format: ImageFormat::Pe,
+            image_base,
+            entry: (pe.entry != 0).then(|| image_base + pe.entry as u64),
+            sections,
+            segments: Vec::new(),
+        }
+    }
+
+    fn mach_layout(bin: &mach::MachO) -> ImageLayout {
+        let sections = bin
+            .segments
+            .sections()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|(sec, _)| {
+                let zero_fill =
+                    sec.flags & mach::constants::SECTION_TYPE == mach::constants::S_ZEROFILL;
+                MappedRegion {
+                    name: sec.name().unwrap_or("").to_string(),
+                    address: sec.addr,
+                    virtual_size: sec.size,
+                    file_offset: sec.offset as u64,
+                    file_size: if zero_fill { 0 } else { sec.size },
+                    executable: sec.flags
+                        & (mach::constants::S_ATTR_PURE_INSTRUCTIONS
+                            | mach::constants::S_ATTR_SOME_INSTRUCTIONS)
+                        != 0,
+                }
+            })
+            .collect();
+        let segments: Vec<MappedRegion> = bin
+            .segments
+            .iter()
+            .map(|seg| MappedRegion {
+                name: seg.name().unwrap_or("").to_string(),
+                address: seg.vmaddr,
+                virtual_size: seg.vmsize,
+                file_offset: seg.fileoff,
+                file_size: seg.filesize,
+                executable: seg.initprot & mach::constants::VM_PROT_EXECUTE != 0,
+            })
+            .collect();
+        let image_base =
+            segments.iter().find(|s| s.name == "__TEXT").map(|s| s.address).unwrap_or(0);
+        ImageLayout {
+            format: ImageFormat::MachO,
+            image_base,
+            entry: (bin.entry != 0).then_some(bin.entry),
+            sections,
+            segments,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Checksum manifest of a run's output directory (`manifest.json`, `verify-run`).
+//!
+//! A finished run lists every file in its output directory with its SHA-256 and size, so a
+//! later check can tell whether outputs were edited, truncated by a crash, or left over from
+//! another run. Files that keep changing after the run (logs, hook output) are left out by
+//! the caller.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// File name of the manifest inside a run directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("Failed to read {}: {source}", .path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("Failed to write {}: {source}", .path.display())]
+    Write { path: PathBuf, source: io::Error },
+    #[error("Invalid manifest {}: {source}", .path.display())]
+    Parse { path: PathBuf, source: serde_json::Error },
+    #[error("No {MANIFEST_FILE} in {} (re-run the ritual to create one)", .0.display())]
+    Missing(PathBuf),
+}
+
+/// `manifest.json`: the run it belongs to and the files it wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputManifest {
+    pub binary: String,
+    pub ritual: String,
+    pub spec_hash: String,
+    #[serde(default)]
+    pub binary_hash: Option<String>,
+    /// When the manifest was written (the run's epoch for deterministic runs).
+    pub created_at: String,
+    /// Output files by name.
+    pub files: Vec<ManifestEntry>,
+}
+
+/// One output file and its checksum.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// An output file whose contents no longer match the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestMismatch {
+    pub path: String,
+    pub expected: ManifestEntry,
+    pub actual: ManifestEntry,
+}
+
+/// Result of checking a run directory against its manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ManifestCheck {
+    /// Files whose hash and size match.
+    pub verified: usize,
+    pub mismatched: Vec<ManifestMismatch>,
+    /// Listed files that are gone.
+    pub missing: Vec<String>,
+    /// Files in the directory the manifest does not list.
+    pub unlisted: Vec<String>,
+}
+
+impl ManifestCheck {
+    pub fn is_ok(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty() && self.unlisted.is_empty()
+    }
+}
+
+impl OutputManifest {
+    /// Load `manifest.json` from a run directory.
+    pub fn load(run_dir: &Path) -> Result<Self, ManifestError> {
+        let path = run_dir.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Err(ManifestError::Missing(run_dir.to_path_buf()));
+        }
+        let text = fs::read_to_string(&path)
+            .map_err(|source| ManifestError::Read { path: path.clone(), source })?;
+        serde_json::from_str(&text).map_err(|source| ManifestError::Parse { path, source })
+    }
+
+    /// Write `manifest.json` into a run directory.
+    pub fn write(&self, run_dir: &Path) -> Result<(), ManifestError> {
+        let path = run_dir.join(MANIFEST_FILE);
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|source| ManifestError::Parse { path: path.clone(), source })?;
+        fs::write(&path, json).map_err(|source| ManifestError::Write { path, source })
+    }
+
+    /// Recompute the checksums of the run directory's files and compare them.
+    pub fn verify(
+        &self,
+        run_dir: &Path,
+        untracked: &[&str],
+    ) -> Result<ManifestCheck, ManifestError> {
+        let actual = hash_outputs(run_dir, untracked)?;
+        let mut check = ManifestCheck::default();
+        for expected in &self.files {
+            match actual.iter().find(|a| a.path == expected.path) {
+                Some(found) if found == expected => check.verified += 1,
+                Some(found) => check.mismatched.push(ManifestMismatch {
+                    path: expected.path.clone(),
+                    expected: expected.clone(),
+                    actual: found.clone(),
+                }),
+                None => check.missing.push(expected.path.clone()),
+            }
+        }
+        check.unlisted = actual
+            .into_iter()
+            .filter(|a| !self.files.iter().any(|e| e.path == a.path))
+            .map(|a| a.path)
+            .collect();
+        Ok(check)
+    }
+}
+
+/// Hash every file directly in `run_dir` except the manifest and the `untracked` names,
+/// sorted by name.
+pub fn hash_outputs(
+    run_dir: &Path,
+    untracked: &[&str],
+) -> Result<Vec<ManifestEntry>, ManifestError> {
+    let read_err = |source| ManifestError::Read { path: run_dir.to_path_buf(), source };
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(run_dir).map_err(read_err)? {
+        let entry = entry.map_err(read_err)?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.file_type().map_err(read_err)?.is_file()
+            || name == MANIFEST_FILE
+            || untracked.contains(&name.as_str())
+        {
+            continue;
+        }
+        let path = entry.path();
+        let (sha256, size) =
+            hash_file(&path).map_err(|source| ManifestError::Read { path, source })?;
+        entries.push(ManifestEntry { path: name, sha256, size });
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+fn hash_file(path: &Path) -> io::Result<(String, u64)> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 8192];
+    let mut size = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
@@ -1,2 +1,33 @@
 pub mod analysis;
 pub mod backends;
+pub mod binary_info;
+pub mod cache;
+pub mod config;
+pub mod decoders;
+pub mod demangle;
+pub mod deterministic;
+pub mod docs;
+pub mod entry_points;
+pub mod exchange;
+pub mod fingerprint;
+pub mod fixtures;
+pub mod health;
+pub mod image;
+pub mod import;
+pub mod loader;
+pub mod manifest;
+pub mod pipeline;
+pub mod plt;
+pub mod plugins;
+pub mod process;
+pub mod query;
+pub mod render;
+pub mod report;
+pub mod retention;
+pub mod roots;
+pub mod scoring;
+pub mod search;
+pub mod signature;
+pub mod strings;
+pub mod tables;
+pub mod wasi;
@@ -0,0 +1,267 @@
+//! Multi-stage ritual pipelines.
+//!
+//! A spec may declare `stages` (`[{name: symbols, backend: rizin}, {name: carve, needs:
+//! symbols}]`). Stages run in dependency order, each against one backend. A stage receives
+//! the results of the stages it `needs`: roots naming a function an upstream stage found are
+//! rewritten to that function's address, so a symbol-recovery stage can feed a stage whose
+//! backend only understands addresses.
+//!
+//! Per-stage status (`stages.json`) and results (`stages/<name>.json`) live in the run
+//! directory, so rerunning an unchanged spec resumes from the first stage that did not
+//! succeed.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use crate::services::analysis::AnalysisResult;
+
+/// File (inside a run directory) holding per-stage status.
+pub const STAGES_FILE: &str = "stages.json";
+/// Directory (inside a run directory) holding per-stage analysis results.
+pub const STAGE_RESULTS_DIR: &str = "stages";
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error("Pipeline stage names must not be empty")]
+    EmptyStageName,
+    #[error("Pipeline stage '{0}' is declared more than once")]
+    DuplicateStage(String),
+    #[error("Pipeline stage '{stage}' needs unknown stage '{needs}'")]
+    UnknownDependency { stage: String, needs: String },
+    #[error("Pipeline stages form a dependency cycle: {}", .0.join(", "))]
+    Cycle(Vec<String>),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// One stage of a pipeline spec.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StageSpec {
+    pub name: String,
+    /// Backend for this stage; defaults to the spec-level backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// Stages that must succeed first (`needs: symbols` or `needs: [symbols, strings]`).
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    #[schemars(with = "OneOrMany")]
+    pub needs: Vec<String>,
+    /// Roots for this stage; defaults to the spec roots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+}
+
+/// `needs:` as written: one stage name or a list.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Indices of `stages` in execution order.
+///
+/// Dependencies run first; otherwise declaration order is kept, so the order is stable.
+pub fn stage_order(stages: &[StageSpec]) -> Result<Vec<usize>, PipelineError> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (i, stage) in stages.iter().enumerate() {
+        if stage.name.trim().is_empty() {
+            return Err(PipelineError::EmptyStageName);
+        }
+        if index.insert(stage.name.as_str(), i).is_some() {
+            return Err(PipelineError::DuplicateStage(stage.name.clone()));
+        }
+    }
+    for stage in stages {
+        if let Some(missing) = stage.needs.iter().find(|n| !index.contains_key(n.as_str())) {
+            return Err(PipelineError::UnknownDependency {
+                stage: stage.name.clone(),
+                needs: missing.clone(),
+            });
+        }
+    }
+
+    let mut order = Vec::with_capacity(stages.len());
+    let mut done = vec![false; stages.len()];
+    while order.len() < stages.len() {
+        let ready = stages
+            .iter()
+            .enumerate()
+            .find(|(i, stage)| !done[*i] && stage.needs.iter().all(|n| done[index[n.as_str()]]));
+        match ready {
+            Some((i, _)) => {
+                done[i] = true;
+                order.push(i);
+            }
+            None => {
+                let stuck = stages
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !done[*i])
+                    .map(|(_, s)| s.name.clone())
+                    .collect();
+                return Err(PipelineError::Cycle(stuck));
+            }
+        }
+    }
+    Ok(order)
+}
+
+/// Rewrite roots that name a function found by an upstream stage to its `0x…` address.
+///
+/// Roots that already are addresses, or that no upstream stage resolved, pass through.
+/// Earlier entries in `upstream` win when several stages name the same function.
+pub fn feed_roots(roots: &[String], upstream: &[&AnalysisResult]) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    roots
+        .iter()
+        .map(|root| {
+            upstream
+                .iter()
+                .flat_map(|result| &result.functions)
+                .find(|f| f.name.as_deref() == Some(root.as_str()))
+                .map(|f| format!("0x{:X}", f.address))
+                .unwrap_or_else(|| root.clone())
+        })
+        .filter(|root| seen.insert(root.clone()))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+impl StageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StageStatus::Pending => "pending",
+            StageStatus::Succeeded => "succeeded",
+            StageStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Recorded outcome of one stage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageState {
+    pub name: String,
+    pub status: StageStatus,
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub functions: Option<usize>,
+}
+
+/// Per-stage status of one pipeline run, persisted as `stages.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineState {
+    /// Hash of the spec the stages were run from; a changed spec cannot resume.
+    pub spec_hash: String,
+    /// Stages in execution order.
+    pub stages: Vec<StageState>,
+}
+
+impl PipelineState {
+    /// Fresh state with every stage pending.
+    pub fn new(spec_hash: &str, stages: &[StageSpec], order: &[usize]) -> Self {
+        Self {
+            spec_hash: spec_hash.to_string(),
+            stages: order
+                .iter()
+                .map(|&i| StageState {
+                    name: stages[i].name.clone(),
+                    status: StageStatus::Pending,
+                    backend: None,
+                    started_at: None,
+                    finished_at: None,
+                    error: None,
+                    functions: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Load `stages.json` from a run directory, if present.
+    pub fn load(run_dir: &Path) -> Result<Option<Self>, PipelineError> {
+        let path = run_dir.join(STAGES_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+    }
+
+    pub fn save(&self, run_dir: &Path) -> Result<(), PipelineError> {
+        fs::create_dir_all(run_dir)?;
+        fs::write(run_dir.join(STAGES_FILE), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn stage(&self, name: &str) -> Option<&StageState> {
+        self.stages.iter().find(|s| s.name == name)
+    }
+
+    pub fn stage_mut(&mut self, name: &str) -> Option<&mut StageState> {
+        self.stages.iter_mut().find(|s| s.name == name)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.stages.iter().all(|s| s.status == StageStatus::Succeeded)
+    }
+
+    /// True when a rerun of `spec_hash` should pick up where this state left off.
+    pub fn can_resume(&self, spec_hash: &str) -> bool {
+        self.spec_hash == spec_hash && !self.is_complete()
+    }
+}
+
+/// Where a stage's analysis result is stored inside a run directory.
+pub fn stage_result_path(run_dir: &Path, stage: &str) -> PathBuf {
+    run_dir.join(STAGE_RESULTS_DIR).join(format!("{stage}.json"))
+}
+
+pub fn save_stage_result(
+    run_dir: &Path,
+    stage: &str,
+    result: &AnalysisResult,
+) -> Result<(), PipelineError> {
+    let path = stage_result_path(run_dir, stage);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_vec_pretty(result)?)?;
+    Ok(())
+}
+
+/// Load a stored stage result; `None` when it is missing or unreadable.
+pub fn load_stage_result(run_dir: &Path, stage: &str) -> Option<AnalysisResult> {
+    let bytes = fs::read(stage_result_path(run_dir, stage)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
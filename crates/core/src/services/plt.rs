@@ -0,0 +1,281 @@
+//! Imports reached through the PLT of dynamically linked ELF images.
+//!
+//! A call to `memcpy` in a dynamically linked ELF lands on a stub in `.plt` (or `.plt.sec` /
+//! `.plt.got`) that jumps through a GOT slot the dynamic linker fills in. The slot's
+//! relocation (`.rela.plt`, or `.rela.dyn` for `.plt.got`) names the symbol, and
+//! `.gnu.version` / `.gnu.version_r` the version and library it was linked against.
+//! [`elf_plt_imports`] decodes the stubs of x86, x86_64, arm64, and 32-bit ARM images, follows
+//! each to its slot, and returns the import behind it, so a backend can name the call
+//! `memcpy@GLIBC_2.14` instead of synthesizing a `sub_XXXX` function for the stub.
+
+use crate::services::analysis::{EvidenceKind, EvidenceRecord, EvidenceSource};
+
+/// An imported function and the PLT stub calls to it go through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PltImport {
+    /// Address of the stub.
+    pub address: u64,
+    pub name: String,
+    /// Symbol version the binary was linked against, e.g. `GLIBC_2.14`.
+    pub version: Option<String>,
+    /// Library that provides `version`.
+    pub library: Option<String>,
+}
+
+impl PltImport {
+    /// `memcpy@GLIBC_2.14`, or the bare name for unversioned symbols.
+    pub fn versioned_name(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{}@{}", self.name, version),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Import record at the stub: `import: memcpy@GLIBC_2.14 (libc.so.6)`.
+    pub fn import_evidence(&self, source: Option<EvidenceSource>) -> EvidenceRecord {
+        EvidenceRecord {
+            address: self.address,
+            description: match &self.library {
+                Some(library) => format!("import: {} ({})", self.versioned_name(), library),
+                None => format!("import: {}", self.versioned_name()),
+            },
+            kind: Some(EvidenceKind::Import),
+            source,
+            run_id: None,
+        }
+    }
+
+    /// Call record at `address`, a call into this import: `call -> memcpy@GLIBC_2.14`.
+    pub fn call_evidence(&self, address: u64, source: Option<EvidenceSource>) -> EvidenceRecord {
+        EvidenceRecord {
+            address,
+            description: format!("call -> {}", self.versioned_name()),
+            kind: Some(EvidenceKind::Call),
+            source,
+            run_id: None,
+        }
+    }
+}
+
+/// Imports behind the PLT stubs of an ELF image, sorted by stub address; empty for other
+/// formats, statically linked images, and architectures whose stubs are not recognized.
+pub fn elf_plt_imports(bytes: &[u8]) -> Vec<PltImport> {
+    #[cfg(feature = "capstone-backend")]
+    {
+        parse::plt_imports(bytes)
+    }
+    #[cfg(not(feature = "capstone-backend"))]
+    {
+        let _ = bytes;
+        Vec::new()
+    }
+}
+
+#[cfg(feature = "capstone-backend")]
+mod parse {
+    use std::collections::{BTreeMap, HashMap};
+
+    use goblin::elf::{self, Elf};
+
+    use super::PltImport;
+
+    /// Sections holding PLT stubs.
+    const PLT_SECTIONS: &[&str] = &[".plt", ".plt.sec", ".plt.got"];
+
+    /// Symbol behind a GOT slot.
+    struct SlotSymbol {
+        name: String,
+        version: Option<String>,
+        library: Option<String>,
+    }
+
+    pub fn plt_imports(bytes: &[u8]) -> Vec<PltImport> {
+        let Ok(elf) = Elf::parse(bytes) else {
+            return Vec::new();
+        };
+        let slots = got_slots(&elf);
+        if slots.is_empty() {
+            return Vec::new();
+        }
+        let got_base = elf
+            .dynamic
+            .as_ref()
+            .and_then(|d| d.info.pltgot)
+            .or_else(|| section(&elf, ".got.plt").map(|s| s.sh_addr));
+        let mut stubs: BTreeMap<u64, &SlotSymbol> = BTreeMap::new();
+        for shdr in &elf.section_headers {
+            let name = elf.shdr_strtab.get_at(shdr.sh_name).unwrap_or("");
+            if !PLT_SECTIONS.contains(&name) || !shdr.is_executable() {
+                continue;
+            }
+            let Some(code) = shdr
+                .file_range()
+                .and_then(|range| bytes.get(range))
+                .filter(|_| shdr.sh_type != elf::section_header::SHT_NOBITS)
+            else {
+                continue;
+            };
+            let found = match elf.header.e_machine {
+                elf::header::EM_X86_64 => x86_stubs(code, shdr.sh_addr, true, got_base),
+                elf::header::EM_386 => x86_stubs(code, shdr.sh_addr, false, got_base),
+                elf::header::EM_AARCH64 => arm64_stubs(code, shdr.sh_addr),
+                elf::header::EM_ARM => arm_stubs(code, shdr.sh_addr),
+                _ => Vec::new(),
+            };
+            for (stub, slot) in found {
+                if let Some(symbol) = slots.get(&slot) {
+                    stubs.entry(stub).or_insert(symbol);
+                }
+            }
+        }
+        stubs
+            .into_iter()
+            .map(|(address, symbol)| PltImport {
+                address,
+                name: symbol.name.clone(),
+                version: symbol.version.clone(),
+                library: symbol.library.clone(),
+            })
+            .collect()
+    }
+
+    fn section<'a>(elf: &'a Elf, name: &str) -> Option<&'a elf::SectionHeader> {
+        elf.section_headers.iter().find(|s| elf.shdr_strtab.get_at(s.sh_name) == Some(name))
+    }
+
+    /// Undefined dynamic symbols by the GOT slot their relocation fills: `JUMP_SLOT`
+    /// relocations for `.plt`, `GLOB_DAT` ones for `.plt.got`.
+    fn got_slots(elf: &Elf) -> HashMap<u64, SlotSymbol> {
+        let versions = needed_versions(elf);
+        let mut slots = HashMap::new();
+        let relocs = elf.pltrelocs.iter().chain(elf.dynrelas.iter()).chain(elf.dynrels.iter());
+        for reloc in relocs {
+            let Some(sym) = elf.dynsyms.get(reloc.r_sym).filter(|_| reloc.r_sym != 0) else {
+                continue;
+            };
+            if sym.st_shndx != elf::section_header::SHN_UNDEF as usize {
+                continue;
+            }
+            let Some(name) = elf.dynstrtab.get_at(sym.st_name).filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            let needed = elf
+                .versym
+                .as_ref()
+                .and_then(|versym| versym.get_at(reloc.r_sym))
+                .and_then(|versym| versions.get(&versym.version()));
+            slots.entry(reloc.r_offset).or_insert_with(|| SlotSymbol {
+                name: name.to_string(),
+                version: needed.map(|(version, _)| version.clone()),
+                library: needed.map(|(_, library)| library.clone()),
+            });
+        }
+        slots
+    }
+
+    /// Version index to (version name, library) from `.gnu.version_r`.
+    fn needed_versions(elf: &Elf) -> HashMap<u16, (String, String)> {
+        let mut versions = HashMap::new();
+        let Some(verneed) = &elf.verneed else {
+            return versions;
+        };
+        for need in verneed.iter() {
+            let library = elf.dynstrtab.get_at(need.vn_file).unwrap_or("").to_string();
+            for aux in need.iter() {
+                if let Some(version) = elf.dynstrtab.get_at(aux.vna_name) {
+                    let index = aux.vna_other & elf::symver::VERSYM_VERSION;
+                    versions.insert(index, (version.to_string(), library.clone()));
+                }
+            }
+        }
+        versions
+    }
+
+    /// `(stub, slot)` for each `jmp [slot]` of an x86 PLT section at `base`: `ff 25` with a
+    /// RIP-relative displacement on x86_64; `ff 25` with an absolute address or `ff a3` relative
+    /// to the GOT (`ebx`) on x86. A `bnd` prefix and a preceding `endbr64`/`endbr32` are part
+    /// of the stub.
+    fn x86_stubs(code: &[u8], base: u64, x86_64: bool, got: Option<u64>) -> Vec<(u64, u64)> {
+        let mut stubs = Vec::new();
+        let mut i = 0;
+        while i + 6 <= code.len() {
+            let disp = i32::from_le_bytes([code[i + 2], code[i + 3], code[i + 4], code[i + 5]]);
+            let slot = match (code[i], code[i + 1]) {
+                (0xff, 0x25) if x86_64 => Some((base + i as u64 + 6).wrapping_add(disp as u64)),
+                (0xff, 0x25) => Some(disp as u32 as u64),
+                (0xff, 0xa3) if !x86_64 => got.map(|got| got.wrapping_add(disp as u64)),
+                _ => None,
+            };
+            let Some(slot) = slot else {
+                i += 1;
+                continue;
+            };
+            let mut start = i;
+            if start >= 1 && code[start - 1] == 0xf2 {
+                start -= 1;
+            }
+            if start >= 4 && matches!(code[start - 4..start], [0xf3, 0x0f, 0x1e, 0xfa | 0xfb]) {
+                start -= 4;
+            }
+            stubs.push((base + start as u64, slot));
+            i += 6;
+        }
+        stubs
+    }
+
+    /// `(stub, slot)` for each `adrp x16, page` + `ldr x17, [x16, #off]` of an arm64 PLT
+    /// section at `base`; a preceding `bti c` is part of the stub.
+    fn arm64_stubs(code: &[u8], base: u64) -> Vec<(u64, u64)> {
+        const BTI_C: u32 = 0xd503_245f;
+        let words = words(code);
+        let mut stubs = Vec::new();
+        for (i, pair) in words.windows(2).enumerate() {
+            let (adrp, ldr) = (pair[0], pair[1]);
+            // adrp x16 / ldr x17, [x16, #imm12 * 8]
+            if adrp & 0x9f00_001f != 0x9000_0010 || ldr & 0xffc0_03ff != 0xf940_0211 {
+                continue;
+            }
+            let pc = base + 4 * i as u64;
+            let imm = (((adrp >> 5) & 0x7_ffff) << 2 | (adrp >> 29) & 0x3) as u64;
+            // Sign-extend the 21-bit page count and scale it to bytes.
+            let pages = ((imm << 43) as i64 >> 43) << 12;
+            let slot = (pc & !0xfff).wrapping_add(pages as u64) + ((ldr >> 10) & 0xfff) as u64 * 8;
+            let start = if i > 0 && words[i - 1] == BTI_C { pc - 4 } else { pc };
+            stubs.push((start, slot));
+        }
+        stubs
+    }
+
+    /// `(stub, slot)` for each `add ip, pc, #a` + `add ip, ip, #b` + `ldr pc, [ip, #c]!` of a
+    /// 32-bit ARM PLT section at `base` (the ARM-mode stubs GNU ld and lld emit).
+    fn arm_stubs(code: &[u8], base: u64) -> Vec<(u64, u64)> {
+        let words = words(code);
+        let mut stubs = Vec::new();
+        for (i, triple) in words.windows(3).enumerate() {
+            let (first, second, load) = (triple[0], triple[1], triple[2]);
+            if first & 0xffff_f000 != 0xe28f_c000
+                || second & 0xffff_f000 != 0xe28c_c000
+                || load & 0xffff_f000 != 0xe5bc_f000
+            {
+                continue;
+            }
+            let pc = base + 4 * i as u64;
+            // The pc operand reads 8 bytes ahead in ARM mode.
+            let slot = (pc + 8)
+                .wrapping_add(arm_immediate(first))
+                .wrapping_add(arm_immediate(second))
+                .wrapping_add((load & 0xfff) as u64);
+            stubs.push((pc, slot & 0xffff_ffff));
+        }
+        stubs
+    }
+
+    /// Rotated immediate of an ARM data-processing instruction.
+    fn arm_immediate(insn: u32) -> u64 {
+        (insn & 0xff).rotate_right(2 * ((insn >> 8) & 0xf)) as u64
+    }
+
+    fn words(code: &[u8]) -> Vec<u32> {
+        code.chunks_exact(4).map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])).collect()
+    }
+}
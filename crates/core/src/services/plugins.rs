@@ -0,0 +1,193 @@
+//! WebAssembly post-processing plugins.
+//!
+//! A ritual spec may list WASI modules (`plugins: [./plugins/score.wasm]`) that run after
+//! analysis, in order, each in-process on the built-in WASI runtime (see
+//! [`crate::services::wasi`]). A plugin reads a [`PluginInput`] as JSON on stdin and writes a [`PluginOutput`] as JSON on stdout: evidence
+//! to add, functions to rename, and slice membership to change. Each plugin sees the result
+//! as left by the ones before it.
+//!
+//! The input is versioned by [`PLUGIN_API_VERSION`]; fields are only ever added to it, so a
+//! plugin built against one version keeps working until the version changes.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::services::analysis::{
+    resolve_call_edges, AnalysisError, AnalysisResult, EvidenceRecord, EvidenceSource,
+};
+use crate::services::process::Deadline;
+use crate::services::wasi::run_wasi;
+
+/// Version of the plugin input and output formats.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Plugin module not found at {0}")]
+    MissingModule(PathBuf),
+    #[error("Plugin {plugin} could not run")]
+    Run {
+        plugin: String,
+        #[source]
+        source: AnalysisError,
+    },
+    #[error("Plugin {plugin} exited with {}: {stderr}", .code.map_or("a signal".to_string(), |c| format!("status {c}")))]
+    Exit { plugin: String, code: Option<i32>, stderr: String },
+    #[error("Plugin {plugin} wrote invalid output: {message}")]
+    InvalidOutput { plugin: String, message: String },
+    #[error("Plugin {plugin} speaks API version {version} (expected {PLUGIN_API_VERSION})")]
+    UnsupportedApi { plugin: String, version: u32 },
+}
+
+/// What a plugin reads on stdin.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInput<'a> {
+    pub api_version: u32,
+    pub ritual: &'a str,
+    pub binary: &'a str,
+    pub result: &'a AnalysisResult,
+}
+
+/// What a plugin writes on stdout; every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PluginOutput {
+    /// When set, must equal [`PLUGIN_API_VERSION`].
+    pub api_version: Option<u32>,
+    /// Evidence records to add (duplicates of existing records are dropped).
+    pub evidence: Vec<EvidenceRecord>,
+    /// Functions to rename, by entry address.
+    pub renames: Vec<FunctionRename>,
+    /// Functions to move into or out of the slice, by entry address.
+    pub slice: Vec<SliceMembership>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FunctionRename {
+    pub address: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SliceMembership {
+    pub address: u64,
+    pub in_slice: bool,
+}
+
+/// What one plugin changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PluginReport {
+    pub plugin: String,
+    pub evidence_added: usize,
+    pub renamed: usize,
+    pub slice_changed: usize,
+    /// Rename and membership addresses that name no function; those edits are skipped.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown_addresses: Vec<u64>,
+}
+
+/// Run each module of `plugins` against `result`, applying its edits
+/// before the next one runs. Relative module paths are resolved against `base`.
+pub fn run_plugins(
+    result: &mut AnalysisResult,
+    plugins: &[String],
+    base: &Path,
+    ritual: &str,
+    binary: &str,
+    deadline: &Deadline,
+) -> Result<Vec<PluginReport>, PluginError> {
+    let mut reports = Vec::new();
+    for plugin in plugins {
+        let module = base.join(plugin);
+        if !module.is_file() {
+            return Err(PluginError::MissingModule(module));
+        }
+        let input = PluginInput { api_version: PLUGIN_API_VERSION, ritual, binary, result };
+        let input = serde_json::to_vec(&input).expect("analysis results serialize");
+        let output = run_wasi(&module, plugin, input, deadline)
+            .map_err(|source| PluginError::Run { plugin: plugin.clone(), source })?;
+        if output.code != 0 {
+            return Err(PluginError::Exit {
+                plugin: plugin.clone(),
+                code: Some(output.code),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        let edits = parse_plugin_output(plugin, &output.stdout)?;
+        reports.push(apply_plugin_output(result, plugin, edits));
+    }
+    Ok(reports)
+}
+
+/// Parse a plugin's stdout; empty output means no edits.
+pub fn parse_plugin_output(plugin: &str, stdout: &[u8]) -> Result<PluginOutput, PluginError> {
+    if stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(PluginOutput::default());
+    }
+    let output: PluginOutput = serde_json::from_slice(stdout).map_err(|e| {
+        PluginError::InvalidOutput { plugin: plugin.to_string(), message: e.to_string() }
+    })?;
+    if let Some(version) = output.api_version.filter(|v| *v != PLUGIN_API_VERSION) {
+        return Err(PluginError::UnsupportedApi { plugin: plugin.to_string(), version });
+    }
+    if let Some(rename) = output.renames.iter().find(|r| r.name.trim().is_empty()) {
+        return Err(PluginError::InvalidOutput {
+            plugin: plugin.to_string(),
+            message: format!("empty name for function 0x{:x}", rename.address),
+        });
+    }
+    Ok(output)
+}
+
+/// Apply `edits` from `plugin` to `result`. Added evidence is attributed to the plugin
+/// (source `plugin/<module stem>`), renamed functions drop their mangled name, and call edges
+/// are renamed along with their targets.
+pub fn apply_plugin_output(
+    result: &mut AnalysisResult,
+    plugin: &str,
+    edits: PluginOutput,
+) -> PluginReport {
+    let mut report = PluginReport { plugin: plugin.to_string(), ..Default::default() };
+    let name = Path::new(plugin).file_stem().map_or(plugin.into(), |stem| stem.to_string_lossy());
+    for mut record in edits.evidence {
+        record.source = Some(EvidenceSource::new("plugin", name.as_ref()));
+        record.run_id = None;
+        if !result.evidence.iter().any(|e| {
+            (e.address, &e.description, &e.kind)
+                == (record.address, &record.description, &record.kind)
+        }) {
+            result.evidence.push(record);
+            report.evidence_added += 1;
+        }
+    }
+    for rename in edits.renames {
+        let Some(function) = result.functions.iter_mut().find(|f| f.address == rename.address)
+        else {
+            report.unknown_addresses.push(rename.address);
+            continue;
+        };
+        if function.name.as_deref() != Some(rename.name.as_str()) {
+            function.name = Some(rename.name);
+            function.mangled_name = None;
+            report.renamed += 1;
+        }
+    }
+    for mark in edits.slice {
+        let Some(function) = result.functions.iter_mut().find(|f| f.address == mark.address) else {
+            report.unknown_addresses.push(mark.address);
+            continue;
+        };
+        if function.in_slice != mark.in_slice {
+            function.in_slice = mark.in_slice;
+            report.slice_changed += 1;
+        }
+    }
+    if report.renamed > 0 {
+        resolve_call_edges(result);
+    }
+    report.unknown_addresses.sort_unstable();
+    report.unknown_addresses.dedup();
+    report
+}
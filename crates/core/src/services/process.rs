@@ -0,0 +1,385 @@
+//! Supervised child processes for backends that drive external tools.
+//!
+//! A run's `timeout` is a wall-clock budget shared by every tool invocation of the run
+//! ([`Deadline`]). [`run_supervised`] spawns the tool with captured output, polls it, and
+//! kills it once the budget is spent, reporting [`AnalysisError::Timeout`] instead of
+//! blocking forever on a tool that hangs (e.g. rizin on a huge binary).
+//!
+//! On Unix each tool runs in its own process group, so a timeout also kills the helpers it
+//! spawned, and helpers still running when the tool exits are killed before its output is
+//! collected. Being in its own group, a tool does not see the terminal's Ctrl-C: SIGINT and
+//! SIGTERM are forwarded to the groups of the running tools, which are then killed and
+//! reported as [`AnalysisError::Interrupted`]. A signal that arrives while no tool runs keeps
+//! its default action. Each tool's CPU time and peak memory are read when it is reaped, and
+//! [`track_child_usage`] sums them over the tools one analysis ran.
+
+use std::cell::Cell;
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::services::analysis::AnalysisError;
+
+/// How often a running child is checked for exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// CPU time and peak memory of the tool processes reaped while tracking.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChildUsage {
+    /// User plus system CPU time, summed over the processes.
+    pub cpu_ms: u64,
+    /// Largest resident set of any one process, in KiB.
+    pub peak_rss_kb: u64,
+    /// Processes measured.
+    pub processes: u32,
+}
+
+thread_local! {
+    /// Usage collected on this thread by [`track_child_usage`]; `None` when not tracking.
+    static CHILD_USAGE: Cell<Option<ChildUsage>> = const { Cell::new(None) };
+}
+
+/// Run `f` and return what the tool processes it supervised on this thread used; `None`
+/// when it ran none (or none could be measured). Tracking nests: the outer call also
+/// counts the inner call's processes.
+pub fn track_child_usage<T>(f: impl FnOnce() -> T) -> (T, Option<ChildUsage>) {
+    let outer = CHILD_USAGE.replace(Some(ChildUsage::default()));
+    let value = f();
+    let usage = CHILD_USAGE.replace(outer).filter(|usage| usage.processes > 0);
+    if let (Some(outer), Some(inner)) = (outer, usage) {
+        CHILD_USAGE.set(Some(outer.merge(inner)));
+    }
+    (value, usage)
+}
+
+impl ChildUsage {
+    fn merge(self, other: ChildUsage) -> ChildUsage {
+        ChildUsage {
+            cpu_ms: self.cpu_ms + other.cpu_ms,
+            peak_rss_kb: self.peak_rss_kb.max(other.peak_rss_kb),
+            processes: self.processes + other.processes,
+        }
+    }
+}
+
+fn record_child_usage(usage: ChildUsage) {
+    CHILD_USAGE.with(|cell| {
+        if let Some(total) = cell.get() {
+            cell.set(Some(total.merge(usage)));
+        }
+    });
+}
+
+/// Wall-clock budget for the external tools of one run; unlimited without a timeout.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    limit: Option<Duration>,
+    started: Instant,
+}
+
+impl Deadline {
+    /// Start the clock now.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Deadline { limit: timeout, started: Instant::now() }
+    }
+
+    /// Budget left (`None` when unlimited, zero once spent).
+    pub fn remaining(&self) -> Option<Duration> {
+        self.limit.map(|limit| limit.saturating_sub(self.started.elapsed()))
+    }
+
+    pub(crate) fn timeout_error(&self, tool: &str) -> AnalysisError {
+        AnalysisError::Timeout { tool: tool.to_string(), after: self.limit.unwrap_or_default() }
+    }
+}
+
+/// Run `cmd` to completion with stdout/stderr captured (stdin closed), killing it when
+/// `deadline` runs out.
+///
+/// On Unix the whole process group of the child is killed; elsewhere only the direct child.
+pub fn run_supervised(
+    cmd: &mut Command,
+    tool: &str,
+    deadline: &Deadline,
+) -> Result<Output, AnalysisError> {
+    supervise(cmd, tool, None, deadline)
+}
+
+/// [`run_supervised`] with `input` written to the child's stdin (then closed).
+pub fn run_supervised_with_input(
+    cmd: &mut Command,
+    tool: &str,
+    input: Vec<u8>,
+    deadline: &Deadline,
+) -> Result<Output, AnalysisError> {
+    supervise(cmd, tool, Some(input), deadline)
+}
+
+fn supervise(
+    cmd: &mut Command,
+    tool: &str,
+    input: Option<Vec<u8>>,
+    deadline: &Deadline,
+) -> Result<Output, AnalysisError> {
+    if deadline.remaining() == Some(Duration::ZERO) {
+        return Err(deadline.timeout_error(tool));
+    }
+    let stdin = if input.is_some() { Stdio::piped() } else { Stdio::null() };
+    #[cfg(unix)]
+    {
+        forward_interrupts();
+        std::os::unix::process::CommandExt::process_group(cmd, 0);
+    }
+    let mut child = cmd
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AnalysisError::Backend(format!("failed to spawn {tool}: {e}")))?;
+    let group = ActiveGroup::register(&child);
+    // Feed stdin from its own thread; a child that exits without reading it just breaks
+    // the pipe.
+    if let (Some(mut pipe), Some(input)) = (child.stdin.take(), input) {
+        thread::spawn(move || {
+            let _ = pipe.write_all(&input);
+        });
+    }
+    // Drain both pipes while waiting so a chatty tool cannot block on a full pipe.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let wait_error =
+        |e: std::io::Error| AnalysisError::Backend(format!("failed to wait for {tool}: {e}"));
+    let status = loop {
+        if group.interrupted() {
+            kill_group(&mut child);
+            let _ = reap(&mut child);
+            tracing::warn!(tool, "killed backend tool after an interrupt");
+            return Err(AnalysisError::Interrupted { tool: tool.to_string() });
+        }
+        if has_exited(&mut child).map_err(wait_error)? {
+            // Helpers left running would keep the pipes open, and the drains below waiting.
+            // Until it is reaped the exited tool still holds its group id, so only its own
+            // helpers are hit.
+            kill_group(&mut child);
+            drop(group);
+            break reap(&mut child).map_err(wait_error)?;
+        }
+        match deadline.remaining() {
+            Some(Duration::ZERO) => {
+                kill_group(&mut child);
+                drop(group);
+                let _ = reap(&mut child);
+                let secs = deadline.limit.unwrap_or_default().as_secs();
+                tracing::warn!(tool, timeout_secs = secs, "killed backend tool after timeout");
+                return Err(deadline.timeout_error(tool));
+            }
+            Some(left) => thread::sleep(left.min(POLL_INTERVAL)),
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    };
+    Ok(Output { status, stdout: collect(stdout), stderr: collect(stderr) })
+}
+
+/// Kill `child` and everything else in its process group.
+#[cfg(unix)]
+fn kill_group(child: &mut Child) {
+    // SAFETY: plain syscall. The child is not reaped yet, so its pid (which is also the
+    // group id) cannot have been reused.
+    if unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } == -1 {
+        let _ = child.kill();
+    }
+}
+
+/// Whether `child` has exited, leaving it unreaped.
+#[cfg(unix)]
+fn has_exited(child: &mut Child) -> std::io::Result<bool> {
+    // SAFETY: `siginfo_t` is plain data, valid when zeroed; `si_pid` stays 0 while the
+    // child runs.
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let flags = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+    loop {
+        // SAFETY: the pid is our unreaped child; `info` is valid for writes.
+        if unsafe { libc::waitid(libc::P_PID, child.id() as libc::id_t, &mut info, flags) } == 0 {
+            // SAFETY: `waitid` succeeded, so `info` holds a child state (or is still zeroed).
+            return Ok(unsafe { info.si_pid() } != 0);
+        }
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+/// Wait for `child` to exit, recording its usage.
+#[cfg(unix)]
+fn reap(child: &mut Child) -> std::io::Result<ExitStatus> {
+    wait4(child, 0)?.ok_or_else(|| std::io::Error::other("wait4 returned no status"))
+}
+
+/// `wait4` instead of `Child::try_wait`, which reaps the child without its resource usage.
+#[cfg(unix)]
+fn wait4(child: &mut Child, flags: libc::c_int) -> std::io::Result<Option<ExitStatus>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let mut status: libc::c_int = 0;
+    // SAFETY: `rusage` is plain data, valid when zeroed, and filled in by `wait4`.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: `pid` is our unreaped child; both out-pointers are valid for writes.
+        let reaped = unsafe { libc::wait4(pid, &mut status, flags, &mut rusage) };
+        match reaped {
+            0 => return Ok(None),
+            -1 => {
+                let err = std::io::Error::last_os_error();
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            _ => break,
+        }
+    }
+    let millis = |tv: libc::timeval| tv.tv_sec as u64 * 1000 + tv.tv_usec as u64 / 1000;
+    // Linux reports `ru_maxrss` in KiB, macOS in bytes.
+    let rss_unit = if cfg!(target_os = "macos") { 1024 } else { 1 };
+    record_child_usage(ChildUsage {
+        cpu_ms: millis(rusage.ru_utime) + millis(rusage.ru_stime),
+        peak_rss_kb: rusage.ru_maxrss.max(0) as u64 / rss_unit,
+        processes: 1,
+    });
+    Ok(Some(ExitStatus::from_raw(status)))
+}
+
+#[cfg(not(unix))]
+fn kill_group(child: &mut Child) {
+    let _ = child.kill();
+}
+
+#[cfg(not(unix))]
+fn has_exited(child: &mut Child) -> std::io::Result<bool> {
+    Ok(child.try_wait()?.is_some())
+}
+
+#[cfg(not(unix))]
+fn reap(child: &mut Child) -> std::io::Result<ExitStatus> {
+    child.wait()
+}
+
+/// Slots for the process groups of running tools: a group id while its tool runs, negated
+/// once an interrupt was forwarded to it, and 0 when free. Atomics because the signal
+/// handler may not lock or allocate.
+#[cfg(unix)]
+static ACTIVE_GROUPS: [AtomicI32; MAX_ACTIVE_GROUPS] =
+    [const { AtomicI32::new(0) }; MAX_ACTIVE_GROUPS];
+
+/// Tools that can be interrupted at once; more still run, under their deadline only.
+#[cfg(unix)]
+const MAX_ACTIVE_GROUPS: usize = 64;
+
+/// A running tool's slot in [`ACTIVE_GROUPS`], freed on drop (before the tool is reaped, so
+/// its group id cannot be reused while it is still listed).
+struct ActiveGroup {
+    #[cfg(unix)]
+    slot: Option<&'static AtomicI32>,
+}
+
+impl ActiveGroup {
+    #[cfg(unix)]
+    fn register(child: &Child) -> Self {
+        let group = child.id() as i32;
+        let slot = ACTIVE_GROUPS.iter().find(|slot| {
+            slot.compare_exchange(0, group, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+        });
+        ActiveGroup { slot }
+    }
+
+    #[cfg(not(unix))]
+    fn register(_child: &Child) -> Self {
+        ActiveGroup {}
+    }
+
+    /// Whether a SIGINT or SIGTERM was forwarded to this tool.
+    fn interrupted(&self) -> bool {
+        #[cfg(unix)]
+        return self.slot.is_some_and(|slot| slot.load(Ordering::SeqCst) < 0);
+        #[cfg(not(unix))]
+        false
+    }
+}
+
+impl Drop for ActiveGroup {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(slot) = self.slot {
+            slot.store(0, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Install the SIGINT/SIGTERM handler, once, unless the signal is ignored or handled
+/// elsewhere.
+#[cfg(unix)]
+fn forward_interrupts() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        for signal in [libc::SIGINT, libc::SIGTERM] {
+            // SAFETY: `sigaction` is plain data, valid when zeroed; the handler only uses
+            // async-signal-safe calls and atomics.
+            unsafe {
+                let mut previous: libc::sigaction = std::mem::zeroed();
+                if libc::sigaction(signal, std::ptr::null(), &mut previous) != 0
+                    || previous.sa_sigaction != libc::SIG_DFL
+                {
+                    continue;
+                }
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = on_interrupt as extern "C" fn(libc::c_int) as usize;
+                action.sa_flags = libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(signal, &action, std::ptr::null_mut());
+            }
+        }
+    });
+}
+
+/// Forward `signal` to every running tool's group and mark it interrupted; with no tool
+/// running, die of the signal as if no handler were installed.
+#[cfg(unix)]
+extern "C" fn on_interrupt(signal: libc::c_int) {
+    let mut forwarded = false;
+    for slot in &ACTIVE_GROUPS {
+        let group = slot.load(Ordering::SeqCst);
+        if group > 0
+            && slot.compare_exchange(group, -group, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+        {
+            // SAFETY: async-signal-safe syscall on a group whose leader is not reaped yet.
+            unsafe { libc::killpg(group, signal) };
+            forwarded = true;
+        }
+    }
+    if !forwarded {
+        // SAFETY: both are async-signal-safe; the re-raised signal is delivered once this
+        // handler returns.
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> Option<JoinHandle<Vec<u8>>> {
+    pipe.map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            buf
+        })
+    })
+}
+
+fn collect(handle: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    handle.and_then(|h| h.join().ok()).unwrap_or_default()
+}
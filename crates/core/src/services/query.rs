@@ -0,0 +1,627 @@
+//! Tiny filter language over persisted analysis results (`binary-slicer query`).
+//!
+//! ```text
+//! query      := entity [ "where" expr ] [ "order" "by" field [ "asc" | "desc" ] ] [ "limit" N ]
+//! entity     := functions | edges | evidence | blocks | xrefs
+//! expr       := and_expr { "or" and_expr }
+//! and_expr   := unary { "and" unary }
+//! unary      := "not" unary | "(" expr ")" | field [ op value ]
+//! op         := = | != | < | <= | > | >= | contains
+//! value      := number (decimal or 0x-hex) | "string" | 'string' | word | true | false | null
+//! ```
+//!
+//! A bare field is true when it is `true` or non-null, so `functions where in_slice` works.
+//! Keywords are case-insensitive; `contains` is a case-insensitive substring match.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::analysis::metrics::function_metrics;
+use crate::services::analysis::{AnalysisResult, EvidenceKind};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QueryError {
+    #[error("Query is empty")]
+    Empty,
+    #[error("Unknown entity '{0}' (expected functions, edges, evidence, blocks, or xrefs)")]
+    UnknownEntity(String),
+    #[error("Unknown field '{field}' for {entity} (known: {known})")]
+    UnknownField { entity: &'static str, field: String, known: String },
+    #[error("Syntax error at position {pos}: {message}")]
+    Syntax { pos: usize, message: String },
+}
+
+pub type QueryResult<T> = Result<T, QueryError>;
+
+/// Table a query selects rows from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entity {
+    Functions,
+    Edges,
+    Evidence,
+    Blocks,
+    Xrefs,
+}
+
+impl Entity {
+    fn parse(word: &str) -> Option<Self> {
+        match word.to_lowercase().as_str() {
+            "functions" | "function" | "funcs" => Some(Entity::Functions),
+            "edges" | "edge" | "calls" | "call_edges" => Some(Entity::Edges),
+            "evidence" => Some(Entity::Evidence),
+            "blocks" | "block" | "basic_blocks" => Some(Entity::Blocks),
+            "xrefs" | "xref" => Some(Entity::Xrefs),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Entity::Functions => "functions",
+            Entity::Edges => "edges",
+            Entity::Evidence => "evidence",
+            Entity::Blocks => "blocks",
+            Entity::Xrefs => "xrefs",
+        }
+    }
+
+    /// Column names, in output order.
+    pub fn fields(&self) -> &'static [&'static str] {
+        match self {
+            Entity::Functions => &[
+                "address",
+                "name",
+                "size",
+                "in_slice",
+                "is_boundary",
+                "insn_count",
+                "blocks",
+                "complexity",
+                "fan_in",
+                "fan_out",
+                "string_refs",
+            ],
+            Entity::Edges => &["from", "to", "from_name", "to_name", "is_cross_slice"],
+            Entity::Evidence => &["address", "kind", "description", "source", "run_id"],
+            Entity::Blocks => &["start", "byte_len", "insn_count", "successors"],
+            Entity::Xrefs => &["from", "to", "kind", "section", "preview"],
+        }
+    }
+
+    fn field_index(&self, field: &str) -> QueryResult<usize> {
+        let lower = field.to_lowercase();
+        self.fields().iter().position(|f| *f == lower).ok_or_else(|| QueryError::UnknownField {
+            entity: self.as_str(),
+            field: field.to_string(),
+            known: self.fields().join(", "),
+        })
+    }
+}
+
+/// A cell value. Addresses compare numerically but render as hex in tables.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(u64),
+    Addr(u64),
+    Str(String),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        !matches!(self, Value::Null | Value::Bool(false))
+    }
+
+    fn as_int(&self) -> Option<u64> {
+        match self {
+            Value::Int(v) | Value::Addr(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_text(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Bool(b) => b.to_string(),
+            Value::Int(v) => v.to_string(),
+            Value::Addr(v) => format!("0x{:X}", v),
+            Value::Str(s) => s.clone(),
+        }
+    }
+
+    /// Ordering used by comparisons and `order by`; `None` when types are incomparable.
+    fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Null, Value::Null) => Some(Ordering::Equal),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+            _ => match (self.as_int(), other.as_int()) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "-"),
+            other => write!(f, "{}", other.as_text()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+/// Filter expression; fields are resolved to column indexes at parse time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Truthy(usize),
+    Compare { field: usize, op: CompareOp, value: Value },
+}
+
+impl Expr {
+    fn eval(&self, row: &[Value]) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(row) && b.eval(row),
+            Expr::Or(a, b) => a.eval(row) || b.eval(row),
+            Expr::Not(inner) => !inner.eval(row),
+            Expr::Truthy(field) => row[*field].truthy(),
+            Expr::Compare { field, op, value } => {
+                let cell = &row[*field];
+                match op {
+                    CompareOp::Contains => {
+                        !matches!(cell, Value::Null)
+                            && cell
+                                .as_text()
+                                .to_lowercase()
+                                .contains(&value.as_text().to_lowercase())
+                    }
+                    CompareOp::Eq => loose_eq(cell, value),
+                    CompareOp::Ne => !loose_eq(cell, value),
+                    ordering => match cell.compare(value) {
+                        Some(ord) => match ordering {
+                            CompareOp::Lt => ord == Ordering::Less,
+                            CompareOp::Le => ord != Ordering::Greater,
+                            CompareOp::Gt => ord == Ordering::Greater,
+                            _ => ord != Ordering::Less,
+                        },
+                        None => false,
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Equality that lets bare words match strings/kinds case-insensitively (`kind = String`).
+fn loose_eq(cell: &Value, value: &Value) -> bool {
+    match (cell, value) {
+        (Value::Str(a), Value::Str(b)) => a.eq_ignore_ascii_case(b),
+        _ => cell.compare(value) == Some(Ordering::Equal),
+    }
+}
+
+/// Parsed query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub entity: Entity,
+    pub filter: Option<Expr>,
+    /// Column index and descending flag.
+    pub order_by: Option<(usize, bool)>,
+    pub limit: Option<usize>,
+}
+
+/// Rows matched by a query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryOutput {
+    pub entity: Entity,
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl QueryOutput {
+    /// Rows as JSON objects keyed by column name.
+    pub fn to_json(&self) -> serde_json::Value {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: serde_json::Map<String, serde_json::Value> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(col, value)| {
+                        (col.to_string(), serde_json::to_value(value).unwrap_or_default())
+                    })
+                    .collect();
+                serde_json::Value::Object(object)
+            })
+            .collect();
+        serde_json::Value::Array(rows)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(u64),
+    Str(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> QueryResult<Vec<(usize, Token)>> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let syntax = |message: String| QueryError::Syntax { pos, message };
+        match c {
+            '(' | ')' => {
+                tokens.push((pos, if c == '(' { Token::LParen } else { Token::RParen }));
+                i += 1;
+            }
+            '"' | '\'' => {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some((_, '\\')) if chars.get(i + 1).is_some() => {
+                            text.push(chars[i + 1].1);
+                            i += 2;
+                        }
+                        Some((_, ch)) if *ch == c => {
+                            i += 1;
+                            break;
+                        }
+                        Some((_, ch)) => {
+                            text.push(*ch);
+                            i += 1;
+                        }
+                        None => return Err(syntax("unterminated string".into())),
+                    }
+                }
+                tokens.push((pos, Token::Str(text)));
+            }
+            '=' | '!' | '<' | '>' => {
+                let next = chars.get(i + 1).map(|(_, ch)| *ch);
+                let (op, width) = match (c, next) {
+                    ('=', Some('=')) => (CompareOp::Eq, 2),
+                    ('=', _) => (CompareOp::Eq, 1),
+                    ('!', Some('=')) => (CompareOp::Ne, 2),
+                    ('<', Some('=')) => (CompareOp::Le, 2),
+                    ('<', Some('>')) => (CompareOp::Ne, 2),
+                    ('<', _) => (CompareOp::Lt, 1),
+                    ('>', Some('=')) => (CompareOp::Ge, 2),
+                    ('>', _) => (CompareOp::Gt, 1),
+                    _ => return Err(syntax("expected '!='".into())),
+                };
+                tokens.push((pos, Token::Op(op)));
+                i += width;
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == ':' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].1.is_ascii_alphanumeric() || matches!(chars[i].1, '_' | '.' | ':'))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().map(|(_, ch)| *ch).collect();
+                let number = match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+                    Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                    None if c.is_ascii_digit() => word.parse::<u64>().ok(),
+                    None => None,
+                };
+                match number {
+                    Some(n) => tokens.push((pos, Token::Number(n))),
+                    None if c.is_ascii_digit() => {
+                        return Err(syntax(format!("invalid number '{word}'")))
+                    }
+                    None if word.eq_ignore_ascii_case("contains") => {
+                        tokens.push((pos, Token::Op(CompareOp::Contains)))
+                    }
+                    None => tokens.push((pos, Token::Word(word))),
+                }
+            }
+            other => return Err(syntax(format!("unexpected character '{other}'"))),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    entity: Entity,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map(|(p, _)| *p).unwrap_or(self.end)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> QueryResult<T> {
+        Err(QueryError::Syntax { pos: self.offset(), message: message.into() })
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn field(&mut self) -> QueryResult<usize> {
+        match self.peek().cloned() {
+            Some(Token::Word(word)) => {
+                self.pos += 1;
+                self.entity.field_index(&word)
+            }
+            _ => self.error("expected a field name"),
+        }
+    }
+
+    fn or_expr(&mut self) -> QueryResult<Expr> {
+        let mut expr = self.and_expr()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> QueryResult<Expr> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> QueryResult<Expr> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.or_expr()?;
+            if self.peek() != Some(&Token::RParen) {
+                return self.error("expected ')'");
+            }
+            self.pos += 1;
+            return Ok(expr);
+        }
+        let field = self.field()?;
+        let Some(Token::Op(op)) = self.peek().cloned() else {
+            return Ok(Expr::Truthy(field));
+        };
+        self.pos += 1;
+        let value = match self.peek().cloned() {
+            Some(Token::Number(n)) => Value::Int(n),
+            Some(Token::Str(s)) => Value::Str(s),
+            Some(Token::Word(w)) => match w.to_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" | "none" => Value::Null,
+                _ => Value::Str(w),
+            },
+            _ => return self.error("expected a value"),
+        };
+        self.pos += 1;
+        Ok(Expr::Compare { field, op, value })
+    }
+}
+
+/// Parse a query string.
+pub fn parse_query(input: &str) -> QueryResult<Query> {
+    let tokens = tokenize(input)?;
+    let Some((_, first)) = tokens.first() else {
+        return Err(QueryError::Empty);
+    };
+    let entity = match first {
+        Token::Word(w) => Entity::parse(w).ok_or_else(|| QueryError::UnknownEntity(w.clone()))?,
+        _ => return Err(QueryError::Syntax { pos: 0, message: "expected an entity".into() }),
+    };
+    let mut parser = Parser { tokens, pos: 1, end: input.len(), entity };
+
+    let filter = if parser.keyword("where") { Some(parser.or_expr()?) } else { None };
+    let order_by = if parser.keyword("order") {
+        if !parser.keyword("by") {
+            return parser.error("expected 'by' after 'order'");
+        }
+        let field = parser.field()?;
+        let descending = if parser.keyword("desc") {
+            true
+        } else {
+            parser.keyword("asc");
+            false
+        };
+        Some((field, descending))
+    } else {
+        None
+    };
+    let limit = if parser.keyword("limit") {
+        match parser.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n as usize;
+                parser.pos += 1;
+                Some(n)
+            }
+            _ => return parser.error("expected a number after 'limit'"),
+        }
+    } else {
+        None
+    };
+    if parser.pos < parser.tokens.len() {
+        return parser.error("unexpected trailing input");
+    }
+    Ok(Query { entity, filter, order_by, limit })
+}
+
+fn evidence_kind(kind: &Option<EvidenceKind>) -> Value {
+    match kind {
+        Some(EvidenceKind::String) => Value::Str("string".into()),
+        Some(EvidenceKind::Import) => Value::Str("import".into()),
+        Some(EvidenceKind::Call) => Value::Str("call".into()),
+        Some(EvidenceKind::Other) => Value::Str("other".into()),
+        None => Value::Null,
+    }
+}
+
+fn opt_str(value: &Option<String>) -> Value {
+    value.clone().map(Value::Str).unwrap_or(Value::Null)
+}
+
+/// Materialize an entity's rows in [`Entity::fields`] order.
+fn rows(entity: Entity, result: &AnalysisResult) -> Vec<Vec<Value>> {
+    match entity {
+        Entity::Functions => result
+            .functions
+            .iter()
+            .zip(function_metrics(result))
+            .map(|(f, m)| {
+                vec![
+                    Value::Addr(f.address),
+                    opt_str(&f.name),
+                    f.size.map(|s| Value::Int(s as u64)).unwrap_or(Value::Null),
+                    Value::Bool(f.in_slice),
+                    Value::Bool(f.is_boundary),
+                    m.insn_count.map(Value::Int).unwrap_or(Value::Null),
+                    Value::Int(m.blocks),
+                    m.complexity.map(Value::Int).unwrap_or(Value::Null),
+                    Value::Int(m.fan_in),
+                    Value::Int(m.fan_out),
+                    Value::Int(m.string_refs),
+                ]
+            })
+            .collect(),
+        Entity::Edges => {
+            let names: HashMap<u64, &String> = result
+                .functions
+                .iter()
+                .filter_map(|f| f.name.as_ref().map(|n| (f.address, n)))
+                .collect();
+            let name = |addr: u64| {
+                names.get(&addr).map(|n| Value::Str((*n).clone())).unwrap_or(Value::Null)
+            };
+            result
+                .call_edges
+                .iter()
+                .map(|e| {
+                    vec![
+                        Value::Addr(e.from),
+                        Value::Addr(e.to),
+                        name(e.from),
+                        e.to_name.clone().map(Value::Str).unwrap_or_else(|| name(e.to)),
+                        Value::Bool(e.is_cross_slice),
+                    ]
+                })
+                .collect()
+        }
+        Entity::Evidence => result
+            .evidence
+            .iter()
+            .map(|e| {
+                vec![
+                    Value::Addr(e.address),
+                    evidence_kind(&e.kind),
+                    Value::Str(e.description.clone()),
+                    e.source.as_ref().map(|s| Value::Str(s.to_string())).unwrap_or(Value::Null),
+                    e.run_id.map(|id| Value::Int(id as u64)).unwrap_or(Value::Null),
+                ]
+            })
+            .collect(),
+        Entity::Blocks => result
+            .basic_blocks
+            .iter()
+            .map(|b| {
+                vec![
+                    Value::Addr(b.start),
+                    Value::Int(b.byte_len as u64),
+                    b.insn_count.map(|n| Value::Int(n as u64)).unwrap_or(Value::Null),
+                    Value::Int(b.successors.len() as u64),
+                ]
+            })
+            .collect(),
+        Entity::Xrefs => result
+            .xrefs
+            .iter()
+            .map(|x| {
+                vec![
+                    Value::Addr(x.from),
+                    Value::Addr(x.to),
+                    Value::Str(x.kind.as_str().into()),
+                    opt_str(&x.section),
+                    opt_str(&x.preview),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Run a parsed query against an analysis result.
+pub fn execute_query(query: &Query, result: &AnalysisResult) -> QueryOutput {
+    let mut matched: Vec<Vec<Value>> = rows(query.entity, result)
+        .into_iter()
+        .filter(|row| query.filter.as_ref().is_none_or(|f| f.eval(row)))
+        .collect();
+    if let Some((field, descending)) = query.order_by {
+        // Nulls sort last in either direction.
+        matched.sort_by(|a, b| {
+            let ord = match (&a[field], &b[field]) {
+                (Value::Null, Value::Null) => Ordering::Equal,
+                (Value::Null, _) => return Ordering::Greater,
+                (_, Value::Null) => return Ordering::Less,
+                (x, y) => x.compare(y).unwrap_or(Ordering::Equal),
+            };
+            if descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+    }
+    if let Some(limit) = query.limit {
+        matched.truncate(limit);
+    }
+    QueryOutput { entity: query.entity, columns: query.entity.fields().to_vec(), rows: matched }
+}
+
+/// Parse and run a query in one step.
+pub fn run_query(input: &str, result: &AnalysisResult) -> QueryResult<QueryOutput> {
+    Ok(execute_query(&parse_query(input)?, result))
+}
@@ -0,0 +1,446 @@
+//! Self-contained HTML slice reports (`reports/<slice>.html`).
+//!
+//! The page inlines its stylesheet and an SVG rendering of the call graph, so it can be
+//! mailed or attached to a ticket and opened without the CLI, Graphviz, or network access.
+//! Evidence sections are `<details>` blocks and need no script.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::Write;
+
+use crate::analysis::coverage::slice_coverage;
+use crate::db::RunNote;
+use crate::services::analysis::{AnalysisResult, EvidenceRecord};
+
+/// Nodes drawn in the embedded call graph; larger graphs are truncated (by address).
+pub const SVG_MAX_NODES: usize = 300;
+
+const NODE_WIDTH: usize = 168;
+const NODE_HEIGHT: usize = 28;
+const H_GAP: usize = 24;
+const V_GAP: usize = 48;
+const MARGIN: usize = 16;
+const NODES_PER_ROW: usize = 8;
+const LABEL_CHARS: usize = 22;
+
+/// One collapsible evidence section: exact total plus the records to list.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceSection {
+    pub title: String,
+    pub total: usize,
+    pub records: Vec<EvidenceRecord>,
+}
+
+/// Inputs for one slice's HTML report.
+#[derive(Debug, Clone, Default)]
+pub struct SliceHtmlReport<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub binary: Option<&'a str>,
+    pub backend: Option<&'a str>,
+    pub backend_version: Option<&'a str>,
+    pub roots: &'a [String],
+    pub analysis: Option<&'a AnalysisResult>,
+    /// Evidence totals per function address, shown in the function table.
+    pub function_evidence: HashMap<u64, usize>,
+    pub evidence: Vec<EvidenceSection>,
+    /// Notes attached to the run with `annotate-run`, oldest first.
+    pub notes: &'a [RunNote],
+}
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #1d2330; }
+h1 { margin-bottom: 0.2em; }
+.meta { color: #5a6272; margin-bottom: 1.5em; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5em; font-size: 0.92em; }
+th, td { border: 1px solid #d6dae2; padding: 4px 8px; text-align: left; }
+th { background: #eef1f6; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+code { font-family: "SFMono-Regular", Consolas, monospace; }
+tr.out td { color: #7a8190; }
+details { border: 1px solid #d6dae2; border-radius: 4px; margin-bottom: 0.6em; padding: 0.4em 0.8em; }
+summary { cursor: pointer; font-weight: 600; }
+.graph { overflow: auto; border: 1px solid #d6dae2; border-radius: 4px; margin-bottom: 1.5em; }
+.graph svg text { font-family: "SFMono-Regular", Consolas, monospace; font-size: 11px; }
+.note { color: #5a6272; font-style: italic; }
+"#;
+
+/// Escape text for HTML element content and attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn function_label(name: Option<&String>, address: u64) -> String {
+    name.cloned().unwrap_or_else(|| format!("0x{:X}", address))
+}
+
+fn truncate_label(label: &str) -> String {
+    if label.chars().count() <= LABEL_CHARS {
+        label.to_string()
+    } else {
+        let head: String = label.chars().take(LABEL_CHARS - 1).collect();
+        format!("{head}…")
+    }
+}
+
+/// Render the full report page.
+pub fn render_slice_report(report: &SliceHtmlReport<'_>) -> String {
+    let mut out = String::new();
+    let title = escape_html(report.name);
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Slice report: {title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n"
+    );
+    if let Some(desc) = report.description {
+        let _ = writeln!(out, "<p>{}</p>", escape_html(desc));
+    }
+    let mut meta = Vec::new();
+    if let Some(binary) = report.binary {
+        meta.push(format!("Binary: <code>{}</code>", escape_html(binary)));
+    }
+    if let Some(backend) = report.backend {
+        let version =
+            report.backend_version.map(|v| format!(" {}", escape_html(v))).unwrap_or_default();
+        meta.push(format!("Backend: {}{}", escape_html(backend), version));
+    }
+    meta.push(format!("binary-slicer {}", crate::version()));
+    let _ = writeln!(out, "<div class=\"meta\">{}</div>", meta.join(" &middot; "));
+
+    let Some(analysis) = report.analysis else {
+        out.push_str("<p class=\"note\">No analysis run recorded for this slice yet.</p>\n");
+        out.push_str("</body>\n</html>\n");
+        return out;
+    };
+
+    write_summary(&mut out, report, analysis);
+    write_roots(&mut out, report.roots, analysis);
+    write_notes(&mut out, report.notes);
+
+    out.push_str("<h2>Call graph</h2>\n<div class=\"graph\">\n");
+    out.push_str(&call_graph_svg(analysis));
+    out.push_str("</div>\n");
+
+    write_function_table(&mut out, analysis, &report.function_evidence);
+
+    out.push_str("<h2>Evidence</h2>\n");
+    if report.evidence.iter().all(|s| s.total == 0) {
+        out.push_str("<p class=\"note\">No evidence recorded.</p>\n");
+    }
+    for section in report.evidence.iter().filter(|s| s.total > 0) {
+        write_evidence_section(&mut out, section);
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn write_summary(out: &mut String, report: &SliceHtmlReport<'_>, analysis: &AnalysisResult) {
+    let in_slice = analysis.functions.iter().filter(|f| f.in_slice).count();
+    let boundary = analysis.functions.iter().filter(|f| f.is_boundary).count();
+    let cross = analysis.call_edges.iter().filter(|e| e.is_cross_slice).count();
+    let evidence: usize = report.evidence.iter().map(|s| s.total).sum();
+    let _ = writeln!(
+        out,
+        "<h2>Summary</h2>\n<table>\n\
+         <tr><th>Functions</th><td class=\"num\">{}</td><th>In slice</th><td class=\"num\">{}</td>\
+         <th>Boundary</th><td class=\"num\">{}</td></tr>\n\
+         <tr><th>Call edges</th><td class=\"num\">{}</td><th>Cross-slice</th><td class=\"num\">{}</td>\
+         <th>Basic blocks</th><td class=\"num\">{}</td></tr>\n\
+         <tr><th>Roots</th><td class=\"num\">{}</td><th>Evidence</th><td class=\"num\">{}</td>\
+         <th>Sub-slices</th><td class=\"num\">{}</td></tr>",
+        analysis.functions.len(),
+        in_slice,
+        boundary,
+        analysis.call_edges.len(),
+        cross,
+        analysis.basic_blocks.len(),
+        report.roots.len(),
+        evidence,
+        analysis.sub_slices.len(),
+    );
+    let coverage = slice_coverage(analysis);
+    let dash = || "-".to_string();
+    let _ = writeln!(
+        out,
+        "<tr><th>Slice coverage</th><td class=\"num\">{}</td>\
+         <th>Slice bytes</th><td class=\"num\">{}</td>\
+         <th>Unexplored bytes</th><td class=\"num\">{}</td></tr>\n</table>",
+        coverage.slice_ratio().map(|r| format!("{:.1}%", r * 100.0)).unwrap_or_else(dash),
+        match coverage.text_bytes {
+            Some(text) => format!("{} / {}", coverage.slice_bytes, text),
+            None => coverage.slice_bytes.to_string(),
+        },
+        coverage.unexplored_bytes().map(|n| n.to_string()).unwrap_or_else(dash),
+    );
+}
+
+fn write_notes(out: &mut String, notes: &[RunNote]) {
+    if notes.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Notes</h2>\n<ul>\n");
+    for note in notes {
+        let _ = writeln!(
+            out,
+            "<li><span class=\"meta\">{}</span> {}</li>",
+            escape_html(&note.created_at),
+            escape_html(&note.note)
+        );
+    }
+    out.push_str("</ul>\n");
+}
+
+fn write_roots(out: &mut String, roots: &[String], analysis: &AnalysisResult) {
+    out.push_str("<h2>Roots</h2>\n");
+    if roots.is_empty() {
+        out.push_str("<p class=\"note\">No roots recorded.</p>\n");
+        return;
+    }
+    out.push_str("<ul>\n");
+    for root in roots {
+        let hits = analysis
+            .root_hits
+            .iter()
+            .find(|h| &h.root == root)
+            .map(|h| h.functions.as_slice())
+            .unwrap_or_default();
+        let status = if hits.is_empty() {
+            "unmatched".to_string()
+        } else {
+            let addrs: Vec<String> = hits.iter().map(|a| format!("0x{:X}", a)).collect();
+            format!("matched &rarr; {}", addrs.join(", "))
+        };
+        let _ = writeln!(out, "<li><code>{}</code> ({})</li>", escape_html(root), status);
+    }
+    out.push_str("</ul>\n");
+}
+
+fn write_function_table(
+    out: &mut String,
+    analysis: &AnalysisResult,
+    function_evidence: &HashMap<u64, usize>,
+) {
+    let mut calls_out: HashMap<u64, usize> = HashMap::new();
+    let mut calls_in: HashMap<u64, usize> = HashMap::new();
+    for e in &analysis.call_edges {
+        *calls_out.entry(e.from).or_default() += 1;
+        *calls_in.entry(e.to).or_default() += 1;
+    }
+    out.push_str(
+        "<h2>Functions</h2>\n<table>\n<tr><th>Address</th><th>Name</th><th>Size</th>\
+         <th>In slice</th><th>Boundary</th><th>Calls out</th><th>Called by</th>\
+         <th>Evidence</th></tr>\n",
+    );
+    let mut functions: Vec<_> = analysis.functions.iter().collect();
+    functions.sort_by_key(|f| f.address);
+    for f in functions {
+        let class = if f.in_slice { "" } else { " class=\"out\"" };
+        let _ = writeln!(
+            out,
+            "<tr{class} id=\"fn-{addr:x}\"><td><code>0x{addr:X}</code></td><td>{name}</td>\
+             <td class=\"num\">{size}</td><td>{in_slice}</td><td>{boundary}</td>\
+             <td class=\"num\">{out_calls}</td><td class=\"num\">{in_calls}</td>\
+             <td class=\"num\">{evidence}</td></tr>",
+            addr = f.address,
+            name = escape_html(&function_label(f.name.as_ref(), f.address)),
+            size = f.size.map(|s| s.to_string()).unwrap_or_default(),
+            in_slice = if f.in_slice { "yes" } else { "no" },
+            boundary = if f.is_boundary { "yes" } else { "" },
+            out_calls = calls_out.get(&f.address).copied().unwrap_or(0),
+            in_calls = calls_in.get(&f.address).copied().unwrap_or(0),
+            evidence = function_evidence.get(&f.address).copied().unwrap_or(0),
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+fn write_evidence_section(out: &mut String, section: &EvidenceSection) {
+    let _ = writeln!(
+        out,
+        "<details>\n<summary>{} ({})</summary>\n<table>\n<tr><th>Address</th><th>Description</th><th>Source</th></tr>",
+        escape_html(&section.title),
+        section.total
+    );
+    for e in &section.records {
+        let _ = writeln!(
+            out,
+            "<tr><td><code>0x{:X}</code></td><td>{}</td><td>{}</td></tr>",
+            e.address,
+            escape_html(&e.description),
+            e.source.as_ref().map_or_else(String::new, |s| escape_html(&s.to_string()))
+        );
+    }
+    out.push_str("</table>\n");
+    if section.total > section.records.len() {
+        let _ = writeln!(
+            out,
+            "<p class=\"note\">Showing {} of {}; see the JSON report for all records.</p>",
+            section.records.len(),
+            section.total
+        );
+    }
+    out.push_str("</details>\n");
+}
+
+/// Lay out the call graph top-down and render it as inline SVG.
+///
+/// Levels are breadth-first distances from the root hits (or from functions nobody calls);
+/// wide levels wrap onto several rows. Call targets that are not known functions are drawn
+/// dashed, cross-slice edges in orange.
+pub fn call_graph_svg(analysis: &AnalysisResult) -> String {
+    let mut names: BTreeMap<u64, Option<&String>> =
+        analysis.functions.iter().map(|f| (f.address, f.name.as_ref())).collect();
+    let known: BTreeSet<u64> = names.keys().copied().collect();
+    for e in &analysis.call_edges {
+        names.entry(e.from).or_insert(None);
+        names.entry(e.to).or_insert(None);
+    }
+    let omitted = names.len().saturating_sub(SVG_MAX_NODES);
+    let nodes: Vec<u64> = names.keys().copied().take(SVG_MAX_NODES).collect();
+    let included: BTreeSet<u64> = nodes.iter().copied().collect();
+    let edges: Vec<_> = analysis
+        .call_edges
+        .iter()
+        .filter(|e| included.contains(&e.from) && included.contains(&e.to))
+        .collect();
+
+    let mut successors: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+    let mut has_caller: BTreeSet<u64> = BTreeSet::new();
+    for e in &edges {
+        successors.entry(e.from).or_default().push(e.to);
+        if e.from != e.to {
+            has_caller.insert(e.to);
+        }
+    }
+    let mut seeds: Vec<u64> = analysis
+        .root_hits
+        .iter()
+        .flat_map(|h| h.functions.iter().copied())
+        .filter(|a| included.contains(a))
+        .collect();
+    if seeds.is_empty() {
+        seeds = nodes.iter().copied().filter(|a| !has_caller.contains(a)).collect();
+    }
+
+    // BFS levels; anything unreachable starts a new search at level 0.
+    let mut level: HashMap<u64, usize> = HashMap::new();
+    let mut pending = seeds.into_iter().chain(nodes.iter().copied());
+    while let Some(start) = pending.by_ref().find(|a| !level.contains_key(a)) {
+        let mut queue = VecDeque::from([start]);
+        level.insert(start, 0);
+        while let Some(addr) = queue.pop_front() {
+            let next = level[&addr] + 1;
+            for target in successors.get(&addr).into_iter().flatten() {
+                if !level.contains_key(target) {
+                    level.insert(*target, next);
+                    queue.push_back(*target);
+                }
+            }
+        }
+    }
+
+    let mut by_level: BTreeMap<usize, Vec<u64>> = BTreeMap::new();
+    for addr in &nodes {
+        by_level.entry(level[addr]).or_default().push(*addr);
+    }
+    let mut position: HashMap<u64, (usize, usize)> = HashMap::new();
+    let mut row = 0;
+    let mut max_columns = 1;
+    for members in by_level.values() {
+        for chunk in members.chunks(NODES_PER_ROW) {
+            max_columns = max_columns.max(chunk.len());
+            for (col, addr) in chunk.iter().enumerate() {
+                let x = MARGIN + col * (NODE_WIDTH + H_GAP);
+                let y = MARGIN + row * (NODE_HEIGHT + V_GAP);
+                position.insert(*addr, (x, y));
+            }
+            row += 1;
+        }
+    }
+    let width = MARGIN * 2 + max_columns * NODE_WIDTH + (max_columns - 1) * H_GAP;
+    let height = MARGIN * 2 + row.max(1) * NODE_HEIGHT + row.saturating_sub(1) * V_GAP;
+    let footer = if omitted > 0 { 20 } else { 0 };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"Call graph\">",
+        w = width,
+        h = height + footer
+    );
+    svg.push_str(
+        "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+         markerWidth=\"7\" markerHeight=\"7\" orient=\"auto-start-reverse\">\
+         <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#6b7385\"/></marker></defs>\n",
+    );
+    if nodes.is_empty() {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{MARGIN}\" y=\"{}\">(no functions recorded)</text>",
+            MARGIN + 14
+        );
+    }
+    for e in &edges {
+        let (fx, fy) = position[&e.from];
+        let (tx, ty) = position[&e.to];
+        let stroke = if e.is_cross_slice { "#d9822b" } else { "#6b7385" };
+        let (x1, y1, x2, y2) = if ty > fy {
+            (fx + NODE_WIDTH / 2, fy + NODE_HEIGHT, tx + NODE_WIDTH / 2, ty)
+        } else if ty < fy {
+            (fx + NODE_WIDTH / 2, fy, tx + NODE_WIDTH / 2, ty + NODE_HEIGHT)
+        } else if e.from == e.to {
+            (fx + NODE_WIDTH, fy + 6, fx + NODE_WIDTH, fy + NODE_HEIGHT - 6)
+        } else if tx > fx {
+            (fx + NODE_WIDTH, fy + NODE_HEIGHT / 2, tx, ty + NODE_HEIGHT / 2)
+        } else {
+            (fx, fy + NODE_HEIGHT / 2, tx + NODE_WIDTH, ty + NODE_HEIGHT / 2)
+        };
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{stroke}\" \
+             stroke-width=\"1.2\" marker-end=\"url(#arrow)\"/>"
+        );
+    }
+    for addr in &nodes {
+        let (x, y) = position[addr];
+        let label = function_label(names[addr], *addr);
+        let (fill, dash) = if known.contains(addr) {
+            let in_slice = analysis.functions.iter().any(|f| f.address == *addr && f.in_slice);
+            (if in_slice { "#e3ecfb" } else { "#f2f3f5" }, "")
+        } else {
+            ("#ffffff", " stroke-dasharray=\"4 3\"")
+        };
+        let _ = writeln!(
+            svg,
+            "<g><title>{title} @ 0x{addr:X}</title>\
+             <rect x=\"{x}\" y=\"{y}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"4\" \
+             fill=\"{fill}\" stroke=\"#5b6b8c\"{dash}/>\
+             <text x=\"{tx}\" y=\"{ty}\" text-anchor=\"middle\">{text}</text></g>",
+            title = escape_html(&label),
+            tx = x + NODE_WIDTH / 2,
+            ty = y + NODE_HEIGHT / 2 + 4,
+            text = escape_html(&truncate_label(&label)),
+        );
+    }
+    if omitted > 0 {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{MARGIN}\" y=\"{}\">… {omitted} more nodes omitted</text>",
+            height + 12
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
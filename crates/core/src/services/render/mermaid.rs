@@ -0,0 +1,106 @@
+//! Mermaid call-graph snippets for slice markdown docs.
+//!
+//! GitHub renders ```` ```mermaid ```` blocks inline, so a slice doc can show its call
+//! structure without Graphviz. Only functions and call edges are drawn; in-slice functions
+//! are clustered by sub-slice and the graph is capped so large slices stay readable.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::services::analysis::AnalysisResult;
+
+/// Default node cap for embedded graphs (larger Mermaid graphs render poorly on GitHub).
+pub const MERMAID_MAX_NODES: usize = 50;
+
+/// Render a fenced `mermaid` block with a `graph TD` of the result's call graph.
+///
+/// In-slice functions are grouped into a subgraph per sub-slice (the rest into `slice`);
+/// functions outside the slice and external call targets are drawn around them.
+///
+/// At most `max_nodes` functions are drawn: in-slice functions first, then other functions,
+/// then external call targets, each by address. The rest fold into one overflow node that
+/// keeps their edges. Parallel calls are drawn once, boundary functions are highlighted, and
+/// cross-slice calls are dotted.
+pub fn mermaid_call_graph(result: &AnalysisResult, max_nodes: usize) -> String {
+    let names: HashMap<u64, &str> =
+        result.functions.iter().filter_map(|f| Some((f.address, f.name.as_deref()?))).collect();
+    let known: HashMap<u64, (bool, bool)> =
+        result.functions.iter().map(|f| (f.address, (f.in_slice, f.is_boundary))).collect();
+    let mut addresses: BTreeSet<u64> = known.keys().copied().collect();
+    for edge in &result.call_edges {
+        addresses.insert(edge.from);
+        addresses.insert(edge.to);
+    }
+    let mut order: Vec<u64> = addresses.into_iter().collect();
+    order.sort_by_key(|addr| match known.get(addr) {
+        Some((true, _)) => (0, *addr),
+        Some((false, _)) => (1, *addr),
+        None => (2, *addr),
+    });
+    let dropped = order.len().saturating_sub(max_nodes);
+    order.truncate(max_nodes);
+    let ids: HashMap<u64, String> =
+        order.iter().enumerate().map(|(i, addr)| (*addr, format!("f{i}"))).collect();
+    let node_id = |addr: u64| ids.get(&addr).map(String::as_str).unwrap_or("overflow");
+
+    let mut clusters: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+    let mut clustered = BTreeSet::new();
+    for sub in &result.sub_slices {
+        for addr in sub.functions.iter().filter(|a| ids.contains_key(a)) {
+            if clustered.insert(*addr) {
+                clusters.entry(sub.label.as_str()).or_default().push(*addr);
+            }
+        }
+    }
+    for addr in order.iter().filter(|a| known.get(a).is_some_and(|(in_slice, _)| *in_slice)) {
+        if clustered.insert(*addr) {
+            clusters.entry("slice").or_default().push(*addr);
+        }
+    }
+
+    let mut out = String::from("```mermaid\ngraph TD\n");
+    let node = |addr: u64| {
+        let label = names.get(&addr).map(|n| n.to_string()).unwrap_or(format!("0x{:X}", addr));
+        format!("{}[\"{}\"]", node_id(addr), escape_label(&label))
+    };
+    for (i, (label, members)) in clusters.iter().enumerate() {
+        let _ = writeln!(out, "  subgraph c{}[\"{}\"]", i, escape_label(label));
+        for addr in members {
+            let _ = writeln!(out, "    {}", node(*addr));
+        }
+        out.push_str("  end\n");
+    }
+    for addr in order.iter().filter(|a| !clustered.contains(a)) {
+        let _ = writeln!(out, "  {}", node(*addr));
+    }
+    if dropped > 0 {
+        let _ = writeln!(out, "  overflow[\"+{} more functions\"]", dropped);
+    }
+
+    let mut seen = BTreeSet::new();
+    for edge in &result.call_edges {
+        let (from, to) = (node_id(edge.from), node_id(edge.to));
+        if (from == "overflow" && to == "overflow") || !seen.insert((from, to)) {
+            continue;
+        }
+        let arrow = if edge.is_cross_slice { "-.->" } else { "-->" };
+        let _ = writeln!(out, "  {} {} {}", from, arrow, to);
+    }
+
+    let boundary: Vec<&str> = order
+        .iter()
+        .filter(|a| known.get(a).is_some_and(|(_, is_boundary)| *is_boundary))
+        .map(|a| node_id(*a))
+        .collect();
+    if !boundary.is_empty() {
+        out.push_str("  classDef boundary fill:#f6d7a7\n");
+        let _ = writeln!(out, "  class {} boundary", boundary.join(","));
+    }
+    out.push_str("```\n");
+    out
+}
+
+/// Escape a node label for a quoted Mermaid string (entity codes for markup characters).
+fn escape_label(text: &str) -> String {
+    text.replace('#', "#35;").replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
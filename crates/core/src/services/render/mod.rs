@@ -0,0 +1,5 @@
+//! Renderers that turn persisted analysis into shareable artifacts.
+
+pub mod html;
+pub mod mermaid;
+pub mod sarif;
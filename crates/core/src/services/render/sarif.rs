@@ -0,0 +1,375 @@
+//! SARIF 2.1.0 slice reports (`reports/<slice>.sarif`) for code-review tooling.
+//!
+//! Each slice becomes one SARIF run. Slice functions, boundary functions, evidence, and
+//! roots that resolved to nothing become results. Locations are binary addresses: the
+//! binary is the run's artifact, its sections are listed in `run.addresses`, and every
+//! result's `physicalLocation.address` points at its section via `parentIndex` (with the
+//! section-relative offset in `offsetFromParent`). The containing function is reported as a
+//! logical location.
+//!
+//! [`SliceSarifWriter`] writes the log incrementally, so a run's evidence can be streamed
+//! from the project DB page by page; [`render_slice_sarif`] renders a log in memory.
+
+use std::io::{self, Write};
+
+use serde_json::{json, Map, Value};
+
+use crate::services::analysis::{AnalysisResult, EvidenceKind, EvidenceRecord, FunctionRecord};
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A named address range of the analyzed binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinarySection {
+    pub name: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Named sections of an object file; empty when the format is unknown or no object parser
+/// is compiled in.
+pub fn binary_sections(bytes: &[u8]) -> Vec<BinarySection> {
+    #[cfg(feature = "capstone-backend")]
+    {
+        crate::services::backends::capstone::binary_sections(bytes)
+    }
+    #[cfg(not(feature = "capstone-backend"))]
+    {
+        let _ = bytes;
+        Vec::new()
+    }
+}
+
+/// Inputs for one slice's SARIF log.
+#[derive(Debug, Clone, Default)]
+pub struct SliceSarifReport<'a> {
+    pub slice: &'a str,
+    pub description: Option<&'a str>,
+    pub tool_version: &'a str,
+    pub ritual: Option<&'a str>,
+    pub binary: Option<&'a str>,
+    /// Artifact URI of the binary (its registered path).
+    pub binary_uri: Option<&'a str>,
+    pub binary_hash: Option<&'a str>,
+    pub backend: Option<&'a str>,
+    pub backend_version: Option<&'a str>,
+    /// Functions and root hits of the run; its `evidence` is only used by
+    /// [`render_slice_sarif`].
+    pub analysis: Option<&'a AnalysisResult>,
+    pub sections: &'a [BinarySection],
+}
+
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    level: &'static str,
+    description: &'static str,
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        id: "slice/function",
+        name: "SliceFunction",
+        level: "note",
+        description: "Function belongs to the slice.",
+    },
+    Rule {
+        id: "slice/boundary",
+        name: "SliceBoundary",
+        level: "note",
+        description: "Function sits on the slice boundary (called from the slice, not carved).",
+    },
+    Rule {
+        id: "evidence/string",
+        name: "StringEvidence",
+        level: "note",
+        description: "String referenced from slice code.",
+    },
+    Rule {
+        id: "evidence/import",
+        name: "ImportEvidence",
+        level: "note",
+        description: "Imported symbol used by slice code.",
+    },
+    Rule {
+        id: "evidence/call",
+        name: "CallEvidence",
+        level: "note",
+        description: "Call recorded as evidence for the slice.",
+    },
+    Rule {
+        id: "evidence/other",
+        name: "OtherEvidence",
+        level: "note",
+        description: "Other evidence recorded for the slice.",
+    },
+    Rule {
+        id: "root/unmatched",
+        name: "UnmatchedRoot",
+        level: "warning",
+        description: "A ritual root did not resolve to any function.",
+    },
+];
+
+fn rule_index(id: &str) -> usize {
+    RULES.iter().position(|r| r.id == id).expect("known SARIF rule")
+}
+
+/// Render a complete SARIF log with a single run for the slice, including every evidence
+/// record of `report.analysis`.
+pub fn render_slice_sarif(report: &SliceSarifReport<'_>) -> Value {
+    let render = || -> io::Result<Vec<u8>> {
+        let mut writer = SliceSarifWriter::new(Vec::new(), report)?;
+        for e in report.analysis.map(|a| a.evidence.as_slice()).unwrap_or_default() {
+            writer.evidence(e)?;
+        }
+        writer.finish()
+    };
+    let bytes = render().expect("writing SARIF to memory cannot fail");
+    serde_json::from_slice(&bytes).expect("SARIF writer emits valid JSON")
+}
+
+/// Writes one slice's SARIF log as it goes: the run header and function results on
+/// [`SliceSarifWriter::new`], evidence results one at a time, and unmatched roots on
+/// [`SliceSarifWriter::finish`].
+pub struct SliceSarifWriter<'a, W: Write> {
+    out: W,
+    report: &'a SliceSarifReport<'a>,
+    sections: Vec<&'a BinarySection>,
+    results: usize,
+}
+
+impl<'a, W: Write> SliceSarifWriter<'a, W> {
+    /// Start the log for `report`, writing everything but the evidence and unmatched roots.
+    pub fn new(mut out: W, report: &'a SliceSarifReport<'a>) -> io::Result<Self> {
+        let mut sections: Vec<&BinarySection> =
+            report.sections.iter().filter(|s| !s.name.is_empty() && s.end > s.start).collect();
+        sections.sort_by_key(|s| (s.start, s.end));
+
+        write!(
+            out,
+            "{{\"$schema\": {}, \"version\": {}, \"runs\": [{{",
+            json!(SARIF_SCHEMA),
+            json!(SARIF_VERSION)
+        )?;
+        for (key, value) in run_header(report, &sections) {
+            write!(out, "\n  {}: {},", json!(key), value)?;
+        }
+        out.write_all(b"\n  \"results\": [")?;
+        let mut writer = SliceSarifWriter { out, report, sections, results: 0 };
+
+        let functions = report.analysis.map(|a| a.functions.as_slice()).unwrap_or_default();
+        for f in functions {
+            let rule = if f.is_boundary {
+                "slice/boundary"
+            } else if f.in_slice {
+                "slice/function"
+            } else {
+                continue;
+            };
+            let label = function_label(f);
+            let what = if f.is_boundary { "a boundary of" } else { "part of" };
+            let message = format!("{} is {} slice {}", label, what, report.slice);
+            let result = writer.locator().result(
+                rule,
+                message,
+                f.address,
+                f.size.map(u64::from),
+                "function",
+                Some(f),
+            );
+            writer.push(&result)?;
+        }
+        Ok(writer)
+    }
+
+    /// Add the result for one evidence record.
+    pub fn evidence(&mut self, e: &EvidenceRecord) -> io::Result<()> {
+        let rule = match e.kind {
+            Some(EvidenceKind::String) => "evidence/string",
+            Some(EvidenceKind::Import) => "evidence/import",
+            Some(EvidenceKind::Call) => "evidence/call",
+            Some(EvidenceKind::Other) | None => "evidence/other",
+        };
+        let functions = self.report.analysis.map(|a| a.functions.as_slice()).unwrap_or_default();
+        let function = containing_function(functions, e.address);
+        let result = self.locator().result(
+            rule,
+            e.description.clone(),
+            e.address,
+            None,
+            "instruction",
+            function,
+        );
+        self.push(&result)
+    }
+
+    /// Write the unmatched roots, close the log, and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let hits = self.report.analysis.map(|a| a.root_hits.as_slice()).unwrap_or_default();
+        for hit in hits.iter().filter(|h| h.functions.is_empty()) {
+            let reason = hit.error.as_deref().unwrap_or("no function matched");
+            self.push(&json!({
+                "ruleId": "root/unmatched",
+                "ruleIndex": rule_index("root/unmatched"),
+                "level": "warning",
+                "message": { "text": format!("Root {} did not resolve: {}", hit.root, reason) },
+                "properties": { "root": hit.root },
+            }))?;
+        }
+        self.out.write_all(b"\n  ]\n}]}\n")?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn locator(&self) -> Locator<'_> {
+        Locator { report: self.report, sections: &self.sections }
+    }
+
+    fn push(&mut self, result: &Value) -> io::Result<()> {
+        self.out.write_all(if self.results == 0 { b"\n    " } else { b",\n    " })?;
+        serde_json::to_writer(&mut self.out, result)?;
+        self.results += 1;
+        Ok(())
+    }
+}
+
+/// Every member of the SARIF run object except `results`.
+fn run_header(report: &SliceSarifReport<'_>, sections: &[&BinarySection]) -> Map<String, Value> {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|r| {
+            json!({
+                "id": r.id,
+                "name": r.name,
+                "shortDescription": { "text": r.description },
+                "defaultConfiguration": { "level": r.level },
+            })
+        })
+        .collect();
+    let addresses: Vec<Value> = sections
+        .iter()
+        .map(|s| {
+            json!({
+                "name": s.name,
+                "kind": "section",
+                "absoluteAddress": s.start,
+                "length": s.end - s.start,
+            })
+        })
+        .collect();
+
+    let mut run = Map::new();
+    run.insert(
+        "tool".into(),
+        json!({
+            "driver": {
+                "name": "binary-slicer",
+                "version": report.tool_version,
+                "rules": rules,
+            }
+        }),
+    );
+    if let Some(uri) = report.binary_uri {
+        let mut artifact = json!({
+            "location": { "uri": uri },
+            "roles": ["analysisTarget"],
+        });
+        if let Some(hash) = report.binary_hash {
+            artifact["hashes"] = json!({ "sha-256": hash });
+        }
+        run.insert("artifacts".into(), json!([artifact]));
+    }
+    run.insert("addresses".into(), Value::Array(addresses));
+    let mut properties = Map::new();
+    properties.insert("slice".into(), json!(report.slice));
+    for (key, value) in [
+        ("description", report.description),
+        ("ritual", report.ritual),
+        ("binary", report.binary),
+        ("backend", report.backend),
+        ("backendVersion", report.backend_version),
+    ] {
+        if let Some(value) = value {
+            properties.insert(key.into(), json!(value));
+        }
+    }
+    run.insert("properties".into(), Value::Object(properties));
+    run
+}
+
+struct Locator<'a> {
+    report: &'a SliceSarifReport<'a>,
+    sections: &'a [&'a BinarySection],
+}
+
+impl Locator<'_> {
+    fn result(
+        &self,
+        rule: &'static str,
+        message: String,
+        address: u64,
+        length: Option<u64>,
+        kind: &str,
+        function: Option<&FunctionRecord>,
+    ) -> Value {
+        let mut addr = json!({ "absoluteAddress": address, "kind": kind });
+        if let Some(length) = length {
+            addr["length"] = json!(length);
+        }
+        if let Some(f) = function.filter(|_| kind == "function") {
+            addr["name"] = json!(function_label(f));
+            if let Some(mangled) = &f.mangled_name {
+                addr["fullyQualifiedName"] = json!(mangled);
+            }
+        }
+        let section = self.sections.iter().position(|s| s.start <= address && address < s.end);
+        if let Some(index) = section {
+            addr["parentIndex"] = json!(index);
+            addr["offsetFromParent"] = json!(address - self.sections[index].start);
+        }
+
+        let mut physical = json!({ "address": addr });
+        if self.report.binary_uri.is_some() {
+            physical["artifactLocation"] = json!({ "index": 0 });
+        }
+        let mut location = json!({ "physicalLocation": physical });
+        if let Some(f) = function {
+            location["logicalLocations"] = json!([{
+                "name": function_label(f),
+                "kind": "function",
+            }]);
+        }
+
+        let mut properties = json!({ "address": format!("0x{:X}", address) });
+        if let Some(index) = section {
+            properties["section"] = json!(self.sections[index].name);
+        }
+        json!({
+            "ruleId": rule,
+            "ruleIndex": rule_index(rule),
+            "level": RULES[rule_index(rule)].level,
+            "message": { "text": message },
+            "locations": [location],
+            "properties": properties,
+        })
+    }
+}
+
+fn function_label(f: &FunctionRecord) -> String {
+    f.name.clone().unwrap_or_else(|| format!("sub_{:X}", f.address))
+}
+
+/// The function whose extent covers `address` (a sized function, else one starting there).
+fn containing_function(functions: &[FunctionRecord], address: u64) -> Option<&FunctionRecord> {
+    functions
+        .iter()
+        .filter(|f| {
+            f.address == address
+                || f.size.is_some_and(|size| {
+                    f.address <= address && address < f.address + u64::from(size)
+                })
+        })
+        .min_by_key(|f| f.size.unwrap_or(u32::MAX))
+}
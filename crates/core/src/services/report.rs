@@ -0,0 +1,200 @@
+//! `report.json`: the versioned report a finished run writes into its output directory.
+//!
+//! [`ReportV1`] is the one shape every writer uses (ritual runs, `self-test` artifacts), so
+//! consumers can read any `report.json` with a single type. `report_schema_version` says which
+//! shape a file has; it only changes when a field is removed, renamed, or changes meaning, and
+//! new optional fields are added without a bump. Reports written before the field existed
+//! read back as version 0, with their `edges` list as `call_edges`.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::analysis::baseline::BaselineComparison;
+use crate::analysis::metrics::{function_metrics, FunctionMetrics};
+use crate::db::RitualRunStatus;
+use crate::services::analysis::{
+    AnalysisResult, BackendFailure, BasicBlock, CallEdge, EvidenceRecord, FunctionRecord,
+    ResultValidation, RootHit, XrefRecord,
+};
+use crate::services::pipeline::StageState;
+
+/// File name of the report inside a run directory.
+pub const REPORT_FILE: &str = "report.json";
+
+/// Schema version written to `report_schema_version` (see the module docs).
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("Failed to read {}: {source}", .path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("Failed to write {}: {source}", .path.display())]
+    Write { path: PathBuf, source: io::Error },
+    #[error("Invalid report {}: {source}", .path.display())]
+    Parse { path: PathBuf, source: serde_json::Error },
+    #[error(
+        "Report {} has schema version {version}; this build reads up to {REPORT_SCHEMA_VERSION}",
+        .path.display()
+    )]
+    UnsupportedVersion { path: PathBuf, version: u32 },
+}
+
+/// Version 1 of `report.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportV1 {
+    /// [`REPORT_SCHEMA_VERSION`] when written; 0 for reports that predate the field.
+    #[serde(default)]
+    pub report_schema_version: u32,
+    pub ritual: String,
+    pub binary: String,
+    #[serde(default)]
+    pub binary_id: Option<i64>,
+    #[serde(default)]
+    pub binary_hash: Option<String>,
+    /// Roots as the spec lists them (canonical strings).
+    #[serde(default)]
+    pub roots: Vec<String>,
+    #[serde(default)]
+    pub root_hits: Vec<RootHit>,
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    pub status: RitualRunStatus,
+    pub backend: String,
+    #[serde(default)]
+    pub backend_version: Option<String>,
+    #[serde(default)]
+    pub backend_path: Option<String>,
+    /// Architectures of a universal binary's slices the functions came from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arch_slices: Vec<String>,
+    pub functions: Vec<FunctionRecord>,
+    #[serde(default, alias = "edges")]
+    pub call_edges: Vec<CallEdge>,
+    #[serde(default)]
+    pub basic_blocks: Vec<BasicBlock>,
+    #[serde(default)]
+    pub evidence: Vec<EvidenceRecord>,
+    #[serde(default)]
+    pub xrefs: Vec<XrefRecord>,
+    /// Metrics of every function, in function order.
+    #[serde(default)]
+    pub metrics: Vec<FunctionMetrics>,
+    #[serde(default)]
+    pub sub_slices: Vec<SubSliceReport>,
+    #[serde(default)]
+    pub validation: ResultValidation,
+    /// Backends of a fallback chain that failed before `backend` produced the result.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_backends: Vec<BackendFailure>,
+    /// Per-stage status of a staged ritual.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<StageState>>,
+    /// Comparison with the ritual's baseline run, when one is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineComparison>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deterministic: bool,
+    /// Fixed timestamp of a deterministic run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<String>,
+}
+
+/// A labeled sub-slice with its function records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubSliceReport {
+    pub label: String,
+    pub roots: Vec<String>,
+    pub functions: Vec<FunctionRecord>,
+}
+
+impl ReportV1 {
+    /// Report of `result` for `ritual` on `binary` as run by `backend`, with the metrics,
+    /// sub-slices, and universal-binary slices derived from it. Run metadata the result does
+    /// not carry (binary id and hash, `max_depth`, validation, stages, baseline, ...) is left
+    /// empty for the caller to fill in.
+    pub fn new(
+        ritual: &str,
+        binary: &str,
+        backend: &str,
+        status: RitualRunStatus,
+        result: &AnalysisResult,
+    ) -> Self {
+        let arch_slices: BTreeSet<&str> =
+            result.functions.iter().filter_map(|f| f.arch.as_deref()).collect();
+        Self {
+            report_schema_version: REPORT_SCHEMA_VERSION,
+            ritual: ritual.to_string(),
+            binary: binary.to_string(),
+            binary_id: None,
+            binary_hash: None,
+            roots: result.roots.clone(),
+            root_hits: result.root_hits.clone(),
+            max_depth: None,
+            status,
+            backend: backend.to_string(),
+            backend_version: result.backend_version.clone(),
+            backend_path: result.backend_path.clone(),
+            arch_slices: arch_slices.into_iter().map(str::to_string).collect(),
+            functions: result.functions.clone(),
+            call_edges: result.call_edges.clone(),
+            basic_blocks: result.basic_blocks.clone(),
+            evidence: result.evidence.clone(),
+            xrefs: result.xrefs.clone(),
+            metrics: function_metrics(result),
+            sub_slices: sub_slices(result),
+            validation: ResultValidation::default(),
+            failed_backends: Vec::new(),
+            stages: None,
+            baseline: None,
+            deterministic: false,
+            epoch: None,
+        }
+    }
+
+    /// Load `report.json` from a run directory, refusing versions newer than this build.
+    pub fn load(run_dir: &Path) -> Result<Self, ReportError> {
+        let path = run_dir.join(REPORT_FILE);
+        let text = fs::read_to_string(&path)
+            .map_err(|source| ReportError::Read { path: path.clone(), source })?;
+        let report: Self = serde_json::from_str(&text)
+            .map_err(|source| ReportError::Parse { path: path.clone(), source })?;
+        if report.report_schema_version > REPORT_SCHEMA_VERSION {
+            return Err(ReportError::UnsupportedVersion {
+                path,
+                version: report.report_schema_version,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Write `report.json` into a run directory.
+    pub fn write(&self, run_dir: &Path) -> Result<(), ReportError> {
+        let path = run_dir.join(REPORT_FILE);
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|source| ReportError::Parse { path: path.clone(), source })?;
+        fs::write(&path, json).map_err(|source| ReportError::Write { path, source })
+    }
+}
+
+/// Function records of each labeled sub-slice of `result`.
+fn sub_slices(result: &AnalysisResult) -> Vec<SubSliceReport> {
+    result
+        .sub_slices
+        .iter()
+        .map(|sub| SubSliceReport {
+            label: sub.label.clone(),
+            roots: sub.roots.clone(),
+            functions: result
+                .functions
+                .iter()
+                .filter(|f| sub.functions.contains(&f.address))
+                .cloned()
+                .collect(),
+        })
+        .collect()
+}
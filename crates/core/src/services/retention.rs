@@ -0,0 +1,117 @@
+//! Retention of ritual run outputs (`gc-outputs`).
+//!
+//! A [`RetentionConfig`] decides which runs keep their output directory. Runs are ranked
+//! newest first per ritual name (across binaries); the newest `keep_last` are kept, and
+//! older ones go unless they succeeded (with `keep_succeeded`) or are a baseline (with
+//! `keep_baselines`). Failed, canceled, and stubbed runs older than `failed_max_age_days`
+//! go even within `keep_last`. Pending, running, and already purged runs are left alone.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{RetentionConfig, RitualRunRecord, RitualRunStatus};
+
+/// Why a run is purged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum PurgeReason {
+    /// Older than the newest `keep` runs of its ritual.
+    PastKeepLast { keep: u64 },
+    /// A failed, canceled, or stubbed run finished more than `max_age_days` ago.
+    Expired { age_days: i64, max_age_days: u64 },
+}
+
+impl std::fmt::Display for PurgeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PurgeReason::PastKeepLast { keep } => {
+                write!(f, "older than the last {} run(s) of its ritual", keep)
+            }
+            PurgeReason::Expired { age_days, max_age_days } => {
+                write!(f, "finished {} day(s) ago (limit {})", age_days, max_age_days)
+            }
+        }
+    }
+}
+
+/// A run the retention policy purges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeCandidate {
+    pub run_id: i64,
+    pub binary: String,
+    pub ritual: String,
+    pub status: RitualRunStatus,
+    pub finished_at: String,
+    pub reason: PurgeReason,
+    /// Whether the run is the latest of its binary and ritual, whose outputs are the ones
+    /// on disk; older runs were overwritten and only their DB row changes.
+    pub owns_outputs: bool,
+}
+
+/// Runs of `runs` (row id and record, as from `ProjectDb::list_ritual_runs_with_ids`) that
+/// `policy` purges at `now`, oldest first. `baselines` holds the run ids of baselines.
+pub fn plan_purge(
+    runs: &[(i64, RitualRunRecord)],
+    baselines: &BTreeSet<i64>,
+    policy: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Vec<PurgeCandidate> {
+    let keep_last = policy.keep_last.unwrap_or(RetentionConfig::DEFAULT_KEEP_LAST);
+    let keep_succeeded = policy.keep_succeeded.unwrap_or(true);
+    let keep_baselines = policy.keep_baselines.unwrap_or(true);
+
+    let mut latest: HashMap<(&str, &str), i64> = HashMap::new();
+    for (id, run) in runs {
+        let entry = latest.entry((run.binary.as_str(), run.ritual.as_str())).or_insert(*id);
+        *entry = (*entry).max(*id);
+    }
+
+    let mut newest_first: Vec<&(i64, RitualRunRecord)> = runs.iter().collect();
+    newest_first.sort_by_key(|(id, _)| Reverse(*id));
+    let mut rank: HashMap<&str, u64> = HashMap::new();
+    let mut out = Vec::new();
+    for (id, run) in newest_first {
+        if matches!(
+            run.status,
+            RitualRunStatus::Purged | RitualRunStatus::Pending | RitualRunStatus::Running
+        ) {
+            continue;
+        }
+        let position = rank.entry(run.ritual.as_str()).or_insert(0);
+        let within_keep = *position < keep_last;
+        *position += 1;
+        if keep_baselines && baselines.contains(id) {
+            continue;
+        }
+
+        let failed = matches!(
+            run.status,
+            RitualRunStatus::Failed | RitualRunStatus::Canceled | RitualRunStatus::Stubbed
+        );
+        let expired = policy.failed_max_age_days.filter(|_| failed).and_then(|max_age_days| {
+            let finished = DateTime::parse_from_rfc3339(&run.finished_at).ok()?;
+            let age_days = (now - finished.with_timezone(&Utc)).num_days();
+            (age_days > max_age_days as i64)
+                .then_some(PurgeReason::Expired { age_days, max_age_days })
+        });
+        let kept = within_keep || (keep_succeeded && run.status == RitualRunStatus::Succeeded);
+        let past_keep = (!kept).then_some(PurgeReason::PastKeepLast { keep: keep_last });
+        let Some(reason) = expired.or(past_keep) else {
+            continue;
+        };
+        out.push(PurgeCandidate {
+            run_id: *id,
+            binary: run.binary.clone(),
+            ritual: run.ritual.clone(),
+            status: run.status.clone(),
+            finished_at: run.finished_at.clone(),
+            reason,
+            owns_outputs: latest.get(&(run.binary.as_str(), run.ritual.as_str())) == Some(id),
+        });
+    }
+    out.reverse();
+    out
+}
@@ -0,0 +1,295 @@
+//! Ritual roots beyond exact symbol names.
+//!
+//! A spec root is a symbol name (`main_loop`) or one of:
+//! - `{ address: 0x401000 }` — the function starting at that address,
+//! - `{ regex: ".*Update.*" }` — every function whose raw or demangled name matches the whole
+//!   pattern,
+//! - `{ demangled: "Game::tick" }` — functions whose demangled name is `Game::tick`, with or
+//!   without the parameter list (`Game::tick(float)`),
+//! - `{ string: "AutoUpdate" }` — functions referencing a string containing `AutoUpdate`.
+//!   These need the whole analysis result and are resolved after the backend runs (see
+//!   [`crate::services::strings::resolve_string_roots`]).
+//! - `entrypoint` / `exports` — the function at the binary's entry point / every exported
+//!   function, for a first ritual on a binary whose symbol names are not known yet. The
+//!   backend reports them after it runs (see
+//!   [`crate::services::entry_points::resolve_auto_roots`]); a function literally named
+//!   `exports` needs `{ regex: exports }`. (`entry` stays a plain name: Ghidra and many test
+//!   binaries call the entry function that.)
+//!
+//! Analysis requests, run records, and `root_hits` carry roots as strings, so each
+//! [`RootSpec`] has a canonical string form ([`RootSpec::canonical`]) that
+//! [`RootPattern::parse`] reads back: `0x401000`, `regex:<pattern>`, `demangled:<name>`,
+//! `string:<text>`, `auto:entrypoint`, `auto:exports`.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::services::analysis::FunctionRecord;
+use crate::services::demangle::demangle;
+
+const REGEX_PREFIX: &str = "regex:";
+const DEMANGLED_PREFIX: &str = "demangled:";
+pub(crate) const STRING_PREFIX: &str = "string:";
+const AUTO_PREFIX: &str = "auto:";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RootError {
+    #[error("Root must not be empty")]
+    Empty,
+    #[error("Invalid root address '{0}'")]
+    InvalidAddress(String),
+    #[error("Invalid root regex '{pattern}': {message}")]
+    InvalidRegex { pattern: String, message: String },
+    #[error("Unknown automatic root '{0}' (expected entrypoint or exports)")]
+    UnknownAuto(String),
+}
+
+/// Roots the binary itself declares, written `entrypoint` / `exports` in a spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutoRoot {
+    /// The function at the entry point (ELF `e_entry`, PE `AddressOfEntryPoint`, Mach-O
+    /// `LC_MAIN`, the wasm start function).
+    Entry,
+    /// Every exported function.
+    Exports,
+}
+
+impl AutoRoot {
+    /// Both automatic roots, as `roots: auto` expands to.
+    pub const ALL: [AutoRoot; 2] = [AutoRoot::Entry, AutoRoot::Exports];
+
+    /// Spec keyword: `entrypoint` or `exports`.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            AutoRoot::Entry => "entrypoint",
+            AutoRoot::Exports => "exports",
+        }
+    }
+
+    /// The automatic root a spec keyword names, if any.
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        AutoRoot::ALL.into_iter().find(|auto| auto.keyword() == keyword)
+    }
+
+    /// Canonical root string: `auto:entrypoint` or `auto:exports`.
+    pub fn canonical(self) -> String {
+        format!("{AUTO_PREFIX}{}", self.keyword())
+    }
+
+    /// The automatic root a canonical root string names, if any.
+    pub fn from_canonical(root: &str) -> Option<Self> {
+        root.strip_prefix(AUTO_PREFIX).and_then(Self::from_keyword)
+    }
+}
+
+/// One root entry as written in a ritual spec.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum RootSpec {
+    Name(String),
+    Address { address: RootAddress },
+    Regex { regex: String },
+    Demangled { demangled: String },
+    String { string: String },
+}
+
+/// An address given as a YAML/JSON integer or as a `0x…` string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum RootAddress {
+    Number(u64),
+    Text(String),
+}
+
+impl RootAddress {
+    /// The numeric address, or `None` when the text is not valid hex.
+    pub fn value(&self) -> Option<u64> {
+        match self {
+            RootAddress::Number(n) => Some(*n),
+            RootAddress::Text(text) => parse_address(text),
+        }
+    }
+}
+
+impl RootSpec {
+    /// String form used in analysis requests, `root_hits`, and the project DB.
+    ///
+    /// Never fails: a malformed entry keeps its text so resolution can report it per root.
+    pub fn canonical(&self) -> String {
+        match self {
+            RootSpec::Name(name) => match AutoRoot::from_keyword(name) {
+                Some(auto) => auto.canonical(),
+                None => name.clone(),
+            },
+            RootSpec::Address { address: RootAddress::Number(n) } => format!("0x{:X}", n),
+            RootSpec::Address { address: RootAddress::Text(text) } => match parse_address(text) {
+                Some(n) => format!("0x{:X}", n),
+                None if text.starts_with("0x") => text.clone(),
+                None => format!("0x{text}"),
+            },
+            RootSpec::Regex { regex } => format!("{REGEX_PREFIX}{regex}"),
+            RootSpec::Demangled { demangled } => format!("{DEMANGLED_PREFIX}{demangled}"),
+            RootSpec::String { string } => format!("{STRING_PREFIX}{string}"),
+        }
+    }
+
+    /// Spec entry for a canonical root string (the reverse of [`RootSpec::canonical`]), so
+    /// roots typed as `0x401000` or `regex:.*Update.*` are written in their structured form.
+    pub fn from_canonical(root: &str) -> Self {
+        if let Some(regex) = root.strip_prefix(REGEX_PREFIX) {
+            return RootSpec::Regex { regex: regex.to_string() };
+        }
+        if let Some(demangled) = root.strip_prefix(DEMANGLED_PREFIX) {
+            return RootSpec::Demangled { demangled: demangled.to_string() };
+        }
+        if let Some(string) = root.strip_prefix(STRING_PREFIX) {
+            return RootSpec::String { string: string.to_string() };
+        }
+        if let Some(auto) = AutoRoot::from_canonical(root) {
+            return RootSpec::Name(auto.keyword().to_string());
+        }
+        if root.starts_with("0x") {
+            return RootSpec::Address { address: RootAddress::Text(root.to_string()) };
+        }
+        RootSpec::Name(root.to_string())
+    }
+}
+
+impl From<String> for RootSpec {
+    fn from(name: String) -> Self {
+        RootSpec::Name(name)
+    }
+}
+
+impl From<&str> for RootSpec {
+    fn from(name: &str) -> Self {
+        RootSpec::Name(name.to_string())
+    }
+}
+
+/// A canonical root string, ready to match functions.
+#[derive(Debug, Clone)]
+pub enum RootPattern {
+    /// Exact symbol name; hex-looking names also match that address.
+    Name(String),
+    Address(u64),
+    Regex(Regex),
+    Demangled(String),
+    /// Substring of a referenced string; never matches a function on its own.
+    String(String),
+    /// Entry point or exports; never matches a function on its own.
+    Auto(AutoRoot),
+}
+
+impl RootPattern {
+    pub fn parse(root: &str) -> Result<Self, RootError> {
+        if let Some(pattern) = root.strip_prefix(REGEX_PREFIX) {
+            return Ok(RootPattern::Regex(compile(pattern)?));
+        }
+        if let Some(name) = root.strip_prefix(DEMANGLED_PREFIX) {
+            if name.trim().is_empty() {
+                return Err(RootError::Empty);
+            }
+            return Ok(RootPattern::Demangled(name.to_string()));
+        }
+        if let Some(text) = root.strip_prefix(STRING_PREFIX) {
+            if text.is_empty() {
+                return Err(RootError::Empty);
+            }
+            return Ok(RootPattern::String(text.to_string()));
+        }
+        if let Some(keyword) = root.strip_prefix(AUTO_PREFIX) {
+            return AutoRoot::from_keyword(keyword)
+                .map(RootPattern::Auto)
+                .ok_or_else(|| RootError::UnknownAuto(keyword.to_string()));
+        }
+        if root.trim().is_empty() {
+            return Err(RootError::Empty);
+        }
+        if root.starts_with("0x") {
+            return parse_address(root)
+                .map(RootPattern::Address)
+                .ok_or_else(|| RootError::InvalidAddress(root.to_string()));
+        }
+        Ok(RootPattern::Name(root.to_string()))
+    }
+
+    pub fn matches(&self, function: &FunctionRecord) -> bool {
+        match self {
+            RootPattern::Address(address) => function.address == *address,
+            RootPattern::Name(name) => {
+                function.name.as_deref() == Some(name.as_str())
+                    || function.mangled_name.as_deref() == Some(name.as_str())
+                    || parse_address(name) == Some(function.address)
+            }
+            RootPattern::Regex(regex) => names(function).iter().any(|n| regex.is_match(n)),
+            RootPattern::Demangled(target) => readable_name(function)
+                .is_some_and(|readable| readable == *target || base_name(&readable) == target),
+            RootPattern::String(_) | RootPattern::Auto(_) => false,
+        }
+    }
+}
+
+/// Anchored: the pattern must match the whole name.
+fn compile(pattern: &str) -> Result<Regex, RootError> {
+    Regex::new(&format!("^(?:{pattern})$")).map_err(|err| RootError::InvalidRegex {
+        pattern: pattern.to_string(),
+        message: err.to_string(),
+    })
+}
+
+fn parse_address(text: &str) -> Option<u64> {
+    let hex = text.trim().trim_start_matches("0x");
+    if hex.is_empty() {
+        return None;
+    }
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// The function's name plus its demangled form when that differs.
+fn names(function: &FunctionRecord) -> Vec<String> {
+    let mut out: Vec<String> = function.name.iter().cloned().collect();
+    if let Some(readable) = readable_name(function) {
+        if !out.contains(&readable) {
+            out.push(readable);
+        }
+    }
+    out.extend(function.mangled_name.iter().cloned());
+    out
+}
+
+/// Demangled name, whether or not the backend result was demangled yet.
+fn readable_name(function: &FunctionRecord) -> Option<String> {
+    let name = function.name.as_deref()?;
+    if function.mangled_name.is_some() {
+        return Some(name.to_string());
+    }
+    Some(demangle(name).unwrap_or_else(|| name.to_string()))
+}
+
+const ANONYMOUS_NAMESPACE: &str = "(anonymous namespace)";
+
+/// `void Game::tick<int>(float) const` -> `Game::tick<int>`.
+fn base_name(readable: &str) -> &str {
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut skip_to = 0;
+    for (i, ch) in readable.char_indices() {
+        if i < skip_to {
+            continue;
+        }
+        match ch {
+            '(' if readable[i..].starts_with(ANONYMOUS_NAMESPACE) => {
+                skip_to = i + ANONYMOUS_NAMESPACE.len();
+            }
+            '(' if depth == 0 => return &readable[start..i],
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ' ' if depth == 0 => start = i + 1,
+            _ => {}
+        }
+    }
+    &readable[start..]
+}
@@ -0,0 +1,215 @@
+//! Evidence scoring: rank a run's evidence so docs lead with the records that tell a
+//! function apart instead of listing every operand.
+//!
+//! A record scores its kind's weight ([`EvidenceWeights`]) scaled by rarity. Strings seen
+//! `n` times across the run (the literal plus each `string xref`) score `1/n` of the string
+//! weight, so a unique message outranks a format string used everywhere. Imports, and calls
+//! to an import, score `1/n` of the import weight where `n` is the number of calls to that
+//! import. Other calls score the call weight, and untyped/`Other` evidence (register and
+//! memory operands, raw disassembly) the low `other` weight.
+//!
+//! Scoring needs the whole run: feed every record to [`EvidenceScorer::observe`] first, then
+//! call [`EvidenceScorer::score`].
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::db::EvidenceWeightOverrides;
+use crate::services::analysis::{EvidenceKind, EvidenceRecord};
+use crate::services::strings::string_text;
+
+/// Base weight per evidence kind.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EvidenceWeights {
+    pub string: f64,
+    pub import: f64,
+    pub call: f64,
+    /// `EvidenceKind::Other` and evidence without a kind.
+    pub other: f64,
+}
+
+impl Default for EvidenceWeights {
+    fn default() -> Self {
+        Self { string: 4.0, import: 3.0, call: 1.0, other: 0.1 }
+    }
+}
+
+impl EvidenceWeights {
+    /// Defaults with the project's configured overrides applied.
+    pub fn with_overrides(overrides: &EvidenceWeightOverrides) -> Self {
+        let defaults = Self::default();
+        Self {
+            string: overrides.string.unwrap_or(defaults.string),
+            import: overrides.import.unwrap_or(defaults.import),
+            call: overrides.call.unwrap_or(defaults.call),
+            other: overrides.other.unwrap_or(defaults.other),
+        }
+    }
+
+    pub fn weight(&self, kind: &Option<EvidenceKind>) -> f64 {
+        match kind {
+            Some(EvidenceKind::String) => self.string,
+            Some(EvidenceKind::Import) => self.import,
+            Some(EvidenceKind::Call) => self.call,
+            Some(EvidenceKind::Other) | None => self.other,
+        }
+    }
+}
+
+/// Run-wide frequencies behind the rarity factors.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceScorer {
+    weights: EvidenceWeights,
+    /// Records per string text.
+    strings: HashMap<String, usize>,
+    /// Imported names.
+    imports: HashSet<String>,
+    /// Call records per callee name.
+    calls: HashMap<String, usize>,
+}
+
+impl EvidenceScorer {
+    pub fn new(weights: EvidenceWeights) -> Self {
+        Self { weights, ..Self::default() }
+    }
+
+    /// Count one record towards the rarity of its string, import, or callee.
+    pub fn observe(&mut self, e: &EvidenceRecord) {
+        match e.kind {
+            Some(EvidenceKind::String) => {
+                if let Some(text) = string_text(e) {
+                    *self.strings.entry(text.to_string()).or_default() += 1;
+                }
+            }
+            Some(EvidenceKind::Import) => {
+                if let Some(name) = import_name(e) {
+                    self.imports.insert(name);
+                }
+            }
+            Some(EvidenceKind::Call) => {
+                if let Some(name) = callee_name(e) {
+                    *self.calls.entry(name).or_default() += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Score of one record given everything observed so far.
+    pub fn score(&self, e: &EvidenceRecord) -> f64 {
+        let rarity = |count: usize| 1.0 / count.max(1) as f64;
+        match e.kind {
+            Some(EvidenceKind::String) => {
+                let count = string_text(e).and_then(|t| self.strings.get(t)).copied();
+                self.weights.string * rarity(count.unwrap_or(1))
+            }
+            Some(EvidenceKind::Import) => {
+                let calls = import_name(e).and_then(|n| self.calls.get(&n)).copied();
+                self.weights.import * rarity(calls.unwrap_or(1))
+            }
+            Some(EvidenceKind::Call) => match callee_name(e) {
+                Some(name) if self.imports.contains(&name) => {
+                    self.weights.import * rarity(self.calls.get(&name).copied().unwrap_or(1))
+                }
+                _ => self.weights.call,
+            },
+            _ => self.weights.weight(&e.kind),
+        }
+    }
+}
+
+/// Imported symbol of an `import: <name> [(<library>)]` record.
+fn import_name(e: &EvidenceRecord) -> Option<String> {
+    let rest = e.description.strip_prefix("import: ")?;
+    let name = rest.split_once(" (").map_or(rest, |(name, _)| name);
+    Some(normalize_symbol(name)).filter(|n| !n.is_empty())
+}
+
+/// Named callee of a `call -> <name>` record (`None` for `call -> 0x…`).
+fn callee_name(e: &EvidenceRecord) -> Option<String> {
+    let target = e.description.strip_prefix("call -> ")?;
+    if target.starts_with("0x") {
+        return None;
+    }
+    Some(normalize_symbol(target)).filter(|n| !n.is_empty())
+}
+
+/// Strip import-stub prefixes so `sym.imp.printf`, `imp.printf`, and `printf` match.
+fn normalize_symbol(name: &str) -> String {
+    let name = name.trim();
+    let name = name.strip_prefix("sym.").unwrap_or(name);
+    let name = name.strip_prefix("imp.").unwrap_or(name);
+    name.to_string()
+}
+
+/// An evidence record with its score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoredEvidence {
+    pub score: f64,
+    pub evidence: EvidenceRecord,
+}
+
+/// The `limit` best-scoring records pushed so far, highest score first (ties by address,
+/// then description, so output is stable across runs).
+#[derive(Debug, Clone)]
+pub struct TopEvidence {
+    limit: usize,
+    items: Vec<ScoredEvidence>,
+}
+
+impl TopEvidence {
+    pub fn new(limit: usize) -> Self {
+        Self { limit, items: Vec::new() }
+    }
+
+    pub fn push(&mut self, e: &EvidenceRecord, score: f64) {
+        if self.limit == 0 {
+            return;
+        }
+        let candidate = ScoredEvidence { score, evidence: e.clone() };
+        let pos = self.items.partition_point(|item| rank(item, &candidate) == Ordering::Less);
+        if pos < self.limit {
+            self.items.insert(pos, candidate);
+            self.items.truncate(self.limit);
+        }
+    }
+
+    pub fn items(&self) -> &[ScoredEvidence] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+fn rank(a: &ScoredEvidence, b: &ScoredEvidence) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.evidence.address.cmp(&b.evidence.address))
+        .then_with(|| a.evidence.description.cmp(&b.evidence.description))
+}
+
+/// Score `evidence` as one run and return the `limit` best records scoring at least
+/// `min_score`.
+pub fn rank_evidence(
+    evidence: &[EvidenceRecord],
+    weights: EvidenceWeights,
+    min_score: f64,
+    limit: usize,
+) -> Vec<ScoredEvidence> {
+    let mut scorer = EvidenceScorer::new(weights);
+    for e in evidence {
+        scorer.observe(e);
+    }
+    let mut top = TopEvidence::new(limit);
+    for e in evidence {
+        let score = scorer.score(e);
+        if score >= min_score {
+            top.push(e, score);
+        }
+    }
+    top.items
+}
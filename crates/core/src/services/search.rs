@@ -0,0 +1,224 @@
+//! Project-wide text search over persisted analysis (`binary-slicer search`).
+//!
+//! Function names (demangled and mangled) and evidence descriptions of the latest run of
+//! every binary/ritual pair are matched case-insensitively, as a substring or a regex.
+//! Evidence hits carry the backend and pass that produced them, and can be limited to one.
+//! Evidence is read in pages, so memory stays flat on runs with millions of rows.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::db::{DbError, ProjectDb, ANALYSIS_PAGE_SIZE};
+use crate::services::analysis::{EvidenceKind, EvidenceSource};
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("Search pattern is empty")]
+    EmptyPattern,
+    #[error("Invalid search regex: {0}")]
+    InvalidRegex(#[from] regex::Error),
+    #[error("Unknown search kind '{0}' (expected string, function, or import)")]
+    UnknownKind(String),
+    #[error(transparent)]
+    Db(#[from] DbError),
+}
+
+/// What a search looks at; `--kind` narrows a search to some of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    /// Function names.
+    Function,
+    /// String evidence.
+    String,
+    /// Import evidence.
+    Import,
+    /// Any other evidence (calls, unclassified); only searched when no kind is requested.
+    Evidence,
+}
+
+impl SearchKind {
+    /// Kinds that can be requested by name.
+    pub const SELECTABLE: [SearchKind; 3] =
+        [SearchKind::String, SearchKind::Function, SearchKind::Import];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchKind::Function => "function",
+            SearchKind::String => "string",
+            SearchKind::Import => "import",
+            SearchKind::Evidence => "evidence",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, SearchError> {
+        Self::SELECTABLE
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| SearchError::UnknownKind(value.to_string()))
+    }
+
+    fn of_evidence(kind: Option<&EvidenceKind>) -> Self {
+        match kind {
+            Some(EvidenceKind::String) => SearchKind::String,
+            Some(EvidenceKind::Import) => SearchKind::Import,
+            _ => SearchKind::Evidence,
+        }
+    }
+}
+
+impl fmt::Display for SearchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Case-insensitive text matcher.
+#[derive(Debug, Clone)]
+pub enum SearchPattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl SearchPattern {
+    /// Build a matcher for `text`, as a regex when `regex` is set.
+    pub fn new(text: &str, regex: bool) -> Result<Self, SearchError> {
+        if text.is_empty() {
+            return Err(SearchError::EmptyPattern);
+        }
+        if regex {
+            Ok(SearchPattern::Regex(RegexBuilder::new(text).case_insensitive(true).build()?))
+        } else {
+            Ok(SearchPattern::Substring(text.to_lowercase()))
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            SearchPattern::Substring(needle) => text.to_lowercase().contains(needle),
+            SearchPattern::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+/// Restrictions on a search.
+#[derive(Debug, Clone, Default)]
+pub struct SearchScope {
+    /// Only search runs of this binary.
+    pub binary: Option<String>,
+    /// Only search these kinds; empty searches everything.
+    pub kinds: BTreeSet<SearchKind>,
+    /// Only search evidence produced by this backend or `backend/pass` (see
+    /// [`EvidenceSource::matches`]); function names have no source and are skipped.
+    pub source: Option<String>,
+}
+
+impl SearchScope {
+    fn includes(&self, kind: SearchKind) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&kind))
+            && (self.source.is_none() || kind != SearchKind::Function)
+    }
+
+    fn includes_source(&self, source: Option<&EvidenceSource>) -> bool {
+        match &self.source {
+            None => true,
+            Some(filter) => source.is_some_and(|s| s.matches(filter)),
+        }
+    }
+}
+
+/// One matching function name or evidence description.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub binary: String,
+    pub ritual: String,
+    pub run_id: i64,
+    /// Slice the run belongs to (rituals are named after their slice), if it exists.
+    pub slice: Option<String>,
+    pub address: u64,
+    pub kind: SearchKind,
+    /// The matched name or description.
+    pub text: String,
+    /// `backend/pass` that produced matched evidence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Search the latest run of every binary/ritual pair in scope.
+///
+/// Hits are grouped by binary and ritual (in name order), then listed functions first and
+/// evidence after, each by address.
+pub fn search_analysis(
+    db: &ProjectDb,
+    pattern: &SearchPattern,
+    scope: &SearchScope,
+) -> Result<Vec<SearchHit>, SearchError> {
+    let pairs: BTreeSet<(String, String)> = db
+        .list_ritual_runs(scope.binary.as_deref())?
+        .into_iter()
+        .map(|run| (run.binary, run.ritual))
+        .collect();
+    let slices: HashSet<String> = db.list_slices()?.into_iter().map(|s| s.name).collect();
+    let search_evidence = [SearchKind::String, SearchKind::Import, SearchKind::Evidence]
+        .into_iter()
+        .any(|kind| scope.includes(kind));
+
+    let mut hits = Vec::new();
+    for (binary, ritual) in pairs {
+        let Some((run_id, skeleton)) = db.load_analysis_skeleton(&binary, &ritual)? else {
+            continue;
+        };
+        let slice = slices.contains(&ritual).then(|| ritual.clone());
+        let hit = |address: u64, kind: SearchKind, text: &str, source: Option<&EvidenceSource>| {
+            SearchHit {
+                binary: binary.clone(),
+                ritual: ritual.clone(),
+                run_id,
+                slice: slice.clone(),
+                address,
+                kind,
+                text: text.to_string(),
+                source: source.map(|s| s.to_string()),
+            }
+        };
+
+        let mut functions = Vec::new();
+        if scope.includes(SearchKind::Function) {
+            for func in &skeleton.functions {
+                let matched = [func.name.as_deref(), func.mangled_name.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .find(|name| pattern.is_match(name));
+                if let Some(name) = matched {
+                    functions.push(hit(func.address, SearchKind::Function, name, None));
+                }
+            }
+            functions.sort_by_key(|h| h.address);
+        }
+
+        let mut evidence = Vec::new();
+        if search_evidence {
+            for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+                for record in page? {
+                    let kind = SearchKind::of_evidence(record.kind.as_ref());
+                    if scope.includes(kind)
+                        && scope.includes_source(record.source.as_ref())
+                        && pattern.is_match(&record.description)
+                    {
+                        let source = record.source.as_ref();
+                        evidence.push(hit(record.address, kind, &record.description, source));
+                    }
+                }
+            }
+            evidence.sort_by_key(|h| h.address);
+        }
+
+        hits.extend(functions);
+        hits.extend(evidence);
+    }
+    Ok(hits)
+}
@@ -0,0 +1,244 @@
+//! Function signature inference: parameter counts and calling conventions.
+//!
+//! Backends that know signatures (Ghidra, rizin) report them directly. For raw disassembly,
+//! [`infer_signature`] works from per-instruction register effects: an argument register read
+//! before it is written (and before the first call or branch) is taken as a parameter, and
+//! stack slots above the return address read through the stack/frame pointer count as stack
+//! parameters. Parameter counts assume arguments are used contiguously from the first slot,
+//! so an unused leading parameter still counts when a later one is read.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Calling convention of a function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallingConvention {
+    /// System V AMD64 (`rdi, rsi, rdx, rcx, r8, r9`).
+    Sysv64,
+    /// Microsoft x64 (`rcx, rdx, r8, r9`).
+    Win64,
+    /// 32-bit x86, caller cleans the stack.
+    Cdecl,
+    /// 32-bit x86, callee cleans the stack (`ret N`).
+    Stdcall,
+    /// 32-bit x86, first two arguments in `ecx, edx`.
+    Fastcall,
+    /// 32-bit x86 member functions, `this` in `ecx`.
+    Thiscall,
+    /// 32-bit ARM (`r0-r3`).
+    Aapcs,
+    /// AArch64 (`x0-x7`).
+    Aapcs64,
+    /// RISC-V (`a0-a7`).
+    Riscv,
+    Unknown,
+}
+
+impl CallingConvention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CallingConvention::Sysv64 => "sysv64",
+            CallingConvention::Win64 => "win64",
+            CallingConvention::Cdecl => "cdecl",
+            CallingConvention::Stdcall => "stdcall",
+            CallingConvention::Fastcall => "fastcall",
+            CallingConvention::Thiscall => "thiscall",
+            CallingConvention::Aapcs => "aapcs",
+            CallingConvention::Aapcs64 => "aapcs64",
+            CallingConvention::Riscv => "riscv",
+            CallingConvention::Unknown => "unknown",
+        }
+    }
+
+    /// Parse a name as stored by [`CallingConvention::as_str`] or as reported by Ghidra
+    /// (`__stdcall`, `__fastcall`...) and rizin (`amd64`, `ms`, `arm64`...).
+    pub fn from_name(name: &str) -> Self {
+        let name = name.trim().trim_start_matches('_').to_ascii_lowercase();
+        match name.as_str() {
+            "sysv64" | "amd64" | "x86_64" | "sysv" => CallingConvention::Sysv64,
+            "win64" | "ms" | "ms64" | "amd64ms" => CallingConvention::Win64,
+            "cdecl" | "cdecl64" => CallingConvention::Cdecl,
+            "stdcall" | "pascal" => CallingConvention::Stdcall,
+            "fastcall" => CallingConvention::Fastcall,
+            "thiscall" => CallingConvention::Thiscall,
+            "aapcs" | "arm32" | "arm16" => CallingConvention::Aapcs,
+            "aapcs64" | "arm64" | "aarch64" => CallingConvention::Aapcs64,
+            "riscv" | "riscv64" | "riscv32" => CallingConvention::Riscv,
+            _ => CallingConvention::Unknown,
+        }
+    }
+
+    /// Default convention for code of `arch` (Capstone/goblin names); `pe` selects the
+    /// Windows x64 convention.
+    pub fn for_arch(arch: &str, pe: bool) -> Option<Self> {
+        match arch {
+            "x86_64" | "amd64" if pe => Some(CallingConvention::Win64),
+            "x86_64" | "amd64" => Some(CallingConvention::Sysv64),
+            "x86" | "i386" => Some(CallingConvention::Cdecl),
+            "arm" | "armv7" => Some(CallingConvention::Aapcs),
+            "arm64" | "aarch64" => Some(CallingConvention::Aapcs64),
+            "riscv" | "riscv64" | "riscv32" => Some(CallingConvention::Riscv),
+            _ => None,
+        }
+    }
+
+    /// Integer argument registers in order; each slot lists the register and its aliases.
+    pub fn arg_registers(&self) -> &'static [&'static [&'static str]] {
+        match self {
+            CallingConvention::Sysv64 => &[
+                &["rdi", "edi", "di", "dil"],
+                &["rsi", "esi", "si", "sil"],
+                &["rdx", "edx", "dx", "dl", "dh"],
+                &["rcx", "ecx", "cx", "cl", "ch"],
+                &["r8", "r8d", "r8w", "r8b"],
+                &["r9", "r9d", "r9w", "r9b"],
+            ],
+            CallingConvention::Win64 => &[
+                &["rcx", "ecx", "cx", "cl", "ch"],
+                &["rdx", "edx", "dx", "dl", "dh"],
+                &["r8", "r8d", "r8w", "r8b"],
+                &["r9", "r9d", "r9w", "r9b"],
+            ],
+            CallingConvention::Fastcall => {
+                &[&["ecx", "cx", "cl", "ch"], &["edx", "dx", "dl", "dh"]]
+            }
+            CallingConvention::Thiscall => &[&["ecx", "cx", "cl", "ch"]],
+            CallingConvention::Aapcs => &[&["r0"], &["r1"], &["r2"], &["r3"]],
+            CallingConvention::Aapcs64 => &[
+                &["x0", "w0"],
+                &["x1", "w1"],
+                &["x2", "w2"],
+                &["x3", "w3"],
+                &["x4", "w4"],
+                &["x5", "w5"],
+                &["x6", "w6"],
+                &["x7", "w7"],
+            ],
+            CallingConvention::Riscv => {
+                &[&["a0"], &["a1"], &["a2"], &["a3"], &["a4"], &["a5"], &["a6"], &["a7"]]
+            }
+            CallingConvention::Cdecl | CallingConvention::Stdcall | CallingConvention::Unknown => {
+                &[]
+            }
+        }
+    }
+
+    /// Whether arguments (beyond any register ones) are passed in 4-byte stack slots.
+    fn uses_stack_slots(&self) -> bool {
+        matches!(
+            self,
+            CallingConvention::Cdecl
+                | CallingConvention::Stdcall
+                | CallingConvention::Fastcall
+                | CallingConvention::Thiscall
+        )
+    }
+}
+
+impl fmt::Display for CallingConvention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Inferred (or backend-reported) function signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionSignature {
+    /// Number of parameters.
+    pub params: u32,
+    pub convention: CallingConvention,
+    /// Bytes of arguments the callee pops on return (`ret N`), for callee-clean conventions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_cleanup: Option<u32>,
+}
+
+impl fmt::Display for FunctionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.params == 1 { "" } else { "s" };
+        write!(f, "{}({} param{}", self.convention, self.params, plural)?;
+        if let Some(bytes) = self.stack_cleanup {
+            write!(f, ", ret {}", bytes)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Register and stack effects of one instruction, as seen by [`infer_signature`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InsnEffects {
+    /// Registers read (lower-case names).
+    pub reads: Vec<String>,
+    /// Registers written (lower-case names).
+    pub writes: Vec<String>,
+    /// Stack reads as offsets from the stack pointer at function entry (the return address
+    /// is at 0 on x86).
+    pub stack_reads: Vec<i64>,
+    /// Calls, branches, and returns end the window where register reads count as parameters.
+    pub transfers: bool,
+    /// Immediate of a `ret N`.
+    pub ret_pop: Option<u32>,
+}
+
+/// Infer a signature for a function under `convention` from its instructions, in address
+/// order starting at the entry.
+///
+/// On 32-bit x86, `ret N` turns `cdecl` into `stdcall`, and `ecx`/`edx` read before being
+/// written turn it into `thiscall`/`fastcall`.
+pub fn infer_signature(convention: CallingConvention, insns: &[InsnEffects]) -> FunctionSignature {
+    let ret_pop = insns.iter().find_map(|i| i.ret_pop).filter(|bytes| *bytes > 0);
+    let convention = if convention == CallingConvention::Cdecl {
+        let early = live_in_registers(CallingConvention::Fastcall, insns);
+        match (early, ret_pop) {
+            (2.., _) => CallingConvention::Fastcall,
+            (1, _) => CallingConvention::Thiscall,
+            (_, Some(_)) => CallingConvention::Stdcall,
+            _ => CallingConvention::Cdecl,
+        }
+    } else {
+        convention
+    };
+
+    let mut params = live_in_registers(convention, insns);
+    if convention.uses_stack_slots() {
+        let stack_slots = insns
+            .iter()
+            .flat_map(|i| i.stack_reads.iter())
+            .filter(|offset| **offset >= 4)
+            .map(|offset| (*offset as u32 - 4) / 4 + 1)
+            .max()
+            .unwrap_or(0);
+        let popped = ret_pop.map(|bytes| bytes / 4).unwrap_or(0);
+        params += stack_slots.max(popped);
+    }
+    let stack_cleanup = if convention.uses_stack_slots() { ret_pop } else { None };
+    FunctionSignature { params, convention, stack_cleanup }
+}
+
+/// Argument registers read before being written, up to the first control transfer; returns
+/// one past the highest such slot.
+fn live_in_registers(convention: CallingConvention, insns: &[InsnEffects]) -> u32 {
+    let slots = convention.arg_registers();
+    let slot_of = |reg: &str| slots.iter().position(|aliases| aliases.contains(&reg));
+    let mut written = vec![false; slots.len()];
+    let mut highest = 0;
+    for insn in insns {
+        for reg in &insn.reads {
+            if let Some(slot) = slot_of(reg) {
+                if !written[slot] {
+                    highest = highest.max(slot as u32 + 1);
+                }
+            }
+        }
+        for reg in &insn.writes {
+            if let Some(slot) = slot_of(reg) {
+                written[slot] = true;
+            }
+        }
+        if insn.transfers {
+            break;
+        }
+    }
+    highest
+}
@@ -0,0 +1,212 @@
+//! Strings-xref analysis: link extracted strings to the code that references them.
+//!
+//! Backends report strings as `EvidenceKind::String` evidence (`string: <text>` at the
+//! string's address) and code references as [`XrefRecord`]s (immediates and PC-relative
+//! loads). [`link_string_xrefs`] joins the two: every xref landing inside a string becomes a
+//! `string xref` evidence row at the referencing instruction, so reports, queries, and docs
+//! can answer "who uses this string". [`resolve_string_roots`] then turns `string:<text>`
+//! roots into the functions holding those references.
+
+use std::collections::HashSet;
+
+use crate::services::analysis::{
+    AnalysisResult, EvidenceKind, EvidenceRecord, EvidenceSource, FunctionRecord, XrefKind,
+};
+use crate::services::roots::STRING_PREFIX;
+
+/// Description prefix of backend-extracted strings.
+const LITERAL_PREFIX: &str = "string: ";
+/// Description prefix of the evidence rows added by [`link_string_xrefs`].
+const XREF_PREFIX: &str = "string xref: ";
+
+/// Sections without file contents (their offset points at unrelated bytes).
+pub const ZERO_FILL_SECTIONS: &[&str] = &[".bss", ".tbss", "__bss", "__common", ".sbss"];
+
+/// Read-only and writable data sections string literals live in: ELF `.rodata*`/`.data*`,
+/// PE `.rdata`/`.data`, Mach-O `__cstring`/`__const`/`__data`. Symbol and section-name
+/// tables (`.dynstr`, `.strtab`), notes, and debug info are not scanned.
+pub fn is_string_section(name: &str) -> bool {
+    let name = name.trim_start_matches(['.', '_']).to_ascii_lowercase();
+    ["rodata", "rdata", "data", "cstring", "const"].iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// A string found in the binary's data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringLiteral {
+    pub address: u64,
+    pub value: String,
+}
+
+/// A code reference to a [`StringLiteral`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringReference {
+    /// Address of the referencing instruction.
+    pub from: u64,
+    /// Start of the referenced string.
+    pub string: u64,
+    pub value: String,
+    /// Entry of the function containing `from`, when known.
+    pub function: Option<u64>,
+}
+
+impl StringLiteral {
+    /// The `EvidenceKind::String` evidence row `backend` reports for this string.
+    pub fn evidence(&self, backend: &str) -> EvidenceRecord {
+        EvidenceRecord {
+            address: self.address,
+            description: format!("{LITERAL_PREFIX}{}", self.value),
+            kind: Some(EvidenceKind::String),
+            source: Some(EvidenceSource::new(backend, "strings")),
+            run_id: None,
+        }
+    }
+
+    fn contains(&self, address: u64) -> bool {
+        address >= self.address && address < self.address + self.value.len() as u64
+    }
+}
+
+/// The literal covering `address` in `literals` (sorted by address, as [`string_literals`]
+/// returns them).
+pub fn literal_at(literals: &[StringLiteral], address: u64) -> Option<&StringLiteral> {
+    // Last string starting at or before the address; the address must fall inside it.
+    let idx = literals.partition_point(|s| s.address <= address).checked_sub(1)?;
+    Some(&literals[idx]).filter(|literal| literal.contains(address))
+}
+
+/// Strings reported as evidence, ordered by address. A string decoded in place (see
+/// [`crate::services::decoders`]) shares its address with the encoded bytes' literal, if any.
+pub fn string_literals(result: &AnalysisResult) -> Vec<StringLiteral> {
+    let mut out: Vec<StringLiteral> = result
+        .evidence
+        .iter()
+        .filter(|e| e.kind == Some(EvidenceKind::String))
+        .filter_map(|e| {
+            let value = e.description.strip_prefix(LITERAL_PREFIX)?;
+            Some(StringLiteral { address: e.address, value: value.to_string() })
+        })
+        .filter(|s| s.address != 0 && !s.value.is_empty())
+        .collect();
+    out.sort_by(|a, b| a.address.cmp(&b.address).then_with(|| a.value.cmp(&b.value)));
+    out.dedup();
+    out
+}
+
+/// Text of a string literal or `string xref` evidence row (`None` for other evidence).
+pub fn string_text(e: &EvidenceRecord) -> Option<&str> {
+    e.description.strip_prefix(LITERAL_PREFIX).or_else(|| referenced_string(e))
+}
+
+/// Text of the string a `string xref` evidence row says its instruction uses (`None` for
+/// literals and other evidence).
+pub fn referenced_string(e: &EvidenceRecord) -> Option<&str> {
+    let (quoted, _) = e.description.strip_prefix(XREF_PREFIX)?.rsplit_once(" @ 0x")?;
+    quoted.strip_prefix('"')?.strip_suffix('"')
+}
+
+/// Data xrefs (immediates, memory operands, and computed addresses) that point into a string,
+/// in xref order.
+pub fn string_references(result: &AnalysisResult) -> Vec<StringReference> {
+    let literals = string_literals(result);
+    if literals.is_empty() {
+        return Vec::new();
+    }
+    result
+        .xrefs
+        .iter()
+        .filter(|x| matches!(x.kind, XrefKind::Immediate | XrefKind::Memory | XrefKind::Computed))
+        .flat_map(|x| {
+            literals_at(&literals, x.to).iter().filter(|l| l.contains(x.to)).map(|literal| {
+                StringReference {
+                    from: x.from,
+                    string: literal.address,
+                    value: literal.value.clone(),
+                    function: containing_function(&result.functions, x.from),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Every literal starting where the last literal at or before `address` starts.
+fn literals_at(literals: &[StringLiteral], address: u64) -> &[StringLiteral] {
+    let end = literals.partition_point(|s| s.address <= address);
+    let Some(last) = end.checked_sub(1) else {
+        return &[];
+    };
+    let start = literals[..end].partition_point(|s| s.address < literals[last].address);
+    &literals[start..end]
+}
+
+/// Record every string reference as `EvidenceKind::String` evidence at the referencing
+/// instruction. Idempotent; returns the number of rows added.
+pub fn link_string_xrefs(result: &mut AnalysisResult) -> usize {
+    let existing: HashSet<(u64, String)> = result
+        .evidence
+        .iter()
+        .filter(|e| e.description.starts_with(XREF_PREFIX))
+        .map(|e| (e.address, e.description.clone()))
+        .collect();
+    let mut added = 0;
+    for reference in string_references(result) {
+        let description =
+            format!("{XREF_PREFIX}\"{}\" @ 0x{:X}", reference.value, reference.string);
+        if existing.contains(&(reference.from, description.clone())) {
+            continue;
+        }
+        result.evidence.push(EvidenceRecord {
+            address: reference.from,
+            description,
+            kind: Some(EvidenceKind::String),
+            source: Some(EvidenceSource::new(EvidenceSource::CORE, "string-xrefs")),
+            run_id: None,
+        });
+        added += 1;
+    }
+    added
+}
+
+/// Functions referencing a string that contains `needle`, in address order.
+pub fn functions_referencing(result: &AnalysisResult, needle: &str) -> Vec<u64> {
+    let mut out: Vec<u64> = string_references(result)
+        .into_iter()
+        .filter(|r| r.value.contains(needle))
+        .filter_map(|r| r.function)
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Resolve `string:<text>` root hits to the functions referencing a matching string and put
+/// those functions in the slice.
+pub fn resolve_string_roots(result: &mut AnalysisResult) {
+    let mut seeded = HashSet::new();
+    for index in 0..result.root_hits.len() {
+        let Some(needle) = result.root_hits[index].root.strip_prefix(STRING_PREFIX) else {
+            continue;
+        };
+        if needle.is_empty() {
+            continue;
+        }
+        let functions = functions_referencing(result, needle);
+        let hit = &mut result.root_hits[index];
+        hit.error = functions.is_empty().then(|| "no function references this string".into());
+        seeded.extend(functions.iter().copied());
+        hit.functions = functions;
+    }
+    for function in &mut result.functions {
+        function.in_slice |= seeded.contains(&function.address);
+    }
+}
+
+/// The function whose extent covers `address`; unsized functions cover up to the next entry.
+fn containing_function(functions: &[FunctionRecord], address: u64) -> Option<u64> {
+    if let Some(f) = functions.iter().find(|f| {
+        f.size.is_some_and(|size| f.address <= address && address < f.address + u64::from(size))
+    }) {
+        return Some(f.address);
+    }
+    let candidate = functions.iter().filter(|f| f.address <= address).max_by_key(|f| f.address)?;
+    candidate.size.is_none().then_some(candidate.address)
+}
@@ -0,0 +1,463 @@
+//! Flat tables of a run's analysis for data tools (`export-tables`).
+//!
+//! pandas and DuckDB read a directory of CSV or Parquet files far faster than a nested
+//! `report.json` with millions of records. [`export_tables`] writes one file per table
+//! ([`TABLES`]) for the latest run of a ritual; evidence is streamed from the project DB a page
+//! at a time, so it is never loaded whole. Addresses are unsigned integers, and missing values
+//! are empty CSV fields / Parquet nulls. Parquet output needs the `parquet` feature.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::analysis::graph::edge_kind_label;
+use crate::db::{DbError, ProjectDb, ANALYSIS_PAGE_SIZE};
+use crate::services::analysis::{BasicBlock, CallEdge, EvidenceRecord, FunctionRecord};
+
+/// Tables written by [`export_tables`], in order.
+pub const TABLES: [&str; 5] =
+    ["functions", "call_edges", "basic_blocks", "block_edges", "evidence"];
+
+/// File format of exported tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Parquet,
+}
+
+impl TableFormat {
+    pub const ALL: [TableFormat; 2] = [TableFormat::Csv, TableFormat::Parquet];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, TablesError> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| TablesError::UnknownFormat(value.to_string()))
+    }
+
+    /// Whether this build can write the format.
+    pub fn is_available(&self) -> bool {
+        match self {
+            TableFormat::Csv => true,
+            TableFormat::Parquet => cfg!(feature = "parquet"),
+        }
+    }
+}
+
+impl fmt::Display for TableFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TablesError {
+    #[error("Unknown table format '{0}' (expected csv or parquet)")]
+    UnknownFormat(String),
+    #[error("Parquet output is not available in this build (enable the `parquet` feature)")]
+    ParquetUnavailable,
+    #[error("No run of ritual '{ritual}' for binary '{binary}'")]
+    NoRun { binary: String, ritual: String },
+    #[error(transparent)]
+    Db(#[from] DbError),
+    #[error("Failed to write {}: {source}", .path.display())]
+    Write { path: PathBuf, source: io::Error },
+    #[cfg(feature = "parquet")]
+    #[error("Failed to write {}: {source}", .path.display())]
+    Parquet { path: PathBuf, source: parquet::errors::ParquetError },
+}
+
+/// One written table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedTable {
+    pub name: &'static str,
+    pub path: PathBuf,
+    pub rows: usize,
+}
+
+/// Tables of one run, as written by [`export_tables`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableExport {
+    pub run_id: i64,
+    pub tables: Vec<ExportedTable>,
+}
+
+/// Write the latest run of `ritual` on `binary` as [`TABLES`] into `out_dir` (created if
+/// missing), one `<table>.<format>` file each, replacing files of the same name.
+pub fn export_tables(
+    db: &ProjectDb,
+    binary: &str,
+    ritual: &str,
+    format: TableFormat,
+    out_dir: &Path,
+) -> Result<TableExport, TablesError> {
+    if !format.is_available() {
+        return Err(TablesError::ParquetUnavailable);
+    }
+    let Some((run_id, result)) = db.load_analysis_skeleton(binary, ritual)? else {
+        return Err(TablesError::NoRun { binary: binary.to_string(), ritual: ritual.to_string() });
+    };
+    fs::create_dir_all(out_dir)
+        .map_err(|source| TablesError::Write { path: out_dir.to_path_buf(), source })?;
+
+    let mut tables = Vec::with_capacity(TABLES.len());
+    let batches = [
+        function_batch(&result.functions),
+        call_edge_batch(&result.call_edges),
+        block_batch(&result.basic_blocks),
+        block_edge_batch(&result.basic_blocks),
+    ];
+    for (name, batch) in TABLES.into_iter().zip(batches) {
+        let mut writer = TableWriter::create(format, out_dir, name, batch.schema)?;
+        writer.write(&batch)?;
+        tables.push(writer.finish()?);
+    }
+    let mut writer = TableWriter::create(format, out_dir, "evidence", EVIDENCE)?;
+    for page in db.evidence_pages(run_id, ANALYSIS_PAGE_SIZE) {
+        writer.write(&evidence_batch(&page?))?;
+    }
+    tables.push(writer.finish()?);
+    Ok(TableExport { run_id, tables })
+}
+
+/// Type of a table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    UInt,
+    Bool,
+    Text,
+}
+
+type Schema = &'static [(&'static str, ColumnType)];
+
+const FUNCTIONS: Schema = &[
+    ("address", ColumnType::UInt),
+    ("name", ColumnType::Text),
+    ("size", ColumnType::UInt),
+    ("in_slice", ColumnType::Bool),
+    ("is_boundary", ColumnType::Bool),
+    ("mangled_name", ColumnType::Text),
+    ("signature", ColumnType::Text),
+    ("arch", ColumnType::Text),
+    ("isa", ColumnType::Text),
+    ("fuzzy_hash", ColumnType::Text),
+];
+const CALL_EDGES: Schema = &[
+    ("from", ColumnType::UInt),
+    ("to", ColumnType::UInt),
+    ("to_name", ColumnType::Text),
+    ("to_import", ColumnType::Bool),
+    ("is_cross_slice", ColumnType::Bool),
+];
+const BASIC_BLOCKS: Schema = &[
+    ("start", ColumnType::UInt),
+    ("byte_len", ColumnType::UInt),
+    ("insn_count", ColumnType::UInt),
+    ("successors", ColumnType::UInt),
+];
+const BLOCK_EDGES: Schema =
+    &[("from", ColumnType::UInt), ("to", ColumnType::UInt), ("kind", ColumnType::Text)];
+const EVIDENCE: Schema = &[
+    ("address", ColumnType::UInt),
+    ("kind", ColumnType::Text),
+    ("source", ColumnType::Text),
+    ("description", ColumnType::Text),
+];
+
+/// Values of one column.
+#[derive(Debug, Clone, PartialEq)]
+enum Values {
+    UInt(Vec<Option<u64>>),
+    Bool(Vec<bool>),
+    Text(Vec<Option<String>>),
+}
+
+impl Values {
+    fn len(&self) -> usize {
+        match self {
+            Values::UInt(v) => v.len(),
+            Values::Bool(v) => v.len(),
+            Values::Text(v) => v.len(),
+        }
+    }
+}
+
+/// Rows of one table, column by column in schema order.
+struct Batch {
+    schema: Schema,
+    columns: Vec<Values>,
+}
+
+impl Batch {
+    fn rows(&self) -> usize {
+        self.columns.first().map_or(0, Values::len)
+    }
+}
+
+fn uint<T>(rows: &[T], get: impl Fn(&T) -> Option<u64>) -> Values {
+    Values::UInt(rows.iter().map(get).collect())
+}
+
+fn boolean<T>(rows: &[T], get: impl Fn(&T) -> bool) -> Values {
+    Values::Bool(rows.iter().map(get).collect())
+}
+
+fn text<T>(rows: &[T], get: impl Fn(&T) -> Option<String>) -> Values {
+    Values::Text(rows.iter().map(get).collect())
+}
+
+fn function_batch(functions: &[FunctionRecord]) -> Batch {
+    Batch {
+        schema: FUNCTIONS,
+        columns: vec![
+            uint(functions, |f| Some(f.address)),
+            text(functions, |f| f.name.clone()),
+            uint(functions, |f| f.size.map(u64::from)),
+            boolean(functions, |f| f.in_slice),
+            boolean(functions, |f| f.is_boundary),
+            text(functions, |f| f.mangled_name.clone()),
+            text(functions, |f| f.signature.as_ref().map(ToString::to_string)),
+            text(functions, |f| f.arch.clone()),
+            text(functions, |f| f.isa.clone()),
+            text(functions, |f| f.fuzzy_hash.clone()),
+        ],
+    }
+}
+
+fn call_edge_batch(edges: &[CallEdge]) -> Batch {
+    Batch {
+        schema: CALL_EDGES,
+        columns: vec![
+            uint(edges, |e| Some(e.from)),
+            uint(edges, |e| Some(e.to)),
+            text(edges, |e| e.to_name.clone()),
+            boolean(edges, |e| e.to_import),
+            boolean(edges, |e| e.is_cross_slice),
+        ],
+    }
+}
+
+fn block_batch(blocks: &[BasicBlock]) -> Batch {
+    Batch {
+        schema: BASIC_BLOCKS,
+        columns: vec![
+            uint(blocks, |b| Some(b.start)),
+            // 0 means unknown (runs recorded before block sizes were).
+            uint(blocks, |b| (b.byte_len > 0).then_some(b.byte_len.into())),
+            uint(blocks, |b| b.insn_count.map(u64::from)),
+            uint(blocks, |b| Some(b.successors.len() as u64)),
+        ],
+    }
+}
+
+/// Successor edges of `blocks`, one row per edge.
+fn block_edge_batch(blocks: &[BasicBlock]) -> Batch {
+    let edges: Vec<(u64, u64, &'static str)> = blocks
+        .iter()
+        .flat_map(|b| b.successors.iter().map(|s| (b.start, s.target, edge_kind_label(&s.kind))))
+        .collect();
+    Batch {
+        schema: BLOCK_EDGES,
+        columns: vec![
+            uint(&edges, |e| Some(e.0)),
+            uint(&edges, |e| Some(e.1)),
+            text(&edges, |e| Some(e.2.to_string())),
+        ],
+    }
+}
+
+fn evidence_batch(evidence: &[EvidenceRecord]) -> Batch {
+    Batch {
+        schema: EVIDENCE,
+        columns: vec![
+            uint(evidence, |e| Some(e.address)),
+            text(evidence, |e| e.kind.as_ref().map(|k| k.as_str().to_string())),
+            text(evidence, |e| e.source.as_ref().map(ToString::to_string)),
+            text(evidence, |e| Some(e.description.clone())),
+        ],
+    }
+}
+
+/// Writer of one table file.
+struct TableWriter {
+    name: &'static str,
+    path: PathBuf,
+    rows: usize,
+    sink: Sink,
+}
+
+enum Sink {
+    Csv(BufWriter<File>),
+    #[cfg(feature = "parquet")]
+    Parquet(Box<parquet_out::Writer>),
+}
+
+impl TableWriter {
+    fn create(
+        format: TableFormat,
+        dir: &Path,
+        name: &'static str,
+        schema: Schema,
+    ) -> Result<Self, TablesError> {
+        let path = dir.join(format!("{}.{}", name, format.as_str()));
+        let file = File::create(&path)
+            .map_err(|source| TablesError::Write { path: path.clone(), source })?;
+        let sink = match format {
+            TableFormat::Csv => {
+                let mut out = BufWriter::new(file);
+                let header: Vec<&str> = schema.iter().map(|(column, _)| *column).collect();
+                writeln!(out, "{}", header.join(","))
+                    .map_err(|source| TablesError::Write { path: path.clone(), source })?;
+                Sink::Csv(out)
+            }
+            #[cfg(feature = "parquet")]
+            TableFormat::Parquet => Sink::Parquet(Box::new(
+                parquet_out::Writer::new(file, schema)
+                    .map_err(|source| TablesError::Parquet { path: path.clone(), source })?,
+            )),
+            #[cfg(not(feature = "parquet"))]
+            TableFormat::Parquet => return Err(TablesError::ParquetUnavailable),
+        };
+        Ok(Self { name, path, rows: 0, sink })
+    }
+
+    fn write(&mut self, batch: &Batch) -> Result<(), TablesError> {
+        let path = &self.path;
+        match &mut self.sink {
+            Sink::Csv(out) => write_csv_rows(out, batch)
+                .map_err(|source| TablesError::Write { path: path.clone(), source })?,
+            #[cfg(feature = "parquet")]
+            Sink::Parquet(writer) => writer
+                .write(batch)
+                .map_err(|source| TablesError::Parquet { path: path.clone(), source })?,
+        }
+        self.rows += batch.rows();
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ExportedTable, TablesError> {
+        let path = self.path;
+        match self.sink {
+            Sink::Csv(mut out) => {
+                out.flush().map_err(|source| TablesError::Write { path: path.clone(), source })?
+            }
+            #[cfg(feature = "parquet")]
+            Sink::Parquet(writer) => writer
+                .finish()
+                .map_err(|source| TablesError::Parquet { path: path.clone(), source })?,
+        }
+        Ok(ExportedTable { name: self.name, path, rows: self.rows })
+    }
+}
+
+fn write_csv_rows(out: &mut impl Write, batch: &Batch) -> io::Result<()> {
+    let mut line = String::new();
+    for row in 0..batch.rows() {
+        line.clear();
+        for (i, column) in batch.columns.iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            match column {
+                Values::UInt(values) => {
+                    if let Some(value) = values[row] {
+                        line.push_str(&value.to_string());
+                    }
+                }
+                Values::Bool(values) => line.push_str(if values[row] { "true" } else { "false" }),
+                Values::Text(values) => {
+                    if let Some(value) = &values[row] {
+                        push_csv_field(&mut line, value);
+                    }
+                }
+            }
+        }
+        line.push('\n');
+        out.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Append `value` as an RFC 4180 field, quoted when it holds a comma, quote, or line break.
+fn push_csv_field(line: &mut String, value: &str) {
+    if value.contains([',', '"', '\n', '\r']) {
+        line.push('"');
+        line.push_str(&value.replace('"', "\"\""));
+        line.push('"');
+    } else {
+        line.push_str(value);
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_out {
+    use std::fs::File;
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, BooleanArray, RecordBatch, StringArray, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema as ArrowSchema, SchemaRef};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::{Compression, ZstdLevel};
+    use parquet::errors::ParquetError;
+    use parquet::file::properties::WriterProperties;
+
+    use super::{Batch, ColumnType, Schema, Values};
+
+    /// Zstandard-compressed Parquet file of one table.
+    pub struct Writer {
+        schema: SchemaRef,
+        inner: ArrowWriter<File>,
+    }
+
+    impl Writer {
+        pub fn new(file: File, schema: Schema) -> Result<Self, ParquetError> {
+            let fields: Vec<Field> = schema
+                .iter()
+                .map(|(name, kind)| match kind {
+                    ColumnType::UInt => Field::new(*name, DataType::UInt64, true),
+                    ColumnType::Bool => Field::new(*name, DataType::Boolean, false),
+                    ColumnType::Text => Field::new(*name, DataType::Utf8, true),
+                })
+                .collect();
+            let schema = Arc::new(ArrowSchema::new(fields));
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build();
+            let inner = ArrowWriter::try_new(file, schema.clone(), Some(properties))?;
+            Ok(Self { schema, inner })
+        }
+
+        pub fn write(&mut self, batch: &Batch) -> Result<(), ParquetError> {
+            if batch.rows() == 0 {
+                return Ok(());
+            }
+            let columns: Vec<ArrayRef> = batch
+                .columns
+                .iter()
+                .map(|values| -> ArrayRef {
+                    match values {
+                        Values::UInt(v) => Arc::new(UInt64Array::from(v.clone())),
+                        Values::Bool(v) => Arc::new(BooleanArray::from(v.clone())),
+                        Values::Text(v) => Arc::new(StringArray::from(v.clone())),
+                    }
+                })
+                .collect();
+            let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+            self.inner.write(&batch)
+        }
+
+        pub fn finish(self) -> Result<(), ParquetError> {
+            self.inner.close().map(|_| ())
+        }
+    }
+}
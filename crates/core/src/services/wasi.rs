@@ -0,0 +1,441 @@
+//! In-process WASI runtime for plugin and string decoder modules.
+//!
+//! Modules run on the `wasmi` interpreter (feature `wasm-plugins`) against a minimal
+//! `wasi_snapshot_preview1`: stdin is the input the caller hands over, stdout and stderr are
+//! captured, `argv` is the module's name, and there are no environment variables, preopened
+//! directories, or sockets. `random_get` fills buffers with zeros so runs stay reproducible.
+//! Every other WASI call fails with `ENOSYS`.
+//!
+//! Each module runs on its own thread with an instruction budget and a memory limit. The
+//! interpreter cannot be interrupted, so under a run's [`Deadline`] the budget is the time left
+//! converted to instructions, at a rate measured once per process: a module that runs out of
+//! time runs out of fuel too, and the caller gets [`AnalysisError::Timeout`] once its thread
+//! has stopped. Without a deadline the budget is [`FUEL`].
+
+use std::path::Path;
+
+use crate::services::analysis::AnalysisError;
+use crate::services::process::Deadline;
+
+/// Instructions (roughly) a module may execute before it is stopped; under a deadline the
+/// budget is smaller when less time is left.
+pub const FUEL: u64 = 20_000_000_000;
+
+/// What a module wrote, and the status it exited with (0 when `_start` returned).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WasiOutput {
+    pub code: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Run the `_start` function of the WASI module at `path` with `input` on stdin. `tool` names
+/// the module in errors and is its `argv[0]`.
+pub fn run_wasi(
+    path: &Path,
+    tool: &str,
+    input: Vec<u8>,
+    deadline: &Deadline,
+) -> Result<WasiOutput, AnalysisError> {
+    #[cfg(feature = "wasm-plugins")]
+    {
+        runtime::run(path, tool, input, deadline)
+    }
+    #[cfg(not(feature = "wasm-plugins"))]
+    {
+        let _ = (path, input, deadline);
+        Err(AnalysisError::Backend(format!(
+            "{tool} is a WASM module, but this build has no WASM runtime (feature `wasm-plugins`)"
+        )))
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+mod runtime {
+    use std::path::Path;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::sync::OnceLock;
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use wasmi::core::{TrapCode, ValType};
+    use wasmi::{
+        Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Val,
+    };
+
+    use super::{WasiOutput, FUEL};
+    use crate::services::analysis::AnalysisError;
+    use crate::services::process::Deadline;
+
+    /// Largest linear memory a module may grow to.
+    const MEMORY_LIMIT: usize = 1 << 30;
+
+    /// How long the caller still waits for a module's thread after the deadline; its fuel
+    /// should already have run out by then.
+    const STOP_GRACE: Duration = Duration::from_secs(2);
+
+    /// Fuel spent measuring the interpreter's speed.
+    const CALIBRATION_FUEL: u64 = 1_000_000;
+
+    /// `(module (func (export "spin") (loop (br 0))))`, burned to measure the speed.
+    const SPIN_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // magic, version
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type: () -> ()
+        0x03, 0x02, 0x01, 0x00, // function 0 has type 0
+        0x07, 0x08, 0x01, 0x04, b's', b'p', b'i', b'n', 0x00, 0x00, // export "spin"
+        0x0A, 0x09, 0x01, 0x07, 0x00, 0x03, 0x40, 0x0C, 0x00, 0x0B, 0x0B, // loop br 0 end
+    ];
+
+    const WASI: &str = "wasi_snapshot_preview1";
+
+    const ERRNO_SUCCESS: i32 = 0;
+    const ERRNO_BADF: i32 = 8;
+    const ERRNO_FAULT: i32 = 21;
+    const ERRNO_NOSYS: i32 = 52;
+
+    /// WASI calls defined by [`define_wasi`]; other imports from `wasi_snapshot_preview1`
+    /// are stubbed to fail with `ENOSYS`.
+    const IMPLEMENTED: &[&str] = &[
+        "args_get",
+        "args_sizes_get",
+        "clock_time_get",
+        "environ_get",
+        "environ_sizes_get",
+        "fd_close",
+        "fd_fdstat_get",
+        "fd_prestat_get",
+        "fd_read",
+        "fd_write",
+        "proc_exit",
+        "random_get",
+        "sched_yield",
+    ];
+
+    /// Store state: the module's stdio and `argv[0]`.
+    struct Wasi {
+        name: Vec<u8>,
+        stdin: Vec<u8>,
+        stdin_read: usize,
+        stdout: Vec<u8>,
+        stderr: Vec<u8>,
+        limits: StoreLimits,
+    }
+
+    pub(super) fn run(
+        path: &Path,
+        tool: &str,
+        input: Vec<u8>,
+        deadline: &Deadline,
+    ) -> Result<WasiOutput, AnalysisError> {
+        if deadline.remaining() == Some(Duration::ZERO) {
+            return Err(deadline.timeout_error(tool));
+        }
+        let wasm = std::fs::read(path)
+            .map_err(|e| AnalysisError::Backend(format!("failed to read {tool}: {e}")))?;
+        let fuel = match deadline.remaining() {
+            Some(left) => FUEL.min((left.as_secs_f64() * fuel_per_second() as f64) as u64),
+            None => FUEL,
+        };
+        let (tx, rx) = mpsc::channel();
+        let (name, deadline) = (tool.to_string(), *deadline);
+        let module = thread::Builder::new()
+            .name(format!("wasi {tool}"))
+            .spawn(move || {
+                let _ = tx.send(execute(&wasm, &name, input, fuel, &deadline));
+            })
+            .map_err(|e| AnalysisError::Backend(format!("failed to start {tool}: {e}")))?;
+        let outcome = match deadline.remaining() {
+            None => rx.recv().ok(),
+            Some(left) => match rx.recv_timeout(left) {
+                Ok(outcome) => Some(outcome),
+                Err(RecvTimeoutError::Timeout) => {
+                    match rx.recv_timeout(STOP_GRACE) {
+                        Ok(_) => drop(module.join()),
+                        Err(_) => tracing::warn!(tool, "WASM module still running after timeout"),
+                    }
+                    return Err(deadline.timeout_error(tool));
+                }
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+        };
+        // The thread only has to return once it sent its outcome.
+        let _ = module.join();
+        outcome.unwrap_or_else(|| {
+            Err(AnalysisError::Backend(format!("the WASM runtime crashed while running {tool}")))
+        })
+    }
+
+    /// Fuel the interpreter burns per second in this process, measured on first use.
+    fn fuel_per_second() -> u64 {
+        static RATE: OnceLock<u64> = OnceLock::new();
+        *RATE.get_or_init(|| {
+            let mut config = Config::default();
+            config.consume_fuel(true);
+            let engine = Engine::new(&config);
+            let module = Module::new(&engine, SPIN_MODULE).expect("spin module is valid");
+            let mut store = Store::new(&engine, ());
+            store.set_fuel(CALIBRATION_FUEL).expect("fuel metering is enabled");
+            let spin = Linker::new(&engine)
+                .instantiate(&mut store, &module)
+                .and_then(|pre| pre.start(&mut store))
+                .and_then(|instance| instance.get_typed_func::<(), ()>(&store, "spin"))
+                .expect("spin module instantiates");
+            let started = Instant::now();
+            let _ = spin.call(&mut store, ());
+            let elapsed = started.elapsed().as_secs_f64().max(1e-6);
+            (CALIBRATION_FUEL as f64 / elapsed) as u64
+        })
+    }
+
+    fn execute(
+        wasm: &[u8],
+        tool: &str,
+        input: Vec<u8>,
+        fuel: u64,
+        deadline: &Deadline,
+    ) -> Result<WasiOutput, AnalysisError> {
+        let fail =
+            |what: &str, e: wasmi::Error| AnalysisError::Backend(format!("{tool} {what}: {e}"));
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module =
+            Module::new(&engine, wasm).map_err(|e| fail("is not a valid WASM module", e))?;
+        let wasi = Wasi {
+            name: tool.as_bytes().to_vec(),
+            stdin: input,
+            stdin_read: 0,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+        };
+        let mut store = Store::new(&engine, wasi);
+        store.limiter(|wasi| &mut wasi.limits);
+        store.set_fuel(fuel).expect("fuel metering is enabled");
+
+        let mut linker = Linker::new(&engine);
+        define_wasi(&mut linker).map_err(|e| fail("cannot be linked", e.into()))?;
+        for import in module.imports().filter(|i| i.module() == WASI) {
+            let Some(ty) = import.ty().func() else {
+                continue;
+            };
+            if IMPLEMENTED.contains(&import.name()) {
+                continue;
+            }
+            let returns_errno = ty.results() == [ValType::I32];
+            let name = import.name().to_string();
+            linker
+                .func_new(WASI, import.name(), ty.clone(), move |_, _, results| {
+                    if returns_errno {
+                        results[0] = Val::I32(ERRNO_NOSYS);
+                        Ok(())
+                    } else {
+                        Err(wasmi::Error::new(format!("unsupported WASI call {name}")))
+                    }
+                })
+                .map_err(|e| fail("cannot be linked", e.into()))?;
+        }
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| fail("cannot be instantiated", e))?;
+        let start = instance
+            .get_typed_func::<(), ()>(&store, "_start")
+            .map_err(|e| fail("has no `_start` function", e))?;
+        let code = match start.call(&mut store, ()) {
+            Ok(()) => 0,
+            Err(e) => match e.i32_exit_status() {
+                Some(code) => code,
+                None if e.as_trap_code() == Some(TrapCode::OutOfFuel) && fuel < FUEL => {
+                    return Err(deadline.timeout_error(tool));
+                }
+                None if e.as_trap_code() == Some(TrapCode::OutOfFuel) => {
+                    return Err(AnalysisError::Backend(format!(
+                        "{tool} ran past its budget of {FUEL} instructions"
+                    )));
+                }
+                None => {
+                    let stderr = String::from_utf8_lossy(&store.data().stderr).trim().to_string();
+                    return Err(AnalysisError::Backend(format!("{tool} trapped: {e}: {stderr}")));
+                }
+            },
+        };
+        let wasi = store.into_data();
+        Ok(WasiOutput { code, stdout: wasi.stdout, stderr: wasi.stderr })
+    }
+
+    fn define_wasi(linker: &mut Linker<Wasi>) -> Result<(), wasmi::errors::LinkerError> {
+        linker.func_wrap(
+            WASI,
+            "args_sizes_get",
+            |mut caller: Caller<'_, Wasi>, argc: i32, size: i32| {
+                with_memory(&mut caller, |mem, wasi| {
+                    write_u32(mem, argc, 1)?;
+                    write_u32(mem, size, wasi.name.len() as u32 + 1)
+                })
+            },
+        )?;
+        linker.func_wrap(
+            WASI,
+            "args_get",
+            |mut caller: Caller<'_, Wasi>, argv: i32, buf: i32| {
+                with_memory(&mut caller, |mem, wasi| {
+                    write_u32(mem, argv, buf as u32)?;
+                    let dest = bytes_mut(mem, buf, wasi.name.len() as u32 + 1)?;
+                    dest[..wasi.name.len()].copy_from_slice(&wasi.name);
+                    dest[wasi.name.len()] = 0;
+                    Ok(())
+                })
+            },
+        )?;
+        linker.func_wrap(
+            WASI,
+            "environ_sizes_get",
+            |mut caller: Caller<'_, Wasi>, count: i32, size: i32| {
+                with_memory(&mut caller, |mem, _| {
+                    write_u32(mem, count, 0)?;
+                    write_u32(mem, size, 0)
+                })
+            },
+        )?;
+        linker
+            .func_wrap(WASI, "environ_get", |_: Caller<'_, Wasi>, _: i32, _: i32| ERRNO_SUCCESS)?;
+        linker.func_wrap(
+            WASI,
+            "clock_time_get",
+            |mut caller: Caller<'_, Wasi>, _clock: i32, _precision: i64, time: i32| {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                with_memory(&mut caller, |mem, _| {
+                    bytes_mut(mem, time, 8)?
+                        .copy_from_slice(&(now.as_nanos() as u64).to_le_bytes());
+                    Ok(())
+                })
+            },
+        )?;
+        linker.func_wrap(WASI, "fd_close", |_: Caller<'_, Wasi>, fd: i32| {
+            if (0..=2).contains(&fd) {
+                ERRNO_SUCCESS
+            } else {
+                ERRNO_BADF
+            }
+        })?;
+        linker.func_wrap(
+            WASI,
+            "fd_fdstat_get",
+            |mut caller: Caller<'_, Wasi>, fd: i32, stat: i32| {
+                if !(0..=2).contains(&fd) {
+                    return ERRNO_BADF;
+                }
+                with_memory(&mut caller, |mem, _| {
+                    let stat = bytes_mut(mem, stat, 24)?;
+                    stat.fill(0);
+                    // Filetype: character device.
+                    stat[0] = 2;
+                    Ok(())
+                })
+            },
+        )?;
+        linker
+            .func_wrap(WASI, "fd_prestat_get", |_: Caller<'_, Wasi>, _: i32, _: i32| ERRNO_BADF)?;
+        linker.func_wrap(
+            WASI,
+            "fd_read",
+            |mut caller: Caller<'_, Wasi>, fd: i32, iovs: i32, iovs_len: i32, nread: i32| {
+                if fd != 0 {
+                    return ERRNO_BADF;
+                }
+                with_memory(&mut caller, |mem, wasi| {
+                    let mut total = 0u32;
+                    for (ptr, len) in iovecs(mem, iovs, iovs_len)? {
+                        let rest = &wasi.stdin[wasi.stdin_read..];
+                        let n = rest.len().min(len as usize);
+                        bytes_mut(mem, ptr as i32, n as u32)?.copy_from_slice(&rest[..n]);
+                        wasi.stdin_read += n;
+                        total += n as u32;
+                    }
+                    write_u32(mem, nread, total)
+                })
+            },
+        )?;
+        linker.func_wrap(
+            WASI,
+            "fd_write",
+            |mut caller: Caller<'_, Wasi>, fd: i32, iovs: i32, iovs_len: i32, nwritten: i32| {
+                with_memory(&mut caller, |mem, wasi| {
+                    let sink = match fd {
+                        1 => &mut wasi.stdout,
+                        2 => &mut wasi.stderr,
+                        _ => return Err(ERRNO_BADF),
+                    };
+                    let mut total = 0u32;
+                    for (ptr, len) in iovecs(mem, iovs, iovs_len)? {
+                        sink.extend_from_slice(bytes(mem, ptr as i32, len)?);
+                        total += len;
+                    }
+                    write_u32(mem, nwritten, total)
+                })
+            },
+        )?;
+        linker.func_wrap(
+            WASI,
+            "proc_exit",
+            |_: Caller<'_, Wasi>, code: i32| -> Result<(), wasmi::Error> {
+                Err(wasmi::Error::i32_exit(code))
+            },
+        )?;
+        linker.func_wrap(
+            WASI,
+            "random_get",
+            |mut caller: Caller<'_, Wasi>, buf: i32, len: i32| {
+                with_memory(&mut caller, |mem, _| {
+                    bytes_mut(mem, buf, len as u32)?.fill(0);
+                    Ok(())
+                })
+            },
+        )?;
+        linker.func_wrap(WASI, "sched_yield", |_: Caller<'_, Wasi>| ERRNO_SUCCESS)?;
+        Ok(())
+    }
+
+    /// Run `f` over the module's exported memory, turning its result into a WASI errno.
+    fn with_memory(
+        caller: &mut Caller<'_, Wasi>,
+        f: impl FnOnce(&mut [u8], &mut Wasi) -> Result<(), i32>,
+    ) -> i32 {
+        let Some(memory) = caller.get_export("memory").and_then(Extern::into_memory) else {
+            return ERRNO_FAULT;
+        };
+        let (mem, wasi) = memory.data_and_store_mut(caller);
+        match f(mem, wasi) {
+            Ok(()) => ERRNO_SUCCESS,
+            Err(errno) => errno,
+        }
+    }
+
+    fn bytes(mem: &[u8], ptr: i32, len: u32) -> Result<&[u8], i32> {
+        let start = ptr as u32 as usize;
+        mem.get(start..start + len as usize).ok_or(ERRNO_FAULT)
+    }
+
+    fn bytes_mut(mem: &mut [u8], ptr: i32, len: u32) -> Result<&mut [u8], i32> {
+        let start = ptr as u32 as usize;
+        mem.get_mut(start..start + len as usize).ok_or(ERRNO_FAULT)
+    }
+
+    fn write_u32(mem: &mut [u8], ptr: i32, value: u32) -> Result<(), i32> {
+        bytes_mut(mem, ptr, 4)?.copy_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    /// `(pointer, length)` of each of the `count` iovecs at `iovs`.
+    fn iovecs(mem: &[u8], iovs: i32, count: i32) -> Result<Vec<(u32, u32)>, i32> {
+        let table = bytes(mem, iovs, (count as u32).checked_mul(8).ok_or(ERRNO_FAULT)?)?;
+        Ok(table
+            .chunks_exact(8)
+            .map(|iov| {
+                let word = |at: usize| u32::from_le_bytes(iov[at..at + 4].try_into().unwrap());
+                (word(0), word(4))
+            })
+            .collect())
+    }
+}
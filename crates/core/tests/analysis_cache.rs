@@ -0,0 +1,151 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ritual_core::db::{ProjectConfig, ProjectContext, ProjectLayout, RitualRunStatus};
+use ritual_core::services::analysis::{
+    AnalysisBackend, AnalysisError, AnalysisOptions, AnalysisRequest, AnalysisResult,
+    FunctionRecord, RitualRunner, RootHit, RunMetadata,
+};
+use ritual_core::services::cache::{AnalysisCache, CacheKey};
+
+/// Backend that counts invocations so tests can tell cache hits from misses.
+struct CountingBackend {
+    calls: AtomicUsize,
+}
+
+impl AnalysisBackend for CountingBackend {
+    fn analyze(&self, request: &AnalysisRequest) -> Result<AnalysisResult, AnalysisError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(AnalysisResult {
+            functions: vec![FunctionRecord {
+                address: 0x1000,
+                name: request.roots.first().cloned(),
+                size: Some(8),
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            }],
+            call_edges: Vec::new(),
+            evidence: Vec::new(),
+            xrefs: Vec::new(),
+            basic_blocks: Vec::new(),
+            roots: request.roots.clone(),
+            root_hits: vec![RootHit {
+                root: request.roots[0].clone(),
+                functions: vec![0x1000],
+                error: None,
+            }],
+            sub_slices: Vec::new(),
+            backend_version: Some("count-1".into()),
+            backend_path: None,
+            text_bytes: None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "counting"
+    }
+}
+
+fn request(bin_path: std::path::PathBuf, max_depth: Option<u32>) -> AnalysisRequest {
+    AnalysisRequest {
+        ritual_name: "Cached".into(),
+        binary_name: "Bin".into(),
+        binary_path: bin_path,
+        roots: vec!["entry".into()],
+        arch: Some("x86_64".into()),
+        options: AnalysisOptions { max_depth, ..Default::default() },
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    }
+}
+
+fn meta(binary_hash: Option<&str>) -> RunMetadata {
+    RunMetadata {
+        spec_hash: "spec".into(),
+        binary_id: None,
+        binary_hash: binary_hash.map(str::to_string),
+        backend: "counting".into(),
+        backend_version: Some("count-1".into()),
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        deterministic: false,
+        epoch: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        started_at: None,
+        usage: None,
+    }
+}
+
+#[test]
+fn run_cached_skips_backend_on_matching_key() {
+    let temp = tempfile::tempdir().unwrap();
+    let layout = ProjectLayout::new(temp.path());
+    std::fs::create_dir_all(&layout.meta_dir).unwrap();
+    let config = ProjectConfig::new("Cache", layout.db_path_relative_string());
+    std::fs::write(&layout.project_config_path, serde_json::to_string(&config).unwrap()).unwrap();
+    let ctx = ProjectContext::from_root(temp.path()).unwrap();
+    let bin_path = temp.path().join("bin.so");
+    std::fs::write(&bin_path, b"bin").unwrap();
+
+    let backend = CountingBackend { calls: AtomicUsize::new(0) };
+    let runner = RitualRunner { ctx: &ctx, backend: &backend };
+    let cache = AnalysisCache::new(layout.cache_dir());
+
+    let first = runner.run_cached(&request(bin_path.clone(), Some(2)), &meta(Some("h1")), &cache);
+    let first = first.unwrap();
+    assert!(!first.cache_hit);
+    let second = runner.run_cached(&request(bin_path.clone(), Some(2)), &meta(Some("h1")), &cache);
+    let second = second.unwrap();
+    assert!(second.cache_hit);
+    assert_eq!(second.result, first.result);
+    assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+    // Every run is still recorded in the DB, cached or not.
+    assert_eq!(ctx.db.list_ritual_runs(None).unwrap().len(), 2);
+
+    // Different options or binary hash miss; an unknown hash bypasses the cache entirely.
+    runner.run_cached(&request(bin_path.clone(), Some(3)), &meta(Some("h1")), &cache).unwrap();
+    runner.run_cached(&request(bin_path.clone(), Some(2)), &meta(Some("h2")), &cache).unwrap();
+    let unhashed = runner.run_cached(&request(bin_path, Some(2)), &meta(None), &cache).unwrap();
+    assert!(!unhashed.cache_hit);
+    assert_eq!(backend.calls.load(Ordering::SeqCst), 4);
+    assert_eq!(cache.stats().unwrap().entries, 3);
+}
+
+#[test]
+fn cache_stats_and_clear_cover_all_entries() {
+    let temp = tempfile::tempdir().unwrap();
+    let cache = AnalysisCache::new(temp.path().join("cache"));
+    assert_eq!(cache.stats().unwrap().entries, 0);
+    assert_eq!(cache.clear().unwrap(), (0, 0));
+
+    let bin_path = temp.path().join("bin.so");
+    let key = CacheKey::for_request(&request(bin_path.clone(), None), &meta(Some("abc"))).unwrap();
+    assert!(CacheKey::for_request(&request(bin_path.clone(), None), &meta(None)).is_none());
+    let result =
+        CountingBackend { calls: AtomicUsize::new(0) }.analyze(&request(bin_path, None)).unwrap();
+    let path = cache.put(&key, &result).unwrap();
+    assert_eq!(cache.get(&key), Some(result));
+
+    // Corrupt entries are misses and reported as unreadable, not errors.
+    std::fs::write(temp.path().join("cache").join("junk.json"), b"{").unwrap();
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.unreadable, 1);
+    assert_eq!(stats.items[0].binary_hash, "abc");
+    assert_eq!(stats.items[0].backend, "counting");
+
+    let (removed, freed) = cache.clear().unwrap();
+    assert_eq!(removed, 2);
+    assert_eq!(freed, stats.total_bytes);
+    assert!(!path.exists());
+    assert_eq!(cache.get(&key), None);
+}
@@ -0,0 +1,118 @@
+use ritual_core::analysis::diff::{diff_analysis, EdgeRef, FunctionMove, FunctionRename};
+use ritual_core::services::analysis::{
+    AnalysisResult, CallEdge, EvidenceKind, EvidenceRecord, FunctionRecord,
+};
+
+fn func(address: u64, name: &str) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn edge(from: u64, to: u64) -> CallEdge {
+    CallEdge { from, to, is_cross_slice: false, to_name: None, to_import: false }
+}
+
+fn evidence(address: u64, description: &str) -> EvidenceRecord {
+    EvidenceRecord {
+        address,
+        description: description.into(),
+        kind: Some(EvidenceKind::String),
+        source: None,
+        run_id: None,
+    }
+}
+
+fn result(
+    functions: Vec<FunctionRecord>,
+    call_edges: Vec<CallEdge>,
+    evidence: Vec<EvidenceRecord>,
+) -> AnalysisResult {
+    AnalysisResult {
+        functions,
+        call_edges,
+        evidence,
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn identical_results_produce_empty_diff() {
+    let a = result(vec![func(0x10, "main")], vec![edge(0x10, 0x20)], vec![evidence(0x10, "hi")]);
+    let diff = diff_analysis(&a, &a.clone());
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn diff_reports_added_removed_renamed_and_moved_functions() {
+    let base = result(
+        vec![func(0x10, "main"), func(0x20, "helper"), func(0x30, "old_fn"), func(0x40, "sub_40")],
+        vec![edge(0x10, 0x20), edge(0x10, 0x30)],
+        vec![evidence(0x10, "string: hello"), evidence(0x30, "string: legacy")],
+    );
+    // New build: helper moved, old_fn removed, sub_40 got a real name, new_fn added.
+    let other = result(
+        vec![func(0x10, "main"), func(0x24, "helper"), func(0x40, "Render"), func(0x50, "new_fn")],
+        vec![edge(0x10, 0x24), edge(0x10, 0x50)],
+        vec![evidence(0x12, "string: hello"), evidence(0x50, "string: fresh")],
+    );
+
+    let diff = diff_analysis(&base, &other);
+    assert_eq!(diff.functions.added, vec![func(0x50, "new_fn")]);
+    assert_eq!(diff.functions.removed, vec![func(0x30, "old_fn")]);
+    assert_eq!(
+        diff.functions.renamed,
+        vec![FunctionRename {
+            address: 0x40,
+            from: Some("sub_40".into()),
+            to: Some("Render".into())
+        }]
+    );
+    assert_eq!(
+        diff.functions.moved,
+        vec![FunctionMove { name: "helper".into(), from: 0x20, to: 0x24 }]
+    );
+
+    // main -> helper survives the move; only the old_fn/new_fn edges change.
+    assert_eq!(
+        diff.call_edges.removed,
+        vec![EdgeRef {
+            from: 0x10,
+            to: 0x30,
+            from_name: Some("main".into()),
+            to_name: Some("old_fn".into())
+        }]
+    );
+    assert_eq!(
+        diff.call_edges.added,
+        vec![EdgeRef {
+            from: 0x10,
+            to: 0x50,
+            from_name: Some("main".into()),
+            to_name: Some("new_fn".into())
+        }]
+    );
+
+    // Evidence compares by content, not address.
+    assert_eq!(diff.evidence.removed, vec![evidence(0x30, "string: legacy")]);
+    assert_eq!(diff.evidence.added, vec![evidence(0x50, "string: fresh")]);
+
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["functions"]["moved"][0]["name"], "helper");
+}
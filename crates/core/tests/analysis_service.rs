@@ -1,7 +1,7 @@
 use ritual_core::db::ProjectContext;
 use ritual_core::services::analysis::{
     AnalysisBackend, AnalysisOptions, AnalysisRequest, AnalysisResult, BackendRegistry,
-    EvidenceRecord, FunctionRecord, RitualRunner, RunMetadata,
+    EvidenceRecord, EvidenceSource, FunctionRecord, RitualRunner, RunMetadata,
 };
 
 struct NoopBackend;
@@ -22,6 +22,11 @@ impl AnalysisBackend for NoopBackend {
                     size: None,
                     in_slice: true,
                     is_boundary: false,
+                    mangled_name: None,
+                    signature: None,
+                    arch: None,
+                    fuzzy_hash: None,
+                    isa: None,
                 })
                 .collect(),
             call_edges: vec![],
@@ -29,6 +34,8 @@ impl AnalysisBackend for NoopBackend {
                 address: 0,
                 description: "noop backend".into(),
                 kind: None,
+                source: None,
+                run_id: None,
             }],
             basic_blocks: vec![],
             roots: request.roots.clone(),
@@ -43,11 +50,19 @@ impl AnalysisBackend for NoopBackend {
                         size: None,
                         in_slice: true,
                         is_boundary: false,
+                        mangled_name: None,
+                        signature: None,
+                        arch: None,
+                        fuzzy_hash: None,
+                        isa: None,
                     })
                     .collect::<Vec<_>>(),
             ),
             backend_version: Some("noop-1.0".into()),
             backend_path: None,
+            sub_slices: Vec::new(),
+            xrefs: Vec::new(),
+            text_bytes: None,
         })
     }
 
@@ -91,9 +106,15 @@ fn ritual_runner_persists_meta_backend_path_when_backend_omits_it() {
             include_imports: false,
             include_strings: false,
             max_instructions: Some(16),
+            max_total_instructions: None,
         },
         arch: None,
         backend_path: Some(std::path::PathBuf::from("/configured/tool")),
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let runner = RitualRunner { ctx: &ctx, backend };
@@ -107,6 +128,13 @@ fn ritual_runner_persists_meta_backend_path_when_backend_omits_it() {
                 backend_version: None,
                 backend_path: Some("/configured/tool".into()),
                 status: ritual_core::db::RitualRunStatus::Succeeded,
+                deterministic: false,
+                epoch: None,
+                binary_id: None,
+                failed_backends: Vec::new(),
+                spec_path: None,
+                started_at: None,
+                usage: None,
             },
         )
         .expect("analysis");
@@ -146,9 +174,15 @@ fn ritual_runner_invokes_backend_and_inserts_run() {
             include_imports: false,
             include_strings: false,
             max_instructions: Some(16),
+            max_total_instructions: None,
         },
         arch: None,
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let runner = RitualRunner { ctx: &ctx, backend };
@@ -162,14 +196,258 @@ fn ritual_runner_invokes_backend_and_inserts_run() {
                 backend_version: None,
                 backend_path: None,
                 status: ritual_core::db::RitualRunStatus::Succeeded,
+                deterministic: false,
+                epoch: None,
+                binary_id: None,
+                failed_backends: Vec::new(),
+                spec_path: None,
+                started_at: None,
+                usage: None,
             },
         )
         .expect("analysis");
     assert_eq!(result.functions.len(), 1);
     assert_eq!(result.functions[0].name.as_deref(), Some("entry_point"));
+    // Evidence the backend left unlabeled is attributed to it.
+    assert_eq!(result.evidence[0].source, Some(EvidenceSource::new("noop", "analysis")));
 
     // DB should contain a run record.
     let runs = ctx.db.list_ritual_runs(None).expect("runs");
     assert_eq!(runs.len(), 1);
     assert_eq!(runs[0].status, ritual_core::db::RitualRunStatus::Succeeded);
+    let stored = ctx.db.load_analysis_result("Bin", "TestRitual").unwrap().expect("analysis");
+    assert_eq!(stored.evidence[0].source, result.evidence[0].source);
+    assert!(stored.evidence[0].run_id.is_some());
+}
+
+#[test]
+fn build_sub_slices_follows_call_edges_per_label() {
+    use ritual_core::services::analysis::{build_sub_slices, CallEdge, RootGroup, RootHit};
+
+    let func = |address: u64, name: &str| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let result = AnalysisResult {
+        functions: vec![
+            func(0x10, "CUIManager::init"),
+            func(0x20, "draw"),
+            func(0x30, "send_packet"),
+            func(0x40, "encode"),
+        ],
+        call_edges: vec![
+            CallEdge {
+                from: 0x10,
+                to: 0x20,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x30,
+                to: 0x40,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x40,
+                to: 0x999,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+        evidence: vec![],
+        basic_blocks: vec![],
+        roots: vec!["CUIManager::init".into(), "send_packet".into()],
+        root_hits: vec![RootHit { root: "send_packet".into(), functions: vec![0x30], error: None }],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    let groups = vec![
+        RootGroup { label: "net".into(), roots: vec!["send_packet".into()] },
+        RootGroup { label: "ui".into(), roots: vec!["CUIManager::init".into()] },
+    ];
+
+    let subs = build_sub_slices(&groups, &result);
+    assert_eq!(subs.len(), 2);
+    assert_eq!(subs[0].label, "net");
+    // Edges to addresses outside the analyzed functions are not followed.
+    assert_eq!(subs[0].functions, vec![0x30, 0x40]);
+    // Roots without a recorded hit fall back to name matching.
+    assert_eq!(subs[1].label, "ui");
+    assert_eq!(subs[1].functions, vec![0x10, 0x20]);
+}
+
+#[test]
+fn resolve_call_edges_names_functions_and_imports() {
+    use ritual_core::services::analysis::{resolve_call_edges, CallEdge, EvidenceKind};
+    let func = |address: u64, name: &str| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let import = |address: u64, description: &str| EvidenceRecord {
+        address,
+        description: description.into(),
+        kind: Some(EvidenceKind::Import),
+        source: None,
+        run_id: None,
+    };
+    let edge = |to: u64| CallEdge {
+        from: 0x10,
+        to,
+        is_cross_slice: false,
+        to_name: None,
+        to_import: false,
+    };
+    let mut result = AnalysisResult {
+        functions: vec![
+            func(0x10, "net_send"),
+            func(0x20, "encode"),
+            func(0x500, "sym.imp.memcpy"),
+        ],
+        call_edges: vec![edge(0x20), edge(0x500), edge(0x510), edge(0x999)],
+        evidence: vec![
+            import(0x500, "import: memcpy (libc.so.6)"),
+            import(0x510, "import env.abort"),
+            EvidenceRecord {
+                address: 0x999,
+                description: "call -> 0x999".into(),
+                kind: None,
+                source: None,
+                run_id: None,
+            },
+        ],
+        basic_blocks: vec![],
+        roots: vec![],
+        root_hits: vec![],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+
+    resolve_call_edges(&mut result);
+    let labels: Vec<String> = result.call_edges.iter().map(CallEdge::target_label).collect();
+    assert_eq!(labels, ["encode", "memcpy@plt", "env.abort@plt", "0x999"]);
+    assert_eq!(result.call_edges[0].to_name.as_deref(), Some("encode"));
+    assert!(!result.call_edges[0].to_import);
+    assert_eq!(result.call_edges[1].to_name.as_deref(), Some("memcpy"));
+    assert!(result.call_edges[1].to_import);
+    assert_eq!(result.call_edges[3].to_name, None);
+
+    // Unresolved fields stay out of report.json.
+    let json = serde_json::to_value(&result.call_edges).unwrap();
+    assert_eq!(
+        json[0],
+        serde_json::json!({"from": 16, "to": 32, "is_cross_slice": false, "to_name": "encode"})
+    );
+    assert_eq!(json[3], serde_json::json!({"from": 16, "to": 2457, "is_cross_slice": false}));
+}
+
+struct BrokenBackend;
+
+impl AnalysisBackend for BrokenBackend {
+    fn analyze(
+        &self,
+        _request: &AnalysisRequest,
+    ) -> Result<AnalysisResult, ritual_core::services::analysis::AnalysisError> {
+        Err(ritual_core::services::analysis::AnalysisError::Backend("tool crashed".into()))
+    }
+
+    fn name(&self) -> &'static str {
+        "broken"
+    }
+}
+
+#[test]
+fn analyze_with_fallback_uses_the_first_backend_that_succeeds() {
+    use ritual_core::services::analysis::{analyze_with_fallback, AnalysisError, BackendFailure};
+
+    let temp = tempfile::tempdir().unwrap();
+    let bin_path = temp.path().join("bin.so");
+    std::fs::write(&bin_path, b"bin").unwrap();
+    let mut registry = BackendRegistry::new();
+    registry.register(BrokenBackend).register(NoopBackend);
+    let prepare = |name: &str| {
+        let request = AnalysisRequest {
+            ritual_name: "TestRitual".into(),
+            binary_name: "Bin".into(),
+            binary_path: bin_path.clone(),
+            roots: vec!["entry_point".into()],
+            options: AnalysisOptions::default(),
+            arch: None,
+            backend_path: None,
+            root_groups: Vec::new(),
+            raw: None,
+            timeout: None,
+            symbols: Default::default(),
+            pdb: None,
+        };
+        let meta = RunMetadata {
+            spec_hash: "hash123".into(),
+            binary_hash: None,
+            backend: name.into(),
+            backend_version: None,
+            backend_path: None,
+            status: ritual_core::db::RitualRunStatus::Succeeded,
+            deterministic: false,
+            epoch: None,
+            binary_id: None,
+            failed_backends: Vec::new(),
+            spec_path: None,
+            started_at: None,
+            usage: None,
+        };
+        (request, meta)
+    };
+    let chain: Vec<String> = ["ghidra", "broken", "noop"].map(String::from).to_vec();
+
+    let (run, meta) = analyze_with_fallback(&registry, &chain, None, prepare).expect("fallback");
+    assert_eq!(run.result.functions.len(), 1);
+    assert_eq!(meta.backend, "noop");
+    let failed: Vec<&str> = meta.failed_backends.iter().map(|f| f.backend.as_str()).collect();
+    assert_eq!(failed, ["ghidra", "broken"]);
+    assert_eq!(
+        meta.failed_backends[1],
+        BackendFailure {
+            backend: "broken".into(),
+            error: "Analysis backend error: tool crashed".into()
+        }
+    );
+
+    // A single backend fails as itself; a chain reports every backend it tried.
+    let err = analyze_with_fallback(&registry, &chain[1..2], None, prepare).unwrap_err();
+    assert!(matches!(err, AnalysisError::Backend(_)), "{err}");
+    let err = analyze_with_fallback(&registry, &chain[..2], None, prepare).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Backend fallback failed (tried ghidra, broken): Analysis backend error: tool crashed"
+    );
+
+    // A missing binary ends the chain: no other backend could read it either.
+    std::fs::remove_file(&bin_path).unwrap();
+    let err = analyze_with_fallback(&registry, &chain[1..], None, prepare).unwrap_err();
+    assert!(matches!(err, AnalysisError::MissingBinary(_)), "{err}");
 }
@@ -0,0 +1,61 @@
+use std::fs;
+
+use ritual_core::services::analysis::AnalysisError;
+use ritual_core::services::image::BinaryImage;
+use ritual_core::services::loader::MappedRegion;
+use tempfile::tempdir;
+
+fn region(file_offset: u64, file_size: u64, virtual_size: u64) -> MappedRegion {
+    MappedRegion {
+        name: ".rodata".into(),
+        address: 0x1000,
+        virtual_size,
+        file_offset,
+        file_size,
+        executable: false,
+    }
+}
+
+#[test]
+fn images_are_mapped_and_read_through_checked_views() {
+    let temp = tempdir().unwrap();
+    let path = temp.path().join("game.bin");
+    let bytes: Vec<u8> = (0..=255).collect();
+    fs::write(&path, &bytes).unwrap();
+
+    let image = BinaryImage::open(&path).unwrap();
+    assert!(image.is_mapped());
+    assert_eq!(image.path(), path);
+    assert_eq!(&image[..], &bytes[..]);
+    assert_eq!(image.view(0x10, 4), Some(&bytes[0x10..0x14]));
+    assert_eq!(image.view(0xfc, 4), Some(&bytes[0xfc..]));
+    assert_eq!(image.view(0xfd, 4), None);
+    assert_eq!(image.view(u64::MAX, 1), None);
+
+    // Sections read their file-backed part, cut short by a truncated file.
+    assert_eq!(image.region(&region(0x20, 0x10, 0x40)), Some(&bytes[0x20..0x30]));
+    assert_eq!(image.region(&region(0x20, 0x40, 0x08)), Some(&bytes[0x20..0x28]));
+    assert_eq!(image.region(&region(0xf0, 0x40, 0x40)), Some(&bytes[0xf0..]));
+    assert_eq!(image.region(&region(0x200, 0x10, 0x10)), None);
+    assert_eq!(image.region(&region(0x20, 0, 0x10)), None);
+}
+
+#[test]
+fn empty_and_missing_files() {
+    let temp = tempdir().unwrap();
+    let empty = temp.path().join("empty.bin");
+    fs::write(&empty, b"").unwrap();
+    let image = BinaryImage::open(&empty).unwrap();
+    assert!(!image.is_mapped());
+    assert!(image.is_empty());
+    assert_eq!(image.view(0, 0), Some(&[][..]));
+
+    let missing = temp.path().join("missing.bin");
+    assert!(
+        matches!(BinaryImage::open(&missing), Err(AnalysisError::MissingBinary(p)) if p == missing)
+    );
+
+    let owned = BinaryImage::from_bytes("fixture", vec![1, 2, 3]);
+    assert!(!owned.is_mapped());
+    assert_eq!(owned.view(1, 2), Some(&[2, 3][..]));
+}
@@ -0,0 +1,69 @@
+use tempfile::tempdir;
+
+use ritual_core::db::{BinaryRecord, ProjectDb};
+use ritual_core::services::analysis::Endian;
+use ritual_core::services::binary_info::{binary_info, BinaryFormat, BinaryInfo};
+
+#[test]
+fn wasm_modules_are_recognized_by_their_magic() {
+    let info = binary_info(b"\0asm\x01\0\0\0").expect("wasm module");
+    assert_eq!(info.format, BinaryFormat::Wasm);
+    assert_eq!(info.bits, Some(32));
+    assert_eq!(info.endian, Some(Endian::Little));
+    assert_eq!(info.arch, None);
+
+    assert_eq!(binary_info(b"not a binary"), None);
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn elf_headers_give_arch_word_size_and_stripping() {
+    use ritual_core::services::fixtures::{synthetic_elf_x86_64, synthetic_elf_x86_64_stripped};
+
+    let info = binary_info(&synthetic_elf_x86_64().bytes).expect("elf");
+    assert_eq!(info.format, BinaryFormat::Elf);
+    assert_eq!(info.arch.as_deref(), Some("x86_64"));
+    assert_eq!(info.bits, Some(64));
+    assert_eq!(info.endian, Some(Endian::Little));
+    assert_eq!(info.stripped, Some(false));
+    assert!(info.libraries.is_empty());
+
+    let stripped = binary_info(&synthetic_elf_x86_64_stripped().bytes).expect("elf");
+    assert_eq!(stripped.stripped, Some(true));
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn universal_mach_o_lists_its_slices() {
+    let info = binary_info(&ritual_core::services::fixtures::synthetic_macho_fat().bytes)
+        .expect("fat mach-o");
+    assert_eq!(info.format, BinaryFormat::FatMachO);
+    assert_eq!(info.arch, None);
+    assert_eq!(info.slices, vec!["x86_64".to_string(), "arm64".to_string()]);
+}
+
+#[test]
+fn binary_info_round_trips_and_goes_with_its_binary() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let id = db.insert_binary(&BinaryRecord::new("libGame.so", "bin/libGame.so")).unwrap();
+    assert_eq!(db.binary_info(id).unwrap(), None);
+
+    let mut info = BinaryInfo {
+        format: BinaryFormat::Elf,
+        arch: Some("arm64".into()),
+        bits: Some(64),
+        endian: Some(Endian::Little),
+        build_id: Some("9f2c".into()),
+        libraries: vec!["libc.so".into(), "libm.so".into()],
+        stripped: Some(true),
+        slices: Vec::new(),
+    };
+    db.set_binary_info(id, &info).unwrap();
+    info.build_id = Some("a0b1".into());
+    db.set_binary_info(id, &info).unwrap();
+    assert_eq!(db.binary_info(id).unwrap(), Some(info));
+
+    db.delete_binary(id).unwrap();
+    assert_eq!(db.binary_info(id).unwrap(), None);
+}
@@ -4,8 +4,10 @@ use object::write::{Object, Symbol, SymbolSection};
 use object::{
     Architecture, BinaryFormat, Endianness, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
 };
-use ritual_core::services::analysis::{AnalysisBackend, AnalysisOptions, AnalysisRequest};
-use ritual_core::services::backends::CapstoneBackend;
+use ritual_core::services::analysis::{
+    AnalysisBackend, AnalysisOptions, AnalysisRequest, AnalysisResult, Endian, RawImage,
+};
+use ritual_core::services::backends::{disassemble_range, CapstoneBackend};
 
 #[test]
 fn capstone_backend_disassembles_and_returns_functions() {
@@ -27,9 +29,15 @@ fn capstone_backend_disassembles_and_returns_functions() {
             include_imports: false,
             include_strings: false,
             max_instructions: Some(32),
+            max_total_instructions: None,
         },
         arch: Some("x86_64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze");
@@ -75,6 +83,11 @@ fn capstone_backend_emits_edges_and_block_kinds() {
         },
         arch: Some("x86_64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze");
@@ -101,6 +114,11 @@ fn capstone_backend_falls_back_to_roots_when_no_symbols_or_edges() {
         },
         arch: Some("x86_64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze empty");
@@ -127,6 +145,11 @@ fn capstone_backend_handles_arm_arch_hint() {
         },
         arch: Some("arm".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze arm");
@@ -153,6 +176,11 @@ fn capstone_backend_handles_riscv32_arch_hint() {
         },
         arch: Some("riscv32".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze riscv32");
@@ -179,6 +207,11 @@ fn capstone_backend_handles_arm64_arch_hint() {
         },
         arch: Some("arm64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze arm64");
@@ -205,6 +238,11 @@ fn capstone_backend_handles_ppc_arch_hint() {
         },
         arch: Some("ppc64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze ppc64");
@@ -231,6 +269,11 @@ fn capstone_backend_marks_indirect_jump_edges() {
         },
         arch: Some("x86_64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze ijump");
@@ -273,6 +316,11 @@ fn capstone_backend_auto_detects_arch_and_sections() {
         },
         arch: None, // force object-based detection
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze auto-detect");
@@ -302,6 +350,11 @@ fn capstone_backend_falls_back_on_unknown_arch_hint() {
         },
         arch: Some("totally-unknown".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze unknown arch");
@@ -331,6 +384,11 @@ fn capstone_backend_handles_empty_bytes_with_detected_arch() {
         },
         arch: None,
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     // Should not error even if nothing is disassembled.
@@ -357,6 +415,11 @@ fn capstone_backend_decodes_arm64_calls() {
         },
         arch: Some("arm64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze arm64 call");
@@ -387,6 +450,11 @@ fn capstone_backend_decodes_riscv_jal_call() {
         },
         arch: Some("riscv32".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze riscv jal");
@@ -432,6 +500,11 @@ fn capstone_backend_auto_detects_macho_arch_none() {
         },
         arch: None, // force Mach-O detection path
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
 
     let result = backend.analyze(&request).expect("analyze macho none");
@@ -440,3 +513,304 @@ fn capstone_backend_auto_detects_macho_arch_none() {
         "expected Mach-O symbol via auto-detect with arch=None"
     );
 }
+
+#[test]
+fn capstone_backend_follows_jumps_over_data_in_raw_blobs() {
+    let temp = tempfile::tempdir().unwrap();
+    let bin_path = temp.path().join("jump_over.bin");
+    // 0: jmp +2 -> 4 (skips two bytes of data)
+    // 2: data 0xFF 0xFF
+    // 4: call 0x10
+    // 9: ret
+    // 10: padding
+    // 0x10: xor eax, eax; ret
+    let mut bytes = vec![0xEB, 0x02, 0xFF, 0xFF, 0xE8, 0x07, 0x00, 0x00, 0x00, 0xC3];
+    bytes.resize(0x10, 0xCC);
+    bytes.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+    std::fs::write(&bin_path, bytes).unwrap();
+
+    let request = AnalysisRequest {
+        ritual_name: "JumpOver".into(),
+        binary_name: "JumpOverBin".into(),
+        binary_path: bin_path,
+        roots: vec!["sub_10".into()],
+        options: AnalysisOptions { max_instructions: Some(64), ..Default::default() },
+        arch: Some("x86_64".into()),
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    };
+    let result = CapstoneBackend.analyze(&request).expect("analyze jump over data");
+
+    assert_eq!(result.functions.len(), 1, "{:?}", result.functions);
+    assert_eq!(result.functions[0].address, 0x10);
+    assert_eq!(result.functions[0].name.as_deref(), Some("sub_10"));
+    assert!(result.functions[0].in_slice);
+    assert_eq!(result.root_hits[0].functions, vec![0x10]);
+    let starts: Vec<u64> = result.basic_blocks.iter().map(|b| b.start).collect();
+    assert_eq!(starts, vec![0x0, 0x4, 0x9, 0x10]);
+    assert!(result.call_edges.iter().any(|e| e.from == 0x4 && e.to == 0x10));
+}
+
+fn raw_request(
+    path: std::path::PathBuf,
+    arch: &str,
+    roots: &[&str],
+    raw: RawImage,
+) -> AnalysisRequest {
+    AnalysisRequest {
+        ritual_name: "Firmware".into(),
+        binary_name: "FirmwareBin".into(),
+        binary_path: path,
+        roots: roots.iter().map(|r| r.to_string()).collect(),
+        options: AnalysisOptions { max_instructions: Some(64), ..Default::default() },
+        arch: Some(arch.into()),
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: Some(raw),
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    }
+}
+
+#[test]
+fn capstone_backend_maps_raw_images_at_their_load_address() {
+    let temp = tempfile::tempdir().unwrap();
+    let bin_path = temp.path().join("firmware.bin");
+    // 0x8000000: call 0x800000A; ret; padding
+    // 0x800000A: xor eax, eax; ret
+    let mut bytes = vec![0xE8, 0x05, 0x00, 0x00, 0x00, 0xC3];
+    bytes.resize(0x0A, 0xCC);
+    bytes.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+    std::fs::write(&bin_path, bytes).unwrap();
+
+    let raw = RawImage { load_address: 0x800_0000, ..Default::default() };
+    let request = raw_request(bin_path.clone(), "x86_64", &["0x8000000"], raw.clone());
+    let result = CapstoneBackend.analyze(&request).expect("analyze raw image");
+
+    let addrs: Vec<u64> = result.functions.iter().map(|f| f.address).collect();
+    assert_eq!(addrs, vec![0x800_0000, 0x800_000A], "{:?}", result.functions);
+    assert_eq!(result.root_hits[0].functions, vec![0x800_0000]);
+    assert!(result.call_edges.iter().any(|e| e.from == 0x800_0000 && e.to == 0x800_000A));
+
+    let listing =
+        disassemble_range(&bin_path, Some("x86_64"), Some(&raw), 0x800_000A, None, 8).unwrap();
+    let mnemonics: Vec<&str> = listing.iter().map(|i| i.mnemonic.as_str()).collect();
+    assert_eq!(mnemonics, vec!["xor", "ret"]);
+    assert_eq!(listing[0].address, 0x800_000A);
+}
+
+#[test]
+fn capstone_backend_records_where_budgets_and_max_depth_cut_the_analysis() {
+    let temp = tempfile::tempdir().unwrap();
+    let bin_path = temp.path().join("chain.bin");
+    // 0x00: call 0x10; ret
+    // 0x10: call 0x20; ret
+    // 0x20: nop x8; ret
+    let mut bytes = vec![0xE8, 0x0B, 0x00, 0x00, 0x00, 0xC3];
+    bytes.resize(0x10, 0xCC);
+    bytes.extend_from_slice(&[0xE8, 0x0B, 0x00, 0x00, 0x00, 0xC3]);
+    bytes.resize(0x20, 0xCC);
+    bytes.extend_from_slice(&[0x90; 8]);
+    bytes.push(0xC3);
+    std::fs::write(&bin_path, bytes).unwrap();
+    let analyze = |options: AnalysisOptions| {
+        let mut request = raw_request(bin_path.clone(), "x86_64", &["0x0"], RawImage::default());
+        request.options = options;
+        CapstoneBackend.analyze(&request).expect("analyze call chain")
+    };
+    let truncated = |result: &AnalysisResult| -> Vec<(u64, String)> {
+        result
+            .evidence
+            .iter()
+            .filter(|e| e.description.starts_with("truncated: "))
+            .map(|e| (e.address, e.description.clone()))
+            .collect()
+    };
+
+    let full = analyze(AnalysisOptions::default());
+    let addrs: Vec<u64> = full.functions.iter().map(|f| f.address).collect();
+    assert_eq!(addrs, vec![0x0, 0x10, 0x20]);
+    assert!(truncated(&full).is_empty(), "{:?}", truncated(&full));
+
+    // The root is depth 0, so depth 1 explores 0x10 but not what it calls; 0x20 stays an
+    // unsized stub for the edge to land on.
+    let shallow = analyze(AnalysisOptions { max_depth: Some(1), ..Default::default() });
+    let addrs: Vec<u64> = shallow.functions.iter().map(|f| f.address).collect();
+    assert_eq!(addrs, vec![0x0, 0x10, 0x20]);
+    assert_eq!(shallow.functions[2].name.as_deref(), Some("sub_20"));
+    assert_eq!(shallow.functions[2].size, None);
+    assert!(shallow.call_edges.iter().any(|e| e.from == 0x10 && e.to == 0x20));
+    assert_eq!(
+        truncated(&shallow),
+        vec![(0x10, "truncated: call to 0x20 not followed past max_depth 1".to_string())]
+    );
+
+    let small = analyze(AnalysisOptions { max_instructions: Some(4), ..Default::default() });
+    let record = &truncated(&small)[0];
+    assert_eq!(record.0, 0x20);
+    assert!(record.1.contains("budget of 4 instructions per function"), "{}", record.1);
+    assert_eq!(small.functions[2].size, Some(4));
+
+    let total = analyze(AnalysisOptions { max_total_instructions: Some(3), ..Default::default() });
+    assert_eq!(
+        truncated(&total),
+        vec![
+            (0x10, "truncated: total budget of 3 instructions ran out in the code at 0x10".into()),
+            (0x20, "truncated: 1 function(s) not explored after the total budget ran out".into()),
+        ]
+    );
+    let evidence = total.evidence.iter().find(|e| e.address == 0x20).unwrap();
+    assert_eq!(evidence.source.as_ref().map(|s| s.to_string()).as_deref(), Some("capstone/limits"));
+}
+
+#[test]
+fn capstone_backend_decodes_thumb_and_big_endian_raw_images() {
+    let temp = tempfile::tempdir().unwrap();
+    let thumb_path = temp.path().join("thumb.bin");
+    // bl 0x1004; bx lr (little-endian Thumb halfwords)
+    std::fs::write(&thumb_path, [0x00, 0xF0, 0x00, 0xF8, 0x70, 0x47]).unwrap();
+    let thumb = RawImage { load_address: 0x1000, thumb: true, ..Default::default() };
+    let listing =
+        disassemble_range(&thumb_path, Some("arm"), Some(&thumb), 0x1000, Some(0x1006), 8).unwrap();
+    let mnemonics: Vec<&str> = listing.iter().map(|i| i.mnemonic.as_str()).collect();
+    assert_eq!(mnemonics, vec!["bl", "bx"]);
+
+    let be_path = temp.path().join("be.bin");
+    // bl 0x2008; bx lr (big-endian ARM words)
+    std::fs::write(&be_path, [0xEB, 0x00, 0x00, 0x00, 0xE1, 0x2F, 0xFF, 0x1E]).unwrap();
+    let big = RawImage { load_address: 0x2000, endian: Endian::Big, ..Default::default() };
+    let listing =
+        disassemble_range(&be_path, Some("arm"), Some(&big), 0x2000, Some(0x2008), 8).unwrap();
+    let mnemonics: Vec<&str> = listing.iter().map(|i| i.mnemonic.as_str()).collect();
+    assert_eq!(mnemonics, vec!["bl", "bx"]);
+
+    // Options the arch cannot honor are errors rather than silently ignored.
+    let err =
+        CapstoneBackend.analyze(&raw_request(be_path.clone(), "x86_64", &[], big)).unwrap_err();
+    assert!(err.to_string().contains("big-endian"), "{err}");
+    let err = CapstoneBackend.analyze(&raw_request(be_path, "x86_64", &[], thumb)).unwrap_err();
+    assert!(err.to_string().contains("thumb"), "{err}");
+}
+
+#[test]
+fn capstone_backend_follows_arm_thumb_interworking() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut obj = Object::new(BinaryFormat::Elf, Architecture::Arm, Endianness::Little);
+    let text_id = obj.add_section(Vec::new(), b".text".to_vec(), SectionKind::Text);
+    obj.section_mut(text_id).append_data(
+        &[
+            0x00, 0x00, 0x00, 0xFA, // 0x00 (ARM): blx 0x8
+            0x1E, 0xFF, 0x2F, 0xE1, // 0x04 (ARM): bx lr
+            0x00, 0xF0, 0x02, 0xF8, // 0x08 (Thumb): bl 0x10
+            0x70, 0x47, 0x00, 0xBF, // 0x0C (Thumb): bx lr; nop
+            0x00, 0x20, 0x70, 0x47, // 0x10 (Thumb): movs r0, #0; bx lr
+        ],
+        4,
+    );
+    let mut symbol = |name: &[u8], value: u64, size: u64, st_info: u8| {
+        obj.add_symbol(Symbol {
+            name: name.to_vec(),
+            value,
+            size,
+            kind: if st_info == 0 { SymbolKind::Label } else { SymbolKind::Text },
+            scope: if st_info == 0 { SymbolScope::Compilation } else { SymbolScope::Linkage },
+            weak: false,
+            section: SymbolSection::Section(text_id),
+            flags: SymbolFlags::Elf { st_info, st_other: 0 },
+        });
+    };
+    symbol(b"arm_main", 0x0, 8, 0x12);
+    // Thumb function symbols carry the Thumb bit; mapping symbols mark each mode's code.
+    symbol(b"thumb_helper", 0x9, 8, 0x12);
+    symbol(b"$a", 0x0, 0, 0);
+    symbol(b"$t", 0x8, 0, 0);
+    let bin_path = temp.path().join("interwork.o");
+    std::fs::write(&bin_path, obj.write().unwrap()).unwrap();
+
+    let request = AnalysisRequest {
+        ritual_name: "Interwork".into(),
+        binary_name: "InterworkBin".into(),
+        binary_path: bin_path.clone(),
+        roots: vec!["arm_main".into(), "0x11".into()],
+        options: AnalysisOptions { max_instructions: Some(32), ..Default::default() },
+        arch: None,
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    };
+    let result = CapstoneBackend.analyze(&request).expect("analyze interworking object");
+
+    let functions: Vec<(u64, Option<&str>, Option<&str>)> =
+        result.functions.iter().map(|f| (f.address, f.name.as_deref(), f.isa.as_deref())).collect();
+    assert_eq!(
+        functions,
+        vec![
+            (0x0, Some("arm_main"), Some("arm")),
+            (0x8, Some("thumb_helper"), Some("thumb")),
+            (0x10, Some("sub_10"), Some("thumb")),
+        ]
+    );
+    // `0x11` names the Thumb function at 0x10.
+    assert!(result.functions[2].in_slice);
+    let edges: Vec<(u64, u64)> = result.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    assert_eq!(edges, vec![(0x0, 0x8), (0x8, 0x10)]);
+
+    // Listings pick the mode from the Thumb bit or the mapping symbols.
+    for start in [0x9, 0x8] {
+        let listing = disassemble_range(&bin_path, None, None, start, Some(0xC), 8).unwrap();
+        let mnemonics: Vec<&str> = listing.iter().map(|i| i.mnemonic.as_str()).collect();
+        assert_eq!(mnemonics, vec!["bl"], "from 0x{start:X}");
+        assert_eq!(listing[0].address, 0x8);
+    }
+}
+
+#[test]
+fn capstone_capabilities_report_in_process_disassembly() {
+    let caps = CapstoneBackend.capabilities(None);
+    assert!(caps.is_available());
+    assert!(caps.archs.iter().any(|a| a == "x86_64"));
+    assert!(caps.archs.iter().any(|a| a == "arm64"));
+    assert!(caps.strings && caps.basic_blocks);
+    assert!(!caps.decompilation);
+    assert_eq!(caps.external_tool, None);
+    assert!(caps.tool_version.is_some());
+}
+
+#[test]
+fn capstone_backend_follows_arm64_pointer_authentication() {
+    let temp = tempfile::tempdir().unwrap();
+    let bin_path = temp.path().join("arm64e.bin");
+    // 0x1000: paciasp; bl 0x1010; blraa x16, x17; retaa
+    // 0x1010: ret
+    let words: [u32; 5] = [0xD503_233F, 0x9400_0003, 0xD73F_0A11, 0xD65F_0BFF, 0xD65F_03C0];
+    let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    std::fs::write(&bin_path, bytes).unwrap();
+
+    let raw = RawImage { load_address: 0x1000, ..Default::default() };
+    let request = raw_request(bin_path.clone(), "arm64", &["0x1000"], raw.clone());
+    let result = CapstoneBackend.analyze(&request).expect("analyze arm64e code");
+
+    assert!(result.call_edges.iter().any(|e| e.from == 0x1004 && e.to == 0x1010));
+    // `retaa` ends the function instead of running on into the next one.
+    let entry = result.functions.iter().find(|f| f.address == 0x1000).unwrap();
+    assert_eq!(entry.size, Some(0x10), "{:?}", result.functions);
+    for tag in ["pac_call 0x1008 blraa", "pac_return 0x100C retaa"] {
+        assert!(
+            result.evidence.iter().any(|e| e.description.starts_with(tag)),
+            "missing {tag}: {:?}",
+            result.evidence
+        );
+    }
+
+    let listing = disassemble_range(&bin_path, Some("arm64"), Some(&raw), 0x1000, None, 8).unwrap();
+    assert_eq!(listing.last().map(|i| i.mnemonic.as_str()), Some("retaa"));
+    assert_eq!(listing.len(), 4);
+}
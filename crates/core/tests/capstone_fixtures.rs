@@ -20,9 +20,15 @@ fn capstone_request(binary_path: PathBuf, roots: Vec<String>) -> AnalysisRequest
             include_imports: false,
             include_strings: false,
             max_instructions: Some(256),
+            max_total_instructions: None,
         },
         arch: Some("x86_64".into()),
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     }
 }
 
@@ -202,6 +208,11 @@ fn capstone_auto_detects_pe_arch_and_symbols() {
         },
         arch: None, // force PE arch detection
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
     let result = backend.analyze(&request).expect("analyze pe auto");
     assert!(
@@ -261,3 +272,58 @@ fn capstone_handles_macho_if_fixture_provided() {
     let result = backend.analyze(&request).expect("analyze macho fixture");
     assert!(!result.functions.is_empty(), "expected Mach-O functions");
 }
+
+#[test]
+fn capstone_splits_fat_macho_by_arch_hint_or_analyzes_every_slice() {
+    let fixture = ritual_core::services::fixtures::synthetic_macho_fat();
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join(fixture.file_name);
+    std::fs::write(&path, &fixture.bytes).unwrap();
+    let analyze = |arch: Option<&str>| {
+        let mut request = capstone_request(path.clone(), vec!["main".into()]);
+        request.arch = arch.map(str::to_string);
+        CapstoneBackend.analyze(&request)
+    };
+    let summary = |result: &ritual_core::services::analysis::AnalysisResult| {
+        let mut functions: Vec<(String, u64, String)> = result
+            .functions
+            .iter()
+            .map(|f| (f.name.clone().unwrap(), f.address, f.arch.clone().unwrap()))
+            .collect();
+        functions.sort();
+        functions
+    };
+
+    // No hint: every slice, each function tagged with its slice.
+    let all = analyze(None).expect("analyze fat");
+    let x86 = &fixture.functions[..2];
+    let arm = &fixture.functions[2..];
+    assert_eq!(
+        summary(&all),
+        vec![
+            ("helper".into(), x86[1].address, "x86_64".into()),
+            ("helper".into(), arm[1].address, "arm64".into()),
+            ("main".into(), x86[0].address, "x86_64".into()),
+            ("main".into(), arm[0].address, "arm64".into()),
+        ]
+    );
+    let mut edges: Vec<(u64, u64)> = all.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    edges.sort();
+    assert_eq!(edges, fixture.calls);
+    // A root matches in every slice.
+    assert_eq!(all.root_hits[0].functions.len(), 2);
+
+    // Hints pick one slice (aliases included).
+    let arm_only = analyze(Some("aarch64")).expect("analyze arm64 slice");
+    assert_eq!(
+        summary(&arm_only),
+        vec![
+            ("helper".into(), arm[1].address, "arm64".into()),
+            ("main".into(), arm[0].address, "arm64".into()),
+        ]
+    );
+    assert_eq!(summary(&analyze(Some("all")).unwrap()), summary(&all));
+
+    let err = analyze(Some("ppc")).unwrap_err().to_string();
+    assert!(err.contains("no 'ppc' slice (available: x86_64, arm64)"), "{err}");
+}
@@ -0,0 +1,266 @@
+use ritual_core::db::{unknown_config_keys, ConfigKeyError, ProjectConfig, CONFIG_KEYS};
+
+fn config() -> ProjectConfig {
+    ProjectConfig::new("Proj", ".ritual/project.db")
+}
+
+#[test]
+fn get_and_set_cover_every_key() {
+    let mut config = config();
+    for (key, writable) in CONFIG_KEYS {
+        assert!(config.get_key(key).is_ok(), "{key}");
+        let value = match *key {
+            "default_backend" | "backend_priority" => "capstone",
+            k if k.starts_with("evidence_weights.") => "2.5",
+            "regressions.unresolved_roots" => "false",
+            k if k.starts_with("regressions.") => "5",
+            "retention.keep_succeeded" | "retention.keep_baselines" => "true",
+            k if k.starts_with("retention.") => "3",
+            _ => "value",
+        };
+        assert_eq!(config.set_key(key, value).is_ok(), *writable, "{key}");
+    }
+    assert_eq!(config.backends.ghidra_headless.as_deref(), Some("value"));
+    assert_eq!(config.backend_versions.capstone.as_deref(), Some("value"));
+    assert_eq!(config.db.path, "value");
+    assert_eq!(config.evidence_weights.other, Some(2.5));
+    assert_eq!(config.regressions.max_call_edge_drop, Some(5.0));
+    assert_eq!(config.regressions.unresolved_roots, Some(false));
+    assert_eq!(config.retention.keep_last, Some(3));
+    assert_eq!(config.retention.keep_baselines, Some(true));
+    assert_eq!(config.entries().len(), CONFIG_KEYS.len());
+}
+
+#[test]
+fn default_backend_accepts_known_backends_only() {
+    let mut config = config();
+    config.set_key("default_backend", "rizin").unwrap();
+    assert_eq!(config.get_key("default_backend").unwrap().as_deref(), Some("rizin"));
+
+    let err = config.set_key("default_backend", "ida").unwrap_err();
+    assert!(matches!(err, ConfigKeyError::InvalidValue { .. }));
+    assert!(err.to_string().contains("unknown backend 'ida'"), "{err}");
+    assert_eq!(config.default_backend.as_deref(), Some("rizin"));
+}
+
+#[test]
+fn unknown_keys_suggest_the_closest_key() {
+    let config = config();
+    let err = config.get_key("default-backend").unwrap_err();
+    assert_eq!(
+        err,
+        ConfigKeyError::UnknownKey {
+            key: "default-backend".into(),
+            suggestion: Some("default_backend".into())
+        }
+    );
+    let message = err.to_string();
+    assert!(message.contains("did you mean 'default_backend'?"), "{message}");
+    assert!(message.contains("backends.rizin"), "{message}");
+
+    match config.get_key("rizin").unwrap_err() {
+        ConfigKeyError::UnknownKey { suggestion, .. } => {
+            assert_eq!(suggestion.as_deref(), Some("backends.rizin"))
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+    match config.get_key("completely_unrelated").unwrap_err() {
+        ConfigKeyError::UnknownKey { suggestion, .. } => assert!(suggestion.is_none()),
+        other => panic!("unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn read_only_required_and_empty_values_are_rejected() {
+    let mut config = config();
+    assert_eq!(
+        config.set_key("config_version", "9").unwrap_err(),
+        ConfigKeyError::ReadOnly("config_version".into())
+    );
+    assert_eq!(config.unset_key("name").unwrap_err(), ConfigKeyError::Required("name".into()));
+    assert!(matches!(
+        config.set_key("backends.rizin", "  ").unwrap_err(),
+        ConfigKeyError::InvalidValue { .. }
+    ));
+
+    config.set_key("description", "notes").unwrap();
+    config.unset_key("description").unwrap();
+    assert_eq!(config.get_key("description").unwrap(), None);
+}
+
+#[test]
+fn evidence_weights_must_be_non_negative_numbers() {
+    let mut config = config();
+    config.set_key("evidence_weights.string", "6").unwrap();
+    assert_eq!(config.get_key("evidence_weights.string").unwrap().as_deref(), Some("6"));
+
+    for bad in ["heavy", "-1", "NaN"] {
+        let err = config.set_key("evidence_weights.string", bad).unwrap_err();
+        assert!(matches!(err, ConfigKeyError::InvalidValue { .. }), "{bad}");
+    }
+    assert_eq!(config.evidence_weights.string, Some(6.0));
+
+    config.unset_key("evidence_weights.string").unwrap();
+    assert!(config.evidence_weights.is_empty());
+}
+
+#[test]
+fn spec_variables_are_dynamic_keys() {
+    let mut config = config();
+    config.set_key("variables.version", "1.2").unwrap();
+    config.set_key("variables.build-id", "abc").unwrap();
+    assert_eq!(config.get_key("variables.version").unwrap().as_deref(), Some("1.2"));
+    assert_eq!(config.get_key("variables.missing").unwrap(), None);
+    let keys: Vec<String> = config.entries().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys.len(), CONFIG_KEYS.len() + 2);
+    assert_eq!(keys[CONFIG_KEYS.len()..], ["variables.build-id", "variables.version"]);
+
+    for bad in ["variables.", "variables.1st", "variables.a b", "variables.binary.arch"] {
+        let err = config.set_key(bad, "x").unwrap_err();
+        assert!(matches!(err, ConfigKeyError::InvalidValue { .. }), "{bad}");
+    }
+    assert!(config
+        .set_key("variables.project.name", "x")
+        .unwrap_err()
+        .to_string()
+        .contains("built-in"));
+
+    config.unset_key("variables.version").unwrap();
+    assert_eq!(config.variables.len(), 1);
+}
+
+#[test]
+fn backend_priority_takes_a_list_of_known_backends() {
+    let mut config = config();
+    assert_eq!(config.get_key("backend_priority").unwrap(), None);
+    config.set_key("backend_priority", "ghidra, rizin,capstone").unwrap();
+    assert_eq!(config.backend_priority, ["ghidra", "rizin", "capstone"]);
+
+    config.set_key("backend_priority", r#"["rizin", "validate-only"]"#).unwrap();
+    assert_eq!(
+        config.get_key("backend_priority").unwrap().as_deref(),
+        Some(r#"["rizin","validate-only"]"#)
+    );
+
+    let err = config.set_key("backend_priority", "rizin,ida").unwrap_err();
+    assert!(err.to_string().contains("unknown backend 'ida'"), "{err}");
+    assert_eq!(config.backend_priority, ["rizin", "validate-only"]);
+    config.unset_key("backend_priority").unwrap();
+    assert!(config.backend_priority.is_empty());
+}
+
+#[test]
+fn post_run_hooks_take_one_command_or_a_json_array() {
+    let mut config = config();
+    assert_eq!(config.get_key("hooks.post_run").unwrap(), None);
+    config.set_key("hooks.post_run", "./scripts/upload.sh {run_dir}").unwrap();
+    assert_eq!(config.hooks.post_run, ["./scripts/upload.sh {run_dir}"]);
+
+    config.set_key("hooks.post_run", r#"["notify {status}", "sync {run_dir}"]"#).unwrap();
+    assert_eq!(config.hooks.post_run, ["notify {status}", "sync {run_dir}"]);
+    assert_eq!(
+        config.get_key("hooks.post_run").unwrap().as_deref(),
+        Some(r#"["notify {status}","sync {run_dir}"]"#)
+    );
+
+    let err = config.set_key("hooks.post_run", "[not json").unwrap_err();
+    assert!(matches!(err, ConfigKeyError::InvalidValue { .. }), "{err}");
+    config.unset_key("hooks.post_run").unwrap();
+    assert!(config.hooks.is_empty());
+}
+
+#[test]
+fn external_tools_are_dynamic_keys() {
+    let mut config = config();
+    config.set_key("tools.ghidra", "ghidraRun {binary_path}").unwrap();
+    assert_eq!(config.get_key("tools.ghidra").unwrap().as_deref(), Some("ghidraRun {binary_path}"));
+    assert_eq!(config.get_key("tools.ida").unwrap(), None);
+    let keys: Vec<String> = config.entries().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys.last().map(String::as_str), Some("tools.ghidra"));
+
+    for bad in ["tools.", "tools.1ida", "tools.a b"] {
+        let err = config.set_key(bad, "x").unwrap_err();
+        assert!(matches!(err, ConfigKeyError::InvalidValue { .. }), "{bad}");
+    }
+    config.unset_key("tools.ghidra").unwrap();
+    assert!(config.tools.is_empty());
+}
+
+#[test]
+fn layout_keys_accept_known_placeholders_only() {
+    let mut config = config();
+    assert_eq!(config.get_key("layout.outputs_dir").unwrap(), None);
+    config.set_key("layout.outputs_dir", "/scratch/{project}/outputs").unwrap();
+    config.set_key("layout.reports_dir", "{root}/../shared-reports").unwrap();
+    assert_eq!(config.layout.outputs_dir.as_deref(), Some("/scratch/{project}/outputs"));
+    assert_eq!(
+        config.get_key("layout.reports_dir").unwrap().as_deref(),
+        Some("{root}/../shared-reports")
+    );
+
+    let err = config.set_key("layout.graphs_dir", "/scratch/{user}/graphs").unwrap_err();
+    assert!(err.to_string().contains("unknown placeholder '{user}'"), "{err}");
+    assert_eq!(config.layout.graphs_dir, None);
+
+    config.unset_key("layout.outputs_dir").unwrap();
+    config.unset_key("layout.reports_dir").unwrap();
+    assert!(config.layout.is_empty());
+}
+
+#[test]
+fn regression_limits_take_percentages_and_switches() {
+    let mut config = config();
+    assert!(config.regressions.is_empty());
+    config.set_key("regressions.max_in_slice_drop", "25").unwrap();
+    config.set_key("regressions.unresolved_roots", "false").unwrap();
+    assert_eq!(config.get_key("regressions.max_in_slice_drop").unwrap().as_deref(), Some("25"));
+    assert_eq!(config.get_key("regressions.unresolved_roots").unwrap().as_deref(), Some("false"));
+
+    let err = config.set_key("regressions.max_function_drop", "-5").unwrap_err();
+    assert!(err.to_string().contains("expected a non-negative number"), "{err}");
+    let err = config.set_key("regressions.unresolved_roots", "maybe").unwrap_err();
+    assert!(err.to_string().contains("expected true or false, got 'maybe'"), "{err}");
+
+    config.unset_key("regressions.max_in_slice_drop").unwrap();
+    config.unset_key("regressions.unresolved_roots").unwrap();
+    assert!(config.regressions.is_empty());
+}
+
+#[test]
+fn retention_policy_takes_counts_and_switches() {
+    let mut config = config();
+    assert!(config.retention.is_empty());
+    config.set_key("retention.keep_last", "2").unwrap();
+    config.set_key("retention.failed_max_age_days", "30").unwrap();
+    config.set_key("retention.keep_succeeded", "false").unwrap();
+    assert_eq!(config.get_key("retention.keep_last").unwrap().as_deref(), Some("2"));
+    assert_eq!(config.get_key("retention.failed_max_age_days").unwrap().as_deref(), Some("30"));
+
+    let err = config.set_key("retention.keep_last", "1.5").unwrap_err();
+    assert!(err.to_string().contains("expected a non-negative integer, got '1.5'"), "{err}");
+    let err = config.set_key("retention.failed_max_age_days", "-1").unwrap_err();
+    assert!(err.to_string().contains("expected a non-negative integer"), "{err}");
+
+    for key in ["retention.keep_last", "retention.failed_max_age_days", "retention.keep_succeeded"]
+    {
+        config.unset_key(key).unwrap();
+    }
+    assert!(config.retention.is_empty());
+}
+
+#[test]
+fn unknown_config_keys_lists_typos_in_a_raw_config() {
+    let mut raw = serde_json::to_value(config()).unwrap();
+    raw["defualt_backend"] = "capstone".into();
+    raw["backends"]["rizn"] = "/opt/rizin".into();
+    raw["variables"]["anything"] = "goes".into();
+    raw["tools"] = serde_json::json!({ "objdump": "/usr/bin/objdump" });
+    assert_eq!(
+        unknown_config_keys(&raw),
+        [
+            ("backends.rizn".to_string(), Some("backends.rizin".to_string())),
+            ("defualt_backend".to_string(), Some("default_backend".to_string())),
+        ]
+    );
+    assert!(unknown_config_keys(&serde_json::to_value(config()).unwrap()).is_empty());
+}
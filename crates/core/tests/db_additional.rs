@@ -73,6 +73,12 @@ fn ritual_run_record_round_trip() {
         status: ritual_core::db::RitualRunStatus::Stubbed,
         started_at: "now".into(),
         finished_at: "now".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let json = serde_json::to_string(&record).expect("serialize run");
     let de: RitualRunRecord = serde_json::from_str(&json).expect("deserialize run");
@@ -2,6 +2,7 @@ use ritual_core::db::ProjectDb;
 use ritual_core::services::analysis::{
     AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, FunctionRecord,
 };
+use ritual_core::services::signature::{CallingConvention, FunctionSignature};
 
 #[test]
 fn analysis_result_is_persisted_with_run() {
@@ -20,26 +21,73 @@ fn analysis_result_is_persisted_with_run() {
         status: ritual_core::db::RitualRunStatus::Succeeded,
         started_at: "now".into(),
         finished_at: "now".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let run_id = db.insert_ritual_run(&run_record).unwrap();
 
     let result = AnalysisResult {
-        functions: vec![FunctionRecord {
-            address: 0x1000,
-            name: Some("func".into()),
-            size: Some(8),
-            in_slice: true,
-            is_boundary: false,
-        }],
-        call_edges: vec![CallEdge { from: 0x1000, to: 0x2000, is_cross_slice: false }],
+        functions: vec![
+            FunctionRecord {
+                address: 0x1000,
+                name: Some("Example::init()".into()),
+                size: Some(8),
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: Some("_ZN7Example4initEv".into()),
+                signature: Some(FunctionSignature {
+                    params: 2,
+                    convention: CallingConvention::Stdcall,
+                    stack_cleanup: Some(8),
+                }),
+                arch: Some("x86".into()),
+                fuzzy_hash: Some("0123456789abcdef".into()),
+                isa: None,
+            },
+            FunctionRecord {
+                address: 0x2000,
+                name: Some("callee".into()),
+                size: None,
+                in_slice: false,
+                is_boundary: true,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: Some("thumb".into()),
+            },
+        ],
+        call_edges: vec![
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: Some("callee".into()),
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1004,
+                to: 0x3000,
+                is_cross_slice: false,
+                to_name: Some("memcpy".into()),
+                to_import: true,
+            },
+        ],
         evidence: vec![ritual_core::services::analysis::EvidenceRecord {
             address: 0x1000,
             description: "string: test".into(),
             kind: None,
+            source: None,
+            run_id: None,
         }],
         basic_blocks: vec![BasicBlock {
             start: 0x1000,
-            len: 4,
+            byte_len: 4,
+            insn_count: Some(2),
             successors: vec![BlockEdge { target: 0x1004, kind: BlockEdgeKind::Fallthrough }],
         }],
         roots: vec!["root_a".into(), "root_b".into()],
@@ -47,25 +95,52 @@ fn analysis_result_is_persisted_with_run() {
             ritual_core::services::analysis::RootHit {
                 root: "root_a".into(),
                 functions: vec![0x1000],
+                error: None,
             },
             ritual_core::services::analysis::RootHit {
                 root: "root_b".into(),
                 functions: Vec::new(),
+                error: Some("no function matched".into()),
             },
         ],
         backend_version: Some("1.0".into()),
         backend_path: Some("/usr/bin/rizin".into()),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: Some(0x100),
     };
 
     db.insert_analysis_result(run_id, &result).unwrap();
 
     // Load back via helper.
     let loaded = db.load_analysis_result("Bin", "Run").unwrap().expect("analysis result");
-    assert_eq!(loaded.functions.len(), 1);
-    assert_eq!(loaded.call_edges.len(), 1);
-    assert_eq!(loaded.basic_blocks.len(), 1);
+    assert_eq!(loaded.functions.len(), 2);
+    let callee = loaded.functions.iter().find(|f| f.address == 0x2000).unwrap();
+    assert!(!callee.in_slice && callee.is_boundary, "slice flags persist");
+    assert_eq!(callee.mangled_name, None);
+    let init = loaded.functions.iter().find(|f| f.address == 0x1000).unwrap();
+    assert_eq!(init.mangled_name.as_deref(), Some("_ZN7Example4initEv"), "mangled name persists");
+    assert_eq!(init.signature, result.functions[0].signature, "signature persists");
+    assert_eq!(callee.signature, None);
+    assert_eq!(init.fuzzy_hash.as_deref(), Some("0123456789abcdef"), "fingerprint persists");
+    assert_eq!(callee.fuzzy_hash, None);
+    assert_eq!((init.isa.as_deref(), callee.isa.as_deref()), (None, Some("thumb")), "isa persists");
+    let mut edges = loaded.call_edges.clone();
+    edges.sort_by_key(|e| e.from);
+    assert_eq!(edges, result.call_edges, "resolved call targets persist");
+    assert_eq!(loaded.basic_blocks, result.basic_blocks, "block bytes and insn count persist");
     assert_eq!(loaded.evidence.len(), 1);
     assert_eq!(loaded.roots, vec!["root_a".to_string(), "root_b".to_string()]);
+    assert_eq!(loaded.root_hits[0].error, None);
+    assert_eq!(loaded.root_hits[1].error.as_deref(), Some("no function matched"));
+    assert_eq!(loaded.text_bytes, Some(0x100));
+    let coverage = db.load_run_coverage(run_id).unwrap().expect("coverage recorded");
+    assert_eq!(
+        (coverage.text_bytes, coverage.slice_bytes, coverage.slice_functions),
+        (Some(0x100), 8, 1)
+    );
+    assert_eq!(coverage.slice_insns, Some(2));
+    assert_eq!(db.load_run_coverage(run_id + 1).unwrap(), None);
 
     // Spot-check that data was written.
     let func_count: i64 = db
@@ -74,7 +149,7 @@ fn analysis_result_is_persisted_with_run() {
             row.get(0)
         })
         .unwrap();
-    assert_eq!(func_count, 1);
+    assert_eq!(func_count, 2);
 
     let edge_count: i64 = db
         .connection()
@@ -82,7 +157,7 @@ fn analysis_result_is_persisted_with_run() {
             row.get(0)
         })
         .unwrap();
-    assert_eq!(edge_count, 1);
+    assert_eq!(edge_count, 2);
 
     let bb_count: i64 = db
         .connection()
@@ -107,6 +182,19 @@ fn analysis_result_is_persisted_with_run() {
         })
         .unwrap();
     assert_eq!(roots_count, 2);
+
+    let counts = db.run_counts(run_id).unwrap();
+    assert_eq!(
+        counts,
+        ritual_core::db::RunCounts {
+            functions: 2,
+            in_slice_functions: 1,
+            call_edges: 2,
+            evidence: 1,
+            xrefs: 0
+        }
+    );
+    assert_eq!(db.run_counts(run_id + 1).unwrap(), ritual_core::db::RunCounts::default());
 }
 
 #[test]
@@ -126,6 +214,12 @@ fn insert_analysis_result_overwrites_existing_rows() {
         status: ritual_core::db::RitualRunStatus::Succeeded,
         started_at: "now".into(),
         finished_at: "now".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
     let run_id = db.insert_ritual_run(&run_record).unwrap();
 
@@ -136,25 +230,43 @@ fn insert_analysis_result_overwrites_existing_rows() {
             size: None,
             in_slice: true,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x1,
+            to: 0x2,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x1, to: 0x2, is_cross_slice: false }],
         evidence: vec![ritual_core::services::analysis::EvidenceRecord {
             address: 0x1,
             description: "first".into(),
             kind: None,
+            source: None,
+            run_id: None,
         }],
         basic_blocks: vec![BasicBlock {
             start: 0x1,
-            len: 4,
+            byte_len: 4,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x2, kind: BlockEdgeKind::Jump }],
         }],
         roots: vec!["root1".into()],
         root_hits: vec![ritual_core::services::analysis::RootHit {
             root: "root1".into(),
             functions: vec![0x1],
+            error: None,
         }],
         backend_version: None,
         backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(run_id, &first).unwrap();
 
@@ -165,16 +277,30 @@ fn insert_analysis_result_overwrites_existing_rows() {
             size: None,
             in_slice: true,
             is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x10,
+            to: 0x20,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
         }],
-        call_edges: vec![CallEdge { from: 0x10, to: 0x20, is_cross_slice: false }],
         evidence: vec![ritual_core::services::analysis::EvidenceRecord {
             address: 0x10,
             description: "second".into(),
             kind: None,
+            source: None,
+            run_id: None,
         }],
         basic_blocks: vec![BasicBlock {
             start: 0x10,
-            len: 8,
+            byte_len: 8,
+            insn_count: None,
             successors: vec![BlockEdge { target: 0x20, kind: BlockEdgeKind::Fallthrough }],
         }],
         roots: vec!["root2".into(), "root3".into()],
@@ -182,14 +308,19 @@ fn insert_analysis_result_overwrites_existing_rows() {
             ritual_core::services::analysis::RootHit {
                 root: "root2".into(),
                 functions: vec![0x10],
+                error: None,
             },
             ritual_core::services::analysis::RootHit {
                 root: "root3".into(),
                 functions: Vec::new(),
+                error: None,
             },
         ],
         backend_version: None,
         backend_path: None,
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        text_bytes: None,
     };
     db.insert_analysis_result(run_id, &second).unwrap();
 
@@ -201,3 +332,388 @@ fn insert_analysis_result_overwrites_existing_rows() {
     assert_eq!(loaded.basic_blocks.len(), 1);
     assert_eq!(loaded.roots, vec!["root2".to_string(), "root3".to_string()]);
 }
+
+#[test]
+fn sub_slices_round_trip_through_db() {
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let run_id = db
+        .insert_ritual_run(&ritual_core::db::RitualRunRecord {
+            binary: "Bin".into(),
+            binary_id: None,
+            ritual: "Grouped".into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "validate-only".into(),
+            backend_version: None,
+            backend_path: None,
+            status: ritual_core::db::RitualRunStatus::Stubbed,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+
+    let sub_slices = vec![
+        ritual_core::services::analysis::SubSlice {
+            label: "net".into(),
+            roots: vec!["send_packet".into(), "recv_packet".into()],
+            functions: vec![0x30, 0x40],
+        },
+        ritual_core::services::analysis::SubSlice {
+            label: "ui".into(),
+            roots: vec!["CUIManager::init".into()],
+            functions: Vec::new(),
+        },
+    ];
+    let result = AnalysisResult {
+        functions: vec![],
+        call_edges: vec![],
+        evidence: vec![],
+        basic_blocks: vec![],
+        roots: vec!["send_packet".into(), "recv_packet".into(), "CUIManager::init".into()],
+        root_hits: vec![],
+        sub_slices: sub_slices.clone(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+    // Re-inserting must not duplicate sub-slice rows.
+    db.insert_analysis_result(run_id, &result).unwrap();
+
+    let loaded = db.load_analysis_result("Bin", "Grouped").unwrap().expect("analysis result");
+    assert_eq!(loaded.sub_slices, sub_slices);
+}
+
+#[test]
+fn xrefs_round_trip_and_are_replaced_on_reinsert() {
+    use ritual_core::services::analysis::{XrefKind, XrefRecord};
+
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let run_id = db
+        .insert_ritual_run(&ritual_core::db::RitualRunRecord {
+            binary: "Bin".into(),
+            binary_id: None,
+            ritual: "Xrefs".into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: ritual_core::db::RitualRunStatus::Succeeded,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+
+    let xrefs = vec![
+        XrefRecord {
+            from: 0x20,
+            to: 0x4000,
+            kind: XrefKind::Memory,
+            section: Some(".data".into()),
+            preview: None,
+        },
+        XrefRecord {
+            from: 0x10,
+            to: 0x3000,
+            kind: XrefKind::Immediate,
+            section: Some(".rodata".into()),
+            preview: Some("hello".into()),
+        },
+    ];
+    let mut result = AnalysisResult {
+        functions: vec![],
+        call_edges: vec![],
+        evidence: vec![],
+        basic_blocks: vec![],
+        roots: vec![],
+        root_hits: vec![],
+        sub_slices: vec![],
+        backend_version: None,
+        backend_path: None,
+        xrefs: xrefs.clone(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+
+    // Ordered by source address, with kind/section/preview preserved.
+    let listed = db.list_xrefs(run_id).unwrap();
+    assert_eq!(listed, vec![xrefs[1].clone(), xrefs[0].clone()]);
+    let loaded = db.load_analysis_result("Bin", "Xrefs").unwrap().expect("analysis result");
+    assert_eq!(loaded.xrefs, listed);
+
+    // Re-inserting the analysis replaces stale xrefs.
+    result.xrefs.truncate(1);
+    db.insert_analysis_result(run_id, &result).unwrap();
+    assert_eq!(db.list_xrefs(run_id).unwrap(), vec![xrefs[0].clone()]);
+
+    // insert_xrefs appends standalone rows (e.g. from a secondary pass).
+    db.insert_xrefs(
+        run_id,
+        &[XrefRecord { from: 0x30, to: 0x10, kind: XrefKind::Call, section: None, preview: None }],
+    )
+    .unwrap();
+    let listed = db.list_xrefs(run_id).unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[1].kind, XrefKind::Call);
+    assert_eq!(listed[1].section, None);
+}
+
+#[test]
+fn evidence_and_xrefs_page_through_large_runs() {
+    use ritual_core::services::analysis::{EvidenceRecord, EvidenceSource, XrefKind, XrefRecord};
+
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let run_id = db
+        .insert_ritual_run(&ritual_core::db::RitualRunRecord {
+            binary: "Bin".into(),
+            binary_id: None,
+            ritual: "Paged".into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: ritual_core::db::RitualRunStatus::Succeeded,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+
+    // Descending addresses so insertion order and address order differ.
+    let evidence: Vec<EvidenceRecord> = (0..25u64)
+        .map(|i| EvidenceRecord {
+            address: 0x100 - i * 4,
+            description: format!("ev{}", i),
+            kind: None,
+            // Rows recorded before provenance was tracked have no source.
+            source: (i % 2 == 0).then(|| EvidenceSource::new("capstone", "disasm")),
+            run_id: None,
+        })
+        .collect();
+    let xrefs: Vec<XrefRecord> = (0..7u64)
+        .map(|i| XrefRecord {
+            from: 0x50 - i,
+            to: 0x4000,
+            kind: XrefKind::Immediate,
+            section: None,
+            preview: None,
+        })
+        .collect();
+    let result = AnalysisResult {
+        functions: vec![],
+        call_edges: vec![],
+        evidence: evidence.clone(),
+        basic_blocks: vec![],
+        roots: vec![],
+        root_hits: vec![],
+        sub_slices: vec![],
+        backend_version: None,
+        backend_path: None,
+        xrefs,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+
+    // Full scans keep insertion order and never exceed the page size.
+    let pages: Vec<Vec<EvidenceRecord>> =
+        db.evidence_pages(run_id, 10).collect::<Result<_, _>>().unwrap();
+    assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![10, 10, 5]);
+    // Rows read back carry the run they belong to.
+    let stored: Vec<EvidenceRecord> =
+        evidence.iter().map(|e| EvidenceRecord { run_id: Some(run_id), ..e.clone() }).collect();
+    assert_eq!(pages.concat(), stored);
+
+    // Range scans are half-open and ordered by address.
+    let ranged: Vec<u64> = db
+        .evidence_pages_in_range(run_id, 0xC0, 0xE0, 3)
+        .flat_map(|p| p.unwrap())
+        .map(|e| e.address)
+        .collect();
+    let expected: Vec<u64> = (0xC0..0xE0).step_by(4).collect();
+    assert_eq!(ranged, expected);
+
+    let paged_xrefs: Vec<XrefRecord> = db.xref_pages(run_id, 2).flat_map(|p| p.unwrap()).collect();
+    assert_eq!(paged_xrefs, db.list_xrefs(run_id).unwrap());
+
+    // The skeleton skips the bulk tables; the full loader still returns everything.
+    let (skeleton_run, skeleton) = db.load_analysis_skeleton("Bin", "Paged").unwrap().unwrap();
+    assert_eq!(skeleton_run, run_id);
+    assert!(skeleton.evidence.is_empty() && skeleton.xrefs.is_empty());
+    let full = db.load_analysis_result("Bin", "Paged").unwrap().unwrap();
+    assert_eq!(full.evidence, stored);
+    assert_eq!(full.xrefs.len(), 7);
+}
+
+#[test]
+fn legacy_block_lengths_are_split_into_bytes_and_instructions() {
+    let temp = tempfile::tempdir().unwrap();
+    let db_path = temp.path().join("proj.db");
+    {
+        let db = ProjectDb::open(&db_path).unwrap();
+        for (binary, backend) in [("A", "capstone"), ("B", "rizin")] {
+            db.insert_ritual_run(&ritual_core::db::RitualRunRecord {
+                binary: binary.into(),
+                ritual: "Run".into(),
+                spec_hash: "spec".into(),
+                binary_hash: None,
+                backend: backend.into(),
+                backend_version: None,
+                backend_path: None,
+                status: ritual_core::db::RitualRunStatus::Succeeded,
+                started_at: "now".into(),
+                finished_at: "now".into(),
+                binary_id: None,
+                superseded_by: None,
+                deterministic: false,
+                spec_path: None,
+                analysis_options: None,
+                usage: None,
+            })
+            .unwrap();
+        }
+        // Recreate the v27 table: one `len` column, instructions for Capstone, bytes otherwise.
+        db.connection()
+            .execute_batch(
+                r#"
+                DROP TABLE analysis_basic_blocks;
+                CREATE TABLE analysis_basic_blocks (
+                    run_id INTEGER NOT NULL,
+                    start  INTEGER NOT NULL,
+                    len    INTEGER NOT NULL,
+                    PRIMARY KEY(run_id, start)
+                );
+                INSERT INTO analysis_basic_blocks (run_id, start, len) VALUES (1, 4096, 5), (2, 4096, 24);
+                PRAGMA user_version = 27;
+                "#,
+            )
+            .unwrap();
+    }
+
+    let db = ProjectDb::open(&db_path).unwrap();
+    let capstone = db.load_analysis_result("A", "Run").unwrap().unwrap();
+    assert_eq!(
+        (capstone.basic_blocks[0].byte_len, capstone.basic_blocks[0].insn_count),
+        (0, Some(5))
+    );
+    let rizin = db.load_analysis_result("B", "Run").unwrap().unwrap();
+    assert_eq!((rizin.basic_blocks[0].byte_len, rizin.basic_blocks[0].insn_count), (24, None));
+}
+
+#[test]
+fn list_functions_filters_and_pages_with_annotation_names() {
+    use ritual_core::db::{FunctionAnnotation, FunctionFilter};
+
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let run_id = db
+        .insert_ritual_run(&ritual_core::db::RitualRunRecord {
+            binary: "Bin".into(),
+            binary_id: None,
+            ritual: "List".into(),
+            spec_hash: "spec".into(),
+            binary_hash: Some("binhash".into()),
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: ritual_core::db::RitualRunStatus::Succeeded,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let function =
+        |address: u64, name: Option<&str>, size: Option<u32>, in_slice: bool| FunctionRecord {
+            address,
+            name: name.map(Into::into),
+            size,
+            in_slice,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        };
+    let result = AnalysisResult {
+        functions: vec![
+            function(0x3000, Some("net_send"), Some(64), true),
+            function(0x1000, Some("Net_Init"), Some(16), true),
+            function(0x2000, Some("sub_2000"), Some(200), false),
+            function(0x4000, None, None, true),
+        ],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+    for (address, name) in [(0x2000, "net_recv"), (0x1000, "init")] {
+        db.upsert_function_annotation(&FunctionAnnotation {
+            binary_hash: "binhash".into(),
+            address,
+            name: Some(name.into()),
+            note: None,
+            updated_at: "t0".into(),
+        })
+        .unwrap();
+    }
+    let addresses = |filter: FunctionFilter| {
+        let page = db.list_functions(run_id, &filter).unwrap();
+        (page.total, page.functions.iter().map(|f| f.address).collect::<Vec<_>>())
+    };
+
+    let all = db.list_functions(run_id, &FunctionFilter::default()).unwrap();
+    assert_eq!(all.total, 4);
+    assert_eq!(all.functions[0].name.as_deref(), Some("Net_Init"));
+    assert_eq!(all.functions[0].annotation.as_deref(), Some("init"));
+    assert_eq!(all.functions[3].size, None);
+    assert_eq!(
+        addresses(FunctionFilter { in_slice: true, ..Default::default() }),
+        (3, vec![0x1000, 0x3000, 0x4000])
+    );
+    // Unsized functions never pass a size filter.
+    assert_eq!(
+        addresses(FunctionFilter { min_size: Some(64), ..Default::default() }),
+        (2, vec![0x2000, 0x3000])
+    );
+    // Names match case-insensitively, and annotation names count.
+    assert_eq!(
+        addresses(FunctionFilter { name_contains: Some("NET_".into()), ..Default::default() }),
+        (3, vec![0x1000, 0x2000, 0x3000])
+    );
+    assert_eq!(
+        addresses(FunctionFilter { limit: Some(2), offset: 1, ..Default::default() }),
+        (4, vec![0x2000, 0x3000])
+    );
+    assert_eq!(addresses(FunctionFilter { offset: 10, ..Default::default() }), (4, vec![]));
+}
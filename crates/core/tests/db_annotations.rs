@@ -0,0 +1,148 @@
+use tempfile::tempdir;
+
+use std::collections::BTreeMap;
+
+use ritual_core::db::{FunctionAnnotation, ImportedSymbol, ProjectDb};
+use ritual_core::services::analysis::{
+    apply_annotations, apply_imported_symbols, AnalysisResult, FunctionRecord,
+};
+
+fn annotation(address: u64, name: Option<&str>, note: Option<&str>) -> FunctionAnnotation {
+    FunctionAnnotation {
+        binary_hash: "hash-a".into(),
+        address,
+        name: name.map(Into::into),
+        note: note.map(Into::into),
+        updated_at: "t0".into(),
+    }
+}
+
+#[test]
+fn annotations_upsert_merge_and_delete() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+
+    db.upsert_function_annotation(&annotation(0x2000, Some("b"), None)).unwrap();
+    db.upsert_function_annotation(&annotation(0x1000, Some("parse"), None)).unwrap();
+    // Setting only a note keeps the stored name.
+    db.upsert_function_annotation(&annotation(0x1000, None, Some("framing"))).unwrap();
+
+    let listed = db.list_function_annotations("hash-a").unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].address, 0x1000);
+    assert_eq!(listed[0].name.as_deref(), Some("parse"));
+    assert_eq!(listed[0].note.as_deref(), Some("framing"));
+    assert!(db.list_function_annotations("hash-b").unwrap().is_empty());
+
+    assert_eq!(db.delete_function_annotation("hash-a", 0x2000).unwrap(), 1);
+    assert_eq!(db.delete_function_annotation("hash-a", 0x2000).unwrap(), 0);
+    assert_eq!(db.list_function_annotations("hash-a").unwrap().len(), 1);
+}
+
+#[test]
+fn apply_annotations_prefers_human_names() {
+    let func = |address, name: Option<&str>| FunctionRecord {
+        address,
+        name: name.map(Into::into),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let mut result = AnalysisResult {
+        functions: vec![
+            func(0x1000, Some("sub_1000")),
+            func(0x2000, None),
+            func(0x3000, Some("keep")),
+        ],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    apply_annotations(
+        &mut result,
+        &[
+            annotation(0x1000, Some("parse"), None),
+            annotation(0x2000, Some("helper"), None),
+            annotation(0x3000, None, Some("note only")),
+        ],
+    );
+    let names: Vec<_> = result.functions.iter().map(|f| f.name.as_deref()).collect();
+    assert_eq!(names, vec![Some("parse"), Some("helper"), Some("keep")]);
+}
+
+#[test]
+fn imported_symbols_upsert_list_and_delete() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let symbol = |address, name: &str, source: &str| ImportedSymbol {
+        binary_hash: "hash-a".into(),
+        address,
+        name: name.into(),
+        source: source.into(),
+        imported_at: "t0".into(),
+    };
+
+    db.upsert_imported_symbol(&symbol(0x2000, "net_send", "a.map (map)")).unwrap();
+    db.upsert_imported_symbol(&symbol(0x1000, "net_init", "a.map (map)")).unwrap();
+    // A later import of the same address wins.
+    db.upsert_imported_symbol(&symbol(0x2000, "net_send_all", "exports.json (json)")).unwrap();
+
+    let listed = db.list_imported_symbols("hash-a").unwrap();
+    let names: Vec<_> = listed.iter().map(|s| (s.address, s.name.as_str())).collect();
+    assert_eq!(names, vec![(0x1000, "net_init"), (0x2000, "net_send_all")]);
+    assert_eq!(listed[1].source, "exports.json (json)");
+    assert!(db.list_imported_symbols("hash-b").unwrap().is_empty());
+
+    assert_eq!(db.delete_imported_symbols("hash-a").unwrap(), 2);
+    assert!(db.list_imported_symbols("hash-a").unwrap().is_empty());
+}
+
+#[test]
+fn apply_imported_symbols_replaces_backend_names() {
+    let func = |address, name: &str, mangled: Option<&str>| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: mangled.map(Into::into),
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let mut result = AnalysisResult {
+        functions: vec![
+            func(0x1000, "sub_1000", None),
+            func(0x2000, "Example::init()", Some("_ZN7Example4initEv")),
+            func(0x3000, "keep", None),
+        ],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    let symbols = BTreeMap::from([(0x1000, "net_init".to_string()), (0x2000, "boot".to_string())]);
+    apply_imported_symbols(&mut result, &symbols);
+    let names: Vec<_> =
+        result.functions.iter().map(|f| (f.name.as_deref(), f.mangled_name.as_deref())).collect();
+    assert_eq!(names, vec![(Some("net_init"), None), (Some("boot"), None), (Some("keep"), None)]);
+}
@@ -0,0 +1,40 @@
+use tempfile::tempdir;
+
+use ritual_core::db::{BinaryRecord, DbError, ProjectDb};
+
+#[test]
+fn binary_groups_collect_versioned_builds() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+
+    let v1 = db.insert_binary(&BinaryRecord::new("game-1.2", "bins/1.2/libgame.so")).unwrap();
+    let v2 = db.insert_binary(&BinaryRecord::new("game-1.3", "bins/1.3/libgame.so")).unwrap();
+    let loose = db.insert_binary(&BinaryRecord::new("tool", "bins/tool")).unwrap();
+
+    let group = db.ensure_binary_group("libgame.so", "t0").unwrap();
+    assert_eq!(db.ensure_binary_group("libgame.so", "t1").unwrap(), group, "groups are reused");
+    assert_eq!(db.assign_binary_group(v2, group, Some("v1.3"), None).unwrap(), 1);
+    assert_eq!(db.assign_binary_group(v1, group, Some("v1.2"), Some("deadbeef")).unwrap(), 1);
+    assert_eq!(db.assign_binary_group(999, group, Some("v2"), None).unwrap(), 0);
+
+    // Build labels are unique within a group.
+    assert!(matches!(
+        db.assign_binary_group(loose, group, Some("v1.2"), None),
+        Err(DbError::Sql(_))
+    ));
+    let other = db.ensure_binary_group("tools", "t2").unwrap();
+    db.assign_binary_group(loose, other, Some("v1.2"), None).unwrap();
+
+    let groups = db.list_binary_groups().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].name, "libgame.so");
+    assert_eq!(groups[0].created_at, "t0");
+    let versions: Vec<_> = groups[0].members.iter().map(|m| m.version.as_deref()).collect();
+    assert_eq!(versions, vec![Some("v1.2"), Some("v1.3")]);
+    assert_eq!(groups[0].members[0].build_id.as_deref(), Some("deadbeef"));
+    assert_eq!(groups[0].members[0].binary.name, "game-1.2");
+    assert_eq!(groups[1].members.len(), 1);
+
+    // Plain binary listings are unaffected by grouping.
+    assert_eq!(db.list_binaries().unwrap().len(), 3);
+}
@@ -60,3 +60,25 @@ fn project_db_initializes_and_handles_binaries_and_slices() {
         assert!(runs.is_empty());
     }
 }
+
+#[test]
+fn slices_archive_and_restore_without_losing_rows() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    db.insert_slice(&SliceRecord::new("Legacy", SliceStatus::Active)).expect("insert slice");
+
+    assert_eq!(db.archive_slice("Legacy", "2024-01-01T00:00:00Z").expect("archive"), 1);
+    // Archiving twice or an unknown slice is a no-op.
+    assert_eq!(db.archive_slice("Legacy", "2024-02-01T00:00:00Z").expect("archive again"), 0);
+    assert_eq!(db.archive_slice("Missing", "2024-02-01T00:00:00Z").expect("archive missing"), 0);
+
+    let slices = db.list_slices().expect("list slices");
+    assert_eq!(slices.len(), 1);
+    assert!(slices[0].is_archived());
+    assert_eq!(slices[0].archived_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+    assert_eq!(slices[0].status, SliceStatus::Active);
+
+    assert_eq!(db.restore_slice("Legacy").expect("restore"), 1);
+    assert_eq!(db.restore_slice("Legacy").expect("restore again"), 0);
+    assert!(!db.list_slices().expect("list slices")[0].is_archived());
+}
@@ -1,4 +1,4 @@
-use ritual_core::db::ProjectLayout;
+use ritual_core::db::{ProjectConfig, ProjectLayout};
 
 #[test]
 fn db_path_relative_string_prefers_relative() {
@@ -15,3 +15,23 @@ fn binary_output_root_appends_name() {
     let out = layout.binary_output_root("GameBin");
     assert!(out.ends_with("outputs/binaries/GameBin"));
 }
+
+#[test]
+fn with_config_moves_artifact_directories() {
+    let root = tempfile::tempdir().unwrap();
+    let scratch = tempfile::tempdir().unwrap();
+    let mut config = ProjectConfig::new("Game", ".ritual/project.db");
+    config.layout.outputs_dir = Some(format!("{}/{{project}}/outputs", scratch.path().display()));
+    config.layout.reports_dir = Some("build/reports".into());
+
+    let layout = ProjectLayout::new(root.path()).with_config(&config);
+    assert_eq!(layout.outputs_dir, scratch.path().join("Game/outputs"));
+    assert_eq!(
+        layout.binary_output_root("GameBin"),
+        scratch.path().join("Game/outputs/binaries/GameBin")
+    );
+    assert_eq!(layout.reports_dir, root.path().join("build/reports"));
+    // Unset entries and the project metadata keep their default places.
+    assert_eq!(layout.graphs_dir, root.path().join("graphs"));
+    assert_eq!(layout.db_path, root.path().join(".ritual/project.db"));
+}
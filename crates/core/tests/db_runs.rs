@@ -1,7 +1,8 @@
 use rusqlite::Connection;
 use tempfile::tempdir;
 
-use ritual_core::db::{ProjectDb, RitualRunRecord};
+use ritual_core::db::{ProjectDb, RitualRunRecord, RunUsage};
+use ritual_core::services::analysis::AnalysisOptions;
 
 #[test]
 fn ritual_runs_insert_and_list_round_trip() {
@@ -22,6 +23,18 @@ fn ritual_runs_insert_and_list_round_trip() {
         status: ritual_core::db::RitualRunStatus::Stubbed,
         started_at: "t0".into(),
         finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: Some(AnalysisOptions {
+            max_depth: Some(3),
+            include_imports: true,
+            include_strings: false,
+            max_instructions: Some(1024),
+            max_total_instructions: None,
+        }),
+        usage: Some(RunUsage { duration_ms: 2_500, cpu_ms: Some(1_900), peak_rss_kb: None }),
     };
     let run_b = RitualRunRecord {
         binary: "BinB".into(),
@@ -34,6 +47,12 @@ fn ritual_runs_insert_and_list_round_trip() {
         status: ritual_core::db::RitualRunStatus::Stubbed,
         started_at: "t2".into(),
         finished_at: "t3".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
     };
 
     db.insert_ritual_run(&run_a).expect("insert run a");
@@ -46,6 +65,10 @@ fn ritual_runs_insert_and_list_round_trip() {
     assert_eq!(only_a.len(), 1);
     assert_eq!(only_a[0].binary, "BinA");
     assert_eq!(only_a[0].binary_hash.as_deref(), Some("binhashA"));
+    assert_eq!(only_a[0].analysis_options, run_a.analysis_options);
+    assert_eq!(db.list_ritual_runs(Some("BinB")).unwrap()[0].analysis_options, None);
+    assert_eq!(only_a[0].usage, run_a.usage);
+    assert_eq!(db.list_ritual_runs_with_ids(Some("BinB")).unwrap()[0].1.usage, None);
 
     // Update status and finished_at.
     let updated = db
@@ -57,6 +80,57 @@ fn ritual_runs_insert_and_list_round_trip() {
     assert_eq!(only_a_after[0].finished_at, "t9");
 }
 
+#[test]
+fn status_updates_target_the_latest_run_or_a_run_id() {
+    use ritual_core::db::RitualRunStatus::{Canceled, Failed, Pending, Succeeded};
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let run = RitualRunRecord {
+        binary: "Bin".into(),
+        ritual: "Net".into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: Pending,
+        started_at: "t0".into(),
+        finished_at: "t0".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let first = db.insert_ritual_run(&run).unwrap();
+    let second = db.insert_ritual_run(&run).unwrap();
+    let statuses = |db: &ProjectDb| -> Vec<_> {
+        db.list_ritual_runs_with_ids(Some("Bin"))
+            .unwrap()
+            .into_iter()
+            .map(|(id, r)| (id, r.status, r.finished_at))
+            .collect()
+    };
+
+    // By name, only the latest run changes.
+    assert_eq!(db.update_ritual_run_status("Bin", "Net", "succeeded", Some("t1")).unwrap(), 1);
+    assert_eq!(
+        statuses(&db),
+        [(first, Pending, "t0".to_string()), (second, Succeeded, "t1".to_string())]
+    );
+
+    // By id, without a finished_at, the timestamp is kept.
+    assert_eq!(db.update_run_status(first, "failed", None).unwrap(), 1);
+    assert_eq!(db.update_run_status(second + 1, "failed", None).unwrap(), 0);
+    assert_eq!(statuses(&db)[0], (first, Failed, "t0".to_string()));
+
+    assert_eq!(db.update_all_ritual_run_statuses("Bin", "Net", "canceled", Some("t2")).unwrap(), 2);
+    assert!(statuses(&db).iter().all(|(_, status, at)| *status == Canceled && at == "t2"));
+    assert_eq!(db.update_ritual_run_status("Other", "Net", "failed", None).unwrap(), 0);
+    assert!(db.list_ritual_runs_with_ids(Some("Other")).unwrap().is_empty());
+}
+
 #[test]
 fn existing_schema_is_migrated_to_latest() {
     let dir = tempdir().expect("tempdir");
@@ -106,9 +180,348 @@ fn existing_schema_is_migrated_to_latest() {
         status: ritual_core::db::RitualRunStatus::Stubbed,
         started_at: "t".into(),
         finished_at: "t".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: Some("rituals/run_x.yaml".into()),
+        analysis_options: None,
+        usage: None,
     };
     db.insert_ritual_run(&run).expect("insert after migration");
     let all = db.list_ritual_runs(None).expect("list runs");
     assert_eq!(all.len(), 1);
     assert_eq!(all[0].status, ritual_core::db::RitualRunStatus::Stubbed);
+    assert_eq!(all[0].spec_path.as_deref(), Some("rituals/run_x.yaml"));
+}
+
+#[test]
+fn ritual_runs_persist_resolved_binary_id() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+
+    let mut armv7 = ritual_core::db::BinaryRecord::new("libGame.so", "armv7/libGame.so");
+    armv7.arch = Some("armv7".into());
+    let mut arm64 = ritual_core::db::BinaryRecord::new("libGame.so", "arm64/libGame.so");
+    arm64.arch = Some("arm64".into());
+    db.insert_binary(&armv7).expect("insert armv7");
+    let arm64_id = db.insert_binary(&arm64).expect("insert arm64");
+
+    let listed = db.list_binaries_with_ids().expect("list binaries");
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[1], (arm64_id, arm64));
+
+    let run = RitualRunRecord {
+        binary: "libGame.so".into(),
+        binary_id: Some(arm64_id),
+        ritual: "Run".into(),
+        spec_hash: "spec".into(),
+        binary_hash: Some("hash".into()),
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: ritual_core::db::RitualRunStatus::Stubbed,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    db.insert_ritual_run(&run).expect("insert run");
+    let runs = db.list_ritual_runs(Some("libGame.so")).expect("list runs");
+    assert_eq!(runs, vec![run]);
+}
+
+#[test]
+fn runs_can_be_marked_superseded() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let run = RitualRunRecord {
+        binary: "BinA".into(),
+        ritual: "Run1".into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: ritual_core::db::RitualRunStatus::Stubbed,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let first = db.insert_ritual_run(&run).expect("insert first");
+    let second = db.insert_ritual_run(&run).expect("insert second");
+
+    assert_eq!(db.mark_run_superseded(first, second).expect("mark"), 1);
+    assert_eq!(db.mark_run_superseded(999, second).expect("mark missing"), 0);
+    let runs = db.list_ritual_runs(Some("BinA")).expect("list runs");
+    assert_eq!(runs[0].superseded_by, Some(second));
+    assert_eq!(runs[1].superseded_by, None);
+}
+
+#[test]
+fn runs_with_a_stale_binary_hash_can_be_marked_outdated() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let run = |binary: &str, hash: Option<&str>, binary_id: Option<i64>| RitualRunRecord {
+        binary: binary.into(),
+        ritual: "Run".into(),
+        spec_hash: "spec".into(),
+        binary_hash: hash.map(Into::into),
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: ritual_core::db::RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    db.insert_ritual_run(&run("BinA", Some("old"), Some(1))).unwrap();
+    db.insert_ritual_run(&run("BinA", Some("new"), Some(1))).unwrap();
+    db.insert_ritual_run(&run("BinA", Some("old"), None)).unwrap(); // legacy, matched by name
+    db.insert_ritual_run(&run("BinA", None, Some(1))).unwrap();
+    db.insert_ritual_run(&run("BinB", Some("old"), Some(2))).unwrap();
+
+    assert_eq!(db.mark_runs_outdated(1, "BinA", "new").unwrap(), 2);
+    assert_eq!(db.mark_runs_outdated(1, "BinA", "new").unwrap(), 0, "already outdated");
+    let statuses: Vec<&str> =
+        db.list_ritual_runs(None).unwrap().iter().map(|r| r.status.as_str()).collect();
+    assert_eq!(statuses, ["outdated", "succeeded", "outdated", "succeeded", "succeeded"]);
+}
+
+#[test]
+fn transactions_commit_or_roll_back_runs_together() {
+    use ritual_core::db::{DbError, RitualRunStatus};
+    use ritual_core::services::analysis::{
+        persist_run, AnalysisOptions, AnalysisRequest, AnalysisResult, FunctionRecord, RunMetadata,
+    };
+
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let request = |ritual: &str| AnalysisRequest {
+        ritual_name: ritual.into(),
+        binary_name: "BinA".into(),
+        binary_path: dir.path().join("bin"),
+        roots: vec!["main".into()],
+        arch: None,
+        options: AnalysisOptions::default(),
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    };
+    let meta = RunMetadata {
+        spec_hash: "spec".into(),
+        binary_id: None,
+        binary_hash: Some("hash".into()),
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Stubbed,
+        deterministic: false,
+        epoch: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        started_at: None,
+        usage: None,
+    };
+    let result = AnalysisResult {
+        functions: vec![FunctionRecord {
+            address: 0x1000,
+            name: Some("main".into()),
+            size: Some(4),
+            in_slice: true,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["main".into()],
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+
+    // A failure part-way through discards every run written in the transaction, including
+    // analysis rows written by helpers that normally open their own transaction.
+    let err = db.transaction(|db| {
+        persist_run(db, &request("First"), &meta, &result)?;
+        db.connection().execute("INSERT INTO no_such_table VALUES (1)", [])?;
+        Ok(())
+    });
+    assert!(matches!(err, Err(DbError::Sql(_))));
+    assert!(db.list_ritual_runs(None).unwrap().is_empty());
+
+    let ids = db
+        .transaction(|db| {
+            Ok(vec![
+                persist_run(db, &request("First"), &meta, &result)?,
+                persist_run(db, &request("Second"), &meta, &result)?,
+            ])
+        })
+        .unwrap();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(db.list_ritual_runs(None).unwrap().len(), 2);
+    let loaded = db.load_analysis_result("BinA", "Second").unwrap().unwrap();
+    assert_eq!(loaded.functions.len(), 1);
+}
+
+#[test]
+fn binaries_can_be_removed_or_renamed_with_their_runs() {
+    use ritual_core::db::{BinaryRecord, RitualRunStatus, SliceRecord, SliceStatus};
+    use ritual_core::services::analysis::{AnalysisResult, FunctionRecord};
+
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let bin_a = db.insert_binary(&BinaryRecord::new("BinA", "a.bin")).unwrap();
+    let bin_b = db.insert_binary(&BinaryRecord::new("BinB", "b.bin")).unwrap();
+    let run = |binary: &str, ritual: &str, binary_id: Option<i64>| RitualRunRecord {
+        binary: binary.into(),
+        ritual: ritual.into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let run_a = db.insert_ritual_run(&run("BinA", "Run", Some(bin_a))).unwrap();
+    db.insert_ritual_run(&run("BinA", "Legacy", None)).unwrap(); // matched by name
+    db.insert_ritual_run(&run("BinB", "Run", Some(bin_b))).unwrap();
+    let result = AnalysisResult {
+        functions: vec![FunctionRecord {
+            address: 0x1000,
+            name: Some("main".into()),
+            size: None,
+            in_slice: true,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["main".into()],
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_a, &result).unwrap();
+    db.add_run_note(run_a, "first finding", "t2").unwrap();
+    db.add_run_note(run_a, "second finding", "t3").unwrap();
+    let notes: Vec<String> =
+        db.list_run_notes(run_a).unwrap().into_iter().map(|n| n.note).collect();
+    assert_eq!(notes, ["first finding", "second finding"]);
+    let slice = SliceRecord {
+        default_binary: Some("BinB".into()),
+        ..SliceRecord::new("Net", SliceStatus::Planned)
+    };
+    db.insert_slice(&slice).unwrap();
+
+    // Renaming with runs moves runs (and slice defaults) to the new name.
+    assert_eq!(db.rename_binary(bin_b, "BinB", "BinC", true).unwrap(), 1);
+    assert_eq!(db.list_ritual_runs(Some("BinC")).unwrap().len(), 1);
+    assert_eq!(db.list_slices().unwrap()[0].default_binary.as_deref(), Some("BinC"));
+
+    assert_eq!(db.delete_binary_runs(bin_a, "BinA").unwrap(), 2);
+    let functions: i64 = db
+        .connection()
+        .query_row("SELECT COUNT(*) FROM analysis_functions", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(functions, 0, "analysis rows go with their run");
+    assert!(db.list_run_notes(run_a).unwrap().is_empty(), "notes go with their run");
+    assert_eq!(db.delete_binary(bin_a).unwrap(), 1);
+    let names: Vec<String> = db.list_binaries().unwrap().into_iter().map(|b| b.name).collect();
+    assert_eq!(names, ["BinC"]);
+
+    // Detached runs outlive the binary under the name they were recorded with.
+    assert_eq!(db.detach_binary_runs(bin_b).unwrap(), 1);
+    assert_eq!(db.delete_binary(bin_b).unwrap(), 1);
+    let runs = db.list_ritual_runs(None).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!((runs[0].binary.as_str(), runs[0].binary_id), ("BinC", None));
+}
+
+#[test]
+fn same_named_binaries_get_distinct_run_names() {
+    use ritual_core::db::{BinaryRecord, RitualRunStatus};
+
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let binary = |arch: &str, hash: Option<&str>| BinaryRecord {
+        arch: Some(arch.into()),
+        hash: hash.map(str::to_string),
+        ..BinaryRecord::new("libgame.so", format!("{arch}/libgame.so"))
+    };
+    let armv7 = db.insert_binary(&binary("armv7", Some("aaaa0000aaaa0000aaaa"))).unwrap();
+    let arm64 = db.insert_binary(&binary("arm64", Some("bbbb1111bbbb1111bbbb"))).unwrap();
+    let x86 = db.insert_binary(&binary("x86", None)).unwrap();
+
+    assert_eq!(db.binary_run_name(armv7).unwrap().as_deref(), Some("libgame.so"));
+    assert_eq!(db.binary_run_name(arm64).unwrap().as_deref(), Some("libgame.so@bbbb1111bbbb"));
+    assert_eq!(db.binary_run_name(x86).unwrap().as_deref(), Some(&*format!("libgame.so#{x86}")));
+    assert_eq!(db.binary_run_name(x86 + 1).unwrap(), None);
+
+    // Names stick to the runs already recorded, even once the first binary is gone.
+    let run = |binary: &str, binary_id: Option<i64>| RitualRunRecord {
+        binary: binary.into(),
+        ritual: "Net".into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    db.insert_ritual_run(&run("libgame.so", Some(armv7))).unwrap();
+    db.insert_ritual_run(&run("libgame.so@bbbb1111bbbb", Some(arm64))).unwrap();
+    db.detach_binary_runs(armv7).unwrap();
+    db.delete_binary(armv7).unwrap();
+    assert_eq!(db.binary_run_name(arm64).unwrap().as_deref(), Some("libgame.so@bbbb1111bbbb"));
+    assert_eq!(db.binary_run_name(x86).unwrap().as_deref(), Some(&*format!("libgame.so#{x86}")));
+    let runs: Vec<_> = db.list_ritual_runs(Some("libgame.so@bbbb1111bbbb")).unwrap();
+    assert_eq!(runs.iter().map(|r| r.binary_id).collect::<Vec<_>>(), [Some(arm64)]);
 }
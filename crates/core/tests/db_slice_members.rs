@@ -0,0 +1,305 @@
+use tempfile::tempdir;
+
+use ritual_core::db::{
+    BinaryRecord, MemberSource, ProjectDb, RitualRunRecord, RitualRunStatus, SliceMember,
+    SliceRecord, SliceStatus,
+};
+use ritual_core::services::analysis::{apply_slice_members, AnalysisResult, FunctionRecord};
+
+fn member(address: u64, source: MemberSource, excluded: bool) -> SliceMember {
+    SliceMember {
+        slice: "Net".into(),
+        binary_hash: "hash-a".into(),
+        address,
+        source,
+        excluded,
+        updated_at: "t0".into(),
+    }
+}
+
+#[test]
+fn slice_members_upsert_replace_and_delete() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    db.insert_slice(&SliceRecord::new("Net", SliceStatus::Active)).unwrap();
+
+    // Unknown slices are not written.
+    let mut orphan = member(0x1000, MemberSource::Manual, false);
+    orphan.slice = "Missing".into();
+    assert_eq!(db.upsert_slice_member(&orphan).unwrap(), 0);
+
+    assert_eq!(db.upsert_slice_member(&member(0x2000, MemberSource::Manual, false)).unwrap(), 1);
+    // Upserting the same manual row flips it to an exclusion.
+    db.upsert_slice_member(&member(0x2000, MemberSource::Manual, true)).unwrap();
+    db.replace_analysis_slice_members("Net", "hash-a", &[0x1000, 0x2000], "t1").unwrap();
+    db.replace_analysis_slice_members("Net", "hash-b", &[0x9000], "t1").unwrap();
+
+    let listed = db.list_slice_members("Net", Some("hash-a")).unwrap();
+    assert_eq!(listed.len(), 3);
+    assert_eq!(listed[1].address, 0x2000);
+    assert_eq!(listed[1].source, MemberSource::Analysis);
+    assert_eq!(listed[2].source, MemberSource::Manual);
+    assert!(listed[2].excluded);
+    assert_eq!(db.list_slice_members("Net", None).unwrap().len(), 4);
+
+    // Re-recording analysis members replaces only analysis rows for that build.
+    db.replace_analysis_slice_members("Net", "hash-a", &[0x3000], "t2").unwrap();
+    let listed = db.list_slice_members("Net", Some("hash-a")).unwrap();
+    let addrs: Vec<(u64, MemberSource)> = listed.iter().map(|m| (m.address, m.source)).collect();
+    assert_eq!(addrs, vec![(0x2000, MemberSource::Manual), (0x3000, MemberSource::Analysis)]);
+
+    assert_eq!(db.delete_slice_member("Net", "hash-a", 0x2000, MemberSource::Manual).unwrap(), 1);
+    assert_eq!(db.delete_slice_member("Net", "hash-a", 0x2000, MemberSource::Manual).unwrap(), 0);
+}
+
+#[test]
+fn apply_slice_members_overrides_analysis_membership() {
+    let func = |address, in_slice| FunctionRecord {
+        address,
+        name: None,
+        size: None,
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let mut result = AnalysisResult {
+        functions: vec![func(0x1000, true), func(0x2000, false)],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    apply_slice_members(
+        &mut result,
+        &[
+            member(0x1000, MemberSource::Manual, true),
+            member(0x2000, MemberSource::Manual, false),
+            member(0x3000, MemberSource::Manual, false),
+            member(0x4000, MemberSource::Manual, true),
+            member(0x5000, MemberSource::Analysis, false),
+        ],
+    );
+    let flags: Vec<(u64, bool)> =
+        result.functions.iter().map(|f| (f.address, f.in_slice)).collect();
+    assert_eq!(flags, vec![(0x1000, false), (0x2000, true), (0x3000, true)]);
+}
+
+#[test]
+fn slice_status_updates_record_transitions() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    db.insert_slice(&SliceRecord::new("Net", SliceStatus::Planned)).unwrap();
+
+    let change = db.update_slice_status("Net", SliceStatus::Active, "t1", None).unwrap().unwrap();
+    assert_eq!((change.from, change.to), (SliceStatus::Planned, SliceStatus::Active));
+    // Re-applying the current status is not a transition; unknown slices are not written.
+    assert!(db.update_slice_status("Net", SliceStatus::Active, "t2", None).unwrap().is_none());
+    assert!(db.update_slice_status("Missing", SliceStatus::Active, "t2", None).unwrap().is_none());
+    db.update_slice_status("Net", SliceStatus::Deprecated, "t3", Some("superseded")).unwrap();
+
+    assert_eq!(db.list_slices().unwrap()[0].status, SliceStatus::Deprecated);
+    let history = db.slice_status_history("Net").unwrap();
+    let steps: Vec<_> = history.iter().map(|c| (c.from, c.to, c.changed_at.as_str())).collect();
+    assert_eq!(
+        steps,
+        vec![
+            (SliceStatus::Planned, SliceStatus::Active, "t1"),
+            (SliceStatus::Active, SliceStatus::Deprecated, "t3"),
+        ]
+    );
+    assert_eq!(history[1].note.as_deref(), Some("superseded"));
+    assert!(db.slice_status_history("Missing").unwrap().is_empty());
+    assert_eq!(SliceStatus::parse(" ACTIVE "), Some(SliceStatus::Active));
+    assert_eq!(SliceStatus::parse("retired"), None);
+}
+
+#[test]
+fn slice_roots_and_notes_round_trip() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    db.insert_slice(&SliceRecord::new("Net", SliceStatus::Planned)).unwrap();
+    let slice = &db.list_slices().unwrap()[0];
+    assert!(slice.roots.is_empty());
+    assert_eq!(slice.notes, None);
+
+    let roots = vec!["Net::open".to_string(), "0x401000".to_string()];
+    assert_eq!(db.set_slice_roots_and_notes("Net", &roots, Some("handshake")).unwrap(), 1);
+    let slice = &db.list_slices().unwrap()[0];
+    assert_eq!(slice.roots, roots);
+    assert_eq!(slice.notes.as_deref(), Some("handshake"));
+
+    assert_eq!(db.set_slice_roots_and_notes("Net", &[], None).unwrap(), 1);
+    assert!(db.list_slices().unwrap()[0].roots.is_empty());
+    assert_eq!(db.set_slice_roots_and_notes("Missing", &[], None).unwrap(), 0);
+}
+
+#[test]
+fn slice_roots_keep_insertion_order_without_duplicates() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let mut record = SliceRecord::new("Net", SliceStatus::Active);
+    record.roots = vec!["Net::open".into(), "Net::open".into()];
+    db.insert_slice(&record).unwrap();
+    db.insert_slice(&SliceRecord::new("Ui", SliceStatus::Active)).unwrap();
+    assert_eq!(db.list_slice_roots("Net").unwrap(), ["Net::open"]);
+
+    assert_eq!(db.add_slice_root("Net", "0x401000").unwrap(), 1);
+    assert_eq!(db.add_slice_root("Net", "Net::open").unwrap(), 0);
+    assert_eq!(db.add_slice_root("Missing", "x").unwrap(), 0);
+    assert_eq!(db.remove_slice_root("Net", "Net::open").unwrap(), 1);
+    assert_eq!(db.remove_slice_root("Net", "Net::open").unwrap(), 0);
+    assert_eq!(db.add_slice_root("Net", "Net::open").unwrap(), 1);
+    assert_eq!(db.list_slice_roots("Net").unwrap(), ["0x401000", "Net::open"]);
+    assert!(db.list_slice_roots("Ui").unwrap().is_empty());
+
+    let slices = db.list_slices().unwrap();
+    assert_eq!(slices[0].roots, ["0x401000", "Net::open"]);
+    assert!(slices[1].roots.is_empty());
+}
+
+#[test]
+fn slice_roots_column_is_migrated_into_rows() {
+    let dir = tempdir().expect("tempdir");
+    let db_path = dir.path().join("project.db");
+    {
+        let db = ProjectDb::open(&db_path).expect("open db");
+        db.insert_slice(&SliceRecord::new("Net", SliceStatus::Active)).unwrap();
+        // Roots as a v44 project stored them: a JSON list on the slice row.
+        db.connection()
+            .execute_batch(
+                r#"
+                DROP TABLE slice_roots;
+                UPDATE slices SET roots = '["Net::open","0x401000"]';
+                PRAGMA user_version = 44;
+                "#,
+            )
+            .unwrap();
+    }
+
+    let db = ProjectDb::open(&db_path).expect("reopen and migrate");
+    assert_eq!(db.list_slice_roots("Net").unwrap(), ["Net::open", "0x401000"]);
+    let legacy: Option<String> =
+        db.connection().query_row("SELECT roots FROM slices", [], |row| row.get(0)).unwrap();
+    assert_eq!(legacy, None);
+}
+
+#[test]
+fn namespaced_slices_share_a_name_but_not_members() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    db.insert_slice(&SliceRecord::new("Net", SliceStatus::Active)).unwrap();
+    let scoped = SliceRecord::new("Net", SliceStatus::Planned).with_namespace(Some("armv7".into()));
+    assert_eq!(scoped.name, "Net@armv7");
+    assert_eq!(scoped.ritual_name(), "Net");
+    db.insert_slice(&scoped).unwrap();
+
+    let slices = db.list_slices().unwrap();
+    assert_eq!(slices[0].namespace, None);
+    assert_eq!(slices[0].ritual_name(), "Net");
+    assert_eq!(slices[1], scoped);
+
+    db.upsert_slice_member(&member(0x1000, MemberSource::Manual, false)).unwrap();
+    let mut other = member(0x2000, MemberSource::Manual, false);
+    other.slice = "Net@armv7".into();
+    db.upsert_slice_member(&other).unwrap();
+    let addresses = |slice: &str| -> Vec<u64> {
+        db.list_slice_members(slice, None).unwrap().iter().map(|m| m.address).collect()
+    };
+    assert_eq!(addresses("Net"), [0x1000]);
+    assert_eq!(addresses("Net@armv7"), [0x2000]);
+}
+
+#[test]
+fn slice_snapshots_keep_each_runs_members_with_manual_overrides() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let binary = BinaryRecord {
+        name: "GameArm".into(),
+        path: "GameArm.so".into(),
+        arch: None,
+        hash: Some("hash-a".into()),
+    };
+    let binary_id = db.insert_binary(&binary).unwrap();
+    let arm = db.ensure_binary_group("armv7", "t0").unwrap();
+    db.assign_binary_group(binary_id, arm, Some("1.0"), None).unwrap();
+    db.ensure_binary_group("x86", "t0").unwrap();
+    db.insert_slice(&SliceRecord::new("Net", SliceStatus::Active)).unwrap();
+    db.insert_slice(
+        &SliceRecord::new("Net", SliceStatus::Active).with_namespace(Some("x86".into())),
+    )
+    .unwrap();
+    db.insert_slice(&SliceRecord::new("Ui", SliceStatus::Active)).unwrap();
+    db.upsert_slice_member(&member(0x1000, MemberSource::Manual, true)).unwrap();
+    db.upsert_slice_member(&member(0x3000, MemberSource::Manual, false)).unwrap();
+
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "GameArm".into(),
+            ritual: "Net".into(),
+            spec_hash: "sh".into(),
+            binary_hash: Some("hash-a".into()),
+            backend: "validate-only".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "t1".into(),
+            finished_at: "t1".into(),
+            binary_id: Some(binary_id),
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let func = |address, in_slice| FunctionRecord {
+        address,
+        name: None,
+        size: None,
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let result = AnalysisResult {
+        functions: vec![func(0x1000, true), func(0x2000, true), func(0x2500, false)],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+
+    // Only the global slice takes the run: Net@x86 is scoped to another group, Ui to
+    // another ritual.
+    assert_eq!(db.record_slice_snapshots(run_id).unwrap(), 1);
+    let snapshots = db.slice_snapshots("Net").unwrap();
+    assert_eq!(snapshots.get(&run_id), Some(&vec![0x2000, 0x3000]));
+    assert!(db.slice_snapshots("Net@x86").unwrap().is_empty());
+    assert_eq!(db.record_slice_snapshots(run_id + 1).unwrap(), 0);
+
+    // Later overrides do not rewrite recorded snapshots; re-persisting the run drops them.
+    db.upsert_slice_member(&member(0x2000, MemberSource::Manual, true)).unwrap();
+    assert_eq!(db.slice_snapshots("Net").unwrap()[&run_id], vec![0x2000, 0x3000]);
+    db.insert_analysis_result(run_id, &result).unwrap();
+    assert!(db.slice_snapshots("Net").unwrap().is_empty());
+}
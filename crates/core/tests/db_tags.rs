@@ -0,0 +1,73 @@
+use tempfile::tempdir;
+
+use ritual_core::db::{
+    BinaryRecord, ProjectDb, RitualRunRecord, RitualRunStatus, SliceRecord, SliceStatus, TagKind,
+};
+
+fn run(binary: &str, ritual: &str, binary_id: i64) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        binary_id: Some(binary_id),
+        ritual: ritual.into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+#[test]
+fn tags_attach_to_slices_binaries_and_runs() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let slice = db.insert_slice(&SliceRecord::new("Net", SliceStatus::Planned)).unwrap();
+    assert_eq!(db.slice_id("Net").unwrap(), Some(slice));
+    assert_eq!(db.slice_id("Missing").unwrap(), None);
+    let binary = db.insert_binary(&BinaryRecord::new("Game", "game.bin")).unwrap();
+    let run_id = db.insert_ritual_run(&run("Game", "Boot", binary)).unwrap();
+
+    assert!(db.add_tag(TagKind::Slice, slice, "priority", "t0").unwrap());
+    assert!(db.add_tag(TagKind::Binary, binary, "obfuscated", "t1").unwrap());
+    assert!(db.add_tag(TagKind::Run, run_id, "baseline", "t2").unwrap());
+    assert!(db.add_tag(TagKind::Binary, binary, "priority", "t3").unwrap());
+    assert!(!db.add_tag(TagKind::Binary, binary, "priority", "t4").unwrap(), "already tagged");
+
+    let all = db.list_taggings(None, None).unwrap();
+    let summary: Vec<(&str, TagKind, &str)> =
+        all.iter().map(|t| (t.tag.as_str(), t.kind, t.name.as_str())).collect();
+    assert_eq!(
+        summary,
+        [
+            ("baseline", TagKind::Run, "Game"),
+            ("obfuscated", TagKind::Binary, "Game"),
+            ("priority", TagKind::Binary, "Game"),
+            ("priority", TagKind::Slice, "Net"),
+        ]
+    );
+    assert_eq!(all[0].ritual.as_deref(), Some("Boot"));
+    assert_eq!(all[0].target_id, run_id);
+    assert_eq!(db.list_taggings(Some(TagKind::Binary), Some("priority")).unwrap().len(), 1);
+
+    assert_eq!(db.remove_tag(TagKind::Binary, binary, "obfuscated").unwrap(), 1);
+    assert_eq!(db.remove_tag(TagKind::Binary, binary, "obfuscated").unwrap(), 0);
+    let unused: i64 = db
+        .connection()
+        .query_row("SELECT COUNT(*) FROM tags WHERE name = 'obfuscated'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(unused, 0, "tags left on nothing are dropped");
+
+    // Removing a binary and its runs takes their tags along.
+    db.delete_binary_runs(binary, "Game").unwrap();
+    db.delete_binary(binary).unwrap();
+    let left: Vec<TagKind> = db.list_taggings(None, None).unwrap().iter().map(|t| t.kind).collect();
+    assert_eq!(left, [TagKind::Slice]);
+}
@@ -0,0 +1,264 @@
+use std::fs;
+use std::time::Duration;
+
+use ritual_core::services::analysis::{
+    AnalysisResult, EvidenceKind, EvidenceSource, FunctionRecord, RootHit, XrefKind, XrefRecord,
+};
+use ritual_core::services::decoders::{
+    byte_sequences, parse_decoder_output, run_decoders, ByteSequence, Decoder, DecoderError,
+};
+use ritual_core::services::loader::ImageLayout;
+use ritual_core::services::process::Deadline;
+use tempfile::tempdir;
+
+fn xor(text: &[u8], key: u8) -> Vec<u8> {
+    text.iter().map(|b| b ^ key).collect()
+}
+
+/// A raw image at 0x1000: an XOR-encoded string (terminator encoded too), a base64 string,
+/// and a plain one.
+fn image() -> Vec<u8> {
+    let mut bytes = vec![0u8; 0x10];
+    bytes.extend(xor(b"AutoUpdate server\0", 0x5a));
+    bytes.push(0);
+    bytes.extend(b"c2VjcmV0IGtleQ==\0"); // "secret key"
+    bytes.extend(b"plain text\0");
+    bytes
+}
+
+fn result() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![FunctionRecord {
+            address: 0x2000,
+            name: Some("check_update".into()),
+            size: Some(0x20),
+            in_slice: false,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["string:AutoUpdate".into()],
+        root_hits: vec![RootHit {
+            root: "string:AutoUpdate".into(),
+            functions: Vec::new(),
+            error: Some("no function references this string".into()),
+        }],
+        sub_slices: Vec::new(),
+        xrefs: vec![XrefRecord {
+            from: 0x2004,
+            to: 0x1010,
+            kind: XrefKind::Immediate,
+            section: None,
+            preview: None,
+        }],
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    }
+}
+
+fn decoders(names: &[&str]) -> Vec<Decoder> {
+    names.iter().map(|n| Decoder::parse(n).unwrap()).collect()
+}
+
+fn deadline() -> Deadline {
+    Deadline::new(Some(Duration::from_secs(30)))
+}
+
+#[test]
+fn decoder_names_parse_to_builtins_and_hooks() {
+    assert_eq!(Decoder::parse("xor").unwrap(), Decoder::Xor(None));
+    assert_eq!(Decoder::parse("xor:0x5A").unwrap(), Decoder::Xor(Some(0x5a)));
+    assert_eq!(Decoder::parse("xor:90").unwrap().name(), "xor:0x5a");
+    assert_eq!(Decoder::parse("base64").unwrap(), Decoder::Base64);
+    assert_eq!(
+        Decoder::parse("./hooks/rc4.wasm").unwrap(),
+        Decoder::Hook("./hooks/rc4.wasm".into())
+    );
+    for bad in ["xor:0", "xor:0x100", "xor:key"] {
+        assert!(matches!(Decoder::parse(bad), Err(DecoderError::InvalidKey(_))), "{bad}");
+    }
+}
+
+#[test]
+fn byte_sequences_split_data_on_nul() {
+    let bytes = image();
+    let layout = ImageLayout::raw(0x1000, bytes.len() as u64);
+    let sequences = byte_sequences(&bytes, &layout);
+    let found: Vec<(u64, usize)> = sequences.iter().map(|s| (s.address, s.bytes.len())).collect();
+    // The encoded terminator is not NUL, so the XOR string runs into its real terminator.
+    assert_eq!(found, [(0x1010, 18), (0x1023, 16), (0x1034, 10)]);
+}
+
+#[test]
+fn builtin_decoders_add_string_evidence_and_resolve_string_roots() {
+    let bytes = image();
+    let sequences = byte_sequences(&bytes, &ImageLayout::raw(0x1000, bytes.len() as u64));
+    let temp = tempdir().unwrap();
+    let mut result = result();
+
+    let reports = run_decoders(
+        &mut result,
+        &sequences,
+        &decoders(&["xor", "base64"]),
+        temp.path(),
+        "Game",
+        &deadline(),
+    )
+    .unwrap();
+    let added: Vec<(String, usize)> =
+        reports.into_iter().map(|r| (r.decoder, r.strings_added)).collect();
+    assert_eq!(added, [("xor".to_string(), 1), ("base64".to_string(), 1)]);
+
+    let strings: Vec<(u64, &str, String)> = result
+        .evidence
+        .iter()
+        .filter(|e| e.kind == Some(EvidenceKind::String))
+        .map(|e| (e.address, e.description.as_str(), e.source.as_ref().unwrap().to_string()))
+        .collect();
+    assert_eq!(
+        strings,
+        [
+            (0x1010, "string: AutoUpdate server", "decoder/xor:0x5a".to_string()),
+            (0x1023, "string: secret key", "decoder/base64".to_string()),
+            (
+                0x2004,
+                "string xref: \"AutoUpdate server\" @ 0x1010",
+                "core/string-xrefs".to_string()
+            ),
+        ]
+    );
+    // The root now matches the decoded text.
+    assert_eq!(result.root_hits[0].functions, [0x2000]);
+    assert_eq!(result.root_hits[0].error, None);
+    assert!(result.functions[0].in_slice);
+
+    // A fixed key decodes the same string; already reported strings are not added twice.
+    let reports = run_decoders(
+        &mut result,
+        &sequences,
+        &decoders(&["xor:0x5a"]),
+        temp.path(),
+        "Game",
+        &deadline(),
+    )
+    .unwrap();
+    assert_eq!(reports[0].strings_added, 0);
+    assert_eq!(result.evidence.len(), 3);
+}
+
+#[test]
+fn xor_key_search_ignores_plain_and_random_data() {
+    let sequences = [
+        ByteSequence { address: 0x10, bytes: b"plain text".to_vec() },
+        ByteSequence { address: 0x20, bytes: vec![0x8f, 0x13, 0xe2, 0x77, 0x41, 0xc9, 0x05, 0xba] },
+    ];
+    let mut result = result();
+    let temp = tempdir().unwrap();
+    let reports = run_decoders(
+        &mut result,
+        &sequences,
+        &decoders(&["xor", "base64"]),
+        temp.path(),
+        "Game",
+        &deadline(),
+    )
+    .unwrap();
+    assert!(reports.iter().all(|r| r.strings_added == 0));
+    assert!(result.evidence.is_empty());
+}
+
+#[test]
+fn malformed_decoder_output_is_rejected() {
+    assert_eq!(parse_decoder_output("d", b"\n").unwrap(), Default::default());
+    let err = parse_decoder_output("d", br#"{"string": []}"#).unwrap_err();
+    assert!(err.to_string().starts_with("String decoder d wrote invalid output: unknown field"));
+    let err = parse_decoder_output("d", br#"{"api_version": 2}"#).unwrap_err();
+    assert!(matches!(err, DecoderError::UnsupportedApi { version: 2, .. }));
+}
+
+#[cfg(unix)]
+#[test]
+fn decoder_hooks_receive_sequences_and_return_strings() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().unwrap();
+    fs::create_dir(temp.path().join("hooks")).unwrap();
+    let hook = temp.path().join("hooks").join("rot13.sh");
+    fs::write(
+        &hook,
+        "#!/bin/sh\ncat > hook.input\necho '{\"strings\": [{\"address\": 4132, \"value\": \"net_connect\"}]}'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    let custom = r#"(module
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 64) "no key")
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 64))
+    (i32.store (i32.const 4) (i32.const 6))
+    (drop (call $fd_write (i32.const 2) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (call $proc_exit (i32.const 3))))"#;
+    fs::write(temp.path().join("hooks").join("custom.wasm"), wat::parse_str(custom).unwrap())
+        .unwrap();
+
+    let sequences = [ByteSequence { address: 0x1024, bytes: b"arg_pbaarpg".to_vec() }];
+    let mut result = result();
+    let reports = run_decoders(
+        &mut result,
+        &sequences,
+        &decoders(&["hooks/rot13.sh"]),
+        temp.path(),
+        "Game",
+        &deadline(),
+    )
+    .unwrap();
+    assert_eq!(reports[0].strings_added, 1);
+    assert_eq!(result.evidence[0].description, "string: net_connect");
+    assert_eq!(result.evidence[0].source, Some(EvidenceSource::new("decoder", "rot13")));
+
+    // Hooks run from the project root and get the sequences as hex.
+    let input: serde_json::Value =
+        serde_json::from_slice(&fs::read(temp.path().join("hook.input")).unwrap()).unwrap();
+    assert_eq!(input["api_version"], 1);
+    assert_eq!(input["binary"], "Game");
+    assert_eq!(input["sequences"][0]["address"], 0x1024);
+    assert_eq!(input["sequences"][0]["bytes"], "6172675f70626161727067");
+
+    // WASM hooks run on the built-in WASI runtime.
+    let err = run_decoders(
+        &mut result,
+        &sequences,
+        &decoders(&["hooks/custom.wasm"]),
+        temp.path(),
+        "Game",
+        &deadline(),
+    )
+    .unwrap_err();
+    if cfg!(feature = "wasm-plugins") {
+        assert_eq!(
+            err.to_string(),
+            "String decoder hooks/custom.wasm exited with status 3: no key"
+        );
+    } else {
+        assert!(matches!(err, DecoderError::Run { .. }));
+    }
+    let err = run_decoders(
+        &mut result,
+        &sequences,
+        &decoders(&["hooks/gone.sh"]),
+        temp.path(),
+        "Game",
+        &deadline(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, DecoderError::MissingHook(_)));
+}
@@ -0,0 +1,172 @@
+use ritual_core::db::RitualRunStatus;
+use ritual_core::services::analysis::{
+    finalize_result, AnalysisOptions, AnalysisRequest, AnalysisResult, FunctionRecord, RootGroup,
+    RunMetadata,
+};
+use ritual_core::services::demangle::{demangle, demangle_functions};
+
+fn func(address: u64, name: &str) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+#[test]
+fn demangles_itanium_symbols_like_cxxfilt() {
+    let cases = [
+        ("_ZN7Example4initEv", "Example::init()"),
+        ("_ZNK7Example4sizeEv", "Example::size() const"),
+        ("_Z3fooPKcRi", "foo(char const*, int&)"),
+        ("_ZN3foo3barIiEEvT_", "void foo::bar<int>(int)"),
+        (
+            "_ZNSt6vectorIiSaIiEE9push_backERKi",
+            "std::vector<int, std::allocator<int> >::push_back(int const&)",
+        ),
+        ("_ZN7ExampleC2Ev", "Example::Example()"),
+        ("_ZN7ExampleD1Ev", "Example::~Example()"),
+        ("_ZN3FooC1ERKS_", "Foo::Foo(Foo const&)"),
+        ("_ZN7ExampleeqERKS_", "Example::operator==(Example const&)"),
+        (
+            "_Z4swapISt6vectorIiSaIiEEEvRT_S4_",
+            "void swap<std::vector<int, std::allocator<int> > >(std::vector<int, std::allocator<int> >&, std::vector<int, std::allocator<int> >&)",
+        ),
+        ("_ZN12_GLOBAL__N_14stepEv", "(anonymous namespace)::step()"),
+        ("_Z3maxIiET_S0_S0_", "int max<int>(int, int)"),
+        ("_ZZ4mainE5count", "main::count"),
+        ("_Z3fooILi3EEvv", "void foo<3>()"),
+        ("_Z3bazv.cold", "baz() [clone .cold]"),
+        (
+            "_ZNSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEED1Ev",
+            "std::__cxx11::basic_string<char, std::char_traits<char>, std::allocator<char> >::~basic_string()",
+        ),
+        ("_ZdlPv", "operator delete(void*)"),
+    ];
+    for (mangled, expected) in cases {
+        assert_eq!(demangle(mangled).as_deref(), Some(expected), "{mangled}");
+    }
+}
+
+#[test]
+fn demangles_rust_legacy_and_macho_symbols() {
+    assert_eq!(
+        demangle("_ZN4core3fmt5write17h0123456789abcdefE").as_deref(),
+        Some("core::fmt::write")
+    );
+    assert_eq!(
+        demangle("_ZN48_$LT$app..Config$u20$as$u20$core..fmt..Debug$GT$3fmt17h0123456789abcdefE")
+            .as_deref(),
+        Some("<app::Config as core::fmt::Debug>::fmt")
+    );
+    assert_eq!(demangle("_RNvCs1234_3app4main").as_deref(), Some("app::main"));
+    assert_eq!(demangle("_RNvC3app4main").as_deref(), Some("app::main"));
+    // Mach-O adds a leading underscore to every symbol.
+    assert_eq!(demangle("__ZN7Example4initEv").as_deref(), Some("Example::init()"));
+    assert_eq!(demangle("_Z3fooPFviE").as_deref(), Some("foo(void (*)(int))"));
+}
+
+#[test]
+fn leaves_plain_and_malformed_symbols_alone() {
+    for symbol in ["main", "helper", "_start", "i", "_Z", "_ZN3foo", "_R"] {
+        assert_eq!(demangle(symbol), None, "{symbol}");
+    }
+}
+
+/// Symbol names come from untrusted binaries: crafted lengths and nesting must not panic or
+/// overflow the stack.
+#[test]
+fn rejects_crafted_symbols_without_panicking() {
+    let deep = format!("_Z1f{}i", "P".repeat(200_000));
+    let nested = format!("_ZN{}E", "1a".repeat(100_000));
+    let templates = format!("_Z1f{}v", "I1a".repeat(50_000));
+    for symbol in [
+        "_ZN18446744073709551615E",
+        "_Z18446744073709551615v",
+        "_Z1fIiEvT18446744073709551615_",
+        "_ZN99999999999999999999999999E",
+        "_RNvC18446744073709551615_3app",
+        deep.as_str(),
+        nested.as_str(),
+        templates.as_str(),
+    ] {
+        assert_eq!(demangle(symbol), None, "{}", &symbol[..symbol.len().min(40)]);
+    }
+}
+
+#[test]
+fn demangle_functions_keeps_original_and_is_idempotent() {
+    let mut functions = vec![func(0x1000, "_ZN7Example4initEv"), func(0x1010, "main")];
+    demangle_functions(&mut functions);
+    assert_eq!(functions[0].name.as_deref(), Some("Example::init()"));
+    assert_eq!(functions[0].mangled_name.as_deref(), Some("_ZN7Example4initEv"));
+    assert_eq!(functions[1].name.as_deref(), Some("main"));
+    assert_eq!(functions[1].mangled_name, None);
+
+    let before = functions.clone();
+    demangle_functions(&mut functions);
+    assert_eq!(functions, before);
+}
+
+#[test]
+fn finalize_result_demangles_after_matching_sub_slice_roots() {
+    let request = AnalysisRequest {
+        ritual_name: "Demo".into(),
+        binary_name: "demo".into(),
+        binary_path: "demo.bin".into(),
+        roots: vec!["_ZN7Example4initEv".into()],
+        root_groups: vec![RootGroup {
+            label: "init".into(),
+            roots: vec!["_ZN7Example4initEv".into()],
+        }],
+        arch: None,
+        options: AnalysisOptions::default(),
+        backend_path: None,
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    };
+    let result = AnalysisResult {
+        functions: vec![func(0x1000, "_ZN7Example4initEv")],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    let meta = RunMetadata {
+        spec_hash: "spec".into(),
+        binary_id: None,
+        binary_hash: None,
+        backend: "test".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        deterministic: false,
+        epoch: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        started_at: None,
+        usage: None,
+    };
+    let finalized = finalize_result(&request, &meta, result);
+    assert_eq!(finalized.sub_slices[0].functions, vec![0x1000]);
+    assert_eq!(finalized.functions[0].name.as_deref(), Some("Example::init()"));
+
+    // The original symbol survives JSON reports.
+    let json = serde_json::to_value(&finalized.functions[0]).unwrap();
+    assert_eq!(json["mangled_name"], "_ZN7Example4initEv");
+}
@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use ritual_core::db::RitualRunStatus;
+use ritual_core::services::analysis::{
+    AnalysisResult, CallEdge, EvidenceKind, EvidenceRecord, FunctionRecord, RootHit, RunMetadata,
+};
+use ritual_core::services::deterministic::{
+    canonicalize_result, normalize_path, parse_epoch, DETERMINISTIC_EPOCH,
+};
+
+fn func(address: u64, name: &str) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn evidence(address: u64, description: &str, kind: Option<EvidenceKind>) -> EvidenceRecord {
+    EvidenceRecord { address, description: description.into(), kind, source: None, run_id: None }
+}
+
+#[test]
+fn canonicalize_orders_every_list_independently_of_backend_order() {
+    let mut result = AnalysisResult {
+        functions: vec![func(0x3000, "c"), func(0x1000, "a"), func(0x2000, "b")],
+        call_edges: vec![
+            CallEdge {
+                from: 0x2000,
+                to: 0x3000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1000,
+                to: 0x3000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+        evidence: vec![
+            evidence(0x2000, "string: \"b\"", Some(EvidenceKind::String)),
+            evidence(0x1000, "call -> b", Some(EvidenceKind::Call)),
+            evidence(0x1000, "call -> a", Some(EvidenceKind::Call)),
+        ],
+        basic_blocks: Vec::new(),
+        roots: vec!["c".into(), "a".into()],
+        root_hits: vec![RootHit { root: "c".into(), functions: vec![0x3000, 0x1000], error: None }],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    };
+    let mut shuffled = result.clone();
+    shuffled.functions.reverse();
+    shuffled.call_edges.reverse();
+    shuffled.evidence.reverse();
+
+    canonicalize_result(&mut result);
+    canonicalize_result(&mut shuffled);
+    assert_eq!(result, shuffled);
+
+    let addrs: Vec<u64> = result.functions.iter().map(|f| f.address).collect();
+    assert_eq!(addrs, vec![0x1000, 0x2000, 0x3000]);
+    let edges: Vec<(u64, u64)> = result.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    assert_eq!(edges, vec![(0x1000, 0x2000), (0x1000, 0x3000), (0x2000, 0x3000)]);
+    assert_eq!(result.evidence[0].description, "call -> a");
+    assert_eq!(result.root_hits[0].functions, vec![0x1000, 0x3000]);
+    // Roots keep the order the spec declared them in.
+    assert_eq!(result.roots, vec!["c", "a"]);
+}
+
+#[test]
+fn epochs_accept_rfc3339_or_unix_seconds() {
+    assert_eq!(parse_epoch("0").as_deref(), Some(DETERMINISTIC_EPOCH));
+    assert_eq!(parse_epoch("1700000000").as_deref(), Some("2023-11-14T22:13:20+00:00"));
+    assert_eq!(
+        parse_epoch("2024-01-02T03:04:05+02:00").as_deref(),
+        Some("2024-01-02T01:04:05+00:00")
+    );
+    assert_eq!(parse_epoch("yesterday"), None);
+    assert_eq!(parse_epoch(""), None);
+}
+
+#[test]
+fn run_metadata_timestamp_uses_the_epoch_when_set() {
+    let mut meta = RunMetadata {
+        spec_hash: "spec".into(),
+        binary_id: None,
+        binary_hash: None,
+        backend: "test".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        deterministic: true,
+        epoch: Some(DETERMINISTIC_EPOCH.into()),
+        failed_backends: Vec::new(),
+        spec_path: None,
+        started_at: None,
+        usage: None,
+    };
+    assert_eq!(meta.timestamp(), DETERMINISTIC_EPOCH);
+    meta.epoch = None;
+    assert_ne!(meta.timestamp(), DETERMINISTIC_EPOCH);
+}
+
+#[test]
+fn paths_are_project_relative_or_reduced_to_file_names() {
+    let root = Path::new("/work/project");
+    assert_eq!(normalize_path("/work/project/tools/rizin", root), "tools/rizin");
+    assert_eq!(normalize_path("/usr/local/bin/analyzeHeadless", root), "analyzeHeadless");
+    assert_eq!(normalize_path("rizin", root), "rizin");
+}
@@ -0,0 +1,150 @@
+use ritual_core::services::analysis::{AnalysisResult, CallEdge, FunctionRecord};
+use ritual_core::services::docs::{
+    analyst_sections, anchor_slug, function_anchor, parse_root_list, render_analyst_section,
+    render_toc, replace_analyst_section, slice_doc_link, DocIndex, TocEntry, ANALYST_NOTES,
+    ANALYST_ROOTS,
+};
+
+fn func(address: u64, name: Option<&str>) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: name.map(|n| n.to_string()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn analysis(functions: Vec<FunctionRecord>, call_edges: Vec<CallEdge>) -> AnalysisResult {
+    AnalysisResult {
+        functions,
+        call_edges,
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn anchors_and_toc_render_like_markdown_headings() {
+    assert_eq!(
+        anchor_slug("Unmapped evidence (no matching function)"),
+        "unmapped-evidence-no-matching-function"
+    );
+    assert_eq!(function_anchor(0x1A2B), "fn-0x1a2b");
+    assert_eq!(slice_doc_link("Net Code"), "Net%20Code.md");
+
+    let toc = render_toc(&[
+        TocEntry::section("Functions"),
+        TocEntry { level: 1, title: "main @ 0x10".into(), anchor: function_anchor(0x10) },
+    ]);
+    assert_eq!(toc, "## Contents\n- [Functions](#functions)\n  - [main @ 0x10](#fn-0x10)\n\n");
+}
+
+#[test]
+fn doc_index_links_within_and_across_slices() {
+    let ui = analysis(
+        vec![func(0x1000, Some("ui_init")), func(0x1100, None)],
+        vec![
+            CallEdge {
+                from: 0x1000,
+                to: 0x1100,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: true,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+    );
+    let net = analysis(vec![func(0x2000, Some("send_packet"))], Vec::new());
+
+    let mut index = DocIndex::new();
+    index.add_slice("UI", "libGame.so", &ui);
+    index.add_slice("Net", "libGame.so", &net);
+
+    assert_eq!(index.function_link("UI", "libGame.so", 0x1100).unwrap(), "[0x1100](#fn-0x1100)");
+    assert_eq!(
+        index.function_link("UI", "libGame.so", 0x2000).unwrap(),
+        "[send_packet](Net.md#fn-0x2000) (Net)"
+    );
+    // Same address in another binary is not documented.
+    assert!(index.function_link("UI", "other.so", 0x2000).is_none());
+    assert_eq!(
+        index.slice_function_link("UI", "libGame.so", 0x1000).unwrap(),
+        "[ui_init](UI.md#fn-0x1000)"
+    );
+
+    let backlinks = index.backlinks("Net", "libGame.so", &net);
+    assert_eq!(backlinks.len(), 1);
+    assert_eq!((backlinks[0].slice.as_str(), backlinks[0].from), ("UI", 0x1000));
+    // Intra-slice calls are not backlinks.
+    assert!(index.backlinks("UI", "libGame.so", &ui).is_empty());
+}
+
+#[test]
+fn analyst_sections_round_trip_through_a_doc() {
+    let doc = format!(
+        "# Net\n\n## Analyst notes\n{}\n## Analyst roots\n{}\n## Roots\n- generated\n",
+        render_analyst_section(ANALYST_NOTES, "Handshake lives in `Net::open`.\n\nSee 0x1000."),
+        render_analyst_section(ANALYST_ROOTS, "- Net::open\n* `send_packet`\n- Net::open\ntext"),
+    );
+    let sections = analyst_sections(&doc);
+    assert_eq!(sections[ANALYST_NOTES], "Handshake lives in `Net::open`.\n\nSee 0x1000.");
+    assert_eq!(parse_root_list(&sections[ANALYST_ROOTS]), ["Net::open", "send_packet"]);
+
+    // Empty sections render as bare markers and read back empty.
+    assert_eq!(
+        render_analyst_section(ANALYST_ROOTS, ""),
+        "<!-- analyst:begin roots -->\n<!-- analyst:end roots -->\n"
+    );
+    assert_eq!(analyst_sections(&render_analyst_section("roots", ""))["roots"], "");
+
+    // Unterminated or mismatched sections are ignored.
+    let broken = "<!-- analyst:begin notes -->\nlost\n<!-- analyst:end roots -->\n\
+                  <!-- analyst:begin roots -->\n- a\n";
+    assert!(analyst_sections(broken).is_empty());
+}
+
+#[test]
+fn replacing_an_analyst_section_keeps_the_rest_of_the_doc() {
+    let doc = format!(
+        "# Net\n{}intro\n{}## Roots\n",
+        render_analyst_section(ANALYST_NOTES, "keep me"),
+        render_analyst_section(ANALYST_ROOTS, "- old"),
+    );
+    let updated = replace_analyst_section(&doc, ANALYST_ROOTS, "- Net::open\n- 0x1000").unwrap();
+    assert_eq!(
+        updated,
+        format!(
+            "# Net\n{}intro\n{}## Roots\n",
+            render_analyst_section(ANALYST_NOTES, "keep me"),
+            render_analyst_section(ANALYST_ROOTS, "- Net::open\n- 0x1000"),
+        )
+    );
+    assert_eq!(
+        parse_root_list(&analyst_sections(&updated)[ANALYST_ROOTS]),
+        ["Net::open", "0x1000"]
+    );
+
+    // Missing or unterminated sections are not replaced.
+    assert_eq!(replace_analyst_section("# Net\n", ANALYST_ROOTS, "- a"), None);
+    let broken = "<!-- analyst:begin roots -->\n- a\n<!-- analyst:begin notes -->\n";
+    assert_eq!(replace_analyst_section(broken, ANALYST_ROOTS, "- b"), None);
+}
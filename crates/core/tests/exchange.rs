@@ -0,0 +1,159 @@
+use std::fs;
+
+use ritual_core::db::project_db::CURRENT_SCHEMA_VERSION;
+use ritual_core::db::{
+    BinaryRecord, FunctionAnnotation, ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus,
+};
+use ritual_core::services::analysis::{
+    AnalysisResult, CallEdge, EvidenceRecord, FunctionRecord, RootHit, XrefKind, XrefRecord,
+};
+use ritual_core::services::exchange::{
+    export_run, import_run, read_archive, write_archive, ExchangeError,
+};
+use tempfile::tempdir;
+
+fn project(dir: &std::path::Path) -> (ProjectLayout, ProjectDb) {
+    let layout = ProjectLayout::new(dir);
+    fs::create_dir_all(&layout.meta_dir).unwrap();
+    let db = ProjectDb::open(&layout.db_path).unwrap();
+    (layout, db)
+}
+
+fn seed_run(layout: &ProjectLayout, db: &ProjectDb) -> AnalysisResult {
+    let mut binary = BinaryRecord::new("libNet.so", "bins/libNet.so");
+    binary.hash = Some("hash-net".into());
+    let binary_id = db.insert_binary(&binary).unwrap();
+    let run = RitualRunRecord {
+        binary: "libNet.so".into(),
+        binary_id: Some(binary_id),
+        ritual: "Net".into(),
+        spec_hash: "spec".into(),
+        binary_hash: Some("hash-net".into()),
+        backend: "capstone".into(),
+        backend_version: Some("cs-5".into()),
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    };
+    let run_id = db.insert_ritual_run(&run).unwrap();
+    let analysis = AnalysisResult {
+        functions: vec![FunctionRecord {
+            address: 0x1000,
+            name: Some("send".into()),
+            size: Some(4),
+            in_slice: true,
+            is_boundary: false,
+            mangled_name: None,
+            signature: None,
+            arch: None,
+            fuzzy_hash: None,
+            isa: None,
+        }],
+        call_edges: vec![CallEdge {
+            from: 0x1000,
+            to: 0x2000,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
+        }],
+        evidence: vec![EvidenceRecord {
+            address: 0x1000,
+            description: "string \"hello\"".into(),
+            kind: None,
+            source: None,
+            run_id: None,
+        }],
+        xrefs: vec![XrefRecord {
+            from: 0x1000,
+            to: 0x4000,
+            kind: XrefKind::Immediate,
+            section: Some(".rodata".into()),
+            preview: None,
+        }],
+        basic_blocks: Vec::new(),
+        roots: vec!["send".into()],
+        root_hits: vec![RootHit { root: "send".into(), functions: vec![0x1000], error: None }],
+        sub_slices: Vec::new(),
+        backend_version: Some("cs-5".into()),
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &analysis).unwrap();
+    db.add_run_note(run_id, "vtable at 0x4000", "t3").unwrap();
+    db.upsert_function_annotation(&FunctionAnnotation {
+        binary_hash: "hash-net".into(),
+        address: 0x1000,
+        name: Some("send_packet".into()),
+        note: None,
+        updated_at: "t2".into(),
+    })
+    .unwrap();
+
+    let run_dir = layout.binary_output_root("libNet.so").join("Net");
+    fs::create_dir_all(&run_dir).unwrap();
+    fs::write(run_dir.join("spec.yaml"), "name: Net\n").unwrap();
+    fs::write(run_dir.join("report.json"), "{}").unwrap();
+    analysis
+}
+
+#[test]
+fn run_archive_round_trips_between_projects() {
+    let src = tempdir().unwrap();
+    let (src_layout, src_db) = project(src.path());
+    let analysis = seed_run(&src_layout, &src_db);
+
+    let archive = export_run(&src_db, &src_layout, "libNet.so", "Net").unwrap();
+    assert_eq!(archive.manifest.schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(archive.manifest.files, vec!["report.json".to_string(), "spec.yaml".to_string()]);
+    let mut bytes = Vec::new();
+    write_archive(&archive, &mut bytes).unwrap();
+    let archive = read_archive(bytes.as_slice()).unwrap();
+    assert_eq!(archive.analysis, analysis);
+
+    let dst = tempdir().unwrap();
+    let (dst_layout, dst_db) = project(dst.path());
+    let imported = import_run(&dst_db, &dst_layout, &archive, false).unwrap();
+    assert!(imported.registered_binary);
+    assert_eq!(imported.binary, "libNet.so");
+    let mut expected = analysis;
+    for evidence in &mut expected.evidence {
+        evidence.run_id = Some(imported.run_id);
+    }
+    assert_eq!(dst_db.load_analysis_result("libNet.so", "Net").unwrap(), Some(expected));
+    assert_eq!(dst_db.list_binaries().unwrap()[0].hash.as_deref(), Some("hash-net"));
+    assert_eq!(dst_db.list_function_annotations("hash-net").unwrap().len(), 1);
+    let notes = dst_db.list_run_notes(imported.run_id).unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!((notes[0].note.as_str(), notes[0].created_at.as_str()), ("vtable at 0x4000", "t3"));
+    let run_dir = dst_layout.binary_output_root("libNet.so").join("Net");
+    assert_eq!(fs::read_to_string(run_dir.join("spec.yaml")).unwrap(), "name: Net\n");
+
+    // Re-importing needs force and reuses the now-registered binary.
+    let err = import_run(&dst_db, &dst_layout, &archive, false).unwrap_err();
+    assert!(matches!(err, ExchangeError::OutputExists(_)), "{err}");
+    let again = import_run(&dst_db, &dst_layout, &archive, true).unwrap();
+    assert!(!again.registered_binary);
+    assert_eq!(dst_db.list_binaries().unwrap().len(), 1);
+    assert_eq!(dst_db.list_ritual_runs(Some("libNet.so")).unwrap().len(), 2);
+}
+
+#[test]
+fn archives_from_newer_schemas_are_rejected() {
+    let src = tempdir().unwrap();
+    let (layout, db) = project(src.path());
+    seed_run(&layout, &db);
+    let mut archive = export_run(&db, &layout, "libNet.so", "Net").unwrap();
+    archive.manifest.schema_version = CURRENT_SCHEMA_VERSION + 1;
+    let mut bytes = Vec::new();
+    write_archive(&archive, &mut bytes).unwrap();
+    assert!(matches!(read_archive(bytes.as_slice()), Err(ExchangeError::NewerSchema { .. })));
+
+    let err = export_run(&db, &layout, "libNet.so", "Missing").unwrap_err();
+    assert!(matches!(err, ExchangeError::MissingRun { .. }));
+}
@@ -0,0 +1,89 @@
+use ritual_core::analysis::matching::{match_functions, DEFAULT_MIN_SIMILARITY};
+use ritual_core::services::analysis::FunctionRecord;
+use ritual_core::services::fingerprint::{hash_similarity, mnemonic_hash};
+
+const PROLOGUE: [&str; 12] =
+    ["push", "mov", "sub", "mov", "call", "test", "je", "mov", "add", "pop", "ret", "nop"];
+
+fn body(seed: &[&str], repeat: usize) -> Vec<String> {
+    seed.iter().cycle().take(seed.len() * repeat).map(|m| m.to_string()).collect()
+}
+
+fn func(address: u64, name: Option<&str>, hash: Option<String>) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: name.map(str::to_string),
+        size: Some(0x40),
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: hash,
+        isa: None,
+    }
+}
+
+#[test]
+fn fingerprints_ignore_case_and_spacing_and_track_edits() {
+    let base = body(&PROLOGUE, 4);
+    let hash = mnemonic_hash(&base).unwrap();
+    assert_eq!(hash.len(), 16);
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+    let shouted: Vec<String> = base.iter().map(|m| format!(" {} ", m.to_uppercase())).collect();
+    assert_eq!(mnemonic_hash(&shouted).unwrap(), hash);
+    assert_eq!(hash_similarity(&hash, &hash), Some(1.0));
+
+    // One changed instruction in 48 keeps the fingerprint close; different code does not.
+    let mut edited = base.clone();
+    edited[20] = "xor".into();
+    let edited = mnemonic_hash(&edited).unwrap();
+    assert!(hash_similarity(&hash, &edited).unwrap() >= DEFAULT_MIN_SIMILARITY);
+    let other = mnemonic_hash(&body(&["ldr", "str", "bl", "cmp", "bne", "ldp", "b"], 7)).unwrap();
+    assert!(hash_similarity(&hash, &other).unwrap() < DEFAULT_MIN_SIMILARITY);
+
+    assert_eq!(mnemonic_hash::<&str>(&[]), None);
+    assert_eq!(mnemonic_hash(&["", "  "]), None);
+    assert!(mnemonic_hash(&["ret"]).is_some());
+    assert_eq!(hash_similarity(&hash, "not-a-hash"), None);
+}
+
+#[test]
+fn functions_are_matched_across_moved_addresses() {
+    let a = mnemonic_hash(&body(&PROLOGUE, 4)).unwrap();
+    let mut tweaked = body(&PROLOGUE, 4);
+    tweaked[20] = "xor".into();
+    let a_tweaked = mnemonic_hash(&tweaked).unwrap();
+    let b = mnemonic_hash(&body(&["ldr", "str", "bl", "cmp", "bne", "ldp", "b"], 7)).unwrap();
+    let c = mnemonic_hash(&body(&["fld", "fmul", "fstp", "leave"], 9)).unwrap();
+
+    let from = vec![
+        func(0x1000, Some("init"), Some(a.clone())),
+        func(0x1100, Some("parse"), Some(b.clone())),
+        func(0x1200, Some("gone"), Some(c)),
+        func(0x1300, Some("thunk"), None),
+    ];
+    let to = vec![
+        // Stripped and moved, with a small edit.
+        func(0x2400, None, Some(a_tweaked)),
+        // Two identical copies: the one keeping the name wins.
+        func(0x2000, Some("sub_2000"), Some(b.clone())),
+        func(0x2100, Some("parse"), Some(b)),
+        func(0x2200, Some("fresh"), Some("ffffffffffffffff".into())),
+    ];
+    let matches = match_functions(&from, &to, DEFAULT_MIN_SIMILARITY);
+    let pairs: Vec<(u64, u64)> = matches.matched.iter().map(|m| (m.from, m.to)).collect();
+    assert_eq!(pairs, vec![(0x1000, 0x2400), (0x1100, 0x2100)]);
+    assert!(matches.matched[0].similarity < 1.0);
+    assert_eq!(matches.matched[0].to_name, None);
+    assert_eq!(matches.matched[1].similarity, 1.0);
+    let addrs = |funcs: &[FunctionRecord]| funcs.iter().map(|f| f.address).collect::<Vec<_>>();
+    assert_eq!(addrs(&matches.unmatched_from), vec![0x1200]);
+    assert_eq!(addrs(&matches.unmatched_to), vec![0x2000, 0x2200]);
+    assert_eq!((matches.unhashed_from, matches.unhashed_to), (1, 0));
+
+    // Only identical fingerprints pair at the strictest threshold.
+    let strict = match_functions(&from, &to, 1.0);
+    let pairs: Vec<(u64, u64)> = strict.matched.iter().map(|m| (m.from, m.to)).collect();
+    assert_eq!(pairs, vec![(0x1100, 0x2100)]);
+}
@@ -10,3 +10,155 @@ fn ghidra_backend_is_registered_when_feature_enabled() {
         "ghidra backend should be registered when feature is enabled"
     );
 }
+
+use std::sync::Mutex;
+
+use ritual_core::services::analysis::{
+    AnalysisBackend, AnalysisOptions, AnalysisRequest, BlockEdgeKind, EvidenceKind,
+};
+use ritual_core::services::backends::GhidraBackend;
+use ritual_core::services::signature::CallingConvention;
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn ghidra_request(binary_path: std::path::PathBuf) -> AnalysisRequest {
+    AnalysisRequest {
+        ritual_name: "GhidraRun".into(),
+        binary_name: "GhidraBin".into(),
+        binary_path,
+        roots: vec!["main".into()],
+        options: AnalysisOptions {
+            include_imports: true,
+            include_strings: true,
+            ..Default::default()
+        },
+        arch: None,
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    }
+}
+
+#[test]
+fn ghidra_backend_errors_for_missing_binary() {
+    let req = ghidra_request(std::path::PathBuf::from("does_not_exist.bin"));
+    let err = GhidraBackend.analyze(&req).unwrap_err();
+    assert!(format!("{err:?}").contains("MissingBinary"));
+}
+
+#[test]
+fn ghidra_backend_maps_export_json_without_ghidra_installed() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let bin = temp.path().join("bin");
+    std::fs::write(&bin, b"bin").unwrap();
+
+    let export = temp.path().join("export.json");
+    std::fs::write(
+        &export,
+        r#"{
+            "program": "bin",
+            "language": "x86:LE:64:default",
+            "text_bytes": 4096,
+            "functions": [
+                {"entry": 4096, "name": "main", "size": 32, "signature": "int main(int argc, char **argv)",
+                 "params": 2, "calling_convention": "__cdecl",
+                 "calls": [{"to": 8192, "name": "helper"}],
+                 "blocks": [{"start": 4096, "size": 16, "instructions": 5, "successors": [{"target": 4112, "kind": "cjump"}, {"target": 8192, "kind": "call"}]}]},
+                {"entry": 8192, "name": "helper", "size": 8, "thunk": true}
+            ],
+            "strings": [{"address": 12288, "value": "hello"}],
+            "imports": [{"name": "printf", "address": 16384, "library": "libc.so.6"}]
+        }"#,
+    )
+    .unwrap();
+    std::env::set_var("BS_GHIDRA_FAKE_JSON", &export);
+    std::env::set_var("BS_GHIDRA_FAKE_VERSION", "ghidra 11.0-fake");
+
+    let mut req = ghidra_request(bin);
+    req.backend_path = Some(temp.path().join("analyzeHeadless"));
+    let result = GhidraBackend.analyze(&req);
+    std::env::remove_var("BS_GHIDRA_FAKE_JSON");
+    std::env::remove_var("BS_GHIDRA_FAKE_VERSION");
+    let result = result.expect("analyze fake export");
+
+    assert_eq!(result.functions.len(), 2);
+    assert!(result.functions[1].is_boundary, "thunks are recorded as boundaries");
+    let sig = result.functions[0].signature.as_ref().expect("ghidra signature");
+    assert_eq!((sig.params, sig.convention), (2, CallingConvention::Cdecl));
+    assert_eq!(result.functions[1].signature, None);
+    assert_eq!(result.call_edges.len(), 1);
+    assert_eq!((result.call_edges[0].from, result.call_edges[0].to), (4096, 8192));
+    assert_eq!(result.text_bytes, Some(4096));
+    assert_eq!(result.basic_blocks.len(), 1);
+    assert_eq!((result.basic_blocks[0].byte_len, result.basic_blocks[0].insn_count), (16, Some(5)));
+    assert_eq!(result.basic_blocks[0].successors[0].kind, BlockEdgeKind::ConditionalJump);
+    assert_eq!(result.basic_blocks[0].successors[1].kind, BlockEdgeKind::Call);
+    assert_eq!(result.root_hits[0].functions, vec![4096]);
+    assert!(result
+        .evidence
+        .iter()
+        .any(|e| e.description == "signature: int main(int argc, char **argv)"));
+    assert!(result.evidence.iter().any(|e| e.kind == Some(EvidenceKind::String)));
+    assert!(result.evidence.iter().any(|e| e.description == "import: printf (libc.so.6)"));
+    assert_eq!(result.backend_version.as_deref(), Some("ghidra 11.0-fake"));
+    assert_eq!(
+        result.backend_path,
+        Some(temp.path().join("analyzeHeadless").display().to_string())
+    );
+}
+
+#[test]
+fn ghidra_backend_reads_version_from_install_properties() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let bin = temp.path().join("bin");
+    std::fs::write(&bin, b"bin").unwrap();
+    let install = temp.path().join("ghidra_11.1_PUBLIC");
+    std::fs::create_dir_all(install.join("support")).unwrap();
+    std::fs::create_dir_all(install.join("Ghidra")).unwrap();
+    std::fs::write(
+        install.join("Ghidra").join("application.properties"),
+        "application.name=Ghidra\napplication.version=11.1\n",
+    )
+    .unwrap();
+    let export = temp.path().join("export.json");
+    std::fs::write(&export, r#"{"functions": []}"#).unwrap();
+    std::env::set_var("BS_GHIDRA_FAKE_JSON", &export);
+
+    let mut req = ghidra_request(bin);
+    req.backend_path = Some(install.join("support").join("analyzeHeadless"));
+    let result = GhidraBackend.analyze(&req);
+    std::env::remove_var("BS_GHIDRA_FAKE_JSON");
+
+    assert_eq!(result.expect("analyze").backend_version.as_deref(), Some("ghidra 11.1"));
+}
+
+#[test]
+fn ghidra_capabilities_resolve_the_configured_headless_path() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let missing = temp.path().join("analyzeHeadless");
+    let caps = GhidraBackend.capabilities(Some(&missing));
+    assert!(!caps.is_available());
+    assert!(caps.unavailable.as_deref().unwrap().contains("analyzeHeadless not found"));
+    assert!(caps.decompilation);
+
+    let install = temp.path().join("ghidra_11.1_PUBLIC");
+    std::fs::create_dir_all(install.join("support")).unwrap();
+    std::fs::create_dir_all(install.join("Ghidra")).unwrap();
+    std::fs::write(
+        install.join("Ghidra").join("application.properties"),
+        "application.version=11.1\n",
+    )
+    .unwrap();
+    let headless = install.join("support").join("analyzeHeadless");
+    std::fs::write(&headless, b"#!/bin/sh\n").unwrap();
+    let caps = GhidraBackend.capabilities(Some(&headless));
+    assert!(caps.is_available(), "{:?}", caps.unavailable);
+    assert_eq!(caps.tool_version.as_deref(), Some("ghidra 11.1"));
+    assert_eq!(caps.tool_path.as_deref(), Some(headless.as_path()));
+}
@@ -0,0 +1,74 @@
+use ritual_core::services::analysis::{
+    default_backend_registry, AnalysisBackend, AnalysisOptions, AnalysisRequest,
+};
+use ritual_core::services::backends::GhidraXmlBackend;
+
+const EXPORT: &str = r#"<?xml version="1.0" standalone="yes"?>
+<PROGRAM NAME="game.bin">
+    <FUNCTIONS>
+        <FUNCTION ENTRY_POINT="00401000" NAME="main">
+            <ADDRESS_RANGE START="00401000" END="0040100f" />
+        </FUNCTION>
+        <FUNCTION ENTRY_POINT="00401010" NAME="helper" />
+    </FUNCTIONS>
+</PROGRAM>
+"#;
+
+fn request(backend_path: Option<std::path::PathBuf>) -> AnalysisRequest {
+    AnalysisRequest {
+        ritual_name: "XmlRun".into(),
+        binary_name: "XmlBin".into(),
+        binary_path: "game.bin".into(),
+        roots: vec!["main".into()],
+        options: AnalysisOptions {
+            include_imports: true,
+            include_strings: true,
+            ..Default::default()
+        },
+        arch: None,
+        backend_path,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    }
+}
+
+#[test]
+fn ghidra_xml_backend_is_always_registered() {
+    assert!(default_backend_registry().names().contains(&"ghidra-xml".to_string()));
+    let caps = GhidraXmlBackend.capabilities(None);
+    assert!(caps.is_available());
+    assert!(caps.external_tool.is_none());
+}
+
+#[test]
+fn ghidra_xml_backend_maps_the_export_named_by_backend_path() {
+    let temp = tempfile::tempdir().unwrap();
+    let export = temp.path().join("game.xml");
+    std::fs::write(&export, EXPORT).unwrap();
+
+    let result = GhidraXmlBackend.analyze(&request(Some(export.clone()))).unwrap();
+    let functions: Vec<_> =
+        result.functions.iter().map(|f| (f.address, f.name.as_deref(), f.size)).collect();
+    assert_eq!(functions, [(0x401000, Some("main"), Some(16)), (0x401010, Some("helper"), None)]);
+    assert_eq!(result.root_hits[0].functions, [0x401000]);
+    assert_eq!(result.backend_path.as_deref(), Some(export.to_str().unwrap()));
+    let version = result.backend_version.unwrap();
+    assert!(version.starts_with("export ") && version.len() == "export ".len() + 12, "{version}");
+
+    // A different export is a different version, so cached runs do not outlive it.
+    std::fs::write(&export, EXPORT.replace("helper", "worker")).unwrap();
+    let changed = GhidraXmlBackend.analyze(&request(Some(export))).unwrap();
+    assert_ne!(changed.backend_version.unwrap(), version);
+}
+
+#[test]
+fn ghidra_xml_backend_needs_an_export() {
+    let err = GhidraXmlBackend.analyze(&request(None)).unwrap_err();
+    assert!(err.to_string().contains("set 'ghidra_xml' in the ritual spec"), "{err}");
+    let missing = request(Some("missing.xml".into()));
+    let err = GhidraXmlBackend.analyze(&missing).unwrap_err();
+    assert!(err.to_string().contains("failed to read Ghidra XML export missing.xml"), "{err}");
+}
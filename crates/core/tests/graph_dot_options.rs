@@ -0,0 +1,141 @@
+use ritual_core::analysis::graph::{
+    block_metrics, dot_body, dot_body_with_options, DotOptions, NodeIds, OVERFLOW_NODE_ID,
+};
+use ritual_core::services::analysis::{
+    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, FunctionRecord, SubSlice,
+};
+
+fn func(address: u64, name: &str, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(0x10),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn call(from: u64, to: u64) -> CallEdge {
+    CallEdge { from, to, is_cross_slice: false, to_name: None, to_import: false }
+}
+
+fn block(start: u64, successors: &[(u64, BlockEdgeKind)]) -> BasicBlock {
+    BasicBlock {
+        start,
+        byte_len: 4,
+        insn_count: None,
+        successors: successors
+            .iter()
+            .map(|(target, kind)| BlockEdge { target: *target, kind: kind.clone() })
+            .collect(),
+    }
+}
+
+/// `main` and `ui_init` are in the slice (ui_init in sub-slice `ui`); `net` and `log` are not.
+fn result() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![
+            func(0x1000, "main", true),
+            func(0x1010, "ui_init", true),
+            func(0x1020, "net", false),
+            func(0x1030, "log", false),
+        ],
+        call_edges: vec![call(0x1000, 0x1010), call(0x1000, 0x1010), call(0x1010, 0x1020)],
+        evidence: Vec::new(),
+        basic_blocks: vec![
+            block(0x1000, &[(0x1004, BlockEdgeKind::Fallthrough), (0x1010, BlockEdgeKind::Call)]),
+            block(0x1004, &[(0x1030, BlockEdgeKind::Jump)]),
+            block(0x1010, &[(0x1020, BlockEdgeKind::Call)]),
+        ],
+        roots: vec!["main".into()],
+        root_hits: Vec::new(),
+        sub_slices: vec![SubSlice {
+            label: "ui".into(),
+            roots: vec!["ui_init".into()],
+            functions: vec![0x1010],
+        }],
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+fn render(options: DotOptions) -> String {
+    let result = result();
+    dot_body_with_options(&result, &NodeIds::new(&result), &options)
+}
+
+#[test]
+fn default_options_match_the_flat_rendering() {
+    assert_eq!(render(DotOptions::default()), dot_body(&result()));
+}
+
+#[test]
+fn parallel_edges_merge_with_counts() {
+    let dot = render(DotOptions { dedup_edges: true, ..Default::default() });
+    assert!(dot.contains("\"fn:main\" -> \"fn:ui_init\" [label=\"call x2\" count=2];"), "{dot}");
+    assert_eq!(dot.matches("\"fn:main\" -> \"fn:ui_init\"").count(), 1, "{dot}");
+}
+
+#[test]
+fn collapsed_blocks_keep_only_inter_function_jumps() {
+    let dot = render(DotOptions { collapse_blocks: true, ..Default::default() });
+    assert!(!dot.contains("shape=ellipse"), "{dot}");
+    // The tail jump from main's second block becomes a function-level edge; calls are not
+    // repeated from block successors and the intra-function fallthrough disappears.
+    assert!(dot.contains("\"fn:main\" -> \"fn:log\" [label=\"jump\"];"), "{dot}");
+    assert!(!dot.contains("fallthrough"), "{dot}");
+    assert_eq!(dot.matches("-> \"fn:ui_init\"").count(), 2, "{dot}");
+}
+
+#[test]
+fn clusters_group_sub_slices_and_the_rest_of_the_slice() {
+    let dot = render(DotOptions { cluster: true, ..Default::default() });
+    let slice = dot.find("subgraph \"cluster_slice\" {").expect("slice cluster");
+    let ui = dot.find("subgraph \"cluster_ui\" {").expect("ui cluster");
+    let ui_body = &dot[ui..ui + dot[ui..].find("  }\n").unwrap()];
+    assert!(ui_body.contains("\"fn:ui_init\" [label"), "{dot}");
+    assert!(ui_body.contains("\"fn:ui_init+0x0\" [label"), "blocks follow their function");
+    let slice_body = &dot[slice..slice + dot[slice..].find("  }\n").unwrap()];
+    assert!(slice_body.contains("\"fn:main\" [label") && !slice_body.contains("fn:net"));
+    assert!(dot.contains("\n  \"fn:net\" [label"), "out-of-slice functions stay top-level");
+}
+
+#[test]
+fn node_cap_keeps_slice_functions_and_reroutes_to_overflow() {
+    let dot = render(DotOptions { max_nodes: Some(3), dedup_edges: true, ..Default::default() });
+    // Nodes: 4 functions + 3 blocks; in-slice functions win, then other functions by order.
+    assert!(dot.contains("\"fn:main\" [label") && dot.contains("\"fn:ui_init\" [label"));
+    assert!(dot.contains("\"fn:net\" [label") && !dot.contains("\"fn:log\" [label"));
+    assert!(!dot.contains("shape=ellipse"), "{dot}");
+    assert!(
+        dot.contains(&format!("\"{OVERFLOW_NODE_ID}\" [label=\"+4 more nodes\" shape=note")),
+        "{dot}"
+    );
+    // Edges between dropped nodes vanish; edges into them point at the overflow node.
+    assert!(!dot.contains("\"overflow\" -> \"overflow\""), "{dot}");
+    assert!(dot.contains("\"fn:main\" -> \"fn:ui_init\""), "{dot}");
+
+    let untouched = render(DotOptions { max_nodes: Some(100), ..Default::default() });
+    assert!(!untouched.contains(OVERFLOW_NODE_ID));
+}
+
+#[test]
+fn block_labels_show_bytes_and_instruction_counts() {
+    let dot = render(DotOptions::default());
+    assert!(dot.contains("\\n4 bytes\" shape=ellipse"), "{dot}");
+
+    let mut counted = block(0x10, &[]);
+    counted.insn_count = Some(3);
+    assert_eq!(block_metrics(&counted), "4 bytes, 3 insns");
+    counted.byte_len = 0;
+    assert_eq!(block_metrics(&counted), "3 insns");
+    counted.insn_count = None;
+    assert_eq!(block_metrics(&counted), "size unknown");
+}
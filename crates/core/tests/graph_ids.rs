@@ -0,0 +1,97 @@
+use ritual_core::analysis::graph::{dot_body, function_content_hash, NodeIds};
+use ritual_core::services::analysis::{
+    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind, EvidenceRecord,
+    FunctionRecord,
+};
+
+fn func(address: u64, name: Option<&str>, size: u32) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: name.map(|n| n.to_string()),
+        size: Some(size),
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+/// One "build" of a tiny program placed at `base`.
+fn build(base: u64) -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![
+            func(base, Some("main"), 0x20),
+            func(base + 0x40, None, 0x10),
+            func(base + 0x80, Some("dup"), 0x8),
+            func(base + 0xC0, Some("dup"), 0x4),
+        ],
+        call_edges: vec![CallEdge {
+            from: base,
+            to: base + 0x40,
+            is_cross_slice: false,
+            to_name: None,
+            to_import: false,
+        }],
+        basic_blocks: vec![
+            BasicBlock {
+                start: base,
+                byte_len: 0x10,
+                insn_count: None,
+                successors: vec![BlockEdge { target: base + 0x10, kind: BlockEdgeKind::Jump }],
+            },
+            BasicBlock {
+                start: base + 0x10,
+                byte_len: 0x10,
+                insn_count: None,
+                successors: vec![BlockEdge { target: base + 0x40, kind: BlockEdgeKind::Call }],
+            },
+        ],
+        evidence: vec![EvidenceRecord {
+            address: base + 0x44,
+            description: "string: hello".into(),
+            kind: Some(EvidenceKind::String),
+            source: None,
+            run_id: None,
+        }],
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn node_ids_prefer_names_and_fall_back_to_content_hash() {
+    let result = build(0x1000);
+    let ids = NodeIds::new(&result);
+    assert_eq!(ids.function(0x1000), "fn:main");
+    let unnamed = ids.function(0x1040);
+    assert_eq!(unnamed, format!("sub:{}", function_content_hash(&result.functions[1], &result)));
+    assert_eq!(unnamed.len(), "sub:".len() + 12);
+    // Duplicate names are disambiguated by content hash.
+    assert!(ids.function(0x1080).starts_with("fn:dup#"));
+    assert_ne!(ids.function(0x1080), ids.function(0x10C0));
+    // Blocks are addressed relative to their function; unknown addresses keep the address.
+    assert_eq!(ids.block(0x1010), "fn:main+0x10");
+    assert_eq!(ids.block(0x9000), "bb:0x9000");
+    assert_eq!(ids.function(0x9000), "addr:0x9000");
+}
+
+#[test]
+fn node_ids_are_stable_across_rebased_builds() {
+    let old = dot_body(&build(0x1000));
+    let new = dot_body(&build(0x8000));
+    let ids = |dot: &str| -> Vec<String> {
+        dot.lines().map(|l| l.split(" [").next().unwrap_or_default().to_string()).collect()
+    };
+    assert_eq!(ids(&old), ids(&new));
+    assert!(old.contains("\"fn:main\" [label=\"main\" shape=box addr=\"0x1000\"];"));
+    assert!(new.contains("\"fn:main\" [label=\"main\" shape=box addr=\"0x8000\"];"));
+    assert!(old.contains("\"fn:main+0x0\" -> \"fn:main+0x10\" [label=\"jump\"];"));
+}
@@ -0,0 +1,84 @@
+use std::fs;
+
+use ritual_core::db::{
+    project_db::CURRENT_SCHEMA_VERSION, ProjectDb, ProjectLayout, RitualRunRecord, RitualRunStatus,
+};
+use ritual_core::services::health::{audit_outputs, MissingOutput, OrphanedOutput};
+
+fn run(binary: &str, ritual: &str, status: RitualRunStatus) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        ritual: ritual.into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status,
+        started_at: "now".into(),
+        finished_at: "now".into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+#[test]
+fn audit_outputs_matches_latest_runs_against_output_dirs() {
+    let temp = tempfile::tempdir().unwrap();
+    let layout = ProjectLayout::new(temp.path());
+    for (binary, ritual) in [("Bin", "Net"), ("Bin", "Old"), ("Other", "Net")] {
+        fs::create_dir_all(layout.binary_output_root(binary).join(ritual)).unwrap();
+    }
+    let runs = vec![
+        (1, run("Bin", "Net", RitualRunStatus::Succeeded)),
+        (2, run("Bin", "Net", RitualRunStatus::Failed)),
+        (3, run("Bin", "Gone", RitualRunStatus::Succeeded)),
+        (4, run("Bin", "Purged", RitualRunStatus::Purged)),
+        (5, run("Bin", "Busy", RitualRunStatus::Running)),
+    ];
+
+    let audit = audit_outputs(&layout, &runs).unwrap();
+    assert_eq!(
+        audit.orphaned,
+        [
+            OrphanedOutput {
+                binary: "Bin".into(),
+                ritual: "Old".into(),
+                path: layout.binary_output_root("Bin").join("Old"),
+            },
+            OrphanedOutput {
+                binary: "Other".into(),
+                ritual: "Net".into(),
+                path: layout.binary_output_root("Other").join("Net"),
+            },
+        ]
+    );
+    // Purged and running runs are not expected to have outputs.
+    assert_eq!(
+        audit.missing,
+        [MissingOutput {
+            run_id: 3,
+            binary: "Bin".into(),
+            ritual: "Gone".into(),
+            path: layout.binary_output_root("Bin").join("Gone"),
+        }]
+    );
+    assert!(!audit.is_clean());
+
+    let empty = ProjectLayout::new(temp.path().join("empty"));
+    assert!(audit_outputs(&empty, &[]).unwrap().is_clean());
+}
+
+#[test]
+fn schema_version_at_reads_without_migrating() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("proj.db");
+    drop(ProjectDb::open(&path).unwrap());
+    assert_eq!(ProjectDb::schema_version_at(&path).unwrap(), CURRENT_SCHEMA_VERSION);
+    assert!(ProjectDb::schema_version_at(&temp.path().join("missing.db")).is_err());
+    assert!(!temp.path().join("missing.db").exists());
+}
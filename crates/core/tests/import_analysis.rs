@@ -0,0 +1,407 @@
+use ritual_core::services::analysis::{BlockEdge, BlockEdgeKind, EvidenceKind, XrefKind};
+use ritual_core::services::import::symbols::{parse_symbols, SymbolFormat};
+use ritual_core::services::import::{import_analysis, ImportError, ImportFormat};
+
+const SHA: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+/// Minimal protobuf writer for building BinExport2 messages.
+#[derive(Default)]
+struct Msg(Vec<u8>);
+
+impl Msg {
+    fn varint(buf: &mut Vec<u8>, mut v: u64) {
+        while v >= 0x80 {
+            buf.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        buf.push(v as u8);
+    }
+
+    fn uint(mut self, field: u64, v: u64) -> Self {
+        Self::varint(&mut self.0, field << 3);
+        Self::varint(&mut self.0, v);
+        self
+    }
+
+    fn bytes(mut self, field: u64, b: &[u8]) -> Self {
+        Self::varint(&mut self.0, (field << 3) | 2);
+        Self::varint(&mut self.0, b.len() as u64);
+        self.0.extend_from_slice(b);
+        self
+    }
+
+    fn str(self, field: u64, s: &str) -> Self {
+        self.bytes(field, s.as_bytes())
+    }
+
+    fn msg(self, field: u64, m: Msg) -> Self {
+        self.bytes(field, &m.0)
+    }
+
+    fn packed(self, field: u64, values: &[u64]) -> Self {
+        let mut buf = Vec::new();
+        for v in values {
+            Self::varint(&mut buf, *v);
+        }
+        self.bytes(field, &buf)
+    }
+}
+
+fn insn(address: Option<u64>, len: usize) -> Msg {
+    let m = match address {
+        Some(a) => Msg::default().uint(1, a),
+        None => Msg::default(),
+    };
+    m.bytes(5, &vec![0x90; len])
+}
+
+fn vertex(address: u64, kind: u64, mangled: &str) -> Msg {
+    Msg::default().uint(1, address).uint(2, kind).str(3, mangled)
+}
+
+/// Two functions (`Example::init()` at 0x1000 with two blocks, `helper` at 0x2000) calling
+/// each other and an import, plus a string reference and two sections.
+fn binexport() -> Vec<u8> {
+    let meta = Msg::default().str(1, "game").str(2, SHA).str(3, "x86-64");
+    let call_graph = Msg::default()
+        .msg(1, vertex(0x1000, 0, "_ZN7Example4initEv").str(4, "Example::init()"))
+        .msg(1, vertex(0x2000, 0, "helper"))
+        .msg(1, vertex(0x3000, 2, "recv").uint(5, 0))
+        .msg(2, Msg::default().uint(1, 0).uint(2, 1))
+        .msg(2, Msg::default().uint(1, 0).uint(2, 2))
+        .msg(2, Msg::default().uint(1, 0).uint(2, 1));
+    Msg::default()
+        .msg(1, meta)
+        // 0x1000 (4 bytes), 0x1004 (2), 0x1006 (1); 0x2000 (5), 0x2005 (1).
+        .msg(5, insn(Some(0x1000), 4))
+        .msg(5, insn(None, 2))
+        .msg(5, insn(None, 1))
+        .msg(5, insn(Some(0x2000), 5))
+        .msg(5, insn(None, 1))
+        .msg(6, Msg::default().msg(1, Msg::default().uint(1, 0).uint(2, 2)))
+        .msg(6, Msg::default().msg(1, Msg::default().uint(1, 2)))
+        .msg(6, Msg::default().msg(1, Msg::default().uint(1, 3).uint(2, 5)))
+        .msg(
+            7,
+            Msg::default()
+                .packed(1, &[0, 1])
+                .uint(3, 0)
+                .msg(2, Msg::default().uint(1, 0).uint(2, 1).uint(3, 1)),
+        )
+        // Unpacked indexes are accepted too.
+        .msg(7, Msg::default().uint(1, 2).uint(3, 2))
+        .msg(8, call_graph)
+        .str(9, "hello")
+        .msg(11, Msg::default().uint(1, 1).uint(4, 0))
+        .msg(13, Msg::default().uint(1, 0x1000).uint(2, 0x2000).uint(5, 1))
+        .msg(13, Msg::default().uint(1, 0x4000).uint(2, 0x100).uint(5, 0))
+        .msg(14, Msg::default().str(3, "libc.so.6"))
+        .0
+}
+
+#[test]
+fn binexport_maps_call_graph_flow_graphs_and_references() {
+    let imported =
+        import_analysis(ImportFormat::BinExport, &binexport(), None, &["helper".to_string()])
+            .unwrap();
+    assert_eq!(imported.executable_name.as_deref(), Some("game"));
+    assert_eq!(imported.architecture.as_deref(), Some("x86-64"));
+    assert_eq!(imported.matches_hash(&SHA.to_uppercase()), Some(true));
+    assert_eq!(imported.matches_hash("00"), Some(false));
+
+    let result = imported.result;
+    let functions: Vec<_> = result
+        .functions
+        .iter()
+        .map(|f| (f.address, f.name.as_deref(), f.size, f.is_boundary))
+        .collect();
+    assert_eq!(
+        functions,
+        [
+            (0x1000, Some("Example::init()"), Some(7), false),
+            (0x2000, Some("helper"), Some(6), false),
+            (0x3000, Some("recv"), None, true),
+        ]
+    );
+    assert_eq!(result.functions[0].mangled_name.as_deref(), Some("_ZN7Example4initEv"));
+
+    let blocks: Vec<_> =
+        result.basic_blocks.iter().map(|b| (b.start, b.byte_len, b.insn_count)).collect();
+    assert_eq!(blocks, [(0x1000, 6, Some(2)), (0x1006, 1, Some(1)), (0x2000, 6, Some(2))]);
+    assert_eq!(
+        result.basic_blocks[0].successors,
+        [BlockEdge { target: 0x1006, kind: BlockEdgeKind::ConditionalJump }]
+    );
+
+    let edges: Vec<_> = result.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    assert_eq!(edges, [(0x1000, 0x2000), (0x1000, 0x3000)]);
+    let evidence: Vec<_> = result
+        .evidence
+        .iter()
+        .map(|e| (e.address, e.kind.clone(), e.description.as_str()))
+        .collect();
+    assert!(evidence.contains(&(0x1000, Some(EvidenceKind::Call), "call -> helper")));
+    assert!(evidence.contains(&(0x3000, Some(EvidenceKind::Import), "import: recv (libc.so.6)")));
+    assert!(evidence.contains(&(0x1004, Some(EvidenceKind::String), "string: hello")));
+
+    assert_eq!(result.text_bytes, Some(0x2000));
+    assert_eq!(result.roots, ["helper"]);
+    assert_eq!(result.root_hits[0].functions, [0x2000]);
+}
+
+#[test]
+fn malformed_binexport_is_an_error() {
+    let data = binexport();
+    let err =
+        import_analysis(ImportFormat::BinExport, &data[..data.len() - 3], None, &[]).unwrap_err();
+    assert!(matches!(err, ImportError::BinExport(_)), "{err}");
+    let err =
+        import_analysis(ImportFormat::BinExport, b"{\"functions\": []}", None, &[]).unwrap_err();
+    assert!(err.to_string().starts_with("Invalid BinExport file"), "{err}");
+}
+
+#[test]
+fn ghidra_json_uses_the_export_script_format() {
+    let json = format!(
+        r#"{{"program": "game", "sha256": "{SHA}", "language": "x86:LE:64:default",
+            "text_bytes": 4096,
+            "functions": [
+              {{"entry": 4096, "name": "net_init", "size": 32, "calls": [{{"to": 8192, "name": "send"}}],
+                "blocks": [{{"start": 4096, "size": 32, "instructions": 9}}]}},
+              {{"entry": 8192, "name": "send", "thunk": true}}
+            ],
+            "strings": [{{"address": 12288, "value": "connect"}}],
+            "imports": [{{"name": "send", "address": 8192, "library": "libc.so.6"}}]}}"#
+    );
+    let imported =
+        import_analysis(ImportFormat::GhidraJson, json.as_bytes(), None, &["net_init".to_string()])
+            .unwrap();
+    assert_eq!(imported.executable_name.as_deref(), Some("game"));
+    assert_eq!(imported.architecture.as_deref(), Some("x86:LE:64:default"));
+    assert_eq!(imported.matches_hash(SHA), Some(true));
+    let result = imported.result;
+    assert_eq!(result.functions.len(), 2);
+    assert!(result.functions[1].is_boundary);
+    assert_eq!(result.call_edges.len(), 1);
+    assert_eq!(result.basic_blocks[0].insn_count, Some(9));
+    assert_eq!(result.text_bytes, Some(4096));
+    assert!(result.evidence.iter().any(|e| e.description == "import: send (libc.so.6)"));
+    assert!(result.evidence.iter().any(|e| e.description == "string: connect"));
+    assert_eq!(result.root_hits[0].functions, [4096]);
+
+    assert!(matches!(
+        import_analysis(ImportFormat::GhidraJson, b"not json", None, &[]).unwrap_err(),
+        ImportError::Json(_)
+    ));
+}
+
+#[test]
+fn import_formats_parse_by_name() {
+    for format in ImportFormat::ALL {
+        assert_eq!(ImportFormat::parse(format.as_str()).unwrap(), format);
+    }
+    let err = ImportFormat::parse("ida").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown import format 'ida' (expected binexport, ghidra-json, or ghidra-xml)"
+    );
+}
+
+/// Ghidra XML export of three functions: `net_init` calls `net_send` (named only by the symbol
+/// table) twice and references a string, `net_send` calls `send` through an import slot, and
+/// `memcpy` is a library function. `.rdata` bytes live at offset 0x100 of `game.bytes`.
+const GHIDRA_XML: &str = r#"<?xml version="1.0" standalone="yes"?>
+<?program_dtd version="1"?>
+<PROGRAM NAME="game.bin" EXE_FORMAT="Executable and Linking Format (ELF)" IMAGE_BASE="00400000">
+    <PROCESSOR NAME="x86" LANGUAGE_PROVIDER="x86:LE:64:default" ENDIAN="little" />
+    <MEMORY_MAP>
+        <MEMORY_SECTION NAME=".text" START_ADDR="00401000" LENGTH="0x100" PERMISSIONS="r x">
+            <MEMORY_CONTENTS FILE_NAME="game.bytes" FILE_OFFSET="0x0" />
+        </MEMORY_SECTION>
+        <MEMORY_SECTION NAME=".rdata" START_ADDR="ram:00402000" LENGTH="0x20" PERMISSIONS="r">
+            <MEMORY_CONTENTS FILE_NAME="game.bytes" FILE_OFFSET="0x100" />
+        </MEMORY_SECTION>
+    </MEMORY_MAP>
+    <DATA>
+        <DEFINED_DATA ADDRESS="00402000" DATATYPE="string" DATATYPE_NAMESPACE="/" SIZE="0xb" />
+        <DEFINED_DATA ADDRESS="00402010" DATATYPE="unicode" DATATYPE_NAMESPACE="/" SIZE="0x6" />
+        <DEFINED_DATA ADDRESS="00402018" DATATYPE="dword" DATATYPE_NAMESPACE="/" SIZE="0x4" />
+    </DATA>
+    <SYMBOL_TABLE>
+        <SYMBOL ADDRESS="00401020" NAME="net_send_alias" NAMESPACE="" TYPE="global" PRIMARY="n" />
+        <SYMBOL ADDRESS="00401020" NAME="net_send" NAMESPACE="" TYPE="global" PRIMARY="y" />
+        <SYMBOL ADDRESS="EXTERNAL:00000001" NAME="send" NAMESPACE="libc.so.6" PRIMARY="y" />
+    </SYMBOL_TABLE>
+    <MARKUP>
+        <MEMORY_REFERENCE ADDRESS="00401004" TO_ADDRESS="00401020" OPERAND_INDEX="0x0" PRIMARY="y" />
+        <MEMORY_REFERENCE ADDRESS="00401008" TO_ADDRESS="00402000" OPERAND_INDEX="0x1" PRIMARY="y" />
+        <MEMORY_REFERENCE ADDRESS="0040100c" TO_ADDRESS="00401020" OPERAND_INDEX="0x0" PRIMARY="y" />
+        <MEMORY_REFERENCE ADDRESS="00401024" TO_ADDRESS="00403000" OPERAND_INDEX="0x0" PRIMARY="y" />
+        <MEMORY_REFERENCE ADDRESS="00405000" TO_ADDRESS="00401000" OPERAND_INDEX="0x0" PRIMARY="y" />
+        <EXT_LIBRARY_REFERENCE ADDRESS="00403000" OPERAND_INDEX="0x0" LIB_PROG_NAME="libc.so.6" LIB_LABEL="send" />
+    </MARKUP>
+    <FUNCTIONS>
+        <FUNCTION ENTRY_POINT="00401000" NAME="net_init" LIBRARY_FUNCTION="n">
+            <ADDRESS_RANGE START="00401000" END="0040101f" />
+            <TYPEINFO_CMT>int net_init(void)</TYPEINFO_CMT>
+        </FUNCTION>
+        <FUNCTION ENTRY_POINT="00401020" NAME="FUN_00401020" LIBRARY_FUNCTION="n">
+            <ADDRESS_RANGE START="00401020" END="0040102f" />
+        </FUNCTION>
+        <FUNCTION ENTRY_POINT="00401040" NAME="memcpy" LIBRARY_FUNCTION="y">
+            <ADDRESS_RANGE START="00401040" END="00401047" />
+            <ADDRESS_RANGE START="00401050" END="00401057" />
+        </FUNCTION>
+    </FUNCTIONS>
+</PROGRAM>
+"#;
+
+/// `game.bytes` for [`GHIDRA_XML`]: zeroed `.text`, then `.rdata` with an ASCII and a UTF-16
+/// string.
+fn ghidra_xml_bytes() -> Vec<u8> {
+    let mut bytes = vec![0; 0x100];
+    let mut rdata = b"connect %s\0".to_vec();
+    rdata.resize(0x10, 0);
+    rdata.extend_from_slice(b"h\0i\0\0\0");
+    rdata.resize(0x20, 0);
+    bytes.extend(rdata);
+    bytes
+}
+
+#[test]
+fn ghidra_xml_maps_functions_symbols_and_references() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("game.xml");
+    std::fs::write(temp.path().join("game.bytes"), ghidra_xml_bytes()).unwrap();
+
+    let imported = import_analysis(
+        ImportFormat::GhidraXml,
+        GHIDRA_XML.as_bytes(),
+        Some(&path),
+        &["net_send".to_string()],
+    )
+    .unwrap();
+    assert_eq!(imported.executable_name.as_deref(), Some("game.bin"));
+    assert_eq!(imported.architecture.as_deref(), Some("x86:LE:64:default"));
+    assert_eq!(imported.matches_hash(SHA), None);
+
+    let result = imported.result;
+    let functions: Vec<_> = result
+        .functions
+        .iter()
+        .map(|f| (f.address, f.name.as_deref(), f.size, f.is_boundary))
+        .collect();
+    assert_eq!(
+        functions,
+        [
+            (0x401000, Some("net_init"), Some(32), false),
+            (0x401020, Some("net_send"), Some(16), false),
+            (0x401040, Some("memcpy"), Some(16), true),
+        ]
+    );
+    let edges: Vec<_> = result.call_edges.iter().map(|e| (e.from, e.to)).collect();
+    assert_eq!(edges, [(0x401000, 0x401020), (0x401020, 0x403000)]);
+    // References from outside any function are dropped.
+    let xrefs: Vec<_> =
+        result.xrefs.iter().map(|x| (x.from, x.kind, x.section.as_deref())).collect();
+    assert_eq!(
+        xrefs,
+        [
+            (0x401004, XrefKind::Call, Some(".text")),
+            (0x401008, XrefKind::Memory, Some(".rdata")),
+            (0x40100c, XrefKind::Call, Some(".text")),
+            (0x401024, XrefKind::Call, None),
+        ]
+    );
+
+    let evidence: Vec<_> = result
+        .evidence
+        .iter()
+        .map(|e| (e.address, e.kind.clone(), e.description.as_str()))
+        .collect();
+    for expected in [
+        (0x401000, Some(EvidenceKind::Other), "signature: int net_init(void)"),
+        (0x401000, Some(EvidenceKind::Call), "call -> net_send"),
+        (0x401020, Some(EvidenceKind::Call), "call -> send"),
+        (0x402000, Some(EvidenceKind::String), "string: connect %s"),
+        (0x402010, Some(EvidenceKind::String), "string: hi"),
+        (0x403000, Some(EvidenceKind::Import), "import: send (libc.so.6)"),
+    ] {
+        assert!(evidence.contains(&expected), "{expected:?} in {evidence:?}");
+    }
+    assert_eq!(evidence.len(), 6);
+    assert!(result.evidence.iter().all(|e| e.source.as_ref().unwrap().backend == "ghidra-xml"));
+
+    assert_eq!(result.text_bytes, Some(0x100));
+    assert_eq!(result.root_hits[0].functions, [0x401020]);
+
+    // Without the `.bytes` file there are no string values to read.
+    let without_bytes =
+        import_analysis(ImportFormat::GhidraXml, GHIDRA_XML.as_bytes(), None, &[]).unwrap();
+    assert!(!without_bytes.result.evidence.iter().any(|e| e.kind == Some(EvidenceKind::String)));
+    assert_eq!(without_bytes.result.functions.len(), 3);
+}
+
+#[test]
+fn malformed_ghidra_xml_is_an_error() {
+    let err = import_analysis(ImportFormat::GhidraXml, b"<PROGRAM>", None, &[]).unwrap_err();
+    assert!(matches!(err, ImportError::Xml(_)), "{err}");
+    let err = import_analysis(ImportFormat::GhidraXml, b"<BinExport/>", None, &[]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid Ghidra XML export: root element is <BinExport>, expected <PROGRAM>"
+    );
+}
+
+#[test]
+fn symbol_maps_read_ida_nm_and_objcopy_lines() {
+    let text = "\
+ Start         Length     Name                   Class
+ 0001:00401000 00001000H .text                  CODE
+
+  Address         Publics by Value
+
+ 0001:00401000       _start
+ 0001:00401020       net_init
+0000000000401040 T net_send
+                 U puts
+--add-symbol render=0x401080,global,function
+0x4010a0 draw_frame
+; comment
+Program entry point at 0001:00401000
+";
+    let map = parse_symbols(SymbolFormat::Map, text, 0).unwrap();
+    let symbols: Vec<_> = map.symbols.iter().map(|(a, n)| (*a, n.as_str())).collect();
+    assert_eq!(
+        symbols,
+        vec![
+            (0x401000, "_start"),
+            (0x401020, "net_init"),
+            (0x401040, "net_send"),
+            (0x401080, "render"),
+            (0x4010a0, "draw_frame"),
+        ]
+    );
+    assert!(map.skipped > 0);
+}
+
+#[test]
+fn symbol_json_reads_frida_exports_relative_to_a_base() {
+    let text = r#"[
+        {"type": "function", "name": "net_init", "address": "0x7f0000001000"},
+        {"type": "variable", "name": "g_state", "address": "0x7f0000009000"},
+        {"name": "net_send", "address": 139637976735744}
+    ]"#;
+    let map = parse_symbols(SymbolFormat::Json, text, 0x7f00_0000_0000).unwrap();
+    let symbols: Vec<_> = map.symbols.iter().map(|(a, n)| (*a, n.as_str())).collect();
+    assert_eq!(symbols, vec![(0x1000, "net_init"), (0x2000, "net_send")]);
+    assert_eq!(map.skipped, 1);
+
+    let err = parse_symbols(SymbolFormat::Json, text, 0x7f00_0000_2000).unwrap_err();
+    assert!(matches!(err, ImportError::SymbolBelowBase { .. }), "{err}");
+    let err = parse_symbols(SymbolFormat::Json, r#"[{"name": "x", "address": true}]"#, 0);
+    assert!(err.unwrap_err().to_string().contains("invalid address true for symbol 'x'"));
+    let err = parse_symbols(SymbolFormat::Map, "just some text\n", 0).unwrap_err();
+    assert!(matches!(err, ImportError::NoSymbols), "{err}");
+    assert!(matches!(SymbolFormat::parse("ida"), Err(ImportError::UnknownSymbolFormat(_))));
+}
@@ -0,0 +1,117 @@
+use ritual_core::services::loader::{ImageFormat, ImageLayout, MappedRegion};
+
+fn region(
+    name: &str,
+    address: u64,
+    virtual_size: u64,
+    file_offset: u64,
+    file_size: u64,
+) -> MappedRegion {
+    MappedRegion {
+        name: name.into(),
+        address,
+        virtual_size,
+        file_offset,
+        file_size,
+        executable: name == ".text",
+    }
+}
+
+/// PE32+ at the default DLL base: `.text` at RVA 0x1000 (file 0x400), `.data` at RVA 0x2000
+/// with 0x200 raw bytes and a 0x800 zero-filled tail.
+fn pe_layout() -> ImageLayout {
+    ImageLayout {
+        format: ImageFormat::Pe,
+        image_base: 0x1_8000_0000,
+        entry: Some(0x1_8000_1000),
+        sections: vec![
+            region(".text", 0x1_8000_1000, 0x180, 0x400, 0x200),
+            region(".data", 0x1_8000_2000, 0xA00, 0x600, 0x200),
+        ],
+        segments: Vec::new(),
+    }
+}
+
+#[test]
+fn pe_addresses_convert_between_rva_va_and_file_offset() {
+    let layout = pe_layout();
+    assert_eq!(layout.rva_to_va(0x1010), 0x1_8000_1010);
+    assert_eq!(layout.va_to_rva(0x1_8000_1010), Some(0x1010));
+    assert_eq!(layout.va_to_rva(0x1000), None);
+
+    assert_eq!(layout.rva_to_file_offset(0x1010), Some(0x410));
+    assert_eq!(layout.va_to_file_offset(0x1_8000_2010), Some(0x610));
+    assert_eq!(layout.file_offset_to_va(0x410), Some(0x1_8000_1010));
+    // Raw padding past VirtualSize is not mapped.
+    assert_eq!(layout.file_offset_to_va(0x590), None);
+    assert_eq!(layout.va_to_file_offset(0x1_8000_0000), None);
+}
+
+#[test]
+fn zero_fill_tails_have_no_file_bytes() {
+    let layout = pe_layout();
+    let tail = 0x1_8000_2300;
+    assert_eq!(layout.region_for(tail).map(|r| r.name.as_str()), Some(".data"));
+    assert_eq!(layout.va_to_file_offset(tail), None);
+    assert_eq!(layout.file_range(tail, Some(4), 0x1000), None);
+}
+
+#[test]
+fn file_ranges_stop_at_the_file_backed_part_of_a_region() {
+    let layout = pe_layout();
+    // Without a size the range runs to the end of VirtualSize (smaller than the raw size).
+    assert_eq!(layout.file_range(0x1_8000_1100, None, 0x1000), Some((0x500, 0x580)));
+    assert_eq!(layout.file_range(0x1_8000_1100, Some(0x10), 0x1000), Some((0x500, 0x510)));
+    assert_eq!(layout.file_range(0x1_8000_2000, None, 0x1000), Some((0x600, 0x800)));
+    // Truncated files clamp the range.
+    assert_eq!(layout.file_range(0x1_8000_2000, None, 0x700), Some((0x600, 0x700)));
+}
+
+#[test]
+fn code_regions_fall_back_to_executable_segments() {
+    let mut layout = pe_layout();
+    let names: Vec<&str> = layout.code_regions().iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec![".text"]);
+
+    layout.format = ImageFormat::Elf;
+    layout.sections.clear();
+    layout.segments =
+        vec![MappedRegion { executable: true, ..region("", 0x40_0000, 0x100, 0, 0x100) }];
+    assert_eq!(layout.code_regions().len(), 1);
+    assert_eq!(layout.va_to_file_offset(0x40_0010), Some(0x10));
+}
+
+#[test]
+fn raw_layout_maps_the_whole_file_at_the_load_address() {
+    let layout = ImageLayout::raw(0x800_0000, 0x400);
+    assert_eq!(layout.format, ImageFormat::Raw);
+    assert_eq!(layout.image_base, 0x800_0000);
+    assert_eq!(layout.entry, None);
+    assert_eq!(layout.code_regions().len(), 1);
+    assert_eq!(layout.va_to_file_offset(0x800_0010), Some(0x10));
+    assert_eq!(layout.va_to_file_offset(0x10), None);
+    assert_eq!(layout.file_range(0x800_0100, None, 0x400), Some((0x100, 0x400)));
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn parsed_elf_layout_maps_symbols_to_file_bytes() {
+    use ritual_core::services::fixtures::synthetic_elf_x86_64;
+    use ritual_core::services::loader::image_layout;
+
+    let binary = synthetic_elf_x86_64();
+    let layout = image_layout(&binary.bytes).expect("layout");
+    assert_eq!(layout.format, ImageFormat::Elf);
+    assert_eq!(layout.entry, Some(binary.functions[0].address));
+    for func in &binary.functions {
+        let offset = layout.va_to_file_offset(func.address).expect("mapped function") as usize;
+        assert_eq!(layout.file_offset_to_va(offset as u64), Some(func.address));
+        assert!(offset < binary.bytes.len());
+    }
+    // Symbol and string tables are not mapped into memory.
+    assert!(layout
+        .sections
+        .iter()
+        .filter(|s| s.name == ".symtab" || s.name == ".strtab")
+        .all(|s| s.virtual_size == 0));
+}
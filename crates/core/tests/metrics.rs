@@ -0,0 +1,148 @@
+use ritual_core::analysis::metrics::{function_metrics, FunctionMetrics};
+use ritual_core::db::{ProjectDb, RitualRunRecord, RitualRunStatus};
+use ritual_core::services::analysis::{
+    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind, EvidenceRecord,
+    FunctionRecord, XrefKind, XrefRecord,
+};
+
+fn func(address: u64, name: &str) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn block(start: u64, insns: Option<u32>, successors: &[(u64, BlockEdgeKind)]) -> BasicBlock {
+    BasicBlock {
+        start,
+        byte_len: 4,
+        insn_count: insns,
+        successors: successors
+            .iter()
+            .map(|(target, kind)| BlockEdge { target: *target, kind: kind.clone() })
+            .collect(),
+    }
+}
+
+fn call(from: u64, to: u64) -> CallEdge {
+    CallEdge { from, to, is_cross_slice: false, to_name: None, to_import: false }
+}
+
+/// `dispatch` (0x100) branches into a loop and calls `send` twice and `log` once; `send`
+/// (0x200) tail-jumps into `log` (0x300), whose blocks lack instruction counts.
+fn sample() -> AnalysisResult {
+    use BlockEdgeKind::*;
+    AnalysisResult {
+        functions: vec![func(0x100, "dispatch"), func(0x200, "send"), func(0x300, "log")],
+        call_edges: vec![call(0x100, 0x200), call(0x100, 0x200), call(0x100, 0x300)],
+        evidence: vec![EvidenceRecord {
+            address: 0x900,
+            description: "string: hello".into(),
+            kind: Some(EvidenceKind::String),
+            source: None,
+            run_id: None,
+        }],
+        xrefs: vec![
+            XrefRecord {
+                from: 0x104,
+                to: 0x900,
+                kind: XrefKind::Immediate,
+                section: None,
+                preview: None,
+            },
+            XrefRecord {
+                from: 0x118,
+                to: 0x902,
+                kind: XrefKind::Memory,
+                section: None,
+                preview: None,
+            },
+        ],
+        basic_blocks: vec![
+            block(0x100, Some(3), &[(0x110, ConditionalJump), (0x120, Fallthrough)]),
+            block(0x110, Some(2), &[(0x200, Call), (0x118, Fallthrough)]),
+            block(0x118, Some(2), &[(0x110, ConditionalJump), (0x120, Fallthrough)]),
+            block(0x120, Some(1), &[(0x300, Call), (0x5000, IndirectJump)]),
+            block(0x200, Some(4), &[(0x300, Jump)]),
+            block(0x300, None, &[]),
+        ],
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn metrics_count_blocks_complexity_calls_and_strings_per_function() {
+    let metrics = function_metrics(&sample());
+    assert_eq!(
+        metrics[0],
+        FunctionMetrics {
+            address: 0x100,
+            insn_count: Some(8),
+            blocks: 4,
+            // 5 local edges (the indirect jump goes nowhere known) - 4 blocks + 2.
+            complexity: Some(3),
+            fan_in: 0,
+            fan_out: 2,
+            string_refs: 2,
+        }
+    );
+    // The tail jump into `log` stays out of `send`.
+    assert_eq!((metrics[1].blocks, metrics[1].complexity, metrics[1].fan_in), (1, Some(1), 1));
+    assert_eq!(metrics[1].insn_count, Some(4));
+    assert_eq!(metrics[2].insn_count, None);
+    assert_eq!(metrics[2].fan_in, 1);
+
+    // Functions without blocks have no complexity.
+    let mut result = sample();
+    result.basic_blocks.clear();
+    let metrics = function_metrics(&result);
+    assert_eq!((metrics[0].blocks, metrics[0].complexity, metrics[0].insn_count), (0, None, None));
+}
+
+#[test]
+fn metrics_are_stored_with_the_run() {
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "Bin".into(),
+            ritual: "Net".into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    assert!(db.load_function_metrics(run_id).unwrap().is_empty());
+
+    db.insert_analysis_result(run_id, &sample()).unwrap();
+    assert_eq!(db.load_function_metrics(run_id).unwrap(), function_metrics(&sample()));
+
+    let mut fewer = sample();
+    fewer.functions.truncate(1);
+    db.insert_analysis_result(run_id, &fewer).unwrap();
+    assert_eq!(db.load_function_metrics(run_id).unwrap().len(), 1);
+}
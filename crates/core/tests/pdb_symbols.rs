@@ -0,0 +1,132 @@
+use tempfile::tempdir;
+
+use ritual_core::db::{BinaryPdb, BinaryRecord, ProjectDb};
+use ritual_core::services::analysis::{
+    apply_pdb_symbols, AnalysisResult, EvidenceKind, FunctionRecord,
+};
+use ritual_core::services::import::pdb::{read_pdb, PdbFunction, PdbIdentity, PdbSymbols};
+
+fn identity(guid: &str, age: u32) -> PdbIdentity {
+    PdbIdentity { guid: guid.into(), age }
+}
+
+#[test]
+fn codeview_guids_use_the_windows_guid_layout() {
+    let signature = [
+        0x4e, 0x3d, 0x2c, 0x1b, 0x6a, 0x5f, 0x8c, 0x7b, 0x9d, 0x0e, 0x1f, 0x20, 0x31, 0x42, 0x53,
+        0x64,
+    ];
+    let from_pe = PdbIdentity::from_codeview(&signature, 3);
+    assert_eq!(from_pe.guid, "1b2c3d4e-5f6a-7b8c-9d0e-1f2031425364");
+    assert_eq!(from_pe.to_string(), "1b2c3d4e-5f6a-7b8c-9d0e-1f2031425364/3");
+
+    assert!(from_pe.matches(&identity("1B2C3D4E-5F6A-7B8C-9D0E-1F2031425364", 3)));
+    assert!(!from_pe.matches(&identity("1b2c3d4e-5f6a-7b8c-9d0e-1f2031425364", 4)));
+    assert!(!from_pe.matches(&identity("00000000-5f6a-7b8c-9d0e-1f2031425364", 3)));
+}
+
+#[test]
+fn unreadable_pdbs_are_rejected() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("game.pdb");
+    std::fs::write(&path, b"not a program database").unwrap();
+    assert!(read_pdb(&path).is_err());
+}
+
+#[test]
+fn binary_pdbs_round_trip_and_go_with_their_binary() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let id = db.insert_binary(&BinaryRecord::new("Game.exe", "bin/Game.exe")).unwrap();
+    assert_eq!(db.binary_pdb(id).unwrap(), None);
+
+    let mut pdb = BinaryPdb {
+        binary_id: id,
+        path: "bin/Game.pdb".into(),
+        guid: "1b2c3d4e-5f6a-7b8c-9d0e-1f2031425364".into(),
+        age: 2,
+        associated_at: "t0".into(),
+    };
+    db.set_binary_pdb(&pdb).unwrap();
+    pdb.age = 3;
+    db.set_binary_pdb(&pdb).unwrap();
+    assert_eq!(db.binary_pdb(id).unwrap(), Some(pdb));
+
+    db.delete_binary(id).unwrap();
+    assert_eq!(db.binary_pdb(id).unwrap(), None);
+}
+
+#[test]
+fn pdb_symbols_name_size_and_type_functions() {
+    let func = |address, name: &str, in_slice| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let mut result = AnalysisResult {
+        functions: vec![
+            func(0x140001000, "sub_140001000", true),
+            func(0x140002000, "?tick@Game@@QEAAXM@Z", false),
+            func(0x140003000, "main", true),
+        ],
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    let pdb = PdbSymbols {
+        identity: identity("1b2c3d4e-5f6a-7b8c-9d0e-1f2031425364", 1),
+        functions: vec![
+            PdbFunction {
+                address: 0x1000,
+                name: "Net::connect".into(),
+                mangled_name: Some("?connect@Net@@SA_NPEBD@Z".into()),
+                size: Some(0x40),
+                type_name: Some("bool (const char*)".into()),
+            },
+            PdbFunction {
+                address: 0x2000,
+                name: "Game::tick".into(),
+                mangled_name: None,
+                size: Some(0x80),
+                type_name: Some("void (Game::*)(float)".into()),
+            },
+        ],
+    }
+    .rebase(0x140000000);
+    apply_pdb_symbols(&mut result, &pdb);
+    // Applying twice adds no duplicate evidence.
+    apply_pdb_symbols(&mut result, &pdb);
+
+    let functions: Vec<_> = result
+        .functions
+        .iter()
+        .map(|f| (f.name.as_deref(), f.mangled_name.as_deref(), f.size))
+        .collect();
+    assert_eq!(
+        functions,
+        vec![
+            (Some("Net::connect"), Some("?connect@Net@@SA_NPEBD@Z"), Some(0x40)),
+            (Some("Game::tick"), None, Some(0x80)),
+            (Some("main"), None, None),
+        ]
+    );
+    // Only in-slice functions get their type as evidence.
+    assert_eq!(result.evidence.len(), 1);
+    assert_eq!(result.evidence[0].address, 0x140001000);
+    assert_eq!(result.evidence[0].description, "pdb type: bool (const char*)");
+    assert_eq!(result.evidence[0].kind, Some(EvidenceKind::Other));
+}
@@ -0,0 +1,130 @@
+use tempfile::tempdir;
+
+use ritual_core::services::analysis::{AnalysisResult, FunctionRecord};
+use ritual_core::services::pipeline::{
+    feed_roots, load_stage_result, save_stage_result, stage_order, PipelineError, PipelineState,
+    StageSpec, StageStatus,
+};
+
+fn stage(name: &str, needs: &[&str]) -> StageSpec {
+    StageSpec {
+        name: name.into(),
+        backend: None,
+        needs: needs.iter().map(|n| n.to_string()).collect(),
+        roots: None,
+        max_depth: None,
+    }
+}
+
+fn result_with(functions: &[(u64, &str)]) -> AnalysisResult {
+    AnalysisResult {
+        functions: functions
+            .iter()
+            .map(|(address, name)| FunctionRecord {
+                address: *address,
+                name: Some(name.to_string()),
+                size: None,
+                in_slice: true,
+                is_boundary: false,
+                mangled_name: None,
+                signature: None,
+                arch: None,
+                fuzzy_hash: None,
+                isa: None,
+            })
+            .collect(),
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn stages_run_after_their_dependencies_in_declaration_order() {
+    let stages = vec![
+        stage("report", &["carve", "strings"]),
+        stage("carve", &["symbols"]),
+        stage("symbols", &[]),
+        stage("strings", &[]),
+    ];
+    assert_eq!(stage_order(&stages).unwrap(), vec![2, 1, 3, 0]);
+}
+
+#[test]
+fn invalid_stage_graphs_are_rejected() {
+    assert!(matches!(stage_order(&[stage(" ", &[])]), Err(PipelineError::EmptyStageName)));
+    assert!(matches!(
+        stage_order(&[stage("a", &[]), stage("a", &[])]),
+        Err(PipelineError::DuplicateStage(name)) if name == "a"
+    ));
+    assert!(matches!(
+        stage_order(&[stage("a", &["ghost"])]),
+        Err(PipelineError::UnknownDependency { needs, .. }) if needs == "ghost"
+    ));
+    let err = stage_order(&[stage("root", &[]), stage("a", &["b"]), stage("b", &["a"])]);
+    assert!(matches!(err, Err(PipelineError::Cycle(names)) if names == ["a", "b"]));
+    assert!(matches!(stage_order(&[stage("self", &["self"])]), Err(PipelineError::Cycle(_))));
+}
+
+#[test]
+fn needs_accepts_a_single_name_or_a_list() {
+    let single: StageSpec =
+        serde_json::from_str(r#"{"name": "carve", "needs": "symbols"}"#).unwrap();
+    assert_eq!(single.needs, vec!["symbols"]);
+    let many: StageSpec =
+        serde_json::from_str(r#"{"name": "carve", "needs": ["symbols", "strings"]}"#).unwrap();
+    assert_eq!(many.needs, vec!["symbols", "strings"]);
+    let none: StageSpec =
+        serde_json::from_str(r#"{"name": "symbols", "backend": "rizin"}"#).unwrap();
+    assert!(none.needs.is_empty());
+    assert_eq!(none.backend.as_deref(), Some("rizin"));
+}
+
+#[test]
+fn feed_roots_resolves_names_found_upstream() {
+    let symbols = result_with(&[(0x401000, "main"), (0x401010, "helper")]);
+    let later = result_with(&[(0x500000, "helper")]);
+    let roots = vec!["helper".to_string(), "0x401000".into(), "unknown".into(), "main".into()];
+    assert_eq!(
+        feed_roots(&roots, &[&symbols, &later]),
+        vec!["0x401010", "0x401000", "unknown"],
+        "first upstream wins and duplicates collapse"
+    );
+    assert_eq!(feed_roots(&roots, &[]), roots);
+}
+
+#[test]
+fn pipeline_state_round_trips_and_controls_resume() {
+    let dir = tempdir().unwrap();
+    let stages = vec![stage("carve", &["symbols"]), stage("symbols", &[])];
+    let order = stage_order(&stages).unwrap();
+    assert_eq!(PipelineState::load(dir.path()).unwrap(), None);
+
+    let mut state = PipelineState::new("hash-1", &stages, &order);
+    let names: Vec<_> = state.stages.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["symbols", "carve"]);
+    state.stage_mut("symbols").unwrap().status = StageStatus::Succeeded;
+    state.stage_mut("carve").unwrap().status = StageStatus::Failed;
+    state.save(dir.path()).unwrap();
+
+    let loaded = PipelineState::load(dir.path()).unwrap().unwrap();
+    assert_eq!(loaded, state);
+    assert!(loaded.can_resume("hash-1"));
+    assert!(!loaded.can_resume("hash-2"), "a changed spec starts over");
+
+    state.stage_mut("carve").unwrap().status = StageStatus::Succeeded;
+    assert!(state.is_complete());
+    assert!(!state.can_resume("hash-1"));
+
+    let result = result_with(&[(0x10, "f")]);
+    assert_eq!(load_stage_result(dir.path(), "symbols"), None);
+    save_stage_result(dir.path(), "symbols", &result).unwrap();
+    assert_eq!(load_stage_result(dir.path(), "symbols"), Some(result));
+}
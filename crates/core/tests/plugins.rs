@@ -0,0 +1,186 @@
+use ritual_core::services::analysis::{
+    AnalysisResult, CallEdge, EvidenceKind, EvidenceRecord, EvidenceSource, FunctionRecord,
+};
+use ritual_core::services::plugins::{apply_plugin_output, parse_plugin_output, PluginError};
+
+fn func(address: u64, name: &str, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice,
+        is_boundary: false,
+        mangled_name: Some(format!("_Z{}", name)),
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn result() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![func(0x10, "net_init", true), func(0x20, "sub_20", false)],
+        call_edges: vec![CallEdge {
+            from: 0x10,
+            to: 0x20,
+            is_cross_slice: false,
+            to_name: Some("sub_20".into()),
+            to_import: false,
+        }],
+        evidence: vec![EvidenceRecord {
+            address: 0x10,
+            description: "string: \"connect\"".into(),
+            kind: Some(EvidenceKind::String),
+            source: None,
+            run_id: None,
+        }],
+        basic_blocks: vec![],
+        roots: vec!["net_init".into()],
+        root_hits: vec![],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn plugin_edits_add_evidence_rename_functions_and_change_membership() {
+    let mut result = result();
+    let edits = parse_plugin_output(
+        "score.wasm",
+        br#"{
+            "api_version": 1,
+            "evidence": [
+                {"address": 16, "description": "string: \"connect\"", "kind": "string"},
+                {"address": 32, "description": "score: 0.9 (packet encoder)", "kind": "other"}
+            ],
+            "renames": [{"address": 32, "name": "encode_packet"}, {"address": 48, "name": "x"}],
+            "slice": [{"address": 32, "in_slice": true}, {"address": 16, "in_slice": true}]
+        }"#,
+    )
+    .unwrap();
+    let report = apply_plugin_output(&mut result, "score.wasm", edits);
+
+    assert_eq!(report.evidence_added, 1, "duplicates are dropped");
+    assert_eq!(report.renamed, 1);
+    assert_eq!(report.slice_changed, 1);
+    assert_eq!(report.unknown_addresses, [0x30]);
+    assert_eq!(result.evidence.len(), 2);
+    assert_eq!(result.evidence[1].source, Some(EvidenceSource::new("plugin", "score")));
+    assert_eq!(result.functions[1].name.as_deref(), Some("encode_packet"));
+    assert_eq!(result.functions[1].mangled_name, None);
+    assert!(result.functions[1].in_slice);
+    assert_eq!(result.call_edges[0].to_name.as_deref(), Some("encode_packet"));
+}
+
+#[test]
+fn malformed_plugin_output_is_rejected() {
+    assert_eq!(parse_plugin_output("p", b"  \n").unwrap(), Default::default());
+    let err = parse_plugin_output("p", br#"{"rename": []}"#).unwrap_err();
+    assert!(err.to_string().starts_with("Plugin p wrote invalid output: unknown field `rename`"));
+    let err = parse_plugin_output("p", br#"{"api_version": 2}"#).unwrap_err();
+    assert!(matches!(err, PluginError::UnsupportedApi { version: 2, .. }));
+    let err =
+        parse_plugin_output("p", br#"{"renames": [{"address": 16, "name": " "}]}"#).unwrap_err();
+    assert!(err.to_string().contains("empty name for function 0x10"));
+}
+
+/// A WASI module that writes `text` to `fd` and exits with `code`. With `echo_stdin`, it first
+/// copies its stdin to `fd`.
+#[cfg(feature = "wasm-plugins")]
+fn module(fd: u32, text: &str, echo_stdin: bool, code: i32) -> Vec<u8> {
+    let echo = if echo_stdin {
+        r#"(loop $copy
+      (i32.store (i32.const 0) (i32.const 4096))
+      (i32.store (i32.const 4) (i32.const 60000))
+      (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+      (if (i32.load (i32.const 8))
+        (then
+          (i32.store (i32.const 4) (i32.load (i32.const 8)))
+          (drop (call $fd_write (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 8)))
+          (br $copy))))"#
+    } else {
+        ""
+    };
+    wat::parse_str(format!(
+        r#"(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 64) "{}")
+  (func (export "_start") (local $fd i32)
+    (local.set $fd (i32.const {fd}))
+    {echo}
+    (i32.store (i32.const 0) (i32.const 64))
+    (i32.store (i32.const 4) (i32.const {}))
+    (drop (call $fd_write (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (call $proc_exit (i32.const {code}))))"#,
+        text.replace('"', "\\\""),
+        text.len()
+    ))
+    .unwrap()
+}
+
+#[cfg(feature = "wasm-plugins")]
+#[test]
+fn plugins_run_in_order_on_the_wasi_runtime() {
+    use std::fs;
+    use std::time::Duration;
+
+    use ritual_core::services::plugins::run_plugins;
+    use ritual_core::services::process::Deadline;
+    use tempfile::tempdir;
+
+    let temp = tempdir().unwrap();
+    let plugins_dir = temp.path().join("plugins");
+    fs::create_dir(&plugins_dir).unwrap();
+    let rename = r#"{"renames": [{"address": 32, "name": "encode_packet"}]}"#;
+    fs::write(plugins_dir.join("name.wasm"), module(1, rename, false, 0)).unwrap();
+    let mark = r#"{"slice": [{"address": 32, "in_slice": true}]}"#;
+    fs::write(plugins_dir.join("mark.wasm"), module(1, mark, false, 0)).unwrap();
+    // Fails, showing the input it was given.
+    fs::write(plugins_dir.join("dump.wasm"), module(2, "", true, 7)).unwrap();
+    let deadline = Deadline::new(Some(Duration::from_secs(30)));
+
+    let mut result = result();
+    let plugins = vec!["plugins/name.wasm".to_string(), "plugins/mark.wasm".to_string()];
+    let reports =
+        run_plugins(&mut result, &plugins, temp.path(), "Net", "Game", &deadline).unwrap();
+    assert_eq!(reports.len(), 2);
+    assert_eq!((reports[0].renamed, reports[1].slice_changed), (1, 1));
+    assert!(result.functions[1].in_slice);
+
+    // Each plugin sees the edits of the ones before it, in a versioned envelope.
+    let dumped = vec!["plugins/name.wasm".to_string(), "plugins/dump.wasm".to_string()];
+    let err = run_plugins(&mut crate::result(), &dumped, temp.path(), "Net", "Game", &deadline)
+        .unwrap_err();
+    let PluginError::Exit { plugin, code: Some(7), stderr } = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(plugin, "plugins/dump.wasm");
+    let input: serde_json::Value = serde_json::from_str(&stderr).unwrap();
+    assert_eq!(input["api_version"], 1);
+    assert_eq!(input["ritual"], "Net");
+    assert_eq!(input["binary"], "Game");
+    assert_eq!(input["result"]["functions"][1]["name"], "encode_packet");
+
+    let missing = vec!["plugins/gone.wasm".to_string()];
+    let err =
+        run_plugins(&mut result, &missing, temp.path(), "Net", "Game", &deadline).unwrap_err();
+    assert!(matches!(err, PluginError::MissingModule(_)));
+
+    fs::write(temp.path().join("other.wasm"), module(2, "no such plugin", false, 7)).unwrap();
+    let failing = vec!["other.wasm".to_string()];
+    let err =
+        run_plugins(&mut result, &failing, temp.path(), "Net", "Game", &deadline).unwrap_err();
+    assert_eq!(err.to_string(), "Plugin other.wasm exited with status 7: no such plugin");
+
+    fs::write(temp.path().join("broken.wasm"), b"\0asm").unwrap();
+    let broken = vec!["broken.wasm".to_string()];
+    let err = run_plugins(&mut result, &broken, temp.path(), "Net", "Game", &deadline).unwrap_err();
+    assert!(matches!(err, PluginError::Run { .. }), "{err}");
+}
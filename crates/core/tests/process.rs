@@ -0,0 +1,121 @@
+#![cfg(unix)]
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use ritual_core::services::analysis::AnalysisError;
+use ritual_core::services::process::{
+    run_supervised, run_supervised_with_input, track_child_usage, Deadline,
+};
+
+#[test]
+fn hung_tools_are_killed_when_the_deadline_passes() {
+    let deadline = Deadline::new(Some(Duration::from_millis(200)));
+    let started = Instant::now();
+    let err =
+        run_supervised(Command::new("sh").args(["-c", "exec sleep 30"]), "sleeper", &deadline)
+            .unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(10));
+    match err {
+        AnalysisError::Timeout { tool, after } => {
+            assert_eq!(tool, "sleeper");
+            assert_eq!(after, Duration::from_millis(200));
+        }
+        other => panic!("expected a timeout, got {other:?}"),
+    }
+    assert!(deadline.remaining() == Some(Duration::ZERO));
+    // A spent deadline fails later invocations of the same run without spawning them.
+    let err = run_supervised(&mut Command::new("true"), "true", &deadline).unwrap_err();
+    assert!(err.to_string().contains("true timed out after 200ms"));
+}
+
+#[test]
+fn timeouts_also_kill_processes_the_tool_spawned() {
+    let temp = tempfile::tempdir().unwrap();
+    let marker = temp.path().join("survived");
+    // The tool's helper would create the marker a second after the tool is killed.
+    let script = format!("(sleep 1; touch '{}') & wait", marker.display());
+    let deadline = Deadline::new(Some(Duration::from_millis(200)));
+    let err =
+        run_supervised(Command::new("sh").args(["-c", &script]), "spawner", &deadline).unwrap_err();
+    assert!(matches!(err, AnalysisError::Timeout { .. }), "{err:?}");
+    std::thread::sleep(Duration::from_millis(1500));
+    assert!(!marker.exists(), "helper outlived the killed tool");
+}
+
+#[test]
+fn helpers_left_running_are_killed_when_the_tool_exits() {
+    // The helper inherits stdout; if it survived, collecting the output would wait for it.
+    let started = Instant::now();
+    let output = run_supervised(
+        Command::new("sh").args(["-c", "sleep 30 & echo done"]),
+        "forker",
+        &Deadline::new(None),
+    )
+    .unwrap();
+    assert_eq!(output.stdout, b"done\n");
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn finished_tools_return_their_output() {
+    let unlimited = Deadline::new(None);
+    assert_eq!(unlimited.remaining(), None);
+    let output = run_supervised(
+        Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]),
+        "sh",
+        &unlimited,
+    )
+    .unwrap();
+    assert_eq!(output.stdout, b"out\n");
+    assert_eq!(output.stderr, b"err\n");
+    assert_eq!(output.status.code(), Some(3));
+
+    let generous = Deadline::new(Some(Duration::from_secs(30)));
+    let output = run_supervised(Command::new("echo").arg("hi"), "echo", &generous).unwrap();
+    assert_eq!(output.stdout, b"hi\n");
+
+    let err =
+        run_supervised(&mut Command::new("/nonexistent/tool"), "tool", &generous).unwrap_err();
+    assert!(err.to_string().contains("failed to spawn tool"));
+}
+
+#[test]
+fn input_is_written_to_stdin() {
+    let deadline = Deadline::new(Some(Duration::from_secs(30)));
+    let output = run_supervised_with_input(
+        Command::new("sh").args(["-c", "tr a-z A-Z"]),
+        "tr",
+        b"plugin input".to_vec(),
+        &deadline,
+    )
+    .unwrap();
+    assert_eq!(output.stdout, b"PLUGIN INPUT");
+
+    // Children that never read their input still finish.
+    let output = run_supervised_with_input(
+        Command::new("echo").arg("done"),
+        "echo",
+        vec![0; 1 << 20],
+        &deadline,
+    )
+    .unwrap();
+    assert_eq!(output.stdout, b"done\n");
+}
+
+#[test]
+fn tracked_tools_report_cpu_time_and_peak_memory() {
+    let deadline = Deadline::new(Some(Duration::from_secs(30)));
+    let (output, usage) = track_child_usage(|| {
+        run_supervised(Command::new("echo").arg("one"), "echo", &deadline).unwrap();
+        run_supervised(Command::new("sh").args(["-c", "echo two"]), "sh", &deadline)
+    });
+    assert_eq!(output.unwrap().stdout, b"two\n");
+    let usage = usage.expect("two tools ran");
+    assert_eq!(usage.processes, 2);
+    assert!(usage.peak_rss_kb > 0);
+
+    // Nothing spawned, nothing to report; untracked runs are not counted anywhere.
+    assert_eq!(track_child_usage(|| ()).1, None);
+    run_supervised(&mut Command::new("true"), "true", &deadline).unwrap();
+}
@@ -0,0 +1,35 @@
+#![cfg(unix)]
+//! Sends SIGINT to the test process itself, so it lives in its own test binary.
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use ritual_core::services::analysis::AnalysisError;
+use ritual_core::services::process::{run_supervised, Deadline};
+
+#[test]
+fn sigint_kills_running_tools_and_their_helpers() {
+    let temp = tempfile::tempdir().unwrap();
+    let (started, marker) = (temp.path().join("started"), temp.path().join("survived"));
+    let script = format!(
+        "touch '{}'; (sleep 1; touch '{}') & sleep 30",
+        started.display(),
+        marker.display()
+    );
+    let tool = std::thread::spawn(move || {
+        run_supervised(Command::new("sh").args(["-c", &script]), "sleeper", &Deadline::new(None))
+    });
+    while !started.exists() {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let sent = Instant::now();
+    let pid = std::process::id().to_string();
+    assert!(Command::new("kill").args(["-INT", &pid]).status().unwrap().success());
+    let err = tool.join().unwrap().unwrap_err();
+    assert!(sent.elapsed() < Duration::from_secs(10));
+    assert!(matches!(err, AnalysisError::Interrupted { ref tool } if tool == "sleeper"), "{err}");
+    assert!(err.is_canceled());
+    std::thread::sleep(Duration::from_millis(1500));
+    assert!(!marker.exists(), "helper outlived the interrupted tool");
+}
@@ -0,0 +1,59 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+use ritual_core::db::{LockError, ProjectLayout, ProjectLock};
+
+fn layout() -> (tempfile::TempDir, ProjectLayout) {
+    let temp = tempfile::tempdir().unwrap();
+    let layout = ProjectLayout::new(temp.path());
+    std::fs::create_dir_all(&layout.meta_dir).unwrap();
+    (temp, layout)
+}
+
+#[test]
+fn project_lock_names_its_holder_and_clears_on_drop() {
+    let (_temp, layout) = layout();
+    let lock = ProjectLock::try_acquire(&layout, "binary-slicer run-ritual --file a.yaml").unwrap();
+
+    assert_eq!(
+        ProjectLock::holder(&layout).unwrap().command,
+        "binary-slicer run-ritual --file a.yaml"
+    );
+    let err = ProjectLock::try_acquire(&layout, "binary-slicer add-binary").unwrap_err();
+    let LockError::Held { holder: Some(holder) } = &err else { panic!("{err:?}") };
+    assert_eq!(holder.pid, std::process::id());
+    assert_eq!(holder.command, "binary-slicer run-ritual --file a.yaml");
+    assert!(
+        err.to_string().starts_with(&format!(
+            "Project is locked by pid {} (`binary-slicer run-ritual --file a.yaml`, since ",
+            std::process::id()
+        )),
+        "{err}"
+    );
+
+    drop(lock);
+    assert_eq!(std::fs::read_to_string(layout.lock_path()).unwrap(), "");
+    assert_eq!(ProjectLock::holder(&layout), None);
+    let again = ProjectLock::try_acquire(&layout, "binary-slicer add-binary").unwrap();
+    drop(again);
+}
+
+#[test]
+fn project_lock_acquire_waits_for_the_holder() {
+    let (_temp, layout) = layout();
+    let lock = ProjectLock::try_acquire(&layout, "first").unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let waiter = {
+        let layout = layout.clone();
+        std::thread::spawn(move || {
+            let lock = ProjectLock::acquire(&layout, "second").unwrap();
+            tx.send(()).unwrap();
+            drop(lock);
+        })
+    };
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err(), "acquired while held");
+    drop(lock);
+    rx.recv_timeout(Duration::from_secs(10)).expect("acquired after release");
+    waiter.join().unwrap();
+}
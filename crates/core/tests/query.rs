@@ -0,0 +1,202 @@
+use ritual_core::services::analysis::{
+    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind, EvidenceRecord,
+    EvidenceSource, FunctionRecord,
+};
+use ritual_core::services::query::{parse_query, run_query, Entity, QueryError, Value};
+use ritual_core::services::search::{SearchError, SearchKind, SearchPattern};
+
+fn func(address: u64, name: &str, size: u32, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(size),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn evidence(address: u64, description: &str, kind: EvidenceKind) -> EvidenceRecord {
+    EvidenceRecord {
+        address,
+        description: description.into(),
+        kind: Some(kind),
+        source: None,
+        run_id: None,
+    }
+}
+
+fn sample() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![
+            func(0x1000, "main", 600, true),
+            func(0x2000, "net_send", 1024, true),
+            func(0x3000, "big_helper", 2048, false),
+            func(0x4000, "tiny", 16, true),
+        ],
+        call_edges: vec![
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1000,
+                to: 0x3000,
+                is_cross_slice: true,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+        evidence: vec![
+            evidence(0x1010, "string: http://example.com", EvidenceKind::String),
+            evidence(0x2010, "string: hello", EvidenceKind::String),
+            evidence(0x2020, "import: HTTPSendRequest", EvidenceKind::Import),
+        ],
+        basic_blocks: vec![BasicBlock {
+            start: 0x1000,
+            byte_len: 12,
+            insn_count: None,
+            successors: vec![
+                BlockEdge { target: 0x100C, kind: BlockEdgeKind::Fallthrough },
+                BlockEdge { target: 0x2000, kind: BlockEdgeKind::Call },
+            ],
+        }],
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+fn names(rows: &[Vec<Value>]) -> Vec<String> {
+    rows.iter().map(|row| row[1].to_string()).collect()
+}
+
+#[test]
+fn functions_filter_by_size_and_slice_membership() {
+    let out = run_query("functions where size > 512 and in_slice", &sample()).unwrap();
+    assert_eq!(out.entity, Entity::Functions);
+    assert_eq!(out.columns[..5], ["address", "name", "size", "in_slice", "is_boundary"]);
+    assert_eq!(names(&out.rows), vec!["main", "net_send"]);
+
+    let out = run_query("functions where not in_slice or size <= 0x10", &sample()).unwrap();
+    assert_eq!(names(&out.rows), vec!["big_helper", "tiny"]);
+}
+
+#[test]
+fn evidence_filter_by_kind_and_contains() {
+    let out =
+        run_query(r#"evidence where kind = string and description contains "http""#, &sample())
+            .unwrap();
+    assert_eq!(out.rows.len(), 1);
+    assert_eq!(out.rows[0][0], Value::Addr(0x1010));
+
+    // `contains` is case-insensitive; parentheses group.
+    let out = run_query(
+        "evidence where (kind = 'import' or kind = string) and description contains HTTP",
+        &sample(),
+    )
+    .unwrap();
+    assert_eq!(out.rows.len(), 2);
+
+    // Provenance columns: `backend/pass` and the run the row was read from.
+    let mut result = sample();
+    result.evidence[2].source = Some(EvidenceSource::new("rizin", "imports"));
+    result.evidence[2].run_id = Some(7);
+    let out = run_query(r#"evidence where source contains "rizin""#, &result).unwrap();
+    assert_eq!(out.rows.len(), 1);
+    assert_eq!(out.rows[0][3], Value::Str("rizin/imports".into()));
+    assert_eq!(out.rows[0][4], Value::Int(7));
+    let out = run_query("evidence where source = null", &result).unwrap();
+    assert_eq!(out.rows.len(), 2);
+}
+
+#[test]
+fn order_by_and_limit() {
+    let out = run_query("functions order by size desc limit 2", &sample()).unwrap();
+    assert_eq!(names(&out.rows), vec!["big_helper", "net_send"]);
+}
+
+#[test]
+fn edges_and_blocks_expose_derived_columns() {
+    let out = run_query("edges where is_cross_slice", &sample()).unwrap();
+    assert_eq!(out.rows.len(), 1);
+    assert_eq!(out.rows[0][2], Value::Str("main".into()));
+    assert_eq!(out.rows[0][3], Value::Str("big_helper".into()));
+
+    let out = run_query("blocks where successors >= 2", &sample()).unwrap();
+    assert_eq!(out.rows.len(), 1);
+    assert_eq!(out.columns, ["start", "byte_len", "insn_count", "successors"]);
+    assert_eq!(out.rows[0][1], Value::Int(12));
+    assert_eq!(out.rows[0][2], Value::Null);
+}
+
+#[test]
+fn functions_order_by_metrics() {
+    let out = run_query("functions order by complexity desc limit 20", &sample()).unwrap();
+    assert_eq!(
+        out.columns[5..],
+        ["insn_count", "blocks", "complexity", "fan_in", "fan_out", "string_refs"]
+    );
+    assert_eq!(names(&out.rows), vec!["main", "net_send", "big_helper", "tiny"]);
+    assert_eq!(out.rows[0][6], Value::Int(1));
+    assert_eq!(out.rows[0][7], Value::Int(1));
+    assert_eq!(out.rows[1][7], Value::Null);
+
+    let out = run_query("functions where fan_in > 0 order by address", &sample()).unwrap();
+    assert_eq!(names(&out.rows), vec!["net_send", "big_helper"]);
+    let out = run_query("functions where fan_out = 2", &sample()).unwrap();
+    assert_eq!(names(&out.rows), vec!["main"]);
+}
+
+#[test]
+fn json_output_is_keyed_by_column() {
+    let out = run_query("functions where name = tiny", &sample()).unwrap();
+    let json = out.to_json();
+    assert_eq!(json[0]["address"], 0x4000);
+    assert_eq!(json[0]["name"], "tiny");
+    assert_eq!(json[0]["in_slice"], true);
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    assert_eq!(parse_query("   "), Err(QueryError::Empty));
+    assert!(matches!(parse_query("widgets"), Err(QueryError::UnknownEntity(_))));
+    assert!(matches!(
+        parse_query("functions where colour = red"),
+        Err(QueryError::UnknownField { entity: "functions", .. })
+    ));
+    assert!(matches!(parse_query("functions where size >"), Err(QueryError::Syntax { .. })));
+    assert!(matches!(parse_query("functions where (in_slice"), Err(QueryError::Syntax { .. })));
+    assert!(matches!(
+        parse_query("evidence where description = \"x"),
+        Err(QueryError::Syntax { .. })
+    ));
+    assert!(matches!(parse_query("functions limit"), Err(QueryError::Syntax { .. })));
+    assert!(matches!(parse_query("functions in_slice"), Err(QueryError::Syntax { .. })));
+}
+
+#[test]
+fn search_patterns_match_case_insensitively() {
+    let plain = SearchPattern::new("AutoUpdate", false).unwrap();
+    assert!(plain.is_match("string: AUTOUPDATE.cfg"));
+    assert!(!plain.is_match("auto_update"));
+    let regex = SearchPattern::new(r"auto_?update\b", true).unwrap();
+    assert!(regex.is_match("Auto_Update"));
+    assert!(!regex.is_match("autoupdater"));
+
+    assert!(matches!(SearchPattern::new("", false), Err(SearchError::EmptyPattern)));
+    assert!(matches!(SearchPattern::new("(", true), Err(SearchError::InvalidRegex(_))));
+    assert_eq!(SearchKind::parse("Import").unwrap(), SearchKind::Import);
+    assert!(matches!(SearchKind::parse("evidence"), Err(SearchError::UnknownKind(_))));
+}
@@ -0,0 +1,172 @@
+use ritual_core::db::RunNote;
+use ritual_core::services::analysis::{
+    AnalysisResult, CallEdge, EvidenceKind, EvidenceRecord, EvidenceSource, FunctionRecord, RootHit,
+};
+use ritual_core::services::render::html::{
+    call_graph_svg, escape_html, render_slice_report, EvidenceSection, SliceHtmlReport,
+    SVG_MAX_NODES,
+};
+
+fn func(address: u64, name: &str, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(16),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn analysis(functions: Vec<FunctionRecord>, call_edges: Vec<CallEdge>) -> AnalysisResult {
+    AnalysisResult {
+        functions,
+        call_edges,
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["main".into()],
+        root_hits: vec![RootHit { root: "main".into(), functions: vec![0x1000], error: None }],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn escape_html_covers_markup_characters() {
+    assert_eq!(escape_html(r#"<a href="x">&'"#), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;");
+}
+
+#[test]
+fn report_has_table_graph_and_collapsible_evidence() {
+    let result = analysis(
+        vec![func(0x1000, "main", true), func(0x2000, "operator<<", false)],
+        vec![
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: true,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x2000,
+                to: 0x9000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+    );
+    let roots = vec!["main".to_string(), "missing".to_string()];
+    let notes = vec![RunNote {
+        run_id: 1,
+        note: "vtable at 0x4000 <Session>".into(),
+        created_at: "2024-01-01T00:00:00Z".into(),
+    }];
+    let page = render_slice_report(&SliceHtmlReport {
+        name: "Net<1>",
+        description: Some("Packet handling"),
+        binary: Some("libNet.so"),
+        backend: Some("capstone"),
+        roots: &roots,
+        analysis: Some(&result),
+        function_evidence: [(0x1000, 3)].into_iter().collect(),
+        evidence: vec![
+            EvidenceSection {
+                title: "Strings".into(),
+                total: 3,
+                records: vec![EvidenceRecord {
+                    address: 0x1004,
+                    description: "string: <http://x>".into(),
+                    kind: Some(EvidenceKind::String),
+                    source: Some(EvidenceSource::new("capstone", "strings")),
+                    run_id: None,
+                }],
+            },
+            EvidenceSection { title: "Imports".into(), total: 0, records: Vec::new() },
+        ],
+        notes: &notes,
+        ..Default::default()
+    });
+
+    assert!(page.contains("<title>Slice report: Net&lt;1&gt;</title>"));
+    assert!(page.contains("<td>operator&lt;&lt;</td>"));
+    assert!(page.contains("<code>main</code> (matched &rarr; 0x1000)"));
+    assert!(page.contains("<code>missing</code> (unmatched)"));
+    assert!(page.contains("<details>\n<summary>Strings (3)</summary>"));
+    assert!(page.contains("string: &lt;http://x&gt;</td><td>capstone/strings</td>"));
+    assert!(page.contains("Showing 1 of 3"));
+    assert!(!page.contains("Imports (0)"), "empty sections are skipped");
+    assert!(page.contains("<svg"));
+    assert!(page.contains("<h2>Notes</h2>"));
+    assert!(page.contains("vtable at 0x4000 &lt;Session&gt;</li>"));
+}
+
+#[test]
+fn report_without_analysis_says_so() {
+    let page = render_slice_report(&SliceHtmlReport { name: "Empty", ..Default::default() });
+    assert!(page.contains("No analysis run recorded"));
+    assert!(!page.contains("<svg"));
+}
+
+#[test]
+fn call_graph_svg_draws_nodes_edges_and_externals() {
+    let result = analysis(
+        vec![func(0x1000, "main", true), func(0x2000, "a_rather_long_function_name_here", true)],
+        vec![
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x2000,
+                to: 0x9000,
+                is_cross_slice: true,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x2000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+    );
+    let svg = call_graph_svg(&result);
+    assert_eq!(svg.matches("<rect").count(), 3);
+    assert_eq!(svg.matches("<line").count(), 3);
+    assert_eq!(svg.matches("stroke-dasharray").count(), 1, "unknown call target is dashed");
+    assert!(svg.contains("#d9822b"), "cross-slice edge is highlighted");
+    assert!(svg.contains("a_rather_long_functio…"));
+    assert!(svg.contains("<title>a_rather_long_function_name_here @ 0x2000</title>"));
+
+    // Levels follow call depth from the root hit: main above its callee.
+    let y_of = |label: &str| {
+        let at = svg.find(&format!("<title>{label}")).unwrap();
+        let rect = &svg[at..];
+        let y = rect.split("y=\"").nth(1).unwrap();
+        y.split('"').next().unwrap().parse::<usize>().unwrap()
+    };
+    assert!(y_of("main") < y_of("a_rather"));
+}
+
+#[test]
+fn call_graph_svg_truncates_large_graphs() {
+    let functions: Vec<_> =
+        (0..SVG_MAX_NODES as u64 + 5).map(|i| func(0x1000 + i * 0x10, "f", true)).collect();
+    let svg = call_graph_svg(&analysis(functions, Vec::new()));
+    assert_eq!(svg.matches("<rect").count(), SVG_MAX_NODES);
+    assert!(svg.contains("5 more nodes omitted"));
+}
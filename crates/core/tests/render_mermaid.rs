@@ -0,0 +1,114 @@
+use ritual_core::services::analysis::{AnalysisResult, CallEdge, FunctionRecord, SubSlice};
+use ritual_core::services::render::mermaid::mermaid_call_graph;
+
+fn func(address: u64, name: &str, in_slice: bool, is_boundary: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(16),
+        in_slice,
+        is_boundary,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn analysis(functions: Vec<FunctionRecord>, call_edges: Vec<CallEdge>) -> AnalysisResult {
+    AnalysisResult {
+        functions,
+        call_edges,
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["main".into()],
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn mermaid_graph_clusters_sub_slices_and_styles_edges() {
+    let mut result = analysis(
+        vec![
+            func(0x1000, "main", true, false),
+            func(0x2000, "operator<<", true, false),
+            func(0x3000, "send", true, true),
+            func(0x4000, "log", false, false),
+        ],
+        vec![
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1000,
+                to: 0x2000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1000,
+                to: 0x3000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x3000,
+                to: 0x4000,
+                is_cross_slice: true,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x3000,
+                to: 0x9000,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+    );
+    result.sub_slices =
+        vec![SubSlice { label: "net".into(), roots: vec!["send".into()], functions: vec![0x3000] }];
+
+    let graph = mermaid_call_graph(&result, 50);
+    assert!(graph.starts_with("```mermaid\ngraph TD\n"), "{graph}");
+    assert!(graph.ends_with("```\n"), "{graph}");
+    assert!(graph.contains("  subgraph c0[\"net\"]\n    f2[\"send\"]\n  end\n"), "{graph}");
+    assert!(graph.contains("  subgraph c1[\"slice\"]\n    f0[\"main\"]\n"), "{graph}");
+    assert!(graph.contains("f1[\"operator#lt;#lt;\"]"), "{graph}");
+    assert!(graph.contains("  f3[\"log\"]\n  f4[\"0x9000\"]\n"), "{graph}");
+    assert_eq!(graph.matches("f0 --> f1").count(), 1, "parallel calls are drawn once");
+    assert!(graph.contains("  f2 -.-> f3\n"), "{graph}");
+    assert!(graph.contains("  class f2 boundary\n"), "{graph}");
+}
+
+#[test]
+fn mermaid_graph_caps_nodes_into_overflow() {
+    let functions = (0..10).map(|i| func(0x1000 + i * 0x10, &format!("f_{i}"), i < 6, false));
+    let edges = (0..9).map(|i| CallEdge {
+        from: 0x1000 + i * 0x10,
+        to: 0x1010 + i * 0x10,
+        is_cross_slice: false,
+        to_name: None,
+        to_import: false,
+    });
+    let result = analysis(functions.collect(), edges.collect());
+
+    let graph = mermaid_call_graph(&result, 4);
+    assert!(graph.contains("overflow[\"+6 more functions\"]"), "{graph}");
+    assert!(graph.contains("  f3 --> overflow\n"), "{graph}");
+    assert!(!graph.contains("overflow --> overflow"), "{graph}");
+    assert!(!graph.contains("f_4"), "in-slice functions beyond the cap are dropped: {graph}");
+}
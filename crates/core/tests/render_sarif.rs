@@ -0,0 +1,155 @@
+use ritual_core::services::analysis::{
+    AnalysisResult, EvidenceKind, EvidenceRecord, FunctionRecord, RootHit,
+};
+use ritual_core::services::render::sarif::{
+    render_slice_sarif, BinarySection, SliceSarifReport, SliceSarifWriter, SARIF_VERSION,
+};
+use serde_json::Value;
+
+fn func(address: u64, name: &str, in_slice: bool, is_boundary: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(0x10),
+        in_slice,
+        is_boundary,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn analysis() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![
+            func(0x1000, "main", true, false),
+            func(0x1010, "helper", false, true),
+            func(0x1020, "unrelated", false, false),
+        ],
+        call_edges: Vec::new(),
+        evidence: vec![EvidenceRecord {
+            address: 0x1004,
+            description: "String: \"hello\"".into(),
+            kind: Some(EvidenceKind::String),
+            source: None,
+            run_id: None,
+        }],
+        basic_blocks: Vec::new(),
+        roots: vec!["main".into(), "missing".into()],
+        root_hits: vec![
+            RootHit { root: "main".into(), functions: vec![0x1000], error: None },
+            RootHit {
+                root: "missing".into(),
+                functions: Vec::new(),
+                error: Some("no function matched".into()),
+            },
+        ],
+        sub_slices: Vec::new(),
+        backend_version: Some("5.0".into()),
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+fn results(log: &Value) -> &Vec<Value> {
+    log["runs"][0]["results"].as_array().expect("results")
+}
+
+#[test]
+fn slice_functions_evidence_and_unmatched_roots_become_results() {
+    let analysis = analysis();
+    let sections = [
+        BinarySection { name: ".data".into(), start: 0x3000, end: 0x3100 },
+        BinarySection { name: ".text".into(), start: 0x1000, end: 0x2000 },
+    ];
+    let log = render_slice_sarif(&SliceSarifReport {
+        slice: "Boot",
+        tool_version: "1.2.3",
+        binary: Some("demo"),
+        binary_uri: Some("bin/demo.elf"),
+        binary_hash: Some("abc"),
+        backend: Some("capstone"),
+        analysis: Some(&analysis),
+        sections: &sections,
+        ..Default::default()
+    });
+
+    assert_eq!(log["version"], SARIF_VERSION);
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "binary-slicer");
+    assert_eq!(run["tool"]["driver"]["version"], "1.2.3");
+    assert_eq!(run["artifacts"][0]["location"]["uri"], "bin/demo.elf");
+    assert_eq!(run["artifacts"][0]["hashes"]["sha-256"], "abc");
+    assert_eq!(run["properties"]["slice"], "Boot");
+
+    // Sections are sorted by address, so .text is index 0.
+    assert_eq!(run["addresses"][0]["name"], ".text");
+    assert_eq!(run["addresses"][0]["length"], 0x1000);
+
+    let results = results(&log);
+    let rule_ids: Vec<&str> = results.iter().map(|r| r["ruleId"].as_str().unwrap()).collect();
+    assert_eq!(rule_ids, ["slice/function", "slice/boundary", "evidence/string", "root/unmatched"]);
+
+    let main = &results[0];
+    let address = &main["locations"][0]["physicalLocation"]["address"];
+    assert_eq!(address["absoluteAddress"], 0x1000);
+    assert_eq!(address["parentIndex"], 0);
+    assert_eq!(address["offsetFromParent"], 0);
+    assert_eq!(address["name"], "main");
+    assert_eq!(main["locations"][0]["physicalLocation"]["artifactLocation"]["index"], 0);
+    assert_eq!(main["properties"]["section"], ".text");
+    assert_eq!(main["properties"]["address"], "0x1000");
+
+    // Evidence is attributed to the function that contains it.
+    let evidence = &results[2];
+    assert_eq!(evidence["locations"][0]["logicalLocations"][0]["name"], "main");
+    assert_eq!(evidence["locations"][0]["physicalLocation"]["address"]["offsetFromParent"], 4);
+
+    let unmatched = &results[3];
+    assert_eq!(unmatched["level"], "warning");
+    assert!(unmatched["message"]["text"].as_str().unwrap().contains("missing"));
+    assert!(unmatched.get("locations").is_none());
+}
+
+#[test]
+fn report_without_analysis_or_sections_is_still_valid() {
+    let log = render_slice_sarif(&SliceSarifReport {
+        slice: "Empty",
+        tool_version: "0.1.0",
+        ..Default::default()
+    });
+    let run = &log["runs"][0];
+    assert!(results(&log).is_empty());
+    assert!(run.get("artifacts").is_none());
+    assert_eq!(run["addresses"], serde_json::json!([]));
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 7);
+}
+
+#[test]
+fn streamed_evidence_matches_the_in_memory_log() {
+    let full = analysis();
+    let sections = [BinarySection { name: ".text".into(), start: 0x1000, end: 0x2000 }];
+    let report = SliceSarifReport {
+        slice: "Boot",
+        tool_version: "1.2.3",
+        analysis: Some(&full),
+        sections: &sections,
+        ..Default::default()
+    };
+    let expected = render_slice_sarif(&report);
+
+    // The writer only needs functions and root hits up front; evidence arrives later.
+    let skeleton = AnalysisResult { evidence: Vec::new(), ..analysis() };
+    let streamed_report = SliceSarifReport { analysis: Some(&skeleton), ..report };
+    let mut writer = SliceSarifWriter::new(Vec::new(), &streamed_report).unwrap();
+    for e in &full.evidence {
+        writer.evidence(e).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+    let streamed: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(streamed, expected);
+    assert_eq!(results(&streamed).len(), 4);
+}
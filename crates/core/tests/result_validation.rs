@@ -0,0 +1,130 @@
+use ritual_core::services::analysis::{
+    validate_result, AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind,
+    EvidenceRecord, FunctionRecord, RootHit, ValidationCheck,
+};
+use ritual_core::services::loader::ImageLayout;
+
+fn func(address: u64) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(format!("sub_{:X}", address)),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn block(start: u64, successors: Vec<(u64, BlockEdgeKind)>) -> BasicBlock {
+    BasicBlock {
+        start,
+        byte_len: 4,
+        insn_count: Some(1),
+        successors: successors
+            .into_iter()
+            .map(|(target, kind)| BlockEdge { target, kind })
+            .collect(),
+    }
+}
+
+fn result() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![func(0x1000), func(0x1100), func(0x1000)],
+        call_edges: vec![
+            CallEdge {
+                from: 0x1004,
+                to: 0x1100,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1008,
+                to: 0x1050,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x100C,
+                to: 0x1050,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+            CallEdge {
+                from: 0x1010,
+                to: 0x1200,
+                is_cross_slice: false,
+                to_name: None,
+                to_import: false,
+            },
+        ],
+        evidence: vec![EvidenceRecord {
+            address: 0x1200,
+            description: "import: puts".into(),
+            kind: Some(EvidenceKind::Import),
+            source: None,
+            run_id: None,
+        }],
+        xrefs: Vec::new(),
+        basic_blocks: vec![
+            block(0x1000, vec![(0x1004, BlockEdgeKind::Fallthrough)]),
+            block(0x1100, vec![(0x9000, BlockEdgeKind::Jump), (0, BlockEdgeKind::IndirectJump)]),
+            block(0x8000, Vec::new()),
+        ],
+        roots: vec!["sub_1000".into(), "missing".into()],
+        root_hits: vec![
+            RootHit { root: "sub_1000".into(), functions: vec![0x1000], error: None },
+            RootHit {
+                root: "missing".into(),
+                functions: Vec::new(),
+                error: Some("no function matched".into()),
+            },
+        ],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn validate_result_reports_each_broken_invariant() {
+    let layout = ImageLayout::raw(0x1000, 0x1000);
+    let validation = validate_result(&result(), Some(&layout));
+    let found: Vec<_> = validation.issues.iter().map(|i| (i.check, i.address)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (ValidationCheck::DuplicateFunction, Some(0x1000)),
+            (ValidationCheck::UnmappedBlockTarget, Some(0x9000)),
+            (ValidationCheck::UnmappedBlockTarget, Some(0x8000)),
+            // Reported once per target; calls to imports are fine.
+            (ValidationCheck::CallTargetNotFunction, Some(0x1050)),
+            (ValidationCheck::UnresolvedRoot, None),
+        ]
+    );
+    assert!(validation.skipped.is_empty());
+    assert!(!validation.is_ok());
+    assert_eq!(validation.issues[4].message, "root 'missing' did not resolve: no function matched");
+
+    let json = serde_json::to_value(&validation).unwrap();
+    assert_eq!(json["issues"][1]["check"], "unmapped_block_target");
+}
+
+#[test]
+fn validate_result_skips_block_bounds_without_a_layout() {
+    let mut clean = result();
+    clean.functions.pop();
+    clean.call_edges.retain(|e| e.to != 0x1050);
+    clean.roots.pop();
+    let validation = validate_result(&clean, None);
+    assert!(validation.is_ok(), "{:?}", validation.issues);
+    assert_eq!(validation.skipped.len(), 1);
+    assert!(validation.skipped[0].starts_with("block targets"));
+}
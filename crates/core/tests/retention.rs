@@ -0,0 +1,79 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use ritual_core::db::{RetentionConfig, RitualRunRecord, RitualRunStatus};
+use ritual_core::services::retention::{plan_purge, PurgeReason};
+
+fn run(binary: &str, ritual: &str, status: RitualRunStatus, finished_at: &str) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        ritual: ritual.into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status,
+        started_at: finished_at.into(),
+        finished_at: finished_at.into(),
+        binary_id: None,
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+fn now() -> DateTime<Utc> {
+    "2026-03-01T00:00:00Z".parse().unwrap()
+}
+
+#[test]
+fn keeps_the_newest_runs_of_each_ritual_plus_succeeded_and_baselines() {
+    use RitualRunStatus::*;
+    let runs: Vec<(i64, RitualRunRecord)> = vec![
+        (1, run("game-1.0", "Net", Succeeded, "2026-01-01T00:00:00Z")),
+        (2, run("game-1.0", "Net", Failed, "2026-01-02T00:00:00Z")),
+        (3, run("game-1.1", "Net", Stubbed, "2026-01-03T00:00:00Z")),
+        (4, run("game-1.1", "Net", Outdated, "2026-01-04T00:00:00Z")),
+        (5, run("game-1.2", "Net", Failed, "2026-02-20T00:00:00Z")),
+        (6, run("game-1.2", "Net", Succeeded, "2026-02-21T00:00:00Z")),
+        (7, run("game-1.2", "Render", Failed, "2026-01-05T00:00:00Z")),
+        (8, run("game-1.3", "Net", Running, "2026-02-22T00:00:00Z")),
+    ];
+    let policy = RetentionConfig { keep_last: Some(2), ..Default::default() };
+
+    // Net keeps runs 6 and 5 (the running run 8 does not count) and succeeded run 1.
+    let purge = plan_purge(&runs, &BTreeSet::new(), &policy, now());
+    let ids: Vec<i64> = purge.iter().map(|c| c.run_id).collect();
+    assert_eq!(ids, [2, 3, 4]);
+    assert_eq!(purge[0].reason, PurgeReason::PastKeepLast { keep: 2 });
+    // Run 2 is the latest of game-1.0 / Net, so its outputs go with it.
+    assert!(purge[0].owns_outputs);
+    assert!(!purge[1].owns_outputs, "run 3 was overwritten by run 4");
+    assert!(purge[2].owns_outputs);
+
+    // Baselines stay, and succeeded runs go too once they are not protected.
+    let baselines = BTreeSet::from([3]);
+    let policy = RetentionConfig { keep_succeeded: Some(false), ..policy };
+    let ids: Vec<i64> =
+        plan_purge(&runs, &baselines, &policy, now()).iter().map(|c| c.run_id).collect();
+    assert_eq!(ids, [1, 2, 4]);
+
+    // Old failures go even among the newest runs; unparsable timestamps never expire.
+    let policy = RetentionConfig { failed_max_age_days: Some(30), ..Default::default() };
+    let mut runs = runs;
+    runs.push((9, run("game-1.3", "Render", Canceled, "yesterday")));
+    let purge = plan_purge(&runs, &BTreeSet::new(), &policy, now());
+    let ids: Vec<i64> = purge.iter().map(|c| c.run_id).collect();
+    assert_eq!(ids, [2, 3, 7]);
+    assert_eq!(purge[2].reason, PurgeReason::Expired { age_days: 55, max_age_days: 30 });
+    assert_eq!(purge[2].reason.to_string(), "finished 55 day(s) ago (limit 30)");
+
+    // Purged runs are not purged twice and do not count against keep_last.
+    runs[1].1.status = Purged;
+    runs[2].1.status = Purged;
+    runs[6].1.status = Purged;
+    assert!(plan_purge(&runs, &BTreeSet::new(), &policy, now()).is_empty());
+}
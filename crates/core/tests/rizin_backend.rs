@@ -4,6 +4,7 @@ use ritual_core::services::analysis::{
     AnalysisBackend, AnalysisOptions, AnalysisRequest, AnalysisResult,
 };
 use ritual_core::services::backends::RizinBackend;
+use ritual_core::services::signature::CallingConvention;
 
 #[test]
 fn rizin_backend_errors_for_missing_binary() {
@@ -16,6 +17,11 @@ fn rizin_backend_errors_for_missing_binary() {
         options: Default::default(),
         arch: None,
         backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
     let err = backend.analyze(&req).unwrap_err();
     assert!(format!("{err:?}").contains("MissingBinary"));
@@ -33,13 +39,13 @@ fn rizin_backend_parses_fake_json_without_rizin_installed() {
     let fake_json = temp.path().join("aflj.json");
     std::fs::write(
         &fake_json,
-        r#"[{"offset":4096,"name":"func_a","size":16,"callrefs":[{"addr":12288,"type":"C"}]},{"offset":8192,"name":"func_b","size":8}]"#,
+        r#"[{"offset":4096,"name":"func_a","size":16,"nargs":3,"calltype":"amd64","callrefs":[{"addr":12288,"type":"C"}]},{"offset":8192,"name":"func_b","size":8}]"#,
     )
     .unwrap();
     let fake_graph = temp.path().join("agfj.json");
     std::fs::write(
         &fake_graph,
-        r#"[{"offset":4096,"blocks":[{"offset":4096,"size":4,"jump":4100,"fail":4104}]}]"#,
+        r#"[{"offset":4096,"blocks":[{"offset":4096,"size":4,"jump":4100,"fail":4104,"ops":[{},{}]}]}]"#,
     )
     .unwrap();
     let fake_strings = temp.path().join("strings.json");
@@ -51,6 +57,13 @@ fn rizin_backend_parses_fake_json_without_rizin_installed() {
     std::env::set_var("BS_RIZIN_FAKE_GRAPH", &fake_graph);
     std::env::set_var("BS_RIZIN_FAKE_STRINGS", &fake_strings);
     std::env::set_var("BS_RIZIN_FAKE_IMPORTS", &fake_imports);
+    let fake_sections = temp.path().join("sections.json");
+    std::fs::write(
+        &fake_sections,
+        r#"[{"name":".text","size":4096,"perm":"-r-x"},{"name":".data","size":512,"perm":"-rw-"},{"name":".plt","size":64,"perm":"-r-x"}]"#,
+    )
+    .unwrap();
+    std::env::set_var("BS_RIZIN_FAKE_SECTIONS", &fake_sections);
 
     let req = AnalysisRequest {
         ritual_name: "Fake".into(),
@@ -64,15 +77,24 @@ fn rizin_backend_parses_fake_json_without_rizin_installed() {
         },
         arch: None,
         backend_path: Some(fake_rizin_path.clone()),
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
     };
     let result: AnalysisResult = backend.analyze(&req).expect("analyze fake");
     assert_eq!(result.functions.len(), 2);
     assert_eq!(result.functions[0].name.as_deref(), Some("func_a"));
+    let sig = result.functions[0].signature.as_ref().expect("rizin signature");
+    assert_eq!((sig.params, sig.convention), (3, CallingConvention::Sysv64));
+    assert_eq!(result.functions[1].signature, None);
     assert_eq!(result.backend_version.as_deref(), Some("rizin 1.0-fake"));
     assert_eq!(result.call_edges.len(), 1);
     assert_eq!(result.call_edges[0].from, 4096);
     assert_eq!(result.call_edges[0].to, 12288);
-    assert!(!result.basic_blocks.is_empty());
+    assert_eq!((result.basic_blocks[0].byte_len, result.basic_blocks[0].insn_count), (4, Some(2)));
+    assert_eq!(result.text_bytes, Some(4160));
     assert!(result.evidence.iter().any(|e| e.description.contains("string:")));
     assert!(result.evidence.iter().any(|e| e.description.contains("import:")));
     assert!(result.evidence.iter().any(|e| e.description.contains("call ->")));
@@ -84,4 +106,5 @@ fn rizin_backend_parses_fake_json_without_rizin_installed() {
     std::env::remove_var("BS_RIZIN_FAKE_GRAPH");
     std::env::remove_var("BS_RIZIN_FAKE_STRINGS");
     std::env::remove_var("BS_RIZIN_FAKE_IMPORTS");
+    std::env::remove_var("BS_RIZIN_FAKE_SECTIONS");
 }
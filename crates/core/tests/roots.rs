@@ -0,0 +1,140 @@
+use ritual_core::services::analysis::{build_root_hits, AnalysisResult, FunctionRecord};
+use ritual_core::services::entry_points::{resolve_auto_roots, EntryKind, EntryPoint};
+use ritual_core::services::roots::{AutoRoot, RootError, RootPattern, RootSpec};
+
+fn func(address: u64, name: &str) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: None,
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+#[test]
+fn spec_roots_deserialize_and_canonicalize() {
+    let roots: Vec<RootSpec> = serde_json::from_str(
+        r#"["main_loop", {"address": 4198400}, {"address": "0x40100a"},
+            {"regex": ".*Update.*"}, {"demangled": "Game::tick"}]"#,
+    )
+    .unwrap();
+    let canonical: Vec<String> = roots.iter().map(RootSpec::canonical).collect();
+    assert_eq!(
+        canonical,
+        vec!["main_loop", "0x401000", "0x40100A", "regex:.*Update.*", "demangled:Game::tick"]
+    );
+    // Structured entries serialize back in their structured form.
+    assert_eq!(
+        serde_json::to_value(&roots[3]).unwrap(),
+        serde_json::json!({"regex": ".*Update.*"})
+    );
+    for (root, spec) in canonical.iter().zip(&roots).skip(3) {
+        assert_eq!(&RootSpec::from_canonical(root), spec);
+    }
+    assert_eq!(RootSpec::from_canonical("main_loop"), roots[0]);
+    assert_eq!(RootSpec::from_canonical("0x40100A").canonical(), "0x40100A");
+    assert_eq!(RootSpec::from_canonical("string:http").canonical(), "string:http");
+}
+
+#[test]
+fn patterns_match_names_addresses_regexes_and_demangled_names() {
+    let functions = [
+        func(0x1000, "main"),
+        func(0x1010, "_ZN4Game4tickEf"),
+        func(0x1020, "_ZN6Player6UpdateEv"),
+        func(0x1030, "UpdateAll"),
+    ];
+    let matching = |root: &str| -> Vec<u64> {
+        let pattern = RootPattern::parse(root).unwrap();
+        functions.iter().filter(|f| pattern.matches(f)).map(|f| f.address).collect()
+    };
+    assert_eq!(matching("main"), vec![0x1000]);
+    assert_eq!(matching("0x1010"), vec![0x1010]);
+    // Regexes are anchored and see both raw and demangled names.
+    assert_eq!(matching("regex:.*Update.*"), vec![0x1020, 0x1030]);
+    assert_eq!(matching("regex:Update"), Vec::<u64>::new());
+    assert_eq!(matching("regex:Player::Update\\(\\)"), vec![0x1020]);
+    // Demangled roots match with or without the parameter list.
+    assert_eq!(matching("demangled:Game::tick"), vec![0x1010]);
+    assert_eq!(matching("demangled:Game::tick(float)"), vec![0x1010]);
+    assert_eq!(matching("demangled:Game"), Vec::<u64>::new());
+
+    // Already-demangled results still match by either name.
+    let mut demangled = func(0x1010, "Game::tick(float)");
+    demangled.mangled_name = Some("_ZN4Game4tickEf".into());
+    assert!(RootPattern::parse("_ZN4Game4tickEf").unwrap().matches(&demangled));
+    assert!(RootPattern::parse("demangled:Game::tick").unwrap().matches(&demangled));
+}
+
+#[test]
+fn root_hits_record_per_root_errors() {
+    let functions = vec![func(0x1000, "main")];
+    let roots: Vec<String> =
+        vec!["main".into(), "missing".into(), "regex:(".into(), "0xZZ".into(), "demangled:".into()];
+    let hits = build_root_hits(&roots, &functions);
+    assert_eq!(hits[0].functions, vec![0x1000]);
+    assert_eq!(hits[0].error, None);
+    assert_eq!(hits[1].error.as_deref(), Some("no function matched"));
+    assert!(hits[2].error.as_deref().unwrap().starts_with("Invalid root regex '('"));
+    assert_eq!(hits[3].error, Some(RootError::InvalidAddress("0xZZ".into()).to_string()));
+    assert_eq!(hits[4].error, Some(RootError::Empty.to_string()));
+    assert!(hits[1..].iter().all(|h| h.functions.is_empty()));
+}
+
+#[test]
+fn entry_and_exports_roots_resolve_to_declared_entry_points() {
+    let roots: Vec<RootSpec> =
+        serde_json::from_str(r#"["entrypoint", "exports", "entry"]"#).unwrap();
+    let canonical: Vec<String> = roots.iter().map(RootSpec::canonical).collect();
+    // `entry` stays a symbol name.
+    assert_eq!(canonical, ["auto:entrypoint", "auto:exports", "entry"]);
+    assert_eq!(RootSpec::from_canonical("auto:exports"), roots[1]);
+    assert!(matches!(
+        RootPattern::parse("auto:entrypoint"),
+        Ok(RootPattern::Auto(AutoRoot::Entry))
+    ));
+    assert!(!RootPattern::parse("auto:exports").unwrap().matches(&func(0x1000, "exports")));
+    assert_eq!(RootPattern::parse("auto:main").unwrap_err(), RootError::UnknownAuto("main".into()));
+
+    let mut functions =
+        vec![func(0x1000, "_start"), func(0x1100, "api_open"), func(0x1200, "helper")];
+    functions.iter_mut().for_each(|f| f.in_slice = false);
+    let mut result = AnalysisResult {
+        root_hits: build_root_hits(&canonical, &functions),
+        functions,
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: canonical.clone(),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    let point = |kind, address| EntryPoint { kind, address, name: None };
+    // Exports without a function at their address (data) are skipped.
+    let points = [
+        point(EntryKind::Entry, 0x1000),
+        point(EntryKind::Export, 0x1100),
+        point(EntryKind::Export, 0x9000),
+    ];
+    resolve_auto_roots(&canonical, &mut result, &points);
+    assert_eq!(result.root_hits[0].functions, [0x1000]);
+    assert_eq!(result.root_hits[1].functions, [0x1100]);
+    assert_eq!(result.root_hits[1].error, None);
+    let in_slice: Vec<u64> =
+        result.functions.iter().filter(|f| f.in_slice).map(|f| f.address).collect();
+    assert_eq!(in_slice, [0x1000, 0x1100]);
+
+    // Nothing declared: the hits say why.
+    resolve_auto_roots(&canonical, &mut result, &[]);
+    assert!(result.root_hits[0].error.as_deref().unwrap().contains("no entry point"));
+    assert_eq!(result.root_hits[1].error.as_deref(), Some("binary exports no known function"));
+}
@@ -0,0 +1,141 @@
+use tempfile::tempdir;
+
+use ritual_core::analysis::baseline::{BaselineComparison, RunSnapshot};
+use ritual_core::db::{
+    BinaryRecord, ProjectDb, RegressionConfig, RitualRunRecord, RitualRunStatus, RunBaseline,
+    RunCounts,
+};
+use ritual_core::services::analysis::RootHit;
+
+fn run(binary: &str, binary_id: i64) -> RitualRunRecord {
+    RitualRunRecord {
+        binary: binary.into(),
+        ritual: "Net".into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        backend: "validate-only".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        started_at: "t0".into(),
+        finished_at: "t1".into(),
+        binary_id: Some(binary_id),
+        superseded_by: None,
+        deterministic: false,
+        spec_path: None,
+        analysis_options: None,
+        usage: None,
+    }
+}
+
+fn baseline(binary_id: i64, binary: &str, run_id: i64, set_at: &str) -> RunBaseline {
+    RunBaseline {
+        binary_id,
+        binary: binary.into(),
+        ritual: "Net".into(),
+        run_id,
+        set_at: set_at.into(),
+    }
+}
+
+#[test]
+fn baselines_cover_their_binary_group_and_go_with_their_runs() {
+    let dir = tempdir().expect("tempdir");
+    let db = ProjectDb::open(&dir.path().join("project.db")).expect("open db");
+    let v1 = db.insert_binary(&BinaryRecord::new("game-1.2", "bins/1.2/libgame.so")).unwrap();
+    let v2 = db.insert_binary(&BinaryRecord::new("game-1.3", "bins/1.3/libgame.so")).unwrap();
+    let loose = db.insert_binary(&BinaryRecord::new("tool", "bins/tool")).unwrap();
+    let group = db.ensure_binary_group("libgame.so", "t0").unwrap();
+    db.assign_binary_group(v1, group, Some("v1.2"), None).unwrap();
+    db.assign_binary_group(v2, group, Some("v1.3"), None).unwrap();
+    let run_v1 = db.insert_ritual_run(&run("game-1.2", v1)).unwrap();
+    let run_v2 = db.insert_ritual_run(&run("game-1.3", v2)).unwrap();
+    assert_eq!(db.run_baseline(v1, "Net").unwrap(), None);
+
+    // A baseline covers the other builds of its group, but not other rituals or binaries.
+    db.set_run_baseline(&baseline(v1, "game-1.2", run_v1, "t2")).unwrap();
+    assert_eq!(db.run_baseline(v2, "Net").unwrap(), Some(baseline(v1, "game-1.2", run_v1, "t2")));
+    assert_eq!(db.run_baseline(v2, "Render").unwrap(), None);
+    assert_eq!(db.run_baseline(loose, "Net").unwrap(), None);
+
+    // A binary's own baseline wins over its group's; setting again replaces it.
+    db.set_run_baseline(&baseline(v2, "game-1.3", run_v1, "t3")).unwrap();
+    db.set_run_baseline(&baseline(v2, "game-1.3", run_v2, "t4")).unwrap();
+    assert_eq!(db.run_baseline(v2, "Net").unwrap().map(|b| b.run_id), Some(run_v2));
+    assert_eq!(db.run_baseline(v1, "Net").unwrap().map(|b| b.run_id), Some(run_v1));
+
+    assert_eq!(db.clear_run_baseline(v2, "Net").unwrap(), 1);
+    assert_eq!(db.clear_run_baseline(v2, "Net").unwrap(), 0);
+    assert_eq!(db.run_baseline(v2, "Net").unwrap().map(|b| b.run_id), Some(run_v1));
+
+    // Deleting the baseline run (or its binary) drops the baseline.
+    db.delete_binary_runs(v1, "game-1.2").unwrap();
+    assert_eq!(db.run_baseline(v2, "Net").unwrap(), None);
+    db.set_run_baseline(&baseline(v2, "game-1.3", run_v2, "t5")).unwrap();
+    db.delete_binary(v2).unwrap();
+    assert_eq!(db.run_baseline(v2, "Net").unwrap(), None);
+}
+
+fn snapshot(in_slice: usize, functions: usize, call_edges: usize, roots: &[&str]) -> RunSnapshot {
+    let counts =
+        RunCounts { functions, in_slice_functions: in_slice, call_edges, evidence: 0, xrefs: 0 };
+    let hits: Vec<RootHit> = roots
+        .iter()
+        .map(|root| {
+            let (root, functions) = match root.strip_prefix('!') {
+                Some(root) => (root, Vec::new()),
+                None => (*root, vec![0x1000]),
+            };
+            RootHit { root: root.into(), functions, error: None }
+        })
+        .collect();
+    RunSnapshot::new(counts, &hits)
+}
+
+#[test]
+fn comparisons_flag_count_drops_and_lost_roots() {
+    let before = snapshot(40, 120, 300, &["net_init", "net_send", "!legacy"]);
+    let compare = |current: &RunSnapshot, config: &RegressionConfig| {
+        BaselineComparison::new(baseline(1, "game-1.2", 7, "t0"), &before, current, config)
+    };
+
+    // Within the default 10% in-slice drop, with roots that were already unresolved.
+    let steady =
+        compare(&snapshot(37, 100, 200, &["net_init", "net_send", "!legacy"]), &Default::default());
+    assert!(steady.is_ok(), "{:?}", steady.regressions);
+    assert_eq!(
+        steady.describe(),
+        "in-slice functions 40 -> 37 (-7.5%), functions 120 -> 100 (-16.7%), call edges 300 -> 200 (-33.3%)"
+    );
+
+    let current = snapshot(30, 100, 200, &["net_init", "!net_send", "!legacy"]);
+    let regressed = compare(&current, &Default::default());
+    assert_eq!(regressed.lost_roots, ["net_send"]);
+    assert_eq!(
+        regressed.regressions,
+        [
+            "in-slice functions dropped 25.0% (40 -> 30; limit 10%)",
+            "roots no longer resolved: net_send",
+        ]
+    );
+
+    // Other counts are checked once configured, and root checks can be turned off.
+    let config = RegressionConfig {
+        max_in_slice_drop: Some(30.0),
+        max_function_drop: Some(20.0),
+        max_call_edge_drop: Some(25.0),
+        unresolved_roots: Some(false),
+    };
+    let regressed = compare(&current, &config);
+    assert_eq!(regressed.regressions, ["call edges dropped 33.3% (300 -> 200; limit 25%)"]);
+    assert_eq!(regressed.lost_roots, ["net_send"]);
+
+    // Empty baselines never regress.
+    let empty = BaselineComparison::new(
+        baseline(1, "game-1.2", 7, "t0"),
+        &snapshot(0, 0, 0, &[]),
+        &snapshot(0, 0, 0, &[]),
+        &Default::default(),
+    );
+    assert!(empty.is_ok());
+}
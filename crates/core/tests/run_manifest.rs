@@ -0,0 +1,49 @@
+use ritual_core::services::manifest::{hash_outputs, ManifestError, OutputManifest, MANIFEST_FILE};
+
+#[test]
+fn manifest_detects_modified_missing_and_unlisted_outputs() {
+    let temp = tempfile::tempdir().unwrap();
+    let dir = temp.path();
+    std::fs::write(dir.join("report.json"), "{}").unwrap();
+    std::fs::write(dir.join("graph.dot"), "digraph {}").unwrap();
+    std::fs::write(dir.join("hooks.log"), "").unwrap();
+    std::fs::create_dir(dir.join("stages")).unwrap();
+    assert!(matches!(OutputManifest::load(dir), Err(ManifestError::Missing(_))));
+
+    let manifest = OutputManifest {
+        binary: "bin".into(),
+        ritual: "Net".into(),
+        spec_hash: "spec".into(),
+        binary_hash: None,
+        created_at: "1970-01-01T00:00:00+00:00".into(),
+        files: hash_outputs(dir, &["hooks.log"]).unwrap(),
+    };
+    let names: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(names, ["graph.dot", "report.json"]);
+    assert_eq!(manifest.files[1].size, 2);
+    assert_eq!(
+        manifest.files[1].sha256,
+        "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+    );
+    manifest.write(dir).unwrap();
+    assert!(dir.join(MANIFEST_FILE).is_file());
+    assert_eq!(OutputManifest::load(dir).unwrap(), manifest);
+
+    // Untracked files may change freely.
+    std::fs::write(dir.join("hooks.log"), "hook ran").unwrap();
+    let check = manifest.verify(dir, &["hooks.log"]).unwrap();
+    assert!(check.is_ok());
+    assert_eq!(check.verified, 2);
+
+    std::fs::write(dir.join("report.json"), "{\"x\":1}").unwrap();
+    std::fs::remove_file(dir.join("graph.dot")).unwrap();
+    std::fs::write(dir.join("extra.txt"), "?").unwrap();
+    let check = manifest.verify(dir, &["hooks.log"]).unwrap();
+    assert!(!check.is_ok());
+    assert_eq!(check.verified, 0);
+    assert_eq!(check.mismatched.len(), 1);
+    assert_eq!(check.mismatched[0].path, "report.json");
+    assert_eq!(check.mismatched[0].actual.size, 7);
+    assert_eq!(check.missing, ["graph.dot"]);
+    assert_eq!(check.unlisted, ["extra.txt"]);
+}
@@ -0,0 +1,92 @@
+use ritual_core::db::RitualRunStatus;
+use ritual_core::services::analysis::{AnalysisResult, CallEdge, FunctionRecord, SubSlice};
+use ritual_core::services::report::{ReportError, ReportV1, REPORT_FILE, REPORT_SCHEMA_VERSION};
+
+fn func(address: u64, name: &str, arch: Option<&str>) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(0x10),
+        in_slice: true,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: arch.map(str::to_string),
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn result() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![func(0x1000, "net_init", Some("x86_64")), func(0x2000, "net_send", None)],
+        call_edges: vec![CallEdge {
+            from: 0x1000,
+            to: 0x2000,
+            is_cross_slice: false,
+            to_name: Some("net_send".into()),
+            to_import: false,
+        }],
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["net_init".into()],
+        root_hits: Vec::new(),
+        sub_slices: vec![SubSlice {
+            label: "send".into(),
+            roots: vec!["net_send".into()],
+            functions: vec![0x2000],
+        }],
+        backend_version: Some("1.0".into()),
+        backend_path: None,
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn reports_round_trip_with_their_schema_version() {
+    let temp = tempfile::tempdir().unwrap();
+    let report =
+        ReportV1::new("Net", "libnet.so", "capstone", RitualRunStatus::Succeeded, &result());
+    assert_eq!(report.report_schema_version, REPORT_SCHEMA_VERSION);
+    assert_eq!(report.arch_slices, ["x86_64"]);
+    assert_eq!(report.metrics.len(), 2);
+    assert_eq!(report.sub_slices[0].functions[0].name.as_deref(), Some("net_send"));
+    report.write(temp.path()).unwrap();
+    assert_eq!(ReportV1::load(temp.path()).unwrap(), report);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(temp.path().join(REPORT_FILE)).unwrap())
+            .unwrap();
+    assert_eq!(json["report_schema_version"], 1);
+    assert_eq!(json["status"], "succeeded");
+    assert_eq!(json["call_edges"][0]["to_name"], "net_send");
+    assert!(json.get("edges").is_none());
+    // Optional sections are left out rather than written empty.
+    assert!(json.get("baseline").is_none());
+    assert!(json.get("deterministic").is_none());
+}
+
+#[test]
+fn unversioned_reports_load_and_newer_versions_are_refused() {
+    let temp = tempfile::tempdir().unwrap();
+    let legacy = serde_json::json!({
+        "ritual": "Net",
+        "binary": "libnet.so",
+        "status": "stubbed",
+        "backend": "validate-only",
+        "functions": [],
+        "edges": [{"from": 4096, "to": 8192, "is_cross_slice": false}],
+    });
+    std::fs::write(temp.path().join(REPORT_FILE), legacy.to_string()).unwrap();
+    let report = ReportV1::load(temp.path()).unwrap();
+    assert_eq!(report.report_schema_version, 0);
+    assert_eq!(report.call_edges[0].to, 0x2000);
+
+    let mut newer = serde_json::to_value(&report).unwrap();
+    newer["report_schema_version"] = (REPORT_SCHEMA_VERSION + 1).into();
+    std::fs::write(temp.path().join(REPORT_FILE), newer.to_string()).unwrap();
+    let err = ReportV1::load(temp.path()).unwrap_err();
+    assert!(matches!(err, ReportError::UnsupportedVersion { version: 2, .. }));
+    assert!(err.to_string().contains("this build reads up to 1"), "{err}");
+}
@@ -0,0 +1,83 @@
+use ritual_core::db::EvidenceWeightOverrides;
+use ritual_core::services::analysis::{EvidenceKind, EvidenceRecord};
+use ritual_core::services::scoring::{rank_evidence, EvidenceScorer, EvidenceWeights, TopEvidence};
+
+fn evidence(address: u64, description: &str, kind: Option<EvidenceKind>) -> EvidenceRecord {
+    EvidenceRecord { address, description: description.into(), kind, source: None, run_id: None }
+}
+
+fn scorer(records: &[EvidenceRecord]) -> EvidenceScorer {
+    let mut scorer = EvidenceScorer::new(EvidenceWeights::default());
+    for e in records {
+        scorer.observe(e);
+    }
+    scorer
+}
+
+#[test]
+fn repeated_strings_score_less_than_unique_ones() {
+    let records = vec![
+        evidence(0x2000, "string: %s\\n", Some(EvidenceKind::String)),
+        evidence(0x1000, "string xref: \"%s\\n\" @ 0x2000", Some(EvidenceKind::String)),
+        evidence(0x1100, "string xref: \"%s\\n\" @ 0x2000", Some(EvidenceKind::String)),
+        evidence(0x1200, "string xref: \"unique banner\" @ 0x2010", Some(EvidenceKind::String)),
+    ];
+    let scorer = scorer(&records);
+    let weights = EvidenceWeights::default();
+    assert_eq!(scorer.score(&records[1]), weights.string / 3.0);
+    assert_eq!(scorer.score(&records[3]), weights.string);
+}
+
+#[test]
+fn calls_to_rarely_used_imports_outrank_common_ones() {
+    let records = vec![
+        evidence(0x5000, "import: printf", Some(EvidenceKind::Import)),
+        evidence(0x5008, "import: CreateMutexW (KERNEL32.dll)", Some(EvidenceKind::Import)),
+        evidence(0x1000, "call -> sym.imp.printf", Some(EvidenceKind::Call)),
+        evidence(0x1100, "call -> printf", Some(EvidenceKind::Call)),
+        evidence(0x1200, "call -> CreateMutexW", Some(EvidenceKind::Call)),
+        evidence(0x1300, "call -> helper", Some(EvidenceKind::Call)),
+        evidence(0x1304, "reg operand 35", None),
+    ];
+    let scorer = scorer(&records);
+    let weights = EvidenceWeights::default();
+    assert_eq!(scorer.score(&records[0]), weights.import / 2.0);
+    assert_eq!(scorer.score(&records[1]), weights.import);
+    assert_eq!(scorer.score(&records[2]), weights.import / 2.0);
+    assert_eq!(scorer.score(&records[4]), weights.import);
+    assert_eq!(scorer.score(&records[5]), weights.call);
+    assert_eq!(scorer.score(&records[6]), weights.other);
+}
+
+#[test]
+fn configured_weights_override_the_defaults() {
+    let overrides = EvidenceWeightOverrides { other: Some(5.0), ..Default::default() };
+    let weights = EvidenceWeights::with_overrides(&overrides);
+    assert_eq!(weights.other, 5.0);
+    assert_eq!(weights.string, EvidenceWeights::default().string);
+
+    let records = vec![
+        evidence(0x1000, "reg operand 35", None),
+        evidence(0x1004, "string: banner", Some(EvidenceKind::String)),
+    ];
+    let ranked = rank_evidence(&records, weights, 0.0, 10);
+    assert_eq!(ranked[0].evidence.address, 0x1000);
+}
+
+#[test]
+fn ranking_is_bounded_filtered_and_stable() {
+    let records: Vec<EvidenceRecord> = (0..6)
+        .map(|i| evidence(0x1000 + i, &format!("reg operand {i}"), None))
+        .chain([evidence(0x2000, "call -> helper", Some(EvidenceKind::Call))])
+        .collect();
+    let ranked = rank_evidence(&records, EvidenceWeights::default(), 0.0, 3);
+    let addresses: Vec<u64> = ranked.iter().map(|r| r.evidence.address).collect();
+    assert_eq!(addresses, vec![0x2000, 0x1000, 0x1001]);
+
+    let ranked = rank_evidence(&records, EvidenceWeights::default(), 0.5, 10);
+    assert_eq!(ranked.len(), 1);
+
+    let mut top = TopEvidence::new(0);
+    top.push(&records[0], 1.0);
+    assert!(top.is_empty());
+}
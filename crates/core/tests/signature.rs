@@ -0,0 +1,161 @@
+use ritual_core::services::signature::{
+    infer_signature, CallingConvention, FunctionSignature, InsnEffects,
+};
+
+fn insn(reads: &[&str], writes: &[&str]) -> InsnEffects {
+    InsnEffects {
+        reads: reads.iter().map(|r| r.to_string()).collect(),
+        writes: writes.iter().map(|r| r.to_string()).collect(),
+        ..InsnEffects::default()
+    }
+}
+
+fn transfer() -> InsnEffects {
+    InsnEffects { transfers: true, ..InsnEffects::default() }
+}
+
+#[test]
+fn register_params_count_reads_before_writes_and_before_the_first_call() {
+    let insns = vec![
+        // mov rax, rsi  (2nd argument read -> at least 2 params)
+        insn(&["rsi"], &["rax"]),
+        // xor edx, edx  (3rd argument overwritten before any read)
+        insn(&[], &["edx"]),
+        insn(&["edx"], &["rax"]),
+        transfer(),
+        // After the call, r8 is a scratch register.
+        insn(&["r8"], &["rax"]),
+    ];
+    let sig = infer_signature(CallingConvention::Sysv64, &insns);
+    assert_eq!(
+        sig,
+        FunctionSignature { params: 2, convention: CallingConvention::Sysv64, stack_cleanup: None }
+    );
+    assert_eq!(sig.to_string(), "sysv64(2 params)");
+
+    // Sub-registers alias their slot.
+    let sig = infer_signature(CallingConvention::Aapcs64, &[insn(&["w2"], &["w0"])]);
+    assert_eq!(sig.params, 3);
+    assert_eq!(infer_signature(CallingConvention::Win64, &[insn(&["r9d"], &[])]).params, 4);
+}
+
+#[test]
+fn x86_stack_params_and_callee_cleanup_pick_the_32_bit_convention() {
+    let stack = |offsets: &[i64], ret_pop: Option<u32>| InsnEffects {
+        stack_reads: offsets.to_vec(),
+        ret_pop,
+        ..InsnEffects::default()
+    };
+
+    // [esp+4] and [esp+12] are the 1st and 3rd arguments; the return address is ignored.
+    let cdecl = infer_signature(CallingConvention::Cdecl, &[stack(&[0, 4, 12, -8], None)]);
+    assert_eq!(cdecl.convention, CallingConvention::Cdecl);
+    assert_eq!(cdecl.params, 3);
+    assert_eq!(cdecl.stack_cleanup, None);
+
+    let stdcall = infer_signature(CallingConvention::Cdecl, &[stack(&[4], Some(16))]);
+    assert_eq!(stdcall.convention, CallingConvention::Stdcall);
+    assert_eq!(stdcall.params, 4);
+    assert_eq!(stdcall.to_string(), "stdcall(4 params, ret 16)");
+
+    let thiscall = infer_signature(CallingConvention::Cdecl, &[insn(&["ecx"], &["eax"])]);
+    assert_eq!(thiscall.convention, CallingConvention::Thiscall);
+    assert_eq!(thiscall.to_string(), "thiscall(1 param)");
+
+    let fastcall =
+        infer_signature(CallingConvention::Cdecl, &[insn(&["edx"], &[]), stack(&[4], None)]);
+    assert_eq!(fastcall.convention, CallingConvention::Fastcall);
+    assert_eq!(fastcall.params, 3);
+}
+
+#[test]
+fn convention_names_round_trip_and_accept_tool_spellings() {
+    for convention in [
+        CallingConvention::Sysv64,
+        CallingConvention::Win64,
+        CallingConvention::Cdecl,
+        CallingConvention::Stdcall,
+        CallingConvention::Fastcall,
+        CallingConvention::Thiscall,
+        CallingConvention::Aapcs,
+        CallingConvention::Aapcs64,
+        CallingConvention::Riscv,
+        CallingConvention::Unknown,
+    ] {
+        assert_eq!(CallingConvention::from_name(convention.as_str()), convention);
+    }
+    assert_eq!(CallingConvention::from_name("__stdcall"), CallingConvention::Stdcall);
+    assert_eq!(CallingConvention::from_name("amd64"), CallingConvention::Sysv64);
+    assert_eq!(CallingConvention::from_name("ms"), CallingConvention::Win64);
+    assert_eq!(CallingConvention::from_name("__vectorcall"), CallingConvention::Unknown);
+    assert_eq!(CallingConvention::for_arch("x86_64", true), Some(CallingConvention::Win64));
+    assert_eq!(CallingConvention::for_arch("ppc", false), None);
+}
+
+#[cfg(feature = "capstone-backend")]
+mod capstone {
+    use ritual_core::services::analysis::{AnalysisBackend, AnalysisOptions, AnalysisRequest};
+    use ritual_core::services::backends::CapstoneBackend;
+    use ritual_core::services::signature::{CallingConvention, FunctionSignature};
+
+    fn signature_of(code: &[u8], arch: &str) -> Option<FunctionSignature> {
+        let temp = tempfile::tempdir().unwrap();
+        let bin_path = temp.path().join("sig.bin");
+        std::fs::write(&bin_path, code).unwrap();
+        let request = AnalysisRequest {
+            ritual_name: "Sig".into(),
+            binary_name: "SigBin".into(),
+            binary_path: bin_path,
+            roots: vec!["0x0".into()],
+            options: AnalysisOptions::default(),
+            arch: Some(arch.into()),
+            backend_path: None,
+            root_groups: Vec::new(),
+            raw: None,
+            timeout: None,
+            symbols: Default::default(),
+            pdb: None,
+        };
+        let result = CapstoneBackend.analyze(&request).expect("analyze");
+        result.functions.into_iter().find(|f| f.address == 0).expect("function at 0").signature
+    }
+
+    #[test]
+    fn capstone_infers_sysv_register_params() {
+        // mov rax, rdi; add rax, rsi; xor edx, edx; ret
+        let sig = signature_of(&[0x48, 0x89, 0xF8, 0x48, 0x01, 0xF0, 0x31, 0xD2, 0xC3], "x86_64")
+            .expect("signature");
+        assert_eq!(sig.convention, CallingConvention::Sysv64);
+        assert_eq!(sig.params, 2);
+    }
+
+    #[test]
+    fn capstone_infers_stdcall_from_frame_reads_and_ret_imm() {
+        // push ebp; mov ebp, esp; mov eax, [ebp+8]; add eax, [ebp+0xc]; pop ebp; ret 8
+        let code = [0x55, 0x89, 0xE5, 0x8B, 0x45, 0x08, 0x03, 0x45, 0x0C, 0x5D, 0xC2, 0x08, 0x00];
+        let sig = signature_of(&code, "x86").expect("signature");
+        assert_eq!(
+            sig,
+            FunctionSignature {
+                params: 2,
+                convention: CallingConvention::Stdcall,
+                stack_cleanup: Some(8),
+            }
+        );
+
+        // push ebp; mov ebp, esp; mov eax, [ebp+0x10]; pop ebp; ret  (3rd cdecl argument)
+        let code = [0x55, 0x89, 0xE5, 0x8B, 0x45, 0x10, 0x5D, 0xC3];
+        let sig = signature_of(&code, "x86").expect("signature");
+        assert_eq!(sig.convention, CallingConvention::Cdecl);
+        assert_eq!(sig.params, 3);
+    }
+
+    #[test]
+    fn capstone_infers_aapcs64_register_params() {
+        // add x0, x0, x1; ret
+        let sig = signature_of(&[0x00, 0x00, 0x01, 0x8B, 0xC0, 0x03, 0x5F, 0xD6], "arm64")
+            .expect("signature");
+        assert_eq!(sig.convention, CallingConvention::Aapcs64);
+        assert_eq!(sig.params, 2);
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeSet;
+
+use ritual_core::analysis::graph::dot_body;
+use ritual_core::analysis::slices::{classify_slice_boundaries, BoundaryCounts, SliceMembership};
+use ritual_core::services::analysis::{AnalysisResult, CallEdge, FunctionRecord};
+
+fn func(address: u64, name: &str, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size: Some(0x10),
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn edge(from: u64, to: u64) -> CallEdge {
+    CallEdge { from, to, is_cross_slice: false, to_name: None, to_import: false }
+}
+
+/// `net_send` (in slice) calls `log_write` (Logging slice) and `helper` (no slice); `log_flush`
+/// (Logging) calls back into `net_recv`; `net_send` -> `net_recv` stays inside.
+fn analysis() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![
+            func(0x1000, "net_send", true),
+            func(0x1100, "net_recv", true),
+            func(0x1200, "net_idle", true),
+            func(0x2000, "log_write", false),
+            func(0x2100, "log_flush", false),
+            func(0x3000, "helper", false),
+        ],
+        call_edges: vec![
+            edge(0x1000, 0x1100),
+            edge(0x1000, 0x2000),
+            edge(0x2100, 0x1100),
+            edge(0x1200, 0x3000),
+            edge(0x1200, 0x9999),
+        ],
+        evidence: Vec::new(),
+        basic_blocks: Vec::new(),
+        roots: vec!["net_send".into()],
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: Vec::new(),
+        text_bytes: None,
+    }
+}
+
+fn membership() -> SliceMembership {
+    let mut membership = SliceMembership::new();
+    membership.insert("Logging".into(), BTreeSet::from([0x2000, 0x2100]));
+    // The slice's own entry is ignored in favour of the result's in_slice flags.
+    membership.insert("Net".into(), BTreeSet::from([0x3000]));
+    membership
+}
+
+#[test]
+fn edges_into_other_slices_are_cross_slice_and_mark_boundaries() {
+    let mut result = analysis();
+    let counts = classify_slice_boundaries(&mut result, "Net", &membership());
+    assert_eq!(counts, BoundaryCounts { boundary_functions: 3, cross_slice_calls: 2 });
+
+    let cross: Vec<(u64, u64)> =
+        result.call_edges.iter().filter(|e| e.is_cross_slice).map(|e| (e.from, e.to)).collect();
+    assert_eq!(cross, [(0x1000, 0x2000), (0x2100, 0x1100)]);
+
+    let boundary: Vec<&str> = result
+        .functions
+        .iter()
+        .filter(|f| f.is_boundary)
+        .map(|f| f.name.as_deref().unwrap())
+        .collect();
+    // net_idle calls an unsliced known function; calls to unknown addresses do not count.
+    assert_eq!(boundary, ["net_send", "net_recv", "net_idle"]);
+}
+
+#[test]
+fn single_slice_has_no_cross_slice_edges_and_classification_is_idempotent() {
+    let mut result = analysis();
+    let alone = classify_slice_boundaries(&mut result, "Net", &SliceMembership::new());
+    assert_eq!(alone.cross_slice_calls, 0);
+    assert_eq!(alone.boundary_functions, 3, "calls to unsliced functions still cross the edge");
+
+    let first = classify_slice_boundaries(&mut result, "Net", &membership());
+    let second = classify_slice_boundaries(&mut result, "Net", &membership());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn backend_boundaries_are_kept() {
+    let mut result = analysis();
+    result.functions[3].is_boundary = true;
+    let counts = classify_slice_boundaries(&mut result, "Net", &SliceMembership::new());
+    assert!(result.functions[3].is_boundary);
+    assert_eq!(counts.boundary_functions, 4);
+}
+
+#[test]
+fn dot_colors_boundaries_and_cross_slice_calls() {
+    let mut result = analysis();
+    classify_slice_boundaries(&mut result, "Net", &membership());
+    let dot = dot_body(&result);
+    assert!(dot.contains(
+        "\"fn:net_send\" [label=\"net_send\" shape=box addr=\"0x1000\" style=filled fillcolor=\"#f6d7a7\" boundary=true];"
+    ), "{dot}");
+    assert!(dot.contains("\"fn:log_write\" [label=\"log_write\" shape=box addr=\"0x2000\"];"));
+    assert!(dot.contains(
+        "\"fn:net_send\" -> \"fn:log_write\" [label=\"call\" color=\"#d9822b\" style=dashed cross_slice=true];"
+    ), "{dot}");
+    assert!(dot.contains("\"fn:net_send\" -> \"fn:net_recv\" [label=\"call\"];"));
+}
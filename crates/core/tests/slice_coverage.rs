@@ -0,0 +1,95 @@
+use ritual_core::analysis::coverage::{slice_coverage, SliceCoverage};
+use ritual_core::services::analysis::{AnalysisResult, BasicBlock, FunctionRecord};
+
+fn func(address: u64, size: Option<u32>, in_slice: bool) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: None,
+        size,
+        in_slice,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn block(start: u64, insn_count: Option<u32>) -> BasicBlock {
+    BasicBlock { start, byte_len: 0, insn_count, successors: Vec::new() }
+}
+
+fn result(
+    functions: Vec<FunctionRecord>,
+    blocks: Vec<BasicBlock>,
+    text: Option<u64>,
+) -> AnalysisResult {
+    AnalysisResult {
+        functions,
+        call_edges: Vec::new(),
+        evidence: Vec::new(),
+        xrefs: Vec::new(),
+        basic_blocks: blocks,
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: text,
+    }
+}
+
+#[test]
+fn coverage_merges_overlapping_functions_and_counts_slice_instructions() {
+    let analysis = result(
+        vec![
+            func(0x1000, Some(0x40), true),
+            // A thunk inside the first function is not counted twice.
+            func(0x1010, Some(0x10), true),
+            func(0x1030, Some(0x20), true),
+            func(0x2000, Some(0x100), false),
+            // Unsized functions count toward the slice but cover no bytes.
+            func(0x3000, None, true),
+        ],
+        vec![block(0x1000, Some(5)), block(0x1030, Some(3)), block(0x2000, Some(40))],
+        Some(0x1000),
+    );
+    let coverage = slice_coverage(&analysis);
+    assert_eq!(
+        coverage,
+        SliceCoverage {
+            text_bytes: Some(0x1000),
+            function_bytes: 0x150,
+            slice_bytes: 0x50,
+            slice_functions: 4,
+            slice_insns: Some(8),
+            total_insns: Some(48),
+        }
+    );
+    assert_eq!(coverage.slice_ratio(), Some(0x50 as f64 / 0x1000 as f64));
+    assert_eq!(coverage.unexplored_bytes(), Some(0x1000 - 0x150));
+    assert_eq!(
+        coverage.describe(),
+        "slice 2.0% of text (80 of 4096 bytes, 4 function(s), 8 of 48 insns); \
+         3760 bytes outside any function"
+    );
+}
+
+#[test]
+fn coverage_without_text_size_or_instruction_counts() {
+    let analysis = result(
+        vec![func(0x10, Some(0x10), true)],
+        vec![block(0x10, None), block(0x14, Some(2))],
+        None,
+    );
+    let coverage = slice_coverage(&analysis);
+    assert_eq!((coverage.slice_insns, coverage.total_insns), (None, None));
+    assert_eq!((coverage.slice_ratio(), coverage.unexplored_bytes()), (None, None));
+    assert_eq!(coverage.describe(), "slice 16 bytes (1 function(s)); text size unknown");
+
+    // A text size smaller than the functions (e.g. sizes overrunning a section) caps at 100%.
+    let capped = SliceCoverage { text_bytes: Some(8), ..coverage };
+    assert_eq!((capped.slice_ratio(), capped.unexplored_bytes()), (Some(1.0), Some(0)));
+    assert_eq!(SliceCoverage { text_bytes: Some(0), ..capped }.slice_ratio(), None);
+}
@@ -0,0 +1,160 @@
+use ritual_core::services::analysis::{
+    AnalysisResult, EvidenceKind, EvidenceRecord, EvidenceSource, FunctionRecord, RootHit,
+    XrefKind, XrefRecord,
+};
+use ritual_core::services::roots::{RootPattern, RootSpec};
+use ritual_core::services::strings::{
+    functions_referencing, link_string_xrefs, literal_at, referenced_string, resolve_string_roots,
+    string_literals, string_references, StringReference,
+};
+
+fn func(address: u64, name: &str, size: Option<u32>) -> FunctionRecord {
+    FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size,
+        in_slice: false,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    }
+}
+
+fn literal(address: u64, text: &str) -> EvidenceRecord {
+    EvidenceRecord {
+        address,
+        description: format!("string: {text}"),
+        kind: Some(EvidenceKind::String),
+        source: None,
+        run_id: None,
+    }
+}
+
+fn xref(from: u64, to: u64, kind: XrefKind) -> XrefRecord {
+    XrefRecord { from, to, kind, section: None, preview: None }
+}
+
+fn analysis() -> AnalysisResult {
+    AnalysisResult {
+        functions: vec![
+            func(0x1000, "main", Some(0x10)),
+            func(0x1010, "update", Some(0x10)),
+            func(0x1040, "tail", None),
+        ],
+        call_edges: Vec::new(),
+        evidence: vec![literal(0x3000, "AutoUpdate enabled"), literal(0x3020, "ready")],
+        basic_blocks: Vec::new(),
+        roots: vec!["string:AutoUpdate".into(), "string:missing".into()],
+        root_hits: vec![
+            RootHit { root: "string:AutoUpdate".into(), functions: Vec::new(), error: None },
+            RootHit { root: "string:missing".into(), functions: Vec::new(), error: None },
+        ],
+        sub_slices: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        xrefs: vec![
+            xref(0x1004, 0x3000, XrefKind::Memory),
+            // Points into the middle of the string.
+            xref(0x1014, 0x3005, XrefKind::Immediate),
+            xref(0x1048, 0x3020, XrefKind::Immediate),
+            // Calls and out-of-range targets are not string references.
+            xref(0x1008, 0x3000, XrefKind::Call),
+            xref(0x100C, 0x3012, XrefKind::Memory),
+        ],
+        text_bytes: None,
+    }
+}
+
+#[test]
+fn references_resolve_to_strings_and_containing_functions() {
+    let result = analysis();
+    assert_eq!(string_literals(&result).len(), 2);
+    assert_eq!(
+        string_references(&result),
+        vec![
+            StringReference {
+                from: 0x1004,
+                string: 0x3000,
+                value: "AutoUpdate enabled".into(),
+                function: Some(0x1000),
+            },
+            StringReference {
+                from: 0x1014,
+                string: 0x3000,
+                value: "AutoUpdate enabled".into(),
+                function: Some(0x1010),
+            },
+            StringReference {
+                from: 0x1048,
+                string: 0x3020,
+                value: "ready".into(),
+                function: Some(0x1040),
+            },
+        ]
+    );
+    assert_eq!(functions_referencing(&result, "AutoUpdate"), vec![0x1000, 0x1010]);
+}
+
+#[test]
+fn strings_decoded_in_place_are_referenced_alongside_the_encoded_literal() {
+    let mut result = analysis();
+    result.evidence.push(literal(0x3020, "ready"));
+    result.evidence.push(literal(0x3020, "rdy"));
+    let literals: Vec<(u64, String)> =
+        string_literals(&result).into_iter().map(|s| (s.address, s.value)).collect();
+    assert_eq!(literals[1..], [(0x3020, "rdy".to_string()), (0x3020, "ready".to_string())]);
+    let values: Vec<String> = string_references(&result)
+        .into_iter()
+        .filter(|r| r.from == 0x1048)
+        .map(|r| r.value)
+        .collect();
+    assert_eq!(values, ["rdy", "ready"]);
+}
+
+#[test]
+fn linking_adds_structured_evidence_once() {
+    let mut result = analysis();
+    assert_eq!(link_string_xrefs(&mut result), 3);
+    assert_eq!(link_string_xrefs(&mut result), 0);
+    let row = result.evidence.iter().find(|e| e.address == 0x1014).expect("xref evidence");
+    assert_eq!(row.description, "string xref: \"AutoUpdate enabled\" @ 0x3000");
+    assert_eq!(row.kind, Some(EvidenceKind::String));
+    assert_eq!(row.source.as_ref().map(|s| s.to_string()).as_deref(), Some("core/string-xrefs"));
+    assert_eq!(referenced_string(row), Some("AutoUpdate enabled"));
+    assert_eq!(referenced_string(&literal(0x3000, "AutoUpdate enabled")), None);
+}
+
+#[test]
+fn string_roots_seed_referencing_functions() {
+    let mut result = analysis();
+    resolve_string_roots(&mut result);
+    assert_eq!(result.root_hits[0].functions, vec![0x1000, 0x1010]);
+    assert_eq!(result.root_hits[0].error, None);
+    assert_eq!(result.root_hits[1].error.as_deref(), Some("no function references this string"));
+    let in_slice: Vec<bool> = result.functions.iter().map(|f| f.in_slice).collect();
+    assert_eq!(in_slice, vec![true, true, false]);
+}
+
+#[test]
+fn string_root_specs_round_trip() {
+    let spec: RootSpec = serde_json::from_str(r#"{"string": "AutoUpdate"}"#).unwrap();
+    assert_eq!(spec.canonical(), "string:AutoUpdate");
+    assert!(matches!(RootPattern::parse("string:AutoUpdate"), Ok(RootPattern::String(_))));
+    assert!(RootPattern::parse("string:").is_err());
+}
+
+#[test]
+fn literals_are_found_by_any_address_they_cover() {
+    let literals = string_literals(&analysis());
+    let row = literals[0].evidence("capstone");
+    assert_eq!(row.source, Some(EvidenceSource::new("capstone", "strings")));
+    assert_eq!(EvidenceRecord { source: None, ..row }, literal(0x3000, "AutoUpdate enabled"));
+    assert_eq!(literal_at(&literals, 0x3005).map(|s| s.address), Some(0x3000));
+    assert_eq!(literal_at(&literals, 0x3024).map(|s| s.address), Some(0x3020));
+    assert_eq!(literal_at(&literals, 0x3012), None, "past the end of the first string");
+    assert_eq!(literal_at(&literals, 0x2FFF), None);
+    assert_eq!(literal_at(&[], 0x3000), None);
+}
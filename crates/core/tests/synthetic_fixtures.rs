@@ -0,0 +1,240 @@
+use ritual_core::services::analysis::{
+    AnalysisBackend, AnalysisOptions, AnalysisRequest, ValidateOnlyBackend,
+};
+use ritual_core::services::fixtures::{check_invariants, synthetic_elf_x86_64, SyntheticBinary};
+
+fn request_for(binary: &SyntheticBinary, path: &std::path::Path) -> AnalysisRequest {
+    AnalysisRequest {
+        ritual_name: "SelfTest".into(),
+        binary_name: binary.file_name.into(),
+        binary_path: path.to_path_buf(),
+        roots: binary.roots(),
+        arch: Some(binary.arch.into()),
+        options: AnalysisOptions::default(),
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    }
+}
+
+#[test]
+fn synthetic_elf_has_valid_header_and_planted_functions() {
+    let binary = synthetic_elf_x86_64();
+    assert_eq!(&binary.bytes[..4], b"\x7fELF");
+    assert_eq!(binary.roots(), vec!["main".to_string(), "helper".to_string()]);
+    assert_eq!(binary.calls, vec![(binary.functions[0].address, binary.functions[1].address)]);
+}
+
+#[test]
+fn validate_only_passes_structural_invariants() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = synthetic_elf_x86_64();
+    let path = temp.path().join(binary.file_name);
+    std::fs::write(&path, &binary.bytes).unwrap();
+
+    let result = ValidateOnlyBackend.analyze(&request_for(&binary, &path)).unwrap();
+    assert!(check_invariants(&binary, &result, false).is_empty());
+    // Without functions the stricter expectations must flag the gaps.
+    let failures = check_invariants(&binary, &result, true);
+    assert!(failures.iter().any(|f| f.contains("missing function 'main'")), "{failures:?}");
+    assert!(failures.iter().any(|f| f.contains("missing call edge")), "{failures:?}");
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn capstone_recovers_synthetic_functions_and_call() {
+    let temp = tempfile::tempdir().unwrap();
+    let binary = synthetic_elf_x86_64();
+    let path = temp.path().join(binary.file_name);
+    std::fs::write(&path, &binary.bytes).unwrap();
+
+    let backend = ritual_core::services::backends::CapstoneBackend;
+    let mut result = backend.analyze(&request_for(&binary, &path)).unwrap();
+    assert_eq!(check_invariants(&binary, &result, true), Vec::<String>::new());
+
+    result.call_edges.clear();
+    result.root_hits[0].functions.push(0xDEAD);
+    let failures = check_invariants(&binary, &result, true);
+    assert!(failures.iter().any(|f| f.contains("unknown function 0xDEAD")), "{failures:?}");
+    assert!(failures.iter().any(|f| f.contains("missing call edge")), "{failures:?}");
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn capstone_recovers_stripped_functions_by_recursive_descent() {
+    use ritual_core::services::analysis::BlockEdgeKind;
+    use ritual_core::services::fixtures::synthetic_elf_x86_64_stripped;
+
+    let temp = tempfile::tempdir().unwrap();
+    let binary = synthetic_elf_x86_64_stripped();
+    let path = temp.path().join(binary.file_name);
+    std::fs::write(&path, &binary.bytes).unwrap();
+
+    let backend = ritual_core::services::backends::CapstoneBackend;
+    let result = backend.analyze(&request_for(&binary, &path)).unwrap();
+    assert_eq!(check_invariants(&binary, &result, true), Vec::<String>::new());
+    assert_eq!(result.functions.len(), 3, "{:?}", result.functions);
+    assert_eq!(result.functions[0].size, Some(0xC));
+
+    // Blocks split at the call, the conditional branch, and its target; padding is never
+    // decoded.
+    let starts: Vec<u64> = result.basic_blocks.iter().map(|b| b.start).collect();
+    assert_eq!(starts, vec![0x401000, 0x401005, 0x401009, 0x40100B, 0x401020, 0x401025, 0x401030]);
+    let branch = &result.basic_blocks[1];
+    assert_eq!((branch.byte_len, branch.insn_count), (4, Some(2)));
+    assert!(branch
+        .successors
+        .iter()
+        .any(|e| e.target == 0x40100B && e.kind == BlockEdgeKind::ConditionalJump));
+    assert!(branch
+        .successors
+        .iter()
+        .any(|e| e.target == 0x401009 && e.kind == BlockEdgeKind::Fallthrough));
+    assert_eq!(
+        result.basic_blocks[2].successors[0].kind,
+        BlockEdgeKind::Fallthrough,
+        "xor falls into the shared ret block"
+    );
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn capstone_links_rodata_strings_to_referencing_functions() {
+    use ritual_core::services::analysis::XrefKind;
+    use ritual_core::services::fixtures::{synthetic_elf_x86_64_strings, SYNTHETIC_STRINGS};
+    use ritual_core::services::strings;
+
+    let temp = tempfile::tempdir().unwrap();
+    let binary = synthetic_elf_x86_64_strings();
+    let path = temp.path().join(binary.file_name);
+    std::fs::write(&path, &binary.bytes).unwrap();
+
+    let mut request = request_for(&binary, &path);
+    request.options.include_strings = true;
+    let backend = ritual_core::services::backends::CapstoneBackend;
+    let mut result = backend.analyze(&request).unwrap();
+    assert_eq!(check_invariants(&binary, &result, true), Vec::<String>::new());
+
+    let literals: Vec<(u64, String)> =
+        strings::string_literals(&result).into_iter().map(|s| (s.address, s.value)).collect();
+    let expected: Vec<(u64, String)> =
+        SYNTHETIC_STRINGS.iter().map(|(a, v)| (*a, v.to_string())).collect();
+    assert_eq!(literals, expected);
+
+    // `lea rdi, [rip + disp]` resolves to the string's absolute address.
+    assert!(result
+        .xrefs
+        .iter()
+        .any(|x| x.from == 0x401000 && x.to == 0x402000 && x.kind == XrefKind::Memory));
+    assert!(result
+        .xrefs
+        .iter()
+        .any(|x| x.from == 0x401010 && x.to == 0x402021 && x.kind == XrefKind::Immediate));
+    // Xrefs into a string preview the literal from the referenced byte on.
+    let preview = |from: u64| {
+        result.xrefs.iter().find(|x| x.from == from).and_then(|x| x.preview.clone()).unwrap()
+    };
+    assert_eq!(preview(0x401000), "AutoUpdate: checking for patches");
+    assert_eq!(preview(0x401020), "checking for patches");
+
+    assert_eq!(strings::link_string_xrefs(&mut result), 3);
+    assert_eq!(strings::functions_referencing(&result, "AutoUpdate"), vec![0x401000, 0x401020]);
+    assert_eq!(strings::functions_referencing(&result, "Network"), vec![0x401010]);
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn capstone_resolves_adrp_pairs_by_constant_propagation() {
+    use ritual_core::services::analysis::XrefKind;
+    use ritual_core::services::fixtures::synthetic_elf_arm64_strings;
+    use ritual_core::services::strings;
+
+    let temp = tempfile::tempdir().unwrap();
+    let binary = synthetic_elf_arm64_strings();
+    let path = temp.path().join(binary.file_name);
+    std::fs::write(&path, &binary.bytes).unwrap();
+
+    let mut request = request_for(&binary, &path);
+    request.options.include_strings = true;
+    let backend = ritual_core::services::backends::CapstoneBackend;
+    let result = backend.analyze(&request).unwrap();
+    assert_eq!(check_invariants(&binary, &result, true), Vec::<String>::new());
+
+    // `adrp` pages are not references of their own; the built addresses are.
+    let xrefs: Vec<(u64, u64, XrefKind)> =
+        result.xrefs.iter().map(|x| (x.from, x.to, x.kind)).collect();
+    assert_eq!(
+        xrefs,
+        vec![(0x401004, 0x402021, XrefKind::Computed), (0x40100C, 0x40200C, XrefKind::Computed)]
+    );
+    let references: Vec<(u64, String)> =
+        strings::string_references(&result).into_iter().map(|r| (r.from, r.value)).collect();
+    assert_eq!(
+        references,
+        vec![
+            (0x401004, "Network ready".to_string()),
+            (0x40100C, "AutoUpdate: checking for patches".to_string()),
+        ]
+    );
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn plt_stubs_resolve_to_versioned_imports() {
+    use ritual_core::services::fixtures::{synthetic_elf_x86_64_plt, SYNTHETIC_PLT_IMPORTS};
+    use ritual_core::services::plt::elf_plt_imports;
+
+    let imports: Vec<(u64, String, Option<String>)> =
+        elf_plt_imports(&synthetic_elf_x86_64_plt().bytes)
+            .into_iter()
+            .map(|i| (i.address, i.versioned_name(), i.library))
+            .collect();
+    let expected: Vec<(u64, String, Option<String>)> = SYNTHETIC_PLT_IMPORTS
+        .iter()
+        .map(|(address, name, library)| (*address, name.to_string(), Some(library.to_string())))
+        .collect();
+    assert_eq!(imports, expected);
+    // Statically linked images have no PLT.
+    assert!(elf_plt_imports(&synthetic_elf_x86_64().bytes).is_empty());
+}
+
+#[cfg(feature = "capstone-backend")]
+#[test]
+fn capstone_names_plt_calls_after_their_imports() {
+    use ritual_core::services::analysis::{resolve_call_edges, CallEdge, EvidenceKind};
+    use ritual_core::services::fixtures::{synthetic_elf_x86_64_plt, SYNTHETIC_PLT_IMPORTS};
+
+    let temp = tempfile::tempdir().unwrap();
+    let binary = synthetic_elf_x86_64_plt();
+    let path = temp.path().join(binary.file_name);
+    std::fs::write(&path, &binary.bytes).unwrap();
+
+    let backend = ritual_core::services::backends::CapstoneBackend;
+    let mut result = backend.analyze(&request_for(&binary, &path)).unwrap();
+    assert_eq!(check_invariants(&binary, &result, true), Vec::<String>::new());
+    // The stubs are imports, not `sub_XXXX` functions.
+    let names: Vec<_> = result.functions.iter().filter_map(|f| f.name.as_deref()).collect();
+    assert_eq!(names, ["main"]);
+
+    resolve_call_edges(&mut result);
+    let labels: Vec<String> = result.call_edges.iter().map(CallEdge::target_label).collect();
+    assert_eq!(labels, ["puts@GLIBC_2.2.5", "memcpy@GLIBC_2.14"]);
+    assert!(result.call_edges.iter().all(|e| e.to_import));
+    let calls: Vec<(u64, &str)> = result
+        .evidence
+        .iter()
+        .filter(|e| e.kind == Some(EvidenceKind::Call) && e.description.starts_with("call -> "))
+        .map(|e| (e.address, e.description.as_str()))
+        .collect();
+    assert_eq!(
+        calls,
+        [(0x401000, "call -> puts@GLIBC_2.2.5"), (0x401005, "call -> memcpy@GLIBC_2.14")]
+    );
+    let (memcpy, _, _) = SYNTHETIC_PLT_IMPORTS[1];
+    assert!(result.evidence.iter().any(|e| e.address == memcpy
+        && e.kind == Some(EvidenceKind::Import)
+        && e.description == "import: memcpy@GLIBC_2.14 (libc.so.6)"));
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::Path;
+
+use ritual_core::db::{ProjectDb, RitualRunRecord, RitualRunStatus};
+use ritual_core::services::analysis::{
+    AnalysisResult, BasicBlock, BlockEdge, BlockEdgeKind, CallEdge, EvidenceKind, EvidenceRecord,
+    EvidenceSource, FunctionRecord,
+};
+use ritual_core::services::tables::{export_tables, TableFormat, TablesError, TABLES};
+
+fn record_run(db: &ProjectDb) -> i64 {
+    let run_id = db
+        .insert_ritual_run(&RitualRunRecord {
+            binary: "Bin".into(),
+            ritual: "Net".into(),
+            spec_hash: "spec".into(),
+            binary_hash: None,
+            backend: "capstone".into(),
+            backend_version: None,
+            backend_path: None,
+            status: RitualRunStatus::Succeeded,
+            started_at: "now".into(),
+            finished_at: "now".into(),
+            binary_id: None,
+            superseded_by: None,
+            deterministic: false,
+            spec_path: None,
+            analysis_options: None,
+            usage: None,
+        })
+        .unwrap();
+    let function = |address: u64, name: &str, size: Option<u32>| FunctionRecord {
+        address,
+        name: Some(name.into()),
+        size,
+        in_slice: address == 0x1000,
+        is_boundary: false,
+        mangled_name: None,
+        signature: None,
+        arch: None,
+        fuzzy_hash: None,
+        isa: None,
+    };
+    let result = AnalysisResult {
+        functions: vec![function(0x1000, "net_init", Some(16)), function(0x2000, "send", None)],
+        call_edges: vec![CallEdge {
+            from: 0x1004,
+            to: 0x3000,
+            is_cross_slice: false,
+            to_name: Some("memcpy".into()),
+            to_import: true,
+        }],
+        evidence: vec![
+            EvidenceRecord {
+                address: 0x1008,
+                description: "string: \"host, port\"".into(),
+                kind: Some(EvidenceKind::String),
+                source: Some(EvidenceSource::new("capstone", "strings")),
+                run_id: None,
+            },
+            EvidenceRecord {
+                address: 0x2000,
+                description: "unclassified".into(),
+                kind: None,
+                source: None,
+                run_id: None,
+            },
+        ],
+        basic_blocks: vec![BasicBlock {
+            start: 0x1000,
+            byte_len: 8,
+            insn_count: Some(3),
+            successors: vec![
+                BlockEdge { target: 0x1008, kind: BlockEdgeKind::Fallthrough },
+                BlockEdge { target: 0x1010, kind: BlockEdgeKind::ConditionalJump },
+            ],
+        }],
+        roots: Vec::new(),
+        root_hits: Vec::new(),
+        sub_slices: Vec::new(),
+        xrefs: Vec::new(),
+        backend_version: None,
+        backend_path: None,
+        text_bytes: None,
+    };
+    db.insert_analysis_result(run_id, &result).unwrap();
+    run_id
+}
+
+fn read(dir: &Path, table: &str) -> String {
+    fs::read_to_string(dir.join(format!("{table}.csv"))).unwrap()
+}
+
+#[test]
+fn csv_tables_flatten_the_latest_run() {
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let run_id = record_run(&db);
+    let out = temp.path().join("tables");
+
+    let export = export_tables(&db, "Bin", "Net", TableFormat::Csv, &out).unwrap();
+    assert_eq!(export.run_id, run_id);
+    let rows: Vec<(&str, usize)> = export.tables.iter().map(|t| (t.name, t.rows)).collect();
+    assert_eq!(
+        rows,
+        [
+            ("functions", 2),
+            ("call_edges", 1),
+            ("basic_blocks", 1),
+            ("block_edges", 2),
+            ("evidence", 2)
+        ]
+    );
+    assert_eq!(export.tables.iter().map(|t| t.name).collect::<Vec<_>>(), TABLES);
+
+    assert_eq!(
+        read(&out, "functions"),
+        "address,name,size,in_slice,is_boundary,mangled_name,signature,arch,isa,fuzzy_hash\n\
+         4096,net_init,16,true,false,,,,,\n\
+         8192,send,,false,false,,,,,\n"
+    );
+    assert_eq!(
+        read(&out, "call_edges"),
+        "from,to,to_name,to_import,is_cross_slice\n4100,12288,memcpy,true,false\n"
+    );
+    assert_eq!(read(&out, "basic_blocks"), "start,byte_len,insn_count,successors\n4096,8,3,2\n");
+    assert_eq!(read(&out, "block_edges"), "from,to,kind\n4096,4104,fallthrough\n4096,4112,cjump\n");
+    // Fields with commas or quotes are quoted, quotes doubled.
+    assert_eq!(
+        read(&out, "evidence"),
+        "address,kind,source,description\n\
+         4104,string,capstone/strings,\"string: \"\"host, port\"\"\"\n\
+         8192,,,unclassified\n"
+    );
+}
+
+#[test]
+fn export_tables_rejects_unknown_runs_and_formats() {
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    let err = export_tables(&db, "Bin", "Net", TableFormat::Csv, temp.path()).unwrap_err();
+    assert!(matches!(err, TablesError::NoRun { .. }), "{err}");
+    assert!(matches!(TableFormat::parse("xlsx"), Err(TablesError::UnknownFormat(_))));
+    assert_eq!(TableFormat::parse("parquet").unwrap(), TableFormat::Parquet);
+    if !cfg!(feature = "parquet") {
+        record_run(&db);
+        let err = export_tables(&db, "Bin", "Net", TableFormat::Parquet, temp.path()).unwrap_err();
+        assert!(matches!(err, TablesError::ParquetUnavailable), "{err}");
+    }
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_tables_read_back_with_nulls() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let temp = tempfile::tempdir().unwrap();
+    let db = ProjectDb::open(&temp.path().join("proj.db")).unwrap();
+    record_run(&db);
+    let out = temp.path().join("tables");
+    let export = export_tables(&db, "Bin", "Net", TableFormat::Parquet, &out).unwrap();
+    assert_eq!(export.tables[0].path, out.join("functions.parquet"));
+
+    let file = fs::File::open(out.join("functions.parquet")).unwrap();
+    let reader = SerializedFileReader::new(file).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+    let rows: Vec<String> =
+        reader.get_row_iter(None).unwrap().map(|row| row.unwrap().to_string()).collect();
+    assert_eq!(
+        rows[1],
+        "{address: 8192, name: \"send\", size: null, in_slice: false, is_boundary: false, \
+         mangled_name: null, signature: null, arch: null, isa: null, fuzzy_hash: null}"
+    );
+}
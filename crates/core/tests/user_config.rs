@@ -0,0 +1,78 @@
+use std::fs;
+
+use ritual_core::db::ProjectConfig;
+use ritual_core::services::config::{ColorChoice, UserConfig, UserConfigError};
+use tempfile::tempdir;
+
+#[test]
+fn user_config_parses_and_rejects_unknown_keys_and_values() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    assert_eq!(UserConfig::load_from(&path).unwrap(), UserConfig::default());
+
+    fs::write(
+        &path,
+        r#"
+default_backend = "rizin"
+jobs = 4
+color = "never"
+
+[backends]
+rizin = "/opt/rizin/bin/rizin"
+"#,
+    )
+    .unwrap();
+    let config = UserConfig::load_from(&path).unwrap();
+    assert_eq!(config.default_backend.as_deref(), Some("rizin"));
+    assert_eq!(config.jobs, Some(4));
+    assert_eq!(config.color, Some(ColorChoice::Never));
+    assert_eq!(config.backend_paths().rizin.as_deref(), Some("/opt/rizin/bin/rizin"));
+
+    fs::write(&path, "default_bakend = \"rizin\"\n").unwrap();
+    let err = UserConfig::load_from(&path).unwrap_err();
+    assert!(matches!(err, UserConfigError::Parse { .. }), "{err}");
+    assert!(err.to_string().contains("default_bakend"), "{err}");
+
+    fs::write(&path, "default_backend = \"ida\"\n").unwrap();
+    let err = UserConfig::load_from(&path).unwrap_err();
+    assert!(err.to_string().contains("unknown backend 'ida'"), "{err}");
+    fs::write(&path, "jobs = 0\n").unwrap();
+    assert!(matches!(
+        UserConfig::load_from(&path),
+        Err(UserConfigError::InvalidValue { key, .. }) if key == "jobs"
+    ));
+    fs::write(&path, "color = \"sometimes\"\n").unwrap();
+    assert!(UserConfig::load_from(&path).is_err());
+}
+
+#[test]
+fn project_settings_win_over_user_settings() {
+    let user = UserConfig {
+        default_backend: Some("rizin".into()),
+        backends: ritual_core::db::BackendPaths {
+            rizin: Some("/user/rizin".into()),
+            ghidra_headless: Some("/user/analyzeHeadless".into()),
+        },
+        jobs: None,
+        color: None,
+    };
+
+    let mut config = ProjectConfig::new("Proj", ".ritual/project.db");
+    user.merge_into(&mut config);
+    assert_eq!(config.default_backend.as_deref(), Some("rizin"));
+    assert_eq!(config.backends.ghidra_headless.as_deref(), Some("/user/analyzeHeadless"));
+
+    let mut config = ProjectConfig::new("Proj", ".ritual/project.db");
+    config.default_backend = Some("capstone".into());
+    config.backends.rizin = Some("/project/rizin".into());
+    user.merge_into(&mut config);
+    assert_eq!(config.default_backend.as_deref(), Some("capstone"));
+    assert_eq!(config.backends.rizin.as_deref(), Some("/project/rizin"));
+    assert_eq!(config.backends.ghidra_headless.as_deref(), Some("/user/analyzeHeadless"));
+
+    // A project backend priority list is not overridden by a user default backend.
+    let mut config = ProjectConfig::new("Proj", ".ritual/project.db");
+    config.backend_priority = vec!["ghidra".into()];
+    user.merge_into(&mut config);
+    assert_eq!(config.default_backend, None);
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "wasm-plugins")]
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use ritual_core::services::analysis::AnalysisError;
+use ritual_core::services::process::Deadline;
+use ritual_core::services::wasi::{run_wasi, WasiOutput};
+use tempfile::tempdir;
+
+/// Copies stdin to stdout, prints its `argv[0]` to stderr, and exits with status 3.
+const ECHO: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "args_get" (func $args_get (param i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "path_open"
+    (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+  (memory (export "memory") 1)
+  (func (export "_start") (local $n i32)
+    ;; No filesystem: opening anything fails with ENOSYS (52).
+    (if (i32.ne (call $path_open (i32.const 3) (i32.const 0) (i32.const 0) (i32.const 0)
+                  (i32.const 0) (i64.const 0) (i64.const 0) (i32.const 0) (i32.const 0))
+                (i32.const 52))
+      (then unreachable))
+    (loop $copy
+      (i32.store (i32.const 0) (i32.const 1024))
+      (i32.store (i32.const 4) (i32.const 4096))
+      (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+      (local.set $n (i32.load (i32.const 8)))
+      (if (local.get $n)
+        (then
+          (i32.store (i32.const 4) (local.get $n))
+          (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+          (br $copy))))
+    (drop (call $args_get (i32.const 16) (i32.const 64)))
+    (i32.store (i32.const 0) (i32.const 64))
+    (i32.store (i32.const 4) (i32.const 9))
+    (drop (call $fd_write (i32.const 2) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (call $proc_exit (i32.const 3))))"#;
+
+fn write_module(dir: &Path, name: &str, wat: &str) -> std::path::PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+    path
+}
+
+#[test]
+fn modules_read_stdin_and_report_output_and_exit_status() {
+    let temp = tempdir().unwrap();
+    let module = write_module(temp.path(), "echo.wasm", ECHO);
+    let input = b"{\"api_version\": 1}".repeat(500);
+
+    let output = run_wasi(
+        &module,
+        "echo.wasm",
+        input.clone(),
+        &Deadline::new(Some(Duration::from_secs(30))),
+    )
+    .unwrap();
+    assert_eq!(output, WasiOutput { code: 3, stdout: input, stderr: b"echo.wasm".to_vec() });
+}
+
+#[test]
+fn traps_and_invalid_modules_are_errors() {
+    let temp = tempdir().unwrap();
+    let deadline = Deadline::new(None);
+    let trap =
+        write_module(temp.path(), "trap.wasm", r#"(module (func (export "_start") unreachable))"#);
+    let err = run_wasi(&trap, "trap.wasm", Vec::new(), &deadline).unwrap_err();
+    assert!(err.to_string().contains("trap.wasm trapped"), "{err}");
+
+    let garbage = temp.path().join("garbage.wasm");
+    std::fs::write(&garbage, b"\0asm").unwrap();
+    let err = run_wasi(&garbage, "garbage.wasm", Vec::new(), &deadline).unwrap_err();
+    assert!(err.to_string().contains("garbage.wasm is not a valid WASM module"), "{err}");
+}
+
+#[test]
+fn modules_that_run_past_the_deadline_time_out() {
+    let temp = tempdir().unwrap();
+    let spin = write_module(
+        temp.path(),
+        "spin.wasm",
+        r#"(module (func (export "_start") (loop $forever (br $forever))))"#,
+    );
+    let started = Instant::now();
+    let err =
+        run_wasi(&spin, "spin.wasm", Vec::new(), &Deadline::new(Some(Duration::from_millis(200))))
+            .unwrap_err();
+    assert!(matches!(err, AnalysisError::Timeout { ref tool, .. } if tool == "spin.wasm"), "{err}");
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // Its fuel ran out with the time, so the module's thread is gone too.
+    #[cfg(target_os = "linux")]
+    {
+        let threads: Vec<String> = std::fs::read_dir("/proc/self/task")
+            .unwrap()
+            .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+            .collect();
+        assert!(!threads.iter().any(|name| name.trim() == "wasi spin.wasm"), "{threads:?}");
+    }
+}
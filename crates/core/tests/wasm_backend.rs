@@ -0,0 +1,171 @@
+#![cfg(feature = "wasm-backend")]
+
+use std::collections::HashMap;
+
+use ritual_core::db::RitualRunStatus;
+use ritual_core::services::analysis::{
+    analyze_request, AnalysisBackend, AnalysisOptions, AnalysisRequest, EvidenceKind, RunMetadata,
+    XrefKind,
+};
+use ritual_core::services::backends::WasmBackend;
+use ritual_core::services::entry_points::EntryKind;
+
+const MODULE: &str = r#"
+(module
+  (type $handler (func (param i32) (result i32)))
+  (import "env" "log" (func $log (param i32)))
+  (memory 1)
+  (table 2 funcref)
+  (elem (i32.const 0) $on_ping $on_close)
+  (data (i32.const 1024) "connect failed\00ok\00/api/ping\00")
+  (func $on_ping (type $handler) (local.get 0))
+  (func $on_close (type $handler) (i32.const 0))
+  (func $tick (param i32) (result i32 i32) (local.get 0) (i32.const 1))
+  (func $dispatch (export "dispatch") (param i32 i32) (result i32)
+    (call_indirect (type $handler) (local.get 1) (local.get 0)))
+  (func (export "connect") (param i32) (result i32)
+    (call $log (i32.const 1024))
+    (drop (call $dispatch (local.get 0) (i32.const 1))))
+)
+"#;
+
+fn request(path: std::path::PathBuf, roots: &[&str]) -> AnalysisRequest {
+    AnalysisRequest {
+        ritual_name: "Wasm".into(),
+        binary_name: "game.wasm".into(),
+        binary_path: path,
+        roots: roots.iter().map(|r| r.to_string()).collect(),
+        options: AnalysisOptions {
+            include_strings: true,
+            include_imports: true,
+            ..AnalysisOptions::default()
+        },
+        arch: None,
+        backend_path: None,
+        root_groups: Vec::new(),
+        raw: None,
+        timeout: None,
+        symbols: Default::default(),
+        pdb: None,
+    }
+}
+
+#[test]
+fn wasm_backend_reports_functions_calls_and_data_strings() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("game.wasm");
+    std::fs::write(&path, wat::parse_str(MODULE).unwrap()).unwrap();
+
+    let result = WasmBackend.analyze(&request(path, &["connect"])).unwrap();
+    assert_eq!(result.backend_version.as_deref(), Some("wasmparser 0.245"));
+    let by_name: HashMap<&str, u64> =
+        result.functions.iter().map(|f| (f.name.as_deref().unwrap(), f.address)).collect();
+    let mut names: Vec<&str> = by_name.keys().copied().collect();
+    names.sort_unstable();
+    assert_eq!(names, ["connect", "dispatch", "log", "on_close", "on_ping", "tick"]);
+
+    // Defined functions sit at their body offsets with fingerprints; the import has no body.
+    let log = result.functions.iter().find(|f| f.address == by_name["log"]).unwrap();
+    assert_eq!((log.size, log.fuzzy_hash.as_deref()), (None, None));
+    let connect = result.functions.iter().find(|f| f.address == by_name["connect"]).unwrap();
+    assert!(connect.in_slice && connect.size.is_some() && connect.fuzzy_hash.is_some());
+    assert!(result.functions.iter().filter(|f| f.in_slice).count() == 1);
+    assert!(by_name["log"] < by_name["on_ping"]);
+    let body_bytes: u64 = result.functions.iter().filter_map(|f| f.size).map(u64::from).sum();
+    assert!(result.text_bytes.is_some_and(|text| text > body_bytes));
+
+    // `call` edges leave from inside the caller; `call_indirect` reaches the table entries of
+    // the matching type.
+    let callees = |caller: &str| -> Vec<u64> {
+        let func = result.functions.iter().find(|f| f.address == by_name[caller]).unwrap();
+        let end = func.address + u64::from(func.size.unwrap());
+        let mut to: Vec<u64> = result
+            .call_edges
+            .iter()
+            .filter(|e| e.from >= func.address && e.from < end)
+            .map(|e| e.to)
+            .collect();
+        to.sort_unstable();
+        to
+    };
+    assert_eq!(callees("connect"), [by_name["log"], by_name["dispatch"]]);
+    assert_eq!(callees("dispatch"), [by_name["on_ping"], by_name["on_close"]]);
+    assert!(callees("tick").is_empty());
+
+    // Strings are reported at their linear-memory address, and `i32.const` operands pointing
+    // into them become xrefs.
+    let strings: Vec<(u64, &str)> = result
+        .evidence
+        .iter()
+        .filter(|e| e.kind == Some(EvidenceKind::String))
+        .map(|e| (e.address, e.description.as_str()))
+        .collect();
+    assert_eq!(strings, [(1024, "string: connect failed"), (1042, "string: /api/ping")]);
+    assert_eq!(result.xrefs.len(), 1);
+    let xref = &result.xrefs[0];
+    assert_eq!((xref.to, xref.kind), (1024, XrefKind::Immediate));
+    assert_eq!(xref.preview.as_deref(), Some("connect failed"));
+    assert!(xref.from > by_name["connect"]);
+    assert!(result
+        .evidence
+        .iter()
+        .any(|e| e.kind == Some(EvidenceKind::Import) && e.description == "import env.log"));
+}
+
+#[test]
+fn wasm_backend_rejects_missing_and_malformed_modules() {
+    let temp = tempfile::tempdir().unwrap();
+    let err = WasmBackend.analyze(&request(temp.path().join("missing.wasm"), &[])).unwrap_err();
+    assert!(format!("{err:?}").contains("MissingBinary"));
+
+    let path = temp.path().join("broken.wasm");
+    std::fs::write(&path, b"\0asm\x01\0\0\0\x01\xff").unwrap();
+    let err = WasmBackend.analyze(&request(path, &[])).unwrap_err();
+    assert!(err.to_string().contains("invalid wasm module"), "{err}");
+}
+
+#[test]
+fn wasm_start_and_exported_functions_seed_auto_roots() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("app.wasm");
+    let module = r#"
+        (module
+          (func $init)
+          (func $helper)
+          (func (export "run") (call $helper))
+          (start $init))
+    "#;
+    std::fs::write(&path, wat::parse_str(module).unwrap()).unwrap();
+
+    let request = request(path, &["auto:entrypoint", "auto:exports"]);
+    let points = WasmBackend.entry_points(&request).unwrap();
+    let kinds: Vec<(EntryKind, Option<&str>)> =
+        points.iter().map(|p| (p.kind, p.name.as_deref())).collect();
+    assert_eq!(kinds, [(EntryKind::Entry, Some("init")), (EntryKind::Export, Some("run"))]);
+
+    let meta = RunMetadata {
+        spec_hash: "spec".into(),
+        binary_id: None,
+        binary_hash: None,
+        backend: "wasm".into(),
+        backend_version: None,
+        backend_path: None,
+        status: RitualRunStatus::Succeeded,
+        deterministic: false,
+        epoch: None,
+        failed_backends: Vec::new(),
+        spec_path: None,
+        started_at: None,
+        usage: None,
+    };
+    let result = analyze_request(&WasmBackend, &request, &meta, None).unwrap().result;
+    let mut in_slice: Vec<&str> = result
+        .functions
+        .iter()
+        .filter(|f| f.in_slice)
+        .map(|f| f.name.as_deref().unwrap())
+        .collect();
+    in_slice.sort_unstable();
+    assert_eq!(in_slice, ["init", "run"]);
+    assert!(result.root_hits.iter().all(|hit| hit.functions.len() == 1 && hit.error.is_none()));
+}
@@ -0,0 +1,458 @@
+//! ritual (Python bindings)
+//!
+//! Exposes the project database, ritual specs, ritual execution, and analysis results from
+//! `ritual-core` as the `ritual` Python module so batch analyses can be scripted from Python
+//! or Jupyter. Records cross the boundary as plain dicts/lists (their JSON shape), so they
+//! match `report.json` and the CLI's `--json` output.
+//!
+//! Build and install into the active virtualenv with `maturin develop` from `crates/python`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use ritual_core::db::{load_project_layout, open_project_db, ProjectLayout};
+use ritual_core::rituals::run::{
+    check_baseline, check_validation, run_ritual_spec, RunFlags, SpecSource,
+};
+use ritual_core::rituals::spec::{AutoRoots, BackendChoice, BinarySelector, RitualRoots};
+use ritual_core::services::roots::RootSpec;
+use serde::Serialize;
+
+create_exception!(ritual, RitualError, PyException, "Error raised by ritual-core operations.");
+
+fn py_err(err: impl Into<anyhow::Error>) -> PyErr {
+    RitualError::new_err(format!("{:#}", err.into()))
+}
+
+/// Convert any serializable value into the equivalent Python object (via its JSON shape).
+fn to_py<T: Serialize + ?Sized>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_value(value).map_err(py_err)?;
+    json_to_py(py, &json)
+}
+
+fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+    use serde_json::Value;
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => u.into_py(py),
+            (None, Some(i)) => i.into_py(py),
+            _ => n.as_f64().unwrap_or_default().into_py(py),
+        },
+        Value::String(s) => s.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new_bound(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+/// Handle to a project's SQLite database.
+#[pyclass(name = "ProjectDb", module = "ritual")]
+struct ProjectDb {
+    inner: ritual_core::db::ProjectDb,
+}
+
+#[pymethods]
+impl ProjectDb {
+    /// Open (or create) a database file directly, applying schema migrations.
+    #[new]
+    fn new(path: PathBuf) -> PyResult<Self> {
+        let inner = ritual_core::db::ProjectDb::open(&path).map_err(py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Open the database of an initialized project (resolves the path from `.ritual/project.json`).
+    #[staticmethod]
+    fn from_project(root: PathBuf) -> PyResult<Self> {
+        let (_, _, inner) = open_project_db(&ProjectLayout::new(root)).map_err(py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Registered binaries as dicts (`id`, `name`, `path`, `arch`, `hash`).
+    fn list_binaries(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let binaries = self.inner.list_binaries_with_ids().map_err(py_err)?;
+        let rows: Vec<serde_json::Value> = binaries
+            .into_iter()
+            .map(|(id, record)| {
+                let mut row = serde_json::to_value(record).unwrap_or_default();
+                row["id"] = id.into();
+                row
+            })
+            .collect();
+        to_py(py, &rows)
+    }
+
+    /// Slice records as dicts; archived slices are skipped unless `include_archived`.
+    #[pyo3(signature = (include_archived = false))]
+    fn list_slices(&self, py: Python<'_>, include_archived: bool) -> PyResult<PyObject> {
+        let slices: Vec<_> = self
+            .inner
+            .list_slices()
+            .map_err(py_err)?
+            .into_iter()
+            .filter(|s| include_archived || !s.is_archived())
+            .collect();
+        to_py(py, &slices)
+    }
+
+    /// Ritual run records as dicts, optionally filtered by binary name.
+    #[pyo3(signature = (binary = None))]
+    fn list_ritual_runs(&self, py: Python<'_>, binary: Option<&str>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.list_ritual_runs(binary).map_err(py_err)?)
+    }
+
+    /// Row id of the latest run of `ritual` against `binary`, if any.
+    fn latest_run_id(&self, binary: &str, ritual: &str) -> PyResult<Option<i64>> {
+        self.inner.latest_run_id(binary, ritual).map_err(py_err)
+    }
+
+    /// Persisted analysis of the latest run of `ritual` against `binary`, if any.
+    fn load_analysis_result(&self, binary: &str, ritual: &str) -> PyResult<Option<AnalysisResult>> {
+        let result = self.inner.load_analysis_result(binary, ritual).map_err(py_err)?;
+        Ok(result.map(|inner| AnalysisResult { inner }))
+    }
+
+    /// Structured xrefs recorded for a run id.
+    fn list_xrefs(&self, py: Python<'_>, run_id: i64) -> PyResult<PyObject> {
+        to_py(py, &self.inner.list_xrefs(run_id).map_err(py_err)?)
+    }
+
+    fn __repr__(&self) -> String {
+        "ProjectDb(...)".to_string()
+    }
+}
+
+/// Roots argument: a flat list, `{label: [roots]}` for labeled sub-slices, or `"auto"`.
+#[derive(FromPyObject)]
+enum RootsArg {
+    List(Vec<String>),
+    Labeled(BTreeMap<String, Vec<String>>),
+    Keyword(String),
+}
+
+/// A ritual spec (same schema as the YAML/JSON files under `rituals/`).
+#[pyclass(name = "RitualSpec", module = "ritual")]
+#[derive(Clone)]
+struct RitualSpec {
+    inner: ritual_core::rituals::spec::RitualSpec,
+}
+
+#[pymethods]
+impl RitualSpec {
+    #[new]
+    #[pyo3(signature = (name, binary, roots, backend = None, max_depth = None, description = None))]
+    fn new(
+        name: String,
+        binary: String,
+        roots: RootsArg,
+        backend: Option<String>,
+        max_depth: Option<u32>,
+        description: Option<String>,
+    ) -> PyResult<Self> {
+        let roots = match roots {
+            RootsArg::List(roots) => RitualRoots::from(roots),
+            RootsArg::Labeled(groups) => RitualRoots::Labeled(
+                groups
+                    .into_iter()
+                    .map(|(label, roots)| (label, roots.into_iter().map(RootSpec::from).collect()))
+                    .collect(),
+            ),
+            RootsArg::Keyword(word) if word == "auto" => RitualRoots::Auto(AutoRoots::Auto),
+            RootsArg::Keyword(word) => {
+                return Err(RitualError::new_err(format!(
+                    "roots must be a list, a dict of lists, or \"auto\" (got \"{}\")",
+                    word
+                )))
+            }
+        };
+        let inner = ritual_core::rituals::spec::RitualSpec {
+            name,
+            binary: BinarySelector::Name(binary),
+            roots,
+            max_depth,
+            backend: backend.map(BackendChoice::One),
+            description,
+            outputs: None,
+            stages: Vec::new(),
+            epoch: None,
+            raw: None,
+            timeout: None,
+            plugins: Vec::new(),
+            string_decoders: Vec::new(),
+            ghidra_xml: None,
+        };
+        inner.validate().map_err(py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Load a spec file (`.json` is parsed as JSON, anything else as YAML).
+    #[staticmethod]
+    fn load(path: PathBuf) -> PyResult<Self> {
+        let inner = ritual_core::rituals::spec::RitualSpec::from_path(&path).map_err(py_err)?;
+        inner.validate().map_err(py_err)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    fn from_yaml(text: &str) -> PyResult<Self> {
+        Self::parse(text, false)
+    }
+
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        Self::parse(text, true)
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.name.clone()
+    }
+
+    /// Binary selector as written in the spec (name, `sha256:<prefix>`, or `name (arch)`).
+    #[getter]
+    fn binary(&self) -> String {
+        self.inner.binary.to_string()
+    }
+
+    /// Roots as a list, or a `{label: [roots]}` dict for labeled sub-slices.
+    #[getter]
+    fn roots(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.roots)
+    }
+
+    /// Backend name, or the list of backends tried in order.
+    #[getter]
+    fn backend(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.backend)
+    }
+
+    #[getter]
+    fn max_depth(&self) -> Option<u32> {
+        self.inner.max_depth
+    }
+
+    #[getter]
+    fn description(&self) -> Option<String> {
+        self.inner.description.clone()
+    }
+
+    fn validate(&self) -> PyResult<()> {
+        self.inner.validate().map_err(py_err)
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner)
+    }
+
+    fn to_yaml(&self) -> PyResult<String> {
+        serde_yaml::to_string(&self.inner).map_err(py_err)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(&self.inner).map_err(py_err)
+    }
+
+    /// Write the spec to `path` (JSON for `.json`, YAML otherwise).
+    fn save(&self, path: PathBuf) -> PyResult<()> {
+        let body = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            self.to_json()?
+        } else {
+            self.to_yaml()?
+        };
+        std::fs::write(&path, body).map_err(py_err)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RitualSpec(name={:?}, binary={:?})",
+            self.inner.name,
+            self.inner.binary.to_string()
+        )
+    }
+}
+
+impl RitualSpec {
+    fn parse(text: &str, json: bool) -> PyResult<Self> {
+        let inner = ritual_core::rituals::spec::RitualSpec::from_slice(text.as_bytes(), json)
+            .map_err(py_err)?;
+        inner.validate().map_err(py_err)?;
+        Ok(Self { inner })
+    }
+}
+
+/// Analysis produced by a ritual run (functions, call edges, evidence, xrefs, CFG, roots).
+#[pyclass(name = "AnalysisResult", module = "ritual")]
+struct AnalysisResult {
+    inner: ritual_core::services::analysis::AnalysisResult,
+}
+
+#[pymethods]
+impl AnalysisResult {
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        let inner = serde_json::from_str(text).map_err(py_err)?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn functions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.functions)
+    }
+
+    #[getter]
+    fn call_edges(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.call_edges)
+    }
+
+    #[getter]
+    fn evidence(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.evidence)
+    }
+
+    #[getter]
+    fn xrefs(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.xrefs)
+    }
+
+    #[getter]
+    fn basic_blocks(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.basic_blocks)
+    }
+
+    #[getter]
+    fn roots(&self) -> Vec<String> {
+        self.inner.roots.clone()
+    }
+
+    #[getter]
+    fn root_hits(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.root_hits)
+    }
+
+    #[getter]
+    fn sub_slices(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.sub_slices)
+    }
+
+    #[getter]
+    fn backend_version(&self) -> Option<String> {
+        self.inner.backend_version.clone()
+    }
+
+    #[getter]
+    fn backend_path(&self) -> Option<String> {
+        self.inner.backend_path.clone()
+    }
+
+    #[getter]
+    fn text_bytes(&self) -> Option<u64> {
+        self.inner.text_bytes
+    }
+
+    /// Slice coverage against the binary's executable bytes, as a dict.
+    fn coverage(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &ritual_core::analysis::coverage::slice_coverage(&self.inner))
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(&self.inner).map_err(py_err)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AnalysisResult(functions={}, call_edges={}, basic_blocks={}, evidence={}, xrefs={})",
+            self.inner.functions.len(),
+            self.inner.call_edges.len(),
+            self.inner.basic_blocks.len(),
+            self.inner.evidence.len(),
+            self.inner.xrefs.len()
+        )
+    }
+}
+
+/// `spec` argument of `run_ritual`: a spec file path or a `RitualSpec` object.
+#[derive(FromPyObject)]
+enum SpecArg<'py> {
+    Spec(PyRef<'py, RitualSpec>),
+    Path(PathBuf),
+}
+
+/// Run a ritual against a project (like `binary-slicer run-ritual`) and return its analysis.
+///
+/// A `RitualSpec` object is first saved to `rituals/<name>.yaml` so the run is reproducible
+/// from the project alone, exactly as if the spec had been authored by hand. `timeout` (in
+/// seconds) kills a backend tool that runs longer and records the run as canceled.
+/// `variables` fill `${name}` references in a spec file, like `--set name=value`.
+#[pyfunction]
+#[pyo3(signature = (root, spec, backend = None, force = false, deterministic = false, timeout = None, variables = None))]
+fn run_ritual(
+    root: PathBuf,
+    spec: SpecArg<'_>,
+    backend: Option<&str>,
+    force: bool,
+    deterministic: bool,
+    timeout: Option<u64>,
+    variables: Option<BTreeMap<String, String>>,
+) -> PyResult<AnalysisResult> {
+    let root = std::fs::canonicalize(&root).unwrap_or(root);
+    let layout = load_project_layout(&root);
+    let sets: Vec<String> = variables
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    let spec_path = match spec {
+        SpecArg::Path(path) => path,
+        SpecArg::Spec(spec) => {
+            std::fs::create_dir_all(&layout.rituals_dir).map_err(py_err)?;
+            let path = layout.rituals_dir.join(format!("{}.yaml", spec.inner.name));
+            spec.save(path.clone())?;
+            path
+        }
+    };
+
+    let flags = RunFlags { force, deterministic, timeout, ..RunFlags::default() };
+    let source = SpecSource::File(&spec_path.to_string_lossy());
+    let outcome =
+        run_ritual_spec(&layout, source, backend, flags, &[], &sets, None).map_err(py_err)?;
+    check_validation(flags, &outcome.validation, &outcome.run_dir).map_err(py_err)?;
+    check_baseline(outcome.baseline.as_ref(), &outcome.run_dir).map_err(py_err)?;
+    Ok(AnalysisResult { inner: outcome.result })
+}
+
+/// Version of the underlying `ritual-core` library.
+#[pyfunction]
+fn version() -> &'static str {
+    ritual_core::version()
+}
+
+#[pymodule]
+fn ritual(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("RitualError", m.py().get_type_bound::<RitualError>())?;
+    m.add_class::<ProjectDb>()?;
+    m.add_class::<RitualSpec>()?;
+    m.add_class::<AnalysisResult>()?;
+    m.add_function(wrap_pyfunction!(run_ritual, m)?)?;
+    m.add_function(wrap_pyfunction!(version, m)?)?;
+    Ok(())
+}
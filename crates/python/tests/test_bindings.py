@@ -0,0 +1,70 @@
+"""Smoke tests for the `ritual` Python module.
+
+Run after `maturin develop` (or with the built extension on PYTHONPATH):
+
+    cargo build -p binary-slicer
+    python -m unittest discover crates/python/tests
+
+The CLI is used to scaffold a project; set BINARY_SLICER to its path if it is not at
+target/debug/binary-slicer.
+"""
+
+import os
+import pathlib
+import subprocess
+import tempfile
+import unittest
+
+import ritual
+
+REPO_ROOT = pathlib.Path(__file__).resolve().parents[3]
+CLI = os.environ.get("BINARY_SLICER", str(REPO_ROOT / "target" / "debug" / "binary-slicer"))
+
+
+def cli(*args):
+    subprocess.run([CLI, *args], check=True, capture_output=True)
+
+
+class BindingsTest(unittest.TestCase):
+    def setUp(self):
+        self.tmp = tempfile.TemporaryDirectory()
+        self.root = self.tmp.name
+        cli("init-project", "--root", self.root, "--name", "PyProj")
+        binary = pathlib.Path(self.root) / "libDemo.so"
+        binary.write_bytes(b"\x7fELF demo")
+        cli("add-binary", "--root", self.root, "--path", str(binary), "--name", "libDemo.so")
+
+    def tearDown(self):
+        self.tmp.cleanup()
+
+    def test_spec_round_trips_and_validates(self):
+        spec = ritual.RitualSpec("Net", "libDemo.so", {"net": ["send"], "ui": ["draw"]})
+        self.assertEqual(spec.roots, {"net": ["send"], "ui": ["draw"]})
+        path = pathlib.Path(self.root) / "net.json"
+        spec.save(str(path))
+        loaded = ritual.RitualSpec.load(str(path))
+        self.assertEqual(loaded.to_dict(), spec.to_dict())
+        with self.assertRaises(ritual.RitualError):
+            ritual.RitualSpec("Empty", "libDemo.so", [])
+
+    def test_run_ritual_returns_persisted_analysis(self):
+        spec = ritual.RitualSpec("Demo", "libDemo.so", ["entry"], backend="validate-only")
+        result = ritual.run_ritual(self.root, spec)
+        self.assertIsInstance(result, ritual.AnalysisResult)
+        self.assertEqual(result.roots, ["entry"])
+        self.assertTrue((pathlib.Path(self.root) / "rituals" / "Demo.yaml").is_file())
+
+        db = ritual.ProjectDb.from_project(self.root)
+        self.assertEqual([b["name"] for b in db.list_binaries()], ["libDemo.so"])
+        runs = db.list_ritual_runs("libDemo.so")
+        self.assertEqual([r["ritual"] for r in runs], ["Demo"])
+        again = db.load_analysis_result("libDemo.so", "Demo")
+        self.assertEqual(again.to_dict(), result.to_dict())
+        self.assertIsNone(db.load_analysis_result("libDemo.so", "Missing"))
+        self.assertEqual(
+            ritual.AnalysisResult.from_json(result.to_json()).to_dict(), result.to_dict()
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()